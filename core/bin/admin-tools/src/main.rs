@@ -12,6 +12,7 @@ use crate::application::create_app;
 mod application;
 mod blocks;
 mod prover;
+mod repair;
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
 enum JobStatus {
@@ -49,6 +50,8 @@ enum ProverCommand {
 #[derive(Subcommand)]
 enum BlockCommand {
     Show(BlockShowCommand),
+    /// Find and repair L1 batches with missing or inconsistent metadata.
+    Repair(BlockRepairCommand),
 }
 
 type AppFnBox<'a> = Box<dyn FnOnce(&mut App) -> Result<(), AppError> + 'a>;
@@ -78,8 +81,8 @@ struct ProverLsCommand {
     round: Option<i32>,
 }
 
-fn prover_ls<'a>(cmd: &ProverLsCommand) -> Result<AppFnBox<'a>, AppError> {
-    let range = match &cmd.range {
+fn parse_block_range(input: &Option<String>) -> Result<Option<(u32, u32)>, AppError> {
+    match input {
         Some(input) => {
             let split = input
                 .split("..")
@@ -89,13 +92,17 @@ fn prover_ls<'a>(cmd: &ProverLsCommand) -> Result<AppFnBox<'a>, AppError> {
 
             match split.as_slice() {
                 [] => Ok(None),
-                [id] => Ok(Some(L1BatchNumber(*id)..L1BatchNumber(*id))),
-                [s, e] => Ok(Some(L1BatchNumber(*s)..L1BatchNumber(*e))),
+                [id] => Ok(Some((*id, *id))),
+                [s, e] => Ok(Some((*s, *e))),
                 _ => Err(AppError::Command("Wrong range format".to_owned())),
             }
         }
         None => Ok(None),
-    }?;
+    }
+}
+
+fn prover_ls<'a>(cmd: &ProverLsCommand) -> Result<AppFnBox<'a>, AppError> {
+    let range = parse_block_range(&cmd.range)?.map(|(s, e)| L1BatchNumber(s)..L1BatchNumber(e));
 
     let opts = GetProverJobsParams {
         blocks: range,
@@ -135,6 +142,34 @@ fn block_show<'a>(id: L1BatchNumber) -> AppFnBox<'a> {
     })
 }
 
+#[derive(Args)]
+struct BlockRepairCommand {
+    #[arg(long)]
+    /// Block range. Format: `x` or `x..y` (inclusive). All blocks are checked if not specified.
+    range: Option<String>,
+    #[arg(long)]
+    /// Apply repairs. Without this flag, found issues and planned repairs are only printed.
+    apply: bool,
+}
+
+fn block_repair<'a>(cmd: &BlockRepairCommand) -> Result<AppFnBox<'a>, AppError> {
+    let (start, end) = parse_block_range(&cmd.range)?.unwrap_or((0, u32::MAX));
+    let range = L1BatchNumber(start)..=L1BatchNumber(end);
+    let apply = cmd.apply;
+
+    Ok(Box::new(move |app| {
+        let plan = repair::plan_repairs(range, app)?;
+        repair::print_repair_plan(&plan)?;
+        if apply {
+            println!();
+            repair::apply_repair_plan(&plan, app)
+        } else {
+            println!("\nDry run; pass `--apply` to repair");
+            Ok(())
+        }
+    }))
+}
+
 fn match_prover_cmd(cmd: &ProverCommand) -> CmdMatch {
     match cmd {
         ProverCommand::Stats => Ok(prover_stats()),
@@ -145,6 +180,7 @@ fn match_prover_cmd(cmd: &ProverCommand) -> CmdMatch {
 fn match_block_cmd(cmd: &BlockCommand) -> CmdMatch {
     match cmd {
         BlockCommand::Show(cmd) => Ok(block_show(L1BatchNumber(cmd.id))),
+        BlockCommand::Repair(cmd) => block_repair(cmd),
     }
 }
 
@@ -0,0 +1,139 @@
+use std::ops;
+
+use zksync_dal::blocks_dal::L1BatchMetadataIssue;
+use zksync_types::{aggregated_operations::AggregatedActionType, L1BatchNumber};
+
+use crate::application::{App, AppError};
+
+/// Action repairing one or more metadata issues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepairAction {
+    /// Set the parent hash to the root hash of the previous L1 batch.
+    RecomputeParentHash(L1BatchNumber),
+    /// Clear the reference to an ETH transaction of a wrong type, so that the ETH sender aggregates the batch again.
+    ClearEthTx(L1BatchNumber, AggregatedActionType),
+    /// Clear metadata starting from the specified L1 batch, so that the metadata calculator recomputes it
+    /// once the Merkle tree is rolled back.
+    ClearMetadata(L1BatchNumber),
+}
+
+pub struct RepairPlan {
+    issues: Vec<(L1BatchNumber, L1BatchMetadataIssue)>,
+    actions: Vec<RepairAction>,
+}
+
+pub fn plan_repairs(
+    range: ops::RangeInclusive<L1BatchNumber>,
+    app: &mut App,
+) -> Result<RepairPlan, AppError> {
+    let issues = app
+        .tokio
+        .handle()
+        .block_on(app.db.blocks_dal().find_l1_batch_metadata_issues(range));
+
+    // Metadata can only be recomputed by the Merkle tree, which processes L1 batches sequentially,
+    // so all metadata starting from the first broken batch needs to be cleared.
+    let first_broken_batch = issues
+        .iter()
+        .filter(|(_, issue)| {
+            matches!(
+                issue,
+                L1BatchMetadataIssue::MissingRootHash | L1BatchMetadataIssue::MissingCommitment
+            )
+        })
+        .map(|(number, _)| *number)
+        .min();
+
+    let mut actions = vec![];
+    for &(number, issue) in &issues {
+        match issue {
+            L1BatchMetadataIssue::ParentHashMismatch => {
+                if first_broken_batch.map_or(true, |first| number < first) {
+                    actions.push(RepairAction::RecomputeParentHash(number));
+                }
+            }
+            L1BatchMetadataIssue::OrphanedEthTx(action_type) => {
+                actions.push(RepairAction::ClearEthTx(number, action_type));
+            }
+            L1BatchMetadataIssue::MissingRootHash | L1BatchMetadataIssue::MissingCommitment => {}
+        }
+    }
+    // Clearing metadata must go last; otherwise, orphaned commit transactions could prevent it.
+    if let Some(first) = first_broken_batch {
+        actions.push(RepairAction::ClearMetadata(first));
+    }
+    Ok(RepairPlan { issues, actions })
+}
+
+pub fn print_repair_plan(plan: &RepairPlan) -> Result<(), AppError> {
+    if plan.issues.is_empty() {
+        println!("No L1 batch metadata issues found");
+        return Ok(());
+    }
+
+    println!("Found {} issue(s):", plan.issues.len());
+    for (number, issue) in &plan.issues {
+        println!("  L1 batch #{}: {:?}", number, issue);
+    }
+    println!("Planned actions:");
+    for action in &plan.actions {
+        println!("  {}", describe_action(action));
+    }
+    Ok(())
+}
+
+fn describe_action(action: &RepairAction) -> String {
+    match action {
+        RepairAction::RecomputeParentHash(number) => {
+            format!("recompute parent hash of L1 batch #{}", number)
+        }
+        RepairAction::ClearEthTx(number, action_type) => format!(
+            "clear {} ETH transaction reference of L1 batch #{}",
+            action_type.to_string(),
+            number
+        ),
+        RepairAction::ClearMetadata(number) => {
+            format!("clear metadata of L1 batches starting from #{}", number)
+        }
+    }
+}
+
+pub fn apply_repair_plan(plan: &RepairPlan, app: &mut App) -> Result<(), AppError> {
+    let handle = app.tokio.handle();
+    for action in &plan.actions {
+        let applied = match *action {
+            RepairAction::RecomputeParentHash(number) => {
+                handle.block_on(app.db.blocks_dal().recompute_parent_hash(number))
+            }
+            RepairAction::ClearEthTx(number, action_type) => handle.block_on(
+                app.db
+                    .blocks_dal()
+                    .clear_orphaned_eth_tx_id(number, action_type),
+            ),
+            RepairAction::ClearMetadata(number) => {
+                let cleared = handle.block_on(app.db.blocks_dal().clear_l1_batch_metadata(number));
+                let cleared = cleared.ok_or_else(|| {
+                    AppError::Command(format!(
+                        "Cannot clear metadata starting from L1 batch #{}: some of the batches \
+                         are already committed. Revert them first",
+                        number
+                    ))
+                })?;
+                println!(
+                    "Cleared metadata of {} L1 batch(es). Roll back the Merkle tree so that it's recomputed: \
+                     block_reverter rollback-db --l1-batch-number {} --rollback-tree",
+                    cleared,
+                    number.0.saturating_sub(1)
+                );
+                true
+            }
+        };
+
+        if applied {
+            println!("Done: {}", describe_action(action));
+        } else {
+            println!("Skipped (already consistent): {}", describe_action(action));
+        }
+    }
+    Ok(())
+}
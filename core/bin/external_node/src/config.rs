@@ -3,10 +3,17 @@ use serde::Deserialize;
 use std::{env, time::Duration};
 use url::Url;
 
-use zksync_basic_types::{Address, L1ChainId, L2ChainId, H256};
+use zksync_basic_types::{Address, L1ChainId, L2ChainId, ProtocolVersionId, H256};
 use zksync_contracts::BaseSystemContractsHashes;
-use zksync_core::api_server::{tx_sender::TxSenderConfig, web3::state::InternalApiConfig};
-use zksync_types::api::BridgeAddresses;
+use zksync_core::{
+    api_server::{
+        execution_sandbox::SandboxOutputLimits,
+        tx_sender::{AcceptedTxTypes, TxSenderConfig},
+        web3::state::InternalApiConfig,
+    },
+    chain_contracts::ChainContracts,
+};
+use zksync_types::MAX_NEW_FACTORY_DEPS;
 
 use zksync_web3_decl::{
     jsonrpsee::http_client::{HttpClient, HttpClientBuilder},
@@ -17,13 +24,12 @@ use zksync_web3_decl::{
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct RemoteENConfig {
     pub diamond_proxy_addr: Address,
-    pub l1_erc20_bridge_proxy_addr: Address,
-    pub l2_erc20_bridge_addr: Address,
-    pub l1_weth_bridge_proxy_addr: Option<Address>,
-    pub l2_weth_bridge_addr: Option<Address>,
+    pub chain_contracts: ChainContracts,
     pub l2_testnet_paymaster_addr: Option<Address>,
     pub l2_chain_id: L2ChainId,
     pub l1_chain_id: L1ChainId,
+    /// Latest protocol version known to the main node at the moment of the start.
+    pub protocol_version: ProtocolVersionId,
 }
 
 impl RemoteENConfig {
@@ -54,16 +60,19 @@ impl RemoteENConfig {
                 .context("Failed to fetch L1 chain ID")?
                 .as_u64(),
         );
+        let protocol_version = client
+            .get_protocol_version(None)
+            .await
+            .context("Failed to fetch protocol version")?
+            .map_or(ProtocolVersionId(0), |version| version.version_id);
 
         Ok(Self {
             diamond_proxy_addr,
             l2_testnet_paymaster_addr,
-            l1_erc20_bridge_proxy_addr: bridges.l1_erc20_default_bridge,
-            l2_erc20_bridge_addr: bridges.l2_erc20_default_bridge,
-            l1_weth_bridge_proxy_addr: bridges.l1_weth_bridge,
-            l2_weth_bridge_addr: bridges.l2_weth_bridge,
+            chain_contracts: bridges.into(),
             l2_chain_id,
             l1_chain_id,
+            protocol_version,
         })
     }
 }
@@ -96,6 +105,14 @@ pub struct OptionalENConfig {
     /// Max number of cache misses during one VM execution. If the number of cache misses exceeds this value, the api server panics.
     /// This is a temporary solution to mitigate API request resulting in thousands of DB queries.
     pub vm_execution_cache_misses_limit: Option<usize>,
+    /// Max size of the data returned by a single `eth_call` or gas estimation VM execution (in bytes).
+    pub vm_execution_max_returndata_size: Option<usize>,
+    /// Max number of events emitted during a single `eth_call` or gas estimation VM execution.
+    pub vm_execution_max_events: Option<usize>,
+    /// Max number of storage reads and writes during a single `eth_call` or gas estimation VM execution.
+    pub vm_execution_max_storage_accesses: Option<usize>,
+    /// Ratio of soft limits on the VM execution output to the corresponding limits above.
+    vm_execution_soft_limits_ratio: Option<f64>,
     /// Inbound transaction limit used for throttling.
     pub transactions_per_sec_limit: Option<u32>,
     /// Port on which the Prometheus exporter server is listening.
@@ -110,6 +127,28 @@ pub struct OptionalENConfig {
     vm_concurrency_limit: Option<usize>,
     /// Smart contract source code cache size for the API server.
     factory_deps_cache_size_mb: Option<usize>,
+    /// Size of the decoded bytecode cache shared by the state keeper and the API server. Set to 0 to disable the cache.
+    bytecode_cache_size_mb: Option<usize>,
+    /// Max number of accounts with cached validation rule violations. Set to 0 to disable the cache.
+    validation_cache_size: Option<usize>,
+    /// Max number of factory dependencies in a single transaction.
+    max_factory_deps_per_tx: Option<usize>,
+    /// Max total size of factory dependencies in a single transaction in bytes.
+    pub max_factory_deps_size_per_tx: Option<usize>,
+    /// Whether `zks_getAllAccountBalances` resolves balances from the token balances table. Should only be
+    /// enabled if the node was synced from the genesis with a server version populating the table.
+    indexed_balances: Option<bool>,
+    /// Max number of transactions in a bundle simulated by `zks_simulateBundle`.
+    max_bundle_size: Option<usize>,
+    /// API calls taking longer than this threshold (in ms) are logged together with their parameters.
+    /// If not set, slow calls are not logged.
+    slow_call_threshold_ms: Option<u64>,
+    /// Share of slow API calls that are logged, from 0 to 1.
+    slow_call_log_sample_rate: Option<f64>,
+    /// Max number of calls in a single batch request.
+    max_batch_request_size: Option<usize>,
+    /// Max size of the request body (in bytes).
+    max_request_body_size: Option<u32>,
 }
 
 impl OptionalENConfig {
@@ -121,6 +160,31 @@ impl OptionalENConfig {
         self.req_entities_limit.unwrap_or(1024)
     }
 
+    pub fn indexed_balances(&self) -> bool {
+        self.indexed_balances.unwrap_or(false)
+    }
+
+    pub fn max_bundle_size(&self) -> usize {
+        self.max_bundle_size.unwrap_or(16)
+    }
+
+    pub fn slow_call_threshold(&self) -> Option<Duration> {
+        self.slow_call_threshold_ms.map(Duration::from_millis)
+    }
+
+    pub fn slow_call_log_sample_rate(&self) -> f64 {
+        self.slow_call_log_sample_rate.unwrap_or(0.1)
+    }
+
+    pub fn max_batch_request_size(&self) -> usize {
+        self.max_batch_request_size.unwrap_or(500)
+    }
+
+    pub fn max_request_body_size(&self) -> u32 {
+        // 10MB is the default limit of the RPC servers.
+        self.max_request_body_size.unwrap_or(10 * 1024 * 1024)
+    }
+
     pub fn filters_limit(&self) -> usize {
         self.filters_limit.unwrap_or(10000)
     }
@@ -165,10 +229,29 @@ impl OptionalENConfig {
         self.vm_concurrency_limit
     }
 
+    pub fn vm_execution_soft_limits_ratio(&self) -> f64 {
+        self.vm_execution_soft_limits_ratio.unwrap_or(0.5)
+    }
+
     pub fn factory_deps_cache_size_mb(&self) -> usize {
         // 128MB is the default smart contract code cache size.
         self.factory_deps_cache_size_mb.unwrap_or(128)
     }
+
+    pub fn bytecode_cache_size_mb(&self) -> usize {
+        self.bytecode_cache_size_mb.unwrap_or(128)
+    }
+
+    pub fn validation_cache_size(&self) -> usize {
+        self.validation_cache_size.unwrap_or(10_000)
+    }
+
+    pub fn max_factory_deps_per_tx(&self) -> usize {
+        self.max_factory_deps_per_tx
+            .map_or(MAX_NEW_FACTORY_DEPS, |limit| {
+                limit.min(MAX_NEW_FACTORY_DEPS)
+            })
+    }
 }
 
 /// This part of the external node config is required for its operation.
@@ -320,15 +403,16 @@ impl From<ExternalNodeConfig> for InternalApiConfig {
             estimate_gas_acceptable_overestimation: config
                 .optional
                 .estimate_gas_acceptable_overestimation(),
-            bridge_addresses: BridgeAddresses {
-                l1_erc20_default_bridge: config.remote.l1_erc20_bridge_proxy_addr,
-                l2_erc20_default_bridge: config.remote.l2_erc20_bridge_addr,
-                l1_weth_bridge: config.remote.l1_weth_bridge_proxy_addr,
-                l2_weth_bridge: config.remote.l2_weth_bridge_addr,
-            },
+            bridge_addresses: config.remote.chain_contracts.bridge_addresses(),
             diamond_proxy_addr: config.remote.diamond_proxy_addr,
             l2_testnet_paymaster_addr: config.remote.l2_testnet_paymaster_addr,
             req_entities_limit: config.optional.req_entities_limit(),
+            indexed_balances: config.optional.indexed_balances(),
+            max_bundle_size: config.optional.max_bundle_size(),
+            // The execution delay is only known to the main node.
+            l1_batch_execution_delay: None,
+            // The external node doesn't encode protected data.
+            protected_data_namespaces: None,
         }
     }
 }
@@ -342,9 +426,21 @@ impl From<ExternalNodeConfig> for TxSenderConfig {
             max_allowed_l2_tx_gas_limit: config.required.max_allowed_l2_tx_gas_limit,
             fair_l2_gas_price: config.required.fair_l2_gas_price,
             vm_execution_cache_misses_limit: config.optional.vm_execution_cache_misses_limit,
+            output_limits: SandboxOutputLimits::new(
+                config.optional.vm_execution_max_returndata_size,
+                config.optional.vm_execution_max_events,
+                config.optional.vm_execution_max_storage_accesses,
+                config.optional.vm_execution_soft_limits_ratio(),
+            ),
             validation_computational_gas_limit: config.required.validation_computational_gas_limit,
+            validation_cache_size: config.optional.validation_cache_size(),
             default_aa: config.required.default_aa_hash,
             bootloader: config.required.bootloader_hash,
+            max_factory_deps_per_tx: config.optional.max_factory_deps_per_tx(),
+            max_factory_deps_size_per_tx: config.optional.max_factory_deps_size_per_tx,
+            protocol_version: config.remote.protocol_version,
+            // Transactions are proxied to the main node, which applies its own restrictions.
+            accepted_tx_types: AcceptedTxTypes::default(),
         }
     }
 }
@@ -1,19 +1,24 @@
 use prometheus_exporter::run_prometheus_exporter;
 use tokio::{sync::watch, task, time::sleep};
-use zksync_state::FactoryDepsCache;
+use zksync_state::{BytecodeCache, FactoryDepsCache};
 
 use config::ExternalNodeConfig;
 use std::{sync::Arc, time::Duration};
-use zksync_basic_types::Address;
 use zksync_config::DBConfig;
 
 use zksync_core::api_server::healthcheck::HealthCheckHandle;
 use zksync_core::{
     api_server::{
-        execution_sandbox::VmConcurrencyLimiter, healthcheck, tx_sender::TxSenderBuilder,
-        web3::ApiBuilder,
+        execution_sandbox::VmConcurrencyLimiter,
+        healthcheck,
+        tx_sender::TxSenderBuilder,
+        web3::{
+            backend_jsonrpc::call_metrics::CallMetricsConfig, request_limits::RequestLimitsConfig,
+            ApiBuilder,
+        },
     },
     block_reverter::{BlockReverter, BlockReverterFlags, L1ExecutedBatchesRevert},
+    chain_contracts::ChainContracts,
     consistency_checker::ConsistencyChecker,
     l1_gas_price::MainNodeGasPriceFetcher,
     metadata_calculator::{
@@ -21,7 +26,9 @@ use zksync_core::{
     },
     reorg_detector::ReorgDetector,
     setup_sigint_handler,
-    state_keeper::{MainBatchExecutorBuilder, SealManager, ZkSyncStateKeeper},
+    state_keeper::{
+        MainBatchExecutorBuilder, MiniblockHashesBackfill, SealManager, ZkSyncStateKeeper,
+    },
     sync_layer::{
         batch_status_updater::BatchStatusUpdater, external_io::ExternalIO,
         fetcher::MainNodeFetcher, genesis::perform_genesis_if_needed, ActionQueue,
@@ -42,7 +49,8 @@ async fn build_state_keeper(
     main_node_url: String,
     connection_pool: ConnectionPool,
     sync_state: SyncState,
-    l2_erc20_bridge_addr: Address,
+    chain_contracts: ChainContracts,
+    bytecode_cache: Option<BytecodeCache>,
     stop_receiver: watch::Receiver<bool>,
 ) -> ZkSyncStateKeeper {
     let en_sealer = ExternalNodeSealer::new(action_queue.clone());
@@ -60,15 +68,20 @@ async fn build_state_keeper(
     let validation_computational_gas_limit = u32::MAX;
     // We don't need call traces on the external node.
     let save_call_traces = false;
+    // Transactions are already included into blocks by the main node, so they cannot be rejected on timeout.
+    let tx_execution_timeout = None;
 
-    let batch_executor_base: Box<MainBatchExecutorBuilder> =
-        Box::new(MainBatchExecutorBuilder::new(
-            state_keeper_db_path,
-            connection_pool.clone(),
-            max_allowed_l2_tx_gas_limit,
-            save_call_traces,
-            validation_computational_gas_limit,
-        ));
+    let mut batch_executor_base = MainBatchExecutorBuilder::new(
+        state_keeper_db_path,
+        connection_pool.clone(),
+        max_allowed_l2_tx_gas_limit,
+        save_call_traces,
+        validation_computational_gas_limit,
+        tx_execution_timeout,
+    );
+    if let Some(bytecode_cache) = bytecode_cache {
+        batch_executor_base = batch_executor_base.with_bytecode_cache(bytecode_cache);
+    }
 
     let io = Box::new(
         ExternalIO::new(
@@ -76,12 +89,12 @@ async fn build_state_keeper(
             action_queue,
             sync_state,
             main_node_url,
-            l2_erc20_bridge_addr,
+            chain_contracts,
         )
         .await,
     );
 
-    ZkSyncStateKeeper::new(stop_receiver, io, batch_executor_base, sealer)
+    ZkSyncStateKeeper::new(stop_receiver, io, Box::new(batch_executor_base), sealer)
 }
 
 async fn init_tasks(
@@ -103,13 +116,17 @@ async fn init_tasks(
 
     let sync_state = SyncState::new();
     let action_queue = ActionQueue::new();
+    let bytecode_cache_size_mb = config.optional.bytecode_cache_size_mb();
+    let bytecode_cache = (bytecode_cache_size_mb > 0)
+        .then(|| BytecodeCache::new("bytecode_cache", bytecode_cache_size_mb));
     let state_keeper = build_state_keeper(
         action_queue.clone(),
         config.required.state_cache_path.clone(),
         main_node_url.to_string(),
         connection_pool.clone(),
         sync_state.clone(),
-        config.remote.l2_erc20_bridge_addr,
+        config.remote.chain_contracts,
+        bytecode_cache.clone(),
         stop_receiver.clone(),
     )
     .await;
@@ -158,6 +175,9 @@ async fn init_tasks(
     let sk_handle = task::spawn(state_keeper.run());
     let fetcher_handle = tokio::spawn(fetcher.run());
     let gas_adjuster_handle = tokio::spawn(gas_adjuster.clone().run(stop_receiver.clone()));
+    let miniblock_hashes_backfill =
+        MiniblockHashesBackfill::new(ConnectionPool::new(Some(1), DbVariant::Master).await);
+    let backfill_handle = tokio::spawn(miniblock_hashes_backfill.run(stop_receiver.clone()));
 
     let tx_sender = {
         let mut tx_sender_builder =
@@ -177,6 +197,9 @@ async fn init_tasks(
             "factory_deps_cache",
             config.optional.factory_deps_cache_size_mb(),
         );
+        if let Some(bytecode_cache) = bytecode_cache {
+            tx_sender_builder = tx_sender_builder.with_bytecode_cache(bytecode_cache);
+        }
 
         tx_sender_builder
             .build(
@@ -188,11 +211,25 @@ async fn init_tasks(
             .await
     };
 
+    let call_metrics = CallMetricsConfig {
+        slow_call_threshold: config.optional.slow_call_threshold(),
+        slow_call_log_sample_rate: config.optional.slow_call_log_sample_rate(),
+    };
+    // The `jsonrpc` HTTP server doesn't expose peer addresses, so calls are not rate limited.
+    let request_limits = RequestLimitsConfig {
+        reads_per_second_per_ip: None,
+        executions_per_second_per_ip: None,
+        max_batch_request_size: config.optional.max_batch_request_size(),
+        max_request_body_size: config.optional.max_request_body_size(),
+        trusted_proxies: vec![],
+    };
     let (http_api_handle, http_api_healthcheck) =
         ApiBuilder::jsonrpc_backend(config.clone().into(), connection_pool.clone())
             .http(config.required.http_port)
             .with_filter_limit(config.optional.filters_limit())
             .with_threads(config.required.threads_per_server)
+            .with_call_metrics(call_metrics)
+            .with_request_limits(request_limits.clone())
             .with_tx_sender(tx_sender.clone())
             .with_sync_state(sync_state.clone())
             .build(stop_receiver.clone())
@@ -205,6 +242,8 @@ async fn init_tasks(
             .with_subscriptions_limit(config.optional.subscriptions_limit())
             .with_polling_interval(config.optional.polling_interval())
             .with_threads(config.required.threads_per_server)
+            .with_call_metrics(call_metrics)
+            .with_request_limits(request_limits)
             .with_tx_sender(tx_sender)
             .with_sync_state(sync_state)
             .build(stop_receiver.clone())
@@ -232,6 +271,7 @@ async fn init_tasks(
         tree_handle,
         gas_adjuster_handle,
         consistency_checker_handle,
+        backfill_handle,
     ]);
 
     (task_handles, stop_sender, healthcheck_handle)
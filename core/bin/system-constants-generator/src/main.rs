@@ -14,6 +14,7 @@ use vm::{
 use zksync_types::{
     IntrinsicSystemGasConstants, GUARANTEED_PUBDATA_IN_TX, L1_GAS_PER_PUBDATA_BYTE,
     MAX_GAS_PER_PUBDATA_BYTE, MAX_NEW_FACTORY_DEPS, MAX_TXS_IN_BLOCK,
+    P256VERIFY_CIRCUIT_COST_IN_ERGS,
 };
 
 mod intrinsic_costs;
@@ -94,6 +95,7 @@ struct L2SystemConfig {
     keccak_round_cost_gas: u32,
     sha256_round_cost_gas: u32,
     ecrecover_cost_gas: u32,
+    p256verify_cost_gas: u32,
 }
 
 pub fn generate_l2_contracts_system_config(gas_constants: &IntrinsicSystemGasConstants) -> String {
@@ -113,6 +115,7 @@ pub fn generate_l2_contracts_system_config(gas_constants: &IntrinsicSystemGasCon
         keccak_round_cost_gas: KECCAK256_CIRCUIT_COST_IN_ERGS,
         sha256_round_cost_gas: SHA256_CIRCUIT_COST_IN_ERGS,
         ecrecover_cost_gas: ECRECOVER_CIRCUIT_COST_IN_ERGS,
+        p256verify_cost_gas: P256VERIFY_CIRCUIT_COST_IN_ERGS,
     };
 
     serde_json::to_string_pretty(&l2_contracts_config).unwrap()
@@ -459,6 +459,7 @@ impl EthNamespaceT for InMemoryNode {
                 logs_bloom: Default::default(),
                 transaction_type: None,
                 effective_gas_price: Some(500.into()),
+                confirmation_status: None,
             }
         });
 
@@ -502,6 +503,10 @@ impl EthNamespaceT for InMemoryNode {
         not_implemented()
     }
 
+    fn max_priority_fee_per_gas(&self) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<U256>> {
+        not_implemented()
+    }
+
     fn new_filter(&self, _filter: Filter) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<U256>> {
         not_implemented()
     }
@@ -70,6 +70,7 @@ impl ZksNamespaceT for ZkMockNamespaceImpl {
         &self,
         _from: u32,
         _limit: u8,
+        _filter: Option<zksync_types::api::TokenFilter>,
     ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<Vec<zksync_web3_decl::types::Token>>> {
         not_implemented!()
     }
@@ -84,6 +85,7 @@ impl ZksNamespaceT for ZkMockNamespaceImpl {
     fn get_all_account_balances(
         &self,
         _address: zksync_basic_types::Address,
+        _filter: Option<zksync_types::api::BalancesFilter>,
     ) -> jsonrpc_core::BoxFuture<
         jsonrpc_core::Result<std::collections::HashMap<zksync_basic_types::Address, U256>>,
     > {
@@ -110,6 +112,25 @@ impl ZksNamespaceT for ZkMockNamespaceImpl {
         not_implemented!()
     }
 
+    fn get_l2_to_l1_log_proofs(
+        &self,
+        _tx_hash: zksync_basic_types::H256,
+        _indices: Vec<usize>,
+    ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<Vec<Option<zksync_types::api::L2ToL1LogProof>>>>
+    {
+        not_implemented!()
+    }
+
+    fn get_withdrawal_finalization_params(
+        &self,
+        _tx_hash: zksync_basic_types::H256,
+        _index: Option<usize>,
+    ) -> jsonrpc_core::BoxFuture<
+        jsonrpc_core::Result<Option<zksync_types::api::WithdrawalFinalizationParams>>,
+    > {
+        not_implemented!()
+    }
+
     fn get_l1_batch_number(
         &self,
     ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<zksync_basic_types::U64>> {
@@ -170,4 +191,24 @@ impl ZksNamespaceT for ZkMockNamespaceImpl {
     ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<zksync_basic_types::U64>> {
         not_implemented!()
     }
+
+    fn get_gas_per_pubdata(
+        &self,
+    ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<zksync_basic_types::U64>> {
+        not_implemented!()
+    }
+
+    fn get_protocol_version(
+        &self,
+        _version_id: Option<u16>,
+    ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<Option<zksync_types::api::ProtocolVersion>>>
+    {
+        not_implemented!()
+    }
+
+    fn get_operator_address(
+        &self,
+    ) -> jsonrpc_core::BoxFuture<jsonrpc_core::Result<zksync_basic_types::Address>> {
+        not_implemented!()
+    }
 }
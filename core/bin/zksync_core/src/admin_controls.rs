@@ -0,0 +1,172 @@
+//! Operator controls shared between the node components and the `admin` Web3 namespace.
+//!
+//! The controls only affect components running in the same process; e.g., requesting an L1 batch seal
+//! has no effect if the state keeper runs on another machine.
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc, RwLock,
+    },
+};
+
+use zksync_health_check::{CheckHealth, CheckHealthStatus};
+use zksync_types::{
+    api::{ComponentHealth, OperatorBalance},
+    L1BatchNumber,
+};
+
+#[derive(Clone, Default)]
+pub struct AdminControls {
+    tx_admission_paused: Arc<AtomicBool>,
+    l1_batch_seal_requested: Arc<AtomicBool>,
+    /// Last L1 batch for which the execution delay is skipped; 0 if the delay isn't skipped for any batch.
+    execution_delay_skipped_until: Arc<AtomicU32>,
+    health_checks: Arc<RwLock<Vec<Arc<dyn CheckHealth>>>>,
+    /// Latest operator balance reported by `eth_tx_aggregator`.
+    operator_balance: Arc<RwLock<Option<OperatorBalance>>>,
+}
+
+impl fmt::Debug for AdminControls {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("AdminControls")
+            .field("tx_admission_paused", &self.is_tx_admission_paused())
+            .field(
+                "l1_batch_seal_requested",
+                &self.l1_batch_seal_requested.load(Ordering::Relaxed),
+            )
+            .field(
+                "execution_delay_skipped_until",
+                &self.execution_delay_skipped_until(),
+            )
+            .field("operator_balance", &self.operator_balance())
+            .finish_non_exhaustive()
+    }
+}
+
+impl AdminControls {
+    /// Makes the API servers reject all incoming transactions until [`Self::resume_tx_admission()`]
+    /// is called.
+    pub fn pause_tx_admission(&self) {
+        self.tx_admission_paused.store(true, Ordering::Relaxed);
+    }
+
+    pub fn resume_tx_admission(&self) {
+        self.tx_admission_paused.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_tx_admission_paused(&self) -> bool {
+        self.tx_admission_paused.load(Ordering::Relaxed)
+    }
+
+    /// Requests the state keeper to seal the current L1 batch as soon as it contains at least one transaction.
+    pub fn request_l1_batch_seal(&self) {
+        self.l1_batch_seal_requested.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether an L1 batch seal was requested, resetting the request.
+    pub(crate) fn take_l1_batch_seal_request(&self) -> bool {
+        self.l1_batch_seal_requested.swap(false, Ordering::Relaxed)
+    }
+
+    /// Allows executing L1 batches up to and including `l1_batch_number` on L1 without waiting for the execution
+    /// delay to pass. Intended for emergencies, e.g., if a critical fix must be executed as soon as possible.
+    pub fn skip_execution_delay(&self, l1_batch_number: L1BatchNumber) {
+        self.execution_delay_skipped_until
+            .fetch_max(l1_batch_number.0, Ordering::Relaxed);
+    }
+
+    /// Returns the last L1 batch for which the execution delay is skipped.
+    pub(crate) fn execution_delay_skipped_until(&self) -> Option<L1BatchNumber> {
+        let number = self.execution_delay_skipped_until.load(Ordering::Relaxed);
+        (number > 0).then_some(L1BatchNumber(number))
+    }
+
+    pub(crate) fn set_operator_balance(&self, balance: OperatorBalance) {
+        *self
+            .operator_balance
+            .write()
+            .expect("operator balance lock is poisoned") = Some(balance);
+    }
+
+    /// Returns the latest operator balance, or `None` if `eth_tx_aggregator` doesn't run in this process.
+    pub fn operator_balance(&self) -> Option<OperatorBalance> {
+        self.operator_balance
+            .read()
+            .expect("operator balance lock is poisoned")
+            .clone()
+    }
+
+    pub(crate) fn set_health_checks(&self, health_checks: Vec<Arc<dyn CheckHealth>>) {
+        *self
+            .health_checks
+            .write()
+            .expect("health checks lock is poisoned") = health_checks;
+    }
+
+    /// Checks health of all registered components, returning the component name and its status.
+    pub async fn check_health(&self) -> Vec<(&'static str, CheckHealthStatus)> {
+        let health_checks = self
+            .health_checks
+            .read()
+            .expect("health checks lock is poisoned")
+            .clone();
+        let mut statuses = Vec::with_capacity(health_checks.len());
+        for health_check in health_checks {
+            statuses.push((health_check.name(), health_check.check_health().await));
+        }
+        statuses
+    }
+
+    /// Same as [`Self::check_health()`], but converts the statuses to the API format.
+    pub async fn component_health(&self) -> Vec<ComponentHealth> {
+        let statuses = self.check_health().await;
+        statuses
+            .into_iter()
+            .map(|(name, status)| {
+                let (ready, message) = match status {
+                    CheckHealthStatus::Ready => (true, None),
+                    CheckHealthStatus::NotReady(message) => (false, Some(message)),
+                };
+                ComponentHealth {
+                    name: name.to_owned(),
+                    ready,
+                    message,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn l1_batch_seal_request_is_taken_once() {
+        let controls = AdminControls::default();
+        assert!(!controls.take_l1_batch_seal_request());
+        controls.clone().request_l1_batch_seal();
+        assert!(controls.take_l1_batch_seal_request());
+        assert!(!controls.take_l1_batch_seal_request());
+    }
+
+    #[test]
+    fn skipping_execution_delay() {
+        let controls = AdminControls::default();
+        assert_eq!(controls.execution_delay_skipped_until(), None);
+        controls.skip_execution_delay(L1BatchNumber(5));
+        assert_eq!(
+            controls.execution_delay_skipped_until(),
+            Some(L1BatchNumber(5))
+        );
+        // The delay cannot be restored for batches for which it was skipped.
+        controls.skip_execution_delay(L1BatchNumber(3));
+        assert_eq!(
+            controls.execution_delay_skipped_until(),
+            Some(L1BatchNumber(5))
+        );
+    }
+}
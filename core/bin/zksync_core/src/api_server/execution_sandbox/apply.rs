@@ -30,6 +30,7 @@ use zksync_types::{
 use zksync_utils::{h256_to_u256, time::seconds_since_epoch, u256_to_h256};
 
 use super::{vm_metrics, BlockArgs, TxExecutionArgs, TxSharedArgs};
+use crate::state_keeper::{PendingStateOverlay, PendingStorage};
 
 #[allow(clippy::too_many_arguments)]
 pub(super) fn apply_vm_in_sandbox<T>(
@@ -71,6 +72,11 @@ pub(super) fn apply_vm_in_sandbox<T>(
 
     let storage = PostgresStorage::new(rt_handle, connection, state_block_number, false)
         .with_factory_deps_cache(shared_args.factory_deps_cache.clone());
+    let pending_state = match &shared_args.pending_storage_diffs {
+        Some(diffs) if block_args.is_pending_miniblock() => diffs.overlay_after(state_block_number),
+        _ => PendingStateOverlay::default(),
+    };
+    let storage = PendingStorage::new(storage, pending_state);
     // Moving `storage_read_cache` to `storage_view`. It will be moved back once execution is finished and `storage_view` is not needed.
     let mut storage_view = StorageView::new_with_read_keys(storage, storage_read_cache);
 
@@ -95,6 +101,9 @@ pub(super) fn apply_vm_in_sandbox<T>(
     }
 
     let mut oracle_tools = vm::OracleTools::new(&mut storage_view, HistoryDisabled);
+    if let Some(cache) = &shared_args.bytecode_cache {
+        oracle_tools = oracle_tools.with_bytecode_cache(cache.clone());
+    }
     let block_properties = BlockProperties {
         default_aa_code_hash: h256_to_u256(shared_args.base_system_contracts.default_aa.hash),
         zkporter_is_available: ZKPORTER_IS_AVAILABLE,
@@ -129,6 +138,7 @@ pub(super) fn apply_vm_in_sandbox<T>(
         execution_args.execution_mode,
         &shared_args.base_system_contracts,
     );
+    vm.set_output_limits(execution_args.output_limits.hard);
 
     metrics::histogram!("api.web3.sandbox", stage_started_at.elapsed(), "stage" => "initialization");
     span.exit();
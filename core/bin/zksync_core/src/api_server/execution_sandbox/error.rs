@@ -29,6 +29,8 @@ pub(crate) enum SandboxExecutionError {
     UnexpectedVMBehavior(String),
     #[error("Transaction is unexecutable. Reason: {0}")]
     Unexecutable(String),
+    #[error("Execution output limit exceeded: {0}")]
+    OutputLimitExceeded(String),
 }
 
 impl From<TxRevertReason> for SandboxExecutionError {
@@ -78,6 +80,12 @@ impl From<TxRevertReason> for SandboxExecutionError {
                 SandboxExecutionError::Revert(TxRevertReason::TooBigGasLimit.to_string(), vec![])
             }
             TxRevertReason::MissingInvocationLimitReached => SandboxExecutionError::InnerTxError,
+            TxRevertReason::ExecutionTimeout => SandboxExecutionError::UnexpectedVMBehavior(
+                TxRevertReason::ExecutionTimeout.to_string(),
+            ),
+            TxRevertReason::OutputLimitExceeded(reason) => {
+                SandboxExecutionError::OutputLimitExceeded(reason)
+            }
         }
     }
 }
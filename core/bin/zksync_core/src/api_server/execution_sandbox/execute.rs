@@ -2,14 +2,15 @@
 
 use tracing::{span, Level};
 
-use std::{collections::HashMap, mem};
+use std::{collections::HashMap, iter, mem};
 
 use vm::{
     utils::ETH_CALL_GAS_LIMIT,
+    vm::VmTxExecutionResult,
     vm_with_bootloader::{
         push_transaction_to_bootloader_memory, BootloaderJobType, TxExecutionMode,
     },
-    VmExecutionResult,
+    TxRevertReason, VmExecutionResult,
 };
 use zksync_dal::ConnectionPool;
 use zksync_types::{
@@ -17,7 +18,10 @@ use zksync_types::{
     Transaction, H256, U256,
 };
 
-use super::{apply, error::SandboxExecutionError, vm_metrics, BlockArgs, TxSharedArgs, VmPermit};
+use super::{
+    apply, error::SandboxExecutionError, vm_metrics, BlockArgs, SandboxOutputLimits, TxSharedArgs,
+    VmPermit,
+};
 
 #[derive(Debug)]
 pub(crate) struct TxExecutionArgs {
@@ -25,6 +29,7 @@ pub(crate) struct TxExecutionArgs {
     pub enforced_nonce: Option<Nonce>,
     pub added_balance: U256,
     pub enforced_base_fee: Option<u64>,
+    pub output_limits: SandboxOutputLimits,
 }
 
 impl TxExecutionArgs {
@@ -34,12 +39,14 @@ impl TxExecutionArgs {
             enforced_nonce: Some(tx.nonce()),
             added_balance: U256::zero(),
             enforced_base_fee: Some(tx.common_data.fee.max_fee_per_gas.as_u64()),
+            output_limits: SandboxOutputLimits::default(),
         }
     }
 
     fn for_eth_call(
         enforced_base_fee: u64,
         vm_execution_cache_misses_limit: Option<usize>,
+        output_limits: SandboxOutputLimits,
     ) -> Self {
         let missed_storage_invocation_limit = vm_execution_cache_misses_limit.unwrap_or(usize::MAX);
         Self {
@@ -49,11 +56,13 @@ impl TxExecutionArgs {
             enforced_nonce: None,
             added_balance: U256::zero(),
             enforced_base_fee: Some(enforced_base_fee),
+            output_limits,
         }
     }
 
     pub fn for_gas_estimate(
         vm_execution_cache_misses_limit: Option<usize>,
+        output_limits: SandboxOutputLimits,
         tx: &Transaction,
         base_fee: u64,
     ) -> Self {
@@ -72,6 +81,7 @@ impl TxExecutionArgs {
             enforced_nonce: tx.nonce(),
             added_balance,
             enforced_base_fee: Some(base_fee),
+            output_limits,
         }
     }
 }
@@ -84,11 +94,15 @@ pub(crate) async fn execute_tx_eth_call(
     mut tx: L2Tx,
     block_args: BlockArgs,
     vm_execution_cache_misses_limit: Option<usize>,
+    output_limits: SandboxOutputLimits,
     trace_call: bool,
 ) -> Result<VmExecutionResult, SandboxExecutionError> {
     let enforced_base_fee = tx.common_data.fee.max_fee_per_gas.as_u64();
-    let execution_args =
-        TxExecutionArgs::for_eth_call(enforced_base_fee, vm_execution_cache_misses_limit);
+    let execution_args = TxExecutionArgs::for_eth_call(
+        enforced_base_fee,
+        vm_execution_cache_misses_limit,
+        output_limits,
+    );
 
     // Protection against infinite-loop eth_calls and alike:
     // limiting the amount of gas the call can use.
@@ -110,6 +124,106 @@ pub(crate) async fn execute_tx_eth_call(
     vm_result
 }
 
+/// Re-executes an already included transaction with the call tracer attached.
+///
+/// `block_args` should point to the state the transaction is executed on top of. Unlike other
+/// sandbox methods, reverted transactions are not treated as errors: the returned result contains
+/// the revert reason along with the collected call trace. If `attribute_pubdata` is set, calls
+/// in the trace are annotated with the pubdata they have produced.
+pub(crate) async fn execute_tx_for_tracing(
+    vm_permit: &VmPermit<'_>, // Proof that permit was acquired.
+    shared_args: TxSharedArgs,
+    connection_pool: ConnectionPool,
+    tx: Transaction,
+    block_args: BlockArgs,
+    enforced_base_fee: u64,
+    attribute_pubdata: bool,
+) -> VmExecutionResult {
+    let execution_args = TxExecutionArgs {
+        execution_mode: TxExecutionMode::VerifyExecute,
+        enforced_nonce: tx.nonce(),
+        added_balance: U256::zero(),
+        enforced_base_fee: Some(enforced_base_fee),
+        output_limits: SandboxOutputLimits::default(),
+    };
+
+    run_vm_in_sandbox(
+        vm_permit,
+        shared_args,
+        execution_args,
+        connection_pool,
+        tx,
+        block_args,
+        BootloaderJobType::TransactionExecution,
+        true,
+        attribute_pubdata,
+        &mut HashMap::new(),
+    )
+    .await
+}
+
+/// Executes a bundle of transactions one after another in a single VM on top of the state at `block_args`.
+///
+/// State changes made by a transaction are visible to the following transactions in the bundle; nothing
+/// is persisted. Execution stops at the first transaction rejected by the VM (e.g., because of an invalid nonce
+/// or signature), so if the bundle has a rejected transaction, its error is the last returned result.
+pub(crate) async fn execute_bundle_in_sandbox(
+    vm_permit: &VmPermit<'_>, // Proof that permit was acquired.
+    shared_args: TxSharedArgs,
+    connection_pool: ConnectionPool,
+    txs: Vec<Transaction>,
+    block_args: BlockArgs,
+    validation_computational_gas_limit: u32,
+    output_limits: SandboxOutputLimits,
+) -> Vec<Result<VmTxExecutionResult, TxRevertReason>> {
+    // Nonces and balances are not overridden, so that transactions are executed exactly as in a block.
+    let execution_args = TxExecutionArgs {
+        execution_mode: TxExecutionMode::VerifyExecute,
+        enforced_nonce: None,
+        added_balance: U256::zero(),
+        enforced_base_fee: None,
+        output_limits,
+    };
+    let mut txs = txs.into_iter();
+    let first_tx = txs.next().expect("transaction bundle is empty");
+
+    let rt_handle = vm_permit.rt_handle();
+    tokio::task::spawn_blocking(move || {
+        let span = span!(Level::DEBUG, "execute_bundle_in_sandbox").entered();
+        let (results, _) = apply::apply_vm_in_sandbox(
+            rt_handle,
+            &shared_args,
+            &execution_args,
+            &connection_pool,
+            first_tx,
+            block_args,
+            HashMap::new(),
+            |vm, first_tx| {
+                let mut results = vec![];
+                for tx in iter::once(first_tx).chain(txs) {
+                    push_transaction_to_bootloader_memory(
+                        vm,
+                        &tx,
+                        TxExecutionMode::VerifyExecute,
+                        None,
+                    );
+                    let result = vm.execute_next_tx(validation_computational_gas_limit, true);
+                    let is_rejected = result.is_err();
+                    results.push(result);
+                    if is_rejected {
+                        break;
+                    }
+                }
+                results
+            },
+        );
+        span.exit();
+        results
+    })
+    .await
+    .unwrap()
+}
+
 #[tracing::instrument(skip_all)]
 pub(crate) async fn execute_tx_with_pending_state(
     vm_permit: &VmPermit<'_>, // Proof that permit was acquired.
@@ -166,7 +280,48 @@ async fn execute_tx_in_sandbox(
         .factory_deps
         .as_ref()
         .map_or(0, |deps| deps.len() as u16);
+    let output_limits = execution_args.output_limits;
+    let tx_hash = tx.hash();
 
+    let execution_result = run_vm_in_sandbox(
+        vm_permit,
+        shared_args,
+        execution_args,
+        connection_pool,
+        tx,
+        block_args,
+        job_type,
+        trace_call,
+        false,
+        storage_read_cache,
+    )
+    .await;
+
+    output_limits.report_soft_limits_violations(tx_hash, &execution_result);
+    let tx_execution_metrics =
+        vm_metrics::collect_tx_execution_metrics(total_factory_deps, &execution_result);
+    let result = match execution_result.revert_reason {
+        None => Ok(execution_result),
+        Some(revert) => Err(revert.revert_reason.into()),
+    };
+    (result, tx_execution_metrics)
+}
+
+/// Runs the transaction in the VM and returns the raw execution result, without interpreting
+/// the revert reason.
+#[allow(clippy::too_many_arguments)]
+async fn run_vm_in_sandbox(
+    vm_permit: &VmPermit<'_>,
+    shared_args: TxSharedArgs,
+    execution_args: TxExecutionArgs,
+    connection_pool: ConnectionPool,
+    tx: Transaction,
+    block_args: BlockArgs,
+    job_type: BootloaderJobType,
+    trace_call: bool,
+    attribute_pubdata: bool,
+    storage_read_cache: &mut HashMap<StorageKey, H256>,
+) -> VmExecutionResult {
     let rt_handle = vm_permit.rt_handle();
     let moved_cache = mem::take(storage_read_cache);
     let (execution_result, moved_cache) = tokio::task::spawn_blocking(move || {
@@ -182,7 +337,9 @@ async fn execute_tx_in_sandbox(
             moved_cache,
             |vm, tx| {
                 push_transaction_to_bootloader_memory(vm, &tx, execution_mode, None);
-                let result = if trace_call {
+                let result = if trace_call && attribute_pubdata {
+                    vm.execute_till_block_end_with_zk_tracer(job_type)
+                } else if trace_call {
                     vm.execute_till_block_end_with_call_tracer(job_type)
                 } else {
                     vm.execute_till_block_end(job_type)
@@ -197,12 +354,5 @@ async fn execute_tx_in_sandbox(
     .unwrap();
 
     *storage_read_cache = moved_cache;
-
-    let tx_execution_metrics =
-        vm_metrics::collect_tx_execution_metrics(total_factory_deps, &execution_result);
-    let result = match execution_result.revert_reason {
-        None => Ok(execution_result),
-        Some(revert) => Err(revert.revert_reason.into()),
-    };
-    (result, tx_execution_metrics)
+    execution_result
 }
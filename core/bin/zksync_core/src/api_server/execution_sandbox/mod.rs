@@ -1,14 +1,19 @@
 use std::time::{Duration, Instant};
 
 use tokio::runtime::{Handle, Runtime};
-use vm::vm_with_bootloader::derive_base_fee_and_gas_per_pubdata;
+use vm::{
+    vm_with_bootloader::{derive_base_fee_and_gas_per_pubdata, ExecutionOutputLimits},
+    VmExecutionResult,
+};
 use zksync_config::constants::PUBLISH_BYTECODE_OVERHEAD;
 use zksync_contracts::BaseSystemContracts;
-use zksync_dal::{ConnectionPool, SqlxError, StorageProcessor};
-use zksync_state::{FactoryDepsCache, PostgresStorage, ReadStorage, StorageView};
-use zksync_types::{api, AccountTreeId, MiniblockNumber, U256};
+use zksync_dal::{pruning_dal::HistoryQueryError, ConnectionPool, StorageProcessor};
+use zksync_state::{BytecodeCache, FactoryDepsCache, PostgresStorage, ReadStorage, StorageView};
+use zksync_types::{api, AccountTreeId, MiniblockNumber, H256, U256};
 use zksync_utils::bytecode::{compress_bytecode, hash_bytecode};
 
+use crate::state_keeper::PendingStorageDiffs;
+
 // Note: keep the modules private, and instead re-export functions that make public interface.
 mod apply;
 mod error;
@@ -18,7 +23,10 @@ mod vm_metrics;
 
 pub(super) use self::{
     error::SandboxExecutionError,
-    execute::{execute_tx_eth_call, execute_tx_with_pending_state, TxExecutionArgs},
+    execute::{
+        execute_bundle_in_sandbox, execute_tx_eth_call, execute_tx_for_tracing,
+        execute_tx_with_pending_state, TxExecutionArgs,
+    },
 };
 
 /// Permit to invoke VM code.
@@ -209,6 +217,78 @@ pub(crate) struct TxSharedArgs {
     pub fair_l2_gas_price: u64,
     pub base_system_contracts: BaseSystemContracts,
     pub factory_deps_cache: FactoryDepsCache,
+    pub bytecode_cache: Option<BytecodeCache>,
+    /// Storage diffs of miniblocks executed by the state keeper, but not yet persisted. If set, they are layered
+    /// over the persisted state when executing against the pending block.
+    pub pending_storage_diffs: Option<PendingStorageDiffs>,
+}
+
+/// Soft and hard limits on the output of `eth_call` and gas estimation executions. The limits protect
+/// the API server from calls producing excessive amounts of data.
+///
+/// Executions exceeding a hard limit are aborted with an error. Executions exceeding a soft limit
+/// succeed, but are logged and reported in metrics, so that hard limits can be tuned.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SandboxOutputLimits {
+    soft: ExecutionOutputLimits,
+    hard: ExecutionOutputLimits,
+}
+
+impl SandboxOutputLimits {
+    /// Creates limits based on the hard limits (`None` means no limit). Soft limits are set
+    /// to `soft_limits_ratio` of the corresponding hard limits.
+    pub fn new(
+        max_returndata_size: Option<usize>,
+        max_events: Option<usize>,
+        max_storage_accesses: Option<usize>,
+        soft_limits_ratio: f64,
+    ) -> Self {
+        let hard = ExecutionOutputLimits {
+            max_returndata_size: max_returndata_size.unwrap_or(usize::MAX),
+            max_events: max_events.unwrap_or(usize::MAX),
+            max_storage_accesses: max_storage_accesses.unwrap_or(usize::MAX),
+        };
+        let soft_limit = |limit: usize| {
+            if limit == usize::MAX {
+                limit
+            } else {
+                (limit as f64 * soft_limits_ratio) as usize
+            }
+        };
+        let soft = ExecutionOutputLimits {
+            max_returndata_size: soft_limit(hard.max_returndata_size),
+            max_events: soft_limit(hard.max_events),
+            max_storage_accesses: soft_limit(hard.max_storage_accesses),
+        };
+        Self { soft, hard }
+    }
+
+    /// Reports an execution result exceeding soft limits. Storage accesses are estimated based on
+    /// the storage logs in the result, so the estimate may be lower than the number of accesses
+    /// checked against the hard limit.
+    fn report_soft_limits_violations(&self, tx_hash: H256, result: &VmExecutionResult) {
+        let output = [
+            (
+                "returndata_size",
+                result.return_data.len() * 32,
+                self.soft.max_returndata_size,
+            ),
+            ("events", result.events.len(), self.soft.max_events),
+            (
+                "storage_accesses",
+                result.storage_log_queries.len(),
+                self.soft.max_storage_accesses,
+            ),
+        ];
+        for (name, value, soft_limit) in output {
+            if value > soft_limit {
+                vlog::warn!(
+                    "Execution of transaction {tx_hash:?} exceeded soft limit on {name}: {value} > {soft_limit}"
+                );
+                metrics::increment_counter!("api.web3.sandbox.soft_limit_exceeded", "limit" => name);
+            }
+        }
+    }
 }
 
 /// Information about a block provided to VM.
@@ -229,16 +309,24 @@ impl BlockArgs {
         }
     }
 
-    /// Loads block information from DB.
+    /// Loads block information from DB. Returns an error if the state at the block is pruned.
     pub async fn new(
         connection: &mut StorageProcessor<'_>,
         block_id: api::BlockId,
-    ) -> Result<Option<Self>, SqlxError> {
+    ) -> Result<Option<Self>, HistoryQueryError> {
         let resolved_block_number = connection
             .blocks_web3_dal()
             .resolve_block_id(block_id)
             .await?;
-        let Some(resolved_block_number) = resolved_block_number else { return Ok(None) };
+        let Some(resolved_block_number) = resolved_block_number else {
+            return Ok(None);
+        };
+        if !matches!(block_id, api::BlockId::Number(api::BlockNumber::Pending)) {
+            connection
+                .storage_web3_dal()
+                .check_state_history(resolved_block_number)
+                .await?;
+        }
 
         let block_timestamp_s = connection
             .blocks_web3_dal()
@@ -250,4 +338,8 @@ impl BlockArgs {
             block_timestamp_s,
         }))
     }
+
+    pub fn resolved_block_number(&self) -> MiniblockNumber {
+        self.resolved_block_number
+    }
 }
@@ -1,7 +1,7 @@
 //! Module responsible for observing the VM behavior, i.e. calculating the statistics of the VM runs
 //! or reporting the VM memory usage.
 
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
 use vm::{HistoryMode, VmExecutionResult, VmInstance};
 use zksync_state::StorageViewMetrics;
@@ -9,6 +9,7 @@ use zksync_types::{
     event::{extract_long_l2_to_l1_messages, extract_published_bytecodes},
     fee::TransactionExecutionMetrics,
     storage_writes_deduplicator::StorageWritesDeduplicator,
+    StorageLogQuery,
 };
 use zksync_utils::bytecode::bytecode_len_in_bytes;
 
@@ -153,9 +154,33 @@ pub(super) fn collect_tx_execution_metrics(
         total_log_queries: result.total_log_queries,
         cycles_used: result.cycles_used,
         computational_gas_used: result.computational_gas_used,
+        refunded_storage_writes: count_refunded_storage_writes(&result.storage_log_queries),
     }
 }
 
+/// Counts storage slots that were written during the execution, but have their initial values in the end.
+/// The sandbox executes a transaction on top of a fresh batch, so the initial values are the ones read
+/// by the first write to each slot.
+fn count_refunded_storage_writes(logs: &[StorageLogQuery]) -> usize {
+    let mut slots = HashMap::new();
+    for log in logs.iter().filter(|log| log.log_query.rw_flag) {
+        let query = &log.log_query;
+        let value = if query.rollback {
+            query.read_value
+        } else {
+            query.written_value
+        };
+        let (_, last_value) = slots
+            .entry((query.address, query.key))
+            .or_insert((query.read_value, value));
+        *last_value = value;
+    }
+    slots
+        .values()
+        .filter(|(initial_value, last_value)| initial_value == last_value)
+        .count()
+}
+
 /// Returns the sum of all oracles' sizes.
 pub(super) fn record_vm_memory_metrics<H: HistoryMode>(vm: &VmInstance<'_, H>) -> usize {
     let event_sink_inner = vm.state.event_sink.get_size();
@@ -28,14 +28,30 @@ async fn healthcheck(healthchecks: web::Data<[Box<dyn CheckHealth>]>) -> impl Re
     HttpResponse::Ok().json(response)
 }
 
+/// Returns the currently used log filtering directives.
+#[get("/log_directives")]
+async fn get_log_directives() -> impl Responder {
+    match vlog::log_directives() {
+        Some(message) => HttpResponse::Ok().json(Response { message }),
+        None => HttpResponse::NotFound().json(Response {
+            message: "Log directives cannot be changed at runtime".to_string(),
+        }),
+    }
+}
+
 fn run_server(bind_address: SocketAddr, healthchecks: Vec<Box<dyn CheckHealth>>) -> Server {
     let healthchecks: Arc<[Box<dyn CheckHealth>]> = healthchecks.into();
     let data = web::Data::from(healthchecks);
-    HttpServer::new(move || App::new().service(healthcheck).app_data(data.clone()))
-        .workers(1)
-        .bind(bind_address)
-        .unwrap()
-        .run()
+    HttpServer::new(move || {
+        App::new()
+            .service(healthcheck)
+            .service(get_log_directives)
+            .app_data(data.clone())
+    })
+    .workers(1)
+    .bind(bind_address)
+    .unwrap()
+    .run()
 }
 
 pub struct HealthCheckHandle {
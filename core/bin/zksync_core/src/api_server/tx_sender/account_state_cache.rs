@@ -0,0 +1,187 @@
+//! Cache of account nonces and balances used to validate submitted transactions.
+//!
+//! Entries are tagged with the number of the latest sealed miniblock at the time they were read from Postgres.
+//! Once the miniblock sealer reports a newer sealed miniblock, all entries read before it are discarded,
+//! so the cache never returns values older than the latest sealed state known to the API server.
+
+use tokio::sync::watch;
+
+use std::{collections::HashMap, sync::Mutex};
+
+use zksync_types::{Address, MiniblockNumber, Nonce, U256};
+
+#[derive(Debug, Clone, Copy)]
+struct CachedValue<T> {
+    value: T,
+    /// Latest sealed miniblock at the time the value was read.
+    read_at: MiniblockNumber,
+}
+
+#[derive(Debug)]
+struct CacheEntries {
+    nonces: HashMap<Address, CachedValue<Nonce>>,
+    balances: HashMap<Address, CachedValue<U256>>,
+    /// Latest sealed miniblock for which the cache was invalidated.
+    invalidated_at: MiniblockNumber,
+}
+
+impl CacheEntries {
+    fn new(invalidated_at: MiniblockNumber) -> Self {
+        Self {
+            nonces: HashMap::new(),
+            balances: HashMap::new(),
+            invalidated_at,
+        }
+    }
+
+    fn invalidate_if_outdated(&mut self, latest_sealed_miniblock: MiniblockNumber) {
+        if self.invalidated_at < latest_sealed_miniblock {
+            self.nonces.clear();
+            self.balances.clear();
+            self.invalidated_at = latest_sealed_miniblock;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.nonces.len() + self.balances.len()
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct AccountStateCache {
+    capacity: usize,
+    sealed_miniblocks: watch::Receiver<MiniblockNumber>,
+    entries: Mutex<CacheEntries>,
+}
+
+impl AccountStateCache {
+    pub fn new(capacity: usize, sealed_miniblocks: watch::Receiver<MiniblockNumber>) -> Self {
+        let entries = CacheEntries::new(*sealed_miniblocks.borrow());
+        Self {
+            capacity,
+            sealed_miniblocks,
+            entries: Mutex::new(entries),
+        }
+    }
+
+    fn latest_sealed_miniblock(&self) -> MiniblockNumber {
+        *self.sealed_miniblocks.borrow()
+    }
+
+    pub fn get_nonce(&self, address: Address) -> Option<Nonce> {
+        self.get(address, |entries| &entries.nonces, "nonce")
+    }
+
+    pub fn get_balance(&self, address: Address) -> Option<U256> {
+        self.get(address, |entries| &entries.balances, "balance")
+    }
+
+    fn get<T: Copy>(
+        &self,
+        address: Address,
+        map: impl FnOnce(&CacheEntries) -> &HashMap<Address, CachedValue<T>>,
+        kind: &'static str,
+    ) -> Option<T> {
+        let latest_sealed_miniblock = self.latest_sealed_miniblock();
+        let mut entries = self
+            .entries
+            .lock()
+            .expect("account state cache is poisoned");
+        entries.invalidate_if_outdated(latest_sealed_miniblock);
+        let value = map(&entries).get(&address).map(|cached| cached.value);
+
+        metrics::increment_counter!(
+            "api.tx_sender.account_state_cache",
+            "kind" => kind,
+            "result" => if value.is_some() { "hit" } else { "miss" }
+        );
+        value
+    }
+
+    pub fn insert_nonce(&self, address: Address, nonce: Nonce, read_at: MiniblockNumber) {
+        self.insert(address, nonce, read_at, |entries| &mut entries.nonces);
+    }
+
+    pub fn insert_balance(&self, address: Address, balance: U256, read_at: MiniblockNumber) {
+        self.insert(address, balance, read_at, |entries| &mut entries.balances);
+    }
+
+    fn insert<T>(
+        &self,
+        address: Address,
+        value: T,
+        read_at: MiniblockNumber,
+        map: impl FnOnce(&mut CacheEntries) -> &mut HashMap<Address, CachedValue<T>>,
+    ) {
+        let latest_sealed_miniblock = self.latest_sealed_miniblock();
+        if read_at < latest_sealed_miniblock {
+            // The value was read from a lagging replica or before a miniblock got sealed; caching it
+            // would make the cache return outdated values until the next miniblock is sealed.
+            return;
+        }
+
+        let mut entries = self
+            .entries
+            .lock()
+            .expect("account state cache is poisoned");
+        entries.invalidate_if_outdated(latest_sealed_miniblock);
+        if entries.len() >= self.capacity {
+            return;
+        }
+        map(&mut entries).insert(address, CachedValue { value, read_at });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_are_invalidated_on_miniblock_seal() {
+        let (sender, receiver) = watch::channel(MiniblockNumber(1));
+        let cache = AccountStateCache::new(10, receiver);
+        let account = Address::repeat_byte(1);
+        assert_eq!(cache.get_nonce(account), None);
+
+        cache.insert_nonce(account, Nonce(3), MiniblockNumber(1));
+        cache.insert_balance(account, U256::from(100), MiniblockNumber(1));
+        assert_eq!(cache.get_nonce(account), Some(Nonce(3)));
+        assert_eq!(cache.get_balance(account), Some(U256::from(100)));
+
+        // Once a new sealed miniblock is reported, all entries read before it are discarded.
+        sender.send_replace(MiniblockNumber(2));
+        assert_eq!(cache.get_nonce(account), None);
+        assert_eq!(cache.get_balance(account), None);
+
+        cache.insert_nonce(account, Nonce(4), MiniblockNumber(2));
+        assert_eq!(cache.get_nonce(account), Some(Nonce(4)));
+    }
+
+    #[test]
+    fn outdated_reads_are_not_cached() {
+        let (sender, receiver) = watch::channel(MiniblockNumber(1));
+        let cache = AccountStateCache::new(10, receiver);
+        let account = Address::repeat_byte(1);
+
+        // E.g., a transaction was rejected based on the state read from a lagging replica.
+        sender.send_replace(MiniblockNumber(2));
+        cache.insert_nonce(account, Nonce(3), MiniblockNumber(1));
+        cache.insert_balance(account, U256::zero(), MiniblockNumber(1));
+        assert_eq!(cache.get_nonce(account), None);
+        assert_eq!(cache.get_balance(account), None);
+
+        // Values read at a miniblock newer than the notified one are fine to cache.
+        cache.insert_balance(account, U256::one(), MiniblockNumber(3));
+        assert_eq!(cache.get_balance(account), Some(U256::one()));
+    }
+
+    #[test]
+    fn cache_capacity_is_respected() {
+        let (_sender, receiver) = watch::channel(MiniblockNumber(0));
+        let cache = AccountStateCache::new(1, receiver);
+        cache.insert_nonce(Address::repeat_byte(1), Nonce(1), MiniblockNumber(0));
+        cache.insert_nonce(Address::repeat_byte(2), Nonce(2), MiniblockNumber(0));
+        assert_eq!(cache.get_nonce(Address::repeat_byte(1)), Some(Nonce(1)));
+        assert_eq!(cache.get_nonce(Address::repeat_byte(2)), None);
+    }
+}
@@ -0,0 +1,257 @@
+//! Policies deciding whether a transaction is admitted to the mempool.
+
+use std::{
+    collections::HashSet,
+    fmt,
+    sync::{Arc, RwLock},
+};
+
+use zksync_config::configs::api::Web3JsonRpcConfig;
+use zksync_types::{api::AdmissionPoliciesConfig, l2::L2Tx, Address, CONTRACT_DEPLOYER_ADDRESS};
+
+use super::SubmitTxError;
+
+/// Hook checked for every submitted transaction before it is inserted into the mempool.
+/// Permissioned chains can provide their own implementation via `TxSenderBuilder::with_admission_policy`.
+pub trait AdmissionPolicy: 'static + fmt::Debug + Send + Sync {
+    /// Name of the policy reported to the user if a transaction is rejected.
+    fn name(&self) -> &'static str;
+
+    /// Checks whether the transaction may be admitted, returning the rejection reason otherwise.
+    fn check(&self, tx: &L2Tx) -> Result<(), String>;
+}
+
+/// Allows only the listed accounts to deploy contracts.
+#[derive(Debug, Clone)]
+pub struct DeployerAllowlistPolicy {
+    allowlist: HashSet<Address>,
+}
+
+impl DeployerAllowlistPolicy {
+    pub fn new(allowlist: HashSet<Address>) -> Self {
+        Self { allowlist }
+    }
+}
+
+impl AdmissionPolicy for DeployerAllowlistPolicy {
+    fn name(&self) -> &'static str {
+        "deployer_allowlist"
+    }
+
+    fn check(&self, tx: &L2Tx) -> Result<(), String> {
+        let initiator = tx.initiator_account();
+        if tx.execute.contract_address == CONTRACT_DEPLOYER_ADDRESS
+            && !self.allowlist.contains(&initiator)
+        {
+            return Err(format!(
+                "account {initiator:?} is not allowed to deploy contracts"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Denies calls to the listed contracts.
+#[derive(Debug, Clone)]
+pub struct CalleeDenylistPolicy {
+    denylist: HashSet<Address>,
+}
+
+impl CalleeDenylistPolicy {
+    pub fn new(denylist: HashSet<Address>) -> Self {
+        Self { denylist }
+    }
+}
+
+impl AdmissionPolicy for CalleeDenylistPolicy {
+    fn name(&self) -> &'static str {
+        "callee_denylist"
+    }
+
+    fn check(&self, tx: &L2Tx) -> Result<(), String> {
+        let callee = tx.execute.contract_address;
+        if self.denylist.contains(&callee) {
+            return Err(format!("calls to {callee:?} are not allowed"));
+        }
+        Ok(())
+    }
+}
+
+/// Limits the size of the transaction calldata.
+#[derive(Debug, Clone, Copy)]
+pub struct MaxCalldataSizePolicy {
+    max_size: usize,
+}
+
+impl MaxCalldataSizePolicy {
+    pub fn new(max_size: usize) -> Self {
+        Self { max_size }
+    }
+}
+
+impl AdmissionPolicy for MaxCalldataSizePolicy {
+    fn name(&self) -> &'static str {
+        "max_calldata_size"
+    }
+
+    fn check(&self, tx: &L2Tx) -> Result<(), String> {
+        let size = tx.execute.calldata.len();
+        if size > self.max_size {
+            return Err(format!(
+                "calldata is too large: {size} bytes provided, while only {} bytes allowed",
+                self.max_size
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Chain of admission policies. A transaction is admitted only if all policies allow it.
+///
+/// The chain consists of custom policies, which are fixed on creation, and built-in policies,
+/// which can be reconfigured at runtime (e.g., via the `admin` namespace).
+#[derive(Debug, Clone, Default)]
+pub struct AdmissionPolicyChain {
+    custom: Vec<Arc<dyn AdmissionPolicy>>,
+    built_in: Arc<RwLock<Vec<Arc<dyn AdmissionPolicy>>>>,
+}
+
+impl AdmissionPolicyChain {
+    pub(super) fn new(
+        custom: Vec<Arc<dyn AdmissionPolicy>>,
+        built_in_config: &AdmissionPoliciesConfig,
+    ) -> Self {
+        let this = Self {
+            custom,
+            built_in: Arc::default(),
+        };
+        this.configure_built_in(built_in_config);
+        this
+    }
+
+    pub fn built_in_config(config: &Web3JsonRpcConfig) -> AdmissionPoliciesConfig {
+        AdmissionPoliciesConfig {
+            deployer_allowlist: config.deployer_allowlist.clone(),
+            callee_denylist: config.callee_denylist(),
+            max_calldata_size: config.max_calldata_size,
+        }
+    }
+
+    /// Replaces built-in policies according to the provided config. Custom policies are retained.
+    pub fn configure_built_in(&self, config: &AdmissionPoliciesConfig) {
+        let mut policies: Vec<Arc<dyn AdmissionPolicy>> = vec![];
+        if let Some(allowlist) = &config.deployer_allowlist {
+            let allowlist = allowlist.iter().copied().collect();
+            policies.push(Arc::new(DeployerAllowlistPolicy::new(allowlist)));
+        }
+        if !config.callee_denylist.is_empty() {
+            let denylist = config.callee_denylist.iter().copied().collect();
+            policies.push(Arc::new(CalleeDenylistPolicy::new(denylist)));
+        }
+        if let Some(max_size) = config.max_calldata_size {
+            policies.push(Arc::new(MaxCalldataSizePolicy::new(max_size)));
+        }
+        *self
+            .built_in
+            .write()
+            .expect("admission policies lock is poisoned") = policies;
+    }
+
+    pub(super) fn check(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
+        let built_in = self
+            .built_in
+            .read()
+            .expect("admission policies lock is poisoned")
+            .clone();
+        for policy in built_in.iter().chain(&self.custom) {
+            if let Err(reason) = policy.check(tx) {
+                metrics::increment_counter!("api.tx_sender.admission_denied", "policy" => policy.name());
+                return Err(SubmitTxError::AdmissionDenied(policy.name(), reason));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{fee::Fee, Nonce, U256};
+
+    use super::*;
+
+    fn create_tx(initiator: Address, contract_address: Address, calldata: Vec<u8>) -> L2Tx {
+        L2Tx::new(
+            contract_address,
+            calldata,
+            Nonce(0),
+            Fee::default(),
+            initiator,
+            U256::zero(),
+            None,
+            Default::default(),
+        )
+    }
+
+    #[test]
+    fn built_in_policies() {
+        let deployer = Address::repeat_byte(1);
+        let other = Address::repeat_byte(2);
+        let denied_callee = Address::repeat_byte(3);
+        let config = AdmissionPoliciesConfig {
+            deployer_allowlist: Some(vec![deployer]),
+            callee_denylist: vec![denied_callee],
+            max_calldata_size: Some(4),
+        };
+        let chain = AdmissionPolicyChain::new(vec![], &config);
+
+        chain
+            .check(&create_tx(deployer, CONTRACT_DEPLOYER_ADDRESS, vec![]))
+            .unwrap();
+        assert!(matches!(
+            chain.check(&create_tx(other, CONTRACT_DEPLOYER_ADDRESS, vec![])),
+            Err(SubmitTxError::AdmissionDenied("deployer_allowlist", _))
+        ));
+        assert!(matches!(
+            chain.check(&create_tx(other, denied_callee, vec![])),
+            Err(SubmitTxError::AdmissionDenied("callee_denylist", _))
+        ));
+        assert!(matches!(
+            chain.check(&create_tx(other, Address::repeat_byte(4), vec![0; 5])),
+            Err(SubmitTxError::AdmissionDenied("max_calldata_size", _))
+        ));
+        chain
+            .check(&create_tx(other, Address::repeat_byte(4), vec![0; 4]))
+            .unwrap();
+
+        // Built-in policies can be relaxed at runtime.
+        chain.configure_built_in(&AdmissionPoliciesConfig::default());
+        chain
+            .check(&create_tx(other, CONTRACT_DEPLOYER_ADDRESS, vec![0; 5]))
+            .unwrap();
+    }
+
+    #[test]
+    fn custom_policies_are_retained() {
+        #[derive(Debug)]
+        struct DenyAll;
+
+        impl AdmissionPolicy for DenyAll {
+            fn name(&self) -> &'static str {
+                "deny_all"
+            }
+
+            fn check(&self, _tx: &L2Tx) -> Result<(), String> {
+                Err("denied".to_owned())
+            }
+        }
+
+        let chain =
+            AdmissionPolicyChain::new(vec![Arc::new(DenyAll)], &AdmissionPoliciesConfig::default());
+        chain.configure_built_in(&AdmissionPoliciesConfig::default());
+        let tx = create_tx(Address::repeat_byte(1), Address::repeat_byte(2), vec![]);
+        assert!(matches!(
+            chain.check(&tx),
+            Err(SubmitTxError::AdmissionDenied("deny_all", _))
+        ));
+    }
+}
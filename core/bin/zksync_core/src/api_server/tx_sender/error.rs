@@ -1,8 +1,8 @@
 use crate::api_server::execution_sandbox::SandboxExecutionError;
 use thiserror::Error;
 use vm::oracles::tracer::ValidationError;
-use zksync_types::l2::error::TxCheckError;
-use zksync_types::U256;
+use zksync_types::l2::{error::TxCheckError, TransactionType};
+use zksync_types::{Address, U256};
 
 #[derive(Debug, Error)]
 pub enum SubmitTxError {
@@ -49,10 +49,18 @@ pub enum SubmitTxError {
         "too many factory dependencies in the transaction. {0} provided, while only {1} allowed"
     )]
     TooManyFactoryDependencies(usize, usize),
+    #[error(
+        "factory dependencies of the transaction are too large. {0} bytes provided, while only {1} bytes allowed"
+    )]
+    FactoryDependenciesTooLarge(usize, usize),
     #[error("max fee per gas higher than 2^32")]
     FeePerGasTooHigh,
     #[error("max fee per pubdata byte higher than 2^32")]
     FeePerPubdataByteTooHigh,
+    /// Transactions with a gas per pubdata limit below the currently required value are never picked
+    /// from the mempool. The required value is returned in the error data.
+    #[error("gas per pubdata limit is too low. provided: {0}, required: {1}")]
+    GasPerPubdataLimitTooLow(U256, u64),
     /// InsufficientFundsForTransfer is returned if the transaction sender doesn't
     /// have enough funds for transfer.
     #[error("insufficient balance for transfer")]
@@ -64,6 +72,31 @@ pub enum SubmitTxError {
     /// Error returned from main node
     #[error("{0}")]
     ProxyError(#[from] zksync_web3_decl::jsonrpsee::core::Error),
+    #[error("deployment of EVM bytecode is not supported by protocol version {0}")]
+    EvmEmulationNotSupported(u16),
+    #[error("init code of the EVM contract is too large. {0} bytes provided, while only {1} bytes allowed")]
+    EvmInitCodeTooLarge(usize, usize),
+    #[error("paymaster {0:?} is not allowed to sponsor transactions")]
+    PaymasterNotAllowed(Address),
+    #[error("paymaster {0:?} balance is too low. balance: {1}, required: {2}")]
+    PaymasterBalanceTooLow(Address, U256, U256),
+    #[error("transaction admission is paused by the operator")]
+    AdmissionPaused,
+    #[error("transaction is rejected by the `{0}` admission policy: {1}")]
+    AdmissionDenied(&'static str, String),
+    #[error("transaction deadline has passed. valid until: {0}, current timestamp: {1}")]
+    DeadlinePassed(u64, u64),
+    #[error("execution output limit exceeded: {0}")]
+    ExecutionOutputLimitExceeded(String),
+    /// Chain ID of the transaction (for EIP-712 transactions, the chain ID of the signed domain)
+    /// doesn't match the chain ID of the node.
+    #[error(
+        "wrong chain id. expected: {1}, actual: {}",
+        .0.map_or_else(|| "none".to_owned(), |id| id.to_string())
+    )]
+    WrongChainId(Option<u16>, u16),
+    #[error("transactions of type {0:?} are not accepted by this node")]
+    TxTypeNotAccepted(TransactionType),
 }
 
 impl SubmitTxError {
@@ -90,19 +123,36 @@ impl SubmitTxError {
             SubmitTxError::UnexpectedVMBehavior(_) => "unexpected-vm-behavior",
             SubmitTxError::UnrealisticPubdataPriceLimit => "unrealistic-pubdata-price-limit",
             SubmitTxError::TooManyFactoryDependencies(_, _) => "too-many-factory-dependencies",
+            SubmitTxError::FactoryDependenciesTooLarge(_, _) => "factory-dependencies-too-large",
             SubmitTxError::FeePerGasTooHigh => "gas-price-limit-too-high",
             SubmitTxError::FeePerPubdataByteTooHigh => "pubdata-price-limit-too-high",
+            SubmitTxError::GasPerPubdataLimitTooLow(_, _) => "gas-per-pubdata-limit-too-low",
             SubmitTxError::InsufficientFundsForTransfer => "insufficient-funds-for-transfer",
             SubmitTxError::IntrinsicGas => "intrinsic-gas",
             SubmitTxError::ProxyError(_) => "proxy-error",
+            SubmitTxError::EvmEmulationNotSupported(_) => "evm-emulation-not-supported",
+            SubmitTxError::EvmInitCodeTooLarge(_, _) => "evm-init-code-too-large",
+            SubmitTxError::PaymasterNotAllowed(_) => "paymaster-not-allowed",
+            SubmitTxError::PaymasterBalanceTooLow(_, _, _) => "paymaster-balance-too-low",
+            SubmitTxError::AdmissionPaused => "admission-paused",
+            SubmitTxError::AdmissionDenied(_, _) => "admission-denied",
+            SubmitTxError::DeadlinePassed(_, _) => "deadline-passed",
+            SubmitTxError::ExecutionOutputLimitExceeded(_) => "execution-output-limit-exceeded",
+            SubmitTxError::WrongChainId(_, _) => "wrong-chain-id",
+            SubmitTxError::TxTypeNotAccepted(_) => "tx-type-not-accepted",
         }
     }
 
     pub fn data(&self) -> Vec<u8> {
-        if let SubmitTxError::ExecutionReverted(_, data) = self {
-            data.clone()
-        } else {
-            Vec::new()
+        match self {
+            SubmitTxError::ExecutionReverted(_, data) => data.clone(),
+            // Encoded as a single `uint256` word, so that clients can decode it the same way as revert data.
+            SubmitTxError::GasPerPubdataLimitTooLow(_, required) => {
+                let mut data = vec![0_u8; 32];
+                U256::from(*required).to_big_endian(&mut data);
+                data
+            }
+            _ => Vec::new(),
         }
     }
 }
@@ -139,6 +189,9 @@ impl From<SandboxExecutionError> for SubmitTxError {
                 SubmitTxError::FailedToChargeFee(reason)
             }
             SandboxExecutionError::Unexecutable(reason) => SubmitTxError::Unexecutable(reason),
+            SandboxExecutionError::OutputLimitExceeded(reason) => {
+                SubmitTxError::ExecutionOutputLimitExceeded(reason)
+            }
         }
     }
 }
@@ -148,3 +201,30 @@ impl From<ValidationError> for SubmitTxError {
         Self::ValidationFailed(err.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gas_per_pubdata_error_data_contains_required_value() {
+        let err = SubmitTxError::GasPerPubdataLimitTooLow(U256::from(100), 800);
+        let data = err.data();
+        assert_eq!(data.len(), 32);
+        assert_eq!(U256::from_big_endian(&data), U256::from(800));
+    }
+
+    #[test]
+    fn wrong_chain_id_error_message() {
+        let err = SubmitTxError::WrongChainId(Some(270), 280);
+        assert_eq!(
+            err.to_string(),
+            "wrong chain id. expected: 280, actual: 270"
+        );
+        let err = SubmitTxError::WrongChainId(None, 280);
+        assert_eq!(
+            err.to_string(),
+            "wrong chain id. expected: 280, actual: none"
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Policies used to suggest gas prices in `eth_gasPrice` and `eth_maxPriorityFeePerGas`.
+
+use std::{fmt, sync::Arc};
+
+use zksync_config::configs::api::{GasPricePolicy, Web3JsonRpcConfig};
+
+/// Gas price suggested to the users.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasPriceSuggestion {
+    /// Suggested max fee per gas, returned by `eth_gasPrice`.
+    pub gas_price: u64,
+    /// Suggested priority fee, returned by `eth_maxPriorityFeePerGas`. Since the operator doesn't
+    /// take tips, this is the headroom above the latest base fee covering potential fee growth.
+    pub max_priority_fee_per_gas: u64,
+}
+
+/// Policy deriving the suggested gas price from the fee model and the recent base fees.
+/// Custom policies can be provided via `TxSenderBuilder::with_gas_price_policy`.
+pub trait GasPriceSuggestionPolicy: 'static + fmt::Debug + Send + Sync {
+    /// Returns the number of the latest miniblocks which base fees should be provided to [`Self::suggest()`].
+    /// Allows to avoid a DB query for policies not depending on the fee history.
+    fn history_size(&self) -> usize;
+
+    /// Suggests the gas price. `model_base_fee` is the base fee derived from the current L1 gas price
+    /// using the fee model; `recent_base_fees` are base fees of the latest miniblocks, from the oldest
+    /// to the newest one.
+    fn suggest(&self, model_base_fee: u64, recent_base_fees: &[u64]) -> GasPriceSuggestion;
+}
+
+fn suggestion_with_headroom(gas_price: u64, recent_base_fees: &[u64]) -> GasPriceSuggestion {
+    let latest_base_fee = recent_base_fees.last().copied().unwrap_or(gas_price);
+    GasPriceSuggestion {
+        gas_price,
+        max_priority_fee_per_gas: gas_price.saturating_sub(latest_base_fee),
+    }
+}
+
+/// Suggests the base fee derived from the fee model, with no priority fee.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StaticGasPricePolicy;
+
+impl GasPriceSuggestionPolicy for StaticGasPricePolicy {
+    fn history_size(&self) -> usize {
+        0
+    }
+
+    fn suggest(&self, model_base_fee: u64, _recent_base_fees: &[u64]) -> GasPriceSuggestion {
+        GasPriceSuggestion {
+            gas_price: model_base_fee,
+            max_priority_fee_per_gas: 0,
+        }
+    }
+}
+
+/// Suggests the specified percentile of the recent base fees, but not less than the fee model base fee.
+#[derive(Debug, Clone, Copy)]
+pub struct PercentileGasPricePolicy {
+    history_size: usize,
+    percentile: u8,
+}
+
+impl PercentileGasPricePolicy {
+    pub fn new(history_size: usize, percentile: u8) -> Self {
+        assert!(percentile <= 100, "Percentile must be in 0..=100");
+        Self {
+            history_size,
+            percentile,
+        }
+    }
+}
+
+impl GasPriceSuggestionPolicy for PercentileGasPricePolicy {
+    fn history_size(&self) -> usize {
+        self.history_size
+    }
+
+    fn suggest(&self, model_base_fee: u64, recent_base_fees: &[u64]) -> GasPriceSuggestion {
+        let mut sorted_fees = recent_base_fees.to_vec();
+        sorted_fees.sort_unstable();
+        let percentile_fee = if sorted_fees.is_empty() {
+            0
+        } else {
+            let idx = (sorted_fees.len() - 1) * usize::from(self.percentile) / 100;
+            sorted_fees[idx]
+        };
+        suggestion_with_headroom(model_base_fee.max(percentile_fee), recent_base_fees)
+    }
+}
+
+/// Marks up the fee model base fee proportionally to the growth of the base fees over the recent
+/// miniblocks. The markup is capped by `max_markup`; if base fees do not grow, there is no markup.
+#[derive(Debug, Clone, Copy)]
+pub struct CongestionAwareGasPricePolicy {
+    history_size: usize,
+    max_markup: f64,
+}
+
+impl CongestionAwareGasPricePolicy {
+    pub fn new(history_size: usize, max_markup: f64) -> Self {
+        assert!(max_markup >= 0.0, "Max markup must be non-negative");
+        Self {
+            history_size,
+            max_markup,
+        }
+    }
+}
+
+impl GasPriceSuggestionPolicy for CongestionAwareGasPricePolicy {
+    fn history_size(&self) -> usize {
+        self.history_size
+    }
+
+    fn suggest(&self, model_base_fee: u64, recent_base_fees: &[u64]) -> GasPriceSuggestion {
+        let growth = match (recent_base_fees.first(), recent_base_fees.last()) {
+            (Some(&oldest), Some(&latest)) if oldest > 0 && latest > oldest => {
+                (latest - oldest) as f64 / oldest as f64
+            }
+            _ => 0.0,
+        };
+        let markup = growth.min(self.max_markup);
+        let latest_base_fee = recent_base_fees.last().copied().unwrap_or(0);
+        let base_price = model_base_fee.max(latest_base_fee);
+        let gas_price = (base_price as f64 * (1.0 + markup)).round() as u64;
+        suggestion_with_headroom(gas_price, recent_base_fees)
+    }
+}
+
+/// Creates the gas price policy specified in the config.
+pub fn gas_price_policy_from_config(
+    config: &Web3JsonRpcConfig,
+) -> Arc<dyn GasPriceSuggestionPolicy> {
+    match config.gas_price_policy() {
+        GasPricePolicy::Static => Arc::new(StaticGasPricePolicy),
+        GasPricePolicy::Percentile => Arc::new(PercentileGasPricePolicy::new(
+            config.gas_price_history_size(),
+            config.gas_price_percentile(),
+        )),
+        GasPricePolicy::CongestionAware => Arc::new(CongestionAwareGasPricePolicy::new(
+            config.gas_price_history_size(),
+            config.gas_price_max_congestion_markup(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn static_policy() {
+        let policy = StaticGasPricePolicy;
+        assert_eq!(policy.history_size(), 0);
+        let suggestion = policy.suggest(100, &[]);
+        assert_eq!(suggestion.gas_price, 100);
+        assert_eq!(suggestion.max_priority_fee_per_gas, 0);
+    }
+
+    #[test]
+    fn percentile_policy() {
+        let policy = PercentileGasPricePolicy::new(5, 50);
+        let suggestion = policy.suggest(100, &[300, 100, 200, 500, 400]);
+        assert_eq!(suggestion.gas_price, 300);
+        assert_eq!(suggestion.max_priority_fee_per_gas, 0);
+
+        let suggestion = policy.suggest(100, &[300, 100, 200, 500, 150]);
+        assert_eq!(suggestion.gas_price, 200);
+        assert_eq!(suggestion.max_priority_fee_per_gas, 50);
+
+        // The fee model price is a lower bound.
+        let suggestion = policy.suggest(1_000, &[300, 100, 200]);
+        assert_eq!(suggestion.gas_price, 1_000);
+        let suggestion = policy.suggest(100, &[]);
+        assert_eq!(suggestion.gas_price, 100);
+    }
+
+    #[test]
+    fn congestion_aware_policy() {
+        let policy = CongestionAwareGasPricePolicy::new(3, 0.5);
+        // Base fees are stable; no markup.
+        let suggestion = policy.suggest(100, &[100, 100, 100]);
+        assert_eq!(suggestion.gas_price, 100);
+        assert_eq!(suggestion.max_priority_fee_per_gas, 0);
+
+        // Base fees grow by 20%.
+        let suggestion = policy.suggest(100, &[100, 110, 120]);
+        assert_eq!(suggestion.gas_price, 144);
+        assert_eq!(suggestion.max_priority_fee_per_gas, 24);
+
+        // Markup is capped.
+        let suggestion = policy.suggest(100, &[100, 200, 300]);
+        assert_eq!(suggestion.gas_price, 450);
+    }
+}
@@ -7,48 +7,105 @@ use governor::{
     state::{InMemoryState, NotKeyed},
     Quota, RateLimiter,
 };
+use tokio::sync::watch;
 
 // Built-in uses
-use std::{cmp, collections::HashMap, num::NonZeroU32, sync::Arc, time::Instant};
+use std::{
+    cmp,
+    collections::{BTreeMap, HashMap},
+    num::NonZeroU32,
+    sync::Arc,
+    time::Instant,
+};
 
 // Workspace uses
 use vm::{
     transaction_data::{derive_overhead, OverheadCoeficients},
+    vm::VmTxExecutionResult,
     vm_with_bootloader::derive_base_fee_and_gas_per_pubdata,
     zk_evm::zkevm_opcode_defs::system_params::MAX_PUBDATA_PER_BLOCK,
-    VmExecutionResult,
+    TxRevertReason, VmExecutionResult,
 };
 use zksync_config::configs::{api::Web3JsonRpcConfig, chain::StateKeeperConfig};
 use zksync_contracts::{
     BaseSystemContracts, SystemContractCode, ESTIMATE_FEE_BLOCK_CODE,
     PLAYGROUND_BLOCK_BOOTLOADER_CODE,
 };
-use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool};
-use zksync_state::FactoryDepsCache;
+use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool, SqlxError};
+use zksync_state::{BytecodeCache, FactoryDepsCache};
 use zksync_types::{
-    fee::{Fee, TransactionExecutionMetrics},
+    api::AdmissionPoliciesConfig,
+    fee::{Fee, FeeBreakdown, FeeEstimate, TransactionExecutionMetrics},
     get_code_key, get_intrinsic_constants,
+    helpers::unix_timestamp_ms,
     l2::error::TxCheckError::TxDuplication,
-    l2::L2Tx,
+    l2::{L2Tx, TransactionType},
+    tx::tx_execution_info::{DeduplicatedWritesMetrics, ExecutionMetrics},
     utils::storage_key_for_eth_balance,
-    AccountTreeId, Address, ExecuteTransactionCommon, Nonce, StorageKey, Transaction, H160, H256,
-    MAX_GAS_PER_PUBDATA_BYTE, MAX_L2_TX_GAS_LIMIT, MAX_NEW_FACTORY_DEPS, U256,
+    AccountTreeId, Address, ExecuteTransactionCommon, MiniblockNumber, Nonce, ProtocolVersionId,
+    StorageKey, Transaction, H160, H256, MAX_EVM_INIT_CODE_SIZE, MAX_GAS_PER_PUBDATA_BYTE,
+    MAX_L2_TX_GAS_LIMIT, U256,
 };
 use zksync_utils::{bytes_to_be_words, h256_to_u256};
 
 // Local uses
 use crate::api_server::execution_sandbox::{
-    adjust_l1_gas_price_for_tx, execute_tx_eth_call, execute_tx_with_pending_state,
-    get_pubdata_for_factory_deps, BlockArgs, SandboxExecutionError, TxExecutionArgs, TxSharedArgs,
-    VmConcurrencyLimiter, VmPermit,
+    adjust_l1_gas_price_for_tx, execute_bundle_in_sandbox, execute_tx_eth_call,
+    execute_tx_with_pending_state, get_pubdata_for_factory_deps, BlockArgs, SandboxExecutionError,
+    SandboxOutputLimits, TxExecutionArgs, TxSharedArgs, VmConcurrencyLimiter, VmPermit,
 };
 use crate::l1_gas_price::L1GasPriceProvider;
 use crate::state_keeper::seal_criteria::{ConditionalSealer, SealData};
+use crate::state_keeper::PendingStorageDiffs;
 
+mod account_state_cache;
+mod admission_policy;
 mod error;
+mod gas_price_policy;
+mod paymaster_policy;
 mod proxy;
+mod rejection_stats;
+#[cfg(test)]
+mod tests;
+mod validation_cache;
+
+pub use self::admission_policy::{
+    AdmissionPolicy, AdmissionPolicyChain, CalleeDenylistPolicy, DeployerAllowlistPolicy,
+    MaxCalldataSizePolicy,
+};
+pub(crate) use self::error::SubmitTxError;
+pub use self::gas_price_policy::{
+    gas_price_policy_from_config, CongestionAwareGasPricePolicy, GasPriceSuggestion,
+    GasPriceSuggestionPolicy, PercentileGasPricePolicy, StaticGasPricePolicy,
+};
+pub use self::paymaster_policy::{ConfiguredPaymasterPolicy, PaymasterPolicy};
+pub(super) use self::proxy::TxProxy;
+use self::{
+    account_state_cache::AccountStateCache,
+    rejection_stats::TxRejectionStats,
+    validation_cache::{ValidationCacheKey, ValidationViolationsCache},
+};
 
-pub(super) use self::{error::SubmitTxError, proxy::TxProxy};
+/// Splits the gas used by the transaction into computation and pubdata parts based on the execution metrics.
+fn fee_breakdown(
+    tx_metrics: &TransactionExecutionMetrics,
+    gas_per_pubdata_byte: u64,
+    overhead: u32,
+) -> FeeBreakdown {
+    let pubdata_bytes = ExecutionMetrics::from_tx_metrics(tx_metrics).size()
+        + DeduplicatedWritesMetrics::from_tx_metrics(tx_metrics).size();
+    let pubdata_gas = (pubdata_bytes as u64).saturating_mul(gas_per_pubdata_byte);
+    // Pubdata is paid for during the execution, so it is included into the used gas.
+    let compute_gas = (tx_metrics.gas_used as u64).saturating_sub(pubdata_gas);
+
+    FeeBreakdown {
+        compute_gas: compute_gas.into(),
+        pubdata_gas: pubdata_gas.into(),
+        pubdata_bytes: pubdata_bytes.into(),
+        overhead_gas: overhead.into(),
+        gas_per_pubdata: gas_per_pubdata_byte.into(),
+    }
+}
 
 /// Type alias for the rate limiter implementation.
 type TxSenderRateLimiter =
@@ -70,6 +127,23 @@ pub struct TxSenderBuilder {
     /// Actual state keeper configuration, required for tx verification.
     /// If not set, transactions would not be checked against seal criteria.
     state_keeper_config: Option<StateKeeperConfig>,
+    /// Policy restricting the paymasters. If not set, any paymaster is allowed.
+    paymaster_policy: Option<Arc<dyn PaymasterPolicy>>,
+    /// Policy used to suggest gas prices. If not set, the static policy is used.
+    gas_price_policy: Option<Arc<dyn GasPriceSuggestionPolicy>>,
+    /// Cache of decoded bytecodes used by the sandbox VMs. If not set, bytecodes are loaded from the storage.
+    bytecode_cache: Option<BytecodeCache>,
+    /// Operator controls allowing to pause transaction admission.
+    admin_controls: Option<AdminControls>,
+    /// Custom admission policies checked in addition to the built-in ones.
+    admission_policies: Vec<Arc<dyn AdmissionPolicy>>,
+    /// Config of the built-in admission policies. If not set, built-in policies are disabled.
+    admission_policies_config: AdmissionPoliciesConfig,
+    /// Capacity of the account state cache and the notifications about sealed miniblocks used to invalidate it.
+    /// If not set, account nonces and balances are always loaded from the storage.
+    account_state_cache: Option<(usize, watch::Receiver<MiniblockNumber>)>,
+    /// Storage diffs of miniblocks not persisted yet. If not set, gas is always estimated against the persisted state.
+    pending_storage_diffs: Option<PendingStorageDiffs>,
 }
 
 impl TxSenderBuilder {
@@ -81,9 +155,61 @@ impl TxSenderBuilder {
             rate_limiter: None,
             proxy: None,
             state_keeper_config: None,
+            paymaster_policy: None,
+            gas_price_policy: None,
+            bytecode_cache: None,
+            admin_controls: None,
+            admission_policies: vec![],
+            admission_policies_config: AdmissionPoliciesConfig::default(),
+            account_state_cache: None,
+            pending_storage_diffs: None,
         }
     }
 
+    /// Enables caching of account nonces and balances. The cache is invalidated each time a new sealed miniblock
+    /// is reported via `sealed_miniblocks`, so this only works if the API server runs in the same process
+    /// as the state keeper.
+    pub fn with_account_state_cache(
+        mut self,
+        capacity: usize,
+        sealed_miniblocks: watch::Receiver<MiniblockNumber>,
+    ) -> Self {
+        self.account_state_cache = Some((capacity, sealed_miniblocks));
+        self
+    }
+
+    /// Enables gas estimation against the pending state, which layers storage diffs of miniblocks executed
+    /// by the state keeper over the persisted state. This only works if the API server runs in the same process
+    /// as the state keeper.
+    pub fn with_pending_storage_diffs(
+        mut self,
+        pending_storage_diffs: PendingStorageDiffs,
+    ) -> Self {
+        self.pending_storage_diffs = Some(pending_storage_diffs);
+        self
+    }
+
+    /// Adds a custom admission policy, which cannot be reconfigured at runtime.
+    pub fn with_admission_policy(mut self, policy: Arc<dyn AdmissionPolicy>) -> Self {
+        self.admission_policies.push(policy);
+        self
+    }
+
+    pub fn with_admission_policies_config(mut self, config: AdmissionPoliciesConfig) -> Self {
+        self.admission_policies_config = config;
+        self
+    }
+
+    pub fn with_admin_controls(mut self, admin_controls: AdminControls) -> Self {
+        self.admin_controls = Some(admin_controls);
+        self
+    }
+
+    pub fn with_bytecode_cache(mut self, bytecode_cache: BytecodeCache) -> Self {
+        self.bytecode_cache = Some(bytecode_cache);
+        self
+    }
+
     pub fn with_rate_limiter(self, transactions_per_sec: u32) -> Self {
         let rate_limiter = RateLimiter::direct_with_clock(
             Quota::per_second(NonZeroU32::new(transactions_per_sec).unwrap()),
@@ -110,6 +236,19 @@ impl TxSenderBuilder {
         self
     }
 
+    pub fn with_paymaster_policy(mut self, paymaster_policy: Arc<dyn PaymasterPolicy>) -> Self {
+        self.paymaster_policy = Some(paymaster_policy);
+        self
+    }
+
+    pub fn with_gas_price_policy(
+        mut self,
+        gas_price_policy: Arc<dyn GasPriceSuggestionPolicy>,
+    ) -> Self {
+        self.gas_price_policy = Some(gas_price_policy);
+        self
+    }
+
     pub async fn build<G: L1GasPriceProvider>(
         self,
         l1_gas_price_source: Arc<G>,
@@ -148,6 +287,15 @@ impl TxSenderBuilder {
             bootloader: ESTIMATE_FEE_BLOCK_CODE.clone(),
         };
 
+        let validation_cache = (self.config.validation_cache_size > 0)
+            .then(|| ValidationViolationsCache::new(self.config.validation_cache_size));
+        let account_state_cache = self
+            .account_state_cache
+            .filter(|(capacity, _)| *capacity > 0)
+            .map(|(capacity, sealed_miniblocks)| {
+                AccountStateCache::new(capacity, sealed_miniblocks)
+            });
+
         TxSender(Arc::new(TxSenderInner {
             sender_config: self.config,
             master_connection_pool: self.master_connection_pool,
@@ -160,10 +308,66 @@ impl TxSenderBuilder {
             state_keeper_config: self.state_keeper_config,
             vm_concurrency_limiter,
             factory_deps_cache,
+            bytecode_cache: self.bytecode_cache,
+            admin_controls: self.admin_controls,
+            admission_policies: AdmissionPolicyChain::new(
+                self.admission_policies,
+                &self.admission_policies_config,
+            ),
+            validation_cache,
+            account_state_cache,
+            pending_storage_diffs: self.pending_storage_diffs,
+            paymaster_policy: self.paymaster_policy,
+            gas_price_policy: self
+                .gas_price_policy
+                .unwrap_or_else(|| Arc::new(StaticGasPricePolicy)),
+            rejection_stats: TxRejectionStats::default(),
         }))
     }
 }
 
+/// Types of L2 transactions accepted by `TxSender`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AcceptedTxTypes {
+    pub legacy: bool,
+    pub eip2930: bool,
+    pub eip1559: bool,
+    pub eip712: bool,
+}
+
+impl Default for AcceptedTxTypes {
+    fn default() -> Self {
+        Self {
+            legacy: true,
+            eip2930: true,
+            eip1559: true,
+            eip712: true,
+        }
+    }
+}
+
+impl AcceptedTxTypes {
+    pub fn new(web3_json_config: &Web3JsonRpcConfig) -> Self {
+        Self {
+            legacy: web3_json_config.accept_legacy_txs(),
+            eip2930: web3_json_config.accept_eip2930_txs(),
+            eip1559: web3_json_config.accept_eip1559_txs(),
+            eip712: web3_json_config.accept_eip712_txs(),
+        }
+    }
+
+    fn accepts(self, tx_type: TransactionType) -> bool {
+        match tx_type {
+            TransactionType::LegacyTransaction => self.legacy,
+            TransactionType::EIP2930Transaction => self.eip2930,
+            TransactionType::EIP1559Transaction => self.eip1559,
+            TransactionType::EIP712Transaction => self.eip712,
+            // Priority operations cannot be submitted via the API.
+            TransactionType::PriorityOpTransaction => false,
+        }
+    }
+}
+
 /// Internal static `TxSender` configuration.
 /// This structure is detached from `ZkSyncConfig`, since different node types (main, external, etc)
 /// may require different configuration layouts.
@@ -176,9 +380,17 @@ pub struct TxSenderConfig {
     pub max_allowed_l2_tx_gas_limit: u32,
     pub fair_l2_gas_price: u64,
     pub vm_execution_cache_misses_limit: Option<usize>,
+    /// Limits on the output of `eth_call` and gas estimation executions.
+    pub output_limits: SandboxOutputLimits,
     pub validation_computational_gas_limit: u32,
+    pub validation_cache_size: usize,
     pub default_aa: H256,
     pub bootloader: H256,
+    pub max_factory_deps_per_tx: usize,
+    pub max_factory_deps_size_per_tx: Option<usize>,
+    /// Protocol version used to decide whether transactions deploying EVM bytecode are accepted.
+    pub protocol_version: ProtocolVersionId,
+    pub accepted_tx_types: AcceptedTxTypes,
 }
 
 impl TxSenderConfig {
@@ -187,16 +399,27 @@ impl TxSenderConfig {
         web3_json_config: &Web3JsonRpcConfig,
     ) -> Self {
         Self {
-            fee_account_addr: state_keeper_config.fee_account_addr,
+            fee_account_addr: state_keeper_config.fee_collector_addr(),
             gas_price_scale_factor: web3_json_config.gas_price_scale_factor,
             max_nonce_ahead: web3_json_config.max_nonce_ahead,
             max_allowed_l2_tx_gas_limit: state_keeper_config.max_allowed_l2_tx_gas_limit,
             fair_l2_gas_price: state_keeper_config.fair_l2_gas_price,
             vm_execution_cache_misses_limit: web3_json_config.vm_execution_cache_misses_limit,
+            output_limits: SandboxOutputLimits::new(
+                web3_json_config.vm_execution_max_returndata_size,
+                web3_json_config.vm_execution_max_events,
+                web3_json_config.vm_execution_max_storage_accesses,
+                web3_json_config.vm_execution_soft_limits_ratio(),
+            ),
             validation_computational_gas_limit: state_keeper_config
                 .validation_computational_gas_limit,
+            validation_cache_size: web3_json_config.validation_cache_size(),
             default_aa: state_keeper_config.default_aa_hash,
             bootloader: state_keeper_config.bootloader_hash,
+            max_factory_deps_per_tx: state_keeper_config.max_factory_deps_per_tx(),
+            max_factory_deps_size_per_tx: state_keeper_config.max_factory_deps_size_per_tx,
+            protocol_version: state_keeper_config.protocol_version(),
+            accepted_tx_types: AcceptedTxTypes::new(web3_json_config),
         }
     }
 }
@@ -221,6 +444,24 @@ pub struct TxSenderInner<G> {
     pub(super) vm_concurrency_limiter: Arc<VmConcurrencyLimiter>,
     // Smart contract source code cache.
     pub(super) factory_deps_cache: FactoryDepsCache,
+    /// Cache of decoded bytecodes shared with other VM instances.
+    pub(super) bytecode_cache: Option<BytecodeCache>,
+    /// Operator controls. If set, transactions are rejected while admission is paused.
+    admin_controls: Option<AdminControls>,
+    /// Policies checked before a transaction is inserted into the mempool.
+    pub(super) admission_policies: AdmissionPolicyChain,
+    /// Cache of the account validation rule violations. If not set, validation is always executed.
+    validation_cache: Option<ValidationViolationsCache>,
+    /// Cache of account nonces and balances. If not set, they are always loaded from the storage.
+    account_state_cache: Option<AccountStateCache>,
+    /// Storage diffs of miniblocks not persisted yet, used for gas estimation against the pending state.
+    pending_storage_diffs: Option<PendingStorageDiffs>,
+    /// Policy restricting the paymasters that may sponsor transactions.
+    paymaster_policy: Option<Arc<dyn PaymasterPolicy>>,
+    /// Policy used to suggest gas prices to the users.
+    gas_price_policy: Arc<dyn GasPriceSuggestionPolicy>,
+    /// Numbers of rejected transactions grouped by the rejection reason.
+    rejection_stats: TxRejectionStats,
 }
 
 pub struct TxSender<G>(pub Arc<TxSenderInner<G>>);
@@ -241,58 +482,34 @@ impl<G> std::fmt::Debug for TxSender<G> {
 }
 
 impl<G: L1GasPriceProvider> TxSender<G> {
-    #[tracing::instrument(skip(self, tx))]
+    #[tracing::instrument(skip(self, tx), fields(tx_hash = ?tx.hash()))]
     pub async fn submit_tx(&self, tx: L2Tx) -> Result<L2TxSubmissionResult, SubmitTxError> {
+        let result = self.submit_tx_inner(tx).await;
+        if let Err(err) = &result {
+            self.0.rejection_stats.record(err);
+        }
+        result
+    }
+
+    /// Returns cumulative numbers of rejected transactions grouped by the rejection reason.
+    pub fn rejection_stats(&self) -> BTreeMap<String, u64> {
+        self.0.rejection_stats.snapshot()
+    }
+
+    async fn submit_tx_inner(&self, tx: L2Tx) -> Result<L2TxSubmissionResult, SubmitTxError> {
+        if let Some(admin_controls) = &self.0.admin_controls {
+            if admin_controls.is_tx_admission_paused() {
+                return Err(SubmitTxError::AdmissionPaused);
+            }
+        }
         if let Some(rate_limiter) = &self.0.rate_limiter {
             if rate_limiter.check().is_err() {
                 return Err(SubmitTxError::RateLimitExceeded);
             }
         }
 
-        let mut stage_started_at = Instant::now();
-        self.validate_tx(&tx).await?;
-        metrics::histogram!("api.web3.submit_tx", stage_started_at.elapsed(), "stage" => "1_validate");
-        stage_started_at = Instant::now();
-
-        let shared_args = self.shared_args();
-        let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
-        let (_, tx_metrics) = execute_tx_with_pending_state(
-            &vm_permit,
-            shared_args.clone(),
-            TxExecutionArgs::for_validation(&tx),
-            self.0.replica_connection_pool.clone(),
-            tx.clone().into(),
-            &mut HashMap::new(),
-        )
-        .await;
-
-        vlog::info!(
-            "Submit tx {:?} with execution metrics {:?}",
-            tx.hash(),
-            tx_metrics
-        );
-        metrics::histogram!("api.web3.submit_tx", stage_started_at.elapsed(), "stage" => "2_dry_run");
-        stage_started_at = Instant::now();
-
-        let computational_gas_limit = self.0.sender_config.validation_computational_gas_limit;
-        let validation_result = shared_args
-            .validate_tx_with_pending_state(
-                &vm_permit,
-                self.0.replica_connection_pool.clone(),
-                tx.clone(),
-                computational_gas_limit,
-            )
-            .await;
-        drop(vm_permit); // Unblock other VMs to enter.
-
-        metrics::histogram!("api.web3.submit_tx", stage_started_at.elapsed(), "stage" => "3_verify_execute");
-        stage_started_at = Instant::now();
-
-        if let Err(err) = validation_result {
-            return Err(err.into());
-        }
-
-        self.ensure_tx_executable(tx.clone().into(), &tx_metrics, true)?;
+        let tx_metrics = self.validate_submitted_tx(&tx).await?;
+        let stage_started_at = Instant::now();
 
         if let Some(proxy) = &self.0.proxy {
             // We're running an external node: we have to proxy the transaction to the main node.
@@ -361,6 +578,70 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         submission_result
     }
 
+    /// Performs the checks a transaction must pass before it's added to the mempool (admission policies,
+    /// stateless and account checks, and execution in the sandbox). Returns execution metrics of the transaction.
+    pub(crate) async fn validate_submitted_tx(
+        &self,
+        tx: &L2Tx,
+    ) -> Result<TransactionExecutionMetrics, SubmitTxError> {
+        let mut stage_started_at = Instant::now();
+        self.0.admission_policies.check(tx)?;
+        self.validate_tx(tx).await?;
+
+        // Accounts which are known to violate the validation rules are rejected before spawning any VMs.
+        let cached_account = self.validation_cache_account(tx).await;
+        if let Some((cache, key)) = cached_account {
+            if let Some(violation) = cache.get(key) {
+                return Err(SubmitTxError::ValidationFailed(violation));
+            }
+        }
+        metrics::histogram!("api.web3.submit_tx", stage_started_at.elapsed(), "stage" => "1_validate");
+        stage_started_at = Instant::now();
+
+        let shared_args = self.shared_args();
+        let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
+        let (_, tx_metrics) = execute_tx_with_pending_state(
+            &vm_permit,
+            shared_args.clone(),
+            TxExecutionArgs::for_validation(tx),
+            self.0.replica_connection_pool.clone(),
+            tx.clone().into(),
+            &mut HashMap::new(),
+        )
+        .await;
+
+        vlog::info!(
+            "Submit tx {:?} with execution metrics {:?}",
+            tx.hash(),
+            tx_metrics
+        );
+        metrics::histogram!("api.web3.submit_tx", stage_started_at.elapsed(), "stage" => "2_dry_run");
+        stage_started_at = Instant::now();
+
+        let computational_gas_limit = self.0.sender_config.validation_computational_gas_limit;
+        let validation_result = shared_args
+            .validate_tx_with_pending_state(
+                &vm_permit,
+                self.0.replica_connection_pool.clone(),
+                tx.clone(),
+                computational_gas_limit,
+            )
+            .await;
+        drop(vm_permit); // Unblock other VMs to enter.
+        if let Some((cache, key)) = cached_account {
+            cache.insert_if_violation(key, &validation_result);
+        }
+
+        metrics::histogram!("api.web3.submit_tx", stage_started_at.elapsed(), "stage" => "3_verify_execute");
+
+        if let Err(err) = validation_result {
+            return Err(err.into());
+        }
+
+        self.ensure_tx_executable(tx.clone().into(), &tx_metrics, true)?;
+        Ok(tx_metrics)
+    }
+
     fn shared_args(&self) -> TxSharedArgs {
         TxSharedArgs {
             operator_account: AccountTreeId::new(self.0.sender_config.fee_account_addr),
@@ -368,10 +649,17 @@ impl<G: L1GasPriceProvider> TxSender<G> {
             fair_l2_gas_price: self.0.sender_config.fair_l2_gas_price,
             base_system_contracts: self.0.playground_base_system_contracts.clone(),
             factory_deps_cache: self.0.factory_deps_cache.clone(),
+            bytecode_cache: self.0.bytecode_cache.clone(),
+            pending_storage_diffs: None,
         }
     }
 
     async fn validate_tx(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
+        let tx_type = tx.common_data.transaction_type;
+        if !self.0.sender_config.accepted_tx_types.accepts(tx_type) {
+            return Err(SubmitTxError::TxTypeNotAccepted(tx_type));
+        }
+
         let max_gas = U256::from(u32::MAX);
         if tx.common_data.fee.gas_limit > max_gas
             || tx.common_data.fee.gas_per_pubdata_limit > max_gas
@@ -403,18 +691,23 @@ impl<G: L1GasPriceProvider> TxSender<G> {
             );
             return Err(SubmitTxError::MaxPriorityFeeGreaterThanMaxFee);
         }
-        if tx.execute.factory_deps_length() > MAX_NEW_FACTORY_DEPS {
-            return Err(SubmitTxError::TooManyFactoryDependencies(
-                tx.execute.factory_deps_length(),
-                MAX_NEW_FACTORY_DEPS,
+        self.validate_factory_deps(tx)?;
+        self.validate_evm_deployment(tx)?;
+        Self::validate_deadline(tx, unix_timestamp_ms() / 1_000)?;
+
+        let gas_per_pubdata_byte = self.gas_per_pubdata();
+        if tx.common_data.fee.gas_per_pubdata_limit < gas_per_pubdata_byte.into() {
+            vlog::info!(
+                "Submitted Tx is Unexecutable {:?} because of GasPerPubdataLimitTooLow {} (required {})",
+                tx.hash(),
+                tx.common_data.fee.gas_per_pubdata_limit,
+                gas_per_pubdata_byte
+            );
+            return Err(SubmitTxError::GasPerPubdataLimitTooLow(
+                tx.common_data.fee.gas_per_pubdata_limit,
+                gas_per_pubdata_byte,
             ));
         }
-
-        let l1_gas_price = self.0.l1_gas_price_source.estimate_effective_gas_price();
-        let (_, gas_per_pubdata_byte) = derive_base_fee_and_gas_per_pubdata(
-            l1_gas_price,
-            self.0.sender_config.fair_l2_gas_price,
-        );
         let effective_gas_per_pubdata = cmp::min(
             tx.common_data.fee.gas_per_pubdata_limit,
             gas_per_pubdata_byte.into(),
@@ -427,6 +720,8 @@ impl<G: L1GasPriceProvider> TxSender<G> {
             return Err(SubmitTxError::IntrinsicGas);
         }
 
+        self.validate_paymaster(tx).await?;
+
         // We still double-check the nonce manually
         // to make sure that only the correct nonce is submitted and the transaction's hashes never repeat
         self.validate_account_nonce(tx).await?;
@@ -436,6 +731,57 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         Ok(())
     }
 
+    fn validate_deadline(tx: &L2Tx, timestamp: u64) -> Result<(), SubmitTxError> {
+        if let Some(valid_until) = tx.common_data.valid_until {
+            if tx.common_data.is_expired_at(timestamp) {
+                return Err(SubmitTxError::DeadlinePassed(valid_until, timestamp));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_evm_deployment(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
+        let Some(init_code) = tx.execute.evm_init_code() else {
+            return Ok(());
+        };
+        let protocol_version = self.0.sender_config.protocol_version;
+        if !protocol_version.supports_evm_emulation() {
+            return Err(SubmitTxError::EvmEmulationNotSupported(protocol_version.0));
+        }
+        if init_code.len() > MAX_EVM_INIT_CODE_SIZE {
+            return Err(SubmitTxError::EvmInitCodeTooLarge(
+                init_code.len(),
+                MAX_EVM_INIT_CODE_SIZE,
+            ));
+        }
+        Ok(())
+    }
+
+    fn validate_factory_deps(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
+        let factory_deps_count = tx.execute.factory_deps_length();
+        let max_count = self.0.sender_config.max_factory_deps_per_tx;
+        if factory_deps_count > max_count {
+            metrics::increment_counter!("api.tx_sender.oversized_factory_deps", "limit" => "count");
+            return Err(SubmitTxError::TooManyFactoryDependencies(
+                factory_deps_count,
+                max_count,
+            ));
+        }
+
+        let factory_deps_size = tx.execute.factory_deps_size();
+        metrics::histogram!("api.tx_sender.factory_deps_size", factory_deps_size as f64);
+        if let Some(max_size) = self.0.sender_config.max_factory_deps_size_per_tx {
+            if factory_deps_size > max_size {
+                metrics::increment_counter!("api.tx_sender.oversized_factory_deps", "limit" => "size");
+                return Err(SubmitTxError::FactoryDependenciesTooLarge(
+                    factory_deps_size,
+                    max_size,
+                ));
+            }
+        }
+        Ok(())
+    }
+
     async fn validate_account_nonce(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
         let expected_nonce = self.get_expected_nonce(tx).await;
 
@@ -460,6 +806,12 @@ impl<G: L1GasPriceProvider> TxSender<G> {
     }
 
     async fn get_expected_nonce(&self, tx: &L2Tx) -> Nonce {
+        let account = tx.initiator_account();
+        let cache = self.0.account_state_cache.as_ref();
+        if let Some(nonce) = cache.and_then(|cache| cache.get_nonce(account)) {
+            return nonce;
+        }
+
         let mut connection = self
             .0
             .replica_connection_pool
@@ -473,10 +825,29 @@ impl<G: L1GasPriceProvider> TxSender<G> {
             .unwrap();
         let nonce = connection
             .storage_web3_dal()
-            .get_address_historical_nonce(tx.initiator_account(), latest_block_number)
+            .get_address_historical_nonce(account, latest_block_number)
             .await
             .unwrap();
-        Nonce(nonce.as_u32())
+        let nonce = Nonce(nonce.as_u32());
+        if let Some(cache) = cache {
+            cache.insert_nonce(account, nonce, latest_block_number);
+        }
+        nonce
+    }
+
+    async fn validate_paymaster(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
+        let paymaster = tx.common_data.paymaster_params.paymaster;
+        let policy = match &self.0.paymaster_policy {
+            Some(policy) if paymaster != Address::default() => policy,
+            _ => return Ok(()),
+        };
+
+        let balance = if policy.requires_balance() {
+            Some(self.get_balance(&paymaster).await)
+        } else {
+            None
+        };
+        policy.check(paymaster, balance)
     }
 
     async fn validate_enough_balance(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
@@ -510,24 +881,97 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         }
     }
 
+    /// Returns the validation cache together with the cache key (the initiator account, the paymaster
+    /// and their code hashes) if the transaction is subject to caching. EOAs are skipped since they use
+    /// the default account code.
+    async fn validation_cache_account(
+        &self,
+        tx: &L2Tx,
+    ) -> Option<(&ValidationViolationsCache, ValidationCacheKey)> {
+        let cache = self.0.validation_cache.as_ref()?;
+        let account = tx.initiator_account();
+        let account_code_hash = self.get_account_code_hash(account).await;
+        if account_code_hash == H256::zero() {
+            return None;
+        }
+
+        let paymaster = tx.common_data.paymaster_params.paymaster;
+        let paymaster_code_hash = if paymaster == Address::zero() {
+            H256::zero()
+        } else {
+            self.get_account_code_hash(paymaster).await
+        };
+        let key = ValidationCacheKey {
+            account,
+            account_code_hash,
+            paymaster,
+            paymaster_code_hash,
+        };
+        Some((cache, key))
+    }
+
+    async fn get_account_code_hash(&self, account: Address) -> H256 {
+        self.0
+            .replica_connection_pool
+            .access_storage_tagged("api")
+            .await
+            .storage_dal()
+            .get_by_key(&get_code_key(&account))
+            .await
+            .unwrap_or_default()
+    }
+
     async fn get_balance(&self, initiator_address: &H160) -> U256 {
-        let eth_balance_key = storage_key_for_eth_balance(initiator_address);
+        let cache = self.0.account_state_cache.as_ref();
+        if let Some(balance) = cache.and_then(|cache| cache.get_balance(*initiator_address)) {
+            return balance;
+        }
 
-        let balance = self
+        let eth_balance_key = storage_key_for_eth_balance(initiator_address);
+        let mut connection = self
             .0
             .replica_connection_pool
             .access_storage_tagged("api")
-            .await
+            .await;
+        // The balance is read from the latest storage, so it's at least as fresh as the sealed miniblock
+        // loaded before it.
+        let latest_block_number = if cache.is_some() {
+            let number = connection
+                .blocks_web3_dal()
+                .get_sealed_miniblock_number()
+                .await
+                .unwrap();
+            Some(number)
+        } else {
+            None
+        };
+        let balance = connection
             .storage_dal()
             .get_by_key(&eth_balance_key)
             .await
             .unwrap_or_default();
+        let balance = h256_to_u256(balance);
 
-        h256_to_u256(balance)
+        if let (Some(cache), Some(read_at)) = (cache, latest_block_number) {
+            cache.insert_balance(*initiator_address, balance, read_at);
+        }
+        balance
+    }
+
+    /// Returns the balance used in fee estimation, taking into account pending miniblocks if requested.
+    async fn get_balance_for_estimate(&self, address: &H160, use_pending_state: bool) -> U256 {
+        let pending_diffs = self.0.pending_storage_diffs.as_ref();
+        let pending_balance = pending_diffs
+            .filter(|_| use_pending_state)
+            .and_then(|diffs| diffs.read_value(&storage_key_for_eth_balance(address)));
+        match pending_balance {
+            Some(balance) => h256_to_u256(balance),
+            None => self.get_balance(address).await,
+        }
     }
 
     /// Given the gas_limit to be used for the body of the transaction,
-    /// returns the result for executing the transaction with such gas_limit
+    /// returns the result for executing the transaction with such gas_limit together with its execution metrics.
     #[allow(clippy::too_many_arguments)]
     async fn estimate_gas_step(
         &self,
@@ -537,8 +981,9 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         tx_gas_limit: u32,
         l1_gas_price: u64,
         base_fee: u64,
+        use_pending_state: bool,
         storage_read_cache: &mut HashMap<StorageKey, H256>,
-    ) -> Result<VmExecutionResult, SandboxExecutionError> {
+    ) -> Result<(VmExecutionResult, TransactionExecutionMetrics), SandboxExecutionError> {
         let gas_limit_with_overhead = tx_gas_limit
             + derive_overhead(
                 tx_gas_limit,
@@ -559,10 +1004,14 @@ impl<G: L1GasPriceProvider> TxSender<G> {
             }
         }
 
-        let shared_args = self.shared_args_for_gas_estimate(l1_gas_price);
+        let shared_args = self.shared_args_for_gas_estimate(l1_gas_price, use_pending_state);
         let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
-        let execution_args =
-            TxExecutionArgs::for_gas_estimate(vm_execution_cache_misses_limit, &tx, base_fee);
+        let execution_args = TxExecutionArgs::for_gas_estimate(
+            vm_execution_cache_misses_limit,
+            self.0.sender_config.output_limits,
+            &tx,
+            base_fee,
+        );
         let (exec_result, tx_metrics) = execute_tx_with_pending_state(
             vm_permit,
             shared_args,
@@ -577,25 +1026,39 @@ impl<G: L1GasPriceProvider> TxSender<G> {
             let SubmitTxError::Unexecutable(message) = err else { unreachable!() };
             return Err(SandboxExecutionError::Unexecutable(message));
         }
-        exec_result
+        exec_result.map(|result| (result, tx_metrics))
     }
 
-    fn shared_args_for_gas_estimate(&self, l1_gas_price: u64) -> TxSharedArgs {
+    fn shared_args_for_gas_estimate(
+        &self,
+        l1_gas_price: u64,
+        use_pending_state: bool,
+    ) -> TxSharedArgs {
         TxSharedArgs {
             operator_account: AccountTreeId::new(self.0.sender_config.fee_account_addr),
             l1_gas_price,
             fair_l2_gas_price: self.0.sender_config.fair_l2_gas_price,
             base_system_contracts: self.0.estimate_fee_base_system_contracts.clone(),
             factory_deps_cache: self.0.factory_deps_cache.clone(),
+            bytecode_cache: self.0.bytecode_cache.clone(),
+            pending_storage_diffs: if use_pending_state {
+                self.0.pending_storage_diffs.clone()
+            } else {
+                None
+            },
         }
     }
 
+    /// Estimates the fee for `tx` using binary search on the gas limit. If `use_pending_state` is set and
+    /// the sender has access to storage diffs of miniblocks not persisted yet, the transaction is executed
+    /// on top of these diffs; otherwise, it's executed on top of the last persisted miniblock.
     pub async fn get_txs_fee_in_wei(
         &self,
         mut tx: Transaction,
         estimated_fee_scale_factor: f64,
         acceptable_overestimation: u32,
-    ) -> Result<Fee, SubmitTxError> {
+        use_pending_state: bool,
+    ) -> Result<FeeEstimate, SubmitTxError> {
         let estimation_started_at = Instant::now();
         let l1_gas_price = {
             let effective_gas_price = self.0.l1_gas_price_source.estimate_effective_gas_price();
@@ -641,7 +1104,10 @@ impl<G: L1GasPriceProvider> TxSender<G> {
 
         if !tx.is_l1()
             && account_code_hash == H256::zero()
-            && tx.execute.value > self.get_balance(&tx.initiator_account()).await
+            && tx.execute.value
+                > self
+                    .get_balance_for_estimate(&tx.initiator_account(), use_pending_state)
+                    .await
         {
             vlog::info!(
                 "fee estimation failed on validation step.
@@ -674,12 +1140,19 @@ impl<G: L1GasPriceProvider> TxSender<G> {
                 self.0.factory_deps_cache.clone(),
             )
             .await;
-            if pubdata_for_factory_deps > MAX_PUBDATA_PER_BLOCK {
+            // The bytecode of a contract deployed with EVM bytecode is published by the EVM interpreter
+            // during the execution. Its size is not known in advance, so the init code size is used as an upper bound.
+            let pubdata_for_evm_bytecode = tx
+                .execute
+                .evm_init_code()
+                .map_or(0, |init_code| init_code.len() as u32);
+            let pubdata_for_bytecodes = pubdata_for_factory_deps + pubdata_for_evm_bytecode;
+            if pubdata_for_bytecodes > MAX_PUBDATA_PER_BLOCK {
                 return Err(SubmitTxError::Unexecutable(
                     "exceeds limit for published pubdata".to_string(),
                 ));
             }
-            pubdata_for_factory_deps * (gas_per_pubdata_byte as u32)
+            pubdata_for_bytecodes * (gas_per_pubdata_byte as u32)
         };
 
         // Rolling cache with storage values that were read from the DB.
@@ -718,9 +1191,14 @@ impl<G: L1GasPriceProvider> TxSender<G> {
                     try_gas_limit,
                     l1_gas_price,
                     base_fee,
+                    use_pending_state,
                     &mut storage_read_cache,
                 )
                 .await;
+            // Increasing the gas limit cannot help a transaction producing too much output.
+            if let Err(SandboxExecutionError::OutputLimitExceeded(reason)) = &result {
+                return Err(SubmitTxError::ExecutionOutputLimitExceeded(reason.clone()));
+            }
             if result.is_err() {
                 lower_bound = mid + 1;
             } else {
@@ -756,6 +1234,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
                 suggested_gas_limit,
                 l1_gas_price,
                 base_fee,
+                use_pending_state,
                 &mut storage_read_cache,
             )
             .await;
@@ -763,7 +1242,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         drop(vm_permit); // Unblock other VMs to enter.
         match result {
             Err(err) => Err(err.into()),
-            Ok(_) => {
+            Ok((_, tx_metrics)) => {
                 let overhead = derive_overhead(
                     suggested_gas_limit,
                     gas_per_pubdata_byte as u32,
@@ -782,12 +1261,14 @@ impl<G: L1GasPriceProvider> TxSender<G> {
                         }
                     };
 
-                Ok(Fee {
+                let fee = Fee {
                     max_fee_per_gas: base_fee.into(),
                     max_priority_fee_per_gas: 0u32.into(),
                     gas_limit: full_gas_limit.into(),
                     gas_per_pubdata_limit: gas_per_pubdata_byte.into(),
-                })
+                };
+                let breakdown = fee_breakdown(&tx_metrics, gas_per_pubdata_byte, overhead);
+                Ok(FeeEstimate { fee, breakdown })
             }
         }
     }
@@ -806,6 +1287,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
             tx,
             block_args,
             vm_execution_cache_misses_limit,
+            self.0.sender_config.output_limits,
             false,
         )
         .await?;
@@ -821,6 +1303,34 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         })
     }
 
+    /// Simulates a bundle of transactions on top of the state at `block_args` without submitting them.
+    /// Results are returned in the execution order; execution stops at the first transaction rejected by the VM.
+    pub(super) async fn simulate_bundle(
+        &self,
+        block_args: BlockArgs,
+        txs: Vec<L2Tx>,
+    ) -> Vec<Result<VmTxExecutionResult, TxRevertReason>> {
+        let mut shared_args = self.shared_args();
+        for tx in &txs {
+            shared_args.adjust_l1_gas_price(tx.common_data.fee.gas_per_pubdata_limit);
+        }
+        let txs = txs.into_iter().map(Transaction::from).collect();
+
+        let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
+        let results = execute_bundle_in_sandbox(
+            &vm_permit,
+            shared_args,
+            self.0.replica_connection_pool.clone(),
+            txs,
+            block_args,
+            self.0.sender_config.validation_computational_gas_limit,
+            self.0.sender_config.output_limits,
+        )
+        .await;
+        drop(vm_permit); // Unblock other VMs to enter.
+        results
+    }
+
     pub fn gas_price(&self) -> u64 {
         let gas_price = self.0.l1_gas_price_source.estimate_effective_gas_price();
         let l1_gas_price = (gas_price as f64 * self.0.sender_config.gas_price_scale_factor).round();
@@ -831,6 +1341,35 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         base_fee
     }
 
+    /// Suggests the gas price and the priority fee using the configured policy.
+    pub async fn suggest_gas_price(&self) -> Result<GasPriceSuggestion, SqlxError> {
+        let policy = &self.0.gas_price_policy;
+        let history_size = policy.history_size();
+        let recent_base_fees = if history_size > 0 {
+            self.0
+                .replica_connection_pool
+                .access_storage_tagged("api")
+                .await
+                .blocks_web3_dal()
+                .get_recent_base_fees(history_size)
+                .await?
+        } else {
+            vec![]
+        };
+        Ok(policy.suggest(self.gas_price(), &recent_base_fees))
+    }
+
+    /// Returns the minimum gas per pubdata limit a transaction must have in order to be picked
+    /// from the mempool. Uses the same inputs as the mempool filter of the state keeper.
+    pub fn gas_per_pubdata(&self) -> u64 {
+        let l1_gas_price = self.0.l1_gas_price_source.estimate_effective_gas_price();
+        let (_, gas_per_pubdata_byte) = derive_base_fee_and_gas_per_pubdata(
+            l1_gas_price,
+            self.0.sender_config.fair_l2_gas_price,
+        );
+        gas_per_pubdata_byte
+    }
+
     fn ensure_tx_executable(
         &self,
         transaction: Transaction,
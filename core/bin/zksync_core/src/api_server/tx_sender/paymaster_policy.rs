@@ -0,0 +1,137 @@
+//! Policies restricting which paymasters are allowed to sponsor transactions.
+
+use std::{collections::HashSet, fmt};
+
+use zksync_config::configs::api::Web3JsonRpcConfig;
+use zksync_types::{Address, U256};
+
+use super::SubmitTxError;
+
+/// Policy checked for every submitted transaction that uses a paymaster.
+/// Permissioned chains can provide their own implementation via `TxSenderBuilder::with_paymaster_policy`.
+pub trait PaymasterPolicy: 'static + fmt::Debug + Send + Sync {
+    /// Returns `true` if the policy needs the paymaster balance to be provided to [`Self::check()`].
+    /// Allows to avoid a storage lookup for policies not depending on the balance.
+    fn requires_balance(&self) -> bool;
+
+    /// Checks whether the `paymaster` may sponsor a transaction.
+    /// `balance` is `Some(_)` iff [`Self::requires_balance()`] returns `true`.
+    fn check(&self, paymaster: Address, balance: Option<U256>) -> Result<(), SubmitTxError>;
+}
+
+/// Policy based on the configured lists of paymasters and the minimum paymaster balance.
+#[derive(Debug, Clone, Default)]
+pub struct ConfiguredPaymasterPolicy {
+    /// If set, only the paymasters from this list are allowed.
+    allowlist: Option<HashSet<Address>>,
+    /// Paymasters that are never allowed.
+    denylist: HashSet<Address>,
+    /// Min ETH balance of the paymaster (in wei).
+    min_balance: U256,
+}
+
+impl ConfiguredPaymasterPolicy {
+    pub fn new(
+        allowlist: Option<HashSet<Address>>,
+        denylist: HashSet<Address>,
+        min_balance: U256,
+    ) -> Self {
+        Self {
+            allowlist,
+            denylist,
+            min_balance,
+        }
+    }
+
+    pub fn from_config(config: &Web3JsonRpcConfig) -> Self {
+        Self::new(
+            config
+                .paymaster_allowlist
+                .as_ref()
+                .map(|list| list.iter().copied().collect()),
+            config.paymaster_denylist().into_iter().collect(),
+            U256::from(config.min_paymaster_balance_gwei()) * U256::exp10(9),
+        )
+    }
+
+    /// Returns `true` if the policy doesn't restrict anything.
+    pub fn is_permissive(&self) -> bool {
+        self.allowlist.is_none() && self.denylist.is_empty() && self.min_balance.is_zero()
+    }
+}
+
+impl PaymasterPolicy for ConfiguredPaymasterPolicy {
+    fn requires_balance(&self) -> bool {
+        !self.min_balance.is_zero()
+    }
+
+    fn check(&self, paymaster: Address, balance: Option<U256>) -> Result<(), SubmitTxError> {
+        let is_allowed = self
+            .allowlist
+            .as_ref()
+            .map_or(true, |allowlist| allowlist.contains(&paymaster));
+        if !is_allowed || self.denylist.contains(&paymaster) {
+            return Err(SubmitTxError::PaymasterNotAllowed(paymaster));
+        }
+
+        let balance = balance.unwrap_or_default();
+        if balance < self.min_balance {
+            return Err(SubmitTxError::PaymasterBalanceTooLow(
+                paymaster,
+                balance,
+                self.min_balance,
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allowlist_and_denylist() {
+        let allowed = Address::repeat_byte(1);
+        let denied = Address::repeat_byte(2);
+        let policy = ConfiguredPaymasterPolicy::new(
+            Some(vec![allowed, denied].into_iter().collect()),
+            vec![denied].into_iter().collect(),
+            U256::zero(),
+        );
+        assert!(!policy.requires_balance());
+
+        policy.check(allowed, None).unwrap();
+        assert!(matches!(
+            policy.check(denied, None),
+            Err(SubmitTxError::PaymasterNotAllowed(address)) if address == denied
+        ));
+        assert!(matches!(
+            policy.check(Address::repeat_byte(3), None),
+            Err(SubmitTxError::PaymasterNotAllowed(_))
+        ));
+    }
+
+    #[test]
+    fn min_balance() {
+        let policy =
+            ConfiguredPaymasterPolicy::new(None, HashSet::new(), U256::from(1_000_000_000u64));
+        assert!(policy.requires_balance());
+
+        let paymaster = Address::repeat_byte(1);
+        policy
+            .check(paymaster, Some(U256::from(1_000_000_000u64)))
+            .unwrap();
+        assert!(matches!(
+            policy.check(paymaster, Some(U256::from(1u64))),
+            Err(SubmitTxError::PaymasterBalanceTooLow(..))
+        ));
+    }
+
+    #[test]
+    fn default_policy_is_permissive() {
+        let policy = ConfiguredPaymasterPolicy::default();
+        assert!(policy.is_permissive());
+        policy.check(Address::repeat_byte(1), None).unwrap();
+    }
+}
@@ -45,7 +45,14 @@ impl TxProxy {
         let input_data = tx.common_data.input_data().expect("raw tx is absent");
         let raw_tx = zksync_types::Bytes(input_data.to_vec());
         vlog::info!("Proxying tx {}", tx.hash());
-        self.client.send_raw_transaction(raw_tx).await
+        match tx.common_data.valid_until {
+            Some(valid_until) => {
+                self.client
+                    .send_raw_transaction_with_deadline(raw_tx, valid_until.into())
+                    .await
+            }
+            None => self.client.send_raw_transaction(raw_tx).await,
+        }
     }
 
     pub async fn request_tx(&self, id: TransactionId) -> RpcResult<Option<Transaction>> {
@@ -0,0 +1,102 @@
+//! Statistics of transactions rejected by the `TxSender`, grouped by the rejection reason.
+//!
+//! Stats are exported to Prometheus and via the `zks_getRejectionStats` endpoint, so that operators can diagnose
+//! sudden drops in the number of accepted transactions (e.g., caused by a gas price spike).
+
+use std::{
+    collections::BTreeMap,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use super::SubmitTxError;
+
+/// Reason of a transaction rejection tracked in [`TxRejectionStats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TxRejectionReason {
+    FeeTooLow,
+    GasPerPubdataTooLow,
+    NonceTooLow,
+    NotEnoughBalance,
+    Other,
+}
+
+impl TxRejectionReason {
+    const ALL: [Self; 5] = [
+        Self::FeeTooLow,
+        Self::GasPerPubdataTooLow,
+        Self::NonceTooLow,
+        Self::NotEnoughBalance,
+        Self::Other,
+    ];
+
+    fn new(err: &SubmitTxError) -> Self {
+        match err {
+            SubmitTxError::MaxFeePerGasTooLow => Self::FeeTooLow,
+            SubmitTxError::GasPerPubdataLimitTooLow(..) => Self::GasPerPubdataTooLow,
+            SubmitTxError::NonceIsTooLow(..) => Self::NonceTooLow,
+            SubmitTxError::NotEnoughBalanceForFeeValue(..)
+            | SubmitTxError::InsufficientFundsForTransfer
+            | SubmitTxError::FailedToChargeFee(_) => Self::NotEnoughBalance,
+            _ => Self::Other,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::FeeTooLow => "fee_too_low",
+            Self::GasPerPubdataTooLow => "gas_per_pubdata_too_low",
+            Self::NonceTooLow => "nonce_too_low",
+            Self::NotEnoughBalance => "not_enough_balance",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// Cumulative numbers of rejected transactions since the server start.
+#[derive(Debug, Default)]
+pub(crate) struct TxRejectionStats {
+    counters: [AtomicU64; TxRejectionReason::ALL.len()],
+}
+
+impl TxRejectionStats {
+    pub fn record(&self, err: &SubmitTxError) {
+        let reason = TxRejectionReason::new(err);
+        self.counters[reason as usize].fetch_add(1, Ordering::Relaxed);
+        metrics::increment_counter!("api.tx_sender.rejected_txs", "reason" => reason.as_str());
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<String, u64> {
+        TxRejectionReason::ALL
+            .iter()
+            .map(|&reason| {
+                let count = self.counters[reason as usize].load(Ordering::Relaxed);
+                (reason.as_str().to_owned(), count)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::U256;
+
+    use super::*;
+
+    #[test]
+    fn rejections_are_grouped_by_reason() {
+        let stats = TxRejectionStats::default();
+        stats.record(&SubmitTxError::MaxFeePerGasTooLow);
+        stats.record(&SubmitTxError::NonceIsTooLow(1, 10, 0));
+        stats.record(&SubmitTxError::NonceIsTooLow(1, 10, 0));
+        stats.record(&SubmitTxError::InsufficientFundsForTransfer);
+        stats.record(&SubmitTxError::GasPerPubdataLimitTooLow(U256::one(), 800));
+        stats.record(&SubmitTxError::RateLimitExceeded);
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot["fee_too_low"], 1);
+        assert_eq!(snapshot["gas_per_pubdata_too_low"], 1);
+        assert_eq!(snapshot["nonce_too_low"], 2);
+        assert_eq!(snapshot["not_enough_balance"], 1);
+        assert_eq!(snapshot["other"], 1);
+    }
+}
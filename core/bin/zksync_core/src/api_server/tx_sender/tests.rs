@@ -0,0 +1,190 @@
+//! Tests for the account state cache integration in `TxSender`.
+
+use assert_matches::assert_matches;
+use db_test_macro::db_test;
+use tokio::sync::watch;
+
+use std::sync::Arc;
+
+use zksync_config::configs::{api::Web3JsonRpcConfig, chain::StateKeeperConfig};
+use zksync_contracts::BaseSystemContracts;
+use zksync_dal::ConnectionPool;
+use zksync_state::FactoryDepsCache;
+use zksync_types::{
+    block::MiniblockHeader, fee::Fee, get_nonce_key, l2::L2Tx, utils::storage_key_for_eth_balance,
+    Address, L2ChainId, MiniblockNumber, Nonce, PaymasterParams, StorageLog, H256, U256,
+};
+use zksync_utils::u256_to_h256;
+
+use super::*;
+use crate::genesis::create_genesis_block;
+
+#[derive(Debug)]
+struct MockL1GasPriceProvider;
+
+impl L1GasPriceProvider for MockL1GasPriceProvider {
+    fn estimate_effective_gas_price(&self) -> u64 {
+        1_000_000_000
+    }
+}
+
+struct Tester {
+    pool: ConnectionPool,
+    base_system_contracts: BaseSystemContracts,
+    sealed_miniblocks: watch::Sender<MiniblockNumber>,
+}
+
+impl Tester {
+    async fn new(pool: ConnectionPool) -> Self {
+        let base_system_contracts = BaseSystemContracts::load_from_disk();
+        let mut storage = pool.access_storage().await;
+        if storage.blocks_dal().is_genesis_needed().await {
+            create_genesis_block(
+                &mut storage,
+                Address::repeat_byte(0x01),
+                L2ChainId(270),
+                base_system_contracts.clone(),
+            )
+            .await;
+        }
+        drop(storage);
+
+        let (sealed_miniblocks, _) = watch::channel(MiniblockNumber(0));
+        Self {
+            pool,
+            base_system_contracts,
+            sealed_miniblocks,
+        }
+    }
+
+    async fn create_tx_sender(&self) -> TxSender<MockL1GasPriceProvider> {
+        let hashes = self.base_system_contracts.hashes();
+        let state_keeper_config = StateKeeperConfig {
+            fair_l2_gas_price: 250_000_000,
+            bootloader_hash: hashes.bootloader,
+            default_aa_hash: hashes.default_aa,
+            ..StateKeeperConfig::default()
+        };
+        let config = TxSenderConfig::new(&state_keeper_config, &Web3JsonRpcConfig::from_env());
+        TxSenderBuilder::new(config, self.pool.clone())
+            .with_main_connection_pool(self.pool.clone())
+            .with_account_state_cache(100, self.sealed_miniblocks.subscribe())
+            .build(
+                Arc::new(MockL1GasPriceProvider),
+                hashes.default_aa,
+                Arc::new(VmConcurrencyLimiter::new(None)),
+                FactoryDepsCache::new("test_factory_deps_cache", 1),
+            )
+            .await
+    }
+
+    /// Persists a sealed miniblock with the specified storage logs without notifying the cache.
+    async fn seal_miniblock(&self, number: u32, logs: Vec<StorageLog>) {
+        let mut storage = self.pool.access_storage().await;
+        storage
+            .blocks_dal()
+            .insert_miniblock(&MiniblockHeader {
+                number: MiniblockNumber(number),
+                timestamp: number.into(),
+                hash: H256::default(),
+                l1_tx_count: 0,
+                l2_tx_count: 0,
+                base_fee_per_gas: 100,
+                l1_gas_price: 100,
+                l2_fair_gas_price: 100,
+                base_system_contracts_hashes: self.base_system_contracts.hashes(),
+            })
+            .await;
+        let logs = [(H256::zero(), logs)];
+        storage
+            .storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(number), &logs)
+            .await;
+        storage.storage_dal().apply_storage_logs(&logs).await;
+    }
+
+    fn notify_sealed_miniblock(&self, number: u32) {
+        self.sealed_miniblocks.send_replace(MiniblockNumber(number));
+    }
+}
+
+fn create_tx(initiator: Address, nonce: u32, max_fee_per_gas: u64) -> L2Tx {
+    let fee = Fee {
+        gas_limit: 1_000_000_u64.into(),
+        max_fee_per_gas: max_fee_per_gas.into(),
+        max_priority_fee_per_gas: 0_u64.into(),
+        gas_per_pubdata_limit: 800_u32.into(),
+    };
+    let mut tx = L2Tx::new(
+        Address::repeat_byte(0x22),
+        vec![],
+        Nonce(nonce),
+        fee,
+        initiator,
+        U256::zero(),
+        None,
+        PaymasterParams::default(),
+    );
+    tx.set_input(H256::random().0.to_vec(), H256::random());
+    tx
+}
+
+#[db_test]
+async fn replacement_tx_uses_cached_nonce_until_miniblock_is_sealed(pool: ConnectionPool) {
+    let tester = Tester::new(pool).await;
+    let tx_sender = tester.create_tx_sender().await;
+    let account = Address::repeat_byte(0x11);
+
+    let tx = create_tx(account, 0, 100);
+    tx_sender.validate_account_nonce(&tx).await.unwrap();
+    let cache = tx_sender.0.account_state_cache.as_ref().unwrap();
+    assert_eq!(cache.get_nonce(account), Some(Nonce(0)));
+
+    // The original transaction is executed, but the miniblock with it isn't reported as sealed yet.
+    // The replacement has the same nonce, so it passes the check against the cached nonce; it will be rejected
+    // by the state keeper if the original transaction gets sealed first.
+    let nonce_log = StorageLog::new_write_log(get_nonce_key(&account), u256_to_h256(1.into()));
+    tester.seal_miniblock(1, vec![nonce_log]).await;
+    let replacement_tx = create_tx(account, 0, 200);
+    tx_sender
+        .validate_account_nonce(&replacement_tx)
+        .await
+        .unwrap();
+
+    // Once the miniblock is reported as sealed, the nonce is reloaded and the replacement is rejected.
+    tester.notify_sealed_miniblock(1);
+    let err = tx_sender
+        .validate_account_nonce(&replacement_tx)
+        .await
+        .unwrap_err();
+    assert_matches!(err, SubmitTxError::NonceIsTooLow(1, _, 0));
+    assert_eq!(cache.get_nonce(account), Some(Nonce(1)));
+
+    let next_tx = create_tx(account, 1, 100);
+    tx_sender.validate_account_nonce(&next_tx).await.unwrap();
+}
+
+#[db_test]
+async fn rejected_tx_is_accepted_after_balance_top_up_is_sealed(pool: ConnectionPool) {
+    let tester = Tester::new(pool).await;
+    let tx_sender = tester.create_tx_sender().await;
+    let account = Address::repeat_byte(0x11);
+
+    let tx = create_tx(account, 0, 100);
+    let err = tx_sender.validate_enough_balance(&tx).await.unwrap_err();
+    assert_matches!(err, SubmitTxError::NotEnoughBalanceForFeeValue(..));
+    let cache = tx_sender.0.account_state_cache.as_ref().unwrap();
+    assert_eq!(cache.get_balance(account), Some(U256::zero()));
+
+    // The top-up isn't visible until the miniblock with it is reported as sealed.
+    let balance = U256::from(10).pow(18.into());
+    let balance_log =
+        StorageLog::new_write_log(storage_key_for_eth_balance(&account), u256_to_h256(balance));
+    tester.seal_miniblock(1, vec![balance_log]).await;
+    let err = tx_sender.validate_enough_balance(&tx).await.unwrap_err();
+    assert_matches!(err, SubmitTxError::NotEnoughBalanceForFeeValue(..));
+
+    tester.notify_sealed_miniblock(1);
+    tx_sender.validate_enough_balance(&tx).await.unwrap();
+    assert_eq!(cache.get_balance(account), Some(balance));
+}
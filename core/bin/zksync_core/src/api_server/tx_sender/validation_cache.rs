@@ -0,0 +1,194 @@
+//! Cache of account abstraction validation rule violations.
+//!
+//! Validation of a custom account transaction is executed in the sandbox with restricted storage slot access rules.
+//! If the validation of a certain account code touched a banned slot (or otherwise violated the rules), it will do so
+//! for the subsequent transactions as well, so such transactions are rejected without spawning a VM.
+//! Violations are cached per account and paymaster together with their code hashes: validation may depend on
+//! the account storage, so the result isn't shared between accounts deployed with the same bytecode, and it's
+//! invalidated once the account or paymaster code is upgraded. The paymaster is a part of the key since its
+//! validation is executed with the same rules, so a violation may be caused by the paymaster rather than
+//! by the account. EOAs aren't cached since they use the default account code.
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use vm::oracles::tracer::{ValidationError, ViolatedValidationRule};
+use zksync_types::{Address, H256};
+
+/// Time after which the cached violation is discarded, so that the account could get another chance
+/// (e.g. if the violation depended on the state of other contracts).
+const VIOLATION_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Contracts the result of the transaction validation depends on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct ValidationCacheKey {
+    pub account: Address,
+    pub account_code_hash: H256,
+    /// Zero address if the transaction doesn't use a paymaster.
+    pub paymaster: Address,
+    /// Zero if the transaction doesn't use a paymaster.
+    pub paymaster_code_hash: H256,
+}
+
+#[derive(Debug, Clone)]
+struct CachedViolation {
+    message: String,
+    cached_at: Instant,
+}
+
+#[derive(Debug)]
+pub(crate) struct ValidationViolationsCache {
+    capacity: usize,
+    entries: Mutex<HashMap<ValidationCacheKey, CachedViolation>>,
+}
+
+impl ValidationViolationsCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the cached violation message for the account and paymaster with the specified code, if any.
+    pub fn get(&self, key: ValidationCacheKey) -> Option<String> {
+        let mut entries = self.entries.lock().expect("validation cache is poisoned");
+        let violation = match entries.get(&key) {
+            Some(violation) if violation.cached_at.elapsed() < VIOLATION_TTL => {
+                Some(violation.message.clone())
+            }
+            Some(_) => {
+                entries.remove(&key);
+                None
+            }
+            None => None,
+        };
+
+        metrics::increment_counter!(
+            "api.tx_sender.validation_cache",
+            "kind" => if violation.is_some() { "hit" } else { "miss" }
+        );
+        violation
+    }
+
+    /// Caches the validation result if it is a violation of the validation rules.
+    /// Other errors (e.g. reverts) depend on the transaction data and thus aren't cached.
+    pub fn insert_if_violation(
+        &self,
+        key: ValidationCacheKey,
+        result: &Result<(), ValidationError>,
+    ) {
+        let rule = match result {
+            Err(ValidationError::ViolatedRule(rule)) => rule,
+            _ => return,
+        };
+        if matches!(rule, ViolatedValidationRule::TookTooManyComputationalGas(_)) {
+            // Computational gas depends on the input, not only on the account code.
+            return;
+        }
+
+        let mut entries = self.entries.lock().expect("validation cache is poisoned");
+        if entries.len() >= self.capacity {
+            entries.retain(|_, violation| violation.cached_at.elapsed() < VIOLATION_TTL);
+            if entries.len() >= self.capacity {
+                return;
+            }
+        }
+
+        let violation = CachedViolation {
+            message: ValidationError::ViolatedRule(rule.clone()).to_string(),
+            cached_at: Instant::now(),
+        };
+        entries.insert(key, violation);
+        metrics::gauge!("api.tx_sender.validation_cache_size", entries.len() as f64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::U256;
+
+    fn cache_key(account: u8, account_code_hash: u8) -> ValidationCacheKey {
+        ValidationCacheKey {
+            account: Address::repeat_byte(account),
+            account_code_hash: H256::repeat_byte(account_code_hash),
+            paymaster: Address::zero(),
+            paymaster_code_hash: H256::zero(),
+        }
+    }
+
+    #[test]
+    fn only_rule_violations_are_cached() {
+        let cache = ValidationViolationsCache::new(10);
+        let key = cache_key(1, 1);
+
+        cache.insert_if_violation(key, &Ok(()));
+        cache.insert_if_violation(
+            key,
+            &Err(ValidationError::ViolatedRule(
+                ViolatedValidationRule::TookTooManyComputationalGas(100),
+            )),
+        );
+        assert_eq!(cache.get(key), None);
+
+        cache.insert_if_violation(
+            key,
+            &Err(ValidationError::ViolatedRule(
+                ViolatedValidationRule::TouchedUnallowedStorageSlots(
+                    Address::repeat_byte(2),
+                    U256::one(),
+                ),
+            )),
+        );
+        assert!(cache.get(key).is_some());
+        // Other accounts with the same code and the upgraded account code are different keys.
+        assert_eq!(cache.get(cache_key(3, 1)), None);
+        assert_eq!(cache.get(cache_key(1, 3)), None);
+    }
+
+    #[test]
+    fn violations_are_cached_per_paymaster() {
+        let cache = ValidationViolationsCache::new(10);
+        let key = ValidationCacheKey {
+            paymaster: Address::repeat_byte(0x10),
+            paymaster_code_hash: H256::repeat_byte(0x10),
+            ..cache_key(1, 1)
+        };
+        let violation = Err(ValidationError::ViolatedRule(
+            ViolatedValidationRule::TouchedUnallowedContext,
+        ));
+
+        cache.insert_if_violation(key, &violation);
+        assert!(cache.get(key).is_some());
+        // The violation may be caused by the paymaster, so it doesn't apply to other paymasters
+        // or to transactions without a paymaster.
+        assert_eq!(cache.get(cache_key(1, 1)), None);
+        let other_paymaster = ValidationCacheKey {
+            paymaster: Address::repeat_byte(0x20),
+            ..key
+        };
+        assert_eq!(cache.get(other_paymaster), None);
+        let upgraded_paymaster = ValidationCacheKey {
+            paymaster_code_hash: H256::repeat_byte(0x20),
+            ..key
+        };
+        assert_eq!(cache.get(upgraded_paymaster), None);
+    }
+
+    #[test]
+    fn cache_is_bounded() {
+        let cache = ValidationViolationsCache::new(1);
+        let violation = Err(ValidationError::ViolatedRule(
+            ViolatedValidationRule::TouchedUnallowedContext,
+        ));
+
+        cache.insert_if_violation(cache_key(1, 1), &violation);
+        cache.insert_if_violation(cache_key(2, 1), &violation);
+        assert!(cache.get(cache_key(1, 1)).is_some());
+        assert_eq!(cache.get(cache_key(2, 1)), None);
+    }
+}
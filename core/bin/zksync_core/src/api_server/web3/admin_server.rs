@@ -0,0 +1,52 @@
+//! Internal HTTP server serving the `admin` namespace. The namespace is not served by the public API servers,
+//! so that admin methods are only reachable on the interface the server binds to (the loopback one by default).
+
+use tokio::{sync::watch, task::JoinHandle};
+
+use std::net::SocketAddr;
+
+use zksync_health_check::CheckHealthStatus;
+use zksync_web3_decl::{jsonrpsee::server::ServerBuilder, namespaces::AdminNamespaceServer};
+
+use super::{api_health_check::ApiHealthCheck, namespaces::AdminNamespace};
+use crate::{
+    admin_controls::AdminControls, api_server::tx_sender::TxSender, state_keeper::MempoolGuard,
+};
+
+/// Starts the `admin` namespace server. Unlike the public API servers, the server runs on the main runtime
+/// since it's not expected to handle a significant load.
+pub(crate) async fn run_admin_server<G>(
+    addr: SocketAddr,
+    token: String,
+    controls: AdminControls,
+    mempool: Option<MempoolGuard>,
+    tx_sender: &TxSender<G>,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> (JoinHandle<()>, ApiHealthCheck) {
+    let admission_policies = tx_sender.0.admission_policies.clone();
+    let admin = AdminNamespace::new(controls, mempool, Some(admission_policies), token);
+    let (health_sender, health_receiver) =
+        watch::channel(CheckHealthStatus::NotReady("Api is not ready".into()));
+    let server = ServerBuilder::default()
+        .http_only()
+        .build(addr)
+        .await
+        .expect("Can't start the admin JSON RPC server");
+    let server_handle = server
+        .start(admin.into_rpc())
+        .expect("Failed to start admin JSON RPC application");
+    vlog::info!("Admin JSON RPC API is listening on {addr}");
+
+    let task = tokio::spawn(async move {
+        let close_handle = server_handle.clone();
+        tokio::spawn(async move {
+            if stop_receiver.changed().await.is_ok() {
+                vlog::info!("Stop signal received, admin JSON RPC API is shutting down");
+                close_handle.stop().unwrap();
+            }
+        });
+        health_sender.send(CheckHealthStatus::Ready).unwrap();
+        server_handle.stopped().await;
+    });
+    (task, ApiHealthCheck::new(vec![health_receiver]))
+}
@@ -4,23 +4,27 @@ use zksync_health_check::{CheckHealth, CheckHealthStatus};
 
 /// HealthCheck used to verify if the Api is ready.
 /// Used in the /health endpoint
+///
+/// If the API is served over multiple transports, it's ready only if all servers are ready.
 #[derive(Clone, Debug)]
 pub struct ApiHealthCheck {
-    receiver: watch::Receiver<CheckHealthStatus>,
+    receivers: Vec<watch::Receiver<CheckHealthStatus>>,
 }
 
 impl ApiHealthCheck {
-    pub(super) fn new(receiver: watch::Receiver<CheckHealthStatus>) -> ApiHealthCheck {
-        ApiHealthCheck { receiver }
+    pub(super) fn new(receivers: Vec<watch::Receiver<CheckHealthStatus>>) -> ApiHealthCheck {
+        ApiHealthCheck { receivers }
     }
 }
 
 #[async_trait]
 impl CheckHealth for ApiHealthCheck {
     async fn check_health(&self) -> CheckHealthStatus {
-        match *self.receiver.borrow() {
-            CheckHealthStatus::Ready => CheckHealthStatus::Ready,
-            CheckHealthStatus::NotReady(ref error) => CheckHealthStatus::NotReady(error.clone()),
+        for receiver in &self.receivers {
+            if let CheckHealthStatus::NotReady(ref error) = *receiver.borrow() {
+                return CheckHealthStatus::NotReady(error.clone());
+            }
         }
+        CheckHealthStatus::Ready
     }
 }
@@ -0,0 +1,173 @@
+//! `jsonrpc` middleware applying request limits to the calls made via the `jsonrpc` servers.
+//!
+//! Unlike HTTP requests to the `jsonrpsee` server, requests to the `jsonrpc` servers don't pass through
+//! the HTTP middleware, and the servers don't expose the peer address of the connection. Thus, the batch size
+//! is checked by this middleware, and calls are rate limited per connection; the number of connections is capped
+//! separately by the subscriptions limit. Calls made via the HTTP server are not rate limited.
+
+use futures::future::{self, Either};
+use jsonrpc_core::{
+    BoxFuture, Call, Error, ErrorCode, Id, Metadata, Middleware, Output, Request, Response, Version,
+};
+use jsonrpc_pubsub::{PubSubMetadata, Session};
+
+use std::{future::Future, sync::Arc};
+
+use crate::api_server::web3::request_limits::{
+    ConnectionLimiter, LimitViolation, RequestLimitsConfig, RATE_LIMITED_ERROR_CODE,
+};
+
+/// Metadata of calls made via the pub-sub (WebSocket and IPC) servers.
+#[derive(Debug, Clone)]
+pub struct SessionMetadata {
+    session: Arc<Session>,
+    /// Rate limits for the calls made in this session. Not set for IPC sessions and if rate limiting is disabled.
+    limiter: Option<Arc<ConnectionLimiter>>,
+}
+
+impl SessionMetadata {
+    pub fn new(session: Session, limiter: Option<ConnectionLimiter>) -> Self {
+        Self {
+            session: Arc::new(session),
+            limiter: limiter.map(Arc::new),
+        }
+    }
+}
+
+impl Metadata for SessionMetadata {}
+
+impl PubSubMetadata for SessionMetadata {
+    fn session(&self) -> Option<Arc<Session>> {
+        Some(self.session.clone())
+    }
+}
+
+/// Metadata providing rate limits for the calls.
+pub trait RateLimitedMetadata: Metadata {
+    fn limiter(&self) -> Option<&ConnectionLimiter>;
+}
+
+impl RateLimitedMetadata for SessionMetadata {
+    fn limiter(&self) -> Option<&ConnectionLimiter> {
+        self.limiter.as_deref()
+    }
+}
+
+/// Middleware rejecting too large batch requests and calls that exceed the rate limits of the session.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CallLimitsMiddleware {
+    max_batch_request_size: Option<usize>,
+}
+
+impl CallLimitsMiddleware {
+    pub fn new(config: Option<&RequestLimitsConfig>) -> Self {
+        Self {
+            max_batch_request_size: config.map(|config| config.max_batch_request_size),
+        }
+    }
+
+    fn report_rejection(violation: &LimitViolation) {
+        metrics::increment_counter!(
+            "api.web3.rejected_requests",
+            "reason" => violation.as_str()
+        );
+    }
+}
+
+impl<M: RateLimitedMetadata> Middleware<M> for CallLimitsMiddleware {
+    type Future = BoxFuture<Option<Response>>;
+    type CallFuture = BoxFuture<Option<Output>>;
+
+    fn on_request<F, X>(&self, request: Request, meta: M, next: F) -> Either<Self::Future, X>
+    where
+        F: Fn(Request, M) -> X + Send + Sync,
+        X: Future<Output = Option<Response>> + Send + 'static,
+    {
+        let violation = match (&request, self.max_batch_request_size) {
+            (Request::Batch(calls), Some(limit)) if calls.len() > limit => {
+                Some(LimitViolation::BatchTooLarge {
+                    size: calls.len(),
+                    limit,
+                })
+            }
+            _ => None,
+        };
+
+        match violation {
+            Some(violation) => {
+                Self::report_rejection(&violation);
+                let error = Error {
+                    code: ErrorCode::InvalidRequest,
+                    message: violation.to_string(),
+                    data: None,
+                };
+                let output = Output::from(Err(error), Id::Null, Some(Version::V2));
+                Either::Left(Box::pin(future::ready(Some(Response::Single(output)))))
+            }
+            None => Either::Right(next(request, meta)),
+        }
+    }
+
+    fn on_call<F, X>(&self, call: Call, meta: M, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, M) -> X + Send + Sync,
+        X: Future<Output = Option<Output>> + Send + 'static,
+    {
+        let rejected_output = match (&call, meta.limiter()) {
+            (Call::MethodCall(method_call), Some(limiter)) => limiter
+                .check_call(&method_call.method)
+                .err()
+                .map(|violation| {
+                    Self::report_rejection(&violation);
+                    let error = Error {
+                        code: ErrorCode::ServerError(RATE_LIMITED_ERROR_CODE),
+                        message: violation.to_string(),
+                        data: None,
+                    };
+                    Output::from(Err(error), method_call.id.clone(), method_call.jsonrpc)
+                }),
+            _ => None,
+        };
+
+        match rejected_output {
+            Some(output) => Either::Left(Box::pin(future::ready(Some(output)))),
+            None => Either::Right(next(call, meta)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonrpc_core::{MetaIoHandler, Value};
+    use serde_json::json;
+
+    use super::*;
+    use crate::api_server::web3::backend_jsonrpc::call_metrics::HttpCallMetadata;
+
+    #[tokio::test]
+    async fn too_large_batches_are_rejected() {
+        let config = RequestLimitsConfig {
+            reads_per_second_per_ip: None,
+            executions_per_second_per_ip: None,
+            max_batch_request_size: 2,
+            max_request_body_size: 1_024,
+            trusted_proxies: vec![],
+        };
+        let mut io = MetaIoHandler::with_middleware(CallLimitsMiddleware::new(Some(&config)));
+        io.add_method_with_meta("eth_blockNumber", |_, _: HttpCallMetadata| async {
+            Ok(Value::from("0x1"))
+        });
+        let call = |id: u64| json!({ "jsonrpc": "2.0", "id": id, "method": "eth_blockNumber" });
+
+        let batch = json!([call(1), call(2)]).to_string();
+        let response = io.handle_request(&batch, HttpCallMetadata::default()).await;
+        let response: Value = serde_json::from_str(&response.unwrap()).unwrap();
+        assert_eq!(response.as_array().unwrap().len(), 2);
+
+        let batch = json!([call(1), call(2), call(3)]).to_string();
+        let response = io.handle_request(&batch, HttpCallMetadata::default()).await;
+        let response: Value = serde_json::from_str(&response.unwrap()).unwrap();
+        assert_eq!(response["error"]["code"], ErrorCode::InvalidRequest.code());
+        assert_eq!(response["id"], Value::Null);
+    }
+}
@@ -0,0 +1,276 @@
+//! `jsonrpc` middleware reporting per-method latency and error metrics.
+//!
+//! Optionally, calls taking longer than the configured threshold are logged together with their parameters,
+//! so that it's possible to pinpoint which calls (and which callers) degrade the API. Only a share of slow calls
+//! is logged to keep the log volume bounded. Parameters of admin methods are redacted since they include
+//! the admin token; long string parameters (e.g., raw transactions) are truncated.
+
+use futures::future::Either;
+use jsonrpc_core::{
+    middleware::NoopFuture, BoxFuture, Call, ErrorCode, Metadata, Middleware, Output, Params,
+};
+use jsonrpc_http_server::hyper;
+use rand::Rng;
+use serde_json::Value;
+
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+
+use zksync_config::configs::api::Web3JsonRpcConfig;
+
+use super::call_limits::{RateLimitedMetadata, SessionMetadata};
+use crate::api_server::web3::request_limits::ConnectionLimiter;
+
+/// Max length of a string parameter of a logged call; longer strings are truncated.
+const MAX_LOGGED_STRING_LEN: usize = 256;
+/// Label used for methods not served by the API to keep metric cardinality bounded.
+const UNKNOWN_METHOD: &str = "unknown";
+
+/// Configuration of [`CallMetricsMiddleware`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CallMetricsConfig {
+    /// Calls taking longer than this threshold are logged. If not set, slow calls are not logged.
+    pub slow_call_threshold: Option<Duration>,
+    /// Share of slow calls that are logged, from 0 to 1.
+    pub slow_call_log_sample_rate: f64,
+}
+
+impl Default for CallMetricsConfig {
+    fn default() -> Self {
+        Self {
+            slow_call_threshold: None,
+            slow_call_log_sample_rate: 0.0,
+        }
+    }
+}
+
+impl CallMetricsConfig {
+    pub fn from_config(config: &Web3JsonRpcConfig) -> Self {
+        Self {
+            slow_call_threshold: config.slow_call_threshold(),
+            slow_call_log_sample_rate: config.slow_call_log_sample_rate(),
+        }
+    }
+
+    fn is_slow(&self, latency: Duration) -> bool {
+        self.slow_call_threshold
+            .map_or(false, |threshold| latency >= threshold)
+    }
+
+    fn sample_slow_call(&self) -> bool {
+        rand::thread_rng().gen_bool(self.slow_call_log_sample_rate.clamp(0.0, 1.0))
+    }
+}
+
+/// Metadata allowing to identify the caller in slow call logs.
+pub trait CallerMetadata: Metadata {
+    fn caller(&self) -> Option<&str>;
+}
+
+/// Metadata of calls to the HTTP server. The caller is identified by the `X-Forwarded-For` header
+/// set by the load balancer, since the HTTP server doesn't expose the peer address.
+#[derive(Debug, Clone, Default)]
+pub struct HttpCallMetadata {
+    caller: Option<String>,
+}
+
+impl HttpCallMetadata {
+    pub fn from_request(request: &hyper::Request<hyper::Body>) -> Self {
+        let caller = request
+            .headers()
+            .get("x-forwarded-for")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .map(|caller| caller.trim().to_owned());
+        Self { caller }
+    }
+}
+
+impl Metadata for HttpCallMetadata {}
+
+impl CallerMetadata for HttpCallMetadata {
+    fn caller(&self) -> Option<&str> {
+        self.caller.as_deref()
+    }
+}
+
+impl RateLimitedMetadata for HttpCallMetadata {
+    fn limiter(&self) -> Option<&ConnectionLimiter> {
+        None
+    }
+}
+
+impl CallerMetadata for SessionMetadata {
+    fn caller(&self) -> Option<&str> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CallMetricsMiddleware {
+    config: CallMetricsConfig,
+}
+
+impl CallMetricsMiddleware {
+    pub fn new(config: CallMetricsConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl<M: CallerMetadata> Middleware<M> for CallMetricsMiddleware {
+    type Future = NoopFuture;
+    type CallFuture = BoxFuture<Option<Output>>;
+
+    fn on_call<F, X>(&self, call: Call, meta: M, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, M) -> X + Send + Sync,
+        X: Future<Output = Option<Output>> + Send + 'static,
+    {
+        let (method, params) = match &call {
+            Call::MethodCall(method_call) => {
+                (method_call.method.clone(), method_call.params.clone())
+            }
+            Call::Notification(_) | Call::Invalid { .. } => return Either::Right(next(call, meta)),
+        };
+        let caller = meta.caller().map(str::to_owned);
+        let config = self.config;
+        let started_at = Instant::now();
+        let output = next(call, meta);
+
+        Either::Left(Box::pin(async move {
+            let output = output.await;
+            let latency = started_at.elapsed();
+            let error_code = match &output {
+                Some(Output::Failure(failure)) => Some(failure.error.code.clone()),
+                _ => None,
+            };
+            let (namespace, method_label) = method_labels(&method, error_code.as_ref());
+
+            metrics::histogram!(
+                "api.jsonrpc.call_latency",
+                latency,
+                "namespace" => namespace,
+                "method" => method_label.clone()
+            );
+            if let Some(code) = &error_code {
+                metrics::counter!(
+                    "api.jsonrpc.call_errors",
+                    1,
+                    "namespace" => namespace,
+                    "method" => method_label.clone(),
+                    "code" => code.code().to_string()
+                );
+            }
+            if config.is_slow(latency) {
+                metrics::counter!(
+                    "api.jsonrpc.slow_calls",
+                    1,
+                    "namespace" => namespace,
+                    "method" => method_label
+                );
+                if config.sample_slow_call() {
+                    vlog::warn!(
+                        "Slow call to `{}` from {} took {:?}: params {}",
+                        method,
+                        caller.as_deref().unwrap_or("unknown caller"),
+                        latency,
+                        redact_params(&method, &params)
+                    );
+                }
+            }
+            output
+        }))
+    }
+}
+
+/// Returns namespace and method labels for the call metrics.
+fn method_labels(method: &str, error_code: Option<&ErrorCode>) -> (&'static str, String) {
+    if matches!(error_code, Some(ErrorCode::MethodNotFound)) {
+        return (UNKNOWN_METHOD, UNKNOWN_METHOD.to_owned());
+    }
+    let namespace = match method.split('_').next() {
+        Some("eth") => "eth",
+        Some("zks") => "zks",
+        Some("en") => "en",
+        Some("net") => "net",
+        Some("web3") => "web3",
+        Some("txpool") => "txpool",
+        Some("debug") => "debug",
+        Some("admin") => "admin",
+        _ => UNKNOWN_METHOD,
+    };
+    (namespace, method.to_owned())
+}
+
+/// Formats call parameters for logging, redacting sensitive parameters and truncating long strings.
+fn redact_params(method: &str, params: &Params) -> String {
+    if method.starts_with("admin_") {
+        return "<redacted>".to_owned();
+    }
+    let mut value = match params {
+        Params::None => return "[]".to_owned(),
+        Params::Array(values) => Value::Array(values.clone()),
+        Params::Map(map) => Value::Object(map.clone()),
+    };
+    truncate_strings(&mut value);
+    value.to_string()
+}
+
+fn truncate_strings(value: &mut Value) {
+    match value {
+        Value::String(s) => {
+            if let Some((cutoff, _)) = s.char_indices().nth(MAX_LOGGED_STRING_LEN) {
+                *s = format!("{}...<{} bytes>", &s[..cutoff], s.len());
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(truncate_strings),
+        Value::Object(map) => map.values_mut().for_each(truncate_strings),
+        Value::Null | Value::Bool(_) | Value::Number(_) => { /* nothing to truncate */ }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn redacting_params() {
+        let params = Params::Array(vec![json!("secret-token"), json!("info")]);
+        assert_eq!(
+            redact_params("admin_setLogDirectives", &params),
+            "<redacted>"
+        );
+
+        let raw_tx = format!("0x{}", "ab".repeat(200));
+        let params = Params::Array(vec![json!(raw_tx)]);
+        let redacted = redact_params("eth_sendRawTransaction", &params);
+        let expected_prefix = &raw_tx[..MAX_LOGGED_STRING_LEN];
+        assert_eq!(redacted, format!("[\"{expected_prefix}...<402 bytes>\"]"));
+
+        let params: Params = serde_json::from_value(json!({ "data": "0x", "to": "0x01" })).unwrap();
+        assert_eq!(
+            redact_params("eth_call", &params),
+            r#"{"data":"0x","to":"0x01"}"#
+        );
+    }
+
+    #[test]
+    fn labelling_methods() {
+        assert_eq!(
+            method_labels("eth_call", None),
+            ("eth", "eth_call".to_owned())
+        );
+        assert_eq!(
+            method_labels("zks_simulateBundle", Some(&ErrorCode::InvalidParams)),
+            ("zks", "zks_simulateBundle".to_owned())
+        );
+        assert_eq!(
+            method_labels("eth_noSuchMethod", Some(&ErrorCode::MethodNotFound)),
+            (UNKNOWN_METHOD, UNKNOWN_METHOD.to_owned())
+        );
+    }
+}
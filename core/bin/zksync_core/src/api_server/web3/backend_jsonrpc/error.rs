@@ -1,4 +1,5 @@
 use jsonrpc_core::{Error, ErrorCode};
+use zksync_dal::pruning_dal::HistoryQueryError;
 use zksync_web3_decl::error::Web3Error;
 
 pub fn into_jsrpc_error(err: Web3Error) -> Error {
@@ -13,10 +14,16 @@ pub fn into_jsrpc_error(err: Web3Error) -> Error {
             | Web3Error::FilterNotFound
             | Web3Error::InvalidFeeParams(_)
             | Web3Error::LogsLimitExceeded(_, _, _)
-            | Web3Error::InvalidFilterBlockHash => ErrorCode::InvalidParams,
+            | Web3Error::InvalidFilterBlockHash
+            | Web3Error::TooManyItemsRequested(_)
+            | Web3Error::InvalidLogDirectives(_)
+            | Web3Error::InvalidBundle(_)
+            | Web3Error::InvalidL1BatchRange(_) => ErrorCode::InvalidParams,
             Web3Error::SubmitTransactionError(_, _) | Web3Error::SerializationError(_) => 3.into(),
             Web3Error::PubSubTimeout => 4.into(),
             Web3Error::RequestTimeout => 5.into(),
+            Web3Error::PrunedHistory(_) => 6.into(),
+            Web3Error::Unauthorized => 7.into(),
         },
         message: match err {
             Web3Error::SubmitTransactionError(_, _) => err.to_string(),
@@ -41,3 +48,13 @@ pub fn internal_error(method_name: &str, error: impl ToString) -> Web3Error {
 
     Web3Error::InternalError
 }
+
+/// Converts an error of a DAL query that may concern pruned historical data.
+pub fn history_query_error(method_name: &str, error: HistoryQueryError) -> Web3Error {
+    match error {
+        HistoryQueryError::PrunedHistory {
+            last_pruned_miniblock,
+        } => Web3Error::PrunedHistory(last_pruned_miniblock.0),
+        HistoryQueryError::Sqlx(err) => internal_error(method_name, err),
+    }
+}
@@ -1,3 +1,5 @@
+pub mod call_limits;
+pub mod call_metrics;
 pub mod error;
 pub mod namespaces;
 pub mod pub_sub;
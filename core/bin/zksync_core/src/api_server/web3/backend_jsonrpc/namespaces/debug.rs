@@ -5,8 +5,12 @@ use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_derive::rpc;
 
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig, H256},
+    api::{
+        BlockId, BlockNumber, DebugCall, RejectedPriorityOp, ResultDebugCall, TracerConfig,
+        TransactionAccessSet, H256,
+    },
     transaction_request::CallRequest,
+    L1BatchNumber,
 };
 
 #[rpc]
@@ -39,6 +43,15 @@ pub trait DebugNamespaceT {
         tx_hash: H256,
         options: Option<TracerConfig>,
     ) -> BoxFuture<Result<Option<DebugCall>>>;
+
+    #[rpc(name = "debug_getL1BatchAccessSets")]
+    fn get_l1_batch_access_sets(
+        &self,
+        batch: L1BatchNumber,
+    ) -> BoxFuture<Result<Option<Vec<TransactionAccessSet>>>>;
+
+    #[rpc(name = "debug_getRejectedPriorityOps")]
+    fn get_rejected_priority_ops(&self) -> BoxFuture<Result<Vec<RejectedPriorityOp>>>;
 }
 
 impl DebugNamespaceT for DebugNamespace {
@@ -91,6 +104,24 @@ impl DebugNamespaceT for DebugNamespace {
         options: Option<TracerConfig>,
     ) -> BoxFuture<Result<Option<DebugCall>>> {
         let self_ = self.clone();
-        Box::pin(async move { Ok(self_.debug_trace_transaction_impl(tx_hash, options).await) })
+        Box::pin(async move {
+            self_
+                .debug_trace_transaction_impl(tx_hash, options)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
+    fn get_l1_batch_access_sets(
+        &self,
+        batch: L1BatchNumber,
+    ) -> BoxFuture<Result<Option<Vec<TransactionAccessSet>>>> {
+        let self_ = self.clone();
+        Box::pin(async move { Ok(self_.debug_get_l1_batch_access_sets_impl(batch).await) })
+    }
+
+    fn get_rejected_priority_ops(&self) -> BoxFuture<Result<Vec<RejectedPriorityOp>>> {
+        let self_ = self.clone();
+        Box::pin(async move { Ok(self_.debug_get_rejected_priority_ops_impl().await) })
     }
 }
@@ -42,6 +42,9 @@ pub trait EthNamespaceT {
     #[rpc(name = "eth_gasPrice")]
     fn gas_price(&self) -> BoxFuture<Result<U256>>;
 
+    #[rpc(name = "eth_maxPriorityFeePerGas")]
+    fn max_priority_fee_per_gas(&self) -> BoxFuture<Result<U256>>;
+
     #[rpc(name = "eth_newFilter")]
     fn new_filter(&self, filter: Filter) -> BoxFuture<Result<U256>>;
 
@@ -217,7 +220,17 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> EthNamespaceT for EthNamespa
 
     fn gas_price(&self) -> BoxFuture<Result<U256>> {
         let self_ = self.clone();
-        Box::pin(async move { self_.gas_price_impl().map_err(into_jsrpc_error) })
+        Box::pin(async move { self_.gas_price_impl().await.map_err(into_jsrpc_error) })
+    }
+
+    fn max_priority_fee_per_gas(&self) -> BoxFuture<Result<U256>> {
+        let self_ = self.clone();
+        Box::pin(async move {
+            self_
+                .max_priority_fee_per_gas_impl()
+                .await
+                .map_err(into_jsrpc_error)
+        })
     }
 
     fn new_filter(&self, filter: Filter) -> BoxFuture<Result<U256>> {
@@ -2,5 +2,6 @@ pub mod debug;
 pub mod en;
 pub mod eth;
 pub mod net;
+pub mod txpool;
 pub mod web3;
 pub mod zks;
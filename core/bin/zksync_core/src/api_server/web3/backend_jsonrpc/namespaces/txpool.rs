@@ -0,0 +1,25 @@
+// Built-in uses
+
+// External uses
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+
+// Workspace uses
+use zksync_types::api::TxpoolStatus;
+
+// Local uses
+use crate::api_server::web3::{
+    backend_jsonrpc::error::into_jsrpc_error, namespaces::TxpoolNamespace,
+};
+
+#[rpc]
+pub trait TxpoolNamespaceT {
+    #[rpc(name = "txpool_status", returns = "TxpoolStatus")]
+    fn txpool_status(&self) -> Result<TxpoolStatus>;
+}
+
+impl TxpoolNamespaceT for TxpoolNamespace {
+    fn txpool_status(&self) -> Result<TxpoolStatus> {
+        self.status_impl().map_err(into_jsrpc_error)
+    }
+}
@@ -8,9 +8,14 @@ use jsonrpc_derive::rpc;
 
 // Workspace uses
 use zksync_types::{
-    api::{BridgeAddresses, L2ToL1LogProof, TransactionDetails},
+    api::{
+        AccountSummary, BalancesFilter, BatchResourceUsage, BlockIdVariant, BridgeAddresses,
+        BundleSimulation, L2ToL1LogProof, L2ToL1MessageProof, NodeStatus, ProtocolVersion,
+        RejectionStats, StorageDiff, StorageDiffPagination, TokenFilter, TransactionDetails,
+        TransactionHistory, WithdrawalFinalizationParams,
+    },
     explorer_api::{BlockDetails, L1BatchDetails},
-    fee::Fee,
+    fee::FeeEstimate,
     transaction_request::CallRequest,
     Address, Bytes, L1BatchNumber, MiniblockNumber, H256, U256, U64,
 };
@@ -24,7 +29,7 @@ use crate::{l1_gas_price::L1GasPriceProvider, web3::backend_jsonrpc::error::into
 #[rpc]
 pub trait ZksNamespaceT {
     #[rpc(name = "zks_estimateFee")]
-    fn estimate_fee(&self, req: CallRequest) -> BoxFuture<Result<Fee>>;
+    fn estimate_fee(&self, req: CallRequest) -> BoxFuture<Result<FeeEstimate>>;
 
     #[rpc(name = "zks_estimateGasL1ToL2")]
     fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> BoxFuture<Result<U256>>;
@@ -42,7 +47,12 @@ pub trait ZksNamespaceT {
     fn l1_chain_id(&self) -> BoxFuture<Result<U64>>;
 
     #[rpc(name = "zks_getConfirmedTokens")]
-    fn get_confirmed_tokens(&self, from: u32, limit: u8) -> BoxFuture<Result<Vec<Token>>>;
+    fn get_confirmed_tokens(
+        &self,
+        from: u32,
+        limit: u8,
+        filter: Option<TokenFilter>,
+    ) -> BoxFuture<Result<Vec<Token>>>;
 
     #[rpc(name = "zks_getTokenPrice")]
     fn get_token_price(&self, token_address: Address) -> BoxFuture<Result<BigDecimal>>;
@@ -51,6 +61,7 @@ pub trait ZksNamespaceT {
     fn get_all_account_balances(
         &self,
         address: Address,
+        filter: Option<BalancesFilter>,
     ) -> BoxFuture<Result<HashMap<Address, U256>>>;
 
     #[rpc(name = "zks_getL2ToL1MsgProof")]
@@ -69,6 +80,27 @@ pub trait ZksNamespaceT {
         index: Option<usize>,
     ) -> BoxFuture<Result<Option<L2ToL1LogProof>>>;
 
+    #[rpc(name = "zks_getL2ToL1LogProofs")]
+    fn get_l2_to_l1_log_proofs(
+        &self,
+        tx_hash: H256,
+        indices: Vec<usize>,
+    ) -> BoxFuture<Result<Vec<Option<L2ToL1LogProof>>>>;
+
+    #[rpc(name = "zks_getWithdrawalFinalizationParams")]
+    fn get_withdrawal_finalization_params(
+        &self,
+        tx_hash: H256,
+        index: Option<usize>,
+    ) -> BoxFuture<Result<Option<WithdrawalFinalizationParams>>>;
+
+    #[rpc(name = "zks_getL2ToL1MessageProof")]
+    fn get_l2_to_l1_message_proof(
+        &self,
+        batch: L1BatchNumber,
+        index: u32,
+    ) -> BoxFuture<Result<Option<L2ToL1MessageProof>>>;
+
     #[rpc(name = "zks_L1BatchNumber")]
     fn get_l1_batch_number(&self) -> BoxFuture<Result<U64>>;
 
@@ -87,6 +119,19 @@ pub trait ZksNamespaceT {
     #[rpc(name = "zks_getTransactionDetails")]
     fn get_transaction_details(&self, hash: H256) -> BoxFuture<Result<Option<TransactionDetails>>>;
 
+    #[rpc(name = "zks_getTransactionHistory")]
+    fn get_transaction_history(&self, hash: H256) -> BoxFuture<Result<Option<TransactionHistory>>>;
+
+    #[rpc(name = "zks_getNodeStatus")]
+    fn get_node_status(&self) -> BoxFuture<Result<NodeStatus>>;
+
+    #[rpc(name = "zks_simulateBundle")]
+    fn simulate_bundle(
+        &self,
+        txs: Vec<Bytes>,
+        block: Option<BlockIdVariant>,
+    ) -> BoxFuture<Result<BundleSimulation>>;
+
     #[rpc(name = "zks_getRawBlockTransactions")]
     fn get_raw_block_transactions(
         &self,
@@ -99,15 +144,55 @@ pub trait ZksNamespaceT {
         batch: L1BatchNumber,
     ) -> BoxFuture<Result<Option<L1BatchDetails>>>;
 
+    #[rpc(name = "zks_getBatchResourceUsage")]
+    fn get_batch_resource_usage(
+        &self,
+        batch: L1BatchNumber,
+    ) -> BoxFuture<Result<Option<BatchResourceUsage>>>;
+
     #[rpc(name = "zks_getBytecodeByHash")]
     fn get_bytecode_by_hash(&self, hash: H256) -> BoxFuture<Result<Option<Vec<u8>>>>;
 
     #[rpc(name = "zks_getL1GasPrice")]
     fn get_l1_gas_price(&self) -> BoxFuture<Result<U64>>;
+
+    #[rpc(name = "zks_getGasPerPubdata")]
+    fn get_gas_per_pubdata(&self) -> BoxFuture<Result<U64>>;
+
+    #[rpc(name = "zks_getProtocolVersion")]
+    fn get_protocol_version(
+        &self,
+        version_id: Option<u16>,
+    ) -> BoxFuture<Result<Option<ProtocolVersion>>>;
+
+    #[rpc(name = "zks_getOperatorAddress")]
+    fn get_operator_address(&self) -> BoxFuture<Result<Address>>;
+
+    #[rpc(name = "zks_getRejectionStats")]
+    fn get_rejection_stats(&self) -> BoxFuture<Result<RejectionStats>>;
+
+    #[rpc(name = "zks_getStorageDiff")]
+    fn get_storage_diff(
+        &self,
+        l1_batch_from: L1BatchNumber,
+        l1_batch_to: L1BatchNumber,
+        address: Option<Address>,
+        pagination: Option<StorageDiffPagination>,
+    ) -> BoxFuture<Result<StorageDiff>>;
+
+    #[rpc(name = "zks_getAccountSummary")]
+    fn get_account_summary(&self, address: Address) -> BoxFuture<Result<Option<AccountSummary>>>;
+
+    #[rpc(name = "zks_sendRawTransactionWithDeadline")]
+    fn send_raw_transaction_with_deadline(
+        &self,
+        tx_bytes: Bytes,
+        valid_until: U64,
+    ) -> BoxFuture<Result<H256>>;
 }
 
 impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceT for ZksNamespace<G> {
-    fn estimate_fee(&self, req: CallRequest) -> BoxFuture<Result<Fee>> {
+    fn estimate_fee(&self, req: CallRequest) -> BoxFuture<Result<FeeEstimate>> {
         let self_ = self.clone();
         Box::pin(async move { self_.estimate_fee_impl(req).await.map_err(into_jsrpc_error) })
     }
@@ -152,11 +237,16 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceT for ZksNamespa
         Box::pin(async move { Ok(self_.l1_chain_id_impl()) })
     }
 
-    fn get_confirmed_tokens(&self, from: u32, limit: u8) -> BoxFuture<Result<Vec<Token>>> {
+    fn get_confirmed_tokens(
+        &self,
+        from: u32,
+        limit: u8,
+        filter: Option<TokenFilter>,
+    ) -> BoxFuture<Result<Vec<Token>>> {
         let self_ = self.clone();
         Box::pin(async move {
             self_
-                .get_confirmed_tokens_impl(from, limit)
+                .get_confirmed_tokens_impl(from, limit, filter)
                 .await
                 .map_err(into_jsrpc_error)
         })
@@ -175,11 +265,12 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceT for ZksNamespa
     fn get_all_account_balances(
         &self,
         address: Address,
+        filter: Option<BalancesFilter>,
     ) -> BoxFuture<Result<HashMap<Address, U256>>> {
         let self_ = self.clone();
         Box::pin(async move {
             self_
-                .get_all_account_balances_impl(address)
+                .get_all_account_balances_impl(address, filter)
                 .await
                 .map_err(into_jsrpc_error)
         })
@@ -215,6 +306,48 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceT for ZksNamespa
         })
     }
 
+    fn get_l2_to_l1_log_proofs(
+        &self,
+        tx_hash: H256,
+        indices: Vec<usize>,
+    ) -> BoxFuture<Result<Vec<Option<L2ToL1LogProof>>>> {
+        let self_ = self.clone();
+        Box::pin(async move {
+            self_
+                .get_l2_to_l1_log_proofs_impl(tx_hash, indices)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
+    fn get_withdrawal_finalization_params(
+        &self,
+        tx_hash: H256,
+        index: Option<usize>,
+    ) -> BoxFuture<Result<Option<WithdrawalFinalizationParams>>> {
+        let self_ = self.clone();
+        Box::pin(async move {
+            self_
+                .get_withdrawal_finalization_params_impl(tx_hash, index)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
+    fn get_l2_to_l1_message_proof(
+        &self,
+        batch: L1BatchNumber,
+        index: u32,
+    ) -> BoxFuture<Result<Option<L2ToL1MessageProof>>> {
+        let self_ = self.clone();
+        Box::pin(async move {
+            self_
+                .get_l2_to_l1_message_proof_impl(batch, index)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
     fn get_l1_batch_number(&self) -> BoxFuture<Result<U64>> {
         let self_ = self.clone();
         Box::pin(async move {
@@ -248,6 +381,35 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceT for ZksNamespa
         })
     }
 
+    fn get_transaction_history(&self, hash: H256) -> BoxFuture<Result<Option<TransactionHistory>>> {
+        let self_ = self.clone();
+        Box::pin(async move {
+            self_
+                .get_transaction_history_impl(hash)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
+    fn get_node_status(&self) -> BoxFuture<Result<NodeStatus>> {
+        let self_ = self.clone();
+        Box::pin(async move { self_.get_node_status_impl().await.map_err(into_jsrpc_error) })
+    }
+
+    fn simulate_bundle(
+        &self,
+        txs: Vec<Bytes>,
+        block: Option<BlockIdVariant>,
+    ) -> BoxFuture<Result<BundleSimulation>> {
+        let self_ = self.clone();
+        Box::pin(async move {
+            self_
+                .simulate_bundle_impl(txs, block.map(Into::into))
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
     fn set_known_bytecode(&self, _bytecode: Bytes) -> BoxFuture<Result<bool>> {
         #[cfg(feature = "openzeppelin_tests")]
         let self_ = self.clone();
@@ -286,6 +448,19 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceT for ZksNamespa
         })
     }
 
+    fn get_batch_resource_usage(
+        &self,
+        batch: L1BatchNumber,
+    ) -> BoxFuture<Result<Option<BatchResourceUsage>>> {
+        let self_ = self.clone();
+        Box::pin(async move {
+            self_
+                .get_batch_resource_usage_impl(batch)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
     fn get_bytecode_by_hash(&self, hash: H256) -> BoxFuture<Result<Option<Vec<u8>>>> {
         let self_ = self.clone();
         Box::pin(async move { Ok(self_.get_bytecode_by_hash_impl(hash).await) })
@@ -295,4 +470,67 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceT for ZksNamespa
         let self_ = self.clone();
         Box::pin(async move { Ok(self_.get_l1_gas_price_impl()) })
     }
+
+    fn get_gas_per_pubdata(&self) -> BoxFuture<Result<U64>> {
+        let self_ = self.clone();
+        Box::pin(async move { Ok(self_.get_gas_per_pubdata_impl()) })
+    }
+
+    fn get_protocol_version(
+        &self,
+        version_id: Option<u16>,
+    ) -> BoxFuture<Result<Option<ProtocolVersion>>> {
+        let self_ = self.clone();
+        Box::pin(async move { Ok(self_.get_protocol_version_impl(version_id).await) })
+    }
+
+    fn get_operator_address(&self) -> BoxFuture<Result<Address>> {
+        let self_ = self.clone();
+        Box::pin(async move { Ok(self_.get_operator_address_impl().await) })
+    }
+
+    fn get_rejection_stats(&self) -> BoxFuture<Result<RejectionStats>> {
+        let self_ = self.clone();
+        Box::pin(async move { Ok(self_.get_rejection_stats_impl()) })
+    }
+
+    fn get_storage_diff(
+        &self,
+        l1_batch_from: L1BatchNumber,
+        l1_batch_to: L1BatchNumber,
+        address: Option<Address>,
+        pagination: Option<StorageDiffPagination>,
+    ) -> BoxFuture<Result<StorageDiff>> {
+        let self_ = self.clone();
+        Box::pin(async move {
+            self_
+                .get_storage_diff_impl(l1_batch_from, l1_batch_to, address, pagination)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
+    fn get_account_summary(&self, address: Address) -> BoxFuture<Result<Option<AccountSummary>>> {
+        let self_ = self.clone();
+        Box::pin(async move {
+            self_
+                .get_account_summary_impl(address)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
+    fn send_raw_transaction_with_deadline(
+        &self,
+        tx_bytes: Bytes,
+        valid_until: U64,
+    ) -> BoxFuture<Result<H256>> {
+        let self_ = self.clone();
+        Box::pin(async move {
+            self_
+                .send_raw_transaction_with_deadline_impl(tx_bytes, valid_until)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
 }
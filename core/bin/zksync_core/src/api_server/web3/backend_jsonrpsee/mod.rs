@@ -24,10 +24,16 @@ pub fn into_jsrpc_error(err: Web3Error) -> ErrorObjectOwned {
             | Web3Error::FilterNotFound
             | Web3Error::InvalidFeeParams(_)
             | Web3Error::InvalidFilterBlockHash
-            | Web3Error::LogsLimitExceeded(_, _, _) => ErrorCode::InvalidParams.code(),
+            | Web3Error::LogsLimitExceeded(_, _, _)
+            | Web3Error::TooManyItemsRequested(_)
+            | Web3Error::InvalidLogDirectives(_)
+            | Web3Error::InvalidBundle(_)
+            | Web3Error::InvalidL1BatchRange(_) => ErrorCode::InvalidParams.code(),
             Web3Error::SubmitTransactionError(_, _) | Web3Error::SerializationError(_) => 3,
             Web3Error::PubSubTimeout => 4,
             Web3Error::RequestTimeout => 5,
+            Web3Error::PrunedHistory(_) => 6,
+            Web3Error::Unauthorized => 7,
         },
         match err {
             Web3Error::SubmitTransactionError(ref message, _) => message.clone(),
@@ -0,0 +1,59 @@
+use zksync_types::{
+    api::{AdmissionPoliciesConfig, ComponentHealth},
+    L1BatchNumber,
+};
+use zksync_web3_decl::{
+    jsonrpsee::core::{async_trait, RpcResult},
+    namespaces::admin::AdminNamespaceServer,
+};
+
+use crate::api_server::web3::{backend_jsonrpsee::into_jsrpc_error, namespaces::AdminNamespace};
+
+#[async_trait]
+impl AdminNamespaceServer for AdminNamespace {
+    fn pause_tx_admission(&self, token: String) -> RpcResult<()> {
+        self.pause_tx_admission_impl(&token)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn resume_tx_admission(&self, token: String) -> RpcResult<()> {
+        self.resume_tx_admission_impl(&token)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn force_seal_batch(&self, token: String) -> RpcResult<()> {
+        self.force_seal_batch_impl(&token).map_err(into_jsrpc_error)
+    }
+
+    fn resync_mempool(&self, token: String) -> RpcResult<()> {
+        self.resync_mempool_impl(&token).map_err(into_jsrpc_error)
+    }
+
+    fn skip_execution_delay(&self, token: String, l1_batch_number: L1BatchNumber) -> RpcResult<()> {
+        self.skip_execution_delay_impl(&token, l1_batch_number)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn set_log_directives(&self, token: String, directives: String) -> RpcResult<()> {
+        self.set_log_directives_impl(&token, &directives)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn get_log_directives(&self, token: String) -> RpcResult<Option<String>> {
+        self.get_log_directives_impl(&token)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn set_admission_policies(
+        &self,
+        token: String,
+        config: AdmissionPoliciesConfig,
+    ) -> RpcResult<()> {
+        self.set_admission_policies_impl(&token, &config)
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn health(&self, token: String) -> RpcResult<Vec<ComponentHealth>> {
+        self.health_impl(&token).await.map_err(into_jsrpc_error)
+    }
+}
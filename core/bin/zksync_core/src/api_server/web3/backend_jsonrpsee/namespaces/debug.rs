@@ -1,7 +1,10 @@
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{
+        BlockId, BlockNumber, DebugCall, RejectedPriorityOp, ResultDebugCall, TracerConfig,
+        TransactionAccessSet,
+    },
     transaction_request::CallRequest,
-    H256,
+    L1BatchNumber, H256,
 };
 use zksync_web3_decl::{
     jsonrpsee::core::{async_trait, RpcResult},
@@ -45,6 +48,17 @@ impl DebugNamespaceServer for DebugNamespace {
         tx_hash: H256,
         options: Option<TracerConfig>,
     ) -> RpcResult<Option<DebugCall>> {
-        Ok(self.debug_trace_transaction_impl(tx_hash, options).await)
+        self.debug_trace_transaction_impl(tx_hash, options)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+    async fn get_l1_batch_access_sets(
+        &self,
+        batch: L1BatchNumber,
+    ) -> RpcResult<Option<Vec<TransactionAccessSet>>> {
+        Ok(self.debug_get_l1_batch_access_sets_impl(batch).await)
+    }
+    async fn get_rejected_priority_ops(&self) -> RpcResult<Vec<RejectedPriorityOp>> {
+        Ok(self.debug_get_rejected_priority_ops_impl().await)
     }
 }
@@ -41,7 +41,13 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> EthNamespaceServer for EthNa
     }
 
     async fn gas_price(&self) -> RpcResult<U256> {
-        self.gas_price_impl().map_err(into_jsrpc_error)
+        self.gas_price_impl().await.map_err(into_jsrpc_error)
+    }
+
+    async fn max_priority_fee_per_gas(&self) -> RpcResult<U256> {
+        self.max_priority_fee_per_gas_impl()
+            .await
+            .map_err(into_jsrpc_error)
     }
 
     async fn new_filter(&self, filter: Filter) -> RpcResult<U256> {
@@ -0,0 +1,10 @@
+use zksync_types::api::TxpoolStatus;
+use zksync_web3_decl::{jsonrpsee::core::RpcResult, namespaces::txpool::TxpoolNamespaceServer};
+
+use crate::api_server::web3::{backend_jsonrpsee::into_jsrpc_error, namespaces::TxpoolNamespace};
+
+impl TxpoolNamespaceServer for TxpoolNamespace {
+    fn status(&self) -> RpcResult<TxpoolStatus> {
+        self.status_impl().map_err(into_jsrpc_error)
+    }
+}
@@ -3,11 +3,16 @@ use bigdecimal::BigDecimal;
 use std::collections::HashMap;
 
 use zksync_types::{
-    api::{BridgeAddresses, L2ToL1LogProof, TransactionDetails, U64},
+    api::{
+        AccountSummary, BalancesFilter, BatchResourceUsage, BlockIdVariant, BridgeAddresses,
+        BundleSimulation, L2ToL1LogProof, L2ToL1MessageProof, NodeStatus, ProtocolVersion,
+        RejectionStats, StorageDiff, StorageDiffPagination, TokenFilter, TransactionDetails,
+        TransactionHistory, WithdrawalFinalizationParams, U64,
+    },
     explorer_api::{BlockDetails, L1BatchDetails},
-    fee::Fee,
+    fee::FeeEstimate,
     transaction_request::CallRequest,
-    Address, L1BatchNumber, MiniblockNumber, H256, U256,
+    Address, Bytes, L1BatchNumber, MiniblockNumber, H256, U256,
 };
 use zksync_web3_decl::{
     jsonrpsee::core::{async_trait, RpcResult},
@@ -22,7 +27,7 @@ use crate::{
 
 #[async_trait]
 impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceServer for ZksNamespace<G> {
-    async fn estimate_fee(&self, req: CallRequest) -> RpcResult<Fee> {
+    async fn estimate_fee(&self, req: CallRequest) -> RpcResult<FeeEstimate> {
         self.estimate_fee_impl(req).await.map_err(into_jsrpc_error)
     }
 
@@ -48,8 +53,13 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceServer for ZksNa
         Ok(self.l1_chain_id_impl())
     }
 
-    async fn get_confirmed_tokens(&self, from: u32, limit: u8) -> RpcResult<Vec<Token>> {
-        self.get_confirmed_tokens_impl(from, limit)
+    async fn get_confirmed_tokens(
+        &self,
+        from: u32,
+        limit: u8,
+        filter: Option<TokenFilter>,
+    ) -> RpcResult<Vec<Token>> {
+        self.get_confirmed_tokens_impl(from, limit, filter)
             .await
             .map_err(into_jsrpc_error)
     }
@@ -63,8 +73,9 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceServer for ZksNa
     async fn get_all_account_balances(
         &self,
         address: Address,
+        filter: Option<BalancesFilter>,
     ) -> RpcResult<HashMap<Address, U256>> {
-        self.get_all_account_balances_impl(address)
+        self.get_all_account_balances_impl(address, filter)
             .await
             .map_err(into_jsrpc_error)
     }
@@ -91,6 +102,36 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceServer for ZksNa
             .map_err(into_jsrpc_error)
     }
 
+    async fn get_l2_to_l1_log_proofs(
+        &self,
+        tx_hash: H256,
+        indices: Vec<usize>,
+    ) -> RpcResult<Vec<Option<L2ToL1LogProof>>> {
+        self.get_l2_to_l1_log_proofs_impl(tx_hash, indices)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_withdrawal_finalization_params(
+        &self,
+        tx_hash: H256,
+        index: Option<usize>,
+    ) -> RpcResult<Option<WithdrawalFinalizationParams>> {
+        self.get_withdrawal_finalization_params_impl(tx_hash, index)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_l2_to_l1_message_proof(
+        &self,
+        batch: L1BatchNumber,
+        index: u32,
+    ) -> RpcResult<Option<L2ToL1MessageProof>> {
+        self.get_l2_to_l1_message_proof_impl(batch, index)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_l1_batch_number(&self) -> RpcResult<U64> {
         self.get_l1_batch_number_impl()
             .await
@@ -118,6 +159,26 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceServer for ZksNa
             .map_err(into_jsrpc_error)
     }
 
+    async fn get_transaction_history(&self, hash: H256) -> RpcResult<Option<TransactionHistory>> {
+        self.get_transaction_history_impl(hash)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_node_status(&self) -> RpcResult<NodeStatus> {
+        self.get_node_status_impl().await.map_err(into_jsrpc_error)
+    }
+
+    async fn simulate_bundle(
+        &self,
+        txs: Vec<Bytes>,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<BundleSimulation> {
+        self.simulate_bundle_impl(txs, block.map(Into::into))
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_raw_block_transactions(
         &self,
         block_number: MiniblockNumber,
@@ -136,6 +197,15 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceServer for ZksNa
             .map_err(into_jsrpc_error)
     }
 
+    async fn get_batch_resource_usage(
+        &self,
+        batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<BatchResourceUsage>> {
+        self.get_batch_resource_usage_impl(batch_number)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_bytecode_by_hash(&self, hash: H256) -> RpcResult<Option<Vec<u8>>> {
         Ok(self.get_bytecode_by_hash_impl(hash).await)
     }
@@ -143,4 +213,51 @@ impl<G: L1GasPriceProvider + Send + Sync + 'static> ZksNamespaceServer for ZksNa
     async fn get_l1_gas_price(&self) -> RpcResult<U64> {
         Ok(self.get_l1_gas_price_impl())
     }
+
+    async fn get_gas_per_pubdata(&self) -> RpcResult<U64> {
+        Ok(self.get_gas_per_pubdata_impl())
+    }
+
+    async fn get_protocol_version(
+        &self,
+        version_id: Option<u16>,
+    ) -> RpcResult<Option<ProtocolVersion>> {
+        Ok(self.get_protocol_version_impl(version_id).await)
+    }
+
+    async fn get_operator_address(&self) -> RpcResult<Address> {
+        Ok(self.get_operator_address_impl().await)
+    }
+
+    async fn get_rejection_stats(&self) -> RpcResult<RejectionStats> {
+        Ok(self.get_rejection_stats_impl())
+    }
+
+    async fn get_storage_diff(
+        &self,
+        l1_batch_from: L1BatchNumber,
+        l1_batch_to: L1BatchNumber,
+        address: Option<Address>,
+        pagination: Option<StorageDiffPagination>,
+    ) -> RpcResult<StorageDiff> {
+        self.get_storage_diff_impl(l1_batch_from, l1_batch_to, address, pagination)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_account_summary(&self, address: Address) -> RpcResult<Option<AccountSummary>> {
+        self.get_account_summary_impl(address)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn send_raw_transaction_with_deadline(
+        &self,
+        tx_bytes: Bytes,
+        valid_until: U64,
+    ) -> RpcResult<H256> {
+        self.send_raw_transaction_with_deadline_impl(tx_bytes, valid_until)
+            .await
+            .map_err(into_jsrpc_error)
+    }
 }
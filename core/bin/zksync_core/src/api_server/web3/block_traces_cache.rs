@@ -0,0 +1,88 @@
+//! Cache of call traces obtained by re-executing miniblocks in the sandbox.
+//!
+//! Traces of sealed miniblocks never change, so once a block is re-executed for `debug_traceBlock*`,
+//! its traces are kept in memory. This makes repeated requests (e.g. from explorers backfilling
+//! historical blocks) cheap. Once the cache is full, the oldest inserted block is evicted.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use zksync_types::{vm_trace::Call, MiniblockNumber};
+
+#[derive(Debug, Default)]
+struct CacheEntries {
+    traces: HashMap<MiniblockNumber, Arc<Vec<Call>>>,
+    insertion_order: VecDeque<MiniblockNumber>,
+}
+
+#[derive(Debug)]
+pub(crate) struct BlockTracesCache {
+    capacity: usize,
+    entries: Mutex<CacheEntries>,
+}
+
+impl BlockTracesCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::default(),
+        }
+    }
+
+    /// Returns the cached traces for the miniblock, if any.
+    pub fn get(&self, block_number: MiniblockNumber) -> Option<Arc<Vec<Call>>> {
+        let entries = self.entries.lock().expect("block traces cache is poisoned");
+        let traces = entries.traces.get(&block_number).cloned();
+
+        metrics::increment_counter!(
+            "api.debug.block_traces_cache",
+            "kind" => if traces.is_some() { "hit" } else { "miss" }
+        );
+        traces
+    }
+
+    pub fn insert(&self, block_number: MiniblockNumber, traces: Arc<Vec<Call>>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.lock().expect("block traces cache is poisoned");
+        if entries.traces.insert(block_number, traces).is_some() {
+            // The block was traced concurrently by another request; the order is already recorded.
+            return;
+        }
+        entries.insertion_order.push_back(block_number);
+        while entries.traces.len() > self.capacity {
+            if let Some(evicted) = entries.insertion_order.pop_front() {
+                entries.traces.remove(&evicted);
+            }
+        }
+        metrics::gauge!(
+            "api.debug.block_traces_cache_size",
+            entries.traces.len() as f64
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oldest_blocks_are_evicted() {
+        let cache = BlockTracesCache::new(2);
+        for number in 1..=3 {
+            cache.insert(MiniblockNumber(number), Arc::new(vec![Call::default()]));
+        }
+
+        assert!(cache.get(MiniblockNumber(1)).is_none());
+        assert!(cache.get(MiniblockNumber(2)).is_some());
+        assert_eq!(cache.get(MiniblockNumber(3)).unwrap().len(), 1);
+
+        // Re-inserting a block doesn't evict anything.
+        cache.insert(MiniblockNumber(3), Arc::new(vec![]));
+        assert!(cache.get(MiniblockNumber(2)).is_some());
+    }
+}
@@ -0,0 +1,140 @@
+//! Cache of `eth_call` results.
+//!
+//! Frontends commonly issue identical view calls many times per second. A call executed at a sealed
+//! miniblock is deterministic, so its result is cached for `ttl`, keyed by the hash of the call request
+//! and the miniblock number. Calls at the pending block are never cached, since the pending state changes
+//! with each executed transaction. Entries aren't invalidated on reverts; the TTL bounds the staleness
+//! of the cached results in this case.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use zksync_types::{
+    transaction_request::CallRequest, web3::signing::keccak256, MiniblockNumber, H256,
+};
+
+/// Key of a cached call result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct CallCacheKey {
+    request_hash: H256,
+    block_number: MiniblockNumber,
+}
+
+impl CallCacheKey {
+    pub fn new(request: &CallRequest, block_number: MiniblockNumber) -> Self {
+        let request_bytes = serde_json::to_vec(request).expect("failed serializing call request");
+        Self {
+            request_hash: H256(keccak256(&request_bytes)),
+            block_number,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    result: Vec<u8>,
+    inserted_at: Instant,
+}
+
+#[derive(Debug, Default)]
+struct CacheEntries {
+    results: HashMap<CallCacheKey, CacheEntry>,
+    insertion_order: VecDeque<CallCacheKey>,
+}
+
+#[derive(Debug)]
+pub(crate) struct CallCache {
+    capacity: usize,
+    ttl: Duration,
+    entries: Mutex<CacheEntries>,
+}
+
+impl CallCache {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            entries: Mutex::default(),
+        }
+    }
+
+    pub fn get(&self, key: &CallCacheKey) -> Option<Vec<u8>> {
+        let entries = self.entries.lock().expect("call cache is poisoned");
+        let (result, outcome) = match entries.results.get(key) {
+            Some(entry) if entry.inserted_at.elapsed() <= self.ttl => {
+                (Some(entry.result.clone()), "hit")
+            }
+            Some(_) => (None, "expired"),
+            None => (None, "miss"),
+        };
+        metrics::increment_counter!("api.web3.call_cache", "outcome" => outcome);
+        result
+    }
+
+    pub fn insert(&self, key: CallCacheKey, result: Vec<u8>) {
+        let mut entries = self.entries.lock().expect("call cache is poisoned");
+        let entry = CacheEntry {
+            result,
+            inserted_at: Instant::now(),
+        };
+        if entries.results.insert(key, entry).is_none() {
+            entries.insertion_order.push_back(key);
+        }
+        while entries.results.len() > self.capacity {
+            match entries.insertion_order.pop_front() {
+                Some(evicted) => {
+                    entries.results.remove(&evicted);
+                }
+                None => break,
+            }
+        }
+        metrics::gauge!("api.web3.call_cache_size", entries.results.len() as f64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{Address, Bytes};
+
+    use super::*;
+
+    fn key(data: u8, block_number: u32) -> CallCacheKey {
+        let request = CallRequest {
+            to: Some(Address::repeat_byte(1)),
+            data: Some(Bytes(vec![data])),
+            ..CallRequest::default()
+        };
+        CallCacheKey::new(&request, MiniblockNumber(block_number))
+    }
+
+    #[test]
+    fn results_are_keyed_by_request_and_block() {
+        let cache = CallCache::new(10, Duration::from_secs(60));
+        cache.insert(key(1, 5), vec![1]);
+        assert_eq!(cache.get(&key(1, 5)), Some(vec![1]));
+        assert_eq!(cache.get(&key(1, 6)), None);
+        assert_eq!(cache.get(&key(2, 5)), None);
+    }
+
+    #[test]
+    fn oldest_results_are_evicted() {
+        let cache = CallCache::new(2, Duration::from_secs(60));
+        for data in 0..3 {
+            cache.insert(key(data, 5), vec![data]);
+        }
+        assert_eq!(cache.get(&key(0, 5)), None);
+        assert_eq!(cache.get(&key(1, 5)), Some(vec![1]));
+        assert_eq!(cache.get(&key(2, 5)), Some(vec![2]));
+    }
+
+    #[test]
+    fn expired_results_are_not_returned() {
+        let cache = CallCache::new(10, Duration::ZERO);
+        cache.insert(key(1, 5), vec![1]);
+        std::thread::sleep(Duration::from_millis(1));
+        assert_eq!(cache.get(&key(1, 5)), None);
+    }
+}
@@ -0,0 +1,144 @@
+//! Cache of Merkle trees built over L2->L1 logs of sealed L1 batches.
+//!
+//! Proving an L2->L1 log requires building the Merkle tree over all logs of the batch. Withdrawal
+//! finalizers usually request proofs for many logs of the same batch, so the tree is built once with
+//! the proofs for all leaves, and is kept in memory. Logs of a sealed batch never change, so entries
+//! don't need to be invalidated. Once the cache is full, the oldest inserted batch is evicted.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use zksync_mini_merkle_tree::MiniMerkleTree;
+use zksync_types::{
+    api::L2ToL1LogProof, commitment::SerializeCommitment, l2_to_l1_log::L2ToL1Log, L1BatchNumber,
+    H256,
+};
+
+/// Number of L1 batches which L2->L1 logs trees are kept in memory.
+const L2_TO_L1_LOGS_CACHE_CAPACITY: usize = 64;
+
+/// L2->L1 logs of an L1 batch together with the Merkle proofs for all of them.
+#[derive(Debug)]
+pub(crate) struct L2ToL1LogsTree {
+    pub logs: Vec<L2ToL1Log>,
+    root: H256,
+    merkle_paths: Vec<Vec<H256>>,
+}
+
+impl L2ToL1LogsTree {
+    pub fn new(logs: Vec<L2ToL1Log>) -> Self {
+        if logs.is_empty() {
+            return Self {
+                logs,
+                root: H256::zero(),
+                merkle_paths: vec![],
+            };
+        }
+
+        let leaves = logs.iter().map(L2ToL1Log::to_bytes);
+        let indices: Vec<_> = (0..logs.len()).collect();
+        let (root, merkle_paths) =
+            MiniMerkleTree::new(leaves, L2ToL1Log::LIMIT_PER_BLOCK).merkle_root_and_paths(&indices);
+        Self {
+            logs,
+            root,
+            merkle_paths,
+        }
+    }
+
+    /// Returns the proof for the log with the specified index in the batch.
+    pub fn proof(&self, l1_log_index: usize) -> Option<L2ToL1LogProof> {
+        let proof = self.merkle_paths.get(l1_log_index)?.clone();
+        Some(L2ToL1LogProof {
+            proof,
+            root: self.root,
+            id: l1_log_index as u32,
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct CacheEntries {
+    trees: HashMap<L1BatchNumber, Arc<L2ToL1LogsTree>>,
+    insertion_order: VecDeque<L1BatchNumber>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct L2ToL1LogsCache {
+    entries: Mutex<CacheEntries>,
+}
+
+impl L2ToL1LogsCache {
+    pub fn get(&self, l1_batch_number: L1BatchNumber) -> Option<Arc<L2ToL1LogsTree>> {
+        let entries = self.entries.lock().expect("L2->L1 logs cache is poisoned");
+        let tree = entries.trees.get(&l1_batch_number).cloned();
+
+        metrics::increment_counter!(
+            "api.web3.l2_to_l1_logs_cache",
+            "kind" => if tree.is_some() { "hit" } else { "miss" }
+        );
+        tree
+    }
+
+    pub fn insert(&self, l1_batch_number: L1BatchNumber, tree: Arc<L2ToL1LogsTree>) {
+        let mut entries = self.entries.lock().expect("L2->L1 logs cache is poisoned");
+        if entries.trees.insert(l1_batch_number, tree).is_some() {
+            return;
+        }
+        entries.insertion_order.push_back(l1_batch_number);
+        while entries.trees.len() > L2_TO_L1_LOGS_CACHE_CAPACITY {
+            if let Some(evicted) = entries.insertion_order.pop_front() {
+                entries.trees.remove(&evicted);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::L1_MESSENGER_ADDRESS;
+
+    fn log(index: u8) -> L2ToL1Log {
+        L2ToL1Log {
+            shard_id: 0,
+            is_service: false,
+            tx_number_in_block: index.into(),
+            sender: L1_MESSENGER_ADDRESS,
+            key: H256::from_low_u64_be(index.into()),
+            value: H256::repeat_byte(index),
+        }
+    }
+
+    #[test]
+    fn cached_proofs_match_computed_ones() {
+        let logs: Vec<_> = (0..10).map(log).collect();
+        let tree = L2ToL1LogsTree::new(logs.clone());
+
+        for index in 0..logs.len() {
+            let (root, proof) = MiniMerkleTree::new(
+                logs.iter().map(L2ToL1Log::to_bytes),
+                L2ToL1Log::LIMIT_PER_BLOCK,
+            )
+            .merkle_root_and_path(index);
+            let cached_proof = tree.proof(index).unwrap();
+            assert_eq!(cached_proof.root, root);
+            assert_eq!(cached_proof.proof, proof);
+            assert_eq!(cached_proof.id, index as u32);
+        }
+        assert!(tree.proof(logs.len()).is_none());
+        assert!(L2ToL1LogsTree::new(vec![]).proof(0).is_none());
+    }
+
+    #[test]
+    fn oldest_batches_are_evicted() {
+        let cache = L2ToL1LogsCache::default();
+        for number in 0..=L2_TO_L1_LOGS_CACHE_CAPACITY as u32 {
+            cache.insert(L1BatchNumber(number), Arc::new(L2ToL1LogsTree::new(vec![])));
+        }
+        assert!(cache.get(L1BatchNumber(0)).is_none());
+        assert!(cache.get(L1BatchNumber(1)).is_some());
+    }
+}
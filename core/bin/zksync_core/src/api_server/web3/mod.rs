@@ -1,15 +1,16 @@
 // Built-in uses
 use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
 // External uses
 use futures::channel::oneshot;
 use futures::FutureExt;
-use jsonrpc_core::IoHandler;
+use jsonrpc_core::MetaIoHandler;
 use jsonrpc_http_server::hyper;
-use jsonrpc_pubsub::PubSubHandler;
+use jsonrpc_pubsub::{PubSubHandler, Session};
 use tokio::sync::{watch, RwLock};
 use tower_http::{cors::CorsLayer, metrics::InFlightRequestsLayer};
 
@@ -23,39 +24,59 @@ use zksync_web3_decl::{
     jsonrpsee::{server::ServerBuilder, RpcModule},
     namespaces::{
         DebugNamespaceServer, EnNamespaceServer, EthNamespaceServer, NetNamespaceServer,
-        Web3NamespaceServer, ZksNamespaceServer,
+        TxpoolNamespaceServer, Web3NamespaceServer, ZksNamespaceServer,
     },
 };
 
 use self::state::InternalApiConfig;
+use crate::admin_controls::AdminControls;
+use crate::l1_batch_status_bus::L1BatchStatusBus;
 use crate::l1_gas_price::L1GasPriceProvider;
+use crate::state_keeper::{MempoolGuard, PendingReceipts};
 use crate::sync_layer::SyncState;
 
 // Local uses
 use super::tx_sender::TxSender;
 use crate::api_server::web3::api_health_check::ApiHealthCheck;
+pub(crate) use admin_server::run_admin_server;
 use backend_jsonrpc::{
+    call_limits::{CallLimitsMiddleware, SessionMetadata},
+    call_metrics::{CallMetricsConfig, CallMetricsMiddleware, HttpCallMetadata},
     error::internal_error,
     namespaces::{
         debug::DebugNamespaceT, en::EnNamespaceT, eth::EthNamespaceT, net::NetNamespaceT,
-        web3::Web3NamespaceT, zks::ZksNamespaceT,
+        txpool::TxpoolNamespaceT, web3::Web3NamespaceT, zks::ZksNamespaceT,
     },
     pub_sub::Web3PubSub,
 };
+pub(crate) use call_cache::CallCache;
 use namespaces::{
-    DebugNamespace, EnNamespace, EthNamespace, EthSubscribe, NetNamespace, Web3Namespace,
-    ZksNamespace,
+    DebugNamespace, EnNamespace, EthNamespace, EthSubscribe, NetNamespace, TxpoolNamespace,
+    Web3Namespace, ZksNamespace,
 };
-use pubsub_notifier::{notify_blocks, notify_logs, notify_txs};
+use pubsub_notifier::{notify_blocks, notify_logs, notify_tx_statuses, notify_txs};
+use request_limits::{
+    ClientIpLogger, ConnectionLimiter, RequestLimiter, RequestLimitsConfig, RequestLimitsLayer,
+};
+pub(crate) use response_cache::ResponseCache;
+pub use response_cache::ResponseCacheConfig;
 use state::{Filters, RpcState};
+use trace_context::TraceContextLayer;
 use zksync_health_check::CheckHealthStatus;
 
+mod admin_server;
 pub mod api_health_check;
 pub mod backend_jsonrpc;
 pub mod backend_jsonrpsee;
+mod block_traces_cache;
+mod call_cache;
+mod l2_to_l1_logs_cache;
 pub mod namespaces;
 mod pubsub_notifier;
+pub mod request_limits;
+mod response_cache;
 pub mod state;
+mod trace_context;
 
 #[derive(Debug, Clone, Copy)]
 enum ApiBackend {
@@ -63,10 +84,12 @@ enum ApiBackend {
     Jsonrpc,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 enum ApiTransport {
     WebSocket(SocketAddr),
     Http(SocketAddr),
+    /// Unix domain socket. Always served by the `jsonrpc` backend since `jsonrpsee` doesn't support IPC.
+    Ipc(PathBuf),
 }
 
 #[derive(Debug)]
@@ -74,7 +97,7 @@ pub struct ApiBuilder<G> {
     backend: ApiBackend,
     pool: ConnectionPool,
     config: InternalApiConfig,
-    transport: Option<ApiTransport>,
+    transports: Vec<ApiTransport>,
     tx_sender: Option<TxSender<G>>,
     filters_limit: Option<usize>,
     subscriptions_limit: Option<usize>,
@@ -84,13 +107,22 @@ pub struct ApiBuilder<G> {
     polling_interval: Option<Duration>,
     accounts: HashMap<Address, PrivateKeySigner>,
     debug_namespace_config: Option<(BaseSystemContractsHashes, u64, Option<usize>)>,
+    request_limits: Option<RequestLimitsConfig>,
+    call_metrics: CallMetricsConfig,
+    response_cache_config: Option<ResponseCacheConfig>,
+    response_cache: Option<Arc<ResponseCache>>,
+    call_cache: Option<Arc<CallCache>>,
+    mempool: Option<MempoolGuard>,
+    pending_receipts: Option<PendingReceipts>,
+    l1_batch_status_bus: Option<L1BatchStatusBus>,
+    admin_controls: Option<AdminControls>,
 }
 
 impl<G> ApiBuilder<G> {
     pub fn jsonrpsee_backend(config: InternalApiConfig, pool: ConnectionPool) -> Self {
         Self {
             backend: ApiBackend::Jsonrpsee,
-            transport: None,
+            transports: vec![],
             pool,
             sync_state: None,
             tx_sender: None,
@@ -100,6 +132,15 @@ impl<G> ApiBuilder<G> {
             vm_concurrency_limit: None,
             polling_interval: None,
             debug_namespace_config: None,
+            request_limits: None,
+            call_metrics: CallMetricsConfig::default(),
+            response_cache_config: None,
+            response_cache: None,
+            call_cache: None,
+            mempool: None,
+            pending_receipts: None,
+            l1_batch_status_bus: None,
+            admin_controls: None,
             accounts: Default::default(),
             config,
         }
@@ -108,7 +149,7 @@ impl<G> ApiBuilder<G> {
     pub fn jsonrpc_backend(config: InternalApiConfig, pool: ConnectionPool) -> Self {
         Self {
             backend: ApiBackend::Jsonrpc,
-            transport: None,
+            transports: vec![],
             pool,
             sync_state: None,
             tx_sender: None,
@@ -118,18 +159,40 @@ impl<G> ApiBuilder<G> {
             vm_concurrency_limit: None,
             polling_interval: None,
             debug_namespace_config: None,
+            request_limits: None,
+            call_metrics: CallMetricsConfig::default(),
+            response_cache_config: None,
+            response_cache: None,
+            call_cache: None,
+            mempool: None,
+            pending_receipts: None,
+            l1_batch_status_bus: None,
+            admin_controls: None,
             accounts: Default::default(),
             config,
         }
     }
 
+    /// Serves the API over WebSocket on the specified port. Transports can be combined; in this case,
+    /// all servers share the RPC state (including caches), and the `jsonrpc` WebSocket and IPC servers
+    /// share the runtime and subscription notifiers.
     pub fn ws(mut self, port: u16) -> Self {
-        self.transport = Some(ApiTransport::WebSocket(([0, 0, 0, 0], port).into()));
+        self.transports
+            .push(ApiTransport::WebSocket(([0, 0, 0, 0], port).into()));
         self
     }
 
+    /// Serves the API over HTTP on the specified port. Can be combined with other transports.
     pub fn http(mut self, port: u16) -> Self {
-        self.transport = Some(ApiTransport::Http(([0, 0, 0, 0], port).into()));
+        self.transports
+            .push(ApiTransport::Http(([0, 0, 0, 0], port).into()));
+        self
+    }
+
+    /// Serves the API over IPC using a Unix domain socket at the specified path. Can be combined
+    /// with other transports.
+    pub fn ipc(mut self, path: impl Into<PathBuf>) -> Self {
+        self.transports.push(ApiTransport::Ipc(path.into()));
         self
     }
 
@@ -168,6 +231,43 @@ impl<G> ApiBuilder<G> {
         self
     }
 
+    pub fn with_request_limits(mut self, request_limits: RequestLimitsConfig) -> Self {
+        self.request_limits = Some(request_limits);
+        self
+    }
+
+    /// Configures logging of slow calls. Only affects the `jsonrpc` backend.
+    pub fn with_call_metrics(mut self, call_metrics: CallMetricsConfig) -> Self {
+        self.call_metrics = call_metrics;
+        self
+    }
+
+    /// Enables caching of responses concerning immutable data (old blocks, transactions, etc.).
+    pub fn with_response_cache(mut self, config: ResponseCacheConfig) -> Self {
+        self.response_cache_config = Some(config);
+        self
+    }
+
+    /// Uses a response cache shared with other API servers. Unlike [`Self::with_response_cache()`],
+    /// the caller is responsible for running [`ResponseCache::run_watermark_updates()`] for the cache.
+    pub(crate) fn with_shared_response_cache(mut self, response_cache: Arc<ResponseCache>) -> Self {
+        self.response_cache = Some(response_cache);
+        self
+    }
+
+    /// Enables caching of `eth_call` results for calls at sealed miniblocks. The cache may be shared
+    /// with other API servers.
+    pub(crate) fn with_call_cache(mut self, call_cache: Arc<CallCache>) -> Self {
+        self.call_cache = Some(call_cache);
+        self
+    }
+
+    /// Enables the `txpool` namespace backed by the provided mempool.
+    pub fn with_mempool(mut self, mempool: MempoolGuard) -> Self {
+        self.mempool = Some(mempool);
+        self
+    }
+
     pub fn enable_debug_namespace(
         mut self,
         base_system_contract_hashes: BaseSystemContractsHashes,
@@ -182,6 +282,27 @@ impl<G> ApiBuilder<G> {
         self
     }
 
+    /// Makes `eth_getTransactionReceipt` return provisional receipts for transactions executed
+    /// by the state keeper, but not yet persisted.
+    pub fn with_pending_receipts(mut self, pending_receipts: PendingReceipts) -> Self {
+        self.pending_receipts = Some(pending_receipts);
+        self
+    }
+
+    /// Makes `zks_subscribe("txStatus")` notifications rely on L1 batch status updates from the provided bus,
+    /// reducing the number of Postgres polls.
+    pub fn with_l1_batch_status_bus(mut self, bus: L1BatchStatusBus) -> Self {
+        self.l1_batch_status_bus = Some(bus);
+        self
+    }
+
+    /// Provides controls of the components running in the same process, so that their health
+    /// is reported by `zks_getNodeStatus`.
+    pub fn with_admin_controls(mut self, controls: AdminControls) -> Self {
+        self.admin_controls = Some(controls);
+        self
+    }
+
     pub fn enable_oz_tests(mut self, account_pks: Vec<H256>) -> Self {
         if cfg!(feature = "openzeppelin_tests") {
             self.accounts = account_pks
@@ -211,6 +332,13 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
             sync_state: self.sync_state.clone(),
             api_config: self.config.clone(),
             accounts: self.accounts.clone(),
+            l2_to_l1_logs_cache: Arc::default(),
+            response_cache: self.response_cache.clone(),
+            call_cache: self.call_cache.clone(),
+            pending_receipts: self.pending_receipts.clone(),
+            mempool: self.mempool.clone(),
+            admin_controls: self.admin_controls.clone(),
+            protected_data_access: true,
             #[cfg(feature = "openzeppelin_tests")]
             known_bytecodes: Arc::new(RwLock::new(Default::default())),
         }
@@ -221,11 +349,12 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
         let rpc_app = self.build_rpc_state();
 
         // Declare namespaces we have.
-        let eth = EthNamespace::new(rpc_app.clone());
+        let eth = EthNamespace::new(rpc_app.for_namespace("eth"));
         let net = NetNamespace::new(zksync_network_id);
         let web3 = Web3Namespace;
-        let zks = ZksNamespace::new(rpc_app.clone());
-        let en = EnNamespace::new(rpc_app.clone());
+        let zks = ZksNamespace::new(rpc_app.for_namespace("zks"));
+        let en = EnNamespace::new(rpc_app.for_namespace("en"));
+        let txpool = TxpoolNamespace::new(self.mempool.clone());
 
         // Collect all the methods into a single RPC module.
         let mut rpc: RpcModule<_> = eth.into_rpc();
@@ -236,6 +365,8 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
         rpc.merge(zks.into_rpc())
             .expect("Can't merge zks namespace");
         rpc.merge(en.into_rpc()).expect("Can't merge en namespace");
+        rpc.merge(txpool.into_rpc())
+            .expect("Can't merge txpool namespace");
 
         if let Some((hashes, fair_l2_gas_price, cache_misses_limit)) = self.debug_namespace_config {
             rpc.merge(
@@ -244,8 +375,10 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
                     hashes,
                     fair_l2_gas_price,
                     cache_misses_limit,
+                    rpc_app.tx_sender.0.sender_config.output_limits,
                     rpc_app.tx_sender.0.vm_concurrency_limiter.clone(),
                     rpc_app.tx_sender.0.factory_deps_cache.clone(),
+                    rpc_app.tx_sender.0.bytecode_cache.clone(),
                 )
                 .await
                 .into_rpc(),
@@ -259,86 +392,108 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
         mut self,
         stop_receiver: watch::Receiver<bool>,
     ) -> (Vec<tokio::task::JoinHandle<()>>, ApiHealthCheck) {
+        assert!(!self.transports.is_empty(), "ApiTransport is not specified");
         if self.filters_limit.is_none() {
             vlog::warn!("Filters limit is not set - unlimited filters are allowed");
         }
-        match (&self.transport, self.subscriptions_limit) {
-            (Some(ApiTransport::WebSocket(_)), None) => {
+        let has_ws_transport = self
+            .transports
+            .iter()
+            .any(|transport| matches!(transport, ApiTransport::WebSocket(_)));
+        match (has_ws_transport, self.subscriptions_limit) {
+            (true, None) => {
                 vlog::warn!(
                     "`subscriptions_limit` is not set - unlimited subscriptions are allowed"
                 );
             }
-            (Some(ApiTransport::Http(_)), Some(_)) => {
+            (false, Some(_)) => {
                 vlog::warn!(
-                    "`subscriptions_limit` is ignored for HTTP transport, use WebSocket instead"
+                    "`subscriptions_limit` is only applied to WebSocket transport, which is not enabled"
                 );
             }
             _ => {}
         }
 
-        match (self.backend, self.transport.take()) {
-            (ApiBackend::Jsonrpc, Some(ApiTransport::Http(addr))) => {
-                let (api_health_check, status_sender) = self.create_health_check();
-                (
-                    vec![
+        let mut tasks = vec![];
+        let response_cache_config = self
+            .response_cache_config
+            .filter(|config| config.capacity > 0);
+        if let (None, Some(config)) = (&self.response_cache, response_cache_config) {
+            let response_cache = Arc::new(ResponseCache::new(config));
+            tasks.push(tokio::spawn(
+                response_cache
+                    .clone()
+                    .run_watermark_updates(self.pool.clone(), stop_receiver.clone()),
+            ));
+            self.response_cache = Some(response_cache);
+        }
+
+        let mut health_receivers = vec![];
+        let mut pub_sub_transports = vec![];
+        for transport in std::mem::take(&mut self.transports) {
+            let (status_sender, status_receiver) =
+                watch::channel(CheckHealthStatus::NotReady("Api is not ready".into()));
+            health_receivers.push(status_receiver);
+
+            let stop_receiver = stop_receiver.clone();
+            match (self.backend, transport) {
+                (ApiBackend::Jsonrpc, ApiTransport::Http(addr)) => {
+                    tasks.push(
                         self.build_jsonrpc_http(addr, stop_receiver, status_sender)
                             .await,
-                    ],
-                    api_health_check,
-                )
-            }
-            (ApiBackend::Jsonrpc, Some(ApiTransport::WebSocket(addr))) => {
-                let (api_health_check, status_sender) = self.create_health_check();
-                (
-                    self.build_jsonrpc_ws(addr, stop_receiver, status_sender),
-                    api_health_check,
-                )
-            }
-            (ApiBackend::Jsonrpsee, Some(ApiTransport::Http(addr))) => {
-                let (api_health_check, status_sender) = self.create_health_check();
-                (
-                    vec![
+                    );
+                }
+                (ApiBackend::Jsonrpsee, ApiTransport::Http(addr)) => {
+                    tasks.push(
                         self.build_jsonrpsee_http(addr, stop_receiver, status_sender)
                             .await,
-                    ],
-                    api_health_check,
-                )
-            }
-            (ApiBackend::Jsonrpsee, Some(ApiTransport::WebSocket(addr))) => {
-                let (api_health_check, status_sender) = self.create_health_check();
-                (
-                    vec![
+                    );
+                }
+                (ApiBackend::Jsonrpsee, ApiTransport::WebSocket(addr)) => {
+                    tasks.push(
                         self.build_jsonrpsee_ws(addr, stop_receiver, status_sender)
                             .await,
-                    ],
-                    api_health_check,
-                )
+                    );
+                }
+                (ApiBackend::Jsonrpc, transport @ ApiTransport::WebSocket(_))
+                | (_, transport @ ApiTransport::Ipc(_)) => {
+                    pub_sub_transports.push((transport, status_sender));
+                }
             }
-            (_, None) => panic!("ApiTransport is not specified"),
         }
-    }
-
-    fn create_health_check(&self) -> (ApiHealthCheck, watch::Sender<CheckHealthStatus>) {
-        let (status_sender, receiver) =
-            watch::channel(CheckHealthStatus::NotReady("Api is not ready".into()));
-        (ApiHealthCheck::new(receiver), status_sender)
+        if !pub_sub_transports.is_empty() {
+            tasks.extend(self.build_jsonrpc_pub_sub(pub_sub_transports, stop_receiver));
+        }
+        (tasks, ApiHealthCheck::new(health_receivers))
     }
 
     async fn build_jsonrpc_http(
-        self,
+        &self,
         addr: SocketAddr,
         mut stop_receiver: watch::Receiver<bool>,
         api_health_check: watch::Sender<CheckHealthStatus>,
     ) -> tokio::task::JoinHandle<()> {
+        if let Some(request_limits) = &self.request_limits {
+            assert!(
+                !request_limits.has_rate_limits(),
+                "Rate limits cannot be enforced by the `jsonrpc` HTTP server since it doesn't expose \
+                 peer addresses; use the `jsonrpsee` backend or disable rate limiting"
+            );
+        }
+
         let io_handler = {
             let zksync_network_id = self.config.l2_chain_id;
             let rpc_state = self.build_rpc_state();
-            let mut io = IoHandler::new();
-            io.extend_with(EthNamespace::new(rpc_state.clone()).to_delegate());
-            io.extend_with(ZksNamespace::new(rpc_state.clone()).to_delegate());
-            io.extend_with(EnNamespace::new(rpc_state.clone()).to_delegate());
+            let mut io = MetaIoHandler::with_middleware((
+                CallMetricsMiddleware::new(self.call_metrics),
+                CallLimitsMiddleware::new(self.request_limits.as_ref()),
+            ));
+            io.extend_with(EthNamespace::new(rpc_state.for_namespace("eth")).to_delegate());
+            io.extend_with(ZksNamespace::new(rpc_state.for_namespace("zks")).to_delegate());
+            io.extend_with(EnNamespace::new(rpc_state.for_namespace("en")).to_delegate());
             io.extend_with(Web3Namespace.to_delegate());
             io.extend_with(NetNamespace::new(zksync_network_id).to_delegate());
+            io.extend_with(TxpoolNamespace::new(self.mempool.clone()).to_delegate());
             if let Some((hashes, fair_l2_gas_price, cache_misses_limit)) =
                 self.debug_namespace_config
             {
@@ -348,8 +503,10 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
                         hashes,
                         fair_l2_gas_price,
                         cache_misses_limit,
+                        rpc_state.tx_sender.0.sender_config.output_limits,
                         rpc_state.tx_sender.0.vm_concurrency_limiter.clone(),
                         rpc_state.tx_sender.0.factory_deps_cache.clone(),
+                        rpc_state.tx_sender.0.bytecode_cache.clone(),
                     )
                     .await
                     .to_delegate(),
@@ -359,19 +516,27 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
             io
         };
 
+        let threads = self.threads.unwrap();
+        let request_limits = self.request_limits.clone();
         let (sender, recv) = oneshot::channel::<()>();
         std::thread::spawn(move || {
             let runtime = tokio::runtime::Builder::new_multi_thread()
                 .enable_all()
-                .worker_threads(self.threads.unwrap())
+                .worker_threads(threads)
                 .build()
                 .unwrap();
 
-            let server = jsonrpc_http_server::ServerBuilder::new(io_handler)
-                .threads(1)
-                .event_loop_executor(runtime.handle().clone())
-                .start_http(&addr)
-                .unwrap();
+            let mut server_builder = jsonrpc_http_server::ServerBuilder::with_meta_extractor(
+                io_handler,
+                HttpCallMetadata::from_request,
+            )
+            .threads(1)
+            .event_loop_executor(runtime.handle().clone());
+            if let Some(request_limits) = request_limits {
+                server_builder = server_builder
+                    .max_request_body_size(request_limits.max_request_body_size as usize);
+            }
+            let server = server_builder.start_http(&addr).unwrap();
 
             let close_handler = server.close_handle();
             std::thread::spawn(move || {
@@ -392,19 +557,21 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
     }
 
     async fn build_jsonrpsee_http(
-        self,
+        &self,
         addr: SocketAddr,
         mut stop_receiver: watch::Receiver<bool>,
         api_health_check: watch::Sender<CheckHealthStatus>,
     ) -> tokio::task::JoinHandle<()> {
         let rpc = self.build_rpc_module().await;
+        let threads = self.threads.unwrap();
+        let request_limits_config = self.request_limits.clone();
 
         // Start the server in a separate tokio runtime from a dedicated thread.
         let (sender, recv) = oneshot::channel::<()>();
         std::thread::spawn(move || {
             let runtime = tokio::runtime::Builder::new_multi_thread()
                 .enable_all()
-                .worker_threads(self.threads.unwrap())
+                .worker_threads(threads)
                 .build()
                 .unwrap();
 
@@ -422,15 +589,29 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
                 metrics::histogram!("api.web3.in_flight_requests", count as f64, "scheme" => "http");
             }));
 
+            // Setup per-client request limits.
+            let client_ip_logger = ClientIpLogger::new(request_limits_config.as_ref());
+            let request_limits = request_limits_config.clone().map(|config| {
+                let limiter = Arc::new(RequestLimiter::new(config));
+                runtime.spawn(retain_recent_clients(limiter.clone()));
+                limiter
+            });
+
             // Prepare middleware.
             let middleware = tower::ServiceBuilder::new()
                 .layer(in_flight_requests_layer)
-                .layer(cors);
+                .layer(cors)
+                .layer(TraceContextLayer)
+                .option_layer(request_limits.map(RequestLimitsLayer::new));
 
             runtime.block_on(async move {
-                let server = ServerBuilder::default()
-                    .http_only()
-                    .max_connections(5000)
+                let mut server_builder = ServerBuilder::default().http_only().max_connections(5000);
+                if let Some(config) = request_limits_config {
+                    server_builder =
+                        server_builder.max_request_body_size(config.max_request_body_size);
+                }
+                let server = server_builder
+                    .set_logger(client_ip_logger)
                     .set_middleware(middleware)
                     .build(addr)
                     .await
@@ -462,7 +643,7 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
     }
 
     async fn build_jsonrpsee_ws(
-        self,
+        &self,
         addr: SocketAddr,
         mut stop_receiver: watch::Receiver<bool>,
         api_health_check: watch::Sender<CheckHealthStatus>,
@@ -471,14 +652,22 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
             "`eth_subscribe` is not implemented for jsonrpsee backend, use jsonrpc instead"
         );
 
+        // Calls made via WebSocket connections don't pass through the middleware, so neither the batch size
+        // nor the rate limits can be enforced.
+        assert!(
+            self.request_limits.is_none(),
+            "Request limits cannot be enforced by the `jsonrpsee` WS server; use the `jsonrpc` backend instead"
+        );
+
         let rpc = self.build_rpc_module().await;
+        let threads = self.threads.unwrap();
 
         // Start the server in a separate tokio runtime from a dedicated thread.
         let (sender, recv) = oneshot::channel::<()>();
         std::thread::spawn(move || {
             let runtime = tokio::runtime::Builder::new_multi_thread()
                 .enable_all()
-                .worker_threads(self.threads.unwrap())
+                .worker_threads(threads)
                 .build()
                 .unwrap();
 
@@ -511,11 +700,32 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
         tokio::spawn(recv.map(drop))
     }
 
-    fn build_jsonrpc_ws(
-        self,
-        addr: SocketAddr,
-        mut stop_receiver: watch::Receiver<bool>,
-        api_health_check: watch::Sender<CheckHealthStatus>,
+    fn build_jsonrpc_pub_sub_io(
+        &self,
+        pub_sub: EthSubscribe,
+    ) -> PubSubHandler<SessionMetadata, (CallMetricsMiddleware, CallLimitsMiddleware)> {
+        let zksync_network_id = self.config.l2_chain_id;
+        let rpc_state = self.build_rpc_state();
+        let mut io = PubSubHandler::new(MetaIoHandler::with_middleware((
+            CallMetricsMiddleware::new(self.call_metrics),
+            CallLimitsMiddleware::new(self.request_limits.as_ref()),
+        )));
+        io.extend_with(pub_sub.to_delegate());
+        io.extend_with(EthNamespace::new(rpc_state.for_namespace("eth")).to_delegate());
+        io.extend_with(ZksNamespace::new(rpc_state.for_namespace("zks")).to_delegate());
+        io.extend_with(EnNamespace::new(rpc_state.for_namespace("en")).to_delegate());
+        io.extend_with(Web3Namespace.to_delegate());
+        io.extend_with(NetNamespace::new(zksync_network_id).to_delegate());
+        io.extend_with(TxpoolNamespace::new(self.mempool.clone()).to_delegate());
+        io
+    }
+
+    /// Builds `jsonrpc` servers supporting subscriptions (i.e., WebSocket and IPC ones). The servers
+    /// share a runtime and subscription notifiers.
+    fn build_jsonrpc_pub_sub(
+        &self,
+        transports: Vec<(ApiTransport, watch::Sender<CheckHealthStatus>)>,
+        stop_receiver: watch::Receiver<bool>,
     ) -> Vec<tokio::task::JoinHandle<()>> {
         let jsonrpc_runtime = tokio::runtime::Builder::new_multi_thread()
             .enable_all()
@@ -543,48 +753,90 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
                 pub_sub.active_log_subs.clone(),
                 self.pool.clone(),
                 polling_interval,
+                // Log subscriptions are a part of the `eth` namespace.
+                self.config.has_protected_data_access("eth"),
+                stop_receiver.clone(),
+            )),
+            tokio::spawn(notify_tx_statuses(
+                pub_sub.active_tx_status_subs.clone(),
+                self.pool.clone(),
+                polling_interval,
+                self.l1_batch_status_bus
+                    .as_ref()
+                    .map(L1BatchStatusBus::subscribe),
                 stop_receiver.clone(),
             )),
         ];
 
-        let (sender, recv) = oneshot::channel::<()>();
-        let io = {
-            let zksync_network_id = self.config.l2_chain_id;
-            let rpc_state = self.build_rpc_state();
-            let mut io = PubSubHandler::default();
-            io.extend_with(pub_sub.to_delegate());
-            io.extend_with(EthNamespace::new(rpc_state.clone()).to_delegate());
-            io.extend_with(ZksNamespace::new(rpc_state.clone()).to_delegate());
-            io.extend_with(EnNamespace::new(rpc_state).to_delegate());
-            io.extend_with(Web3Namespace.to_delegate());
-            io.extend_with(NetNamespace::new(zksync_network_id).to_delegate());
-            io
-        };
+        let servers: Vec<_> = transports
+            .into_iter()
+            .map(|(transport, api_health_check)| {
+                let io = self.build_jsonrpc_pub_sub_io(pub_sub.clone());
+                (transport, io, api_health_check)
+            })
+            .collect();
+        let subscriptions_limit = self.subscriptions_limit;
+        let request_limits = self.request_limits.clone();
 
+        let (sender, recv) = oneshot::channel::<()>();
         std::thread::spawn(move || {
-            let server = jsonrpc_ws_server::ServerBuilder::with_meta_extractor(
-                io,
-                |context: &jsonrpc_ws_server::RequestContext| {
-                    Arc::new(jsonrpc_pubsub::Session::new(context.sender()))
-                },
-            )
-            .event_loop_executor(jsonrpc_runtime.handle().clone())
-            .max_connections(self.subscriptions_limit.unwrap_or(usize::MAX))
-            .session_stats(TrackOpenWsConnections)
-            .start(&addr)
-            .unwrap();
-            let close_handler = server.close_handle();
-
-            std::thread::spawn(move || {
-                let stop_signal = futures::executor::block_on(stop_receiver.changed());
-                if stop_signal.is_ok() {
-                    close_handler.close();
-                    vlog::info!("Stop signal received, WS JSON RPC API is shutting down");
+            let mut servers_to_wait: Vec<Box<dyn FnOnce()>> = vec![];
+            for (transport, io, api_health_check) in servers {
+                let runtime_handle = jsonrpc_runtime.handle().clone();
+                match transport {
+                    ApiTransport::WebSocket(addr) => {
+                        let connection_limits = request_limits.clone();
+                        let mut server_builder =
+                            jsonrpc_ws_server::ServerBuilder::with_meta_extractor(
+                                io,
+                                move |context: &jsonrpc_ws_server::RequestContext| {
+                                    let limiter =
+                                        connection_limits.as_ref().map(ConnectionLimiter::new);
+                                    SessionMetadata::new(Session::new(context.sender()), limiter)
+                                },
+                            )
+                            .event_loop_executor(runtime_handle)
+                            .max_connections(subscriptions_limit.unwrap_or(usize::MAX))
+                            .session_stats(TrackOpenWsConnections);
+                        if let Some(request_limits) = &request_limits {
+                            server_builder = server_builder
+                                .max_payload(request_limits.max_request_body_size as usize);
+                        }
+                        let server = server_builder.start(&addr).unwrap();
+                        let close_handler = server.close_handle();
+                        close_on_stop_signal(stop_receiver.clone(), "WS", move || {
+                            close_handler.close();
+                        });
+                        servers_to_wait.push(Box::new(move || server.wait().unwrap()));
+                    }
+                    ApiTransport::Ipc(path) => {
+                        let server = jsonrpc_ipc_server::ServerBuilder::with_meta_extractor(
+                            io,
+                            |context: &jsonrpc_ipc_server::RequestContext| {
+                                SessionMetadata::new(Session::new(context.sender.clone()), None)
+                            },
+                        )
+                        .event_loop_executor(runtime_handle)
+                        .start(&path.to_string_lossy())
+                        .unwrap_or_else(|err| {
+                            panic!("Can't start the IPC JSON RPC server at {path:?}: {err}")
+                        });
+                        let close_handler = server.close_handle();
+                        close_on_stop_signal(stop_receiver.clone(), "IPC", move || {
+                            close_handler.close();
+                        });
+                        servers_to_wait.push(Box::new(move || server.wait()));
+                    }
+                    ApiTransport::Http(_) => {
+                        unreachable!("HTTP transport doesn't support subscriptions")
+                    }
                 }
-            });
+                api_health_check.send(CheckHealthStatus::Ready).unwrap();
+            }
 
-            api_health_check.send(CheckHealthStatus::Ready).unwrap();
-            server.wait().unwrap();
+            for wait_for_server in servers_to_wait {
+                wait_for_server();
+            }
             jsonrpc_runtime.shutdown_timeout(Duration::from_secs(10));
             let _ = sender;
         });
@@ -594,6 +846,31 @@ impl<G: 'static + Send + Sync + L1GasPriceProvider> ApiBuilder<G> {
     }
 }
 
+/// Closes a `jsonrpc` server once the stop signal is received.
+fn close_on_stop_signal(
+    mut stop_receiver: watch::Receiver<bool>,
+    transport_name: &'static str,
+    close: impl FnOnce() + Send + 'static,
+) {
+    std::thread::spawn(move || {
+        let stop_signal = futures::executor::block_on(stop_receiver.changed());
+        if stop_signal.is_ok() {
+            close();
+            vlog::info!("Stop signal received, {transport_name} JSON RPC API is shutting down");
+        }
+    });
+}
+
+/// Periodically removes the rate limiting state for the inactive clients,
+/// so that the limiter memory doesn't grow unboundedly.
+async fn retain_recent_clients(limiter: Arc<RequestLimiter>) {
+    let mut interval = tokio::time::interval(Duration::from_secs(60));
+    loop {
+        interval.tick().await;
+        limiter.retain_recent();
+    }
+}
+
 struct TrackOpenWsConnections;
 
 impl jsonrpc_ws_server::SessionStats for TrackOpenWsConnections {
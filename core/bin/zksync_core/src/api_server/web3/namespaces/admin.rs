@@ -0,0 +1,167 @@
+use zksync_types::{
+    api::{AdmissionPoliciesConfig, ComponentHealth},
+    L1BatchNumber,
+};
+use zksync_utils::constant_time_eq;
+use zksync_web3_decl::error::Web3Error;
+
+use crate::{
+    admin_controls::AdminControls, api_server::tx_sender::AdmissionPolicyChain,
+    state_keeper::MempoolGuard,
+};
+
+/// `admin` namespace allowing node operators to control the node without accessing the machine it runs on.
+/// The namespace is only served by the internal admin server bound to `admin_api_port`; each method
+/// is additionally authenticated with the token from the API config.
+#[derive(Debug, Clone)]
+pub struct AdminNamespace {
+    controls: AdminControls,
+    mempool: Option<MempoolGuard>,
+    admission_policies: Option<AdmissionPolicyChain>,
+    token: String,
+}
+
+impl AdminNamespace {
+    pub fn new(
+        controls: AdminControls,
+        mempool: Option<MempoolGuard>,
+        admission_policies: Option<AdmissionPolicyChain>,
+        token: String,
+    ) -> Self {
+        Self {
+            controls,
+            mempool,
+            admission_policies,
+            token,
+        }
+    }
+
+    /// Compares the provided token with the expected one in constant time.
+    fn authorize(&self, token: &str) -> Result<(), Web3Error> {
+        if constant_time_eq(self.token.as_bytes(), token.as_bytes()) {
+            Ok(())
+        } else {
+            Err(Web3Error::Unauthorized)
+        }
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub fn pause_tx_admission_impl(&self, token: &str) -> Result<(), Web3Error> {
+        self.authorize(token)?;
+        self.controls.pause_tx_admission();
+        vlog::warn!("Transaction admission was paused by the operator");
+        Ok(())
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub fn resume_tx_admission_impl(&self, token: &str) -> Result<(), Web3Error> {
+        self.authorize(token)?;
+        self.controls.resume_tx_admission();
+        vlog::info!("Transaction admission was resumed by the operator");
+        Ok(())
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub fn force_seal_batch_impl(&self, token: &str) -> Result<(), Web3Error> {
+        self.authorize(token)?;
+        self.controls.request_l1_batch_seal();
+        vlog::info!("L1 batch seal was requested by the operator");
+        Ok(())
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub fn resync_mempool_impl(&self, token: &str) -> Result<(), Web3Error> {
+        self.authorize(token)?;
+        let mempool = self.mempool.as_ref().ok_or(Web3Error::NotImplemented)?;
+        mempool.request_resync();
+        vlog::info!("Mempool resync was requested by the operator");
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, token))]
+    pub fn skip_execution_delay_impl(
+        &self,
+        token: &str,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<(), Web3Error> {
+        self.authorize(token)?;
+        self.controls.skip_execution_delay(l1_batch_number);
+        vlog::warn!(
+            "Execution delay was skipped by the operator for L1 batches up to #{l1_batch_number}"
+        );
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, token))]
+    pub fn set_log_directives_impl(&self, token: &str, directives: &str) -> Result<(), Web3Error> {
+        self.authorize(token)?;
+        vlog::set_log_directives(directives).map_err(Web3Error::InvalidLogDirectives)?;
+        vlog::info!("Log directives were changed by the operator to `{directives}`");
+        Ok(())
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub fn get_log_directives_impl(&self, token: &str) -> Result<Option<String>, Web3Error> {
+        self.authorize(token)?;
+        Ok(vlog::log_directives())
+    }
+
+    #[tracing::instrument(skip(self, token))]
+    pub fn set_admission_policies_impl(
+        &self,
+        token: &str,
+        config: &AdmissionPoliciesConfig,
+    ) -> Result<(), Web3Error> {
+        self.authorize(token)?;
+        let admission_policies = self
+            .admission_policies
+            .as_ref()
+            .ok_or(Web3Error::NotImplemented)?;
+        admission_policies.configure_built_in(config);
+        vlog::info!("Admission policies were changed by the operator to {config:?}");
+        Ok(())
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn health_impl(&self, token: &str) -> Result<Vec<ComponentHealth>, Web3Error> {
+        self.authorize(token)?;
+        Ok(self.controls.component_health().await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn admin_methods_require_token() {
+        let controls = AdminControls::default();
+        let namespace = AdminNamespace::new(controls.clone(), None, None, "secret".to_owned());
+
+        for token in ["", "secre", "secret1", "public"] {
+            assert!(matches!(
+                namespace.pause_tx_admission_impl(token),
+                Err(Web3Error::Unauthorized)
+            ));
+        }
+        assert!(!controls.is_tx_admission_paused());
+
+        namespace.pause_tx_admission_impl("secret").unwrap();
+        assert!(controls.is_tx_admission_paused());
+        namespace.resume_tx_admission_impl("secret").unwrap();
+        assert!(!controls.is_tx_admission_paused());
+
+        assert!(matches!(
+            namespace.get_log_directives_impl("public"),
+            Err(Web3Error::Unauthorized)
+        ));
+        assert!(matches!(
+            namespace.set_log_directives_impl("public", "debug"),
+            Err(Web3Error::Unauthorized)
+        ));
+        assert!(matches!(
+            namespace.resync_mempool_impl("secret"),
+            Err(Web3Error::NotImplemented)
+        ));
+    }
+}
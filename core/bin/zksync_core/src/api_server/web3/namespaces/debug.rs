@@ -1,42 +1,68 @@
 use std::{sync::Arc, time::Instant};
 
+use futures::{stream, StreamExt};
 use zksync_contracts::{
     BaseSystemContracts, BaseSystemContractsHashes, PLAYGROUND_BLOCK_BOOTLOADER_CODE,
 };
 use zksync_dal::ConnectionPool;
-use zksync_state::FactoryDepsCache;
+use zksync_state::{BytecodeCache, FactoryDepsCache};
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{
+        BlockId, BlockNumber, DebugCall, RejectedPriorityOp, ResultDebugCall, SupportedTracers,
+        TracerConfig, TransactionAccessSet,
+    },
     transaction_request::{l2_tx_from_call_req, CallRequest},
+    tx::TxAccessSet,
     vm_trace::{Call, VmTrace},
-    AccountTreeId, H256, USED_BOOTLOADER_MEMORY_BYTES,
+    AccountTreeId, L1BatchNumber, MiniblockNumber, Transaction, H256, U64,
+    USED_BOOTLOADER_MEMORY_BYTES,
 };
 use zksync_web3_decl::error::Web3Error;
 
 use crate::api_server::{
-    execution_sandbox::{execute_tx_eth_call, BlockArgs, TxSharedArgs, VmConcurrencyLimiter},
+    execution_sandbox::{
+        execute_tx_eth_call, execute_tx_for_tracing, BlockArgs, SandboxOutputLimits, TxSharedArgs,
+        VmConcurrencyLimiter,
+    },
     tx_sender::SubmitTxError,
-    web3::{backend_jsonrpc::error::internal_error, resolve_block},
+    web3::{
+        backend_jsonrpc::error::history_query_error, block_traces_cache::BlockTracesCache,
+        resolve_block,
+    },
 };
 
+/// Maximum number of transactions of a single miniblock that are re-executed concurrently.
+/// Each execution additionally acquires a permit from the VM concurrency limiter.
+const TRACE_BLOCK_CONCURRENCY: usize = 8;
+/// Number of re-executed miniblocks which traces are kept in memory.
+const BLOCK_TRACES_CACHE_CAPACITY: usize = 128;
+/// Max number of rejected priority operations returned by `debug_getRejectedPriorityOps`.
+const MAX_REJECTED_PRIORITY_OPS: usize = 1_000;
+
 #[derive(Debug, Clone)]
 pub struct DebugNamespace {
     connection_pool: ConnectionPool,
     fair_l2_gas_price: u64,
     base_system_contracts: BaseSystemContracts,
     vm_execution_cache_misses_limit: Option<usize>,
+    output_limits: SandboxOutputLimits,
     vm_concurrency_limiter: Arc<VmConcurrencyLimiter>,
     factory_deps_cache: FactoryDepsCache,
+    bytecode_cache: Option<BytecodeCache>,
+    block_traces_cache: Arc<BlockTracesCache>,
 }
 
 impl DebugNamespace {
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         connection_pool: ConnectionPool,
         base_system_contract_hashes: BaseSystemContractsHashes,
         fair_l2_gas_price: u64,
         vm_execution_cache_misses_limit: Option<usize>,
+        output_limits: SandboxOutputLimits,
         vm_concurrency_limiter: Arc<VmConcurrencyLimiter>,
         factory_deps_cache: FactoryDepsCache,
+        bytecode_cache: Option<BytecodeCache>,
     ) -> Self {
         let mut storage = connection_pool.access_storage_tagged("api").await;
 
@@ -56,8 +82,11 @@ impl DebugNamespace {
             fair_l2_gas_price,
             base_system_contracts,
             vm_execution_cache_misses_limit,
+            output_limits,
             vm_concurrency_limiter,
             factory_deps_cache,
+            bytecode_cache,
+            block_traces_cache: Arc::new(BlockTracesCache::new(BLOCK_TRACES_CACHE_CAPACITY)),
         }
     }
 
@@ -74,15 +103,25 @@ impl DebugNamespace {
             .unwrap_or(false);
         let mut connection = self.connection_pool.access_storage_tagged("api").await;
         let block_number = resolve_block(&mut connection, block, METHOD_NAME).await?;
-        let call_trace = connection
+        let mut call_trace = connection
             .blocks_web3_dal()
             .get_trace_for_miniblock(block_number)
-            .await;
+            .await
+            .map_err(|err| history_query_error(METHOD_NAME, err))?;
+        drop(connection);
+
+        if call_trace.is_empty() {
+            // Traces are not persisted for this block (e.g., it was sealed before traces were saved),
+            // so we re-execute its transactions to obtain them.
+            let traces = self.trace_miniblock(block_number, METHOD_NAME).await?;
+            call_trace = traces.iter().map(|trace| (trace.clone(), false)).collect();
+        }
 
         Ok(call_trace
             .into_iter()
-            .map(|call_trace| {
+            .map(|(call_trace, truncated)| {
                 let mut result: DebugCall = call_trace.into();
+                result.results_truncated = truncated;
                 if only_top_call {
                     result.calls = vec![];
                 }
@@ -96,24 +135,34 @@ impl DebugNamespace {
         &self,
         tx_hash: H256,
         options: Option<TracerConfig>,
-    ) -> Option<DebugCall> {
-        let only_top_call = options
-            .map(|options| options.tracer_config.only_top_call)
-            .unwrap_or(false);
-        let call_trace = self
-            .connection_pool
-            .access_storage_tagged("api")
-            .await
-            .transactions_dal()
-            .get_call_trace(tx_hash)
-            .await;
-        call_trace.map(|call_trace| {
+    ) -> Result<Option<DebugCall>, Web3Error> {
+        const METHOD_NAME: &str = "debug_trace_transaction";
+
+        let (only_top_call, zk_tracer) = options.map_or((false, false), |options| {
+            let zk_tracer = matches!(options.tracer, SupportedTracers::ZkTracer);
+            (options.tracer_config.only_top_call, zk_tracer)
+        });
+        let call_trace = if zk_tracer {
+            // Pubdata attribution is not persisted, so the transaction is always re-executed.
+            self.trace_included_transaction(tx_hash, METHOD_NAME)
+                .await?
+                .map(|call_trace| (call_trace, false))
+        } else {
+            self.connection_pool
+                .access_storage_tagged("api")
+                .await
+                .transactions_dal()
+                .get_call_trace(tx_hash)
+                .await
+        };
+        Ok(call_trace.map(|(call_trace, truncated)| {
             let mut result: DebugCall = call_trace.into();
+            result.results_truncated = truncated;
             if only_top_call {
                 result.calls = vec![];
             }
             result
-        })
+        }))
     }
 
     #[tracing::instrument(skip(self, request, block))]
@@ -132,7 +181,7 @@ impl DebugNamespace {
         let mut connection = self.connection_pool.access_storage_tagged("api").await;
         let block_args = BlockArgs::new(&mut connection, block)
             .await
-            .map_err(|err| internal_error("debug_trace_call", err))?
+            .map_err(|err| history_query_error("debug_trace_call", err))?
             .ok_or(Web3Error::NoBlock)?;
         drop(connection);
 
@@ -148,6 +197,7 @@ impl DebugNamespace {
             tx.clone(),
             block_args,
             self.vm_execution_cache_misses_limit,
+            self.output_limits,
             !only_top_call,
         )
         .await
@@ -186,6 +236,211 @@ impl DebugNamespace {
         Ok(call.into())
     }
 
+    /// Returns storage access sets of transactions in the L1 batch together with dependencies between them.
+    /// Returns `None` if access sets were not recorded for the batch.
+    #[tracing::instrument(skip(self))]
+    pub async fn debug_get_l1_batch_access_sets_impl(
+        &self,
+        batch: L1BatchNumber,
+    ) -> Option<Vec<TransactionAccessSet>> {
+        let start = Instant::now();
+        let mut connection = self.connection_pool.access_storage_tagged("api").await;
+        let access_sets = connection
+            .blocks_dal()
+            .get_l1_batch_tx_access_sets(batch)
+            .await?;
+        drop(connection);
+
+        let dependencies = TxAccessSet::dependencies(&access_sets);
+        let access_sets = access_sets
+            .into_iter()
+            .zip(dependencies)
+            .map(|(access_set, depends_on)| TransactionAccessSet {
+                transaction_hash: access_set.tx_hash,
+                index_in_l1_batch: access_set.index_in_l1_batch.into(),
+                reads: access_set.read_keys,
+                writes: access_set.written_keys,
+                depends_on: depends_on.into_iter().map(U64::from).collect(),
+            })
+            .collect();
+
+        metrics::histogram!(
+            "api.web3.call",
+            start.elapsed(),
+            "method" => "debug_get_l1_batch_access_sets"
+        );
+        Some(access_sets)
+    }
+
+    /// Returns the most recently rejected L1 priority operations, starting from the latest one.
+    #[tracing::instrument(skip(self))]
+    pub async fn debug_get_rejected_priority_ops_impl(&self) -> Vec<RejectedPriorityOp> {
+        let start = Instant::now();
+        let rejected_ops = self
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .eth_watcher_dal()
+            .get_rejected_priority_ops(MAX_REJECTED_PRIORITY_OPS)
+            .await;
+
+        metrics::histogram!(
+            "api.web3.call",
+            start.elapsed(),
+            "method" => "debug_get_rejected_priority_ops"
+        );
+        rejected_ops
+    }
+
+    /// Re-executes all transactions of a sealed miniblock in the sandbox with the call tracer attached.
+    ///
+    /// Transactions are executed concurrently, each on top of the state at the end of the previous
+    /// miniblock. Thus, the traces don't reflect dependencies between transactions of the same miniblock.
+    async fn trace_miniblock(
+        &self,
+        block_number: MiniblockNumber,
+        method_name: &'static str,
+    ) -> Result<Arc<Vec<Call>>, Web3Error> {
+        if let Some(traces) = self.block_traces_cache.get(block_number) {
+            return Ok(traces);
+        }
+        if block_number.0 == 0 {
+            // Genesis miniblock contains no transactions.
+            return Ok(Arc::new(vec![]));
+        }
+
+        let start = Instant::now();
+        let mut connection = self.connection_pool.access_storage_tagged("api").await;
+        let transactions = connection
+            .transactions_web3_dal()
+            .get_raw_miniblock_transactions(block_number)
+            .await
+            .map_err(|err| history_query_error(method_name, err))?;
+        drop(connection);
+        let (shared_args, block_args, base_fee) = self
+            .miniblock_tracing_args(block_number, method_name)
+            .await?;
+
+        let tx_count = transactions.len();
+        let traces: Vec<Call> = stream::iter(transactions)
+            .map(|tx| self.trace_transaction(tx, &shared_args, block_args, base_fee, false))
+            .buffered(TRACE_BLOCK_CONCURRENCY)
+            .collect()
+            .await;
+        let traces = Arc::new(traces);
+        self.block_traces_cache.insert(block_number, traces.clone());
+
+        metrics::histogram!("api.debug.trace_block_reexecution", start.elapsed());
+        metrics::histogram!("api.debug.trace_block_tx_count", tx_count as f64);
+        Ok(traces)
+    }
+
+    /// Re-executes a single transaction included into a sealed miniblock with pubdata attribution enabled.
+    /// As with [`Self::trace_miniblock()`], the transaction is executed on top of the state at the end
+    /// of the previous miniblock.
+    async fn trace_included_transaction(
+        &self,
+        tx_hash: H256,
+        method_name: &'static str,
+    ) -> Result<Option<Call>, Web3Error> {
+        let mut connection = self.connection_pool.access_storage_tagged("api").await;
+        let included_tx = connection
+            .transactions_web3_dal()
+            .get_raw_included_transaction(tx_hash)
+            .await
+            .map_err(|err| history_query_error(method_name, err))?;
+        drop(connection);
+        let Some((tx, block_number)) = included_tx else {
+            return Ok(None);
+        };
+
+        let (shared_args, block_args, base_fee) = self
+            .miniblock_tracing_args(block_number, method_name)
+            .await?;
+        let call = self
+            .trace_transaction(tx, &shared_args, block_args, base_fee, true)
+            .await;
+        Ok(Some(call))
+    }
+
+    /// Returns arguments to re-execute transactions of a sealed miniblock: shared args with the miniblock gas prices,
+    /// the state at the end of the previous miniblock and the miniblock base fee.
+    async fn miniblock_tracing_args(
+        &self,
+        block_number: MiniblockNumber,
+        method_name: &'static str,
+    ) -> Result<(TxSharedArgs, BlockArgs, u64), Web3Error> {
+        let mut connection = self.connection_pool.access_storage_tagged("api").await;
+        let header = connection
+            .blocks_dal()
+            .get_miniblock_header(block_number)
+            .await
+            .ok_or(Web3Error::NoBlock)?;
+        let previous_block = BlockId::Number(BlockNumber::Number((block_number.0 - 1).into()));
+        let block_args = BlockArgs::new(&mut connection, previous_block)
+            .await
+            .map_err(|err| history_query_error(method_name, err))?
+            .ok_or(Web3Error::NoBlock)?;
+        drop(connection);
+
+        let shared_args = TxSharedArgs {
+            l1_gas_price: header.l1_gas_price,
+            fair_l2_gas_price: header.l2_fair_gas_price,
+            ..self.shared_args()
+        };
+        Ok((shared_args, block_args, header.base_fee_per_gas))
+    }
+
+    async fn trace_transaction(
+        &self,
+        tx: Transaction,
+        shared_args: &TxSharedArgs,
+        block_args: BlockArgs,
+        base_fee: u64,
+        attribute_pubdata: bool,
+    ) -> Call {
+        let value = tx.execute.value;
+        let calldata = tx.execute.calldata.clone();
+
+        let vm_permit = self.vm_concurrency_limiter.acquire().await;
+        let result = execute_tx_for_tracing(
+            &vm_permit,
+            shared_args.clone(),
+            self.connection_pool.clone(),
+            tx,
+            block_args,
+            base_fee,
+            attribute_pubdata,
+        )
+        .await;
+        drop(vm_permit); // Unblock other VMs to enter.
+
+        let (output, revert_reason) = match result.revert_reason {
+            Some(result) => (vec![], Some(result.revert_reason.to_string())),
+            None => (
+                result
+                    .return_data
+                    .into_iter()
+                    .flat_map(<[u8; 32]>::from)
+                    .collect(),
+                None,
+            ),
+        };
+        let trace = match result.trace {
+            VmTrace::CallTrace(trace) => trace,
+            VmTrace::ExecutionTrace(_) => vec![],
+        };
+        Call::new_high_level(
+            u32::MAX,
+            result.gas_used,
+            value,
+            calldata,
+            output,
+            revert_reason,
+            trace,
+        )
+    }
+
     fn shared_args(&self) -> TxSharedArgs {
         TxSharedArgs {
             operator_account: AccountTreeId::default(),
@@ -193,6 +448,8 @@ impl DebugNamespace {
             fair_l2_gas_price: self.fair_l2_gas_price,
             base_system_contracts: self.base_system_contracts.clone(),
             factory_deps_cache: self.factory_deps_cache.clone(),
+            bytecode_cache: self.bytecode_cache.clone(),
+            pending_storage_diffs: None,
         }
     }
 }
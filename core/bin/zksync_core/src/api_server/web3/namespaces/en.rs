@@ -2,7 +2,7 @@ use zksync_types::{api::en::SyncBlock, MiniblockNumber};
 use zksync_web3_decl::error::Web3Error;
 
 use crate::{
-    api_server::{web3::backend_jsonrpc::error::internal_error, web3::state::RpcState},
+    api_server::{web3::backend_jsonrpc::error::history_query_error, web3::state::RpcState},
     l1_gas_price::L1GasPriceProvider,
 };
 
@@ -32,11 +32,7 @@ impl<G: L1GasPriceProvider> EnNamespace<G> {
         block_number: MiniblockNumber,
         include_transactions: bool,
     ) -> Result<Option<SyncBlock>, Web3Error> {
-        let mut storage = self
-            .state
-            .connection_pool
-            .access_storage_tagged("api")
-            .await;
+        let mut storage = self.state.access_storage().await;
         storage
             .sync_dal()
             .sync_block(
@@ -45,6 +41,6 @@ impl<G: L1GasPriceProvider> EnNamespace<G> {
                 include_transactions,
             )
             .await
-            .map_err(|err| internal_error("en_syncL2Block", err))
+            .map_err(|err| history_query_error("en_syncL2Block", err))
     }
 }
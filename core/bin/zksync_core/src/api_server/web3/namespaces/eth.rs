@@ -33,7 +33,13 @@ use {
 use crate::{
     api_server::{
         execution_sandbox::BlockArgs,
-        web3::{backend_jsonrpc::error::internal_error, resolve_block, state::RpcState},
+        web3::{
+            backend_jsonrpc::error::{history_query_error, internal_error},
+            call_cache::CallCacheKey,
+            resolve_block,
+            response_cache::{CacheKey, CachedResponse},
+            state::RpcState,
+        },
     },
     l1_gas_price::L1GasPriceProvider,
 };
@@ -66,8 +72,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         let start = Instant::now();
         let block_number = self
             .state
-            .connection_pool
-            .access_storage_tagged("api")
+            .access_storage()
             .await
             .blocks_web3_dal()
             .get_sealed_miniblock_number()
@@ -88,17 +93,29 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         let start = Instant::now();
 
         let block = block.unwrap_or(BlockId::Number(BlockNumber::Pending));
-        let mut connection = self
-            .state
-            .connection_pool
-            .access_storage_tagged("api")
-            .await;
+        let mut connection = self.state.access_storage().await;
         let block_args = BlockArgs::new(&mut connection, block)
             .await
-            .map_err(|err| internal_error("eth_call", err))?
+            .map_err(|err| history_query_error("eth_call", err))?
             .ok_or(Web3Error::NoBlock)?;
         drop(connection);
 
+        // Calls at the pending block are not cached since the pending state changes with each executed transaction.
+        let is_pending = matches!(block, BlockId::Number(BlockNumber::Pending));
+        let call_cache = match &self.state.call_cache {
+            Some(cache) if !is_pending => {
+                let key = CallCacheKey::new(&request, block_args.resolved_block_number());
+                Some((cache, key))
+            }
+            _ => None,
+        };
+        if let Some((cache, key)) = &call_cache {
+            if let Some(cached_bytes) = cache.get(key) {
+                metrics::histogram!("api.web3.call", start.elapsed(), "method" => "call");
+                return Ok(cached_bytes.into());
+            }
+        }
+
         let mut request_with_set_nonce = request.clone();
         self.state
             .set_nonce_for_call_request(&mut request_with_set_nonce)
@@ -124,16 +141,19 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         {
             res_bytes = res_bytes[96..].to_vec();
         }
+        if let Some((cache, key)) = call_cache {
+            cache.insert(key, res_bytes.clone());
+        }
 
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => "call");
         Ok(res_bytes.into())
     }
 
-    #[tracing::instrument(skip(self, request, _block))]
+    #[tracing::instrument(skip(self, request, block))]
     pub async fn estimate_gas_impl(
         &self,
         request: CallRequest,
-        _block: Option<BlockNumber>,
+        block: Option<BlockNumber>,
     ) -> Result<U256, Web3Error> {
         let start = Instant::now();
         let mut request_with_gas_per_pubdata_overridden = request;
@@ -183,25 +203,53 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         let acceptable_overestimation =
             self.state.api_config.estimate_gas_acceptable_overestimation;
 
+        // The pending block tag opts into estimation on top of transactions executed, but not yet persisted
+        // by the state keeper.
+        let use_pending_state = matches!(block, Some(BlockNumber::Pending));
         let fee = self
             .state
             .tx_sender
-            .get_txs_fee_in_wei(tx.into(), scale_factor, acceptable_overestimation)
+            .get_txs_fee_in_wei(
+                tx.into(),
+                scale_factor,
+                acceptable_overestimation,
+                use_pending_state,
+            )
             .await
             .map_err(|err| Web3Error::SubmitTransactionError(err.to_string(), err.data()))?;
 
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => "estimate_gas");
-        Ok(fee.gas_limit)
+        Ok(fee.fee.gas_limit)
     }
 
     #[tracing::instrument(skip(self))]
-    pub fn gas_price_impl(&self) -> Result<U256, Web3Error> {
+    pub async fn gas_price_impl(&self) -> Result<U256, Web3Error> {
         const METHOD_NAME: &str = "gas_price";
 
         let start = Instant::now();
-        let price = self.state.tx_sender.gas_price();
+        let suggestion = self
+            .state
+            .tx_sender
+            .suggest_gas_price()
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+        Ok(suggestion.gas_price.into())
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn max_priority_fee_per_gas_impl(&self) -> Result<U256, Web3Error> {
+        const METHOD_NAME: &str = "max_priority_fee_per_gas";
+
+        let start = Instant::now();
+        let suggestion = self
+            .state
+            .tx_sender
+            .suggest_gas_price()
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
-        Ok(price.into())
+        Ok(suggestion.max_priority_fee_per_gas.into())
     }
 
     #[tracing::instrument(skip(self))]
@@ -213,11 +261,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         const METHOD_NAME: &str = "get_balance";
 
         let start = Instant::now();
-        let mut connection = self
-            .state
-            .connection_pool
-            .access_storage_tagged("api")
-            .await;
+        let mut connection = self.state.access_storage().await;
         let block = block.unwrap_or(BlockId::Number(BlockNumber::Pending));
         let block_number = resolve_block(&mut connection, block, METHOD_NAME).await?;
         let balance = connection
@@ -291,16 +335,33 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
             "get_block"
         };
 
+        let cache_key = match block {
+            BlockId::Number(BlockNumber::Number(number)) => Some(CacheKey::BlockByNumber(
+                RpcState::<G>::u64_to_block_number(number),
+                full_transactions,
+            )),
+            BlockId::Hash(hash) => Some(CacheKey::BlockByHash(hash, full_transactions)),
+            _ => None,
+        };
+        let cached_block = cache_key.and_then(|key| self.state.get_cached_response(&key));
+        if let Some(CachedResponse::Block(block)) = cached_block {
+            metrics::histogram!("api.web3.call", start.elapsed(), "method" => method_name);
+            return Ok(Some(*block));
+        }
+
         let block = self
             .state
-            .connection_pool
-            .access_storage_tagged("api")
+            .access_storage()
             .await
             .blocks_web3_dal()
             .get_block_by_web3_block_id(block, full_transactions, self.state.api_config.l2_chain_id)
             .await
-            .map_err(|err| internal_error(method_name, err));
+            .map_err(|err| history_query_error(method_name, err));
 
+        if let (Some(key), Ok(Some(block))) = (cache_key, &block) {
+            let response = CachedResponse::Block(Box::new(block.clone()));
+            self.state.cache_response(key, block.number, response);
+        }
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => method_name);
         block
     }
@@ -315,8 +376,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         let start = Instant::now();
         let tx_count = self
             .state
-            .connection_pool
-            .access_storage_tagged("api")
+            .access_storage()
             .await
             .blocks_web3_dal()
             .get_block_tx_count(block)
@@ -336,21 +396,30 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         const METHOD_NAME: &str = "get_code";
 
         let start = Instant::now();
-        let mut connection = self
-            .state
-            .connection_pool
-            .access_storage_tagged("api")
-            .await;
+        let mut connection = self.state.access_storage().await;
         let block = block.unwrap_or(BlockId::Number(BlockNumber::Pending));
         let block_number = resolve_block(&mut connection, block, METHOD_NAME).await?;
-        let contract_code = connection
+        let cache_key = CacheKey::Code(address, block_number);
+        if let Some(CachedResponse::Code(code)) = self.state.get_cached_response(&cache_key) {
+            metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+            return Ok(code);
+        }
+
+        let contract_code: Bytes = connection
             .storage_web3_dal()
             .get_contract_code_unchecked(address, block_number)
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err))?;
-
+            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .unwrap_or_default()
+            .into();
+
+        self.state.cache_response(
+            cache_key,
+            block_number.0.into(),
+            CachedResponse::Code(contract_code.clone()),
+        );
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
-        Ok(contract_code.unwrap_or_default().into())
+        Ok(contract_code)
     }
 
     #[tracing::instrument(skip(self))]
@@ -370,17 +439,13 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         let start = Instant::now();
         let block = block.unwrap_or(BlockId::Number(BlockNumber::Pending));
         let storage_key = StorageKey::new(AccountTreeId::new(address), u256_to_h256(idx));
-        let mut connection = self
-            .state
-            .connection_pool
-            .access_storage_tagged("api")
-            .await;
+        let mut connection = self.state.access_storage().await;
         let block_number = resolve_block(&mut connection, block, METHOD_NAME).await?;
         let value = connection
             .storage_web3_dal()
-            .get_historical_value_unchecked(&storage_key, block_number)
+            .get_historical_value(&storage_key, block_number)
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err))?;
+            .map_err(|err| history_query_error(METHOD_NAME, err))?;
 
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
         Ok(value)
@@ -400,11 +465,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
             BlockId::Number(BlockNumber::Pending) => "get_pending_transaction_count",
             _ => "get_historical_transaction_count",
         };
-        let mut connection = self
-            .state
-            .connection_pool
-            .access_storage_tagged("api")
-            .await;
+        let mut connection = self.state.access_storage().await;
 
         let full_nonce = match block {
             BlockId::Number(BlockNumber::Pending) => connection
@@ -436,16 +497,32 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         const METHOD_NAME: &str = "get_transaction";
 
         let start = Instant::now();
+        let cache_key = match id {
+            TransactionId::Hash(hash) => Some(CacheKey::Transaction(hash)),
+            TransactionId::Block(..) => None,
+        };
+        let cached_transaction = cache_key.and_then(|key| self.state.get_cached_response(&key));
+        if let Some(CachedResponse::Transaction(transaction)) = cached_transaction {
+            metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+            return Ok(Some(*transaction));
+        }
+
         let mut transaction = self
             .state
-            .connection_pool
-            .access_storage_tagged("api")
+            .access_storage()
             .await
             .transactions_web3_dal()
             .get_transaction(id, self.state.api_config.l2_chain_id)
             .await
             .map_err(|err| internal_error(METHOD_NAME, err));
 
+        if let (Some(key), Ok(Some(tx))) = (cache_key, &transaction) {
+            if let Some(block_number) = tx.block_number {
+                let response = CachedResponse::Transaction(Box::new(tx.clone()));
+                self.state.cache_response(key, block_number, response);
+            }
+        }
+
         if let Some(proxy) = &self.state.tx_sender.0.proxy {
             // We're running an external node - check the proxy cache in
             // case the transaction was proxied but not yet synced back to us
@@ -481,16 +558,36 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         const METHOD_NAME: &str = "get_transaction_receipt";
 
         let start = Instant::now();
+        let cache_key = CacheKey::Receipt(hash);
+        if let Some(CachedResponse::Receipt(receipt)) = self.state.get_cached_response(&cache_key) {
+            metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+            return Ok(Some(*receipt));
+        }
+        // Pending receipts must be checked before Postgres; otherwise, a receipt could be missed
+        // if its miniblock is persisted between the two checks.
+        if let Some(pending_receipts) = &self.state.pending_receipts {
+            if let Some(receipt) = pending_receipts.get(hash) {
+                metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+                return Ok(Some(receipt));
+            }
+        }
+
         let mut receipt = self
             .state
-            .connection_pool
-            .access_storage_tagged("api")
+            .access_storage()
             .await
             .transactions_web3_dal()
             .get_transaction_receipt(hash)
             .await
             .map_err(|err| internal_error(METHOD_NAME, err));
 
+        if let Ok(Some(receipt)) = &receipt {
+            if let Some(block_number) = receipt.block_number {
+                let response = CachedResponse::Receipt(Box::new(receipt.clone()));
+                self.state.cache_response(cache_key, block_number, response);
+            }
+        }
+
         if let Some(proxy) = &self.state.tx_sender.0.proxy {
             // We're running an external node
             if matches!(receipt, Ok(None)) {
@@ -527,8 +624,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
         let start = Instant::now();
         let last_block_number = self
             .state
-            .connection_pool
-            .access_storage_tagged("api")
+            .access_storage()
             .await
             .blocks_web3_dal()
             .get_sealed_miniblock_number()
@@ -690,8 +786,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
             TypedFilter::Blocks(from_block) => {
                 let (block_hashes, last_block_number) = self
                     .state
-                    .connection_pool
-                    .access_storage_tagged("api")
+                    .access_storage()
                     .await
                     .blocks_web3_dal()
                     .get_block_hashes_after(from_block, self.state.api_config.req_entities_limit)
@@ -705,8 +800,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
             TypedFilter::PendingTransactions(from_timestamp) => {
                 let (tx_hashes, last_timestamp) = self
                     .state
-                    .connection_pool
-                    .access_storage_tagged("api")
+                    .access_storage()
                     .await
                     .transactions_web3_dal()
                     .get_pending_txs_hashes_after(
@@ -751,11 +845,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
                     .resolve_filter_block_number(filter.to_block)
                     .await?;
 
-                let mut storage = self
-                    .state
-                    .connection_pool
-                    .access_storage_tagged("api")
-                    .await;
+                let mut storage = self.state.access_storage().await;
 
                 // Check if there is more than one block in range and there are more than `req_entities_limit` logs that satisfies filter.
                 // In this case we should return error and suggest requesting logs with smaller block range.
@@ -781,7 +871,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
                     .events_web3_dal()
                     .get_logs(get_logs_filter, i32::MAX as usize)
                     .await
-                    .map_err(|err| internal_error(METHOD_NAME, err))?;
+                    .map_err(|err| history_query_error(METHOD_NAME, err))?;
                 let new_from_block = logs
                     .last()
                     .map(|log| MiniblockNumber(log.block_number.unwrap().as_u32()))
@@ -805,8 +895,7 @@ impl<G: L1GasPriceProvider> EthNamespace<G> {
             nonce
         } else {
             self.state
-                .connection_pool
-                .access_storage_tagged("api")
+                .access_storage()
                 .await
                 .transactions_web3_dal()
                 .next_nonce_by_initiator_account(transaction_request.from)
@@ -6,7 +6,7 @@ use jsonrpc_pubsub::typed;
 use jsonrpc_pubsub::SubscriptionId;
 use tokio::sync::RwLock;
 
-use zksync_types::web3::types::H128;
+use zksync_types::{api::TxLifecycleStage, web3::types::H128, H256};
 use zksync_web3_decl::types::{PubSubFilter, PubSubResult};
 
 use super::eth::EVENT_TOPIC_NUMBER_LIMIT;
@@ -18,6 +18,7 @@ enum SubscriptionType {
     Blocks,
     Txs,
     Logs,
+    TxStatus,
 }
 
 impl SubscriptionType {
@@ -26,10 +27,26 @@ impl SubscriptionType {
             Self::Blocks => "blocks",
             Self::Txs => "txs",
             Self::Logs => "logs",
+            Self::TxStatus => "tx_status",
         }
     }
 }
 
+/// Parameters of the `zks_subscribe("txStatus")` subscription.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxStatusSubscriptionParams {
+    pub tx_hash: H256,
+}
+
+/// State of the `zks_subscribe("txStatus")` subscription.
+#[derive(Debug, Clone)]
+pub struct TxStatusSubscription {
+    pub tx_hash: H256,
+    /// Last stage reported to the subscriber.
+    pub last_stage: Option<TxLifecycleStage>,
+}
+
 #[derive(Debug, Clone)]
 pub struct EthSubscribe {
     // `jsonrpc` backend executes task subscription on a separate thread that has no tokio context.
@@ -37,6 +54,7 @@ pub struct EthSubscribe {
     pub active_block_subs: SubscriptionMap<typed::Sink<PubSubResult>>,
     pub active_tx_subs: SubscriptionMap<typed::Sink<PubSubResult>>,
     pub active_log_subs: SubscriptionMap<(typed::Sink<PubSubResult>, PubSubFilter)>,
+    pub active_tx_status_subs: SubscriptionMap<(typed::Sink<PubSubResult>, TxStatusSubscription)>,
 }
 
 impl EthSubscribe {
@@ -46,6 +64,7 @@ impl EthSubscribe {
             active_block_subs: SubscriptionMap::default(),
             active_tx_subs: SubscriptionMap::default(),
             active_log_subs: SubscriptionMap::default(),
+            active_tx_status_subs: SubscriptionMap::default(),
         }
     }
 
@@ -131,6 +150,42 @@ impl EthSubscribe {
         }
     }
 
+    /// Handles `zks_subscribe`. The only supported subscription is `txStatus`, which reports lifecycle
+    /// stages of the transaction with the specified hash.
+    #[tracing::instrument(skip(self, subscriber, params))]
+    pub async fn zks_sub(
+        &self,
+        subscriber: typed::Subscriber<PubSubResult>,
+        sub_type: String,
+        params: Option<serde_json::Value>,
+    ) {
+        let params = match (sub_type.as_str(), params) {
+            ("txStatus", Some(params)) => {
+                serde_json::from_value::<TxStatusSubscriptionParams>(params)
+            }
+            _ => {
+                Self::reject(subscriber);
+                return;
+            }
+        };
+        let params = match params {
+            Ok(params) => params,
+            Err(_) => {
+                Self::reject(subscriber);
+                return;
+            }
+        };
+
+        let mut tx_status_subs = self.active_tx_status_subs.write().await;
+        let (sink, id) = Self::assign_id(subscriber);
+        let subscription = TxStatusSubscription {
+            tx_hash: params.tx_hash,
+            last_stage: None,
+        };
+        tx_status_subs.insert(id, (sink, subscription));
+        metrics::increment_gauge!("api.web3.pubsub.active_subscribers", 1f64, "subscription_type" => SubscriptionType::TxStatus.as_str());
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn unsub(&self, id: SubscriptionId) -> Result<bool, Error> {
         let removed = if self.active_block_subs.write().await.remove(&id).is_some() {
@@ -139,6 +194,14 @@ impl EthSubscribe {
             Some(SubscriptionType::Txs)
         } else if self.active_log_subs.write().await.remove(&id).is_some() {
             Some(SubscriptionType::Logs)
+        } else if self
+            .active_tx_status_subs
+            .write()
+            .await
+            .remove(&id)
+            .is_some()
+        {
+            Some(SubscriptionType::TxStatus)
         } else {
             None
         };
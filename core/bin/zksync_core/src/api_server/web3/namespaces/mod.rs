@@ -6,20 +6,24 @@ use num::{rational::Ratio, BigUint};
 use zksync_types::U256;
 use zksync_utils::{biguint_to_u256, u256_to_biguint};
 
+mod admin;
 mod debug;
 mod en;
 mod eth;
 mod eth_subscribe;
 mod net;
+mod txpool;
 mod web3;
 mod zks;
 
 pub use self::{
+    admin::AdminNamespace,
     debug::DebugNamespace,
     en::EnNamespace,
     eth::EthNamespace,
-    eth_subscribe::{EthSubscribe, SubscriptionMap},
+    eth_subscribe::{EthSubscribe, SubscriptionMap, TxStatusSubscription},
     net::NetNamespace,
+    txpool::TxpoolNamespace,
     web3::Web3Namespace,
     zks::ZksNamespace,
 };
@@ -0,0 +1,26 @@
+use zksync_types::api::TxpoolStatus;
+use zksync_web3_decl::error::Web3Error;
+
+use crate::state_keeper::MempoolGuard;
+
+/// `txpool` namespace exposing the state of the in-memory mempool.
+/// Only available if the API server runs in the same process as the state keeper.
+#[derive(Debug, Clone)]
+pub struct TxpoolNamespace {
+    mempool: Option<MempoolGuard>,
+}
+
+impl TxpoolNamespace {
+    pub fn new(mempool: Option<MempoolGuard>) -> Self {
+        Self { mempool }
+    }
+
+    pub fn status_impl(&self) -> Result<TxpoolStatus, Web3Error> {
+        let mempool = self.mempool.as_ref().ok_or(Web3Error::NotImplemented)?;
+        let stats = mempool.stats();
+        Ok(TxpoolStatus {
+            pending: (stats.l1_transactions + stats.pending_l2_transactions).into(),
+            queued: stats.queued_l2_transactions.into(),
+        })
+    }
+}
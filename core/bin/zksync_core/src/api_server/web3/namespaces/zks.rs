@@ -1,35 +1,56 @@
 use std::time::Instant;
-use std::{collections::HashMap, convert::TryInto};
+use std::{collections::HashMap, convert::TryInto, sync::Arc};
 
 use bigdecimal::{BigDecimal, Zero};
 
-use zksync_mini_merkle_tree::MiniMerkleTree;
-
-#[cfg(feature = "openzeppelin_tests")]
-use zksync_types::Bytes;
+use zksync_dal::StorageProcessor;
+use zksync_mempool::FilterMismatch;
 use zksync_types::{
-    api::{BridgeAddresses, GetLogsFilter, L2ToL1LogProof, TransactionDetails, U64},
+    aggregated_operations::AggregatedActionType,
+    api::{
+        AccountSummary, BalancesFilter, BatchResourceUsage, BlockId, BlockNumber, BridgeAddresses,
+        BundleSimulation, BundleTransactionResult, GetLogsFilter, L2ToL1LogProof,
+        L2ToL1MessageProof, Log, NodeStatus, ProtocolVersion, RejectionStats, StorageDiff,
+        StorageDiffPagination, StorageSlotDiff, TokenFilter, TransactionDetails,
+        TransactionHistory, TxpoolStatus, WithdrawalFinalizationParams, U64,
+    },
     commitment::SerializeCommitment,
+    ethabi,
+    event::L1_MESSAGE_EVENT_SIGNATURE,
     explorer_api::{BlockDetails, L1BatchDetails},
-    fee::Fee,
+    fee::FeeEstimate,
     l1::L1Tx,
     l2_to_l1_log::L2ToL1Log,
     tokens::ETHEREUM_ADDRESS,
     transaction_request::{l2_tx_from_call_req, CallRequest},
-    L1BatchNumber, MiniblockNumber, Transaction, L1_MESSENGER_ADDRESS, L2_ETH_TOKEN_ADDRESS,
-    MAX_GAS_PER_PUBDATA_BYTE, REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256,
+    tx::tx_execution_info::TxExecutionStatus,
+    vm_trace::Call,
+    Bytes, L1BatchNumber, MiniblockNumber, ProtocolVersionId, StorageLog, StorageLogQuery,
+    Transaction, L1_MESSENGER_ADDRESS, L2_ETH_TOKEN_ADDRESS, MAX_GAS_PER_PUBDATA_BYTE,
+    REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256,
 };
-use zksync_utils::address_to_h256;
+use zksync_utils::{address_to_h256, h256_to_account_address, u256_to_h256};
 use zksync_web3_decl::{
     error::Web3Error,
     types::{Address, Token, H256},
 };
 
-use crate::api_server::web3::{backend_jsonrpc::error::internal_error, RpcState};
+use crate::api_server::{
+    execution_sandbox::{BlockArgs, SandboxExecutionError},
+    tx_sender::SubmitTxError,
+    web3::{
+        backend_jsonrpc::error::{history_query_error, internal_error},
+        l2_to_l1_logs_cache::L2ToL1LogsTree,
+        RpcState,
+    },
+};
 use crate::fee_ticker::FeeTicker;
 use crate::fee_ticker::{error::TickerError, TokenPriceRequestType};
 use crate::l1_gas_price::L1GasPriceProvider;
 
+/// Max number of storage slot diffs returned by `zks_getStorageDiff` in a single page.
+const MAX_STORAGE_DIFF_PAGE_SIZE: usize = 1_000;
+
 #[derive(Debug)]
 pub struct ZksNamespace<G> {
     pub state: RpcState<G>,
@@ -49,7 +70,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
     }
 
     #[tracing::instrument(skip(self, request))]
-    pub async fn estimate_fee_impl(&self, request: CallRequest) -> Result<Fee, Web3Error> {
+    pub async fn estimate_fee_impl(&self, request: CallRequest) -> Result<FeeEstimate, Web3Error> {
         let start = Instant::now();
         let mut request_with_gas_per_pubdata_overridden = request;
 
@@ -99,10 +120,10 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         let fee = self.estimate_fee(tx.into()).await?;
 
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => "estimate_gas_l1_to_l2");
-        Ok(fee.gas_limit)
+        Ok(fee.fee.gas_limit)
     }
 
-    async fn estimate_fee(&self, tx: Transaction) -> Result<Fee, Web3Error> {
+    async fn estimate_fee(&self, tx: Transaction) -> Result<FeeEstimate, Web3Error> {
         let scale_factor = self.state.api_config.estimate_gas_scale_factor;
         let acceptable_overestimation =
             self.state.api_config.estimate_gas_acceptable_overestimation;
@@ -110,7 +131,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         let fee = self
             .state
             .tx_sender
-            .get_txs_fee_in_wei(tx, scale_factor, acceptable_overestimation)
+            .get_txs_fee_in_wei(tx, scale_factor, acceptable_overestimation, false)
             .await
             .map_err(|err| Web3Error::SubmitTransactionError(err.to_string(), err.data()))?;
 
@@ -142,28 +163,27 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         &self,
         from: u32,
         limit: u8,
+        filter: Option<TokenFilter>,
     ) -> Result<Vec<Token>, Web3Error> {
         const METHOD_NAME: &str = "get_confirmed_tokens";
 
         let start = Instant::now();
         let tokens = self
             .state
-            .connection_pool
-            .access_storage_tagged("api")
+            .access_storage()
             .await
             .tokens_web3_dal()
-            .get_well_known_tokens()
+            .get_confirmed_tokens(from, limit, &filter.unwrap_or_default())
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?
             .into_iter()
-            .skip(from as usize)
-            .take(limit.into())
-            .map(|token_info| Token {
+            .map(|(token_info, first_seen_l1_batch)| Token {
                 l1_address: token_info.l1_address,
                 l2_address: token_info.l2_address,
                 name: token_info.metadata.name,
                 symbol: token_info.metadata.symbol,
                 decimals: token_info.metadata.decimals,
+                first_seen_l1_batch,
             })
             .collect();
 
@@ -177,11 +197,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
 
         let start = Instant::now();
         let token_price_result = {
-            let mut storage = self
-                .state
-                .connection_pool
-                .access_storage_tagged("api")
-                .await;
+            let mut storage = self.state.access_storage().await;
             let mut tokens_web3_dal = storage.tokens_web3_dal();
             FeeTicker::get_l2_token_price(
                 &mut tokens_web3_dal,
@@ -205,32 +221,59 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
     pub async fn get_all_account_balances_impl(
         &self,
         address: Address,
+        filter: Option<BalancesFilter>,
     ) -> Result<HashMap<Address, U256>, Web3Error> {
         const METHOD_NAME: &str = "get_all_balances";
 
         let start = Instant::now();
-        let balances = self
-            .state
-            .connection_pool
-            .access_storage_tagged("api")
-            .await
-            .explorer()
-            .accounts_dal()
-            .get_balances_for_address(address)
-            .await
-            .map_err(|err| internal_error(METHOD_NAME, err))?
-            .into_iter()
-            .map(|(address, balance_item)| {
-                if address == L2_ETH_TOKEN_ADDRESS {
-                    (ETHEREUM_ADDRESS, balance_item.balance)
-                } else {
-                    (address, balance_item.balance)
-                }
-            })
-            .collect();
+        let filter = filter.unwrap_or_default();
+        let max_limit = self.state.api_config.req_entities_limit;
+        let limit = filter.limit.map_or(max_limit, |limit| limit as usize);
+        if limit > max_limit {
+            return Err(Web3Error::TooManyItemsRequested(max_limit));
+        }
+        let offset = filter.offset.unwrap_or(0);
+
+        let mut storage = self.state.access_storage().await;
+        let balances = if self.state.api_config.indexed_balances {
+            storage
+                .tokens_web3_dal()
+                .get_indexed_token_balances(address, filter.tokens.as_deref(), offset, limit as u32)
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?
+        } else {
+            let balances = storage
+                .explorer()
+                .accounts_dal()
+                .get_balances_for_address(address)
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?;
+            let mut balances: Vec<_> = balances
+                .into_iter()
+                .map(|(address, balance_item)| {
+                    if address == L2_ETH_TOKEN_ADDRESS {
+                        (ETHEREUM_ADDRESS, balance_item.balance)
+                    } else {
+                        (address, balance_item.balance)
+                    }
+                })
+                .filter(|(token, _)| {
+                    filter
+                        .tokens
+                        .as_ref()
+                        .map_or(true, |tokens| tokens.contains(token))
+                })
+                .collect();
+            balances.sort_unstable_by_key(|(token, _)| *token);
+            balances
+                .into_iter()
+                .skip(offset as usize)
+                .take(limit)
+                .collect()
+        };
 
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
-        Ok(balances)
+        Ok(balances.into_iter().collect())
     }
 
     #[tracing::instrument(skip(self))]
@@ -244,11 +287,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         const METHOD_NAME: &str = "get_l2_to_l1_msg_proof";
 
         let start = Instant::now();
-        let mut storage = self
-            .state
-            .connection_pool
-            .access_storage_tagged("api")
-            .await;
+        let mut storage = self.state.access_storage().await;
         let l1_batch_number = match storage
             .blocks_web3_dal()
             .get_l1_batch_number_of_miniblock(block_number)
@@ -265,11 +304,9 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
             .map_err(|err| internal_error(METHOD_NAME, err))?
             .expect("L1 batch should contain at least one miniblock");
 
-        let all_l1_logs_in_block = storage
-            .blocks_web3_dal()
-            .get_l2_to_l1_logs(l1_batch_number)
-            .await
-            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let logs_tree = self
+            .l2_to_l1_logs_tree(&mut storage, l1_batch_number, METHOD_NAME)
+            .await?;
 
         // Position of l1 log in block relative to logs with identical data
         let l1_log_relative_position = if let Some(l2_log_position) = l2_log_position {
@@ -285,7 +322,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
                     self.state.api_config.req_entities_limit,
                 )
                 .await
-                .map_err(|err| internal_error(METHOD_NAME, err))?
+                .map_err(|err| history_query_error(METHOD_NAME, err))?
                 .iter()
                 .position(|event| {
                     event.block_number == Some(block_number.0.into())
@@ -301,7 +338,8 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
             0
         };
 
-        let l1_log_index = match all_l1_logs_in_block
+        let l1_log_index = match logs_tree
+            .logs
             .iter()
             .enumerate()
             .filter(|(_, log)| {
@@ -317,16 +355,9 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
             }
         };
 
-        let merkle_tree_leaves = all_l1_logs_in_block.iter().map(L2ToL1Log::to_bytes);
-        let (root, proof) = MiniMerkleTree::new(merkle_tree_leaves, L2ToL1Log::LIMIT_PER_BLOCK)
-            .merkle_root_and_path(l1_log_index);
-        let msg_proof = L2ToL1LogProof {
-            proof,
-            root,
-            id: l1_log_index as u32,
-        };
+        let msg_proof = logs_tree.proof(l1_log_index);
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
-        Ok(Some(msg_proof))
+        Ok(msg_proof)
     }
 
     #[tracing::instrument(skip(self))]
@@ -338,11 +369,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         const METHOD_NAME: &str = "get_l2_to_l1_msg_proof";
 
         let start = Instant::now();
-        let mut storage = self
-            .state
-            .connection_pool
-            .access_storage_tagged("api")
-            .await;
+        let mut storage = self.state.access_storage().await;
         let (l1_batch_number, l1_batch_tx_index) = match storage
             .blocks_web3_dal()
             .get_l1_batch_info_for_tx(tx_hash)
@@ -353,35 +380,224 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
             None => return Ok(None),
         };
 
-        let all_l1_logs_in_block = storage
+        let logs_tree = self
+            .l2_to_l1_logs_tree(&mut storage, l1_batch_number, METHOD_NAME)
+            .await?;
+
+        let msg_proof = logs_tree
+            .logs
+            .iter()
+            .enumerate()
+            .filter(|(_, log)| log.tx_number_in_block == l1_batch_tx_index)
+            .nth(index.unwrap_or(0))
+            .and_then(|(l1_log_index, _)| logs_tree.proof(l1_log_index));
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+        Ok(msg_proof)
+    }
+
+    /// Batch version of [`Self::get_l2_to_l1_log_proof_impl()`]: returns proofs for multiple logs
+    /// emitted by the transaction. Proofs are returned in the order of `indices`; `None` is returned
+    /// for indices that don't correspond to any log of the transaction.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_l2_to_l1_log_proofs_impl(
+        &self,
+        tx_hash: H256,
+        indices: Vec<usize>,
+    ) -> Result<Vec<Option<L2ToL1LogProof>>, Web3Error> {
+        const METHOD_NAME: &str = "get_l2_to_l1_log_proofs";
+
+        if indices.len() > L2ToL1Log::LIMIT_PER_BLOCK {
+            return Err(Web3Error::TooManyItemsRequested(L2ToL1Log::LIMIT_PER_BLOCK));
+        }
+
+        let start = Instant::now();
+        let mut storage = self.state.access_storage().await;
+        let (l1_batch_number, l1_batch_tx_index) = match storage
             .blocks_web3_dal()
-            .get_l2_to_l1_logs(l1_batch_number)
+            .get_l1_batch_info_for_tx(tx_hash)
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err))?;
+            .map_err(|err| internal_error(METHOD_NAME, err))?
+        {
+            Some(x) => x,
+            None => return Ok(vec![None; indices.len()]),
+        };
+
+        let logs_tree = self
+            .l2_to_l1_logs_tree(&mut storage, l1_batch_number, METHOD_NAME)
+            .await?;
+        drop(storage);
 
-        let l1_log_index = match all_l1_logs_in_block
+        let tx_log_indices: Vec<usize> = logs_tree
+            .logs
             .iter()
             .enumerate()
             .filter(|(_, log)| log.tx_number_in_block == l1_batch_tx_index)
-            .nth(index.unwrap_or(0))
+            .map(|(l1_log_index, _)| l1_log_index)
+            .collect();
+        let proofs = indices
+            .into_iter()
+            .map(|index| {
+                let l1_log_index = *tx_log_indices.get(index)?;
+                logs_tree.proof(l1_log_index)
+            })
+            .collect();
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+        Ok(proofs)
+    }
+
+    /// Assembles the data needed to finalize a withdrawal on L1 from the L1 messenger events and
+    /// L2->L1 logs of the transaction. `index` selects the message if the transaction sent several
+    /// of them. Returns `None` if the transaction isn't included in an L1 batch yet, or if it doesn't
+    /// have a message with the specified index.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_withdrawal_finalization_params_impl(
+        &self,
+        tx_hash: H256,
+        index: Option<usize>,
+    ) -> Result<Option<WithdrawalFinalizationParams>, Web3Error> {
+        const METHOD_NAME: &str = "get_withdrawal_finalization_params";
+
+        let start = Instant::now();
+        let index = index.unwrap_or(0);
+        let mut storage = self.state.access_storage().await;
+        let (l1_batch_number, l1_batch_tx_index) = match storage
+            .blocks_web3_dal()
+            .get_l1_batch_info_for_tx(tx_hash)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?
         {
-            Some(nth_elem) => nth_elem.0,
-            None => {
-                return Ok(None);
-            }
+            Some(x) => x,
+            None => return Ok(None),
         };
+        let receipt = storage
+            .transactions_web3_dal()
+            .get_transaction_receipt(tx_hash)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .ok_or_else(|| {
+                internal_error(METHOD_NAME, "receipt of a sealed transaction is missing")
+            })?;
 
-        let merkle_tree_leaves = all_l1_logs_in_block.iter().map(L2ToL1Log::to_bytes);
-        let (root, proof) = MiniMerkleTree::new(merkle_tree_leaves, L2ToL1Log::LIMIT_PER_BLOCK)
-            .merkle_root_and_path(l1_log_index);
-        let msg_proof = L2ToL1LogProof {
-            proof,
-            root,
-            id: l1_log_index as u32,
+        let message_event = match receipt
+            .logs
+            .into_iter()
+            .filter(|log| {
+                log.address == L1_MESSENGER_ADDRESS
+                    && log.topics.len() == 3
+                    && log.topics[0] == *L1_MESSAGE_EVENT_SIGNATURE
+            })
+            .nth(index)
+        {
+            Some(event) => event,
+            None => return Ok(None),
         };
+        let sender = h256_to_account_address(&message_event.topics[1]);
+        let message = ethabi::decode(&[ethabi::ParamType::Bytes], &message_event.data.0)
+            .ok()
+            .and_then(|tokens| tokens.into_iter().next()?.into_bytes())
+            .ok_or_else(|| internal_error(METHOD_NAME, "malformed L1MessageSent event"))?;
+
+        let logs_tree = self
+            .l2_to_l1_logs_tree(&mut storage, l1_batch_number, METHOD_NAME)
+            .await?;
+        drop(storage);
 
+        // Each message is accompanied by an L2->L1 log sent by the L1 messenger, in the same order.
+        let proof = match logs_tree
+            .logs
+            .iter()
+            .enumerate()
+            .filter(|(_, log)| {
+                log.tx_number_in_block == l1_batch_tx_index && log.sender == L1_MESSENGER_ADDRESS
+            })
+            .nth(index)
+            .and_then(|(l1_log_index, _)| logs_tree.proof(l1_log_index))
+        {
+            Some(proof) => proof,
+            None => return Ok(None),
+        };
+
+        let params = WithdrawalFinalizationParams {
+            l1_batch_number,
+            l2_message_index: proof.id,
+            l2_tx_number_in_block: l1_batch_tx_index,
+            message: message.into(),
+            sender,
+            proof: proof.proof,
+        };
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
-        Ok(Some(msg_proof))
+        Ok(Some(params))
+    }
+
+    /// Returns the message with the specified index among messages sent via the L1 messenger in an L1 batch,
+    /// together with the proof of the accompanying L2->L1 log. Unlike [`Self::get_l2_to_l1_msg_proof_impl()`],
+    /// the message content is returned as well, so clients don't need to parse `L1MessageSent` events.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_l2_to_l1_message_proof_impl(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        index: u32,
+    ) -> Result<Option<L2ToL1MessageProof>, Web3Error> {
+        const METHOD_NAME: &str = "get_l2_to_l1_message_proof";
+
+        let start = Instant::now();
+        let mut storage = self.state.access_storage().await;
+        let Some(message) = storage
+            .events_dal()
+            .get_l1_messenger_message(l1_batch_number, index)
+            .await
+        else {
+            return Ok(None);
+        };
+        let Some(l2_to_l1_log_index) = message.l2_to_l1_log_index else {
+            return Ok(None);
+        };
+
+        let logs_tree = self
+            .l2_to_l1_logs_tree(&mut storage, l1_batch_number, METHOD_NAME)
+            .await?;
+        drop(storage);
+
+        let proof = logs_tree
+            .proof(l2_to_l1_log_index as usize)
+            .map(|proof| L2ToL1MessageProof {
+                l1_batch_number,
+                message_index: index,
+                tx_index_in_l1_batch: message.tx_index_in_l1_batch,
+                sender: message.sender,
+                message: message.message.into(),
+                proof: proof.proof,
+                id: proof.id,
+                root: proof.root,
+            });
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+        Ok(proof)
+    }
+
+    /// Returns L2->L1 logs of a sealed L1 batch together with the Merkle tree over them,
+    /// using the cached tree if possible.
+    async fn l2_to_l1_logs_tree(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+        method_name: &'static str,
+    ) -> Result<Arc<L2ToL1LogsTree>, Web3Error> {
+        if let Some(tree) = self.state.l2_to_l1_logs_cache.get(l1_batch_number) {
+            return Ok(tree);
+        }
+
+        let all_l1_logs_in_block = storage
+            .blocks_web3_dal()
+            .get_l2_to_l1_logs(l1_batch_number)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let tree = Arc::new(L2ToL1LogsTree::new(all_l1_logs_in_block));
+        self.state
+            .l2_to_l1_logs_cache
+            .insert(l1_batch_number, tree.clone());
+        Ok(tree)
     }
 
     #[tracing::instrument(skip(self))]
@@ -391,8 +607,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         let start = Instant::now();
         let l1_batch_number = self
             .state
-            .connection_pool
-            .access_storage_tagged("api")
+            .access_storage()
             .await
             .blocks_web3_dal()
             .get_sealed_l1_batch_number()
@@ -414,8 +629,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         let start = Instant::now();
         let minmax = self
             .state
-            .connection_pool
-            .access_storage_tagged("api")
+            .access_storage()
             .await
             .blocks_web3_dal()
             .get_miniblock_range_of_l1_batch(batch)
@@ -427,6 +641,70 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         minmax
     }
 
+    /// Returns net storage changes made by L1 batches in `(l1_batch_from, l1_batch_to]`, optionally
+    /// restricted to a single account.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_storage_diff_impl(
+        &self,
+        l1_batch_from: L1BatchNumber,
+        l1_batch_to: L1BatchNumber,
+        address: Option<Address>,
+        pagination: Option<StorageDiffPagination>,
+    ) -> Result<StorageDiff, Web3Error> {
+        const METHOD_NAME: &str = "get_storage_diff";
+
+        let start = Instant::now();
+        if l1_batch_from >= l1_batch_to {
+            return Err(Web3Error::InvalidL1BatchRange(format!(
+                "starting L1 batch #{} must precede ending L1 batch #{}",
+                l1_batch_from, l1_batch_to
+            )));
+        }
+        let pagination = pagination.unwrap_or_default();
+        let limit = pagination.limit.unwrap_or(MAX_STORAGE_DIFF_PAGE_SIZE);
+        if limit > MAX_STORAGE_DIFF_PAGE_SIZE {
+            return Err(Web3Error::TooManyItemsRequested(MAX_STORAGE_DIFF_PAGE_SIZE));
+        }
+
+        let mut storage = self.state.access_storage().await;
+        let mut blocks_dal = storage.blocks_web3_dal();
+        let from_range = blocks_dal
+            .get_miniblock_range_of_l1_batch(l1_batch_from)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let to_range = blocks_dal
+            .get_miniblock_range_of_l1_batch(l1_batch_to)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let (Some((_, from_last_miniblock)), Some((_, to_last_miniblock))) = (from_range, to_range)
+        else {
+            return Err(Web3Error::NoBlock);
+        };
+
+        let diffs = storage
+            .storage_logs_dal()
+            .get_storage_diff(
+                (from_last_miniblock + 1)..=to_last_miniblock,
+                address,
+                pagination.cursor,
+                limit,
+            )
+            .await;
+
+        let next_cursor = if diffs.len() == limit {
+            diffs.last().map(|(hashed_key, _)| *hashed_key)
+        } else {
+            None
+        };
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+        Ok(StorageDiff {
+            l1_batch_from,
+            l1_batch_to,
+            diffs: diffs.into_iter().map(|(_, diff)| diff).collect(),
+            next_cursor,
+        })
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_block_details_impl(
         &self,
@@ -435,22 +713,43 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         const METHOD_NAME: &str = "get_block_details";
 
         let start = Instant::now();
-        let block_details = self
-            .state
-            .connection_pool
-            .access_storage_tagged("api")
+        let operator_address = self.state.tx_sender.0.sender_config.fee_account_addr;
+        let mut storage = self.state.access_storage().await;
+        let block_details = storage
+            .api_read_models()
+            .block_details_dal()
+            .get_block_details(block_number, operator_address)
             .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        if block_details.is_some() {
+            metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+            return Ok(block_details);
+        }
+
+        // Miniblocks sealed before the read model was introduced are served from the core tables.
+        let block_details = storage
             .explorer()
             .blocks_dal()
-            .get_block_details(
-                block_number,
-                self.state.tx_sender.0.sender_config.fee_account_addr,
-            )
+            .get_block_details(block_number, operator_address)
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err));
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let block_details = match block_details {
+            Some(mut block_details) => {
+                block_details.protocol_version = storage
+                    .blocks_dal()
+                    .get_miniblock_protocol_version(block_number)
+                    .await;
+                block_details.seal_reason = storage
+                    .blocks_dal()
+                    .get_miniblock_seal_reason(block_number)
+                    .await;
+                Some(block_details)
+            }
+            None => None,
+        };
 
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
-        block_details
+        Ok(block_details)
     }
 
     #[tracing::instrument(skip(self))]
@@ -463,13 +762,12 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         let start = Instant::now();
         let transactions = self
             .state
-            .connection_pool
-            .access_storage_tagged("api")
+            .access_storage()
             .await
             .transactions_web3_dal()
             .get_raw_miniblock_transactions(block_number)
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err));
+            .map_err(|err| history_query_error(METHOD_NAME, err));
 
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
         transactions
@@ -483,15 +781,24 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         const METHOD_NAME: &str = "get_transaction_details";
 
         let start = Instant::now();
-        let mut tx_details = self
-            .state
-            .connection_pool
-            .access_storage_tagged("api")
-            .await
-            .transactions_web3_dal()
+        let mut storage = self.state.access_storage().await;
+        let tx_details = storage
+            .api_read_models()
+            .transaction_details_dal()
             .get_transaction_details(hash)
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err));
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        // Pending transactions and transactions included before the read model was introduced
+        // are served from the core tables.
+        let mut tx_details = match tx_details {
+            Some(tx_details) => Ok(Some(tx_details)),
+            None => storage
+                .transactions_web3_dal()
+                .get_transaction_details(hash)
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err)),
+        };
+        drop(storage);
 
         if let Some(proxy) = &self.state.tx_sender.0.proxy {
             // We're running an external node - we should query the main node directly
@@ -509,6 +816,203 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         tx_details
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn get_account_summary_impl(
+        &self,
+        address: Address,
+    ) -> Result<Option<AccountSummary>, Web3Error> {
+        const METHOD_NAME: &str = "get_account_summary";
+
+        let start = Instant::now();
+        let summary = self
+            .state
+            .access_storage()
+            .await
+            .api_read_models()
+            .account_summaries_dal()
+            .get_account_summary(address)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+        Ok(summary)
+    }
+
+    /// Returns replacements of transactions with the same initiator and nonce as the specified transaction,
+    /// so that clients can find out why a transaction they've submitted has disappeared.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_transaction_history_impl(
+        &self,
+        hash: H256,
+    ) -> Result<Option<TransactionHistory>, Web3Error> {
+        const METHOD_NAME: &str = "get_transaction_history";
+
+        let start = Instant::now();
+        let history = self
+            .state
+            .access_storage()
+            .await
+            .transaction_history_dal()
+            .get_transaction_history(hash)
+            .await;
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+        Ok(history)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_node_status_impl(&self) -> Result<NodeStatus, Web3Error> {
+        const METHOD_NAME: &str = "get_node_status";
+
+        let start = Instant::now();
+        let mut storage = self.state.access_storage().await;
+        let last_sealed_miniblock = storage
+            .blocks_web3_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let last_sealed_l1_batch = storage
+            .blocks_web3_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let mut blocks_dal = storage.blocks_dal();
+        let last_committed_l1_batch = blocks_dal.get_number_of_last_block_committed_on_eth().await;
+        let last_proven_l1_batch = blocks_dal.get_number_of_last_block_proven_on_eth().await;
+        let last_executed_l1_batch = blocks_dal.get_number_of_last_block_executed_on_eth().await;
+        let last_l1_batch_with_metadata = blocks_dal.get_last_block_number_with_metadata().await;
+        let eth_sender_next_nonce = storage.eth_sender_dal().get_next_nonce().await;
+        drop(storage);
+
+        let mempool = self.state.mempool.as_ref().map(|mempool| {
+            let stats = mempool.stats();
+            TxpoolStatus {
+                pending: (stats.l1_transactions + stats.pending_l2_transactions).into(),
+                queued: stats.queued_l2_transactions.into(),
+            }
+        });
+        let (components, operator_balance) = match &self.state.admin_controls {
+            Some(controls) => (
+                controls.component_health().await,
+                controls.operator_balance(),
+            ),
+            None => (vec![], None),
+        };
+        let status = NodeStatus {
+            last_sealed_miniblock,
+            last_sealed_l1_batch,
+            last_committed_l1_batch,
+            last_proven_l1_batch,
+            last_executed_l1_batch,
+            tree_lag: last_sealed_l1_batch
+                .0
+                .saturating_sub(last_l1_batch_with_metadata.0),
+            eth_sender_next_nonce,
+            mempool,
+            components,
+            operator_balance,
+        };
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+        Ok(status)
+    }
+
+    #[tracing::instrument(skip(self, txs))]
+    pub async fn simulate_bundle_impl(
+        &self,
+        txs: Vec<Bytes>,
+        block: Option<BlockId>,
+    ) -> Result<BundleSimulation, Web3Error> {
+        const METHOD_NAME: &str = "simulate_bundle";
+
+        let start = Instant::now();
+        if txs.is_empty() {
+            return Err(Web3Error::InvalidBundle("bundle is empty".to_owned()));
+        }
+        let max_bundle_size = self.state.api_config.max_bundle_size;
+        if txs.len() > max_bundle_size {
+            return Err(Web3Error::TooManyItemsRequested(max_bundle_size));
+        }
+        let txs = txs
+            .into_iter()
+            .map(|tx_bytes| {
+                let (mut tx, hash) = self.state.parse_transaction_bytes(&tx_bytes.0)?;
+                tx.set_input(tx_bytes.0, hash);
+                Ok(tx)
+            })
+            .collect::<Result<Vec<_>, Web3Error>>()?;
+        let tx_hashes: Vec<_> = txs.iter().map(|tx| tx.hash()).collect();
+        let gas_limits: Vec<_> = txs.iter().map(|tx| tx.common_data.fee.gas_limit).collect();
+
+        let block = block.unwrap_or(BlockId::Number(BlockNumber::Pending));
+        let mut connection = self.state.access_storage().await;
+        let block_args = BlockArgs::new(&mut connection, block)
+            .await
+            .map_err(|err| history_query_error(METHOD_NAME, err))?
+            .ok_or(Web3Error::NoBlock)?;
+        drop(connection);
+
+        let block_number = block_args.resolved_block_number();
+        let results = self.state.tx_sender.simulate_bundle(block_args, txs).await;
+
+        let mut transactions = Vec::with_capacity(results.len());
+        let mut total_gas_used = U256::zero();
+        for (i, result) in results.into_iter().enumerate() {
+            let tx_hash = tx_hashes[i];
+            let result = result.map_err(|reason| {
+                let err = SubmitTxError::from(SandboxExecutionError::from(reason));
+                let message = format!("transaction #{i} ({tx_hash:?}) was rejected: {err}");
+                Web3Error::SubmitTransactionError(message, err.data())
+            })?;
+
+            let gas_refunded = U256::from(result.gas_refunded);
+            let gas_used = gas_limits[i].saturating_sub(gas_refunded);
+            total_gas_used += gas_used;
+            let revert_reason = match result.status {
+                TxExecutionStatus::Success => None,
+                TxExecutionStatus::Failure => revert_reason_from_call_traces(&result.call_traces),
+            };
+            let logs = result
+                .result
+                .logs
+                .events
+                .iter()
+                .enumerate()
+                .map(|(index_in_tx, event)| Log {
+                    address: event.address,
+                    topics: event.indexed_topics.clone(),
+                    data: Bytes(event.value.clone()),
+                    block_hash: None,
+                    block_number: None,
+                    l1_batch_number: None,
+                    transaction_hash: Some(tx_hash),
+                    transaction_index: None,
+                    log_index: None,
+                    transaction_log_index: Some(U256::from(index_in_tx)),
+                    log_type: None,
+                    removed: None,
+                })
+                .collect();
+
+            transactions.push(BundleTransactionResult {
+                transaction_hash: tx_hash,
+                status: U64::from((result.status == TxExecutionStatus::Success) as u8),
+                gas_used,
+                gas_refunded,
+                revert_reason,
+                logs,
+                state_diffs: storage_slot_diffs(&result.result.logs.storage_logs),
+            });
+        }
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+        Ok(BundleSimulation {
+            block_number: block_number.0.into(),
+            transactions,
+            total_gas_used,
+        })
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_l1_batch_details_impl(
         &self,
@@ -517,19 +1021,78 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         const METHOD_NAME: &str = "get_l1_batch";
 
         let start = Instant::now();
-        let l1_batch = self
-            .state
-            .connection_pool
-            .access_storage_tagged("api")
-            .await
-            .explorer()
-            .blocks_dal()
+        let mut storage = self.state.access_storage().await;
+        let mut blocks_dal = storage.explorer().blocks_dal();
+        let l1_batch = blocks_dal
             .get_l1_batch_details(batch_number)
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err));
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let l1_batch = match l1_batch {
+            Some(mut l1_batch) => {
+                let pipeline_status = blocks_dal
+                    .get_l1_batch_pipeline_status(batch_number)
+                    .await
+                    .map_err(|err| internal_error(METHOD_NAME, err))?;
+                l1_batch.pipeline_status = Some(pipeline_status);
+                l1_batch.protocol_version = storage
+                    .blocks_dal()
+                    .get_l1_batch_protocol_version(batch_number)
+                    .await;
+                l1_batch.seal_reason = storage
+                    .blocks_dal()
+                    .get_l1_batch_seal_reason(batch_number)
+                    .await;
+                if let Some(delay) = self.state.api_config.l1_batch_execution_delay {
+                    if l1_batch.executed_at.is_none() {
+                        let delay = chrono::Duration::from_std(delay)
+                            .expect("execution delay is out of range");
+                        l1_batch.scheduled_execution_at =
+                            l1_batch.proven_at.map(|proven_at| proven_at + delay);
+                    }
+                }
+                Some(l1_batch)
+            }
+            None => None,
+        };
 
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
-        l1_batch
+        Ok(l1_batch)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_batch_resource_usage_impl(
+        &self,
+        batch_number: L1BatchNumber,
+    ) -> Result<Option<BatchResourceUsage>, Web3Error> {
+        const METHOD_NAME: &str = "get_batch_resource_usage";
+
+        let start = Instant::now();
+        let mut storage = self.state.access_storage().await;
+        let mut blocks_dal = storage.blocks_dal();
+        let Some(usage) = blocks_dal.get_l1_batch_resource_usage(batch_number).await else {
+            metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+            return Ok(None);
+        };
+
+        let mut resource_usage = BatchResourceUsage::new(batch_number, usage);
+        resource_usage.commit = blocks_dal
+            .get_l1_batch_operation_cost(batch_number, AggregatedActionType::CommitBlocks)
+            .await
+            .map(Into::into);
+        resource_usage.prove = blocks_dal
+            .get_l1_batch_operation_cost(
+                batch_number,
+                AggregatedActionType::PublishProofBlocksOnchain,
+            )
+            .await
+            .map(Into::into);
+        resource_usage.execute = blocks_dal
+            .get_l1_batch_operation_cost(batch_number, AggregatedActionType::ExecuteBlocks)
+            .await
+            .map(Into::into);
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+        Ok(Some(resource_usage))
     }
 
     #[tracing::instrument(skip(self))]
@@ -539,8 +1102,7 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         let start = Instant::now();
         let bytecode = self
             .state
-            .connection_pool
-            .access_storage_tagged("api")
+            .access_storage()
             .await
             .storage_dal()
             .get_factory_dep(hash)
@@ -566,6 +1128,120 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         gas_price.into()
     }
 
+    #[tracing::instrument(skip(self))]
+    pub fn get_gas_per_pubdata_impl(&self) -> U64 {
+        const METHOD_NAME: &str = "get_gas_per_pubdata";
+
+        let start = Instant::now();
+        let gas_per_pubdata = self.state.tx_sender.gas_per_pubdata();
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+        gas_per_pubdata.into()
+    }
+
+    /// Returns the protocol version with the specified ID, or the latest version registered on L1
+    /// if the ID is not specified.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_protocol_version_impl(
+        &self,
+        version_id: Option<u16>,
+    ) -> Option<ProtocolVersion> {
+        const METHOD_NAME: &str = "get_protocol_version";
+
+        let start = Instant::now();
+        let mut storage = self.state.access_storage().await;
+        let mut protocol_versions_dal = storage.protocol_versions_dal();
+        let version_id = match version_id {
+            Some(version_id) => Some(ProtocolVersionId(version_id)),
+            None => protocol_versions_dal.latest_version_id().await,
+        };
+        let protocol_version = match version_id {
+            Some(version_id) => protocol_versions_dal.get_protocol_version(version_id).await,
+            None => None,
+        };
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+        protocol_version
+    }
+
+    /// Returns the operator address collecting fees in the latest miniblock. If no miniblocks
+    /// are stored yet, returns the configured fee account.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_operator_address_impl(&self) -> Address {
+        const METHOD_NAME: &str = "get_operator_address";
+
+        let start = Instant::now();
+        let operator_address = self
+            .state
+            .access_storage()
+            .await
+            .blocks_dal()
+            .get_last_miniblock_fee_account_address()
+            .await
+            .unwrap_or(self.state.tx_sender.0.sender_config.fee_account_addr);
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+        operator_address
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn get_rejection_stats_impl(&self) -> RejectionStats {
+        const METHOD_NAME: &str = "get_rejection_stats";
+
+        let start = Instant::now();
+        let mempool = self.state.mempool.as_ref().map(|mempool| {
+            let stats = mempool.filtered_tx_stats();
+            [
+                (FilterMismatch::FeeTooLow, stats.fee_too_low),
+                (
+                    FilterMismatch::GasPerPubdataTooLow,
+                    stats.gas_per_pubdata_too_low,
+                ),
+            ]
+            .iter()
+            .map(|(reason, count)| (reason.as_str().to_owned(), *count))
+            .collect()
+        });
+        let stats = RejectionStats {
+            tx_sender: self.state.tx_sender.rejection_stats(),
+            mempool,
+        };
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+        stats
+    }
+
+    /// Submits a raw transaction that must not be included into a miniblock with the timestamp greater than
+    /// or equal to `valid_until` (a UNIX timestamp in seconds). The deadline is not signed, so it's best-effort only:
+    /// the same signed transaction can be resubmitted with another deadline (e.g., via `eth_sendRawTransaction`).
+    #[tracing::instrument(skip(self, tx_bytes))]
+    pub async fn send_raw_transaction_with_deadline_impl(
+        &self,
+        tx_bytes: Bytes,
+        valid_until: U64,
+    ) -> Result<H256, Web3Error> {
+        const METHOD_NAME: &str = "send_raw_transaction_with_deadline";
+
+        let start = Instant::now();
+        let (mut tx, hash) = self.state.parse_transaction_bytes(&tx_bytes.0)?;
+        tx.set_input(tx_bytes.0, hash);
+        tx.common_data.valid_until = Some(valid_until.as_u64());
+
+        let submit_result = self.state.tx_sender.submit_tx(tx).await;
+        let submit_result = submit_result.map(|_| hash).map_err(|err| {
+            vlog::debug!("Send raw transaction with deadline error: {err}");
+            metrics::counter!(
+                "api.submit_tx_error",
+                1,
+                "reason" => err.grafana_error_code()
+            );
+            Web3Error::SubmitTransactionError(err.to_string(), err.data())
+        });
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => METHOD_NAME);
+        submit_result
+    }
+
     #[cfg(feature = "openzeppelin_tests")]
     /// Saves contract bytecode to memory.
     pub fn set_known_bytecode_impl(&self, bytecode: Bytes) -> bool {
@@ -574,3 +1250,33 @@ impl<G: L1GasPriceProvider> ZksNamespace<G> {
         true
     }
 }
+
+/// Returns the revert reason of the first failed top-level call of a transaction.
+fn revert_reason_from_call_traces(call_traces: &[Call]) -> Option<String> {
+    call_traces
+        .iter()
+        .find_map(|call| call.revert_reason.clone().or_else(|| call.error.clone()))
+}
+
+/// Computes storage slots changed by a transaction from its storage logs, in the order the slots were first written.
+/// Slots written back to their initial values are not included.
+fn storage_slot_diffs(storage_logs: &[StorageLogQuery]) -> Vec<StorageSlotDiff> {
+    let mut diffs: Vec<StorageSlotDiff> = vec![];
+    let mut diff_indices = HashMap::new();
+    for log in storage_logs.iter().filter(|log| log.log_query.rw_flag) {
+        let storage_log = StorageLog::from_log_query(log);
+        let key = storage_log.key;
+        let index = *diff_indices.entry(key).or_insert_with(|| {
+            diffs.push(StorageSlotDiff {
+                address: *key.address(),
+                key: *key.key(),
+                previous_value: u256_to_h256(log.log_query.read_value),
+                value: H256::zero(),
+            });
+            diffs.len() - 1
+        });
+        diffs[index].value = storage_log.value;
+    }
+    diffs.retain(|diff| diff.previous_value != diff.value);
+    diffs
+}
@@ -1,12 +1,26 @@
+use std::collections::{HashMap, HashSet};
+
 use jsonrpc_pubsub::typed;
 use tokio::sync::watch;
 use tokio::time::{interval, Duration, Instant};
 
 use zksync_dal::ConnectionPool;
-use zksync_types::MiniblockNumber;
+use zksync_types::{api::TxLifecycleStage, MiniblockNumber};
 use zksync_web3_decl::types::{PubSubFilter, PubSubResult};
 
-use super::namespaces::SubscriptionMap;
+use super::namespaces::{SubscriptionMap, TxStatusSubscription};
+use crate::l1_batch_status_bus::{L1BatchStage, L1BatchStatusReceiver};
+
+/// L1 batch stages that move transactions to the corresponding lifecycle stages.
+const TX_STATUS_L1_BATCH_STAGES: [L1BatchStage; 4] = [
+    L1BatchStage::Sealed,
+    L1BatchStage::Committed,
+    L1BatchStage::Proven,
+    L1BatchStage::Executed,
+];
+/// Maximum number of consecutive skipped DB polls in `notify_tx_statuses()`. Polls cannot be skipped
+/// indefinitely because the replica DB may lag behind L1 batch status updates.
+const MAX_SKIPPED_TX_STATUS_POLLS: usize = 10;
 
 pub async fn notify_blocks(
     subscribers: SubscriptionMap<typed::Sink<PubSubResult>>,
@@ -117,6 +131,7 @@ pub async fn notify_logs(
     subscribers: SubscriptionMap<(typed::Sink<PubSubResult>, PubSubFilter)>,
     connection_pool: ConnectionPool,
     polling_interval: Duration,
+    protected_data_access: bool,
     stop_receiver: watch::Receiver<bool>,
 ) {
     let mut last_block_number = connection_pool
@@ -136,13 +151,16 @@ pub async fn notify_logs(
         timer.tick().await;
 
         let start = Instant::now();
-        let new_logs = connection_pool
-            .access_storage_tagged("api")
-            .await
+        let mut storage = connection_pool.access_storage_tagged("api").await;
+        if !protected_data_access {
+            storage.restrict_protected_data_access();
+        }
+        let new_logs = storage
             .events_web3_dal()
             .get_all_logs(last_block_number)
             .await
             .unwrap();
+        drop(storage);
         metrics::histogram!("api.web3.pubsub.db_poll_latency", start.elapsed(), "subscription_type" => "logs");
         if !new_logs.is_empty() {
             last_block_number =
@@ -171,3 +189,108 @@ pub async fn notify_logs(
         }
     }
 }
+
+/// Notifies `zks_subscribe("txStatus")` subscribers about lifecycle stage changes of the subscribed
+/// transactions. Since the stages are polled, a subscriber may not observe some intermediate stages
+/// (e.g., if a batch was committed and proven between two polls); only the latest stage is reported.
+/// Subscriptions are removed once a final stage is reported.
+///
+/// If `l1_batch_status` is provided and receives updates for all L1 batch stages (i.e., the components
+/// changing L1 batch statuses run in the same process), Postgres is only polled for transactions
+/// not sealed in an L1 batch yet, or after an L1 batch status update.
+pub async fn notify_tx_statuses(
+    subscribers: SubscriptionMap<(typed::Sink<PubSubResult>, TxStatusSubscription)>,
+    connection_pool: ConnectionPool,
+    polling_interval: Duration,
+    mut l1_batch_status: Option<L1BatchStatusReceiver>,
+    stop_receiver: watch::Receiver<bool>,
+) {
+    let mut timer = interval(polling_interval);
+    let mut skipped_polls = 0;
+    loop {
+        if *stop_receiver.borrow() {
+            vlog::info!("Stop signal received, pubsub_tx_status_notifier is shutting down");
+            break;
+        }
+
+        timer.tick().await;
+
+        let (tx_hashes, awaiting_batch) = {
+            let subscribers = subscribers.read().await;
+            let tx_hashes = subscribers
+                .values()
+                .map(|(_, subscription)| subscription.tx_hash)
+                .collect::<HashSet<_>>();
+            let awaiting_batch = subscribers.values().any(|(_, subscription)| {
+                subscription
+                    .last_stage
+                    .map_or(true, |stage| stage < TxLifecycleStage::SealedInBatch)
+            });
+            (tx_hashes, awaiting_batch)
+        };
+        if tx_hashes.is_empty() {
+            continue;
+        }
+        if let Some(receiver) = &mut l1_batch_status {
+            let l1_batch_status_updated = !receiver.poll_updates().is_empty();
+            let bus_is_complete = TX_STATUS_L1_BATCH_STAGES
+                .iter()
+                .all(|&stage| receiver.latest(stage).is_some());
+            let can_skip = bus_is_complete && !awaiting_batch && !l1_batch_status_updated;
+            if can_skip && skipped_polls < MAX_SKIPPED_TX_STATUS_POLLS {
+                skipped_polls += 1;
+                metrics::increment_counter!("api.web3.pubsub.skipped_db_polls", "subscription_type" => "tx_status");
+                continue;
+            }
+        }
+        skipped_polls = 0;
+        let tx_hashes: Vec<_> = tx_hashes.into_iter().collect();
+
+        let start = Instant::now();
+        let statuses = connection_pool
+            .access_storage_tagged("api")
+            .await
+            .transactions_web3_dal()
+            .get_tx_lifecycle_statuses(&tx_hashes)
+            .await
+            .unwrap();
+        metrics::histogram!("api.web3.pubsub.db_poll_latency", start.elapsed(), "subscription_type" => "tx_status");
+        let statuses: HashMap<_, _> = statuses
+            .into_iter()
+            .map(|status| (status.tx_hash, status))
+            .collect();
+
+        let start = Instant::now();
+        let mut subscribers = subscribers.write().await;
+        let mut finished_subscriptions = vec![];
+        for (id, (sink, subscription)) in subscribers.iter_mut() {
+            let status = match statuses.get(&subscription.tx_hash) {
+                Some(status) => status,
+                None => continue,
+            };
+            if subscription.last_stage == Some(status.stage) {
+                continue;
+            }
+
+            if sink
+                .notify(Ok(PubSubResult::TxStatus(status.clone())))
+                .is_err()
+            {
+                // Subscriber disconnected.
+                finished_subscriptions.push(id.clone());
+                continue;
+            }
+            metrics::counter!("api.web3.pubsub.notify", 1, "subscription_type" => "tx_status");
+            subscription.last_stage = Some(status.stage);
+            if status.stage.is_final() {
+                finished_subscriptions.push(id.clone());
+            }
+        }
+        for id in finished_subscriptions {
+            subscribers.remove(&id);
+            metrics::decrement_gauge!("api.web3.pubsub.active_subscribers", 1f64, "subscription_type" => "tx_status");
+        }
+        drop(subscribers);
+        metrics::histogram!("api.web3.pubsub.notify_subscribers_latency", start.elapsed(), "subscription_type" => "tx_status");
+    }
+}
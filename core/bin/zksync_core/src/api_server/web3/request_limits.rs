@@ -0,0 +1,596 @@
+//! Middleware enforcing per-client request limits for the Web3 API servers.
+//!
+//! The limits are applied before a request reaches the JSON RPC handler:
+//! - request body size is capped, oversized requests are rejected with `413`;
+//! - the number of calls in a batch request is capped;
+//! - calls are rate limited per client IP, separately for cheap read methods and for methods
+//!   that spawn a VM (sandbox executions). Limited requests are rejected with `429`.
+//!
+//! The client IP is the peer address of the connection. Forwarding headers are only taken into account
+//! for requests coming from the trusted proxies (i.e., the load balancers in front of the server).
+//!
+//! WebSocket calls don't pass through the HTTP middleware; they are limited by [`ConnectionLimiter`]
+//! separately for each connection. Servers of the `jsonrpc` backend apply the limits via a `jsonrpc` middleware
+//! instead; since its HTTP server doesn't expose peer addresses, it only supports body and batch size limits.
+
+// Built-in uses
+use std::{
+    fmt,
+    net::{IpAddr, SocketAddr},
+    num::NonZeroU32,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+// External uses
+use futures::future::BoxFuture;
+use governor::{DefaultDirectRateLimiter, DefaultKeyedRateLimiter, Quota, RateLimiter};
+use hyper::{body::HttpBody, header, Body, HeaderMap, Request, Response, StatusCode};
+use serde_json::{json, Value};
+use tower::{Layer, Service};
+
+// Workspace uses
+use zksync_config::configs::api::Web3JsonRpcConfig;
+use zksync_web3_decl::jsonrpsee::server::logger::{
+    HttpRequest, Logger, MethodKind, Params, SuccessOrError, TransportProtocol,
+};
+
+/// JSON RPC error code returned for rate-limited requests.
+pub const RATE_LIMITED_ERROR_CODE: i64 = -32005;
+/// JSON RPC error code returned for invalid requests (e.g. too large batches).
+const INVALID_REQUEST_ERROR_CODE: i64 = -32600;
+
+/// Class of the RPC method, used to pick the rate limit applied to the call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MethodClass {
+    /// Cheap method served from the database.
+    Read,
+    /// Method that spawns a VM to execute a transaction in the sandbox.
+    Execution,
+}
+
+impl MethodClass {
+    pub fn for_method(method: &str) -> Self {
+        match method {
+            "eth_call"
+            | "eth_estimateGas"
+            | "eth_sendRawTransaction"
+            | "zks_sendRawTransactionWithDeadline"
+            | "zks_estimateFee"
+            | "zks_estimateGasL1ToL2"
+            | "debug_traceCall"
+            | "debug_traceTransaction"
+            | "debug_traceBlockByNumber"
+            | "debug_traceBlockByHash" => Self::Execution,
+            _ => Self::Read,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Execution => "execution",
+        }
+    }
+}
+
+/// Limits applied to the incoming requests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestLimitsConfig {
+    /// Max number of read calls per second from a single IP.
+    pub reads_per_second_per_ip: Option<NonZeroU32>,
+    /// Max number of sandbox executions per second from a single IP.
+    pub executions_per_second_per_ip: Option<NonZeroU32>,
+    /// Max number of calls in a single batch request.
+    pub max_batch_request_size: usize,
+    /// Max size of the request body in bytes.
+    pub max_request_body_size: u32,
+    /// Addresses of the proxies allowed to set forwarding headers.
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+impl RequestLimitsConfig {
+    pub fn from_config(config: &Web3JsonRpcConfig) -> Self {
+        Self {
+            reads_per_second_per_ip: config.reads_per_second_per_ip.and_then(NonZeroU32::new),
+            executions_per_second_per_ip: config
+                .executions_per_second_per_ip
+                .and_then(NonZeroU32::new),
+            max_batch_request_size: config.max_batch_request_size(),
+            max_request_body_size: config.max_request_body_size(),
+            trusted_proxies: config.trusted_proxies(),
+        }
+    }
+
+    /// Returns `true` if calls are rate limited.
+    pub fn has_rate_limits(&self) -> bool {
+        self.reads_per_second_per_ip.is_some() || self.executions_per_second_per_ip.is_some()
+    }
+
+    /// Checks the number of calls in a batch request.
+    pub fn check_batch_size(&self, size: usize) -> Result<(), LimitViolation> {
+        if size > self.max_batch_request_size {
+            return Err(LimitViolation::BatchTooLarge {
+                size,
+                limit: self.max_batch_request_size,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Reason for the request rejection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LimitViolation {
+    BodyTooLarge,
+    BatchTooLarge { size: usize, limit: usize },
+    RateLimited(MethodClass),
+}
+
+impl LimitViolation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BodyTooLarge => "body_too_large",
+            Self::BatchTooLarge { .. } => "batch_too_large",
+            Self::RateLimited(MethodClass::Read) => "rate_limited_read",
+            Self::RateLimited(MethodClass::Execution) => "rate_limited_execution",
+        }
+    }
+
+    fn into_response(self) -> Response<Body> {
+        let (status, code) = match self {
+            Self::BodyTooLarge => (StatusCode::PAYLOAD_TOO_LARGE, INVALID_REQUEST_ERROR_CODE),
+            Self::BatchTooLarge { .. } => (StatusCode::BAD_REQUEST, INVALID_REQUEST_ERROR_CODE),
+            Self::RateLimited(_) => (StatusCode::TOO_MANY_REQUESTS, RATE_LIMITED_ERROR_CODE),
+        };
+        let message = self.to_string();
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": Value::Null,
+            "error": { "code": code, "message": message },
+        });
+
+        Response::builder()
+            .status(status)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+}
+
+impl fmt::Display for LimitViolation {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BodyTooLarge => formatter.write_str("Request body is too large"),
+            Self::BatchTooLarge { size, limit } => write!(
+                formatter,
+                "Batch request contains {} calls, max allowed is {}",
+                size, limit
+            ),
+            Self::RateLimited(class) => write!(
+                formatter,
+                "Rate limit exceeded for {} methods",
+                class.as_str()
+            ),
+        }
+    }
+}
+
+/// Stateful limiter shared by all the connections of a single server.
+#[derive(Debug)]
+pub struct RequestLimiter {
+    config: RequestLimitsConfig,
+    reads: Option<DefaultKeyedRateLimiter<IpAddr>>,
+    executions: Option<DefaultKeyedRateLimiter<IpAddr>>,
+}
+
+impl RequestLimiter {
+    pub fn new(config: RequestLimitsConfig) -> Self {
+        Self {
+            reads: config
+                .reads_per_second_per_ip
+                .map(|limit| RateLimiter::keyed(Quota::per_second(limit))),
+            executions: config
+                .executions_per_second_per_ip
+                .map(|limit| RateLimiter::keyed(Quota::per_second(limit))),
+            config,
+        }
+    }
+
+    /// Checks the size of the request body and returns classes of the calls in it.
+    /// Bodies that aren't valid JSON RPC requests are let through so that the RPC server
+    /// could respond with a proper parse error.
+    fn check_body(&self, body: &[u8]) -> Result<Vec<MethodClass>, LimitViolation> {
+        if body.len() > self.config.max_request_body_size as usize {
+            return Err(LimitViolation::BodyTooLarge);
+        }
+
+        let parsed = serde_json::from_slice::<Value>(body).ok();
+        let methods: Vec<&str> = match &parsed {
+            Some(Value::Array(calls)) => {
+                self.config.check_batch_size(calls.len())?;
+                calls.iter().filter_map(extract_method).collect()
+            }
+            Some(call) => extract_method(call).into_iter().collect(),
+            None => vec![],
+        };
+        Ok(methods.into_iter().map(MethodClass::for_method).collect())
+    }
+
+    /// Checks the rate limits for calls of the specified classes made by the client with the provided IP.
+    fn check_rate(&self, client_ip: IpAddr, classes: &[MethodClass]) -> Result<(), LimitViolation> {
+        for &class in classes {
+            let limiter = match class {
+                MethodClass::Read => &self.reads,
+                MethodClass::Execution => &self.executions,
+            };
+            if let Some(limiter) = limiter {
+                if limiter.check_key(&client_ip).is_err() {
+                    return Err(LimitViolation::RateLimited(class));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the state for the clients that were not active recently.
+    pub fn retain_recent(&self) {
+        for limiter in [&self.reads, &self.executions].iter().copied().flatten() {
+            limiter.retain_recent();
+        }
+    }
+}
+
+/// Rate limits for calls made via a single WebSocket connection.
+#[derive(Debug)]
+pub struct ConnectionLimiter {
+    reads: Option<DefaultDirectRateLimiter>,
+    executions: Option<DefaultDirectRateLimiter>,
+}
+
+impl ConnectionLimiter {
+    pub fn new(config: &RequestLimitsConfig) -> Self {
+        Self {
+            reads: config
+                .reads_per_second_per_ip
+                .map(|limit| RateLimiter::direct(Quota::per_second(limit))),
+            executions: config
+                .executions_per_second_per_ip
+                .map(|limit| RateLimiter::direct(Quota::per_second(limit))),
+        }
+    }
+
+    pub fn check_call(&self, method: &str) -> Result<(), LimitViolation> {
+        let class = MethodClass::for_method(method);
+        let limiter = match class {
+            MethodClass::Read => &self.reads,
+            MethodClass::Execution => &self.executions,
+        };
+        match limiter {
+            Some(limiter) if limiter.check().is_err() => Err(LimitViolation::RateLimited(class)),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn extract_method(call: &Value) -> Option<&str> {
+    call.get("method")?.as_str()
+}
+
+/// Determines the client IP. If the request comes from a trusted proxy, the client IP is the last address
+/// in the `X-Forwarded-For` chain not belonging to a trusted proxy (or the `X-Real-IP` value if there's no such address).
+/// Otherwise, forwarding headers may be forged by the client, so the peer address is used.
+fn client_ip(peer_addr: SocketAddr, headers: &HeaderMap, trusted_proxies: &[IpAddr]) -> IpAddr {
+    let peer_ip = peer_addr.ip();
+    if !trusted_proxies.contains(&peer_ip) {
+        return peer_ip;
+    }
+
+    let forwarded_ip = headers
+        .get("x-forwarded-for")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| {
+            value
+                .rsplit(',')
+                .filter_map(|ip| ip.trim().parse::<IpAddr>().ok())
+                .find(|ip| !trusted_proxies.contains(ip))
+        });
+    let real_ip = || {
+        headers
+            .get("x-real-ip")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|ip| ip.trim().parse().ok())
+    };
+    forwarded_ip.or_else(real_ip).unwrap_or(peer_ip)
+}
+
+/// Client IP resolved by [`ClientIpLogger`] for a particular request.
+#[derive(Debug, Clone, Default)]
+struct ClientIpSlot(Arc<Mutex<Option<IpAddr>>>);
+
+impl ClientIpSlot {
+    fn get(&self) -> Option<IpAddr> {
+        *self.0.lock().unwrap()
+    }
+
+    fn set(&self, ip: IpAddr) {
+        *self.0.lock().unwrap() = Some(ip);
+    }
+}
+
+/// `jsonrpsee` logger resolving client IPs of the incoming requests. `jsonrpsee` only exposes the peer address
+/// to the logger, so the logger passes the resolved IP to [`RequestLimitsService`] via the request extensions.
+#[derive(Debug, Clone, Default)]
+pub struct ClientIpLogger {
+    trusted_proxies: Arc<[IpAddr]>,
+}
+
+impl ClientIpLogger {
+    pub fn new(config: Option<&RequestLimitsConfig>) -> Self {
+        let trusted_proxies = config.map_or_else(Vec::new, |config| config.trusted_proxies.clone());
+        Self {
+            trusted_proxies: trusted_proxies.into(),
+        }
+    }
+}
+
+impl Logger for ClientIpLogger {
+    type Instant = ();
+
+    fn on_connect(&self, remote_addr: SocketAddr, request: &HttpRequest, _: TransportProtocol) {
+        if let Some(slot) = request.extensions().get::<ClientIpSlot>() {
+            slot.set(client_ip(
+                remote_addr,
+                request.headers(),
+                &self.trusted_proxies,
+            ));
+        }
+    }
+
+    fn on_request(&self, _: TransportProtocol) {}
+
+    fn on_call(&self, _: &str, _: Params, _: MethodKind, _: TransportProtocol) {}
+
+    fn on_result(&self, _: &str, _: SuccessOrError, _: (), _: TransportProtocol) {}
+
+    fn on_response(&self, _: &str, _: (), _: TransportProtocol) {}
+
+    fn on_disconnect(&self, _: SocketAddr, _: TransportProtocol) {}
+}
+
+/// Reads the request body, bailing out as soon as it exceeds the `limit`.
+async fn read_body(mut body: Body, limit: usize) -> Result<Vec<u8>, LimitViolation> {
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        // Let the RPC server handle the broken bodies.
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(_) => break,
+        };
+        if bytes.len() + chunk.len() > limit {
+            return Err(LimitViolation::BodyTooLarge);
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+/// `tower` layer applying [`RequestLimiter`] to the HTTP requests.
+#[derive(Debug, Clone)]
+pub struct RequestLimitsLayer {
+    limiter: Arc<RequestLimiter>,
+}
+
+impl RequestLimitsLayer {
+    pub fn new(limiter: Arc<RequestLimiter>) -> Self {
+        Self { limiter }
+    }
+}
+
+impl<S> Layer<S> for RequestLimitsLayer {
+    type Service = RequestLimitsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestLimitsService {
+            inner,
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RequestLimitsService<S> {
+    inner: S,
+    limiter: Arc<RequestLimiter>,
+}
+
+impl<S> Service<Request<Body>> for RequestLimitsService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // The service that was polled for readiness must be the one handling the request.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let limiter = self.limiter.clone();
+
+        Box::pin(async move {
+            let reject = |violation: LimitViolation| {
+                metrics::increment_counter!(
+                    "api.web3.rejected_requests",
+                    "reason" => violation.as_str()
+                );
+                Ok(violation.into_response())
+            };
+
+            // WebSocket upgrades don't carry calls, they are only checked against the read limit.
+            let is_upgrade = request.headers().contains_key(header::UPGRADE);
+            let (mut parts, body) = request.into_parts();
+            let (body, classes) = if is_upgrade {
+                (body, vec![MethodClass::Read])
+            } else {
+                let max_size = limiter.config.max_request_body_size as usize;
+                let body = match read_body(body, max_size).await {
+                    Ok(body) => body,
+                    Err(violation) => return reject(violation),
+                };
+                match limiter.check_body(&body) {
+                    Ok(classes) => (Body::from(body), classes),
+                    Err(violation) => return reject(violation),
+                }
+            };
+
+            // The server reports the peer address to the logger synchronously when the request is dispatched,
+            // while the request itself is only processed once the returned future is polled. Thus, dropping
+            // the future for a rate-limited request is safe.
+            let client_ip_slot = ClientIpSlot::default();
+            parts.extensions.insert(client_ip_slot.clone());
+            let response = inner.call(Request::from_parts(parts, body));
+            if let Some(client_ip) = client_ip_slot.get() {
+                if let Err(violation) = limiter.check_rate(client_ip, &classes) {
+                    return reject(violation);
+                }
+            }
+            response.await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    fn config(reads: u32, executions: u32) -> RequestLimitsConfig {
+        RequestLimitsConfig {
+            reads_per_second_per_ip: NonZeroU32::new(reads),
+            executions_per_second_per_ip: NonZeroU32::new(executions),
+            max_batch_request_size: 3,
+            max_request_body_size: 1_024,
+            trusted_proxies: vec!["10.0.0.1".parse().unwrap()],
+        }
+    }
+
+    fn call(method: &str) -> Value {
+        json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": [] })
+    }
+
+    #[test]
+    fn method_classes() {
+        assert_eq!(MethodClass::for_method("eth_call"), MethodClass::Execution);
+        assert_eq!(
+            MethodClass::for_method("zks_estimateFee"),
+            MethodClass::Execution
+        );
+        assert_eq!(
+            MethodClass::for_method("eth_blockNumber"),
+            MethodClass::Read
+        );
+    }
+
+    #[test]
+    fn rate_limits_are_applied_per_class_and_ip() {
+        let limiter = RequestLimiter::new(config(2, 1));
+        let ip: IpAddr = "1.2.3.4".parse().unwrap();
+        let other_ip: IpAddr = "4.3.2.1".parse().unwrap();
+        let execution = [MethodClass::Execution];
+        let read = [MethodClass::Read];
+
+        limiter.check_rate(ip, &execution).unwrap();
+        assert_eq!(
+            limiter.check_rate(ip, &execution),
+            Err(LimitViolation::RateLimited(MethodClass::Execution))
+        );
+        // Reads and other clients are not affected.
+        limiter.check_rate(ip, &read).unwrap();
+        limiter.check_rate(other_ip, &execution).unwrap();
+    }
+
+    #[test]
+    fn batch_and_body_size_limits() {
+        let limiter = RequestLimiter::new(config(100, 100));
+
+        let batch = Value::Array(vec![call("eth_blockNumber"); 4]).to_string();
+        assert_eq!(
+            limiter.check_body(batch.as_bytes()),
+            Err(LimitViolation::BatchTooLarge { size: 4, limit: 3 })
+        );
+        let batch = json!([call("eth_blockNumber"), call("eth_call")]).to_string();
+        assert_eq!(
+            limiter.check_body(batch.as_bytes()).unwrap(),
+            [MethodClass::Read, MethodClass::Execution]
+        );
+
+        let large_body = vec![b' '; 2_048];
+        assert_eq!(
+            limiter.check_body(&large_body),
+            Err(LimitViolation::BodyTooLarge)
+        );
+        // Malformed requests are left for the RPC server to handle.
+        assert_eq!(limiter.check_body(b"not a json").unwrap(), []);
+    }
+
+    #[test]
+    fn connection_rate_limits() {
+        let limiter = ConnectionLimiter::new(&config(2, 1));
+        limiter.check_call("eth_call").unwrap();
+        assert_eq!(
+            limiter.check_call("eth_estimateGas"),
+            Err(LimitViolation::RateLimited(MethodClass::Execution))
+        );
+        limiter.check_call("eth_blockNumber").unwrap();
+        limiter.check_call("eth_getBalance").unwrap();
+        assert_eq!(
+            limiter.check_call("eth_blockNumber"),
+            Err(LimitViolation::RateLimited(MethodClass::Read))
+        );
+    }
+
+    #[test]
+    fn client_ip_resolution() {
+        let trusted_proxies: Vec<IpAddr> = vec!["10.0.0.1".parse().unwrap()];
+        let proxy_addr: SocketAddr = "10.0.0.1:50000".parse().unwrap();
+        let client_addr: SocketAddr = "1.2.3.4:50000".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "5.6.7.8, 9.9.9.9".parse().unwrap());
+
+        // Forwarding headers are trusted only if set by a trusted proxy.
+        assert_eq!(
+            client_ip(proxy_addr, &headers, &trusted_proxies),
+            "9.9.9.9".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(
+            client_ip(client_addr, &headers, &trusted_proxies),
+            client_addr.ip()
+        );
+        assert_eq!(client_ip(proxy_addr, &headers, &[]), proxy_addr.ip());
+
+        // Trusted proxies in the forwarding chain are skipped.
+        headers.insert("x-forwarded-for", "5.6.7.8, 10.0.0.1".parse().unwrap());
+        assert_eq!(
+            client_ip(proxy_addr, &headers, &trusted_proxies),
+            "5.6.7.8".parse::<IpAddr>().unwrap()
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-real-ip", "5.6.7.8".parse().unwrap());
+        assert_eq!(
+            client_ip(proxy_addr, &headers, &trusted_proxies),
+            "5.6.7.8".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(
+            client_ip(proxy_addr, &HeaderMap::new(), &trusted_proxies),
+            IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))
+        );
+    }
+}
@@ -0,0 +1,324 @@
+//! In-process cache of immutable Web3 API responses.
+//!
+//! Explorers and indexers repeatedly request the same historical blocks, transactions, receipts
+//! and contract bytecodes, each request resulting in one or more DAL queries. Responses concerning
+//! miniblocks that are at least `min_l1_batch_depth` L1 batches behind the last sealed batch are
+//! considered immutable and are cached for `ttl`.
+//!
+//! The cache only stores responses for miniblocks not newer than the *immutability watermark*, which
+//! is periodically refreshed from Postgres by [`ResponseCache::run_watermark_updates()`]. If the
+//! sealed miniblock number decreases (i.e., the node state was reverted), the cache is cleared.
+
+use tokio::sync::watch;
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use zksync_config::configs::api::Web3JsonRpcConfig;
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    api::{Transaction, TransactionReceipt, TransactionVariant},
+    Address, Bytes, L1BatchNumber, MiniblockNumber, H256,
+};
+use zksync_web3_decl::types::Block;
+
+/// Interval between refreshing the immutability watermark.
+const WATERMARK_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Configuration of the [`ResponseCache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponseCacheConfig {
+    /// Max number of cached responses. If set to 0, the cache is disabled.
+    pub capacity: usize,
+    /// Time after which a cached response is discarded.
+    pub ttl: Duration,
+    /// Min number of L1 batches sealed after the batch of a miniblock for the miniblock data
+    /// to be considered immutable.
+    pub min_l1_batch_depth: u32,
+}
+
+impl ResponseCacheConfig {
+    pub fn from_config(config: &Web3JsonRpcConfig) -> Self {
+        Self {
+            capacity: config.response_cache_size(),
+            ttl: config.response_cache_ttl(),
+            min_l1_batch_depth: config.response_cache_min_l1_batch_depth(),
+        }
+    }
+}
+
+/// Key of a cached response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum CacheKey {
+    BlockByNumber(MiniblockNumber, bool),
+    BlockByHash(H256, bool),
+    Transaction(H256),
+    Receipt(H256),
+    Code(Address, MiniblockNumber),
+}
+
+impl CacheKey {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::BlockByNumber(..) | Self::BlockByHash(..) => "block",
+            Self::Transaction(_) => "transaction",
+            Self::Receipt(_) => "receipt",
+            Self::Code(..) => "code",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum CachedResponse {
+    Block(Box<Block<TransactionVariant>>),
+    Transaction(Box<Transaction>),
+    Receipt(Box<TransactionReceipt>),
+    Code(Bytes),
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    response: CachedResponse,
+    inserted_at: Instant,
+}
+
+#[derive(Debug, Default)]
+struct CacheEntries {
+    responses: HashMap<CacheKey, CacheEntry>,
+    insertion_order: VecDeque<CacheKey>,
+    /// Last miniblock which data is considered immutable.
+    immutable_watermark: Option<MiniblockNumber>,
+    /// Last sealed miniblock observed by the watermark updater; used to detect reverts.
+    last_sealed_miniblock: Option<MiniblockNumber>,
+}
+
+impl CacheEntries {
+    fn clear(&mut self) {
+        self.responses.clear();
+        self.insertion_order.clear();
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ResponseCache {
+    config: ResponseCacheConfig,
+    entries: Mutex<CacheEntries>,
+}
+
+impl ResponseCache {
+    pub fn new(config: ResponseCacheConfig) -> Self {
+        Self {
+            config,
+            entries: Mutex::default(),
+        }
+    }
+
+    pub fn get(&self, key: &CacheKey) -> Option<CachedResponse> {
+        if self.config.capacity == 0 {
+            return None;
+        }
+
+        let entries = self.entries.lock().expect("response cache is poisoned");
+        // Expired entries are not removed here; they are overwritten once the response is
+        // re-fetched, or evicted.
+        let (response, outcome) = match entries.responses.get(key) {
+            Some(entry) if entry.inserted_at.elapsed() <= self.config.ttl => {
+                (Some(entry.response.clone()), "hit")
+            }
+            Some(_) => (None, "expired"),
+            None => (None, "miss"),
+        };
+
+        metrics::increment_counter!(
+            "api.web3.response_cache",
+            "kind" => key.kind(),
+            "outcome" => outcome
+        );
+        response
+    }
+
+    /// Caches the response if it concerns an immutable miniblock.
+    pub fn insert(&self, key: CacheKey, block_number: MiniblockNumber, response: CachedResponse) {
+        if self.config.capacity == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.lock().expect("response cache is poisoned");
+        let is_immutable = entries
+            .immutable_watermark
+            .map_or(false, |watermark| block_number <= watermark);
+        if !is_immutable {
+            return;
+        }
+
+        let entry = CacheEntry {
+            response,
+            inserted_at: Instant::now(),
+        };
+        if entries.responses.insert(key, entry).is_none() {
+            entries.insertion_order.push_back(key);
+        }
+        while entries.responses.len() > self.config.capacity {
+            match entries.insertion_order.pop_front() {
+                Some(evicted) => {
+                    entries.responses.remove(&evicted);
+                }
+                None => break,
+            }
+        }
+        metrics::gauge!(
+            "api.web3.response_cache_size",
+            entries.responses.len() as f64
+        );
+    }
+
+    fn update_watermark(
+        &self,
+        sealed_miniblock: MiniblockNumber,
+        immutable_watermark: Option<MiniblockNumber>,
+    ) {
+        let mut entries = self.entries.lock().expect("response cache is poisoned");
+        let is_revert = entries
+            .last_sealed_miniblock
+            .map_or(false, |last_sealed| sealed_miniblock < last_sealed)
+            || matches!(
+                (entries.immutable_watermark, immutable_watermark),
+                (Some(old), new) if new.map_or(true, |new| new < old)
+            );
+        if is_revert {
+            vlog::warn!(
+                "Sealed miniblock number decreased to {}; clearing API response cache",
+                sealed_miniblock
+            );
+            metrics::increment_counter!("api.web3.response_cache_invalidations");
+            entries.clear();
+        }
+        entries.last_sealed_miniblock = Some(sealed_miniblock);
+        entries.immutable_watermark = immutable_watermark;
+    }
+
+    /// Periodically refreshes the immutability watermark until a stop signal is received.
+    pub async fn run_watermark_updates(
+        self: Arc<Self>,
+        connection_pool: ConnectionPool,
+        stop_receiver: watch::Receiver<bool>,
+    ) {
+        let mut timer = tokio::time::interval(WATERMARK_UPDATE_INTERVAL);
+        loop {
+            if *stop_receiver.borrow() {
+                vlog::info!("Stop signal received, response cache updater is shutting down");
+                break;
+            }
+            timer.tick().await;
+
+            let mut storage = connection_pool.access_storage_tagged("api").await;
+            let mut blocks_dal = storage.blocks_web3_dal();
+            let sealed_miniblock = blocks_dal.get_sealed_miniblock_number().await.unwrap();
+            let sealed_l1_batch = blocks_dal.get_sealed_l1_batch_number().await.unwrap();
+            let immutable_watermark = match sealed_l1_batch
+                .0
+                .checked_sub(self.config.min_l1_batch_depth)
+            {
+                Some(batch_number) => blocks_dal
+                    .get_miniblock_range_of_l1_batch(L1BatchNumber(batch_number))
+                    .await
+                    .unwrap()
+                    .map(|(_, last_miniblock)| last_miniblock),
+                None => None,
+            };
+            drop(storage);
+
+            self.update_watermark(sealed_miniblock, immutable_watermark);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(capacity: usize) -> ResponseCacheConfig {
+        ResponseCacheConfig {
+            capacity,
+            ttl: Duration::from_secs(60),
+            min_l1_batch_depth: 1,
+        }
+    }
+
+    fn code(byte: u8) -> CachedResponse {
+        CachedResponse::Code(vec![byte; 32].into())
+    }
+
+    fn assert_cached_code(cache: &ResponseCache, key: &CacheKey, expected_byte: u8) {
+        match cache.get(key) {
+            Some(CachedResponse::Code(code)) => assert_eq!(code.0, vec![expected_byte; 32]),
+            other => panic!("unexpected cached response: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn only_immutable_responses_are_cached() {
+        let cache = ResponseCache::new(config(10));
+        let key = CacheKey::Code(Address::zero(), MiniblockNumber(5));
+        cache.insert(key, MiniblockNumber(5), code(1));
+        assert!(cache.get(&key).is_none());
+
+        cache.update_watermark(MiniblockNumber(10), Some(MiniblockNumber(5)));
+        cache.insert(key, MiniblockNumber(5), code(1));
+        assert_cached_code(&cache, &key, 1);
+
+        let newer_key = CacheKey::Code(Address::zero(), MiniblockNumber(6));
+        cache.insert(newer_key, MiniblockNumber(6), code(2));
+        assert!(cache.get(&newer_key).is_none());
+    }
+
+    #[test]
+    fn oldest_responses_are_evicted() {
+        let cache = ResponseCache::new(config(2));
+        cache.update_watermark(MiniblockNumber(10), Some(MiniblockNumber(10)));
+        for number in 1..=3 {
+            let key = CacheKey::Code(Address::zero(), MiniblockNumber(number));
+            cache.insert(key, MiniblockNumber(number), code(number as u8));
+        }
+
+        assert!(cache
+            .get(&CacheKey::Code(Address::zero(), MiniblockNumber(1)))
+            .is_none());
+        assert_cached_code(
+            &cache,
+            &CacheKey::Code(Address::zero(), MiniblockNumber(3)),
+            3,
+        );
+    }
+
+    #[test]
+    fn expired_responses_are_not_returned() {
+        let cache = ResponseCache::new(ResponseCacheConfig {
+            ttl: Duration::ZERO,
+            ..config(10)
+        });
+        cache.update_watermark(MiniblockNumber(10), Some(MiniblockNumber(10)));
+        let key = CacheKey::Receipt(H256::zero());
+        cache.insert(key, MiniblockNumber(1), code(1));
+        std::thread::sleep(Duration::from_millis(1));
+        assert!(cache.get(&key).is_none());
+    }
+
+    #[test]
+    fn cache_is_cleared_on_revert() {
+        let cache = ResponseCache::new(config(10));
+        cache.update_watermark(MiniblockNumber(10), Some(MiniblockNumber(8)));
+        let key = CacheKey::Transaction(H256::zero());
+        cache.insert(key, MiniblockNumber(7), code(1));
+        assert_cached_code(&cache, &key, 1);
+
+        cache.update_watermark(MiniblockNumber(12), Some(MiniblockNumber(8)));
+        assert_cached_code(&cache, &key, 1);
+
+        cache.update_watermark(MiniblockNumber(9), Some(MiniblockNumber(6)));
+        assert!(cache.get(&key).is_none());
+    }
+}
@@ -3,15 +3,26 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::convert::TryInto;
 use std::sync::Arc;
+use std::time::Duration;
 
 use tokio::sync::RwLock;
-use zksync_config::configs::{api::Web3JsonRpcConfig, chain::NetworkConfig, ContractsConfig};
+use zksync_config::configs::{
+    api::Web3JsonRpcConfig, chain::NetworkConfig, eth_sender::SenderConfig, ContractsConfig,
+};
 
-use crate::api_server::tx_sender::TxSender;
-use crate::api_server::web3::{backend_jsonrpc::error::internal_error, resolve_block};
+use crate::admin_controls::AdminControls;
+use crate::api_server::tx_sender::{SubmitTxError, TxSender};
+use crate::api_server::web3::{
+    backend_jsonrpc::error::internal_error,
+    call_cache::CallCache,
+    l2_to_l1_logs_cache::L2ToL1LogsCache,
+    resolve_block,
+    response_cache::{CacheKey, CachedResponse, ResponseCache},
+};
+use crate::state_keeper::{MempoolGuard, PendingReceipts};
 use crate::sync_layer::SyncState;
 
-use zksync_dal::ConnectionPool;
+use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_eth_signer::PrivateKeySigner;
 
 use zksync_types::{
@@ -38,6 +49,14 @@ pub struct InternalApiConfig {
     pub diamond_proxy_addr: Address,
     pub l2_testnet_paymaster_addr: Option<Address>,
     pub req_entities_limit: usize,
+    /// Whether account balances are resolved from the token balances table.
+    pub indexed_balances: bool,
+    /// Max number of transactions in a bundle simulated by `zks_simulateBundle`.
+    pub max_bundle_size: usize,
+    /// Delay between proving and executing L1 batches on L1, if it's known to the node.
+    pub l1_batch_execution_delay: Option<Duration>,
+    /// Namespaces returning decoded protected data; `None` means all namespaces.
+    pub protected_data_namespaces: Option<Vec<String>>,
 }
 
 impl InternalApiConfig {
@@ -45,6 +64,8 @@ impl InternalApiConfig {
         eth_config: &NetworkConfig,
         web3_config: &Web3JsonRpcConfig,
         contracts_config: &ContractsConfig,
+        eth_sender_config: &SenderConfig,
+        chain_contracts: &ChainContracts,
     ) -> Self {
         Self {
             l1_chain_id: eth_config.network.chain_id(),
@@ -53,17 +74,25 @@ impl InternalApiConfig {
             estimate_gas_scale_factor: web3_config.estimate_gas_scale_factor,
             estimate_gas_acceptable_overestimation: web3_config
                 .estimate_gas_acceptable_overestimation,
-            bridge_addresses: api::BridgeAddresses {
-                l1_erc20_default_bridge: contracts_config.l1_erc20_bridge_proxy_addr,
-                l2_erc20_default_bridge: contracts_config.l2_erc20_bridge_addr,
-                l1_weth_bridge: contracts_config.l1_weth_bridge_proxy_addr,
-                l2_weth_bridge: contracts_config.l2_weth_bridge_addr,
-            },
+            bridge_addresses: chain_contracts.bridge_addresses(),
             diamond_proxy_addr: contracts_config.diamond_proxy_addr,
             l2_testnet_paymaster_addr: contracts_config.l2_testnet_paymaster_addr,
             req_entities_limit: web3_config.req_entities_limit(),
+            indexed_balances: web3_config.indexed_balances(),
+            max_bundle_size: web3_config.max_bundle_size(),
+            l1_batch_execution_delay: eth_sender_config.l1_batch_execution_delay(),
+            protected_data_namespaces: web3_config.protected_data_namespaces.clone(),
         }
     }
+
+    /// Checks whether the specified namespace (e.g., `eth`) returns decoded protected data.
+    pub fn has_protected_data_access(&self, namespace: &str) -> bool {
+        self.protected_data_namespaces
+            .as_ref()
+            .map_or(true, |namespaces| {
+                namespaces.iter().any(|ns| ns == namespace)
+            })
+    }
 }
 
 /// Holder for the data required for the API to be functional.
@@ -75,6 +104,20 @@ pub struct RpcState<E> {
     pub sync_state: Option<SyncState>,
     pub(super) api_config: InternalApiConfig,
     pub accounts: HashMap<Address, PrivateKeySigner>,
+    pub(super) l2_to_l1_logs_cache: Arc<L2ToL1LogsCache>,
+    /// Cache of responses concerning immutable data; `None` if caching is disabled.
+    pub(super) response_cache: Option<Arc<ResponseCache>>,
+    /// Cache of `eth_call` results; `None` if caching is disabled.
+    pub(super) call_cache: Option<Arc<CallCache>>,
+    /// Receipts of transactions executed by the state keeper, but not yet persisted; `None` if the API server
+    /// doesn't run together with the state keeper.
+    pub(super) pending_receipts: Option<PendingReceipts>,
+    /// Mempool of the state keeper; `None` if the API server doesn't run together with the state keeper.
+    pub(super) mempool: Option<MempoolGuard>,
+    /// Controls of the components running in the same process; used to report their health.
+    pub(super) admin_controls: Option<AdminControls>,
+    /// Whether the namespace using this state returns decoded protected data.
+    pub(super) protected_data_access: bool,
     #[cfg(feature = "openzeppelin_tests")]
     pub known_bytecodes: Arc<RwLock<HashSet<Vec<u8>>>>,
 }
@@ -91,6 +134,13 @@ impl<E> Clone for RpcState<E> {
             sync_state: self.sync_state.clone(),
             api_config: self.api_config.clone(),
             accounts: self.accounts.clone(),
+            l2_to_l1_logs_cache: self.l2_to_l1_logs_cache.clone(),
+            response_cache: self.response_cache.clone(),
+            call_cache: self.call_cache.clone(),
+            pending_receipts: self.pending_receipts.clone(),
+            mempool: self.mempool.clone(),
+            admin_controls: self.admin_controls.clone(),
+            protected_data_access: self.protected_data_access,
             #[cfg(feature = "openzeppelin_tests")]
             known_bytecodes: self.known_bytecodes.clone(),
         }
@@ -98,14 +148,64 @@ impl<E> Clone for RpcState<E> {
 }
 
 impl<E> RpcState<E> {
+    /// Returns the state for the specified namespace (e.g., `eth`). If the namespace isn't allowed to return
+    /// decoded protected data, the returned state doesn't use the response cache and pending receipts,
+    /// which may contain decoded data.
+    pub(super) fn for_namespace(&self, namespace: &str) -> Self {
+        let mut state = self.clone();
+        state.protected_data_access = self.api_config.has_protected_data_access(namespace);
+        if !state.protected_data_access {
+            state.response_cache = None;
+            state.pending_receipts = None;
+        }
+        state
+    }
+
+    /// Acquires a DB connection for serving API requests, restricting access to protected data if necessary.
+    pub(super) async fn access_storage(&self) -> StorageProcessor<'_> {
+        let mut storage = self.connection_pool.access_storage_tagged("api").await;
+        if !self.protected_data_access {
+            storage.restrict_protected_data_access();
+        }
+        storage
+    }
+
     pub fn parse_transaction_bytes(&self, bytes: &[u8]) -> Result<(L2Tx, H256), Web3Error> {
         let chain_id = self.api_config.l2_chain_id;
         let (tx_request, hash) =
-            api::TransactionRequest::from_bytes(bytes, chain_id.0, self.api_config.max_tx_size)?;
+            api::TransactionRequest::from_bytes(bytes, chain_id.0, self.api_config.max_tx_size)
+                .map_err(|err| match err {
+                    api::SerializationTransactionError::WrongChainId(tx_chain_id) => {
+                        let err = SubmitTxError::WrongChainId(tx_chain_id, chain_id.0);
+                        metrics::counter!(
+                            "api.submit_tx_error",
+                            1,
+                            "reason" => err.grafana_error_code()
+                        );
+                        Web3Error::SubmitTransactionError(err.to_string(), err.data())
+                    }
+                    err => err.into(),
+                })?;
 
         Ok((tx_request.try_into()?, hash))
     }
 
+    pub(super) fn get_cached_response(&self, key: &CacheKey) -> Option<CachedResponse> {
+        self.response_cache.as_ref()?.get(key)
+    }
+
+    /// Caches the response concerning the specified miniblock, provided that the miniblock is old enough.
+    pub(super) fn cache_response(
+        &self,
+        key: CacheKey,
+        block_number: U64,
+        response: CachedResponse,
+    ) {
+        if let Some(cache) = &self.response_cache {
+            cache.insert(key, Self::u64_to_block_number(block_number), response);
+        }
+    }
+
     pub fn u64_to_block_number(n: U64) -> MiniblockNumber {
         if n.as_u64() > u32::MAX as u64 {
             MiniblockNumber(u32::MAX)
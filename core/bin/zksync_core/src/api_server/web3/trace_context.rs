@@ -0,0 +1,58 @@
+//! Middleware opening a tracing span for each HTTP request to the Web3 API.
+//!
+//! If the client provides the W3C Trace Context headers, the request span is attached to the client trace,
+//! so that the whole path of a transaction (API → `TxSender` → mempool → state keeper) can be observed
+//! in the tracing backend.
+
+use std::task::{Context, Poll};
+
+use futures::future::BoxFuture;
+use hyper::{Body, Request};
+use tower::{Layer, Service};
+use tracing::Instrument;
+
+/// `tower` layer wrapping each HTTP request into an `rpc_request` span.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TraceContextLayer;
+
+impl<S> Layer<S> for TraceContextLayer {
+    type Service = TraceContextService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TraceContextService { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TraceContextService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request<Body>> for TraceContextService<S>
+where
+    S: Service<Request<Body>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let span = tracing::info_span!("rpc_request", scheme = "http");
+        let headers = request
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)));
+        vlog::set_remote_parent(&span, headers);
+
+        let response = {
+            let _entered = span.enter();
+            self.inner.call(request)
+        };
+        Box::pin(response.instrument(span))
+    }
+}
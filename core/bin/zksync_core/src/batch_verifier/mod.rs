@@ -0,0 +1,481 @@
+//! Reproducible verification of L1 batch commitments.
+//!
+//! [`BatchVerifier`] recomputes the Merkle tree root hash for an L1 batch by replaying storage logs of all batches
+//! up to it into a separate tree, rebuilds the batch commitment and compares it with the metadata stored in Postgres
+//! and with the calldata of the L1 transaction committing the batch. Mismatches are reported field by field.
+//!
+//! The replayed tree is persisted up to the batch preceding the verified one, so that subsequent verifications
+//! of the same or later batches only replay new batches. Batch headers (L2-to-L1 logs and messages, etc.) and
+//! factory deps are taken from Postgres as is; they are checked against L1 as a part of the commit data.
+
+use anyhow::Context as _;
+use itertools::Itertools;
+use serde::Serialize;
+
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_merkle_tree::domain::ZkSyncTree;
+use zksync_storage::RocksDB;
+use zksync_types::{
+    commitment::BlockWithMetadata,
+    web3::{
+        ethabi::{self, Token},
+        transports::Http,
+        types::TransactionId,
+        Web3,
+    },
+    L1BatchNumber, H256,
+};
+
+use crate::{
+    consistency_checker::extract_batch_commitment,
+    metadata_calculator::{get_logs_for_l1_batch, AsyncTree, MetadataCalculator},
+};
+
+/// Max number of L1 batches replayed into the tree before it's saved.
+const REPLAYED_BATCHES_PER_SAVE: u32 = 100;
+
+/// Names of `CommitBlockInfo` fields in the `commitBlocks()` calldata for batches publishing
+/// initial and repeated writes.
+const COMMIT_DATA_FIELDS: &[&str] = &[
+    "blockNumber",
+    "timestamp",
+    "indexRepeatedStorageChanges",
+    "newStateRoot",
+    "numberOfLayer1Txs",
+    "l2LogsTreeRoot",
+    "priorityOperationsHash",
+    "initialStorageChanges",
+    "repeatedStorageChanges",
+    "l2Logs",
+    "l2ArbitraryLengthMessages",
+    "factoryDeps",
+];
+/// Names of `CommitBlockInfo` fields for batches publishing compressed state diffs.
+const COMMIT_DATA_FIELDS_WITH_STATE_DIFFS: &[&str] = &[
+    "blockNumber",
+    "timestamp",
+    "indexRepeatedStorageChanges",
+    "newStateRoot",
+    "numberOfLayer1Txs",
+    "l2LogsTreeRoot",
+    "priorityOperationsHash",
+    "stateDiffs",
+    "l2Logs",
+    "l2ArbitraryLengthMessages",
+    "factoryDeps",
+];
+
+/// Source of the data compared with the recomputed batch commitment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MismatchSource {
+    /// Batch metadata stored in Postgres.
+    Postgres,
+    /// Calldata of the L1 transaction committing the batch.
+    L1,
+}
+
+/// Mismatch between a recomputed value and the value from a [`MismatchSource`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Mismatch {
+    pub source: MismatchSource,
+    pub field: String,
+    pub recomputed: String,
+    pub actual: String,
+}
+
+impl Mismatch {
+    fn new(
+        source: MismatchSource,
+        field: String,
+        recomputed: impl ToString,
+        actual: impl ToString,
+    ) -> Self {
+        Self {
+            source,
+            field,
+            recomputed: recomputed.to_string(),
+            actual: actual.to_string(),
+        }
+    }
+}
+
+/// Outcome of verifying an L1 batch.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchVerificationReport {
+    pub l1_batch_number: L1BatchNumber,
+    pub recomputed_root_hash: H256,
+    pub recomputed_commitment: H256,
+    /// Whether the batch metadata is stored in Postgres. If not, the metadata isn't compared.
+    pub has_stored_metadata: bool,
+    /// Hash of the L1 transaction committing the batch. If the batch isn't committed yet,
+    /// the commit data isn't compared.
+    pub commit_tx_hash: Option<H256>,
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl BatchVerificationReport {
+    pub fn is_consistent(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Verifies commitments of L1 batches.
+#[derive(Debug)]
+pub struct BatchVerifier {
+    pool: ConnectionPool,
+    web3: Web3<Http>,
+    contract: ethabi::Contract,
+    tree: AsyncTree,
+}
+
+impl BatchVerifier {
+    /// Creates a verifier replaying storage logs into the tree at `tree_path`. The tree must not be used
+    /// by the metadata calculator.
+    pub fn new(pool: ConnectionPool, web3_url: &str, tree_path: &str) -> anyhow::Result<Self> {
+        let transport = Http::new(web3_url).context("failed creating L1 client")?;
+        Ok(Self {
+            pool,
+            web3: Web3::new(transport),
+            contract: zksync_contracts::zksync_contract(),
+            tree: AsyncTree::new(ZkSyncTree::new_lightweight(RocksDB::new(tree_path, true))),
+        })
+    }
+
+    /// Verifies the specified L1 batch. The verifier is consumed since the tree contains the unsaved verified batch.
+    pub async fn verify(
+        mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<BatchVerificationReport> {
+        let pool = self.pool.clone();
+        let mut storage = pool.access_storage_tagged("batch_verifier").await;
+        let recomputed = self.recompute(&mut storage, l1_batch_number).await?;
+        let mut report = BatchVerificationReport {
+            l1_batch_number,
+            recomputed_root_hash: recomputed.metadata.root_hash,
+            recomputed_commitment: recomputed.metadata.commitment,
+            has_stored_metadata: false,
+            commit_tx_hash: None,
+            mismatches: vec![],
+        };
+
+        let stored = storage
+            .blocks_dal()
+            .get_block_metadata(l1_batch_number)
+            .await;
+        if let Some(stored) = stored {
+            report.has_stored_metadata = true;
+            report
+                .mismatches
+                .extend(diff_metadata(&recomputed, &stored));
+        } else {
+            vlog::warn!("L1 batch #{} has no metadata in Postgres", l1_batch_number);
+        }
+
+        let commit_tx_hash = self.commit_tx_hash(&mut storage, l1_batch_number).await;
+        drop(storage);
+        if let Some(commit_tx_hash) = commit_tx_hash {
+            let committed = self
+                .fetch_commit_data(commit_tx_hash, l1_batch_number)
+                .await?;
+            report.commit_tx_hash = Some(commit_tx_hash);
+            report.mismatches.extend(diff_commit_data(
+                MismatchSource::L1,
+                &recomputed.l1_commit_data(),
+                &committed,
+            ));
+        } else {
+            vlog::warn!("L1 batch #{} is not committed on L1", l1_batch_number);
+        }
+        Ok(report)
+    }
+
+    /// Replays storage logs up to the specified batch into the tree and rebuilds the batch commitment.
+    async fn recompute(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<BlockWithMetadata> {
+        let replayed_batch_count = self.tree.block_number();
+        anyhow::ensure!(
+            replayed_batch_count <= l1_batch_number.0,
+            "tree already contains {} L1 batches; use a tree at another path to verify L1 batch #{}",
+            replayed_batch_count,
+            l1_batch_number
+        );
+
+        for chunk_start in
+            (replayed_batch_count..l1_batch_number.0).step_by(REPLAYED_BATCHES_PER_SAVE as usize)
+        {
+            let chunk_end = (chunk_start + REPLAYED_BATCHES_PER_SAVE).min(l1_batch_number.0);
+            vlog::info!(
+                "Replaying L1 batches #{}..#{} into the tree",
+                chunk_start,
+                chunk_end - 1
+            );
+            for number in chunk_start..chunk_end {
+                let block = get_logs_for_l1_batch(storage, L1BatchNumber(number))
+                    .await
+                    .with_context(|| format!("L1 batch #{} is not sealed", number))?;
+                self.tree.process_block(block.storage_logs).await;
+            }
+            self.tree.save().await;
+        }
+
+        // The verified batch is not saved to the tree, so that it can be verified again.
+        let block = get_logs_for_l1_batch(storage, l1_batch_number)
+            .await
+            .with_context(|| format!("L1 batch #{} is not sealed", l1_batch_number))?;
+        let tree_metadata = self.tree.process_block(block.storage_logs).await;
+        let state_diffs = storage
+            .blocks_dal()
+            .get_l1_batch_state_diffs(l1_batch_number)
+            .await;
+        let metadata =
+            MetadataCalculator::build_block_metadata(tree_metadata, &block.header, state_diffs);
+        let factory_deps = storage
+            .blocks_dal()
+            .get_l1_batch_factory_deps(l1_batch_number)
+            .await;
+        Ok(BlockWithMetadata::new(block.header, metadata, factory_deps))
+    }
+
+    async fn commit_tx_hash(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+    ) -> Option<H256> {
+        let commit_tx_id = storage
+            .blocks_dal()
+            .get_storage_block(l1_batch_number)
+            .await?
+            .eth_commit_tx_id?;
+        storage
+            .eth_sender_dal()
+            .get_confirmed_tx_hash_by_eth_tx_id(commit_tx_id as u32)
+            .await
+    }
+
+    async fn fetch_commit_data(
+        &self,
+        commit_tx_hash: H256,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Token> {
+        // Calldata is fetched from L1 rather than from Postgres to not trust the stored data.
+        let commit_tx = self
+            .web3
+            .eth()
+            .transaction(TransactionId::Hash(commit_tx_hash))
+            .await
+            .context("failed fetching commit tx")?
+            .with_context(|| format!("commit tx {:?} not found on L1", commit_tx_hash))?;
+        let receipt = self
+            .web3
+            .eth()
+            .transaction_receipt(commit_tx_hash)
+            .await
+            .context("failed fetching commit tx receipt")?
+            .with_context(|| format!("commit tx {:?} receipt not found on L1", commit_tx_hash))?;
+        anyhow::ensure!(
+            receipt.status == Some(1.into()),
+            "commit tx {:?} failed on L1",
+            commit_tx_hash
+        );
+
+        extract_batch_commitment(&self.contract, &commit_tx.input.0, l1_batch_number).with_context(
+            || {
+                format!(
+                    "commit tx {:?} does not commit L1 batch #{}",
+                    commit_tx_hash, l1_batch_number
+                )
+            },
+        )
+    }
+}
+
+fn diff_metadata(recomputed: &BlockWithMetadata, stored: &BlockWithMetadata) -> Vec<Mismatch> {
+    let source = MismatchSource::Postgres;
+    let mut mismatches = diff_commit_data(
+        source,
+        &recomputed.l1_commit_data(),
+        &stored.l1_commit_data(),
+    );
+
+    let (recomputed, stored) = (&recomputed.metadata, &stored.metadata);
+    let hashes = [
+        ("commitment", recomputed.commitment, stored.commitment),
+        (
+            "auxDataHash",
+            recomputed.aux_data_hash,
+            stored.aux_data_hash,
+        ),
+        (
+            "metaParametersHash",
+            recomputed.meta_parameters_hash,
+            stored.meta_parameters_hash,
+        ),
+        (
+            "passThroughDataHash",
+            recomputed.pass_through_data_hash,
+            stored.pass_through_data_hash,
+        ),
+    ];
+    for (field, recomputed, stored) in hashes.iter().copied() {
+        if recomputed != stored {
+            mismatches.push(Mismatch::new(
+                source,
+                field.to_owned(),
+                format!("{:?}", recomputed),
+                format!("{:?}", stored),
+            ));
+        }
+    }
+    mismatches
+}
+
+/// Compares `CommitBlockInfo` tuples field by field.
+fn diff_commit_data(source: MismatchSource, recomputed: &Token, actual: &Token) -> Vec<Mismatch> {
+    let mut mismatches = vec![];
+    match (recomputed, actual) {
+        (Token::Tuple(recomputed_fields), Token::Tuple(actual_fields))
+            if recomputed_fields.len() == actual_fields.len() =>
+        {
+            let names = match recomputed_fields.len() {
+                len if len == COMMIT_DATA_FIELDS.len() => COMMIT_DATA_FIELDS,
+                _ => COMMIT_DATA_FIELDS_WITH_STATE_DIFFS,
+            };
+            let fields = recomputed_fields.iter().zip(actual_fields).enumerate();
+            for (i, (recomputed, actual)) in fields {
+                let name = names
+                    .get(i)
+                    .map_or_else(|| format!("field{}", i), |&name| name.to_owned());
+                diff_tokens(source, name, recomputed, actual, &mut mismatches);
+            }
+        }
+        _ => diff_tokens(
+            source,
+            "commitData".to_owned(),
+            recomputed,
+            actual,
+            &mut mismatches,
+        ),
+    }
+    mismatches
+}
+
+fn diff_tokens(
+    source: MismatchSource,
+    field: String,
+    recomputed: &Token,
+    actual: &Token,
+    mismatches: &mut Vec<Mismatch>,
+) {
+    match (recomputed, actual) {
+        (Token::Array(recomputed_items), Token::Array(actual_items)) => {
+            if recomputed_items.len() != actual_items.len() {
+                mismatches.push(Mismatch::new(
+                    source,
+                    format!("{}.length", field),
+                    recomputed_items.len(),
+                    actual_items.len(),
+                ));
+            }
+            for (i, (recomputed, actual)) in recomputed_items.iter().zip(actual_items).enumerate() {
+                diff_tokens(
+                    source,
+                    format!("{}[{}]", field, i),
+                    recomputed,
+                    actual,
+                    mismatches,
+                );
+            }
+        }
+        _ if recomputed != actual => {
+            mismatches.push(Mismatch::new(
+                source,
+                field,
+                format_token(recomputed),
+                format_token(actual),
+            ));
+        }
+        _ => { /* values match */ }
+    }
+}
+
+fn format_token(token: &Token) -> String {
+    match token {
+        Token::Uint(value) | Token::Int(value) => value.to_string(),
+        Token::Bool(value) => value.to_string(),
+        Token::Address(address) => format!("{:?}", address),
+        Token::FixedBytes(bytes) | Token::Bytes(bytes) => format!("0x{}", hex::encode(bytes)),
+        Token::String(value) => value.clone(),
+        Token::Array(tokens) | Token::FixedArray(tokens) | Token::Tuple(tokens) => {
+            format!("[{}]", tokens.iter().map(format_token).join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::U256;
+
+    use super::*;
+
+    fn commit_data(root_hash: H256, messages: Vec<Vec<u8>>) -> Token {
+        let mut tokens = vec![
+            Token::Uint(U256::from(1)),
+            Token::Uint(U256::from(1_000)),
+            Token::Uint(U256::from(10)),
+            Token::FixedBytes(root_hash.as_bytes().to_vec()),
+            Token::Uint(U256::zero()),
+            Token::FixedBytes(vec![0; 32]),
+            Token::FixedBytes(vec![0; 32]),
+            Token::Bytes(vec![1, 2, 3]),
+            Token::Bytes(vec![]),
+            Token::Bytes(vec![]),
+        ];
+        tokens.push(Token::Array(
+            messages.into_iter().map(Token::Bytes).collect(),
+        ));
+        tokens.push(Token::Array(vec![]));
+        Token::Tuple(tokens)
+    }
+
+    #[test]
+    fn diffing_commit_data() {
+        let recomputed = commit_data(H256::repeat_byte(1), vec![vec![1], vec![2]]);
+        let mismatches = diff_commit_data(MismatchSource::L1, &recomputed, &recomputed);
+        assert!(mismatches.is_empty());
+
+        let actual = commit_data(H256::repeat_byte(2), vec![vec![1], vec![3], vec![4]]);
+        let mismatches = diff_commit_data(MismatchSource::L1, &recomputed, &actual);
+        let fields: Vec<_> = mismatches.iter().map(|m| m.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            [
+                "newStateRoot",
+                "l2ArbitraryLengthMessages.length",
+                "l2ArbitraryLengthMessages[1]"
+            ]
+        );
+        assert_eq!(
+            mismatches[0].recomputed,
+            format!("0x{}", hex::encode(H256::repeat_byte(1)))
+        );
+        assert_eq!(mismatches[1].recomputed, "2");
+        assert_eq!(mismatches[1].actual, "3");
+        assert_eq!(mismatches[2].actual, "0x03");
+    }
+
+    #[test]
+    fn diffing_commit_data_with_different_layouts() {
+        let recomputed = commit_data(H256::zero(), vec![]);
+        let actual = Token::Tuple(vec![Token::Uint(U256::from(1))]);
+        let mismatches = diff_commit_data(MismatchSource::Postgres, &recomputed, &actual);
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].field, "commitData");
+        assert_eq!(mismatches[0].actual, "[1]");
+    }
+}
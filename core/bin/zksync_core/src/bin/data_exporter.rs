@@ -0,0 +1,77 @@
+use clap::{Parser, Subcommand};
+
+use zksync_dal::{connection::DbVariant, data_export_dal::ExportedTable, ConnectionPool};
+use zksync_object_store::ObjectStoreFactory;
+use zksync_types::MiniblockNumber;
+
+use zksync_core::data_exporter::DataExporter;
+
+#[derive(Debug, Parser)]
+#[command(author = "Matter Labs", version, about = "Chain data export utility", long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Exports transactions, events and storage logs for a miniblock range to the object store as CSV files.
+    /// An interrupted export is resumed when the command is re-run with the same arguments.
+    #[command(name = "export")]
+    Export {
+        /// Name of the export used as a prefix for exported objects.
+        #[arg(long)]
+        name: String,
+        /// First exported miniblock.
+        #[arg(long)]
+        from_miniblock: u32,
+        /// Last exported miniblock (inclusive).
+        #[arg(long)]
+        to_miniblock: u32,
+        /// Comma-separated tables to export: `transactions`, `events` and / or `storage_logs`.
+        /// All tables are exported if not specified.
+        #[arg(long, value_delimiter = ',')]
+        tables: Vec<ExportedTable>,
+        /// Number of miniblocks exported to a single object.
+        #[arg(long, default_value_t = 1_000)]
+        chunk_size: u32,
+    },
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    vlog::init();
+    let _sentry_guard = vlog::init_sentry();
+
+    match Cli::parse().command {
+        Command::Export {
+            name,
+            from_miniblock,
+            to_miniblock,
+            tables,
+            chunk_size,
+        } => {
+            anyhow::ensure!(
+                from_miniblock <= to_miniblock,
+                "invalid miniblock range: {from_miniblock}..={to_miniblock}"
+            );
+            let tables = if tables.is_empty() {
+                ExportedTable::ALL.to_vec()
+            } else {
+                tables
+            };
+            // Export only reads data, so it can be run against a replica.
+            let pool = ConnectionPool::new(Some(1), DbVariant::Replica).await;
+            let exporter = DataExporter::new(
+                &ObjectStoreFactory::from_env(),
+                pool,
+                name,
+                MiniblockNumber(from_miniblock)..=MiniblockNumber(to_miniblock),
+                chunk_size,
+            )
+            .await;
+            exporter.export(&tables).await?;
+        }
+    }
+    Ok(())
+}
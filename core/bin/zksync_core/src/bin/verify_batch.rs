@@ -0,0 +1,46 @@
+use clap::Parser;
+
+use zksync_config::ETHClientConfig;
+use zksync_dal::{connection::DbVariant, ConnectionPool};
+use zksync_types::L1BatchNumber;
+
+use zksync_core::batch_verifier::BatchVerifier;
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "Matter Labs",
+    version,
+    about = "L1 batch commitment verification utility",
+    long_about = None
+)]
+struct Cli {
+    /// Number of the verified L1 batch.
+    #[arg(long)]
+    l1_batch: u32,
+    /// Path to the Merkle tree replayed from storage logs. Must not be the path used by the metadata calculator.
+    /// The tree is reused by subsequent runs verifying the same or later L1 batches.
+    #[arg(long)]
+    tree_path: String,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    vlog::init();
+    let _sentry_guard = vlog::init_sentry();
+    let opts = Cli::parse();
+
+    // Verification only reads data, so it can be run against a replica.
+    let pool = ConnectionPool::new(Some(1), DbVariant::Replica).await;
+    let eth_client_config = ETHClientConfig::from_env();
+    let verifier = BatchVerifier::new(pool, &eth_client_config.web3_url, &opts.tree_path)?;
+    let report = verifier.verify(L1BatchNumber(opts.l1_batch)).await?;
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    anyhow::ensure!(
+        report.is_consistent(),
+        "L1 batch #{} has {} mismatches with the recomputed commitment",
+        opts.l1_batch,
+        report.mismatches.len()
+    );
+    Ok(())
+}
@@ -230,6 +230,15 @@ impl BlockReverter {
             .await
             .expect("L1 batch should contain at least one miniblock");
 
+        // Account summaries are rolled back based on transactions, so this must happen before the transactions
+        // state is reset.
+        // Other API read models are removed together with miniblocks.
+        vlog::info!("rolling back account summaries...");
+        transaction
+            .api_read_models()
+            .account_summaries_dal()
+            .rollback(last_miniblock_to_keep)
+            .await;
         vlog::info!("rolling back transactions state...");
         transaction
             .transactions_dal()
@@ -250,6 +259,11 @@ impl BlockReverter {
             .tokens_dal()
             .rollback_tokens(last_miniblock_to_keep)
             .await;
+        vlog::info!("rolling back token balances...");
+        transaction
+            .tokens_dal()
+            .rollback_token_balances(last_miniblock_to_keep)
+            .await;
         vlog::info!("rolling back factory deps....");
         transaction
             .storage_dal()
@@ -0,0 +1,102 @@
+//! Internal HTTP server accepting batches of transactions from trusted sequencer frontends.
+//! Transactions are validated in the same way as in the public Web3 API (including the sandbox execution);
+//! each batch is persisted atomically and inserted directly into the state keeper mempool. The server
+//! binds to the loopback interface by default, and all requests must present the configured bearer token.
+//!
+//! The server also streams the mempool snapshot to a standby sequencer (see the [`snapshot`] module).
+
+use actix_web::{dev::Server, web, App, HttpServer};
+use tokio::{sync::watch, task::JoinHandle};
+
+use std::{net::SocketAddr, time::Duration};
+
+use zksync_config::configs::chain::MempoolConfig;
+use zksync_dal::ConnectionPool;
+use zksync_types::L2ChainId;
+use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
+
+use self::request_processor::BulkIngestionProcessor;
+use crate::{
+    api_server::tx_sender::TxSender, l1_gas_price::L1GasPriceProvider, state_keeper::MempoolGuard,
+};
+
+mod request_processor;
+mod snapshot;
+
+pub(crate) use self::snapshot::import_mempool_snapshot;
+
+fn start_server<G: L1GasPriceProvider + Send + Sync + 'static>(
+    processor: BulkIngestionProcessor<G>,
+    bind_to: SocketAddr,
+) -> Server {
+    HttpServer::new(move || {
+        App::new()
+            .app_data(web::Data::new(processor.clone()))
+            .service(web::scope("/bulk").route(
+                "/transactions",
+                web::post().to(BulkIngestionProcessor::<G>::submit_batch),
+            ))
+            .service(web::scope("/mempool").route(
+                "/snapshot",
+                web::get().to(BulkIngestionProcessor::<G>::mempool_snapshot),
+            ))
+    })
+    .bind(bind_to)
+    .unwrap()
+    .shutdown_timeout(60)
+    .keep_alive(Duration::from_secs(10))
+    .client_request_timeout(Duration::from_secs(60))
+    .run()
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn start_server_thread_detached<G: L1GasPriceProvider + Send + Sync + 'static>(
+    config: &MempoolConfig,
+    port: u16,
+    tx_sender: TxSender<G>,
+    mempool: MempoolGuard,
+    pool: ConnectionPool,
+    chain_id: L2ChainId,
+    max_tx_size: usize,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> JoinHandle<()> {
+    let (handler, panic_sender) = spawn_panic_handler();
+    let auth_token = config
+        .bulk_ingestion_auth_token()
+        .expect("`bulk_ingestion_auth_token` must be set for the bulk tx ingestion server")
+        .to_owned();
+    let bind_address = SocketAddr::new(config.bulk_ingestion_bind_address(), port);
+    let processor = BulkIngestionProcessor::new(
+        auth_token,
+        tx_sender,
+        mempool,
+        pool,
+        chain_id,
+        max_tx_size,
+        config.bulk_ingestion_max_batch_size(),
+        config.bulk_ingestion_max_concurrent_batches(),
+    );
+
+    std::thread::Builder::new()
+        .name("bulk-tx-ingestion".to_string())
+        .spawn(move || {
+            let _panic_sentinel = ThreadPanicNotify(panic_sender);
+
+            actix_rt::System::new().block_on(async move {
+                let server = start_server(processor, bind_address);
+                let close_handle = server.handle();
+                actix_rt::spawn(async move {
+                    if stop_receiver.changed().await.is_ok() {
+                        close_handle.stop(true).await;
+                        vlog::info!(
+                            "Stop signal received, bulk tx ingestion server is shutting down"
+                        );
+                    }
+                });
+                server.await.expect("Bulk tx ingestion server crashed");
+            });
+        })
+        .expect("Failed to spawn thread for bulk tx ingestion server");
+
+    handler
+}
@@ -0,0 +1,266 @@
+use actix_web::{
+    http::{header, StatusCode},
+    web::{self, Json},
+    HttpRequest, HttpResponse, ResponseError,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::Semaphore;
+
+use std::{sync::Arc, time::Instant};
+
+use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool};
+use zksync_types::{
+    api::TransactionRequest, l2::L2Tx, transaction_request::SerializationTransactionError, Bytes,
+    L2ChainId, Transaction, H256,
+};
+use zksync_utils::constant_time_eq;
+
+use super::snapshot::snapshot_response;
+use crate::{
+    api_server::tx_sender::{SubmitTxError, TxSender},
+    l1_gas_price::L1GasPriceProvider,
+    state_keeper::MempoolGuard,
+};
+
+/// Batch of raw (RLP- or EIP-712-encoded) signed transactions.
+#[derive(Debug, Deserialize)]
+pub(crate) struct SubmitBatchRequest {
+    pub transactions: Vec<Bytes>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SubmitBatchResponse {
+    pub tx_hashes: Vec<H256>,
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum BulkIngestionError {
+    #[error("missing or invalid auth token")]
+    Unauthorized,
+    #[error("too many batches are being processed; retry later")]
+    TooManyBatches,
+    #[error("batch contains {0} transactions, the limit is {1}")]
+    BatchTooLarge(usize, usize),
+    #[error("mempool can only accept {0} more transactions; retry later")]
+    MempoolFull(u64),
+    #[error("transaction #{0} in the batch is invalid: {1}")]
+    InvalidTransaction(usize, SerializationTransactionError),
+    #[error("transaction #{0} in the batch failed validation: {1}")]
+    Validation(usize, SubmitTxError),
+    #[error("transaction {0:?} cannot be added to the mempool: {1}")]
+    Rejected(H256, L2TxSubmissionResult),
+}
+
+impl ResponseError for BulkIngestionError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::Unauthorized => StatusCode::UNAUTHORIZED,
+            Self::TooManyBatches => StatusCode::TOO_MANY_REQUESTS,
+            Self::MempoolFull(_) => StatusCode::SERVICE_UNAVAILABLE,
+            Self::BatchTooLarge(..) | Self::InvalidTransaction(..) | Self::Validation(..) => {
+                StatusCode::BAD_REQUEST
+            }
+            Self::Rejected(..) => StatusCode::CONFLICT,
+        }
+    }
+}
+
+impl BulkIngestionError {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Unauthorized => "unauthorized",
+            Self::TooManyBatches => "too_many_batches",
+            Self::BatchTooLarge(..) => "batch_too_large",
+            Self::MempoolFull(_) => "mempool_full",
+            Self::InvalidTransaction(..) => "invalid_transaction",
+            Self::Validation(..) => "validation",
+            Self::Rejected(..) => "rejected",
+        }
+    }
+}
+
+/// Processes batches of transactions submitted by trusted frontends.
+#[derive(Debug)]
+pub(crate) struct BulkIngestionProcessor<G> {
+    auth_token: String,
+    tx_sender: TxSender<G>,
+    mempool: MempoolGuard,
+    pool: ConnectionPool,
+    chain_id: L2ChainId,
+    max_tx_size: usize,
+    max_batch_size: usize,
+    batch_permits: Arc<Semaphore>,
+}
+
+// Derived `Clone` would require `G: Clone`.
+impl<G> Clone for BulkIngestionProcessor<G> {
+    fn clone(&self) -> Self {
+        Self {
+            auth_token: self.auth_token.clone(),
+            tx_sender: self.tx_sender.clone(),
+            mempool: self.mempool.clone(),
+            pool: self.pool.clone(),
+            chain_id: self.chain_id,
+            max_tx_size: self.max_tx_size,
+            max_batch_size: self.max_batch_size,
+            batch_permits: self.batch_permits.clone(),
+        }
+    }
+}
+
+impl<G: L1GasPriceProvider> BulkIngestionProcessor<G> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        auth_token: String,
+        tx_sender: TxSender<G>,
+        mempool: MempoolGuard,
+        pool: ConnectionPool,
+        chain_id: L2ChainId,
+        max_tx_size: usize,
+        max_batch_size: usize,
+        max_concurrent_batches: usize,
+    ) -> Self {
+        Self {
+            auth_token,
+            tx_sender,
+            mempool,
+            pool,
+            chain_id,
+            max_tx_size,
+            max_batch_size,
+            batch_permits: Arc::new(Semaphore::new(max_concurrent_batches)),
+        }
+    }
+
+    /// Checks the bearer token of the request, comparing it with the expected one in constant time.
+    fn authorize(&self, request: &HttpRequest) -> Result<(), BulkIngestionError> {
+        let provided = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or(BulkIngestionError::Unauthorized)?;
+        if constant_time_eq(self.auth_token.as_bytes(), provided.as_bytes()) {
+            Ok(())
+        } else {
+            Err(BulkIngestionError::Unauthorized)
+        }
+    }
+
+    fn parse_transaction(&self, bytes: &[u8]) -> Result<L2Tx, SerializationTransactionError> {
+        let (tx_request, _) =
+            TransactionRequest::from_bytes(bytes, self.chain_id.0, self.max_tx_size)?;
+        tx_request.try_into()
+    }
+
+    pub async fn submit_batch(
+        self_: web::Data<Self>,
+        http_request: HttpRequest,
+        request: Json<SubmitBatchRequest>,
+    ) -> Result<Json<SubmitBatchResponse>, BulkIngestionError> {
+        let started_at = Instant::now();
+        let result = match self_.authorize(&http_request) {
+            Ok(()) => self_.submit_batch_inner(request.into_inner()).await,
+            Err(err) => Err(err),
+        };
+        match &result {
+            Ok(response) => {
+                metrics::histogram!(
+                    "server.bulk_tx_ingestion.batch_latency",
+                    started_at.elapsed()
+                );
+                metrics::counter!(
+                    "server.bulk_tx_ingestion.accepted_txs",
+                    response.tx_hashes.len() as u64
+                );
+            }
+            Err(err) => {
+                vlog::info!("Rejected transaction batch: {err}");
+                metrics::increment_counter!(
+                    "server.bulk_tx_ingestion.rejected_batches",
+                    "reason" => err.as_str()
+                );
+            }
+        }
+        result.map(Json)
+    }
+
+    /// Streams L2 transactions currently in the mempool together with the filter state.
+    pub async fn mempool_snapshot(
+        self_: web::Data<Self>,
+        http_request: HttpRequest,
+    ) -> Result<HttpResponse, BulkIngestionError> {
+        self_.authorize(&http_request)?;
+        Ok(snapshot_response(self_.mempool.snapshot()))
+    }
+
+    async fn submit_batch_inner(
+        &self,
+        request: SubmitBatchRequest,
+    ) -> Result<SubmitBatchResponse, BulkIngestionError> {
+        let _permit = self
+            .batch_permits
+            .try_acquire()
+            .map_err(|_| BulkIngestionError::TooManyBatches)?;
+
+        let batch_size = request.transactions.len();
+        if batch_size > self.max_batch_size {
+            return Err(BulkIngestionError::BatchTooLarge(
+                batch_size,
+                self.max_batch_size,
+            ));
+        }
+        let remaining_capacity = self.mempool.remaining_capacity();
+        if batch_size as u64 > remaining_capacity {
+            return Err(BulkIngestionError::MempoolFull(remaining_capacity));
+        }
+
+        let txs = request
+            .transactions
+            .iter()
+            .enumerate()
+            .map(|(i, bytes)| {
+                self.parse_transaction(&bytes.0)
+                    .map_err(|err| BulkIngestionError::InvalidTransaction(i, err))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // Transactions undergo the same checks as ones submitted via the Web3 API, including the sandbox execution.
+        let mut validated_txs = Vec::with_capacity(txs.len());
+        for (i, tx) in txs.into_iter().enumerate() {
+            let tx_metrics = self
+                .tx_sender
+                .validate_submitted_tx(&tx)
+                .await
+                .map_err(|err| BulkIngestionError::Validation(i, err))?;
+            validated_txs.push((tx, tx_metrics));
+        }
+        let tx_hashes: Vec<_> = validated_txs.iter().map(|(tx, _)| tx.hash()).collect();
+
+        let mut storage = self.pool.access_storage_tagged("bulk_tx_ingestion").await;
+        let mut transaction = storage.start_transaction().await;
+        let nonces = transaction
+            .transactions_dal()
+            .insert_transactions_l2_bulk(&validated_txs)
+            .await
+            .map_err(|(tx_hash, result)| BulkIngestionError::Rejected(tx_hash, result))?;
+        transaction.commit().await;
+
+        let txs = validated_txs
+            .into_iter()
+            .map(|(tx, _)| Transaction::from(tx))
+            .collect();
+        if let Err(err) = self.mempool.clone().insert_many(txs, nonces) {
+            // Another batch has taken the remaining capacity after the check above. Transactions
+            // are already persisted, so we let the mempool fetcher load them instead.
+            vlog::warn!("Cannot insert transaction batch into mempool directly: {err}");
+            metrics::increment_counter!("server.bulk_tx_ingestion.deferred_batches");
+            storage
+                .transactions_dal()
+                .unmark_txs_as_in_mempool(&tx_hashes)
+                .await;
+        }
+        Ok(SubmitBatchResponse { tx_hashes })
+    }
+}
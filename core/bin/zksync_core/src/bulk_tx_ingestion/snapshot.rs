@@ -0,0 +1,258 @@
+//! Handover of the mempool contents to a standby sequencer, so that transactions accepted by the primary
+//! sequencer, but not sealed yet, aren't dropped on failover.
+//!
+//! The snapshot is streamed as newline-delimited JSON: the first line is a [`SnapshotEntry::Header`],
+//! followed by a [`SnapshotEntry::Transaction`] line for each L2 transaction.
+
+use actix_web::{web, HttpResponse};
+use anyhow::{ensure, Context as _};
+use serde::{Deserialize, Serialize};
+
+use std::{iter, time::Instant};
+
+use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool};
+use zksync_mempool::{L2TxFilter, MempoolSnapshot};
+use zksync_types::{fee::TransactionExecutionMetrics, l2::L2Tx, PriorityOpId};
+
+/// Filter used by the state keeper of the primary sequencer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SnapshotFilter {
+    pub l1_gas_price: u64,
+    pub fee_per_gas: u64,
+    pub gas_per_pubdata: u32,
+}
+
+impl From<L2TxFilter> for SnapshotFilter {
+    fn from(filter: L2TxFilter) -> Self {
+        Self {
+            l1_gas_price: filter.l1_gas_price,
+            fee_per_gas: filter.fee_per_gas,
+            gas_per_pubdata: filter.gas_per_pubdata,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SnapshotHeader {
+    pub next_priority_id: PriorityOpId,
+    pub filter: Option<SnapshotFilter>,
+    /// Number of transaction entries following the header.
+    pub transaction_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub(crate) enum SnapshotEntry {
+    Header(SnapshotHeader),
+    Transaction(Box<L2Tx>),
+}
+
+fn snapshot_entries(snapshot: MempoolSnapshot) -> impl Iterator<Item = SnapshotEntry> {
+    let header = SnapshotHeader {
+        next_priority_id: snapshot.next_priority_id,
+        filter: snapshot.last_filter.map(SnapshotFilter::from),
+        transaction_count: snapshot.l2_transactions.len(),
+    };
+    let transactions = snapshot
+        .l2_transactions
+        .into_iter()
+        .map(|tx| SnapshotEntry::Transaction(Box::new(tx)));
+    iter::once(SnapshotEntry::Header(header)).chain(transactions)
+}
+
+/// Streams the snapshot. Entries are serialized lazily, so that the response doesn't need
+/// to be buffered in memory.
+pub(super) fn snapshot_response(snapshot: MempoolSnapshot) -> HttpResponse {
+    metrics::histogram!(
+        "server.bulk_tx_ingestion.snapshot_txs",
+        snapshot.l2_transactions.len() as f64
+    );
+    let lines = snapshot_entries(snapshot).map(|entry| {
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+        Ok::<_, serde_json::Error>(web::Bytes::from(line))
+    });
+    HttpResponse::Ok()
+        .content_type("application/x-ndjson")
+        .streaming(futures::stream::iter(lines))
+}
+
+/// Splits a stream of byte chunks into snapshot entries.
+#[derive(Debug, Default)]
+struct SnapshotParser {
+    buffer: Vec<u8>,
+}
+
+impl SnapshotParser {
+    fn push_chunk(&mut self, chunk: &[u8]) -> anyhow::Result<Vec<SnapshotEntry>> {
+        self.buffer.extend_from_slice(chunk);
+        let mut entries = vec![];
+        while let Some(line_end) = self.buffer.iter().position(|&byte| byte == b'\n') {
+            let line: Vec<_> = self.buffer.drain(..=line_end).collect();
+            let entry = serde_json::from_slice(&line[..line_end])
+                .context("malformed mempool snapshot entry")?;
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+
+    fn finish(self) -> anyhow::Result<()> {
+        ensure!(
+            self.buffer.is_empty(),
+            "mempool snapshot ends with an incomplete entry"
+        );
+        Ok(())
+    }
+}
+
+/// Imports the mempool snapshot from the internal server of another sequencer. Transactions are persisted
+/// to Postgres, from which they are loaded by the mempool fetcher; transactions already present in Postgres
+/// are skipped. Returns the header of the imported snapshot.
+///
+/// `auth_token` is presented as the bearer token to the internal server of the other sequencer.
+pub(crate) async fn import_mempool_snapshot(
+    source_url: &str,
+    auth_token: Option<&str>,
+    pool: &ConnectionPool,
+) -> anyhow::Result<SnapshotHeader> {
+    let started_at = Instant::now();
+    let url = format!("{}/mempool/snapshot", source_url.trim_end_matches('/'));
+    let mut request = reqwest::Client::new().get(&url);
+    if let Some(auth_token) = auth_token {
+        request = request.bearer_auth(auth_token);
+    }
+    let mut response = request
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .with_context(|| format!("failed requesting mempool snapshot from {url}"))?;
+
+    let mut storage = pool.access_storage_tagged("state_keeper").await;
+    let mut parser = SnapshotParser::default();
+    let mut header = None;
+    let (mut added_count, mut skipped_count) = (0, 0);
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .context("failed reading mempool snapshot")?
+    {
+        for entry in parser.push_chunk(&chunk)? {
+            match entry {
+                SnapshotEntry::Header(snapshot_header) => {
+                    ensure!(header.is_none(), "mempool snapshot has multiple headers");
+                    header = Some(snapshot_header);
+                }
+                SnapshotEntry::Transaction(tx) => {
+                    ensure!(
+                        header.is_some(),
+                        "mempool snapshot transaction precedes the header"
+                    );
+                    // Transactions were validated by the other sequencer, so we don't have execution metrics for them.
+                    let result = storage
+                        .transactions_dal()
+                        .insert_transaction_l2(*tx, TransactionExecutionMetrics::default())
+                        .await;
+                    if result == L2TxSubmissionResult::Added {
+                        added_count += 1;
+                    } else {
+                        skipped_count += 1;
+                    }
+                }
+            }
+        }
+    }
+    parser.finish()?;
+
+    let header = header.context("mempool snapshot has no header")?;
+    ensure!(
+        added_count + skipped_count == header.transaction_count,
+        "mempool snapshot is truncated: received {} of {} transactions",
+        added_count + skipped_count,
+        header.transaction_count
+    );
+    vlog::info!(
+        "Imported mempool snapshot from {url} in {:?}: {added_count} new transactions, \
+         {skipped_count} transactions already present in Postgres; snapshot header: {header:?}",
+        started_at.elapsed()
+    );
+    metrics::counter!(
+        "server.bulk_tx_ingestion.imported_snapshot_txs",
+        added_count as u64
+    );
+    Ok(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{fee::Fee, Address, Nonce, U256};
+
+    use super::*;
+
+    fn create_transaction(nonce: u32) -> L2Tx {
+        let mut tx = L2Tx::new(
+            Address::repeat_byte(1),
+            vec![1, 2, 3],
+            Nonce(nonce),
+            Fee::default(),
+            Address::repeat_byte(2),
+            U256::zero(),
+            None,
+            Default::default(),
+        );
+        tx.set_input(vec![nonce as u8], Default::default());
+        tx
+    }
+
+    #[test]
+    fn snapshot_entries_are_parsed_from_arbitrary_chunks() {
+        let snapshot = MempoolSnapshot {
+            next_priority_id: PriorityOpId(5),
+            l2_transactions: vec![create_transaction(0), create_transaction(1)],
+            last_filter: Some(L2TxFilter {
+                l1_gas_price: 1_000,
+                fee_per_gas: 100,
+                gas_per_pubdata: 800,
+            }),
+        };
+        let serialized: Vec<u8> = snapshot_entries(snapshot)
+            .flat_map(|entry| {
+                let mut line = serde_json::to_vec(&entry).unwrap();
+                line.push(b'\n');
+                line
+            })
+            .collect();
+
+        for chunk_size in [1, 7, 100, serialized.len()] {
+            let mut parser = SnapshotParser::default();
+            let mut entries = vec![];
+            for chunk in serialized.chunks(chunk_size) {
+                entries.extend(parser.push_chunk(chunk).unwrap());
+            }
+            parser.finish().unwrap();
+
+            assert_eq!(entries.len(), 3);
+            let SnapshotEntry::Header(header) = &entries[0] else {
+                panic!("unexpected first entry: {:?}", entries[0]);
+            };
+            assert_eq!(header.next_priority_id, PriorityOpId(5));
+            assert_eq!(header.transaction_count, 2);
+            assert_eq!(header.filter.as_ref().unwrap().l1_gas_price, 1_000);
+            for (i, entry) in entries[1..].iter().enumerate() {
+                let SnapshotEntry::Transaction(tx) = entry else {
+                    panic!("unexpected entry: {entry:?}");
+                };
+                assert_eq!(tx.common_data.nonce, Nonce(i as u32));
+                assert_eq!(tx.common_data.input_data(), Some([i as u8].as_slice()));
+            }
+        }
+    }
+
+    #[test]
+    fn incomplete_snapshot_entry_is_detected() {
+        let mut parser = SnapshotParser::default();
+        assert!(parser.push_chunk(b"{\"type\":").unwrap().is_empty());
+        parser.finish().unwrap_err();
+    }
+}
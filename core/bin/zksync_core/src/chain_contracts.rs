@@ -0,0 +1,147 @@
+//! Chain-specific contract addresses used by the node.
+//!
+//! Addresses of L2 bridges are resolved at startup from the corresponding L1 bridges, so that the node
+//! doesn't silently use stale addresses from the config after a chain is redeployed.
+
+use serde::{Deserialize, Serialize};
+
+use zksync_config::ContractsConfig;
+use zksync_contracts::l1_bridge_contract;
+use zksync_eth_client::{types::Error, EthInterface};
+use zksync_types::{api::BridgeAddresses, web3::contract::Options, Address};
+
+/// Addresses of chain-specific contracts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChainContracts {
+    pub l1_erc20_bridge: Address,
+    pub l2_erc20_bridge: Address,
+    pub l1_weth_bridge: Option<Address>,
+    pub l2_weth_bridge: Option<Address>,
+    pub l2_weth_token: Option<Address>,
+    pub l2_timestamp_asserter: Option<Address>,
+}
+
+impl ChainContracts {
+    /// Takes all addresses from the config without checking them against L1.
+    pub fn from_config(config: &ContractsConfig) -> Self {
+        Self {
+            l1_erc20_bridge: config.l1_erc20_bridge_proxy_addr,
+            l2_erc20_bridge: config.l2_erc20_bridge_addr,
+            l1_weth_bridge: config.l1_weth_bridge_proxy_addr,
+            l2_weth_bridge: config.l2_weth_bridge_addr,
+            l2_weth_token: config.l2_weth_token_proxy_addr,
+            l2_timestamp_asserter: config.l2_timestamp_asserter_addr,
+        }
+    }
+
+    /// Resolves addresses of L2 bridges from the L1 bridges specified in the config. Other addresses
+    /// are taken from the config as-is.
+    pub async fn resolve<E: EthInterface>(
+        config: &ContractsConfig,
+        eth_client: &E,
+    ) -> Result<Self, Error> {
+        let mut contracts = Self::from_config(config);
+        let l2_erc20_bridge = Self::get_l2_bridge(eth_client, contracts.l1_erc20_bridge).await?;
+        contracts.l2_erc20_bridge = Self::reconcile(
+            "l2_erc20_bridge",
+            contracts.l2_erc20_bridge,
+            l2_erc20_bridge,
+        );
+
+        if let Some(l1_weth_bridge) = contracts.l1_weth_bridge {
+            let l2_weth_bridge = Self::get_l2_bridge(eth_client, l1_weth_bridge).await?;
+            contracts.l2_weth_bridge = Some(match contracts.l2_weth_bridge {
+                Some(configured) => Self::reconcile("l2_weth_bridge", configured, l2_weth_bridge),
+                None => l2_weth_bridge,
+            });
+        }
+        vlog::info!("Resolved chain contracts: {contracts:?}");
+        Ok(contracts)
+    }
+
+    async fn get_l2_bridge<E: EthInterface>(
+        eth_client: &E,
+        l1_bridge: Address,
+    ) -> Result<Address, Error> {
+        eth_client
+            .call_contract_function(
+                "l2Bridge",
+                (),
+                None,
+                Options::default(),
+                None,
+                l1_bridge,
+                l1_bridge_contract(),
+            )
+            .await
+    }
+
+    /// L1 is the source of truth for L2 bridge addresses, but a mismatch with the config most probably
+    /// means that the config is outdated, so it's worth a warning.
+    fn reconcile(name: &str, configured: Address, resolved: Address) -> Address {
+        if configured != resolved {
+            vlog::warn!(
+                "Configured address of `{name}` ({configured:?}) differs from the one resolved from L1 \
+                 ({resolved:?}); using the latter"
+            );
+        }
+        resolved
+    }
+
+    pub fn bridge_addresses(&self) -> BridgeAddresses {
+        BridgeAddresses {
+            l1_erc20_default_bridge: self.l1_erc20_bridge,
+            l2_erc20_default_bridge: self.l2_erc20_bridge,
+            l1_weth_bridge: self.l1_weth_bridge,
+            l2_weth_bridge: self.l2_weth_bridge,
+            l2_weth_token: self.l2_weth_token,
+            l2_timestamp_asserter: self.l2_timestamp_asserter,
+        }
+    }
+}
+
+impl From<BridgeAddresses> for ChainContracts {
+    fn from(addresses: BridgeAddresses) -> Self {
+        Self {
+            l1_erc20_bridge: addresses.l1_erc20_default_bridge,
+            l2_erc20_bridge: addresses.l2_erc20_default_bridge,
+            l1_weth_bridge: addresses.l1_weth_bridge,
+            l2_weth_bridge: addresses.l2_weth_bridge,
+            l2_weth_token: addresses.l2_weth_token,
+            l2_timestamp_asserter: addresses.l2_timestamp_asserter,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chain_contracts_roundtrip_via_bridge_addresses() {
+        let contracts = ChainContracts {
+            l1_erc20_bridge: Address::repeat_byte(1),
+            l2_erc20_bridge: Address::repeat_byte(2),
+            l1_weth_bridge: None,
+            l2_weth_bridge: None,
+            l2_weth_token: Some(Address::repeat_byte(3)),
+            l2_timestamp_asserter: Some(Address::repeat_byte(4)),
+        };
+        let addresses = contracts.bridge_addresses();
+        assert_eq!(ChainContracts::from(addresses), contracts);
+    }
+
+    #[test]
+    fn resolved_addresses_take_precedence() {
+        let configured = Address::repeat_byte(1);
+        let resolved = Address::repeat_byte(2);
+        assert_eq!(
+            ChainContracts::reconcile("l2_erc20_bridge", configured, resolved),
+            resolved
+        );
+        assert_eq!(
+            ChainContracts::reconcile("l2_erc20_bridge", resolved, resolved),
+            resolved
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! Change data capture stream publishing sealed miniblocks to an external message broker.
+//!
+//! For each sealed miniblock, the publisher sends a single JSON message containing the miniblock header,
+//! its transactions, their receipts (including logs) and storage writes. Messages are published strictly
+//! in the miniblock order; the publishing offset is persisted in Postgres only after the broker acknowledges
+//! a message, so delivery is at-least-once and resumes from the persisted offset after a restart. Each message
+//! carries a unique ID that brokers can use to deduplicate messages re-sent after a crash.
+
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use serde::Serialize;
+use tokio::sync::watch;
+
+use std::{fmt, time::Instant};
+
+use zksync_config::configs::ChangeStreamConfig;
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    api::TransactionReceipt, block::MiniblockHeader, Address, MiniblockNumber, Transaction, H256,
+};
+
+mod nats;
+#[cfg(test)]
+mod tests;
+
+pub use self::nats::NatsSink;
+
+/// Destination of the change stream messages.
+#[async_trait]
+pub trait ChangeStreamSink: 'static + fmt::Debug + Send + Sync {
+    /// Publishes a message and waits until it is durably acknowledged by the broker.
+    async fn publish(&self, message_id: String, payload: Vec<u8>) -> anyhow::Result<()>;
+}
+
+/// Storage slot write performed in a miniblock.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageWrite {
+    pub tx_hash: H256,
+    pub address: Address,
+    pub key: H256,
+    pub value: H256,
+}
+
+/// Message published for each sealed miniblock.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MiniblockMessage {
+    pub header: MiniblockHeader,
+    pub transactions: Vec<Transaction>,
+    pub receipts: Vec<TransactionReceipt>,
+    pub storage_writes: Vec<StorageWrite>,
+}
+
+impl MiniblockMessage {
+    /// ID unique for the miniblock contents, so that re-published messages can be deduplicated.
+    fn id(&self) -> String {
+        format!("{}:{:?}", self.header.number, self.header.hash)
+    }
+}
+
+/// Component publishing sealed miniblocks to a [`ChangeStreamSink`].
+#[derive(Debug)]
+pub struct ChangeStreamPublisher {
+    config: ChangeStreamConfig,
+    pool: ConnectionPool,
+    sink: Box<dyn ChangeStreamSink>,
+    sealed_miniblocks: Option<watch::Receiver<MiniblockNumber>>,
+}
+
+impl ChangeStreamPublisher {
+    pub fn new(
+        config: ChangeStreamConfig,
+        pool: ConnectionPool,
+        sink: Box<dyn ChangeStreamSink>,
+    ) -> Self {
+        Self {
+            config,
+            pool,
+            sink,
+            sealed_miniblocks: None,
+        }
+    }
+
+    /// Subscribes to notifications from the miniblock sealer, so that miniblocks are published right after
+    /// they are sealed rather than on the next poll. Only works if the publisher runs in the same process
+    /// as the state keeper.
+    pub fn with_sealed_miniblocks(
+        mut self,
+        sealed_miniblocks: watch::Receiver<MiniblockNumber>,
+    ) -> Self {
+        self.sealed_miniblocks = Some(sealed_miniblocks);
+        self
+    }
+
+    async fn first_miniblock_to_publish(&self) -> MiniblockNumber {
+        let mut storage = self.pool.access_storage_tagged("change_stream").await;
+        let last_published = storage
+            .change_stream_dal()
+            .get_last_published_miniblock(self.config.publisher_name())
+            .await
+            .expect("Failed loading change stream offset");
+        if let Some(last_published) = last_published {
+            return last_published + 1;
+        }
+        match self.config.start_miniblock {
+            Some(number) => MiniblockNumber(number),
+            None => storage.blocks_dal().get_sealed_miniblock_number().await + 1,
+        }
+    }
+
+    /// Loads data for the specified miniblock, or returns `None` if the miniblock is not sealed yet.
+    async fn load_message(
+        &self,
+        number: MiniblockNumber,
+    ) -> anyhow::Result<Option<MiniblockMessage>> {
+        let mut storage = self.pool.access_storage_tagged("change_stream").await;
+        let Some(header) = storage.blocks_dal().get_miniblock_header(number).await else {
+            return Ok(None);
+        };
+        let transactions = storage
+            .transactions_web3_dal()
+            .get_raw_miniblock_transactions(number)
+            .await?;
+
+        let mut receipts = Vec::with_capacity(transactions.len());
+        for tx in &transactions {
+            let receipt = storage
+                .transactions_web3_dal()
+                .get_transaction_receipt(tx.hash())
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("no receipt for transaction {:?}", tx.hash()))?;
+            receipts.push(receipt);
+        }
+
+        let storage_writes = storage
+            .change_stream_dal()
+            .stream_miniblock_storage_writes(number)
+            .map_ok(|(tx_hash, log)| StorageWrite {
+                tx_hash,
+                address: *log.key.address(),
+                key: *log.key.key(),
+                value: log.value,
+            })
+            .try_collect()
+            .await?;
+
+        Ok(Some(MiniblockMessage {
+            header,
+            transactions,
+            receipts,
+            storage_writes,
+        }))
+    }
+
+    /// Publishes the specified miniblock and persists the offset. Returns `false` if the miniblock is not sealed yet.
+    async fn publish_miniblock(&self, number: MiniblockNumber) -> anyhow::Result<bool> {
+        let started_at = Instant::now();
+        let Some(message) = self.load_message(number).await? else {
+            return Ok(false);
+        };
+        let payload = serde_json::to_vec(&message)?;
+        let payload_size = payload.len();
+        self.sink.publish(message.id(), payload).await?;
+
+        let mut storage = self.pool.access_storage_tagged("change_stream").await;
+        storage
+            .change_stream_dal()
+            .set_last_published_miniblock(self.config.publisher_name(), number)
+            .await?;
+
+        vlog::debug!(
+            "Published miniblock #{number} ({} txs, {payload_size} bytes) to the change stream",
+            message.transactions.len()
+        );
+        metrics::histogram!("server.change_stream.publish_latency", started_at.elapsed());
+        metrics::histogram!("server.change_stream.message_size", payload_size as f64);
+        metrics::gauge!(
+            "server.change_stream.last_published_miniblock",
+            number.0 as f64
+        );
+        Ok(true)
+    }
+
+    /// Waits until a new miniblock is sealed or the poll interval elapses.
+    async fn wait_for_miniblocks(&mut self) {
+        let poll_interval = self.config.poll_interval();
+        if let Some(sealed_miniblocks) = &mut self.sealed_miniblocks {
+            let changed = tokio::time::timeout(poll_interval, sealed_miniblocks.changed()).await;
+            if let Ok(Err(_)) = changed {
+                // The sealer has stopped; fall back to polling.
+                self.sealed_miniblocks = None;
+                tokio::time::sleep(poll_interval).await;
+            }
+        } else {
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) {
+        let mut next_miniblock = self.first_miniblock_to_publish().await;
+        vlog::info!(
+            "Starting change stream publisher `{}` from miniblock #{next_miniblock}",
+            self.config.publisher_name()
+        );
+
+        loop {
+            if *stop_receiver.borrow() {
+                vlog::info!("Stop signal received, change stream publisher is shutting down");
+                break;
+            }
+
+            match self.publish_miniblock(next_miniblock).await {
+                Ok(true) => {
+                    next_miniblock += 1;
+                }
+                Ok(false) => {
+                    tokio::select! {
+                        _ = stop_receiver.changed() => {}
+                        () = self.wait_for_miniblocks() => {}
+                    }
+                }
+                Err(err) => {
+                    vlog::warn!(
+                        "Failed publishing miniblock #{next_miniblock} to the change stream: {err:#}; \
+                         retrying in {:?}",
+                        self.config.retry_interval()
+                    );
+                    metrics::increment_counter!("server.change_stream.errors");
+                    tokio::select! {
+                        _ = stop_receiver.changed() => {}
+                        () = tokio::time::sleep(self.config.retry_interval()) => {}
+                    }
+                }
+            }
+        }
+    }
+}
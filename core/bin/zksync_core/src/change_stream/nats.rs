@@ -0,0 +1,53 @@
+//! NATS JetStream sink for the change stream.
+
+use async_nats::{jetstream, HeaderMap};
+use async_trait::async_trait;
+
+use std::fmt;
+
+use super::ChangeStreamSink;
+
+/// Sink publishing messages to a NATS JetStream subject. JetStream acknowledges a message only after
+/// it's persisted in the stream; the `Nats-Msg-Id` header allows JetStream to discard duplicates.
+pub struct NatsSink {
+    jetstream: jetstream::Context,
+    subject: String,
+}
+
+impl fmt::Debug for NatsSink {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("NatsSink")
+            .field("subject", &self.subject)
+            .finish_non_exhaustive()
+    }
+}
+
+impl NatsSink {
+    pub async fn connect(url: &str, subject: String) -> anyhow::Result<Self> {
+        let client = async_nats::connect(url)
+            .await
+            .map_err(|err| anyhow::anyhow!("failed connecting to NATS server at {url}: {err}"))?;
+        Ok(Self {
+            jetstream: jetstream::new(client),
+            subject,
+        })
+    }
+}
+
+#[async_trait]
+impl ChangeStreamSink for NatsSink {
+    async fn publish(&self, message_id: String, payload: Vec<u8>) -> anyhow::Result<()> {
+        let mut headers = HeaderMap::new();
+        headers.insert("Nats-Msg-Id", message_id.as_str());
+        let ack = self
+            .jetstream
+            .publish_with_headers(self.subject.clone(), headers, payload.into())
+            .await
+            .map_err(|err| anyhow::anyhow!("failed publishing message {message_id}: {err}"))?;
+        ack.await.map_err(|err| {
+            anyhow::anyhow!("message {message_id} was not acknowledged by JetStream: {err}")
+        })?;
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+use db_test_macro::db_test;
+use zksync_contracts::BaseSystemContractsHashes;
+use zksync_types::{AccountTreeId, StorageKey, StorageLog};
+use zksync_utils::miniblock_hash;
+
+use super::*;
+
+#[derive(Debug, Clone, Default)]
+struct MockSink {
+    messages: Arc<Mutex<Vec<(String, serde_json::Value)>>>,
+    fail: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl ChangeStreamSink for MockSink {
+    async fn publish(&self, message_id: String, payload: Vec<u8>) -> anyhow::Result<()> {
+        if self.fail.load(Ordering::SeqCst) {
+            anyhow::bail!("broker is unavailable");
+        }
+        let payload = serde_json::from_slice(&payload)?;
+        self.messages.lock().unwrap().push((message_id, payload));
+        Ok(())
+    }
+}
+
+fn test_config() -> ChangeStreamConfig {
+    ChangeStreamConfig {
+        nats_url: "nats://127.0.0.1:4222".to_owned(),
+        subject: "zksync.miniblocks".to_owned(),
+        publisher_name: Some("test".to_owned()),
+        start_miniblock: Some(1),
+        poll_interval_ms: Some(10),
+        retry_interval_ms: Some(10),
+    }
+}
+
+async fn seal_miniblock(pool: &ConnectionPool, number: u32) -> Vec<StorageLog> {
+    let mut storage = pool.access_test_storage().await;
+    let number = MiniblockNumber(number);
+    let header = MiniblockHeader {
+        number,
+        timestamp: number.0.into(),
+        hash: miniblock_hash(number),
+        l1_tx_count: 0,
+        l2_tx_count: 0,
+        base_fee_per_gas: 100,
+        l1_gas_price: 100,
+        l2_fair_gas_price: 100,
+        base_system_contracts_hashes: BaseSystemContractsHashes::default(),
+    };
+    storage.blocks_dal().insert_miniblock(&header).await;
+
+    let key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(1)), H256::zero());
+    let logs = vec![StorageLog::new_write_log(
+        key,
+        H256::from_low_u64_be(number.0.into()),
+    )];
+    storage
+        .storage_logs_dal()
+        .insert_storage_logs(number, &[(H256::zero(), logs.clone())])
+        .await;
+    logs
+}
+
+async fn last_published_miniblock(pool: &ConnectionPool) -> Option<MiniblockNumber> {
+    let mut storage = pool.access_test_storage().await;
+    storage
+        .change_stream_dal()
+        .get_last_published_miniblock("test")
+        .await
+        .unwrap()
+}
+
+#[db_test]
+async fn publishing_sealed_miniblocks(pool: ConnectionPool) {
+    let mut storage = pool.access_test_storage().await;
+    storage
+        .blocks_dal()
+        .delete_miniblocks(MiniblockNumber(0))
+        .await;
+    drop(storage);
+    let logs = seal_miniblock(&pool, 1).await;
+
+    let sink = MockSink::default();
+    let publisher = ChangeStreamPublisher::new(test_config(), pool.clone(), Box::new(sink.clone()));
+    assert_eq!(
+        publisher.first_miniblock_to_publish().await,
+        MiniblockNumber(1)
+    );
+    assert!(publisher
+        .publish_miniblock(MiniblockNumber(1))
+        .await
+        .unwrap());
+    assert!(!publisher
+        .publish_miniblock(MiniblockNumber(2))
+        .await
+        .unwrap());
+    assert_eq!(
+        last_published_miniblock(&pool).await,
+        Some(MiniblockNumber(1))
+    );
+
+    let messages = sink.messages.lock().unwrap().clone();
+    assert_eq!(messages.len(), 1);
+    let (message_id, payload) = &messages[0];
+    assert_eq!(
+        *message_id,
+        format!("1:{:?}", miniblock_hash(MiniblockNumber(1)))
+    );
+    assert_eq!(payload["header"]["number"], 1);
+    assert_eq!(payload["transactions"], serde_json::json!([]));
+    let storage_writes = payload["storageWrites"].as_array().unwrap();
+    assert_eq!(storage_writes.len(), 1);
+    assert_eq!(
+        storage_writes[0]["value"],
+        serde_json::to_value(logs[0].value).unwrap()
+    );
+
+    // The offset is resumed after a restart.
+    let publisher = ChangeStreamPublisher::new(test_config(), pool.clone(), Box::new(sink));
+    assert_eq!(
+        publisher.first_miniblock_to_publish().await,
+        MiniblockNumber(2)
+    );
+}
+
+#[db_test]
+async fn failed_publishing_does_not_advance_offset(pool: ConnectionPool) {
+    let mut storage = pool.access_test_storage().await;
+    storage
+        .blocks_dal()
+        .delete_miniblocks(MiniblockNumber(0))
+        .await;
+    drop(storage);
+    seal_miniblock(&pool, 1).await;
+
+    let sink = MockSink::default();
+    sink.fail.store(true, Ordering::SeqCst);
+    let publisher = ChangeStreamPublisher::new(test_config(), pool.clone(), Box::new(sink.clone()));
+    publisher
+        .publish_miniblock(MiniblockNumber(1))
+        .await
+        .unwrap_err();
+    assert_eq!(last_published_miniblock(&pool).await, None);
+
+    sink.fail.store(false, Ordering::SeqCst);
+    assert!(publisher
+        .publish_miniblock(MiniblockNumber(1))
+        .await
+        .unwrap());
+    assert_eq!(
+        last_published_miniblock(&pool).await,
+        Some(MiniblockNumber(1))
+    );
+    assert_eq!(sink.messages.lock().unwrap().len(), 1);
+}
@@ -3,6 +3,37 @@ use zksync_dal::ConnectionPool;
 use zksync_types::web3::{error, ethabi, transports::Http, types::TransactionId, Web3};
 use zksync_types::L1BatchNumber;
 
+/// Extracts the commitment for the specified L1 batch from the calldata of a `commitBlocks()` call.
+/// Returns `None` if the call doesn't commit the batch.
+///
+/// # Panics
+///
+/// Panics if the calldata doesn't match the `commitBlocks()` function ABI.
+pub(crate) fn extract_batch_commitment(
+    contract: &ethabi::Contract,
+    calldata: &[u8],
+    batch_number: L1BatchNumber,
+) -> Option<ethabi::Token> {
+    let mut commitments = contract
+        .function("commitBlocks")
+        .unwrap()
+        .decode_input(&calldata[4..])
+        .unwrap()
+        .pop()
+        .unwrap()
+        .into_array()
+        .unwrap();
+
+    // Commit transactions usually publish multiple commitments at once, so we need to find
+    // the one that corresponds to the batch we're checking.
+    let first_batch_number = match commitments.first()? {
+        ethabi::Token::Tuple(tuple) => tuple[0].clone().into_uint().unwrap().as_usize(),
+        _ => panic!("ABI does not match the commitBlocks() function on the zkSync contract"),
+    };
+    let index = (batch_number.0 as usize).checked_sub(first_batch_number)?;
+    (index < commitments.len()).then(|| commitments.swap_remove(index))
+}
+
 #[derive(Debug)]
 pub struct ConsistencyChecker {
     // ABI of the zkSync contract
@@ -91,26 +122,10 @@ impl ConsistencyChecker {
             "Main node gave us a failed commit tx"
         );
 
-        let commitments = self
-            .contract
-            .function("commitBlocks")
-            .unwrap()
-            .decode_input(&commit_tx.input.0[4..])
-            .unwrap()
-            .pop()
-            .unwrap()
-            .into_array()
-            .unwrap();
-
-        // Commit transactions usually publish multiple commitments at once, so we need to find
-        // the one that corresponds to the batch we're checking.
-        let first_batch_number = match &commitments[0] {
-            ethabi::Token::Tuple(tuple) => tuple[0].clone().into_uint().unwrap().as_usize(),
-            _ => panic!("ABI does not match the commitBlocks() function on the zkSync contract"),
-        };
-        let commitment = &commitments[batch_number.0 as usize - first_batch_number];
-
-        Ok(commitment == &block_metadata.l1_commit_data())
+        let commitment = extract_batch_commitment(&self.contract, &commit_tx.input.0, batch_number)
+            .expect("Commit tx does not commit the checked batch");
+
+        Ok(commitment == block_metadata.l1_commit_data())
     }
 
     async fn last_committed_batch(&self) -> L1BatchNumber {
@@ -0,0 +1,277 @@
+//! Offline export of transactions, events and storage logs to the object store for analytics.
+//!
+//! Data is exported in chunks of miniblocks as CSV files with a header row, one object per table and chunk
+//! (`{export_name}/{table}/{from_miniblock}_{to_miniblock}.csv`, with the exclusive upper bound). Export progress
+//! is saved to the `{export_name}/progress.json` object after each chunk, so an interrupted export resumes
+//! from the first chunk not yet exported. Chunks without rows are not written.
+//!
+//! Only CSV is supported; Parquet output would require an Arrow / Parquet writer, which is not a dependency
+//! of the server.
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+
+use std::{collections::BTreeMap, ops::RangeInclusive};
+
+use zksync_dal::{data_export_dal::ExportedTable, ConnectionPool};
+use zksync_object_store::{Bucket, ObjectStore, ObjectStoreError, ObjectStoreFactory};
+use zksync_types::MiniblockNumber;
+
+/// Progress of an export saved to the object store.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportProgress {
+    first_miniblock: u32,
+    last_miniblock: u32,
+    chunk_size: u32,
+    /// Next miniblock to export for each table that was started.
+    next_miniblocks: BTreeMap<String, u32>,
+}
+
+impl ExportProgress {
+    fn new(miniblocks: &RangeInclusive<MiniblockNumber>, chunk_size: u32) -> Self {
+        Self {
+            first_miniblock: miniblocks.start().0,
+            last_miniblock: miniblocks.end().0,
+            chunk_size,
+            next_miniblocks: BTreeMap::new(),
+        }
+    }
+
+    /// Checks that the saved progress corresponds to the same export parameters.
+    fn ensure_matches(&self, expected: &Self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.first_miniblock == expected.first_miniblock
+                && self.last_miniblock == expected.last_miniblock
+                && self.chunk_size == expected.chunk_size,
+            "export was started with miniblocks {}..={} and chunk size {}; use a different export name \
+             to export another range",
+            self.first_miniblock,
+            self.last_miniblock,
+            self.chunk_size
+        );
+        Ok(())
+    }
+
+    fn next_miniblock(&self, table: ExportedTable) -> u32 {
+        self.next_miniblocks
+            .get(table.as_str())
+            .copied()
+            .unwrap_or(self.first_miniblock)
+    }
+}
+
+/// Exports chain data for a miniblock range to the [`Bucket::DataExports`] bucket of the object store.
+#[derive(Debug)]
+pub struct DataExporter {
+    pool: ConnectionPool,
+    object_store: Box<dyn ObjectStore>,
+    export_name: String,
+    miniblocks: RangeInclusive<MiniblockNumber>,
+    chunk_size: u32,
+}
+
+impl DataExporter {
+    pub async fn new(
+        store_factory: &ObjectStoreFactory,
+        pool: ConnectionPool,
+        export_name: String,
+        miniblocks: RangeInclusive<MiniblockNumber>,
+        chunk_size: u32,
+    ) -> Self {
+        assert!(chunk_size > 0, "chunk size must be positive");
+        Self {
+            pool,
+            object_store: store_factory.create_store().await,
+            export_name,
+            miniblocks,
+            chunk_size,
+        }
+    }
+
+    fn progress_key(&self) -> String {
+        format!("{}/progress.json", self.export_name)
+    }
+
+    async fn load_progress(&self) -> anyhow::Result<ExportProgress> {
+        let expected = ExportProgress::new(&self.miniblocks, self.chunk_size);
+        let progress = match self
+            .object_store
+            .get_raw(Bucket::DataExports, &self.progress_key())
+            .await
+        {
+            Ok(bytes) => serde_json::from_slice::<ExportProgress>(&bytes)
+                .context("failed deserializing export progress")?,
+            Err(ObjectStoreError::KeyNotFound(_)) => return Ok(expected),
+            Err(err) => return Err(err).context("failed loading export progress"),
+        };
+        progress.ensure_matches(&expected)?;
+        Ok(progress)
+    }
+
+    async fn save_progress(&self, progress: &ExportProgress) -> anyhow::Result<()> {
+        let bytes = serde_json::to_vec(progress).context("failed serializing export progress")?;
+        self.object_store
+            .put_raw(Bucket::DataExports, &self.progress_key(), bytes)
+            .await
+            .context("failed saving export progress")
+    }
+
+    /// Exports the specified tables, resuming a previously interrupted export with the same name if any.
+    pub async fn export(&self, tables: &[ExportedTable]) -> anyhow::Result<()> {
+        let mut progress = self.load_progress().await?;
+        for &table in tables {
+            self.export_table(table, &mut progress)
+                .await
+                .with_context(|| format!("failed exporting table `{table}`"))?;
+        }
+        Ok(())
+    }
+
+    async fn export_table(
+        &self,
+        table: ExportedTable,
+        progress: &mut ExportProgress,
+    ) -> anyhow::Result<()> {
+        let last_miniblock = self.miniblocks.end().0;
+        let mut chunk_start = progress.next_miniblock(table);
+        if chunk_start > last_miniblock {
+            vlog::info!("Table `{}` is already exported", table);
+            return Ok(());
+        }
+        if chunk_start > self.miniblocks.start().0 {
+            vlog::info!(
+                "Resuming export of table `{}` from miniblock #{}",
+                table,
+                chunk_start
+            );
+        }
+
+        let columns = table.columns();
+        let mut storage = self.pool.access_storage_tagged("data_exporter").await;
+        while chunk_start <= last_miniblock {
+            let chunk_end = chunk_start
+                .saturating_add(self.chunk_size)
+                .min(last_miniblock + 1);
+            let rows = storage
+                .data_export_dal()
+                .export_rows(
+                    table,
+                    MiniblockNumber(chunk_start)..MiniblockNumber(chunk_end),
+                )
+                .await;
+            if !rows.is_empty() {
+                let key = format!("{}/{table}/{chunk_start}_{chunk_end}.csv", self.export_name);
+                let csv = encode_csv(&columns, &rows);
+                let csv_len = csv.len();
+                self.object_store
+                    .put_raw(Bucket::DataExports, &key, csv)
+                    .await
+                    .with_context(|| format!("failed saving chunk `{key}`"))?;
+                metrics::counter!(
+                    "server.data_exporter.exported_bytes",
+                    csv_len as u64,
+                    "table" => table.as_str()
+                );
+            }
+            metrics::counter!(
+                "server.data_exporter.exported_rows",
+                rows.len() as u64,
+                "table" => table.as_str()
+            );
+
+            progress
+                .next_miniblocks
+                .insert(table.as_str().to_owned(), chunk_end);
+            self.save_progress(progress).await?;
+            vlog::info!(
+                "Exported {} rows of table `{}` for miniblocks {}..{}",
+                rows.len(),
+                table,
+                chunk_start,
+                chunk_end
+            );
+            chunk_start = chunk_end;
+        }
+        Ok(())
+    }
+}
+
+/// Encodes rows as CSV with a header row. `NULL` values are encoded as empty fields.
+fn encode_csv(columns: &[&str], rows: &[Vec<Option<String>>]) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    write_csv_row(&mut buffer, columns.iter().copied());
+    for row in rows {
+        write_csv_row(
+            &mut buffer,
+            row.iter().map(|value| value.as_deref().unwrap_or("")),
+        );
+    }
+    buffer
+}
+
+fn write_csv_row<'a>(buffer: &mut Vec<u8>, fields: impl Iterator<Item = &'a str>) {
+    for (i, field) in fields.enumerate() {
+        if i > 0 {
+            buffer.push(b',');
+        }
+        let needs_quoting = field.contains(|ch| matches!(ch, ',' | '"' | '\n' | '\r'));
+        if needs_quoting {
+            buffer.push(b'"');
+            buffer.extend_from_slice(field.replace('"', "\"\"").as_bytes());
+            buffer.push(b'"');
+        } else {
+            buffer.extend_from_slice(field.as_bytes());
+        }
+    }
+    buffer.push(b'\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoding_csv() {
+        let rows = vec![
+            vec![Some("0x01".to_owned()), None],
+            vec![
+                Some("0x02".to_owned()),
+                Some("Error: \"out of gas\", reverted".to_owned()),
+            ],
+        ];
+        let csv = encode_csv(&["hash", "error"], &rows);
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "hash,error\n0x01,\n0x02,\"Error: \"\"out of gas\"\", reverted\"\n"
+        );
+    }
+
+    #[test]
+    fn resuming_export_progress() {
+        let miniblocks = MiniblockNumber(10)..=MiniblockNumber(100);
+        let mut progress = ExportProgress::new(&miniblocks, 50);
+        assert_eq!(progress.next_miniblock(ExportedTable::Events), 10);
+
+        progress
+            .next_miniblocks
+            .insert(ExportedTable::Events.as_str().to_owned(), 60);
+        let bytes = serde_json::to_vec(&progress).unwrap();
+        let restored: ExportProgress = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(restored, progress);
+        assert_eq!(restored.next_miniblock(ExportedTable::Events), 60);
+        assert_eq!(restored.next_miniblock(ExportedTable::Transactions), 10);
+
+        restored
+            .ensure_matches(&ExportProgress::new(&miniblocks, 50))
+            .unwrap();
+        let other_miniblocks = MiniblockNumber(10)..=MiniblockNumber(200);
+        let err = restored
+            .ensure_matches(&ExportProgress::new(&other_miniblocks, 50))
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("use a different export name"),
+            "{err}"
+        );
+    }
+}
@@ -11,9 +11,12 @@ use zksync_types::{
     L1BatchNumber,
 };
 
-use crate::eth_sender::block_publish_criterion::{
-    BlockNumberCriterion, BlockPublishCriterion, DataSizeCriterion, GasCriterion,
-    TimestampDeadlineCriterion,
+use crate::{
+    admin_controls::AdminControls,
+    eth_sender::block_publish_criterion::{
+        BlockNumberCriterion, BlockPublishCriterion, DataSizeCriterion, GasCriterion,
+        TimestampDeadlineCriterion,
+    },
 };
 
 #[derive(Debug)]
@@ -22,6 +25,7 @@ pub struct Aggregator {
     proof_criterion: Vec<Box<dyn BlockPublishCriterion>>,
     execute_criterion: Vec<Box<dyn BlockPublishCriterion>>,
     config: SenderConfig,
+    admin_controls: AdminControls,
 }
 
 impl Aggregator {
@@ -80,9 +84,20 @@ impl Aggregator {
                 }),
             ],
             config,
+            admin_controls: AdminControls::default(),
         }
     }
 
+    /// Sets the operator controls allowing to skip the L1 batch execution delay.
+    pub fn with_admin_controls(mut self, admin_controls: AdminControls) -> Self {
+        self.admin_controls = admin_controls;
+        self
+    }
+
+    pub(super) fn admin_controls(&self) -> &AdminControls {
+        &self.admin_controls
+    }
+
     pub async fn get_next_ready_operation(
         &mut self,
         storage: &mut StorageProcessor<'_>,
@@ -135,6 +150,9 @@ impl Aggregator {
             .blocks_dal()
             .get_ready_for_execute_blocks(limit, max_l1_batch_timestamp_millis)
             .await;
+        let ready_for_execute_blocks = self
+            .apply_execution_delay(storage, ready_for_execute_blocks)
+            .await;
         let blocks = extract_ready_subrange(
             storage,
             &mut self.execute_criterion,
@@ -146,6 +164,34 @@ impl Aggregator {
         blocks.map(|blocks| BlocksExecuteOperation { blocks })
     }
 
+    /// Retains only L1 batches for which the execution delay has passed since their proofs were confirmed on L1,
+    /// or for which the delay was skipped by the operator.
+    async fn apply_execution_delay(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        mut blocks: Vec<BlockWithMetadata>,
+    ) -> Vec<BlockWithMetadata> {
+        let Some(delay) = self.config.l1_batch_execution_delay() else {
+            return blocks;
+        };
+        if blocks.is_empty() {
+            return blocks;
+        }
+
+        let max_confirmed_at_seconds = unix_timestamp_ms() as f64 / 1_000.0 - delay.as_secs_f64();
+        let last_ready_block = storage
+            .blocks_dal()
+            .get_last_l1_batch_proven_before(max_confirmed_at_seconds)
+            .await;
+        let last_ready_block =
+            last_ready_block.max(self.admin_controls.execution_delay_skipped_until());
+        let Some(last_ready_block) = last_ready_block else {
+            return vec![];
+        };
+        blocks.retain(|block| block.header.number <= last_ready_block);
+        blocks
+    }
+
     async fn get_commit_operation(
         &mut self,
         storage: &mut StorageProcessor<'_>,
@@ -1,14 +1,30 @@
 use crate::eth_sender::grafana_metrics::track_eth_tx_metrics;
+use crate::eth_sender::operator_balance::{report_operator_balance, OperatorBalanceTracker};
+use crate::eth_sender::pubdata_da::{PubdataCost, PubdataDAChoice};
 use crate::eth_sender::zksync_functions::ZkSyncFunctions;
 use crate::eth_sender::{zksync_functions, Aggregator, ETHSenderError};
 use crate::gas_tracker::agg_block_base_cost;
-use std::cmp::max;
+use std::{cmp::max, time::Instant};
 use tokio::sync::watch;
-use zksync_config::configs::eth_sender::SenderConfig;
+use zksync_config::configs::eth_sender::{PubdataSendingMode, SenderConfig};
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_eth_client::BoundEthInterface;
-use zksync_types::{aggregated_operations::AggregatedOperation, eth_sender::EthTx, Address, H256};
+use zksync_types::{
+    aggregated_operations::{AggregatedActionType, AggregatedOperation, BlocksCommitOperation},
+    eth_sender::EthTx,
+    web3::types::{Bytes, CallRequest},
+    Address, L1BatchNumber, H256, U256,
+};
+
+/// Outcome of simulating an aggregated operation in the dry-run mode.
+#[derive(Debug)]
+pub(super) struct EthTxSimulation {
+    pub calldata_size: usize,
+    pub predicted_gas: u32,
+    /// Gas estimated by L1, or an error message if the simulated transaction fails.
+    pub estimated_gas: Result<U256, String>,
+}
 
 /// The component is responsible for aggregating l1 batches into eth_txs:
 /// Such as CommitBlocks, PublishProofBlocksOnchain and ExecuteBlock
@@ -20,6 +36,11 @@ pub struct EthTxAggregator {
     contract_address: Address,
     functions: ZkSyncFunctions,
     base_nonce: u64,
+    /// Last operation simulated in the dry-run mode, used to not repeat the simulation on each iteration.
+    last_simulated_op: Option<(AggregatedActionType, L1BatchNumber, L1BatchNumber)>,
+    operator_balance: OperatorBalanceTracker,
+    /// Whether aggregation was paused on the previous iteration, used to log pause state transitions.
+    aggregation_paused: bool,
 }
 
 impl EthTxAggregator {
@@ -36,6 +57,9 @@ impl EthTxAggregator {
             config,
             contract_address,
             functions,
+            last_simulated_op: None,
+            operator_balance: OperatorBalanceTracker::default(),
+            aggregation_paused: false,
         }
     }
 
@@ -97,6 +121,51 @@ impl EthTxAggregator {
         })
     }
 
+    /// Checks the operator balance on L1. Returns `true` if aggregation of new operations should be paused
+    /// because the balance is below the configured floor. Transactions that are already created
+    /// are still sent by `EthTxManager`.
+    pub(super) async fn check_operator_balance<E: BoundEthInterface>(
+        &mut self,
+        eth_client: &E,
+    ) -> Result<bool, ETHSenderError> {
+        let balance = eth_client.sender_eth_balance("eth_tx_aggregator").await?;
+        let runway = self.operator_balance.record(balance, Instant::now());
+        let paused = self
+            .config
+            .operator_balance_floor()
+            .map_or(false, |floor| balance < floor);
+
+        if let Some(runway) = runway {
+            if runway < self.config.operator_runway_alert_threshold() {
+                vlog::warn!(
+                    "Operator balance {} wei is projected to run out in {:?}",
+                    balance,
+                    runway
+                );
+            }
+        }
+        if paused != self.aggregation_paused {
+            if paused {
+                vlog::warn!(
+                    "Operator balance {} wei is below the configured floor; pausing aggregation of new operations",
+                    balance
+                );
+            } else {
+                vlog::info!(
+                    "Operator balance {} wei is above the configured floor; resuming aggregation",
+                    balance
+                );
+            }
+            self.aggregation_paused = paused;
+        }
+
+        let operator_balance = report_operator_balance(balance, runway, paused);
+        self.aggregator
+            .admin_controls()
+            .set_operator_balance(operator_balance);
+        Ok(paused)
+    }
+
     #[tracing::instrument(skip(self, storage, eth_client))]
     async fn loop_iteration<E: BoundEthInterface>(
         &mut self,
@@ -104,6 +173,9 @@ impl EthTxAggregator {
         prover_storage: &mut StorageProcessor<'_>,
         eth_client: &E,
     ) -> Result<(), ETHSenderError> {
+        if self.check_operator_balance(eth_client).await? {
+            return Ok(());
+        }
         let base_system_contracts_hashes =
             self.get_l1_base_system_contracts_hashes(eth_client).await?;
         if let Some(agg_op) = self
@@ -111,12 +183,57 @@ impl EthTxAggregator {
             .get_next_ready_operation(storage, prover_storage, base_system_contracts_hashes)
             .await
         {
-            let tx = self.save_eth_tx(storage, &agg_op).await?;
+            if self.config.dry_run() {
+                let (first_block, last_block) = agg_op.get_block_range();
+                let op_key = (agg_op.get_action_type(), first_block, last_block);
+                if self.last_simulated_op != Some(op_key) {
+                    self.simulate_eth_tx(storage, &agg_op, eth_client).await;
+                    self.last_simulated_op = Some(op_key);
+                }
+                return Ok(());
+            }
+
+            let pubdata_da = if let AggregatedOperation::CommitBlocks(commit_op) = &agg_op {
+                Some(self.choose_pubdata_da(commit_op, eth_client).await?)
+            } else {
+                None
+            };
+            let tx = self.save_eth_tx(storage, &agg_op, pubdata_da).await?;
             Self::log_eth_tx_saving(storage, agg_op, &tx).await;
         }
         Ok(())
     }
 
+    /// Chooses the data availability mode for the commit operation based on the current L1 fees.
+    async fn choose_pubdata_da<E: BoundEthInterface>(
+        &self,
+        commit_op: &BlocksCommitOperation,
+        eth_client: &E,
+    ) -> Result<PubdataDAChoice, ETHSenderError> {
+        let mode = self.config.pubdata_sending_mode();
+        let gas_price = eth_client
+            .get_pending_block_base_fee_per_gas("eth_sender")
+            .await?;
+        let blob_base_fee =
+            if mode != PubdataSendingMode::Calldata && eth_client.supports_blob_transactions() {
+                eth_client.blob_base_fee("eth_sender").await?
+            } else {
+                None
+            };
+
+        let batch_costs = commit_op
+            .pubdata_sizes()
+            .into_iter()
+            .map(|size| PubdataCost::new(size, gas_price, blob_base_fee))
+            .collect();
+        let choice = PubdataDAChoice::new(mode, batch_costs);
+        metrics::increment_counter!(
+            "server.eth_sender.pubdata_da",
+            "da" => choice.da.as_str()
+        );
+        Ok(choice)
+    }
+
     async fn log_eth_tx_saving(
         storage: &mut StorageProcessor<'_>,
         aggregated_op: AggregatedOperation,
@@ -137,6 +254,14 @@ impl EthTxAggregator {
                     block.metadata.l2_l1_messages_compressed.len() as f64,
                     "kind" => "l2_l1_messages_compressed"
                 );
+                if let Some(state_diffs) = &block.metadata.state_diffs_compressed {
+                    metrics::histogram!(
+                        "server.eth_sender.pubdata_size",
+                        state_diffs.len() as f64,
+                        "kind" => "state_diffs_compressed"
+                    );
+                    continue;
+                }
                 metrics::histogram!(
                     "server.eth_sender.pubdata_size",
                     block.metadata.initial_writes_compressed.len() as f64,
@@ -177,10 +302,78 @@ impl EthTxAggregator {
         .to_vec()
     }
 
+    /// Simulates the transaction for the aggregated operation via `eth_call` without saving or sending it.
+    pub(super) async fn simulate_eth_tx<E: BoundEthInterface>(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        aggregated_op: &AggregatedOperation,
+        eth_client: &E,
+    ) -> EthTxSimulation {
+        let calldata = self.encode_aggregated_op(aggregated_op);
+        let (first_block, last_block) = aggregated_op.get_block_range();
+        let op_type = aggregated_op.get_action_type();
+        let blocks_predicted_gas = storage
+            .blocks_dal()
+            .get_blocks_predicted_gas(first_block, last_block, op_type)
+            .await;
+        let predicted_gas = agg_block_base_cost(op_type) + blocks_predicted_gas;
+
+        let request = CallRequest {
+            from: Some(eth_client.sender_account()),
+            to: Some(self.contract_address),
+            data: Some(Bytes(calldata.clone())),
+            ..CallRequest::default()
+        };
+        // `eth_call` provides a revert reason on failure, so it's performed before the gas estimation.
+        let estimated_gas = match eth_client.call(request.clone(), None, "eth_sender").await {
+            Ok(_) => eth_client
+                .estimate_gas(request, "eth_sender")
+                .await
+                .map_err(|err| err.to_string()),
+            Err(err) => Err(err.to_string()),
+        };
+
+        let caption = aggregated_op.get_action_caption();
+        match &estimated_gas {
+            Ok(gas) => vlog::info!(
+                "Dry run: {caption} ({first_block}-{last_block}) succeeded; calldata size: {} bytes, \
+                 estimated gas: {gas}, predicted gas: {predicted_gas}",
+                calldata.len()
+            ),
+            Err(err) => vlog::warn!(
+                "Dry run: {caption} ({first_block}-{last_block}) failed; calldata size: {} bytes, \
+                 predicted gas: {predicted_gas}, error: {err}",
+                calldata.len()
+            ),
+        }
+        let outcome = if estimated_gas.is_ok() {
+            "success"
+        } else {
+            "failure"
+        };
+        metrics::increment_counter!(
+            "server.eth_sender.dry_run",
+            "type" => op_type.to_string(),
+            "outcome" => outcome
+        );
+        metrics::histogram!(
+            "server.eth_sender.dry_run_calldata_size",
+            calldata.len() as f64,
+            "type" => op_type.to_string()
+        );
+
+        EthTxSimulation {
+            calldata_size: calldata.len(),
+            predicted_gas,
+            estimated_gas,
+        }
+    }
+
     pub(super) async fn save_eth_tx(
         &self,
         storage: &mut StorageProcessor<'_>,
         aggregated_op: &AggregatedOperation,
+        pubdata_da: Option<PubdataDAChoice>,
     ) -> Result<EthTx, ETHSenderError> {
         let mut transaction = storage.start_transaction().await;
         let nonce = self.get_next_nonce(&mut transaction).await?;
@@ -209,6 +402,15 @@ impl EthTxAggregator {
             .blocks_dal()
             .set_eth_tx_id(first_block, last_block, eth_tx.id, op_type)
             .await;
+        if let Some(pubdata_da) = pubdata_da {
+            let block_numbers = (first_block.0..=last_block.0).map(L1BatchNumber);
+            for (block_number, cost) in block_numbers.zip(pubdata_da.batch_costs) {
+                transaction
+                    .blocks_dal()
+                    .set_pubdata_da(block_number, pubdata_da.da, cost.calldata, cost.blobs)
+                    .await;
+            }
+        }
         transaction.commit().await;
         Ok(eth_tx)
     }
@@ -15,7 +15,11 @@ use zksync_types::{
 use zksync_utils::time::seconds_since_epoch;
 
 use crate::eth_sender::ETHSenderError;
-use crate::{eth_sender::grafana_metrics::track_eth_tx_metrics, l1_gas_price::L1TxParamsProvider};
+use crate::{
+    eth_sender::grafana_metrics::track_eth_tx_metrics,
+    l1_batch_status_bus::{L1BatchStage, L1BatchStatusBus},
+    l1_gas_price::L1TxParamsProvider,
+};
 
 #[derive(Debug)]
 struct EthFee {
@@ -47,6 +51,7 @@ pub struct EthTxManager<E, G> {
     ethereum_gateway: E,
     config: SenderConfig,
     gas_adjuster: Arc<G>,
+    l1_batch_status_bus: Option<L1BatchStatusBus>,
 }
 
 impl<E, G> EthTxManager<E, G>
@@ -59,9 +64,16 @@ where
             ethereum_gateway,
             config,
             gas_adjuster,
+            l1_batch_status_bus: None,
         }
     }
 
+    /// Makes the manager publish L1 batches committed, proven or executed on L1 to the provided bus.
+    pub fn with_l1_batch_status_bus(mut self, bus: L1BatchStatusBus) -> Self {
+        self.l1_batch_status_bus = Some(bus);
+        self
+    }
+
     async fn get_tx_status(
         &self,
         tx_hash: H256,
@@ -312,6 +324,7 @@ where
         );
 
         let operator_nonce = self.get_operator_nonce(l1_block_numbers).await?;
+        self.reconcile_nonces(storage, operator_nonce).await;
 
         let inflight_txs = storage.eth_sender_dal().get_inflight_txs().await;
         metrics::gauge!(
@@ -381,6 +394,123 @@ where
         Ok(None)
     }
 
+    /// Reconciles nonces of unconfirmed eth_txs with the operator nonce on L1. Nonces get out of sync
+    /// if transactions are sent from the operator account bypassing eth_sender (e.g., to cancel stuck transactions).
+    ///
+    /// Nonces are reassigned only if no transaction sent by eth_sender can be mined with its current nonce;
+    /// otherwise, ordering of L1 operations cannot be guaranteed, and the desync is reported to be resolved
+    /// by the operator.
+    async fn reconcile_nonces(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        operator_nonce: OperatorNonce,
+    ) {
+        let inflight_txs = storage.eth_sender_dal().get_inflight_txs().await;
+        let new_txs = storage.eth_sender_dal().get_new_eth_txs(1).await;
+        let Some(first_tx) = inflight_txs.first().or(new_txs.first()) else {
+            metrics::gauge!("server.eth_sender.nonce_desync", 0.0);
+            return;
+        };
+
+        if first_tx.nonce > operator_nonce.latest {
+            // There is a gap in nonces, so none of the transactions can be mined until it's filled.
+            if inflight_txs.is_empty() {
+                self.reassign_nonces(storage, &[], first_tx.id, operator_nonce.latest)
+                    .await;
+            } else {
+                metrics::gauge!("server.eth_sender.nonce_desync", 1.0);
+                vlog::error!(
+                    "Nonce gap detected: operator nonce is {}, while the first inflight eth_tx {} has nonce {}. \
+                     Nonces {}..{} must be used by transactions sent from the operator account",
+                    operator_nonce.latest,
+                    first_tx.id,
+                    first_tx.nonce,
+                    operator_nonce.latest,
+                    first_tx.nonce
+                );
+            }
+            return;
+        }
+        // If the nonce is not finalized yet, we wait for it to be; the transaction having consumed it
+        // may still be reorged.
+        if first_tx.nonce >= operator_nonce.finalized {
+            metrics::gauge!("server.eth_sender.nonce_desync", 0.0);
+            return;
+        }
+        if inflight_txs.is_empty() {
+            // None of the transactions were sent, so their nonces can be reassigned freely.
+            self.reassign_nonces(storage, &[], first_tx.id, operator_nonce.latest)
+                .await;
+            return;
+        }
+
+        let mut consumed_tx_ids = vec![];
+        for tx in &inflight_txs {
+            if tx.nonce >= operator_nonce.finalized
+                || self.check_all_sending_attempts(storage, tx).await.is_some()
+            {
+                break;
+            }
+            consumed_tx_ids.push(tx.id);
+        }
+        if consumed_tx_ids.is_empty() {
+            // The first transaction is mined by eth_sender; it's processed by the inflight tx monitoring.
+            return;
+        }
+
+        if let Some(next_tx) = inflight_txs.get(consumed_tx_ids.len()) {
+            // The next transaction is either mined, or can be mined with its current nonce.
+            metrics::gauge!("server.eth_sender.nonce_desync", 1.0);
+            vlog::error!(
+                "Nonces of eth_txs {:?} were consumed by transactions sent from the operator account, \
+                 but eth_tx {} with nonce {} is mined or may be mined with its current nonce. \
+                 Transactions with nonces {}..{} must be replaced to restore ordering of L1 operations",
+                consumed_tx_ids,
+                next_tx.id,
+                next_tx.nonce,
+                next_tx.nonce,
+                inflight_txs.last().unwrap().nonce + 1
+            );
+            return;
+        }
+        self.reassign_nonces(
+            storage,
+            &consumed_tx_ids,
+            first_tx.id,
+            operator_nonce.latest,
+        )
+        .await;
+    }
+
+    async fn reassign_nonces(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        consumed_tx_ids: &[u32],
+        first_tx_id: u32,
+        first_nonce: Nonce,
+    ) {
+        let mut transaction = storage.start_transaction().await;
+        transaction
+            .eth_sender_dal()
+            .mark_nonces_consumed_externally(consumed_tx_ids)
+            .await;
+        transaction
+            .eth_sender_dal()
+            .reassign_nonces(first_tx_id, first_nonce)
+            .await;
+        transaction.commit().await;
+
+        metrics::gauge!("server.eth_sender.nonce_desync", 0.0);
+        metrics::increment_counter!("server.eth_sender.nonces_reassigned");
+        vlog::warn!(
+            "Nonces of eth_txs starting from {} were reassigned starting from {}; \
+             eth_txs {:?} had their nonces consumed by transactions sent from the operator account",
+            first_tx_id,
+            first_nonce,
+            consumed_tx_ids
+        );
+    }
+
     async fn sign_tx(
         &self,
         tx: &EthTx,
@@ -511,6 +641,12 @@ where
             .await;
 
         track_eth_tx_metrics(storage, "mined", tx).await;
+        if let Some(bus) = &self.l1_batch_status_bus {
+            let blocks = storage.blocks_dal().get_blocks_for_eth_tx_id(tx.id).await;
+            if let Some(last_block) = blocks.iter().map(|block| block.number).max() {
+                bus.publish(L1BatchStage::from_action_type(tx.tx_type), last_block);
+            }
+        }
 
         if gas_used > U256::from(tx.predicted_gas_cost) {
             vlog::error!(
@@ -5,6 +5,8 @@ mod error;
 mod eth_tx_aggregator;
 mod eth_tx_manager;
 mod grafana_metrics;
+mod operator_balance;
+mod pubdata_da;
 mod zksync_functions;
 
 #[cfg(test)]
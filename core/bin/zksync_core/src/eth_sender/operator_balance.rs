@@ -0,0 +1,118 @@
+//! Tracking of the operator ETH balance on L1, which pays for all L1 transactions sent by `eth_sender`.
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use zksync_types::{api::OperatorBalance, U256};
+
+/// Time window over which the spending rate of the operator balance is measured.
+const SPENDING_WINDOW: Duration = Duration::from_secs(6 * 3_600);
+
+/// Projects the operator balance runway based on balance samples. Top-ups of the balance reset
+/// the spending history.
+#[derive(Debug)]
+pub(super) struct OperatorBalanceTracker {
+    window: Duration,
+    samples: VecDeque<(Instant, U256)>,
+}
+
+impl Default for OperatorBalanceTracker {
+    fn default() -> Self {
+        Self::new(SPENDING_WINDOW)
+    }
+}
+
+impl OperatorBalanceTracker {
+    fn new(window: Duration) -> Self {
+        Self {
+            window,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Records a balance sample and returns the projected runway, or `None` if the balance
+    /// wasn't spent within the tracked window.
+    pub fn record(&mut self, balance: U256, now: Instant) -> Option<Duration> {
+        if let Some(&(_, last_balance)) = self.samples.back() {
+            if balance > last_balance {
+                self.samples.clear();
+            }
+        }
+        self.samples.push_back((now, balance));
+        while let Some(&(timestamp, _)) = self.samples.front() {
+            if now.duration_since(timestamp) <= self.window {
+                break;
+            }
+            self.samples.pop_front();
+        }
+
+        let &(first_timestamp, first_balance) = self.samples.front()?;
+        let spent = first_balance - balance;
+        let elapsed_secs = now.duration_since(first_timestamp).as_secs();
+        if spent.is_zero() || elapsed_secs == 0 {
+            return None;
+        }
+        let runway_secs = balance * U256::from(elapsed_secs) / spent;
+        Some(Duration::from_secs(
+            runway_secs.min(u64::MAX.into()).as_u64(),
+        ))
+    }
+}
+
+/// Reports the operator balance to metrics; returns the balance in the API format.
+pub(super) fn report_operator_balance(
+    balance: U256,
+    runway: Option<Duration>,
+    aggregation_paused: bool,
+) -> OperatorBalance {
+    let balance_gwei = balance / U256::exp10(9);
+    metrics::gauge!(
+        "server.eth_sender.operator_balance_gwei",
+        balance_gwei.min(u64::MAX.into()).as_u64() as f64
+    );
+    if let Some(runway) = runway {
+        metrics::gauge!("server.eth_sender.operator_runway", runway.as_secs_f64());
+    }
+    metrics::gauge!(
+        "server.eth_sender.aggregation_paused",
+        if aggregation_paused { 1.0 } else { 0.0 }
+    );
+    OperatorBalance {
+        balance,
+        runway_secs: runway.map(|runway| runway.as_secs()),
+        aggregation_paused,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn projecting_runway() {
+        let mut tracker = OperatorBalanceTracker::new(Duration::from_secs(100));
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert_eq!(tracker.record(U256::from(1_000), at(0)), None);
+        // 100 wei spent in 10 seconds, so the remaining 900 wei last for 90 seconds.
+        assert_eq!(
+            tracker.record(U256::from(900), at(10)),
+            Some(Duration::from_secs(90))
+        );
+        // The first sample falls out of the window; the rate is measured from the second one.
+        assert_eq!(
+            tracker.record(U256::from(850), at(105)),
+            Some(Duration::from_secs(1_615))
+        );
+
+        // A top-up resets the history.
+        assert_eq!(tracker.record(U256::from(2_000), at(110)), None);
+        assert_eq!(
+            tracker.record(U256::from(1_000), at(120)),
+            Some(Duration::from_secs(10))
+        );
+    }
+}
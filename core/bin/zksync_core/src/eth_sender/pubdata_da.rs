@@ -0,0 +1,103 @@
+//! Selection of the data availability mode for pubdata of committed L1 batches.
+
+use zksync_config::configs::eth_sender::PubdataSendingMode;
+use zksync_types::{aggregated_operations::PubdataDA, U256};
+
+/// Number of bytes that can be stored in a single blob (4096 field elements with 31 usable bytes each).
+const BLOB_DATA_SIZE: usize = 4_096 * 31;
+/// Blob gas consumed by a single blob.
+const GAS_PER_BLOB: u64 = 1 << 17;
+/// L1 gas charged per non-zero calldata byte.
+const CALLDATA_GAS_PER_BYTE: u64 = 16;
+
+/// Projected L1 cost of publishing pubdata of a single L1 batch.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct PubdataCost {
+    pub calldata: U256,
+    /// `None` if blobs are not available.
+    pub blobs: Option<U256>,
+}
+
+impl PubdataCost {
+    pub fn new(pubdata_size: usize, gas_price: U256, blob_base_fee: Option<U256>) -> Self {
+        let calldata = U256::from(pubdata_size) * CALLDATA_GAS_PER_BYTE * gas_price;
+        let blobs = blob_base_fee.map(|blob_base_fee| {
+            let blob_count = (pubdata_size + BLOB_DATA_SIZE - 1) / BLOB_DATA_SIZE;
+            U256::from(blob_count.max(1)) * GAS_PER_BLOB * blob_base_fee
+        });
+        Self { calldata, blobs }
+    }
+}
+
+/// Data availability mode chosen for a commit transaction together with costs of all committed batches.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct PubdataDAChoice {
+    pub da: PubdataDA,
+    pub batch_costs: Vec<PubdataCost>,
+}
+
+impl PubdataDAChoice {
+    pub fn new(mode: PubdataSendingMode, batch_costs: Vec<PubdataCost>) -> Self {
+        let calldata_cost = batch_costs
+            .iter()
+            .fold(U256::zero(), |acc, cost| acc + cost.calldata);
+        let blobs_cost = batch_costs
+            .iter()
+            .try_fold(U256::zero(), |acc, cost| Some(acc + cost.blobs?));
+
+        let da = match (mode, blobs_cost) {
+            (PubdataSendingMode::Blobs, Some(_)) => PubdataDA::Blobs,
+            (PubdataSendingMode::Auto, Some(blobs_cost)) if blobs_cost < calldata_cost => {
+                PubdataDA::Blobs
+            }
+            _ => PubdataDA::Calldata,
+        };
+        Self { da, batch_costs }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pubdata_cost() {
+        let cost = PubdataCost::new(1_000, 10.into(), Some(1.into()));
+        assert_eq!(cost.calldata, U256::from(160_000));
+        assert_eq!(cost.blobs, Some(U256::from(GAS_PER_BLOB)));
+
+        let cost = PubdataCost::new(BLOB_DATA_SIZE + 1, 10.into(), Some(1.into()));
+        assert_eq!(cost.blobs, Some(U256::from(2 * GAS_PER_BLOB)));
+        let cost = PubdataCost::new(BLOB_DATA_SIZE, 10.into(), None);
+        assert_eq!(cost.blobs, None);
+    }
+
+    #[test]
+    fn choosing_pubdata_da() {
+        let cheap_blobs = vec![PubdataCost::new(100_000, 10.into(), Some(1.into()))];
+        let expensive_blobs = vec![PubdataCost::new(1_000, 10.into(), Some(1.into()))];
+        let no_blobs = vec![PubdataCost::new(1_000, 10.into(), None)];
+
+        let choose = |mode, costs: &[PubdataCost]| PubdataDAChoice::new(mode, costs.to_vec()).da;
+        assert_eq!(
+            choose(PubdataSendingMode::Auto, &cheap_blobs),
+            PubdataDA::Blobs
+        );
+        assert_eq!(
+            choose(PubdataSendingMode::Auto, &expensive_blobs),
+            PubdataDA::Calldata
+        );
+        assert_eq!(
+            choose(PubdataSendingMode::Blobs, &expensive_blobs),
+            PubdataDA::Blobs
+        );
+        assert_eq!(
+            choose(PubdataSendingMode::Blobs, &no_blobs),
+            PubdataDA::Calldata
+        );
+        assert_eq!(
+            choose(PubdataSendingMode::Calldata, &cheap_blobs),
+            PubdataDA::Calldata
+        );
+    }
+}
@@ -15,13 +15,13 @@ use zksync_types::{
     block::L1BatchHeader,
     commitment::{BlockMetaParameters, BlockMetadata, BlockWithMetadata},
     helpers::unix_timestamp_ms,
-    Address, L1BatchNumber, L1BlockNumber, H256,
+    Address, L1BatchNumber, L1BlockNumber, H256, U256,
 };
 
 use crate::eth_sender::{
     eth_tx_manager::L1BlockNumbers, Aggregator, EthTxAggregator, EthTxManager,
 };
-use crate::l1_gas_price::GasAdjuster;
+use crate::{admin_controls::AdminControls, l1_gas_price::GasAdjuster};
 
 // Alias to conveniently call static methods of ETHSender.
 type MockEthTxManager = EthTxManager<Arc<MockEthereum>, GasAdjuster<Arc<MockEthereum>>>;
@@ -128,7 +128,7 @@ async fn confirm_many(connection_pool: ConnectionPool) -> anyhow::Result<()> {
     for _ in 0..5 {
         let tx = tester
             .aggregator
-            .save_eth_tx(&mut tester.storage().await, &DUMMY_OPERATION)
+            .save_eth_tx(&mut tester.storage().await, &DUMMY_OPERATION, None)
             .await?;
         let hash = tester
             .manager
@@ -199,7 +199,7 @@ async fn resend_each_block(connection_pool: ConnectionPool) -> anyhow::Result<()
     let block = L1BlockNumber(tester.gateway.block_number("").await?.as_u32());
     let tx = tester
         .aggregator
-        .save_eth_tx(&mut tester.storage().await, &DUMMY_OPERATION)
+        .save_eth_tx(&mut tester.storage().await, &DUMMY_OPERATION, None)
         .await?;
 
     let hash = tester
@@ -273,7 +273,7 @@ async fn dont_resend_already_mined(connection_pool: ConnectionPool) -> anyhow::R
     let mut tester = EthSenderTester::new(connection_pool, vec![100; 100], false).await;
     let tx = tester
         .aggregator
-        .save_eth_tx(&mut tester.storage().await, &DUMMY_OPERATION)
+        .save_eth_tx(&mut tester.storage().await, &DUMMY_OPERATION, None)
         .await
         .unwrap();
 
@@ -340,7 +340,7 @@ async fn three_scenarios(connection_pool: ConnectionPool) -> anyhow::Result<()>
     for _ in 0..3 {
         let tx = tester
             .aggregator
-            .save_eth_tx(&mut tester.storage().await, &DUMMY_OPERATION)
+            .save_eth_tx(&mut tester.storage().await, &DUMMY_OPERATION, None)
             .await
             .unwrap();
 
@@ -405,7 +405,7 @@ async fn failed_eth_tx(connection_pool: ConnectionPool) {
 
     let tx = tester
         .aggregator
-        .save_eth_tx(&mut tester.storage().await, &DUMMY_OPERATION)
+        .save_eth_tx(&mut tester.storage().await, &DUMMY_OPERATION, None)
         .await
         .unwrap();
 
@@ -435,6 +435,190 @@ async fn failed_eth_tx(connection_pool: ConnectionPool) {
         .unwrap();
 }
 
+// Tests that nonces are reassigned if the nonce of an inflight transaction was consumed
+// by a transaction sent from the operator account bypassing eth_sender.
+#[db_test]
+async fn reassign_nonce_consumed_externally(connection_pool: ConnectionPool) -> anyhow::Result<()> {
+    let mut tester = EthSenderTester::new(connection_pool, vec![100; 100], false).await;
+    let tx = tester
+        .aggregator
+        .save_eth_tx(&mut tester.storage().await, &DUMMY_OPERATION, None)
+        .await?;
+    tester
+        .manager
+        .send_eth_tx(
+            &mut tester.storage().await,
+            &tx,
+            0,
+            L1BlockNumber(tester.gateway.block_number("").await?.as_u32()),
+        )
+        .await?;
+
+    // The nonce is consumed, but not finalized yet: the transaction must not be touched.
+    tester
+        .gateway
+        .execute_external_tx(EthSenderTester::WAIT_CONFIRMATIONS - 1);
+    let to_resend = tester
+        .manager
+        .monitor_inflight_transactions(
+            &mut tester.storage().await,
+            tester.get_block_numbers().await,
+        )
+        .await?;
+    assert!(to_resend.is_none());
+
+    tester.gateway.advance_block_number(1);
+    let (to_resend, _) = tester
+        .manager
+        .monitor_inflight_transactions(
+            &mut tester.storage().await,
+            tester.get_block_numbers().await,
+        )
+        .await?
+        .expect("transaction with the reassigned nonce should be resent");
+    assert_eq!(to_resend.id, tx.id);
+    assert_eq!(to_resend.nonce.0, 1);
+
+    let hash = tester
+        .manager
+        .send_eth_tx(
+            &mut tester.storage().await,
+            &to_resend,
+            0,
+            L1BlockNumber(tester.gateway.block_number("").await?.as_u32()),
+        )
+        .await?;
+    confirm_tx(&mut tester, hash).await;
+    assert!(tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_inflight_txs()
+        .await
+        .is_empty());
+
+    // The next transaction gets the nonce following the reassigned ones.
+    let next_tx = tester
+        .aggregator
+        .save_eth_tx(&mut tester.storage().await, &DUMMY_OPERATION, None)
+        .await?;
+    assert_eq!(next_tx.nonce.0, 2);
+    Ok(())
+}
+
+// Tests that nonces are not reassigned if transactions sent by eth_sender after the one with the consumed nonce
+// may still be mined.
+#[db_test]
+async fn dont_reassign_nonces_with_pending_txs(
+    connection_pool: ConnectionPool,
+) -> anyhow::Result<()> {
+    let mut tester = EthSenderTester::new(connection_pool, vec![100; 100], false).await;
+    let mut txs = vec![];
+    for _ in 0..2 {
+        let tx = tester
+            .aggregator
+            .save_eth_tx(&mut tester.storage().await, &DUMMY_OPERATION, None)
+            .await?;
+        tester
+            .manager
+            .send_eth_tx(
+                &mut tester.storage().await,
+                &tx,
+                0,
+                L1BlockNumber(tester.gateway.block_number("").await?.as_u32()),
+            )
+            .await?;
+        txs.push(tx);
+    }
+
+    tester
+        .gateway
+        .execute_external_tx(EthSenderTester::WAIT_CONFIRMATIONS);
+    let (to_resend, _) = tester
+        .manager
+        .monitor_inflight_transactions(
+            &mut tester.storage().await,
+            tester.get_block_numbers().await,
+        )
+        .await?
+        .expect("pending transaction should be resent");
+    assert_eq!(to_resend.id, txs[1].id);
+    assert_eq!(to_resend.nonce, txs[1].nonce);
+
+    let first_tx = tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_eth_tx(txs[0].id)
+        .await
+        .unwrap();
+    assert_eq!(first_tx.nonce, txs[0].nonce);
+    Ok(())
+}
+
+// Tests that in the dry-run mode, operations are simulated via `eth_call` and aren't saved.
+#[db_test]
+async fn simulating_eth_tx_in_dry_run(connection_pool: ConnectionPool) -> anyhow::Result<()> {
+    let tester = EthSenderTester::new(connection_pool, vec![100; 100], false).await;
+
+    let simulation = tester
+        .aggregator
+        .simulate_eth_tx(
+            &mut tester.storage().await,
+            &DUMMY_OPERATION,
+            &tester.gateway,
+        )
+        .await;
+    assert!(simulation.calldata_size > 0);
+    assert!(simulation.estimated_gas.is_ok());
+
+    let calls = tester.gateway.calls.read().unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(
+        calls[0].data.as_ref().unwrap().0.len(),
+        simulation.calldata_size
+    );
+    drop(calls);
+
+    assert!(tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_inflight_txs()
+        .await
+        .is_empty());
+    assert!(tester.gateway.sent_txs.read().unwrap().is_empty());
+    Ok(())
+}
+
+// Tests that aggregation of new operations is paused while the operator balance is below the floor.
+#[tokio::test]
+async fn pausing_aggregation_on_low_operator_balance() {
+    let admin_controls = AdminControls::default();
+    let config = SenderConfig {
+        operator_balance_floor_gwei: Some(1_000_000_000),
+        ..ETHSenderConfig::from_env().sender
+    };
+    let mut aggregator = EthTxAggregator::new(
+        config.clone(),
+        Aggregator::new(config).with_admin_controls(admin_controls.clone()),
+        Address::random(),
+        0,
+    );
+    let gateway = MockEthereum::default().with_balance(U256::exp10(18) * 2);
+
+    assert!(!aggregator.check_operator_balance(&gateway).await.unwrap());
+    let operator_balance = admin_controls.operator_balance().unwrap();
+    assert_eq!(operator_balance.balance, U256::exp10(18) * 2);
+    assert!(!operator_balance.aggregation_paused);
+
+    *gateway.balance.write().unwrap() = U256::exp10(17);
+    assert!(aggregator.check_operator_balance(&gateway).await.unwrap());
+    let operator_balance = admin_controls.operator_balance().unwrap();
+    assert_eq!(operator_balance.balance, U256::exp10(17));
+    assert!(operator_balance.aggregation_paused);
+}
+
 fn block_metadata(header: &L1BatchHeader) -> BlockWithMetadata {
     BlockWithMetadata {
         header: header.clone(),
@@ -444,6 +628,7 @@ fn block_metadata(header: &L1BatchHeader) -> BlockWithMetadata {
             merkle_root_hash: Default::default(),
             initial_writes_compressed: vec![],
             repeated_writes_compressed: vec![],
+            state_diffs_compressed: None,
             commitment: Default::default(),
             l2_l1_messages_compressed: vec![],
             l2_l1_merkle_root: Default::default(),
@@ -492,6 +677,41 @@ async fn correct_order_for_confirmations(connection_pool: ConnectionPool) -> any
     Ok(())
 }
 
+#[db_test]
+async fn getting_last_l1_batch_proven_before(
+    connection_pool: ConnectionPool,
+) -> anyhow::Result<()> {
+    let mut tester = EthSenderTester::new(connection_pool, vec![100; 100], true).await;
+    let zero_block = insert_block(&mut tester, L1BatchNumber(0)).await;
+    let first_block = insert_block(&mut tester, L1BatchNumber(1)).await;
+    let second_block = insert_block(&mut tester, L1BatchNumber(2)).await;
+    commit_block(&mut tester, zero_block.clone(), first_block.clone(), true).await;
+    proof_block(&mut tester, zero_block.clone(), first_block.clone(), true).await;
+    commit_block(&mut tester, first_block.clone(), second_block.clone(), true).await;
+    // DO NOT CONFIRM THE PROOF FOR THE SECOND BLOCK
+    proof_block(
+        &mut tester,
+        first_block.clone(),
+        second_block.clone(),
+        false,
+    )
+    .await;
+
+    let now_seconds = unix_timestamp_ms() as f64 / 1_000.0;
+    let mut storage = tester.storage().await;
+    let last_proven_block = storage
+        .blocks_dal()
+        .get_last_l1_batch_proven_before(now_seconds + 60.0)
+        .await;
+    assert_eq!(last_proven_block, Some(L1BatchNumber(1)));
+    let last_proven_block = storage
+        .blocks_dal()
+        .get_last_l1_batch_proven_before(now_seconds - 3_600.0)
+        .await;
+    assert_eq!(last_proven_block, None);
+    Ok(())
+}
+
 #[db_test]
 async fn skipped_block_at_the_start(connection_pool: ConnectionPool) -> anyhow::Result<()> {
     let mut tester = EthSenderTester::new(connection_pool, vec![100; 100], true).await;
@@ -615,6 +835,7 @@ async fn insert_block(tester: &mut EthSenderTester, number: L1BatchNumber) -> L1
                 merkle_root_hash: Default::default(),
                 initial_writes_compressed: vec![],
                 repeated_writes_compressed: vec![],
+                state_diffs_compressed: None,
                 commitment: Default::default(),
                 l2_l1_messages_compressed: vec![],
                 l2_l1_merkle_root: Default::default(),
@@ -679,7 +900,7 @@ async fn send_operation(
 ) -> H256 {
     let tx = tester
         .aggregator
-        .save_eth_tx(&mut tester.storage().await, &aggregated_operation)
+        .save_eth_tx(&mut tester.storage().await, &aggregated_operation, None)
         .await
         .unwrap();
 
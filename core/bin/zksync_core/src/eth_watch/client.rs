@@ -7,14 +7,16 @@ use tokio::time::Instant;
 use zksync_eth_client::{types::Error as EthClientError, EthInterface};
 use zksync_types::ethabi::{Contract, Hash};
 
-use zksync_contracts::zksync_contract;
+use zksync_contracts::{erc20_contract, zksync_contract, BaseSystemContractsHashes};
 use zksync_types::{
     l1::L1Tx,
+    tokens::TokenMetadata,
     web3::{
         self,
+        contract::Options,
         types::{BlockNumber, FilterBuilder, Log},
     },
-    H160,
+    Address, ProtocolVersionId, H160, H256, U256,
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -52,6 +54,13 @@ pub trait EthClient {
         retries_left: usize,
     ) -> Result<Vec<L1Tx>, Error>;
     async fn finalized_block_number(&self) -> Result<u64, Error>;
+    /// Returns the protocol version currently registered in the L1 diamond proxy contract.
+    async fn get_protocol_version(&self) -> Result<ProtocolVersionId, Error>;
+    /// Returns hashes of the L2 bootloader and default account bytecodes registered in the L1 diamond proxy
+    /// contract.
+    async fn get_base_system_contracts_hashes(&self) -> Result<BaseSystemContractsHashes, Error>;
+    /// Returns the name, symbol and decimals of an ERC-20 token deployed on L1.
+    async fn get_token_metadata(&self, l1_token: Address) -> Result<TokenMetadata, Error>;
 }
 
 pub const RETRY_LIMIT: usize = 5;
@@ -62,6 +71,8 @@ const TOO_MANY_RESULTS_ALCHEMY: &str = "response size exceeded";
 pub struct EthHttpQueryClient<E> {
     client: E,
     topics: ContractTopics,
+    zksync_contract: Contract,
+    erc20_contract: Contract,
     zksync_contract_addr: H160,
     confirmations_for_eth_event: Option<u64>,
 }
@@ -73,10 +84,13 @@ impl<E: EthInterface> EthHttpQueryClient<E> {
         confirmations_for_eth_event: Option<u64>,
     ) -> Self {
         vlog::debug!("New eth client, contract addr: {:x}", zksync_contract_addr);
-        let topics = ContractTopics::new(&zksync_contract());
+        let zksync_contract = zksync_contract();
+        let topics = ContractTopics::new(&zksync_contract);
         Self {
             client,
             topics,
+            zksync_contract,
+            erc20_contract: erc20_contract(),
             zksync_contract_addr,
             confirmations_for_eth_event,
         }
@@ -217,4 +231,94 @@ impl<E: EthInterface + Send + Sync + 'static> EthClient for EthHttpQueryClient<E
                 })
         }
     }
+    async fn get_protocol_version(&self) -> Result<ProtocolVersionId, Error> {
+        let version: U256 = self
+            .client
+            .call_contract_function(
+                "getProtocolVersion",
+                (),
+                None,
+                Options::default(),
+                None,
+                self.zksync_contract_addr,
+                self.zksync_contract.clone(),
+            )
+            .await?;
+        Ok(ProtocolVersionId(version.as_u32() as u16))
+    }
+
+    async fn get_base_system_contracts_hashes(&self) -> Result<BaseSystemContractsHashes, Error> {
+        let bootloader: H256 = self
+            .client
+            .call_contract_function(
+                "getL2BootloaderBytecodeHash",
+                (),
+                None,
+                Options::default(),
+                None,
+                self.zksync_contract_addr,
+                self.zksync_contract.clone(),
+            )
+            .await?;
+        let default_aa: H256 = self
+            .client
+            .call_contract_function(
+                "getL2DefaultAccountBytecodeHash",
+                (),
+                None,
+                Options::default(),
+                None,
+                self.zksync_contract_addr,
+                self.zksync_contract.clone(),
+            )
+            .await?;
+        Ok(BaseSystemContractsHashes {
+            bootloader,
+            default_aa,
+        })
+    }
+
+    async fn get_token_metadata(&self, l1_token: Address) -> Result<TokenMetadata, Error> {
+        let name: String = self
+            .client
+            .call_contract_function(
+                "name",
+                (),
+                None,
+                Options::default(),
+                None,
+                l1_token,
+                self.erc20_contract.clone(),
+            )
+            .await?;
+        let symbol: String = self
+            .client
+            .call_contract_function(
+                "symbol",
+                (),
+                None,
+                Options::default(),
+                None,
+                l1_token,
+                self.erc20_contract.clone(),
+            )
+            .await?;
+        let decimals: U256 = self
+            .client
+            .call_contract_function(
+                "decimals",
+                (),
+                None,
+                Options::default(),
+                None,
+                l1_token,
+                self.erc20_contract.clone(),
+            )
+            .await?;
+        Ok(TokenMetadata {
+            name,
+            symbol,
+            decimals: decimals.as_u32() as u8,
+        })
+    }
 }
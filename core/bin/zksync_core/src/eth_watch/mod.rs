@@ -1,19 +1,42 @@
 //! Ethereum watcher polls the Ethereum node for PriorityQueue events.
 //! New events are accepted to the zkSync network once they have the sufficient amount of L1 confirmations.
 //!
+//! Priority operations finalizing ERC-20 bridge deposits are additionally used to maintain the registry
+//! of bridged tokens: once a deposit of a previously unknown token is observed, its metadata is fetched from L1.
+//!
+//! The last processed L1 block is checkpointed in Postgres together with the new priority operations,
+//! so that the watcher resumes from the exact block after a restart. If the watcher falls behind
+//! (e.g., after the node was offline), missed blocks are processed in chunks of bounded size
+//! to respect pagination limits of the L1 RPC provider.
+//!
+//! The watcher also enforces the censorship resistance of the priority queue: if the oldest pending priority operation
+//! is not included into a miniblock within `PRIORITY_EXPIRATION` L1 blocks after it was submitted, the priority mode
+//! is activated. In this mode, the state keeper includes only L1 transactions into new L1 batches until all
+//! expired priority operations are processed.
+//!
+//! Priority operations exceeding the configured gas limit or calldata size caps are not added to the mempool;
+//! instead, they are persisted together with rejection reasons (available via `debug_getRejectedPriorityOps`),
+//! so that a malformed L1 transaction cannot wedge L1 batch production. Note that the L1 contract still expects
+//! rejected operations to be processed, so rejections must be resolved by the operator.
+//!
 //! Poll interval is configured using the `ETH_POLL_INTERVAL` constant.
 //! Number of confirmations is configured using the `CONFIRMATIONS_FOR_ETH_EVENT` environment variable.
 
 // Built-in deps
-use std::time::{Duration, Instant};
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
 
 // External uses
 use tokio::{sync::watch, task::JoinHandle};
 
 // Workspace deps
 use zksync_config::constants::PRIORITY_EXPIRATION;
+use zksync_contracts::l2_bridge_contract;
 use zksync_types::{
-    l1::L1Tx, web3::types::BlockNumber as Web3BlockNumber, L1BlockNumber, PriorityOpId, H160,
+    ethabi::Function, l1::L1Tx, web3::types::BlockNumber as Web3BlockNumber, Address,
+    L1BlockNumber, PriorityOpId, H160, U256,
 };
 
 // Local deps
@@ -30,60 +53,181 @@ mod client;
 #[cfg(test)]
 mod tests;
 
+/// Name of the priority operations watcher checkpoint in the `eth_watcher_checkpoints` table.
+const WATCHER_NAME: &str = "priority_ops";
+
+/// Caps on priority operations accepted into the mempool.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PriorityOpLimits {
+    pub max_gas_limit: Option<U256>,
+    pub max_calldata_size: Option<usize>,
+}
+
+impl PriorityOpLimits {
+    pub fn from_config(config: &ETHWatchConfig) -> Self {
+        Self {
+            max_gas_limit: config.max_priority_op_gas_limit.map(U256::from),
+            max_calldata_size: config.max_priority_op_calldata_size,
+        }
+    }
+
+    fn check(&self, op: &L1Tx) -> Result<(), PriorityOpRejection> {
+        let gas_limit = op.common_data.gas_limit;
+        if let Some(max_gas_limit) = self.max_gas_limit {
+            if gas_limit > max_gas_limit {
+                return Err(PriorityOpRejection::GasLimit {
+                    gas_limit,
+                    max_gas_limit,
+                });
+            }
+        }
+        let calldata_size = op.execute.calldata.len();
+        if let Some(max_calldata_size) = self.max_calldata_size {
+            if calldata_size > max_calldata_size {
+                return Err(PriorityOpRejection::CalldataSize {
+                    calldata_size,
+                    max_calldata_size,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reason of rejecting a priority operation.
+#[derive(Debug, PartialEq, thiserror::Error)]
+enum PriorityOpRejection {
+    #[error("gas limit {gas_limit} exceeds the max allowed gas limit {max_gas_limit}")]
+    GasLimit {
+        gas_limit: U256,
+        max_gas_limit: U256,
+    },
+    #[error("calldata size {calldata_size} bytes exceeds the max allowed size {max_calldata_size} bytes")]
+    CalldataSize {
+        calldata_size: usize,
+        max_calldata_size: usize,
+    },
+}
+
+impl PriorityOpRejection {
+    fn as_metric_label(&self) -> &'static str {
+        match self {
+            Self::GasLimit { .. } => "gas_limit",
+            Self::CalldataSize { .. } => "calldata_size",
+        }
+    }
+}
+
 #[derive(Debug)]
 struct EthWatchState {
     next_expected_priority_id: PriorityOpId,
     last_processed_ethereum_block: u64,
+    known_bridged_tokens: HashSet<Address>,
+    /// ID of the first expired priority operation if the priority mode is active.
+    priority_mode: Option<PriorityOpId>,
 }
 
 #[derive(Debug)]
 pub struct EthWatch<W: EthClient> {
     client: W,
     poll_interval: Duration,
+    max_blocks_per_query: u64,
+    l2_erc20_bridge_addr: Address,
+    finalize_deposit_fn: Function,
+    priority_op_limits: PriorityOpLimits,
 
     state: EthWatchState,
 }
 
 impl<W: EthClient> EthWatch<W> {
-    pub async fn new(client: W, pool: &ConnectionPool, poll_interval: Duration) -> Self {
+    pub async fn new(
+        client: W,
+        pool: &ConnectionPool,
+        poll_interval: Duration,
+        max_blocks_per_query: u64,
+        l2_erc20_bridge_addr: Address,
+    ) -> Self {
         let mut storage = pool.access_storage_tagged("eth_watch").await;
 
         let state = Self::initialize_state(&client, &mut storage).await;
 
-        vlog::info!("initialized state: {:?}", state);
+        vlog::info!(
+            "initialized state: next expected priority id {}, last processed ethereum block {}, {} bridged tokens",
+            state.next_expected_priority_id,
+            state.last_processed_ethereum_block,
+            state.known_bridged_tokens.len()
+        );
+        let finalize_deposit_fn = l2_bridge_contract()
+            .function("finalizeDeposit")
+            .expect("L2 bridge contract abi error")
+            .clone();
         Self {
             client,
             poll_interval,
+            max_blocks_per_query,
+            l2_erc20_bridge_addr,
+            finalize_deposit_fn,
+            priority_op_limits: PriorityOpLimits::default(),
             state,
         }
     }
 
+    pub fn with_priority_op_limits(mut self, limits: PriorityOpLimits) -> Self {
+        self.priority_op_limits = limits;
+        self
+    }
+
     async fn initialize_state(client: &W, storage: &mut StorageProcessor<'_>) -> EthWatchState {
-        let next_expected_priority_id: PriorityOpId = storage
-            .transactions_dal()
-            .last_priority_id()
-            .await
+        let last_priority_id = storage.transactions_dal().last_priority_id().await;
+        // Rejected priority operations are not added to the mempool, but they are processed nevertheless.
+        let last_rejected_priority_id = storage
+            .eth_watcher_dal()
+            .get_last_rejected_priority_id()
+            .await;
+        let next_expected_priority_id: PriorityOpId = last_priority_id
+            .max(last_rejected_priority_id)
             .map_or(PriorityOpId(0), |e| e + 1);
 
-        let last_processed_ethereum_block = match storage
-            .transactions_dal()
-            .get_last_processed_l1_block()
-            .await
-        {
-            // There are some priority ops processed - start from the last processed eth block
-            // but subtract 1 in case the server stopped mid-block.
-            Some(block) => block.0.saturating_sub(1).into(),
+        let checkpoint = storage
+            .eth_watcher_dal()
+            .get_last_processed_l1_block(WATCHER_NAME)
+            .await;
+        let last_processed_priority_op_block = if checkpoint.is_none() {
+            storage
+                .transactions_dal()
+                .get_last_processed_l1_block()
+                .await
+        } else {
+            None
+        };
+        let last_processed_ethereum_block = match (checkpoint, last_processed_priority_op_block) {
+            // The checkpoint is saved atomically with priority ops, so it's exact.
+            (Some(block), _) => block.0.into(),
+            // There is no checkpoint (i.e., the node was updated), but there are some priority ops processed -
+            // start from the last processed eth block but subtract 1 in case the server stopped mid-block.
+            (None, Some(block)) => block.0.saturating_sub(1).into(),
             // There are no priority ops processed - to be safe, scan the last 50k blocks.
-            None => client
+            (None, None) => client
                 .finalized_block_number()
                 .await
                 .expect("cannot initialize eth watch: cannot get current ETH block")
                 .saturating_sub(PRIORITY_EXPIRATION),
         };
 
+        let known_bridged_tokens = storage
+            .tokens_dal()
+            .get_bridged_token_l1_addresses()
+            .await
+            .into_iter()
+            .collect();
+
+        let priority_mode = storage.eth_watcher_dal().get_priority_mode().await;
+
         EthWatchState {
             next_expected_priority_id,
             last_processed_ethereum_block,
+            known_bridged_tokens,
+            priority_mode,
         }
     }
 
@@ -111,20 +255,70 @@ impl<W: EthClient> EthWatch<W> {
 
     #[tracing::instrument(skip(self, storage))]
     async fn loop_iteration(&mut self, storage: &mut StorageProcessor<'_>) -> Result<(), Error> {
-        let mut stage_start = Instant::now();
-        let to_block = self.client.finalized_block_number().await?;
+        let finalized_block = self.client.finalized_block_number().await?;
 
-        if to_block <= self.state.last_processed_ethereum_block {
+        if finalized_block <= self.state.last_processed_ethereum_block {
             return Ok(());
         }
 
-        let new_ops = self
-            .get_new_priority_ops(self.state.last_processed_ethereum_block, to_block)
-            .await?;
+        while self.state.last_processed_ethereum_block < finalized_block {
+            let from_block = self.state.last_processed_ethereum_block;
+            let to_block = from_block
+                .saturating_add(self.max_blocks_per_query)
+                .min(finalized_block);
+            self.process_blocks(storage, from_block, to_block).await?;
+            metrics::gauge!(
+                "server.eth_watch.blocks_behind",
+                (finalized_block - to_block) as f64
+            );
+        }
 
-        self.state.last_processed_ethereum_block = to_block;
+        self.update_priority_mode(storage, finalized_block).await;
+
+        // A failure to fetch the protocol version shouldn't block processing of priority operations.
+        match self.client.get_protocol_version().await {
+            Ok(version) => {
+                // Base system contracts are only used by the state keeper to switch to the new version,
+                // so they can be filled in on the following iterations.
+                let base_system_contracts_hashes = match self
+                    .client
+                    .get_base_system_contracts_hashes()
+                    .await
+                {
+                    Ok(hashes) => Some(hashes),
+                    Err(err) => {
+                        vlog::warn!(
+                            "Failed fetching base system contracts for protocol version {version} from L1: {err}"
+                        );
+                        None
+                    }
+                };
+                storage
+                    .protocol_versions_dal()
+                    .save_protocol_version(
+                        version,
+                        L1BlockNumber(finalized_block as u32),
+                        base_system_contracts_hashes,
+                    )
+                    .await;
+            }
+            Err(err) => vlog::warn!("Failed fetching protocol version from L1: {err}"),
+        }
+        Ok(())
+    }
+
+    /// Processes priority operations in the `from_block..=to_block` range and checkpoints `to_block`.
+    async fn process_blocks(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<(), Error> {
+        let mut stage_start = Instant::now();
+        let new_ops = self.get_new_priority_ops(from_block, to_block).await?;
 
         metrics::histogram!("eth_watcher.poll_eth_node", stage_start.elapsed(), "stage" => "request");
+        let mut transaction = storage.start_transaction().await;
         if !new_ops.is_empty() {
             let first = &new_ops[0].1;
             let last = &new_ops[new_ops.len() - 1].1;
@@ -135,6 +329,7 @@ impl<W: EthClient> EthWatch<W> {
             );
             self.state.next_expected_priority_id = last.serial_id().next();
             stage_start = Instant::now();
+            let new_ops = self.reject_invalid_ops(&mut transaction, new_ops).await;
             metrics::counter!(
                 "server.processed_txs",
                 new_ops.len() as u64,
@@ -145,17 +340,149 @@ impl<W: EthClient> EthWatch<W> {
                 new_ops.len() as u64,
                 "stage" => "mempool_added"
             );
+            self.register_bridged_tokens(&mut transaction, &new_ops)
+                .await;
             for (eth_block, new_op) in new_ops {
-                storage
+                transaction
                     .transactions_dal()
                     .insert_transaction_l1(new_op, eth_block)
                     .await;
             }
             metrics::histogram!("eth_watcher.poll_eth_node", stage_start.elapsed(), "stage" => "persist");
         }
+        transaction
+            .eth_watcher_dal()
+            .save_last_processed_l1_block(WATCHER_NAME, L1BlockNumber(to_block as u32))
+            .await;
+        transaction.commit().await;
+
+        self.state.last_processed_ethereum_block = to_block;
         Ok(())
     }
 
+    /// Persists priority operations exceeding [`PriorityOpLimits`] as rejected. Returns the remaining operations.
+    async fn reject_invalid_ops(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        ops: Vec<(L1BlockNumber, L1Tx)>,
+    ) -> Vec<(L1BlockNumber, L1Tx)> {
+        let mut accepted_ops = Vec::with_capacity(ops.len());
+        for (eth_block, op) in ops {
+            let Err(rejection) = self.priority_op_limits.check(&op) else {
+                accepted_ops.push((eth_block, op));
+                continue;
+            };
+            let serial_id = op.serial_id();
+            vlog::warn!("Rejected priority operation #{serial_id}: {rejection}");
+            storage
+                .eth_watcher_dal()
+                .insert_rejected_priority_op(
+                    serial_id,
+                    op.hash(),
+                    eth_block,
+                    &rejection.to_string(),
+                )
+                .await;
+            metrics::increment_counter!(
+                "server.eth_watch.rejected_priority_ops",
+                "reason" => rejection.as_metric_label()
+            );
+        }
+        accepted_ops
+    }
+
+    /// Activates the priority mode if the oldest pending priority operation has expired,
+    /// or deactivates it once all expired operations are processed.
+    async fn update_priority_mode(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        finalized_block: u64,
+    ) {
+        let oldest_pending_op = storage
+            .transactions_dal()
+            .get_oldest_pending_priority_op()
+            .await;
+        let expired_op_id = oldest_pending_op.and_then(|(op_id, eth_block)| {
+            let deadline_block = u64::from(eth_block.0) + PRIORITY_EXPIRATION;
+            (deadline_block <= finalized_block).then_some(op_id)
+        });
+
+        match (self.state.priority_mode, expired_op_id) {
+            (None, Some(op_id)) => {
+                vlog::warn!(
+                    "Priority operation #{op_id} is not processed before its deadline; activating priority mode \
+                     at L1 block #{finalized_block}"
+                );
+                storage
+                    .eth_watcher_dal()
+                    .activate_priority_mode(op_id, L1BlockNumber(finalized_block as u32))
+                    .await;
+                self.state.priority_mode = Some(op_id);
+            }
+            (Some(first_expired_op_id), None) => {
+                vlog::info!(
+                    "All expired priority operations starting from #{first_expired_op_id} are processed; \
+                     deactivating priority mode"
+                );
+                storage.eth_watcher_dal().deactivate_priority_mode().await;
+                self.state.priority_mode = None;
+            }
+            _ => { /* Priority mode state is unchanged */ }
+        }
+        metrics::gauge!(
+            "server.eth_watch.priority_mode",
+            self.state.priority_mode.is_some() as u8 as f64
+        );
+    }
+
+    /// Saves metadata of the tokens that are deposited via the ERC-20 bridge for the first time.
+    async fn register_bridged_tokens(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        new_ops: &[(L1BlockNumber, L1Tx)],
+    ) {
+        for (eth_block, op) in new_ops {
+            let Some(l1_token) = self.deposited_l1_token(op) else { continue };
+            if self.state.known_bridged_tokens.contains(&l1_token) {
+                continue;
+            }
+
+            // Failing to fetch metadata (e.g., for a token not conforming to ERC-20) must not block
+            // priority operations processing; the token will be retried on its next deposit.
+            match self.client.get_token_metadata(l1_token).await {
+                Ok(metadata) => {
+                    vlog::info!("New bridged token {l1_token:?} ({})", metadata.symbol);
+                    storage
+                        .tokens_dal()
+                        .add_bridged_token(l1_token, metadata, *eth_block, op.hash())
+                        .await;
+                    self.state.known_bridged_tokens.insert(l1_token);
+                    metrics::increment_counter!("server.eth_watch.bridged_tokens");
+                }
+                Err(err) => {
+                    vlog::warn!("Failed fetching metadata for bridged token {l1_token:?}: {err}");
+                }
+            }
+        }
+    }
+
+    /// Returns the L1 token address if the operation finalizes a deposit via the ERC-20 bridge.
+    fn deposited_l1_token(&self, op: &L1Tx) -> Option<Address> {
+        if op.execute.contract_address != self.l2_erc20_bridge_addr {
+            return None;
+        }
+        let calldata = &op.execute.calldata;
+        if calldata.len() < 4 || calldata[..4] != self.finalize_deposit_fn.short_signature() {
+            return None;
+        }
+        // `finalizeDeposit(address _l1Sender, address _l2Receiver, address _l1Token, uint256 _amount, bytes _data)`
+        let mut args = self.finalize_deposit_fn.decode_input(&calldata[4..]).ok()?;
+        if args.len() < 3 {
+            return None;
+        }
+        args.swap_remove(2).into_address()
+    }
+
     async fn get_new_priority_ops(
         &self,
         from_block: u64,
@@ -201,16 +528,26 @@ pub async fn start_eth_watch<E: EthInterface + Send + Sync + 'static>(
     pool: ConnectionPool,
     eth_gateway: E,
     diamond_proxy_addr: H160,
+    l2_erc20_bridge_addr: Address,
     stop_receiver: watch::Receiver<bool>,
 ) -> JoinHandle<()> {
     let eth_watch = ETHWatchConfig::from_env();
+    let priority_op_limits = PriorityOpLimits::from_config(&eth_watch);
     let eth_client = EthHttpQueryClient::new(
         eth_gateway,
         diamond_proxy_addr,
         eth_watch.confirmations_for_eth_event,
     );
 
-    let mut eth_watch = EthWatch::new(eth_client, &pool, eth_watch.poll_interval()).await;
+    let mut eth_watch = EthWatch::new(
+        eth_client,
+        &pool,
+        eth_watch.poll_interval(),
+        eth_watch.max_blocks_per_query(),
+        l2_erc20_bridge_addr,
+    )
+    .await
+    .with_priority_op_limits(priority_op_limits);
 
     tokio::spawn(async move {
         eth_watch.run(pool, stop_receiver).await;
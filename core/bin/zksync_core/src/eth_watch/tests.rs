@@ -1,23 +1,34 @@
 use std::collections::HashMap;
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 use std::sync::Arc;
 
 use tokio::sync::RwLock;
 
 use db_test_macro::db_test;
+use zksync_config::constants::PRIORITY_EXPIRATION;
+use zksync_contracts::{l2_bridge_contract, BaseSystemContractsHashes};
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_types::web3::types::{Address, BlockNumber};
 use zksync_types::{
+    ethabi::Token,
     l1::{L1Tx, OpProcessingType, PriorityQueueType},
-    Execute, L1TxCommonData, PriorityOpId, Transaction, H256, U256,
+    tokens::TokenMetadata,
+    Execute, L1BlockNumber, L1TxCommonData, PriorityOpId, ProtocolVersionId, Transaction, H256,
+    U256,
 };
 
 use super::client::Error;
-use crate::eth_watch::{client::EthClient, EthWatch};
+use crate::eth_watch::{client::EthClient, EthWatch, PriorityOpLimits};
+
+const L2_BRIDGE_ADDRESS: Address = Address::repeat_byte(0xbb);
+const MAX_BLOCKS_PER_QUERY: u64 = 1_000;
 
 struct FakeEthClientData {
     transactions: HashMap<u64, Vec<L1Tx>>,
     last_finalized_block_number: u64,
+    protocol_version: ProtocolVersionId,
+    base_system_contracts_hashes: BaseSystemContractsHashes,
+    queried_ranges: Vec<(u64, u64)>,
 }
 
 impl FakeEthClientData {
@@ -25,6 +36,9 @@ impl FakeEthClientData {
         Self {
             transactions: Default::default(),
             last_finalized_block_number: 0,
+            protocol_version: ProtocolVersionId(0),
+            base_system_contracts_hashes: BaseSystemContractsHashes::default(),
+            queried_ranges: vec![],
         }
     }
 
@@ -65,6 +79,14 @@ impl FakeEthClient {
             .set_last_finalized_block_number(number);
     }
 
+    async fn set_protocol_version(&mut self, version: ProtocolVersionId) {
+        self.inner.write().await.protocol_version = version;
+    }
+
+    async fn set_base_system_contracts_hashes(&mut self, hashes: BaseSystemContractsHashes) {
+        self.inner.write().await.base_system_contracts_hashes = hashes;
+    }
+
     async fn block_to_number(&self, block: BlockNumber) -> u64 {
         match block {
             BlockNumber::Latest => unreachable!(),
@@ -85,6 +107,7 @@ impl EthClient for FakeEthClient {
     ) -> Result<Vec<L1Tx>, Error> {
         let from = self.block_to_number(from).await;
         let to = self.block_to_number(to).await;
+        self.inner.write().await.queried_ranges.push((from, to));
         let mut transactions = vec![];
         for number in from..=to {
             if let Some(ops) = self.inner.read().await.transactions.get(&number) {
@@ -97,6 +120,18 @@ impl EthClient for FakeEthClient {
     async fn finalized_block_number(&self) -> Result<u64, Error> {
         Ok(self.inner.read().await.last_finalized_block_number)
     }
+
+    async fn get_protocol_version(&self) -> Result<ProtocolVersionId, Error> {
+        Ok(self.inner.read().await.protocol_version)
+    }
+
+    async fn get_base_system_contracts_hashes(&self) -> Result<BaseSystemContractsHashes, Error> {
+        Ok(self.inner.read().await.base_system_contracts_hashes)
+    }
+
+    async fn get_token_metadata(&self, l1_token: Address) -> Result<TokenMetadata, Error> {
+        Ok(TokenMetadata::default(l1_token))
+    }
 }
 
 fn build_tx(serial_id: u64, eth_block: u64) -> L1Tx {
@@ -135,6 +170,8 @@ async fn test_normal_operation(connection_pool: ConnectionPool) {
         client.clone(),
         &connection_pool,
         std::time::Duration::from_nanos(1),
+        MAX_BLOCKS_PER_QUERY,
+        L2_BRIDGE_ADDRESS,
     )
     .await;
 
@@ -169,6 +206,8 @@ async fn test_gap_in_single_batch(connection_pool: ConnectionPool) {
         client.clone(),
         &connection_pool,
         std::time::Duration::from_nanos(1),
+        MAX_BLOCKS_PER_QUERY,
+        L2_BRIDGE_ADDRESS,
     )
     .await;
 
@@ -194,6 +233,8 @@ async fn test_gap_between_batches(connection_pool: ConnectionPool) {
         client.clone(),
         &connection_pool,
         std::time::Duration::from_nanos(1),
+        MAX_BLOCKS_PER_QUERY,
+        L2_BRIDGE_ADDRESS,
     )
     .await;
 
@@ -224,6 +265,8 @@ async fn test_overlapping_batches(connection_pool: ConnectionPool) {
         client.clone(),
         &connection_pool,
         std::time::Duration::from_nanos(1),
+        MAX_BLOCKS_PER_QUERY,
+        L2_BRIDGE_ADDRESS,
     )
     .await;
 
@@ -255,6 +298,267 @@ async fn test_overlapping_batches(connection_pool: ConnectionPool) {
     assert_eq!(tx.common_data.serial_id.0, 4);
 }
 
+#[db_test]
+async fn test_protocol_version_registration(connection_pool: ConnectionPool) {
+    let mut client = FakeEthClient::new();
+    let mut watcher = EthWatch::new(
+        client.clone(),
+        &connection_pool,
+        std::time::Duration::from_nanos(1),
+        MAX_BLOCKS_PER_QUERY,
+        L2_BRIDGE_ADDRESS,
+    )
+    .await;
+
+    let mut storage = connection_pool.access_test_storage().await;
+    client.set_protocol_version(ProtocolVersionId(1)).await;
+    client.set_last_finalized_block_number(10).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    let new_hashes = BaseSystemContractsHashes {
+        bootloader: H256::repeat_byte(1),
+        default_aa: H256::repeat_byte(2),
+    };
+    client.set_protocol_version(ProtocolVersionId(2)).await;
+    client.set_base_system_contracts_hashes(new_hashes).await;
+    client.set_last_finalized_block_number(20).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    let mut protocol_versions_dal = storage.protocol_versions_dal();
+    assert_eq!(
+        protocol_versions_dal.latest_version_id().await,
+        Some(ProtocolVersionId(2))
+    );
+    let version = protocol_versions_dal
+        .get_protocol_version(ProtocolVersionId(1))
+        .await
+        .unwrap();
+    assert_eq!(version.l1_block_number.0, 10);
+    assert_eq!(
+        protocol_versions_dal
+            .get_base_system_contracts_hashes(ProtocolVersionId(2))
+            .await,
+        Some(new_hashes)
+    );
+}
+
+fn build_deposit_tx(serial_id: u64, eth_block: u64, l1_token: Address) -> L1Tx {
+    let calldata = l2_bridge_contract()
+        .function("finalizeDeposit")
+        .unwrap()
+        .encode_input(&[
+            Token::Address(Address::repeat_byte(1)),
+            Token::Address(Address::repeat_byte(2)),
+            Token::Address(l1_token),
+            Token::Uint(U256::from(100)),
+            Token::Bytes(vec![]),
+        ])
+        .unwrap();
+    let mut tx = build_tx(serial_id, eth_block);
+    tx.execute.contract_address = L2_BRIDGE_ADDRESS;
+    tx.execute.calldata = calldata;
+    tx
+}
+
+#[db_test]
+async fn test_bridged_token_registration(connection_pool: ConnectionPool) {
+    let mut client = FakeEthClient::new();
+    let mut watcher = EthWatch::new(
+        client.clone(),
+        &connection_pool,
+        std::time::Duration::from_nanos(1),
+        MAX_BLOCKS_PER_QUERY,
+        L2_BRIDGE_ADDRESS,
+    )
+    .await;
+
+    let mut storage = connection_pool.access_test_storage().await;
+    let token = Address::repeat_byte(0x33);
+    client
+        .add_transactions(&[
+            build_tx(0, 10),
+            build_deposit_tx(1, 12, token),
+            build_deposit_tx(2, 14, token),
+        ])
+        .await;
+    client.set_last_finalized_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    let bridged_tokens = storage.tokens_dal().get_bridged_token_l1_addresses().await;
+    assert_eq!(bridged_tokens, [token]);
+    assert!(watcher.state.known_bridged_tokens.contains(&token));
+}
+
+#[db_test]
+async fn test_catch_up_in_chunks(connection_pool: ConnectionPool) {
+    let mut client = FakeEthClient::new();
+    client.set_last_finalized_block_number(5).await;
+    let mut watcher = EthWatch::new(
+        client.clone(),
+        &connection_pool,
+        std::time::Duration::from_nanos(1),
+        10,
+        L2_BRIDGE_ADDRESS,
+    )
+    .await;
+
+    let mut storage = connection_pool.access_test_storage().await;
+    client
+        .add_transactions(&[build_tx(0, 7), build_tx(1, 16), build_tx(2, 33)])
+        .await;
+    client.set_last_finalized_block_number(35).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    let db_txs = get_all_db_txs(&mut storage).await;
+    assert_eq!(db_txs.len(), 3);
+    let queried_ranges = client.inner.read().await.queried_ranges.clone();
+    assert_eq!(queried_ranges, [(0, 10), (10, 20), (20, 30), (30, 35)]);
+
+    let checkpoint = storage
+        .eth_watcher_dal()
+        .get_last_processed_l1_block(super::WATCHER_NAME)
+        .await;
+    assert_eq!(checkpoint, Some(L1BlockNumber(35)));
+
+    // The restarted watcher resumes from the checkpoint.
+    drop(storage);
+    let watcher = EthWatch::new(
+        client.clone(),
+        &connection_pool,
+        std::time::Duration::from_nanos(1),
+        10,
+        L2_BRIDGE_ADDRESS,
+    )
+    .await;
+    assert_eq!(watcher.state.last_processed_ethereum_block, 35);
+    assert_eq!(watcher.state.next_expected_priority_id, PriorityOpId(3));
+}
+
+#[db_test]
+async fn test_priority_mode_activation(connection_pool: ConnectionPool) {
+    let mut client = FakeEthClient::new();
+    let mut watcher = EthWatch::new(
+        client.clone(),
+        &connection_pool,
+        std::time::Duration::from_nanos(1),
+        MAX_BLOCKS_PER_QUERY,
+        L2_BRIDGE_ADDRESS,
+    )
+    .await;
+
+    let mut storage = connection_pool.access_test_storage().await;
+    client
+        .add_transactions(&[build_tx(0, 10), build_tx(1, 14)])
+        .await;
+    client.set_last_finalized_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    assert_eq!(watcher.state.priority_mode, None);
+    assert_eq!(storage.eth_watcher_dal().get_priority_mode().await, None);
+
+    // The first priority operation expires.
+    client
+        .set_last_finalized_block_number(10 + PRIORITY_EXPIRATION)
+        .await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    assert_eq!(watcher.state.priority_mode, Some(PriorityOpId(0)));
+    assert_eq!(
+        storage.eth_watcher_dal().get_priority_mode().await,
+        Some(PriorityOpId(0))
+    );
+
+    // The restarted watcher restores the priority mode.
+    drop(storage);
+    let watcher = EthWatch::new(
+        client.clone(),
+        &connection_pool,
+        std::time::Duration::from_nanos(1),
+        MAX_BLOCKS_PER_QUERY,
+        L2_BRIDGE_ADDRESS,
+    )
+    .await;
+    assert_eq!(watcher.state.priority_mode, Some(PriorityOpId(0)));
+}
+
+#[db_test]
+async fn test_priority_op_rejection(connection_pool: ConnectionPool) {
+    let mut client = FakeEthClient::new();
+    let limits = PriorityOpLimits {
+        max_gas_limit: Some(1_000_000.into()),
+        max_calldata_size: Some(10),
+    };
+    let mut watcher = EthWatch::new(
+        client.clone(),
+        &connection_pool,
+        std::time::Duration::from_nanos(1),
+        MAX_BLOCKS_PER_QUERY,
+        L2_BRIDGE_ADDRESS,
+    )
+    .await
+    .with_priority_op_limits(limits);
+
+    let mut storage = connection_pool.access_test_storage().await;
+    let mut gas_heavy_tx = build_tx(1, 11);
+    gas_heavy_tx.common_data.gas_limit = 2_000_000.into();
+    let mut calldata_heavy_tx = build_tx(3, 13);
+    calldata_heavy_tx.execute.calldata = vec![0; 11];
+    client
+        .add_transactions(&[
+            build_tx(0, 10),
+            gas_heavy_tx,
+            build_tx(2, 12),
+            calldata_heavy_tx.clone(),
+        ])
+        .await;
+    client.set_last_finalized_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    let db_txs = get_all_db_txs(&mut storage).await;
+    let serial_ids: Vec<_> = db_txs
+        .into_iter()
+        .map(|tx| L1Tx::try_from(tx).unwrap().serial_id().0)
+        .collect();
+    assert_eq!(serial_ids, [0, 2]);
+
+    let rejected_ops = storage
+        .eth_watcher_dal()
+        .get_rejected_priority_ops(10)
+        .await;
+    assert_eq!(rejected_ops.len(), 2);
+    assert_eq!(rejected_ops[0].serial_id, 3.into());
+    assert_eq!(rejected_ops[0].transaction_hash, calldata_heavy_tx.hash());
+    assert_eq!(rejected_ops[0].l1_block_number, 13.into());
+    assert!(
+        rejected_ops[0].reason.contains("calldata size 11 bytes"),
+        "{}",
+        rejected_ops[0].reason
+    );
+    assert_eq!(rejected_ops[1].serial_id, 1.into());
+    assert!(
+        rejected_ops[1].reason.contains("gas limit 2000000"),
+        "{}",
+        rejected_ops[1].reason
+    );
+
+    // The restarted watcher accounts for rejected operations when determining the next expected operation.
+    client.add_transactions(&[build_tx(4, 16)]).await;
+    client.set_last_finalized_block_number(20).await;
+    drop(storage);
+    let mut watcher = EthWatch::new(
+        client.clone(),
+        &connection_pool,
+        std::time::Duration::from_nanos(1),
+        MAX_BLOCKS_PER_QUERY,
+        L2_BRIDGE_ADDRESS,
+    )
+    .await
+    .with_priority_op_limits(limits);
+    assert_eq!(watcher.state.next_expected_priority_id, PriorityOpId(4));
+
+    let mut storage = connection_pool.access_test_storage().await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    let db_txs = get_all_db_txs(&mut storage).await;
+    assert_eq!(db_txs.len(), 3);
+}
+
 async fn get_all_db_txs(storage: &mut StorageProcessor<'_>) -> Vec<Transaction> {
     storage.transactions_dal().reset_mempool().await;
     storage
@@ -91,8 +91,14 @@ pub fn gas_count_from_writes(writes_metrics: &DeduplicatedWritesMetrics) -> Bloc
 
 pub fn commit_gas_count_for_block(block: &BlockWithMetadata) -> u32 {
     let base_cost = block_base_cost(AggregatedActionType::CommitBlocks);
-    let additional_calldata_bytes = block.metadata.initial_writes_compressed.len() as u32
-        + block.metadata.repeated_writes_compressed.len() as u32
+    let storage_pubdata_bytes = match &block.metadata.state_diffs_compressed {
+        Some(state_diffs) => state_diffs.len() as u32,
+        None => {
+            block.metadata.initial_writes_compressed.len() as u32
+                + block.metadata.repeated_writes_compressed.len() as u32
+        }
+    };
+    let additional_calldata_bytes = storage_pubdata_bytes
         + block.metadata.l2_l1_messages_compressed.len() as u32
         + block
             .header
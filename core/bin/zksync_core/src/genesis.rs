@@ -110,6 +110,7 @@ pub async fn ensure_genesis_state(
         genesis_root_hash,
         vec![],
         vec![],
+        None,
         base_system_contracts_hashes.bootloader,
         base_system_contracts_hashes.default_aa,
     );
@@ -348,6 +349,7 @@ pub(crate) async fn save_genesis_block_metadata(
         merkle_root_hash: genesis_root_hash,
         initial_writes_compressed: vec![],
         repeated_writes_compressed: vec![],
+        state_diffs_compressed: None,
         commitment: block_commitment_hash.commitment,
         l2_l1_messages_compressed: vec![],
         l2_l1_merkle_root: Default::default(),
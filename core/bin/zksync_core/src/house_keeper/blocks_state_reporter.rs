@@ -1,14 +1,19 @@
 use async_trait::async_trait;
 
 use zksync_dal::ConnectionPool;
+use zksync_types::L1BatchNumber;
 use zksync_utils::time::seconds_since_epoch;
 
-use crate::house_keeper::periodic_job::PeriodicJob;
+use crate::{
+    house_keeper::periodic_job::PeriodicJob,
+    l1_batch_status_bus::{L1BatchStage, L1BatchStatusReceiver},
+};
 
 #[derive(Debug)]
 pub struct L1BatchMetricsReporter {
     reporting_interval_ms: u64,
     connection_pool: ConnectionPool,
+    l1_batch_status: Option<L1BatchStatusReceiver>,
 }
 
 impl L1BatchMetricsReporter {
@@ -16,22 +21,42 @@ impl L1BatchMetricsReporter {
         Self {
             reporting_interval_ms,
             connection_pool,
+            l1_batch_status: None,
         }
     }
 
-    async fn report_metrics(&self) {
+    /// Makes the reporter take L1 batch numbers for stages published to the status bus from the bus
+    /// instead of polling Postgres. Stages without updates on the bus are still polled.
+    pub fn with_l1_batch_status(mut self, receiver: L1BatchStatusReceiver) -> Self {
+        self.l1_batch_status = Some(receiver);
+        self
+    }
+
+    fn latest_from_bus(&self, stage: L1BatchStage) -> Option<L1BatchNumber> {
+        self.l1_batch_status.as_ref()?.latest(stage)
+    }
+
+    async fn report_metrics(&mut self) {
+        if let Some(receiver) = &mut self.l1_batch_status {
+            receiver.poll_updates();
+        }
+
         let mut conn = self.connection_pool.access_storage().await;
-        let mut block_metrics = vec![
-            (
-                conn.blocks_dal().get_sealed_block_number().await,
-                "sealed".to_string(),
-            ),
-            (
+        let sealed_l1_batch = match self.latest_from_bus(L1BatchStage::Sealed) {
+            Some(number) => number,
+            None => conn.blocks_dal().get_sealed_block_number().await,
+        };
+        let l1_batch_with_metadata = match self.latest_from_bus(L1BatchStage::TreeComputed) {
+            Some(number) => number,
+            None => {
                 conn.blocks_dal()
                     .get_last_block_number_with_metadata()
-                    .await,
-                "metadata_calculated".to_string(),
-            ),
+                    .await
+            }
+        };
+        let mut block_metrics = vec![
+            (sealed_l1_batch, "sealed".to_string()),
+            (l1_batch_with_metadata, "metadata_calculated".to_string()),
             (
                 conn.blocks_dal()
                     .get_last_l1_batch_number_with_witness_inputs()
@@ -46,6 +71,9 @@ impl L1BatchMetricsReporter {
         }
 
         for (tx_type, l1_batch) in eth_stats.mined {
+            let l1_batch = self
+                .latest_from_bus(L1BatchStage::from_action_type(tx_type))
+                .unwrap_or(l1_batch);
             block_metrics.push((l1_batch, format!("l1_mined_{:?}", tx_type)))
         }
 
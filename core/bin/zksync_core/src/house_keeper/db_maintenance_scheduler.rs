@@ -0,0 +1,158 @@
+use async_trait::async_trait;
+use chrono::Timelike;
+
+use zksync_config::configs::house_keeper::HouseKeeperConfig;
+use zksync_dal::{maintenance_dal::TableStats, ConnectionPool};
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Tables with the highest write load, for which autovacuum tends to fall behind.
+const HOT_TABLES: &[&str] = &[
+    "storage_logs",
+    "events",
+    "transactions",
+    "storage",
+    "initial_writes",
+    "factory_deps",
+    "miniblocks",
+    "l1_batches",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MaintenanceAction {
+    Vacuum,
+    Analyze,
+}
+
+impl MaintenanceAction {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Vacuum => "vacuum",
+            Self::Analyze => "analyze",
+        }
+    }
+}
+
+/// Reports table / index bloat metrics for hot tables and schedules `VACUUM` / `ANALYZE`
+/// for them during the configured off-peak window.
+#[derive(Debug)]
+pub struct DbMaintenanceScheduler {
+    interval_ms: u64,
+    window_start_hour: u32,
+    window_end_hour: u32,
+    analyze_modified_rows_ratio: f64,
+    vacuum_dead_rows_ratio: f64,
+    pool: ConnectionPool,
+}
+
+impl DbMaintenanceScheduler {
+    pub fn new(config: &HouseKeeperConfig, pool: ConnectionPool) -> Self {
+        Self {
+            interval_ms: config.db_maintenance_interval_ms,
+            window_start_hour: config.db_maintenance_window_start_hour,
+            window_end_hour: config.db_maintenance_window_end_hour,
+            analyze_modified_rows_ratio: config.db_analyze_modified_rows_ratio,
+            vacuum_dead_rows_ratio: config.db_vacuum_dead_rows_ratio,
+            pool,
+        }
+    }
+
+    fn is_off_peak(&self, hour: u32) -> bool {
+        if self.window_start_hour <= self.window_end_hour {
+            (self.window_start_hour..self.window_end_hour).contains(&hour)
+        } else {
+            hour >= self.window_start_hour || hour < self.window_end_hour
+        }
+    }
+
+    fn report_metrics(stats: &TableStats) {
+        let table = stats.table_name.clone();
+        metrics::gauge!("server.db.table_size", stats.table_size_bytes as f64, "table" => table.clone());
+        metrics::gauge!("server.db.indexes_size", stats.indexes_size_bytes as f64, "table" => table.clone());
+        metrics::gauge!("server.db.dead_rows_ratio", stats.dead_rows_ratio(), "table" => table.clone());
+        metrics::gauge!("server.db.modified_rows_ratio", stats.modified_rows_ratio(), "table" => table.clone());
+
+        let now = chrono::Utc::now().naive_utc();
+        if let Some(last_vacuum) = stats.last_vacuum {
+            let lag = (now - last_vacuum).num_seconds();
+            metrics::gauge!("server.db.seconds_since_vacuum", lag as f64, "table" => table.clone());
+        }
+        if let Some(last_analyze) = stats.last_analyze {
+            let lag = (now - last_analyze).num_seconds();
+            metrics::gauge!("server.db.seconds_since_analyze", lag as f64, "table" => table);
+        }
+    }
+
+    /// Chooses the table most in need of maintenance. Vacuuming takes priority since it also
+    /// analyzes the table.
+    fn choose_action<'a>(
+        &self,
+        all_stats: &'a [TableStats],
+    ) -> Option<(&'a TableStats, MaintenanceAction)> {
+        let most_bloated = all_stats
+            .iter()
+            .filter(|stats| stats.dead_rows_ratio() >= self.vacuum_dead_rows_ratio)
+            .max_by(|a, b| a.dead_rows_ratio().total_cmp(&b.dead_rows_ratio()));
+        if let Some(stats) = most_bloated {
+            return Some((stats, MaintenanceAction::Vacuum));
+        }
+
+        all_stats
+            .iter()
+            .filter(|stats| stats.modified_rows_ratio() >= self.analyze_modified_rows_ratio)
+            .max_by(|a, b| a.modified_rows_ratio().total_cmp(&b.modified_rows_ratio()))
+            .map(|stats| (stats, MaintenanceAction::Analyze))
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for DbMaintenanceScheduler {
+    const SERVICE_NAME: &'static str = "DbMaintenanceScheduler";
+
+    async fn run_routine_task(&mut self) {
+        let mut storage = self.pool.access_storage_tagged("house_keeper").await;
+        let all_stats = storage.maintenance_dal().get_table_stats(HOT_TABLES).await;
+        for stats in &all_stats {
+            Self::report_metrics(stats);
+        }
+
+        if !self.is_off_peak(chrono::Utc::now().hour()) {
+            return;
+        }
+        // Only a single table is processed per iteration to limit the impact on the DB.
+        let (stats, action) = match self.choose_action(&all_stats) {
+            Some(choice) => choice,
+            None => return,
+        };
+        vlog::info!(
+            "Running {} for table `{}` (dead rows ratio: {:.3}, modified rows ratio: {:.3})",
+            action.as_str(),
+            stats.table_name,
+            stats.dead_rows_ratio(),
+            stats.modified_rows_ratio()
+        );
+        match action {
+            MaintenanceAction::Vacuum => {
+                storage
+                    .maintenance_dal()
+                    .vacuum_table(&stats.table_name)
+                    .await
+            }
+            MaintenanceAction::Analyze => {
+                storage
+                    .maintenance_dal()
+                    .analyze_table(&stats.table_name)
+                    .await
+            }
+        }
+        metrics::increment_counter!(
+            "server.db.maintenance_runs",
+            "table" => stats.table_name.clone(),
+            "action" => action.as_str()
+        );
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.interval_ms
+    }
+}
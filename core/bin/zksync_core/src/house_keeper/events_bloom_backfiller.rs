@@ -0,0 +1,91 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+
+use std::collections::HashMap;
+
+use zksync_dal::ConnectionPool;
+use zksync_types::{event::accrue_event_bloom, MiniblockNumber, H2048};
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Max number of L1 batches processed in a single iteration; limits the size of the DB transaction.
+const MAX_L1_BATCHES_PER_ITERATION: usize = 10;
+
+/// Computes Bloom filters over events for miniblocks and L1 batches sealed before the filters
+/// were persisted by the state keeper. Until the filters are computed, `eth_getLogs` cannot
+/// skip the corresponding miniblocks.
+#[derive(Debug)]
+pub struct EventsBloomBackfiller {
+    interval_ms: u64,
+    pool: ConnectionPool,
+}
+
+impl EventsBloomBackfiller {
+    pub fn new(interval_ms: u64, pool: ConnectionPool) -> Self {
+        Self { interval_ms, pool }
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for EventsBloomBackfiller {
+    const SERVICE_NAME: &'static str = "EventsBloomBackfiller";
+
+    async fn run_routine_task(&mut self) {
+        let mut storage = self.pool.access_storage_tagged("house_keeper").await;
+        let l1_batches = storage
+            .blocks_dal()
+            .get_l1_batches_without_bloom(MAX_L1_BATCHES_PER_ITERATION)
+            .await;
+
+        for l1_batch_number in l1_batches {
+            let mut transaction = storage.start_transaction().await;
+            let miniblock_range = transaction
+                .blocks_dal()
+                .get_miniblock_range_of_l1_batch(l1_batch_number)
+                .await;
+            // Events are streamed, so that large L1 batches don't require loading all events into memory.
+            let mut miniblock_blooms: HashMap<_, H2048> = HashMap::new();
+            let mut l1_batch_bloom = H2048::zero();
+            let mut event_count = 0;
+            if let Some(range) = miniblock_range {
+                let mut events = transaction.events_dal().stream_events(range);
+                while let Some((miniblock_number, event)) = events.next().await {
+                    accrue_event_bloom(
+                        miniblock_blooms.entry(miniblock_number).or_default(),
+                        &event,
+                    );
+                    accrue_event_bloom(&mut l1_batch_bloom, &event);
+                    event_count += 1;
+                }
+            }
+
+            if let Some((first_miniblock, last_miniblock)) = miniblock_range {
+                for number in first_miniblock.0..=last_miniblock.0 {
+                    let number = MiniblockNumber(number);
+                    let bloom = miniblock_blooms.get(&number).copied().unwrap_or_default();
+                    transaction
+                        .blocks_dal()
+                        .set_miniblock_logs_bloom(number, &bloom)
+                        .await;
+                }
+            }
+            transaction
+                .blocks_dal()
+                .set_l1_batch_bloom(l1_batch_number, &l1_batch_bloom)
+                .await;
+            transaction.commit().await;
+
+            vlog::info!(
+                "Computed events Bloom filters for L1 batch #{l1_batch_number} with {event_count} events"
+            );
+            metrics::gauge!(
+                "server.house_keeper.last_l1_batch_with_backfilled_bloom",
+                l1_batch_number.0 as f64
+            );
+        }
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.interval_ms
+    }
+}
@@ -0,0 +1,146 @@
+use async_trait::async_trait;
+
+use zksync_dal::ConnectionPool;
+use zksync_types::L1BatchNumber;
+
+use crate::{
+    house_keeper::periodic_job::PeriodicJob,
+    l1_batch_status_bus::{L1BatchStage, L1BatchStatusReceiver},
+};
+
+/// Max number of L1 batches pruned in a single iteration; limits the size of the DB transaction.
+const MAX_L1_BATCHES_PER_ITERATION: u32 = 100;
+
+/// Prunes transactions, events and call traces of old L1 batches for nodes running
+/// in the full mode. Only L1 batches executed on L1 are pruned.
+#[derive(Debug)]
+pub struct HistoryPruner {
+    retention_l1_batches: u32,
+    interval_ms: u64,
+    pool: ConnectionPool,
+    l1_batch_status: Option<L1BatchStatusReceiver>,
+}
+
+impl HistoryPruner {
+    pub fn new(retention_l1_batches: u32, interval_ms: u64, pool: ConnectionPool) -> Self {
+        Self {
+            retention_l1_batches,
+            interval_ms,
+            pool,
+            l1_batch_status: None,
+        }
+    }
+
+    /// Makes the pruner learn about executed L1 batches from the status bus instead of polling Postgres
+    /// (as long as the bus receives updates about executed L1 batches).
+    pub fn with_l1_batch_status(mut self, receiver: L1BatchStatusReceiver) -> Self {
+        self.l1_batch_status = Some(receiver);
+        self
+    }
+
+    fn last_executed_l1_batch_from_bus(&mut self) -> Option<L1BatchNumber> {
+        let receiver = self.l1_batch_status.as_mut()?;
+        receiver.poll_updates();
+        receiver.latest(L1BatchStage::Executed)
+    }
+}
+
+/// Returns the last L1 batch to prune on the next iteration, or `None` if there's nothing to prune.
+fn next_l1_batch_to_prune(
+    retention_l1_batches: u32,
+    last_executed_l1_batch: L1BatchNumber,
+    last_pruned_l1_batch: Option<L1BatchNumber>,
+) -> Option<L1BatchNumber> {
+    let horizon = last_executed_l1_batch.0.checked_sub(retention_l1_batches)?;
+    let next_batch = match last_pruned_l1_batch {
+        Some(last_pruned) if last_pruned.0 >= horizon => return None,
+        Some(last_pruned) => last_pruned.0 + MAX_L1_BATCHES_PER_ITERATION,
+        None => MAX_L1_BATCHES_PER_ITERATION - 1,
+    };
+    Some(L1BatchNumber(next_batch.min(horizon)))
+}
+
+#[async_trait]
+impl PeriodicJob for HistoryPruner {
+    const SERVICE_NAME: &'static str = "HistoryPruner";
+
+    async fn run_routine_task(&mut self) {
+        let last_executed_l1_batch = self.last_executed_l1_batch_from_bus();
+        let mut storage = self.pool.access_storage_tagged("house_keeper").await;
+        let last_executed_l1_batch = match last_executed_l1_batch {
+            Some(number) => Some(number),
+            None => {
+                storage
+                    .blocks_dal()
+                    .get_number_of_last_block_executed_on_eth()
+                    .await
+            }
+        };
+        let Some(last_executed_l1_batch) = last_executed_l1_batch else {
+            return;
+        };
+        let pruning_info = storage.pruning_dal().get_pruning_info().await.unwrap();
+        let Some(l1_batch_to_prune) = next_l1_batch_to_prune(
+            self.retention_l1_batches,
+            last_executed_l1_batch,
+            pruning_info.last_pruned_l1_batch,
+        ) else {
+            return;
+        };
+
+        let stats = storage
+            .pruning_dal()
+            .prune_history(l1_batch_to_prune)
+            .await
+            .unwrap();
+        vlog::info!(
+            "Pruned history up to L1 batch #{l1_batch_to_prune}: removed {} transactions and {} events",
+            stats.deleted_transactions,
+            stats.deleted_events
+        );
+        metrics::gauge!(
+            "server.house_keeper.last_pruned_l1_batch",
+            l1_batch_to_prune.0 as f64
+        );
+        metrics::counter!(
+            "server.house_keeper.pruned_rows",
+            stats.deleted_transactions,
+            "table" => "transactions"
+        );
+        metrics::counter!(
+            "server.house_keeper.pruned_rows",
+            stats.deleted_events,
+            "table" => "events"
+        );
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.interval_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selecting_l1_batch_to_prune() {
+        assert_eq!(next_l1_batch_to_prune(10, L1BatchNumber(5), None), None);
+        assert_eq!(
+            next_l1_batch_to_prune(10, L1BatchNumber(50), None),
+            Some(L1BatchNumber(40))
+        );
+        assert_eq!(
+            next_l1_batch_to_prune(10, L1BatchNumber(500), None),
+            Some(L1BatchNumber(99))
+        );
+        assert_eq!(
+            next_l1_batch_to_prune(10, L1BatchNumber(500), Some(L1BatchNumber(99))),
+            Some(L1BatchNumber(199))
+        );
+        assert_eq!(
+            next_l1_batch_to_prune(10, L1BatchNumber(500), Some(L1BatchNumber(490))),
+            None
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use async_trait::async_trait;
+
+use zksync_config::configs::house_keeper::HouseKeeperConfig;
+use zksync_dal::{
+    partitions_dal::{PartitionStatus, PartitionedTable},
+    ConnectionPool, StorageProcessor,
+};
+use zksync_types::MiniblockNumber;
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Creates partitions of `events` and `storage_logs` tables ahead of the last sealed miniblock,
+/// and detaches `events` partitions that fall out of the retention window, so that they can be archived
+/// by [`PartitionArchiver`](super::partition_archiver::PartitionArchiver). `storage_logs` partitions
+/// are never detached; see [`PartitionedTable::is_archivable()`].
+///
+/// State snapshots are created for the configured miniblocks once they are sealed.
+#[derive(Debug)]
+pub struct MiniblockPartitionManager {
+    partition_size: u32,
+    partitions_ahead: u32,
+    retention: Option<u32>,
+    state_snapshot_miniblocks: Vec<u32>,
+    interval_ms: u64,
+    pool: ConnectionPool,
+}
+
+impl MiniblockPartitionManager {
+    pub fn new(config: &HouseKeeperConfig, pool: ConnectionPool) -> Self {
+        assert!(
+            config.miniblock_partition_size > 0,
+            "Miniblock partition size must be positive"
+        );
+        Self {
+            partition_size: config.miniblock_partition_size,
+            partitions_ahead: config.miniblock_partitions_ahead,
+            retention: config.miniblock_partitions_retention,
+            state_snapshot_miniblocks: config.state_snapshot_miniblocks().to_vec(),
+            interval_ms: config.partition_management_interval_ms,
+            pool,
+        }
+    }
+
+    /// Returns the exclusive end of the partition containing `miniblock`. Partition boundaries
+    /// are aligned to the partition size.
+    fn partition_end(&self, miniblock: u32) -> u32 {
+        (miniblock / self.partition_size + 1).saturating_mul(self.partition_size)
+    }
+
+    async fn create_partitions(&mut self, table: PartitionedTable, sealed_miniblock: u32) {
+        let mut storage = self.pool.access_storage_tagged("house_keeper").await;
+        let target_end = self
+            .partition_end(sealed_miniblock)
+            .saturating_add(self.partition_size.saturating_mul(self.partitions_ahead));
+        let mut next_start = storage
+            .partitions_dal()
+            .get_next_partition_start(table)
+            .await
+            .0;
+
+        while next_start < target_end {
+            let end = self.partition_end(next_start);
+            storage
+                .partitions_dal()
+                .create_partition(table, next_start..end)
+                .await;
+            vlog::info!("Created partition of `{table}` for miniblocks {next_start}..{end}");
+            metrics::increment_counter!(
+                "server.house_keeper.miniblock_partitions_created",
+                "table" => table.as_str()
+            );
+            next_start = end;
+        }
+    }
+
+    async fn create_state_snapshot(storage: &mut StorageProcessor<'_>, miniblock: u32) {
+        let slot_count = storage
+            .state_snapshots_dal()
+            .create_snapshot(MiniblockNumber(miniblock))
+            .await
+            .expect("Failed creating state snapshot");
+        if let Some(slot_count) = slot_count {
+            vlog::info!("Created state snapshot at miniblock {miniblock} with {slot_count} slots");
+            metrics::increment_counter!("server.house_keeper.state_snapshots_created");
+        }
+    }
+
+    async fn create_configured_snapshots(&mut self, sealed_miniblock: u32) {
+        let mut storage = self.pool.access_storage_tagged("house_keeper").await;
+        for &miniblock in &self.state_snapshot_miniblocks {
+            if miniblock <= sealed_miniblock {
+                Self::create_state_snapshot(&mut storage, miniblock).await;
+            }
+        }
+    }
+
+    async fn detach_partitions(&mut self, table: PartitionedTable, sealed_miniblock: u32) {
+        let retention = match self.retention {
+            Some(retention) => retention,
+            None => return,
+        };
+        let retained_from = sealed_miniblock.saturating_sub(retention);
+
+        let mut storage = self.pool.access_storage_tagged("house_keeper").await;
+        let partitions = storage
+            .partitions_dal()
+            .get_partitions(table, PartitionStatus::Attached)
+            .await;
+        for partition in partitions {
+            if partition.miniblocks.end > retained_from {
+                break;
+            }
+            storage.partitions_dal().detach_partition(&partition).await;
+            vlog::info!(
+                "Detached partition `{}` for miniblocks {:?}",
+                partition.name,
+                partition.miniblocks
+            );
+            metrics::increment_counter!(
+                "server.house_keeper.miniblock_partitions_detached",
+                "table" => table.as_str()
+            );
+        }
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for MiniblockPartitionManager {
+    const SERVICE_NAME: &'static str = "MiniblockPartitionManager";
+
+    async fn run_routine_task(&mut self) {
+        let sealed_miniblock = self
+            .pool
+            .access_storage_tagged("house_keeper")
+            .await
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .0;
+
+        self.create_configured_snapshots(sealed_miniblock).await;
+        for table in PartitionedTable::ALL {
+            self.create_partitions(table, sealed_miniblock).await;
+            if table.is_archivable() {
+                self.detach_partitions(table, sealed_miniblock).await;
+            }
+        }
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.interval_ms
+    }
+}
@@ -1,4 +1,6 @@
 pub mod blocks_state_reporter;
+pub mod db_maintenance_scheduler;
+pub mod events_bloom_backfiller;
 pub mod fri_prover_job_retry_manager;
 pub mod fri_prover_queue_monitor;
 pub mod fri_scheduler_circuit_queuer;
@@ -6,7 +8,12 @@ pub mod fri_witness_generator_jobs_retry_manager;
 pub mod fri_witness_generator_queue_monitor;
 pub mod gcs_blob_cleaner;
 pub mod gpu_prover_queue_monitor;
+pub mod history_pruner;
+pub mod miniblock_partition_manager;
+pub mod partition_archiver;
 pub mod periodic_job;
+pub mod prover_artifacts_cleaner;
+pub mod prover_job_monitor;
 pub mod prover_job_retry_manager;
 pub mod prover_queue_monitor;
 pub mod waiting_to_queued_fri_witness_job_mover;
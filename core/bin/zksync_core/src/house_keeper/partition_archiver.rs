@@ -0,0 +1,109 @@
+use async_trait::async_trait;
+
+use zksync_dal::{
+    partitions_dal::{MiniblockRangePartition, PartitionStatus, PartitionedTable},
+    ConnectionPool,
+};
+use zksync_object_store::{Bucket, ObjectStore, ObjectStoreFactory};
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Number of miniblocks exported to a single object.
+const ARCHIVE_CHUNK_SIZE: u32 = 1_000;
+
+/// Exports detached partitions of archivable tables (i.e., `events`) to the object store
+/// as JSON lines and drops them afterwards.
+#[derive(Debug)]
+pub struct PartitionArchiver {
+    object_store: Box<dyn ObjectStore>,
+    interval_ms: u64,
+    pool: ConnectionPool,
+}
+
+impl PartitionArchiver {
+    pub async fn new(
+        store_factory: &ObjectStoreFactory,
+        pool: ConnectionPool,
+        interval_ms: u64,
+    ) -> Self {
+        Self {
+            object_store: store_factory.create_store().await,
+            interval_ms,
+            pool,
+        }
+    }
+
+    async fn archive_partition(&mut self, partition: MiniblockRangePartition) {
+        let mut storage = self.pool.access_storage_tagged("house_keeper").await;
+        let prefix = format!("{}/{}", partition.table, partition.name);
+
+        // Chunks are overwritten if the archiver was interrupted, so it's safe to restart
+        // the export from the beginning of the partition.
+        let mut chunk_start = partition.miniblocks.start;
+        while chunk_start < partition.miniblocks.end {
+            let chunk_end = chunk_start
+                .saturating_add(ARCHIVE_CHUNK_SIZE)
+                .min(partition.miniblocks.end);
+            let rows = storage
+                .partitions_dal()
+                .export_partition_rows(&partition, chunk_start..chunk_end)
+                .await;
+            if !rows.is_empty() {
+                let key = format!("{prefix}/{chunk_start}_{chunk_end}.jsonl");
+                let rows_len = rows.len();
+                self.object_store
+                    .put_raw(Bucket::ArchivedPartitions, &key, rows)
+                    .await
+                    .unwrap();
+                metrics::counter!(
+                    "server.house_keeper.archived_partition_bytes",
+                    rows_len as u64,
+                    "table" => partition.table.as_str()
+                );
+            }
+            chunk_start = chunk_end;
+        }
+
+        storage
+            .partitions_dal()
+            .drop_archived_partition(&partition, &prefix)
+            .await;
+        vlog::info!(
+            "Archived partition `{}` for miniblocks {:?} to `{prefix}`",
+            partition.name,
+            partition.miniblocks
+        );
+        metrics::increment_counter!(
+            "server.house_keeper.miniblock_partitions_archived",
+            "table" => partition.table.as_str()
+        );
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for PartitionArchiver {
+    const SERVICE_NAME: &'static str = "PartitionArchiver";
+
+    async fn run_routine_task(&mut self) {
+        for table in PartitionedTable::ALL {
+            if !table.is_archivable() {
+                continue;
+            }
+            let partitions = self
+                .pool
+                .access_storage_tagged("house_keeper")
+                .await
+                .partitions_dal()
+                .get_partitions(table, PartitionStatus::Detached)
+                .await;
+            // Archive at most one partition per table on each iteration to limit the DB load.
+            if let Some(partition) = partitions.into_iter().next() {
+                self.archive_partition(partition).await;
+            }
+        }
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.interval_ms
+    }
+}
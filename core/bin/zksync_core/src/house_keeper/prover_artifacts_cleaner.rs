@@ -0,0 +1,187 @@
+use async_trait::async_trait;
+
+use std::{ops::RangeInclusive, time::Duration};
+
+use zksync_dal::{prover_artifacts_dal::L1BatchProverArtifacts, ConnectionPool};
+use zksync_object_store::{Bucket, ObjectStore, ObjectStoreError, ObjectStoreFactory};
+use zksync_types::L1BatchNumber;
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Max number of L1 batches which artifacts are removed in a single iteration.
+const MAX_L1_BATCHES_PER_ITERATION: u32 = 10;
+
+/// Removes FRI prover artifacts (witness inputs, circuits, aggregation inputs and intermediate proofs)
+/// from the object store once the L1 batch proof is verified on L1 and the retention period has passed.
+/// Final proofs are retained.
+#[derive(Debug)]
+pub struct ProverArtifactsCleaner {
+    object_store: Box<dyn ObjectStore>,
+    retention: Duration,
+    interval_ms: u64,
+    main_pool: ConnectionPool,
+    prover_pool: ConnectionPool,
+}
+
+/// Returns L1 batches to clean up on the next iteration, or `None` if there's nothing to clean up.
+fn l1_batches_to_clean(
+    last_proven_l1_batch: L1BatchNumber,
+    last_cleaned_l1_batch: Option<L1BatchNumber>,
+) -> Option<RangeInclusive<L1BatchNumber>> {
+    // The genesis L1 batch is never proven, so cleanup starts from the L1 batch #1.
+    let first_batch = last_cleaned_l1_batch.map_or(1, |number| number.0 + 1);
+    if first_batch > last_proven_l1_batch.0 {
+        return None;
+    }
+    let last_batch = last_proven_l1_batch
+        .0
+        .min(first_batch + MAX_L1_BATCHES_PER_ITERATION - 1);
+    Some(L1BatchNumber(first_batch)..=L1BatchNumber(last_batch))
+}
+
+impl ProverArtifactsCleaner {
+    pub async fn new(
+        store_factory: &ObjectStoreFactory,
+        retention: Duration,
+        interval_ms: u64,
+        main_pool: ConnectionPool,
+        prover_pool: ConnectionPool,
+    ) -> Self {
+        Self {
+            object_store: store_factory.create_store().await,
+            retention,
+            interval_ms,
+            main_pool,
+            prover_pool,
+        }
+    }
+
+    async fn remove_artifacts(
+        &self,
+        artifacts: L1BatchProverArtifacts,
+    ) -> Result<(), ObjectStoreError> {
+        let L1BatchProverArtifacts {
+            witness_inputs,
+            circuits,
+            intermediate_proofs,
+            leaf_aggregation_inputs,
+            node_aggregations,
+            scheduler_inputs,
+        } = artifacts;
+        let artifacts = [
+            (Bucket::WitnessInput, witness_inputs),
+            (Bucket::ProverJobsFri, circuits),
+            (Bucket::ProofsFri, intermediate_proofs),
+            (
+                Bucket::LeafAggregationWitnessJobsFri,
+                leaf_aggregation_inputs,
+            ),
+            (Bucket::NodeAggregationWitnessJobsFri, node_aggregations),
+            (Bucket::SchedulerWitnessJobsFri, scheduler_inputs),
+        ];
+
+        for (bucket, keys) in artifacts {
+            for key in &keys {
+                match self.object_store.remove_raw(bucket, key).await {
+                    // The object may have been removed on a previous iteration that failed afterwards.
+                    Ok(()) | Err(ObjectStoreError::KeyNotFound(_)) => {}
+                    Err(err) => return Err(err),
+                }
+            }
+            metrics::counter!(
+                "server.house_keeper.prover_artifacts_removed",
+                keys.len() as u64,
+                "bucket" => bucket.to_string()
+            );
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for ProverArtifactsCleaner {
+    const SERVICE_NAME: &'static str = "ProverArtifactsCleaner";
+
+    async fn run_routine_task(&mut self) {
+        let last_proven_l1_batch = self
+            .main_pool
+            .access_storage_tagged("house_keeper")
+            .await
+            .blocks_dal()
+            .get_number_of_last_block_proven_on_eth_before(self.retention)
+            .await;
+        let Some(last_proven_l1_batch) = last_proven_l1_batch else {
+            return;
+        };
+
+        let mut storage = self.prover_pool.access_storage_tagged("house_keeper").await;
+        let last_cleaned_l1_batch = storage
+            .prover_artifacts_dal()
+            .get_last_cleaned_l1_batch()
+            .await
+            .unwrap();
+        let Some(l1_batches) = l1_batches_to_clean(last_proven_l1_batch, last_cleaned_l1_batch)
+        else {
+            return;
+        };
+
+        for l1_batch_number in l1_batches.start().0..=l1_batches.end().0 {
+            let l1_batch_number = L1BatchNumber(l1_batch_number);
+            let artifacts = storage
+                .prover_artifacts_dal()
+                .get_l1_batch_artifacts(l1_batch_number)
+                .await
+                .unwrap();
+            if let Err(err) = self.remove_artifacts(artifacts).await {
+                vlog::warn!(
+                    "Failed removing prover artifacts for L1 batch #{l1_batch_number}: {err}"
+                );
+                return;
+            }
+            storage
+                .prover_artifacts_dal()
+                .set_last_cleaned_l1_batch(l1_batch_number)
+                .await
+                .unwrap();
+            metrics::gauge!(
+                "server.house_keeper.last_cleaned_prover_artifacts_l1_batch",
+                l1_batch_number.0 as f64
+            );
+        }
+        vlog::info!(
+            "Removed prover artifacts for L1 batches #{}..=#{}",
+            l1_batches.start(),
+            l1_batches.end()
+        );
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.interval_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selecting_l1_batches_to_clean() {
+        assert_eq!(l1_batches_to_clean(L1BatchNumber(0), None), None);
+        assert_eq!(
+            l1_batches_to_clean(L1BatchNumber(5), None),
+            Some(L1BatchNumber(1)..=L1BatchNumber(5))
+        );
+        assert_eq!(
+            l1_batches_to_clean(L1BatchNumber(100), None),
+            Some(L1BatchNumber(1)..=L1BatchNumber(10))
+        );
+        assert_eq!(
+            l1_batches_to_clean(L1BatchNumber(100), Some(L1BatchNumber(10))),
+            Some(L1BatchNumber(11)..=L1BatchNumber(20))
+        );
+        assert_eq!(
+            l1_batches_to_clean(L1BatchNumber(100), Some(L1BatchNumber(100))),
+            None
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use async_trait::async_trait;
+
+use std::time::Duration;
+
+use zksync_dal::ConnectionPool;
+use zksync_types::proofs::{AggregationRound, JobQueueStatistics};
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+const ALL_ROUNDS: [AggregationRound; 4] = [
+    AggregationRound::BasicCircuits,
+    AggregationRound::LeafAggregation,
+    AggregationRound::NodeAggregation,
+    AggregationRound::Scheduler,
+];
+
+/// Watches witness generator and prover job queues. Requeues witness generator jobs which have been
+/// `in_progress` for longer than the lease timeout (prover jobs are requeued by [`ProverJobRetryManager`]),
+/// and reports queue depth and the age of the oldest job per stage, so that alerts can be set up
+/// for stalled stages.
+///
+/// [`ProverJobRetryManager`]: crate::house_keeper::prover_job_retry_manager::ProverJobRetryManager
+#[derive(Debug)]
+pub struct ProverJobMonitor {
+    prover_connection_pool: ConnectionPool,
+    witness_max_attempts: u32,
+    witness_processing_timeout: Duration,
+    monitoring_interval_ms: u64,
+}
+
+impl ProverJobMonitor {
+    pub fn new(
+        witness_max_attempts: u32,
+        witness_processing_timeout: Duration,
+        monitoring_interval_ms: u64,
+        prover_connection_pool: ConnectionPool,
+    ) -> Self {
+        Self {
+            prover_connection_pool,
+            witness_max_attempts,
+            witness_processing_timeout,
+            monitoring_interval_ms,
+        }
+    }
+}
+
+fn emit_queue_metrics(stage: &'static str, round: AggregationRound, stats: JobQueueStatistics) {
+    let round = format!("{round:?}");
+    for (status, depth, oldest_age) in [
+        ("queued", stats.queued, stats.oldest_queued_age),
+        (
+            "in_progress",
+            stats.in_progress,
+            stats.oldest_in_progress_age,
+        ),
+    ] {
+        metrics::gauge!(
+            "server.prover_job_monitor.queue_depth",
+            depth as f64,
+            "stage" => stage,
+            "round" => round.clone(),
+            "status" => status
+        );
+        metrics::gauge!(
+            "server.prover_job_monitor.oldest_job_age",
+            oldest_age.unwrap_or_default().as_secs_f64(),
+            "stage" => stage,
+            "round" => round.clone(),
+            "status" => status
+        );
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for ProverJobMonitor {
+    const SERVICE_NAME: &'static str = "ProverJobMonitor";
+
+    async fn run_routine_task(&mut self) {
+        let mut conn = self.prover_connection_pool.access_storage().await;
+
+        for round in ALL_ROUNDS {
+            let stuck_jobs = conn
+                .witness_generator_dal()
+                .requeue_stuck_jobs(
+                    round,
+                    self.witness_processing_timeout,
+                    self.witness_max_attempts,
+                )
+                .await;
+            let mut requeued_count = 0_u64;
+            for stuck_job in stuck_jobs {
+                if stuck_job.status == "failed" {
+                    vlog::error!(
+                        "{round:?} witness job for L1 batch #{} exceeded its lease after {} attempts; marked as failed",
+                        stuck_job.id,
+                        stuck_job.attempts
+                    );
+                    metrics::increment_counter!(
+                        "server.prover_job_monitor.exhausted_jobs",
+                        "stage" => "witness_generator",
+                        "round" => format!("{round:?}")
+                    );
+                } else {
+                    vlog::info!("re-queuing {round:?} witness job {stuck_job:?}");
+                    requeued_count += 1;
+                }
+            }
+            metrics::counter!(
+                "server.prover_job_monitor.requeued_jobs",
+                requeued_count,
+                "stage" => "witness_generator",
+                "round" => format!("{round:?}")
+            );
+
+            let stats = conn
+                .witness_generator_dal()
+                .get_witness_jobs_queue_stats(round)
+                .await;
+            emit_queue_metrics("witness_generator", round, stats);
+        }
+
+        let prover_stats = conn
+            .prover_dal()
+            .get_prover_jobs_queue_stats_per_round()
+            .await;
+        for round in ALL_ROUNDS {
+            let stats = prover_stats.get(&round).copied().unwrap_or_default();
+            emit_queue_metrics("prover", round, stats);
+        }
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.monitoring_interval_ms
+    }
+}
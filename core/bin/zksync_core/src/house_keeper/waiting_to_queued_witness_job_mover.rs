@@ -18,11 +18,29 @@ impl WaitingToQueuedWitnessJobMover {
     }
 
     async fn move_jobs(&mut self) {
+        self.move_basic_circuit_shards().await;
         self.move_leaf_aggregation_jobs().await;
         self.move_node_aggregation_jobs().await;
         self.move_scheduler_jobs().await;
     }
 
+    async fn move_basic_circuit_shards(&mut self) {
+        let mut conn = self.prover_connection_pool.access_storage().await;
+        let shards = conn
+            .witness_generator_dal()
+            .move_basic_circuit_shards_to_proven()
+            .await;
+        let len = shards.len();
+        for (l1_batch_number, circuit_type) in shards {
+            vlog::info!(
+                "Marked {} basic circuits shard for l1_batch {} as proven",
+                circuit_type,
+                l1_batch_number
+            );
+        }
+        metrics::counter!("server.basic_circuit_shards.proven", len as u64);
+    }
+
     async fn move_leaf_aggregation_jobs(&mut self) {
         let mut conn = self.prover_connection_pool.access_storage().await;
         let l1_batch_numbers = conn
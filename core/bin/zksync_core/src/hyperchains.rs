@@ -0,0 +1,432 @@
+//! Running hyperchains from the chain registry in the same process as the main chain.
+//!
+//! Each registered chain gets its own connection pools, mempool, state keeper, web3 API servers and object store
+//! location. The L1 client, gas adjuster, L1 contracts and caches of immutable data (factory deps and decoded
+//! bytecodes) are shared with the main chain. Only the state keeper and the web3 API run for registered chains;
+//! L1 batches of these chains are not committed to L1 or proven, so the registry is intended for dev environments
+//! and small operators.
+
+use anyhow::{ensure, Context as _};
+use tokio::{sync::watch, task::JoinHandle};
+
+use std::{collections::HashSet, sync::Arc, time::Instant};
+
+use zksync_config::configs::{
+    api::Web3JsonRpcConfig,
+    chain::{MempoolConfig, NetworkConfig, StateKeeperConfig},
+    chain_registry::HyperchainConfig,
+    object_store::ObjectStoreMode,
+};
+use zksync_config::{ApiConfig, ContractsConfig, DBConfig, ETHSenderConfig, ObjectStoreConfig};
+use zksync_dal::ConnectionPool;
+use zksync_health_check::CheckHealth;
+use zksync_object_store::ObjectStoreFactory;
+use zksync_state::{BytecodeCache, FactoryDepsCache};
+use zksync_types::{L2ChainId, MiniblockNumber};
+
+use crate::{
+    add_state_keeper_to_task_futures,
+    admin_controls::AdminControls,
+    api_server::{tx_sender::TxSenderConfig, web3::state::InternalApiConfig},
+    chain_contracts::ChainContracts,
+    l1_batch_status_bus::L1BatchStatusBus,
+    l1_gas_price::L1GasPriceProvider,
+    run_web3_api,
+    state_keeper::{MempoolGuard, PendingReceipts, PendingStorageDiffs},
+    Component,
+};
+
+/// Components and data shared by all chains run by the process.
+#[derive(Debug)]
+pub(crate) struct SharedChainResources<G> {
+    pub chain_contracts: ChainContracts,
+    pub gas_adjuster: Arc<G>,
+    pub factory_deps_cache: FactoryDepsCache,
+    pub bytecode_cache: Option<BytecodeCache>,
+}
+
+/// Resources of the main chain that must not be reused by registered chains.
+#[derive(Debug)]
+struct MainChainResources {
+    chain_id: u16,
+    ports: Vec<u16>,
+    state_keeper_db_path: String,
+    /// Location of the object store used for VM artifacts; `None` if VM artifacts are not saved.
+    object_store_location: Option<String>,
+}
+
+impl MainChainResources {
+    fn from_env(state_keeper_config: &StateKeeperConfig) -> Self {
+        let api_config = ApiConfig::from_env();
+        let mut ports = vec![
+            api_config.web3_json_rpc.http_port,
+            api_config.web3_json_rpc.ws_port,
+            api_config.explorer.port,
+            api_config.prometheus.listener_port,
+            api_config.healthcheck.port,
+        ];
+        ports.extend(api_config.web3_json_rpc.admin_api_port);
+        ports.extend(MempoolConfig::from_env().bulk_ingestion_port);
+        let object_store_location = state_keeper_config
+            .save_vm_artifacts()
+            .then(|| object_store_location(&ObjectStoreConfig::from_env()).to_owned());
+
+        Self {
+            chain_id: NetworkConfig::from_env().zksync_network_id,
+            ports,
+            state_keeper_db_path: DBConfig::from_env().state_keeper_db_path,
+            object_store_location,
+        }
+    }
+}
+
+/// Returns the bucket URL or the base path of the object store, depending on its mode.
+fn object_store_location(config: &ObjectStoreConfig) -> &str {
+    match config.mode {
+        ObjectStoreMode::FileBacked => &config.file_backed_base_path,
+        ObjectStoreMode::GCS | ObjectStoreMode::GCSWithCredentialFile => &config.bucket_base_url,
+    }
+}
+
+/// Creates an object store factory for the chain. If the chain doesn't specify the object store location,
+/// VM artifacts are not saved (this is checked in [`validate_chains()`]), so the store is never created.
+fn chain_object_store_factory(chain: &HyperchainConfig) -> ObjectStoreFactory {
+    let mut config = ObjectStoreConfig::from_env();
+    if let Some(location) = &chain.object_store_location {
+        match config.mode {
+            ObjectStoreMode::FileBacked => config.file_backed_base_path = location.clone(),
+            ObjectStoreMode::GCS | ObjectStoreMode::GCSWithCredentialFile => {
+                config.bucket_base_url = location.clone();
+            }
+        }
+    }
+    ObjectStoreFactory::new(config)
+}
+
+/// Checks that registered chains don't conflict with each other or with the main chain.
+fn validate_chains(
+    chains: &[HyperchainConfig],
+    main_chain: &MainChainResources,
+) -> anyhow::Result<()> {
+    let mut names = HashSet::new();
+    let mut chain_ids = HashSet::from([main_chain.chain_id]);
+    let mut ports: HashSet<_> = main_chain.ports.iter().copied().collect();
+    let mut db_paths = HashSet::from([main_chain.state_keeper_db_path.as_str()]);
+    let mut object_store_locations: HashSet<_> = main_chain
+        .object_store_location
+        .as_deref()
+        .into_iter()
+        .collect();
+    for chain in chains {
+        ensure!(
+            names.insert(&chain.name),
+            "chain `{}` is registered multiple times",
+            chain.name
+        );
+        ensure!(
+            chain_ids.insert(chain.chain_id),
+            "chain ID {} of chain `{}` is already used",
+            chain.chain_id,
+            chain.name
+        );
+        ensure!(
+            ports.insert(chain.http_port) && ports.insert(chain.ws_port),
+            "API ports of chain `{}` are already used",
+            chain.name
+        );
+        ensure!(
+            db_paths.insert(chain.state_keeper_db_path.as_str()),
+            "state keeper DB path of chain `{}` is already used",
+            chain.name
+        );
+        if main_chain.object_store_location.is_some() {
+            let location = chain.object_store_location.as_deref().with_context(|| {
+                format!(
+                    "object store location of chain `{}` must be set since VM artifacts are saved",
+                    chain.name
+                )
+            })?;
+            ensure!(
+                object_store_locations.insert(location),
+                "object store location of chain `{}` is already used",
+                chain.name
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Starts the state keeper and / or web3 API servers (depending on `components`) for each registered chain.
+pub(crate) async fn add_hyperchains_to_task_futures<G>(
+    task_futures: &mut Vec<JoinHandle<()>>,
+    healthchecks: &mut Vec<Box<dyn CheckHealth>>,
+    chains: &[HyperchainConfig],
+    components: &[Component],
+    shared: &SharedChainResources<G>,
+    stop_receiver: &watch::Receiver<bool>,
+) -> anyhow::Result<()>
+where
+    G: L1GasPriceProvider + Send + Sync + 'static,
+{
+    let network_config = NetworkConfig::from_env();
+    let main_chain = MainChainResources::from_env(&StateKeeperConfig::from_env());
+    validate_chains(chains, &main_chain)?;
+
+    for chain in chains {
+        let started_at = Instant::now();
+        vlog::info!("initializing hyperchain `{}`", chain.name);
+        add_hyperchain_to_task_futures(
+            task_futures,
+            healthchecks,
+            chain,
+            &network_config,
+            components,
+            shared,
+            stop_receiver,
+        )
+        .await
+        .with_context(|| format!("failed initializing hyperchain `{}`", chain.name))?;
+        vlog::info!(
+            "initialized hyperchain `{}` in {:?}",
+            chain.name,
+            started_at.elapsed()
+        );
+        metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "hyperchain");
+    }
+    Ok(())
+}
+
+async fn add_hyperchain_to_task_futures<G>(
+    task_futures: &mut Vec<JoinHandle<()>>,
+    healthchecks: &mut Vec<Box<dyn CheckHealth>>,
+    chain: &HyperchainConfig,
+    network_config: &NetworkConfig,
+    components: &[Component],
+    shared: &SharedChainResources<G>,
+    stop_receiver: &watch::Receiver<bool>,
+) -> anyhow::Result<()>
+where
+    G: L1GasPriceProvider + Send + Sync + 'static,
+{
+    let with_state_keeper = components.contains(&Component::StateKeeper);
+    let with_http_api = components.contains(&Component::HttpApi);
+    let with_ws_api = components.contains(&Component::WsApi);
+
+    let connection_pool = ConnectionPool::with_url(&chain.database_url, None).await;
+    let replica_connection_pool =
+        ConnectionPool::with_url(chain.replica_database_url(), None).await;
+    let mut storage = connection_pool.access_storage_tagged("hyperchains").await;
+    ensure!(
+        !storage.blocks_dal().is_genesis_needed().await,
+        "database of the chain is not initialized; run genesis for the chain first"
+    );
+    let next_priority_id = storage.transactions_dal().next_priority_id().await;
+    drop(storage);
+
+    let mut state_keeper_config = StateKeeperConfig::from_env();
+    if let Some(fee_account_addr) = chain.fee_account_addr {
+        state_keeper_config.fee_account_addr = fee_account_addr;
+        state_keeper_config.fee_account_addr_path = None;
+        state_keeper_config.fee_collector_addr = None;
+    }
+    // Bulk ingestion and mempool handover are configured for the main chain only.
+    let mempool_config = MempoolConfig {
+        bulk_ingestion_port: None,
+        snapshot_source_url: None,
+        ..MempoolConfig::from_env()
+    };
+    let mempool = with_state_keeper.then(|| {
+        MempoolGuard::new(
+            next_priority_id,
+            mempool_config.capacity,
+            mempool_config.max_nonce_lookahead,
+        )
+    });
+    let admin_controls = AdminControls::default();
+    let l1_batch_status_bus = L1BatchStatusBus::new();
+    let (sealed_miniblocks_sender, sealed_miniblocks) = watch::channel(MiniblockNumber(0));
+    let sealed_miniblocks = with_state_keeper.then_some(sealed_miniblocks);
+    let pending_receipts = sealed_miniblocks.clone().map(PendingReceipts::new);
+    let pending_storage_diffs = sealed_miniblocks.clone().map(PendingStorageDiffs::new);
+
+    if with_http_api || with_ws_api {
+        let mut api_config = ApiConfig::from_env();
+        // The admin namespace server and IPC transport are only available for the main chain.
+        api_config.web3_json_rpc = Web3JsonRpcConfig {
+            http_port: chain.http_port,
+            ws_port: chain.ws_port,
+            admin_api_port: None,
+            admin_api_token: None,
+            ipc_path: None,
+            ..api_config.web3_json_rpc
+        };
+        let network_config = NetworkConfig {
+            zksync_network_id: chain.chain_id,
+            ..network_config.clone()
+        };
+        let tx_sender_config = TxSenderConfig::new(&state_keeper_config, &api_config.web3_json_rpc);
+        let internal_api_config = InternalApiConfig::new(
+            &network_config,
+            &api_config.web3_json_rpc,
+            &ContractsConfig::from_env(),
+            &ETHSenderConfig::from_env().sender,
+            &shared.chain_contracts,
+        );
+        let (futures, api_health_checks) = run_web3_api(
+            &tx_sender_config,
+            &state_keeper_config,
+            &internal_api_config,
+            &api_config,
+            with_http_api,
+            with_ws_api,
+            shared.gas_adjuster.clone(),
+            connection_pool.clone(),
+            replica_connection_pool,
+            stop_receiver.clone(),
+            shared.factory_deps_cache.clone(),
+            shared.bytecode_cache.clone(),
+            mempool.clone(),
+            admin_controls.clone(),
+            sealed_miniblocks,
+            pending_receipts.clone(),
+            pending_storage_diffs.clone(),
+            l1_batch_status_bus.clone(),
+        )
+        .await;
+        task_futures.extend(futures);
+        for health_check in api_health_checks {
+            healthchecks.push(Box::new(health_check));
+        }
+    }
+
+    if let Some(mempool) = mempool {
+        let db_config = DBConfig {
+            state_keeper_db_path: chain.state_keeper_db_path.clone(),
+            ..DBConfig::from_env()
+        };
+        add_state_keeper_to_task_futures(
+            task_futures,
+            &chain.database_url,
+            L2ChainId(chain.chain_id),
+            shared.chain_contracts,
+            state_keeper_config,
+            &db_config,
+            &mempool_config,
+            mempool,
+            shared.gas_adjuster.clone(),
+            shared.bytecode_cache.clone(),
+            &chain_object_store_factory(chain),
+            admin_controls,
+            sealed_miniblocks_sender,
+            pending_receipts,
+            pending_storage_diffs,
+            l1_batch_status_bus,
+            stop_receiver.clone(),
+        )
+        .await;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn main_chain() -> MainChainResources {
+        MainChainResources {
+            chain_id: 270,
+            ports: vec![3050, 3051],
+            state_keeper_db_path: "./db/main/state_keeper".to_owned(),
+            object_store_location: None,
+        }
+    }
+
+    fn chain(name: &str, chain_id: u16, first_port: u16) -> HyperchainConfig {
+        HyperchainConfig {
+            name: name.to_owned(),
+            chain_id,
+            database_url: format!("postgres://postgres@localhost/{name}"),
+            replica_database_url: None,
+            http_port: first_port,
+            ws_port: first_port + 1,
+            state_keeper_db_path: format!("./db/{name}/state_keeper"),
+            object_store_location: None,
+            fee_account_addr: None,
+        }
+    }
+
+    #[test]
+    fn validating_chains() {
+        let chains = [chain("dev-1", 271, 3060), chain("dev-2", 272, 3070)];
+        validate_chains(&chains, &main_chain()).unwrap();
+
+        let main_chain_with_id = MainChainResources {
+            chain_id: 271,
+            ..main_chain()
+        };
+        let err = validate_chains(&chains, &main_chain_with_id)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("chain ID 271"), "{err}");
+
+        let chains = [chain("dev-1", 271, 3060), chain("dev-2", 272, 3061)];
+        let err = validate_chains(&chains, &main_chain())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("API ports of chain `dev-2`"), "{err}");
+
+        let chains = [chain("dev-1", 271, 3060), chain("dev-1", 272, 3070)];
+        let err = validate_chains(&chains, &main_chain())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("registered multiple times"), "{err}");
+    }
+
+    #[test]
+    fn validating_chains_against_main_chain() {
+        let chains = [chain("dev-1", 271, 3051)];
+        let err = validate_chains(&chains, &main_chain())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("API ports of chain `dev-1`"), "{err}");
+
+        let mut chains = [chain("dev-1", 271, 3060)];
+        chains[0].state_keeper_db_path = "./db/main/state_keeper".to_owned();
+        let err = validate_chains(&chains, &main_chain())
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("state keeper DB path of chain `dev-1`"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn validating_object_store_locations() {
+        let main_chain = MainChainResources {
+            object_store_location: Some("./artifacts".to_owned()),
+            ..main_chain()
+        };
+        let mut chains = [chain("dev-1", 271, 3060), chain("dev-2", 272, 3070)];
+        let err = validate_chains(&chains, &main_chain)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("object store location of chain `dev-1` must be set"),
+            "{err}"
+        );
+
+        chains[0].object_store_location = Some("./artifacts/dev-1".to_owned());
+        chains[1].object_store_location = Some("./artifacts".to_owned());
+        let err = validate_chains(&chains, &main_chain)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("object store location of chain `dev-2` is already used"),
+            "{err}"
+        );
+
+        chains[1].object_store_location = Some("./artifacts/dev-2".to_owned());
+        validate_chains(&chains, &main_chain).unwrap();
+    }
+}
@@ -0,0 +1,194 @@
+//! In-process bus for L1 batch lifecycle transitions.
+//!
+//! Components changing the status of L1 batches (the state keeper, the Merkle tree, and the ETH sender) publish
+//! transitions to the bus, so that other components running in the same process can react to them
+//! without polling Postgres. Since publishers may run in other processes, subscribers should fall back to
+//! polling Postgres for the stages they haven't received any updates for.
+
+use tokio::sync::broadcast;
+
+use std::collections::HashMap;
+
+use zksync_types::{aggregated_operations::AggregatedActionType, L1BatchNumber};
+
+/// Capacity of the bus. Since updates are small and only the latest update for each stage matters,
+/// lagging subscribers losing old updates is not a problem.
+const BUS_CAPACITY: usize = 128;
+
+/// Stage of the L1 batch lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum L1BatchStage {
+    /// L1 batch is sealed by the state keeper.
+    Sealed,
+    /// Metadata (incl. the root hash of the Merkle tree) is computed for the L1 batch.
+    TreeComputed,
+    /// Commit transaction for the L1 batch is mined on L1.
+    Committed,
+    /// Proof for the L1 batch is mined on L1.
+    Proven,
+    /// Execute transaction for the L1 batch is mined on L1.
+    Executed,
+}
+
+impl L1BatchStage {
+    pub(crate) fn from_action_type(action_type: AggregatedActionType) -> Self {
+        match action_type {
+            AggregatedActionType::CommitBlocks => Self::Committed,
+            AggregatedActionType::PublishProofBlocksOnchain => Self::Proven,
+            AggregatedActionType::ExecuteBlocks => Self::Executed,
+        }
+    }
+}
+
+/// Notification that an L1 batch (and all L1 batches before it) has reached a certain stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct L1BatchStatusUpdate {
+    pub stage: L1BatchStage,
+    pub l1_batch_number: L1BatchNumber,
+}
+
+/// Publishing side of the bus. Cloned handles publish to the same bus.
+#[derive(Debug, Clone)]
+pub struct L1BatchStatusBus {
+    sender: broadcast::Sender<L1BatchStatusUpdate>,
+}
+
+impl Default for L1BatchStatusBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl L1BatchStatusBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(BUS_CAPACITY);
+        Self { sender }
+    }
+
+    /// Publishes the transition of the specified L1 batch to `stage`.
+    pub fn publish(&self, stage: L1BatchStage, l1_batch_number: L1BatchNumber) {
+        let update = L1BatchStatusUpdate {
+            stage,
+            l1_batch_number,
+        };
+        // An error only means that there are no subscribers at the moment, which is fine.
+        self.sender.send(update).ok();
+        metrics::increment_counter!("server.l1_batch_status_bus.published", "stage" => format!("{stage:?}"));
+    }
+
+    pub fn subscribe(&self) -> L1BatchStatusReceiver {
+        L1BatchStatusReceiver {
+            receiver: self.sender.subscribe(),
+            latest: HashMap::new(),
+        }
+    }
+}
+
+/// Subscribing side of the bus keeping track of the latest L1 batch for each stage.
+#[derive(Debug)]
+pub struct L1BatchStatusReceiver {
+    receiver: broadcast::Receiver<L1BatchStatusUpdate>,
+    latest: HashMap<L1BatchStage, L1BatchNumber>,
+}
+
+impl L1BatchStatusReceiver {
+    /// Returns the latest L1 batch that has reached `stage`, or `None` if no updates were received
+    /// for the stage (e.g., because the publishing component runs in another process).
+    pub fn latest(&self, stage: L1BatchStage) -> Option<L1BatchNumber> {
+        self.latest.get(&stage).copied()
+    }
+
+    fn apply(&mut self, update: L1BatchStatusUpdate) {
+        let latest = self
+            .latest
+            .entry(update.stage)
+            .or_insert(update.l1_batch_number);
+        // Stages can only move forward, except for reverts, which restart the node anyway.
+        *latest = (*latest).max(update.l1_batch_number);
+    }
+
+    /// Applies all updates published since the last call without waiting. Returns updated stages.
+    pub fn poll_updates(&mut self) -> Vec<L1BatchStage> {
+        let mut updated_stages = vec![];
+        loop {
+            match self.receiver.try_recv() {
+                Ok(update) => {
+                    self.apply(update);
+                    if !updated_stages.contains(&update.stage) {
+                        updated_stages.push(update.stage);
+                    }
+                }
+                Err(broadcast::error::TryRecvError::Lagged(skipped)) => {
+                    vlog::debug!("L1 batch status receiver lagged by {skipped} updates");
+                }
+                Err(
+                    broadcast::error::TryRecvError::Empty | broadcast::error::TryRecvError::Closed,
+                ) => break,
+            }
+        }
+        updated_stages
+    }
+
+    /// Waits for the next update and applies it. If all publishers are dropped, never resolves.
+    pub async fn recv(&mut self) -> L1BatchStatusUpdate {
+        loop {
+            match self.receiver.recv().await {
+                Ok(update) => {
+                    self.apply(update);
+                    return update;
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    vlog::debug!("L1 batch status receiver lagged by {skipped} updates");
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    futures::future::pending::<()>().await;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn receiver_tracks_latest_l1_batches() {
+        let bus = L1BatchStatusBus::new();
+        let mut receiver = bus.subscribe();
+        assert_eq!(receiver.latest(L1BatchStage::Sealed), None);
+
+        bus.publish(L1BatchStage::Sealed, L1BatchNumber(2));
+        bus.publish(L1BatchStage::Sealed, L1BatchNumber(3));
+        bus.publish(L1BatchStage::Committed, L1BatchNumber(1));
+        let updated_stages = receiver.poll_updates();
+        assert_eq!(
+            updated_stages,
+            [L1BatchStage::Sealed, L1BatchStage::Committed]
+        );
+        assert_eq!(
+            receiver.latest(L1BatchStage::Sealed),
+            Some(L1BatchNumber(3))
+        );
+        assert_eq!(
+            receiver.latest(L1BatchStage::Committed),
+            Some(L1BatchNumber(1))
+        );
+        assert_eq!(receiver.latest(L1BatchStage::Executed), None);
+        assert!(receiver.poll_updates().is_empty());
+
+        bus.publish(L1BatchStage::Executed, L1BatchNumber(1));
+        let update = receiver.recv().await;
+        assert_eq!(
+            update,
+            L1BatchStatusUpdate {
+                stage: L1BatchStage::Executed,
+                l1_batch_number: L1BatchNumber(1),
+            }
+        );
+        assert_eq!(
+            receiver.latest(L1BatchStage::Executed),
+            Some(L1BatchNumber(1))
+        );
+    }
+}
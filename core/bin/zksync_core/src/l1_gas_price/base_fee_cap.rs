@@ -0,0 +1,219 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use tokio::sync::watch::Receiver;
+
+use vm::vm_with_bootloader::derive_base_fee_and_gas_per_pubdata;
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_dal::ConnectionPool;
+
+use super::L1GasPriceProvider;
+
+const SLEEP_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Adjusts the L1 gas price so that the derived base fee differs from `prev_base_fee` by at most `max_change`
+/// (relative). The base fee is monotonic in the L1 gas price, so the closest L1 gas price satisfying the cap
+/// is found using binary search. The price is returned as is if the cap cannot be satisfied (e.g., because
+/// the fair L2 gas price was raised above the cap).
+pub fn cap_l1_gas_price_by_base_fee(
+    l1_gas_price: u64,
+    fair_l2_gas_price: u64,
+    prev_base_fee: u64,
+    max_change: f64,
+) -> u64 {
+    let base_fee = |price| derive_base_fee_and_gas_per_pubdata(price, fair_l2_gas_price).0;
+    let max_base_fee = (prev_base_fee as f64 * (1.0 + max_change)) as u64;
+    let min_base_fee = (prev_base_fee as f64 * (1.0 - max_change)).ceil() as u64;
+
+    let current_base_fee = base_fee(l1_gas_price);
+    if current_base_fee > max_base_fee {
+        if base_fee(0) > max_base_fee {
+            return l1_gas_price;
+        }
+        // Invariant: `base_fee(low) <= max_base_fee < base_fee(high)`.
+        let (mut low, mut high) = (0, l1_gas_price);
+        while high - low > 1 {
+            let middle = low + (high - low) / 2;
+            if base_fee(middle) <= max_base_fee {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        low
+    } else if current_base_fee < min_base_fee {
+        // Prices above this bound would overflow when converted to the price of pubdata.
+        const MAX_L1_GAS_PRICE: u64 = u64::MAX >> 8;
+
+        let mut high = l1_gas_price.max(1);
+        while base_fee(high) < min_base_fee {
+            if high >= MAX_L1_GAS_PRICE {
+                return l1_gas_price;
+            }
+            high = (high * 2).min(MAX_L1_GAS_PRICE);
+        }
+        // Invariant: `base_fee(low) < min_base_fee <= base_fee(high)`.
+        let mut low = l1_gas_price;
+        while high - low > 1 {
+            let middle = low + (high - low) / 2;
+            if base_fee(middle) < min_base_fee {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        high
+    } else {
+        l1_gas_price
+    }
+}
+
+/// Gas price provider applying the same base fee cap as the state keeper applies to new L1 batches,
+/// relative to the base fee of the last sealed L1 batch. Used by the components that must agree with
+/// the state keeper on the L1 gas price, such as the fee estimation in the API and the mempool fetcher;
+/// otherwise, they'd quote or accept fees that the state keeper doesn't charge.
+///
+/// The state keeper itself caps the price using the base fee it has just sealed, so it doesn't use this provider.
+#[derive(Debug)]
+pub struct BaseFeeCappedGasPriceProvider<G> {
+    inner: Arc<G>,
+    fair_l2_gas_price: u64,
+    max_change: Option<f64>,
+    /// Base fee of the last sealed L1 batch; 0 until the first update or if the last batch is the genesis one.
+    prev_base_fee: AtomicU64,
+}
+
+impl<G> BaseFeeCappedGasPriceProvider<G> {
+    pub fn new(config: &StateKeeperConfig, inner: Arc<G>) -> Self {
+        Self {
+            inner,
+            fair_l2_gas_price: config.fair_l2_gas_price,
+            max_change: config.max_base_fee_change_per_batch,
+            prev_base_fee: AtomicU64::new(0),
+        }
+    }
+
+    pub async fn run(self: Arc<Self>, pool: ConnectionPool, stop_receiver: Receiver<bool>) {
+        if self.max_change.is_none() {
+            return; // The base fee isn't capped, so there's nothing to track.
+        }
+
+        loop {
+            if *stop_receiver.borrow() {
+                vlog::info!("Stop signal received, BaseFeeCappedGasPriceProvider is shutting down");
+                break;
+            }
+
+            let prev_base_fee = pool
+                .access_storage_tagged("l1_gas_price")
+                .await
+                .blocks_dal()
+                .get_sealed_block_base_fee()
+                .await;
+            self.prev_base_fee.store(prev_base_fee, Ordering::Relaxed);
+            tokio::time::sleep(SLEEP_INTERVAL).await;
+        }
+    }
+}
+
+impl<G: L1GasPriceProvider> L1GasPriceProvider for BaseFeeCappedGasPriceProvider<G> {
+    fn estimate_effective_gas_price(&self) -> u64 {
+        let l1_gas_price = self.inner.estimate_effective_gas_price();
+        let prev_base_fee = self.prev_base_fee.load(Ordering::Relaxed);
+        match self.max_change {
+            Some(max_change) if prev_base_fee > 0 => cap_l1_gas_price_by_base_fee(
+                l1_gas_price,
+                self.fair_l2_gas_price,
+                prev_base_fee,
+                max_change,
+            ),
+            _ => l1_gas_price,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FAIR_L2_GAS_PRICE: u64 = 100;
+    const PREV_L1_GAS_PRICE: u64 = 1_000_000_000_000;
+
+    fn base_fee(l1_gas_price: u64) -> u64 {
+        derive_base_fee_and_gas_per_pubdata(l1_gas_price, FAIR_L2_GAS_PRICE).0
+    }
+
+    #[test]
+    fn base_fee_change_is_capped() {
+        let prev_base_fee = base_fee(PREV_L1_GAS_PRICE);
+        assert!(prev_base_fee > FAIR_L2_GAS_PRICE);
+        let max_base_fee = (prev_base_fee as f64 * 1.125) as u64;
+        let min_base_fee = (prev_base_fee as f64 * 0.875).ceil() as u64;
+        let cap =
+            |price| cap_l1_gas_price_by_base_fee(price, FAIR_L2_GAS_PRICE, prev_base_fee, 0.125);
+
+        let capped_price = cap(PREV_L1_GAS_PRICE * 10);
+        assert!(capped_price < PREV_L1_GAS_PRICE * 10);
+        assert!(base_fee(capped_price) <= max_base_fee);
+        assert!(base_fee(capped_price + 1) > max_base_fee);
+
+        let capped_price = cap(PREV_L1_GAS_PRICE / 10);
+        assert!(capped_price > PREV_L1_GAS_PRICE / 10);
+        assert!(base_fee(capped_price) >= min_base_fee);
+        assert!(base_fee(capped_price - 1) < min_base_fee);
+
+        let price = PREV_L1_GAS_PRICE * 21 / 20;
+        assert_eq!(cap(price), price);
+        // The cap cannot be satisfied if the fair L2 gas price is raised above it.
+        let price = PREV_L1_GAS_PRICE * 10;
+        let capped_price =
+            cap_l1_gas_price_by_base_fee(price, prev_base_fee * 2, prev_base_fee, 0.125);
+        assert_eq!(capped_price, price);
+    }
+
+    #[derive(Debug)]
+    struct FixedGasPrice(u64);
+
+    impl L1GasPriceProvider for FixedGasPrice {
+        fn estimate_effective_gas_price(&self) -> u64 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn provider_caps_price_by_last_batch_base_fee() {
+        let config = StateKeeperConfig {
+            fair_l2_gas_price: FAIR_L2_GAS_PRICE,
+            max_base_fee_change_per_batch: Some(0.125),
+            ..StateKeeperConfig::default()
+        };
+        let inner = Arc::new(FixedGasPrice(PREV_L1_GAS_PRICE / 10));
+        let provider = BaseFeeCappedGasPriceProvider::new(&config, inner);
+        // The price isn't capped until the base fee of the last batch is loaded.
+        assert_eq!(
+            provider.estimate_effective_gas_price(),
+            PREV_L1_GAS_PRICE / 10
+        );
+
+        let prev_base_fee = base_fee(PREV_L1_GAS_PRICE);
+        provider
+            .prev_base_fee
+            .store(prev_base_fee, Ordering::Relaxed);
+        let capped_price = provider.estimate_effective_gas_price();
+        assert_eq!(
+            capped_price,
+            cap_l1_gas_price_by_base_fee(
+                PREV_L1_GAS_PRICE / 10,
+                FAIR_L2_GAS_PRICE,
+                prev_base_fee,
+                0.125
+            )
+        );
+        assert!(base_fee(capped_price) >= (prev_base_fee as f64 * 0.875).ceil() as u64);
+    }
+}
@@ -1,12 +1,20 @@
 //! This module determines the fees to pay in txs containing blocks submitted to the L1.
 
 // Built-in deps
+use serde::Deserialize;
 use std::collections::VecDeque;
 use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::watch::Receiver;
 
 use zksync_config::GasAdjusterConfig;
 use zksync_eth_client::{types::Error, EthInterface};
+use zksync_types::{H256, U256, U64};
+use zksync_web3_decl::jsonrpsee::{
+    core::client::{Subscription, SubscriptionClientT},
+    rpc_params,
+    ws_client::WsClientBuilder,
+};
 
 use super::{L1GasPriceProvider, L1TxParamsProvider};
 
@@ -14,6 +22,22 @@ pub mod bounded_gas_adjuster;
 #[cfg(test)]
 mod tests;
 
+/// Number of most recent L1 block hashes kept to detect reorgs.
+const MAX_REORG_DEPTH: usize = 64;
+/// If no new L1 block is received via subscription during this interval, the subscription is considered stalled.
+const NEW_HEADS_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Subset of the L1 block header fields received via the `newHeads` subscription.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct L1BlockHeader {
+    pub number: U64,
+    pub hash: H256,
+    pub parent_hash: H256,
+    pub timestamp: U256,
+    pub base_fee_per_gas: Option<U256>,
+}
+
 /// This component keeps track of the median base_fee from the last `max_base_fee_samples` blocks.
 /// It is used to adjust the base_fee of transactions sent to L1.
 #[derive(Debug)]
@@ -21,6 +45,7 @@ pub struct GasAdjuster<E> {
     pub(super) statistics: GasStatistics,
     pub(super) config: GasAdjusterConfig,
     eth_client: E,
+    l1_ws_url: Option<String>,
 }
 
 impl<E: EthInterface> GasAdjuster<E> {
@@ -40,9 +65,17 @@ impl<E: EthInterface> GasAdjuster<E> {
             statistics: GasStatistics::new(config.max_base_fee_samples, current_block, &history),
             eth_client,
             config,
+            l1_ws_url: None,
         })
     }
 
+    /// Makes `GasAdjuster` receive new L1 blocks via the `newHeads` subscription at the provided WebSocket URL.
+    /// Polling is still used as a fallback if the subscription cannot be established or breaks.
+    pub fn with_l1_ws_url(mut self, l1_ws_url: String) -> Self {
+        self.l1_ws_url = Some(l1_ws_url);
+        self
+    }
+
     /// Performs an actualization routine for `GasAdjuster`.
     /// This method is intended to be invoked periodically.
     pub async fn keep_updated(&self) -> Result<(), Error> {
@@ -56,6 +89,12 @@ impl<E: EthInterface> GasAdjuster<E> {
             .as_usize()
             .saturating_sub(1);
 
+        metrics::increment_counter!("server.gas_adjuster.update", "source" => "polling");
+        self.sync_to_block(current_block).await
+    }
+
+    /// Fetches base fees for all blocks after the last processed one up to and including `current_block`.
+    async fn sync_to_block(&self, current_block: usize) -> Result<(), Error> {
         let last_processed_block = self.statistics.last_processed_block();
 
         if current_block > last_processed_block {
@@ -79,6 +118,80 @@ impl<E: EthInterface> GasAdjuster<E> {
         Ok(())
     }
 
+    /// Updates statistics with a block received via the `newHeads` subscription.
+    /// If the block doesn't extend the last processed one (e.g., because of a reorg or missed blocks),
+    /// base fees for the affected blocks are re-fetched.
+    pub(super) async fn process_new_head(&self, header: &L1BlockHeader) -> Result<(), Error> {
+        metrics::increment_counter!("server.gas_adjuster.update", "source" => "subscription");
+        let Some(base_fee) = header.base_fee_per_gas else {
+            vlog::warn!("L1 block #{} has no base fee; ignoring it", header.number);
+            return Ok(());
+        };
+        let block_number = header.number.as_usize();
+        let base_fee = base_fee.as_u64();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("incorrect system time")
+            .as_secs();
+        metrics::gauge!(
+            "server.gas_adjuster.l1_block_age",
+            now.saturating_sub(header.timestamp.as_u64()) as f64
+        );
+
+        let outcome =
+            self.statistics
+                .add_block(block_number, header.hash, header.parent_hash, base_fee);
+        match outcome {
+            AddBlockOutcome::Added => {
+                metrics::gauge!(
+                    "server.gas_adjuster.current_base_fee_per_gas",
+                    base_fee as f64
+                );
+            }
+            AddBlockOutcome::Known => {}
+            AddBlockOutcome::Gap { reorged_blocks } => {
+                if reorged_blocks > 0 {
+                    vlog::info!(
+                        "Detected L1 reorg at block #{block_number}; rolled back {reorged_blocks} base fee samples"
+                    );
+                    metrics::increment_counter!("server.gas_adjuster.l1_reorgs");
+                }
+                self.sync_to_block(block_number).await?;
+                self.statistics
+                    .set_last_block_hash(block_number, header.hash);
+            }
+        }
+        Ok(())
+    }
+
+    /// Follows the `newHeads` subscription until it breaks or the stop signal is received.
+    async fn follow_new_heads(
+        &self,
+        ws_url: &str,
+        mut stop_receiver: Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let client = WsClientBuilder::default().build(ws_url).await?;
+        let mut subscription: Subscription<L1BlockHeader> = client
+            .subscribe("eth_subscribe", rpc_params!["newHeads"], "eth_unsubscribe")
+            .await?;
+        vlog::info!("Subscribed to new L1 blocks");
+
+        loop {
+            let header = tokio::select! {
+                _ = stop_receiver.changed() => return Ok(()),
+                header = tokio::time::timeout(NEW_HEADS_TIMEOUT, subscription.next()) => header,
+            };
+            let header = match header {
+                Ok(Some(header)) => header?,
+                Ok(None) => anyhow::bail!("subscription was closed by the L1 node"),
+                Err(_) => anyhow::bail!("no new L1 blocks received in {NEW_HEADS_TIMEOUT:?}"),
+            };
+            if let Err(err) = self.process_new_head(&header).await {
+                vlog::warn!("Cannot add the base fee to gas statistics: {}", err);
+            }
+        }
+    }
+
     pub async fn run(self: Arc<Self>, stop_receiver: Receiver<bool>) {
         loop {
             if *stop_receiver.borrow() {
@@ -86,6 +199,20 @@ impl<E: EthInterface> GasAdjuster<E> {
                 break;
             }
 
+            if let Some(ws_url) = &self.l1_ws_url {
+                if let Err(err) = self.follow_new_heads(ws_url, stop_receiver.clone()).await {
+                    vlog::warn!(
+                        "L1 block subscription failed, falling back to polling: {}",
+                        err
+                    );
+                }
+                if *stop_receiver.borrow() {
+                    continue;
+                }
+            }
+
+            // Either the subscription is not configured, or it has failed and will be retried
+            // after the polling period.
             if let Err(err) = self.keep_updated().await {
                 vlog::warn!("Cannot add the base fee to gas statistics: {}", err);
             }
@@ -127,6 +254,12 @@ impl<E: EthInterface> L1TxParamsProvider for GasAdjuster<E> {
         let median = self.statistics.median();
 
         metrics::gauge!("server.gas_adjuster.median_base_fee_per_gas", median as f64);
+        if let Some(staleness) = self.statistics.staleness() {
+            metrics::gauge!(
+                "server.gas_adjuster.base_fee_staleness",
+                staleness.as_secs_f64()
+            );
+        }
 
         let new_fee = median as f64 * scale_factor;
         new_fee as u64
@@ -151,6 +284,19 @@ impl<E: EthInterface> L1TxParamsProvider for GasAdjuster<E> {
     }
 }
 
+/// Result of adding an L1 block to [`GasStatisticsInner`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum AddBlockOutcome {
+    /// The block extends the last processed one and was added.
+    Added,
+    /// The block was already processed.
+    Known,
+    /// The block doesn't extend the last processed one; base fees for the blocks after
+    /// the last processed one must be fetched. `reorged_blocks` samples were rolled back
+    /// because they belong to blocks that are no longer canonical.
+    Gap { reorged_blocks: usize },
+}
+
 /// Helper structure responsible for collecting the data about recent transactions,
 /// calculating the median base fee.
 #[derive(Debug, Clone, Default)]
@@ -159,6 +305,10 @@ pub(super) struct GasStatisticsInner {
     median_cached: u64,
     max_samples: usize,
     last_processed_block: usize,
+    /// Hashes of up to `MAX_REORG_DEPTH` last processed blocks; the last hash corresponds to
+    /// `last_processed_block`. Hashes are only known for blocks received via subscription.
+    block_hashes: VecDeque<H256>,
+    last_sample_added_at: Option<Instant>,
 }
 
 impl GasStatisticsInner {
@@ -168,6 +318,8 @@ impl GasStatisticsInner {
             samples: VecDeque::with_capacity(max_samples),
             median_cached: 0,
             last_processed_block: 0,
+            block_hashes: VecDeque::new(),
+            last_sample_added_at: None,
         };
 
         statistics.add_samples(fee_history);
@@ -187,17 +339,103 @@ impl GasStatisticsInner {
     }
 
     fn add_samples(&mut self, fees: &[u64]) {
+        if fees.is_empty() {
+            return;
+        }
         self.samples.extend(fees);
         self.last_processed_block += fees.len();
+        // Hashes of the added blocks are unknown, so we cannot check whether the next block extends them.
+        self.block_hashes.clear();
+        self.last_sample_added_at = Some(Instant::now());
 
         let extra = self.samples.len().saturating_sub(self.max_samples);
         self.samples.drain(..extra);
+        self.update_median();
+    }
 
+    fn update_median(&mut self) {
+        if self.samples.is_empty() {
+            return;
+        }
         let mut samples: Vec<_> = self.samples.iter().cloned().collect();
         let (_, &mut median, _) = samples.select_nth_unstable(self.samples.len() / 2);
 
         self.median_cached = median;
     }
+
+    fn add_block(
+        &mut self,
+        number: usize,
+        hash: H256,
+        parent_hash: H256,
+        base_fee: u64,
+    ) -> AddBlockOutcome {
+        let mut reorged_blocks = 0;
+        if number <= self.last_processed_block {
+            let depth = self.last_processed_block - number;
+            let known_hash = self
+                .block_hashes
+                .len()
+                .checked_sub(depth + 1)
+                .map(|idx| self.block_hashes[idx]);
+            if known_hash == Some(hash) || (known_hash.is_none() && depth > 0) {
+                // Either a duplicate, or an old block we cannot check; in the latter case,
+                // a reorg would be detected once a block not extending the last processed one arrives.
+                return AddBlockOutcome::Known;
+            }
+            reorged_blocks = self.rollback(depth + 1);
+        } else if number == self.last_processed_block + 1 {
+            match self.block_hashes.back() {
+                Some(&last_hash) if last_hash != parent_hash => {
+                    // We don't know where the chains diverge, so we roll back all blocks
+                    // we can check and re-fetch them.
+                    reorged_blocks = self.rollback(self.block_hashes.len());
+                }
+                _ => {
+                    self.samples.push_back(base_fee);
+                    if self.samples.len() > self.max_samples {
+                        self.samples.pop_front();
+                    }
+                    self.update_median();
+                    self.last_processed_block = number;
+                    self.last_sample_added_at = Some(Instant::now());
+                    self.push_block_hash(hash);
+                    return AddBlockOutcome::Added;
+                }
+            }
+        }
+        AddBlockOutcome::Gap { reorged_blocks }
+    }
+
+    fn push_block_hash(&mut self, hash: H256) {
+        self.block_hashes.push_back(hash);
+        if self.block_hashes.len() > MAX_REORG_DEPTH {
+            self.block_hashes.pop_front();
+        }
+    }
+
+    /// Removes samples for the specified number of last processed blocks. Returns the number of removed samples.
+    fn rollback(&mut self, block_count: usize) -> usize {
+        let block_count = block_count.min(self.samples.len());
+        self.samples.truncate(self.samples.len() - block_count);
+        let hash_count = block_count.min(self.block_hashes.len());
+        self.block_hashes
+            .truncate(self.block_hashes.len() - hash_count);
+        self.last_processed_block -= block_count;
+        self.update_median();
+        block_count
+    }
+
+    fn set_last_block_hash(&mut self, number: usize, hash: H256) {
+        if number == self.last_processed_block {
+            self.block_hashes.clear();
+            self.block_hashes.push_back(hash);
+        }
+    }
+
+    fn staleness(&self) -> Option<Duration> {
+        self.last_sample_added_at.map(|instant| instant.elapsed())
+    }
 }
 
 #[derive(Debug, Default)]
@@ -227,4 +465,25 @@ impl GasStatistics {
     pub fn last_processed_block(&self) -> usize {
         self.0.read().unwrap().last_processed_block
     }
+
+    pub fn add_block(
+        &self,
+        number: usize,
+        hash: H256,
+        parent_hash: H256,
+        base_fee: u64,
+    ) -> AddBlockOutcome {
+        self.0
+            .write()
+            .unwrap()
+            .add_block(number, hash, parent_hash, base_fee)
+    }
+
+    pub fn set_last_block_hash(&self, number: usize, hash: H256) {
+        self.0.write().unwrap().set_last_block_hash(number, hash)
+    }
+
+    pub fn staleness(&self) -> Option<Duration> {
+        self.0.read().unwrap().staleness()
+    }
 }
@@ -1,8 +1,32 @@
-use super::{GasAdjuster, GasStatisticsInner};
+use super::{AddBlockOutcome, GasAdjuster, GasStatisticsInner, L1BlockHeader};
 use std::collections::VecDeque;
 use std::sync::Arc;
 use zksync_config::GasAdjusterConfig;
 use zksync_eth_client::clients::mock::MockEthereum;
+use zksync_types::{H256, U256};
+
+fn test_config() -> GasAdjusterConfig {
+    GasAdjusterConfig {
+        default_priority_fee_per_gas: 5,
+        max_base_fee_samples: 5,
+        pricing_formula_parameter_a: 1.5,
+        pricing_formula_parameter_b: 1.0005,
+        internal_l1_pricing_multiplier: 0.8,
+        internal_enforced_l1_gas_price: None,
+        poll_period: 5,
+        max_l1_gas_price: None,
+    }
+}
+
+fn header(number: u64, hash: u8, parent_hash: u8, base_fee: u64) -> L1BlockHeader {
+    L1BlockHeader {
+        number: number.into(),
+        hash: H256::repeat_byte(hash),
+        parent_hash: H256::repeat_byte(parent_hash),
+        timestamp: U256::zero(),
+        base_fee_per_gas: Some(base_fee.into()),
+    }
+}
 
 /// Check that we compute the median correctly
 #[test]
@@ -32,21 +56,9 @@ async fn kept_updated() {
         Arc::new(MockEthereum::default().with_fee_history(vec![0, 4, 6, 8, 7, 5, 5, 8, 10, 9]));
     eth_client.advance_block_number(5);
 
-    let adjuster = GasAdjuster::new(
-        Arc::clone(&eth_client),
-        GasAdjusterConfig {
-            default_priority_fee_per_gas: 5,
-            max_base_fee_samples: 5,
-            pricing_formula_parameter_a: 1.5,
-            pricing_formula_parameter_b: 1.0005,
-            internal_l1_pricing_multiplier: 0.8,
-            internal_enforced_l1_gas_price: None,
-            poll_period: 5,
-            max_l1_gas_price: None,
-        },
-    )
-    .await
-    .unwrap();
+    let adjuster = GasAdjuster::new(Arc::clone(&eth_client), test_config())
+        .await
+        .unwrap();
 
     assert_eq!(adjuster.statistics.0.read().unwrap().samples.len(), 5);
     assert_eq!(adjuster.statistics.0.read().unwrap().median(), 6);
@@ -57,3 +69,71 @@ async fn kept_updated() {
     assert_eq!(adjuster.statistics.0.read().unwrap().samples.len(), 5);
     assert_eq!(adjuster.statistics.0.read().unwrap().median(), 7);
 }
+
+/// Check that blocks received via subscription are added to the statistics and reorgs are rolled back
+#[test]
+fn reorgs_are_rolled_back() {
+    let mut stats = GasStatisticsInner::new(5, 3, &[1, 2, 3]);
+
+    let outcome = stats.add_block(4, H256::repeat_byte(4), H256::zero(), 10);
+    assert_eq!(outcome, AddBlockOutcome::Added);
+    let outcome = stats.add_block(5, H256::repeat_byte(5), H256::repeat_byte(4), 20);
+    assert_eq!(outcome, AddBlockOutcome::Added);
+    assert_eq!(stats.samples, VecDeque::from([1, 2, 3, 10, 20]));
+
+    // Duplicate block
+    let outcome = stats.add_block(5, H256::repeat_byte(5), H256::repeat_byte(4), 20);
+    assert_eq!(outcome, AddBlockOutcome::Known);
+
+    // Block #5 is replaced
+    let outcome = stats.add_block(5, H256::repeat_byte(0x55), H256::repeat_byte(4), 30);
+    assert_eq!(outcome, AddBlockOutcome::Gap { reorged_blocks: 1 });
+    assert_eq!(stats.last_processed_block, 4);
+    assert_eq!(stats.samples, VecDeque::from([1, 2, 3, 10]));
+
+    // Block not extending the last processed one
+    let outcome = stats.add_block(5, H256::repeat_byte(5), H256::repeat_byte(0x44), 30);
+    assert_eq!(outcome, AddBlockOutcome::Gap { reorged_blocks: 1 });
+    assert_eq!(stats.last_processed_block, 3);
+    assert_eq!(stats.samples, VecDeque::from([1, 2, 3]));
+
+    // Missed blocks
+    let outcome = stats.add_block(6, H256::repeat_byte(6), H256::repeat_byte(5), 30);
+    assert_eq!(outcome, AddBlockOutcome::Gap { reorged_blocks: 0 });
+}
+
+/// Check that base fees are re-fetched when new blocks don't extend the processed ones
+#[tokio::test]
+async fn new_heads_are_processed() {
+    let eth_client =
+        Arc::new(MockEthereum::default().with_fee_history(vec![0, 4, 6, 8, 7, 5, 5, 8, 10, 9]));
+    eth_client.advance_block_number(5);
+
+    let adjuster = GasAdjuster::new(Arc::clone(&eth_client), test_config())
+        .await
+        .unwrap();
+    assert_eq!(adjuster.statistics.last_processed_block(), 4);
+
+    adjuster
+        .process_new_head(&header(5, 5, 4, 100))
+        .await
+        .unwrap();
+    assert_eq!(adjuster.statistics.last_processed_block(), 5);
+    assert_eq!(adjuster.statistics.last_added_value(), 100);
+    assert_eq!(adjuster.statistics.median(), 7);
+
+    // Block #6 is missed, and then block #7 is reorged; base fees for these blocks are fetched from the node.
+    adjuster
+        .process_new_head(&header(7, 7, 6, 8))
+        .await
+        .unwrap();
+    adjuster
+        .process_new_head(&header(8, 8, 0x77, 10))
+        .await
+        .unwrap();
+    assert_eq!(adjuster.statistics.last_processed_block(), 8);
+    assert_eq!(
+        adjuster.statistics.0.read().unwrap().samples,
+        VecDeque::from([7, 100, 5, 8, 10])
+    );
+}
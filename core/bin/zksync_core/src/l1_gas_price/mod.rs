@@ -1,10 +1,12 @@
 //! This module determines the fees to pay in txs containing blocks submitted to the L1.
 
+pub use base_fee_cap::{cap_l1_gas_price_by_base_fee, BaseFeeCappedGasPriceProvider};
 pub use gas_adjuster::bounded_gas_adjuster::BoundedGasAdjuster;
 pub use gas_adjuster::GasAdjuster;
 pub use main_node_fetcher::MainNodeGasPriceFetcher;
 pub use singleton::GasAdjusterSingleton;
 
+mod base_fee_cap;
 mod gas_adjuster;
 mod main_node_fetcher;
 pub mod singleton;
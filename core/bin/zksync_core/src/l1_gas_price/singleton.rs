@@ -20,11 +20,14 @@ impl GasAdjusterSingleton {
             .0
             .get_or_init(|| async {
                 let eth_client_config = ETHClientConfig::from_env();
-                let query_client = QueryClient::new(&eth_client_config.web3_url).unwrap();
+                let query_client = QueryClient::from_config(&eth_client_config).unwrap();
                 let gas_adjuster_config = GasAdjusterConfig::from_env();
-                let adjuster = GasAdjuster::new(query_client.clone(), gas_adjuster_config)
+                let mut adjuster = GasAdjuster::new(query_client.clone(), gas_adjuster_config)
                     .await
                     .unwrap();
+                if let Some(ws_url) = eth_client_config.web3_ws_url {
+                    adjuster = adjuster.with_l1_ws_url(ws_url);
+                }
                 Arc::new(adjuster)
             })
             .await;
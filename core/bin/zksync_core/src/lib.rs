@@ -1,5 +1,6 @@
 #![allow(clippy::upper_case_acronyms, clippy::derive_partial_eq_without_eq)]
 
+use anyhow::Context as _;
 use std::{str::FromStr, sync::Arc, time::Instant};
 
 use api_server::execution_sandbox::VmConcurrencyLimiter;
@@ -18,9 +19,10 @@ use zksync_config::configs::{
         self, CircuitBreakerConfig, MempoolConfig, NetworkConfig, OperationsManagerConfig,
         StateKeeperConfig,
     },
+    database::NodeMode,
     house_keeper::HouseKeeperConfig,
-    FriProverConfig, FriWitnessGeneratorConfig, PrometheusConfig, ProverGroupConfig,
-    WitnessGeneratorConfig,
+    ChainRegistryConfig, ChangeStreamConfig, FriProverConfig, FriWitnessGeneratorConfig,
+    PrometheusConfig, ProofDataHandlerConfig, ProverGroupConfig, WitnessGeneratorConfig,
 };
 use zksync_config::{
     ApiConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, FetcherConfig,
@@ -28,43 +30,64 @@ use zksync_config::{
 };
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::{
-    connection::DbVariant, healthcheck::ConnectionPoolHealthCheck, ConnectionPool, StorageProcessor,
+    connection::DbVariant, data_codec::DataCodecs, get_master_database_url,
+    healthcheck::ConnectionPoolHealthCheck, ConnectionPool, StorageProcessor,
 };
 use zksync_eth_client::clients::http::QueryClient;
 use zksync_eth_client::{clients::http::PKSigningClient, BoundEthInterface};
 use zksync_health_check::CheckHealth;
 use zksync_object_store::ObjectStoreFactory;
 use zksync_queued_job_processor::JobProcessor;
-use zksync_state::FactoryDepsCache;
-use zksync_types::{proofs::AggregationRound, L2ChainId, PackedEthSignature, H160};
+use zksync_state::{BytecodeCache, FactoryDepsCache};
+use zksync_types::{
+    proofs::AggregationRound, L2ChainId, MiniblockNumber, PackedEthSignature, H160,
+};
 
+use crate::admin_controls::AdminControls;
 use crate::api_server::healthcheck::HealthCheckHandle;
-use crate::api_server::tx_sender::TxSenderConfig;
+use crate::api_server::tx_sender::{
+    gas_price_policy_from_config, AdmissionPolicyChain, ConfiguredPaymasterPolicy, TxSenderConfig,
+};
 use crate::api_server::web3::api_health_check::ApiHealthCheck;
+use crate::api_server::web3::backend_jsonrpc::call_metrics::CallMetricsConfig;
+use crate::api_server::web3::request_limits::RequestLimitsConfig;
 use crate::api_server::web3::state::InternalApiConfig;
+use crate::api_server::web3::{CallCache, ResponseCache, ResponseCacheConfig};
 use crate::api_server::{
     healthcheck,
     tx_sender::{TxSender, TxSenderBuilder},
 };
 use crate::eth_sender::{Aggregator, EthTxManager};
+use crate::house_keeper::db_maintenance_scheduler::DbMaintenanceScheduler;
+use crate::house_keeper::events_bloom_backfiller::EventsBloomBackfiller;
 use crate::house_keeper::fri_prover_job_retry_manager::FriProverJobRetryManager;
 use crate::house_keeper::fri_prover_queue_monitor::FriProverStatsReporter;
 use crate::house_keeper::fri_scheduler_circuit_queuer::SchedulerCircuitQueuer;
 use crate::house_keeper::fri_witness_generator_jobs_retry_manager::FriWitnessGeneratorJobRetryManager;
 use crate::house_keeper::fri_witness_generator_queue_monitor::FriWitnessGeneratorStatsReporter;
 use crate::house_keeper::gcs_blob_cleaner::GcsBlobCleaner;
+use crate::house_keeper::history_pruner::HistoryPruner;
+use crate::house_keeper::miniblock_partition_manager::MiniblockPartitionManager;
+use crate::house_keeper::partition_archiver::PartitionArchiver;
+use crate::house_keeper::prover_artifacts_cleaner::ProverArtifactsCleaner;
 use crate::house_keeper::{
     blocks_state_reporter::L1BatchMetricsReporter, gpu_prover_queue_monitor::GpuProverQueueMonitor,
-    prover_job_retry_manager::ProverJobRetryManager, prover_queue_monitor::ProverStatsReporter,
+    prover_job_monitor::ProverJobMonitor, prover_job_retry_manager::ProverJobRetryManager,
+    prover_queue_monitor::ProverStatsReporter,
     waiting_to_queued_fri_witness_job_mover::WaitingToQueuedFriWitnessJobMover,
     waiting_to_queued_witness_job_mover::WaitingToQueuedWitnessJobMover,
     witness_generator_queue_monitor::WitnessGeneratorStatsReporter,
 };
-use crate::l1_gas_price::{GasAdjusterSingleton, L1GasPriceProvider};
+use crate::l1_gas_price::{
+    BaseFeeCappedGasPriceProvider, GasAdjusterSingleton, L1GasPriceProvider,
+};
 use crate::metadata_calculator::{
     MetadataCalculator, MetadataCalculatorConfig, MetadataCalculatorModeConfig, TreeHealthCheck,
 };
-use crate::state_keeper::{create_state_keeper, MempoolFetcher, MempoolGuard, MiniblockSealer};
+use crate::state_keeper::{
+    create_state_keeper, MempoolFetcher, MempoolGuard, MiniblockHashesBackfill, MiniblockSealer,
+    PendingReceipts, PendingStorageDiffs,
+};
 use crate::witness_generator::{
     basic_circuits::BasicWitnessGenerator, leaf_aggregation::LeafAggregationWitnessGenerator,
     node_aggregation::NodeAggregationWitnessGenerator, scheduler::SchedulerWitnessGenerator,
@@ -76,9 +99,15 @@ use crate::{
     eth_watch::start_eth_watch,
 };
 
+pub mod admin_controls;
 pub mod api_server;
+pub mod batch_verifier;
 pub mod block_reverter;
+pub mod bulk_tx_ingestion;
+pub mod chain_contracts;
+pub mod change_stream;
 pub mod consistency_checker;
+pub mod data_exporter;
 pub mod data_fetchers;
 pub mod eth_sender;
 pub mod eth_watch;
@@ -86,13 +115,18 @@ pub mod fee_ticker;
 pub mod gas_tracker;
 pub mod genesis;
 pub mod house_keeper;
+pub mod l1_batch_status_bus;
 pub mod l1_gas_price;
 pub mod metadata_calculator;
+pub mod proof_data_handler;
 pub mod reorg_detector;
+pub mod startup_checks;
 pub mod state_keeper;
 pub mod sync_layer;
 pub mod witness_generator;
 
+mod hyperchains;
+
 /// Inserts the initial information about zkSync tokens into the database.
 pub async fn genesis_init(eth_sender: &ETHSenderConfig, network_config: &NetworkConfig) {
     let mut storage = StorageProcessor::establish_connection(true).await;
@@ -164,6 +198,10 @@ pub enum Component {
     WitnessGenerator(Option<usize>, AggregationRound),
     // Component for housekeeping task such as cleaning blobs from GCS, reporting metrics etc.
     Housekeeper,
+    // HTTP server for TEE provers to fetch batch inputs and submit attested execution results.
+    ProofDataHandler,
+    // Change data capture stream publishing sealed miniblocks to a message broker.
+    ChangeStream,
 }
 
 #[derive(Debug)]
@@ -230,6 +268,8 @@ impl FromStr for Components {
             "eth_watcher" => Ok(Components(vec![Component::EthWatcher])),
             "eth_tx_aggregator" => Ok(Components(vec![Component::EthTxAggregator])),
             "eth_tx_manager" => Ok(Components(vec![Component::EthTxManager])),
+            "proof_data_handler" => Ok(Components(vec![Component::ProofDataHandler])),
+            "change_stream" => Ok(Components(vec![Component::ChangeStream])),
             other => Err(format!("{} is not a valid component name", other)),
         }
     }
@@ -245,6 +285,7 @@ pub async fn initialize_components(
     HealthCheckHandle,
 )> {
     vlog::info!("Starting the components: {components:?}");
+    DataCodecs::from_config(&DBConfig::from_env()).install();
     let connection_pool = ConnectionPool::new(None, DbVariant::Master).await;
     let prover_connection_pool = ConnectionPool::new(None, DbVariant::Prover).await;
     let replica_connection_pool = ConnectionPool::new(None, DbVariant::Replica).await;
@@ -266,7 +307,20 @@ pub async fn initialize_components(
         panic!("Circuit breaker triggered: {}", err);
     });
 
-    let query_client = QueryClient::new(&eth_client_config.web3_url).unwrap();
+    let query_client = QueryClient::from_config(&eth_client_config).unwrap();
+    startup_checks::run_startup_checks(
+        &components,
+        &connection_pool,
+        &query_client,
+        &eth_client_config,
+        &contracts_config,
+        &DBConfig::from_env(),
+    )
+    .await
+    .context("Startup checks failed")?;
+    let chain_contracts = ChainContracts::resolve(&contracts_config, &query_client)
+        .await
+        .context("Failed to resolve chain contracts from L1")?;
     let mut gas_adjuster = GasAdjusterSingleton::new();
 
     let (stop_sender, stop_receiver) = watch::channel(false);
@@ -290,6 +344,43 @@ pub async fn initialize_components(
         "factory_deps_cache",
         Web3JsonRpcConfig::from_env().factory_deps_cache_size_mb(),
     );
+    // Decoded bytecodes are shared between the state keeper and API servers.
+    let bytecode_cache_size_mb = StateKeeperConfig::from_env().bytecode_cache_size_mb();
+    let bytecode_cache = (bytecode_cache_size_mb > 0)
+        .then(|| BytecodeCache::new("bytecode_cache", bytecode_cache_size_mb));
+
+    let mempool_config = MempoolConfig::from_env();
+    // The mempool is shared between the state keeper and the API servers (for `txpool` namespace),
+    // so it's created in advance.
+    let mempool = if components.contains(&Component::StateKeeper) {
+        let next_priority_id = connection_pool
+            .access_storage()
+            .await
+            .transactions_dal()
+            .next_priority_id()
+            .await;
+        Some(MempoolGuard::new(
+            next_priority_id,
+            mempool_config.capacity,
+            mempool_config.max_nonce_lookahead,
+        ))
+    } else {
+        None
+    };
+    // Operator controls exposed via the `admin` API namespace.
+    let admin_controls = AdminControls::default();
+    // L1 batch lifecycle transitions published by components running in this process.
+    let l1_batch_status_bus = L1BatchStatusBus::new();
+    // Notifications about sealed miniblocks, used by the API servers to invalidate cached account state
+    // and by the change stream publisher if they run together with the state keeper.
+    let (sealed_miniblocks_sender, sealed_miniblocks) = watch::channel(MiniblockNumber(0));
+    let sealed_miniblocks = components
+        .contains(&Component::StateKeeper)
+        .then_some(sealed_miniblocks);
+    // Receipts of executed transactions that are not persisted yet, shared by the state keeper and the API servers.
+    let pending_receipts = sealed_miniblocks.clone().map(PendingReceipts::new);
+    // Storage diffs of miniblocks that are not persisted yet, used to estimate gas against the pending block.
+    let pending_storage_diffs = sealed_miniblocks.clone().map(PendingStorageDiffs::new);
 
     if components.contains(&Component::WsApi)
         || components.contains(&Component::HttpApi)
@@ -298,55 +389,48 @@ pub async fn initialize_components(
         let api_config = ApiConfig::from_env();
         let state_keeper_config = StateKeeperConfig::from_env();
         let network_config = NetworkConfig::from_env();
+        let eth_sender_config = ETHSenderConfig::from_env();
         let tx_sender_config = TxSenderConfig::new(&state_keeper_config, &api_config.web3_json_rpc);
         let internal_api_config = InternalApiConfig::new(
             &network_config,
             &api_config.web3_json_rpc,
             &contracts_config,
+            &eth_sender_config.sender,
+            &chain_contracts,
         );
-        if components.contains(&Component::HttpApi) {
-            let started_at = Instant::now();
-            vlog::info!("initializing HTTP API");
-            let bounded_gas_adjuster = gas_adjuster.get_or_init_bounded().await;
-            let (futures, health_check) = run_http_api(
-                &tx_sender_config,
-                &state_keeper_config,
-                &internal_api_config,
-                &api_config,
-                connection_pool.clone(),
-                replica_connection_pool.clone(),
-                stop_receiver.clone(),
-                bounded_gas_adjuster.clone(),
-                state_keeper_config.save_call_traces,
-                factory_deps_cache.clone(),
-            )
-            .await;
-            task_futures.extend(futures);
-            healthchecks.push(Box::new(health_check));
-            vlog::info!("initialized HTTP API in {:?}", started_at.elapsed());
-            metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "http_api");
-        }
-
-        if components.contains(&Component::WsApi) {
+        let with_http_api = components.contains(&Component::HttpApi);
+        let with_ws_api = components.contains(&Component::WsApi);
+        if with_http_api || with_ws_api {
             let started_at = Instant::now();
-            vlog::info!("initializing WS API");
+            vlog::info!("initializing web3 API (HTTP: {with_http_api}, WS: {with_ws_api})");
             let bounded_gas_adjuster = gas_adjuster.get_or_init_bounded().await;
-            let (futures, health_check) = run_ws_api(
+            let (futures, api_health_checks) = run_web3_api(
                 &tx_sender_config,
                 &state_keeper_config,
                 &internal_api_config,
                 &api_config,
+                with_http_api,
+                with_ws_api,
                 bounded_gas_adjuster.clone(),
                 connection_pool.clone(),
                 replica_connection_pool.clone(),
                 stop_receiver.clone(),
                 factory_deps_cache.clone(),
+                bytecode_cache.clone(),
+                mempool.clone(),
+                admin_controls.clone(),
+                sealed_miniblocks.clone(),
+                pending_receipts.clone(),
+                pending_storage_diffs.clone(),
+                l1_batch_status_bus.clone(),
             )
             .await;
             task_futures.extend(futures);
-            healthchecks.push(Box::new(health_check));
-            vlog::info!("initialized WS API in {:?}", started_at.elapsed());
-            metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "ws_api");
+            for health_check in api_health_checks {
+                healthchecks.push(Box::new(health_check));
+            }
+            vlog::info!("initialized web3 API in {:?}", started_at.elapsed());
+            metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "web3_api");
         }
 
         if components.contains(&Component::ExplorerApi) {
@@ -354,8 +438,8 @@ pub async fn initialize_components(
             vlog::info!("initializing explorer REST API");
             task_futures.push(explorer::start_server_thread_detached(
                 api_config.explorer.clone(),
-                contracts_config.l2_erc20_bridge_addr,
-                state_keeper_config.fee_account_addr,
+                chain_contracts.l2_erc20_bridge,
+                state_keeper_config.fee_collector_addr(),
                 connection_pool.clone(),
                 replica_connection_pool.clone(),
                 stop_receiver.clone(),
@@ -374,11 +458,21 @@ pub async fn initialize_components(
         let bounded_gas_adjuster = gas_adjuster.get_or_init_bounded().await;
         add_state_keeper_to_task_futures(
             &mut task_futures,
-            &contracts_config,
+            &get_master_database_url(),
+            L2ChainId(NetworkConfig::from_env().zksync_network_id),
+            chain_contracts,
             StateKeeperConfig::from_env(),
             &DBConfig::from_env(),
-            &MempoolConfig::from_env(),
+            &mempool_config,
+            mempool.expect("mempool is initialized for state keeper"),
             bounded_gas_adjuster,
+            bytecode_cache.clone(),
+            &ObjectStoreFactory::from_env(),
+            admin_controls.clone(),
+            sealed_miniblocks_sender,
+            pending_receipts,
+            pending_storage_diffs,
+            l1_batch_status_bus.clone(),
             stop_receiver.clone(),
         )
         .await;
@@ -386,6 +480,48 @@ pub async fn initialize_components(
         metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "state_keeper");
     }
 
+    let hyperchains = ChainRegistryConfig::from_env().load_chains();
+    let runs_chain_components = components.contains(&Component::StateKeeper)
+        || components.contains(&Component::HttpApi)
+        || components.contains(&Component::WsApi);
+    if !hyperchains.is_empty() && runs_chain_components {
+        let shared = hyperchains::SharedChainResources {
+            chain_contracts,
+            gas_adjuster: gas_adjuster.get_or_init_bounded().await,
+            factory_deps_cache: factory_deps_cache.clone(),
+            bytecode_cache: bytecode_cache.clone(),
+        };
+        hyperchains::add_hyperchains_to_task_futures(
+            &mut task_futures,
+            &mut healthchecks,
+            &hyperchains,
+            &components,
+            &shared,
+            &stop_receiver,
+        )
+        .await?;
+    }
+
+    if components.contains(&Component::ChangeStream) {
+        let started_at = Instant::now();
+        vlog::info!("initializing change stream publisher");
+        let config = ChangeStreamConfig::from_env();
+        let sink = NatsSink::connect(&config.nats_url, config.subject.clone())
+            .await
+            .context("Failed to connect to the change stream broker")?;
+        let pool = ConnectionPool::new(Some(1), DbVariant::Master).await;
+        let mut publisher = ChangeStreamPublisher::new(config, pool, Box::new(sink));
+        if let Some(sealed_miniblocks) = sealed_miniblocks.clone() {
+            publisher = publisher.with_sealed_miniblocks(sealed_miniblocks);
+        }
+        task_futures.push(tokio::spawn(publisher.run(stop_receiver.clone())));
+        vlog::info!(
+            "initialized change stream publisher in {:?}",
+            started_at.elapsed()
+        );
+        metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "change_stream");
+    }
+
     if components.contains(&Component::EthWatcher) {
         let started_at = Instant::now();
         vlog::info!("initializing ETH-Watcher");
@@ -395,6 +531,7 @@ pub async fn initialize_components(
                 eth_watch_pool,
                 query_client.clone(),
                 contracts_config.diamond_proxy_addr,
+                chain_contracts.l2_erc20_bridge,
                 stop_receiver.clone(),
             )
             .await,
@@ -415,7 +552,7 @@ pub async fn initialize_components(
         let nonce = eth_client.pending_nonce("eth_sender").await.unwrap();
         let eth_tx_aggregator_actor = EthTxAggregator::new(
             eth_sender.sender.clone(),
-            Aggregator::new(eth_sender.sender.clone()),
+            Aggregator::new(eth_sender.sender.clone()).with_admin_controls(admin_controls.clone()),
             contracts_config.validator_timelock_addr,
             nonce.as_u64(),
         );
@@ -440,7 +577,8 @@ pub async fn initialize_components(
             eth_sender.sender,
             gas_adjuster.get_or_init().await,
             eth_client,
-        );
+        )
+        .with_l1_batch_status_bus(l1_batch_status_bus.clone());
         task_futures.extend([tokio::spawn(
             eth_tx_manager_actor.run(eth_sender_storage, stop_receiver.clone()),
         )]);
@@ -469,6 +607,7 @@ pub async fn initialize_components(
         &mut healthchecks,
         &components,
         &store_factory,
+        &l1_batch_status_bus,
         &stop_receiver,
     )
     .await;
@@ -483,7 +622,24 @@ pub async fn initialize_components(
     .await;
 
     if components.contains(&Component::Housekeeper) {
-        add_house_keeper_to_task_futures(&mut task_futures, &store_factory).await;
+        add_house_keeper_to_task_futures(&mut task_futures, &store_factory, &l1_batch_status_bus)
+            .await;
+    }
+
+    if components.contains(&Component::ProofDataHandler) {
+        let started_at = Instant::now();
+        vlog::info!("initializing proof data handler");
+        task_futures.push(proof_data_handler::start_server_thread_detached(
+            ProofDataHandlerConfig::from_env(),
+            store_factory.create_store().await,
+            connection_pool.clone(),
+            stop_receiver.clone(),
+        ));
+        vlog::info!(
+            "initialized proof data handler in {:?}",
+            started_at.elapsed()
+        );
+        metrics::gauge!("server.init.latency", started_at.elapsed(), "stage" => "proof_data_handler");
     }
 
     // Run healthcheck server for all components.
@@ -491,6 +647,13 @@ pub async fn initialize_components(
         replica_connection_pool,
     )));
 
+    let healthchecks: Vec<Arc<dyn CheckHealth>> = healthchecks.into_iter().map(Arc::from).collect();
+    admin_controls.set_health_checks(healthchecks.clone());
+    let healthchecks = healthchecks
+        .into_iter()
+        .map(|check| Box::new(check) as Box<dyn CheckHealth>)
+        .collect();
+
     let healtcheck_api_config = HealthCheckConfig::from_env();
     let health_check_handle =
         healthcheck::start_server_thread_detached(healtcheck_api_config.bind_addr(), healthchecks);
@@ -501,48 +664,163 @@ pub async fn initialize_components(
     Ok((task_futures, stop_sender, cb_receiver, health_check_handle))
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn add_state_keeper_to_task_futures<E: L1GasPriceProvider + Send + Sync + 'static>(
     task_futures: &mut Vec<JoinHandle<()>>,
-    contracts_config: &ContractsConfig,
+    master_database_url: &str,
+    l2_chain_id: L2ChainId,
+    chain_contracts: ChainContracts,
     state_keeper_config: StateKeeperConfig,
     db_config: &DBConfig,
     mempool_config: &MempoolConfig,
+    mempool: MempoolGuard,
     gas_adjuster: Arc<E>,
+    bytecode_cache: Option<BytecodeCache>,
+    object_store_factory: &ObjectStoreFactory,
+    admin_controls: AdminControls,
+    sealed_miniblocks_sender: watch::Sender<MiniblockNumber>,
+    pending_receipts: Option<PendingReceipts>,
+    pending_storage_diffs: Option<PendingStorageDiffs>,
+    l1_batch_status_bus: L1BatchStatusBus,
     stop_receiver: watch::Receiver<bool>,
 ) {
     let fair_l2_gas_price = state_keeper_config.fair_l2_gas_price;
-    let state_keeper_pool = ConnectionPool::new(Some(1), DbVariant::Master).await;
-    let next_priority_id = state_keeper_pool
-        .access_storage()
-        .await
-        .transactions_dal()
-        .next_priority_id()
-        .await;
-    let mempool = MempoolGuard::new(next_priority_id, mempool_config.capacity);
+    let state_keeper_pool = ConnectionPool::with_url(master_database_url, Some(1)).await;
+    let backfill_pool = ConnectionPool::with_url(master_database_url, Some(1)).await;
+    let miniblock_hashes_backfill = MiniblockHashesBackfill::new(backfill_pool);
+    task_futures.push(tokio::spawn(
+        miniblock_hashes_backfill.run(stop_receiver.clone()),
+    ));
+    // The mempool fetcher and bulk ingestion must accept transactions with fees quoted by the API,
+    // so they use the same capped L1 gas price.
+    let capped_gas_adjuster = Arc::new(BaseFeeCappedGasPriceProvider::new(
+        &state_keeper_config,
+        gas_adjuster.clone(),
+    ));
+    let base_fee_pool = ConnectionPool::with_url(master_database_url, Some(1)).await;
+    task_futures.push(tokio::spawn(
+        capped_gas_adjuster
+            .clone()
+            .run(base_fee_pool, stop_receiver.clone()),
+    ));
+    let mut mempool_fetcher =
+        MempoolFetcher::new(mempool.clone(), capped_gas_adjuster.clone(), mempool_config);
+    if let Some(source_url) = &mempool_config.snapshot_source_url {
+        // Failing to import the snapshot must not prevent the standby sequencer from taking over.
+        let auth_token = mempool_config.bulk_ingestion_auth_token();
+        match bulk_tx_ingestion::import_mempool_snapshot(source_url, auth_token, &state_keeper_pool)
+            .await
+        {
+            Ok(header) => {
+                if let Some(filter) = header.filter {
+                    mempool_fetcher =
+                        mempool_fetcher.with_initial_filter_gas_price(filter.l1_gas_price);
+                }
+            }
+            Err(err) => {
+                vlog::warn!("Failed importing mempool snapshot from {source_url}: {err:#}");
+            }
+        }
+    }
 
-    let miniblock_sealer_pool = ConnectionPool::new(Some(1), DbVariant::Master).await;
+    let miniblock_seal_concurrency = state_keeper_config.miniblock_seal_concurrency();
+    let miniblock_sealer_pool =
+        ConnectionPool::with_url(master_database_url, Some(miniblock_seal_concurrency as u32))
+            .await;
     let (miniblock_sealer, miniblock_sealer_handle) = MiniblockSealer::new(
         miniblock_sealer_pool,
         state_keeper_config.miniblock_seal_queue_capacity,
     );
+    let mut miniblock_sealer = miniblock_sealer
+        .with_seal_notifier(sealed_miniblocks_sender)
+        .with_removal_notifier(mempool_fetcher.removal_notifier());
+    if miniblock_seal_concurrency > 1 {
+        miniblock_sealer = miniblock_sealer.with_parallel_seal();
+    }
+    if let Some(pending_receipts) = &pending_receipts {
+        miniblock_sealer = miniblock_sealer.with_pending_receipts(pending_receipts.clone());
+    }
     task_futures.push(tokio::spawn(miniblock_sealer.run()));
 
-    let state_keeper = create_state_keeper(
-        contracts_config,
+    // Bulk ingestion is configured for the main chain only; it validates transactions using the same `TxSender`
+    // setup as the Web3 API.
+    let bulk_ingestion_tx_sender = if mempool_config.bulk_ingestion_port.is_some() {
+        let web3_config = ApiConfig::from_env().web3_json_rpc;
+        let tx_sender_config = TxSenderConfig::new(&state_keeper_config, &web3_config);
+        let validation_pool = ConnectionPool::with_url(
+            master_database_url,
+            Some(mempool_config.bulk_ingestion_max_concurrent_batches() as u32),
+        )
+        .await;
+        let factory_deps_cache = FactoryDepsCache::new(
+            "bulk_ingestion_factory_deps_cache",
+            web3_config.factory_deps_cache_size_mb(),
+        );
+        let tx_sender = build_tx_sender(
+            &tx_sender_config,
+            &web3_config,
+            &state_keeper_config,
+            validation_pool.clone(),
+            validation_pool,
+            capped_gas_adjuster,
+            factory_deps_cache,
+            bytecode_cache.clone(),
+            admin_controls.clone(),
+            None,
+            None,
+        )
+        .await;
+        Some((tx_sender, web3_config.max_tx_size))
+    } else {
+        None
+    };
+
+    let mut state_keeper = create_state_keeper(
+        chain_contracts,
         state_keeper_config,
         db_config,
         mempool_config,
         state_keeper_pool,
         mempool.clone(),
-        gas_adjuster.clone(),
+        gas_adjuster,
         miniblock_sealer_handle,
+        mempool_fetcher.removal_notifier(),
+        bytecode_cache,
+        object_store_factory,
+        admin_controls,
         stop_receiver.clone(),
     )
-    .await;
+    .await
+    .with_l1_batch_status_bus(l1_batch_status_bus);
+    if let Some(pending_receipts) = pending_receipts {
+        state_keeper = state_keeper.with_pending_receipts(pending_receipts);
+    }
+    if let Some(pending_storage_diffs) = pending_storage_diffs {
+        state_keeper = state_keeper.with_pending_storage_diffs(pending_storage_diffs);
+    }
     task_futures.push(tokio::spawn(state_keeper.run()));
 
-    let mempool_fetcher_pool = ConnectionPool::new(Some(1), DbVariant::Master).await;
-    let mempool_fetcher = MempoolFetcher::new(mempool, gas_adjuster, mempool_config);
+    if let (Some(port), Some((tx_sender, max_tx_size))) =
+        (mempool_config.bulk_ingestion_port, bulk_ingestion_tx_sender)
+    {
+        let bulk_ingestion_pool = ConnectionPool::with_url(
+            master_database_url,
+            Some(mempool_config.bulk_ingestion_max_concurrent_batches() as u32),
+        )
+        .await;
+        task_futures.push(bulk_tx_ingestion::start_server_thread_detached(
+            mempool_config,
+            port,
+            tx_sender,
+            mempool,
+            bulk_ingestion_pool,
+            l2_chain_id,
+            max_tx_size,
+            stop_receiver.clone(),
+        ));
+    }
+
+    let mempool_fetcher_pool = ConnectionPool::with_url(master_database_url, Some(1)).await;
     let mempool_fetcher_handle = tokio::spawn(mempool_fetcher.run(
         mempool_fetcher_pool,
         mempool_config.remove_stuck_txs,
@@ -558,6 +836,7 @@ async fn add_trees_to_task_futures(
     healthchecks: &mut Vec<Box<dyn CheckHealth>>,
     components: &[Component],
     store_factory: &ObjectStoreFactory,
+    l1_batch_status_bus: &L1BatchStatusBus,
     stop_receiver: &watch::Receiver<bool>,
 ) {
     let db_config = DBConfig::from_env();
@@ -583,8 +862,14 @@ async fn add_trees_to_task_futures(
             } else {
                 MetadataCalculatorModeConfig::Lightweight
             };
-            let (future, tree_health_check) =
-                run_tree(&db_config, &operation_config, mode, stop_receiver.clone()).await;
+            let (future, tree_health_check) = run_tree(
+                &db_config,
+                &operation_config,
+                mode,
+                l1_batch_status_bus.clone(),
+                stop_receiver.clone(),
+            )
+            .await;
             task_futures.push(future);
             healthchecks.push(Box::new(tree_health_check));
         }
@@ -595,6 +880,7 @@ async fn run_tree(
     config: &DBConfig,
     operation_manager: &OperationsManagerConfig,
     mode: MetadataCalculatorModeConfig<'_>,
+    l1_batch_status_bus: L1BatchStatusBus,
     stop_receiver: watch::Receiver<bool>,
 ) -> (JoinHandle<()>, TreeHealthCheck) {
     let started_at = Instant::now();
@@ -606,7 +892,9 @@ async fn run_tree(
     vlog::info!("Initializing Merkle tree in {mode_str} mode");
 
     let config = MetadataCalculatorConfig::for_main_node(config, operation_manager, mode);
-    let metadata_calculator = MetadataCalculator::new(&config).await;
+    let metadata_calculator = MetadataCalculator::new(&config)
+        .await
+        .with_l1_batch_status_bus(l1_batch_status_bus);
     let tree_health_check = metadata_calculator.tree_health_check();
     let tree_tag = metadata_calculator.tree_tag();
     let pool = ConnectionPool::new(Some(1), DbVariant::Master).await;
@@ -713,13 +1001,15 @@ async fn add_witness_generator_to_task_futures(
 async fn add_house_keeper_to_task_futures(
     task_futures: &mut Vec<JoinHandle<()>>,
     store_factory: &ObjectStoreFactory,
+    l1_batch_status_bus: &L1BatchStatusBus,
 ) {
     let house_keeper_config = HouseKeeperConfig::from_env();
     let connection_pool = ConnectionPool::new(Some(1), DbVariant::Replica).await;
     let l1_batch_metrics_reporter = L1BatchMetricsReporter::new(
         house_keeper_config.l1_batch_metrics_reporting_interval_ms,
         connection_pool,
-    );
+    )
+    .with_l1_batch_status(l1_batch_status_bus.subscribe());
 
     let prover_connection_pool = ConnectionPool::new(
         Some(house_keeper_config.prover_db_pool_size),
@@ -765,6 +1055,15 @@ async fn add_house_keeper_to_task_futures(
     task_futures.push(tokio::spawn(waiting_to_queued_witness_job_mover.run()));
     task_futures.push(tokio::spawn(prover_job_retry_manager.run()));
 
+    let witness_generator_config = WitnessGeneratorConfig::from_env();
+    let prover_job_monitor = ProverJobMonitor::new(
+        witness_generator_config.max_attempts,
+        witness_generator_config.witness_generation_timeout(),
+        house_keeper_config.prover_job_monitoring_interval_ms,
+        prover_connection_pool.clone(),
+    );
+    task_futures.push(tokio::spawn(prover_job_monitor.run()));
+
     // All FRI Prover related components are configured below.
     let fri_prover_config = FriProverConfig::from_env();
     let fri_prover_job_retry_manager = FriProverJobRetryManager::new(
@@ -807,8 +1106,56 @@ async fn add_house_keeper_to_task_futures(
         prover_connection_pool.clone(),
     );
     task_futures.push(tokio::spawn(fri_prover_stats_reporter.run()));
+
+    let prover_artifacts_cleaner = ProverArtifactsCleaner::new(
+        store_factory,
+        house_keeper_config.prover_artifacts_retention(),
+        house_keeper_config.prover_artifacts_cleaning_interval_ms,
+        ConnectionPool::new(Some(1), DbVariant::Replica).await,
+        prover_connection_pool.clone(),
+    )
+    .await;
+    task_futures.push(tokio::spawn(prover_artifacts_cleaner.run()));
+
+    // Partitions of `events` and `storage_logs` are managed in the main DB.
+    let master_connection_pool = ConnectionPool::new(Some(1), DbVariant::Master).await;
+    let miniblock_partition_manager =
+        MiniblockPartitionManager::new(&house_keeper_config, master_connection_pool.clone());
+    task_futures.push(tokio::spawn(miniblock_partition_manager.run()));
+
+    let partition_archiver = PartitionArchiver::new(
+        store_factory,
+        master_connection_pool,
+        house_keeper_config.partition_management_interval_ms,
+    )
+    .await;
+    task_futures.push(tokio::spawn(partition_archiver.run()));
+
+    let db_maintenance_scheduler = DbMaintenanceScheduler::new(
+        &house_keeper_config,
+        ConnectionPool::new(Some(1), DbVariant::Master).await,
+    );
+    task_futures.push(tokio::spawn(db_maintenance_scheduler.run()));
+
+    let events_bloom_backfiller = EventsBloomBackfiller::new(
+        house_keeper_config.events_bloom_backfill_interval_ms,
+        ConnectionPool::new(Some(1), DbVariant::Master).await,
+    );
+    task_futures.push(tokio::spawn(events_bloom_backfiller.run()));
+
+    let db_config = DBConfig::from_env();
+    if db_config.node_mode() == NodeMode::Full {
+        let history_pruner = HistoryPruner::new(
+            db_config.history_retention_l1_batches(),
+            house_keeper_config.history_pruning_interval_ms,
+            ConnectionPool::new(Some(1), DbVariant::Master).await,
+        )
+        .with_l1_batch_status(l1_batch_status_bus.subscribe());
+        task_futures.push(tokio::spawn(history_pruner.run()));
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn build_tx_sender<G: L1GasPriceProvider>(
     tx_sender_config: &TxSenderConfig,
     web3_json_config: &Web3JsonRpcConfig,
@@ -817,16 +1164,41 @@ async fn build_tx_sender<G: L1GasPriceProvider>(
     master_pool: ConnectionPool,
     l1_gas_price_provider: Arc<G>,
     factory_deps_cache: FactoryDepsCache,
+    bytecode_cache: Option<BytecodeCache>,
+    admin_controls: AdminControls,
+    sealed_miniblocks: Option<watch::Receiver<MiniblockNumber>>,
+    pending_storage_diffs: Option<PendingStorageDiffs>,
 ) -> TxSender<G> {
     let mut tx_sender_builder = TxSenderBuilder::new(tx_sender_config.clone(), replica_pool)
         .with_main_connection_pool(master_pool)
-        .with_state_keeper_config(state_keeper_config.clone());
+        .with_state_keeper_config(state_keeper_config.clone())
+        .with_admin_controls(admin_controls)
+        .with_admission_policies_config(AdmissionPolicyChain::built_in_config(web3_json_config));
 
     // Add rate limiter if enabled.
     if let Some(transactions_per_sec_limit) = web3_json_config.transactions_per_sec_limit {
         tx_sender_builder = tx_sender_builder.with_rate_limiter(transactions_per_sec_limit);
     };
 
+    let paymaster_policy = ConfiguredPaymasterPolicy::from_config(web3_json_config);
+    if !paymaster_policy.is_permissive() {
+        tx_sender_builder = tx_sender_builder.with_paymaster_policy(Arc::new(paymaster_policy));
+    }
+    tx_sender_builder =
+        tx_sender_builder.with_gas_price_policy(gas_price_policy_from_config(web3_json_config));
+    if let Some(bytecode_cache) = bytecode_cache {
+        tx_sender_builder = tx_sender_builder.with_bytecode_cache(bytecode_cache);
+    }
+    if let Some(sealed_miniblocks) = sealed_miniblocks {
+        tx_sender_builder = tx_sender_builder.with_account_state_cache(
+            web3_json_config.account_state_cache_size(),
+            sealed_miniblocks,
+        );
+    }
+    if let Some(pending_storage_diffs) = pending_storage_diffs {
+        tx_sender_builder = tx_sender_builder.with_pending_storage_diffs(pending_storage_diffs);
+    }
+
     let vm_concurrency_limiter = VmConcurrencyLimiter::new(web3_json_config.vm_concurrency_limit);
 
     tx_sender_builder
@@ -839,83 +1211,176 @@ async fn build_tx_sender<G: L1GasPriceProvider>(
         .await
 }
 
+/// Runs web3 API servers for the enabled transports. All servers share a single [`TxSender`]
+/// (and thus VM concurrency limits and caches) and a response cache. The IPC server is run alongside
+/// the HTTP and / or WebSocket servers if `ipc_path` is configured.
 #[allow(clippy::too_many_arguments)]
-async fn run_http_api<G: L1GasPriceProvider + Send + Sync + 'static>(
+async fn run_web3_api<G: L1GasPriceProvider + Send + Sync + 'static>(
     tx_sender_config: &TxSenderConfig,
     state_keeper_config: &StateKeeperConfig,
     internal_api: &InternalApiConfig,
     api_config: &ApiConfig,
+    with_http_api: bool,
+    with_ws_api: bool,
+    gas_adjuster: Arc<G>,
     master_connection_pool: ConnectionPool,
     replica_connection_pool: ConnectionPool,
     stop_receiver: watch::Receiver<bool>,
-    gas_adjuster: Arc<G>,
-    with_debug_namespace: bool,
     factory_deps_cache: FactoryDepsCache,
-) -> (Vec<JoinHandle<()>>, ApiHealthCheck) {
+    bytecode_cache: Option<BytecodeCache>,
+    mempool: Option<MempoolGuard>,
+    admin_controls: AdminControls,
+    sealed_miniblocks: Option<watch::Receiver<MiniblockNumber>>,
+    pending_receipts: Option<PendingReceipts>,
+    pending_storage_diffs: Option<PendingStorageDiffs>,
+    l1_batch_status_bus: L1BatchStatusBus,
+) -> (Vec<JoinHandle<()>>, Vec<ApiHealthCheck>) {
+    let web3_config = &api_config.web3_json_rpc;
+    // Fees are quoted using the L1 gas price capped in the same way as by the state keeper.
+    let gas_adjuster = Arc::new(BaseFeeCappedGasPriceProvider::new(
+        state_keeper_config,
+        gas_adjuster,
+    ));
     let tx_sender = build_tx_sender(
         tx_sender_config,
-        &api_config.web3_json_rpc,
+        web3_config,
         state_keeper_config,
         replica_connection_pool.clone(),
         master_connection_pool.clone(),
-        gas_adjuster,
+        gas_adjuster.clone(),
         factory_deps_cache.clone(),
+        bytecode_cache,
+        admin_controls.clone(),
+        sealed_miniblocks,
+        pending_storage_diffs,
     )
     .await;
 
-    let mut builder =
-        web3::ApiBuilder::jsonrpsee_backend(internal_api.clone(), replica_connection_pool)
-            .http(api_config.web3_json_rpc.http_port)
-            .with_filter_limit(api_config.web3_json_rpc.filters_limit())
-            .with_threads(api_config.web3_json_rpc.http_server_threads())
-            .with_tx_sender(tx_sender);
-
-    if with_debug_namespace {
-        builder = builder.enable_debug_namespace(
-            BaseSystemContractsHashes {
-                bootloader: tx_sender_config.bootloader,
-                default_aa: tx_sender_config.default_aa,
-            },
-            tx_sender_config.fair_l2_gas_price,
-            api_config.web3_json_rpc.vm_execution_cache_misses_limit,
+    let mut tasks = vec![tokio::spawn(
+        gas_adjuster.run(replica_connection_pool.clone(), stop_receiver.clone()),
+    )];
+    let mut health_checks = vec![];
+    let response_cache_config = ResponseCacheConfig::from_config(web3_config);
+    let response_cache = (response_cache_config.capacity > 0).then(|| {
+        let response_cache = Arc::new(ResponseCache::new(response_cache_config));
+        tasks.push(tokio::spawn(response_cache.clone().run_watermark_updates(
+            replica_connection_pool.clone(),
+            stop_receiver.clone(),
+        )));
+        response_cache
+    });
+    let call_cache = (web3_config.call_cache_size() > 0).then(|| {
+        Arc::new(CallCache::new(
+            web3_config.call_cache_size(),
+            web3_config.call_cache_ttl(),
+        ))
+    });
+
+    if let Some(admin_addr) = web3_config.admin_api_bind_addr() {
+        let token = web3_config
+            .admin_api_token
+            .clone()
+            .expect("`admin_api_token` is validated when loading config");
+        let (admin_task, health_check) = web3::run_admin_server(
+            admin_addr,
+            token,
+            admin_controls.clone(),
+            mempool.clone(),
+            &tx_sender,
+            stop_receiver.clone(),
         )
+        .await;
+        tasks.push(admin_task);
+        health_checks.push(health_check);
     }
 
-    builder.build(stop_receiver.clone()).await
-}
+    if with_http_api {
+        let mut builder = web3::ApiBuilder::jsonrpsee_backend(
+            internal_api.clone(),
+            replica_connection_pool.clone(),
+        )
+        .http(web3_config.http_port)
+        .with_filter_limit(web3_config.filters_limit())
+        .with_threads(web3_config.http_server_threads())
+        .with_request_limits(RequestLimitsConfig::from_config(web3_config))
+        .with_tx_sender(tx_sender.clone());
+        if state_keeper_config.save_call_traces {
+            builder = builder.enable_debug_namespace(
+                BaseSystemContractsHashes {
+                    bootloader: tx_sender_config.bootloader,
+                    default_aa: tx_sender_config.default_aa,
+                },
+                tx_sender_config.fair_l2_gas_price,
+                web3_config.vm_execution_cache_misses_limit,
+            );
+        }
+        builder = with_common_api_options(
+            builder,
+            response_cache.clone(),
+            call_cache.clone(),
+            mempool.clone(),
+            pending_receipts.clone(),
+            &admin_controls,
+        );
+        let (http_tasks, health_check) = builder.build(stop_receiver.clone()).await;
+        tasks.extend(http_tasks);
+        health_checks.push(health_check);
+    }
 
-#[allow(clippy::too_many_arguments)]
-async fn run_ws_api<G: L1GasPriceProvider + Send + Sync + 'static>(
-    tx_sender_config: &TxSenderConfig,
-    state_keeper_config: &StateKeeperConfig,
-    internal_api: &InternalApiConfig,
-    api_config: &ApiConfig,
-    gas_adjuster: Arc<G>,
-    master_connection_pool: ConnectionPool,
-    replica_connection_pool: ConnectionPool,
-    stop_receiver: watch::Receiver<bool>,
-    factory_deps_cache: FactoryDepsCache,
-) -> (Vec<JoinHandle<()>>, ApiHealthCheck) {
-    let tx_sender = build_tx_sender(
-        tx_sender_config,
-        &api_config.web3_json_rpc,
-        state_keeper_config,
-        replica_connection_pool.clone(),
-        master_connection_pool.clone(),
-        gas_adjuster,
-        factory_deps_cache.clone(),
-    )
-    .await;
+    let ipc_path = web3_config.ipc_path.as_ref();
+    if with_ws_api || ipc_path.is_some() {
+        let mut builder =
+            web3::ApiBuilder::jsonrpc_backend(internal_api.clone(), replica_connection_pool)
+                .with_filter_limit(web3_config.filters_limit())
+                .with_subscriptions_limit(web3_config.subscriptions_limit())
+                .with_polling_interval(web3_config.pubsub_interval())
+                .with_threads(web3_config.ws_server_threads())
+                .with_request_limits(RequestLimitsConfig::from_config(web3_config))
+                .with_call_metrics(CallMetricsConfig::from_config(web3_config))
+                .with_tx_sender(tx_sender)
+                .with_l1_batch_status_bus(l1_batch_status_bus);
+        if with_ws_api {
+            builder = builder.ws(web3_config.ws_port);
+        }
+        if let Some(ipc_path) = ipc_path {
+            builder = builder.ipc(ipc_path);
+        }
+        builder = with_common_api_options(
+            builder,
+            response_cache,
+            call_cache,
+            mempool,
+            pending_receipts,
+            &admin_controls,
+        );
+        let (pub_sub_tasks, health_check) = builder.build(stop_receiver).await;
+        tasks.extend(pub_sub_tasks);
+        health_checks.push(health_check);
+    }
+    (tasks, health_checks)
+}
 
-    web3::ApiBuilder::jsonrpc_backend(internal_api.clone(), replica_connection_pool)
-        .ws(api_config.web3_json_rpc.ws_port)
-        .with_filter_limit(api_config.web3_json_rpc.filters_limit())
-        .with_subscriptions_limit(api_config.web3_json_rpc.subscriptions_limit())
-        .with_polling_interval(api_config.web3_json_rpc.pubsub_interval())
-        .with_threads(api_config.web3_json_rpc.ws_server_threads())
-        .with_tx_sender(tx_sender)
-        .build(stop_receiver.clone())
-        .await
+fn with_common_api_options<G>(
+    mut builder: web3::ApiBuilder<G>,
+    response_cache: Option<Arc<ResponseCache>>,
+    call_cache: Option<Arc<CallCache>>,
+    mempool: Option<MempoolGuard>,
+    pending_receipts: Option<PendingReceipts>,
+    admin_controls: &AdminControls,
+) -> web3::ApiBuilder<G> {
+    if let Some(response_cache) = response_cache {
+        builder = builder.with_shared_response_cache(response_cache);
+    }
+    if let Some(call_cache) = call_cache {
+        builder = builder.with_call_cache(call_cache);
+    }
+    if let Some(mempool) = mempool {
+        builder = builder.with_mempool(mempool);
+    }
+    if let Some(pending_receipts) = pending_receipts {
+        builder = builder.with_pending_receipts(pending_receipts);
+    }
+    builder.with_admin_controls(admin_controls.clone())
 }
 
 async fn circuit_breakers_for_components(
@@ -973,5 +1438,5 @@ async fn test_house_keeper_components_get_added() {
         .unwrap();
     // circuit-breaker, prometheus-exporter components are run, irrespective of other components.
     let always_running_component_count = 2;
-    assert_eq!(13, core_task_handles.len() - always_running_component_count);
+    assert_eq!(19, core_task_handles.len() - always_running_component_count);
 }
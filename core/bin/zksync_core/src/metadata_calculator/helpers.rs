@@ -20,7 +20,7 @@ use zksync_types::{
 /// In the unlikely case you get a "`ZkSyncTree` is in inconsistent state" panic,
 /// cancellation is most probably the reason.
 #[derive(Debug, Default)]
-pub(super) struct AsyncTree(Option<ZkSyncTree>);
+pub(crate) struct AsyncTree(Option<ZkSyncTree>);
 
 impl AsyncTree {
     const INCONSISTENT_MSG: &'static str =
@@ -13,8 +13,11 @@ use zksync_object_store::ObjectStoreFactory;
 use zksync_types::{
     block::L1BatchHeader,
     commitment::{BlockCommitment, BlockMetadata, BlockWithMetadata},
+    state_diffs::StateDiffRecord,
 };
 
+use crate::l1_batch_status_bus::L1BatchStatusBus;
+
 mod healthcheck;
 mod helpers;
 mod metrics;
@@ -23,7 +26,7 @@ mod tests;
 mod updater;
 
 pub use self::healthcheck::TreeHealthCheck;
-pub(crate) use self::helpers::get_logs_for_l1_batch;
+pub(crate) use self::helpers::{get_logs_for_l1_batch, AsyncTree};
 use self::{helpers::Delayer, metrics::TreeUpdateStage, updater::TreeUpdater};
 
 #[derive(Debug, Copy, Clone)]
@@ -136,6 +139,12 @@ impl MetadataCalculator {
         }
     }
 
+    /// Makes the calculator publish L1 batches with computed metadata to the provided bus.
+    pub fn with_l1_batch_status_bus(mut self, bus: L1BatchStatusBus) -> Self {
+        self.updater.l1_batch_status_bus = Some(bus);
+        self
+    }
+
     /// Returns a health check for this calculator.
     pub fn tree_health_check(&self) -> TreeHealthCheck {
         let receiver = self.status_sender.subscribe();
@@ -188,11 +197,30 @@ impl MetadataCalculator {
         block_with_metadata
     }
 
-    fn build_block_metadata(
+    /// Assigns enumeration indices from the tree to the state diffs persisted by the state keeper.
+    fn assign_enumeration_indices(
+        tree_metadata_at_block: &TreeMetadata,
+        state_diffs: &mut [StateDiffRecord],
+    ) {
+        for diff in state_diffs {
+            let tree_key = diff.tree_key();
+            diff.enumeration_index = tree_metadata_at_block
+                .repeated_write_indices
+                .get(&tree_key)
+                .copied()
+                .unwrap_or(0);
+        }
+    }
+
+    pub(crate) fn build_block_metadata(
         tree_metadata_at_block: TreeMetadata,
         l1_batch_header: &L1BatchHeader,
+        mut state_diffs: Option<Vec<StateDiffRecord>>,
     ) -> BlockMetadata {
         let merkle_root_hash = tree_metadata_at_block.root_hash;
+        if let Some(state_diffs) = &mut state_diffs {
+            Self::assign_enumeration_indices(&tree_metadata_at_block, state_diffs);
+        }
 
         let block_commitment = BlockCommitment::new(
             l1_batch_header.l2_to_l1_logs.clone(),
@@ -200,6 +228,7 @@ impl MetadataCalculator {
             merkle_root_hash,
             tree_metadata_at_block.initial_writes,
             tree_metadata_at_block.repeated_writes,
+            state_diffs,
             l1_batch_header.base_system_contracts_hashes.bootloader,
             l1_batch_header.base_system_contracts_hashes.default_aa,
         );
@@ -212,6 +241,9 @@ impl MetadataCalculator {
             merkle_root_hash,
             initial_writes_compressed: block_commitment.initial_writes_compressed().to_vec(),
             repeated_writes_compressed: block_commitment.repeated_writes_compressed().to_vec(),
+            state_diffs_compressed: block_commitment
+                .state_diffs_compressed()
+                .map(<[u8]>::to_vec),
             commitment: block_commitment_hash.commitment,
             l2_l1_messages_compressed: block_commitment.l2_l1_logs_compressed().to_vec(),
             l2_l1_merkle_root: block_commitment.l2_l1_logs_merkle_root(),
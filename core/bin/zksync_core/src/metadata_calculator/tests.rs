@@ -296,6 +296,7 @@ async fn setup_calculator_with_options(
             Default::default(),
             vec![],
             vec![],
+            None,
             base_system_contracts.bootloader.hash,
             base_system_contracts.default_aa.hash,
         );
@@ -10,6 +10,8 @@ use zksync_object_store::ObjectStore;
 use zksync_storage::{db::NamedColumnFamily, RocksDB};
 use zksync_types::{block::WitnessBlockWithLogs, L1BatchNumber};
 
+use crate::l1_batch_status_bus::{L1BatchStage, L1BatchStatusBus};
+
 use super::{
     get_logs_for_l1_batch,
     helpers::{AsyncTree, Delayer},
@@ -23,6 +25,7 @@ pub(super) struct TreeUpdater {
     tree: AsyncTree,
     max_block_batch: usize,
     object_store: Option<Box<dyn ObjectStore>>,
+    pub(super) l1_batch_status_bus: Option<L1BatchStatusBus>,
 }
 
 impl TreeUpdater {
@@ -48,6 +51,7 @@ impl TreeUpdater {
             tree,
             max_block_batch,
             object_store,
+            l1_batch_status_bus: None,
         }
     }
 
@@ -100,8 +104,15 @@ impl TreeUpdater {
             let witness_input = metadata_at_block.witness.take();
 
             let next_root_hash = metadata_at_block.root_hash;
-            let metadata =
-                MetadataCalculator::build_block_metadata(metadata_at_block, &block_header);
+            let state_diffs = storage
+                .blocks_dal()
+                .get_l1_batch_state_diffs(block_header.number)
+                .await;
+            let metadata = MetadataCalculator::build_block_metadata(
+                metadata_at_block,
+                &block_header,
+                state_diffs,
+            );
             prepare_results_latency.report();
 
             let block_with_metadata =
@@ -159,6 +170,10 @@ impl TreeUpdater {
         let save_rocksdb_latency = TreeUpdateStage::SaveRocksDB.start();
         self.tree.save().await;
         save_rocksdb_latency.report();
+        if let (Some(bus), Some(last_header)) = (&self.l1_batch_status_bus, updated_headers.last())
+        {
+            bus.publish(L1BatchStage::TreeComputed, last_header.number);
+        }
         MetadataCalculator::update_metrics(self.mode, &updated_headers, total_logs, start);
     }
 
@@ -0,0 +1,78 @@
+//! HTTP server used by TEE-based provers (e.g., Intel SGX) to fetch L1 batch inputs and submit
+//! attested execution results. TEE proofs are generated alongside ZK proofs, which are still
+//! produced by the prover subsystem directly from the prover DB.
+
+use actix_web::{dev::Server, web, App, HttpServer};
+use tokio::{sync::watch, task::JoinHandle};
+
+use std::{net::SocketAddr, time::Duration};
+
+use zksync_config::configs::ProofDataHandlerConfig;
+use zksync_dal::ConnectionPool;
+use zksync_object_store::ObjectStore;
+use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
+
+use self::request_processor::TeeRequestProcessor;
+
+mod request_processor;
+
+fn start_server(processor: TeeRequestProcessor, bind_to: SocketAddr) -> Server {
+    HttpServer::new(move || {
+        App::new()
+            .app_data(web::Data::new(processor.clone()))
+            .service(
+                web::scope("/tee")
+                    .route(
+                        "/proof_inputs",
+                        web::post().to(TeeRequestProcessor::get_proof_generation_data),
+                    )
+                    .route(
+                        "/register_attestation",
+                        web::post().to(TeeRequestProcessor::register_attestation),
+                    )
+                    .route(
+                        "/submit_proofs/{l1_batch_number}",
+                        web::post().to(TeeRequestProcessor::submit_proof),
+                    ),
+            )
+    })
+    .workers(1)
+    .bind(bind_to)
+    .unwrap()
+    .shutdown_timeout(60)
+    .keep_alive(Duration::from_secs(10))
+    .client_request_timeout(Duration::from_secs(60))
+    .run()
+}
+
+pub fn start_server_thread_detached(
+    config: ProofDataHandlerConfig,
+    blob_store: Box<dyn ObjectStore>,
+    pool: ConnectionPool,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> JoinHandle<()> {
+    let (handler, panic_sender) = spawn_panic_handler();
+
+    std::thread::Builder::new()
+        .name("proof-data-handler".to_string())
+        .spawn(move || {
+            let _panic_sentinel = ThreadPanicNotify(panic_sender);
+
+            actix_rt::System::new().block_on(async move {
+                let bind_address = SocketAddr::from(([0, 0, 0, 0], config.http_port));
+                let processor = TeeRequestProcessor::new(blob_store, pool, config);
+                let server = start_server(processor, bind_address);
+                let close_handle = server.handle();
+                actix_rt::spawn(async move {
+                    if stop_receiver.changed().await.is_ok() {
+                        close_handle.stop(true).await;
+                        vlog::info!("Stop signal received, proof data handler is shutting down");
+                    }
+                });
+                server.await.expect("Proof data handler crashed");
+            });
+        })
+        .expect("Failed to spawn thread for proof data handler");
+
+    handler
+}
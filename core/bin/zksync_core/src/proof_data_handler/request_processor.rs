@@ -0,0 +1,222 @@
+use actix_web::{
+    http::StatusCode,
+    web::{self, Json},
+    HttpResponse, ResponseError,
+};
+use thiserror::Error;
+
+use std::{fs, sync::Arc, time::Instant};
+
+use zksync_config::configs::ProofDataHandlerConfig;
+use zksync_dal::ConnectionPool;
+use zksync_object_store::{ObjectStore, ObjectStoreError};
+use zksync_types::{
+    proofs::PrepareBasicCircuitsJob,
+    tee::{
+        RegisterTeeAttestationRequest, SgxQuote, SgxRootCertificate, SubmitTeeProofRequest,
+        SubmitTeeProofResponse, TeeProof, TeeProofGenerationDataRequest,
+        TeeProofGenerationDataResponse, TeeVerificationError, TeeVerifierInput,
+    },
+    L1BatchNumber,
+};
+use zksync_utils::time::seconds_since_epoch;
+
+#[derive(Debug, Error)]
+pub(crate) enum RequestProcessorError {
+    #[error("failed fetching batch inputs from object store: {0}")]
+    ObjectStore(#[from] ObjectStoreError),
+    #[error("verification failed: {0}")]
+    Verification(#[from] TeeVerificationError),
+    #[error("attestations are not accepted since the root CA certificate is not configured")]
+    AttestationsDisabled,
+    #[error("public key is not attested")]
+    UnknownPubkey,
+    #[error("L1 batch #{0} doesn't have a state root hash")]
+    MissingRootHash(L1BatchNumber),
+    #[error("state root hash mismatch for L1 batch #{0}")]
+    RootHashMismatch(L1BatchNumber),
+    #[error("L1 batch #{0} is not picked by a prover")]
+    BatchNotPicked(L1BatchNumber),
+}
+
+impl ResponseError for RequestProcessorError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::ObjectStore(_) | Self::MissingRootHash(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::Verification(_) | Self::RootHashMismatch(_) => StatusCode::BAD_REQUEST,
+            Self::UnknownPubkey => StatusCode::UNAUTHORIZED,
+            Self::AttestationsDisabled => StatusCode::FORBIDDEN,
+            Self::BatchNotPicked(_) => StatusCode::CONFLICT,
+        }
+    }
+}
+
+/// Processes requests from TEE provers.
+#[derive(Debug, Clone)]
+pub(crate) struct TeeRequestProcessor {
+    blob_store: Arc<dyn ObjectStore>,
+    pool: ConnectionPool,
+    config: ProofDataHandlerConfig,
+    /// Root certificate for PCK certificate chains in attestation quotes; `None` if not configured.
+    root_certificate: Option<Arc<SgxRootCertificate>>,
+}
+
+impl TeeRequestProcessor {
+    pub fn new(
+        blob_store: Box<dyn ObjectStore>,
+        pool: ConnectionPool,
+        config: ProofDataHandlerConfig,
+    ) -> Self {
+        let root_certificate = config.tee_root_ca_path.as_ref().map(|path| {
+            let bytes = fs::read(path).unwrap_or_else(|err| {
+                panic!("Failed reading TEE root CA certificate `{path}`: {err}")
+            });
+            let certificate = SgxRootCertificate::new(&bytes)
+                .unwrap_or_else(|err| panic!("Invalid TEE root CA certificate `{path}`: {err}"));
+            Arc::new(certificate)
+        });
+        if root_certificate.is_none() || config.tee_allowed_mrenclaves().is_empty() {
+            vlog::warn!(
+                "TEE root CA certificate or allowed enclave measurements are not configured; \
+                 all TEE attestations will be rejected"
+            );
+        }
+
+        Self {
+            blob_store: Arc::from(blob_store),
+            pool,
+            config,
+            root_certificate,
+        }
+    }
+
+    pub async fn get_proof_generation_data(
+        self_: web::Data<Self>,
+        request: Json<TeeProofGenerationDataRequest>,
+    ) -> Result<Json<TeeProofGenerationDataResponse>, RequestProcessorError> {
+        let started_at = Instant::now();
+        let tee_type = request.tee_type;
+        let mut storage = self_.pool.access_storage_tagged("proof_data_handler").await;
+        let l1_batch_number = storage
+            .tee_proof_generation_dal()
+            .get_next_batch_to_be_proven(tee_type, self_.config.proof_generation_timeout())
+            .await;
+        let Some(l1_batch_number) = l1_batch_number else {
+            return Ok(Json(TeeProofGenerationDataResponse(None)));
+        };
+
+        let previous_root_hash = storage
+            .blocks_dal()
+            .get_block_state_root(l1_batch_number - 1)
+            .await
+            .ok_or(RequestProcessorError::MissingRootHash(l1_batch_number - 1))?;
+        drop(storage);
+        let prepare_basic_circuits_job: PrepareBasicCircuitsJob =
+            self_.blob_store.get(l1_batch_number).await?;
+
+        metrics::histogram!(
+            "server.proof_data_handler.request",
+            started_at.elapsed(),
+            "method" => "tee_proof_inputs",
+            "tee_type" => tee_type.as_str()
+        );
+        Ok(Json(TeeProofGenerationDataResponse(Some(Box::new(
+            TeeVerifierInput {
+                l1_batch_number,
+                previous_root_hash,
+                prepare_basic_circuits_job,
+            },
+        )))))
+    }
+
+    pub async fn register_attestation(
+        self_: web::Data<Self>,
+        request: Json<RegisterTeeAttestationRequest>,
+    ) -> Result<HttpResponse, RequestProcessorError> {
+        let root_certificate = self_
+            .root_certificate
+            .as_deref()
+            .ok_or(RequestProcessorError::AttestationsDisabled)?;
+        let quote = SgxQuote::parse(&request.attestation)?;
+        quote.verify(
+            &request.pubkey,
+            self_.config.tee_allowed_mrenclaves(),
+            root_certificate,
+            seconds_since_epoch(),
+        )?;
+
+        let mut storage = self_.pool.access_storage_tagged("proof_data_handler").await;
+        storage
+            .tee_proof_generation_dal()
+            .save_attestation(&request.pubkey, &request.attestation, quote.mrenclave)
+            .await;
+        vlog::info!(
+            "Registered TEE attestation for enclave {:?} with public key 0x{}",
+            quote.mrenclave,
+            hex::encode(&request.pubkey)
+        );
+        Ok(HttpResponse::Ok().finish())
+    }
+
+    pub async fn submit_proof(
+        self_: web::Data<Self>,
+        l1_batch_number: web::Path<u32>,
+        request: Json<SubmitTeeProofRequest>,
+    ) -> Result<Json<SubmitTeeProofResponse>, RequestProcessorError> {
+        let l1_batch_number = L1BatchNumber(l1_batch_number.into_inner());
+        let mut storage = self_.pool.access_storage_tagged("proof_data_handler").await;
+        let mrenclave = storage
+            .tee_proof_generation_dal()
+            .get_attested_mrenclave(&request.pubkey)
+            .await
+            .ok_or(RequestProcessorError::UnknownPubkey)?;
+        // The list of allowed enclaves may have changed since the attestation was registered.
+        let allowed_mrenclaves = self_.config.tee_allowed_mrenclaves();
+        if !allowed_mrenclaves.contains(&mrenclave) {
+            return Err(TeeVerificationError::EnclaveNotAllowed(mrenclave).into());
+        }
+
+        let proof = TeeProof {
+            l1_batch_number,
+            root_hash: request.root_hash,
+        };
+        proof.verify_signature(&request.pubkey, &request.signature)?;
+
+        let expected_root_hash = storage
+            .blocks_dal()
+            .get_block_state_root(l1_batch_number)
+            .await
+            .ok_or(RequestProcessorError::MissingRootHash(l1_batch_number))?;
+        if expected_root_hash != request.root_hash {
+            metrics::increment_counter!(
+                "server.proof_data_handler.tee_root_hash_mismatch",
+                "tee_type" => request.tee_type.as_str()
+            );
+            vlog::error!(
+                "TEE prover returned root hash {:?} for L1 batch #{l1_batch_number}, expected {expected_root_hash:?}",
+                request.root_hash
+            );
+            return Err(RequestProcessorError::RootHashMismatch(l1_batch_number));
+        }
+
+        let saved = storage
+            .tee_proof_generation_dal()
+            .save_proof(
+                l1_batch_number,
+                request.tee_type,
+                &request.pubkey,
+                &request.signature,
+                request.root_hash,
+            )
+            .await;
+        if !saved {
+            return Err(RequestProcessorError::BatchNotPicked(l1_batch_number));
+        }
+        metrics::gauge!(
+            "server.proof_data_handler.tee_last_proven_batch",
+            l1_batch_number.0 as f64,
+            "tee_type" => request.tee_type.as_str()
+        );
+        Ok(Json(SubmitTeeProofResponse::Success))
+    }
+}
@@ -0,0 +1,226 @@
+//! Consistency checks performed before starting server components.
+//!
+//! The checks catch common operational mistakes (e.g., running a server against a database migrated
+//! by another server version, or reusing a Merkle tree from another environment) and fail fast with
+//! an actionable error, rather than letting components panic later in a less obvious way.
+
+use anyhow::{ensure, Context as _};
+
+use std::path::Path;
+
+use zksync_config::{ContractsConfig, DBConfig, ETHClientConfig};
+use zksync_contracts::zksync_contract;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_eth_client::EthInterface;
+use zksync_merkle_tree::domain::ZkSyncTree;
+use zksync_state::RocksdbStorage;
+use zksync_storage::RocksDB;
+use zksync_types::{web3::contract::Options, Address, L1BatchNumber};
+
+use crate::Component;
+
+const COMPONENT_NAME: &str = "startup_checks";
+
+/// Runs all checks relevant to the specified components.
+pub async fn run_startup_checks<E: EthInterface>(
+    components: &[Component],
+    pool: &ConnectionPool,
+    eth_client: &E,
+    eth_client_config: &ETHClientConfig,
+    contracts_config: &ContractsConfig,
+    db_config: &DBConfig,
+) -> anyhow::Result<()> {
+    let mut storage = pool.access_storage().await;
+    check_schema(&mut storage).await?;
+    check_l1_network(eth_client, eth_client_config, contracts_config).await?;
+
+    if components.contains(&Component::Tree) || components.contains(&Component::TreeLightweight) {
+        check_merkle_tree(&mut storage, &db_config.new_merkle_tree_ssd_path).await?;
+    }
+    if components.contains(&Component::StateKeeper) {
+        check_state_keeper_cache(&mut storage, db_config.state_keeper_db_path()).await?;
+    }
+    vlog::info!("Startup checks passed");
+    Ok(())
+}
+
+/// Checks that the database schema corresponds to the migrations known to the server.
+async fn check_schema(storage: &mut StorageProcessor<'_>) -> anyhow::Result<()> {
+    let mismatch = storage.maintenance_dal().get_schema_mismatch().await;
+    ensure!(
+        mismatch.missing_migrations.is_empty(),
+        "Database is missing migrations {:?}; run `zk db migrate` before starting the server",
+        mismatch.missing_migrations
+    );
+    ensure!(
+        mismatch.unknown_migrations.is_empty(),
+        "Database has migrations {:?} unknown to this server version; the database was probably \
+         migrated by a newer server, so either upgrade the server or revert the migrations",
+        mismatch.unknown_migrations
+    );
+    Ok(())
+}
+
+/// Checks that the L1 client is connected to the expected network, and that the contract addresses
+/// in the config correspond to the ones deployed on it.
+async fn check_l1_network<E: EthInterface>(
+    eth_client: &E,
+    eth_client_config: &ETHClientConfig,
+    contracts_config: &ContractsConfig,
+) -> anyhow::Result<()> {
+    let l1_chain_id = eth_client
+        .fetch_chain_id(COMPONENT_NAME)
+        .await
+        .context("failed fetching L1 chain ID")?;
+    ensure!(
+        l1_chain_id.0 == eth_client_config.chain_id,
+        "L1 node at ETH_CLIENT_WEB3_URL reports chain ID {}, while ETH_CLIENT_CHAIN_ID is {}; \
+         check that the server is connected to the correct L1 network",
+        l1_chain_id.0,
+        eth_client_config.chain_id
+    );
+
+    let diamond_proxy_addr = contracts_config.diamond_proxy_addr;
+    let verifier_addr: Address = eth_client
+        .call_contract_function(
+            "getVerifier",
+            (),
+            None,
+            Options::default(),
+            None,
+            diamond_proxy_addr,
+            zksync_contract(),
+        )
+        .await
+        .with_context(|| {
+            format!(
+                "failed calling `getVerifier` on the diamond proxy at {diamond_proxy_addr:?}; \
+                 check that CONTRACTS_DIAMOND_PROXY_ADDR is correct for the L1 network"
+            )
+        })?;
+    ensure!(
+        verifier_addr == contracts_config.verifier_addr,
+        "Diamond proxy at {diamond_proxy_addr:?} uses verifier {verifier_addr:?}, while \
+         CONTRACTS_VERIFIER_ADDR is {:?}; the contracts config is probably outdated",
+        contracts_config.verifier_addr
+    );
+    Ok(())
+}
+
+/// Checks that the Merkle tree doesn't contain L1 batches unknown to Postgres, and that its root hash
+/// matches the one stored in Postgres.
+async fn check_merkle_tree(
+    storage: &mut StorageProcessor<'_>,
+    path: impl AsRef<Path>,
+) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    if !path.exists() {
+        // The tree will be created from scratch.
+        return Ok(());
+    }
+
+    // The tree is dropped at the end of the function, so that the RocksDB lock is released
+    // before the metadata calculator opens the tree.
+    let tree = ZkSyncTree::new_lightweight(RocksDB::new(path, true));
+    let Some(last_tree_batch) = tree.block_number().checked_sub(1) else {
+        return Ok(());
+    };
+    let last_tree_batch = L1BatchNumber(last_tree_batch);
+    let last_sealed_batch = storage.blocks_dal().get_sealed_block_number().await;
+    ensure!(
+        last_tree_batch <= last_sealed_batch,
+        "Merkle tree at {path:?} contains L1 batch #{last_tree_batch}, while the last L1 batch \
+         in Postgres is #{last_sealed_batch}; roll back the tree using the block reverter, or delete \
+         the tree directory so that it's recovered from Postgres"
+    );
+
+    let expected_root_hash = storage
+        .blocks_dal()
+        .get_block_state_root(last_tree_batch)
+        .await;
+    if let Some(expected_root_hash) = expected_root_hash {
+        let root_hash = tree.root_hash();
+        ensure!(
+            root_hash == expected_root_hash,
+            "Root hash of the Merkle tree at {path:?} for L1 batch #{last_tree_batch} ({root_hash:?}) \
+             differs from the one stored in Postgres ({expected_root_hash:?}); the tree was \
+             probably created for another database, so delete the tree directory so that it's \
+             recovered from Postgres"
+        );
+    }
+    Ok(())
+}
+
+/// Checks that the state keeper cache doesn't contain L1 batches unknown to Postgres.
+async fn check_state_keeper_cache(
+    storage: &mut StorageProcessor<'_>,
+    path: impl AsRef<Path>,
+) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let cache = RocksdbStorage::new(path);
+    // The cache stores the number of the next L1 batch to process.
+    let cached_batch = cache.l1_batch_number();
+    let last_sealed_batch = storage.blocks_dal().get_sealed_block_number().await;
+    ensure!(
+        cached_batch <= last_sealed_batch + 1,
+        "State keeper cache at {path:?} is synced up to L1 batch #{cached_batch}, while the last \
+         L1 batch in Postgres is #{last_sealed_batch}; roll back the cache using the block reverter, \
+         or delete the cache directory so that it's recovered from Postgres"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use db_test_macro::db_test;
+    use tempfile::TempDir;
+
+    use zksync_types::L2ChainId;
+
+    use super::*;
+    use crate::genesis::{ensure_genesis_state, GenesisParams};
+
+    #[db_test]
+    async fn schema_check_passes_for_migrated_database(pool: ConnectionPool) {
+        let mut storage = pool.access_storage().await;
+        check_schema(&mut storage).await.unwrap();
+    }
+
+    #[db_test]
+    async fn merkle_tree_check(pool: ConnectionPool) {
+        let mut storage = pool.access_storage().await;
+        let params = GenesisParams::MainNode {
+            first_validator: Address::repeat_byte(0x01),
+        };
+        ensure_genesis_state(&mut storage, L2ChainId(270), &params).await;
+
+        let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+        check_merkle_tree(&mut storage, temp_dir.path())
+            .await
+            .unwrap();
+
+        {
+            let mut tree = ZkSyncTree::new_lightweight(RocksDB::new(temp_dir.path(), true));
+            tree.process_block(&[]);
+            tree.save();
+        }
+        let err = check_merkle_tree(&mut storage, temp_dir.path())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Root hash"), "{err}");
+
+        {
+            let mut tree = ZkSyncTree::new_lightweight(RocksDB::new(temp_dir.path(), true));
+            tree.process_block(&[]);
+            tree.save();
+        }
+        let err = check_merkle_tree(&mut storage, temp_dir.path())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("L1 batch #1"), "{err}");
+    }
+}
@@ -4,7 +4,12 @@ use tokio::{
     task::JoinHandle,
 };
 
-use std::{collections::HashSet, fmt, time::Instant};
+use std::{
+    collections::HashSet,
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 use vm::{
     vm::{VmPartialExecutionResult, VmTxExecutionResult},
@@ -15,13 +20,21 @@ use vm::{
     HistoryEnabled, HistoryMode, TxRevertReason, VmBlockResult, VmInstance,
 };
 use zksync_dal::ConnectionPool;
-use zksync_state::{RocksdbStorage, StorageView};
-use zksync_types::{tx::ExecutionMetrics, Transaction, U256};
+use zksync_object_store::ObjectStore;
+use zksync_state::{BytecodeCache, InMemoryStorage, ReadStorage, RocksdbStorage, StorageView};
+use zksync_types::{
+    proofs::L1BatchVmArtifacts,
+    tx::{ExecutionMetrics, TxRejectedResource, TxRejection},
+    L1BatchNumber, Transaction, U256,
+};
 use zksync_utils::bytecode::{hash_bytecode, CompressedBytecodeInfo};
 
+mod supervisor;
 #[cfg(test)]
 mod tests;
 
+pub use self::supervisor::SupervisedBatchExecutorBuilder;
+
 use crate::{
     gas_tracker::{gas_count_from_metrics, gas_count_from_tx_and_metrics},
     state_keeper::{io::L1BatchParams, types::ExecutionMetricsForCriteria},
@@ -40,6 +53,8 @@ pub(crate) enum TxExecutionResult {
     },
     /// The VM rejected the tx for some reason.
     RejectedByVm { rejection_reason: TxRevertReason },
+    /// The tx cannot be executed by the batch executor, although it wasn't rejected by the VM.
+    Unexecutable { reason: UnexecutableReason },
     /// Bootloader gas limit is not enough to execute the tx.
     BootloaderOutOfGasForTx,
     /// Bootloader gas limit is enough to run the tx but not enough to execute block tip.
@@ -50,7 +65,7 @@ impl TxExecutionResult {
     /// Returns a revert reason if either transaction was rejected or bootloader ran out of gas.
     pub(super) fn err(&self) -> Option<&TxRevertReason> {
         match self {
-            Self::Success { .. } => None,
+            Self::Success { .. } | Self::Unexecutable { .. } => None,
             Self::RejectedByVm { rejection_reason } => Some(rejection_reason),
             Self::BootloaderOutOfGasForTx | Self::BootloaderOutOfGasForBlockTip { .. } => {
                 Some(&TxRevertReason::BootloaderOutOfGas)
@@ -59,6 +74,36 @@ impl TxExecutionResult {
     }
 }
 
+/// Reason why the batch executor has refused to execute a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum UnexecutableReason {
+    /// The tx execution has exceeded the wall-clock budget.
+    Timeout(Duration),
+    /// The batch executor has crashed (e.g., the VM panicked) while executing the tx.
+    ExecutorCrashed,
+}
+
+impl fmt::Display for UnexecutableReason {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timeout(timeout) => write!(formatter, "execution timed out after {timeout:?}"),
+            Self::ExecutorCrashed => formatter.write_str("batch executor crashed during execution"),
+        }
+    }
+}
+
+impl From<UnexecutableReason> for TxRejection {
+    fn from(reason: UnexecutableReason) -> Self {
+        let message = reason.to_string();
+        match reason {
+            UnexecutableReason::Timeout(_) => {
+                TxRejection::resource_limit_exceeded(TxRejectedResource::ExecutionTime, message)
+            }
+            UnexecutableReason::ExecutorCrashed => TxRejection::executor_crashed(message),
+        }
+    }
+}
+
 /// An abstraction that allows us to create different kinds of batch executors.
 /// The only requirement is to return a [`BatchExecutorHandle`], which does its work
 /// by communicating with the externally initialized thread.
@@ -76,6 +121,9 @@ pub struct MainBatchExecutorBuilder {
     save_call_traces: bool,
     max_allowed_tx_gas_limit: U256,
     validation_computational_gas_limit: u32,
+    tx_execution_timeout: Option<Duration>,
+    bytecode_cache: Option<BytecodeCache>,
+    vm_artifacts_store: Option<Arc<dyn ObjectStore>>,
 }
 
 impl MainBatchExecutorBuilder {
@@ -85,6 +133,7 @@ impl MainBatchExecutorBuilder {
         max_allowed_tx_gas_limit: U256,
         save_call_traces: bool,
         validation_computational_gas_limit: u32,
+        tx_execution_timeout: Option<Duration>,
     ) -> Self {
         Self {
             state_keeper_db_path,
@@ -92,8 +141,26 @@ impl MainBatchExecutorBuilder {
             save_call_traces,
             max_allowed_tx_gas_limit,
             validation_computational_gas_limit,
+            tx_execution_timeout,
+            bytecode_cache: None,
+            vm_artifacts_store: None,
         }
     }
+
+    /// Sets the cache of decoded bytecodes shared by all created batch executors (and, potentially,
+    /// with the API sandbox).
+    #[must_use]
+    pub fn with_bytecode_cache(mut self, cache: BytecodeCache) -> Self {
+        self.bytecode_cache = Some(cache);
+        self
+    }
+
+    /// Sets the object store to save VM run artifacts to when a batch is finished.
+    #[must_use]
+    pub fn with_vm_artifacts_store(mut self, store: Arc<dyn ObjectStore>) -> Self {
+        self.vm_artifacts_store = Some(store);
+        self
+    }
 }
 
 #[async_trait]
@@ -121,9 +188,61 @@ impl L1BatchExecutorBuilder for MainBatchExecutorBuilder {
             self.save_call_traces,
             self.max_allowed_tx_gas_limit,
             self.validation_computational_gas_limit,
+            self.tx_execution_timeout,
             secondary_storage,
             l1_batch_params,
             None,
+            self.bytecode_cache.clone(),
+            self.vm_artifacts_store.clone(),
+        )
+    }
+}
+
+/// [`L1BatchExecutorBuilder`] executing batches in the VM over an in-memory storage. Each batch
+/// is executed over a snapshot of the shared storage taken when the batch is initialized; changes
+/// made by the batch are *not* applied to the storage automatically. Thus, the storage should be
+/// updated by the `StateKeeperIO` implementation when sealing the batch.
+///
+/// This builder allows to run the state keeper without Postgres and RocksDB (e.g., in benchmarks).
+#[derive(Debug, Clone)]
+pub struct InMemoryBatchExecutorBuilder {
+    storage: Arc<Mutex<InMemoryStorage>>,
+    max_allowed_tx_gas_limit: U256,
+    validation_computational_gas_limit: u32,
+}
+
+impl InMemoryBatchExecutorBuilder {
+    pub fn new(
+        storage: Arc<Mutex<InMemoryStorage>>,
+        max_allowed_tx_gas_limit: U256,
+        validation_computational_gas_limit: u32,
+    ) -> Self {
+        Self {
+            storage,
+            max_allowed_tx_gas_limit,
+            validation_computational_gas_limit,
+        }
+    }
+}
+
+#[async_trait]
+impl L1BatchExecutorBuilder for InMemoryBatchExecutorBuilder {
+    async fn init_batch(&self, l1_batch_params: L1BatchParams) -> BatchExecutorHandle {
+        let storage_snapshot = self
+            .storage
+            .lock()
+            .expect("in-memory storage is poisoned")
+            .clone();
+        BatchExecutorHandle::new(
+            false,
+            self.max_allowed_tx_gas_limit,
+            self.validation_computational_gas_limit,
+            None,
+            storage_snapshot,
+            l1_batch_params,
+            None,
+            None,
+            None,
         )
     }
 }
@@ -138,14 +257,22 @@ pub struct BatchExecutorHandle {
 }
 
 impl BatchExecutorHandle {
-    pub(super) fn new(
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn new<S>(
         save_call_traces: bool,
         max_allowed_tx_gas_limit: U256,
         validation_computational_gas_limit: u32,
-        secondary_storage: RocksdbStorage,
+        tx_execution_timeout: Option<Duration>,
+        secondary_storage: S,
         l1_batch_params: L1BatchParams,
         vm_gas_limit: Option<u32>,
-    ) -> Self {
+        bytecode_cache: Option<BytecodeCache>,
+        vm_artifacts_store: Option<Arc<dyn ObjectStore>>,
+    ) -> Self
+    where
+        S: Send + 'static,
+        for<'a> &'a S: ReadStorage + fmt::Debug,
+    {
         // Since we process `BatchExecutor` commands one-by-one (the next command is never enqueued
         // until a previous command is processed), capacity 1 is enough for the commands channel.
         let (commands_sender, commands_receiver) = mpsc::channel(1);
@@ -153,8 +280,11 @@ impl BatchExecutorHandle {
             save_call_traces,
             max_allowed_tx_gas_limit,
             validation_computational_gas_limit,
+            tx_execution_timeout,
             commands: commands_receiver,
             vm_gas_limit,
+            bytecode_cache,
+            vm_artifacts_store,
         };
 
         let handle =
@@ -167,11 +297,12 @@ impl BatchExecutorHandle {
 
     /// Creates a batch executor handle from the provided sender and thread join handle.
     /// Can be used to inject an alternative batch executor implementation.
-    #[cfg(test)]
+    #[cfg(any(test, feature = "testonly"))]
     pub(super) fn from_raw(handle: JoinHandle<()>, commands: mpsc::Sender<Command>) -> Self {
         Self { handle, commands }
     }
 
+    #[tracing::instrument(name = "batch_executor_execute_tx", skip_all, fields(tx_hash = ?tx.hash()))]
     pub(super) async fn execute_tx(&self, tx: Transaction) -> TxExecutionResult {
         let tx_gas_limit = tx.gas_limit().as_u32();
 
@@ -251,23 +382,34 @@ pub(super) struct BatchExecutor {
     save_call_traces: bool,
     max_allowed_tx_gas_limit: U256,
     validation_computational_gas_limit: u32,
+    /// Wall-clock budget for executing a single transaction.
+    tx_execution_timeout: Option<Duration>,
     commands: mpsc::Receiver<Command>,
     vm_gas_limit: Option<u32>,
+    bytecode_cache: Option<BytecodeCache>,
+    /// If set, VM run artifacts are saved to this store when the batch is finished.
+    vm_artifacts_store: Option<Arc<dyn ObjectStore>>,
 }
 
 impl BatchExecutor {
-    pub(super) fn run(mut self, secondary_storage: RocksdbStorage, l1_batch_params: L1BatchParams) {
-        vlog::info!(
-            "Starting executing batch #{}",
+    pub(super) fn run<S>(mut self, secondary_storage: S, l1_batch_params: L1BatchParams)
+    where
+        for<'a> &'a S: ReadStorage + fmt::Debug,
+    {
+        let l1_batch_number = L1BatchNumber(
             l1_batch_params
                 .context_mode
                 .inner_block_context()
                 .context
-                .block_number
+                .block_number,
         );
+        vlog::info!("Starting executing batch #{l1_batch_number}");
 
         let mut storage_view = StorageView::new(&secondary_storage);
         let mut oracle_tools = vm::OracleTools::new(&mut storage_view, HistoryEnabled);
+        if let Some(cache) = self.bytecode_cache.take() {
+            oracle_tools = oracle_tools.with_bytecode_cache(cache);
+        }
         let mut vm = match self.vm_gas_limit {
             Some(vm_gas_limit) => init_vm_with_gas_limit(
                 &mut oracle_tools,
@@ -297,7 +439,12 @@ impl BatchExecutor {
                     resp.send(()).unwrap();
                 }
                 Command::FinishBatch(resp) => {
-                    resp.send(self.finish_batch(&mut vm)).unwrap();
+                    let block_result = self.finish_batch(&mut vm);
+                    let used_bytecodes = self
+                        .vm_artifacts_store
+                        .is_some()
+                        .then(|| Self::used_bytecodes(&vm, &block_result));
+                    resp.send(block_result).unwrap();
 
                     // storage_view cannot be accessed while borrowed by the VM,
                     // so this is the only point at which storage metrics can be obtained
@@ -313,6 +460,24 @@ impl BatchExecutor {
                         "interaction" => "set_value"
                     );
 
+                    if let (Some(store), Some(used_bytecodes)) =
+                        (&self.vm_artifacts_store, used_bytecodes)
+                    {
+                        let artifacts = L1BatchVmArtifacts {
+                            storage_reads: storage_view
+                                .read_storage_keys()
+                                .iter()
+                                .map(|(key, value)| (*key, *value))
+                                .collect(),
+                            initial_writes: storage_view
+                                .initial_writes()
+                                .iter()
+                                .map(|(key, is_initial)| (*key, *is_initial))
+                                .collect(),
+                            used_bytecodes,
+                        };
+                        Self::save_vm_artifacts(store.as_ref(), l1_batch_number, &artifacts);
+                    }
                     return;
                 }
             }
@@ -347,7 +512,10 @@ impl BatchExecutor {
 
         // Execute the transaction.
         let stage_started_at = Instant::now();
-        let tx_result = self.execute_tx_in_vm(tx, vm);
+        let deadline = self
+            .tx_execution_timeout
+            .map(|timeout| stage_started_at + timeout);
+        let tx_result = self.execute_tx_in_vm(tx, vm, deadline);
         metrics::histogram!(
             "server.state_keeper.tx_execution_time",
             stage_started_at.elapsed(),
@@ -367,6 +535,19 @@ impl BatchExecutor {
             Err(TxRevertReason::BootloaderOutOfGas) => {
                 return TxExecutionResult::BootloaderOutOfGasForTx
             }
+            Err(TxRevertReason::ExecutionTimeout) => {
+                let timeout = self
+                    .tx_execution_timeout
+                    .expect("execution timed out without timeout configured");
+                vlog::warn!(
+                    "Execution of tx {:?} has exceeded the time limit of {timeout:?}",
+                    tx.hash()
+                );
+                metrics::increment_counter!("server.state_keeper.tx_execution_timeouts");
+                return TxExecutionResult::Unexecutable {
+                    reason: UnexecutableReason::Timeout(timeout),
+                };
+            }
             Err(rejection_reason) => return TxExecutionResult::RejectedByVm { rejection_reason },
             Ok((exec_result, compressed_bytecodes)) => (exec_result, compressed_bytecodes),
         };
@@ -405,6 +586,47 @@ impl BatchExecutor {
         vm.execute_till_block_end(BootloaderJobType::BlockPostprocessing)
     }
 
+    fn used_bytecodes<H: HistoryMode>(
+        vm: &VmInstance<'_, H>,
+        block_result: &VmBlockResult,
+    ) -> Vec<(U256, Vec<U256>)> {
+        let known_bytecodes = vm.state.decommittment_processor.known_bytecodes.inner();
+        block_result
+            .full_result
+            .used_contract_hashes
+            .iter()
+            .filter_map(|hash| Some((*hash, known_bytecodes.get(hash)?.clone())))
+            .collect()
+    }
+
+    /// Saves VM run artifacts for the witness generator. Errors are not fatal, since the witness generator
+    /// falls back to loading data from Postgres if the artifacts are missing.
+    fn save_vm_artifacts(
+        store: &dyn ObjectStore,
+        l1_batch_number: L1BatchNumber,
+        artifacts: &L1BatchVmArtifacts,
+    ) {
+        let started_at = Instant::now();
+        let rt_handle = tokio::runtime::Handle::current();
+        match rt_handle.block_on(store.put(l1_batch_number, artifacts)) {
+            Ok(url) => {
+                vlog::info!(
+                    "Saved VM artifacts for L1 batch #{l1_batch_number} ({} storage reads, {} bytecodes) to {url}",
+                    artifacts.storage_reads.len(),
+                    artifacts.used_bytecodes.len()
+                );
+            }
+            Err(err) => {
+                vlog::warn!("Failed saving VM artifacts for L1 batch #{l1_batch_number}: {err}");
+                metrics::increment_counter!("server.state_keeper.vm_artifacts_save_errors");
+            }
+        }
+        metrics::histogram!(
+            "server.state_keeper.vm_artifacts_save_latency",
+            started_at.elapsed()
+        );
+    }
+
     // Err when transaction is rejected.
     // Ok(TxExecutionStatus::Success) when the transaction succeeded
     // Ok(TxExecutionStatus::Failure) when the transaction failed.
@@ -413,6 +635,7 @@ impl BatchExecutor {
         &self,
         tx: &Transaction,
         vm: &mut VmInstance<'_, HistoryEnabled>,
+        deadline: Option<Instant>,
     ) -> Result<(VmTxExecutionResult, Vec<CompressedBytecodeInfo>), TxRevertReason> {
         // Note, that the space where we can put the calldata for compressing transactions
         // is limited and the transactions do not pay for taking it.
@@ -454,9 +677,10 @@ impl BatchExecutor {
             TxExecutionMode::VerifyExecute,
             Some(compressed_bytecodes.clone()),
         );
-        let result_with_compression = vm.execute_next_tx(
+        let result_with_compression = vm.execute_next_tx_with_deadline(
             self.validation_computational_gas_limit,
             self.save_call_traces,
+            deadline,
         )?;
 
         let at_least_one_unpublished = {
@@ -477,9 +701,10 @@ impl BatchExecutor {
                 Some(vec![]),
             );
 
-            vm.execute_next_tx(
+            vm.execute_next_tx_with_deadline(
                 self.validation_computational_gas_limit,
                 self.save_call_traces,
+                deadline,
             )
             .map(|val| (val, vec![]))
         } else {
@@ -545,14 +770,18 @@ impl BatchExecutor {
             })
             .unwrap_or(0);
 
-        let execution_metrics = ExecutionMetrics::new(
-            &execution_result.logs,
-            gas_used as usize,
-            total_factory_deps,
-            execution_result.contracts_used,
-            execution_result.cycles_used,
-            execution_result.computational_gas_used,
-        );
+        let execution_metrics = ExecutionMetrics {
+            refunded_storage_writes: execution_result.refunded_storage_writes,
+            circuit_statistic: execution_result.circuit_statistic,
+            ..ExecutionMetrics::new(
+                &execution_result.logs,
+                gas_used as usize,
+                total_factory_deps,
+                execution_result.contracts_used,
+                execution_result.cycles_used,
+                execution_result.computational_gas_used,
+            )
+        };
 
         let l1_gas = match tx {
             Some(tx) => gas_count_from_tx_and_metrics(tx, &execution_metrics),
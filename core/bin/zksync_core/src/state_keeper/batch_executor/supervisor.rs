@@ -0,0 +1,243 @@
+//! Batch executor supervision allowing to recover from VM panics.
+
+use async_trait::async_trait;
+use tokio::sync::{mpsc, oneshot};
+
+use std::sync::Arc;
+
+use vm::VmBlockResult;
+use zksync_types::Transaction;
+
+use super::{
+    BatchExecutorHandle, Command, L1BatchExecutorBuilder, TxExecutionResult, UnexecutableReason,
+};
+use crate::state_keeper::io::L1BatchParams;
+
+/// [`L1BatchExecutorBuilder`] isolating the state keeper from panics in batch executors created
+/// by the wrapped builder.
+///
+/// Batch executors run on dedicated blocking threads, so a VM panic only terminates the executor
+/// thread. Without supervision, such a panic is propagated to the state keeper and takes down
+/// the whole server. With supervision, the crashed executor is replaced with a new one for the same
+/// L1 batch, and all transactions executed in the batch so far are re-executed in it.
+/// The transaction that caused the panic is reported as unexecutable, so that it's rejected
+/// by the state keeper.
+#[derive(Debug)]
+pub struct SupervisedBatchExecutorBuilder {
+    inner: Arc<dyn L1BatchExecutorBuilder>,
+    max_restarts_per_batch: usize,
+}
+
+impl SupervisedBatchExecutorBuilder {
+    pub fn new(inner: Box<dyn L1BatchExecutorBuilder>, max_restarts_per_batch: usize) -> Self {
+        Self {
+            inner: inner.into(),
+            max_restarts_per_batch,
+        }
+    }
+}
+
+#[async_trait]
+impl L1BatchExecutorBuilder for SupervisedBatchExecutorBuilder {
+    async fn init_batch(&self, l1_batch_params: L1BatchParams) -> BatchExecutorHandle {
+        let executor = self.inner.init_batch(l1_batch_params.clone()).await;
+        let supervisor = BatchExecutorSupervisor {
+            builder: self.inner.clone(),
+            l1_batch_params,
+            executor,
+            executed_txs: vec![],
+            restarts: 0,
+            max_restarts: self.max_restarts_per_batch,
+        };
+
+        // Since we process `BatchExecutor` commands one-by-one, capacity 1 is enough for the commands channel.
+        let (commands_sender, commands_receiver) = mpsc::channel(1);
+        let handle = tokio::spawn(supervisor.run(commands_receiver));
+        BatchExecutorHandle {
+            handle,
+            commands: commands_sender,
+        }
+    }
+}
+
+/// Error returned when the supervised batch executor has terminated without responding to a command.
+#[derive(Debug)]
+struct ExecutorCrashed;
+
+/// Proxies commands to a batch executor, restarting the executor if it crashes.
+#[derive(Debug)]
+struct BatchExecutorSupervisor {
+    builder: Arc<dyn L1BatchExecutorBuilder>,
+    l1_batch_params: L1BatchParams,
+    executor: BatchExecutorHandle,
+    /// Transactions executed by the current executor in the order of execution. `None` entries
+    /// correspond to transactions that have crashed the executor; they need to be rolled back
+    /// by the state keeper, but aren't present in the restarted executor.
+    executed_txs: Vec<Option<Transaction>>,
+    restarts: usize,
+    max_restarts: usize,
+}
+
+impl BatchExecutorSupervisor {
+    async fn run(mut self, mut commands: mpsc::Receiver<Command>) {
+        while let Some(cmd) = commands.recv().await {
+            match cmd {
+                Command::ExecuteTx(tx, resp) => {
+                    let result = self.execute_tx(*tx).await;
+                    resp.send(result).ok();
+                }
+                Command::RollbackLastTx(resp) => {
+                    self.rollback_last_tx().await;
+                    resp.send(()).ok();
+                }
+                Command::FinishBatch(resp) => {
+                    let result = self.finish_batch().await;
+                    resp.send(result).ok();
+                    return;
+                }
+            }
+        }
+    }
+
+    async fn execute_tx(&mut self, tx: Transaction) -> TxExecutionResult {
+        let tx_clone = tx.clone();
+        let command = |resp| Command::ExecuteTx(Box::new(tx_clone), resp);
+        match send_command(&self.executor, command).await {
+            Ok(result) => {
+                self.executed_txs.push(Some(tx));
+                result
+            }
+            Err(ExecutorCrashed) => {
+                vlog::error!(
+                    "Batch executor crashed while executing transaction {:?}; restarting the executor",
+                    tx.hash()
+                );
+                self.restart_executor().await;
+                // The state keeper will roll back the transaction after it's reported unexecutable.
+                self.executed_txs.push(None);
+                TxExecutionResult::Unexecutable {
+                    reason: UnexecutableReason::ExecutorCrashed,
+                }
+            }
+        }
+    }
+
+    async fn rollback_last_tx(&mut self) {
+        let Some(last_tx) = self.executed_txs.pop() else {
+            panic!("Requested to roll back a transaction, but no transactions were executed");
+        };
+        if last_tx.is_none() {
+            // The transaction has crashed the previous executor, so it's not present in the current one.
+            return;
+        }
+        if send_command(&self.executor, Command::RollbackLastTx)
+            .await
+            .is_err()
+        {
+            vlog::error!(
+                "Batch executor crashed while rolling back a transaction; restarting the executor"
+            );
+            // The restarted executor re-executes all transactions except for the rolled back one.
+            self.restart_executor().await;
+        }
+    }
+
+    async fn finish_batch(&mut self) -> VmBlockResult {
+        loop {
+            match send_command(&self.executor, Command::FinishBatch).await {
+                Ok(result) => {
+                    // The executor terminates after finishing the batch; we don't care about its result.
+                    return result;
+                }
+                Err(ExecutorCrashed) => {
+                    vlog::error!(
+                        "Batch executor crashed while finishing the batch; restarting the executor"
+                    );
+                    self.restart_executor().await;
+                }
+            }
+        }
+    }
+
+    /// Replaces the crashed executor with a new one and re-executes all transactions executed
+    /// in the batch so far.
+    async fn restart_executor(&mut self) {
+        self.restarts += 1;
+        assert!(
+            self.restarts <= self.max_restarts,
+            "Batch executor crashed {} times during a single L1 batch, giving up",
+            self.restarts
+        );
+        metrics::increment_counter!("server.state_keeper.batch_executor_restarts");
+
+        self.executor = self.builder.init_batch(self.l1_batch_params.clone()).await;
+        let txs_to_reexecute = self.executed_txs.iter().flatten().cloned();
+        for tx in txs_to_reexecute {
+            let tx_hash = tx.hash();
+            let command = |resp| Command::ExecuteTx(Box::new(tx), resp);
+            let result = send_command(&self.executor, command)
+                .await
+                .expect("Batch executor crashed while re-executing transactions");
+            assert!(
+                matches!(result, TxExecutionResult::Success { .. }),
+                "Re-executing transaction {tx_hash:?} after batch executor restart failed: {:?}",
+                result.err()
+            );
+        }
+        vlog::info!(
+            "Restarted batch executor and re-executed {} transactions",
+            self.executed_txs.iter().flatten().count()
+        );
+    }
+}
+
+async fn send_command<T>(
+    executor: &BatchExecutorHandle,
+    command: impl FnOnce(oneshot::Sender<T>) -> Command,
+) -> Result<T, ExecutorCrashed> {
+    let (response_sender, response_receiver) = oneshot::channel();
+    executor
+        .commands
+        .send(command(response_sender))
+        .await
+        .map_err(|_| ExecutorCrashed)?;
+    response_receiver.await.map_err(|_| ExecutorCrashed)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+    use crate::state_keeper::{
+        testonly::{InMemoryIO, MockBatchExecutorBuilder, MockTxOutcome},
+        tests::create_transaction,
+    };
+
+    #[tokio::test]
+    async fn executor_is_restarted_after_panic() {
+        let mock_builder = MockBatchExecutorBuilder::new();
+        let builder = SupervisedBatchExecutorBuilder::new(Box::new(mock_builder.clone()), 1);
+        let (io, _) = InMemoryIO::new();
+        let executor = builder.init_batch(io.l1_batch_params(1)).await;
+
+        let (first_tx, crashing_tx) = (create_transaction(10, 100), create_transaction(10, 100));
+        mock_builder.push_outcome(crashing_tx.hash(), MockTxOutcome::Panic);
+
+        let result = executor.execute_tx(first_tx).await;
+        assert_matches!(result, TxExecutionResult::Success { .. });
+        let result = executor.execute_tx(crashing_tx.clone()).await;
+        assert_matches!(
+            result,
+            TxExecutionResult::Unexecutable {
+                reason: UnexecutableReason::ExecutorCrashed
+            }
+        );
+        executor.rollback_last_tx().await;
+
+        // The transaction is no longer scripted to panic.
+        let result = executor.execute_tx(crashing_tx).await;
+        assert_matches!(result, TxExecutionResult::Success { .. });
+        executor.finish_batch().await;
+    }
+}
@@ -2,12 +2,21 @@ use assert_matches::assert_matches;
 use db_test_macro::db_test;
 
 use zksync_dal::ConnectionPool;
-use zksync_types::{tx::tx_execution_info::TxExecutionStatus, PriorityOpId};
+use zksync_object_store::{ObjectStore, ObjectStoreFactory};
+use zksync_state::BytecodeCache;
+use zksync_types::{
+    proofs::L1BatchVmArtifacts, system_contracts::get_system_smart_contracts,
+    tx::tx_execution_info::TxExecutionStatus, utils::storage_key_for_standard_token_balance,
+    AccountTreeId, L1BatchNumber, PriorityOpId, L2_ETH_TOKEN_ADDRESS,
+};
+use zksync_utils::bytecode::hash_bytecode;
 
 mod tester;
 
+use std::{sync::Arc, time::Duration};
+
 use self::tester::{Account, Tester};
-use super::TxExecutionResult;
+use super::{TxExecutionResult, UnexecutableReason};
 use crate::state_keeper::batch_executor::tests::tester::TestConfig;
 
 /// Ensures that the transaction was executed successfully.
@@ -215,6 +224,71 @@ async fn deploy_and_call_loadtest(connection_pool: ConnectionPool) {
     executor.finish_batch().await;
 }
 
+/// Checks that bytecodes decommitted by the batch executor are put into the shared cache.
+#[db_test]
+async fn bytecodes_are_shared_via_cache(connection_pool: ConnectionPool) {
+    let mut alice = Account::random();
+    let cache = BytecodeCache::new("test_bytecode_cache", 16);
+
+    let mut config = TestConfig::new();
+    config.bytecode_cache = Some(cache.clone());
+    let tester = Tester::with_config(connection_pool, config);
+    tester.genesis().await;
+    tester.fund(&[alice.address()]).await;
+
+    let eth_token_contract = get_system_smart_contracts()
+        .into_iter()
+        .find(|contract| *contract.account_id.address() == L2_ETH_TOKEN_ADDRESS)
+        .unwrap();
+    let eth_token_hash = hash_bytecode(&eth_token_contract.bytecode);
+    assert!(cache.get(&eth_token_hash).is_none());
+
+    // Paying fees requires decommitting the ETH token contract.
+    let executor = tester.create_batch_executor().await;
+    assert_executed(&executor.execute_tx(alice.execute()).await);
+    executor.finish_batch().await;
+    assert!(cache.get(&eth_token_hash).is_some());
+}
+
+/// Checks that VM run artifacts are saved to the object store when the batch is finished.
+#[db_test]
+async fn vm_artifacts_are_saved(connection_pool: ConnectionPool) {
+    let mut alice = Account::random();
+    let store: Arc<dyn ObjectStore> = ObjectStoreFactory::mock().create_store().await.into();
+
+    let mut config = TestConfig::new();
+    config.vm_artifacts_store = Some(store.clone());
+    let tester = Tester::with_config(connection_pool, config);
+    tester.genesis().await;
+    tester.fund(&[alice.address()]).await;
+
+    let executor = tester.create_batch_executor().await;
+    assert_executed(&executor.execute_tx(alice.execute()).await);
+    let block_result = executor.finish_batch().await;
+
+    let artifacts: L1BatchVmArtifacts = store.get(L1BatchNumber(1)).await.unwrap();
+    let alice_balance_key = storage_key_for_standard_token_balance(
+        AccountTreeId::new(L2_ETH_TOKEN_ADDRESS),
+        &alice.address(),
+    );
+    assert!(artifacts
+        .storage_reads
+        .iter()
+        .any(|(key, value)| *key == alice_balance_key && !value.is_zero()));
+    let used_hashes: Vec<_> = artifacts
+        .used_bytecodes
+        .iter()
+        .map(|(hash, _)| *hash)
+        .collect();
+    assert_eq!(
+        used_hashes.len(),
+        block_result.full_result.used_contract_hashes.len()
+    );
+    for hash in &block_result.full_result.used_contract_hashes {
+        assert!(used_hashes.contains(hash));
+    }
+}
+
 /// Checks that a tx that is reverted by the VM still can be included into a batch.
 #[db_test]
 async fn execute_reverted_tx(connection_pool: ConnectionPool) {
@@ -298,6 +372,9 @@ async fn bootloader_out_of_gas_for_any_tx(connection_pool: ConnectionPool) {
             vm_gas_limit: Some(10),
             max_allowed_tx_gas_limit: u32::MAX,
             validation_computational_gas_limit: u32::MAX,
+            tx_execution_timeout: None,
+            bytecode_cache: None,
+            vm_artifacts_store: None,
         },
     );
 
@@ -311,6 +388,41 @@ async fn bootloader_out_of_gas_for_any_tx(connection_pool: ConnectionPool) {
     executor.finish_batch().await;
 }
 
+/// Checks that a tx exceeding the execution time budget is reported as unexecutable,
+/// and the batch can be continued afterwards.
+#[db_test]
+async fn tx_execution_timeout(connection_pool: ConnectionPool) {
+    let mut alice = Account::random();
+
+    let tester = Tester::with_config(
+        connection_pool,
+        TestConfig {
+            save_call_traces: false,
+            vm_gas_limit: None,
+            max_allowed_tx_gas_limit: u32::MAX,
+            validation_computational_gas_limit: u32::MAX,
+            tx_execution_timeout: Some(Duration::ZERO),
+            bytecode_cache: None,
+            vm_artifacts_store: None,
+        },
+    );
+
+    tester.genesis().await;
+    tester.fund(&[alice.address()]).await;
+    let executor = tester.create_batch_executor().await;
+
+    let res = executor.execute_tx(alice.execute()).await;
+    assert_matches!(
+        res,
+        TxExecutionResult::Unexecutable {
+            reason: UnexecutableReason::Timeout(timeout),
+        } if timeout == Duration::ZERO
+    );
+    executor.rollback_last_tx().await;
+
+    executor.finish_batch().await;
+}
+
 /// Checks that we can handle the bootloader out of gas error on tip phase.
 #[db_test]
 #[ignore] // This test fails.
@@ -335,6 +447,9 @@ async fn bootloader_tip_out_of_gas(connection_pool: ConnectionPool) {
         vm_gas_limit: Some(vm_block_res.full_result.gas_used - 10),
         max_allowed_tx_gas_limit: u32::MAX,
         validation_computational_gas_limit: u32::MAX,
+        tx_execution_timeout: None,
+        bytecode_cache: None,
+        vm_artifacts_store: None,
     });
 
     let second_executor = tester.create_batch_executor().await;
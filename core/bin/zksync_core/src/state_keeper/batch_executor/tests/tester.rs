@@ -3,6 +3,8 @@
 
 use tempfile::TempDir;
 
+use std::{sync::Arc, time::Duration};
+
 use vm::{
     test_utils::{
         get_create_zksync_address, get_deploy_tx, mock_loadnext_gas_burn_call,
@@ -18,7 +20,8 @@ use zksync_config::configs::chain::StateKeeperConfig;
 
 use zksync_contracts::{get_loadnext_contract, TestContract};
 use zksync_dal::ConnectionPool;
-use zksync_state::RocksdbStorage;
+use zksync_object_store::ObjectStore;
+use zksync_state::{BytecodeCache, RocksdbStorage};
 use zksync_types::{
     ethabi::{encode, Token},
     fee::Fee,
@@ -49,6 +52,9 @@ pub(super) struct TestConfig {
     pub(super) vm_gas_limit: Option<u32>,
     pub(super) max_allowed_tx_gas_limit: u32,
     pub(super) validation_computational_gas_limit: u32,
+    pub(super) tx_execution_timeout: Option<Duration>,
+    pub(super) bytecode_cache: Option<BytecodeCache>,
+    pub(super) vm_artifacts_store: Option<Arc<dyn ObjectStore>>,
 }
 
 impl TestConfig {
@@ -61,6 +67,9 @@ impl TestConfig {
             save_call_traces: false,
             max_allowed_tx_gas_limit: config.max_allowed_l2_tx_gas_limit,
             validation_computational_gas_limit: config.validation_computational_gas_limit,
+            tx_execution_timeout: None,
+            bytecode_cache: None,
+            vm_artifacts_store: None,
         }
     }
 }
@@ -110,13 +119,17 @@ impl Tester {
             self.config.save_call_traces,
             self.config.max_allowed_tx_gas_limit.into(),
             self.config.validation_computational_gas_limit,
+            self.config.tx_execution_timeout,
             secondary_storage,
             L1BatchParams {
                 context_mode: block_context,
                 properties: block_properties,
                 base_system_contracts: BASE_SYSTEM_CONTRACTS.clone(),
+                protocol_version: None,
             },
             self.config.vm_gas_limit,
+            self.config.bytecode_cache.clone(),
+            self.config.vm_artifacts_store.clone(),
         )
     }
 
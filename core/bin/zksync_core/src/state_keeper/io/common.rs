@@ -6,7 +6,7 @@ use vm::{
 };
 use zksync_contracts::BaseSystemContracts;
 use zksync_dal::StorageProcessor;
-use zksync_types::{Address, L1BatchNumber, U256, ZKPORTER_IS_AVAILABLE};
+use zksync_types::{Address, L1BatchNumber, ProtocolVersionId, U256, ZKPORTER_IS_AVAILABLE};
 use zksync_utils::h256_to_u256;
 
 use super::{L1BatchParams, PendingBatchData};
@@ -21,6 +21,7 @@ pub(crate) fn l1_batch_params(
     l1_gas_price: u64,
     fair_l2_gas_price: u64,
     base_system_contracts: BaseSystemContracts,
+    protocol_version: Option<ProtocolVersionId>,
 ) -> L1BatchParams {
     let block_properties = BlockProperties {
         default_aa_code_hash: h256_to_u256(base_system_contracts.default_aa.hash),
@@ -39,6 +40,7 @@ pub(crate) fn l1_batch_params(
         context_mode: BlockContextMode::NewBlock(context.into(), previous_block_hash),
         properties: block_properties,
         base_system_contracts,
+        protocol_version,
     }
 }
 
@@ -56,6 +58,7 @@ pub(crate) async fn load_pending_batch(
     storage: &mut StorageProcessor<'_>,
     current_l1_batch_number: L1BatchNumber,
     fee_account: Address,
+    protocol_version: Option<ProtocolVersionId>,
 ) -> Option<PendingBatchData> {
     // If pending miniblock doesn't exist, it means that there is no unsynced state (i.e. no transaction
     // were executed after the last sealed batch).
@@ -87,6 +90,18 @@ pub(crate) async fn load_pending_batch(
                 .default_aa,
         )
         .await;
+    // The pending batch must be re-executed with the same protocol version it was started with.
+    let protocol_version = storage
+        .blocks_dal()
+        .get_miniblock_protocol_version(pending_miniblock_number)
+        .await
+        .or(protocol_version);
+    // Likewise, the operator address may have been rotated since the pending batch was opened.
+    let fee_account = storage
+        .blocks_dal()
+        .get_miniblock_fee_account_address(pending_miniblock_number)
+        .await
+        .unwrap_or(fee_account);
 
     vlog::info!("Previous l1_batch_hash: {}", previous_l1_batch_hash);
     let params = l1_batch_params(
@@ -97,14 +112,23 @@ pub(crate) async fn load_pending_batch(
         pending_miniblock_header.l1_gas_price,
         pending_miniblock_header.l2_fair_gas_price,
         base_system_contracts,
+        protocol_version,
     );
 
     let txs = storage
         .transactions_dal()
         .get_transactions_to_reexecute()
         .await;
+    let expected_executions = storage
+        .transactions_dal()
+        .get_persisted_executions_to_reexecute()
+        .await;
 
-    Some(PendingBatchData { params, txs })
+    Some(PendingBatchData {
+        params,
+        txs,
+        expected_executions,
+    })
 }
 
 #[cfg(test)]
@@ -1,7 +1,6 @@
 use async_trait::async_trait;
 
 use std::{
-    cmp,
     collections::HashMap,
     sync::Arc,
     time::{Duration, Instant},
@@ -11,46 +10,105 @@ use vm::{
     vm_with_bootloader::{derive_base_fee_and_gas_per_pubdata, DerivedBlockContext},
     VmBlockResult,
 };
-use zksync_config::configs::chain::StateKeeperConfig;
-use zksync_contracts::BaseSystemContracts;
+use zksync_config::configs::chain::{StateKeeperConfig, StorageLogAnomalyPolicy, TreeLagPolicy};
+use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes};
 use zksync_dal::ConnectionPool;
 use zksync_mempool::L2TxFilter;
-use zksync_types::{Address, L1BatchNumber, MiniblockNumber, Transaction, U256};
-use zksync_utils::time::millis_since_epoch;
+use zksync_types::{
+    event::EventIndexingPolicy, tx::TxRejection, Address, L1BatchNumber, MiniblockNumber,
+    ProtocolVersionId, Transaction, U256,
+};
 
 use crate::{
-    l1_gas_price::L1GasPriceProvider,
+    chain_contracts::ChainContracts,
+    l1_gas_price::{cap_l1_gas_price_by_base_fee, L1GasPriceProvider},
     state_keeper::{
         extractors,
         io::{
             common::{l1_batch_params, load_pending_batch, poll_iters},
+            operator_address::OperatorAddressSource,
+            seal_logic::remove_unsealed_miniblocks,
+            timestamps::MiniblockTimestampPolicy,
             L1BatchParams, MiniblockSealerHandle, PendingBatchData, StateKeeperIO,
         },
-        mempool_actor::l2_tx_filter,
+        mempool_actor::{l2_tx_filter, l2_tx_filter_for_gas_price, MempoolRemovalNotifier},
         updates::UpdatesManager,
         MempoolGuard,
     },
 };
 
+/// Limit on the Merkle tree lag, i.e. the number of sealed L1 batches not yet processed by the tree.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct TreeLagLimit {
+    pub max_lag: u32,
+    pub policy: TreeLagPolicy,
+    pub throttle_delay: Duration,
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) enum TreeLagAction {
+    Proceed,
+    Pause,
+    Throttle(Duration),
+}
+
+impl TreeLagLimit {
+    pub(super) fn action(&self, lag: u32) -> TreeLagAction {
+        if lag <= self.max_lag {
+            return TreeLagAction::Proceed;
+        }
+        match self.policy {
+            TreeLagPolicy::Pause => TreeLagAction::Pause,
+            TreeLagPolicy::Throttle => {
+                TreeLagAction::Throttle(self.throttle_delay * (lag - self.max_lag))
+            }
+        }
+    }
+}
+
 /// Mempool-based IO for the state keeper.
 /// Receives transactions from the database through the mempool filtering logic.
 /// Decides which batch parameters should be used for the new batch.
 /// This is an IO for the main server application.
 #[derive(Debug)]
-pub(crate) struct MempoolIO<G> {
+pub struct MempoolIO<G> {
     mempool: MempoolGuard,
     pool: ConnectionPool,
     filter: L2TxFilter,
     current_miniblock_number: MiniblockNumber,
+    /// Timestamp of the last miniblock (including the one currently being processed).
+    prev_miniblock_timestamp: u64,
+    timestamp_policy: MiniblockTimestampPolicy,
     miniblock_sealer_handle: MiniblockSealerHandle,
+    /// Notifies the mempool fetcher about rejected transactions, so that it stops tracking them.
+    removal_notifier: Option<MempoolRemovalNotifier>,
     current_l1_batch_number: L1BatchNumber,
+    /// Operator address used in the current L1 batch.
     fee_account: Address,
+    fee_account_source: OperatorAddressSource,
     fair_l2_gas_price: u64,
+    /// Max relative change of the base fee between consecutive L1 batches.
+    max_base_fee_change: Option<f64>,
+    /// Base fee of the last sealed L1 batch.
+    prev_base_fee: u64,
+    /// Backpressure applied if the Merkle tree falls too far behind; `None` if the tree lag isn't limited.
+    tree_lag_limit: Option<TreeLagLimit>,
+    /// Instant until which opening the current L1 batch is throttled because of the tree lag.
+    throttled_until: Option<Instant>,
     delay_interval: Duration,
     // Used to keep track of gas prices to set accepted price per pubdata byte in blocks.
     l1_gas_price_provider: Arc<G>,
     base_system_contracts: BaseSystemContracts,
-    l2_erc20_bridge_addr: Address,
+    protocol_version: ProtocolVersionId,
+    chain_contracts: ChainContracts,
+    max_tx_output_size: usize,
+    max_tx_revert_reason_size: usize,
+    event_indexing: EventIndexingPolicy,
+    storage_log_anomaly_policy: StorageLogAnomalyPolicy,
+    save_tx_access_sets: bool,
+    miniblock_gas_limit: Option<u64>,
+    /// Whether the current L1 batch is built in the priority mode, i.e., consists only of L1 transactions.
+    is_priority_mode: bool,
 }
 
 #[async_trait]
@@ -66,12 +124,21 @@ impl<G: L1GasPriceProvider + 'static + Send + Sync> StateKeeperIO for MempoolIO<
     async fn load_pending_batch(&mut self) -> Option<PendingBatchData> {
         let mut storage = self.pool.access_storage_tagged("state_keeper").await;
 
-        let PendingBatchData { params, txs } =
-            load_pending_batch(&mut storage, self.current_l1_batch_number, self.fee_account)
-                .await?;
+        let pending_batch = load_pending_batch(
+            &mut storage,
+            self.current_l1_batch_number,
+            self.fee_account,
+            Some(self.protocol_version),
+        )
+        .await?;
         // Initialize the filter for the transactions that come after the pending batch.
         // We use values from the pending block to match the filter with one used before the restart.
-        let context = params.context_mode.inner_block_context().context;
+        let context = pending_batch
+            .params
+            .context_mode
+            .inner_block_context()
+            .context;
+        self.fee_account = context.operator_address;
         let (base_fee, gas_per_pubdata) =
             derive_base_fee_and_gas_per_pubdata(context.l1_gas_price, context.fair_l2_gas_price);
         self.filter = L2TxFilter {
@@ -80,31 +147,70 @@ impl<G: L1GasPriceProvider + 'static + Send + Sync> StateKeeperIO for MempoolIO<
             gas_per_pubdata: gas_per_pubdata as u32,
         };
 
-        Some(PendingBatchData { params, txs })
+        Some(pending_batch)
     }
 
     async fn wait_for_new_batch_params(&mut self, max_wait: Duration) -> Option<L1BatchParams> {
         let deadline = Instant::now() + max_wait;
+        let mut wait_stats = BatchParamsWaitStats::default();
+        self.update_priority_mode().await;
 
         // Block until at least one transaction in the mempool can match the filter (or timeout happens).
         // This is needed to ensure that block timestamp is not too old.
-        for _ in 0..poll_iters(self.delay_interval, max_wait) {
+        loop {
             // We create a new filter each time, since parameters may change and a previously
             // ignored transaction in the mempool may be scheduled for the execution.
-            self.filter = l2_tx_filter(self.l1_gas_price_provider.as_ref(), self.fair_l2_gas_price);
+            self.filter = self.capped_filter(l2_tx_filter(
+                self.l1_gas_price_provider.as_ref(),
+                self.fair_l2_gas_price,
+            ));
             // We only need to get the root hash when we're certain that we have a new transaction.
-            if !self.mempool.has_next(&self.filter) {
+            if !self.has_next_transaction() {
+                let wait_started_at = Instant::now();
+                if wait_started_at >= deadline {
+                    break;
+                }
+                let stats = self.mempool.stats();
+                let is_idle = stats.l1_transactions == 0
+                    && stats.pending_l2_transactions == 0
+                    && stats.queued_l2_transactions == 0;
+                // Wake up as soon as new transactions arrive, but re-check the filter periodically anyway
+                // since the gas price may change, making the existing mempool transactions eligible.
+                let wait_duration = self.delay_interval.min(deadline - wait_started_at);
+                tokio::time::timeout(wait_duration, self.mempool.wait_for_new_transactions())
+                    .await
+                    .ok();
+                wait_stats.record(is_idle, wait_started_at.elapsed());
+                continue;
+            }
+            if !self.wait_for_tree_lag(deadline).await {
+                if Instant::now() >= deadline {
+                    break;
+                }
+                continue;
+            }
+            if !self.update_protocol_version().await {
+                if Instant::now() >= deadline {
+                    break;
+                }
                 tokio::time::sleep(self.delay_interval).await;
                 continue;
             }
+            wait_stats.report();
 
             let (prev_hash, prev_timestamp) = self.load_previous_l1_batch_params().await;
             // We cannot create two L1 batches with the same timestamp (forbidden by the bootloader).
-            // Hence, we wait until the current timestamp is larger. We can use `timeout_at`
-            // since `sleep_past` is cancel-safe; it only uses `sleep()` async calls.
-            let current_timestamp =
-                tokio::time::timeout_at(deadline.into(), sleep_past(prev_timestamp));
-            let current_timestamp = current_timestamp.await.ok()?;
+            // The L1 batch timestamp is also the timestamp of its first miniblock, so it must follow
+            // the timestamp of the last miniblock in the previous batch.
+            let prev_timestamp = prev_timestamp.max(self.prev_miniblock_timestamp);
+            let current_timestamp = self
+                .timestamp_policy
+                .wait_for_next_timestamp(prev_timestamp, deadline)
+                .await?;
+            self.prev_miniblock_timestamp = current_timestamp;
+            self.throttled_until = None;
+            // The operator address may have been rotated since the previous batch.
+            self.fee_account = self.fee_account_source.current();
 
             vlog::info!(
                 "(l1_gas_price, fair_l2_gas_price) for L1 batch #{} is ({}, {})",
@@ -120,20 +226,33 @@ impl<G: L1GasPriceProvider + 'static + Send + Sync> StateKeeperIO for MempoolIO<
                 self.filter.l1_gas_price,
                 self.fair_l2_gas_price,
                 self.base_system_contracts.clone(),
+                Some(self.protocol_version),
             ));
         }
+        wait_stats.report();
         None
     }
 
-    async fn wait_for_new_miniblock_params(&mut self, _max_wait: Duration) -> Option<u64> {
-        let new_miniblock_timestamp = (millis_since_epoch() / 1000) as u64;
+    async fn wait_for_new_miniblock_params(&mut self, max_wait: Duration) -> Option<u64> {
+        let deadline = Instant::now() + max_wait;
+        let new_miniblock_timestamp = self
+            .timestamp_policy
+            .wait_for_next_timestamp(self.prev_miniblock_timestamp, deadline)
+            .await?;
+        self.prev_miniblock_timestamp = new_miniblock_timestamp;
         Some(new_miniblock_timestamp)
     }
 
     async fn wait_for_next_tx(&mut self, max_wait: Duration) -> Option<Transaction> {
         for _ in 0..poll_iters(self.delay_interval, max_wait) {
             let started_at = Instant::now();
-            let res = self.mempool.next_transaction(&self.filter);
+            let res = if self.is_priority_mode {
+                self.mempool.next_l1_transaction()
+            } else {
+                // `prev_miniblock_timestamp` is the timestamp of the currently open miniblock.
+                self.mempool
+                    .next_transaction(&self.filter, self.prev_miniblock_timestamp)
+            };
             metrics::histogram!(
                 "server.state_keeper.get_tx_from_mempool",
                 started_at.elapsed(),
@@ -155,11 +274,11 @@ impl<G: L1GasPriceProvider + 'static + Send + Sync> StateKeeperIO for MempoolIO<
         self.mempool.insert(vec![tx], HashMap::new());
     }
 
-    async fn reject(&mut self, rejected: &Transaction, error: &str) {
+    async fn reject(&mut self, rejected: &Transaction, rejection: &TxRejection) {
         assert!(
             !rejected.is_l1(),
             "L1 transactions should not be rejected: {}",
-            error
+            rejection
         );
 
         // Reset the nonces in the mempool, but don't insert the transaction back.
@@ -171,20 +290,27 @@ impl<G: L1GasPriceProvider + 'static + Send + Sync> StateKeeperIO for MempoolIO<
         vlog::warn!(
             "transaction {} is rejected with error {}",
             rejected.hash(),
-            error
+            rejection
         );
         storage
             .transactions_dal()
-            .mark_tx_as_rejected(rejected.hash(), &format!("rejected: {}", error))
+            .mark_tx_as_rejected(rejected.hash(), rejection)
             .await;
+        if let Some(notifier) = &self.removal_notifier {
+            notifier.notify(vec![rejected.hash()]);
+        }
     }
 
     async fn seal_miniblock(&mut self, updates_manager: &UpdatesManager) {
-        let command = updates_manager.seal_miniblock_command(
+        let mut command = updates_manager.seal_miniblock_command(
             self.current_l1_batch_number,
             self.current_miniblock_number,
-            self.l2_erc20_bridge_addr,
+            self.chain_contracts,
+            &self.event_indexing,
+            self.storage_log_anomaly_policy,
         );
+        command.gas_limit = self.miniblock_gas_limit;
+        command.truncate_tx_results(self.max_tx_output_size, self.max_tx_revert_reason_size);
         self.miniblock_sealer_handle.submit(command).await;
         self.current_miniblock_number += 1;
     }
@@ -214,95 +340,290 @@ impl<G: L1GasPriceProvider + 'static + Send + Sync> StateKeeperIO for MempoolIO<
                 self.current_l1_batch_number,
                 block_result,
                 block_context,
-                self.l2_erc20_bridge_addr,
+                self.chain_contracts,
+                &self.event_indexing,
+                self.storage_log_anomaly_policy,
+                self.save_tx_access_sets,
+                self.miniblock_gas_limit,
             )
             .await;
+        self.prev_base_fee = block_context.base_fee;
         self.current_miniblock_number += 1; // Due to fictive miniblock being sealed.
         self.current_l1_batch_number += 1;
-    }
-}
 
-/// Sleeps until the current timestamp is larger than the provided `timestamp`.
-///
-/// Returns the current timestamp after the sleep. It is guaranteed to be larger than `timestamp`.
-async fn sleep_past(timestamp: u64) -> u64 {
-    let mut current_timestamp_millis = millis_since_epoch();
-    let mut current_timestamp = (current_timestamp_millis / 1_000) as u64;
-    match timestamp.cmp(&current_timestamp) {
-        cmp::Ordering::Less => return current_timestamp,
-        cmp::Ordering::Equal => {
+        // All transactions in the batch are persisted at this point, so it's safe to drop the mempool
+        // contents; the mempool fetcher will reload the remaining transactions from Postgres.
+        if self.mempool.take_resync_request() {
+            storage.transactions_dal().reset_mempool().await;
+            self.mempool.clear();
             vlog::info!(
-                "Current timestamp {} is equal to previous L1 batch timestamp; waiting until \
-                 timestamp increases",
-                extractors::display_timestamp(current_timestamp)
-            );
-        }
-        cmp::Ordering::Greater => {
-            // This situation can be triggered if the system keeper is started on a pod with a different
-            // system time, or if it is buggy. Thus, a one-time error could require no actions if L1 batches
-            // are expected to be generated frequently.
-            vlog::error!(
-                "Previous L1 batch timestamp {} is larger than the current timestamp {}",
-                extractors::display_timestamp(timestamp),
-                extractors::display_timestamp(current_timestamp)
+                "Mempool was reset after sealing L1 batch #{}",
+                self.current_l1_batch_number.0 - 1
             );
         }
     }
-
-    // This loop should normally run once, since `tokio::time::sleep` sleeps *at least* the specified duration.
-    // The logic is organized in a loop for marginal cases, such as the system time getting changed during `sleep()`.
-    loop {
-        // Time to catch up to `timestamp`; panic / underflow on subtraction is never triggered
-        // since we've ensured that `timestamp >= current_timestamp`.
-        let wait_seconds = timestamp - current_timestamp;
-        // Time to wait until the current timestamp increases.
-        let wait_millis = 1_001 - (current_timestamp_millis % 1_000) as u64;
-        let wait = Duration::from_millis(wait_millis + wait_seconds * 1_000);
-
-        tokio::time::sleep(wait).await;
-        current_timestamp_millis = millis_since_epoch();
-        current_timestamp = (current_timestamp_millis / 1_000) as u64;
-
-        if current_timestamp > timestamp {
-            return current_timestamp;
-        }
-    }
 }
 
 impl<G: L1GasPriceProvider> MempoolIO<G> {
-    pub(in crate::state_keeper) async fn new(
+    pub async fn new(
         mempool: MempoolGuard,
         miniblock_sealer_handle: MiniblockSealerHandle,
         l1_gas_price_provider: Arc<G>,
         pool: ConnectionPool,
         config: &StateKeeperConfig,
         delay_interval: Duration,
-        l2_erc20_bridge_addr: Address,
+        chain_contracts: ChainContracts,
     ) -> Self {
         let mut storage = pool.access_storage_tagged("state_keeper").await;
+        // Miniblocks partially persisted before the restart will be re-executed.
+        remove_unsealed_miniblocks(&mut storage).await;
         let last_sealed_block_header = storage.blocks_dal().get_newest_block_header().await;
         let last_miniblock_number = storage.blocks_dal().get_sealed_miniblock_number().await;
+        let prev_miniblock_timestamp = storage
+            .blocks_dal()
+            .get_last_sealed_miniblock_header()
+            .await
+            .map_or(0, |header| header.timestamp);
+        // The state keeper may have switched to a newer protocol version before the restart. In this case,
+        // the configured version and base system contracts are outdated, so they're taken from the last L1 batch.
+        let mut protocol_version = config.protocol_version();
+        let mut base_system_contracts_hashes = BaseSystemContractsHashes {
+            bootloader: config.bootloader_hash,
+            default_aa: config.default_aa_hash,
+        };
+        let last_batch_protocol_version = storage
+            .blocks_dal()
+            .get_l1_batch_protocol_version(last_sealed_block_header.number)
+            .await;
+        if let Some(version) = last_batch_protocol_version.filter(|&ver| ver > protocol_version) {
+            protocol_version = version;
+            base_system_contracts_hashes = last_sealed_block_header.base_system_contracts_hashes;
+        }
         let base_system_contracts = storage
             .storage_dal()
-            .get_base_system_contracts(config.bootloader_hash, config.default_aa_hash)
+            .get_base_system_contracts(
+                base_system_contracts_hashes.bootloader,
+                base_system_contracts_hashes.default_aa,
+            )
             .await;
         drop(storage);
 
+        let event_indexing = EventIndexingPolicy::parse(config.indexed_event_patterns())
+            .unwrap_or_else(|err| panic!("Invalid indexed event patterns in the config: {err}"));
+        let mut fee_account_source = OperatorAddressSource::new(config);
         Self {
             mempool,
             pool,
             filter: L2TxFilter::default(),
             // ^ Will be initialized properly on the first newly opened batch
+            is_priority_mode: false,
             current_l1_batch_number: last_sealed_block_header.number + 1,
             miniblock_sealer_handle,
+            removal_notifier: None,
             current_miniblock_number: last_miniblock_number + 1,
-            fee_account: config.fee_account_addr,
+            prev_miniblock_timestamp,
+            timestamp_policy: MiniblockTimestampPolicy::new(config),
+            fee_account: fee_account_source.current(),
+            fee_account_source,
             fair_l2_gas_price: config.fair_l2_gas_price,
+            max_base_fee_change: config.max_base_fee_change_per_batch,
+            prev_base_fee: last_sealed_block_header.base_fee_per_gas,
+            tree_lag_limit: config.max_tree_lag.map(|max_lag| TreeLagLimit {
+                max_lag,
+                policy: config.tree_lag_policy(),
+                throttle_delay: config.tree_lag_throttle_delay(),
+            }),
+            throttled_until: None,
             delay_interval,
             l1_gas_price_provider,
             base_system_contracts,
-            l2_erc20_bridge_addr,
+            protocol_version,
+            chain_contracts,
+            max_tx_output_size: config.max_tx_output_size(),
+            max_tx_revert_reason_size: config.max_tx_revert_reason_size(),
+            event_indexing,
+            storage_log_anomaly_policy: config.storage_log_anomaly_policy(),
+            save_tx_access_sets: config.save_tx_access_sets(),
+            miniblock_gas_limit: config.miniblock_gas_limit,
+        }
+    }
+
+    /// Makes the IO notify the mempool fetcher about rejected transactions.
+    pub fn with_removal_notifier(mut self, notifier: MempoolRemovalNotifier) -> Self {
+        self.removal_notifier = Some(notifier);
+        self
+    }
+
+    /// Caps the change of the base fee implied by `filter` relative to the previous L1 batch. The cap is applied
+    /// to the L1 gas price, since the base fee is derived from it and the fair L2 gas price, which is fixed.
+    fn capped_filter(&self, filter: L2TxFilter) -> L2TxFilter {
+        let Some(max_change) = self.max_base_fee_change else {
+            return filter;
+        };
+        if self.prev_base_fee == 0 {
+            return filter; // The previous batch is the genesis one, which has no meaningful base fee.
+        }
+
+        let l1_gas_price = cap_l1_gas_price_by_base_fee(
+            filter.l1_gas_price,
+            self.fair_l2_gas_price,
+            self.prev_base_fee,
+            max_change,
+        );
+        if l1_gas_price == filter.l1_gas_price {
+            return filter;
+        }
+        let capped_filter = l2_tx_filter_for_gas_price(l1_gas_price, self.fair_l2_gas_price);
+        vlog::debug!(
+            "Capped base fee for L1 batch #{}: {} -> {} (previous base fee: {})",
+            self.current_l1_batch_number,
+            filter.fee_per_gas,
+            capped_filter.fee_per_gas,
+            self.prev_base_fee
+        );
+        metrics::increment_counter!("server.state_keeper.capped_base_fee");
+        capped_filter
+    }
+
+    /// Applies backpressure if the Merkle tree falls too far behind. Returns `true` if the L1 batch can be opened,
+    /// or `false` if opening it should be retried later.
+    async fn wait_for_tree_lag(&mut self, deadline: Instant) -> bool {
+        let Some(limit) = self.tree_lag_limit else {
+            return true;
+        };
+        if self.throttled_until.is_none() {
+            let mut storage = self.pool.access_storage_tagged("state_keeper").await;
+            let last_l1_batch_with_metadata = storage
+                .blocks_dal()
+                .get_last_block_number_with_metadata()
+                .await;
+            drop(storage);
+            let last_sealed_l1_batch = self.current_l1_batch_number.0 - 1;
+            let lag = last_sealed_l1_batch.saturating_sub(last_l1_batch_with_metadata.0);
+            metrics::gauge!("server.state_keeper.tree_lag", lag as f64);
+
+            match limit.action(lag) {
+                TreeLagAction::Proceed => return true,
+                TreeLagAction::Pause => {
+                    vlog::debug!(
+                        "Paused opening L1 batch #{}: Merkle tree lags behind by {lag} L1 batches",
+                        self.current_l1_batch_number
+                    );
+                    metrics::increment_counter!("server.state_keeper.tree_lag_backpressure", "policy" => "pause");
+                    let wait_duration = self
+                        .delay_interval
+                        .min(deadline.saturating_duration_since(Instant::now()));
+                    tokio::time::sleep(wait_duration).await;
+                    return false;
+                }
+                TreeLagAction::Throttle(delay) => {
+                    vlog::debug!(
+                        "Throttling opening L1 batch #{} by {delay:?}: Merkle tree lags behind by {lag} L1 batches",
+                        self.current_l1_batch_number
+                    );
+                    metrics::increment_counter!("server.state_keeper.tree_lag_backpressure", "policy" => "throttle");
+                    self.throttled_until = Some(Instant::now() + delay);
+                }
+            }
+        }
+
+        // The throttling delay is persisted across calls, so that it's not reset if the wait times out.
+        let throttled_until = self.throttled_until.unwrap();
+        let now = Instant::now();
+        if now < throttled_until {
+            tokio::time::sleep(
+                (throttled_until - now).min(deadline.saturating_duration_since(now)),
+            )
+            .await;
+        }
+        Instant::now() >= throttled_until
+    }
+
+    /// Loads the priority mode state set by the priority operations watcher. In the priority mode,
+    /// only L1 transactions are included into the new L1 batch.
+    async fn update_priority_mode(&mut self) {
+        let mut storage = self.pool.access_storage_tagged("state_keeper").await;
+        let priority_mode = storage.eth_watcher_dal().get_priority_mode().await;
+        let is_priority_mode = priority_mode.is_some();
+        if is_priority_mode != self.is_priority_mode {
+            if let Some(first_expired_op_id) = priority_mode {
+                vlog::warn!(
+                    "Priority operation #{first_expired_op_id} has expired; L1 batch #{} will include only L1 transactions",
+                    self.current_l1_batch_number
+                );
+            } else {
+                vlog::info!(
+                    "Priority mode is deactivated starting from L1 batch #{}",
+                    self.current_l1_batch_number
+                );
+            }
         }
+        self.is_priority_mode = is_priority_mode;
+    }
+
+    fn has_next_transaction(&self) -> bool {
+        if self.is_priority_mode {
+            self.mempool.has_next_l1_transaction()
+        } else {
+            self.mempool.has_next(&self.filter)
+        }
+    }
+
+    /// Checks that the protocol version used by the state keeper matches the latest version registered on L1.
+    /// If a newer version is registered and its base system contracts are persisted, switches to this version;
+    /// the switch takes effect starting from the L1 batch being opened. If no versions are registered yet
+    /// (e.g., the L1 watcher hasn't caught up), the check passes.
+    async fn update_protocol_version(&mut self) -> bool {
+        let mut storage = self.pool.access_storage_tagged("state_keeper").await;
+        let Some(latest_version) = storage.protocol_versions_dal().latest_version_id().await else {
+            return true;
+        };
+        if latest_version == self.protocol_version {
+            return true;
+        }
+
+        if latest_version > self.protocol_version {
+            let hashes = storage
+                .protocol_versions_dal()
+                .get_base_system_contracts_hashes(latest_version)
+                .await;
+            let base_system_contracts = match hashes {
+                Some(hashes) => {
+                    storage
+                        .storage_dal()
+                        .try_get_base_system_contracts(hashes)
+                        .await
+                }
+                None => None,
+            };
+            if let Some(base_system_contracts) = base_system_contracts {
+                vlog::info!(
+                    "Switching from protocol version {} to {latest_version} starting from L1 batch #{}; \
+                     new base system contracts: {:?}",
+                    self.protocol_version,
+                    self.current_l1_batch_number,
+                    base_system_contracts.hashes()
+                );
+                self.protocol_version = latest_version;
+                self.base_system_contracts = base_system_contracts;
+                metrics::increment_counter!("server.state_keeper.protocol_upgrades");
+                return true;
+            }
+            vlog::error!(
+                "Refusing to open L1 batch #{}: base system contracts for protocol version {latest_version} \
+                 registered on L1 are not persisted yet",
+                self.current_l1_batch_number
+            );
+        } else {
+            vlog::error!(
+                "Refusing to open L1 batch #{}: protocol version {} used by the state keeper is newer than \
+                 the latest version {latest_version} registered on L1",
+                self.current_l1_batch_number,
+                self.protocol_version
+            );
+        }
+        metrics::increment_counter!("server.state_keeper.protocol_version_mismatch");
+        false
     }
 
     async fn load_previous_l1_batch_params(&self) -> (U256, u64) {
@@ -326,51 +647,43 @@ impl<G: L1GasPriceProvider> MempoolIO<G> {
     }
 }
 
-/// Getters required for testing the MempoolIO.
-#[cfg(test)]
-impl<G: L1GasPriceProvider> MempoolIO<G> {
-    pub(super) fn filter(&self) -> &L2TxFilter {
-        &self.filter
-    }
+/// Time spent by [`MempoolIO`] waiting for transactions to open a new L1 batch.
+#[derive(Debug, Default)]
+struct BatchParamsWaitStats {
+    /// Time during which the mempool was empty.
+    idle: Duration,
+    /// Time during which the mempool had transactions, but none of them matched the filter
+    /// (e.g., because of the gas price or nonce gaps).
+    waiting_for_eligible_txs: Duration,
 }
 
-#[cfg(test)]
-mod tests {
-    use tokio::time::timeout_at;
-
-    use zksync_utils::time::seconds_since_epoch;
-
-    use super::*;
-
-    // This test defensively uses large deadlines in order to account for tests running in parallel etc.
-    #[tokio::test]
-    async fn sleeping_past_timestamp() {
-        let past_timestamps = [0, 1_000, 1_000_000_000, seconds_since_epoch() - 10];
-        for timestamp in past_timestamps {
-            let deadline = Instant::now() + Duration::from_secs(1);
-            timeout_at(deadline.into(), sleep_past(timestamp))
-                .await
-                .unwrap();
+impl BatchParamsWaitStats {
+    fn record(&mut self, is_idle: bool, elapsed: Duration) {
+        if is_idle {
+            self.idle += elapsed;
+        } else {
+            self.waiting_for_eligible_txs += elapsed;
         }
+    }
 
-        let current_timestamp = seconds_since_epoch();
-        let deadline = Instant::now() + Duration::from_secs(2);
-        let ts = timeout_at(deadline.into(), sleep_past(current_timestamp))
-            .await
-            .unwrap();
-        assert!(ts > current_timestamp);
+    fn report(&self) {
+        metrics::histogram!(
+            "server.state_keeper.wait_for_batch_params",
+            self.idle,
+            "stage" => "idle"
+        );
+        metrics::histogram!(
+            "server.state_keeper.wait_for_batch_params",
+            self.waiting_for_eligible_txs,
+            "stage" => "waiting_for_eligible_txs"
+        );
+    }
+}
 
-        let future_timestamp = seconds_since_epoch() + 1;
-        let deadline = Instant::now() + Duration::from_secs(3);
-        let ts = timeout_at(deadline.into(), sleep_past(future_timestamp))
-            .await
-            .unwrap();
-        assert!(ts > future_timestamp);
-
-        let future_timestamp = seconds_since_epoch() + 1;
-        let deadline = Instant::now() + Duration::from_millis(100);
-        // ^ This deadline is too small (we need at least 1_000ms)
-        let result = timeout_at(deadline.into(), sleep_past(future_timestamp)).await;
-        assert!(result.is_err());
+/// Getters required for testing the MempoolIO.
+#[cfg(test)]
+impl<G: L1GasPriceProvider> MempoolIO<G> {
+    pub(super) fn filter(&self) -> &L2TxFilter {
+        &self.filter
     }
 }
@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{mpsc, oneshot, watch};
 
 use std::{
     fmt,
@@ -11,15 +11,30 @@ use vm::zk_evm::block_properties::BlockProperties;
 use vm::VmBlockResult;
 use zksync_contracts::BaseSystemContracts;
 use zksync_dal::ConnectionPool;
-use zksync_types::{L1BatchNumber, MiniblockNumber, Transaction};
+use zksync_types::{
+    tx::{tx_execution_info::PersistedTxExecution, TxRejection},
+    L1BatchNumber, MiniblockNumber, ProtocolVersionId, Transaction,
+};
 
 pub(crate) mod common;
 pub(crate) mod mempool;
+pub(crate) mod operator_address;
+pub(crate) mod replay;
 pub(crate) mod seal_logic;
+pub(crate) mod timestamps;
 
-pub(crate) use self::mempool::MempoolIO;
+pub use self::{
+    mempool::MempoolIO,
+    replay::{
+        read_replay_log, RecordingIO, ReplayEvent, ReplayIO, ReplayL1BatchParams, ReplaySegment,
+    },
+};
 
-use super::updates::{MiniblockSealCommand, UpdatesManager};
+use super::{
+    mempool_actor::MempoolRemovalNotifier,
+    pending_receipts::PendingReceipts,
+    updates::{MiniblockSealCommand, UpdatesManager},
+};
 
 #[cfg(test)]
 mod tests;
@@ -32,6 +47,9 @@ pub struct L1BatchParams {
     pub context_mode: BlockContextMode,
     pub properties: BlockProperties,
     pub base_system_contracts: BaseSystemContracts,
+    /// Protocol version the batch is produced for. `None` if the version is unknown
+    /// (e.g., for batches synced by the external node).
+    pub protocol_version: Option<ProtocolVersionId>,
 }
 
 /// Contains information about the un-synced execution state:
@@ -49,6 +67,9 @@ pub struct PendingBatchData {
     pub(crate) params: L1BatchParams,
     /// List of miniblocks and corresponding transactions that were executed within batch.
     pub(crate) txs: Vec<(MiniblockNumber, Vec<Transaction>)>,
+    /// Execution outcomes persisted for `txs` during the original execution. Re-executed transactions
+    /// are checked against these outcomes; transactions without a persisted outcome are not checked.
+    pub(crate) expected_executions: Vec<PersistedTxExecution>,
 }
 
 /// `StateKeeperIO` provides the interactive layer for the state keeper:
@@ -75,7 +96,7 @@ pub trait StateKeeperIO: 'static + Send {
     /// Marks the transaction as "not executed", so it can be retrieved from the IO again.
     async fn rollback(&mut self, tx: Transaction);
     /// Marks the transaction as "rejected", e.g. one that is not correct and can't be executed.
-    async fn reject(&mut self, tx: &Transaction, error: &str);
+    async fn reject(&mut self, tx: &Transaction, rejection: &TxRejection);
     /// Marks the miniblock (aka L2 block) as sealed.
     /// Returns the timestamp for the next miniblock.
     async fn seal_miniblock(&mut self, updates_manager: &UpdatesManager);
@@ -107,7 +128,7 @@ struct Completable<T> {
 
 /// Handle for [`MiniblockSealer`] allowing to submit [`MiniblockSealCommand`]s.
 #[derive(Debug)]
-pub(crate) struct MiniblockSealerHandle {
+pub struct MiniblockSealerHandle {
     commands_sender: mpsc::Sender<Completable<MiniblockSealCommand>>,
     latest_completion_receiver: Option<oneshot::Receiver<()>>,
     // If true, `submit()` will wait for the operation to complete.
@@ -121,7 +142,7 @@ impl MiniblockSealerHandle {
     ///
     /// If there are currently too many unprocessed commands, this method will wait until
     /// enough of them are processed (i.e., there is backpressure).
-    pub async fn submit(&mut self, command: MiniblockSealCommand) {
+    pub(crate) async fn submit(&mut self, command: MiniblockSealCommand) {
         let miniblock_number = command.miniblock_number;
         vlog::debug!(
             "Enqueuing sealing command for miniblock #{miniblock_number} with #{} txs (L1 batch #{})",
@@ -197,21 +218,26 @@ impl MiniblockSealerHandle {
 
 /// Component responsible for sealing miniblocks (i.e., storing their data to Postgres).
 #[derive(Debug)]
-pub(crate) struct MiniblockSealer {
+pub struct MiniblockSealer {
     pool: ConnectionPool,
     is_sync: bool,
     // Weak sender handle to get queue capacity stats.
     commands_sender: mpsc::WeakSender<Completable<MiniblockSealCommand>>,
     commands_receiver: mpsc::Receiver<Completable<MiniblockSealCommand>>,
+    /// Notified with the number of each miniblock once it's persisted.
+    seal_notifier: Option<watch::Sender<MiniblockNumber>>,
+    /// Notified with hashes of transactions in each miniblock once it's persisted.
+    removal_notifier: Option<MempoolRemovalNotifier>,
+    /// Populated with receipts of each miniblock once it's persisted.
+    pending_receipts: Option<PendingReceipts>,
+    /// Whether miniblocks are sealed with concurrent writes using multiple connections from `pool`.
+    parallel_seal: bool,
 }
 
 impl MiniblockSealer {
     /// Creates a sealer that will use the provided Postgres connection and will have the specified
     /// `command_capacity` for unprocessed sealing commands.
-    pub(crate) fn new(
-        pool: ConnectionPool,
-        mut command_capacity: usize,
-    ) -> (Self, MiniblockSealerHandle) {
+    pub fn new(pool: ConnectionPool, mut command_capacity: usize) -> (Self, MiniblockSealerHandle) {
         let is_sync = command_capacity == 0;
         command_capacity = command_capacity.max(1);
 
@@ -221,6 +247,10 @@ impl MiniblockSealer {
             is_sync,
             commands_sender: commands_sender.downgrade(),
             commands_receiver,
+            seal_notifier: None,
+            removal_notifier: None,
+            pending_receipts: None,
+            parallel_seal: false,
         };
         let handle = MiniblockSealerHandle {
             commands_sender,
@@ -230,6 +260,32 @@ impl MiniblockSealer {
         (this, handle)
     }
 
+    /// Makes the sealer report numbers of persisted miniblocks to the provided channel.
+    pub fn with_seal_notifier(mut self, notifier: watch::Sender<MiniblockNumber>) -> Self {
+        self.seal_notifier = Some(notifier);
+        self
+    }
+
+    /// Makes the sealer report transactions in persisted miniblocks to the mempool fetcher.
+    pub fn with_removal_notifier(mut self, notifier: MempoolRemovalNotifier) -> Self {
+        self.removal_notifier = Some(notifier);
+        self
+    }
+
+    /// Makes the sealer cache receipts of persisted miniblocks, so that they can be served by the API
+    /// without querying Postgres.
+    pub fn with_pending_receipts(mut self, pending_receipts: PendingReceipts) -> Self {
+        self.pending_receipts = Some(pending_receipts);
+        self
+    }
+
+    /// Makes the sealer perform independent writes for each miniblock concurrently. The connection pool
+    /// provided to the sealer should have more than one connection for this to have an effect.
+    pub fn with_parallel_seal(mut self) -> Self {
+        self.parallel_seal = true;
+        self
+    }
+
     /// Seals miniblocks as they are received from the [`MiniblockSealerHandle`]. This should be run
     /// on a separate Tokio task.
     pub async fn run(mut self) {
@@ -248,8 +304,26 @@ impl MiniblockSealer {
         // Commands must be processed sequentially: a later miniblock cannot be saved before
         // an earlier one.
         while let Some(completable) = self.next_command().await {
-            let mut conn = self.pool.access_storage_tagged("state_keeper").await;
-            completable.command.seal(&mut conn).await;
+            let miniblock_number = completable.command.miniblock_number;
+            if self.parallel_seal {
+                completable.command.seal_parallel(&self.pool).await;
+            } else {
+                let mut conn = self.pool.access_storage_tagged("state_keeper").await;
+                completable.command.seal(&mut conn).await;
+            }
+            let executed_transactions = &completable.command.miniblock.executed_transactions;
+            if let Some(pending_receipts) = &self.pending_receipts {
+                if !executed_transactions.is_empty() {
+                    self.cache_receipts(pending_receipts, miniblock_number)
+                        .await;
+                }
+            }
+            if let Some(notifier) = &self.seal_notifier {
+                notifier.send_replace(miniblock_number);
+            }
+            if let Some(notifier) = &self.removal_notifier {
+                notifier.notify(executed_transactions.iter().map(|tx| tx.hash).collect());
+            }
             if let Some(delta) = miniblock_seal_delta {
                 metrics::histogram!("server.state_keeper.miniblock.seal_delta", delta.elapsed());
             }
@@ -260,6 +334,25 @@ impl MiniblockSealer {
         }
     }
 
+    async fn cache_receipts(
+        &self,
+        pending_receipts: &PendingReceipts,
+        miniblock_number: MiniblockNumber,
+    ) {
+        let mut conn = self.pool.access_storage_tagged("state_keeper").await;
+        let receipts = conn
+            .transactions_web3_dal()
+            .get_miniblock_receipts(miniblock_number)
+            .await;
+        match receipts {
+            Ok(receipts) => pending_receipts.record_sealed_miniblock(receipts),
+            // Receipts will be loaded from Postgres by the API servers, so it's not a fatal error.
+            Err(err) => {
+                vlog::warn!("Failed loading receipts for miniblock #{miniblock_number}: {err}")
+            }
+        }
+    }
+
     async fn next_command(&mut self) -> Option<Completable<MiniblockSealCommand>> {
         vlog::debug!("Polling miniblock seal queue for next command");
         let start = Instant::now();
@@ -0,0 +1,121 @@
+//! Source of the operator address that collects fees in new L1 batches.
+
+use std::{fs, path::PathBuf, str::FromStr};
+
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_types::Address;
+
+/// Provides the operator (fee account) address for new L1 batches.
+///
+/// If a fee collector address is configured, it's always used and the operator address is ignored.
+/// Otherwise, if a keystore file is configured, the address is re-read from it each time an L1 batch is opened,
+/// so that the operator can be rotated without restarting the node. The file must contain a single
+/// hex-encoded address. If the file cannot be read or parsed, the previously used address is retained.
+#[derive(Debug, Clone)]
+pub(crate) struct OperatorAddressSource {
+    current: Address,
+    keystore_path: Option<PathBuf>,
+}
+
+impl OperatorAddressSource {
+    pub fn new(config: &StateKeeperConfig) -> Self {
+        if let Some(fee_collector) = config.fee_collector_addr {
+            vlog::info!("L2 fees are collected by {fee_collector:?} distinct from the operator");
+            return Self::fixed(fee_collector);
+        }
+
+        let mut this = Self {
+            current: config.fee_account_addr,
+            keystore_path: config.fee_account_addr_path.as_ref().map(PathBuf::from),
+        };
+        this.reload();
+        this
+    }
+
+    /// Creates a source with a fixed address.
+    pub fn fixed(address: Address) -> Self {
+        Self {
+            current: address,
+            keystore_path: None,
+        }
+    }
+
+    /// Returns the operator address to be used in the next L1 batch.
+    pub fn current(&mut self) -> Address {
+        self.reload();
+        self.current
+    }
+
+    fn reload(&mut self) {
+        let Some(path) = &self.keystore_path else {
+            return;
+        };
+        let address = fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|contents| {
+                let contents = contents.trim();
+                let contents = contents.strip_prefix("0x").unwrap_or(contents);
+                Address::from_str(contents).map_err(|err| err.to_string())
+            });
+        match address {
+            Ok(address) if address != self.current => {
+                vlog::info!(
+                    "Operator address changed from {:?} to {address:?}",
+                    self.current
+                );
+                metrics::increment_counter!("server.state_keeper.operator_address_rotations");
+                self.current = address;
+            }
+            Ok(_) => { /* Address is unchanged */ }
+            Err(err) => {
+                vlog::warn!(
+                    "Failed reading operator address from {}: {err}; keeping address {:?}",
+                    path.display(),
+                    self.current
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operator_address_is_reloaded_from_keystore() {
+        let keystore = tempfile::NamedTempFile::new().unwrap();
+        let initial_address = Address::repeat_byte(1);
+        let config = StateKeeperConfig {
+            fee_account_addr: initial_address,
+            fee_account_addr_path: Some(keystore.path().to_str().unwrap().to_owned()),
+            ..StateKeeperConfig::default()
+        };
+        // The keystore is empty, so the configured address is used.
+        let mut source = OperatorAddressSource::new(&config);
+        assert_eq!(source.current(), initial_address);
+
+        let new_address = Address::repeat_byte(2);
+        fs::write(keystore.path(), format!("{new_address:?}\n")).unwrap();
+        assert_eq!(source.current(), new_address);
+
+        fs::write(keystore.path(), "not an address").unwrap();
+        assert_eq!(source.current(), new_address);
+    }
+
+    #[test]
+    fn fee_collector_overrides_operator_address() {
+        let keystore = tempfile::NamedTempFile::new().unwrap();
+        fs::write(keystore.path(), format!("{:?}", Address::repeat_byte(2))).unwrap();
+        let fee_collector = Address::repeat_byte(3);
+        let config = StateKeeperConfig {
+            fee_account_addr: Address::repeat_byte(1),
+            fee_account_addr_path: Some(keystore.path().to_str().unwrap().to_owned()),
+            fee_collector_addr: Some(fee_collector),
+            ..StateKeeperConfig::default()
+        };
+        let mut source = OperatorAddressSource::new(&config);
+        assert_eq!(source.current(), fee_collector);
+        assert_eq!(config.fee_collector_addr(), fee_collector);
+    }
+}
@@ -0,0 +1,473 @@
+//! Replay log of the state keeper inputs.
+//!
+//! [`RecordingIO`] wraps a [`StateKeeperIO`] implementation and appends everything the state keeper receives
+//! from it (L1 batch params, miniblock timestamps, transactions in the order they're returned, and the pending
+//! batch re-executed on start) to a log. Outputs of the state keeper (rolled back and rejected transactions,
+//! sealed miniblocks and L1 batches) are recorded as well, so that a replay can be checked for divergence.
+//!
+//! The log is newline-delimited JSON; each state keeper run appends a segment starting with
+//! a [`ReplayEvent::Start`] entry. [`ReplayIO`] feeds a segment back to the state keeper without Postgres
+//! or the mempool. Replays are deterministic only if seal criteria don't depend on the wall clock
+//! (e.g., miniblock and L1 batch timeouts); otherwise, the replay may diverge from the recorded run,
+//! in which case [`ReplayIO`] panics.
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+
+use std::{
+    collections::VecDeque,
+    fs,
+    io::{BufRead, BufReader, LineWriter, Write},
+    path::Path,
+    time::Duration,
+};
+
+use vm::{
+    vm_with_bootloader::{BlockContext, BlockContextMode, DerivedBlockContext},
+    zk_evm::block_properties::BlockProperties,
+    VmBlockResult,
+};
+use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes};
+use zksync_types::{
+    tx::TxRejection, Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, Transaction, H256,
+    U256, ZKPORTER_IS_AVAILABLE,
+};
+use zksync_utils::h256_to_u256;
+
+use super::{L1BatchParams, PendingBatchData, StateKeeperIO};
+use crate::state_keeper::updates::UpdatesManager;
+
+/// Serializable version of [`L1BatchParams`]. Base system contracts are referenced by their hashes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayL1BatchParams {
+    pub number: L1BatchNumber,
+    pub timestamp: u64,
+    pub operator_address: Address,
+    pub l1_gas_price: u64,
+    pub fair_l2_gas_price: u64,
+    pub base_fee: u64,
+    /// Hash of the previous L1 batch; `None` if the params override the current batch context.
+    pub prev_l1_batch_hash: Option<U256>,
+    pub base_system_contracts: BaseSystemContractsHashes,
+    pub protocol_version: Option<ProtocolVersionId>,
+}
+
+impl ReplayL1BatchParams {
+    fn new(params: &L1BatchParams) -> Self {
+        let (derived_context, prev_l1_batch_hash) = match &params.context_mode {
+            BlockContextMode::NewBlock(context, prev_hash) => (context, Some(*prev_hash)),
+            BlockContextMode::OverrideCurrent(context) => (context, None),
+        };
+        let context = &derived_context.context;
+        Self {
+            number: L1BatchNumber(context.block_number),
+            timestamp: context.block_timestamp,
+            operator_address: context.operator_address,
+            l1_gas_price: context.l1_gas_price,
+            fair_l2_gas_price: context.fair_l2_gas_price,
+            base_fee: derived_context.base_fee,
+            prev_l1_batch_hash,
+            base_system_contracts: params.base_system_contracts.hashes(),
+            protocol_version: params.protocol_version,
+        }
+    }
+
+    fn to_params(&self, base_system_contracts: &BaseSystemContracts) -> L1BatchParams {
+        assert_eq!(
+            base_system_contracts.hashes(),
+            self.base_system_contracts,
+            "L1 batch #{} was recorded with other base system contracts",
+            self.number
+        );
+
+        let context = DerivedBlockContext {
+            context: BlockContext {
+                block_number: self.number.0,
+                block_timestamp: self.timestamp,
+                l1_gas_price: self.l1_gas_price,
+                fair_l2_gas_price: self.fair_l2_gas_price,
+                operator_address: self.operator_address,
+            },
+            base_fee: self.base_fee,
+        };
+        let context_mode = match self.prev_l1_batch_hash {
+            Some(prev_hash) => BlockContextMode::NewBlock(context, prev_hash),
+            None => BlockContextMode::OverrideCurrent(context),
+        };
+        L1BatchParams {
+            context_mode,
+            properties: BlockProperties {
+                default_aa_code_hash: h256_to_u256(base_system_contracts.default_aa.hash),
+                zkporter_is_available: ZKPORTER_IS_AVAILABLE,
+            },
+            base_system_contracts: base_system_contracts.clone(),
+            protocol_version: self.protocol_version,
+        }
+    }
+}
+
+/// Entry of the replay log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ReplayEvent {
+    /// Start of the state keeper; the first entry of each segment.
+    #[serde(rename_all = "camelCase")]
+    Start {
+        l1_batch_number: L1BatchNumber,
+        miniblock_number: MiniblockNumber,
+    },
+    /// Pending batch re-executed on start. Persisted execution outcomes of its transactions are not recorded.
+    #[serde(rename_all = "camelCase")]
+    PendingBatch {
+        params: ReplayL1BatchParams,
+        miniblocks: Vec<(MiniblockNumber, Vec<Transaction>)>,
+    },
+    L1BatchParams(ReplayL1BatchParams),
+    #[serde(rename_all = "camelCase")]
+    MiniblockTimestamp {
+        timestamp: u64,
+    },
+    Transaction(Box<Transaction>),
+    /// No transaction was returned within the wait period.
+    NoTransaction,
+    #[serde(rename_all = "camelCase")]
+    Rollback {
+        tx_hash: H256,
+    },
+    #[serde(rename_all = "camelCase")]
+    Reject {
+        tx_hash: H256,
+        error: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    MiniblockSealed {
+        number: MiniblockNumber,
+        tx_count: usize,
+    },
+    #[serde(rename_all = "camelCase")]
+    L1BatchSealed {
+        number: L1BatchNumber,
+        tx_count: usize,
+    },
+}
+
+/// [`StateKeeperIO`] wrapper appending the state keeper inputs and outputs to a replay log.
+#[derive(Debug)]
+pub struct RecordingIO<IO> {
+    inner: IO,
+    writer: Option<LineWriter<fs::File>>,
+}
+
+impl<IO: StateKeeperIO> RecordingIO<IO> {
+    /// Wraps `inner` IO, appending the log to the file at `path` (the file is created if necessary).
+    pub fn new(inner: IO, path: &Path) -> anyhow::Result<Self> {
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("failed opening replay log `{}`", path.display()))?;
+        let mut this = Self {
+            writer: Some(LineWriter::new(file)),
+            inner,
+        };
+        this.record(&ReplayEvent::Start {
+            l1_batch_number: this.inner.current_l1_batch_number(),
+            miniblock_number: this.inner.current_miniblock_number(),
+        });
+        Ok(this)
+    }
+
+    /// Records an event. The replay log is diagnostic, so a write error doesn't stop the state keeper;
+    /// recording is disabled instead.
+    fn record(&mut self, event: &ReplayEvent) {
+        let Some(writer) = &mut self.writer else {
+            return;
+        };
+        let mut line = serde_json::to_vec(event).expect("failed serializing replay event");
+        line.push(b'\n');
+        if let Err(err) = writer.write_all(&line) {
+            vlog::error!("Failed writing state keeper replay log, recording is disabled: {err}");
+            self.writer = None;
+        }
+    }
+}
+
+#[async_trait]
+impl<IO: StateKeeperIO> StateKeeperIO for RecordingIO<IO> {
+    fn current_l1_batch_number(&self) -> L1BatchNumber {
+        self.inner.current_l1_batch_number()
+    }
+
+    fn current_miniblock_number(&self) -> MiniblockNumber {
+        self.inner.current_miniblock_number()
+    }
+
+    async fn load_pending_batch(&mut self) -> Option<PendingBatchData> {
+        let pending_batch = self.inner.load_pending_batch().await?;
+        self.record(&ReplayEvent::PendingBatch {
+            params: ReplayL1BatchParams::new(&pending_batch.params),
+            miniblocks: pending_batch.txs.clone(),
+        });
+        Some(pending_batch)
+    }
+
+    async fn wait_for_new_batch_params(&mut self, max_wait: Duration) -> Option<L1BatchParams> {
+        let params = self.inner.wait_for_new_batch_params(max_wait).await?;
+        self.record(&ReplayEvent::L1BatchParams(ReplayL1BatchParams::new(
+            &params,
+        )));
+        Some(params)
+    }
+
+    async fn wait_for_new_miniblock_params(&mut self, max_wait: Duration) -> Option<u64> {
+        let timestamp = self.inner.wait_for_new_miniblock_params(max_wait).await?;
+        self.record(&ReplayEvent::MiniblockTimestamp { timestamp });
+        Some(timestamp)
+    }
+
+    async fn wait_for_next_tx(&mut self, max_wait: Duration) -> Option<Transaction> {
+        let tx = self.inner.wait_for_next_tx(max_wait).await;
+        let event = match &tx {
+            Some(tx) => ReplayEvent::Transaction(Box::new(tx.clone())),
+            None => ReplayEvent::NoTransaction,
+        };
+        self.record(&event);
+        tx
+    }
+
+    async fn rollback(&mut self, tx: Transaction) {
+        self.record(&ReplayEvent::Rollback { tx_hash: tx.hash() });
+        self.inner.rollback(tx).await;
+    }
+
+    async fn reject(&mut self, tx: &Transaction, rejection: &TxRejection) {
+        self.record(&ReplayEvent::Reject {
+            tx_hash: tx.hash(),
+            error: rejection.to_string(),
+        });
+        self.inner.reject(tx, rejection).await;
+    }
+
+    async fn seal_miniblock(&mut self, updates_manager: &UpdatesManager) {
+        self.record(&ReplayEvent::MiniblockSealed {
+            number: self.inner.current_miniblock_number(),
+            tx_count: updates_manager.miniblock.executed_transactions.len(),
+        });
+        self.inner.seal_miniblock(updates_manager).await;
+    }
+
+    async fn seal_l1_batch(
+        &mut self,
+        block_result: VmBlockResult,
+        updates_manager: UpdatesManager,
+        block_context: DerivedBlockContext,
+    ) {
+        self.record(&ReplayEvent::L1BatchSealed {
+            number: self.inner.current_l1_batch_number(),
+            tx_count: updates_manager.l1_batch.executed_transactions.len(),
+        });
+        self.inner
+            .seal_l1_batch(block_result, updates_manager, block_context)
+            .await;
+    }
+}
+
+/// Segment of a replay log corresponding to a single state keeper run.
+#[derive(Debug, Clone)]
+pub struct ReplaySegment {
+    pub l1_batch_number: L1BatchNumber,
+    pub miniblock_number: MiniblockNumber,
+    pub events: Vec<ReplayEvent>,
+}
+
+/// Reads all segments from a replay log.
+pub fn read_replay_log(path: &Path) -> anyhow::Result<Vec<ReplaySegment>> {
+    let file = fs::File::open(path)
+        .with_context(|| format!("failed opening replay log `{}`", path.display()))?;
+    let mut segments = Vec::<ReplaySegment>::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.context("failed reading replay log")?;
+        let event: ReplayEvent = serde_json::from_str(&line)
+            .with_context(|| format!("failed parsing replay log entry on line {}", i + 1))?;
+        match (event, segments.last_mut()) {
+            (
+                ReplayEvent::Start {
+                    l1_batch_number,
+                    miniblock_number,
+                },
+                _,
+            ) => segments.push(ReplaySegment {
+                l1_batch_number,
+                miniblock_number,
+                events: vec![],
+            }),
+            (event, Some(segment)) => segment.events.push(event),
+            (_, None) => anyhow::bail!("replay log doesn't start with a start entry"),
+        }
+    }
+    Ok(segments)
+}
+
+/// [`StateKeeperIO`] implementation feeding a recorded [`ReplaySegment`] back to the state keeper.
+/// Inputs are returned in the recorded order without waiting. Each output of the state keeper is checked
+/// against the recorded output; on a mismatch, the IO panics. Once all events are replayed, the IO sends
+/// a stop signal to the state keeper.
+#[derive(Debug)]
+pub struct ReplayIO {
+    events: VecDeque<ReplayEvent>,
+    replayed_events: usize,
+    base_system_contracts: BaseSystemContracts,
+    current_l1_batch_number: L1BatchNumber,
+    current_miniblock_number: MiniblockNumber,
+    stop_sender: watch::Sender<bool>,
+}
+
+impl ReplayIO {
+    /// Creates an IO replaying `segment`. `base_system_contracts` must be the contracts the segment
+    /// was recorded with.
+    pub fn new(
+        segment: ReplaySegment,
+        base_system_contracts: BaseSystemContracts,
+        stop_sender: watch::Sender<bool>,
+    ) -> Self {
+        Self {
+            events: segment.events.into(),
+            replayed_events: 0,
+            base_system_contracts,
+            current_l1_batch_number: segment.l1_batch_number,
+            current_miniblock_number: segment.miniblock_number,
+            stop_sender,
+        }
+    }
+
+    /// Takes the next event if it's accepted by `take`. Returns `None` if the log is exhausted.
+    fn next_event<T>(
+        &mut self,
+        expected: &str,
+        take: impl FnOnce(ReplayEvent) -> Result<T, ReplayEvent>,
+    ) -> Option<T> {
+        let Some(event) = self.events.pop_front() else {
+            self.stop_sender.send_replace(true);
+            return None;
+        };
+        match take(event) {
+            Ok(value) => {
+                self.replayed_events += 1;
+                Some(value)
+            }
+            Err(event) => panic!(
+                "Replay diverged after {} events: state keeper expects {expected}, but the log contains {event:?}",
+                self.replayed_events
+            ),
+        }
+    }
+}
+
+#[async_trait]
+impl StateKeeperIO for ReplayIO {
+    fn current_l1_batch_number(&self) -> L1BatchNumber {
+        self.current_l1_batch_number
+    }
+
+    fn current_miniblock_number(&self) -> MiniblockNumber {
+        self.current_miniblock_number
+    }
+
+    async fn load_pending_batch(&mut self) -> Option<PendingBatchData> {
+        if !matches!(self.events.front(), Some(ReplayEvent::PendingBatch { .. })) {
+            return None;
+        }
+        let (params, miniblocks) = self.next_event("pending batch", |event| match event {
+            ReplayEvent::PendingBatch { params, miniblocks } => Ok((params, miniblocks)),
+            other => Err(other),
+        })?;
+        Some(PendingBatchData {
+            params: params.to_params(&self.base_system_contracts),
+            txs: miniblocks,
+            expected_executions: vec![],
+        })
+    }
+
+    async fn wait_for_new_batch_params(&mut self, _max_wait: Duration) -> Option<L1BatchParams> {
+        let params = self.next_event("L1 batch params", |event| match event {
+            ReplayEvent::L1BatchParams(params) => Ok(params),
+            other => Err(other),
+        })?;
+        Some(params.to_params(&self.base_system_contracts))
+    }
+
+    async fn wait_for_new_miniblock_params(&mut self, _max_wait: Duration) -> Option<u64> {
+        self.next_event("miniblock timestamp", |event| match event {
+            ReplayEvent::MiniblockTimestamp { timestamp } => Ok(timestamp),
+            other => Err(other),
+        })
+    }
+
+    async fn wait_for_next_tx(&mut self, _max_wait: Duration) -> Option<Transaction> {
+        self.next_event("transaction", |event| match event {
+            ReplayEvent::Transaction(tx) => Ok(Some(*tx)),
+            ReplayEvent::NoTransaction => Ok(None),
+            other => Err(other),
+        })?
+    }
+
+    async fn rollback(&mut self, tx: Transaction) {
+        let tx_hash = tx.hash();
+        self.next_event(&format!("rollback of {tx_hash:?}"), |event| match event {
+            ReplayEvent::Rollback { tx_hash: hash } if hash == tx_hash => Ok(()),
+            other => Err(other),
+        });
+    }
+
+    async fn reject(&mut self, tx: &Transaction, rejection: &TxRejection) {
+        let tx_hash = tx.hash();
+        self.next_event(
+            &format!("rejection of {tx_hash:?} ({rejection})"),
+            |event| match event {
+                ReplayEvent::Reject { tx_hash: hash, .. } if hash == tx_hash => Ok(()),
+                other => Err(other),
+            },
+        );
+    }
+
+    async fn seal_miniblock(&mut self, updates_manager: &UpdatesManager) {
+        let number = self.current_miniblock_number;
+        let tx_count = updates_manager.miniblock.executed_transactions.len();
+        self.next_event(
+            &format!("miniblock #{number} sealed with {tx_count} txs"),
+            |event| match event {
+                ReplayEvent::MiniblockSealed {
+                    number: recorded_number,
+                    tx_count: recorded_count,
+                } if recorded_number == number && recorded_count == tx_count => Ok(()),
+                other => Err(other),
+            },
+        );
+        self.current_miniblock_number += 1;
+    }
+
+    async fn seal_l1_batch(
+        &mut self,
+        _block_result: VmBlockResult,
+        updates_manager: UpdatesManager,
+        _block_context: DerivedBlockContext,
+    ) {
+        let number = self.current_l1_batch_number;
+        let tx_count = updates_manager.l1_batch.executed_transactions.len();
+        self.next_event(
+            &format!("L1 batch #{number} sealed with {tx_count} txs"),
+            |event| match event {
+                ReplayEvent::L1BatchSealed {
+                    number: recorded_number,
+                    tx_count: recorded_count,
+                } if recorded_number == number && recorded_count == tx_count => Ok(()),
+                other => Err(other),
+            },
+        );
+        self.current_miniblock_number += 1; // Due to fictive miniblock being sealed.
+        self.current_l1_batch_number += 1;
+    }
+}
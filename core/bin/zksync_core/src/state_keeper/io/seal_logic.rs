@@ -5,6 +5,7 @@ use itertools::Itertools;
 
 use std::{
     collections::HashMap,
+    fmt,
     time::{Duration, Instant},
 };
 
@@ -14,26 +15,38 @@ use vm::{
     },
     VmBlockResult,
 };
-use zksync_config::constants::ACCOUNT_CODE_STORAGE_ADDRESS;
-use zksync_dal::StorageProcessor;
+use zksync_config::{
+    configs::chain::StorageLogAnomalyPolicy, constants::ACCOUNT_CODE_STORAGE_ADDRESS,
+};
+use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_types::{
-    block::{L1BatchHeader, MiniblockHeader},
-    event::{extract_added_tokens, extract_long_l2_to_l1_messages},
+    block::{L1BatchHeader, L1BatchResourceUsage, MiniblockHasher, MiniblockHeader},
+    event::{
+        events_bloom, extract_added_tokens, extract_l1_messenger_messages,
+        extract_long_l2_to_l1_messages, EventIndexingPolicy,
+    },
     l2_to_l1_log::L2ToL1Log,
+    state_diffs::StateDiffRecord,
+    tokens::ETHEREUM_ADDRESS,
     tx::{
         tx_execution_info::DeduplicatedWritesMetrics, IncludedTxLocation,
-        TransactionExecutionResult,
+        TransactionExecutionResult, TxAccessSet,
     },
+    utils::storage_key_for_standard_token_balance,
     zk_evm::aux_structures::LogQuery,
     zkevm_test_harness::witness::sort_storage_access::sort_storage_access_queries,
-    Address, ExecuteTransactionCommon, L1BatchNumber, MiniblockNumber, StorageKey, StorageLog,
-    StorageLogQuery, StorageValue, Transaction, VmEvent, H256, U256,
+    AccountTreeId, Address, ExecuteTransactionCommon, L1BatchNumber, MiniblockNumber, StorageKey,
+    StorageLog, StorageLogQuery, StorageValue, Transaction, VmEvent, H256, L2_ETH_TOKEN_ADDRESS,
+    U256,
 };
-use zksync_utils::{miniblock_hash, time::millis_since_epoch};
+use zksync_utils::{h256_to_account_address, h256_to_u256, time::millis_since_epoch, u256_to_h256};
 
-use crate::state_keeper::{
-    extractors,
-    updates::{L1BatchUpdates, MiniblockSealCommand, UpdatesManager},
+use crate::{
+    chain_contracts::ChainContracts,
+    state_keeper::{
+        extractors,
+        updates::{L1BatchUpdates, MiniblockSealCommand, UpdatesManager},
+    },
 };
 
 #[derive(Debug, Clone, Copy)]
@@ -123,6 +136,8 @@ impl UpdatesManager {
     /// Persists an L1 batch in the storage.
     /// This action includes a creation of an empty "fictive" miniblock that contains
     /// the events generated during the bootloader "tip phase".
+    /// If `save_tx_access_sets` is set, storage access sets of the batch transactions are persisted as well.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn seal_l1_batch(
         mut self,
         storage: &mut StorageProcessor<'_>,
@@ -130,7 +145,11 @@ impl UpdatesManager {
         current_l1_batch_number: L1BatchNumber,
         block_result: VmBlockResult,
         block_context: DerivedBlockContext,
-        l2_erc20_bridge_addr: Address,
+        chain_contracts: ChainContracts,
+        event_indexing: &EventIndexingPolicy,
+        storage_log_anomaly_policy: StorageLogAnomalyPolicy,
+        save_tx_access_sets: bool,
+        miniblock_gas_limit: Option<u64>,
     ) {
         let started_at = Instant::now();
         let mut progress = SealProgress::for_l1_batch();
@@ -149,13 +168,17 @@ impl UpdatesManager {
         );
         progress.end_stage("vm_finalization", None);
 
+        let block_tip_circuit_statistic = block_tip_result.circuit_statistic;
         self.extend_from_fictive_transaction(block_tip_result.logs);
         // Seal fictive miniblock with last events and storage logs.
-        let miniblock_command = self.seal_miniblock_command(
+        let mut miniblock_command = self.seal_miniblock_command(
             current_l1_batch_number,
             current_miniblock_number,
-            l2_erc20_bridge_addr,
+            chain_contracts,
+            event_indexing,
+            storage_log_anomaly_policy,
         );
+        miniblock_command.gas_limit = miniblock_gas_limit;
         miniblock_command.seal_inner(&mut transaction, true).await;
         progress.end_stage("fictive_miniblock", None);
 
@@ -206,7 +229,7 @@ impl UpdatesManager {
             l2_tx_count: l2_tx_count as u16,
             l2_to_l1_logs: full_result.l2_to_l1_logs,
             l2_to_l1_messages: extract_long_l2_to_l1_messages(&full_result.events),
-            bloom: Default::default(),
+            bloom: events_bloom(&full_result.events),
             initial_bootloader_contents: Self::initial_bootloader_memory(
                 &self.l1_batch,
                 block_context_properties,
@@ -222,6 +245,22 @@ impl UpdatesManager {
             .blocks_dal()
             .insert_l1_batch(&l1_batch, self.l1_batch.l1_gas_count)
             .await;
+        if let Some(protocol_version) = self.protocol_version() {
+            transaction
+                .blocks_dal()
+                .set_l1_batch_protocol_version(current_l1_batch_number, protocol_version)
+                .await;
+        }
+        transaction
+            .blocks_dal()
+            .set_l1_batch_bloom(current_l1_batch_number, &l1_batch.bloom)
+            .await;
+        if let Some(seal_reason) = self.l1_batch.seal_reason {
+            transaction
+                .blocks_dal()
+                .set_l1_batch_seal_reason(current_l1_batch_number, seal_reason)
+                .await;
+        }
         progress.end_stage("insert_l1_batch_header", None);
 
         transaction
@@ -239,6 +278,12 @@ impl UpdatesManager {
             .await;
         progress.end_stage("mark_txs_as_executed_in_l1_batch", None);
 
+        transaction
+            .api_read_models()
+            .refresh_for_l1_batch(current_l1_batch_number)
+            .await;
+        progress.end_stage("refresh_api_read_models", None);
+
         let (deduplicated_writes, protective_reads): (Vec<_>, Vec<_>) = deduped_log_queries
             .into_iter()
             .partition(|log_query| log_query.rw_flag);
@@ -254,10 +299,61 @@ impl UpdatesManager {
             .await;
         progress.end_stage("insert_initial_writes", Some(deduplicated_writes.len()));
 
+        let state_diffs: Vec<_> = deduplicated_writes
+            .iter()
+            .map(StateDiffRecord::from_write_query)
+            .collect();
+        transaction
+            .blocks_dal()
+            .insert_l1_batch_state_diffs(current_l1_batch_number, &state_diffs)
+            .await;
+        progress.end_stage("insert_state_diffs", Some(state_diffs.len()));
+
+        let writes_metrics = self.storage_writes_deduplicator.metrics();
+        let execution_metrics = self.pending_execution_metrics();
+        let resource_usage = L1BatchResourceUsage {
+            gas_used: execution_metrics.gas_used as u64,
+            pubdata_bytes: (execution_metrics.size() + writes_metrics.size()) as u64,
+            initial_storage_writes: writes_metrics.initial_storage_writes as u64,
+            repeated_storage_writes: writes_metrics.repeated_storage_writes as u64,
+            circuit_statistic: execution_metrics.circuit_statistic + block_tip_circuit_statistic,
+        };
+        transaction
+            .blocks_dal()
+            .insert_l1_batch_resource_usage(current_l1_batch_number, &resource_usage)
+            .await;
+        progress.end_stage("insert_resource_usage", None);
+
+        let messenger_messages =
+            extract_l1_messenger_messages(&full_result.events, &l1_batch.l2_to_l1_logs);
+        transaction
+            .events_dal()
+            .insert_l1_messenger_messages(current_l1_batch_number, &messenger_messages)
+            .await;
+        progress.end_stage(
+            "insert_l1_messenger_messages",
+            Some(messenger_messages.len()),
+        );
+
+        if save_tx_access_sets {
+            let tx_hashes: Vec<_> = self
+                .l1_batch
+                .executed_transactions
+                .iter()
+                .map(|tx| tx.hash)
+                .collect();
+            let access_sets =
+                TxAccessSet::from_storage_logs(&tx_hashes, &full_result.storage_log_queries);
+            transaction
+                .blocks_dal()
+                .insert_l1_batch_tx_access_sets(current_l1_batch_number, &access_sets)
+                .await;
+            progress.end_stage("insert_tx_access_sets", Some(access_sets.len()));
+        }
+
         transaction.commit().await;
         progress.end_stage("commit_l1_batch", None);
 
-        let writes_metrics = self.storage_writes_deduplicator.metrics();
         // Sanity check metrics.
         assert_eq!(
             deduplicated_writes.len(),
@@ -319,6 +415,10 @@ impl UpdatesManager {
             "server.state_keeper.l1_batch.repeated_writes",
             writes_metrics.repeated_storage_writes as f64
         );
+        metrics::histogram!(
+            "server.state_keeper.l1_batch.refunded_writes",
+            self.pending_execution_metrics().refunded_storage_writes as f64
+        );
 
         metrics::histogram!(
             "server.state_keeper.l1_batch.transactions_in_l1_batch",
@@ -344,6 +444,35 @@ impl UpdatesManager {
 }
 
 impl MiniblockSealCommand {
+    /// Truncates call traces and revert reasons of the executed transactions to the specified sizes in bytes,
+    /// so that oversized results don't bloat Postgres and API responses.
+    pub(crate) fn truncate_tx_results(
+        &mut self,
+        max_output_size: usize,
+        max_revert_reason_size: usize,
+    ) {
+        for tx_result in &mut self.miniblock.executed_transactions {
+            tx_result.truncate_results(max_output_size, max_revert_reason_size);
+            if tx_result.result_truncated {
+                vlog::debug!(
+                    "Truncated execution results of transaction {:?} in miniblock #{}",
+                    tx_result.hash,
+                    self.miniblock_number
+                );
+                metrics::increment_counter!("server.state_keeper.truncated_tx_results");
+            }
+        }
+    }
+
+    #[tracing::instrument(
+        name = "seal_miniblock",
+        skip_all,
+        fields(
+            miniblock_number = self.miniblock_number.0,
+            l1_batch_number = self.l1_batch_number.0,
+            tx_count = self.miniblock.executed_transactions.len()
+        )
+    )]
     pub async fn seal(&self, storage: &mut StorageProcessor<'_>) {
         self.seal_inner(storage, false).await;
     }
@@ -356,43 +485,20 @@ impl MiniblockSealCommand {
     /// the bootloader enters the "tip" phase in which it can still generate events (e.g.,
     /// one for sending fees to the operator).
     ///
-    /// `l2_erc20_bridge_addr` is required to extract the information on newly added tokens.
+    /// Sealing is performed in two phases within a single DB transaction: the miniblock header is inserted
+    /// as unsealed, and the completion marker is set after all other writes. Thus, a miniblock header
+    /// without the marker always signals partially persisted data, which is removed on restart
+    /// by [`remove_unsealed_miniblocks()`].
     async fn seal_inner(&self, storage: &mut StorageProcessor<'_>, is_fictive: bool) {
         self.assert_valid_miniblock(is_fictive);
 
-        let l1_batch_number = self.l1_batch_number;
         let miniblock_number = self.miniblock_number;
         let started_at = Instant::now();
         let mut progress = SealProgress::for_miniblock(is_fictive);
-
-        let (l1_tx_count, l2_tx_count) = l1_l2_tx_count(&self.miniblock.executed_transactions);
-        let (writes_count, reads_count) =
-            storage_log_query_write_read_counts(&self.miniblock.storage_logs);
-        vlog::info!(
-            "Sealing miniblock {miniblock_number} (L1 batch {l1_batch_number}) \
-             with {total_tx_count} ({l2_tx_count} L2 + {l1_tx_count} L1) txs, {event_count} events, \
-             {reads_count} reads, {writes_count} writes",
-            total_tx_count = l1_tx_count + l2_tx_count,
-            event_count = self.miniblock.events.len()
-        );
+        self.log_seal_start();
 
         let mut transaction = storage.start_transaction().await;
-        let miniblock_header = MiniblockHeader {
-            number: miniblock_number,
-            timestamp: self.miniblock.timestamp,
-            hash: miniblock_hash(miniblock_number),
-            l1_tx_count: l1_tx_count as u16,
-            l2_tx_count: l2_tx_count as u16,
-            base_fee_per_gas: self.base_fee_per_gas,
-            l1_gas_price: self.l1_gas_price,
-            l2_fair_gas_price: self.fair_l2_gas_price,
-            base_system_contracts_hashes: self.base_system_contracts_hashes,
-        };
-
-        transaction
-            .blocks_dal()
-            .insert_miniblock(&miniblock_header)
-            .await;
+        self.insert_miniblock_header(&mut transaction).await;
         progress.end_stage("insert_miniblock_header", None);
 
         transaction
@@ -437,7 +543,8 @@ impl MiniblockSealCommand {
         let deployed_contract_count = Self::count_deployed_contracts(&unique_updates);
         progress.end_stage("extract_contracts_deployed", Some(deployed_contract_count));
 
-        let added_tokens = extract_added_tokens(self.l2_erc20_bridge_addr, &self.miniblock.events);
+        let added_tokens =
+            extract_added_tokens(self.chain_contracts.l2_erc20_bridge, &self.miniblock.events);
         progress.end_stage("extract_added_tokens", Some(added_tokens.len()));
         let added_tokens_len = added_tokens.len();
         if !added_tokens.is_empty() {
@@ -453,7 +560,7 @@ impl MiniblockSealCommand {
         progress.end_stage("extract_events", Some(miniblock_event_count));
         transaction
             .events_dal()
-            .save_events(miniblock_number, &miniblock_events)
+            .save_events(miniblock_number, &miniblock_events, &self.event_indexing)
             .await;
         progress.end_stage("insert_events", Some(miniblock_event_count));
 
@@ -469,11 +576,233 @@ impl MiniblockSealCommand {
             .await;
         progress.end_stage("insert_l2_to_l1_logs", Some(l2_to_l1_log_count));
 
+        let token_balances = self.extract_token_balances(&write_logs);
+        progress.end_stage("extract_token_balances", Some(token_balances.len()));
+        transaction
+            .tokens_dal()
+            .insert_token_balances(miniblock_number, &token_balances)
+            .await;
+        progress.end_stage("insert_token_balances", Some(token_balances.len()));
+
+        transaction
+            .api_read_models()
+            .refresh_for_miniblock(miniblock_number)
+            .await;
+        progress.end_stage("refresh_api_read_models", None);
+
+        transaction
+            .blocks_dal()
+            .set_miniblock_is_sealed(miniblock_number, true)
+            .await;
+        progress.end_stage("mark_miniblock_as_sealed", None);
+
         transaction.commit().await;
         progress.end_stage("commit_miniblock", None);
         self.report_miniblock_metrics(started_at);
     }
 
+    /// Seals the miniblock performing independent writes concurrently on separate connections from `pool`.
+    ///
+    /// Unlike [`Self::seal()`], sealing is not atomic. The miniblock header is inserted first and is marked
+    /// as sealed only after all other writes succeed; until then, the miniblock isn't considered sealed
+    /// by the DAL. Partially persisted miniblocks are removed on restart by [`remove_unsealed_miniblocks()`].
+    #[tracing::instrument(
+        name = "seal_miniblock_parallel",
+        skip_all,
+        fields(
+            miniblock_number = self.miniblock_number.0,
+            l1_batch_number = self.l1_batch_number.0,
+            tx_count = self.miniblock.executed_transactions.len()
+        )
+    )]
+    pub async fn seal_parallel(&self, pool: &ConnectionPool) {
+        self.assert_valid_miniblock(false);
+
+        let miniblock_number = self.miniblock_number;
+        let started_at = Instant::now();
+        let mut progress = SealProgress::for_miniblock(false);
+        self.log_seal_start();
+
+        let mut storage = pool.access_storage_tagged("state_keeper").await;
+        let mut transaction = storage.start_transaction().await;
+        self.insert_miniblock_header(&mut transaction).await;
+        transaction.commit().await;
+        drop(storage);
+        progress.end_stage("insert_miniblock_header", None);
+
+        let write_logs = self.extract_write_logs(false);
+        let write_log_count: usize = write_logs.iter().map(|(_, logs)| logs.len()).sum();
+        let new_factory_deps = &self.miniblock.new_factory_deps;
+        let added_tokens =
+            extract_added_tokens(self.chain_contracts.l2_erc20_bridge, &self.miniblock.events);
+        let miniblock_events = self.extract_events(false);
+        let l2_to_l1_logs = self.extract_l2_to_l1_logs(false);
+        let token_balances = self.extract_token_balances(&write_logs);
+        progress.end_stage("extract_miniblock_data", None);
+
+        let mark_transactions = async {
+            let mut storage = pool.access_storage_tagged("state_keeper").await;
+            storage
+                .transactions_dal()
+                .mark_txs_as_executed_in_miniblock(
+                    miniblock_number,
+                    &self.miniblock.executed_transactions,
+                    self.base_fee_per_gas.into(),
+                )
+                .await;
+        };
+        // Storage must only be updated after the storage logs are persisted; otherwise, the update
+        // couldn't be rolled back if the miniblock is partially persisted.
+        let insert_storage = async {
+            let mut storage = pool.access_storage_tagged("state_keeper").await;
+            storage
+                .storage_logs_dal()
+                .insert_storage_logs(miniblock_number, &write_logs)
+                .await;
+            storage.storage_dal().apply_storage_logs(&write_logs).await
+        };
+        let insert_factory_deps = async {
+            if !new_factory_deps.is_empty() {
+                let mut storage = pool.access_storage_tagged("state_keeper").await;
+                storage
+                    .storage_dal()
+                    .insert_factory_deps(miniblock_number, new_factory_deps)
+                    .await;
+            }
+        };
+        let insert_tokens = async {
+            let mut storage = pool.access_storage_tagged("state_keeper").await;
+            if !added_tokens.is_empty() {
+                storage.tokens_dal().add_tokens(added_tokens).await;
+            }
+            storage
+                .tokens_dal()
+                .insert_token_balances(miniblock_number, &token_balances)
+                .await;
+        };
+        let insert_events = async {
+            let mut storage = pool.access_storage_tagged("state_keeper").await;
+            storage
+                .events_dal()
+                .save_events(miniblock_number, &miniblock_events, &self.event_indexing)
+                .await;
+            storage
+                .events_dal()
+                .save_l2_to_l1_logs(miniblock_number, &l2_to_l1_logs)
+                .await;
+        };
+        let ((), unique_updates, (), (), ()) = tokio::join!(
+            mark_transactions,
+            insert_storage,
+            insert_factory_deps,
+            insert_tokens,
+            insert_events
+        );
+        progress.end_stage("concurrent_writes", Some(write_log_count));
+
+        let deployed_contract_count = Self::count_deployed_contracts(&unique_updates);
+        progress.end_stage("extract_contracts_deployed", Some(deployed_contract_count));
+
+        // Read models are refreshed atomically with marking the miniblock as sealed, so that they are
+        // never refreshed for partially persisted miniblocks.
+        let mut storage = pool.access_storage_tagged("state_keeper").await;
+        let mut transaction = storage.start_transaction().await;
+        transaction
+            .api_read_models()
+            .refresh_for_miniblock(miniblock_number)
+            .await;
+        progress.end_stage("refresh_api_read_models", None);
+        transaction
+            .blocks_dal()
+            .set_miniblock_is_sealed(miniblock_number, true)
+            .await;
+        transaction.commit().await;
+        progress.end_stage("mark_miniblock_as_sealed", None);
+        self.report_miniblock_metrics(started_at);
+    }
+
+    fn log_seal_start(&self) {
+        let (l1_tx_count, l2_tx_count) = l1_l2_tx_count(&self.miniblock.executed_transactions);
+        let (writes_count, reads_count) =
+            storage_log_query_write_read_counts(&self.miniblock.storage_logs);
+        vlog::info!(
+            "Sealing miniblock {miniblock_number} (L1 batch {l1_batch_number}) \
+             with {total_tx_count} ({l2_tx_count} L2 + {l1_tx_count} L1) txs, {event_count} events, \
+             {reads_count} reads, {writes_count} writes",
+            miniblock_number = self.miniblock_number,
+            l1_batch_number = self.l1_batch_number,
+            total_tx_count = l1_tx_count + l2_tx_count,
+            event_count = self.miniblock.events.len()
+        );
+    }
+
+    /// Inserts the miniblock header together with the auxiliary miniblock data. The miniblock is not marked
+    /// as sealed; the marker must be set once all miniblock data is persisted.
+    async fn insert_miniblock_header(&self, transaction: &mut StorageProcessor<'_>) {
+        let miniblock_number = self.miniblock_number;
+        let (l1_tx_count, l2_tx_count) = l1_l2_tx_count(&self.miniblock.executed_transactions);
+        // Miniblocks are sealed sequentially, so the previous miniblock is always persisted at this point.
+        // The previous hash is locked, so that the hashes backfill doesn't change it until the header is inserted.
+        let prev_miniblock_hash = transaction
+            .blocks_dal()
+            .get_miniblock_hash_for_share(miniblock_number - 1)
+            .await
+            .expect("previous miniblock must be sealed before the current one");
+        let mut hasher = MiniblockHasher::new(
+            miniblock_number,
+            self.miniblock.timestamp,
+            prev_miniblock_hash,
+        );
+        for tx in &self.miniblock.executed_transactions {
+            hasher.push_tx_hash(tx.hash);
+        }
+
+        let miniblock_header = MiniblockHeader {
+            number: miniblock_number,
+            timestamp: self.miniblock.timestamp,
+            hash: hasher.finalize(),
+            l1_tx_count: l1_tx_count as u16,
+            l2_tx_count: l2_tx_count as u16,
+            base_fee_per_gas: self.base_fee_per_gas,
+            l1_gas_price: self.l1_gas_price,
+            l2_fair_gas_price: self.fair_l2_gas_price,
+            base_system_contracts_hashes: self.base_system_contracts_hashes,
+        };
+
+        transaction
+            .blocks_dal()
+            .insert_miniblock(&miniblock_header)
+            .await;
+        transaction
+            .blocks_dal()
+            .set_miniblock_is_sealed(miniblock_number, false)
+            .await;
+        if let Some(protocol_version) = self.protocol_version {
+            transaction
+                .blocks_dal()
+                .set_miniblock_protocol_version(miniblock_number, protocol_version)
+                .await;
+        }
+        transaction
+            .blocks_dal()
+            .set_miniblock_gas(miniblock_number, self.miniblock.gas_used, self.gas_limit)
+            .await;
+        transaction
+            .blocks_dal()
+            .set_miniblock_fee_account_address(miniblock_number, self.fee_account_address)
+            .await;
+        if let Some(seal_reason) = self.miniblock.seal_reason {
+            transaction
+                .blocks_dal()
+                .set_miniblock_seal_reason(miniblock_number, seal_reason)
+                .await;
+        }
+        transaction
+            .blocks_dal()
+            .set_miniblock_logs_bloom(miniblock_number, &events_bloom(&self.miniblock.events))
+            .await;
+    }
+
     /// Performs several sanity checks to make sure that the miniblock is valid.
     fn assert_valid_miniblock(&self, is_fictive: bool) {
         assert_eq!(self.miniblock.executed_transactions.is_empty(), is_fictive);
@@ -494,6 +823,33 @@ impl MiniblockSealCommand {
             let tx_index = storage_log.log_query.tx_number_in_block as usize;
             assert!(tx_index_range.contains(&tx_index));
         }
+        self.check_storage_writes();
+    }
+
+    /// Checks storage writes of the miniblock for anomalies, handling them according to the configured policy.
+    fn check_storage_writes(&self) {
+        let anomalies = find_storage_write_anomalies(&self.miniblock.storage_logs);
+        if anomalies.is_empty() {
+            return;
+        }
+        let diagnostics = anomalies.iter().join("; ");
+        match self.storage_log_anomaly_policy {
+            StorageLogAnomalyPolicy::Strict => panic!(
+                "Storage writes in miniblock #{} (L1 batch #{}) are inconsistent: {diagnostics}",
+                self.miniblock_number, self.l1_batch_number
+            ),
+            StorageLogAnomalyPolicy::Lenient => {
+                vlog::warn!(
+                    "Storage writes in miniblock #{} (L1 batch #{}) are inconsistent: {diagnostics}",
+                    self.miniblock_number,
+                    self.l1_batch_number
+                );
+                metrics::counter!(
+                    "server.state_keeper.storage_write_anomalies",
+                    anomalies.len() as u64
+                );
+            }
+        }
     }
 
     fn extract_write_logs(&self, is_fictive: bool) -> Vec<(H256, Vec<StorageLog>)> {
@@ -517,6 +873,49 @@ impl MiniblockSealCommand {
         grouped_logs.collect()
     }
 
+    /// Extracts token balances changed in the miniblock as `(account, token, balance)` tuples. Accounts with
+    /// potentially changed balances are determined by address-like indexed topics of events emitted by token contracts
+    /// (e.g., `Transfer`, `Mint` or `Withdrawal`); balances are taken from writes to the standard balance slots.
+    /// ETH balances are reported with the zero token address.
+    pub(super) fn extract_token_balances(
+        &self,
+        write_logs: &[(H256, Vec<StorageLog>)],
+    ) -> Vec<(Address, Address, U256)> {
+        let balance_keys: HashMap<_, _> = self
+            .miniblock
+            .events
+            .iter()
+            .flat_map(|event| {
+                let accounts = event.indexed_topics.iter().skip(1).filter_map(|topic| {
+                    let is_address = topic.as_bytes()[..12].iter().all(|&byte| byte == 0);
+                    is_address.then(|| h256_to_account_address(topic))
+                });
+                accounts.map(move |account| {
+                    let token = AccountTreeId::new(event.address);
+                    let key = storage_key_for_standard_token_balance(token, &account);
+                    (key, (account, event.address))
+                })
+            })
+            .collect();
+
+        // Later writes to the same slot overwrite earlier ones.
+        let mut balances = HashMap::new();
+        for log in write_logs.iter().flat_map(|(_, logs)| logs) {
+            if let Some(&(account, token)) = balance_keys.get(&log.key) {
+                balances.insert((account, token), h256_to_u256(log.value));
+            }
+        }
+        let balances = balances.into_iter().map(|((account, token), balance)| {
+            let token = if token == L2_ETH_TOKEN_ADDRESS {
+                ETHEREUM_ADDRESS
+            } else {
+                token
+            };
+            (account, token, balance)
+        });
+        balances.collect()
+    }
+
     fn transaction(&self, index: usize) -> &Transaction {
         let tx_result = &self.miniblock.executed_transactions[index - self.first_tx_index];
         &tx_result.transaction
@@ -607,6 +1006,151 @@ impl MiniblockSealCommand {
     }
 }
 
+/// Removes data of miniblocks that were partially persisted before the node was stopped, i.e., miniblocks
+/// without the completion marker set by [`MiniblockSealCommand`]. Must be called before the state keeper starts;
+/// removed miniblocks are re-executed afterwards.
+pub(crate) async fn remove_unsealed_miniblocks(storage: &mut StorageProcessor<'_>) {
+    let Some(first_unsealed) = storage.blocks_dal().get_first_unsealed_miniblock().await else {
+        return;
+    };
+    let last_miniblock_to_keep = first_unsealed - 1;
+    vlog::warn!(
+        "Found partially persisted miniblock {first_unsealed}; removing data for miniblocks after {last_miniblock_to_keep}"
+    );
+    metrics::increment_counter!("server.state_keeper.unsealed_miniblock_recoveries");
+
+    let mut transaction = storage.start_transaction().await;
+    transaction
+        .transactions_dal()
+        .reset_transactions_state(last_miniblock_to_keep)
+        .await;
+    transaction
+        .events_dal()
+        .rollback_events(last_miniblock_to_keep)
+        .await;
+    transaction
+        .events_dal()
+        .rollback_l2_to_l1_logs(last_miniblock_to_keep)
+        .await;
+    transaction
+        .tokens_dal()
+        .rollback_tokens(last_miniblock_to_keep)
+        .await;
+    transaction
+        .tokens_dal()
+        .rollback_token_balances(last_miniblock_to_keep)
+        .await;
+    transaction
+        .storage_dal()
+        .rollback_factory_deps(last_miniblock_to_keep)
+        .await;
+    // Storage is restored based on storage logs, so it must be rolled back before them.
+    transaction
+        .storage_logs_dal()
+        .rollback_storage(last_miniblock_to_keep)
+        .await;
+    transaction
+        .storage_logs_dal()
+        .rollback_storage_logs(last_miniblock_to_keep)
+        .await;
+    transaction
+        .blocks_dal()
+        .delete_miniblocks(last_miniblock_to_keep)
+        .await;
+    transaction.commit().await;
+}
+
+/// Anomaly in the order of storage writes of a miniblock. Transaction indices are relative to the L1 batch.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum StorageWriteAnomaly {
+    /// The write is logged after a write of a later transaction.
+    TxIndexOutOfOrder {
+        key: StorageKey,
+        tx_index: u16,
+        prev_tx_index: u16,
+    },
+    /// The value read by the write differs from the value written by the previous write to the same slot.
+    UnexpectedReadValue {
+        key: StorageKey,
+        tx_index: u16,
+        prev_tx_index: u16,
+        expected: U256,
+        actual: U256,
+    },
+}
+
+impl fmt::Display for StorageWriteAnomaly {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TxIndexOutOfOrder {
+                key,
+                tx_index,
+                prev_tx_index,
+            } => write!(
+                formatter,
+                "write to slot {:?}:{:?} by tx #{tx_index} is logged after a write by tx #{prev_tx_index}",
+                key.address(),
+                key.key()
+            ),
+            Self::UnexpectedReadValue {
+                key,
+                tx_index,
+                prev_tx_index,
+                expected,
+                actual,
+            } => write!(
+                formatter,
+                "write to slot {:?}:{:?} by tx #{tx_index} read value {actual:#x}, while tx #{prev_tx_index} \
+                 wrote {expected:#x}",
+                key.address(),
+                key.key()
+            ),
+        }
+    }
+}
+
+/// Finds anomalies in the order of storage writes in `logs`. Rolled back writes restore the value
+/// preceding the write.
+pub(crate) fn find_storage_write_anomalies(logs: &[StorageLogQuery]) -> Vec<StorageWriteAnomaly> {
+    let mut anomalies = vec![];
+    let mut prev_tx_index = None;
+    let mut slot_values = HashMap::<StorageKey, (U256, u16)>::new();
+    for log in logs {
+        let query = &log.log_query;
+        if !query.rw_flag {
+            continue;
+        }
+        let key = StorageKey::new(AccountTreeId::new(query.address), u256_to_h256(query.key));
+        let tx_index = query.tx_number_in_block;
+        if let Some(prev_tx_index) = prev_tx_index.filter(|&prev| prev > tx_index) {
+            anomalies.push(StorageWriteAnomaly::TxIndexOutOfOrder {
+                key,
+                tx_index,
+                prev_tx_index,
+            });
+        }
+        prev_tx_index = Some(tx_index);
+
+        if query.rollback {
+            slot_values.insert(key, (query.read_value, tx_index));
+            continue;
+        }
+        if let Some(&(expected, prev_tx_index)) = slot_values.get(&key) {
+            if query.read_value != expected {
+                anomalies.push(StorageWriteAnomaly::UnexpectedReadValue {
+                    key,
+                    tx_index,
+                    prev_tx_index,
+                    expected,
+                    actual: query.read_value,
+                });
+            }
+        }
+        slot_values.insert(key, (query.written_value, tx_index));
+    }
+    anomalies
+}
+
 fn l1_l2_tx_count(executed_transactions: &[TransactionExecutionResult]) -> (usize, usize) {
     let mut l1_tx_count = 0;
     let mut l2_tx_count = 0;
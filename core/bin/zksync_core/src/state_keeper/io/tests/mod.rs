@@ -1,24 +1,39 @@
 use futures::FutureExt;
 
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
 use db_test_macro::db_test;
 use vm::vm_with_bootloader::{derive_base_fee_and_gas_per_pubdata, BlockContextMode};
+use zksync_config::configs::chain::{StorageLogAnomalyPolicy, TreeLagPolicy};
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::ConnectionPool;
 use zksync_mempool::L2TxFilter;
 use zksync_types::{
-    block::BlockGasCount, tx::ExecutionMetrics, AccountTreeId, Address, L1BatchNumber,
-    MiniblockNumber, StorageKey, VmEvent, H256, U256,
+    block::{BlockGasCount, MiniblockHasher},
+    event::EventIndexingPolicy,
+    tokens::ETHEREUM_ADDRESS,
+    tx::ExecutionMetrics,
+    utils::storage_key_for_standard_token_balance,
+    vm_trace::Call,
+    AccountTreeId, Address, L1BatchNumber, L1BlockNumber, MiniblockNumber, ProtocolVersionId,
+    StorageKey, StorageLog, StorageLogQuery, VmEvent, H256, L2_ETH_TOKEN_ADDRESS, U256,
 };
-use zksync_utils::time::millis_since_epoch;
+use zksync_utils::{address_to_h256, bytecode::hash_bytecode, time::millis_since_epoch};
 
+use crate::chain_contracts::ChainContracts;
 use crate::state_keeper::{
-    io::{MiniblockSealer, StateKeeperIO},
+    io::{
+        mempool::{TreeLagAction, TreeLagLimit},
+        seal_logic::{
+            find_storage_write_anomalies, remove_unsealed_miniblocks, StorageWriteAnomaly,
+        },
+        MiniblockSealer, StateKeeperIO,
+    },
     mempool_actor::l2_tx_filter,
+    testonly::default_vm_block_result,
     tests::{
         create_block_metadata, create_execution_result, create_transaction, create_updates_manager,
-        default_block_context, default_vm_block_result, Query,
+        default_block_context, Query,
     },
     updates::{MiniblockSealCommand, MiniblockUpdates, UpdatesManager},
 };
@@ -162,6 +177,59 @@ async fn l1_batch_timestamp_with_clock_skew(connection_pool: ConnectionPool) {
     test_l1_batch_timestamps_are_distinct(connection_pool, current_timestamp + 2).await;
 }
 
+#[db_test]
+async fn switching_protocol_version_when_opening_l1_batch(connection_pool: ConnectionPool) {
+    let tester = Tester::new();
+    tester.genesis(&connection_pool).await;
+
+    let bootloader_bytecode = vec![1_u8; 32];
+    let default_aa_bytecode = vec![2_u8; 96];
+    let new_hashes = BaseSystemContractsHashes {
+        bootloader: hash_bytecode(&bootloader_bytecode),
+        default_aa: hash_bytecode(&default_aa_bytecode),
+    };
+    let mut storage = connection_pool.access_test_storage().await;
+    let factory_deps = HashMap::from([
+        (new_hashes.bootloader, bootloader_bytecode),
+        (new_hashes.default_aa, default_aa_bytecode),
+    ]);
+    storage
+        .storage_dal()
+        .insert_factory_deps(MiniblockNumber(0), &factory_deps)
+        .await;
+    // Base system contracts for the new version are not fetched from L1 yet.
+    let new_version = ProtocolVersionId(1);
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version(new_version, L1BlockNumber(1), None)
+        .await;
+
+    let (mut mempool, mut guard) = tester
+        .create_test_mempool_io(connection_pool.clone(), 1)
+        .await;
+    let tx_filter = l2_tx_filter(
+        &tester.create_gas_adjuster().await,
+        tester.fair_l2_gas_price(),
+    );
+    tester.insert_tx(&mut guard, tx_filter.fee_per_gas, tx_filter.gas_per_pubdata);
+
+    let batch_params = mempool
+        .wait_for_new_batch_params(Duration::from_millis(100))
+        .await;
+    assert!(batch_params.is_none());
+
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version(new_version, L1BlockNumber(2), Some(new_hashes))
+        .await;
+    let batch_params = mempool
+        .wait_for_new_batch_params(Duration::from_secs(10))
+        .await
+        .expect("No batch params in the test mempool");
+    assert_eq!(batch_params.protocol_version, Some(new_version));
+    assert_eq!(batch_params.base_system_contracts.hashes(), new_hashes);
+}
+
 #[db_test]
 async fn processing_storage_logs_when_sealing_miniblock(connection_pool: ConnectionPool) {
     let mut miniblock = MiniblockUpdates::new(0);
@@ -211,12 +279,21 @@ async fn processing_storage_logs_when_sealing_miniblock(connection_pool: Connect
         miniblock_number: MiniblockNumber(3),
         miniblock,
         first_tx_index: 0,
+        fee_account_address: Address::default(),
         l1_gas_price: 100,
         fair_l2_gas_price: 100,
         base_fee_per_gas: 10,
         base_system_contracts_hashes: BaseSystemContractsHashes::default(),
-        l2_erc20_bridge_addr: Address::default(),
+        protocol_version: None,
+        chain_contracts: ChainContracts::default(),
+        event_indexing: EventIndexingPolicy::default(),
+        storage_log_anomaly_policy: StorageLogAnomalyPolicy::Lenient,
+        gas_limit: None,
     };
+    // The previous miniblock is required to compute the miniblock hash.
+    Tester::new()
+        .insert_miniblock(&connection_pool, 2, 10, 100, 100)
+        .await;
     let mut conn = connection_pool.access_storage_tagged("state_keeper").await;
     seal_command.seal(&mut conn).await;
 
@@ -245,6 +322,58 @@ async fn processing_storage_logs_when_sealing_miniblock(connection_pool: Connect
     }
 }
 
+#[test]
+fn detecting_storage_write_anomalies() {
+    fn write_logs(tx_index: u16, logs: &[(u64, Query)]) -> Vec<StorageLogQuery> {
+        let logs = logs.iter().map(|&(key, query)| (U256::from(key), query));
+        create_execution_result(tx_index, logs)
+            .result
+            .logs
+            .storage_logs
+    }
+
+    let mut logs = write_logs(
+        0,
+        &[
+            (1, Query::InitialWrite(U256::from(1))),
+            (2, Query::RepeatedWrite(U256::from(5), U256::from(6))),
+        ],
+    );
+    logs.extend(write_logs(
+        2,
+        &[(1, Query::RepeatedWrite(U256::from(1), U256::from(2)))],
+    ));
+    assert!(find_storage_write_anomalies(&logs).is_empty());
+
+    // Tx #1 writes to slot 1 after tx #2 and reads the value written by tx #0.
+    logs.extend(write_logs(
+        1,
+        &[(1, Query::RepeatedWrite(U256::from(1), U256::from(3)))],
+    ));
+    let anomalies = find_storage_write_anomalies(&logs);
+    let key = StorageKey::new(AccountTreeId::default(), H256::from_low_u64_be(1));
+    assert_eq!(
+        anomalies,
+        [
+            StorageWriteAnomaly::TxIndexOutOfOrder {
+                key,
+                tx_index: 1,
+                prev_tx_index: 2,
+            },
+            StorageWriteAnomaly::UnexpectedReadValue {
+                key,
+                tx_index: 1,
+                prev_tx_index: 2,
+                expected: U256::from(2),
+                actual: U256::from(1),
+            },
+        ]
+    );
+    let message = anomalies[1].to_string();
+    assert!(message.contains("by tx #1 read value 0x1"), "{message}");
+    assert!(message.contains("tx #2 wrote 0x2"), "{message}");
+}
+
 #[db_test]
 async fn processing_events_when_sealing_miniblock(pool: ConnectionPool) {
     let l1_batch_number = L1BatchNumber(2);
@@ -276,14 +405,26 @@ async fn processing_events_when_sealing_miniblock(pool: ConnectionPool) {
         miniblock_number,
         miniblock,
         first_tx_index: 0,
+        fee_account_address: Address::default(),
         l1_gas_price: 100,
         fair_l2_gas_price: 100,
         base_fee_per_gas: 10,
         base_system_contracts_hashes: BaseSystemContractsHashes::default(),
-        l2_erc20_bridge_addr: Address::default(),
+        protocol_version: None,
+        chain_contracts: ChainContracts::default(),
+        event_indexing: EventIndexingPolicy::default(),
+        storage_log_anomaly_policy: StorageLogAnomalyPolicy::Lenient,
+        gas_limit: None,
     };
+    Tester::new().insert_miniblock(&pool, 2, 10, 100, 100).await;
     let mut conn = pool.access_storage_tagged("state_keeper").await;
     seal_command.seal(&mut conn).await;
+    // The completion marker must be set for the sealed miniblock.
+    assert_eq!(
+        conn.blocks_dal().get_sealed_miniblock_number().await,
+        miniblock_number
+    );
+    assert_eq!(conn.blocks_dal().get_first_unsealed_miniblock().await, None);
 
     let logs = conn
         .events_web3_dal()
@@ -298,6 +439,226 @@ async fn processing_events_when_sealing_miniblock(pool: ConnectionPool) {
     }
 }
 
+#[db_test]
+async fn sealing_miniblock_in_parallel(pool: ConnectionPool) {
+    let l1_batch_number = L1BatchNumber(2);
+    let mut miniblock = MiniblockUpdates::new(0);
+    let tx = create_transaction(10, 100);
+    let storage_logs = [(U256::from(1), Query::InitialWrite(U256::from(2)))];
+    let mut execution_result = create_execution_result(0, storage_logs);
+    execution_result.result.logs.events = vec![VmEvent {
+        location: (l1_batch_number, 0),
+        value: vec![1],
+        ..VmEvent::default()
+    }];
+    miniblock.extend_from_executed_transaction(
+        tx,
+        execution_result,
+        BlockGasCount::default(),
+        ExecutionMetrics::default(),
+        vec![],
+    );
+
+    let miniblock_number = MiniblockNumber(3);
+    let seal_command = MiniblockSealCommand {
+        l1_batch_number,
+        miniblock_number,
+        miniblock,
+        first_tx_index: 0,
+        fee_account_address: Address::default(),
+        l1_gas_price: 100,
+        fair_l2_gas_price: 100,
+        base_fee_per_gas: 10,
+        base_system_contracts_hashes: BaseSystemContractsHashes::default(),
+        protocol_version: None,
+        chain_contracts: ChainContracts::default(),
+        event_indexing: EventIndexingPolicy::default(),
+        storage_log_anomaly_policy: StorageLogAnomalyPolicy::Lenient,
+        gas_limit: None,
+    };
+    Tester::new().insert_miniblock(&pool, 2, 10, 100, 100).await;
+    seal_command.seal_parallel(&pool).await;
+
+    let mut conn = pool.access_storage_tagged("state_keeper").await;
+    assert_eq!(
+        conn.blocks_dal().get_sealed_miniblock_number().await,
+        miniblock_number
+    );
+    let miniblock_header = conn
+        .blocks_dal()
+        .get_miniblock_header(miniblock_number)
+        .await
+        .unwrap();
+    let mut hasher =
+        MiniblockHasher::new(miniblock_number, miniblock_header.timestamp, H256::zero());
+    hasher.push_tx_hash(seal_command.miniblock.executed_transactions[0].hash);
+    assert_eq!(miniblock_header.hash, hasher.finalize());
+    let logs = conn
+        .events_web3_dal()
+        .get_all_logs(miniblock_number - 1)
+        .await
+        .unwrap();
+    assert_eq!(logs.len(), 1);
+
+    // Emulate a miniblock partially persisted before a restart.
+    conn.blocks_dal()
+        .set_miniblock_is_sealed(miniblock_number, false)
+        .await;
+    assert_eq!(
+        conn.blocks_dal().get_sealed_miniblock_number().await,
+        miniblock_number - 1
+    );
+    remove_unsealed_miniblocks(&mut conn).await;
+    assert!(conn
+        .blocks_dal()
+        .get_miniblock_header(miniblock_number)
+        .await
+        .is_none());
+    let logs = conn
+        .events_web3_dal()
+        .get_all_logs(miniblock_number - 1)
+        .await
+        .unwrap();
+    assert!(logs.is_empty());
+}
+
+#[test]
+fn tree_lag_limit_actions() {
+    let mut limit = TreeLagLimit {
+        max_lag: 5,
+        policy: TreeLagPolicy::Pause,
+        throttle_delay: Duration::from_millis(100),
+    };
+    assert_eq!(limit.action(0), TreeLagAction::Proceed);
+    assert_eq!(limit.action(5), TreeLagAction::Proceed);
+    assert_eq!(limit.action(6), TreeLagAction::Pause);
+
+    limit.policy = TreeLagPolicy::Throttle;
+    assert_eq!(limit.action(5), TreeLagAction::Proceed);
+    assert_eq!(
+        limit.action(8),
+        TreeLagAction::Throttle(Duration::from_millis(300))
+    );
+}
+
+#[test]
+fn truncating_tx_results_in_miniblock_seal_command() {
+    let mut miniblock = MiniblockUpdates::new(0);
+    let mut execution_result = create_execution_result(0, []);
+    execution_result.call_traces = vec![Call {
+        output: vec![1; 100],
+        ..Call::default()
+    }];
+    miniblock.extend_from_executed_transaction(
+        create_transaction(10, 100),
+        execution_result,
+        BlockGasCount::default(),
+        ExecutionMetrics::default(),
+        vec![],
+    );
+    let small_execution_result = create_execution_result(1, []);
+    miniblock.extend_from_executed_transaction(
+        create_transaction(10, 100),
+        small_execution_result,
+        BlockGasCount::default(),
+        ExecutionMetrics::default(),
+        vec![],
+    );
+
+    let mut seal_command = MiniblockSealCommand {
+        l1_batch_number: L1BatchNumber(1),
+        miniblock_number: MiniblockNumber(1),
+        miniblock,
+        first_tx_index: 0,
+        fee_account_address: Address::default(),
+        l1_gas_price: 100,
+        fair_l2_gas_price: 100,
+        base_fee_per_gas: 10,
+        base_system_contracts_hashes: BaseSystemContractsHashes::default(),
+        protocol_version: None,
+        chain_contracts: ChainContracts::default(),
+        event_indexing: EventIndexingPolicy::default(),
+        storage_log_anomaly_policy: StorageLogAnomalyPolicy::Lenient,
+        gas_limit: None,
+    };
+    seal_command.truncate_tx_results(32, 32);
+
+    let tx_results = &seal_command.miniblock.executed_transactions;
+    assert!(tx_results[0].result_truncated);
+    assert_eq!(tx_results[0].call_traces[0].output, [1; 32]);
+    assert!(!tx_results[1].result_truncated);
+}
+
+#[test]
+fn extracting_token_balances_in_miniblock_seal_command() {
+    let token = Address::repeat_byte(0x11);
+    let (sender, recipient) = (Address::repeat_byte(1), Address::repeat_byte(2));
+    let transfer_event = |token: Address| VmEvent {
+        address: token,
+        indexed_topics: vec![
+            H256::repeat_byte(0xff), // event signature
+            address_to_h256(&sender),
+            address_to_h256(&recipient),
+        ],
+        ..VmEvent::default()
+    };
+    let mut miniblock = MiniblockUpdates::new(0);
+    let mut execution_result = create_execution_result(0, []);
+    execution_result.result.logs.events =
+        vec![transfer_event(token), transfer_event(L2_ETH_TOKEN_ADDRESS)];
+    miniblock.extend_from_executed_transaction(
+        create_transaction(10, 100),
+        execution_result,
+        BlockGasCount::default(),
+        ExecutionMetrics::default(),
+        vec![],
+    );
+    let seal_command = MiniblockSealCommand {
+        l1_batch_number: L1BatchNumber(1),
+        miniblock_number: MiniblockNumber(1),
+        miniblock,
+        first_tx_index: 0,
+        fee_account_address: Address::default(),
+        l1_gas_price: 100,
+        fair_l2_gas_price: 100,
+        base_fee_per_gas: 10,
+        base_system_contracts_hashes: BaseSystemContractsHashes::default(),
+        protocol_version: None,
+        chain_contracts: ChainContracts::default(),
+        event_indexing: EventIndexingPolicy::default(),
+        storage_log_anomaly_policy: StorageLogAnomalyPolicy::Lenient,
+        gas_limit: None,
+    };
+
+    let balance_key = |token: Address, account: &Address| {
+        storage_key_for_standard_token_balance(AccountTreeId::new(token), account)
+    };
+    let write_logs = [
+        StorageLog::new_write_log(balance_key(token, &sender), H256::from_low_u64_be(10)),
+        StorageLog::new_write_log(balance_key(token, &recipient), H256::from_low_u64_be(5)),
+        StorageLog::new_write_log(balance_key(token, &sender), H256::from_low_u64_be(8)),
+        StorageLog::new_write_log(
+            balance_key(L2_ETH_TOKEN_ADDRESS, &sender),
+            H256::from_low_u64_be(1),
+        ),
+        // Balance of an account not mentioned in events
+        StorageLog::new_write_log(
+            balance_key(token, &Address::repeat_byte(3)),
+            H256::from_low_u64_be(1),
+        ),
+    ];
+    let mut balances = seal_command.extract_token_balances(&[(H256::zero(), write_logs.to_vec())]);
+    balances.sort_unstable();
+    assert_eq!(
+        balances,
+        [
+            (sender, ETHEREUM_ADDRESS, U256::from(1)),
+            (sender, token, U256::from(8)),
+            (recipient, token, U256::from(5)),
+        ]
+    );
+}
+
 async fn test_miniblock_and_l1_batch_processing(
     pool: ConnectionPool,
     miniblock_sealer_capacity: usize,
@@ -321,8 +682,11 @@ async fn test_miniblock_and_l1_batch_processing(
     let mut block_context = default_block_context();
     block_context.context.block_timestamp = 100; // change timestamp to pass monotonicity check
     let block_context_mode = BlockContextMode::NewBlock(block_context, 0.into());
-    let mut updates =
-        UpdatesManager::new(&block_context_mode, BaseSystemContractsHashes::default());
+    let mut updates = UpdatesManager::new(
+        &block_context_mode,
+        BaseSystemContractsHashes::default(),
+        None,
+    );
 
     let tx = create_transaction(10, 100);
     updates.extend_from_executed_transaction(
@@ -374,7 +738,9 @@ async fn miniblock_sealer_handle_blocking(pool: ConnectionPool) {
     let seal_command = updates_manager.seal_miniblock_command(
         L1BatchNumber(1),
         MiniblockNumber(1),
-        Address::default(),
+        ChainContracts::default(),
+        &EventIndexingPolicy::default(),
+        StorageLogAnomalyPolicy::Lenient,
     );
     sealer_handle.submit(seal_command).await;
 
@@ -382,7 +748,9 @@ async fn miniblock_sealer_handle_blocking(pool: ConnectionPool) {
     let seal_command = updates_manager.seal_miniblock_command(
         L1BatchNumber(1),
         MiniblockNumber(2),
-        Address::default(),
+        ChainContracts::default(),
+        &EventIndexingPolicy::default(),
+        StorageLogAnomalyPolicy::Lenient,
     );
     {
         let submit_future = sealer_handle.submit(seal_command);
@@ -410,7 +778,9 @@ async fn miniblock_sealer_handle_blocking(pool: ConnectionPool) {
     let seal_command = updates_manager.seal_miniblock_command(
         L1BatchNumber(2),
         MiniblockNumber(3),
-        Address::default(),
+        ChainContracts::default(),
+        &EventIndexingPolicy::default(),
+        StorageLogAnomalyPolicy::Lenient,
     );
     sealer_handle.submit(seal_command).await;
     let command = sealer.commands_receiver.recv().await.unwrap();
@@ -428,7 +798,9 @@ async fn miniblock_sealer_handle_parallel_processing(pool: ConnectionPool) {
         let seal_command = updates_manager.seal_miniblock_command(
             L1BatchNumber(1),
             MiniblockNumber(i),
-            Address::default(),
+            ChainContracts::default(),
+            &EventIndexingPolicy::default(),
+            StorageLogAnomalyPolicy::Lenient,
         );
         sealer_handle.submit(seal_command).await;
     }
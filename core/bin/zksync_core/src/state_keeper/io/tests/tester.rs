@@ -13,6 +13,7 @@ use zksync_types::{
 };
 
 use crate::{
+    chain_contracts::ChainContracts,
     genesis::create_genesis_block,
     l1_gas_price::GasAdjuster,
     state_keeper::{io::MiniblockSealer, tests::create_transaction, MempoolGuard, MempoolIO},
@@ -64,7 +65,7 @@ impl Tester {
         miniblock_sealer_capacity: usize,
     ) -> (MempoolIO<GasAdjuster<MockEthereum>>, MempoolGuard) {
         let gas_adjuster = Arc::new(self.create_gas_adjuster().await);
-        let mempool = MempoolGuard::new(PriorityOpId(0), 100);
+        let mempool = MempoolGuard::new(PriorityOpId(0), 100, None);
         let (miniblock_sealer, miniblock_sealer_handle) =
             MiniblockSealer::new(pool.clone(), miniblock_sealer_capacity);
         tokio::spawn(miniblock_sealer.run());
@@ -76,7 +77,7 @@ impl Tester {
             default_aa_hash: base_contract_hashes.default_aa,
             ..StateKeeperConfig::default()
         };
-        let l2_erc20_bridge_addr = Address::repeat_byte(0x5a); // Isn't relevant.
+        let chain_contracts = ChainContracts::default(); // Isn't relevant.
         let io = MempoolIO::new(
             mempool.clone(),
             miniblock_sealer_handle,
@@ -84,7 +85,7 @@ impl Tester {
             pool,
             &config,
             Duration::from_secs(1),
-            l2_erc20_bridge_addr,
+            chain_contracts,
         )
         .await;
 
@@ -0,0 +1,177 @@
+//! Policy for choosing miniblock timestamps.
+
+use std::time::{Duration, Instant};
+
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_utils::time::millis_since_epoch;
+
+use crate::state_keeper::extractors;
+
+/// Policy determining the timestamp of the next miniblock based on the timestamp of the previous one
+/// and the wall clock.
+///
+/// The policy guarantees that miniblock timestamps are strictly increasing (two miniblocks never share
+/// a timestamp), even if the wall clock is skewed or goes backwards, which allows to validate timestamps
+/// without relying on the local clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct MiniblockTimestampPolicy {
+    /// If set, the timestamp of the next miniblock is `prev_timestamp + fixed_interval`,
+    /// unless the wall clock is ahead of this value.
+    fixed_interval: Option<u64>,
+    /// Min difference between timestamps of consecutive miniblocks; always positive.
+    min_interval: u64,
+    /// Max number of seconds a timestamp may be ahead of the wall clock.
+    max_drift: u64,
+}
+
+impl Default for MiniblockTimestampPolicy {
+    fn default() -> Self {
+        Self {
+            fixed_interval: None,
+            min_interval: 1,
+            max_drift: 0,
+        }
+    }
+}
+
+impl MiniblockTimestampPolicy {
+    pub fn new(config: &StateKeeperConfig) -> Self {
+        Self {
+            fixed_interval: config.miniblock_timestamp_interval_sec,
+            min_interval: config.miniblock_min_timestamp_interval_sec(),
+            max_drift: config.miniblock_max_timestamp_drift_sec(),
+        }
+    }
+
+    /// Returns the timestamp for the miniblock following a miniblock with `prev_timestamp`
+    /// given the current wall clock timestamp `now`.
+    fn next_timestamp(&self, prev_timestamp: u64, now: u64) -> u64 {
+        let scheduled_timestamp = match self.fixed_interval {
+            // If the wall clock has got ahead of the schedule (e.g., after an idle period), it's used instead
+            // so that timestamps don't lag behind the real time.
+            Some(interval) => (prev_timestamp + interval).max(now),
+            None => now,
+        };
+        scheduled_timestamp.max(prev_timestamp + self.min_interval)
+    }
+
+    /// Returns the earliest wall clock timestamp at which `timestamp` can be used.
+    fn not_before(&self, timestamp: u64) -> u64 {
+        timestamp.saturating_sub(self.max_drift)
+    }
+
+    /// Checks whether `timestamp` is a valid timestamp for a miniblock following a miniblock with `prev_timestamp`.
+    /// Unlike [`Self::wait_for_next_timestamp()`], this check doesn't depend on the wall clock.
+    pub fn is_valid(&self, prev_timestamp: u64, timestamp: u64) -> bool {
+        timestamp >= prev_timestamp + self.min_interval
+    }
+
+    /// Waits until the next miniblock timestamp is allowed by the policy. Returns `None` if this doesn't happen
+    /// before the `deadline`.
+    pub async fn wait_for_next_timestamp(
+        &self,
+        prev_timestamp: u64,
+        deadline: Instant,
+    ) -> Option<u64> {
+        loop {
+            let now_millis = millis_since_epoch() as u64;
+            let now = now_millis / 1_000;
+            let timestamp = self.next_timestamp(prev_timestamp, now);
+            let not_before = self.not_before(timestamp);
+            if now >= not_before {
+                metrics::gauge!(
+                    "server.state_keeper.miniblock_timestamp_drift",
+                    timestamp as f64 - now as f64
+                );
+                return Some(timestamp);
+            }
+
+            vlog::debug!(
+                "Miniblock timestamp {} is too far ahead of the wall clock {}; waiting",
+                extractors::display_timestamp(timestamp),
+                extractors::display_timestamp(now)
+            );
+            // Wait until the wall clock reaches `not_before`. The check is repeated afterwards
+            // to handle marginal cases, such as the system time getting changed during `sleep()`.
+            let wait = Duration::from_millis(not_before * 1_000 - now_millis);
+            tokio::time::timeout_at(deadline.into(), tokio::time::sleep(wait))
+                .await
+                .ok()?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_utils::time::seconds_since_epoch;
+
+    use super::*;
+
+    #[test]
+    fn timestamps_are_increasing_with_wall_clock() {
+        let policy = MiniblockTimestampPolicy::default();
+        assert_eq!(policy.next_timestamp(100, 105), 105);
+        // Wall clock hasn't moved or went backwards.
+        assert_eq!(policy.next_timestamp(100, 100), 101);
+        assert_eq!(policy.next_timestamp(100, 90), 101);
+        assert!(policy.is_valid(100, 101));
+        assert!(!policy.is_valid(100, 100));
+    }
+
+    #[test]
+    fn fixed_interval() {
+        let policy = MiniblockTimestampPolicy {
+            fixed_interval: Some(2),
+            min_interval: 1,
+            max_drift: 5,
+        };
+        assert_eq!(policy.next_timestamp(100, 100), 102);
+        assert_eq!(policy.next_timestamp(100, 101), 102);
+        // Wall clock is ahead of the schedule.
+        assert_eq!(policy.next_timestamp(100, 110), 110);
+        assert_eq!(policy.not_before(102), 97);
+    }
+
+    #[test]
+    fn min_interval() {
+        let policy = MiniblockTimestampPolicy {
+            fixed_interval: None,
+            min_interval: 3,
+            max_drift: 0,
+        };
+        assert_eq!(policy.next_timestamp(100, 101), 103);
+        assert_eq!(policy.next_timestamp(100, 104), 104);
+        assert!(!policy.is_valid(100, 102));
+    }
+
+    // This test defensively uses large deadlines in order to account for tests running in parallel etc.
+    #[tokio::test]
+    async fn waiting_for_next_timestamp() {
+        let policy = MiniblockTimestampPolicy::default();
+        for prev_timestamp in [0, 1_000, seconds_since_epoch() - 10] {
+            let deadline = Instant::now() + Duration::from_secs(1);
+            let timestamp = policy
+                .wait_for_next_timestamp(prev_timestamp, deadline)
+                .await
+                .unwrap();
+            assert!(timestamp > prev_timestamp);
+        }
+
+        let prev_timestamp = seconds_since_epoch() + 1;
+        let deadline = Instant::now() + Duration::from_secs(3);
+        let timestamp = policy
+            .wait_for_next_timestamp(prev_timestamp, deadline)
+            .await
+            .unwrap();
+        assert!(timestamp > prev_timestamp);
+        assert!(seconds_since_epoch() >= timestamp);
+
+        let prev_timestamp = seconds_since_epoch() + 1;
+        let deadline = Instant::now() + Duration::from_millis(100);
+        // ^ This deadline is too small (we need at least 1_000ms)
+        let result = policy
+            .wait_for_next_timestamp(prev_timestamp, deadline)
+            .await;
+        assert!(result.is_none());
+    }
+}
@@ -1,17 +1,29 @@
+use futures::future;
 use tokio::sync::watch;
 
-use std::time::{Duration, Instant};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
-use vm::TxRevertReason;
+use vm::{vm::VmTxExecutionResult, TxRevertReason};
 use zksync_types::{
-    storage_writes_deduplicator::StorageWritesDeduplicator, MiniblockNumber, Transaction,
+    storage_writes_deduplicator::StorageWritesDeduplicator,
+    tx::{
+        tx_execution_info::{ExecutionMetrics, PersistedTxExecution},
+        TxRejectedResource, TxRejection,
+    },
+    MiniblockNumber, Transaction,
 };
 
 use crate::gas_tracker::gas_count_from_writes;
+use crate::l1_batch_status_bus::{L1BatchStage, L1BatchStatusBus};
 use crate::state_keeper::{
     batch_executor::{BatchExecutorHandle, L1BatchExecutorBuilder, TxExecutionResult},
     extractors,
     io::{L1BatchParams, PendingBatchData, StateKeeperIO},
+    pending_receipts::PendingReceipts,
+    pending_state::PendingStorageDiffs,
     seal_criteria::{SealData, SealManager, SealResolution},
     types::ExecutionMetricsForCriteria,
     updates::UpdatesManager,
@@ -21,10 +33,90 @@ use crate::state_keeper::{
 /// we only need it to not block on waiting indefinitely and be able to process cancellation requests.
 pub(super) const POLL_WAIT_DURATION: Duration = Duration::from_secs(1);
 
+/// Converts the reason a transaction was rejected by the VM into a structured rejection.
+fn vm_rejection(reason: &TxRevertReason) -> TxRejection {
+    let message = reason.to_string();
+    match reason {
+        TxRevertReason::TooBigGasLimit => {
+            TxRejection::resource_limit_exceeded(TxRejectedResource::Gas, message)
+        }
+        TxRevertReason::ExecutionTimeout => {
+            TxRejection::resource_limit_exceeded(TxRejectedResource::ExecutionTime, message)
+        }
+        _ => TxRejection::rejected_by_vm(message),
+    }
+}
+
 /// Structure used to indicate that task cancellation was requested.
 #[derive(Debug)]
 struct Canceled;
 
+/// Resolves once the stop signal is received. Never resolves if the stop signal sender is dropped.
+async fn wait_for_stop_signal(stop_receiver: &mut watch::Receiver<bool>) {
+    while !*stop_receiver.borrow() {
+        if stop_receiver.changed().await.is_err() {
+            future::pending::<()>().await;
+        }
+    }
+}
+
+/// Compares the outcome of a re-executed transaction with the outcome persisted during its original execution.
+/// Returns a human-readable description of each mismatch.
+fn diff_reexecuted_tx(
+    expected: &PersistedTxExecution,
+    tx_result: &VmTxExecutionResult,
+    metrics: &ExecutionMetrics,
+) -> Vec<String> {
+    let mut diff = vec![];
+    if expected.status != tx_result.status {
+        diff.push(format!(
+            "status: persisted {:?}, re-executed {:?}",
+            expected.status, tx_result.status
+        ));
+    }
+    if expected.refunded_gas != tx_result.gas_refunded {
+        diff.push(format!(
+            "refunded gas: persisted {}, re-executed {}",
+            expected.refunded_gas, tx_result.gas_refunded
+        ));
+    }
+    let Some(expected_metrics) = &expected.metrics else {
+        return diff;
+    };
+    let compared_metrics = [
+        ("gas used", expected_metrics.gas_used, metrics.gas_used),
+        ("VM events", expected_metrics.vm_events, metrics.vm_events),
+        (
+            "L2->L1 logs",
+            expected_metrics.l2_l1_logs,
+            metrics.l2_l1_logs,
+        ),
+        (
+            "L2->L1 long messages",
+            expected_metrics.l2_l1_long_messages,
+            metrics.l2_l1_long_messages,
+        ),
+        (
+            "storage logs",
+            expected_metrics.storage_logs,
+            metrics.storage_logs,
+        ),
+        (
+            "published bytecode bytes",
+            expected_metrics.published_bytecode_bytes,
+            metrics.published_bytecode_bytes,
+        ),
+    ];
+    for (name, persisted, reexecuted) in compared_metrics {
+        if persisted != reexecuted {
+            diff.push(format!(
+                "{name}: persisted {persisted}, re-executed {reexecuted}"
+            ));
+        }
+    }
+    diff
+}
+
 /// State keeper represents a logic layer of batch/miniblock processing flow.
 /// It's responsible for taking all the data from the `StateKeeperIO`, feeding it into `BatchExecutor` objects
 /// and calling `SealManager` to decide whether miniblock or batch should be sealed.
@@ -40,6 +132,9 @@ pub struct ZkSyncStateKeeper {
     io: Box<dyn StateKeeperIO>,
     batch_executor_base: Box<dyn L1BatchExecutorBuilder>,
     sealer: SealManager,
+    pending_receipts: Option<PendingReceipts>,
+    pending_storage_diffs: Option<PendingStorageDiffs>,
+    l1_batch_status_bus: Option<L1BatchStatusBus>,
 }
 
 impl ZkSyncStateKeeper {
@@ -54,9 +149,35 @@ impl ZkSyncStateKeeper {
             io,
             batch_executor_base,
             sealer,
+            pending_receipts: None,
+            pending_storage_diffs: None,
+            l1_batch_status_bus: None,
         }
     }
 
+    /// Makes the state keeper record provisional receipts for executed transactions, so that they can be served
+    /// by the API before the miniblock containing them is persisted.
+    pub fn with_pending_receipts(mut self, pending_receipts: PendingReceipts) -> Self {
+        self.pending_receipts = Some(pending_receipts);
+        self
+    }
+
+    /// Makes the state keeper record storage diffs of executed transactions, so that the API can execute
+    /// transactions against the pending state before the miniblocks are persisted.
+    pub fn with_pending_storage_diffs(
+        mut self,
+        pending_storage_diffs: PendingStorageDiffs,
+    ) -> Self {
+        self.pending_storage_diffs = Some(pending_storage_diffs);
+        self
+    }
+
+    /// Makes the state keeper publish sealed L1 batches to the provided bus.
+    pub fn with_l1_batch_status_bus(mut self, bus: L1BatchStatusBus) -> Self {
+        self.l1_batch_status_bus = Some(bus);
+        self
+    }
+
     pub async fn run(mut self) {
         match self.run_inner().await {
             Ok(()) => {
@@ -81,6 +202,7 @@ impl ZkSyncStateKeeper {
         let PendingBatchData {
             params,
             txs: txs_to_reexecute,
+            expected_executions,
         } = match self.io.load_pending_batch().await {
             Some(params) => {
                 vlog::info!(
@@ -99,6 +221,7 @@ impl ZkSyncStateKeeper {
                 PendingBatchData {
                     params: self.wait_for_new_batch_params().await?,
                     txs: Vec::new(),
+                    expected_executions: Vec::new(),
                 }
             }
         };
@@ -107,14 +230,20 @@ impl ZkSyncStateKeeper {
         let mut updates_manager = UpdatesManager::new(
             &l1_batch_params.context_mode,
             l1_batch_params.base_system_contracts.hashes(),
+            l1_batch_params.protocol_version,
         );
 
         let mut batch_executor = self
             .batch_executor_base
             .init_batch(l1_batch_params.clone())
             .await;
-        self.restore_state(&batch_executor, &mut updates_manager, txs_to_reexecute)
-            .await?;
+        self.restore_state(
+            &batch_executor,
+            &mut updates_manager,
+            txs_to_reexecute,
+            expected_executions,
+        )
+        .await?;
 
         let mut l1_batch_seal_delta: Option<Instant> = None;
         loop {
@@ -125,7 +254,15 @@ impl ZkSyncStateKeeper {
                 .await?;
 
             // Finish current batch.
+            if let Some(seal_reason) = updates_manager.l1_batch.seal_reason {
+                metrics::increment_counter!(
+                    "server.state_keeper.l1_batch.seal_reason",
+                    "criterion" => seal_reason
+                );
+            }
             if !updates_manager.miniblock.executed_transactions.is_empty() {
+                // The last miniblock in the batch is sealed because the batch is sealed.
+                updates_manager.miniblock.seal_reason = updates_manager.l1_batch.seal_reason;
                 self.io.seal_miniblock(&updates_manager).await;
                 // We've sealed the miniblock that we had, but we still need to setup the timestamp
                 // for the fictive miniblock.
@@ -133,6 +270,7 @@ impl ZkSyncStateKeeper {
                 updates_manager.push_miniblock(fictive_miniblock_timestamp);
             }
             let block_result = batch_executor.finish_batch().await;
+            let sealed_l1_batch_number = self.io.current_l1_batch_number();
             self.io
                 .seal_l1_batch(
                     block_result,
@@ -140,6 +278,13 @@ impl ZkSyncStateKeeper {
                     l1_batch_params.context_mode.inner_block_context(),
                 )
                 .await;
+            if let Some(pending_receipts) = &self.pending_receipts {
+                // Cached receipts don't contain the L1 batch number, which is now assigned.
+                pending_receipts.clear_sealed();
+            }
+            if let Some(bus) = &self.l1_batch_status_bus {
+                bus.publish(L1BatchStage::Sealed, sealed_l1_batch_number);
+            }
             if let Some(delta) = l1_batch_seal_delta {
                 metrics::histogram!("server.state_keeper.l1_batch.seal_delta", delta.elapsed());
             }
@@ -150,6 +295,7 @@ impl ZkSyncStateKeeper {
             updates_manager = UpdatesManager::new(
                 &l1_batch_params.context_mode,
                 l1_batch_params.base_system_contracts.hashes(),
+                l1_batch_params.protocol_version,
             );
             batch_executor = self
                 .batch_executor_base
@@ -165,14 +311,21 @@ impl ZkSyncStateKeeper {
         Ok(())
     }
 
+    /// Waits for the parameters of the next L1 batch. Unlike other waiting methods, this one
+    /// is interrupted as soon as the stop signal is received, since the state keeper may stay idle
+    /// for a long time waiting for transactions.
     async fn wait_for_new_batch_params(&mut self) -> Result<L1BatchParams, Canceled> {
-        let params = loop {
-            if let Some(params) = self.io.wait_for_new_batch_params(POLL_WAIT_DURATION).await {
-                break params;
-            }
+        loop {
             self.check_if_cancelled()?;
-        };
-        Ok(params)
+            tokio::select! {
+                params = self.io.wait_for_new_batch_params(POLL_WAIT_DURATION) => {
+                    if let Some(params) = params {
+                        return Ok(params);
+                    }
+                }
+                () = wait_for_stop_signal(&mut self.stop_receiver) => return Err(Canceled),
+            }
+        }
     }
 
     async fn wait_for_new_miniblock_params(&mut self) -> Result<u64, Canceled> {
@@ -199,7 +352,12 @@ impl ZkSyncStateKeeper {
         batch_executor: &BatchExecutorHandle,
         updates_manager: &mut UpdatesManager,
         txs_to_reexecute: Vec<(MiniblockNumber, Vec<Transaction>)>,
+        expected_executions: Vec<PersistedTxExecution>,
     ) -> Result<(), Canceled> {
+        let expected_executions: HashMap<_, _> = expected_executions
+            .into_iter()
+            .map(|execution| (execution.hash, execution))
+            .collect();
         let miniblocks_count = txs_to_reexecute.len();
         for (idx, (miniblock_number, txs)) in txs_to_reexecute.into_iter().enumerate() {
             vlog::info!(
@@ -229,6 +387,18 @@ impl ZkSyncStateKeeper {
                 let exec_result_status = tx_result.status;
 
                 let tx_hash = tx.hash();
+                if let Some(expected) = expected_executions.get(&tx_hash) {
+                    let diff = diff_reexecuted_tx(expected, &tx_result, &tx_execution_metrics);
+                    if !diff.is_empty() {
+                        // Continuing would diverge from the state already exposed via the API.
+                        metrics::increment_counter!("server.state_keeper.reexecution_mismatches");
+                        panic!(
+                            "Re-executing tx {tx_hash:?} from miniblock {miniblock_number} produced \
+                             a result different from the persisted one: {}",
+                            diff.join("; ")
+                        );
+                    }
+                }
                 let initiator_account = tx.initiator_account();
                 let is_l1 = tx.is_l1();
                 updates_manager.extend_from_executed_transaction(
@@ -271,23 +441,25 @@ impl ZkSyncStateKeeper {
     ) -> Result<(), Canceled> {
         loop {
             self.check_if_cancelled()?;
-            if self
+            if let Some(seal_reason) = self
                 .sealer
                 .should_seal_l1_batch_unconditionally(updates_manager)
             {
                 vlog::debug!(
-                    "L1 batch #{} should be sealed unconditionally as per sealing rules",
+                    "L1 batch #{} should be sealed unconditionally as per sealing rule `{seal_reason}`",
                     self.io.current_l1_batch_number()
                 );
+                updates_manager.l1_batch.seal_reason = Some(seal_reason);
                 return Ok(());
             }
 
-            if self.sealer.should_seal_miniblock(updates_manager) {
+            if let Some(seal_reason) = self.sealer.should_seal_miniblock(updates_manager) {
                 vlog::debug!(
-                    "Miniblock #{} (L1 batch #{}) should be sealed as per sealing rules",
+                    "Miniblock #{} (L1 batch #{}) should be sealed as per sealing rule `{seal_reason}`",
                     self.io.current_miniblock_number(),
                     self.io.current_l1_batch_number()
                 );
+                updates_manager.miniblock.seal_reason = Some(seal_reason);
                 self.io.seal_miniblock(updates_manager).await;
 
                 let new_timestamp = self.wait_for_new_miniblock_params().await?;
@@ -312,7 +484,7 @@ impl ZkSyncStateKeeper {
             );
 
             let tx_hash = tx.hash();
-            let (seal_resolution, exec_result) = self
+            let (seal_resolution, seal_criterion, exec_result) = self
                 .process_one_tx(batch_executor, updates_manager, tx.clone())
                 .await;
 
@@ -339,23 +511,37 @@ impl ZkSyncStateKeeper {
                         tx_l1_gas_this_tx,
                         tx_execution_metrics,
                     );
+                    if let Some(pending_receipts) = &self.pending_receipts {
+                        pending_receipts.record_last_executed_tx(
+                            updates_manager,
+                            self.io.current_l1_batch_number(),
+                            self.io.current_miniblock_number(),
+                        );
+                    }
+                    if let Some(pending_storage_diffs) = &self.pending_storage_diffs {
+                        pending_storage_diffs.record_last_executed_tx(
+                            updates_manager,
+                            self.io.current_miniblock_number(),
+                        );
+                    }
                 }
                 SealResolution::ExcludeAndSeal => {
                     batch_executor.rollback_last_tx().await;
                     self.io.rollback(tx).await;
                 }
-                SealResolution::Unexecutable(reason) => {
+                SealResolution::Unexecutable(rejection) => {
                     batch_executor.rollback_last_tx().await;
-                    self.io.reject(&tx, reason).await;
+                    self.io.reject(&tx, rejection).await;
                 }
             };
 
             if seal_resolution.should_seal() {
                 vlog::debug!(
-                    "L1 batch #{} should be sealed with resolution {seal_resolution:?} after executing \
-                     transaction {tx_hash}",
+                    "L1 batch #{} should be sealed with resolution {seal_resolution:?} (criterion: \
+                     {seal_criterion:?}) after executing transaction {tx_hash}",
                     self.io.current_l1_batch_number()
                 );
+                updates_manager.l1_batch.seal_reason = seal_criterion;
                 return Ok(());
             }
         }
@@ -366,43 +552,54 @@ impl ZkSyncStateKeeper {
     /// 1. The VM entered an incorrect state (e.g. out of gas). In that case, we must revert the transaction and seal
     /// the block.
     /// 2. Seal manager decided that batch is ready to be sealed.
+    /// Besides the seal resolution, returns the name of the criterion that determined it, if any.
     /// Note: this method doesn't mutate `updates_manager` in the end. However, reference should be mutable
     /// because we use `apply_and_rollback` method of `updates_manager.storage_writes_deduplicator`.
+    #[tracing::instrument(skip_all, fields(tx_hash = ?tx.hash()))]
     async fn process_one_tx(
         &mut self,
         batch_executor: &BatchExecutorHandle,
         updates_manager: &mut UpdatesManager,
         tx: Transaction,
-    ) -> (SealResolution, TxExecutionResult) {
+    ) -> (SealResolution, Option<&'static str>, TxExecutionResult) {
         let exec_result = batch_executor.execute_tx(tx.clone()).await;
-        let resolution = match &exec_result {
+        let (resolution, criterion) = match &exec_result {
             TxExecutionResult::BootloaderOutOfGasForTx => {
+                const CRITERION: &str = "bootloader_tx_out_of_gas";
                 metrics::increment_counter!(
                     "server.tx_aggregation.reason",
-                    "criterion" => "bootloader_tx_out_of_gas",
+                    "criterion" => CRITERION,
                     "seal_resolution" => "exclude_and_seal",
                 );
-                SealResolution::ExcludeAndSeal
+                (SealResolution::ExcludeAndSeal, Some(CRITERION))
             }
             TxExecutionResult::BootloaderOutOfGasForBlockTip => {
+                const CRITERION: &str = "bootloader_block_tip_failed";
                 metrics::increment_counter!(
                     "server.tx_aggregation.reason",
-                    "criterion" => "bootloader_block_tip_failed",
+                    "criterion" => CRITERION,
                     "seal_resolution" => "exclude_and_seal",
                 );
-                SealResolution::ExcludeAndSeal
+                (SealResolution::ExcludeAndSeal, Some(CRITERION))
             }
             TxExecutionResult::RejectedByVm { rejection_reason } => match rejection_reason {
                 TxRevertReason::NotEnoughGasProvided => {
+                    const CRITERION: &str = "not_enough_gas_provided_to_start_tx";
                     metrics::increment_counter!(
                         "server.tx_aggregation.reason",
-                        "criterion" => "not_enough_gas_provided_to_start_tx",
+                        "criterion" => CRITERION,
                         "seal_resolution" => "exclude_and_seal",
                     );
-                    SealResolution::ExcludeAndSeal
+                    (SealResolution::ExcludeAndSeal, Some(CRITERION))
                 }
-                _ => SealResolution::Unexecutable(rejection_reason.to_string()),
+                _ => (
+                    SealResolution::Unexecutable(vm_rejection(rejection_reason)),
+                    None,
+                ),
             },
+            TxExecutionResult::Unexecutable { reason } => {
+                (SealResolution::Unexecutable((*reason).into()), None)
+            }
             TxExecutionResult::Success {
                 tx_result,
                 tx_metrics,
@@ -439,6 +636,9 @@ impl ZkSyncStateKeeper {
                     execution_metrics: finish_block_execution_metrics,
                 } = *bootloader_dry_run_metrics;
 
+                let l2_tx_count = usize::from(!tx.is_l1());
+                let factory_deps_count = tx.execute.factory_deps_length();
+                let factory_deps_size = tx.execute.factory_deps_size();
                 let encoding_len = extractors::encoded_transaction_size(tx);
 
                 let logs_to_apply = tx_result.result.logs.storage_logs.iter();
@@ -459,6 +659,9 @@ impl ZkSyncStateKeeper {
                     gas_count: tx_gas_excluding_writes + tx_writes_l1_gas,
                     cumulative_size: encoding_len,
                     writes_metrics: tx_writes_metrics,
+                    l2_tx_count,
+                    factory_deps_count,
+                    factory_deps_size,
                 };
                 let block_data = SealData {
                     execution_metrics: tx_data.execution_metrics
@@ -469,6 +672,9 @@ impl ZkSyncStateKeeper {
                     cumulative_size: tx_data.cumulative_size
                         + updates_manager.pending_txs_encoding_size(),
                     writes_metrics: block_writes_metrics,
+                    l2_tx_count: tx_data.l2_tx_count
+                        + updates_manager.pending_l2_transactions_len(),
+                    ..SealData::default()
                 };
                 self.sealer.should_seal_l1_batch(
                     self.io.current_l1_batch_number().0,
@@ -479,6 +685,6 @@ impl ZkSyncStateKeeper {
                 )
             }
         };
-        (resolution, exec_result)
+        (resolution, criterion, exec_result)
     }
 }
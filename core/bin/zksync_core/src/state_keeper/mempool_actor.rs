@@ -1,14 +1,22 @@
 use super::types::MempoolGuard;
 use crate::l1_gas_price::L1GasPriceProvider;
+use chrono::NaiveDateTime;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
-use tokio::sync::watch;
+use tokio::sync::{mpsc, watch};
 use vm::vm_with_bootloader::derive_base_fee_and_gas_per_pubdata;
 use zksync_config::configs::chain::MempoolConfig;
 
-use zksync_dal::ConnectionPool;
+use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_mempool::L2TxFilter;
+use zksync_types::{Address, ExecuteTransactionCommon, Nonce, Transaction, H256};
+
+/// Overlap between consecutive delta syncs of the mempool. `updated_at` of a transaction is assigned
+/// when the corresponding DB transaction starts, so a transaction can become visible after transactions
+/// with a later `updated_at`; re-reading the overlap window ensures that such transactions are not missed.
+const DELTA_SYNC_OVERLAP: chrono::Duration = chrono::Duration::seconds(10);
 
 /// Creates a mempool filter for L2 transactions based on the current L1 gas price.
 /// The filter is used to filter out transactions from the mempool that do not cover expenses
@@ -18,7 +26,13 @@ pub fn l2_tx_filter<G: L1GasPriceProvider>(
     fair_l2_gas_price: u64,
 ) -> L2TxFilter {
     let effective_gas_price = gas_price_provider.estimate_effective_gas_price();
+    l2_tx_filter_for_gas_price(effective_gas_price, fair_l2_gas_price)
+}
 
+pub(super) fn l2_tx_filter_for_gas_price(
+    effective_gas_price: u64,
+    fair_l2_gas_price: u64,
+) -> L2TxFilter {
     let (base_fee, gas_per_pubdata) =
         derive_base_fee_and_gas_per_pubdata(effective_gas_price, fair_l2_gas_price);
     L2TxFilter {
@@ -28,12 +42,162 @@ pub fn l2_tx_filter<G: L1GasPriceProvider>(
     }
 }
 
+/// Smooths the L1 gas price used in the mempool filter, so that admission criteria change gradually
+/// rather than following every short-lived L1 gas price spike.
+#[derive(Debug)]
+struct GasPriceHysteresis {
+    /// Min time between consecutive changes of the price.
+    min_hold: Duration,
+    /// Max relative change of the price per update.
+    max_step: Option<f64>,
+    current: Option<(u64, Instant)>,
+}
+
+impl GasPriceHysteresis {
+    fn new(config: &MempoolConfig) -> Self {
+        Self {
+            min_hold: config.filter_gas_price_min_hold(),
+            max_step: config.filter_gas_price_max_step,
+            current: None,
+        }
+    }
+
+    /// Updates the smoothed price given the current `target` price and returns the smoothed price.
+    fn update(&mut self, target: u64, now: Instant) -> u64 {
+        let (current, changed_at) = match self.current {
+            Some(current) => current,
+            None => {
+                self.current = Some((target, now));
+                return target;
+            }
+        };
+        if target == current || now.duration_since(changed_at) < self.min_hold {
+            return current;
+        }
+
+        let new_price = match self.max_step {
+            Some(max_step) => {
+                // Always allow changing the price by at least 1 wei, so that it reaches the target eventually.
+                let max_delta = ((current as f64 * max_step) as u64).max(1);
+                target.clamp(
+                    current.saturating_sub(max_delta),
+                    current.saturating_add(max_delta),
+                )
+            }
+            None => target,
+        };
+        self.current = Some((new_price, now));
+        new_price
+    }
+}
+
+/// Sends notifications about transactions that were persisted in a miniblock or rejected by the state keeper,
+/// so that the [`MempoolFetcher`] can stop tracking them.
+#[derive(Debug, Clone)]
+pub struct MempoolRemovalNotifier(mpsc::UnboundedSender<Vec<H256>>);
+
+impl MempoolRemovalNotifier {
+    pub fn notify(&self, tx_hashes: Vec<H256>) {
+        // The fetcher may be stopped, which is fine.
+        self.0.send(tx_hashes).ok();
+    }
+}
+
+/// Transactions loaded into the mempool by the fetcher and not persisted in a miniblock yet.
+/// These transactions are skipped by the fetcher even if they're returned by the sync query again
+/// (e.g., because they're executed, but the miniblock with them is not persisted yet).
+#[derive(Debug, Default)]
+struct LoadedTxs {
+    txs: HashMap<H256, (Address, Option<Nonce>)>,
+    by_account: HashMap<Address, HashSet<H256>>,
+    /// Allows to forget L2 transactions replaced by a transaction with the same nonce.
+    by_nonce: HashMap<(Address, Nonce), H256>,
+}
+
+impl LoadedTxs {
+    fn len(&self) -> usize {
+        self.txs.len()
+    }
+
+    fn contains(&self, tx_hash: &H256) -> bool {
+        self.txs.contains_key(tx_hash)
+    }
+
+    fn insert(&mut self, tx: &Transaction) {
+        let tx_hash = tx.hash();
+        let account = tx.initiator_account();
+        let nonce = match &tx.common_data {
+            ExecuteTransactionCommon::L2(data) => Some(data.nonce),
+            ExecuteTransactionCommon::L1(_) => None,
+        };
+        if let Some(nonce) = nonce {
+            if let Some(replaced_hash) = self.by_nonce.insert((account, nonce), tx_hash) {
+                if replaced_hash != tx_hash {
+                    self.remove(&replaced_hash);
+                }
+            }
+        }
+        self.txs.insert(tx_hash, (account, nonce));
+        self.by_account.entry(account).or_default().insert(tx_hash);
+    }
+
+    fn remove(&mut self, tx_hash: &H256) {
+        let Some((account, nonce)) = self.txs.remove(tx_hash) else {
+            return;
+        };
+        if let Some(account_txs) = self.by_account.get_mut(&account) {
+            account_txs.remove(tx_hash);
+            if account_txs.is_empty() {
+                self.by_account.remove(&account);
+            }
+        }
+        if let Some(nonce) = nonce {
+            if self.by_nonce.get(&(account, nonce)) == Some(tx_hash) {
+                self.by_nonce.remove(&(account, nonce));
+            }
+        }
+    }
+
+    /// Removes all transactions of the specified account and returns their hashes.
+    fn remove_account(&mut self, account: Address) -> Vec<H256> {
+        let tx_hashes: Vec<_> = self
+            .by_account
+            .get(&account)
+            .map(|account_txs| account_txs.iter().copied().collect())
+            .unwrap_or_default();
+        for tx_hash in &tx_hashes {
+            self.remove(tx_hash);
+        }
+        tx_hashes
+    }
+
+    fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Loads transactions from Postgres into the mempool.
+///
+/// The fetcher performs a full sync on start and each time the mempool filter is relaxed; otherwise,
+/// it only loads transactions added or updated since the previous sync (i.e., with `updated_at` exceeding
+/// a watermark). Loaded transactions are tracked in memory until the miniblock sealer reports
+/// that they are persisted.
 #[derive(Debug)]
 pub struct MempoolFetcher<G> {
     mempool: MempoolGuard,
     l1_gas_price_provider: Arc<G>,
+    gas_price_hysteresis: GasPriceHysteresis,
     sync_interval: Duration,
     sync_batch_size: usize,
+    loaded_txs: LoadedTxs,
+    removals_sender: mpsc::UnboundedSender<Vec<H256>>,
+    removals_receiver: mpsc::UnboundedReceiver<Vec<H256>>,
+    /// Position after which transactions are loaded on the next sync; `None` means a full sync.
+    sync_position: Option<(NaiveDateTime, H256)>,
+    /// Postgres time when the current sync pass (which can consist of several pages) has started.
+    pass_started_at: Option<NaiveDateTime>,
+    /// `(fee_per_gas, gas_per_pubdata)` filter used during the previous sync.
+    last_filter: Option<(u64, u32)>,
 }
 
 impl<G: L1GasPriceProvider> MempoolFetcher<G> {
@@ -42,14 +206,35 @@ impl<G: L1GasPriceProvider> MempoolFetcher<G> {
         l1_gas_price_provider: Arc<G>,
         config: &MempoolConfig,
     ) -> Self {
+        let (removals_sender, removals_receiver) = mpsc::unbounded_channel();
         Self {
             mempool,
             l1_gas_price_provider,
+            gas_price_hysteresis: GasPriceHysteresis::new(config),
             sync_interval: config.sync_interval(),
             sync_batch_size: config.sync_batch_size,
+            loaded_txs: LoadedTxs::default(),
+            removals_sender,
+            removals_receiver,
+            sync_position: None,
+            pass_started_at: None,
+            last_filter: None,
         }
     }
 
+    /// Starts filtering transactions with the specified L1 gas price (e.g., the one used by the primary
+    /// sequencer before failover) rather than the current gas adjuster estimate. The price still
+    /// converges to the estimate subject to the configured hysteresis.
+    pub fn with_initial_filter_gas_price(mut self, l1_gas_price: u64) -> Self {
+        self.gas_price_hysteresis.current = Some((l1_gas_price, Instant::now()));
+        self
+    }
+
+    /// Returns a notifier that should be supplied to the miniblock sealer.
+    pub fn removal_notifier(&self) -> MempoolRemovalNotifier {
+        MempoolRemovalNotifier(self.removals_sender.clone())
+    }
+
     pub async fn run(
         mut self,
         pool: ConnectionPool,
@@ -77,25 +262,226 @@ impl<G: L1GasPriceProvider> MempoolFetcher<G> {
             }
             let started_at = Instant::now();
             let mut storage = pool.access_storage_tagged("state_keeper").await;
-            let mempool_info = self.mempool.get_mempool_info();
-            let l2_tx_filter = l2_tx_filter(self.l1_gas_price_provider.as_ref(), fair_l2_gas_price);
+            self.process_removals(&mut storage).await;
+
+            let effective_gas_price = self.l1_gas_price_provider.estimate_effective_gas_price();
+            let filter_gas_price = self
+                .gas_price_hysteresis
+                .update(effective_gas_price, started_at);
+            metrics::gauge!(
+                "server.state_keeper.mempool_filter_l1_gas_price",
+                filter_gas_price as f64
+            );
+            let l2_tx_filter = l2_tx_filter_for_gas_price(filter_gas_price, fair_l2_gas_price);
+            let filter = (l2_tx_filter.fee_per_gas, l2_tx_filter.gas_per_pubdata);
+            if let Some((last_fee_per_gas, last_gas_per_pubdata)) = self.last_filter {
+                if filter.0 < last_fee_per_gas || filter.1 < last_gas_per_pubdata {
+                    // Transactions previously skipped because of the filter may be eligible now.
+                    self.reset_sync_position();
+                }
+            }
+            self.last_filter = Some(filter);
 
-            let (transactions, nonces) = storage
+            let sync_kind = if self.sync_position.is_none() {
+                "full"
+            } else {
+                "delta"
+            };
+            if self.pass_started_at.is_none() {
+                self.pass_started_at = Some(storage.transactions_dal().current_db_time().await);
+            }
+            let delta = storage
                 .transactions_dal()
-                .sync_mempool(
-                    mempool_info.stashed_accounts,
-                    mempool_info.purged_accounts,
+                .get_mempool_delta(
+                    self.sync_position,
                     l2_tx_filter.gas_per_pubdata,
                     l2_tx_filter.fee_per_gas,
                     self.sync_batch_size,
                 )
                 .await;
-            let all_transactions_loaded = transactions.len() < self.sync_batch_size;
-            self.mempool.insert(transactions, nonces);
+            drop(storage);
+
+            let all_transactions_loaded = delta.transactions.len() < self.sync_batch_size;
+            if all_transactions_loaded {
+                let pass_started_at = self.pass_started_at.take().unwrap();
+                self.sync_position = Some((pass_started_at - DELTA_SYNC_OVERLAP, H256::zero()));
+            } else {
+                self.sync_position = delta.last_position;
+            }
+
+            let new_transactions: Vec<_> = delta
+                .transactions
+                .into_iter()
+                .filter(|tx| !self.loaded_txs.contains(&tx.hash()))
+                .collect();
+            for tx in &new_transactions {
+                self.loaded_txs.insert(tx);
+            }
+            metrics::counter!(
+                "server.state_keeper.mempool_sync_txs",
+                new_transactions.len() as u64,
+                "sync" => sync_kind
+            );
+            metrics::gauge!(
+                "server.state_keeper.mempool_fetcher_loaded_txs",
+                self.loaded_txs.len() as f64
+            );
+            self.mempool.insert(new_transactions, delta.nonces);
             metrics::histogram!("server.state_keeper.mempool_sync", started_at.elapsed());
             if all_transactions_loaded {
                 tokio::time::sleep(self.sync_interval).await;
             }
         }
     }
+
+    fn reset_sync_position(&mut self) {
+        self.sync_position = None;
+        self.pass_started_at = None;
+    }
+
+    /// Processes transactions that should no longer be tracked by the fetcher: persisted by the miniblock sealer
+    /// or rejected by the state keeper, expired, deferred by the mempool until the nonce gap is closed,
+    /// and belonging to the accounts stashed or purged by the mempool.
+    async fn process_removals(&mut self, storage: &mut StorageProcessor<'_>) {
+        // Notifications must be processed before the sync query; otherwise, a transaction persisted
+        // after the query could be reloaded into the mempool.
+        while let Ok(tx_hashes) = self.removals_receiver.try_recv() {
+            for tx_hash in &tx_hashes {
+                self.loaded_txs.remove(tx_hash);
+            }
+        }
+
+        if self.mempool.take_cleared_flag() {
+            vlog::info!("Mempool was cleared; reloading all transactions");
+            self.loaded_txs.clear();
+            self.reset_sync_position();
+        }
+
+        let mempool_info = self.mempool.get_mempool_info();
+        // Expired transactions must be marked before unstashing accounts, so that they aren't reloaded.
+        let expired_count = storage
+            .transactions_dal()
+            .mark_expired_txs(&mempool_info.expired_transactions)
+            .await;
+        if expired_count > 0 {
+            vlog::info!("Marked {expired_count} transactions as expired");
+            metrics::counter!(
+                "server.state_keeper.mempool_marked_expired_txs",
+                expired_count as u64
+            );
+        }
+        for tx_hash in &mempool_info.expired_transactions {
+            self.loaded_txs.remove(tx_hash);
+        }
+        if !mempool_info.unblocked_transactions.is_empty() {
+            // Transactions deferred because of the nonce lookahead can now be inserted, so they should be reloaded.
+            for tx_hash in &mempool_info.unblocked_transactions {
+                self.loaded_txs.remove(tx_hash);
+            }
+            storage
+                .transactions_dal()
+                .unmark_txs_as_in_mempool(&mempool_info.unblocked_transactions)
+                .await;
+        }
+
+        if !mempool_info.stashed_accounts.is_empty() {
+            // Transactions of stashed accounts are dropped from the mempool and should be reloaded.
+            let stashed_txs: Vec<_> = mempool_info
+                .stashed_accounts
+                .iter()
+                .flat_map(|&account| self.loaded_txs.remove_account(account))
+                .collect();
+            storage
+                .transactions_dal()
+                .unstash_mempool_accounts(&mempool_info.stashed_accounts, &stashed_txs)
+                .await;
+        }
+        if !mempool_info.purged_accounts.is_empty() {
+            for &account in &mempool_info.purged_accounts {
+                self.loaded_txs.remove_account(account);
+            }
+            storage
+                .transactions_dal()
+                .remove_pending_account_txs(&mempool_info.purged_accounts)
+                .await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_keeper::tests::create_transaction;
+
+    fn create_hysteresis(min_hold: Duration, max_step: Option<f64>) -> GasPriceHysteresis {
+        GasPriceHysteresis {
+            min_hold,
+            max_step,
+            current: None,
+        }
+    }
+
+    #[test]
+    fn gas_price_is_held_for_min_duration() {
+        let mut hysteresis = create_hysteresis(Duration::from_secs(10), None);
+        let start = Instant::now();
+        assert_eq!(hysteresis.update(100, start), 100);
+        assert_eq!(hysteresis.update(200, start + Duration::from_secs(5)), 100);
+        assert_eq!(hysteresis.update(200, start + Duration::from_secs(10)), 200);
+        // The hold time is counted from the last change.
+        assert_eq!(hysteresis.update(100, start + Duration::from_secs(15)), 200);
+        assert_eq!(hysteresis.update(100, start + Duration::from_secs(20)), 100);
+    }
+
+    #[test]
+    fn gas_price_changes_gradually() {
+        let mut hysteresis = create_hysteresis(Duration::ZERO, Some(0.1));
+        let now = Instant::now();
+        assert_eq!(hysteresis.update(1_000, now), 1_000);
+        assert_eq!(hysteresis.update(2_000, now), 1_100);
+        assert_eq!(hysteresis.update(2_000, now), 1_210);
+        assert_eq!(hysteresis.update(1_200, now), 1_200);
+        assert_eq!(hysteresis.update(0, now), 1_080);
+
+        let mut hysteresis = create_hysteresis(Duration::ZERO, Some(0.1));
+        assert_eq!(hysteresis.update(1, now), 1);
+        assert_eq!(hysteresis.update(10, now), 2);
+    }
+
+    #[test]
+    fn loaded_txs_are_tracked() {
+        let mut loaded_txs = LoadedTxs::default();
+        let tx = create_transaction(10, 100);
+        let account = tx.initiator_account();
+        loaded_txs.insert(&tx);
+        assert!(loaded_txs.contains(&tx.hash()));
+
+        // Transactions created by the helper share the initiator and nonce, so the second one
+        // replaces the first one.
+        let replacement_tx = create_transaction(20, 100);
+        assert_eq!(replacement_tx.initiator_account(), account);
+        loaded_txs.insert(&replacement_tx);
+        assert!(!loaded_txs.contains(&tx.hash()));
+        assert!(loaded_txs.contains(&replacement_tx.hash()));
+        assert_eq!(loaded_txs.len(), 1);
+
+        loaded_txs.remove(&replacement_tx.hash());
+        assert_eq!(loaded_txs.len(), 0);
+        assert!(loaded_txs.by_account.is_empty());
+        assert!(loaded_txs.by_nonce.is_empty());
+    }
+
+    #[test]
+    fn loaded_txs_are_removed_by_account() {
+        let mut loaded_txs = LoadedTxs::default();
+        let tx = create_transaction(10, 100);
+        loaded_txs.insert(&tx);
+        assert_eq!(loaded_txs.remove_account(Address::repeat_byte(1)), []);
+        assert_eq!(
+            loaded_txs.remove_account(tx.initiator_account()),
+            [tx.hash()]
+        );
+        assert_eq!(loaded_txs.len(), 0);
+        assert!(loaded_txs.by_nonce.is_empty());
+    }
 }
@@ -0,0 +1,370 @@
+//! Backfilling of miniblock hashes.
+//!
+//! Miniblocks sealed by older server versions have a placeholder hash depending only on the miniblock number
+//! (see [`miniblock_hash()`]). The state keeper now computes a hash committing to the miniblock timestamp,
+//! its transactions and the previous miniblock hash (see [`MiniblockHasher`]), so all legacy hashes are
+//! recomputed in the background. Miniblocks sealed while the backfill is in progress can be chained
+//! to legacy hashes, so they are recomputed as well.
+//!
+//! The genesis miniblock retains the legacy hash, since it's checked by external nodes during genesis.
+
+use tokio::sync::watch;
+
+use std::time::{Duration, Instant};
+
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_types::{block::MiniblockHasher, MiniblockNumber, H256};
+use zksync_utils::miniblock_hash;
+
+/// Number of miniblocks processed in a single DB transaction.
+const MINIBLOCKS_PER_CHUNK: u32 = 1_000;
+/// Interval between checks whether a miniblock sealed with concurrent writes is fully persisted.
+const SEAL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, PartialEq)]
+enum ChunkOutcome {
+    /// Hashes of the miniblocks up to the specified one were recomputed.
+    Processed {
+        last_miniblock: MiniblockNumber,
+        last_hash: H256,
+    },
+    /// The next miniblock is not fully persisted yet.
+    WaitingForSeal,
+    /// All miniblock hashes are up to date.
+    Finished,
+}
+
+/// Recomputes legacy miniblock hashes in the background.
+///
+/// Miniblocks are processed in chunks in the ascending order; the last processed miniblock is persisted
+/// after each chunk, so that the backfill resumes from it after a restart. The backfill finishes once it reaches
+/// the last persisted miniblock or a miniblock with an up-to-date hash; all subsequent hashes are up to date
+/// in the latter case, since they are chained to it. The state keeper locks the previous miniblock hash
+/// when sealing a miniblock, so the backfill cannot miss a miniblock chained to an outdated hash.
+#[derive(Debug)]
+pub struct MiniblockHashesBackfill {
+    pool: ConnectionPool,
+}
+
+impl MiniblockHashesBackfill {
+    pub fn new(pool: ConnectionPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) {
+        self.backfill(&stop_receiver).await;
+        // The task is awaited together with other server tasks, so it must not finish before the stop signal.
+        stop_receiver.changed().await.ok();
+    }
+
+    async fn backfill(&self, stop_receiver: &watch::Receiver<bool>) {
+        let mut storage = self
+            .pool
+            .access_storage_tagged("miniblock_hashes_backfill")
+            .await;
+        let Some(first_miniblock) = first_miniblock_to_process(&mut storage).await else {
+            return;
+        };
+        let mut prev_miniblock_hash = storage
+            .blocks_dal()
+            .get_miniblock_hash(first_miniblock - 1)
+            .await
+            .expect("previous miniblock is not persisted");
+        drop(storage);
+
+        vlog::info!("Backfilling miniblock hashes starting from miniblock #{first_miniblock}");
+        let started_at = Instant::now();
+        let mut chunk_start = first_miniblock;
+        loop {
+            if *stop_receiver.borrow() {
+                vlog::info!("Stop signal received, miniblock hashes backfill is shutting down");
+                return;
+            }
+
+            let mut storage = self
+                .pool
+                .access_storage_tagged("miniblock_hashes_backfill")
+                .await;
+            match process_chunk(&mut storage, chunk_start, prev_miniblock_hash).await {
+                ChunkOutcome::Processed {
+                    last_miniblock,
+                    last_hash,
+                } => {
+                    vlog::info!(
+                        "Backfilled hashes for miniblocks #{chunk_start}..=#{last_miniblock}"
+                    );
+                    metrics::gauge!(
+                        "server.state_keeper.last_miniblock_with_backfilled_hash",
+                        last_miniblock.0 as f64
+                    );
+                    chunk_start = last_miniblock + 1;
+                    prev_miniblock_hash = last_hash;
+                }
+                ChunkOutcome::WaitingForSeal => {
+                    drop(storage);
+                    tokio::time::sleep(SEAL_POLL_INTERVAL).await;
+                }
+                ChunkOutcome::Finished => break,
+            }
+        }
+        vlog::info!(
+            "Finished backfilling miniblock hashes in {:?}",
+            started_at.elapsed()
+        );
+    }
+}
+
+/// Returns the first miniblock to process, or `None` if the backfill is not necessary or impossible.
+async fn first_miniblock_to_process(storage: &mut StorageProcessor<'_>) -> Option<MiniblockNumber> {
+    let first_miniblock = storage
+        .blocks_dal()
+        .get_miniblock_hashes_backfill_progress()
+        .await
+        .map_or(MiniblockNumber(1), |last_processed| last_processed + 1);
+    let pruning_info = storage
+        .pruning_dal()
+        .get_pruning_info()
+        .await
+        .expect("failed getting pruning info");
+    if let Some(last_pruned_miniblock) = pruning_info.last_pruned_miniblock {
+        if first_miniblock <= last_pruned_miniblock {
+            // Hashes cannot be recomputed since transactions of the pruned miniblocks are removed.
+            if has_legacy_hash(storage, first_miniblock).await {
+                vlog::warn!(
+                    "Cannot backfill hashes starting from miniblock #{first_miniblock} since transactions \
+                     up to miniblock #{last_pruned_miniblock} are pruned; legacy hashes are retained"
+                );
+            }
+            return None;
+        }
+    }
+    Some(first_miniblock)
+}
+
+async fn has_legacy_hash(storage: &mut StorageProcessor<'_>, number: MiniblockNumber) -> bool {
+    let hash = storage.blocks_dal().get_miniblock_hash(number).await;
+    hash == Some(miniblock_hash(number))
+}
+
+/// Recomputes hashes for a chunk of miniblocks starting from `chunk_start` in a single DB transaction.
+async fn process_chunk(
+    storage: &mut StorageProcessor<'_>,
+    chunk_start: MiniblockNumber,
+    mut prev_miniblock_hash: H256,
+) -> ChunkOutcome {
+    let chunk_end = chunk_start + (MINIBLOCKS_PER_CHUNK - 1);
+    let mut transaction = storage.start_transaction().await;
+    let miniblocks = transaction
+        .blocks_dal()
+        .get_miniblocks_hashing_data(chunk_start..=chunk_end)
+        .await;
+    if miniblocks.is_empty() {
+        return ChunkOutcome::Finished;
+    }
+
+    let mut last_miniblock = None;
+    let mut is_finished = false;
+    for miniblock in miniblocks {
+        if !miniblock.is_sealed {
+            break;
+        }
+        let mut hasher =
+            MiniblockHasher::new(miniblock.number, miniblock.timestamp, prev_miniblock_hash);
+        for tx_hash in miniblock.tx_hashes {
+            hasher.push_tx_hash(tx_hash);
+        }
+        let hash = hasher.finalize();
+        if hash == miniblock.hash {
+            is_finished = true;
+            break;
+        }
+        transaction
+            .blocks_dal()
+            .set_miniblock_hash(miniblock.number, hash)
+            .await;
+        prev_miniblock_hash = hash;
+        last_miniblock = Some(miniblock.number);
+    }
+    if let Some(last_miniblock) = last_miniblock {
+        transaction
+            .blocks_dal()
+            .set_miniblock_hashes_backfill_progress(last_miniblock)
+            .await;
+    }
+    transaction.commit().await;
+
+    match last_miniblock {
+        _ if is_finished => ChunkOutcome::Finished,
+        Some(last_miniblock) => ChunkOutcome::Processed {
+            last_miniblock,
+            last_hash: prev_miniblock_hash,
+        },
+        None => ChunkOutcome::WaitingForSeal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use db_test_macro::db_test;
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{block::MiniblockHeader, H256};
+
+    use super::*;
+
+    async fn insert_miniblock(storage: &mut StorageProcessor<'_>, number: u32) {
+        let number = MiniblockNumber(number);
+        let header = MiniblockHeader {
+            number,
+            timestamp: number.0.into(),
+            hash: miniblock_hash(number),
+            l1_tx_count: 0,
+            l2_tx_count: 0,
+            base_fee_per_gas: 100,
+            l1_gas_price: 100,
+            l2_fair_gas_price: 100,
+            base_system_contracts_hashes: BaseSystemContractsHashes::default(),
+        };
+        storage.blocks_dal().insert_miniblock(&header).await;
+    }
+
+    fn expected_hashes(count: u32) -> Vec<H256> {
+        let mut prev_hash = miniblock_hash(MiniblockNumber(0));
+        let mut hashes = vec![prev_hash];
+        for number in 1..count {
+            prev_hash =
+                MiniblockHasher::new(MiniblockNumber(number), number.into(), prev_hash).finalize();
+            hashes.push(prev_hash);
+        }
+        hashes
+    }
+
+    async fn run_backfill(pool: &ConnectionPool) {
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        MiniblockHashesBackfill::new(pool.clone())
+            .backfill(&stop_receiver)
+            .await;
+    }
+
+    async fn assert_hashes(pool: &ConnectionPool, expected_hashes: &[H256]) {
+        let mut storage = pool.access_test_storage().await;
+        for (number, &expected_hash) in expected_hashes.iter().enumerate() {
+            let hash = storage
+                .blocks_dal()
+                .get_miniblock_hash(MiniblockNumber(number as u32))
+                .await;
+            assert_eq!(hash, Some(expected_hash), "miniblock #{number}");
+        }
+    }
+
+    #[db_test]
+    async fn backfilling_miniblock_hashes(pool: ConnectionPool) {
+        let mut storage = pool.access_test_storage().await;
+        storage
+            .blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        for number in 0..5 {
+            insert_miniblock(&mut storage, number).await;
+        }
+        // Emulate a partially completed backfill.
+        let expected_hashes = expected_hashes(5);
+        for number in 1..3 {
+            storage
+                .blocks_dal()
+                .set_miniblock_hash(MiniblockNumber(number), expected_hashes[number as usize])
+                .await;
+        }
+        storage
+            .blocks_dal()
+            .set_miniblock_hashes_backfill_progress(MiniblockNumber(2))
+            .await;
+        assert_eq!(
+            first_miniblock_to_process(&mut storage).await,
+            Some(MiniblockNumber(3))
+        );
+        drop(storage);
+
+        run_backfill(&pool).await;
+        assert_hashes(&pool, &expected_hashes).await;
+        let mut storage = pool.access_test_storage().await;
+        let progress = storage
+            .blocks_dal()
+            .get_miniblock_hashes_backfill_progress()
+            .await;
+        assert_eq!(progress, Some(MiniblockNumber(4)));
+    }
+
+    #[db_test]
+    async fn miniblocks_chained_to_legacy_hashes_are_backfilled(pool: ConnectionPool) {
+        let mut storage = pool.access_test_storage().await;
+        storage
+            .blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        for number in 0..5 {
+            insert_miniblock(&mut storage, number).await;
+        }
+        // Emulate miniblocks sealed by the state keeper before the backfill has started.
+        let legacy_hash = miniblock_hash(MiniblockNumber(2));
+        let hash = MiniblockHasher::new(MiniblockNumber(3), 3, legacy_hash).finalize();
+        storage
+            .blocks_dal()
+            .set_miniblock_hash(MiniblockNumber(3), hash)
+            .await;
+        let hash = MiniblockHasher::new(MiniblockNumber(4), 4, hash).finalize();
+        storage
+            .blocks_dal()
+            .set_miniblock_hash(MiniblockNumber(4), hash)
+            .await;
+        storage
+            .blocks_dal()
+            .set_miniblock_is_sealed(MiniblockNumber(4), false)
+            .await;
+        drop(storage);
+
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        let backfill = MiniblockHashesBackfill::new(pool.clone());
+        let mut backfill_task =
+            tokio::spawn(async move { backfill.backfill(&stop_receiver).await });
+        // The backfill must wait until the last miniblock is fully persisted.
+        tokio::time::timeout(SEAL_POLL_INTERVAL * 4, &mut backfill_task)
+            .await
+            .unwrap_err();
+        let mut storage = pool.access_test_storage().await;
+        storage
+            .blocks_dal()
+            .set_miniblock_is_sealed(MiniblockNumber(4), true)
+            .await;
+        drop(storage);
+        backfill_task.await.unwrap();
+
+        assert_hashes(&pool, &expected_hashes(5)).await;
+    }
+
+    #[db_test]
+    async fn backfill_is_noop_for_up_to_date_hashes(pool: ConnectionPool) {
+        let mut storage = pool.access_test_storage().await;
+        storage
+            .blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        let expected_hashes = expected_hashes(3);
+        for number in 0..3 {
+            insert_miniblock(&mut storage, number).await;
+            storage
+                .blocks_dal()
+                .set_miniblock_hash(MiniblockNumber(number), expected_hashes[number as usize])
+                .await;
+        }
+        drop(storage);
+
+        run_backfill(&pool).await;
+        assert_hashes(&pool, &expected_hashes).await;
+        let mut storage = pool.access_test_storage().await;
+        let progress = storage
+            .blocks_dal()
+            .get_miniblock_hashes_backfill_progress()
+            .await;
+        assert_eq!(progress, None);
+    }
+}
@@ -1,36 +1,60 @@
 use tokio::sync::watch;
 
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
 use zksync_config::{
     configs::chain::{MempoolConfig, StateKeeperConfig},
     constants::MAX_TXS_IN_BLOCK,
-    ContractsConfig, DBConfig,
+    DBConfig,
 };
 use zksync_dal::ConnectionPool;
+use zksync_object_store::ObjectStoreFactory;
+use zksync_state::BytecodeCache;
 
 mod batch_executor;
 pub(crate) mod extractors;
 pub(crate) mod io;
 mod keeper;
 mod mempool_actor;
+mod miniblock_hashes;
+mod pending_receipts;
+mod pending_state;
 pub(crate) mod seal_criteria;
+#[cfg(any(test, feature = "testonly"))]
+pub mod testonly;
 #[cfg(test)]
 mod tests;
 mod types;
 pub(crate) mod updates;
 
+pub(crate) use self::mempool_actor::{MempoolFetcher, MempoolRemovalNotifier};
 pub use self::{
-    batch_executor::MainBatchExecutorBuilder, keeper::ZkSyncStateKeeper, seal_criteria::SealManager,
+    batch_executor::{
+        BatchExecutorHandle, InMemoryBatchExecutorBuilder, L1BatchExecutorBuilder,
+        MainBatchExecutorBuilder, SupervisedBatchExecutorBuilder,
+    },
+    io::{
+        read_replay_log, L1BatchParams, MempoolIO, MiniblockSealer, MiniblockSealerHandle,
+        PendingBatchData, RecordingIO, ReplayEvent, ReplayIO, ReplayL1BatchParams, ReplaySegment,
+        StateKeeperIO,
+    },
+    keeper::ZkSyncStateKeeper,
+    miniblock_hashes::MiniblockHashesBackfill,
+    pending_receipts::PendingReceipts,
+    pending_state::{PendingStateOverlay, PendingStorage, PendingStorageDiffs},
+    seal_criteria::SealManager,
+    types::MempoolGuard,
+    updates::UpdatesManager,
 };
-pub(crate) use self::{io::MiniblockSealer, mempool_actor::MempoolFetcher, types::MempoolGuard};
 
-use self::io::{MempoolIO, MiniblockSealerHandle};
-use crate::l1_gas_price::L1GasPriceProvider;
+use crate::{
+    admin_controls::AdminControls, chain_contracts::ChainContracts,
+    l1_gas_price::L1GasPriceProvider,
+};
 
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn create_state_keeper<G>(
-    contracts_config: &ContractsConfig,
+    chain_contracts: ChainContracts,
     state_keeper_config: StateKeeperConfig,
     db_config: &DBConfig,
     mempool_config: &MempoolConfig,
@@ -38,6 +62,10 @@ pub(crate) async fn create_state_keeper<G>(
     mempool: MempoolGuard,
     l1_gas_price_provider: Arc<G>,
     miniblock_sealer_handle: MiniblockSealerHandle,
+    removal_notifier: MempoolRemovalNotifier,
+    bytecode_cache: Option<BytecodeCache>,
+    object_store_factory: &ObjectStoreFactory,
+    admin_controls: AdminControls,
     stop_receiver: watch::Receiver<bool>,
 ) -> ZkSyncStateKeeper
 where
@@ -50,30 +78,52 @@ where
         MAX_TXS_IN_BLOCK
     );
 
-    let batch_executor_base = MainBatchExecutorBuilder::new(
+    let mut batch_executor_base = MainBatchExecutorBuilder::new(
         db_config.state_keeper_db_path.clone(),
         pool.clone(),
         state_keeper_config.max_allowed_l2_tx_gas_limit.into(),
         state_keeper_config.save_call_traces,
         state_keeper_config.validation_computational_gas_limit,
+        state_keeper_config.tx_execution_timeout(),
     );
+    if let Some(bytecode_cache) = bytecode_cache {
+        batch_executor_base = batch_executor_base.with_bytecode_cache(bytecode_cache);
+    }
+    if state_keeper_config.save_vm_artifacts() {
+        let store = object_store_factory.create_store().await;
+        batch_executor_base = batch_executor_base.with_vm_artifacts_store(store.into());
+    }
 
     let io = MempoolIO::new(
         mempool,
         miniblock_sealer_handle,
-        l1_gas_price_provider,
+        l1_gas_price_provider.clone(),
         pool,
         &state_keeper_config,
         mempool_config.delay_interval(),
-        contracts_config.l2_erc20_bridge_addr,
+        chain_contracts,
     )
-    .await;
+    .await
+    .with_removal_notifier(removal_notifier);
 
-    let sealer = SealManager::new(state_keeper_config);
-    ZkSyncStateKeeper::new(
-        stop_receiver,
-        Box::new(io),
-        Box::new(batch_executor_base),
-        sealer,
-    )
+    let io: Box<dyn StateKeeperIO> = match &state_keeper_config.replay_log_path {
+        Some(path) => {
+            vlog::info!("Recording state keeper replay log to `{path}`");
+            let io = RecordingIO::new(io, Path::new(path)).expect("failed creating replay log");
+            Box::new(io)
+        }
+        None => Box::new(io),
+    };
+
+    let mut batch_executor_base: Box<dyn L1BatchExecutorBuilder> = Box::new(batch_executor_base);
+    if let Some(max_restarts) = state_keeper_config.max_batch_executor_restarts {
+        batch_executor_base = Box::new(SupervisedBatchExecutorBuilder::new(
+            batch_executor_base,
+            max_restarts,
+        ));
+    }
+
+    let sealer = SealManager::new(state_keeper_config, l1_gas_price_provider)
+        .with_admin_controls(admin_controls);
+    ZkSyncStateKeeper::new(stop_receiver, io, batch_executor_base, sealer)
 }
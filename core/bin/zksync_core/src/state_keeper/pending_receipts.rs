@@ -0,0 +1,341 @@
+//! Provisional receipts for transactions executed by the state keeper, but not yet persisted.
+//!
+//! The state keeper records a receipt once it has decided to include a transaction into the current miniblock.
+//! The receipts are served by the API servers running in the same process until the miniblock sealer reports
+//! that the miniblock containing the transaction is persisted.
+//!
+//! After persisting a miniblock, the sealer loads its receipts from Postgres and caches them until the current
+//! L1 batch is sealed (at which point the receipts change, since they contain the L1 batch number). This spares
+//! Postgres from bots polling receipts of recently executed transactions; older receipts are loaded from Postgres.
+
+use tokio::sync::watch;
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use zksync_types::{
+    api,
+    event::{events_bloom, DEPLOY_EVENT_SIGNATURE},
+    tx::tx_execution_info::TxExecutionStatus,
+    web3::types::Bytes,
+    ExecuteTransactionCommon, L1BatchNumber, MiniblockNumber, Transaction,
+    CONTRACT_DEPLOYER_ADDRESS, H256, U256, U64,
+};
+use zksync_utils::h256_to_account_address;
+
+use super::updates::UpdatesManager;
+
+#[derive(Debug)]
+struct PendingReceiptsInner {
+    receipts: HashMap<H256, api::TransactionReceipt>,
+    /// Receipts loaded from Postgres for persisted miniblocks in the current L1 batch.
+    sealed_receipts: HashMap<H256, api::TransactionReceipt>,
+    /// Latest persisted miniblock for which receipts were pruned.
+    pruned_at: MiniblockNumber,
+}
+
+/// Read handle for receipts of transactions executed in miniblocks that are not persisted yet.
+#[derive(Debug, Clone)]
+pub struct PendingReceipts {
+    sealed_miniblocks: watch::Receiver<MiniblockNumber>,
+    inner: Arc<RwLock<PendingReceiptsInner>>,
+}
+
+impl PendingReceipts {
+    /// Creates a handle. `sealed_miniblocks` must receive numbers of miniblocks persisted by the miniblock sealer.
+    pub fn new(sealed_miniblocks: watch::Receiver<MiniblockNumber>) -> Self {
+        let pruned_at = *sealed_miniblocks.borrow();
+        Self {
+            sealed_miniblocks,
+            inner: Arc::new(RwLock::new(PendingReceiptsInner {
+                receipts: HashMap::new(),
+                sealed_receipts: HashMap::new(),
+                pruned_at,
+            })),
+        }
+    }
+
+    /// Returns a receipt for the transaction with the specified hash, or `None` if the transaction is not executed
+    /// or is persisted in an earlier L1 batch. The receipt is provisional if the transaction is not persisted yet.
+    pub fn get(&self, tx_hash: H256) -> Option<api::TransactionReceipt> {
+        self.prune();
+        let inner = self
+            .inner
+            .read()
+            .expect("pending receipts lock is poisoned");
+        let receipt = inner
+            .receipts
+            .get(&tx_hash)
+            .or_else(|| inner.sealed_receipts.get(&tx_hash));
+        receipt.cloned()
+    }
+
+    /// Removes receipts for persisted miniblocks.
+    fn prune(&self) {
+        let sealed_miniblock = *self.sealed_miniblocks.borrow();
+        if self
+            .inner
+            .read()
+            .expect("pending receipts lock is poisoned")
+            .pruned_at
+            >= sealed_miniblock
+        {
+            return;
+        }
+
+        let mut inner = self
+            .inner
+            .write()
+            .expect("pending receipts lock is poisoned");
+        inner.receipts.retain(|_, receipt| {
+            receipt
+                .block_number
+                .map_or(false, |number| number.as_u32() > sealed_miniblock.0)
+        });
+        inner.pruned_at = inner.pruned_at.max(sealed_miniblock);
+        metrics::gauge!(
+            "server.state_keeper.pending_receipts",
+            inner.receipts.len() as f64
+        );
+    }
+
+    /// Records a receipt for the last transaction executed in the current miniblock of `updates_manager`.
+    pub(crate) fn record_last_executed_tx(
+        &self,
+        updates_manager: &UpdatesManager,
+        l1_batch_number: L1BatchNumber,
+        miniblock_number: MiniblockNumber,
+    ) {
+        let receipt = pending_receipt(updates_manager, l1_batch_number, miniblock_number);
+        let mut inner = self
+            .inner
+            .write()
+            .expect("pending receipts lock is poisoned");
+        inner.receipts.insert(receipt.transaction_hash, receipt);
+    }
+
+    /// Caches receipts loaded from Postgres for a persisted miniblock. Must be called before the sealer notifies
+    /// about the miniblock, so that there's no gap between pruning provisional receipts and caching persisted ones.
+    pub(crate) fn record_sealed_miniblock(&self, receipts: Vec<api::TransactionReceipt>) {
+        let mut inner = self
+            .inner
+            .write()
+            .expect("pending receipts lock is poisoned");
+        inner.sealed_receipts.extend(
+            receipts
+                .into_iter()
+                .map(|receipt| (receipt.transaction_hash, receipt)),
+        );
+        metrics::gauge!(
+            "server.state_keeper.sealed_receipts",
+            inner.sealed_receipts.len() as f64
+        );
+    }
+
+    /// Drops cached receipts of persisted miniblocks. Called once the L1 batch containing the miniblocks is sealed.
+    pub(crate) fn clear_sealed(&self) {
+        let mut inner = self
+            .inner
+            .write()
+            .expect("pending receipts lock is poisoned");
+        inner.sealed_receipts.clear();
+        metrics::gauge!("server.state_keeper.sealed_receipts", 0.0);
+    }
+}
+
+fn pending_receipt(
+    updates_manager: &UpdatesManager,
+    l1_batch_number: L1BatchNumber,
+    miniblock_number: MiniblockNumber,
+) -> api::TransactionReceipt {
+    let executed_transactions = &updates_manager.miniblock.executed_transactions;
+    let index_in_miniblock = executed_transactions.len() - 1;
+    let index_in_l1_batch = updates_manager.pending_executed_transactions_len() - 1;
+    let tx_result = executed_transactions
+        .last()
+        .expect("no transactions executed in the current miniblock");
+    let tx = &tx_result.transaction;
+    // The miniblock hash commits to all its transactions, so it's unknown until the miniblock is sealed.
+    let block_hash = None;
+
+    // Events of the transaction are located by the index of the transaction in the L1 batch.
+    let tx_location = (l1_batch_number, index_in_l1_batch as u32);
+    let first_event_index = updates_manager
+        .miniblock
+        .events
+        .iter()
+        .position(|event| event.location == tx_location);
+    let events: Vec<_> = updates_manager
+        .miniblock
+        .events
+        .iter()
+        .filter(|event| event.location == tx_location)
+        .collect();
+    let contract_address = events
+        .iter()
+        .find(|event| {
+            event.address == CONTRACT_DEPLOYER_ADDRESS
+                && event.indexed_topics.first() == Some(&*DEPLOY_EVENT_SIGNATURE)
+        })
+        .and_then(|event| event.indexed_topics.get(3))
+        .map(h256_to_account_address);
+    let logs_bloom = events_bloom(events.iter().copied());
+    let logs = events
+        .iter()
+        .enumerate()
+        .map(|(index_in_tx, event)| api::Log {
+            address: event.address,
+            topics: event.indexed_topics.clone(),
+            data: Bytes(event.value.clone()),
+            block_hash,
+            block_number: Some(U64::from(miniblock_number.0)),
+            l1_batch_number: None,
+            transaction_hash: Some(tx_result.hash),
+            transaction_index: Some(U64::from(index_in_miniblock)),
+            log_index: first_event_index.map(|first| U256::from(first + index_in_tx)),
+            transaction_log_index: Some(U256::from(index_in_tx)),
+            log_type: None,
+            removed: Some(false),
+        })
+        .collect();
+    let l2_to_l1_logs = updates_manager
+        .miniblock
+        .l2_to_l1_logs
+        .iter()
+        .filter(|log| usize::from(log.tx_number_in_block) == index_in_l1_batch)
+        .enumerate()
+        .map(|(index_in_tx, log)| api::L2ToL1Log {
+            block_hash,
+            block_number: U64::from(miniblock_number.0),
+            l1_batch_number: None,
+            log_index: U256::from(index_in_tx),
+            transaction_index: U64::from(index_in_miniblock),
+            transaction_hash: tx_result.hash,
+            transaction_log_index: U256::from(index_in_tx),
+            tx_index_in_l1_batch: Some(U64::from(index_in_l1_batch)),
+            shard_id: U64::from(log.shard_id),
+            is_service: log.is_service,
+            sender: log.sender,
+            key: log.key,
+            value: log.value,
+        })
+        .collect();
+
+    let status = match tx_result.execution_status {
+        TxExecutionStatus::Success => 1,
+        TxExecutionStatus::Failure => 0,
+    };
+    api::TransactionReceipt {
+        transaction_hash: tx_result.hash,
+        transaction_index: U64::from(index_in_miniblock),
+        block_hash,
+        block_number: Some(U64::from(miniblock_number.0)),
+        l1_batch_tx_index: Some(U64::from(index_in_l1_batch)),
+        // The L1 batch number is only assigned to the miniblock once the L1 batch is sealed.
+        l1_batch_number: None,
+        from: tx.initiator_account(),
+        to: Some(tx.recipient_account()),
+        cumulative_gas_used: U256::zero(),
+        gas_used: Some(tx.gas_limit() - U256::from(tx_result.refunded_gas)),
+        contract_address,
+        logs,
+        l2_to_l1_logs,
+        status: Some(U64::from(status)),
+        root: block_hash,
+        logs_bloom,
+        transaction_type: Some(U64::from(tx.tx_format() as u32)),
+        effective_gas_price: Some(effective_gas_price(tx, updates_manager.base_fee_per_gas())),
+        confirmation_status: Some(api::ReceiptConfirmationStatus::Pending),
+    }
+}
+
+/// Computes the effective gas price in the same way as it's computed when persisting the transaction.
+fn effective_gas_price(tx: &Transaction, base_fee_per_gas: u64) -> U256 {
+    match &tx.common_data {
+        ExecuteTransactionCommon::L1(data) => data.max_fee_per_gas,
+        ExecuteTransactionCommon::L2(data) => {
+            data.fee.get_effective_gas_price(base_fee_per_gas.into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{block::BlockGasCount, tx::ExecutionMetrics, VmEvent};
+
+    use super::*;
+    use crate::state_keeper::tests::{
+        create_execution_result, create_transaction, create_updates_manager,
+    };
+
+    #[test]
+    fn pending_receipts_are_pruned_after_miniblock_seal() {
+        let (sealed_sender, sealed_miniblocks) = watch::channel(MiniblockNumber(0));
+        let receipts = PendingReceipts::new(sealed_miniblocks);
+        let mut updates_manager = create_updates_manager();
+
+        let tx = create_transaction(10, 100);
+        let tx_hash = tx.hash();
+        let mut execution_result = create_execution_result(0, []);
+        let event = VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: CONTRACT_DEPLOYER_ADDRESS,
+            indexed_topics: vec![
+                *DEPLOY_EVENT_SIGNATURE,
+                H256::zero(),
+                H256::zero(),
+                H256::repeat_byte(0x11),
+            ],
+            value: vec![],
+        };
+        execution_result.result.logs.events.push(event);
+        updates_manager.extend_from_executed_transaction(
+            tx,
+            execution_result,
+            vec![],
+            BlockGasCount::default(),
+            ExecutionMetrics::default(),
+        );
+        receipts.record_last_executed_tx(&updates_manager, L1BatchNumber(1), MiniblockNumber(1));
+
+        let receipt = receipts.get(tx_hash).unwrap();
+        assert_eq!(
+            receipt.confirmation_status,
+            Some(api::ReceiptConfirmationStatus::Pending)
+        );
+        assert_eq!(receipt.block_number, Some(1.into()));
+        assert_eq!(receipt.transaction_index, 0.into());
+        assert_eq!(receipt.status, Some(1.into()));
+        assert_eq!(receipt.logs.len(), 1);
+        assert_eq!(
+            receipt.contract_address,
+            Some(h256_to_account_address(&H256::repeat_byte(0x11)))
+        );
+
+        sealed_sender.send_replace(MiniblockNumber(1));
+        assert_eq!(receipts.get(tx_hash), None);
+    }
+
+    #[test]
+    fn sealed_receipts_are_cached_until_l1_batch_seal() {
+        let (sealed_sender, sealed_miniblocks) = watch::channel(MiniblockNumber(0));
+        let receipts = PendingReceipts::new(sealed_miniblocks);
+        let tx_hash = H256::repeat_byte(1);
+        let sealed_receipt = api::TransactionReceipt {
+            transaction_hash: tx_hash,
+            block_number: Some(1.into()),
+            status: Some(1.into()),
+            ..api::TransactionReceipt::default()
+        };
+
+        receipts.record_sealed_miniblock(vec![sealed_receipt.clone()]);
+        sealed_sender.send_replace(MiniblockNumber(1));
+        assert_eq!(receipts.get(tx_hash), Some(sealed_receipt));
+        assert_eq!(receipts.get(H256::repeat_byte(2)), None);
+
+        receipts.clear_sealed();
+        assert_eq!(receipts.get(tx_hash), None);
+    }
+}
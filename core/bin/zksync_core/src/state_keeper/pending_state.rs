@@ -0,0 +1,249 @@
+//! Storage diffs of miniblocks executed by the state keeper, but not yet persisted.
+//!
+//! The state keeper records storage writes and new factory deps after each executed transaction. API servers
+//! running in the same process layer these diffs over the persisted state when executing transactions against
+//! the pending block (e.g., in `eth_estimateGas` with the `pending` block tag), so that transactions depending
+//! on just executed transactions can be estimated. Diffs are dropped once the miniblock sealer reports that
+//! the corresponding miniblock is persisted.
+
+use tokio::sync::watch;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, RwLock},
+};
+
+use zksync_state::ReadStorage;
+use zksync_types::{AccountTreeId, MiniblockNumber, StorageKey, StorageValue, H256};
+use zksync_utils::u256_to_h256;
+
+use super::updates::UpdatesManager;
+
+#[derive(Debug, Default)]
+struct MiniblockDiff {
+    storage_writes: HashMap<StorageKey, StorageValue>,
+    factory_deps: HashMap<H256, Vec<u8>>,
+    /// Number of storage logs of the miniblock already applied to `storage_writes`.
+    processed_logs: usize,
+}
+
+/// Handle for storage diffs of miniblocks that are not persisted yet.
+#[derive(Debug, Clone)]
+pub struct PendingStorageDiffs {
+    sealed_miniblocks: watch::Receiver<MiniblockNumber>,
+    inner: Arc<RwLock<BTreeMap<MiniblockNumber, MiniblockDiff>>>,
+}
+
+impl PendingStorageDiffs {
+    /// Creates a handle. `sealed_miniblocks` must receive numbers of miniblocks persisted by the miniblock sealer.
+    pub fn new(sealed_miniblocks: watch::Receiver<MiniblockNumber>) -> Self {
+        Self {
+            sealed_miniblocks,
+            inner: Arc::default(),
+        }
+    }
+
+    /// Returns the latest pending value for `key`, or `None` if the key isn't written in pending miniblocks.
+    pub fn read_value(&self, key: &StorageKey) -> Option<StorageValue> {
+        self.prune();
+        let inner = self.inner.read().expect("pending state lock is poisoned");
+        inner
+            .values()
+            .rev()
+            .find_map(|diff| diff.storage_writes.get(key).copied())
+    }
+
+    /// Merges diffs of pending miniblocks with numbers greater than `miniblock_number`, i.e., the diffs
+    /// that are not reflected in the state persisted up to and including `miniblock_number`.
+    pub fn overlay_after(&self, miniblock_number: MiniblockNumber) -> PendingStateOverlay {
+        self.prune();
+        let inner = self.inner.read().expect("pending state lock is poisoned");
+        let mut overlay = PendingStateOverlay::default();
+        for (_, diff) in inner.range(miniblock_number + 1..) {
+            overlay.storage_writes.extend(&diff.storage_writes);
+            overlay.factory_deps.extend(
+                diff.factory_deps
+                    .iter()
+                    .map(|(hash, bytecode)| (*hash, bytecode.clone())),
+            );
+        }
+        overlay
+    }
+
+    /// Removes diffs of persisted miniblocks.
+    fn prune(&self) {
+        let sealed_miniblock = *self.sealed_miniblocks.borrow();
+        let has_sealed_diffs = self
+            .inner
+            .read()
+            .expect("pending state lock is poisoned")
+            .keys()
+            .next()
+            .map_or(false, |&number| number <= sealed_miniblock);
+        if !has_sealed_diffs {
+            return;
+        }
+
+        let mut inner = self.inner.write().expect("pending state lock is poisoned");
+        *inner = inner.split_off(&(sealed_miniblock + 1));
+        let pending_writes: usize = inner.values().map(|diff| diff.storage_writes.len()).sum();
+        metrics::gauge!(
+            "server.state_keeper.pending_storage_writes",
+            pending_writes as f64
+        );
+    }
+
+    /// Records storage writes and factory deps of transactions executed in the current miniblock
+    /// of `updates_manager` since the previous call.
+    pub(crate) fn record_last_executed_tx(
+        &self,
+        updates_manager: &UpdatesManager,
+        miniblock_number: MiniblockNumber,
+    ) {
+        let miniblock = &updates_manager.miniblock;
+        let mut inner = self.inner.write().expect("pending state lock is poisoned");
+        let diff = inner.entry(miniblock_number).or_default();
+        for log in &miniblock.storage_logs[diff.processed_logs..] {
+            let query = &log.log_query;
+            if !query.rw_flag {
+                continue;
+            }
+            let key = StorageKey::new(AccountTreeId::new(query.address), u256_to_h256(query.key));
+            // Rollback queries restore the value preceding the rolled back write.
+            let value = if query.rollback {
+                query.read_value
+            } else {
+                query.written_value
+            };
+            diff.storage_writes.insert(key, u256_to_h256(value));
+        }
+        diff.processed_logs = miniblock.storage_logs.len();
+
+        for (hash, bytecode) in &miniblock.new_factory_deps {
+            diff.factory_deps
+                .entry(*hash)
+                .or_insert_with(|| bytecode.clone());
+        }
+    }
+}
+
+/// Merged storage diffs of pending miniblocks obtained via [`PendingStorageDiffs::overlay_after()`].
+#[derive(Debug, Clone, Default)]
+pub struct PendingStateOverlay {
+    storage_writes: HashMap<StorageKey, StorageValue>,
+    factory_deps: HashMap<H256, Vec<u8>>,
+}
+
+/// Storage layering a [`PendingStateOverlay`] over the persisted state.
+#[derive(Debug)]
+pub struct PendingStorage<S> {
+    inner: S,
+    overlay: PendingStateOverlay,
+}
+
+impl<S: ReadStorage> PendingStorage<S> {
+    pub fn new(inner: S, overlay: PendingStateOverlay) -> Self {
+        Self { inner, overlay }
+    }
+}
+
+impl<S: ReadStorage> ReadStorage for PendingStorage<S> {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        match self.overlay.storage_writes.get(key) {
+            Some(value) => *value,
+            None => self.inner.read_value(key),
+        }
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        // Pending writes are not deduplicated with the sandboxed transaction, so the persisted state is used.
+        // This may overestimate the pubdata published by the transaction, which is fine for the sandbox.
+        self.inner.is_write_initial(key)
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        match self.overlay.factory_deps.get(&hash) {
+            Some(bytecode) => Some(bytecode.clone()),
+            None => self.inner.load_factory_dep(hash),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_state::InMemoryStorage;
+    use zksync_types::{block::BlockGasCount, tx::ExecutionMetrics, Address, U256};
+
+    use super::*;
+    use crate::state_keeper::tests::{
+        create_execution_result, create_transaction, create_updates_manager, Query,
+    };
+
+    fn storage_key(key: u64) -> StorageKey {
+        StorageKey::new(
+            AccountTreeId::new(Address::default()),
+            u256_to_h256(key.into()),
+        )
+    }
+
+    #[test]
+    fn pending_storage_diffs_are_layered_and_pruned() {
+        let (sealed_sender, sealed_miniblocks) = watch::channel(MiniblockNumber(0));
+        let diffs = PendingStorageDiffs::new(sealed_miniblocks);
+        let mut updates_manager = create_updates_manager();
+
+        let execution_result = create_execution_result(
+            0,
+            [
+                (U256::from(1), Query::InitialWrite(U256::from(10))),
+                (U256::from(2), Query::Read(U256::from(20))),
+            ],
+        );
+        updates_manager.extend_from_executed_transaction(
+            create_transaction(10, 100),
+            execution_result,
+            vec![],
+            BlockGasCount::default(),
+            ExecutionMetrics::default(),
+        );
+        diffs.record_last_executed_tx(&updates_manager, MiniblockNumber(1));
+
+        updates_manager.push_miniblock(1);
+        let execution_result = create_execution_result(
+            1,
+            [(
+                U256::from(1),
+                Query::RepeatedWrite(U256::from(10), U256::from(11)),
+            )],
+        );
+        updates_manager.extend_from_executed_transaction(
+            create_transaction(10, 100),
+            execution_result,
+            vec![],
+            BlockGasCount::default(),
+            ExecutionMetrics::default(),
+        );
+        diffs.record_last_executed_tx(&updates_manager, MiniblockNumber(2));
+
+        assert_eq!(
+            diffs.read_value(&storage_key(1)),
+            Some(u256_to_h256(11.into()))
+        );
+        assert_eq!(diffs.read_value(&storage_key(2)), None);
+
+        let mut base_storage = InMemoryStorage::default();
+        base_storage.set_value(storage_key(1), u256_to_h256(10.into()));
+        base_storage.set_value(storage_key(2), u256_to_h256(20.into()));
+        let mut storage =
+            PendingStorage::new(base_storage, diffs.overlay_after(MiniblockNumber(1)));
+        assert_eq!(storage.read_value(&storage_key(1)), u256_to_h256(11.into()));
+        assert_eq!(storage.read_value(&storage_key(2)), u256_to_h256(20.into()));
+
+        sealed_sender.send_replace(MiniblockNumber(2));
+        assert_eq!(diffs.read_value(&storage_key(1)), None);
+        assert!(diffs
+            .overlay_after(MiniblockNumber(0))
+            .storage_writes
+            .is_empty());
+    }
+}
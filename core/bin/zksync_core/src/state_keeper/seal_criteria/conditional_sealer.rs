@@ -52,7 +52,7 @@ impl ConditionalSealer {
         tx_count: usize,
         block_data: &SealData,
         tx_data: &SealData,
-    ) -> SealResolution {
+    ) -> (SealResolution, Option<&'static str>) {
         vlog::debug!(
             "Determining seal resolution for L1 batch #{l1_batch_number} with {tx_count} transactions \
              and metrics {:?}",
@@ -60,6 +60,8 @@ impl ConditionalSealer {
         );
 
         let mut final_seal_resolution = SealResolution::NoSeal;
+        // Name of the first criterion that has produced the final (i.e., the strictest) resolution.
+        let mut final_criterion = None;
         for sealer in &self.sealers {
             let seal_resolution = sealer.should_seal(
                 &self.config,
@@ -86,21 +88,28 @@ impl ConditionalSealer {
                 SealResolution::NoSeal => { /* Don't do anything */ }
             }
 
-            final_seal_resolution = final_seal_resolution.stricter(seal_resolution);
+            let stricter_resolution = final_seal_resolution.clone().stricter(seal_resolution);
+            if stricter_resolution != final_seal_resolution {
+                final_criterion = Some(sealer.prom_criterion_name());
+            }
+            final_seal_resolution = stricter_resolution;
         }
-        final_seal_resolution
+        (final_seal_resolution, final_criterion)
     }
 
     fn default_sealers() -> Vec<Box<dyn SealCriterion>> {
         vec![
             Box::new(criteria::SlotsCriterion),
+            Box::new(criteria::L2TxCountCriterion),
             Box::new(criteria::GasCriterion),
             Box::new(criteria::PubDataBytesCriterion),
             Box::new(criteria::InitialWritesCriterion),
             Box::new(criteria::RepeatedWritesCriterion),
             Box::new(criteria::MaxCyclesCriterion),
             Box::new(criteria::ComputationalGasCriterion),
+            Box::new(criteria::CircuitsCriterion),
             Box::new(criteria::TxEncodingSizeCriterion),
+            Box::new(criteria::FactoryDepsCriterion),
         ]
     }
 }
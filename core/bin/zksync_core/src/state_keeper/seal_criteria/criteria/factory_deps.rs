@@ -0,0 +1,105 @@
+use zksync_types::tx::{TxRejectedResource, TxRejection};
+
+use crate::state_keeper::seal_criteria::{
+    SealCriterion, SealData, SealResolution, StateKeeperConfig,
+};
+
+/// Rejects transactions with too many or too large factory dependencies. Such transactions would otherwise
+/// fail when publishing bytecodes during sealing.
+#[derive(Debug)]
+pub struct FactoryDepsCriterion;
+
+impl SealCriterion for FactoryDepsCriterion {
+    fn should_seal(
+        &self,
+        config: &StateKeeperConfig,
+        _block_open_timestamp_ms: u128,
+        _tx_count: usize,
+        _block_data: &SealData,
+        tx_data: &SealData,
+    ) -> SealResolution {
+        let max_count = config.max_factory_deps_per_tx();
+        if tx_data.factory_deps_count > max_count {
+            let message = format!(
+                "Transaction cannot be included due to too many factory dependencies: {} provided, \
+                 while only {max_count} allowed",
+                tx_data.factory_deps_count
+            );
+            let rejection =
+                TxRejection::resource_limit_exceeded(TxRejectedResource::FactoryDeps, message);
+            return SealResolution::Unexecutable(rejection);
+        }
+
+        match config.max_factory_deps_size_per_tx {
+            Some(max_size) if tx_data.factory_deps_size > max_size => {
+                let message = format!(
+                    "Transaction cannot be included due to large factory dependencies: {} bytes provided, \
+                     while only {max_size} bytes allowed",
+                    tx_data.factory_deps_size
+                );
+                let rejection =
+                    TxRejection::resource_limit_exceeded(TxRejectedResource::FactoryDeps, message);
+                SealResolution::Unexecutable(rejection)
+            }
+            _ => SealResolution::NoSeal,
+        }
+    }
+
+    fn prom_criterion_name(&self) -> &'static str {
+        "factory_deps"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seal_criterion() {
+        let config = StateKeeperConfig {
+            max_factory_deps_per_tx: Some(2),
+            max_factory_deps_size_per_tx: Some(1_000),
+            ..StateKeeperConfig::from_env()
+        };
+        let criterion = FactoryDepsCriterion;
+
+        let resolution = criterion.should_seal(
+            &config,
+            0,
+            0,
+            &SealData::default(),
+            &SealData {
+                factory_deps_count: 2,
+                factory_deps_size: 1_000,
+                ..SealData::default()
+            },
+        );
+        assert_eq!(resolution, SealResolution::NoSeal);
+
+        let resolution = criterion.should_seal(
+            &config,
+            0,
+            0,
+            &SealData::default(),
+            &SealData {
+                factory_deps_count: 3,
+                factory_deps_size: 96,
+                ..SealData::default()
+            },
+        );
+        assert!(matches!(resolution, SealResolution::Unexecutable(_)));
+
+        let resolution = criterion.should_seal(
+            &config,
+            0,
+            0,
+            &SealData::default(),
+            &SealData {
+                factory_deps_count: 1,
+                factory_deps_size: 1_001,
+                ..SealData::default()
+            },
+        );
+        assert!(matches!(resolution, SealResolution::Unexecutable(_)));
+    }
+}
@@ -1,3 +1,5 @@
+use zksync_types::tx::{TxRejectedResource, TxRejection};
+
 use crate::{
     gas_tracker::new_block_gas_count,
     state_keeper::seal_criteria::{SealCriterion, SealData, SealResolution, StateKeeperConfig},
@@ -27,7 +29,9 @@ impl SealCriterion for GasCriterion {
             (config.max_single_tx_gas as f64 * config.close_block_at_gas_percentage).round() as u32;
 
         if (tx_data.gas_count + new_block_gas_count()).has_greater_than(tx_bound) {
-            SealResolution::Unexecutable("Transaction requires too much gas".into())
+            let message = "Transaction requires too much gas";
+            let rejection = TxRejection::resource_limit_exceeded(TxRejectedResource::Gas, message);
+            SealResolution::Unexecutable(rejection)
         } else if block_data
             .gas_count
             .has_greater_than(config.max_single_tx_gas)
@@ -91,7 +95,10 @@ mod tests {
         );
         assert_eq!(
             huge_transaction_resolution,
-            SealResolution::Unexecutable("Transaction requires too much gas".into())
+            SealResolution::Unexecutable(TxRejection::resource_limit_exceeded(
+                TxRejectedResource::Gas,
+                "Transaction requires too much gas"
+            ))
         );
 
         // Check criterion workflow
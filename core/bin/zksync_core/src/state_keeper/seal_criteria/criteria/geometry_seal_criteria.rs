@@ -5,7 +5,10 @@ use vm::{zk_evm::zkevm_opcode_defs::system_params::ERGS_PER_CIRCUIT, MAX_CYCLES_
 use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_types::{
     circuit::{GEOMETRY_CONFIG, SCHEDULER_UPPER_BOUND},
-    tx::tx_execution_info::{DeduplicatedWritesMetrics, ExecutionMetrics},
+    tx::{
+        tx_execution_info::{DeduplicatedWritesMetrics, ExecutionMetrics},
+        TxRejectedResource, TxRejection,
+    },
 };
 
 // Local uses
@@ -14,6 +17,11 @@ use crate::state_keeper::seal_criteria::{SealCriterion, SealData, SealResolution
 // Collected vm execution metrics should fit into geometry limits.
 // Otherwise witness generation will fail and proof won't be generated.
 
+// We subtract constant to take into account that circuits may be not fully filled.
+// This constant should be greater than number of circuits types
+// but we keep it larger to be on the safe side.
+const MARGIN_NUMBER_OF_CIRCUITS: usize = 100;
+
 #[derive(Debug, Default)]
 pub struct RepeatedWritesCriterion;
 #[derive(Debug, Default)]
@@ -22,6 +30,8 @@ pub struct InitialWritesCriterion;
 pub struct MaxCyclesCriterion;
 #[derive(Debug, Default)]
 pub struct ComputationalGasCriterion;
+#[derive(Debug, Default)]
+pub struct CircuitsCriterion;
 
 trait MetricExtractor {
     const PROM_METRIC_CRITERION_NAME: &'static str;
@@ -47,7 +57,10 @@ where
             (T::limit_per_block() as f64 * config.close_block_at_geometry_percentage).round();
 
         if T::extract(&tx_data.execution_metrics, &tx_data.writes_metrics) > reject_bound as usize {
-            SealResolution::Unexecutable("ZK proof cannot be generated for a transaction".into())
+            let message = "ZK proof cannot be generated for a transaction";
+            let rejection =
+                TxRejection::resource_limit_exceeded(TxRejectedResource::Circuits, message);
+            SealResolution::Unexecutable(rejection)
         } else if T::extract(&block_data.execution_metrics, &block_data.writes_metrics)
             >= T::limit_per_block()
         {
@@ -106,10 +119,6 @@ impl MetricExtractor for ComputationalGasCriterion {
     const PROM_METRIC_CRITERION_NAME: &'static str = "computational_gas";
 
     fn limit_per_block() -> usize {
-        // We subtract constant to take into account that circuits may be not fully filled.
-        // This constant should be greater than number of circuits types
-        // but we keep it larger to be on the safe side.
-        const MARGIN_NUMBER_OF_CIRCUITS: usize = 100;
         const MAX_NUMBER_OF_MUTLIINSTANCE_CIRCUITS: usize =
             SCHEDULER_UPPER_BOUND as usize - MARGIN_NUMBER_OF_CIRCUITS;
 
@@ -121,9 +130,22 @@ impl MetricExtractor for ComputationalGasCriterion {
     }
 }
 
+impl MetricExtractor for CircuitsCriterion {
+    const PROM_METRIC_CRITERION_NAME: &'static str = "circuits";
+
+    fn limit_per_block() -> usize {
+        SCHEDULER_UPPER_BOUND as usize - MARGIN_NUMBER_OF_CIRCUITS
+    }
+
+    fn extract(metrics: &ExecutionMetrics, _writes: &DeduplicatedWritesMetrics) -> usize {
+        metrics.circuit_statistic.total()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use zksync_types::circuit::CircuitStatistic;
 
     fn get_config() -> StateKeeperConfig {
         StateKeeperConfig {
@@ -213,7 +235,10 @@ mod tests {
 
         assert_eq!(
             block_resolution,
-            SealResolution::Unexecutable("ZK proof cannot be generated for a transaction".into())
+            SealResolution::Unexecutable(TxRejection::resource_limit_exceeded(
+                TxRejectedResource::Circuits,
+                "ZK proof cannot be generated for a transaction"
+            ))
         );
     }
 
@@ -324,4 +349,54 @@ mod tests {
     fn computational_gas_seal_criterion() {
         test_scenario_execution_metrics!(ComputationalGasCriterion, computational_gas_used, u32);
     }
+
+    fn metrics_with_main_vm_circuits(circuits: usize) -> ExecutionMetrics {
+        ExecutionMetrics {
+            circuit_statistic: CircuitStatistic {
+                main_vm: circuits as f32,
+                ..CircuitStatistic::default()
+            },
+            ..ExecutionMetrics::default()
+        }
+    }
+
+    #[test]
+    fn circuits_seal_criterion() {
+        let config = get_config();
+        let writes_metrics = DeduplicatedWritesMetrics::default();
+        let limit = CircuitsCriterion::limit_per_block();
+
+        let block_execution_metrics = metrics_with_main_vm_circuits(limit / 2);
+        test_no_seal_block_resolution(block_execution_metrics, writes_metrics, &CircuitsCriterion);
+
+        let block_execution_metrics = metrics_with_main_vm_circuits(limit - 1);
+        test_include_and_seal_block_resolution(
+            block_execution_metrics,
+            writes_metrics,
+            &CircuitsCriterion,
+        );
+
+        let block_execution_metrics = metrics_with_main_vm_circuits(limit);
+        test_exclude_and_seal_block_resolution(
+            block_execution_metrics,
+            writes_metrics,
+            &CircuitsCriterion,
+        );
+
+        let tx_circuits = (limit as f64 * config.reject_tx_at_geometry_percentage + 1.0).round();
+        let tx_execution_metrics = metrics_with_main_vm_circuits(tx_circuits as usize);
+        test_unexecutable_tx_resolution(tx_execution_metrics, writes_metrics, &CircuitsCriterion);
+    }
+
+    #[test]
+    fn circuit_statistic_rounds_up_each_circuit_type() {
+        let statistic = CircuitStatistic {
+            main_vm: 1.5,
+            ram_permutation: 0.1,
+            keccak256: 2.0,
+            ..CircuitStatistic::default()
+        };
+        assert_eq!(statistic.total(), 5);
+        assert_eq!((statistic + statistic).total(), 8);
+    }
 }
@@ -1,16 +1,20 @@
+mod factory_deps;
 mod gas;
 mod geometry_seal_criteria;
 mod pubdata_bytes;
 mod slots;
+mod tx_count;
 mod tx_encoding_size;
 
 pub(in crate::state_keeper) use self::{
+    factory_deps::FactoryDepsCriterion,
     gas::GasCriterion,
     geometry_seal_criteria::{
-        ComputationalGasCriterion, InitialWritesCriterion, MaxCyclesCriterion,
+        CircuitsCriterion, ComputationalGasCriterion, InitialWritesCriterion, MaxCyclesCriterion,
         RepeatedWritesCriterion,
     },
     pubdata_bytes::PubDataBytesCriterion,
     slots::SlotsCriterion,
+    tx_count::L2TxCountCriterion,
     tx_encoding_size::TxEncodingSizeCriterion,
 };
@@ -1,4 +1,7 @@
-use zksync_types::MAX_PUBDATA_PER_L1_BATCH;
+use zksync_types::{
+    tx::{TxRejectedResource, TxRejection},
+    MAX_PUBDATA_PER_L1_BATCH,
+};
 
 use crate::state_keeper::seal_criteria::{
     SealCriterion, SealData, SealResolution, StateKeeperConfig,
@@ -26,7 +29,9 @@ impl SealCriterion for PubDataBytesCriterion {
 
         if tx_size > reject_bound as usize {
             let message = "Transaction cannot be sent to L1 due to pubdata limits";
-            SealResolution::Unexecutable(message.into())
+            let rejection =
+                TxRejection::resource_limit_exceeded(TxRejectedResource::Pubdata, message);
+            SealResolution::Unexecutable(rejection)
         } else if block_size > max_pubdata_per_l1_batch {
             SealResolution::ExcludeAndSeal
         } else if block_size > include_and_seal_bound as usize {
@@ -0,0 +1,63 @@
+use crate::state_keeper::seal_criteria::{
+    SealCriterion, SealData, SealResolution, StateKeeperConfig,
+};
+
+/// Checks whether we should seal the block because it contains the max allowed number of L2 transactions.
+/// Unlike [`SlotsCriterion`](super::SlotsCriterion), L1 transactions are not taken into account.
+#[derive(Debug)]
+pub struct L2TxCountCriterion;
+
+impl SealCriterion for L2TxCountCriterion {
+    fn should_seal(
+        &self,
+        config: &StateKeeperConfig,
+        _block_open_timestamp_ms: u128,
+        _tx_count: usize,
+        block_data: &SealData,
+        _tx_data: &SealData,
+    ) -> SealResolution {
+        match config.max_l2_txs_per_l1_batch {
+            Some(max_l2_tx_count) if block_data.l2_tx_count >= max_l2_tx_count => {
+                SealResolution::IncludeAndSeal
+            }
+            _ => SealResolution::NoSeal,
+        }
+    }
+
+    fn prom_criterion_name(&self) -> &'static str {
+        "l2_tx_count"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn l2_tx_count_seal_criterion() {
+        let config = StateKeeperConfig {
+            max_l2_txs_per_l1_batch: Some(10),
+            ..StateKeeperConfig::default()
+        };
+        let criterion = L2TxCountCriterion;
+
+        let block_data = SealData {
+            l2_tx_count: 9,
+            ..SealData::default()
+        };
+        let resolution = criterion.should_seal(&config, 0, 20, &block_data, &SealData::default());
+        assert_eq!(resolution, SealResolution::NoSeal);
+
+        let block_data = SealData {
+            l2_tx_count: 10,
+            ..SealData::default()
+        };
+        let resolution = criterion.should_seal(&config, 0, 10, &block_data, &SealData::default());
+        assert_eq!(resolution, SealResolution::IncludeAndSeal);
+
+        let unlimited_config = StateKeeperConfig::default();
+        let resolution =
+            criterion.should_seal(&unlimited_config, 0, 10, &block_data, &SealData::default());
+        assert_eq!(resolution, SealResolution::NoSeal);
+    }
+}
@@ -1,4 +1,5 @@
 use vm::vm_with_bootloader::BOOTLOADER_TX_ENCODING_SPACE;
+use zksync_types::tx::{TxRejectedResource, TxRejection};
 
 use crate::state_keeper::seal_criteria::{
     SealCriterion, SealData, SealResolution, StateKeeperConfig,
@@ -24,7 +25,9 @@ impl SealCriterion for TxEncodingSizeCriterion {
 
         if tx_data.cumulative_size > reject_bound as usize {
             let message = "Transaction cannot be included due to large encoding size";
-            SealResolution::Unexecutable(message.into())
+            let rejection =
+                TxRejection::resource_limit_exceeded(TxRejectedResource::EncodingSize, message);
+            SealResolution::Unexecutable(rejection)
         } else if block_data.cumulative_size > BOOTLOADER_TX_ENCODING_SPACE as usize {
             SealResolution::ExcludeAndSeal
         } else if block_data.cumulative_size > include_and_seal_bound as usize {
@@ -64,9 +67,10 @@ mod tests {
         );
         assert_eq!(
             unexecutable_resolution,
-            SealResolution::Unexecutable(
-                "Transaction cannot be included due to large encoding size".into()
-            )
+            SealResolution::Unexecutable(TxRejection::resource_limit_exceeded(
+                TxRejectedResource::EncodingSize,
+                "Transaction cannot be included due to large encoding size"
+            ))
         );
 
         let exclude_and_seal_resolution = criterion.should_seal(
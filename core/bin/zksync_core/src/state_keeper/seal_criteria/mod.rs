@@ -10,15 +10,19 @@
 //! Maintaining all the criteria in one place has proven itself to be very error-prone,
 //! thus now every criterion is independent of the others.
 
-use std::fmt;
+use std::{collections::HashSet, fmt, sync::Arc};
 
 use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_types::{
+    aggregated_operations::AggregatedActionType,
     block::BlockGasCount,
     fee::TransactionExecutionMetrics,
-    tx::tx_execution_info::{DeduplicatedWritesMetrics, ExecutionMetrics},
-    Transaction,
+    tx::{
+        tx_execution_info::{DeduplicatedWritesMetrics, ExecutionMetrics},
+        TxRejection,
+    },
+    Address, Transaction,
 };
 use zksync_utils::time::millis_since;
 
@@ -27,7 +31,13 @@ pub(super) mod criteria;
 
 pub(crate) use self::conditional_sealer::ConditionalSealer;
 use super::{extractors, updates::UpdatesManager};
-use crate::gas_tracker::{gas_count_from_tx_and_metrics, gas_count_from_writes};
+use crate::{
+    admin_controls::AdminControls,
+    gas_tracker::{
+        agg_block_base_cost, block_base_cost, gas_count_from_tx_and_metrics, gas_count_from_writes,
+    },
+    l1_gas_price::L1GasPriceProvider,
+};
 
 /// Reported decision regarding block sealing.
 #[derive(Debug, Clone, PartialEq)]
@@ -48,7 +58,7 @@ pub enum SealResolution {
     /// if the block will consist of it solely. Such a transaction must be rejected.
     ///
     /// Contains a reason for why transaction was considered unexecutable.
-    Unexecutable(String),
+    Unexecutable(TxRejection),
 }
 
 impl SealResolution {
@@ -91,6 +101,12 @@ pub struct SealData {
     pub(super) gas_count: BlockGasCount,
     pub(super) cumulative_size: usize,
     pub(super) writes_metrics: DeduplicatedWritesMetrics,
+    /// Number of L2 transactions.
+    pub(super) l2_tx_count: usize,
+    /// Number of new factory dependencies. Only tracked for individual transactions.
+    pub(super) factory_deps_count: usize,
+    /// Total size of new factory dependencies in bytes. Only tracked for individual transactions.
+    pub(super) factory_deps_size: usize,
 }
 
 impl SealData {
@@ -104,11 +120,17 @@ impl SealData {
         let writes_metrics = DeduplicatedWritesMetrics::from_tx_metrics(tx_metrics);
         let gas_count = gas_count_from_tx_and_metrics(&transaction, &execution_metrics)
             + gas_count_from_writes(&writes_metrics);
+        let l2_tx_count = usize::from(!transaction.is_l1());
+        let factory_deps_count = transaction.execute.factory_deps_length();
+        let factory_deps_size = transaction.execute.factory_deps_size();
         Self {
             execution_metrics,
             gas_count,
             cumulative_size: extractors::encoded_transaction_size(transaction),
             writes_metrics,
+            l2_tx_count,
+            factory_deps_count,
+            factory_deps_size,
         }
     }
 }
@@ -131,6 +153,51 @@ pub(super) trait SealCriterion: fmt::Debug + Send + 'static {
 /// Sealer function that returns a boolean.
 pub type SealerFn = dyn Fn(&UpdatesManager) -> bool + Send;
 
+/// Sealer function together with the name of the rule it implements. The name is persisted
+/// as the seal reason of the sealed miniblock / L1 batch.
+type NamedSealer = (&'static str, Box<SealerFn>);
+
+/// Seal reason reported for sealers provided via [`SealManager::custom()`].
+const CUSTOM_RULE: &str = "custom";
+const NO_TXS_TIMEOUT_RULE: &str = "no_txs_timeout";
+const L1_COMMIT_COST_TIMEOUT_RULE: &str = "l1_commit_cost_timeout";
+const DIFFERENT_CODE_HASHES_RULE: &str = "different_code_hashes";
+const MINIBLOCK_TIMEOUT_RULE: &str = "miniblock_timeout";
+const MINIBLOCK_L2_TX_COUNT_RULE: &str = "miniblock_l2_tx_count";
+const MINIBLOCK_GAS_RULE: &str = "miniblock_gas";
+const ADMIN_REQUEST_RULE: &str = "admin_request";
+const PRIORITY_OP_RULE: &str = "priority_op";
+
+/// L1 batch deadline scaled by the projected L1 cost of committing the batch.
+#[derive(Debug, Clone, Copy)]
+struct L1CommitCostDeadline {
+    base_deadline_ms: u64,
+    min_deadline_ms: u64,
+    max_deadline_ms: u64,
+    /// Commit cost (in wei) corresponding to `base_deadline_ms`.
+    reference_cost: u64,
+}
+
+impl L1CommitCostDeadline {
+    /// Returns the fixed part of the L1 cost of committing a batch, which is amortized
+    /// by keeping batches open longer.
+    fn projected_commit_cost(l1_gas_price: u64) -> u64 {
+        let commit_gas = agg_block_base_cost(AggregatedActionType::CommitBlocks)
+            + block_base_cost(AggregatedActionType::CommitBlocks);
+        l1_gas_price.saturating_mul(commit_gas.into())
+    }
+
+    fn deadline_ms(&self, commit_cost: u64) -> u64 {
+        let scaled_deadline = u128::from(self.base_deadline_ms) * u128::from(commit_cost)
+            / u128::from(self.reference_cost.max(1));
+        let scaled_deadline = u64::try_from(scaled_deadline).unwrap_or(u64::MAX);
+        scaled_deadline.clamp(
+            self.min_deadline_ms,
+            self.max_deadline_ms.max(self.min_deadline_ms),
+        )
+    }
+}
+
 pub struct SealManager {
     /// Conditional sealer, i.e. one that can decide whether the batch should be sealed after executing a tx.
     /// Currently, it's expected to be `Some` on the main node and `None` on the external nodes, since external nodes
@@ -140,10 +207,10 @@ pub struct SealManager {
     /// If any of the unconditional sealers returns `true`, the batch will be sealed.
     ///
     /// Note: only non-empty batch can be sealed.
-    unconditional_sealers: Vec<Box<SealerFn>>,
+    unconditional_sealers: Vec<NamedSealer>,
     /// Miniblock sealer function used to determine if we should seal the miniblock.
     /// If any of the miniblock sealers returns `true`, the miniblock will be sealed.
-    miniblock_sealers: Vec<Box<SealerFn>>,
+    miniblock_sealers: Vec<NamedSealer>,
 }
 
 impl fmt::Debug for SealManager {
@@ -156,39 +223,105 @@ impl fmt::Debug for SealManager {
 
 impl SealManager {
     /// Creates a default pre-configured seal manager for the main node.
-    pub(super) fn new(config: StateKeeperConfig) -> Self {
-        let timeout_batch_sealer = Self::timeout_batch_sealer(config.block_commit_deadline_ms);
+    pub fn new<G>(config: StateKeeperConfig, l1_gas_price_provider: Arc<G>) -> Self
+    where
+        G: L1GasPriceProvider + 'static + Send + Sync,
+    {
+        let timeout_batch_sealer = match config.l1_commit_cost_reference_wei {
+            Some(reference_cost) => (
+                L1_COMMIT_COST_TIMEOUT_RULE,
+                Self::l1_commit_cost_batch_sealer(
+                    L1CommitCostDeadline {
+                        base_deadline_ms: config.block_commit_deadline_ms,
+                        min_deadline_ms: config.min_block_commit_deadline_ms(),
+                        max_deadline_ms: config.max_block_commit_deadline_ms(),
+                        reference_cost,
+                    },
+                    l1_gas_price_provider,
+                ),
+            ),
+            None => (
+                NO_TXS_TIMEOUT_RULE,
+                Self::timeout_batch_sealer(config.block_commit_deadline_ms),
+            ),
+        };
         let code_hash_batch_sealer = Self::code_hash_batch_sealer(BaseSystemContractsHashes {
             bootloader: config.bootloader_hash,
             default_aa: config.default_aa_hash,
         });
-        let timeout_miniblock_sealer =
-            Self::timeout_miniblock_sealer(config.miniblock_commit_deadline_ms);
+        let mut miniblock_sealers: Vec<NamedSealer> = vec![(
+            MINIBLOCK_TIMEOUT_RULE,
+            Self::timeout_miniblock_sealer(config.miniblock_commit_deadline_ms),
+        )];
+        if let Some(max_l2_tx_count) = config.max_l2_txs_per_miniblock {
+            miniblock_sealers.push((
+                MINIBLOCK_L2_TX_COUNT_RULE,
+                Self::l2_tx_count_miniblock_sealer(max_l2_tx_count),
+            ));
+        }
+        if let Some(gas_limit) = config.miniblock_gas_limit {
+            miniblock_sealers.push((MINIBLOCK_GAS_RULE, Self::gas_miniblock_sealer(gas_limit)));
+        }
+        let mut unconditional_sealers = vec![
+            timeout_batch_sealer,
+            (DIFFERENT_CODE_HASHES_RULE, code_hash_batch_sealer),
+        ];
+        let priority_op_contracts = config.seal_on_priority_op_contracts();
+        if !priority_op_contracts.is_empty() {
+            unconditional_sealers.push((
+                PRIORITY_OP_RULE,
+                Self::priority_op_batch_sealer(priority_op_contracts.iter().copied().collect()),
+            ));
+        }
         let conditional_sealer = ConditionalSealer::new(config);
 
-        Self::custom(
-            Some(conditional_sealer),
-            vec![timeout_batch_sealer, code_hash_batch_sealer],
-            vec![timeout_miniblock_sealer],
-        )
+        Self {
+            conditional_sealer: Some(conditional_sealer),
+            unconditional_sealers,
+            miniblock_sealers,
+        }
     }
 
     /// Allows to create a seal manager object from externally-defined sealers.
+    /// Blocks sealed by these sealers have the `custom` seal reason.
     pub fn custom(
         conditional_sealer: Option<ConditionalSealer>,
         unconditional_sealers: Vec<Box<SealerFn>>,
         miniblock_sealers: Vec<Box<SealerFn>>,
     ) -> Self {
+        let with_custom_name = |sealer| (CUSTOM_RULE, sealer);
         Self {
             conditional_sealer,
-            unconditional_sealers,
-            miniblock_sealers,
+            unconditional_sealers: unconditional_sealers
+                .into_iter()
+                .map(with_custom_name)
+                .collect(),
+            miniblock_sealers: miniblock_sealers
+                .into_iter()
+                .map(with_custom_name)
+                .collect(),
         }
     }
 
+    /// Makes the seal manager seal the current L1 batch once requested via [`AdminControls`].
+    pub fn with_admin_controls(mut self, controls: AdminControls) -> Self {
+        let admin_sealer: Box<SealerFn> = Box::new(move |_| {
+            let should_seal = controls.take_l1_batch_seal_request();
+            if should_seal {
+                metrics::increment_counter!("server.tx_aggregation.reason", "criterion" => ADMIN_REQUEST_RULE);
+                vlog::info!("Decided to seal L1 batch on operator request");
+            }
+            should_seal
+        });
+        // Place the sealer first, so that the request is consumed even if other sealers fire as well.
+        self.unconditional_sealers
+            .insert(0, (ADMIN_REQUEST_RULE, admin_sealer));
+        self
+    }
+
     /// Creates a sealer function that would seal the batch because of the timeout.
     fn timeout_batch_sealer(block_commit_deadline_ms: u64) -> Box<SealerFn> {
-        const RULE_NAME: &str = "no_txs_timeout";
+        const RULE_NAME: &str = NO_TXS_TIMEOUT_RULE;
 
         Box::new(move |manager| {
             // Verify timestamp
@@ -207,12 +340,45 @@ impl SealManager {
         })
     }
 
+    /// Creates a sealer function that would seal the batch because of the timeout, which is scaled
+    /// by the projected L1 commit cost of the batch.
+    fn l1_commit_cost_batch_sealer<G>(
+        deadline: L1CommitCostDeadline,
+        l1_gas_price_provider: Arc<G>,
+    ) -> Box<SealerFn>
+    where
+        G: L1GasPriceProvider + 'static + Send + Sync,
+    {
+        const RULE_NAME: &str = L1_COMMIT_COST_TIMEOUT_RULE;
+
+        Box::new(move |manager| {
+            let gas_price = l1_gas_price_provider.estimate_effective_gas_price();
+            let commit_cost = L1CommitCostDeadline::projected_commit_cost(gas_price);
+            let deadline_ms = deadline.deadline_ms(commit_cost);
+            metrics::gauge!(
+                "server.state_keeper.l1_batch_commit_deadline_ms",
+                deadline_ms as f64
+            );
+
+            let should_seal_timeout = millis_since(manager.batch_timestamp()) > deadline_ms;
+            if should_seal_timeout {
+                metrics::increment_counter!("server.tx_aggregation.reason", "criterion" => RULE_NAME);
+                vlog::debug!(
+                    "Decided to seal L1 batch using rule `{RULE_NAME}`; batch timestamp: {}, \
+                     commit deadline: {deadline_ms}ms, projected commit cost: {commit_cost} wei",
+                    extractors::display_timestamp(manager.batch_timestamp())
+                );
+            }
+            should_seal_timeout
+        })
+    }
+
     /// Creates a sealer function that would seal the batch if the provided base system contract hashes are different
     /// from ones in the updates manager.
     pub(super) fn code_hash_batch_sealer(
         base_system_contracts_hashes: BaseSystemContractsHashes,
     ) -> Box<SealerFn> {
-        const RULE_NAME: &str = "different_code_hashes";
+        const RULE_NAME: &str = DIFFERENT_CODE_HASHES_RULE;
 
         Box::new(move |manager| {
             // Verify code hashes
@@ -232,15 +398,98 @@ impl SealManager {
         })
     }
 
+    /// Creates a sealer function that would seal the batch once it contains a priority operation
+    /// calling one of the `contracts`.
+    fn priority_op_batch_sealer(contracts: HashSet<Address>) -> Box<SealerFn> {
+        const RULE_NAME: &str = PRIORITY_OP_RULE;
+
+        Box::new(move |manager| {
+            let all_transactions = manager
+                .l1_batch
+                .executed_transactions
+                .iter()
+                .chain(&manager.miniblock.executed_transactions);
+            let priority_op = all_transactions
+                .map(|tx| &tx.transaction)
+                .find(|tx| tx.is_l1() && contracts.contains(&tx.execute.contract_address));
+
+            if let Some(priority_op) = priority_op {
+                metrics::increment_counter!("server.tx_aggregation.reason", "criterion" => RULE_NAME);
+                vlog::info!(
+                    "Decided to seal L1 batch using rule `{RULE_NAME}`; batch contains priority operation {:?} \
+                     calling {:?}",
+                    priority_op.hash(),
+                    priority_op.execute.contract_address
+                );
+            }
+            priority_op.is_some()
+        })
+    }
+
     /// Creates a sealer function that would seal the miniblock because of the timeout.
     /// Will only trigger for the non-empty miniblocks.
     fn timeout_miniblock_sealer(miniblock_commit_deadline_ms: u64) -> Box<SealerFn> {
+        const RULE_NAME: &str = MINIBLOCK_TIMEOUT_RULE;
+
+        Box::new(move |manager| {
+            let should_seal_timeout = !manager.miniblock.executed_transactions.is_empty()
+                && millis_since(manager.miniblock.timestamp) > miniblock_commit_deadline_ms;
+            if should_seal_timeout {
+                metrics::increment_counter!(
+                    "server.state_keeper.miniblock_seal_reason",
+                    "criterion" => RULE_NAME
+                );
+            }
+            should_seal_timeout
+        })
+    }
+
+    /// Creates a sealer function that would seal the miniblock once it contains `max_l2_tx_count` L2 transactions.
+    fn l2_tx_count_miniblock_sealer(max_l2_tx_count: usize) -> Box<SealerFn> {
+        const RULE_NAME: &str = MINIBLOCK_L2_TX_COUNT_RULE;
+
         Box::new(move |manager| {
-            !manager.miniblock.executed_transactions.is_empty()
-                && millis_since(manager.miniblock.timestamp) > miniblock_commit_deadline_ms
+            let l2_tx_count = manager.miniblock.l2_tx_count;
+            let should_seal = l2_tx_count >= max_l2_tx_count;
+            if should_seal {
+                metrics::increment_counter!(
+                    "server.state_keeper.miniblock_seal_reason",
+                    "criterion" => RULE_NAME
+                );
+                vlog::debug!(
+                    "Decided to seal miniblock using rule `{RULE_NAME}`; miniblock contains \
+                     {l2_tx_count} L2 transactions, max allowed: {max_l2_tx_count}"
+                );
+            }
+            should_seal
+        })
+    }
+
+    /// Creates a sealer function that would seal the miniblock once the gas used by its transactions
+    /// reaches `gas_limit`. Since the sealer is invoked after a transaction is executed, the last transaction
+    /// in the miniblock may exceed the limit.
+    fn gas_miniblock_sealer(gas_limit: u64) -> Box<SealerFn> {
+        const RULE_NAME: &str = MINIBLOCK_GAS_RULE;
+
+        Box::new(move |manager| {
+            let gas_used = manager.miniblock.gas_used;
+            let should_seal = gas_used >= gas_limit;
+            if should_seal {
+                metrics::increment_counter!(
+                    "server.state_keeper.miniblock_seal_reason",
+                    "criterion" => RULE_NAME
+                );
+                vlog::debug!(
+                    "Decided to seal miniblock using rule `{RULE_NAME}`; miniblock used \
+                     {gas_used} gas, limit: {gas_limit}"
+                );
+            }
+            should_seal
         })
     }
 
+    /// Returns the seal resolution together with the name of the criterion that determined it
+    /// (`None` if the resolution is [`SealResolution::NoSeal`]).
     pub(super) fn should_seal_l1_batch(
         &self,
         l1_batch_number: u32,
@@ -248,7 +497,7 @@ impl SealManager {
         tx_count: usize,
         block_data: &SealData,
         tx_data: &SealData,
-    ) -> SealResolution {
+    ) -> (SealResolution, Option<&'static str>) {
         if let Some(sealer) = &self.conditional_sealer {
             sealer.should_seal_l1_batch(
                 l1_batch_number,
@@ -258,36 +507,51 @@ impl SealManager {
                 tx_data,
             )
         } else {
-            SealResolution::NoSeal
+            (SealResolution::NoSeal, None)
         }
     }
 
+    /// Returns the name of the first unconditional sealer deciding that the batch should be sealed,
+    /// or `None` if the batch should not be sealed.
     pub(super) fn should_seal_l1_batch_unconditionally(
         &self,
         updates_manager: &UpdatesManager,
-    ) -> bool {
+    ) -> Option<&'static str> {
         // Regardless of which sealers are provided, we never want to seal an empty batch.
-        updates_manager.pending_executed_transactions_len() != 0
-            && self
-                .unconditional_sealers
-                .iter()
-                .any(|sealer| (sealer)(updates_manager))
+        if updates_manager.pending_executed_transactions_len() == 0 {
+            return None;
+        }
+        Self::find_sealing_rule(&self.unconditional_sealers, updates_manager)
     }
 
-    pub(super) fn should_seal_miniblock(&self, updates_manager: &UpdatesManager) -> bool {
+    /// Returns the name of the first sealer deciding that the miniblock should be sealed,
+    /// or `None` if the miniblock should not be sealed.
+    pub(super) fn should_seal_miniblock(
+        &self,
+        updates_manager: &UpdatesManager,
+    ) -> Option<&'static str> {
         // Unlike with the L1 batch, we don't check the number of transactions in the miniblock,
         // because we might want to seal the miniblock even if it's empty (e.g. on an external node,
         // where we have to replicate the state of the main node, including the last (empty) miniblock of the batch).
         // The check for the number of transactions is expected to be done, if relevant, in the `miniblock_sealer`
         // directly.
-        self.miniblock_sealers
+        Self::find_sealing_rule(&self.miniblock_sealers, updates_manager)
+    }
+
+    fn find_sealing_rule(
+        sealers: &[NamedSealer],
+        updates_manager: &UpdatesManager,
+    ) -> Option<&'static str> {
+        sealers
             .iter()
-            .any(|sealer| (sealer)(updates_manager))
+            .find(|(_, sealer)| sealer(updates_manager))
+            .map(|(name, _)| *name)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use zksync_types::{l1::L1Tx, Execute};
     use zksync_utils::time::seconds_since_epoch;
 
     use super::*;
@@ -335,4 +599,90 @@ mod tests {
             "Non-empty miniblock with too recent timestamp shouldn't be sealed"
         );
     }
+
+    #[test]
+    fn l1_commit_cost_deadline() {
+        let deadline = L1CommitCostDeadline {
+            base_deadline_ms: 2_000,
+            min_deadline_ms: 1_000,
+            max_deadline_ms: 8_000,
+            reference_cost: 1_000_000,
+        };
+        assert_eq!(deadline.deadline_ms(1_000_000), 2_000);
+        assert_eq!(deadline.deadline_ms(1_500_000), 3_000);
+        // The deadline is clamped to the configured bounds.
+        assert_eq!(deadline.deadline_ms(100_000), 1_000);
+        assert_eq!(deadline.deadline_ms(100_000_000), 8_000);
+        assert_eq!(deadline.deadline_ms(u64::MAX), 8_000);
+    }
+
+    #[test]
+    fn l2_tx_count_miniblock_sealer() {
+        let l2_tx_count_miniblock_sealer = SealManager::l2_tx_count_miniblock_sealer(2);
+
+        let mut manager = create_updates_manager();
+        apply_tx_to_manager(&mut manager);
+        assert!(!l2_tx_count_miniblock_sealer(&manager));
+        apply_tx_to_manager(&mut manager);
+        assert!(l2_tx_count_miniblock_sealer(&manager));
+    }
+
+    #[test]
+    fn gas_miniblock_sealer() {
+        // Each test transaction uses 1,000 gas.
+        let gas_miniblock_sealer = SealManager::gas_miniblock_sealer(1_500);
+
+        let mut manager = create_updates_manager();
+        assert!(!gas_miniblock_sealer(&manager));
+        apply_tx_to_manager(&mut manager);
+        assert_eq!(manager.miniblock.gas_used, 1_000);
+        assert!(!gas_miniblock_sealer(&manager));
+        apply_tx_to_manager(&mut manager);
+        assert!(gas_miniblock_sealer(&manager));
+    }
+
+    #[test]
+    fn priority_op_batch_sealer() {
+        let upgrade_contract = Address::repeat_byte(0x80);
+        let priority_op_batch_sealer =
+            SealManager::priority_op_batch_sealer(HashSet::from([upgrade_contract]));
+
+        let mut manager = create_updates_manager();
+        apply_tx_to_manager(&mut manager);
+        assert!(!priority_op_batch_sealer(&manager));
+
+        // An L2 transaction calling the contract should not trigger.
+        let mut l2_tx = create_transaction(10, 100);
+        l2_tx.execute.contract_address = upgrade_contract;
+        manager.extend_from_executed_transaction(
+            l2_tx,
+            create_execution_result(0, []),
+            vec![],
+            BlockGasCount::default(),
+            ExecutionMetrics::default(),
+        );
+        assert!(!priority_op_batch_sealer(&manager));
+
+        let priority_op = L1Tx {
+            execute: Execute {
+                contract_address: upgrade_contract,
+                calldata: vec![],
+                value: 0.into(),
+                factory_deps: None,
+            },
+            common_data: Default::default(),
+            received_timestamp_ms: 0,
+        };
+        manager.extend_from_executed_transaction(
+            priority_op.into(),
+            create_execution_result(0, []),
+            vec![],
+            BlockGasCount::default(),
+            ExecutionMetrics::default(),
+        );
+        assert!(priority_op_batch_sealer(&manager));
+        // The sealer should still trigger after the miniblock with the operation is sealed.
+        manager.push_miniblock(1);
+        assert!(priority_op_batch_sealer(&manager));
+    }
 }
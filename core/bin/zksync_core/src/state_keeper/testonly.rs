@@ -0,0 +1,420 @@
+//! Test harness allowing to run the state keeper loop without Postgres or the VM.
+//!
+//! The harness consists of [`InMemoryIO`], which takes transactions from an in-memory queue
+//! and records sealed miniblocks / L1 batches, and [`MockBatchExecutorBuilder`], which returns
+//! scripted execution outcomes instead of running transactions in the VM. Both are available
+//! to external crates with the `testonly` feature enabled.
+
+use async_trait::async_trait;
+use tokio::sync::{mpsc, watch};
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use vm::{
+    vm::{VmPartialExecutionResult, VmTxExecutionResult},
+    vm_with_bootloader::{BlockContext, BlockContextMode, DerivedBlockContext},
+    TxRevertReason, VmBlockResult, VmExecutionResult,
+};
+use zksync_config::constants::ZKPORTER_IS_AVAILABLE;
+use zksync_contracts::BaseSystemContracts;
+use zksync_types::{
+    tx::{
+        tx_execution_info::{TxExecutionStatus, VmExecutionLogs},
+        ExecutionMetrics, TxRejection,
+    },
+    vm_trace::{VmExecutionTrace, VmTrace},
+    zk_evm::block_properties::BlockProperties,
+    Address, L1BatchNumber, MiniblockNumber, Transaction, H256,
+};
+use zksync_utils::h256_to_u256;
+
+use crate::state_keeper::{
+    batch_executor::{BatchExecutorHandle, Command, L1BatchExecutorBuilder, TxExecutionResult},
+    io::{L1BatchParams, PendingBatchData, StateKeeperIO},
+    types::ExecutionMetricsForCriteria,
+    updates::UpdatesManager,
+};
+
+const FEE_ACCOUNT: Address = Address::repeat_byte(0x11);
+
+/// Returns a blank L1 batch execution result.
+pub fn default_vm_block_result() -> VmBlockResult {
+    VmBlockResult {
+        full_result: VmExecutionResult {
+            events: vec![],
+            storage_log_queries: vec![],
+            used_contract_hashes: vec![],
+            l2_to_l1_logs: vec![],
+            return_data: vec![],
+            gas_used: 0,
+            contracts_used: 0,
+            revert_reason: None,
+            trace: VmTrace::ExecutionTrace(VmExecutionTrace::default()),
+            total_log_queries: 0,
+            cycles_used: 0,
+            computational_gas_used: 0,
+        },
+        block_tip_result: partial_execution_result(),
+    }
+}
+
+fn partial_execution_result() -> VmPartialExecutionResult {
+    VmPartialExecutionResult {
+        logs: VmExecutionLogs::default(),
+        revert_reason: None,
+        contracts_used: 0,
+        cycles_used: 0,
+        computational_gas_used: 0,
+        refunded_storage_writes: 0,
+        circuit_statistic: Default::default(),
+    }
+}
+
+/// Scripted outcome of a transaction execution returned by [`MockBatchExecutorBuilder`].
+#[derive(Debug, Clone)]
+pub enum MockTxOutcome {
+    /// Transaction was executed successfully and doesn't contribute to any seal criteria.
+    Success,
+    /// Transaction was executed successfully with the specified execution metrics.
+    SuccessWithMetrics(ExecutionMetrics),
+    /// Transaction was rejected by the VM.
+    Rejected,
+    /// Transaction was executed, but the bootloader ran out of gas for the block tip.
+    BootloaderOutOfGasForBlockTip,
+    /// Batch executor panics when executing the transaction.
+    Panic,
+}
+
+impl MockTxOutcome {
+    pub(crate) fn into_result(self) -> TxExecutionResult {
+        let execution_metrics = match self {
+            Self::Success => ExecutionMetrics::default(),
+            Self::SuccessWithMetrics(metrics) => metrics,
+            Self::Rejected => {
+                return TxExecutionResult::RejectedByVm {
+                    rejection_reason: TxRevertReason::InnerTxError,
+                };
+            }
+            Self::BootloaderOutOfGasForBlockTip => {
+                return TxExecutionResult::BootloaderOutOfGasForBlockTip;
+            }
+            Self::Panic => panic!("scripted batch executor panic"),
+        };
+
+        TxExecutionResult::Success {
+            tx_result: Box::new(VmTxExecutionResult {
+                status: TxExecutionStatus::Success,
+                result: partial_execution_result(),
+                call_traces: vec![],
+                gas_refunded: 0,
+                operator_suggested_refund: 0,
+            }),
+            tx_metrics: ExecutionMetricsForCriteria {
+                l1_gas: Default::default(),
+                execution_metrics,
+            },
+            bootloader_dry_run_metrics: ExecutionMetricsForCriteria {
+                l1_gas: Default::default(),
+                execution_metrics: Default::default(),
+            },
+            bootloader_dry_run_result: Box::new(partial_execution_result()),
+            compressed_bytecodes: vec![],
+        }
+    }
+}
+
+type ScriptedOutcomes = HashMap<H256, VecDeque<MockTxOutcome>>;
+
+/// Batch executor builder returning scripted outcomes instead of executing transactions in the VM.
+/// Transactions without a scripted outcome are considered to be executed successfully.
+/// The builder can be cloned; all clones share the script, so outcomes may be added after
+/// the builder is passed to the state keeper.
+#[derive(Debug, Clone, Default)]
+pub struct MockBatchExecutorBuilder {
+    outcomes: Arc<Mutex<ScriptedOutcomes>>,
+}
+
+impl MockBatchExecutorBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scripts the outcome of the next execution of the specified transaction. If the transaction
+    /// is executed several times (e.g., after a rollback), outcomes are consumed in the order
+    /// they were added.
+    pub fn push_outcome(&self, tx_hash: H256, outcome: MockTxOutcome) {
+        let mut outcomes = self
+            .outcomes
+            .lock()
+            .expect("scripted outcomes are poisoned");
+        outcomes.entry(tx_hash).or_default().push_back(outcome);
+    }
+}
+
+#[async_trait]
+impl L1BatchExecutorBuilder for MockBatchExecutorBuilder {
+    async fn init_batch(&self, _l1_batch_params: L1BatchParams) -> BatchExecutorHandle {
+        let (commands_sender, commands_receiver) = mpsc::channel(1);
+        let outcomes = self.outcomes.clone();
+        let handle = tokio::task::spawn_blocking(move || {
+            run_mock_executor(commands_receiver, &outcomes);
+        });
+        BatchExecutorHandle::from_raw(handle, commands_sender)
+    }
+}
+
+fn run_mock_executor(mut commands: mpsc::Receiver<Command>, outcomes: &Mutex<ScriptedOutcomes>) {
+    while let Some(cmd) = commands.blocking_recv() {
+        match cmd {
+            Command::ExecuteTx(tx, resp) => {
+                let outcome = outcomes
+                    .lock()
+                    .expect("scripted outcomes are poisoned")
+                    .get_mut(&tx.hash())
+                    .and_then(VecDeque::pop_front)
+                    .unwrap_or(MockTxOutcome::Success);
+                // The outcomes lock must be released by this point, so that it's not poisoned by a scripted panic.
+                resp.send(outcome.into_result()).unwrap();
+            }
+            Command::RollbackLastTx(resp) => {
+                resp.send(()).unwrap();
+            }
+            Command::FinishBatch(resp) => {
+                resp.send(default_vm_block_result()).unwrap();
+                return;
+            }
+        }
+    }
+}
+
+/// Miniblock sealed by [`InMemoryIO`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SealedMiniblock {
+    pub number: MiniblockNumber,
+    pub l1_batch_number: L1BatchNumber,
+    pub timestamp: u64,
+    pub tx_hashes: Vec<H256>,
+}
+
+/// L1 batch sealed by [`InMemoryIO`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SealedL1Batch {
+    pub number: L1BatchNumber,
+    pub timestamp: u64,
+    pub tx_hashes: Vec<H256>,
+}
+
+#[derive(Debug, Default)]
+struct InMemoryIOState {
+    pending_txs: VecDeque<Transaction>,
+    sealed_miniblocks: Vec<SealedMiniblock>,
+    sealed_l1_batches: Vec<SealedL1Batch>,
+    rejected_txs: Vec<(H256, TxRejection)>,
+}
+
+/// Handle for [`InMemoryIO`] allowing to feed transactions to the state keeper
+/// and to inspect its output while it's running.
+#[derive(Debug, Clone)]
+pub struct InMemoryIOHandle(Arc<Mutex<InMemoryIOState>>);
+
+impl InMemoryIOHandle {
+    fn lock(&self) -> std::sync::MutexGuard<'_, InMemoryIOState> {
+        self.0.lock().expect("in-memory IO state is poisoned")
+    }
+
+    /// Adds a transaction to the end of the queue.
+    pub fn push_tx(&self, tx: Transaction) {
+        self.lock().pending_txs.push_back(tx);
+    }
+
+    /// Returns the number of transactions not yet taken by the state keeper.
+    pub fn pending_tx_count(&self) -> usize {
+        self.lock().pending_txs.len()
+    }
+
+    pub fn sealed_miniblocks(&self) -> Vec<SealedMiniblock> {
+        self.lock().sealed_miniblocks.clone()
+    }
+
+    pub fn sealed_l1_batches(&self) -> Vec<SealedL1Batch> {
+        self.lock().sealed_l1_batches.clone()
+    }
+
+    /// Returns hashes of rejected transactions together with the rejection reasons.
+    pub fn rejected_txs(&self) -> Vec<(H256, TxRejection)> {
+        self.lock().rejected_txs.clone()
+    }
+}
+
+/// [`StateKeeperIO`] implementation operating fully in memory. Unlike the production IO,
+/// it doesn't persist anything; sealed miniblocks and L1 batches are recorded and can be inspected
+/// via [`InMemoryIOHandle`]. Miniblock timestamps are strictly increasing and don't depend
+/// on the wall clock.
+#[derive(Debug)]
+pub struct InMemoryIO {
+    state: InMemoryIOHandle,
+    base_system_contracts: BaseSystemContracts,
+    pending_batch: Option<PendingBatchData>,
+    current_l1_batch_number: L1BatchNumber,
+    current_miniblock_number: MiniblockNumber,
+    timestamp: u64,
+    stop: Option<(watch::Sender<bool>, usize)>,
+}
+
+impl InMemoryIO {
+    pub fn new() -> (Self, InMemoryIOHandle) {
+        let state = InMemoryIOHandle(Arc::default());
+        let this = Self {
+            state: state.clone(),
+            base_system_contracts: BaseSystemContracts::load_from_disk(),
+            pending_batch: None,
+            // Batch #0 and miniblock #0 are reserved for genesis.
+            current_l1_batch_number: L1BatchNumber(1),
+            current_miniblock_number: MiniblockNumber(1),
+            timestamp: 1,
+            stop: None,
+        };
+        (this, state)
+    }
+
+    /// Sets the pending batch that will be re-executed by the state keeper on start.
+    /// Miniblock numbers in the batch should start from 1.
+    pub fn with_pending_batch(mut self, pending_batch: PendingBatchData) -> Self {
+        self.pending_batch = Some(pending_batch);
+        self
+    }
+
+    /// Sends a stop signal to the state keeper once the specified number of L1 batches is sealed.
+    pub fn stop_after_l1_batches(mut self, stop_sender: watch::Sender<bool>, count: usize) -> Self {
+        self.stop = Some((stop_sender, count));
+        self
+    }
+
+    /// Creates L1 batch params compatible with this IO. Can be used to construct a pending batch.
+    pub fn l1_batch_params(&self, timestamp: u64) -> L1BatchParams {
+        let context = BlockContext {
+            block_number: self.current_l1_batch_number.0,
+            block_timestamp: timestamp,
+            l1_gas_price: 1,
+            fair_l2_gas_price: 1,
+            operator_address: FEE_ACCOUNT,
+        };
+        let derived_context = DerivedBlockContext {
+            context,
+            base_fee: 1,
+        };
+        let properties = BlockProperties {
+            default_aa_code_hash: h256_to_u256(self.base_system_contracts.default_aa.hash),
+            zkporter_is_available: ZKPORTER_IS_AVAILABLE,
+        };
+
+        L1BatchParams {
+            // The previous batch hash isn't checked without the VM.
+            context_mode: BlockContextMode::NewBlock(derived_context, 0.into()),
+            properties,
+            base_system_contracts: self.base_system_contracts.clone(),
+            protocol_version: None,
+        }
+    }
+}
+
+#[async_trait]
+impl StateKeeperIO for InMemoryIO {
+    fn current_l1_batch_number(&self) -> L1BatchNumber {
+        self.current_l1_batch_number
+    }
+
+    fn current_miniblock_number(&self) -> MiniblockNumber {
+        self.current_miniblock_number
+    }
+
+    async fn load_pending_batch(&mut self) -> Option<PendingBatchData> {
+        let pending_batch = self.pending_batch.take()?;
+        // The state keeper doesn't seal miniblocks from the pending batch via IO.
+        self.current_miniblock_number += pending_batch.txs.len() as u32;
+        self.timestamp += pending_batch.txs.len() as u64;
+        Some(pending_batch)
+    }
+
+    async fn wait_for_new_batch_params(&mut self, _max_wait: Duration) -> Option<L1BatchParams> {
+        Some(self.l1_batch_params(self.timestamp))
+    }
+
+    async fn wait_for_new_miniblock_params(&mut self, _max_wait: Duration) -> Option<u64> {
+        Some(self.timestamp)
+    }
+
+    async fn wait_for_next_tx(&mut self, max_wait: Duration) -> Option<Transaction> {
+        let tx = self.state.lock().pending_txs.pop_front();
+        if tx.is_none() {
+            tokio::time::sleep(max_wait).await;
+        }
+        tx
+    }
+
+    async fn rollback(&mut self, tx: Transaction) {
+        self.state.lock().pending_txs.push_front(tx);
+    }
+
+    async fn reject(&mut self, tx: &Transaction, rejection: &TxRejection) {
+        let rejected = (tx.hash(), rejection.clone());
+        self.state.lock().rejected_txs.push(rejected);
+    }
+
+    async fn seal_miniblock(&mut self, updates_manager: &UpdatesManager) {
+        let tx_hashes = updates_manager
+            .miniblock
+            .executed_transactions
+            .iter()
+            .map(|tx| tx.hash)
+            .collect();
+        let miniblock = SealedMiniblock {
+            number: self.current_miniblock_number,
+            l1_batch_number: self.current_l1_batch_number,
+            timestamp: updates_manager.miniblock.timestamp,
+            tx_hashes,
+        };
+        self.state.lock().sealed_miniblocks.push(miniblock);
+
+        self.current_miniblock_number += 1;
+        self.timestamp += 1;
+    }
+
+    async fn seal_l1_batch(
+        &mut self,
+        _block_result: VmBlockResult,
+        updates_manager: UpdatesManager,
+        block_context: DerivedBlockContext,
+    ) {
+        let tx_hashes = updates_manager
+            .l1_batch
+            .executed_transactions
+            .iter()
+            .map(|tx| tx.hash)
+            .collect();
+        let l1_batch = SealedL1Batch {
+            number: self.current_l1_batch_number,
+            timestamp: block_context.context.block_timestamp,
+            tx_hashes,
+        };
+        let sealed_l1_batch_count = {
+            let mut state = self.state.lock();
+            state.sealed_l1_batches.push(l1_batch);
+            state.sealed_l1_batches.len()
+        };
+
+        self.current_miniblock_number += 1; // Due to fictive miniblock being sealed.
+        self.current_l1_batch_number += 1;
+        self.timestamp += 1;
+
+        if let Some((stop_sender, count)) = &self.stop {
+            if sealed_l1_batch_count >= *count {
+                stop_sender.send_replace(true);
+            }
+        }
+    }
+}
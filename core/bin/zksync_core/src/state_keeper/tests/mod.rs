@@ -1,17 +1,18 @@
 use once_cell::sync::Lazy;
+use tokio::sync::watch;
 
 use std::{
+    path::Path,
     sync::{
         atomic::{AtomicBool, AtomicU64, Ordering},
         Arc,
     },
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use vm::{
     vm::{VmPartialExecutionResult, VmTxExecutionResult},
     vm_with_bootloader::{BlockContext, BlockContextMode, DerivedBlockContext},
-    VmBlockResult, VmExecutionResult,
 };
 use zksync_config::{configs::chain::StateKeeperConfig, constants::ZKPORTER_IS_AVAILABLE};
 use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes};
@@ -21,12 +22,14 @@ use zksync_types::{
     fee::Fee,
     l2::L2Tx,
     transaction_request::PaymasterParams,
-    tx::tx_execution_info::{TxExecutionStatus, VmExecutionLogs},
-    vm_trace::{VmExecutionTrace, VmTrace},
+    tx::{
+        tx_execution_info::{PersistedTxExecution, TxExecutionStatus, VmExecutionLogs},
+        TxRejectionKind,
+    },
     zk_evm::aux_structures::{LogQuery, Timestamp},
     zk_evm::block_properties::BlockProperties,
-    Address, L2ChainId, MiniblockNumber, Nonce, StorageLogQuery, StorageLogQueryType, Transaction,
-    H256, U256,
+    Address, L1BatchNumber, L2ChainId, MiniblockNumber, Nonce, StorageLogQuery,
+    StorageLogQueryType, Transaction, H256, U256,
 };
 use zksync_utils::h256_to_u256;
 
@@ -38,13 +41,16 @@ use crate::gas_tracker::constants::{
     BLOCK_COMMIT_BASE_COST, BLOCK_EXECUTE_BASE_COST, BLOCK_PROVE_BASE_COST,
 };
 use crate::state_keeper::{
+    io::{read_replay_log, RecordingIO, ReplayEvent, ReplayIO, ReplaySegment},
     keeper::POLL_WAIT_DURATION,
     seal_criteria::{
         criteria::{GasCriterion, SlotsCriterion},
         ConditionalSealer, SealManager,
     },
+    testonly::{InMemoryIO, MockBatchExecutorBuilder, MockTxOutcome},
     types::ExecutionMetricsForCriteria,
     updates::UpdatesManager,
+    ZkSyncStateKeeper,
 };
 
 mod tester;
@@ -66,6 +72,7 @@ pub(super) fn create_block_metadata(number: u32) -> BlockMetadata {
         merkle_root_hash: H256::from_low_u64_be(number.into()),
         initial_writes_compressed: vec![],
         repeated_writes_compressed: vec![],
+        state_diffs_compressed: None,
         commitment: H256::from_low_u64_be(number.into()),
         l2_l1_messages_compressed: vec![],
         l2_l1_merkle_root: H256::from_low_u64_be(number.into()),
@@ -80,32 +87,6 @@ pub(super) fn create_block_metadata(number: u32) -> BlockMetadata {
     }
 }
 
-pub(super) fn default_vm_block_result() -> VmBlockResult {
-    VmBlockResult {
-        full_result: VmExecutionResult {
-            events: vec![],
-            storage_log_queries: vec![],
-            used_contract_hashes: vec![],
-            l2_to_l1_logs: vec![],
-            return_data: vec![],
-            gas_used: 0,
-            contracts_used: 0,
-            revert_reason: None,
-            trace: VmTrace::ExecutionTrace(VmExecutionTrace::default()),
-            total_log_queries: 0,
-            cycles_used: 0,
-            computational_gas_used: 0,
-        },
-        block_tip_result: VmPartialExecutionResult {
-            logs: VmExecutionLogs::default(),
-            revert_reason: None,
-            contracts_used: 0,
-            cycles_used: 0,
-            computational_gas_used: 0,
-        },
-    }
-}
-
 pub(super) fn default_block_context() -> DerivedBlockContext {
     DerivedBlockContext {
         context: BlockContext {
@@ -121,7 +102,7 @@ pub(super) fn default_block_context() -> DerivedBlockContext {
 
 pub(super) fn create_updates_manager() -> UpdatesManager {
     let block_context = BlockContextMode::NewBlock(default_block_context(), 0.into());
-    UpdatesManager::new(&block_context, BaseSystemContractsHashes::default())
+    UpdatesManager::new(&block_context, BaseSystemContractsHashes::default(), None)
 }
 
 pub(super) fn create_l2_transaction(fee_per_gas: u64, gas_per_pubdata: u32) -> L2Tx {
@@ -177,6 +158,8 @@ pub(super) fn create_execution_result(
             contracts_used: 0,
             cycles_used: 0,
             computational_gas_used: 0,
+            refunded_storage_writes: 0,
+            circuit_statistic: Default::default(),
         },
         call_traces: vec![],
         gas_refunded: 0,
@@ -292,10 +275,12 @@ async fn sealed_by_gas() {
                 l1_gas_per_tx,
                 "L1 gas used by a miniblock should consist of the gas used by its txs"
             );
+            assert_eq!(updates.miniblock.seal_reason, Some("custom"));
         })
         .next_tx("Second tx", random_tx(1), execution_result)
         .miniblock_sealed("Miniblock 2")
         .batch_sealed_with("Batch sealed with both txs", |_, updates, _| {
+            assert_eq!(updates.l1_batch.seal_reason, Some("gas"));
             assert_eq!(
                 updates.l1_batch.l1_gas_count,
                 BlockGasCount {
@@ -580,6 +565,28 @@ async fn pending_batch_is_applied() {
         .await;
 }
 
+#[tokio::test]
+#[should_panic(expected = "State keeper thread panicked")]
+async fn pending_batch_reexecution_mismatch_is_detected() {
+    let sealer = SealManager::custom(None, vec![], vec![Box::new(|_| false)]);
+
+    let tx = random_tx(1);
+    let mut pending_batch = pending_batch_data(vec![(MiniblockNumber(1), vec![tx.clone()])]);
+    // The transaction is re-executed successfully, but it has failed during the original execution.
+    pending_batch.expected_executions = vec![PersistedTxExecution {
+        hash: tx.hash(),
+        status: TxExecutionStatus::Failure,
+        refunded_gas: 0,
+        metrics: None,
+    }];
+
+    TestScenario::new()
+        .load_pending_batch(pending_batch)
+        .batch_sealed("Batch should not be sealed")
+        .run(sealer)
+        .await;
+}
+
 /// Unconditionally seal the batch without triggering specific criteria.
 #[tokio::test]
 async fn unconditional_sealing() {
@@ -733,3 +740,148 @@ async fn time_is_monotonic() {
         .run(sealer)
         .await;
 }
+
+/// Seals L1 batches with 2 transactions and miniblocks with 1 transaction.
+fn tx_count_sealer() -> SealManager {
+    SealManager::custom(
+        None,
+        vec![Box::new(|updates| {
+            updates.pending_executed_transactions_len() == 2
+        })],
+        vec![Box::new(|updates| {
+            updates.miniblock.executed_transactions.len() == 1
+        })],
+    )
+}
+
+#[tokio::test]
+async fn in_memory_harness_basics() {
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let (io, io_handle) = InMemoryIO::new();
+    let io = io.stop_after_l1_batches(stop_sender, 2);
+    let batch_executor_base = MockBatchExecutorBuilder::new();
+
+    let txs: Vec<_> = (1..=5).map(random_tx).collect();
+    batch_executor_base.push_outcome(txs[2].hash(), MockTxOutcome::Rejected);
+    for tx in &txs {
+        io_handle.push_tx(tx.clone());
+    }
+
+    let state_keeper = ZkSyncStateKeeper::new(
+        stop_receiver,
+        Box::new(io),
+        Box::new(batch_executor_base),
+        tx_count_sealer(),
+    );
+    tokio::time::timeout(Duration::from_secs(60), state_keeper.run())
+        .await
+        .expect("state keeper got stuck");
+
+    let rejected_txs = io_handle.rejected_txs();
+    assert_eq!(rejected_txs.len(), 1);
+    assert_eq!(rejected_txs[0].0, txs[2].hash());
+    assert_eq!(rejected_txs[0].1.kind, TxRejectionKind::RejectedByVm);
+
+    let l1_batches = io_handle.sealed_l1_batches();
+    let l1_batch_txs: Vec<_> = l1_batches.iter().map(|batch| &batch.tx_hashes).collect();
+    let expected_txs = [
+        vec![txs[0].hash(), txs[1].hash()],
+        vec![txs[3].hash(), txs[4].hash()],
+    ];
+    assert_eq!(l1_batch_txs, expected_txs.iter().collect::<Vec<_>>());
+    assert_eq!(l1_batches[0].number, L1BatchNumber(1));
+    assert_eq!(l1_batches[1].number, L1BatchNumber(2));
+
+    let miniblocks = io_handle.sealed_miniblocks();
+    // Fictive miniblocks are not reported to the IO, so miniblock #3 is missing.
+    let miniblock_numbers: Vec<_> = miniblocks.iter().map(|block| block.number.0).collect();
+    assert_eq!(miniblock_numbers, [1, 2, 4, 5]);
+    assert!(miniblocks
+        .windows(2)
+        .all(|blocks| blocks[0].timestamp < blocks[1].timestamp));
+}
+
+/// Records a replay log of a run with 2 L1 batches, with the 3rd transaction rejected.
+async fn record_replay_log(path: &Path) -> Vec<Transaction> {
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let (io, io_handle) = InMemoryIO::new();
+    let io = io.stop_after_l1_batches(stop_sender, 2);
+    let io = RecordingIO::new(io, path).unwrap();
+    let batch_executor_base = MockBatchExecutorBuilder::new();
+
+    let txs: Vec<_> = (1..=5).map(random_tx).collect();
+    batch_executor_base.push_outcome(txs[2].hash(), MockTxOutcome::Rejected);
+    for tx in &txs {
+        io_handle.push_tx(tx.clone());
+    }
+
+    let state_keeper = ZkSyncStateKeeper::new(
+        stop_receiver,
+        Box::new(io),
+        Box::new(batch_executor_base),
+        tx_count_sealer(),
+    );
+    tokio::time::timeout(Duration::from_secs(60), state_keeper.run())
+        .await
+        .expect("state keeper got stuck");
+    txs
+}
+
+async fn replay(segment: ReplaySegment, batch_executor_base: MockBatchExecutorBuilder) {
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let io = ReplayIO::new(segment, BASE_SYSTEM_CONTRACTS.clone(), stop_sender);
+    let state_keeper = ZkSyncStateKeeper::new(
+        stop_receiver,
+        Box::new(io),
+        Box::new(batch_executor_base),
+        tx_count_sealer(),
+    );
+    tokio::time::timeout(Duration::from_secs(10), state_keeper.run())
+        .await
+        .expect("replay got stuck");
+}
+
+#[tokio::test]
+async fn replay_log_reproduces_run() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let log_path = temp_dir.path().join("replay.ndjson");
+    let txs = record_replay_log(&log_path).await;
+
+    let mut segments = read_replay_log(&log_path).unwrap();
+    assert_eq!(segments.len(), 1);
+    let segment = segments.pop().unwrap();
+    assert_eq!(segment.l1_batch_number, L1BatchNumber(1));
+    assert_eq!(segment.miniblock_number, MiniblockNumber(1));
+    let recorded_tx_hashes: Vec<_> = segment
+        .events
+        .iter()
+        .filter_map(|event| match event {
+            ReplayEvent::Transaction(tx) => Some(tx.hash()),
+            _ => None,
+        })
+        .collect();
+    let tx_hashes: Vec<_> = txs.iter().map(Transaction::hash).collect();
+    assert_eq!(recorded_tx_hashes, tx_hashes);
+    let sealed_l1_batches = segment
+        .events
+        .iter()
+        .filter(|event| matches!(event, ReplayEvent::L1BatchSealed { .. }))
+        .count();
+    assert_eq!(sealed_l1_batches, 2);
+
+    let batch_executor_base = MockBatchExecutorBuilder::new();
+    batch_executor_base.push_outcome(txs[2].hash(), MockTxOutcome::Rejected);
+    replay(segment, batch_executor_base).await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "Replay diverged")]
+async fn replay_divergence_is_detected() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let log_path = temp_dir.path().join("replay.ndjson");
+    record_replay_log(&log_path).await;
+
+    let segment = read_replay_log(&log_path).unwrap().pop().unwrap();
+    // The rejected transaction is executed successfully during the replay.
+    replay(segment, MockBatchExecutorBuilder::new()).await;
+}
@@ -13,18 +13,16 @@ use vm::{
     VmBlockResult,
 };
 use zksync_types::{
-    tx::tx_execution_info::TxExecutionStatus, Address, L1BatchNumber, MiniblockNumber, Transaction,
-    H256, U256,
+    tx::{tx_execution_info::TxExecutionStatus, TxRejection},
+    Address, L1BatchNumber, MiniblockNumber, Transaction, H256, U256,
 };
 
 use crate::state_keeper::{
     batch_executor::{BatchExecutorHandle, Command, L1BatchExecutorBuilder, TxExecutionResult},
     io::{L1BatchParams, PendingBatchData, StateKeeperIO},
     seal_criteria::SealManager,
-    tests::{
-        create_l2_transaction, default_block_properties, default_vm_block_result,
-        BASE_SYSTEM_CONTRACTS,
-    },
+    testonly::{default_vm_block_result, MockTxOutcome},
+    tests::{create_l2_transaction, default_block_properties, BASE_SYSTEM_CONTRACTS},
     types::ExecutionMetricsForCriteria,
     updates::UpdatesManager,
     ZkSyncStateKeeper,
@@ -204,30 +202,14 @@ fn partial_execution_result() -> VmPartialExecutionResult {
         contracts_used: Default::default(),
         cycles_used: Default::default(),
         computational_gas_used: Default::default(),
+        refunded_storage_writes: Default::default(),
+        circuit_statistic: Default::default(),
     }
 }
 
 /// Creates a `TxExecutionResult` object denoting a successful tx execution.
 pub(crate) fn successful_exec() -> TxExecutionResult {
-    TxExecutionResult::Success {
-        tx_result: Box::new(VmTxExecutionResult {
-            status: TxExecutionStatus::Success,
-            result: partial_execution_result(),
-            call_traces: vec![],
-            gas_refunded: 0,
-            operator_suggested_refund: 0,
-        }),
-        tx_metrics: ExecutionMetricsForCriteria {
-            l1_gas: Default::default(),
-            execution_metrics: Default::default(),
-        },
-        bootloader_dry_run_metrics: ExecutionMetricsForCriteria {
-            l1_gas: Default::default(),
-            execution_metrics: Default::default(),
-        },
-        bootloader_dry_run_result: Box::new(partial_execution_result()),
-        compressed_bytecodes: vec![],
-    }
+    MockTxOutcome::Success.into_result()
 }
 
 /// Creates a `TxExecutionResult` object denoting a successful tx execution with the given execution metrics.
@@ -254,15 +236,13 @@ pub(crate) fn successful_exec_with_metrics(
 
 /// Creates a `TxExecutionResult` object denoting a tx that was rejected.
 pub(crate) fn rejected_exec() -> TxExecutionResult {
-    TxExecutionResult::RejectedByVm {
-        rejection_reason: vm::TxRevertReason::InnerTxError,
-    }
+    MockTxOutcome::Rejected.into_result()
 }
 
 /// Creates a `TxExecutionResult` object denoting a transaction that was executed, but caused a bootloader tip out of
 /// gas error.
 pub(crate) fn bootloader_tip_out_of_gas() -> TxExecutionResult {
-    TxExecutionResult::BootloaderOutOfGasForBlockTip
+    MockTxOutcome::BootloaderOutOfGasForBlockTip.into_result()
 }
 
 /// Creates a mock `PendingBatchData` object containing the provided sequence of miniblocks.
@@ -287,9 +267,14 @@ pub(crate) fn pending_batch_data(
         context_mode: BlockContextMode::NewBlock(derived_context, Default::default()),
         properties: block_properties,
         base_system_contracts: BASE_SYSTEM_CONTRACTS.clone(),
+        protocol_version: None,
     };
 
-    PendingBatchData { params, txs }
+    PendingBatchData {
+        params,
+        txs,
+        expected_executions: vec![],
+    }
 }
 
 #[allow(clippy::type_complexity, clippy::large_enum_variant)] // It's OK for tests.
@@ -574,6 +559,7 @@ impl StateKeeperIO for TestIO {
             context_mode: BlockContextMode::NewBlock(derived_context, previous_block_hash),
             properties: block_properties,
             base_system_contracts: BASE_SYSTEM_CONTRACTS.clone(),
+            protocol_version: None,
         })
     }
 
@@ -612,7 +598,7 @@ impl StateKeeperIO for TestIO {
         self.skipping_txs = false;
     }
 
-    async fn reject(&mut self, tx: &Transaction, error: &str) {
+    async fn reject(&mut self, tx: &Transaction, rejection: &TxRejection) {
         let action = self.pop_next_item("reject");
         let ScenarioItem::Reject(_, expected_tx, expected_err) = action else {
             panic!("Unexpected action: {:?}", action);
@@ -620,10 +606,10 @@ impl StateKeeperIO for TestIO {
         assert_eq!(tx, &expected_tx, "Incorrect transaction has been rejected");
         if let Some(expected_err) = expected_err {
             assert!(
-                error.contains(&expected_err),
+                rejection.message.contains(&expected_err),
                 "Transaction was rejected with an unexpected error. Expected part was {}, but the actual error was {}",
                 expected_err,
-                error
+                rejection
             );
         }
         self.skipping_txs = false;
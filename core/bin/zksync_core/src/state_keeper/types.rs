@@ -1,56 +1,191 @@
+use tokio::sync::Notify;
+
 use std::{
     collections::HashMap,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
 };
 
-use zksync_mempool::{L2TxFilter, MempoolInfo, MempoolStore};
+use zksync_mempool::{
+    FilteredTxStats, L2TxFilter, MempoolInfo, MempoolSnapshot, MempoolStats, MempoolStore,
+};
 use zksync_types::{
     block::BlockGasCount, tx::ExecutionMetrics, Address, Nonce, PriorityOpId, Transaction,
 };
 
+/// Error returned by [`MempoolGuard::insert_many()`] if the mempool cannot fit the entire batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("mempool can only accept {remaining_capacity} more transactions")]
+pub struct MempoolCapacityExceeded {
+    pub remaining_capacity: u64,
+}
+
 #[derive(Debug, Clone)]
-pub struct MempoolGuard(Arc<Mutex<MempoolStore>>);
+pub struct MempoolGuard {
+    store: Arc<Mutex<MempoolStore>>,
+    /// Notified each time transactions are added to the mempool.
+    new_txs_notifier: Arc<Notify>,
+    /// Set if the mempool should be re-populated from Postgres at the next L1 batch boundary.
+    resync_requested: Arc<AtomicBool>,
+    /// Set when the mempool is cleared, so that the mempool fetcher can reload all transactions.
+    cleared: Arc<AtomicBool>,
+}
 
 impl MempoolGuard {
-    pub fn new(next_priority_id: PriorityOpId, capacity: u64) -> Self {
-        let store = MempoolStore::new(next_priority_id, capacity);
-        Self(Arc::new(Mutex::new(store)))
+    pub fn new(
+        next_priority_id: PriorityOpId,
+        capacity: u64,
+        max_nonce_lookahead: Option<u32>,
+    ) -> Self {
+        let mut store = MempoolStore::new(next_priority_id, capacity);
+        if let Some(max_nonce_lookahead) = max_nonce_lookahead {
+            store = store.with_max_nonce_lookahead(max_nonce_lookahead);
+        }
+        Self {
+            store: Arc::new(Mutex::new(store)),
+            new_txs_notifier: Arc::new(Notify::new()),
+            resync_requested: Arc::new(AtomicBool::new(false)),
+            cleared: Arc::new(AtomicBool::new(false)),
+        }
     }
 
+    #[tracing::instrument(name = "mempool_insert", skip_all, fields(tx_count = transactions.len()))]
     pub fn insert(&mut self, transactions: Vec<Transaction>, nonces: HashMap<Address, Nonce>) {
-        self.0
+        self.store
             .lock()
             .expect("failed to acquire mempool lock")
             .insert(transactions, nonces);
+        self.new_txs_notifier.notify_one();
+    }
+
+    /// Inserts a batch of transactions only if the mempool has capacity for all of them, so that
+    /// the batch is either inserted completely or not at all. Unlike [`Self::insert()`], this method
+    /// never triggers eviction of other transactions.
+    #[tracing::instrument(name = "mempool_insert_many", skip_all, fields(tx_count = transactions.len()))]
+    pub fn insert_many(
+        &mut self,
+        transactions: Vec<Transaction>,
+        nonces: HashMap<Address, Nonce>,
+    ) -> Result<(), MempoolCapacityExceeded> {
+        let mut store = self.store.lock().expect("failed to acquire mempool lock");
+        let remaining_capacity = store.remaining_capacity();
+        if (transactions.len() as u64) > remaining_capacity {
+            return Err(MempoolCapacityExceeded { remaining_capacity });
+        }
+        store.insert(transactions, nonces);
+        drop(store);
+        self.new_txs_notifier.notify_one();
+        Ok(())
+    }
+
+    /// Waits until new transactions are added to the mempool. If transactions were added since
+    /// the previous call, returns immediately.
+    pub async fn wait_for_new_transactions(&self) {
+        self.new_txs_notifier.notified().await;
     }
 
     pub fn has_next(&self, filter: &L2TxFilter) -> bool {
-        self.0
+        self.store
             .lock()
             .expect("failed to acquire mempool lock")
             .has_next(filter)
     }
 
-    pub fn next_transaction(&mut self, filter: &L2TxFilter) -> Option<Transaction> {
-        self.0
+    /// Returns the next transaction for execution in a miniblock with the specified timestamp (in seconds).
+    pub fn next_transaction(
+        &mut self,
+        filter: &L2TxFilter,
+        miniblock_timestamp: u64,
+    ) -> Option<Transaction> {
+        self.store
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .next_transaction_at(filter, miniblock_timestamp)
+    }
+
+    pub fn has_next_l1_transaction(&self) -> bool {
+        self.store
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .has_next_l1_transaction()
+    }
+
+    pub fn next_l1_transaction(&mut self) -> Option<Transaction> {
+        self.store
             .lock()
             .expect("failed to acquire mempool lock")
-            .next_transaction(filter)
+            .next_l1_transaction()
     }
 
     pub fn rollback(&mut self, rejected: &Transaction) {
-        self.0
+        self.store
             .lock()
             .expect("failed to acquire mempool lock")
             .rollback(rejected);
     }
 
     pub fn get_mempool_info(&mut self) -> MempoolInfo {
-        self.0
+        self.store
             .lock()
             .expect("failed to acquire mempool lock")
             .get_mempool_info()
     }
+
+    pub fn remaining_capacity(&self) -> u64 {
+        self.store
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .remaining_capacity()
+    }
+
+    pub fn stats(&self) -> MempoolStats {
+        self.store
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .stats()
+    }
+
+    pub fn filtered_tx_stats(&self) -> FilteredTxStats {
+        self.store
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .filtered_tx_stats()
+    }
+
+    /// Copies L2 transactions currently in the mempool, e.g. to hand them over to a standby sequencer.
+    pub fn snapshot(&self) -> MempoolSnapshot {
+        self.store
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .snapshot()
+    }
+
+    /// Removes all transactions from the mempool.
+    pub fn clear(&mut self) {
+        self.store
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .clear();
+        self.cleared.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether the mempool was cleared since the previous call.
+    pub(crate) fn take_cleared_flag(&self) -> bool {
+        self.cleared.swap(false, Ordering::Relaxed)
+    }
+
+    /// Requests the mempool to be re-populated from Postgres. The request is served by the state keeper
+    /// once the current L1 batch is sealed.
+    pub fn request_resync(&self) {
+        self.resync_requested.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether a resync was requested, resetting the request.
+    pub(crate) fn take_resync_request(&self) -> bool {
+        self.resync_requested.swap(false, Ordering::Relaxed)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
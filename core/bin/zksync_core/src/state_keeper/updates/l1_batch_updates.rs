@@ -13,6 +13,10 @@ pub struct L1BatchUpdates {
     // how much L1 gas will it take to submit this block?
     pub l1_gas_count: BlockGasCount,
     pub txs_encoding_size: usize,
+    /// Number of executed L2 transactions.
+    pub l2_tx_count: usize,
+    /// Name of the rule or criterion that decided to seal the batch, if any.
+    pub seal_reason: Option<&'static str>,
 }
 
 impl L1BatchUpdates {
@@ -23,6 +27,8 @@ impl L1BatchUpdates {
             block_execution_metrics: Default::default(),
             l1_gas_count: new_block_gas_count(),
             txs_encoding_size: 0,
+            l2_tx_count: 0,
+            seal_reason: None,
         }
     }
 
@@ -39,6 +45,7 @@ impl L1BatchUpdates {
         self.l1_gas_count += miniblock_updates.l1_gas_count;
         self.block_execution_metrics += miniblock_updates.block_execution_metrics;
         self.txs_encoding_size += miniblock_updates.txs_encoding_size;
+        self.l2_tx_count += miniblock_updates.l2_tx_count;
     }
 }
 
@@ -75,5 +82,6 @@ mod tests {
         assert_eq!(l1_batch_accumulator.priority_ops_onchain_data.len(), 0);
         assert_eq!(l1_batch_accumulator.block_execution_metrics.l2_l1_logs, 0);
         assert_eq!(l1_batch_accumulator.txs_encoding_size, expected_tx_size);
+        assert_eq!(l1_batch_accumulator.l2_tx_count, 1);
     }
 }
@@ -23,7 +23,13 @@ pub struct MiniblockUpdates {
     pub l1_gas_count: BlockGasCount,
     pub block_execution_metrics: ExecutionMetrics,
     pub txs_encoding_size: usize,
+    /// Number of executed L2 transactions.
+    pub l2_tx_count: usize,
+    /// Cumulative L2 gas used by the executed transactions (i.e., gas limits minus refunds).
+    pub gas_used: u64,
     pub timestamp: u64,
+    /// Name of the rule that decided to seal the miniblock, if any.
+    pub seal_reason: Option<&'static str>,
 }
 
 impl MiniblockUpdates {
@@ -37,7 +43,10 @@ impl MiniblockUpdates {
             l1_gas_count: BlockGasCount::default(),
             block_execution_metrics: ExecutionMetrics::default(),
             txs_encoding_size: 0,
+            l2_tx_count: 0,
+            gas_used: 0,
             timestamp,
+            seal_reason: None,
         }
     }
 
@@ -88,6 +97,13 @@ impl MiniblockUpdates {
         self.l1_gas_count += tx_l1_gas_this_tx;
         self.block_execution_metrics += execution_metrics;
         self.txs_encoding_size += extractors::encoded_transaction_size(tx.clone());
+        self.l2_tx_count += usize::from(!tx.is_l1());
+        let tx_gas_used = tx
+            .gas_limit()
+            .saturating_sub(tx_execution_result.gas_refunded.into());
+        self.gas_used = self
+            .gas_used
+            .saturating_add(tx_gas_used.min(u64::MAX.into()).as_u64());
 
         self.executed_transactions.push(TransactionExecutionResult {
             hash: tx.hash(),
@@ -102,6 +118,7 @@ impl MiniblockUpdates {
                 .result
                 .revert_reason
                 .map(|reason| reason.to_string()),
+            result_truncated: false,
         });
     }
 }
@@ -133,5 +150,7 @@ mod tests {
         assert_eq!(accumulator.new_factory_deps.len(), 0);
         assert_eq!(accumulator.block_execution_metrics.l2_l1_logs, 0);
         assert_eq!(accumulator.txs_encoding_size, expected_tx_size);
+        assert_eq!(accumulator.l2_tx_count, 1);
+        assert_eq!(accumulator.gas_used, 1_000);
     }
 }
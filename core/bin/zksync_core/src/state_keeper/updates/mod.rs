@@ -1,15 +1,19 @@
 use std::mem;
 
 use vm::{vm::VmTxExecutionResult, vm_with_bootloader::BlockContextMode};
+use zksync_config::configs::chain::StorageLogAnomalyPolicy;
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_types::{
     block::BlockGasCount,
+    event::EventIndexingPolicy,
     storage_writes_deduplicator::StorageWritesDeduplicator,
     tx::tx_execution_info::{ExecutionMetrics, VmExecutionLogs},
-    Address, L1BatchNumber, MiniblockNumber, Transaction,
+    Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, Transaction,
 };
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
+use crate::chain_contracts::ChainContracts;
+
 pub mod l1_batch_updates;
 pub mod miniblock_updates;
 
@@ -24,10 +28,12 @@ pub(crate) use self::{l1_batch_updates::L1BatchUpdates, miniblock_updates::Minib
 #[derive(Debug, Clone, PartialEq)]
 pub struct UpdatesManager {
     batch_timestamp: u64,
+    fee_account_address: Address,
     l1_gas_price: u64,
     fair_l2_gas_price: u64,
     base_fee_per_gas: u64,
     base_system_contract_hashes: BaseSystemContractsHashes,
+    protocol_version: Option<ProtocolVersionId>,
     pub l1_batch: L1BatchUpdates,
     pub miniblock: MiniblockUpdates,
     pub storage_writes_deduplicator: StorageWritesDeduplicator,
@@ -37,15 +43,18 @@ impl UpdatesManager {
     pub(crate) fn new(
         block_context: &BlockContextMode,
         base_system_contract_hashes: BaseSystemContractsHashes,
+        protocol_version: Option<ProtocolVersionId>,
     ) -> Self {
         let batch_timestamp = block_context.timestamp();
         let context = block_context.inner_block_context().context;
         Self {
             batch_timestamp,
+            fee_account_address: context.operator_address,
             l1_gas_price: context.l1_gas_price,
             fair_l2_gas_price: context.fair_l2_gas_price,
             base_fee_per_gas: block_context.inner_block_context().base_fee,
             base_system_contract_hashes,
+            protocol_version,
             l1_batch: L1BatchUpdates::new(),
             miniblock: MiniblockUpdates::new(batch_timestamp),
             storage_writes_deduplicator: StorageWritesDeduplicator::new(),
@@ -60,6 +69,10 @@ impl UpdatesManager {
         self.base_system_contract_hashes
     }
 
+    pub(crate) fn protocol_version(&self) -> Option<ProtocolVersionId> {
+        self.protocol_version
+    }
+
     pub(crate) fn l1_gas_price(&self) -> u64 {
         self.l1_gas_price
     }
@@ -68,22 +81,33 @@ impl UpdatesManager {
         self.fair_l2_gas_price
     }
 
+    pub(crate) fn base_fee_per_gas(&self) -> u64 {
+        self.base_fee_per_gas
+    }
+
     pub(crate) fn seal_miniblock_command(
         &self,
         l1_batch_number: L1BatchNumber,
         miniblock_number: MiniblockNumber,
-        l2_erc20_bridge_addr: Address,
+        chain_contracts: ChainContracts,
+        event_indexing: &EventIndexingPolicy,
+        storage_log_anomaly_policy: StorageLogAnomalyPolicy,
     ) -> MiniblockSealCommand {
         MiniblockSealCommand {
             l1_batch_number,
             miniblock_number,
             miniblock: self.miniblock.clone(),
             first_tx_index: self.l1_batch.executed_transactions.len(),
+            fee_account_address: self.fee_account_address,
             l1_gas_price: self.l1_gas_price,
             fair_l2_gas_price: self.fair_l2_gas_price,
             base_fee_per_gas: self.base_fee_per_gas,
             base_system_contracts_hashes: self.base_system_contract_hashes,
-            l2_erc20_bridge_addr,
+            protocol_version: self.protocol_version,
+            chain_contracts,
+            event_indexing: event_indexing.clone(),
+            storage_log_anomaly_policy,
+            gas_limit: None,
         }
     }
 
@@ -127,6 +151,10 @@ impl UpdatesManager {
         self.l1_batch.executed_transactions.len() + self.miniblock.executed_transactions.len()
     }
 
+    pub(crate) fn pending_l2_transactions_len(&self) -> usize {
+        self.l1_batch.l2_tx_count + self.miniblock.l2_tx_count
+    }
+
     pub(crate) fn pending_l1_gas_count(&self) -> BlockGasCount {
         self.l1_batch.l1_gas_count + self.miniblock.l1_gas_count
     }
@@ -147,11 +175,20 @@ pub(crate) struct MiniblockSealCommand {
     pub miniblock_number: MiniblockNumber,
     pub miniblock: MiniblockUpdates,
     pub first_tx_index: usize,
+    pub fee_account_address: Address,
     pub l1_gas_price: u64,
     pub fair_l2_gas_price: u64,
     pub base_fee_per_gas: u64,
     pub base_system_contracts_hashes: BaseSystemContractsHashes,
-    pub l2_erc20_bridge_addr: Address,
+    pub protocol_version: Option<ProtocolVersionId>,
+    /// Used to extract the information on newly added tokens.
+    pub chain_contracts: ChainContracts,
+    /// Determines which events are fully indexed in Postgres.
+    pub event_indexing: EventIndexingPolicy,
+    /// Handling of anomalies in the order of storage writes.
+    pub storage_log_anomaly_policy: StorageLogAnomalyPolicy,
+    /// Gas limit of the miniblock reported in the Web3 API. If not set, the default block gas limit is reported.
+    pub gas_limit: Option<u64>,
 }
 
 #[cfg(test)]
@@ -3,18 +3,22 @@ use std::{collections::HashMap, convert::TryFrom, iter::FromIterator, time::Dura
 use super::genesis::fetch_system_contract_by_hash;
 use actix_rt::time::Instant;
 use async_trait::async_trait;
+use zksync_config::configs::chain::StorageLogAnomalyPolicy;
 use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes, SystemContractCode};
 use zksync_dal::ConnectionPool;
 use zksync_types::{
-    ethabi::Address, l1::L1Tx, l2::L2Tx, L1BatchNumber, L1BlockNumber, MiniblockNumber,
-    Transaction, H256, U256,
+    event::EventIndexingPolicy, l1::L1Tx, l2::L2Tx, tx::TxRejection, L1BatchNumber, L1BlockNumber,
+    MiniblockNumber, Transaction, H256, U256,
 };
 use zksync_utils::{be_words_to_bytes, bytes_to_be_words};
 
+use crate::chain_contracts::ChainContracts;
 use crate::state_keeper::{
     extractors,
     io::{
         common::{l1_batch_params, load_pending_batch, poll_iters},
+        seal_logic::remove_unsealed_miniblocks,
+        timestamps::MiniblockTimestampPolicy,
         L1BatchParams, PendingBatchData, StateKeeperIO,
     },
     seal_criteria::SealerFn,
@@ -80,12 +84,16 @@ pub struct ExternalIO {
 
     current_l1_batch_number: L1BatchNumber,
     current_miniblock_number: MiniblockNumber,
+    /// Timestamp of the last miniblock received from the main node; used to validate timestamp monotonicity.
+    prev_miniblock_timestamp: u64,
     actions: ActionQueue,
     sync_state: SyncState,
     main_node_url: String,
 
     /// Required to extract newly added tokens.
-    l2_erc20_bridge_addr: Address,
+    chain_contracts: ChainContracts,
+    /// The external node indexes all events, since it may serve arbitrary log queries.
+    event_indexing: EventIndexingPolicy,
 }
 
 impl ExternalIO {
@@ -94,11 +102,18 @@ impl ExternalIO {
         actions: ActionQueue,
         sync_state: SyncState,
         main_node_url: String,
-        l2_erc20_bridge_addr: Address,
+        chain_contracts: ChainContracts,
     ) -> Self {
         let mut storage = pool.access_storage_tagged("sync_layer").await;
+        // Miniblocks partially persisted before the restart will be re-fetched from the main node.
+        remove_unsealed_miniblocks(&mut storage).await;
         let last_sealed_block_header = storage.blocks_dal().get_newest_block_header().await;
         let last_miniblock_number = storage.blocks_dal().get_sealed_miniblock_number().await;
+        let prev_miniblock_timestamp = storage
+            .blocks_dal()
+            .get_last_sealed_miniblock_header()
+            .await
+            .map_or(0, |header| header.timestamp);
         drop(storage);
 
         vlog::info!(
@@ -113,10 +128,12 @@ impl ExternalIO {
             pool,
             current_l1_batch_number: last_sealed_block_header.number + 1,
             current_miniblock_number: last_miniblock_number + 1,
+            prev_miniblock_timestamp,
             actions,
             sync_state,
             main_node_url,
-            l2_erc20_bridge_addr,
+            chain_contracts,
+            event_indexing: EventIndexingPolicy::default(),
         }
     }
 
@@ -134,6 +151,22 @@ impl ExternalIO {
         hash
     }
 
+    /// Checks that miniblock timestamps received from the main node are increasing. The check doesn't depend
+    /// on the local clock, so it's deterministic. Violations are only reported since historical miniblocks
+    /// may legitimately share timestamps.
+    fn check_miniblock_timestamp(&mut self, number: MiniblockNumber, timestamp: u64) {
+        if !MiniblockTimestampPolicy::default().is_valid(self.prev_miniblock_timestamp, timestamp) {
+            vlog::warn!(
+                "Timestamp {} of miniblock #{} doesn't follow the previous miniblock timestamp {}",
+                extractors::display_timestamp(timestamp),
+                number,
+                extractors::display_timestamp(self.prev_miniblock_timestamp)
+            );
+            metrics::increment_counter!("external_node.invalid_miniblock_timestamps");
+        }
+        self.prev_miniblock_timestamp = timestamp;
+    }
+
     async fn get_base_system_contract(&self, hash: H256) -> SystemContractCode {
         let bytecode = self
             .pool
@@ -193,7 +226,13 @@ impl StateKeeperIO for ExternalIO {
                 )
             })
             .fee_account_address;
-        load_pending_batch(&mut storage, self.current_l1_batch_number, fee_account).await
+        load_pending_batch(
+            &mut storage,
+            self.current_l1_batch_number,
+            fee_account,
+            None,
+        )
+        .await
     }
 
     async fn wait_for_new_batch_params(&mut self, max_wait: Duration) -> Option<L1BatchParams> {
@@ -224,6 +263,8 @@ impl StateKeeperIO for ExternalIO {
                         bootloader: self.get_base_system_contract(bootloader).await,
                         default_aa: self.get_base_system_contract(default_aa).await,
                     };
+                    // The L1 batch timestamp is the timestamp of its first miniblock.
+                    self.check_miniblock_timestamp(self.current_miniblock_number, timestamp);
 
                     return Some(l1_batch_params(
                         number,
@@ -233,6 +274,7 @@ impl StateKeeperIO for ExternalIO {
                         l1_gas_price,
                         l2_fair_gas_price,
                         base_system_contracts,
+                        None,
                     ));
                 }
                 Some(other) => {
@@ -257,6 +299,7 @@ impl StateKeeperIO for ExternalIO {
                         number, self.current_miniblock_number,
                         "Miniblock number mismatch"
                     );
+                    self.check_miniblock_timestamp(number, timestamp);
                     return Some(timestamp);
                 }
                 Some(SyncAction::SealBatch) => {
@@ -310,11 +353,11 @@ impl StateKeeperIO for ExternalIO {
         panic!("Rollback requested: {:?}", tx);
     }
 
-    async fn reject(&mut self, tx: &Transaction, error: &str) {
+    async fn reject(&mut self, tx: &Transaction, rejection: &TxRejection) {
         // We are replaying the already executed transactions so no rejections are expected to occur.
         panic!(
             "Reject requested because of the following error: {}.\n Transaction is: {:?}",
-            error, tx
+            rejection, tx
         );
     }
 
@@ -361,7 +404,10 @@ impl StateKeeperIO for ExternalIO {
         let command = updates_manager.seal_miniblock_command(
             self.current_l1_batch_number,
             self.current_miniblock_number,
-            self.l2_erc20_bridge_addr,
+            self.chain_contracts,
+            &self.event_indexing,
+            // The external node must follow the main node, so storage write anomalies don't stop syncing.
+            StorageLogAnomalyPolicy::Lenient,
         );
         command.seal(&mut transaction).await;
         transaction.commit().await;
@@ -394,7 +440,12 @@ impl StateKeeperIO for ExternalIO {
                 self.current_l1_batch_number,
                 block_result,
                 block_context,
-                self.l2_erc20_bridge_addr,
+                self.chain_contracts,
+                &self.event_indexing,
+                StorageLogAnomalyPolicy::Lenient,
+                false,
+                // The miniblock gas limit of the main node is unknown; the default one is reported.
+                None,
             )
             .await;
 
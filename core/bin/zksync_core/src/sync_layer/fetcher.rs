@@ -4,7 +4,7 @@ use tokio::sync::watch::Receiver;
 
 use crate::sync_layer::sync_action::{ActionQueue, SyncAction};
 use zksync_dal::ConnectionPool;
-use zksync_types::{L1BatchNumber, MiniblockNumber};
+use zksync_types::{Address, L1BatchNumber, MiniblockNumber};
 use zksync_web3_decl::jsonrpsee::core::Error as RpcError;
 use zksync_web3_decl::RpcResult;
 
@@ -19,6 +19,8 @@ pub struct MainNodeFetcher {
     client: CachedMainNodeClient,
     current_l1_batch: L1BatchNumber,
     current_miniblock: MiniblockNumber,
+    /// Address collecting fees in the current L1 batch, or `None` if it's not known yet.
+    current_fee_account: Option<Address>,
 
     actions: ActionQueue,
     sync_state: SyncState,
@@ -51,6 +53,15 @@ impl MainNodeFetcher {
             // We need to open the next batch.
             last_sealed_block_header.number
         };
+        // All miniblocks in an L1 batch must have the same fee account, so we remember it for the pending batch.
+        let current_fee_account = if was_new_batch_open {
+            storage
+                .blocks_dal()
+                .get_miniblock_fee_account_address(last_miniblock_number)
+                .await
+        } else {
+            None
+        };
 
         let client = CachedMainNodeClient::build_client(main_node_url);
 
@@ -58,6 +69,7 @@ impl MainNodeFetcher {
             client,
             current_l1_batch,
             current_miniblock,
+            current_fee_account,
 
             actions,
             sync_state,
@@ -169,11 +181,21 @@ impl MainNodeFetcher {
                 base_system_contracts_hashes: block.base_system_contracts_hashes,
                 operator_address: block.operator_address,
             });
+            self.current_fee_account = Some(block.operator_address);
             metrics::gauge!("external_node.fetcher.l1_batch", block.l1_batch_number.0 as f64, "status" => "open");
             self.current_l1_batch += 1;
         } else {
             // New batch implicitly means a new miniblock, so we only need to push the miniblock action
             // if it's not a new batch.
+            if let Some(fee_account) = self.current_fee_account {
+                // The fee account is applied once per L1 batch, so a mismatch would make re-executed miniblocks
+                // diverge from the main node.
+                assert_eq!(
+                    block.operator_address, fee_account,
+                    "Fee account changed within L1 batch #{} at miniblock #{}",
+                    block.l1_batch_number, block.number
+                );
+            }
             new_actions.push(SyncAction::Miniblock {
                 number: block.number,
                 timestamp: block.timestamp,
@@ -14,13 +14,18 @@ use vm::{memory::SimpleMemory, StorageOracle, MAX_CYCLES_FOR_TX};
 use zksync_config::configs::WitnessGeneratorConfig;
 use zksync_config::constants::BOOTLOADER_ADDRESS;
 use zksync_dal::ConnectionPool;
-use zksync_object_store::{Bucket, ObjectStore, ObjectStoreFactory, StoredObject};
+use zksync_object_store::{
+    Bucket, ObjectStore, ObjectStoreError, ObjectStoreFactory, StoredObject,
+};
 use zksync_queued_job_processor::JobProcessor;
 use zksync_state::{PostgresStorage, StorageView};
 use zksync_types::zkevm_test_harness::toolset::GeometryConfig;
 use zksync_types::{
     circuit::GEOMETRY_CONFIG,
-    proofs::{AggregationRound, BasicCircuitWitnessGeneratorInput, PrepareBasicCircuitsJob},
+    proofs::{
+        AggregationRound, BasicCircuitWitnessGeneratorInput, L1BatchVmArtifacts,
+        PrepareBasicCircuitsJob,
+    },
     zkevm_test_harness::{
         abstract_zksync_circuit::concrete_circuits::ZkSyncCircuit,
         bellman::bn256::Bn256,
@@ -272,6 +277,15 @@ async fn update_database(
             &blob_urls.scheduler_witness_url,
         )
         .await;
+    let circuit_types: Vec<_> = blob_urls
+        .circuit_types_and_urls
+        .iter()
+        .map(|(circuit_type, _)| *circuit_type)
+        .collect();
+    transaction
+        .witness_generator_dal()
+        .create_basic_circuit_shards(block_number, &circuit_types)
+        .await;
     transaction
         .prover_dal()
         .insert_prover_jobs(
@@ -406,7 +420,37 @@ pub async fn generate_witness(
         .map(|hash| u256_to_h256(*hash))
         .collect();
 
-    let mut used_bytecodes = connection.storage_dal().get_factory_deps(&hashes).await;
+    // VM run artifacts are saved by the state keeper if configured; if they are present, the VM
+    // is run on exactly the same inputs as in the state keeper.
+    let vm_artifacts = load_vm_artifacts(&*object_store, input.block_number).await;
+    let mut used_bytecodes = HashMap::new();
+    let mut storage_reads = HashMap::new();
+    let mut initial_writes = HashMap::new();
+    if let Some(artifacts) = vm_artifacts {
+        used_bytecodes = artifacts
+            .used_bytecodes
+            .into_iter()
+            .filter(|(hash, _)| hashes.contains(&u256_to_h256(*hash)))
+            .map(|(hash, words)| {
+                let words = words.into_iter().map(|word| u256_to_h256(word).0);
+                (hash, words.collect::<Vec<_>>())
+            })
+            .collect();
+        storage_reads = artifacts.storage_reads.into_iter().collect();
+        initial_writes = artifacts.initial_writes.into_iter().collect();
+    }
+    let missing_hashes: HashSet<H256> = hashes
+        .iter()
+        .filter(|hash| !used_bytecodes.contains_key(&h256_to_u256(**hash)))
+        .copied()
+        .collect();
+    if !missing_hashes.is_empty() {
+        let missing_bytecodes = connection
+            .storage_dal()
+            .get_factory_deps(&missing_hashes)
+            .await;
+        used_bytecodes.extend(missing_bytecodes);
+    }
     if input.used_bytecodes_hashes.contains(&account_code_hash) {
         used_bytecodes.insert(account_code_hash, account_bytecode);
     }
@@ -439,7 +483,8 @@ pub async fn generate_witness(
             input.previous_block_hash.0,
         );
 
-        let storage_view = &mut StorageView::new(storage);
+        let storage_view = &mut StorageView::new_with_read_keys(storage, storage_reads)
+            .with_initial_writes(initial_writes);
         let storage_oracle: StorageOracle<HistoryDisabled> =
             StorageOracle::new(storage_view.as_ptr());
         let memory: SimpleMemory<HistoryDisabled> = SimpleMemory::default();
@@ -493,6 +538,22 @@ pub async fn generate_witness(
     .unwrap()
 }
 
+async fn load_vm_artifacts(
+    object_store: &dyn ObjectStore,
+    block_number: L1BatchNumber,
+) -> Option<L1BatchVmArtifacts> {
+    match object_store.get(block_number).await {
+        Ok(artifacts) => Some(artifacts),
+        Err(ObjectStoreError::KeyNotFound(_)) => None,
+        Err(err) => {
+            vlog::warn!(
+                "Failed loading VM artifacts for L1 batch #{block_number}, falling back to Postgres: {err}"
+            );
+            None
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn save_run_with_fixed_params_args_to_gcs(
     object_store: Arc<dyn ObjectStore>,
@@ -118,6 +118,22 @@ basic_type!(
     u16
 );
 
+basic_type!(
+    /// Version of the zkSync protocol, as registered in the L1 diamond proxy contract.
+    ProtocolVersionId,
+    u16
+);
+
+impl ProtocolVersionId {
+    /// First protocol version supporting contracts deployed with unmodified EVM bytecode,
+    /// which are executed by the EVM interpreter system contract.
+    pub const FIRST_WITH_EVM_EMULATION: Self = Self(4);
+
+    pub fn supports_evm_emulation(self) -> bool {
+        self >= Self::FIRST_WITH_EVM_EMULATION
+    }
+}
+
 #[allow(clippy::derivable_impls)]
 impl Default for MiniblockNumber {
     fn default() -> Self {
@@ -20,8 +20,8 @@ use zksync_types::{
         error::TransportError,
         ethabi,
         types::{
-            Address, BlockId, BlockNumber, Filter, Log, Transaction, TransactionReceipt, H160,
-            H256, U256,
+            Address, BlockId, BlockNumber, Bytes, CallRequest, Filter, Log, Transaction,
+            TransactionReceipt, H160, H256, U256,
         },
     },
     L1ChainId, U64,
@@ -130,6 +130,10 @@ impl EthInterface for ETHDirectClientMock {
         Ok(Default::default())
     }
 
+    async fn fetch_chain_id(&self, _: &'static str) -> Result<L1ChainId, Error> {
+        Ok(L1ChainId(0))
+    }
+
     async fn send_raw_tx(&self, _tx: Vec<u8>) -> Result<H256, Error> {
         Ok(Default::default())
     }
@@ -191,6 +195,23 @@ impl EthInterface for ETHDirectClientMock {
         Ok(Default::default())
     }
 
+    async fn call(
+        &self,
+        _request: CallRequest,
+        _block: Option<BlockId>,
+        _component: &'static str,
+    ) -> Result<Bytes, Error> {
+        Ok(Bytes::default())
+    }
+
+    async fn estimate_gas(
+        &self,
+        _request: CallRequest,
+        _component: &'static str,
+    ) -> Result<U256, Error> {
+        Ok(Default::default())
+    }
+
     async fn logs(&self, _filter: Filter, _component: &'static str) -> Result<Vec<Log>, Error> {
         Ok(Default::default())
     }
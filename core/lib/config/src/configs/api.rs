@@ -1,12 +1,12 @@
 /// External uses
 use serde::Deserialize;
 /// Built-in uses
-use std::net::SocketAddr;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::time::Duration;
 // Local uses
 use super::envy_load;
 pub use crate::configs::PrometheusConfig;
-use zksync_basic_types::H256;
+use zksync_basic_types::{Address, H256};
 
 /// API configuration.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -32,6 +32,17 @@ impl ApiConfig {
     }
 }
 
+/// Policy used to suggest gas prices in `eth_gasPrice` and `eth_maxPriorityFeePerGas`.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub enum GasPricePolicy {
+    /// Base fee derived from the current L1 gas price and the fee model; no priority fee.
+    Static,
+    /// Percentile of the base fees of the latest miniblocks, but not lower than the static price.
+    Percentile,
+    /// Static price marked up proportionally to the recent growth of miniblock base fees.
+    CongestionAware,
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct Web3JsonRpcConfig {
     /// Port to which the HTTP RPC server is listening.
@@ -72,6 +83,18 @@ pub struct Web3JsonRpcConfig {
     /// Max number of cache misses during one VM execution. If the number of cache misses exceeds this value, the api server panics.
     /// This is a temporary solution to mitigate API request resulting in thousands of DB queries.
     pub vm_execution_cache_misses_limit: Option<usize>,
+    /// Max size of the data returned by a single `eth_call` or gas estimation VM execution (in bytes).
+    /// Executions exceeding this limit are aborted. If not set, the returned data size is not limited.
+    pub vm_execution_max_returndata_size: Option<usize>,
+    /// Max number of events emitted during a single `eth_call` or gas estimation VM execution.
+    /// Executions exceeding this limit are aborted. If not set, the number of events is not limited.
+    pub vm_execution_max_events: Option<usize>,
+    /// Max number of storage reads and writes during a single `eth_call` or gas estimation VM execution.
+    /// Executions exceeding this limit are aborted. If not set, the number of storage accesses is not limited.
+    pub vm_execution_max_storage_accesses: Option<usize>,
+    /// Ratio of soft limits on the VM execution output to the corresponding limits above. Executions exceeding
+    /// soft limits are not aborted, but are logged and reported in metrics.
+    pub vm_execution_soft_limits_ratio: Option<f64>,
     /// Max number of VM instances to be concurrently spawned by the API server.
     /// This option can be tweaked down if the API server is running out of memory.
     /// If not set, the VM concurrency limit will be efficiently disabled.
@@ -84,11 +107,115 @@ pub struct Web3JsonRpcConfig {
     /// Override value for the amount of threads used for WebSocket RPC server.
     /// If not set, the value from `threads_per_server` is used.
     pub ws_threads: Option<u32>,
+    /// Max number of read calls per second accepted from a single IP.
+    /// If not set, read calls are not rate limited.
+    pub reads_per_second_per_ip: Option<u32>,
+    /// Max number of calls resulting in a VM execution (e.g. `eth_call` or `eth_estimateGas`)
+    /// per second accepted from a single IP. If not set, such calls are not rate limited.
+    pub executions_per_second_per_ip: Option<u32>,
+    /// Max number of calls in a single batch request.
+    pub max_batch_request_size: Option<usize>,
+    /// Max size of the request body (in bytes).
+    pub max_request_body_size: Option<u32>,
+    /// Addresses of the load balancers / reverse proxies in front of the API server. Client IPs used for rate limiting
+    /// are taken from the `X-Forwarded-For` and `X-Real-IP` headers only if the request comes from one of these addresses;
+    /// otherwise, the peer address is used.
+    pub trusted_proxies: Option<Vec<IpAddr>>,
+    /// Max number of accounts for which validation rule violations are cached.
+    /// Transactions from accounts with cached violations are rejected without running the validation.
+    /// Set to 0 to disable the cache.
+    pub validation_cache_size: Option<usize>,
+    /// Max number of cached account nonces and balances used to validate submitted transactions.
+    /// The cache is only used if the API server runs together with the state keeper. Set to 0 to disable the cache.
+    pub account_state_cache_size: Option<usize>,
+    /// If set, only the listed paymasters are allowed to sponsor transactions.
+    pub paymaster_allowlist: Option<Vec<Address>>,
+    /// Paymasters that are not allowed to sponsor transactions.
+    pub paymaster_denylist: Option<Vec<Address>>,
+    /// Min ETH balance (in gwei) a paymaster must have to sponsor transactions.
+    pub min_paymaster_balance_gwei: Option<u64>,
+    /// Max number of cached responses for immutable data (old blocks, transactions, receipts, bytecodes).
+    /// Set to 0 to disable the cache.
+    pub response_cache_size: Option<usize>,
+    /// Time-to-live of a cached response (in s).
+    pub response_cache_ttl_sec: Option<u64>,
+    /// Min number of L1 batches sealed on top of a miniblock for its data to be cached.
+    pub response_cache_min_l1_batch_depth: Option<u32>,
+    /// Max number of cached `eth_call` results for calls at sealed miniblocks. Set to 0 to disable the cache,
+    /// which is the default.
+    pub call_cache_size: Option<usize>,
+    /// Time-to-live of a cached `eth_call` result (in ms).
+    pub call_cache_ttl_ms: Option<u64>,
+    /// Policy used to suggest gas prices. If not set, the static policy is used.
+    pub gas_price_policy: Option<GasPricePolicy>,
+    /// Number of the latest miniblocks which base fees are considered by the gas price policy.
+    pub gas_price_history_size: Option<usize>,
+    /// Percentile of recent base fees used by the percentile gas price policy.
+    pub gas_price_percentile: Option<u8>,
+    /// Max markup (as a fraction of the price) applied by the congestion-aware gas price policy.
+    pub gas_price_max_congestion_markup: Option<f64>,
+    /// Port of the internal HTTP server serving the `admin` namespace. The namespace is not served by the public
+    /// API servers. If not set, the namespace is disabled.
+    pub admin_api_port: Option<u16>,
+    /// Address the `admin` namespace server binds to. If not set, the server only listens on the loopback interface.
+    pub admin_api_bind_address: Option<IpAddr>,
+    /// Token authenticating calls to the `admin` namespace. Required if `admin_api_port` is set.
+    pub admin_api_token: Option<String>,
+    /// If set, only the listed accounts are allowed to deploy contracts.
+    pub deployer_allowlist: Option<Vec<Address>>,
+    /// Contracts that transactions are not allowed to call.
+    pub callee_denylist: Option<Vec<Address>>,
+    /// Max size of the transaction calldata (in bytes). If not set, the calldata size is not limited.
+    pub max_calldata_size: Option<usize>,
+    /// Whether `zks_getAllAccountBalances` resolves balances from the token balances table maintained
+    /// when sealing miniblocks, rather than from the storage. Should only be enabled if the table was populated
+    /// starting from the genesis. Disabled by default.
+    pub indexed_balances: Option<bool>,
+    /// Path to the Unix domain socket for the IPC RPC server. The IPC server is served from the same
+    /// process as the HTTP and WebSocket servers and shares their state. IPC is disabled if not set.
+    pub ipc_path: Option<String>,
+    /// Whether legacy (untyped) transactions are accepted. Enabled by default.
+    pub accept_legacy_txs: Option<bool>,
+    /// Whether EIP-2930 transactions are accepted. Only transactions with empty access lists are supported.
+    /// Enabled by default.
+    pub accept_eip2930_txs: Option<bool>,
+    /// Whether EIP-1559 transactions are accepted. Enabled by default.
+    pub accept_eip1559_txs: Option<bool>,
+    /// Whether EIP-712 transactions are accepted. Enabled by default.
+    pub accept_eip712_txs: Option<bool>,
+    /// Max number of transactions in a bundle simulated by `zks_simulateBundle`.
+    pub max_bundle_size: Option<usize>,
+    /// Calls to the `jsonrpc` backend taking longer than this threshold (in ms) are logged together with
+    /// their parameters. If not set, slow calls are not logged.
+    pub slow_call_threshold_ms: Option<u64>,
+    /// Share of slow calls that are logged, from 0 to 1.
+    pub slow_call_log_sample_rate: Option<f64>,
+    /// Namespaces (e.g., `eth` or `zks`) returning decoded protected data, such as encrypted calldata or event data.
+    /// Other namespaces return protected data as stored. If not set, all namespaces return decoded data.
+    pub protected_data_namespaces: Option<Vec<String>>,
 }
 
 impl Web3JsonRpcConfig {
     pub fn from_env() -> Self {
-        envy_load("web3_json_rpc", "API_WEB3_JSON_RPC_")
+        let config: Self = envy_load("web3_json_rpc", "API_WEB3_JSON_RPC_");
+        match &config.admin_api_token {
+            Some(token) if token.is_empty() => {
+                panic!("`admin_api_token` must not be empty");
+            }
+            None if config.admin_api_port.is_some() => {
+                panic!("`admin_api_token` must be set if `admin_api_port` is set");
+            }
+            _ => config,
+        }
+    }
+
+    /// Returns the address of the `admin` namespace server, or `None` if the namespace is disabled.
+    pub fn admin_api_bind_addr(&self) -> Option<SocketAddr> {
+        let bind_address = self
+            .admin_api_bind_address
+            .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        self.admin_api_port
+            .map(|port| SocketAddr::new(bind_address, port))
     }
 
     pub fn http_bind_addr(&self) -> SocketAddr {
@@ -135,6 +262,111 @@ impl Web3JsonRpcConfig {
     pub fn ws_server_threads(&self) -> usize {
         self.ws_threads.unwrap_or(self.threads_per_server) as usize
     }
+
+    pub fn max_batch_request_size(&self) -> usize {
+        self.max_batch_request_size.unwrap_or(500)
+    }
+
+    pub fn max_request_body_size(&self) -> u32 {
+        // 10MB is the default limit of the RPC servers.
+        self.max_request_body_size.unwrap_or(10 * 1024 * 1024)
+    }
+
+    pub fn trusted_proxies(&self) -> Vec<IpAddr> {
+        self.trusted_proxies.clone().unwrap_or_default()
+    }
+
+    pub fn validation_cache_size(&self) -> usize {
+        self.validation_cache_size.unwrap_or(10_000)
+    }
+
+    pub fn account_state_cache_size(&self) -> usize {
+        self.account_state_cache_size.unwrap_or(10_000)
+    }
+
+    pub fn paymaster_denylist(&self) -> Vec<Address> {
+        self.paymaster_denylist.clone().unwrap_or_default()
+    }
+
+    pub fn callee_denylist(&self) -> Vec<Address> {
+        self.callee_denylist.clone().unwrap_or_default()
+    }
+
+    pub fn indexed_balances(&self) -> bool {
+        self.indexed_balances.unwrap_or(false)
+    }
+
+    pub fn accept_legacy_txs(&self) -> bool {
+        self.accept_legacy_txs.unwrap_or(true)
+    }
+
+    pub fn accept_eip2930_txs(&self) -> bool {
+        self.accept_eip2930_txs.unwrap_or(true)
+    }
+
+    pub fn accept_eip1559_txs(&self) -> bool {
+        self.accept_eip1559_txs.unwrap_or(true)
+    }
+
+    pub fn accept_eip712_txs(&self) -> bool {
+        self.accept_eip712_txs.unwrap_or(true)
+    }
+
+    pub fn min_paymaster_balance_gwei(&self) -> u64 {
+        self.min_paymaster_balance_gwei.unwrap_or(0)
+    }
+
+    pub fn response_cache_size(&self) -> usize {
+        self.response_cache_size.unwrap_or(10_000)
+    }
+
+    pub fn response_cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.response_cache_ttl_sec.unwrap_or(60))
+    }
+
+    pub fn response_cache_min_l1_batch_depth(&self) -> u32 {
+        self.response_cache_min_l1_batch_depth.unwrap_or(2)
+    }
+
+    pub fn call_cache_size(&self) -> usize {
+        self.call_cache_size.unwrap_or(0)
+    }
+
+    pub fn call_cache_ttl(&self) -> Duration {
+        Duration::from_millis(self.call_cache_ttl_ms.unwrap_or(1_000))
+    }
+
+    pub fn max_bundle_size(&self) -> usize {
+        self.max_bundle_size.unwrap_or(16)
+    }
+
+    pub fn slow_call_threshold(&self) -> Option<Duration> {
+        self.slow_call_threshold_ms.map(Duration::from_millis)
+    }
+
+    pub fn slow_call_log_sample_rate(&self) -> f64 {
+        self.slow_call_log_sample_rate.unwrap_or(0.1)
+    }
+
+    pub fn gas_price_policy(&self) -> GasPricePolicy {
+        self.gas_price_policy.unwrap_or(GasPricePolicy::Static)
+    }
+
+    pub fn gas_price_history_size(&self) -> usize {
+        self.gas_price_history_size.unwrap_or(20)
+    }
+
+    pub fn gas_price_percentile(&self) -> u8 {
+        self.gas_price_percentile.unwrap_or(60)
+    }
+
+    pub fn gas_price_max_congestion_markup(&self) -> f64 {
+        self.gas_price_max_congestion_markup.unwrap_or(0.5)
+    }
+
+    pub fn vm_execution_soft_limits_ratio(&self) -> f64 {
+        self.vm_execution_soft_limits_ratio.unwrap_or(0.5)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -228,10 +460,52 @@ mod tests {
                 estimate_gas_acceptable_overestimation: 1000,
                 max_tx_size: 1000000,
                 vm_execution_cache_misses_limit: None,
+                vm_execution_max_returndata_size: Some(1048576),
+                vm_execution_max_events: Some(10000),
+                vm_execution_max_storage_accesses: Some(100000),
+                vm_execution_soft_limits_ratio: Some(0.5),
                 vm_concurrency_limit: Some(512),
                 factory_deps_cache_size_mb: Some(128),
                 http_threads: Some(128),
                 ws_threads: Some(256),
+                reads_per_second_per_ip: Some(500),
+                executions_per_second_per_ip: Some(50),
+                max_batch_request_size: Some(200),
+                max_request_body_size: Some(1048576),
+                trusted_proxies: Some(vec![
+                    "10.0.0.1".parse().unwrap(),
+                    "10.0.0.2".parse().unwrap(),
+                ]),
+                validation_cache_size: Some(5000),
+                account_state_cache_size: Some(20000),
+                paymaster_allowlist: None,
+                paymaster_denylist: Some(vec![Address::repeat_byte(0x11)]),
+                min_paymaster_balance_gwei: Some(1000000000),
+                response_cache_size: Some(20000),
+                response_cache_ttl_sec: Some(120),
+                response_cache_min_l1_batch_depth: Some(3),
+                call_cache_size: Some(1000),
+                call_cache_ttl_ms: Some(2000),
+                gas_price_policy: Some(GasPricePolicy::Percentile),
+                gas_price_history_size: Some(50),
+                gas_price_percentile: Some(75),
+                gas_price_max_congestion_markup: Some(0.25),
+                admin_api_port: Some(3055),
+                admin_api_bind_address: None,
+                admin_api_token: Some("secret".to_owned()),
+                deployer_allowlist: Some(vec![Address::repeat_byte(0x22)]),
+                callee_denylist: None,
+                max_calldata_size: Some(100000),
+                indexed_balances: Some(true),
+                ipc_path: Some("/tmp/zksync-web3.ipc".into()),
+                accept_legacy_txs: Some(false),
+                accept_eip2930_txs: Some(false),
+                accept_eip1559_txs: None,
+                accept_eip712_txs: None,
+                max_bundle_size: Some(8),
+                slow_call_threshold_ms: Some(500),
+                slow_call_log_sample_rate: Some(0.5),
+                protected_data_namespaces: Some(vec!["en".to_owned(), "zks".to_owned()]),
             },
             explorer: ExplorerApiConfig {
                 port: 3070,
@@ -270,10 +544,44 @@ API_WEB3_JSON_RPC_ACCOUNT_PKS=0x000000000000000000000000000000000000000000000000
 API_WEB3_JSON_RPC_ESTIMATE_GAS_SCALE_FACTOR=1.0
 API_WEB3_JSON_RPC_ESTIMATE_GAS_ACCEPTABLE_OVERESTIMATION=1000
 API_WEB3_JSON_RPC_MAX_TX_SIZE=1000000
+API_WEB3_JSON_RPC_VM_EXECUTION_MAX_RETURNDATA_SIZE=1048576
+API_WEB3_JSON_RPC_VM_EXECUTION_MAX_EVENTS=10000
+API_WEB3_JSON_RPC_VM_EXECUTION_MAX_STORAGE_ACCESSES=100000
+API_WEB3_JSON_RPC_VM_EXECUTION_SOFT_LIMITS_RATIO=0.5
 API_WEB3_JSON_RPC_VM_CONCURRENCY_LIMIT=512
 API_WEB3_JSON_RPC_FACTORY_DEPS_CACHE_SIZE_MB=128
 API_WEB3_JSON_RPC_HTTP_THREADS=128
 API_WEB3_JSON_RPC_WS_THREADS=256
+API_WEB3_JSON_RPC_READS_PER_SECOND_PER_IP=500
+API_WEB3_JSON_RPC_EXECUTIONS_PER_SECOND_PER_IP=50
+API_WEB3_JSON_RPC_MAX_BATCH_REQUEST_SIZE=200
+API_WEB3_JSON_RPC_MAX_REQUEST_BODY_SIZE=1048576
+API_WEB3_JSON_RPC_TRUSTED_PROXIES=10.0.0.1,10.0.0.2
+API_WEB3_JSON_RPC_VALIDATION_CACHE_SIZE=5000
+API_WEB3_JSON_RPC_ACCOUNT_STATE_CACHE_SIZE=20000
+API_WEB3_JSON_RPC_PAYMASTER_DENYLIST=0x1111111111111111111111111111111111111111
+API_WEB3_JSON_RPC_MIN_PAYMASTER_BALANCE_GWEI=1000000000
+API_WEB3_JSON_RPC_RESPONSE_CACHE_SIZE=20000
+API_WEB3_JSON_RPC_RESPONSE_CACHE_TTL_SEC=120
+API_WEB3_JSON_RPC_RESPONSE_CACHE_MIN_L1_BATCH_DEPTH=3
+API_WEB3_JSON_RPC_CALL_CACHE_SIZE=1000
+API_WEB3_JSON_RPC_CALL_CACHE_TTL_MS=2000
+API_WEB3_JSON_RPC_GAS_PRICE_POLICY="Percentile"
+API_WEB3_JSON_RPC_GAS_PRICE_HISTORY_SIZE=50
+API_WEB3_JSON_RPC_GAS_PRICE_PERCENTILE=75
+API_WEB3_JSON_RPC_GAS_PRICE_MAX_CONGESTION_MARKUP=0.25
+API_WEB3_JSON_RPC_ADMIN_API_PORT=3055
+API_WEB3_JSON_RPC_ADMIN_API_TOKEN="secret"
+API_WEB3_JSON_RPC_DEPLOYER_ALLOWLIST=0x2222222222222222222222222222222222222222
+API_WEB3_JSON_RPC_MAX_CALLDATA_SIZE=100000
+API_WEB3_JSON_RPC_INDEXED_BALANCES=true
+API_WEB3_JSON_RPC_IPC_PATH="/tmp/zksync-web3.ipc"
+API_WEB3_JSON_RPC_ACCEPT_LEGACY_TXS=false
+API_WEB3_JSON_RPC_ACCEPT_EIP2930_TXS=false
+API_WEB3_JSON_RPC_MAX_BUNDLE_SIZE=8
+API_WEB3_JSON_RPC_SLOW_CALL_THRESHOLD_MS=500
+API_WEB3_JSON_RPC_SLOW_CALL_LOG_SAMPLE_RATE=0.5
+API_WEB3_JSON_RPC_PROTECTED_DATA_NAMESPACES=en,zks
 API_EXPLORER_PORT="3070"
 API_EXPLORER_URL="http://127.0.0.1:3070"
 API_EXPLORER_NETWORK_STATS_POLLING_INTERVAL="1000"
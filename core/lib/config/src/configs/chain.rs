@@ -1,13 +1,15 @@
 /// External uses
 use serde::Deserialize;
 /// Built-in uses
+use std::net::{IpAddr, Ipv4Addr};
 use std::time::Duration;
 // Local uses
 use zksync_basic_types::network::Network;
-use zksync_basic_types::{Address, H256};
+use zksync_basic_types::{Address, ProtocolVersionId, H256};
 use zksync_contracts::BaseSystemContractsHashes;
 
 use super::envy_load;
+use crate::constants::MAX_NEW_FACTORY_DEPS;
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct ChainConfig {
@@ -57,15 +59,44 @@ impl NetworkConfig {
 pub struct StateKeeperConfig {
     /// The max number of slots for txs in a block before it should be sealed by the slots sealer.
     pub transaction_slots: usize,
+    /// The max number of L2 transactions in an L1 batch. If not set, only `transaction_slots` limit applies.
+    pub max_l2_txs_per_l1_batch: Option<usize>,
+    /// The max number of L2 transactions in a miniblock. If not set, the number of transactions isn't limited.
+    pub max_l2_txs_per_miniblock: Option<usize>,
+    /// Gas limit of a miniblock. A miniblock is sealed once the gas used by its transactions reaches the limit;
+    /// the limit is also reported as `gasLimit` of the miniblock in the Web3 API. If not set, the gas used
+    /// by a miniblock isn't limited.
+    pub miniblock_gas_limit: Option<u64>,
 
     /// Number of ms after which an L1 batch is going to be unconditionally sealed.
     pub block_commit_deadline_ms: u64,
+    /// L1 cost (in wei) of committing an L1 batch at which the batch is sealed after `block_commit_deadline_ms`.
+    /// If set, the batch deadline is scaled proportionally to the current projected commit cost, so that batches
+    /// are sealed faster when L1 is cheap and are kept open longer when it's expensive.
+    pub l1_commit_cost_reference_wei: Option<u64>,
+    /// Lower bound for the L1 batch deadline scaled by the L1 commit cost. Defaults to `block_commit_deadline_ms / 2`.
+    pub min_block_commit_deadline_ms: Option<u64>,
+    /// Upper bound for the L1 batch deadline scaled by the L1 commit cost. Defaults to `block_commit_deadline_ms * 2`.
+    pub max_block_commit_deadline_ms: Option<u64>,
     /// Number of ms after which a miniblock should be sealed by the timeout sealer.
     pub miniblock_commit_deadline_ms: u64,
     /// Capacity of the queue for asynchronous miniblock sealing. Once this many miniblocks are queued,
     /// sealing will block until some of the miniblocks from the queue are processed.
     /// 0 means that sealing is synchronous; this is mostly useful for performance comparison, testing etc.
     pub miniblock_seal_queue_capacity: usize,
+    /// Max number of Postgres connections used to seal a single miniblock. If greater than 1, independent writes
+    /// for the miniblock are performed concurrently, and the miniblock is marked as sealed once all of them succeed.
+    /// Otherwise, miniblocks are sealed sequentially in a single Postgres transaction.
+    pub miniblock_seal_concurrency: Option<usize>,
+    /// If set, timestamps of consecutive miniblocks differ by exactly this number of seconds, as long as
+    /// the wall clock doesn't get ahead of the schedule. If not set, the wall clock is used.
+    pub miniblock_timestamp_interval_sec: Option<u64>,
+    /// Min difference between timestamps of consecutive miniblocks in seconds. Values less than 1 are treated as 1,
+    /// so that two miniblocks never share a timestamp.
+    pub miniblock_min_timestamp_interval_sec: Option<u64>,
+    /// Max number of seconds a miniblock timestamp may be ahead of the wall clock. If a timestamp required
+    /// by the interval settings is further ahead, the state keeper waits until the wall clock catches up.
+    pub miniblock_max_timestamp_drift_sec: Option<u64>,
 
     /// The max number of gas to spend on an L1 tx before its batch should be sealed by the gas sealer.
     pub max_single_tx_gas: u32,
@@ -89,6 +120,14 @@ pub struct StateKeeperConfig {
     pub close_block_at_gas_percentage: f64,
 
     pub fee_account_addr: Address,
+    /// Path to a keystore file with the operator address collecting fees. If set, the address is re-read
+    /// from the file each time an L1 batch is opened and overrides `fee_account_addr`, which allows rotating
+    /// the operator without a restart.
+    pub fee_account_addr_path: Option<String>,
+    /// Address collecting L2 fees in new L1 batches. If set, it's used instead of the operator address
+    /// (`fee_account_addr` / `fee_account_addr_path`), so that fees can be collected by an account distinct
+    /// from the sequencer operator.
+    pub fee_collector_addr: Option<Address>,
 
     /// The price the operator spends on 1 gas of computation in wei.
     pub fair_l2_gas_price: u64,
@@ -99,6 +138,86 @@ pub struct StateKeeperConfig {
     /// Max number of computational gas that validation step is allowed to take.
     pub validation_computational_gas_limit: u32,
     pub save_call_traces: bool,
+    /// Wall-clock time in ms a single transaction is allowed to be executed by the VM. Transactions exceeding
+    /// this budget are rejected. If not set, the execution time is not limited.
+    pub tx_execution_timeout_ms: Option<u64>,
+    /// Protocol version the state keeper produces batches for. The state keeper won't open new batches
+    /// if this version doesn't match the latest version registered on L1. Defaults to 0.
+    pub protocol_version: Option<u16>,
+    /// Max number of factory dependencies in a single transaction. Cannot exceed the number of factory deps
+    /// supported by the bootloader, which is also the default value.
+    pub max_factory_deps_per_tx: Option<usize>,
+    /// Max total size of factory dependencies in a single transaction in bytes. If not set, the size
+    /// is only limited by the other seal criteria.
+    pub max_factory_deps_size_per_tx: Option<usize>,
+    /// Capacity of the cache of decoded contract bytecodes shared among VM instances (the batch executor
+    /// and the API sandbox) in megabytes. If set to 0, the cache is disabled. Defaults to 128.
+    pub bytecode_cache_size_mb: Option<usize>,
+    /// If set, the batch executor is supervised: if it panics, it's restarted and all transactions executed
+    /// in the current L1 batch are re-executed, while the transaction that caused the panic is rejected.
+    /// The value is the max number of restarts within a single L1 batch. If not set, a panic in the batch
+    /// executor shuts down the server.
+    pub max_batch_executor_restarts: Option<usize>,
+    /// Max size in bytes of the output of a single call persisted in call traces. Larger outputs are truncated
+    /// when the miniblock is sealed, and the transaction is flagged as having truncated results. Defaults to 64 KiB.
+    pub max_tx_output_size: Option<usize>,
+    /// Max size in bytes of a revert reason or an error message persisted for a transaction or a call.
+    /// Longer messages are truncated and marked as such when the miniblock is sealed. Defaults to 4 KiB.
+    pub max_tx_revert_reason_size: Option<usize>,
+    /// Patterns of events fully indexed in Postgres, in the `<address>`, `<address>:<topic>` or `*:<topic>` format
+    /// (the topic is the event signature). Other events are stored raw-only and aren't returned by log queries
+    /// filtering by address or topics. If not set, all events are indexed.
+    pub indexed_event_patterns: Option<Vec<String>>,
+    /// If set, artifacts of the VM run for each L1 batch (values of read storage slots and decommitted bytecodes)
+    /// are saved to the object store when the batch is sealed, so that the witness generator doesn't need to load
+    /// them from Postgres. Disabled by default.
+    pub save_vm_artifacts: Option<bool>,
+    /// If set, an L1 batch is sealed right after a priority operation calling one of the listed contracts
+    /// (e.g., the contract deployer for protocol upgrades) is executed, so that such operations reach L1
+    /// finality without waiting for the batch timeout.
+    pub seal_on_priority_op_contracts: Option<Vec<Address>>,
+    /// If set, storage access sets (hashed keys read and written) of each transaction are persisted when an L1 batch
+    /// is sealed and served by the `debug_getL1BatchAccessSets` API method. Disabled by default.
+    pub save_tx_access_sets: Option<bool>,
+    /// Max relative change of the base fee between consecutive L1 batches (e.g., 0.125 for 12.5%). The base fee
+    /// is capped by adjusting the L1 gas price assumed in the new batch, so pubdata may be temporarily under-
+    /// or overcharged while the base fee converges to the L1 gas price. The API quotes fees using the same capped
+    /// L1 gas price. If not set, the base fee isn't capped.
+    pub max_base_fee_change_per_batch: Option<f64>,
+    /// Max number of sealed L1 batches not yet processed by the Merkle tree. If the tree falls further behind,
+    /// the state keeper applies `tree_lag_policy` before opening new L1 batches. If not set, the lag isn't limited.
+    pub max_tree_lag: Option<u32>,
+    /// Policy applied if the tree lag exceeds `max_tree_lag`. If not set, batch production is paused.
+    pub tree_lag_policy: Option<TreeLagPolicy>,
+    /// Delay before opening a new L1 batch (in ms) per L1 batch of lag exceeding `max_tree_lag`;
+    /// used by the throttling policy.
+    pub tree_lag_throttle_delay_ms: Option<u64>,
+    /// If set, all inputs of the state keeper loop (L1 batch params, miniblock timestamps and transactions
+    /// in the order they're executed) are appended to a replay log at this path. The log can be fed back
+    /// to the state keeper to reproduce its behavior deterministically. Disabled by default.
+    pub replay_log_path: Option<String>,
+    /// Handling of anomalies in the order of storage writes in sealed miniblocks (e.g., a write whose read value
+    /// differs from the value written by the previous write to the same slot). If not set, anomalies are treated
+    /// as hard errors.
+    pub storage_log_anomaly_policy: Option<StorageLogAnomalyPolicy>,
+}
+
+/// Policy applied by the state keeper if the Merkle tree falls too far behind.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub enum TreeLagPolicy {
+    /// New L1 batches are not opened until the tree catches up.
+    Pause,
+    /// Opening new L1 batches is delayed proportionally to the excess lag.
+    Throttle,
+}
+
+/// Handling of storage write anomalies detected by the state keeper when sealing a miniblock.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub enum StorageLogAnomalyPolicy {
+    /// Sealing is aborted with the diagnostics of detected anomalies.
+    Strict,
+    /// Detected anomalies are logged and reported to metrics; sealing proceeds.
+    Lenient,
 }
 
 impl StateKeeperConfig {
@@ -106,6 +225,91 @@ impl StateKeeperConfig {
         envy_load("state_keeper", "CHAIN_STATE_KEEPER_")
     }
 
+    pub fn miniblock_min_timestamp_interval_sec(&self) -> u64 {
+        self.miniblock_min_timestamp_interval_sec
+            .unwrap_or(1)
+            .max(1)
+    }
+
+    pub fn miniblock_max_timestamp_drift_sec(&self) -> u64 {
+        self.miniblock_max_timestamp_drift_sec.unwrap_or(0)
+    }
+
+    pub fn miniblock_seal_concurrency(&self) -> usize {
+        self.miniblock_seal_concurrency.unwrap_or(1).max(1)
+    }
+
+    /// Returns the address collecting L2 fees, falling back to the operator address from the config.
+    pub fn fee_collector_addr(&self) -> Address {
+        self.fee_collector_addr.unwrap_or(self.fee_account_addr)
+    }
+
+    pub fn max_tx_output_size(&self) -> usize {
+        self.max_tx_output_size.unwrap_or(64 * 1_024)
+    }
+
+    pub fn max_tx_revert_reason_size(&self) -> usize {
+        self.max_tx_revert_reason_size.unwrap_or(4 * 1_024)
+    }
+
+    pub fn indexed_event_patterns(&self) -> &[String] {
+        self.indexed_event_patterns.as_deref().unwrap_or(&[])
+    }
+
+    pub fn save_vm_artifacts(&self) -> bool {
+        self.save_vm_artifacts.unwrap_or(false)
+    }
+
+    pub fn seal_on_priority_op_contracts(&self) -> &[Address] {
+        self.seal_on_priority_op_contracts.as_deref().unwrap_or(&[])
+    }
+
+    pub fn save_tx_access_sets(&self) -> bool {
+        self.save_tx_access_sets.unwrap_or(false)
+    }
+
+    pub fn tree_lag_policy(&self) -> TreeLagPolicy {
+        self.tree_lag_policy.unwrap_or(TreeLagPolicy::Pause)
+    }
+
+    pub fn tree_lag_throttle_delay(&self) -> Duration {
+        Duration::from_millis(self.tree_lag_throttle_delay_ms.unwrap_or(1_000))
+    }
+
+    pub fn storage_log_anomaly_policy(&self) -> StorageLogAnomalyPolicy {
+        self.storage_log_anomaly_policy
+            .unwrap_or(StorageLogAnomalyPolicy::Strict)
+    }
+
+    pub fn min_block_commit_deadline_ms(&self) -> u64 {
+        self.min_block_commit_deadline_ms
+            .unwrap_or(self.block_commit_deadline_ms / 2)
+    }
+
+    pub fn max_block_commit_deadline_ms(&self) -> u64 {
+        self.max_block_commit_deadline_ms
+            .unwrap_or(self.block_commit_deadline_ms * 2)
+    }
+
+    pub fn tx_execution_timeout(&self) -> Option<Duration> {
+        self.tx_execution_timeout_ms.map(Duration::from_millis)
+    }
+
+    pub fn protocol_version(&self) -> ProtocolVersionId {
+        ProtocolVersionId(self.protocol_version.unwrap_or(0))
+    }
+
+    pub fn max_factory_deps_per_tx(&self) -> usize {
+        self.max_factory_deps_per_tx
+            .map_or(MAX_NEW_FACTORY_DEPS, |limit| {
+                limit.min(MAX_NEW_FACTORY_DEPS)
+            })
+    }
+
+    pub fn bytecode_cache_size_mb(&self) -> usize {
+        self.bytecode_cache_size_mb.unwrap_or(128)
+    }
+
     pub fn base_system_contracts_hashes(&self) -> BaseSystemContractsHashes {
         BaseSystemContractsHashes {
             bootloader: self.bootloader_hash,
@@ -159,6 +363,33 @@ pub struct MempoolConfig {
     pub stuck_tx_timeout: u64,
     pub remove_stuck_txs: bool,
     pub delay_interval: u64,
+    /// Max number of nonces an L2 transaction may be ahead of its account nonce to be kept in the mempool.
+    /// If not set, transactions are kept regardless of the nonce gap.
+    pub max_nonce_lookahead: Option<u32>,
+    /// Min time the L1 gas price used to filter transactions loaded into the mempool is held
+    /// before it can be changed. Prevents the filter from oscillating during brief L1 gas price spikes.
+    pub filter_gas_price_min_hold_ms: Option<u64>,
+    /// Max relative change of the L1 gas price used to filter transactions loaded into the mempool
+    /// per update (e.g., 0.1 means that the price can change by at most 10% at a time).
+    /// If not set, the price follows the gas adjuster estimate without a limit.
+    pub filter_gas_price_max_step: Option<f64>,
+    /// Port of the internal HTTP server accepting batches of transactions from trusted frontends.
+    /// If not set, the server is not started.
+    pub bulk_ingestion_port: Option<u16>,
+    /// Address the bulk ingestion server binds to. If not set, the server only listens on the loopback interface.
+    pub bulk_ingestion_bind_address: Option<IpAddr>,
+    /// Bearer token that clients of the bulk ingestion server must present. Required if `bulk_ingestion_port` is set;
+    /// also used to authenticate requests to `snapshot_source_url`.
+    pub bulk_ingestion_auth_token: Option<String>,
+    /// Max number of transactions in a single batch submitted to the bulk ingestion server.
+    pub bulk_ingestion_max_batch_size: Option<usize>,
+    /// Max number of batches processed by the bulk ingestion server concurrently. Excess batches
+    /// are rejected, so that frontends can back off.
+    pub bulk_ingestion_max_concurrent_batches: Option<usize>,
+    /// Base URL of the internal server (see `bulk_ingestion_port`) of the primary sequencer. If set, the mempool
+    /// snapshot of the primary sequencer is imported on start, so that transactions accepted by it, but not sealed yet,
+    /// aren't dropped on failover.
+    pub snapshot_source_url: Option<String>,
 }
 
 impl MempoolConfig {
@@ -174,8 +405,36 @@ impl MempoolConfig {
         Duration::from_millis(self.delay_interval)
     }
 
+    pub fn filter_gas_price_min_hold(&self) -> Duration {
+        Duration::from_millis(self.filter_gas_price_min_hold_ms.unwrap_or(0))
+    }
+
+    pub fn bulk_ingestion_bind_address(&self) -> IpAddr {
+        self.bulk_ingestion_bind_address
+            .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+    }
+
+    /// Returns the auth token for the bulk ingestion server; empty tokens are treated as unset.
+    pub fn bulk_ingestion_auth_token(&self) -> Option<&str> {
+        self.bulk_ingestion_auth_token
+            .as_deref()
+            .filter(|token| !token.is_empty())
+    }
+
+    pub fn bulk_ingestion_max_batch_size(&self) -> usize {
+        self.bulk_ingestion_max_batch_size.unwrap_or(1_000)
+    }
+
+    pub fn bulk_ingestion_max_concurrent_batches(&self) -> usize {
+        self.bulk_ingestion_max_concurrent_batches.unwrap_or(4)
+    }
+
     pub fn from_env() -> Self {
-        envy_load("mempool", "CHAIN_MEMPOOL_")
+        let config: Self = envy_load("mempool", "CHAIN_MEMPOOL_");
+        if config.bulk_ingestion_port.is_some() && config.bulk_ingestion_auth_token().is_none() {
+            panic!("`bulk_ingestion_auth_token` must be set to a non-empty value if `bulk_ingestion_port` is set");
+        }
+        config
     }
 }
 
@@ -193,9 +452,19 @@ mod tests {
             },
             state_keeper: StateKeeperConfig {
                 transaction_slots: 50,
+                max_l2_txs_per_l1_batch: Some(40),
+                max_l2_txs_per_miniblock: Some(20),
+                miniblock_gas_limit: Some(80_000_000),
                 block_commit_deadline_ms: 2500,
+                l1_commit_cost_reference_wei: Some(10_000_000_000_000_000),
+                min_block_commit_deadline_ms: Some(1000),
+                max_block_commit_deadline_ms: None,
                 miniblock_commit_deadline_ms: 1000,
                 miniblock_seal_queue_capacity: 10,
+                miniblock_seal_concurrency: Some(4),
+                miniblock_timestamp_interval_sec: None,
+                miniblock_min_timestamp_interval_sec: Some(1),
+                miniblock_max_timestamp_drift_sec: Some(2),
                 max_single_tx_gas: 1_000_000,
                 max_allowed_l2_tx_gas_limit: 2_000_000_000,
                 close_block_at_eth_params_percentage: 0.2,
@@ -204,12 +473,38 @@ mod tests {
                 reject_tx_at_eth_params_percentage: 0.8,
                 reject_tx_at_geometry_percentage: 0.3,
                 fee_account_addr: addr("de03a0B5963f75f1C8485B355fF6D30f3093BDE7"),
+                fee_account_addr_path: Some("/etc/zksync/operator_address".to_owned()),
+                fee_collector_addr: Some(addr("b4a8f4a3d5e1b6c8a2a1c3e7f1d2b9a6c4e5f7a8")),
                 reject_tx_at_gas_percentage: 0.5,
                 fair_l2_gas_price: 250000000,
                 bootloader_hash: H256::from(&[254; 32]),
                 default_aa_hash: H256::from(&[254; 32]),
                 validation_computational_gas_limit: 10_000_000,
                 save_call_traces: false,
+                tx_execution_timeout_ms: Some(5000),
+                protocol_version: Some(3),
+                max_factory_deps_per_tx: Some(16),
+                max_factory_deps_size_per_tx: Some(500_000),
+                bytecode_cache_size_mb: Some(64),
+                max_batch_executor_restarts: Some(3),
+                max_tx_output_size: Some(65536),
+                max_tx_revert_reason_size: Some(1024),
+                indexed_event_patterns: Some(vec![
+                    "0x000000000000000000000000000000000000800a".to_owned(),
+                    "*:0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+                        .to_owned(),
+                ]),
+                save_vm_artifacts: Some(true),
+                seal_on_priority_op_contracts: Some(vec![addr(
+                    "0000000000000000000000000000000000008006",
+                )]),
+                save_tx_access_sets: Some(true),
+                max_base_fee_change_per_batch: Some(0.125),
+                max_tree_lag: Some(10),
+                tree_lag_policy: Some(TreeLagPolicy::Throttle),
+                tree_lag_throttle_delay_ms: Some(500),
+                replay_log_path: Some("./state_keeper_replay.ndjson".to_owned()),
+                storage_log_anomaly_policy: Some(StorageLogAnomalyPolicy::Lenient),
             },
             operations_manager: OperationsManagerConfig {
                 delay_interval: 100,
@@ -221,6 +516,15 @@ mod tests {
                 stuck_tx_timeout: 10,
                 remove_stuck_txs: true,
                 delay_interval: 100,
+                max_nonce_lookahead: Some(50),
+                filter_gas_price_min_hold_ms: Some(30_000),
+                filter_gas_price_max_step: Some(0.1),
+                bulk_ingestion_port: Some(3060),
+                bulk_ingestion_bind_address: Some("10.0.0.1".parse().unwrap()),
+                bulk_ingestion_auth_token: Some("secret".into()),
+                bulk_ingestion_max_batch_size: Some(500),
+                bulk_ingestion_max_concurrent_batches: Some(2),
+                snapshot_source_url: Some("http://127.0.0.1:3060".into()),
             },
             circuit_breaker: CircuitBreakerConfig {
                 sync_interval_ms: 1000,
@@ -237,7 +541,12 @@ CHAIN_ETH_NETWORK="localhost"
 CHAIN_ETH_ZKSYNC_NETWORK="localhost"
 CHAIN_ETH_ZKSYNC_NETWORK_ID=270
 CHAIN_STATE_KEEPER_TRANSACTION_SLOTS="50"
+CHAIN_STATE_KEEPER_MAX_L2_TXS_PER_L1_BATCH="40"
+CHAIN_STATE_KEEPER_MAX_L2_TXS_PER_MINIBLOCK="20"
+CHAIN_STATE_KEEPER_MINIBLOCK_GAS_LIMIT="80000000"
 CHAIN_STATE_KEEPER_FEE_ACCOUNT_ADDR="0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
+CHAIN_STATE_KEEPER_FEE_ACCOUNT_ADDR_PATH="/etc/zksync/operator_address"
+CHAIN_STATE_KEEPER_FEE_COLLECTOR_ADDR="0xb4a8f4a3d5e1b6c8a2a1c3e7f1d2b9a6c4e5f7a8"
 CHAIN_STATE_KEEPER_MAX_SINGLE_TX_GAS="1000000"
 CHAIN_STATE_KEEPER_MAX_ALLOWED_L2_TX_GAS_LIMIT="2000000000"
 CHAIN_STATE_KEEPER_CLOSE_BLOCK_AT_GEOMETRY_PERCENTAGE="0.5"
@@ -247,13 +556,36 @@ CHAIN_STATE_KEEPER_REJECT_TX_AT_GEOMETRY_PERCENTAGE="0.3"
 CHAIN_STATE_KEEPER_REJECT_TX_AT_ETH_PARAMS_PERCENTAGE="0.8"
 CHAIN_STATE_KEEPER_REJECT_TX_AT_GAS_PERCENTAGE="0.5"
 CHAIN_STATE_KEEPER_BLOCK_COMMIT_DEADLINE_MS="2500"
+CHAIN_STATE_KEEPER_L1_COMMIT_COST_REFERENCE_WEI="10000000000000000"
+CHAIN_STATE_KEEPER_MIN_BLOCK_COMMIT_DEADLINE_MS="1000"
 CHAIN_STATE_KEEPER_MINIBLOCK_COMMIT_DEADLINE_MS="1000"
 CHAIN_STATE_KEEPER_MINIBLOCK_SEAL_QUEUE_CAPACITY="10"
+CHAIN_STATE_KEEPER_MINIBLOCK_SEAL_CONCURRENCY="4"
+CHAIN_STATE_KEEPER_MINIBLOCK_MIN_TIMESTAMP_INTERVAL_SEC="1"
+CHAIN_STATE_KEEPER_MINIBLOCK_MAX_TIMESTAMP_DRIFT_SEC="2"
 CHAIN_STATE_KEEPER_FAIR_L2_GAS_PRICE="250000000"
 CHAIN_STATE_KEEPER_BOOTLOADER_HASH="0xfefefefefefefefefefefefefefefefefefefefefefefefefefefefefefefefe"
 CHAIN_STATE_KEEPER_DEFAULT_AA_HASH="0xfefefefefefefefefefefefefefefefefefefefefefefefefefefefefefefefe"
 CHAIN_STATE_KEEPER_VALIDATION_COMPUTATIONAL_GAS_LIMIT="10000000"
 CHAIN_STATE_KEEPER_SAVE_CALL_TRACES="false"
+CHAIN_STATE_KEEPER_TX_EXECUTION_TIMEOUT_MS="5000"
+CHAIN_STATE_KEEPER_PROTOCOL_VERSION="3"
+CHAIN_STATE_KEEPER_MAX_FACTORY_DEPS_PER_TX="16"
+CHAIN_STATE_KEEPER_MAX_FACTORY_DEPS_SIZE_PER_TX="500000"
+CHAIN_STATE_KEEPER_BYTECODE_CACHE_SIZE_MB="64"
+CHAIN_STATE_KEEPER_MAX_BATCH_EXECUTOR_RESTARTS="3"
+CHAIN_STATE_KEEPER_MAX_TX_OUTPUT_SIZE="65536"
+CHAIN_STATE_KEEPER_MAX_TX_REVERT_REASON_SIZE="1024"
+CHAIN_STATE_KEEPER_INDEXED_EVENT_PATTERNS="0x000000000000000000000000000000000000800a,*:0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+CHAIN_STATE_KEEPER_SAVE_VM_ARTIFACTS="true"
+CHAIN_STATE_KEEPER_SEAL_ON_PRIORITY_OP_CONTRACTS="0x0000000000000000000000000000000000008006"
+CHAIN_STATE_KEEPER_SAVE_TX_ACCESS_SETS="true"
+CHAIN_STATE_KEEPER_MAX_BASE_FEE_CHANGE_PER_BATCH="0.125"
+CHAIN_STATE_KEEPER_MAX_TREE_LAG="10"
+CHAIN_STATE_KEEPER_TREE_LAG_POLICY="Throttle"
+CHAIN_STATE_KEEPER_TREE_LAG_THROTTLE_DELAY_MS="500"
+CHAIN_STATE_KEEPER_REPLAY_LOG_PATH="./state_keeper_replay.ndjson"
+CHAIN_STATE_KEEPER_STORAGE_LOG_ANOMALY_POLICY="Lenient"
 CHAIN_OPERATIONS_MANAGER_DELAY_INTERVAL="100"
 CHAIN_MEMPOOL_SYNC_INTERVAL_MS="10"
 CHAIN_MEMPOOL_SYNC_BATCH_SIZE="1000"
@@ -261,6 +593,15 @@ CHAIN_MEMPOOL_STUCK_TX_TIMEOUT="10"
 CHAIN_MEMPOOL_REMOVE_STUCK_TXS="true"
 CHAIN_MEMPOOL_DELAY_INTERVAL="100"
 CHAIN_MEMPOOL_CAPACITY="1000000"
+CHAIN_MEMPOOL_MAX_NONCE_LOOKAHEAD="50"
+CHAIN_MEMPOOL_FILTER_GAS_PRICE_MIN_HOLD_MS="30000"
+CHAIN_MEMPOOL_FILTER_GAS_PRICE_MAX_STEP="0.1"
+CHAIN_MEMPOOL_BULK_INGESTION_PORT="3060"
+CHAIN_MEMPOOL_BULK_INGESTION_BIND_ADDRESS="10.0.0.1"
+CHAIN_MEMPOOL_BULK_INGESTION_AUTH_TOKEN="secret"
+CHAIN_MEMPOOL_BULK_INGESTION_MAX_BATCH_SIZE="500"
+CHAIN_MEMPOOL_BULK_INGESTION_MAX_CONCURRENT_BATCHES="2"
+CHAIN_MEMPOOL_SNAPSHOT_SOURCE_URL="http://127.0.0.1:3060"
 CHAIN_CIRCUIT_BREAKER_SYNC_INTERVAL_MS="1000"
 CHAIN_CIRCUIT_BREAKER_HTTP_REQ_MAX_RETRY_NUMBER="5"
 CHAIN_CIRCUIT_BREAKER_HTTP_REQ_RETRY_INTERVAL_SEC="2"
@@ -0,0 +1,120 @@
+// Built-in uses
+use std::fs;
+// External uses
+use serde::Deserialize;
+// Workspace uses
+use zksync_basic_types::Address;
+// Local uses
+use super::envy_load;
+
+/// Configuration of the chain registry, listing additional hyperchains run by the same server process.
+/// Each registered chain has its own Postgres database, state keeper and API ports; the L1 client
+/// and the gas adjuster are shared with the main chain configured via the environment.
+#[derive(Debug, Deserialize, Clone, PartialEq, Default)]
+pub struct ChainRegistryConfig {
+    /// Path to the JSON file with an array of [`HyperchainConfig`]s. If not set, only the main chain is run.
+    pub path: Option<String>,
+}
+
+/// Settings of a hyperchain from the chain registry. Settings not listed here are shared with the main chain.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct HyperchainConfig {
+    /// Human-readable name of the chain used in logs.
+    pub name: String,
+    /// L2 chain ID.
+    pub chain_id: u16,
+    /// URL of the master Postgres database of the chain. The database must be initialized with the genesis
+    /// of the chain in advance.
+    pub database_url: String,
+    /// URL of the replica Postgres database used by the API servers. If not set, the master database is used.
+    pub replica_database_url: Option<String>,
+    /// Port of the HTTP JSON-RPC server.
+    pub http_port: u16,
+    /// Port of the WebSocket JSON-RPC server.
+    pub ws_port: u16,
+    /// Path to the state keeper RocksDB cache. Must differ from paths used by other chains.
+    pub state_keeper_db_path: String,
+    /// Bucket URL (for GCS object stores) or base path (for file-backed object stores) used to store artifacts
+    /// of the chain, such as VM artifacts. Required if the state keeper saves VM artifacts; must differ
+    /// from locations used by other chains.
+    pub object_store_location: Option<String>,
+    /// Address receiving fees on this chain. If not set, the address of the main chain is used.
+    pub fee_account_addr: Option<Address>,
+}
+
+impl HyperchainConfig {
+    pub fn replica_database_url(&self) -> &str {
+        self.replica_database_url
+            .as_deref()
+            .unwrap_or(&self.database_url)
+    }
+}
+
+impl ChainRegistryConfig {
+    pub fn from_env() -> Self {
+        envy_load("chain_registry", "CHAIN_REGISTRY_")
+    }
+
+    /// Loads the registered chains. Panics if the registry file cannot be read or parsed.
+    pub fn load_chains(&self) -> Vec<HyperchainConfig> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return vec![],
+        };
+        let contents = fs::read_to_string(path)
+            .unwrap_or_else(|err| panic!("Cannot read chain registry at {}: {}", path, err));
+        serde_json::from_str(&contents)
+            .unwrap_or_else(|err| panic!("Cannot parse chain registry at {}: {}", path, err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::{addr, set_env};
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+            CHAIN_REGISTRY_PATH="./etc/chains.json"
+        "#;
+        set_env(config);
+
+        let actual = ChainRegistryConfig::from_env();
+        assert_eq!(actual.path.as_deref(), Some("./etc/chains.json"));
+    }
+
+    #[test]
+    fn parsing_chains() {
+        let chains = r#"[{
+            "name": "dev-1",
+            "chain_id": 271,
+            "database_url": "postgres://postgres@localhost/zksync_dev_1",
+            "http_port": 3060,
+            "ws_port": 3061,
+            "state_keeper_db_path": "./db/dev-1/state_keeper",
+            "object_store_location": "./artifacts/dev-1",
+            "fee_account_addr": "0xde03a0b5963f75f1c8485b355ff6d30f3093bde7"
+        }]"#;
+        let chains: Vec<HyperchainConfig> = serde_json::from_str(chains).unwrap();
+
+        assert_eq!(
+            chains,
+            [HyperchainConfig {
+                name: "dev-1".to_owned(),
+                chain_id: 271,
+                database_url: "postgres://postgres@localhost/zksync_dev_1".to_owned(),
+                replica_database_url: None,
+                http_port: 3060,
+                ws_port: 3061,
+                state_keeper_db_path: "./db/dev-1/state_keeper".to_owned(),
+                object_store_location: Some("./artifacts/dev-1".to_owned()),
+                fee_account_addr: Some(addr("de03a0b5963f75f1c8485b355ff6d30f3093bde7")),
+            }]
+        );
+        assert_eq!(
+            chains[0].replica_database_url(),
+            "postgres://postgres@localhost/zksync_dev_1"
+        );
+    }
+}
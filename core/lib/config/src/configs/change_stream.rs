@@ -0,0 +1,78 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use serde::Deserialize;
+// Local uses
+use super::envy_load;
+
+/// Configuration for the change data capture stream, which publishes sealed miniblocks
+/// (transactions, receipts with logs, and storage writes) to a NATS JetStream subject.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ChangeStreamConfig {
+    /// URL of the NATS server.
+    pub nats_url: String,
+    /// Subject to which miniblock messages are published. The subject must be bound to a JetStream stream,
+    /// since the publisher waits for acknowledgements from the broker.
+    pub subject: String,
+    /// Name under which the publishing offset is persisted. Publishers with different names
+    /// (e.g., publishing to different brokers) track their offsets independently.
+    pub publisher_name: Option<String>,
+    /// Miniblock to start publishing from if the publisher doesn't have a persisted offset yet.
+    /// If not set, publishing starts from the next sealed miniblock.
+    pub start_miniblock: Option<u32>,
+    /// Interval between checks for new miniblocks if the publisher doesn't run together with the state keeper (in ms).
+    pub poll_interval_ms: Option<u64>,
+    /// Delay before retrying to publish a miniblock after an error (in ms).
+    pub retry_interval_ms: Option<u64>,
+}
+
+impl ChangeStreamConfig {
+    pub fn from_env() -> Self {
+        envy_load("change_stream", "CHANGE_STREAM_")
+    }
+
+    pub fn publisher_name(&self) -> &str {
+        self.publisher_name.as_deref().unwrap_or("default")
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms.unwrap_or(1_000))
+    }
+
+    pub fn retry_interval(&self) -> Duration {
+        Duration::from_millis(self.retry_interval_ms.unwrap_or(5_000))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> ChangeStreamConfig {
+        ChangeStreamConfig {
+            nats_url: "nats://127.0.0.1:4222".to_owned(),
+            subject: "zksync.miniblocks".to_owned(),
+            publisher_name: Some("indexer".to_owned()),
+            start_miniblock: Some(100),
+            poll_interval_ms: Some(500),
+            retry_interval_ms: Some(2_000),
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+            CHANGE_STREAM_NATS_URL="nats://127.0.0.1:4222"
+            CHANGE_STREAM_SUBJECT="zksync.miniblocks"
+            CHANGE_STREAM_PUBLISHER_NAME="indexer"
+            CHANGE_STREAM_START_MINIBLOCK=100
+            CHANGE_STREAM_POLL_INTERVAL_MS=500
+            CHANGE_STREAM_RETRY_INTERVAL_MS=2000
+        "#;
+        set_env(config);
+
+        let actual = ChangeStreamConfig::from_env();
+        assert_eq!(actual, expected_config());
+    }
+}
@@ -24,8 +24,11 @@ pub struct ContractsConfig {
     pub l2_erc20_bridge_addr: Address,
     pub l1_weth_bridge_proxy_addr: Option<Address>,
     pub l2_weth_bridge_addr: Option<Address>,
+    pub l2_weth_token_proxy_addr: Option<Address>,
     pub l1_allow_list_addr: Address,
     pub l2_testnet_paymaster_addr: Option<Address>,
+    /// Address of the L2 contract asserting block timestamp ranges, if it's deployed on the chain.
+    pub l2_timestamp_asserter_addr: Option<Address>,
 }
 
 impl ContractsConfig {
@@ -60,7 +63,9 @@ mod tests {
             l1_allow_list_addr: addr("8656770FA78c830456B00B4fFCeE6b1De0e1b888"),
             l1_weth_bridge_proxy_addr: Some(addr("8656770FA78c830456B00B4fFCeE6b1De0e1b888")),
             l2_weth_bridge_addr: Some(addr("8656770FA78c830456B00B4fFCeE6b1De0e1b888")),
+            l2_weth_token_proxy_addr: Some(addr("5E6D086F5eC079ADFF4FB3774CDf3e8D6a34F7E9")),
             l2_testnet_paymaster_addr: Some(addr("FC073319977e314F251EAE6ae6bE76B0B3BAeeCF")),
+            l2_timestamp_asserter_addr: Some(addr("0A2d2B6E1F4c8d3e5a7b9C0D1E2F3a4B5c6D7e8F")),
         }
     }
 
@@ -84,7 +89,9 @@ CONTRACTS_L1_ERC20_BRIDGE_IMPL_ADDR="0x8656770FA78c830456B00B4fFCeE6b1De0e1b888"
 CONTRACTS_L2_ERC20_BRIDGE_ADDR="0x8656770FA78c830456B00B4fFCeE6b1De0e1b888"
 CONTRACTS_L1_WETH_BRIDGE_PROXY_ADDR="0x8656770FA78c830456B00B4fFCeE6b1De0e1b888"
 CONTRACTS_L2_WETH_BRIDGE_ADDR="0x8656770FA78c830456B00B4fFCeE6b1De0e1b888"
+CONTRACTS_L2_WETH_TOKEN_PROXY_ADDR="0x5E6D086F5eC079ADFF4FB3774CDf3e8D6a34F7E9"
 CONTRACTS_L2_TESTNET_PAYMASTER_ADDR="FC073319977e314F251EAE6ae6bE76B0B3BAeeCF"
+CONTRACTS_L2_TIMESTAMP_ASSERTER_ADDR="0x0A2d2B6E1F4c8d3e5a7b9C0D1E2F3a4B5c6D7e8F"
         "#;
         set_env(config);
 
@@ -2,6 +2,46 @@ use serde::Deserialize;
 
 use std::{env, str::FromStr, time::Duration};
 
+/// Mode of the node determining which historical data is kept in Postgres.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum NodeMode {
+    /// All historical data is kept.
+    Archive,
+    /// State is kept, but transactions, events and call traces of L1 batches older than
+    /// `history_retention_l1_batches` are pruned.
+    Full,
+}
+
+impl FromStr for NodeMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Archive" | "archive" => Ok(Self::Archive),
+            "Full" | "full" => Ok(Self::Full),
+            _ => Err(format!("Unknown node mode: {s}")),
+        }
+    }
+}
+
+/// Codec applied to protected data before writing it to the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ProtectedDataCodec {
+    /// Data is erased; it cannot be recovered afterwards.
+    Redact,
+}
+
+impl FromStr for ProtectedDataCodec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Redact" | "redact" => Ok(Self::Redact),
+            _ => Err(format!("Unknown protected data codec: {s}")),
+        }
+    }
+}
+
 /// Database configuration.
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct DBConfig {
@@ -20,6 +60,13 @@ pub struct DBConfig {
     pub backup_interval_ms: u64,
     /// Maximum number of blocks to be processed by the Merkle tree at a time.
     pub max_block_batch: usize,
+    /// Mode of the node determining whether historical data is pruned.
+    pub node_mode: NodeMode,
+    /// Number of the latest executed L1 batches which history is retained in the full mode.
+    pub history_retention_l1_batches: u32,
+    /// Codec applied to event data. If not set, event data is stored as is. Codecs for transaction calldata
+    /// can only be installed programmatically, since calldata must be decodable to re-execute transactions.
+    pub events_data_codec: Option<ProtectedDataCodec>,
 }
 
 impl Default for DBConfig {
@@ -32,6 +79,9 @@ impl Default for DBConfig {
             backup_count: 5,
             backup_interval_ms: 60_000,
             max_block_batch: 100,
+            node_mode: NodeMode::Archive,
+            history_retention_l1_batches: 100_000,
+            events_data_codec: None,
         }
     }
 }
@@ -60,6 +110,15 @@ impl DBConfig {
         if let Some(size) = Self::parse_env_var("DATABASE_MAX_BLOCK_BATCH") {
             config.max_block_batch = size;
         }
+        if let Some(mode) = Self::parse_env_var("DATABASE_NODE_MODE") {
+            config.node_mode = mode;
+        }
+        if let Some(retention) = Self::parse_env_var("DATABASE_HISTORY_RETENTION_L1_BATCHES") {
+            config.history_retention_l1_batches = retention;
+        }
+        if let Some(codec) = Self::parse_env_var("DATABASE_EVENTS_DATA_CODEC") {
+            config.events_data_codec = Some(codec);
+        }
         config
     }
 
@@ -95,6 +154,14 @@ impl DBConfig {
     pub fn max_block_batch(&self) -> usize {
         self.max_block_batch
     }
+
+    pub fn node_mode(&self) -> NodeMode {
+        self.node_mode
+    }
+
+    pub fn history_retention_l1_batches(&self) -> u32 {
+        self.history_retention_l1_batches
+    }
 }
 
 #[cfg(test)]
@@ -112,6 +179,8 @@ DATABASE_NEW_MERKLE_TREE_THROTTLE_MS=0
 DATABASE_BACKUP_COUNT=5
 DATABASE_BACKUP_INTERVAL_MS=60000
 DATABASE_MAX_BLOCK_BATCH=100
+DATABASE_NODE_MODE="Archive"
+DATABASE_HISTORY_RETENTION_L1_BATCHES=100000
         "#;
         set_env(config);
 
@@ -119,6 +188,22 @@ DATABASE_MAX_BLOCK_BATCH=100
         assert_eq!(actual, DBConfig::default());
     }
 
+    #[test]
+    fn parsing_node_mode() {
+        assert_eq!("Full".parse::<NodeMode>(), Ok(NodeMode::Full));
+        assert_eq!("archive".parse::<NodeMode>(), Ok(NodeMode::Archive));
+        assert!("pruned".parse::<NodeMode>().is_err());
+    }
+
+    #[test]
+    fn parsing_protected_data_codec() {
+        assert_eq!(
+            "Redact".parse::<ProtectedDataCodec>(),
+            Ok(ProtectedDataCodec::Redact)
+        );
+        assert!("encrypt".parse::<ProtectedDataCodec>().is_err());
+    }
+
     /// Checks the correctness of the config helper methods.
     #[test]
     fn methods() {
@@ -1,5 +1,7 @@
 // External uses
 use serde::Deserialize;
+// Built-in uses
+use std::iter;
 // Local uses
 use super::envy_load;
 
@@ -10,6 +12,12 @@ pub struct ETHClientConfig {
     pub chain_id: u64,
     /// Address of the Ethereum node API.
     pub web3_url: String,
+    /// Addresses of fallback Ethereum node APIs. Requests are sent to fallback nodes if the primary node
+    /// is unavailable.
+    pub fallback_web3_urls: Option<Vec<String>>,
+    /// WebSocket address of the Ethereum node API. If set, `GasAdjuster` subscribes to new L1 blocks
+    /// instead of polling the node.
+    pub web3_ws_url: Option<String>,
 }
 
 impl ETHClientConfig {
@@ -17,12 +25,18 @@ impl ETHClientConfig {
         let config: Self = envy_load("eth_client", "ETH_CLIENT_");
         if config.web3_url.find(',').is_some() {
             panic!(
-                "Multiple web3 URLs aren't supported anymore. Provided invalid value: {}",
+                "Fallback web3 URLs must be specified in `fallback_web3_urls`. Provided invalid value: {}",
                 config.web3_url
             );
         }
         config
     }
+
+    /// Returns addresses of all configured Ethereum node APIs, starting from the primary one.
+    pub fn web3_urls(&self) -> impl Iterator<Item = &str> + '_ {
+        let fallback_urls = self.fallback_web3_urls.iter().flatten();
+        iter::once(self.web3_url.as_str()).chain(fallback_urls.map(String::as_str))
+    }
 }
 
 #[cfg(test)]
@@ -34,6 +48,11 @@ mod tests {
         ETHClientConfig {
             chain_id: 9,
             web3_url: "http://127.0.0.1:8545".into(),
+            fallback_web3_urls: Some(vec![
+                "http://127.0.0.1:8547".into(),
+                "http://127.0.0.1:8549".into(),
+            ]),
+            web3_ws_url: Some("ws://127.0.0.1:8546".into()),
         }
     }
 
@@ -42,6 +61,8 @@ mod tests {
         let config = r#"
 ETH_CLIENT_CHAIN_ID="9"
 ETH_CLIENT_WEB3_URL="http://127.0.0.1:8545"
+ETH_CLIENT_FALLBACK_WEB3_URLS="http://127.0.0.1:8547,http://127.0.0.1:8549"
+ETH_CLIENT_WEB3_WS_URL="ws://127.0.0.1:8546"
         "#;
         set_env(config);
 
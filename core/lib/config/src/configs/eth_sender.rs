@@ -3,7 +3,7 @@ use std::time::Duration;
 // External uses
 use serde::Deserialize;
 // Workspace uses
-use zksync_basic_types::H256;
+use zksync_basic_types::{H256, U256};
 // Local uses
 use super::envy_load;
 
@@ -32,6 +32,17 @@ pub enum ProofSendingMode {
     SkipEveryProof,
 }
 
+/// Way to publish pubdata of committed L1 batches.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub enum PubdataSendingMode {
+    /// Always publish pubdata in calldata.
+    Calldata,
+    /// Publish pubdata in EIP-4844 blobs if they are supported by L1 and the Ethereum client.
+    Blobs,
+    /// Choose between calldata and blobs for each commit transaction based on the current L1 fees.
+    Auto,
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct SenderConfig {
     pub aggregated_proof_sizes: Vec<usize>,
@@ -60,8 +71,23 @@ pub struct SenderConfig {
     /// Note that this number must be slightly higher than the one set on the contract,
     /// because the contract uses block.timestamp which lags behind the clock time.
     pub l1_batch_min_age_before_execute_seconds: Option<u64>,
+    /// Minimum delay in seconds between the confirmation of the proof for an L1 batch on L1 and the execution
+    /// of the batch (aka the execution window). If not set, L1 batches are executed as soon as they are proven.
+    /// The delay can be skipped for specific L1 batches by the operator via the `admin` Web3 namespace.
+    pub l1_batch_execution_delay_seconds: Option<u64>,
     // Max acceptable fee for sending tx it acts as a safeguard to prevent sending tx with very high fees.
     pub max_acceptable_priority_fee_in_gwei: u64,
+    /// The way to publish pubdata of committed L1 batches. If not set, pubdata is published in calldata.
+    pub pubdata_sending_mode: Option<PubdataSendingMode>,
+    /// If set, aggregated operations are not saved and sent to L1. Instead, their transactions are simulated
+    /// via `eth_call`, and calldata sizes, gas estimates and failures are logged. Defaults to `false`.
+    pub dry_run: Option<bool>,
+    /// If set, aggregation of L1 operations is paused while the ETH balance of the operator account is below
+    /// this value (in gwei). Transactions that are already created are still sent.
+    pub operator_balance_floor_gwei: Option<u64>,
+    /// An alert is logged if the projected runway of the operator balance (i.e., the time until the balance
+    /// is depleted at the recent spending rate) is below this value (in hours). Defaults to 24 hours.
+    pub operator_runway_alert_hours: Option<u64>,
 }
 
 impl SenderConfig {
@@ -69,6 +95,30 @@ impl SenderConfig {
     pub fn tx_poll_period(&self) -> Duration {
         Duration::from_secs(self.tx_poll_period)
     }
+    pub fn pubdata_sending_mode(&self) -> PubdataSendingMode {
+        self.pubdata_sending_mode
+            .unwrap_or(PubdataSendingMode::Calldata)
+    }
+
+    pub fn dry_run(&self) -> bool {
+        self.dry_run.unwrap_or(false)
+    }
+
+    /// Returns the operator balance floor in wei.
+    pub fn operator_balance_floor(&self) -> Option<U256> {
+        self.operator_balance_floor_gwei
+            .map(|floor| U256::from(floor) * U256::exp10(9))
+    }
+
+    pub fn operator_runway_alert_threshold(&self) -> Duration {
+        Duration::from_secs(self.operator_runway_alert_hours.unwrap_or(24) * 3_600)
+    }
+
+    pub fn l1_batch_execution_delay(&self) -> Option<Duration> {
+        self.l1_batch_execution_delay_seconds
+            .map(Duration::from_secs)
+    }
+
     /// Converts `self.aggregate_tx_poll_period` into `Duration`.
     pub fn aggregate_tx_poll_period(&self) -> Duration {
         Duration::from_secs(self.aggregate_tx_poll_period)
@@ -145,7 +195,12 @@ mod tests {
                 max_txs_in_flight: 3,
                 proof_sending_mode: ProofSendingMode::SkipEveryProof,
                 l1_batch_min_age_before_execute_seconds: Some(1000),
+                l1_batch_execution_delay_seconds: Some(75600),
                 max_acceptable_priority_fee_in_gwei: 100_000_000_000,
+                pubdata_sending_mode: Some(PubdataSendingMode::Auto),
+                dry_run: Some(true),
+                operator_balance_floor_gwei: Some(500_000_000),
+                operator_runway_alert_hours: Some(48),
             },
             gas_adjuster: GasAdjusterConfig {
                 default_priority_fee_per_gas: 20000000000,
@@ -187,7 +242,12 @@ ETH_SENDER_SENDER_TIMESTAMP_CRITERIA_MAX_ALLOWED_LAG="30"
 ETH_SENDER_SENDER_MAX_AGGREGATED_TX_GAS="4000000"
 ETH_SENDER_SENDER_MAX_ETH_TX_DATA_SIZE="120000"
 ETH_SENDER_SENDER_L1_BATCH_MIN_AGE_BEFORE_EXECUTE_SECONDS="1000"
+ETH_SENDER_SENDER_L1_BATCH_EXECUTION_DELAY_SECONDS="75600"
 ETH_SENDER_SENDER_MAX_ACCEPTABLE_PRIORITY_FEE_IN_GWEI="100000000000"
+ETH_SENDER_SENDER_PUBDATA_SENDING_MODE="Auto"
+ETH_SENDER_SENDER_DRY_RUN="true"
+ETH_SENDER_SENDER_OPERATOR_BALANCE_FLOOR_GWEI="500000000"
+ETH_SENDER_SENDER_OPERATOR_RUNWAY_ALERT_HOURS="48"
         "#;
         set_env(config);
 
@@ -208,5 +268,9 @@ ETH_SENDER_SENDER_MAX_ACCEPTABLE_PRIORITY_FEE_IN_GWEI="100000000000"
             config.sender.tx_poll_period(),
             Duration::from_secs(config.sender.tx_poll_period)
         );
+        assert_eq!(
+            config.sender.operator_balance_floor(),
+            Some(U256::exp10(17) * 5)
+        );
     }
 }
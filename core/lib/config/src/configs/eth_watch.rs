@@ -14,6 +14,15 @@ pub struct ETHWatchConfig {
     /// How often we want to poll the Ethereum node.
     /// Value in milliseconds.
     pub eth_node_poll_interval: u64,
+    /// Maximum number of L1 blocks queried for events in a single `eth_getLogs` request.
+    /// Larger block ranges (e.g., when catching up after downtime) are processed in chunks of this size.
+    pub max_blocks_per_query: Option<u64>,
+    /// Max L2 gas limit of an accepted priority operation. Operations with a larger gas limit are rejected
+    /// instead of being added to the mempool. If not specified, the gas limit is not checked.
+    pub max_priority_op_gas_limit: Option<u64>,
+    /// Max calldata size of an accepted priority operation in bytes. Operations with larger calldata
+    /// are rejected instead of being added to the mempool. If not specified, the calldata size is not checked.
+    pub max_priority_op_calldata_size: Option<usize>,
 }
 
 impl ETHWatchConfig {
@@ -25,6 +34,10 @@ impl ETHWatchConfig {
     pub fn poll_interval(&self) -> Duration {
         Duration::from_millis(self.eth_node_poll_interval)
     }
+
+    pub fn max_blocks_per_query(&self) -> u64 {
+        self.max_blocks_per_query.unwrap_or(10_000)
+    }
 }
 
 #[cfg(test)]
@@ -36,6 +49,9 @@ mod tests {
         ETHWatchConfig {
             confirmations_for_eth_event: Some(0),
             eth_node_poll_interval: 300,
+            max_blocks_per_query: Some(1000),
+            max_priority_op_gas_limit: Some(80_000_000),
+            max_priority_op_calldata_size: Some(100_000),
         }
     }
 
@@ -44,6 +60,9 @@ mod tests {
         let config = r#"
 ETH_WATCH_CONFIRMATIONS_FOR_ETH_EVENT="0"
 ETH_WATCH_ETH_NODE_POLL_INTERVAL="300"
+ETH_WATCH_MAX_BLOCKS_PER_QUERY="1000"
+ETH_WATCH_MAX_PRIORITY_OP_GAS_LIMIT="80000000"
+ETH_WATCH_MAX_PRIORITY_OP_CALLDATA_SIZE="100000"
         "#;
         set_env(config);
 
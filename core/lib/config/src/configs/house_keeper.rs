@@ -1,5 +1,7 @@
 use serde::Deserialize;
 
+use std::time::Duration;
+
 use super::envy_load;
 
 /// Configuration for the house keeper.
@@ -17,12 +19,53 @@ pub struct HouseKeeperConfig {
     pub fri_witness_generator_job_retrying_interval_ms: u64,
     pub prover_db_pool_size: u32,
     pub fri_prover_stats_reporting_interval_ms: u64,
+    pub prover_job_monitoring_interval_ms: u64,
+    pub partition_management_interval_ms: u64,
+    /// Number of miniblocks covered by a single partition of `events` and `storage_logs` tables.
+    pub miniblock_partition_size: u32,
+    /// Number of partitions created in advance of the last sealed miniblock.
+    pub miniblock_partitions_ahead: u32,
+    /// Number of miniblocks to keep in attached partitions of the `events` table. If not set, partitions
+    /// are never detached and archived. `storage_logs` partitions are always retained.
+    pub miniblock_partitions_retention: Option<u32>,
+    /// Miniblocks for which materialized state snapshots are created, e.g. heights frequently used in
+    /// historical `eth_call`s.
+    pub state_snapshot_miniblocks: Option<Vec<u32>>,
+    pub db_maintenance_interval_ms: u64,
+    /// Start of the daily off-peak window (UTC hour, inclusive) during which `ANALYZE` / `VACUUM`
+    /// are scheduled for hot tables.
+    pub db_maintenance_window_start_hour: u32,
+    /// End of the daily off-peak window (UTC hour, exclusive). May be less than the start hour,
+    /// in which case the window spans midnight.
+    pub db_maintenance_window_end_hour: u32,
+    /// Min ratio of rows modified since the last analysis to live rows to schedule `ANALYZE`.
+    pub db_analyze_modified_rows_ratio: f64,
+    /// Min ratio of dead rows to all rows to schedule `VACUUM`.
+    pub db_vacuum_dead_rows_ratio: f64,
+    /// Interval between pruning iterations for nodes running in the full mode.
+    pub history_pruning_interval_ms: u64,
+    /// Interval between iterations computing events Bloom filters for historical L1 batches.
+    pub events_bloom_backfill_interval_ms: u64,
+    /// Interval between iterations removing FRI prover artifacts from the object store.
+    pub prover_artifacts_cleaning_interval_ms: u64,
+    /// Grace period after an L1 batch proof is verified on L1 before the batch prover artifacts are removed.
+    pub prover_artifacts_retention_sec: u64,
 }
 
 impl HouseKeeperConfig {
     pub fn from_env() -> Self {
         envy_load("house_keeper", "HOUSE_KEEPER_")
     }
+
+    pub fn prover_artifacts_retention(&self) -> Duration {
+        Duration::from_secs(self.prover_artifacts_retention_sec)
+    }
+
+    pub fn state_snapshot_miniblocks(&self) -> &[u32] {
+        self.state_snapshot_miniblocks
+            .as_deref()
+            .unwrap_or_default()
+    }
 }
 
 #[cfg(test)]
@@ -45,6 +88,21 @@ mod tests {
             fri_witness_generator_job_retrying_interval_ms: 30_000,
             prover_db_pool_size: 2,
             fri_prover_stats_reporting_interval_ms: 30_000,
+            prover_job_monitoring_interval_ms: 60_000,
+            partition_management_interval_ms: 60_000,
+            miniblock_partition_size: 1_000_000,
+            miniblock_partitions_ahead: 2,
+            miniblock_partitions_retention: Some(10_000_000),
+            state_snapshot_miniblocks: Some(vec![1_000_000, 2_500_000]),
+            db_maintenance_interval_ms: 600_000,
+            db_maintenance_window_start_hour: 2,
+            db_maintenance_window_end_hour: 6,
+            db_analyze_modified_rows_ratio: 0.05,
+            db_vacuum_dead_rows_ratio: 0.2,
+            history_pruning_interval_ms: 60_000,
+            events_bloom_backfill_interval_ms: 10_000,
+            prover_artifacts_cleaning_interval_ms: 300_000,
+            prover_artifacts_retention_sec: 604_800,
         }
     }
 
@@ -63,6 +121,21 @@ HOUSE_KEEPER_FRI_PROVER_JOB_RETRYING_INTERVAL_MS="30000"
 HOUSE_KEEPER_FRI_WITNESS_GENERATOR_JOB_RETRYING_INTERVAL_MS="30000"
 HOUSE_KEEPER_PROVER_DB_POOL_SIZE="2"
 HOUSE_KEEPER_FRI_PROVER_STATS_REPORTING_INTERVAL_MS="30000"
+HOUSE_KEEPER_PROVER_JOB_MONITORING_INTERVAL_MS="60000"
+HOUSE_KEEPER_PARTITION_MANAGEMENT_INTERVAL_MS="60000"
+HOUSE_KEEPER_MINIBLOCK_PARTITION_SIZE="1000000"
+HOUSE_KEEPER_MINIBLOCK_PARTITIONS_AHEAD="2"
+HOUSE_KEEPER_MINIBLOCK_PARTITIONS_RETENTION="10000000"
+HOUSE_KEEPER_STATE_SNAPSHOT_MINIBLOCKS="1000000,2500000"
+HOUSE_KEEPER_DB_MAINTENANCE_INTERVAL_MS="600000"
+HOUSE_KEEPER_DB_MAINTENANCE_WINDOW_START_HOUR="2"
+HOUSE_KEEPER_DB_MAINTENANCE_WINDOW_END_HOUR="6"
+HOUSE_KEEPER_DB_ANALYZE_MODIFIED_ROWS_RATIO="0.05"
+HOUSE_KEEPER_DB_VACUUM_DEAD_ROWS_RATIO="0.2"
+HOUSE_KEEPER_HISTORY_PRUNING_INTERVAL_MS="60000"
+HOUSE_KEEPER_EVENTS_BLOOM_BACKFILL_INTERVAL_MS="10000"
+HOUSE_KEEPER_PROVER_ARTIFACTS_CLEANING_INTERVAL_MS="300000"
+HOUSE_KEEPER_PROVER_ARTIFACTS_RETENTION_SEC="604800"
         "#;
         set_env(config);
         let actual = HouseKeeperConfig::from_env();
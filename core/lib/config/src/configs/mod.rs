@@ -1,14 +1,14 @@
 // Public re-exports
 pub use self::{
-    alerts::AlertsConfig, api::ApiConfig, chain::ChainConfig,
-    circuit_synthesizer::CircuitSynthesizerConfig, contract_verifier::ContractVerifierConfig,
-    contracts::ContractsConfig, database::DBConfig, eth_client::ETHClientConfig,
-    eth_sender::ETHSenderConfig, eth_sender::GasAdjusterConfig, eth_watch::ETHWatchConfig,
-    fetcher::FetcherConfig, fri_prover::FriProverConfig,
+    alerts::AlertsConfig, api::ApiConfig, chain::ChainConfig, chain_registry::ChainRegistryConfig,
+    change_stream::ChangeStreamConfig, circuit_synthesizer::CircuitSynthesizerConfig,
+    contract_verifier::ContractVerifierConfig, contracts::ContractsConfig, database::DBConfig,
+    eth_client::ETHClientConfig, eth_sender::ETHSenderConfig, eth_sender::GasAdjusterConfig,
+    eth_watch::ETHWatchConfig, fetcher::FetcherConfig, fri_prover::FriProverConfig,
     fri_witness_generator::FriWitnessGeneratorConfig, nfs::NfsConfig,
-    object_store::ObjectStoreConfig, prover::ProverConfig, prover::ProverConfigs,
-    prover_group::ProverGroupConfig, utils::PrometheusConfig,
-    witness_generator::WitnessGeneratorConfig,
+    object_store::ObjectStoreConfig, proof_data_handler::ProofDataHandlerConfig,
+    prover::ProverConfig, prover::ProverConfigs, prover_group::ProverGroupConfig,
+    utils::PrometheusConfig, witness_generator::WitnessGeneratorConfig,
 };
 
 use serde::de::DeserializeOwned;
@@ -16,6 +16,8 @@ use serde::de::DeserializeOwned;
 pub mod alerts;
 pub mod api;
 pub mod chain;
+pub mod chain_registry;
+pub mod change_stream;
 pub mod circuit_synthesizer;
 pub mod contract_verifier;
 pub mod contracts;
@@ -30,6 +32,7 @@ pub mod fri_witness_generator;
 pub mod house_keeper;
 pub mod nfs;
 pub mod object_store;
+pub mod proof_data_handler;
 pub mod prover;
 pub mod prover_group;
 pub mod utils;
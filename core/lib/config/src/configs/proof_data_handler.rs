@@ -0,0 +1,66 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use serde::Deserialize;
+// Workspace uses
+use zksync_basic_types::H256;
+// Local uses
+use super::envy_load;
+
+/// Configuration for the proof data handler, an HTTP server used by TEE-based provers
+/// to fetch L1 batch inputs and submit attested execution results.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ProofDataHandlerConfig {
+    /// Port to which the HTTP server is bound.
+    pub http_port: u16,
+    /// Time after which a batch picked by a prover without a submitted proof can be picked again (in s).
+    pub proof_generation_timeout_in_secs: u16,
+    /// Enclave measurements (`MRENCLAVE`) accepted in attestations. If not set or empty, all attestations are rejected.
+    pub tee_allowed_mrenclaves: Option<Vec<H256>>,
+    /// Path to the DER or PEM-encoded root certificate (e.g., Intel SGX Root CA) which PCK certificate chains
+    /// in attestation quotes must end with. If not set, all attestations are rejected.
+    pub tee_root_ca_path: Option<String>,
+}
+
+impl ProofDataHandlerConfig {
+    pub fn from_env() -> Self {
+        envy_load("proof_data_handler", "PROOF_DATA_HANDLER_")
+    }
+
+    pub fn proof_generation_timeout(&self) -> Duration {
+        Duration::from_secs(self.proof_generation_timeout_in_secs as u64)
+    }
+
+    pub fn tee_allowed_mrenclaves(&self) -> &[H256] {
+        self.tee_allowed_mrenclaves.as_deref().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> ProofDataHandlerConfig {
+        ProofDataHandlerConfig {
+            http_port: 3320,
+            proof_generation_timeout_in_secs: 18000,
+            tee_allowed_mrenclaves: Some(vec![H256::repeat_byte(0x11)]),
+            tee_root_ca_path: Some("/etc/sgx/root_ca.pem".to_owned()),
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+            PROOF_DATA_HANDLER_HTTP_PORT=3320
+            PROOF_DATA_HANDLER_PROOF_GENERATION_TIMEOUT_IN_SECS=18000
+            PROOF_DATA_HANDLER_TEE_ALLOWED_MRENCLAVES=0x1111111111111111111111111111111111111111111111111111111111111111
+            PROOF_DATA_HANDLER_TEE_ROOT_CA_PATH=/etc/sgx/root_ca.pem
+        "#;
+        set_env(config);
+
+        let actual = ProofDataHandlerConfig::from_env();
+        assert_eq!(actual, expected_config());
+    }
+}
@@ -78,6 +78,13 @@ pub const BYTECODE_COMPRESSOR_ADDRESS: Address = H160([
     0x00, 0x00, 0x80, 0x0e,
 ]);
 
+/// The EVM interpreter system contract. Contracts deployed with EVM bytecode have the bytecode hash of this
+/// contract, and their original bytecode is interpreted by it.
+pub const EVM_INTERPRETER_ADDRESS: Address = H160([
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x80, 0x12,
+]);
+
 /// The `ecrecover` system contract address.
 pub const ECRECOVER_PRECOMPILE_ADDRESS: Address = H160([
     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
@@ -90,6 +97,12 @@ pub const SHA256_PRECOMPILE_ADDRESS: Address = H160([
     0x00, 0x00, 0x00, 0x02,
 ]);
 
+/// The `p256Verify` (secp256r1 signature verification) system contract address, as defined by RIP-7212.
+pub const P256VERIFY_PRECOMPILE_ADDRESS: Address = H160([
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x01, 0x00,
+]);
+
 pub const ERC20_TRANSFER_TOPIC: H256 = H256([
     221, 242, 82, 173, 27, 226, 200, 155, 105, 194, 176, 104, 252, 55, 141, 170, 149, 43, 167, 241,
     99, 196, 161, 22, 40, 245, 90, 77, 245, 35, 179, 239,
@@ -20,6 +20,9 @@ pub const MAX_TXS_IN_BLOCK: usize = 1024;
 
 pub const MAX_NEW_FACTORY_DEPS: usize = 32;
 
+/// Max size of the init code of a contract deployed with EVM bytecode, as defined by EIP-3860.
+pub const MAX_EVM_INIT_CODE_SIZE: usize = 2 * 24_576;
+
 pub const PAD_MSG_BEFORE_HASH_BITS_LEN: usize = 736;
 
 /// The size of the bootloader memory in bytes which is used by the protocol.
@@ -33,6 +33,10 @@ pub struct IntrinsicSystemGasConstants {
     pub bootloader_tx_memory_size_slots: u32,
 }
 
+/// The amount of computational gas burnt by a single call to the `p256Verify` precompile.
+/// Unlike the costs of other precompiles, it is not defined by the circuits, since they don't support secp256r1 yet.
+pub const P256VERIFY_CIRCUIT_COST_IN_ERGS: u32 = 12_000;
+
 /// The amount of gas we need to pay for each non-zero pubdata byte.
 /// Note that it is bigger than 16 to account for potential overhead
 pub const L1_GAS_PER_PUBDATA_BYTE: u32 = 17;
@@ -26,6 +26,8 @@ const IERC20_CONTRACT_FILE: &str =
     "contracts/ethereum/artifacts/cache/solpp-generated-contracts/common/interfaces/IERC20.sol/IERC20.json";
 const FAIL_ON_RECEIVE_CONTRACT_FILE: &str =
     "contracts/ethereum/artifacts/cache/solpp-generated-contracts/zksync/dev-contracts/FailOnReceive.sol/FailOnReceive.json";
+const L1_BRIDGE_CONTRACT_FILE: &str =
+    "contracts/ethereum/artifacts/cache/solpp-generated-contracts/bridge/interfaces/IL1Bridge.sol/IL1Bridge.json";
 const L2_BRIDGE_CONTRACT_FILE: &str =
     "contracts/zksync/artifacts-zk/cache-zk/solpp-generated-contracts/bridge/interfaces/IL2Bridge.sol/IL2Bridge.json";
 const LOADNEXT_CONTRACT_FILE: &str =
@@ -69,6 +71,10 @@ pub fn erc20_contract() -> Contract {
     load_contract(IERC20_CONTRACT_FILE)
 }
 
+pub fn l1_bridge_contract() -> Contract {
+    load_contract(L1_BRIDGE_CONTRACT_FILE)
+}
+
 pub fn l2_bridge_contract() -> Contract {
     load_contract(L2_BRIDGE_CONTRACT_FILE)
 }
@@ -0,0 +1,55 @@
+-- Converts `table_name` back to a regular table. All partitions (except for archived ones, which are
+-- already dropped) are merged into a single table.
+CREATE OR REPLACE FUNCTION unpartition_table(table_name TEXT) RETURNS VOID AS $$
+DECLARE
+    plain_name TEXT := table_name || '_plain';
+    index_record RECORD;
+    constraint_record RECORD;
+    partition_record RECORD;
+    index_defs TEXT[] := '{}';
+    constraint_defs TEXT[] := '{}';
+    def TEXT;
+BEGIN
+    FOR constraint_record IN
+        SELECT conname, pg_get_constraintdef(oid) AS condef FROM pg_constraint
+        WHERE conrelid = table_name::regclass AND contype IN ('p', 'f')
+    LOOP
+        constraint_defs := constraint_defs ||
+            format('ALTER TABLE %I ADD CONSTRAINT %I %s', table_name, constraint_record.conname, constraint_record.condef);
+    END LOOP;
+    FOR index_record IN
+        SELECT pg_get_indexdef(i.indexrelid) AS index_def FROM pg_index i
+        WHERE i.indrelid = table_name::regclass
+            AND NOT EXISTS (SELECT 1 FROM pg_constraint c WHERE c.conindid = i.indexrelid)
+    LOOP
+        -- Indexes on partitioned tables are defined as `ON ONLY <table>`.
+        index_defs := index_defs || replace(index_record.index_def, ' ON ONLY ', ' ON ');
+    END LOOP;
+
+    EXECUTE format('CREATE TABLE %I (LIKE %I INCLUDING DEFAULTS)', plain_name, table_name);
+    EXECUTE format('INSERT INTO %I SELECT * FROM %I', plain_name, table_name);
+    -- Detached partitions are not a part of the parent table anymore.
+    FOR partition_record IN
+        SELECT partition_name FROM miniblock_range_partitions
+        WHERE miniblock_range_partitions.table_name = unpartition_table.table_name AND status = 'detached'
+    LOOP
+        EXECUTE format('INSERT INTO %I SELECT * FROM %I', plain_name, partition_record.partition_name);
+        EXECUTE format('DROP TABLE %I', partition_record.partition_name);
+    END LOOP;
+    EXECUTE format('DROP TABLE %I CASCADE', table_name);
+    EXECUTE format('ALTER TABLE %I RENAME TO %I', plain_name, table_name);
+
+    FOREACH def IN ARRAY constraint_defs LOOP
+        EXECUTE def;
+    END LOOP;
+    FOREACH def IN ARRAY index_defs LOOP
+        EXECUTE def;
+    END LOOP;
+END;
+$$ LANGUAGE plpgsql;
+
+SELECT unpartition_table('events');
+SELECT unpartition_table('storage_logs');
+
+DROP FUNCTION unpartition_table(TEXT);
+DROP TABLE IF EXISTS miniblock_range_partitions;
@@ -0,0 +1,91 @@
+-- Metadata for partitions of tables partitioned by miniblock number ranges.
+-- `status` is one of `attached`, `detached` or `archived`.
+CREATE TABLE IF NOT EXISTS miniblock_range_partitions
+(
+    partition_name TEXT PRIMARY KEY,
+    table_name TEXT NOT NULL,
+    from_miniblock BIGINT NOT NULL,
+    to_miniblock BIGINT NOT NULL,
+    status TEXT NOT NULL,
+    archive_object_prefix TEXT,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS miniblock_range_partitions_table_name_status_idx
+    ON miniblock_range_partitions (table_name, status, from_miniblock);
+
+-- Converts `table_name` to a table partitioned by `miniblock_number` ranges. The existing table becomes
+-- the `<table_name>_legacy` partition covering all existing miniblocks (this requires a single scan
+-- of the table to validate the partition constraint, but no data is rewritten). Rows for miniblocks
+-- not covered by any partition end up in the `<table_name>_default` partition; new partitions are
+-- created ahead of time by the server.
+CREATE OR REPLACE FUNCTION partition_by_miniblock_range(table_name TEXT) RETURNS VOID AS $$
+DECLARE
+    legacy_name TEXT := table_name || '_legacy';
+    index_record RECORD;
+    constraint_record RECORD;
+    index_defs TEXT[] := '{}';
+    constraint_defs TEXT[] := '{}';
+    def TEXT;
+    upper_bound BIGINT;
+BEGIN
+    EXECUTE format('ALTER TABLE %I RENAME TO %I', table_name, legacy_name);
+
+    FOR constraint_record IN
+        SELECT conname, pg_get_constraintdef(oid) AS condef FROM pg_constraint
+        WHERE conrelid = legacy_name::regclass AND contype IN ('p', 'f')
+    LOOP
+        constraint_defs := constraint_defs ||
+            format('ALTER TABLE %I ADD CONSTRAINT %I %s', table_name, constraint_record.conname, constraint_record.condef);
+        EXECUTE format(
+            'ALTER TABLE %I RENAME CONSTRAINT %I TO %I',
+            legacy_name, constraint_record.conname, constraint_record.conname || '_legacy'
+        );
+    END LOOP;
+
+    -- Indexes backing constraints are handled above.
+    FOR index_record IN
+        SELECT idx.relname AS index_name, pg_get_indexdef(i.indexrelid) AS index_def FROM pg_index i
+        JOIN pg_class idx ON idx.oid = i.indexrelid
+        WHERE i.indrelid = legacy_name::regclass
+            AND NOT EXISTS (SELECT 1 FROM pg_constraint c WHERE c.conindid = i.indexrelid)
+    LOOP
+        index_defs := index_defs || regexp_replace(
+            index_record.index_def,
+            ' ON (\S+\.)?' || legacy_name || ' ',
+            ' ON \1' || table_name || ' '
+        );
+        EXECUTE format('ALTER INDEX %I RENAME TO %I', index_record.index_name, index_record.index_name || '_legacy');
+    END LOOP;
+
+    EXECUTE format(
+        'CREATE TABLE %I (LIKE %I INCLUDING DEFAULTS) PARTITION BY RANGE (miniblock_number)',
+        table_name, legacy_name
+    );
+    EXECUTE format('SELECT COALESCE(MAX(miniblock_number) + 1, 0) FROM %I', legacy_name) INTO upper_bound;
+    EXECUTE format(
+        'ALTER TABLE %I ATTACH PARTITION %I FOR VALUES FROM (MINVALUE) TO (%s)',
+        table_name, legacy_name, upper_bound
+    );
+    EXECUTE format('CREATE TABLE %I PARTITION OF %I DEFAULT', table_name || '_default', table_name);
+
+    -- Constraints and indexes created on the parent table attach the equivalent ones existing
+    -- on the legacy partition instead of rebuilding them.
+    FOREACH def IN ARRAY constraint_defs LOOP
+        EXECUTE def;
+    END LOOP;
+    FOREACH def IN ARRAY index_defs LOOP
+        EXECUTE def;
+    END LOOP;
+
+    INSERT INTO miniblock_range_partitions
+        (partition_name, table_name, from_miniblock, to_miniblock, status, created_at, updated_at)
+    VALUES (legacy_name, table_name, 0, upper_bound, 'attached', now(), now());
+END;
+$$ LANGUAGE plpgsql;
+
+SELECT partition_by_miniblock_range('events');
+SELECT partition_by_miniblock_range('storage_logs');
+
+DROP FUNCTION partition_by_miniblock_range(TEXT);
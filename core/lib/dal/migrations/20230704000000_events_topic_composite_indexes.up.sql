@@ -0,0 +1,12 @@
+-- Composite indexes allow to select events matching a topic filter in the miniblock order
+-- without scanning the entire requested range. Single-column indexes are covered by the composite ones.
+CREATE INDEX IF NOT EXISTS events_topic1_block_event_index_in_block_index ON events (topic1, miniblock_number, event_index_in_block);
+CREATE INDEX IF NOT EXISTS events_topic2_block_event_index_in_block_index ON events (topic2, miniblock_number, event_index_in_block);
+CREATE INDEX IF NOT EXISTS events_topic3_block_event_index_in_block_index ON events (topic3, miniblock_number, event_index_in_block);
+CREATE INDEX IF NOT EXISTS events_topic4_block_event_index_in_block_index ON events (topic4, miniblock_number, event_index_in_block);
+
+DROP INDEX IF EXISTS events_topic1_idx;
+DROP INDEX IF EXISTS events_topic2_idx;
+DROP INDEX IF EXISTS events_topic3_idx;
+DROP INDEX IF EXISTS events_topic4_idx;
+DROP INDEX IF EXISTS events_address_idx;
@@ -0,0 +1,3 @@
+ALTER TABLE l1_batches DROP COLUMN IF EXISTS pubdata_da;
+ALTER TABLE l1_batches DROP COLUMN IF EXISTS calldata_pubdata_cost;
+ALTER TABLE l1_batches DROP COLUMN IF EXISTS blob_pubdata_cost;
@@ -0,0 +1,3 @@
+ALTER TABLE l1_batches ADD COLUMN IF NOT EXISTS pubdata_da TEXT;
+ALTER TABLE l1_batches ADD COLUMN IF NOT EXISTS calldata_pubdata_cost NUMERIC(80);
+ALTER TABLE l1_batches ADD COLUMN IF NOT EXISTS blob_pubdata_cost NUMERIC(80);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS tee_proof_generation_details;
+DROP TABLE IF EXISTS tee_attestations;
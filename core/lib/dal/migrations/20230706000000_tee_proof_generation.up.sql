@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS tee_attestations
+(
+    pubkey      BYTEA PRIMARY KEY,
+    attestation BYTEA     NOT NULL,
+    mrenclave   BYTEA     NOT NULL,
+    created_at  TIMESTAMP NOT NULL,
+    updated_at  TIMESTAMP NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS tee_proof_generation_details
+(
+    l1_batch_number BIGINT    NOT NULL REFERENCES l1_batches (number) ON DELETE CASCADE,
+    tee_type        TEXT      NOT NULL,
+    status          TEXT      NOT NULL,
+    pubkey          BYTEA REFERENCES tee_attestations (pubkey) ON DELETE SET NULL,
+    signature       BYTEA,
+    root_hash       BYTEA,
+    prover_taken_at TIMESTAMP,
+    created_at      TIMESTAMP NOT NULL,
+    updated_at      TIMESTAMP NOT NULL,
+    PRIMARY KEY (l1_batch_number, tee_type)
+);
+
+CREATE INDEX IF NOT EXISTS idx_tee_proof_generation_details_status_prover_taken_at
+    ON tee_proof_generation_details (prover_taken_at)
+    WHERE status = 'picked_by_prover';
@@ -0,0 +1,3 @@
+ALTER TABLE miniblocks DROP COLUMN IF EXISTS protocol_version;
+ALTER TABLE l1_batches DROP COLUMN IF EXISTS protocol_version;
+DROP TABLE IF EXISTS protocol_versions;
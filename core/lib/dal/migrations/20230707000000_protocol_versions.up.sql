@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS protocol_versions
+(
+    id              INT PRIMARY KEY,
+    l1_block_number BIGINT    NOT NULL,
+    created_at      TIMESTAMP NOT NULL
+);
+
+ALTER TABLE l1_batches ADD COLUMN IF NOT EXISTS protocol_version INT;
+ALTER TABLE miniblocks ADD COLUMN IF NOT EXISTS protocol_version INT;
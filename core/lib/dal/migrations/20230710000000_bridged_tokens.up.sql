@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS bridged_tokens
+(
+    l1_address             BYTEA PRIMARY KEY,
+    name                   VARCHAR   NOT NULL,
+    symbol                 VARCHAR   NOT NULL,
+    decimals               INT       NOT NULL,
+    first_seen_l1_block    BIGINT    NOT NULL,
+    first_deposit_tx_hash  BYTEA     NOT NULL,
+    created_at             TIMESTAMP NOT NULL,
+    updated_at             TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS bridged_tokens_symbol_idx ON bridged_tokens (LOWER(symbol));
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS pruning_info
+(
+    -- Ensures that the table contains at most one row.
+    id                      BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    last_pruned_l1_batch    BIGINT    NOT NULL,
+    last_pruned_miniblock   BIGINT    NOT NULL,
+    created_at              TIMESTAMP NOT NULL,
+    updated_at              TIMESTAMP NOT NULL
+);
@@ -0,0 +1,2 @@
+ALTER TABLE l1_batches DROP COLUMN IF EXISTS state_diffs;
+ALTER TABLE l1_batches DROP COLUMN IF EXISTS compressed_state_diffs;
@@ -0,0 +1,2 @@
+ALTER TABLE l1_batches ADD COLUMN IF NOT EXISTS state_diffs BYTEA;
+ALTER TABLE l1_batches ADD COLUMN IF NOT EXISTS compressed_state_diffs BYTEA;
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS l1_batches_bloom_not_computed_idx;
+ALTER TABLE miniblocks DROP COLUMN IF EXISTS logs_bloom;
+ALTER TABLE l1_batches DROP COLUMN IF EXISTS bloom_computed;
@@ -0,0 +1,5 @@
+-- `NULL` means that the Bloom filter was not computed yet (miniblocks sealed before the column was added).
+ALTER TABLE miniblocks ADD COLUMN IF NOT EXISTS logs_bloom BYTEA;
+-- Marks L1 batches which `bloom` was computed from their events.
+ALTER TABLE l1_batches ADD COLUMN IF NOT EXISTS bloom_computed BOOLEAN NOT NULL DEFAULT FALSE;
+CREATE INDEX IF NOT EXISTS l1_batches_bloom_not_computed_idx ON l1_batches (number) WHERE NOT bloom_computed;
@@ -0,0 +1,3 @@
+-- Operator address collecting fees in the miniblock. `NULL` for miniblocks sealed before the column was added;
+-- for these, the fee account of the containing L1 batch applies.
+ALTER TABLE miniblocks ADD COLUMN IF NOT EXISTS fee_account_address BYTEA;
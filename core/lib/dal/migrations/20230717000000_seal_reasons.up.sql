@@ -0,0 +1,4 @@
+-- Name of the sealing rule / criterion that closed the block. `NULL` for blocks sealed before the columns
+-- were added, and for fictive miniblocks.
+ALTER TABLE miniblocks ADD COLUMN IF NOT EXISTS seal_reason TEXT;
+ALTER TABLE l1_batches ADD COLUMN IF NOT EXISTS seal_reason TEXT;
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS eth_watcher_checkpoints
+(
+    watcher_name                 TEXT PRIMARY KEY,
+    last_processed_l1_block      BIGINT    NOT NULL,
+    created_at                   TIMESTAMP NOT NULL,
+    updated_at                   TIMESTAMP NOT NULL
+);
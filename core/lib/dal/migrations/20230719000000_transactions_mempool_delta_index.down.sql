@@ -0,0 +1 @@
+DROP INDEX IF EXISTS transactions_pending_updated_at_hash_index;
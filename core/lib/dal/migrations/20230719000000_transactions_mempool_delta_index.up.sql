@@ -0,0 +1,4 @@
+-- Allows the mempool fetcher to load pending transactions added or updated since the previous sync
+-- without scanning all pending transactions.
+CREATE INDEX IF NOT EXISTS transactions_pending_updated_at_hash_index ON transactions (updated_at, hash)
+    WHERE miniblock_number IS NULL;
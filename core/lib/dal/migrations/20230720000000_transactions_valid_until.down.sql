@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS transactions_pending_valid_until_index;
+ALTER TABLE transactions DROP COLUMN IF EXISTS valid_until;
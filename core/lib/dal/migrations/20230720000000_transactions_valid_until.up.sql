@@ -0,0 +1,6 @@
+-- UNIX timestamp (in seconds) after which the transaction must not be included into a miniblock.
+-- Stored as a number rather than `TIMESTAMP` so that it can be compared with miniblock timestamps.
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS valid_until BIGINT;
+-- Allows the mempool fetcher to find pending transactions with the passed deadline.
+CREATE INDEX IF NOT EXISTS transactions_pending_valid_until_index ON transactions (valid_until)
+    WHERE miniblock_number IS NULL AND valid_until IS NOT NULL;
@@ -0,0 +1,3 @@
+-- Miniblocks sealed with concurrent writes are inserted with `is_sealed = FALSE` and marked as sealed
+-- once all their data is persisted.
+ALTER TABLE miniblocks ADD COLUMN IF NOT EXISTS is_sealed BOOLEAN NOT NULL DEFAULT TRUE;
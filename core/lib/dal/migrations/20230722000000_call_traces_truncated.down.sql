@@ -0,0 +1 @@
+ALTER TABLE call_traces DROP COLUMN IF EXISTS truncated;
@@ -0,0 +1,2 @@
+-- Set if outputs or revert reasons in the call trace were truncated to fit into the configured size limits.
+ALTER TABLE call_traces ADD COLUMN IF NOT EXISTS truncated BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,20 @@
+-- Events not matching the configured indexing patterns are stored raw-only and aren't covered
+-- by the indexes used to filter logs by address or topics.
+ALTER TABLE events ADD COLUMN IF NOT EXISTS is_indexed BOOLEAN NOT NULL DEFAULT TRUE;
+
+DROP INDEX IF EXISTS events_address_block_event_index_in_block_index;
+DROP INDEX IF EXISTS events_topic1_block_event_index_in_block_index;
+DROP INDEX IF EXISTS events_topic2_block_event_index_in_block_index;
+DROP INDEX IF EXISTS events_topic3_block_event_index_in_block_index;
+DROP INDEX IF EXISTS events_topic4_block_event_index_in_block_index;
+
+CREATE INDEX IF NOT EXISTS events_address_block_event_index_in_block_index
+    ON events (address, miniblock_number, event_index_in_block) WHERE is_indexed;
+CREATE INDEX IF NOT EXISTS events_topic1_block_event_index_in_block_index
+    ON events (topic1, miniblock_number, event_index_in_block) WHERE is_indexed;
+CREATE INDEX IF NOT EXISTS events_topic2_block_event_index_in_block_index
+    ON events (topic2, miniblock_number, event_index_in_block) WHERE is_indexed;
+CREATE INDEX IF NOT EXISTS events_topic3_block_event_index_in_block_index
+    ON events (topic3, miniblock_number, event_index_in_block) WHERE is_indexed;
+CREATE INDEX IF NOT EXISTS events_topic4_block_event_index_in_block_index
+    ON events (topic4, miniblock_number, event_index_in_block) WHERE is_indexed;
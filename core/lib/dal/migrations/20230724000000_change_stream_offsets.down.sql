@@ -0,0 +1 @@
+DROP TABLE IF EXISTS change_stream_offsets;
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS change_stream_offsets (
+    publisher_name TEXT PRIMARY KEY,
+    last_published_miniblock BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
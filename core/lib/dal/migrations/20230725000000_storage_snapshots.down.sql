@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS storage_snapshots;
+DROP TABLE IF EXISTS storage_snapshot_miniblocks;
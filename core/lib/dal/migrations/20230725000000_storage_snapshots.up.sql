@@ -0,0 +1,14 @@
+-- Materialized state snapshots: values of all storage slots as of the end of a miniblock. Snapshots allow
+-- to serve historical state queries without scanning `storage_logs` from genesis, and after old partitions
+-- of `storage_logs` are detached.
+CREATE TABLE IF NOT EXISTS storage_snapshot_miniblocks (
+    miniblock_number BIGINT PRIMARY KEY,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS storage_snapshots (
+    miniblock_number BIGINT NOT NULL REFERENCES storage_snapshot_miniblocks (miniblock_number) ON DELETE CASCADE,
+    hashed_key BYTEA NOT NULL,
+    value BYTEA NOT NULL,
+    PRIMARY KEY (miniblock_number, hashed_key)
+);
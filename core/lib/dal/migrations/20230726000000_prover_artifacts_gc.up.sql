@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS prover_artifacts_gc (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    last_cleaned_l1_batch BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
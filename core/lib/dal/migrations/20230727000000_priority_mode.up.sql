@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS priority_mode (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    first_expired_priority_op_id BIGINT NOT NULL,
+    activated_at_l1_block BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
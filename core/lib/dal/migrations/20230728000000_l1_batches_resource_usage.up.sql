@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS l1_batches_resource_usage (
+    l1_batch_number BIGINT PRIMARY KEY REFERENCES l1_batches (number) ON DELETE CASCADE,
+    gas_used BIGINT NOT NULL,
+    pubdata_bytes BIGINT NOT NULL,
+    initial_storage_writes BIGINT NOT NULL,
+    repeated_storage_writes BIGINT NOT NULL,
+    circuit_statistic JSONB NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1 @@
+ALTER TABLE eth_txs_history DROP COLUMN IF EXISTS nonce_consumed_externally;
@@ -0,0 +1,3 @@
+-- Set for sending attempts that will never be mined because their nonce was consumed by a transaction
+-- sent from the operator account bypassing eth_sender.
+ALTER TABLE eth_txs_history ADD COLUMN IF NOT EXISTS nonce_consumed_externally BOOLEAN NOT NULL DEFAULT FALSE;
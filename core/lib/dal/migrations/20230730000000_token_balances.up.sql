@@ -0,0 +1,11 @@
+-- Token balances changed in each miniblock. Maintained by the state keeper when sealing miniblocks,
+-- so that balances of an account can be listed without computing storage keys for all tokens. ETH balances
+-- are stored with the zero token address, as exposed by the API.
+CREATE TABLE IF NOT EXISTS token_balances (
+    account BYTEA NOT NULL,
+    token BYTEA NOT NULL,
+    miniblock_number BIGINT NOT NULL,
+    balance BYTEA NOT NULL,
+    PRIMARY KEY (account, token, miniblock_number)
+);
+CREATE INDEX IF NOT EXISTS token_balances_miniblock_number_idx ON token_balances (miniblock_number);
@@ -0,0 +1,2 @@
+ALTER TABLE protocol_versions DROP COLUMN IF EXISTS bootloader_code_hash;
+ALTER TABLE protocol_versions DROP COLUMN IF EXISTS default_account_code_hash;
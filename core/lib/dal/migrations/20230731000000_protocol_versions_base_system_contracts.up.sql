@@ -0,0 +1,2 @@
+ALTER TABLE protocol_versions ADD COLUMN IF NOT EXISTS bootloader_code_hash BYTEA;
+ALTER TABLE protocol_versions ADD COLUMN IF NOT EXISTS default_account_code_hash BYTEA;
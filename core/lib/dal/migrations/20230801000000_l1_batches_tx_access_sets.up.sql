@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS l1_batches_tx_access_sets (
+    l1_batch_number BIGINT PRIMARY KEY REFERENCES l1_batches (number) ON DELETE CASCADE,
+    -- Encoded with `zksync_types::tx::encode_access_sets()`.
+    access_sets BYTEA NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS l1_messenger_messages;
@@ -0,0 +1,13 @@
+-- Messages sent to L1 via the L1 messenger, decoded from `L1MessageSent` events when sealing L1 batches,
+-- so that clients don't need to parse raw events to retrieve message contents.
+CREATE TABLE IF NOT EXISTS l1_messenger_messages (
+    l1_batch_number BIGINT NOT NULL REFERENCES l1_batches (number) ON DELETE CASCADE,
+    index_in_l1_batch INT NOT NULL,
+    tx_index_in_l1_batch INT NOT NULL,
+    sender BYTEA NOT NULL,
+    hash BYTEA NOT NULL,
+    message BYTEA NOT NULL,
+    -- Index of the accompanying L2->L1 log in the L1 batch.
+    l2_to_l1_log_index INT,
+    PRIMARY KEY (l1_batch_number, index_in_l1_batch)
+);
@@ -0,0 +1,17 @@
+-- Replacements of L2 transactions by transactions with the same initiator and nonce. Replaced transactions
+-- are overwritten in the `transactions` table, so this is the only place their hashes are retained.
+CREATE TABLE IF NOT EXISTS transaction_replacements (
+    id BIGSERIAL PRIMARY KEY,
+    initiator_address BYTEA NOT NULL,
+    nonce BIGINT NOT NULL,
+    replaced_tx_hash BYTEA NOT NULL,
+    replacement_tx_hash BYTEA NOT NULL,
+    replaced_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS transaction_replacements_initiator_address_nonce_idx
+    ON transaction_replacements (initiator_address, nonce);
+CREATE INDEX IF NOT EXISTS transaction_replacements_replaced_tx_hash_idx
+    ON transaction_replacements (replaced_tx_hash);
+CREATE INDEX IF NOT EXISTS transaction_replacements_replacement_tx_hash_idx
+    ON transaction_replacements (replacement_tx_hash);
@@ -0,0 +1,14 @@
+-- Per-circuit-type shards of basic circuits of an L1 batch. Each shard groups prover jobs for basic circuits
+-- of a single type, so that progress of provers specializing in certain circuit types can be tracked independently.
+CREATE TABLE IF NOT EXISTS basic_circuit_shards (
+    l1_batch_number BIGINT NOT NULL,
+    circuit_type TEXT NOT NULL,
+    circuit_count INT NOT NULL,
+    status TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (l1_batch_number, circuit_type)
+);
+
+CREATE INDEX IF NOT EXISTS basic_circuit_shards_status_idx
+    ON basic_circuit_shards (status) WHERE status = 'waiting_for_proofs';
@@ -0,0 +1,2 @@
+ALTER TABLE miniblocks DROP COLUMN IF EXISTS gas_limit;
+ALTER TABLE miniblocks DROP COLUMN IF EXISTS gas_used;
@@ -0,0 +1,4 @@
+-- L2 gas used by miniblock transactions and the miniblock gas limit. `NULL` for miniblocks sealed
+-- before the columns were added; for such miniblocks, the values are derived when serving the Web3 API.
+ALTER TABLE miniblocks ADD COLUMN IF NOT EXISTS gas_used BIGINT;
+ALTER TABLE miniblocks ADD COLUMN IF NOT EXISTS gas_limit BIGINT;
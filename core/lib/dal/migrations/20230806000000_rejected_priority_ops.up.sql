@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS rejected_priority_ops (
+    priority_op_id BIGINT PRIMARY KEY,
+    tx_hash BYTEA NOT NULL,
+    l1_block_number BIGINT NOT NULL,
+    reason TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
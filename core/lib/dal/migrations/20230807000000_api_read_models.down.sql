@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS api_account_summaries;
+DROP TABLE IF EXISTS api_transaction_details;
+DROP TABLE IF EXISTS api_block_details;
@@ -0,0 +1,54 @@
+-- Read models for the API server. Rows are written by the state keeper when miniblocks and L1 batches are sealed.
+CREATE TABLE IF NOT EXISTS api_block_details (
+    number BIGINT PRIMARY KEY REFERENCES miniblocks (number) ON DELETE CASCADE,
+    l1_batch_number BIGINT,
+    timestamp BIGINT NOT NULL,
+    hash BYTEA NOT NULL,
+    l1_tx_count INT NOT NULL,
+    l2_tx_count INT NOT NULL,
+    l1_gas_price BIGINT NOT NULL,
+    l2_fair_gas_price BIGINT NOT NULL,
+    bootloader_code_hash BYTEA,
+    default_aa_code_hash BYTEA,
+    protocol_version INT,
+    seal_reason TEXT,
+    fee_account_address BYTEA,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+CREATE INDEX IF NOT EXISTS api_block_details_l1_batch_number_idx ON api_block_details (l1_batch_number);
+
+CREATE TABLE IF NOT EXISTS api_transaction_details (
+    hash BYTEA PRIMARY KEY,
+    miniblock_number BIGINT NOT NULL REFERENCES miniblocks (number) ON DELETE CASCADE,
+    l1_batch_number BIGINT,
+    index_in_block INT NOT NULL,
+    is_l1_originated BOOLEAN NOT NULL,
+    initiator_address BYTEA NOT NULL,
+    fee NUMERIC(80) NOT NULL,
+    gas_per_pubdata NUMERIC(80),
+    received_at TIMESTAMP NOT NULL,
+    error VARCHAR,
+    execution_info JSONB NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+CREATE INDEX IF NOT EXISTS api_transaction_details_miniblock_number_idx ON api_transaction_details (miniblock_number);
+
+CREATE TABLE IF NOT EXISTS api_account_summaries (
+    address BYTEA PRIMARY KEY,
+    sent_tx_count BIGINT NOT NULL,
+    first_miniblock_number BIGINT NOT NULL,
+    last_miniblock_number BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+
+-- Block and transaction details for miniblocks sealed before the migration are served from the core tables,
+-- but account summaries cannot be computed on the fly, so they are backfilled.
+INSERT INTO api_account_summaries
+    (address, sent_tx_count, first_miniblock_number, last_miniblock_number, created_at, updated_at)
+SELECT initiator_address, COUNT(*), MIN(miniblock_number), MAX(miniblock_number), now(), now()
+FROM transactions
+WHERE miniblock_number IS NOT NULL
+GROUP BY initiator_address;
@@ -0,0 +1,2 @@
+-- Machine-readable reason for transactions rejected by the state keeper, serialized `TxRejection`.
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS rejection JSONB;
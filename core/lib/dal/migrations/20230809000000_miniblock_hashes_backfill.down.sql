@@ -0,0 +1 @@
+DROP TABLE IF EXISTS miniblock_hashes_backfill;
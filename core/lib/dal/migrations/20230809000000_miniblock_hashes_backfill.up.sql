@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS miniblock_hashes_backfill
+(
+    -- Ensures that the table contains at most one row.
+    id                          BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    last_processed_miniblock    BIGINT    NOT NULL,
+    created_at                  TIMESTAMP NOT NULL,
+    updated_at                  TIMESTAMP NOT NULL
+);
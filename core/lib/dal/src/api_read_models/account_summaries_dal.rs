@@ -0,0 +1,90 @@
+use std::time::Instant;
+
+use zksync_types::{api, Address, MiniblockNumber, U64};
+
+use crate::{SqlxError, StorageProcessor};
+
+#[derive(Debug)]
+pub struct ApiAccountSummariesDal<'a, 'c> {
+    pub(super) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ApiAccountSummariesDal<'_, '_> {
+    /// Accounts for transactions included into a freshly sealed miniblock. Unlike other read models,
+    /// summaries are updated incrementally, so this method must be called exactly once per miniblock.
+    pub async fn refresh_for_miniblock(&mut self, number: MiniblockNumber) {
+        sqlx::query!(
+            "INSERT INTO api_account_summaries \
+                (address, sent_tx_count, first_miniblock_number, last_miniblock_number, created_at, updated_at) \
+            SELECT initiator_address, COUNT(*), $1, $1, now(), now() \
+            FROM transactions WHERE miniblock_number = $1 \
+            GROUP BY initiator_address \
+            ON CONFLICT (address) DO UPDATE SET \
+                sent_tx_count = api_account_summaries.sent_tx_count + EXCLUDED.sent_tx_count, \
+                last_miniblock_number = EXCLUDED.last_miniblock_number, \
+                updated_at = now()",
+            number.0 as i64
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Removes transactions in miniblocks after `last_miniblock_to_keep` from summaries. Must be called
+    /// before the transactions state is reset.
+    pub async fn rollback(&mut self, last_miniblock_to_keep: MiniblockNumber) {
+        sqlx::query!(
+            "WITH reverted AS ( \
+                SELECT initiator_address, COUNT(*) AS tx_count FROM transactions \
+                WHERE miniblock_number > $1 \
+                GROUP BY initiator_address \
+            ) \
+            UPDATE api_account_summaries SET \
+                sent_tx_count = api_account_summaries.sent_tx_count - reverted.tx_count, \
+                last_miniblock_number = COALESCE( \
+                    ( \
+                        SELECT MAX(miniblock_number) FROM transactions \
+                        WHERE initiator_address = reverted.initiator_address AND miniblock_number <= $1 \
+                    ), \
+                    LEAST(api_account_summaries.last_miniblock_number, $1) \
+                ), \
+                updated_at = now() \
+            FROM reverted WHERE api_account_summaries.address = reverted.initiator_address",
+            last_miniblock_to_keep.0 as i64
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+
+        sqlx::query!("DELETE FROM api_account_summaries WHERE sent_tx_count <= 0")
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+    }
+
+    pub async fn get_account_summary(
+        &mut self,
+        address: Address,
+    ) -> Result<Option<api::AccountSummary>, SqlxError> {
+        let started_at = Instant::now();
+        let row = sqlx::query!(
+            "SELECT sent_tx_count, first_miniblock_number, last_miniblock_number \
+            FROM api_account_summaries WHERE address = $1",
+            address.as_bytes()
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        metrics::histogram!(
+            "dal.request",
+            started_at.elapsed(),
+            "method" => "api_get_account_summary"
+        );
+
+        Ok(row.map(|row| api::AccountSummary {
+            address,
+            sent_transaction_count: U64::from(row.sent_tx_count as u64),
+            first_active_block: U64::from(row.first_miniblock_number as u64),
+            last_active_block: U64::from(row.last_miniblock_number as u64),
+        }))
+    }
+}
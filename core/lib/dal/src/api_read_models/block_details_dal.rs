@@ -0,0 +1,141 @@
+use sqlx::{
+    postgres::PgRow,
+    types::chrono::{DateTime, NaiveDateTime, Utc},
+    Row,
+};
+
+use std::{str::FromStr, time::Instant};
+
+use zksync_contracts::BaseSystemContractsHashes;
+use zksync_types::{
+    explorer_api::{BlockDetails, BlockStatus},
+    Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, H256,
+};
+
+use super::L1_TXS_JOIN_SQL;
+use crate::{SqlxError, StorageProcessor};
+
+#[derive(Debug)]
+pub struct ApiBlockDetailsDal<'a, 'c> {
+    pub(super) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ApiBlockDetailsDal<'_, '_> {
+    /// Copies the header of a freshly sealed miniblock into the read model.
+    pub async fn refresh_for_miniblock(&mut self, number: MiniblockNumber) {
+        sqlx::query!(
+            "INSERT INTO api_block_details \
+                (number, timestamp, hash, l1_tx_count, l2_tx_count, l1_gas_price, l2_fair_gas_price, \
+                bootloader_code_hash, default_aa_code_hash, protocol_version, seal_reason, \
+                fee_account_address, created_at, updated_at) \
+            SELECT number, timestamp, hash, l1_tx_count, l2_tx_count, l1_gas_price, l2_fair_gas_price, \
+                bootloader_code_hash, default_aa_code_hash, protocol_version, seal_reason, \
+                fee_account_address, now(), now() \
+            FROM miniblocks WHERE number = $1 \
+            ON CONFLICT (number) DO UPDATE SET \
+                timestamp = EXCLUDED.timestamp, hash = EXCLUDED.hash, \
+                l1_tx_count = EXCLUDED.l1_tx_count, l2_tx_count = EXCLUDED.l2_tx_count, \
+                l1_gas_price = EXCLUDED.l1_gas_price, l2_fair_gas_price = EXCLUDED.l2_fair_gas_price, \
+                bootloader_code_hash = EXCLUDED.bootloader_code_hash, \
+                default_aa_code_hash = EXCLUDED.default_aa_code_hash, \
+                protocol_version = EXCLUDED.protocol_version, seal_reason = EXCLUDED.seal_reason, \
+                fee_account_address = EXCLUDED.fee_account_address, updated_at = now()",
+            number.0 as i64
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Assigns miniblocks of a freshly sealed L1 batch to the batch.
+    pub async fn refresh_for_l1_batch(&mut self, number: L1BatchNumber) {
+        sqlx::query!(
+            "UPDATE api_block_details SET l1_batch_number = $1, updated_at = now() \
+            WHERE number IN (SELECT number FROM miniblocks WHERE l1_batch_number = $1)",
+            number.0 as i64
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Returns block details from the read model, or `None` if the miniblock is not in the read model
+    /// (i.e., it is not sealed, or it was sealed before the read model was introduced).
+    pub async fn get_block_details(
+        &mut self,
+        number: MiniblockNumber,
+        current_operator_address: Address,
+    ) -> Result<Option<BlockDetails>, SqlxError> {
+        let started_at = Instant::now();
+        let query = format!(
+            "SELECT api_block_details.*, \
+                COALESCE(api_block_details.l1_batch_number, (SELECT MAX(number) + 1 FROM l1_batches)) \
+                    AS resolved_l1_batch_number, \
+                commit_tx.tx_hash AS commit_tx_hash, commit_tx.confirmed_at AS committed_at, \
+                prove_tx.tx_hash AS prove_tx_hash, prove_tx.confirmed_at AS proven_at, \
+                execute_tx.tx_hash AS execute_tx_hash, execute_tx.confirmed_at AS executed_at \
+            FROM api_block_details \
+            LEFT JOIN l1_batches ON l1_batches.number = api_block_details.l1_batch_number \
+            {} \
+            WHERE api_block_details.number = $1",
+            L1_TXS_JOIN_SQL
+        );
+        let row = sqlx::query(&query)
+            .bind(number.0 as i64)
+            .fetch_optional(self.storage.conn())
+            .await?;
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "api_get_block_details");
+
+        Ok(row.map(|row| block_details_from_row(&row, current_operator_address)))
+    }
+}
+
+fn block_details_from_row(row: &PgRow, current_operator_address: Address) -> BlockDetails {
+    let number: i64 = row.get("number");
+    let commit_tx_hash: Option<String> = row.get("commit_tx_hash");
+    let prove_tx_hash: Option<String> = row.get("prove_tx_hash");
+    let execute_tx_hash: Option<String> = row.get("execute_tx_hash");
+    let status = if number == 0 || execute_tx_hash.is_some() {
+        BlockStatus::Verified
+    } else {
+        BlockStatus::Sealed
+    };
+    let parse_tx_hash = |hash: Option<String>| {
+        hash.map(|hash| H256::from_str(&hash).expect("Incorrect L1 tx hash"))
+    };
+    let get_time = |column: &str| {
+        row.get::<Option<NaiveDateTime>, _>(column)
+            .map(|time| DateTime::<Utc>::from_utc(time, Utc))
+    };
+    let bootloader_code_hash: Option<&[u8]> = row.get("bootloader_code_hash");
+    let default_aa_code_hash: Option<&[u8]> = row.get("default_aa_code_hash");
+    let fee_account_address: Option<&[u8]> = row.get("fee_account_address");
+    let protocol_version: Option<i32> = row.get("protocol_version");
+
+    BlockDetails {
+        number: MiniblockNumber(number as u32),
+        l1_batch_number: L1BatchNumber(row.get::<i64, _>("resolved_l1_batch_number") as u32),
+        timestamp: row.get::<i64, _>("timestamp") as u64,
+        l1_tx_count: row.get::<i32, _>("l1_tx_count") as usize,
+        l2_tx_count: row.get::<i32, _>("l2_tx_count") as usize,
+        root_hash: Some(H256::from_slice(row.get("hash"))),
+        status,
+        commit_tx_hash: parse_tx_hash(commit_tx_hash),
+        committed_at: get_time("committed_at"),
+        prove_tx_hash: parse_tx_hash(prove_tx_hash),
+        proven_at: get_time("proven_at"),
+        execute_tx_hash: parse_tx_hash(execute_tx_hash),
+        executed_at: get_time("executed_at"),
+        l1_gas_price: row.get::<i64, _>("l1_gas_price") as u64,
+        l2_fair_gas_price: row.get::<i64, _>("l2_fair_gas_price") as u64,
+        base_system_contracts_hashes: BaseSystemContractsHashes {
+            bootloader: H256::from_slice(bootloader_code_hash.expect("should not be none")),
+            default_aa: H256::from_slice(default_aa_code_hash.expect("should not be none")),
+        },
+        operator_address: fee_account_address
+            .map(Address::from_slice)
+            .unwrap_or(current_operator_address),
+        protocol_version: protocol_version.map(|version| ProtocolVersionId(version as u16)),
+        seal_reason: row.get("seal_reason"),
+    }
+}
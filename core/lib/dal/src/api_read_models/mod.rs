@@ -0,0 +1,229 @@
+//! Read models for the API server, i.e., denormalized views of block, transaction and account data
+//! shaped after API queries. Read models are refreshed by the state keeper in the same database transaction
+//! that marks a miniblock or L1 batch as sealed, so that they never lag behind the core tables; this allows
+//! to index and optimize API tables independently of the write path.
+//!
+//! Data that changes after sealing (e.g., L1 transactions committing, proving and executing a batch)
+//! is not copied into read models and is joined from the core tables at query time.
+
+use zksync_types::{L1BatchNumber, MiniblockNumber};
+
+use crate::StorageProcessor;
+use account_summaries_dal::ApiAccountSummariesDal;
+use block_details_dal::ApiBlockDetailsDal;
+use transaction_details_dal::ApiTransactionDetailsDal;
+
+pub mod account_summaries_dal;
+pub mod block_details_dal;
+pub mod transaction_details_dal;
+
+/// Joins confirmed L1 transactions for an L1 batch. Assumes that `l1_batches` is joined in the query.
+const L1_TXS_JOIN_SQL: &str = "\
+    LEFT JOIN eth_txs_history AS commit_tx \
+        ON (l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id AND commit_tx.confirmed_at IS NOT NULL) \
+    LEFT JOIN eth_txs_history AS prove_tx \
+        ON (l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id AND prove_tx.confirmed_at IS NOT NULL) \
+    LEFT JOIN eth_txs_history AS execute_tx \
+        ON (l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id AND execute_tx.confirmed_at IS NOT NULL)";
+
+#[derive(Debug)]
+pub struct ApiReadModelsIntermediary<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl<'a, 'c> ApiReadModelsIntermediary<'a, 'c> {
+    pub fn block_details_dal(self) -> ApiBlockDetailsDal<'a, 'c> {
+        ApiBlockDetailsDal {
+            storage: self.storage,
+        }
+    }
+
+    pub fn transaction_details_dal(self) -> ApiTransactionDetailsDal<'a, 'c> {
+        ApiTransactionDetailsDal {
+            storage: self.storage,
+        }
+    }
+
+    pub fn account_summaries_dal(self) -> ApiAccountSummariesDal<'a, 'c> {
+        ApiAccountSummariesDal {
+            storage: self.storage,
+        }
+    }
+
+    /// Refreshes all read models for a miniblock. Must be called once after all miniblock data is persisted,
+    /// in the same database transaction that marks the miniblock as sealed.
+    pub async fn refresh_for_miniblock(self, number: MiniblockNumber) {
+        let storage = self.storage;
+        storage
+            .api_read_models()
+            .block_details_dal()
+            .refresh_for_miniblock(number)
+            .await;
+        storage
+            .api_read_models()
+            .transaction_details_dal()
+            .refresh_for_miniblock(number)
+            .await;
+        storage
+            .api_read_models()
+            .account_summaries_dal()
+            .refresh_for_miniblock(number)
+            .await;
+    }
+
+    /// Refreshes all read models for an L1 batch. Must be called after miniblocks and transactions
+    /// are assigned to the batch.
+    pub async fn refresh_for_l1_batch(self, number: L1BatchNumber) {
+        let storage = self.storage;
+        storage
+            .api_read_models()
+            .block_details_dal()
+            .refresh_for_l1_batch(number)
+            .await;
+        storage
+            .api_read_models()
+            .transaction_details_dal()
+            .refresh_for_l1_batch(number)
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use db_test_macro::db_test;
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{
+        block::{BlockGasCount, L1BatchHeader},
+        explorer_api::TransactionStatus,
+        fee::TransactionExecutionMetrics,
+        Address, Nonce, U256,
+    };
+
+    use super::*;
+    use crate::{
+        tests::{create_miniblock_header, mock_execution_result, mock_l2_transaction},
+        ConnectionPool,
+    };
+
+    #[db_test(dal_crate)]
+    async fn refreshing_and_rolling_back_read_models(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        let initiator = Address::repeat_byte(1);
+        let operator = Address::repeat_byte(2);
+
+        let mut tx_hashes = vec![];
+        for number in 0..2 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await;
+            let mut tx = mock_l2_transaction();
+            tx.common_data.initiator_address = initiator;
+            tx.common_data.nonce = Nonce(number);
+            tx_hashes.push(tx.hash());
+            conn.transactions_dal()
+                .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+                .await;
+            conn.transactions_dal()
+                .mark_txs_as_executed_in_miniblock(
+                    MiniblockNumber(number),
+                    &[mock_execution_result(tx)],
+                    1.into(),
+                )
+                .await;
+            conn.api_read_models()
+                .refresh_for_miniblock(MiniblockNumber(number))
+                .await;
+
+            // Only the first miniblock is included into an L1 batch.
+            if number == 0 {
+                let header = L1BatchHeader::new(
+                    L1BatchNumber(0),
+                    0,
+                    Address::default(),
+                    BaseSystemContractsHashes::default(),
+                );
+                conn.blocks_dal()
+                    .insert_l1_batch(&header, BlockGasCount::default())
+                    .await;
+                conn.blocks_dal()
+                    .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(0))
+                    .await;
+                conn.api_read_models()
+                    .refresh_for_l1_batch(L1BatchNumber(0))
+                    .await;
+            }
+        }
+
+        let block_details = conn
+            .api_read_models()
+            .block_details_dal()
+            .get_block_details(MiniblockNumber(1), operator)
+            .await
+            .unwrap()
+            .expect("no block details");
+        assert_eq!(block_details.l1_batch_number, L1BatchNumber(1));
+        assert_eq!(block_details.l2_tx_count, 0);
+        assert_eq!(block_details.operator_address, operator);
+
+        let tx_details = conn
+            .api_read_models()
+            .transaction_details_dal()
+            .get_transaction_details(tx_hashes[0])
+            .await
+            .unwrap()
+            .expect("no transaction details");
+        assert!(matches!(tx_details.status, TransactionStatus::Included));
+        assert_eq!(tx_details.initiator_address, initiator);
+        assert_eq!(tx_details.fee, U256::from(1_000_000));
+
+        let summary = conn
+            .api_read_models()
+            .account_summaries_dal()
+            .get_account_summary(initiator)
+            .await
+            .unwrap()
+            .expect("no account summary");
+        assert_eq!(summary.sent_transaction_count, 2.into());
+        assert_eq!(summary.first_active_block, 0.into());
+        assert_eq!(summary.last_active_block, 1.into());
+
+        // Roll back the second miniblock in the same order as the block reverter.
+        conn.api_read_models()
+            .account_summaries_dal()
+            .rollback(MiniblockNumber(0))
+            .await;
+        conn.transactions_dal()
+            .reset_transactions_state(MiniblockNumber(0))
+            .await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+
+        let block_details = conn
+            .api_read_models()
+            .block_details_dal()
+            .get_block_details(MiniblockNumber(1), operator)
+            .await
+            .unwrap();
+        assert!(block_details.is_none());
+        let tx_details = conn
+            .api_read_models()
+            .transaction_details_dal()
+            .get_transaction_details(tx_hashes[1])
+            .await
+            .unwrap();
+        assert!(tx_details.is_none());
+        let summary = conn
+            .api_read_models()
+            .account_summaries_dal()
+            .get_account_summary(initiator)
+            .await
+            .unwrap()
+            .expect("no account summary");
+        assert_eq!(summary.sent_transaction_count, 1.into());
+        assert_eq!(summary.last_active_block, 0.into());
+    }
+}
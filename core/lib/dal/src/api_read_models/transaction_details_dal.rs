@@ -0,0 +1,124 @@
+use sqlx::{
+    types::chrono::{DateTime, NaiveDateTime, Utc},
+    Row,
+};
+
+use std::{str::FromStr, time::Instant};
+
+use zksync_types::{
+    api, explorer_api::TransactionStatus, tx::ExecutionMetrics, Address, L1BatchNumber,
+    MiniblockNumber, H256,
+};
+use zksync_utils::bigdecimal_to_u256;
+
+use super::L1_TXS_JOIN_SQL;
+use crate::{BigDecimal, SqlxError, StorageProcessor};
+
+#[derive(Debug)]
+pub struct ApiTransactionDetailsDal<'a, 'c> {
+    pub(super) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ApiTransactionDetailsDal<'_, '_> {
+    /// Copies details of transactions included into a freshly sealed miniblock into the read model.
+    /// The transaction fee is precomputed, so that it doesn't need to be derived on each request.
+    pub async fn refresh_for_miniblock(&mut self, number: MiniblockNumber) {
+        sqlx::query!(
+            "INSERT INTO api_transaction_details \
+                (hash, miniblock_number, index_in_block, is_l1_originated, initiator_address, fee, \
+                gas_per_pubdata, received_at, error, execution_info, created_at, updated_at) \
+            SELECT hash, miniblock_number, index_in_block, is_priority, initiator_address, \
+                (COALESCE(gas_limit, 0) - refunded_gas) * COALESCE(effective_gas_price, 0), \
+                gas_per_pubdata_limit, received_at, error, execution_info, now(), now() \
+            FROM transactions WHERE miniblock_number = $1 \
+            ON CONFLICT (hash) DO UPDATE SET \
+                miniblock_number = EXCLUDED.miniblock_number, index_in_block = EXCLUDED.index_in_block, \
+                fee = EXCLUDED.fee, error = EXCLUDED.error, execution_info = EXCLUDED.execution_info, \
+                l1_batch_number = NULL, updated_at = now()",
+            number.0 as i64
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Assigns transactions of a freshly sealed L1 batch to the batch.
+    pub async fn refresh_for_l1_batch(&mut self, number: L1BatchNumber) {
+        sqlx::query!(
+            "UPDATE api_transaction_details SET l1_batch_number = $1, updated_at = now() \
+            WHERE miniblock_number IN (SELECT number FROM miniblocks WHERE l1_batch_number = $1)",
+            number.0 as i64
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Returns details of an included transaction from the read model, or `None` if the transaction
+    /// is not in the read model (i.e., it is pending, or it was included before the read model was introduced).
+    pub async fn get_transaction_details(
+        &mut self,
+        hash: H256,
+    ) -> Result<Option<api::TransactionDetails>, SqlxError> {
+        let started_at = Instant::now();
+        let query = format!(
+            "SELECT api_transaction_details.*, \
+                commit_tx.tx_hash AS eth_commit_tx_hash, \
+                prove_tx.tx_hash AS eth_prove_tx_hash, \
+                execute_tx.tx_hash AS eth_execute_tx_hash \
+            FROM api_transaction_details \
+            LEFT JOIN l1_batches ON l1_batches.number = api_transaction_details.l1_batch_number \
+            {} \
+            WHERE api_transaction_details.hash = $1",
+            L1_TXS_JOIN_SQL
+        );
+        let row = sqlx::query(&query)
+            .bind(hash.as_bytes())
+            .fetch_optional(self.storage.conn())
+            .await?;
+        metrics::histogram!(
+            "dal.request",
+            started_at.elapsed(),
+            "method" => "api_get_transaction_details"
+        );
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let parse_tx_hash = |column: &str| {
+            row.get::<Option<String>, _>(column)
+                .map(|hash| H256::from_str(&hash).expect("Incorrect L1 tx hash"))
+        };
+        let eth_commit_tx_hash = parse_tx_hash("eth_commit_tx_hash");
+        let eth_prove_tx_hash = parse_tx_hash("eth_prove_tx_hash");
+        let eth_execute_tx_hash = parse_tx_hash("eth_execute_tx_hash");
+        let status = if row.get::<Option<String>, _>("error").is_some() {
+            TransactionStatus::Failed
+        } else if eth_execute_tx_hash.is_some() {
+            TransactionStatus::Verified
+        } else {
+            TransactionStatus::Included
+        };
+        let execution_metrics =
+            serde_json::from_value::<ExecutionMetrics>(row.get("execution_info"))
+                .ok()
+                .map(api::TransactionExecutionMetrics::from);
+
+        Ok(Some(api::TransactionDetails {
+            is_l1_originated: row.get("is_l1_originated"),
+            status,
+            fee: bigdecimal_to_u256(row.get("fee")),
+            gas_per_pubdata: Some(bigdecimal_to_u256(
+                row.get::<Option<BigDecimal>, _>("gas_per_pubdata")
+                    .unwrap_or_default(),
+            )),
+            initiator_address: Address::from_slice(row.get("initiator_address")),
+            received_at: DateTime::<Utc>::from_utc(row.get::<NaiveDateTime, _>("received_at"), Utc),
+            eth_commit_tx_hash,
+            eth_prove_tx_hash,
+            eth_execute_tx_hash,
+            execution_metrics,
+            rejection: None,
+        }))
+    }
+}
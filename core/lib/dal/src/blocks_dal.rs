@@ -1,24 +1,69 @@
 use std::{
     collections::HashMap,
     convert::{Into, TryInto},
-    time::Instant,
+    ops,
+    str::FromStr,
+    time::{Duration, Instant},
 };
 
 use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
 use sqlx::Row;
 
 use zksync_types::{
-    aggregated_operations::AggregatedActionType,
-    block::{BlockGasCount, L1BatchHeader, MiniblockHeader},
+    aggregated_operations::{AggregatedActionType, PubdataDA},
+    block::{
+        BlockGasCount, L1BatchHeader, L1BatchOperationCost, L1BatchResourceUsage, MiniblockHeader,
+    },
     commitment::{BlockMetadata, BlockWithMetadata},
-    L1BatchNumber, MiniblockNumber, H256, MAX_GAS_PER_PUBDATA_BYTE,
+    state_diffs::{decode_state_diffs, encode_state_diffs, StateDiffRecord},
+    tx::{decode_access_sets, encode_access_sets, TxAccessSet},
+    Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, H2048, H256,
+    MAX_GAS_PER_PUBDATA_BYTE, U256,
 };
+use zksync_utils::u256_to_big_decimal;
 
 use crate::{
     models::storage_block::{StorageBlock, StorageMiniblockHeader},
     StorageProcessor,
 };
 
+/// Data necessary to compute the hash of a miniblock, returned by [`BlocksDal::get_miniblocks_hashing_data()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MiniblockHashingData {
+    pub number: MiniblockNumber,
+    pub timestamp: u64,
+    /// Currently persisted hash of the miniblock.
+    pub hash: H256,
+    /// Hashes of the miniblock transactions in the execution order.
+    pub tx_hashes: Vec<H256>,
+    /// Whether the miniblock is fully persisted. Transactions of a miniblock sealed with concurrent writes
+    /// are persisted after its header.
+    pub is_sealed: bool,
+}
+
+/// Inconsistency in metadata of an L1 batch found by [`BlocksDal::find_l1_batch_metadata_issues()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum L1BatchMetadataIssue {
+    /// Root hash is missing, although it is present for a later L1 batch.
+    MissingRootHash,
+    /// Root hash is present, but the commitment or one of its components is missing.
+    MissingCommitment,
+    /// Parent hash doesn't match the root hash of the previous L1 batch.
+    ParentHashMismatch,
+    /// L1 batch references an ETH transaction of another type for the specified action.
+    OrphanedEthTx(AggregatedActionType),
+}
+
+impl L1BatchMetadataIssue {
+    fn eth_tx_id_column(action_type: AggregatedActionType) -> &'static str {
+        match action_type {
+            AggregatedActionType::CommitBlocks => "eth_commit_tx_id",
+            AggregatedActionType::PublishProofBlocksOnchain => "eth_prove_tx_id",
+            AggregatedActionType::ExecuteBlocks => "eth_execute_tx_id",
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct BlocksDal<'a, 'c> {
     pub(crate) storage: &'a mut StorageProcessor<'c>,
@@ -49,14 +94,33 @@ impl BlocksDal<'_, '_> {
         L1BatchNumber(number as u32)
     }
 
+    /// Returns the base fee of the last sealed L1 batch.
+    pub async fn get_sealed_block_base_fee(&mut self) -> u64 {
+        let started_at = Instant::now();
+        let base_fee = sqlx::query!(
+            "SELECT base_fee_per_gas FROM l1_batches WHERE is_finished = TRUE ORDER BY number DESC LIMIT 1"
+        )
+        .fetch_one(self.storage.conn())
+        .await
+        .unwrap()
+        .base_fee_per_gas;
+
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_sealed_block_base_fee");
+        base_fee
+            .to_u64()
+            .expect("base_fee_per_gas should fit in u64")
+    }
+
+    /// Returns the number of the latest miniblock marked as sealed.
     pub async fn get_sealed_miniblock_number(&mut self) -> MiniblockNumber {
         let started_at = Instant::now();
-        let number: i64 = sqlx::query!("SELECT MAX(number) as \"number\" FROM miniblocks")
-            .fetch_one(self.storage.conn())
-            .await
-            .unwrap()
-            .number
-            .unwrap_or(0);
+        let number: i64 =
+            sqlx::query!("SELECT MAX(number) as \"number\" FROM miniblocks WHERE is_sealed")
+                .fetch_one(self.storage.conn())
+                .await
+                .unwrap()
+                .number
+                .unwrap_or(0);
 
         metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_sealed_miniblock_number");
         MiniblockNumber(number as u32)
@@ -155,6 +219,546 @@ impl BlocksDal<'_, '_> {
         }
     }
 
+    /// Records the data availability mode chosen to publish pubdata of the L1 batch, together with
+    /// the projected costs of the alternatives at the moment of the choice.
+    pub async fn set_pubdata_da(
+        &mut self,
+        number: L1BatchNumber,
+        pubdata_da: PubdataDA,
+        calldata_cost: U256,
+        blobs_cost: Option<U256>,
+    ) {
+        sqlx::query!(
+            "UPDATE l1_batches \
+            SET pubdata_da = $2, calldata_pubdata_cost = $3, blob_pubdata_cost = $4, updated_at = now() \
+            WHERE number = $1",
+            number.0 as i64,
+            pubdata_da.as_str(),
+            u256_to_big_decimal(calldata_cost),
+            blobs_cost.map(u256_to_big_decimal)
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    pub async fn get_pubdata_da(&mut self, number: L1BatchNumber) -> Option<PubdataDA> {
+        let pubdata_da = sqlx::query!(
+            "SELECT pubdata_da FROM l1_batches WHERE number = $1",
+            number.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()?
+        .pubdata_da;
+        pubdata_da.map(|da| da.parse().unwrap())
+    }
+
+    /// Persists state diffs of a sealed L1 batch. Enumeration indices are not yet known at this point
+    /// and are assigned by the metadata calculator.
+    pub async fn insert_l1_batch_state_diffs(
+        &mut self,
+        number: L1BatchNumber,
+        state_diffs: &[StateDiffRecord],
+    ) {
+        sqlx::query!(
+            "UPDATE l1_batches SET state_diffs = $2 WHERE number = $1",
+            number.0 as i64,
+            encode_state_diffs(state_diffs)
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Returns state diffs of an L1 batch, or `None` if the batch was sealed before state diffs
+    /// were persisted.
+    pub async fn get_l1_batch_state_diffs(
+        &mut self,
+        number: L1BatchNumber,
+    ) -> Option<Vec<StateDiffRecord>> {
+        let state_diffs = sqlx::query!(
+            "SELECT state_diffs FROM l1_batches WHERE number = $1",
+            number.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()?
+        .state_diffs;
+        state_diffs.map(|bytes| decode_state_diffs(&bytes))
+    }
+
+    async fn save_compressed_state_diffs(
+        &mut self,
+        number: L1BatchNumber,
+        compressed_state_diffs: Option<&[u8]>,
+    ) {
+        sqlx::query!(
+            "UPDATE l1_batches SET compressed_state_diffs = $2 WHERE number = $1",
+            number.0 as i64,
+            compressed_state_diffs
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    async fn get_compressed_state_diffs(&mut self, number: L1BatchNumber) -> Option<Vec<u8>> {
+        sqlx::query!(
+            "SELECT compressed_state_diffs FROM l1_batches WHERE number = $1",
+            number.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()?
+        .compressed_state_diffs
+    }
+
+    pub async fn set_miniblock_logs_bloom(&mut self, number: MiniblockNumber, bloom: &H2048) {
+        sqlx::query!(
+            "UPDATE miniblocks SET logs_bloom = $2 WHERE number = $1",
+            number.0 as i64,
+            bloom.as_bytes()
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Returns the hash of the specified miniblock, or `None` if the miniblock is not in the storage.
+    pub async fn get_miniblock_hash(&mut self, number: MiniblockNumber) -> Option<H256> {
+        sqlx::query!(
+            "SELECT hash FROM miniblocks WHERE number = $1",
+            number.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()
+        .map(|row| H256::from_slice(&row.hash))
+    }
+
+    /// Same as [`Self::get_miniblock_hash()`], but locks the miniblock row until the end of the current
+    /// DB transaction, so that the hash cannot be changed concurrently (e.g., by the backfill of miniblock hashes)
+    /// while it's used to compute the hash of the next miniblock.
+    pub async fn get_miniblock_hash_for_share(&mut self, number: MiniblockNumber) -> Option<H256> {
+        sqlx::query!(
+            "SELECT hash FROM miniblocks WHERE number = $1 FOR SHARE",
+            number.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()
+        .map(|row| H256::from_slice(&row.hash))
+    }
+
+    pub async fn set_miniblock_hash(&mut self, number: MiniblockNumber, hash: H256) {
+        sqlx::query!(
+            "UPDATE miniblocks SET hash = $2 WHERE number = $1",
+            number.0 as i64,
+            hash.as_bytes()
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Returns the data necessary to compute hashes of miniblocks in the specified range.
+    pub async fn get_miniblocks_hashing_data(
+        &mut self,
+        numbers: ops::RangeInclusive<MiniblockNumber>,
+    ) -> Vec<MiniblockHashingData> {
+        let rows = sqlx::query!(
+            "SELECT number, timestamp, hash, is_sealed, \
+                ARRAY(\
+                    SELECT transactions.hash FROM transactions \
+                    WHERE transactions.miniblock_number = miniblocks.number \
+                    ORDER BY transactions.index_in_block\
+                ) AS \"tx_hashes!\" \
+            FROM miniblocks WHERE number BETWEEN $1 AND $2 ORDER BY number",
+            numbers.start().0 as i64,
+            numbers.end().0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap();
+
+        rows.into_iter()
+            .map(|row| MiniblockHashingData {
+                number: MiniblockNumber(row.number as u32),
+                timestamp: row.timestamp as u64,
+                hash: H256::from_slice(&row.hash),
+                tx_hashes: row
+                    .tx_hashes
+                    .iter()
+                    .map(|hash| H256::from_slice(hash))
+                    .collect(),
+                is_sealed: row.is_sealed,
+            })
+            .collect()
+    }
+
+    /// Returns the last miniblock processed by the backfill of miniblock hashes, or `None` if the backfill
+    /// hasn't processed any miniblocks yet.
+    pub async fn get_miniblock_hashes_backfill_progress(&mut self) -> Option<MiniblockNumber> {
+        sqlx::query!("SELECT last_processed_miniblock FROM miniblock_hashes_backfill")
+            .fetch_optional(self.storage.conn())
+            .await
+            .unwrap()
+            .map(|row| MiniblockNumber(row.last_processed_miniblock as u32))
+    }
+
+    pub async fn set_miniblock_hashes_backfill_progress(
+        &mut self,
+        last_processed_miniblock: MiniblockNumber,
+    ) {
+        sqlx::query!(
+            "INSERT INTO miniblock_hashes_backfill (last_processed_miniblock, created_at, updated_at) \
+            VALUES ($1, now(), now()) \
+            ON CONFLICT (id) DO UPDATE SET last_processed_miniblock = $1, updated_at = now()",
+            last_processed_miniblock.0 as i64
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Sets the Bloom filter over events of an L1 batch and marks it as computed.
+    pub async fn set_l1_batch_bloom(&mut self, number: L1BatchNumber, bloom: &H2048) {
+        sqlx::query!(
+            "UPDATE l1_batches SET bloom = $2, bloom_computed = TRUE, updated_at = now() \
+            WHERE number = $1",
+            number.0 as i64,
+            bloom.as_bytes()
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Returns the earliest L1 batches which Bloom filters were not computed yet.
+    pub async fn get_l1_batches_without_bloom(&mut self, limit: usize) -> Vec<L1BatchNumber> {
+        sqlx::query!(
+            "SELECT number FROM l1_batches WHERE NOT bloom_computed ORDER BY number LIMIT $1",
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| L1BatchNumber(row.number as u32))
+        .collect()
+    }
+
+    pub async fn set_l1_batch_protocol_version(
+        &mut self,
+        number: L1BatchNumber,
+        protocol_version: ProtocolVersionId,
+    ) {
+        sqlx::query!(
+            "UPDATE l1_batches SET protocol_version = $2 WHERE number = $1",
+            number.0 as i64,
+            protocol_version.0 as i32
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    pub async fn get_l1_batch_protocol_version(
+        &mut self,
+        number: L1BatchNumber,
+    ) -> Option<ProtocolVersionId> {
+        let protocol_version = sqlx::query!(
+            "SELECT protocol_version FROM l1_batches WHERE number = $1",
+            number.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()?
+        .protocol_version;
+        protocol_version.map(|version| ProtocolVersionId(version as u16))
+    }
+
+    pub async fn set_miniblock_protocol_version(
+        &mut self,
+        number: MiniblockNumber,
+        protocol_version: ProtocolVersionId,
+    ) {
+        sqlx::query!(
+            "UPDATE miniblocks SET protocol_version = $2 WHERE number = $1",
+            number.0 as i64,
+            protocol_version.0 as i32
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Saves the L2 gas used by the miniblock transactions and the miniblock gas limit (`None` means
+    /// the default block gas limit).
+    pub async fn set_miniblock_gas(
+        &mut self,
+        number: MiniblockNumber,
+        gas_used: u64,
+        gas_limit: Option<u64>,
+    ) {
+        sqlx::query!(
+            "UPDATE miniblocks SET gas_used = $2, gas_limit = $3 WHERE number = $1",
+            number.0 as i64,
+            gas_used as i64,
+            gas_limit.map(|limit| limit as i64)
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    pub async fn get_miniblock_protocol_version(
+        &mut self,
+        number: MiniblockNumber,
+    ) -> Option<ProtocolVersionId> {
+        let protocol_version = sqlx::query!(
+            "SELECT protocol_version FROM miniblocks WHERE number = $1",
+            number.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()?
+        .protocol_version;
+        protocol_version.map(|version| ProtocolVersionId(version as u16))
+    }
+
+    pub async fn set_miniblock_fee_account_address(
+        &mut self,
+        number: MiniblockNumber,
+        fee_account_address: Address,
+    ) {
+        sqlx::query!(
+            "UPDATE miniblocks SET fee_account_address = $2 WHERE number = $1",
+            number.0 as i64,
+            fee_account_address.as_bytes()
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    pub async fn get_miniblock_fee_account_address(
+        &mut self,
+        number: MiniblockNumber,
+    ) -> Option<Address> {
+        let address = sqlx::query!(
+            "SELECT fee_account_address FROM miniblocks WHERE number = $1",
+            number.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()?
+        .fee_account_address;
+        address.map(|address| Address::from_slice(&address))
+    }
+
+    pub async fn set_miniblock_seal_reason(&mut self, number: MiniblockNumber, seal_reason: &str) {
+        sqlx::query!(
+            "UPDATE miniblocks SET seal_reason = $2 WHERE number = $1",
+            number.0 as i64,
+            seal_reason
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Sets the marker signalling that all data of the miniblock is persisted. Miniblocks are inserted
+    /// as sealed by default.
+    pub async fn set_miniblock_is_sealed(&mut self, number: MiniblockNumber, is_sealed: bool) {
+        sqlx::query!(
+            "UPDATE miniblocks SET is_sealed = $2 WHERE number = $1",
+            number.0 as i64,
+            is_sealed
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Returns the earliest miniblock not marked as sealed, i.e., one which data may be partially persisted.
+    pub async fn get_first_unsealed_miniblock(&mut self) -> Option<MiniblockNumber> {
+        let number =
+            sqlx::query!("SELECT MIN(number) as \"number\" FROM miniblocks WHERE NOT is_sealed")
+                .fetch_one(self.storage.conn())
+                .await
+                .unwrap()
+                .number;
+        number.map(|number| MiniblockNumber(number as u32))
+    }
+
+    pub async fn get_miniblock_seal_reason(&mut self, number: MiniblockNumber) -> Option<String> {
+        sqlx::query!(
+            "SELECT seal_reason FROM miniblocks WHERE number = $1",
+            number.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()?
+        .seal_reason
+    }
+
+    pub async fn set_l1_batch_seal_reason(&mut self, number: L1BatchNumber, seal_reason: &str) {
+        sqlx::query!(
+            "UPDATE l1_batches SET seal_reason = $2 WHERE number = $1",
+            number.0 as i64,
+            seal_reason
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    pub async fn get_l1_batch_seal_reason(&mut self, number: L1BatchNumber) -> Option<String> {
+        sqlx::query!(
+            "SELECT seal_reason FROM l1_batches WHERE number = $1",
+            number.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()?
+        .seal_reason
+    }
+
+    pub async fn insert_l1_batch_resource_usage(
+        &mut self,
+        number: L1BatchNumber,
+        usage: &L1BatchResourceUsage,
+    ) {
+        let circuit_statistic = serde_json::to_value(usage.circuit_statistic)
+            .expect("failed to serialize circuit statistic to JSON value");
+        sqlx::query!(
+            "INSERT INTO l1_batches_resource_usage \
+                (l1_batch_number, gas_used, pubdata_bytes, initial_storage_writes, \
+                repeated_storage_writes, circuit_statistic, created_at) \
+            VALUES ($1, $2, $3, $4, $5, $6, now())",
+            number.0 as i64,
+            usage.gas_used as i64,
+            usage.pubdata_bytes as i64,
+            usage.initial_storage_writes as i64,
+            usage.repeated_storage_writes as i64,
+            circuit_statistic
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Returns resources consumed by the L1 batch, or `None` if they weren't persisted
+    /// (e.g., for batches sealed before the summary was introduced).
+    pub async fn get_l1_batch_resource_usage(
+        &mut self,
+        number: L1BatchNumber,
+    ) -> Option<L1BatchResourceUsage> {
+        let row = sqlx::query!(
+            "SELECT gas_used, pubdata_bytes, initial_storage_writes, repeated_storage_writes, \
+                circuit_statistic \
+            FROM l1_batches_resource_usage WHERE l1_batch_number = $1",
+            number.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()?;
+
+        Some(L1BatchResourceUsage {
+            gas_used: row.gas_used as u64,
+            pubdata_bytes: row.pubdata_bytes as u64,
+            initial_storage_writes: row.initial_storage_writes as u64,
+            repeated_storage_writes: row.repeated_storage_writes as u64,
+            circuit_statistic: serde_json::from_value(row.circuit_statistic)
+                .expect("invalid circuit statistic in Postgres"),
+        })
+    }
+
+    pub async fn insert_l1_batch_tx_access_sets(
+        &mut self,
+        number: L1BatchNumber,
+        access_sets: &[TxAccessSet],
+    ) {
+        sqlx::query!(
+            "INSERT INTO l1_batches_tx_access_sets (l1_batch_number, access_sets, created_at) \
+            VALUES ($1, $2, now())",
+            number.0 as i64,
+            encode_access_sets(access_sets)
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Returns storage access sets of the L1 batch transactions, or `None` if they weren't recorded
+    /// (recording is disabled by default).
+    pub async fn get_l1_batch_tx_access_sets(
+        &mut self,
+        number: L1BatchNumber,
+    ) -> Option<Vec<TxAccessSet>> {
+        let row = sqlx::query!(
+            "SELECT access_sets FROM l1_batches_tx_access_sets WHERE l1_batch_number = $1",
+            number.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()?;
+        Some(decode_access_sets(&row.access_sets))
+    }
+
+    /// Returns the cost of the confirmed L1 transaction performing the specified action for the L1 batch,
+    /// or `None` if there is no such transaction yet.
+    pub async fn get_l1_batch_operation_cost(
+        &mut self,
+        number: L1BatchNumber,
+        action_type: AggregatedActionType,
+    ) -> Option<L1BatchOperationCost> {
+        let column = L1BatchMetadataIssue::eth_tx_id_column(action_type);
+        let query = format!(
+            "SELECT eth_txs_history.tx_hash, eth_txs.gas_used, \
+                eth_txs_history.base_fee_per_gas, eth_txs_history.priority_fee_per_gas, \
+                (SELECT COUNT(*) FROM l1_batches WHERE {column} = eth_txs.id) AS l1_batch_count \
+            FROM l1_batches \
+            JOIN eth_txs ON l1_batches.{column} = eth_txs.id \
+            JOIN eth_txs_history ON eth_txs.confirmed_eth_tx_history_id = eth_txs_history.id \
+            WHERE l1_batches.number = $1"
+        );
+        let row = sqlx::query(&query)
+            .bind(number.0 as i64)
+            .fetch_optional(self.storage.conn())
+            .await
+            .unwrap()?;
+
+        let tx_hash = row.get::<String, _>("tx_hash");
+        Some(L1BatchOperationCost {
+            tx_hash: H256::from_str(&tx_hash).expect("invalid L1 transaction hash in Postgres"),
+            gas_used: row.get::<Option<i64>, _>("gas_used").map(|gas| gas as u64),
+            base_fee_per_gas: row.get::<i64, _>("base_fee_per_gas") as u64,
+            priority_fee_per_gas: row.get::<i64, _>("priority_fee_per_gas") as u64,
+            l1_batch_count: row.get::<i64, _>("l1_batch_count") as u32,
+        })
+    }
+
+    /// Returns the operator address used in the latest sealed miniblock. Falls back to the fee account
+    /// of the containing L1 batch for miniblocks sealed before the address was persisted per miniblock.
+    pub async fn get_last_miniblock_fee_account_address(&mut self) -> Option<Address> {
+        let address = sqlx::query!(
+            "SELECT COALESCE(miniblocks.fee_account_address, l1_batches.fee_account_address) \
+                AS fee_account_address \
+            FROM miniblocks \
+            LEFT JOIN l1_batches ON miniblocks.l1_batch_number = l1_batches.number \
+            ORDER BY miniblocks.number DESC \
+            LIMIT 1"
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()?
+        .fee_account_address;
+        address.map(|address| Address::from_slice(&address))
+    }
+
     pub async fn insert_l1_batch(
         &mut self,
         block: &L1BatchHeader,
@@ -337,6 +941,11 @@ impl BlocksDal<'_, '_> {
         .execute(self.storage.conn())
         .await
         .unwrap();
+        self.save_compressed_state_diffs(
+            block_number,
+            block_metadata.state_diffs_compressed.as_deref(),
+        )
+        .await;
     }
 
     pub async fn save_blocks_metadata(
@@ -411,6 +1020,12 @@ impl BlocksDal<'_, '_> {
                 block_metadata.root_hash,
                 block_metadata.l2_l1_merkle_root
             );
+        } else {
+            self.save_compressed_state_diffs(
+                block_number,
+                block_metadata.state_diffs_compressed.as_deref(),
+            )
+            .await;
         }
         metrics::histogram!("dal.request", started_at.elapsed(), "method" => "save_blocks_metadata");
     }
@@ -476,6 +1091,26 @@ impl BlocksDal<'_, '_> {
         .map(|record| L1BatchNumber(record.number as u32))
     }
 
+    /// Returns the number of the last L1 batch which prove tx was confirmed on Ethereum more than `min_age` ago.
+    pub async fn get_number_of_last_block_proven_on_eth_before(
+        &mut self,
+        min_age: Duration,
+    ) -> Option<L1BatchNumber> {
+        let number = sqlx::query!(
+            "SELECT MAX(number) AS number FROM l1_batches \
+            JOIN eth_txs_history AS prove_tx \
+                ON (l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id) \
+            WHERE prove_tx.confirmed_at IS NOT NULL \
+                AND prove_tx.confirmed_at < now() - $1 * INTERVAL '1 second'",
+            min_age.as_secs_f64()
+        )
+        .fetch_one(self.storage.conn())
+        .await
+        .unwrap()
+        .number;
+        number.map(|number| L1BatchNumber(number as u32))
+    }
+
     /// Returns the number of the last block for which an Ethereum execute tx was sent and confirmed.
     pub async fn get_number_of_last_block_executed_on_eth(&mut self) -> Option<L1BatchNumber> {
         sqlx::query!(
@@ -550,7 +1185,9 @@ impl BlocksDal<'_, '_> {
                 l2_l1_compressed_messages, l2_l1_merkle_root, l1_gas_price, l2_fair_gas_price, \
                 rollup_last_leaf_index, zkporter_is_available, bootloader_code_hash, \
                 default_aa_code_hash, base_fee_per_gas, aux_data_hash, pass_through_data_hash, \
-                meta_parameters_hash, skip_proof, gas_per_pubdata_byte_in_block, gas_per_pubdata_limit \
+                meta_parameters_hash, skip_proof, gas_per_pubdata_byte_in_block, gas_per_pubdata_limit, \
+                pubdata_da, calldata_pubdata_cost, blob_pubdata_cost, protocol_version, \
+                state_diffs, compressed_state_diffs, bloom_computed, seal_reason \
             FROM \
             (SELECT l1_batches.*, row_number() OVER (ORDER BY number ASC) AS row_number \
                 FROM l1_batches \
@@ -654,6 +1291,27 @@ impl BlocksDal<'_, '_> {
         }
     }
 
+    /// Returns the last L1 batch with the proof confirmed on L1 before the specified Unix timestamp
+    /// (in seconds).
+    pub async fn get_last_l1_batch_proven_before(
+        &mut self,
+        max_confirmed_at_seconds: f64,
+    ) -> Option<L1BatchNumber> {
+        let number = sqlx::query!(
+            "SELECT MAX(l1_batches.number) AS number FROM l1_batches \
+            JOIN eth_txs ON (l1_batches.eth_prove_tx_id = eth_txs.id) \
+            JOIN eth_txs_history AS prove_tx ON (eth_txs.confirmed_eth_tx_history_id = prove_tx.id) \
+            WHERE prove_tx.confirmed_at IS NOT NULL \
+                AND EXTRACT(epoch FROM prove_tx.confirmed_at) < $1",
+            max_confirmed_at_seconds
+        )
+        .fetch_one(self.storage.conn())
+        .await
+        .unwrap()
+        .number;
+        number.map(|number| L1BatchNumber(number as u32))
+    }
+
     pub async fn get_ready_for_commit_blocks(
         &mut self,
         limit: usize,
@@ -742,8 +1400,10 @@ impl BlocksDal<'_, '_> {
         let unsorted_factory_deps = self
             .get_l1_batch_factory_deps(L1BatchNumber(storage_block.number as u32))
             .await;
+        let number = L1BatchNumber(storage_block.number as u32);
         let block_header = storage_block.clone().into();
-        let block_metadata = storage_block.try_into().ok()?;
+        let mut block_metadata: BlockMetadata = storage_block.try_into().ok()?;
+        block_metadata.state_diffs_compressed = self.get_compressed_state_diffs(number).await;
 
         Some(BlockWithMetadata::new(
             block_header,
@@ -994,6 +1654,167 @@ impl BlocksDal<'_, '_> {
         .unwrap();
     }
 
+    /// Finds L1 batches in the specified range with missing or inconsistent metadata. The returned issues
+    /// are ordered by the L1 batch number.
+    pub async fn find_l1_batch_metadata_issues(
+        &mut self,
+        numbers: ops::RangeInclusive<L1BatchNumber>,
+    ) -> Vec<(L1BatchNumber, L1BatchMetadataIssue)> {
+        let from = numbers.start().0 as i64;
+        let to = numbers.end().0 as i64;
+        let mut issues = vec![];
+
+        let rows = sqlx::query!(
+            "SELECT number FROM l1_batches \
+            WHERE number BETWEEN $1 AND $2 AND hash IS NULL \
+                AND number < (SELECT MAX(number) FROM l1_batches WHERE hash IS NOT NULL)",
+            from,
+            to
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap();
+        issues.extend(rows.into_iter().map(|row| {
+            let number = L1BatchNumber(row.number as u32);
+            (number, L1BatchMetadataIssue::MissingRootHash)
+        }));
+
+        let rows = sqlx::query!(
+            "SELECT number FROM l1_batches \
+            WHERE number BETWEEN $1 AND $2 AND hash IS NOT NULL \
+                AND (commitment IS NULL OR aux_data_hash IS NULL \
+                    OR pass_through_data_hash IS NULL OR meta_parameters_hash IS NULL)",
+            from,
+            to
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap();
+        issues.extend(rows.into_iter().map(|row| {
+            let number = L1BatchNumber(row.number as u32);
+            (number, L1BatchMetadataIssue::MissingCommitment)
+        }));
+
+        let rows = sqlx::query!(
+            "SELECT l1_batches.number FROM l1_batches \
+            INNER JOIN l1_batches AS prev ON prev.number = l1_batches.number - 1 \
+            WHERE l1_batches.number BETWEEN $1 AND $2 \
+                AND l1_batches.hash IS NOT NULL AND prev.hash IS NOT NULL \
+                AND (l1_batches.parent_hash IS NULL OR l1_batches.parent_hash != prev.hash)",
+            from,
+            to
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap();
+        issues.extend(rows.into_iter().map(|row| {
+            let number = L1BatchNumber(row.number as u32);
+            (number, L1BatchMetadataIssue::ParentHashMismatch)
+        }));
+
+        for action_type in [
+            AggregatedActionType::CommitBlocks,
+            AggregatedActionType::PublishProofBlocksOnchain,
+            AggregatedActionType::ExecuteBlocks,
+        ] {
+            let column = L1BatchMetadataIssue::eth_tx_id_column(action_type);
+            let query = format!(
+                "SELECT l1_batches.number FROM l1_batches \
+                INNER JOIN eth_txs ON eth_txs.id = l1_batches.{column} \
+                WHERE l1_batches.number BETWEEN $1 AND $2 AND eth_txs.tx_type != $3"
+            );
+            let rows = sqlx::query(&query)
+                .bind(from)
+                .bind(to)
+                .bind(action_type.to_string())
+                .fetch_all(self.storage.conn())
+                .await
+                .unwrap();
+            issues.extend(rows.into_iter().map(|row| {
+                let number = L1BatchNumber(row.get::<i64, _>("number") as u32);
+                (number, L1BatchMetadataIssue::OrphanedEthTx(action_type))
+            }));
+        }
+
+        issues.sort_by_key(|(number, _)| *number);
+        issues
+    }
+
+    /// Sets the parent hash of the L1 batch to the root hash of the previous L1 batch. Returns `false`
+    /// if the parent hash cannot be recomputed because the previous root hash is missing.
+    pub async fn recompute_parent_hash(&mut self, number: L1BatchNumber) -> bool {
+        let result = sqlx::query!(
+            "UPDATE l1_batches SET parent_hash = prev.hash, updated_at = now() \
+            FROM l1_batches AS prev \
+            WHERE l1_batches.number = $1 AND prev.number = $1 - 1 \
+                AND l1_batches.hash IS NOT NULL AND prev.hash IS NOT NULL",
+            number.0 as i64
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+        result.rows_affected() > 0
+    }
+
+    /// Clears the reference to the ETH transaction for the specified action if the referenced transaction
+    /// has another type, so that the ETH sender aggregates the L1 batch again. Returns `false` if the reference
+    /// is consistent and was left intact.
+    pub async fn clear_orphaned_eth_tx_id(
+        &mut self,
+        number: L1BatchNumber,
+        action_type: AggregatedActionType,
+    ) -> bool {
+        let column = L1BatchMetadataIssue::eth_tx_id_column(action_type);
+        let query = format!(
+            "UPDATE l1_batches SET {column} = NULL, updated_at = now() \
+            FROM eth_txs \
+            WHERE l1_batches.number = $1 AND eth_txs.id = l1_batches.{column} AND eth_txs.tx_type != $2"
+        );
+        let result = sqlx::query(&query)
+            .bind(number.0 as i64)
+            .bind(action_type.to_string())
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        result.rows_affected() > 0
+    }
+
+    /// Clears metadata for the specified L1 batch and all L1 batches after it, so that it can be recomputed
+    /// by the metadata calculator after the Merkle tree is rolled back. Does nothing and returns `None`
+    /// if any of the affected L1 batches is already committed; otherwise, returns the number of updated L1 batches.
+    pub async fn clear_l1_batch_metadata(&mut self, first_number: L1BatchNumber) -> Option<u64> {
+        let mut transaction = self.storage.start_transaction().await;
+        let committed_count = sqlx::query!(
+            "SELECT COUNT(*) AS \"count!\" FROM l1_batches \
+            WHERE number >= $1 AND eth_commit_tx_id IS NOT NULL",
+            first_number.0 as i64
+        )
+        .fetch_one(transaction.conn())
+        .await
+        .unwrap()
+        .count;
+        if committed_count > 0 {
+            return None;
+        }
+
+        let result = sqlx::query!(
+            "UPDATE l1_batches \
+            SET hash = NULL, merkle_root_hash = NULL, commitment = NULL, \
+                compressed_repeated_writes = NULL, compressed_initial_writes = NULL, \
+                l2_l1_compressed_messages = NULL, l2_l1_merkle_root = NULL, \
+                parent_hash = NULL, rollup_last_leaf_index = NULL, \
+                aux_data_hash = NULL, pass_through_data_hash = NULL, meta_parameters_hash = NULL, \
+                updated_at = now() \
+            WHERE number >= $1",
+            first_number.0 as i64
+        )
+        .execute(transaction.conn())
+        .await
+        .unwrap();
+        transaction.commit().await;
+        Some(result.rows_affected())
+    }
+
     /// Deletes all miniblocks and L1 batches, including the genesis ones. Should only be used in tests.
     pub async fn delete_genesis(&mut self) {
         self.delete_miniblocks_inner(None).await;
@@ -1062,4 +1883,64 @@ mod tests {
             assert_eq!(gas, 3 * expected_gas);
         }
     }
+
+    #[db_test(dal_crate)]
+    async fn finding_and_repairing_l1_batch_metadata_issues(pool: ConnectionPool) {
+        let mut conn = pool.access_storage().await;
+        conn.blocks_dal().delete_l1_batches(L1BatchNumber(0)).await;
+
+        let mut header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            100,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+        );
+        for number in 1..=3 {
+            header.number = L1BatchNumber(number);
+            header.timestamp += 100;
+            conn.blocks_dal()
+                .insert_l1_batch(&header, BlockGasCount::default())
+                .await;
+        }
+        conn.blocks_dal()
+            .set_l1_batch_hash(L1BatchNumber(1), H256::repeat_byte(1))
+            .await;
+        conn.blocks_dal()
+            .set_l1_batch_hash(L1BatchNumber(3), H256::repeat_byte(3))
+            .await;
+
+        let all_batches = L1BatchNumber(0)..=L1BatchNumber(10);
+        let issues = conn
+            .blocks_dal()
+            .find_l1_batch_metadata_issues(all_batches.clone())
+            .await;
+        assert_eq!(
+            issues,
+            [
+                (L1BatchNumber(1), L1BatchMetadataIssue::MissingCommitment),
+                (L1BatchNumber(2), L1BatchMetadataIssue::MissingRootHash),
+                (L1BatchNumber(3), L1BatchMetadataIssue::MissingCommitment),
+            ]
+        );
+        assert!(
+            !conn
+                .blocks_dal()
+                .recompute_parent_hash(L1BatchNumber(1))
+                .await
+        );
+
+        let cleared = conn
+            .blocks_dal()
+            .clear_l1_batch_metadata(L1BatchNumber(2))
+            .await;
+        assert_eq!(cleared, Some(2));
+        let issues = conn
+            .blocks_dal()
+            .find_l1_batch_metadata_issues(all_batches)
+            .await;
+        assert_eq!(
+            issues,
+            [(L1BatchNumber(1), L1BatchMetadataIssue::MissingCommitment)]
+        );
+    }
 }
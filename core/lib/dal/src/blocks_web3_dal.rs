@@ -8,17 +8,17 @@ use zksync_types::{
     api,
     l2_to_l1_log::L2ToL1Log,
     vm_trace::Call,
-    web3::types::{BlockHeader, U64},
+    web3::types::{BlockHeader, H64, U64},
     zk_evm::zkevm_opcode_defs::system_params,
     Bytes, L1BatchNumber, L2ChainId, MiniblockNumber, H160, H2048, H256, U256,
 };
-use zksync_utils::{bigdecimal_to_u256, miniblock_hash};
+use zksync_utils::bigdecimal_to_u256;
 
 use crate::models::{
     storage_block::{bind_block_where_sql_params, web3_block_number_to_sql, web3_block_where_sql},
     storage_transaction::{extract_web3_transaction, web3_transaction_select_sql, CallTrace},
 };
-use crate::{SqlxError, StorageProcessor};
+use crate::{pruning_dal::HistoryQueryError, SqlxError, StorageProcessor};
 
 const BLOCK_GAS_LIMIT: u32 = system_params::VM_INITIAL_FRAME_ERGS;
 
@@ -30,11 +30,12 @@ pub struct BlocksWeb3Dal<'a, 'c> {
 impl BlocksWeb3Dal<'_, '_> {
     pub async fn get_sealed_miniblock_number(&mut self) -> Result<MiniblockNumber, SqlxError> {
         let started_at = Instant::now();
-        let number: i64 = sqlx::query!("SELECT MAX(number) as \"number\" FROM miniblocks")
-            .fetch_one(self.storage.conn())
-            .await?
-            .number
-            .expect("DAL invocation before genesis");
+        let number: i64 =
+            sqlx::query!("SELECT MAX(number) as \"number\" FROM miniblocks WHERE is_sealed")
+                .fetch_one(self.storage.conn())
+                .await?
+                .number
+                .expect("DAL invocation before genesis");
         metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_sealed_block_number");
         Ok(MiniblockNumber(number as u32))
     }
@@ -50,12 +51,13 @@ impl BlocksWeb3Dal<'_, '_> {
         Ok(L1BatchNumber(number as u32))
     }
 
+    /// Returns [`HistoryQueryError::PrunedHistory`] if transactions of the block are pruned.
     pub async fn get_block_by_web3_block_id(
         &mut self,
         block_id: api::BlockId,
         include_full_transactions: bool,
         chain_id: L2ChainId,
-    ) -> Result<Option<api::Block<api::TransactionVariant>>, SqlxError> {
+    ) -> Result<Option<api::Block<api::TransactionVariant>>, HistoryQueryError> {
         let transactions_sql = if include_full_transactions {
             web3_transaction_select_sql()
         } else {
@@ -69,11 +71,17 @@ impl BlocksWeb3Dal<'_, '_> {
                 miniblocks.l1_batch_number,
                 miniblocks.timestamp,
                 miniblocks.base_fee_per_gas,
+                miniblocks.logs_bloom,
+                miniblocks.gas_used as block_gas_used,
+                miniblocks.gas_limit as block_gas_limit,
+                prev_miniblock.hash as parent_hash,
                 l1_batches.timestamp as l1_batch_timestamp,
                 transactions.gas_limit as gas_limit,
                 transactions.refunded_gas as refunded_gas,
                 {}
             FROM miniblocks
+            LEFT JOIN miniblocks prev_miniblock
+                ON prev_miniblock.number = miniblocks.number - 1
             LEFT JOIN l1_batches
                 ON l1_batches.number = miniblocks.l1_batch_number
             LEFT JOIN transactions
@@ -86,6 +94,7 @@ impl BlocksWeb3Dal<'_, '_> {
         let query = bind_block_where_sql_params(&block_id, sqlx::query(&query));
         let rows = query.fetch_all(self.storage.conn()).await?.into_iter();
 
+        let codecs = self.storage.protected_data_codecs();
         let block = rows.fold(None, |prev_block, db_row| {
             let mut block = prev_block.unwrap_or_else(|| {
                 // This code will be only executed for the first row in the DB response.
@@ -102,11 +111,20 @@ impl BlocksWeb3Dal<'_, '_> {
                     .try_get::<i64, &str>("l1_batch_timestamp")
                     .map(U256::from)
                     .ok();
-                let parent_hash = match number.as_u32() {
-                    0 => H256::zero(),
-                    number => miniblock_hash(MiniblockNumber(number - 1)),
-                };
+                // The genesis miniblock has no parent.
+                let parent_hash = db_row
+                    .get::<Option<Vec<u8>>, &str>("parent_hash")
+                    .map_or_else(H256::zero, |hash| H256::from_slice(&hash));
                 let base_fee_per_gas = db_row.get::<BigDecimal, &str>("base_fee_per_gas");
+                let logs_bloom = db_row
+                    .get::<Option<Vec<u8>>, &str>("logs_bloom")
+                    .map_or_else(H2048::default, |bloom| H2048::from_slice(&bloom));
+                let gas_limit = db_row
+                    .get::<Option<i64>, &str>("block_gas_limit")
+                    .map_or(BLOCK_GAS_LIMIT.into(), |limit| (limit as u64).into());
+                let gas_used = db_row
+                    .get::<Option<i64>, &str>("block_gas_used")
+                    .map_or_else(U256::zero, |gas_used| (gas_used as u64).into());
 
                 api::Block {
                     hash,
@@ -114,20 +132,27 @@ impl BlocksWeb3Dal<'_, '_> {
                     uncles_hash: EMPTY_UNCLES_HASH,
                     number,
                     l1_batch_number,
-                    gas_limit: BLOCK_GAS_LIMIT.into(),
+                    gas_limit,
+                    gas_used,
                     base_fee_per_gas: bigdecimal_to_u256(base_fee_per_gas),
                     timestamp: db_row.get::<i64, &str>("timestamp").into(),
                     l1_batch_timestamp,
+                    logs_bloom,
                     ..api::Block::default()
                 }
             });
             if db_row.try_get::<&[u8], &str>("tx_hash").is_ok() {
-                let tx_gas_limit = bigdecimal_to_u256(db_row.get::<BigDecimal, &str>("gas_limit"));
-                let tx_refunded_gas = U256::from((db_row.get::<i64, &str>("refunded_gas")) as u32);
-
-                block.gas_used += tx_gas_limit - tx_refunded_gas;
+                // Gas used is only summed up for miniblocks sealed without it being persisted.
+                if db_row.get::<Option<i64>, &str>("block_gas_used").is_none() {
+                    let tx_gas_limit =
+                        bigdecimal_to_u256(db_row.get::<BigDecimal, &str>("gas_limit"));
+                    let tx_refunded_gas =
+                        U256::from((db_row.get::<i64, &str>("refunded_gas")) as u32);
+                    block.gas_used += tx_gas_limit - tx_refunded_gas;
+                }
                 let tx = if include_full_transactions {
-                    let tx = extract_web3_transaction(db_row, chain_id);
+                    let mut tx = extract_web3_transaction(db_row, chain_id);
+                    codecs.decode_api_transaction(&mut tx);
                     api::TransactionVariant::Full(tx)
                 } else {
                     api::TransactionVariant::Hash(H256::from_slice(db_row.get("tx_hash")))
@@ -136,6 +161,13 @@ impl BlocksWeb3Dal<'_, '_> {
             }
             Some(block)
         });
+        if let Some(block) = &block {
+            let block_number = MiniblockNumber(block.number.as_u32());
+            self.storage
+                .pruning_dal()
+                .check_miniblock_history(block_number)
+                .await?;
+        }
         Ok(block)
     }
 
@@ -184,10 +216,13 @@ impl BlocksWeb3Dal<'_, '_> {
         from_block: MiniblockNumber,
     ) -> Result<Vec<BlockHeader>, SqlxError> {
         let rows = sqlx::query!(
-            "SELECT hash, number, timestamp \
+            "SELECT miniblocks.hash, miniblocks.number, miniblocks.timestamp, \
+                miniblocks.gas_used, miniblocks.gas_limit, \
+                prev_miniblock.hash AS \"parent_hash?\" \
             FROM miniblocks \
-            WHERE number > $1 \
-            ORDER BY number ASC",
+            LEFT JOIN miniblocks prev_miniblock ON prev_miniblock.number = miniblocks.number - 1 \
+            WHERE miniblocks.number > $1 \
+            ORDER BY miniblocks.number ASC",
             from_block.0 as i64,
         )
         .fetch_all(self.storage.conn())
@@ -195,22 +230,29 @@ impl BlocksWeb3Dal<'_, '_> {
 
         let blocks = rows.into_iter().map(|row| BlockHeader {
             hash: Some(H256::from_slice(&row.hash)),
-            parent_hash: H256::zero(),
+            parent_hash: row
+                .parent_hash
+                .map_or_else(H256::zero, |hash| H256::from_slice(&hash)),
             uncles_hash: EMPTY_UNCLES_HASH,
             author: H160::zero(),
             state_root: H256::zero(),
             transactions_root: H256::zero(),
             receipts_root: H256::zero(),
             number: Some(U64::from(row.number)),
-            gas_used: U256::zero(),
-            gas_limit: U256::zero(),
+            gas_used: row
+                .gas_used
+                .map_or_else(U256::zero, |gas_used| (gas_used as u64).into()),
+            gas_limit: row
+                .gas_limit
+                .map_or(BLOCK_GAS_LIMIT.into(), |limit| (limit as u64).into()),
             base_fee_per_gas: None,
             extra_data: Bytes::default(),
             logs_bloom: H2048::default(),
             timestamp: U256::from(row.timestamp),
             difficulty: U256::zero(),
-            mix_hash: None,
-            nonce: None,
+            // Provided for compatibility with tooling expecting PoW-style block headers.
+            mix_hash: Some(H256::zero()),
+            nonce: Some(H64::zero()),
         });
         Ok(blocks.collect())
     }
@@ -220,13 +262,15 @@ impl BlocksWeb3Dal<'_, '_> {
         block_id: api::BlockId,
     ) -> Result<Option<MiniblockNumber>, SqlxError> {
         let query_string = match block_id {
-            api::BlockId::Hash(_) => "SELECT number FROM miniblocks WHERE hash = $1".to_owned(),
+            api::BlockId::Hash(_) => {
+                "SELECT number FROM miniblocks WHERE hash = $1 AND is_sealed".to_owned()
+            }
             api::BlockId::Number(api::BlockNumber::Number(_)) => {
                 // The reason why instead of returning the `block_number` directly we use query is
                 // to handle numbers of blocks that are not created yet.
                 // the `SELECT number FROM miniblocks WHERE number=block_number` for
                 // non-existing block number will returns zero.
-                "SELECT number FROM miniblocks WHERE number = $1".to_owned()
+                "SELECT number FROM miniblocks WHERE number = $1 AND is_sealed".to_owned()
             }
             api::BlockId::Number(api::BlockNumber::Earliest) => {
                 return Ok(Some(MiniblockNumber(0)));
@@ -336,19 +380,49 @@ impl BlocksWeb3Dal<'_, '_> {
         Ok(result)
     }
 
-    pub async fn get_trace_for_miniblock(&mut self, block_number: MiniblockNumber) -> Vec<Call> {
-        sqlx::query_as!(
+    /// Returns base fees of up to `count` latest miniblocks, ordered from the oldest to the newest one.
+    pub async fn get_recent_base_fees(&mut self, count: usize) -> Result<Vec<u64>, SqlxError> {
+        let started_at = Instant::now();
+        let rows = sqlx::query!(
+            "SELECT base_fee_per_gas FROM miniblocks \
+            ORDER BY number DESC \
+            LIMIT $1",
+            count as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_recent_base_fees");
+
+        let base_fees = rows
+            .into_iter()
+            .rev()
+            .map(|row| bigdecimal_to_u256(row.base_fee_per_gas).as_u64());
+        Ok(base_fees.collect())
+    }
+
+    pub async fn get_trace_for_miniblock(
+        &mut self,
+        block_number: MiniblockNumber,
+    ) -> Result<Vec<(Call, bool)>, HistoryQueryError> {
+        self.storage
+            .pruning_dal()
+            .check_miniblock_history(block_number)
+            .await?;
+        let traces = sqlx::query_as!(
             CallTrace,
             "SELECT * FROM call_traces WHERE tx_hash IN \
                 (SELECT hash FROM transactions WHERE miniblock_number = $1)",
             block_number.0 as i64
         )
         .fetch_all(self.storage.conn())
-        .await
-        .unwrap()
-        .into_iter()
-        .map(Call::from)
-        .collect()
+        .await?;
+        Ok(traces
+            .into_iter()
+            .map(|trace| {
+                let truncated = trace.truncated;
+                (Call::from(trace), truncated)
+            })
+            .collect())
     }
 }
 
@@ -356,6 +430,7 @@ impl BlocksWeb3Dal<'_, '_> {
 mod tests {
     use db_test_macro::db_test;
     use zksync_types::{block::MiniblockHeader, MiniblockNumber};
+    use zksync_utils::miniblock_hash;
 
     use super::*;
     use crate::{tests::create_miniblock_header, ConnectionPool};
@@ -497,4 +572,64 @@ mod tests {
             .await;
         assert_eq!(miniblock_number.unwrap(), None);
     }
+
+    #[db_test(dal_crate)]
+    async fn getting_parent_hash_of_block(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        let genesis_header = create_miniblock_header(0);
+        conn.blocks_dal().insert_miniblock(&genesis_header).await;
+        let header = MiniblockHeader {
+            hash: H256::repeat_byte(0x01),
+            ..create_miniblock_header(1)
+        };
+        conn.blocks_dal().insert_miniblock(&header).await;
+
+        let block_id = api::BlockId::Number(api::BlockNumber::Number(0.into()));
+        let block = conn
+            .blocks_web3_dal()
+            .get_block_by_web3_block_id(block_id, false, L2ChainId(270))
+            .await;
+        assert_eq!(block.unwrap().unwrap().parent_hash, H256::zero());
+
+        let block_id = api::BlockId::Hash(header.hash);
+        let block = conn
+            .blocks_web3_dal()
+            .get_block_by_web3_block_id(block_id, false, L2ChainId(270))
+            .await;
+        let block = block.unwrap().unwrap();
+        assert_eq!(block.number, U64::one());
+        assert_eq!(block.parent_hash, genesis_header.hash);
+
+        let headers = conn
+            .blocks_web3_dal()
+            .get_block_headers_after(MiniblockNumber(0))
+            .await
+            .unwrap();
+        assert_eq!(headers.len(), 1);
+        assert_eq!(headers[0].hash, Some(header.hash));
+        assert_eq!(headers[0].parent_hash, genesis_header.hash);
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_recent_base_fees(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        for number in 0..3 {
+            let header = MiniblockHeader {
+                base_fee_per_gas: 100 + u64::from(number),
+                ..create_miniblock_header(number)
+            };
+            conn.blocks_dal().insert_miniblock(&header).await;
+        }
+
+        let base_fees = conn.blocks_web3_dal().get_recent_base_fees(2).await;
+        assert_eq!(base_fees.unwrap(), [101, 102]);
+        let base_fees = conn.blocks_web3_dal().get_recent_base_fees(10).await;
+        assert_eq!(base_fees.unwrap(), [100, 101, 102]);
+    }
 }
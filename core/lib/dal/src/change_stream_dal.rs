@@ -0,0 +1,157 @@
+//! Storage for the change data capture stream publishing sealed miniblocks to external brokers.
+//!
+//! Each publisher records the last miniblock acknowledged by the broker in the `change_stream_offsets` table,
+//! so that publishing can be resumed after a restart without gaps. Since the offset is updated only after
+//! the broker acknowledges a message, a miniblock may be published more than once (at-least-once delivery).
+
+use futures::{Stream, StreamExt};
+
+use zksync_types::{AccountTreeId, Address, MiniblockNumber, StorageKey, StorageLog, H256};
+
+use crate::{SqlxError, StorageProcessor};
+
+#[derive(Debug)]
+pub struct ChangeStreamDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ChangeStreamDal<'_, '_> {
+    /// Returns the last miniblock acknowledged by the broker for the specified publisher, or `None`
+    /// if the publisher hasn't published anything yet.
+    pub async fn get_last_published_miniblock(
+        &mut self,
+        publisher_name: &str,
+    ) -> Result<Option<MiniblockNumber>, SqlxError> {
+        let row = sqlx::query!(
+            "SELECT last_published_miniblock FROM change_stream_offsets WHERE publisher_name = $1",
+            publisher_name
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        Ok(row.map(|row| MiniblockNumber(row.last_published_miniblock as u32)))
+    }
+
+    pub async fn set_last_published_miniblock(
+        &mut self,
+        publisher_name: &str,
+        miniblock_number: MiniblockNumber,
+    ) -> Result<(), SqlxError> {
+        sqlx::query!(
+            "INSERT INTO change_stream_offsets \
+                (publisher_name, last_published_miniblock, created_at, updated_at) \
+            VALUES ($1, $2, now(), now()) \
+            ON CONFLICT (publisher_name) DO UPDATE SET \
+                last_published_miniblock = $2, updated_at = now()",
+            publisher_name,
+            miniblock_number.0 as i64
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Streams storage writes performed in the specified miniblock, in the execution order. Writes are not
+    /// materialized in memory, so that large miniblocks can be converted to messages without intermediate copies.
+    pub fn stream_miniblock_storage_writes(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> impl Stream<Item = Result<(H256, StorageLog), SqlxError>> + '_ {
+        sqlx::query!(
+            "SELECT address, key, value, tx_hash FROM storage_logs \
+            WHERE miniblock_number = $1 \
+            ORDER BY operation_number",
+            miniblock_number.0 as i64
+        )
+        .fetch(self.storage.conn())
+        .map(|row| {
+            let row = row?;
+            let address = Address::from_slice(&row.address);
+            let key = StorageKey::new(AccountTreeId::new(address), H256::from_slice(&row.key));
+            let value = H256::from_slice(&row.value);
+            let tx_hash = H256::from_slice(&row.tx_hash);
+            Ok((tx_hash, StorageLog::new_write_log(key, value)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use db_test_macro::db_test;
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::{tests::create_miniblock_header, ConnectionPool};
+
+    #[db_test(dal_crate)]
+    async fn publisher_offsets(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        let offset = conn
+            .change_stream_dal()
+            .get_last_published_miniblock("indexer")
+            .await
+            .unwrap();
+        assert_eq!(offset, None);
+
+        for number in [3, 5] {
+            conn.change_stream_dal()
+                .set_last_published_miniblock("indexer", MiniblockNumber(number))
+                .await
+                .unwrap();
+            let offset = conn
+                .change_stream_dal()
+                .get_last_published_miniblock("indexer")
+                .await
+                .unwrap();
+            assert_eq!(offset, Some(MiniblockNumber(number)));
+        }
+
+        let other_offset = conn
+            .change_stream_dal()
+            .get_last_published_miniblock("other")
+            .await
+            .unwrap();
+        assert_eq!(other_offset, None);
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_miniblock_storage_writes(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await;
+
+        let tx_hash = H256::repeat_byte(1);
+        let logs: Vec<_> = (0_u8..3)
+            .map(|i| {
+                let key = StorageKey::new(
+                    AccountTreeId::new(Address::repeat_byte(i)),
+                    H256::repeat_byte(i),
+                );
+                StorageLog::new_write_log(key, H256::repeat_byte(0xff - i))
+            })
+            .collect();
+        conn.storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(1), &[(tx_hash, logs.clone())])
+            .await;
+
+        let writes: Vec<_> = conn
+            .change_stream_dal()
+            .stream_miniblock_storage_writes(MiniblockNumber(1))
+            .try_collect()
+            .await
+            .unwrap();
+        let expected: Vec<_> = logs.into_iter().map(|log| (tx_hash, log)).collect();
+        assert_eq!(writes, expected);
+
+        let writes: Vec<_> = conn
+            .change_stream_dal()
+            .stream_miniblock_storage_writes(MiniblockNumber(2))
+            .try_collect()
+            .await
+            .unwrap();
+        assert!(writes.is_empty());
+    }
+}
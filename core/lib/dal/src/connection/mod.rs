@@ -37,10 +37,16 @@ impl ConnectionPool {
             DbVariant::Replica => get_replica_database_url(),
             DbVariant::Prover => get_prover_database_url(),
         };
+        Self::with_url(&database_url, pool_max_size).await
+    }
+
+    /// Establishes a pool of the connections to the database with the specified URL, e.g. a database
+    /// of another chain. `pool_max_size` has the same meaning as in [`Self::new()`].
+    pub async fn with_url(database_url: &str, pool_max_size: Option<u32>) -> Self {
         let max_connections = pool_max_size.unwrap_or_else(|| parse_env("DATABASE_POOL_SIZE"));
 
         let options = PgPoolOptions::new().max_connections(max_connections);
-        let pool = options.connect(&database_url).await.unwrap();
+        let pool = options.connect(database_url).await.unwrap();
         Self::Real(pool)
     }
 
@@ -0,0 +1,262 @@
+//! Pluggable codecs for data in protected columns, e.g. at-rest encryption or redaction of transaction calldata
+//! and event data for chains with data privacy requirements.
+//!
+//! Codecs are installed once per process using [`DataCodecs::install()`]. Values are encoded when written
+//! to the database and decoded when read, unless access to protected data is restricted for the connection
+//! (see [`StorageProcessor::restrict_protected_data_access()`]); in the latter case, values are returned as stored.
+//! Call traces are not covered by codecs. Archived and exported data contains values as stored.
+//!
+//! [`StorageProcessor::restrict_protected_data_access()`]: crate::StorageProcessor::restrict_protected_data_access()
+
+use once_cell::sync::OnceCell;
+
+use std::{borrow::Cow, fmt, sync::Arc};
+
+use zksync_config::configs::database::{DBConfig, ProtectedDataCodec};
+use zksync_types::{api, Execute, ExecuteTransactionCommon, Transaction};
+
+static DATA_CODECS: OnceCell<DataCodecs> = OnceCell::new();
+
+/// Column which data can be encoded using a [`DataCodec`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtectedColumn {
+    /// Calldata of transactions (including the raw bytes of L2 transactions).
+    TransactionCalldata,
+    /// Data of events (i.e., non-indexed event fields).
+    EventData,
+}
+
+/// Codec applied to values of a [`ProtectedColumn`].
+pub trait DataCodec: fmt::Debug + Send + Sync + 'static {
+    /// Encodes a value before writing it to the database.
+    fn encode(&self, data: &[u8]) -> Vec<u8>;
+
+    /// Decodes a value read from the database. Returns `None` if the value cannot be decoded
+    /// (e.g., if it was redacted).
+    fn decode(&self, stored: &[u8]) -> Option<Vec<u8>>;
+
+    /// Whether encoded values can be decoded back. Only reversible codecs can be used for transaction calldata,
+    /// since transactions are re-executed from the database.
+    fn is_reversible(&self) -> bool {
+        true
+    }
+}
+
+/// Codec erasing values.
+#[derive(Debug, Clone, Copy)]
+pub struct RedactingCodec;
+
+impl DataCodec for RedactingCodec {
+    fn encode(&self, _data: &[u8]) -> Vec<u8> {
+        Vec::new()
+    }
+
+    fn decode(&self, _stored: &[u8]) -> Option<Vec<u8>> {
+        None
+    }
+
+    fn is_reversible(&self) -> bool {
+        false
+    }
+}
+
+/// Codecs used for protected columns. By default, values in all columns are stored as is.
+#[derive(Debug, Clone, Default)]
+pub struct DataCodecs {
+    transaction_calldata: Option<Arc<dyn DataCodec>>,
+    event_data: Option<Arc<dyn DataCodec>>,
+}
+
+impl DataCodecs {
+    pub fn from_config(config: &DBConfig) -> Self {
+        let mut codecs = Self::default();
+        if let Some(ProtectedDataCodec::Redact) = config.events_data_codec {
+            codecs = codecs.with_codec(ProtectedColumn::EventData, Arc::new(RedactingCodec));
+        }
+        codecs
+    }
+
+    /// Sets the codec for the specified column.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an irreversible codec is set for transaction calldata.
+    pub fn with_codec(mut self, column: ProtectedColumn, codec: Arc<dyn DataCodec>) -> Self {
+        match column {
+            ProtectedColumn::TransactionCalldata => {
+                assert!(
+                    codec.is_reversible(),
+                    "Codec {:?} cannot be used for transaction calldata since it is irreversible",
+                    codec
+                );
+                self.transaction_calldata = Some(codec);
+            }
+            ProtectedColumn::EventData => {
+                self.event_data = Some(codec);
+            }
+        }
+        self
+    }
+
+    /// Installs codecs for the current process. Should be called before any data is written to the database.
+    ///
+    /// # Panics
+    ///
+    /// Panics if codecs were already installed.
+    pub fn install(self) {
+        vlog::info!("Installing data codecs for protected columns: {:?}", self);
+        DATA_CODECS
+            .set(self)
+            .expect("Data codecs are already installed");
+    }
+
+    /// Returns installed codecs, or [`Self::none()`] if codecs were not installed.
+    pub(crate) fn global() -> &'static Self {
+        DATA_CODECS.get().unwrap_or_else(Self::none)
+    }
+
+    /// Returns codecs leaving values in all columns as is.
+    pub(crate) fn none() -> &'static Self {
+        static NO_CODECS: DataCodecs = DataCodecs {
+            transaction_calldata: None,
+            event_data: None,
+        };
+        &NO_CODECS
+    }
+
+    fn codec(&self, column: ProtectedColumn) -> Option<&dyn DataCodec> {
+        match column {
+            ProtectedColumn::TransactionCalldata => self.transaction_calldata.as_deref(),
+            ProtectedColumn::EventData => self.event_data.as_deref(),
+        }
+    }
+
+    pub(crate) fn encode<'a>(&self, column: ProtectedColumn, data: &'a [u8]) -> Cow<'a, [u8]> {
+        match self.codec(column) {
+            Some(codec) => Cow::Owned(codec.encode(data)),
+            None => Cow::Borrowed(data),
+        }
+    }
+
+    /// Decodes a stored value. Values that cannot be decoded are returned as stored.
+    pub(crate) fn decode(&self, column: ProtectedColumn, stored: Vec<u8>) -> Vec<u8> {
+        match self.codec(column) {
+            Some(codec) => codec.decode(&stored).unwrap_or(stored),
+            None => stored,
+        }
+    }
+
+    /// Serializes transaction `execute` data with encoded calldata.
+    pub(crate) fn encode_execute(&self, execute: &Execute) -> serde_json::Value {
+        let mut value =
+            serde_json::to_value(execute).expect("failed to serialize transaction data");
+        if let Cow::Owned(calldata) =
+            self.encode(ProtectedColumn::TransactionCalldata, &execute.calldata)
+        {
+            value["calldata"] = serde_json::Value::String(format!("0x{}", hex::encode(calldata)));
+        }
+        value
+    }
+
+    pub(crate) fn decode_transaction(&self, tx: &mut Transaction) {
+        if self.transaction_calldata.is_none() {
+            return;
+        }
+        let calldata = std::mem::take(&mut tx.execute.calldata);
+        tx.execute.calldata = self.decode(ProtectedColumn::TransactionCalldata, calldata);
+        if let ExecuteTransactionCommon::L2(common_data) = &mut tx.common_data {
+            if let Some(input) = &mut common_data.input {
+                let data = std::mem::take(&mut input.data);
+                input.data = self.decode(ProtectedColumn::TransactionCalldata, data);
+            }
+        }
+    }
+
+    pub(crate) fn decode_api_transaction(&self, tx: &mut api::Transaction) {
+        let input = std::mem::take(&mut tx.input.0);
+        tx.input.0 = self.decode(ProtectedColumn::TransactionCalldata, input);
+    }
+
+    pub(crate) fn decode_logs(&self, logs: &mut [api::Log]) {
+        if self.event_data.is_none() {
+            return;
+        }
+        for log in logs {
+            let data = std::mem::take(&mut log.data.0);
+            log.data.0 = self.decode(ProtectedColumn::EventData, data);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reversible codec used in tests.
+    #[derive(Debug)]
+    struct XorCodec(u8);
+
+    impl DataCodec for XorCodec {
+        fn encode(&self, data: &[u8]) -> Vec<u8> {
+            data.iter().map(|byte| byte ^ self.0).collect()
+        }
+
+        fn decode(&self, stored: &[u8]) -> Option<Vec<u8>> {
+            Some(self.encode(stored))
+        }
+    }
+
+    #[test]
+    fn encoding_and_decoding_values() {
+        let codecs = DataCodecs::default()
+            .with_codec(
+                ProtectedColumn::TransactionCalldata,
+                Arc::new(XorCodec(0xff)),
+            )
+            .with_codec(ProtectedColumn::EventData, Arc::new(RedactingCodec));
+
+        let calldata = [1_u8, 2, 3];
+        let encoded = codecs.encode(ProtectedColumn::TransactionCalldata, &calldata);
+        assert_eq!(*encoded, [0xfe, 0xfd, 0xfc]);
+        let decoded = codecs.decode(ProtectedColumn::TransactionCalldata, encoded.into_owned());
+        assert_eq!(decoded, calldata);
+
+        let encoded = codecs.encode(ProtectedColumn::EventData, &calldata);
+        assert!(encoded.is_empty());
+        let decoded = codecs.decode(ProtectedColumn::EventData, encoded.into_owned());
+        assert!(decoded.is_empty());
+
+        let no_codecs = DataCodecs::default();
+        let encoded = no_codecs.encode(ProtectedColumn::EventData, &calldata);
+        assert!(matches!(encoded, Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn encoding_execute_data() {
+        let codecs = DataCodecs::default().with_codec(
+            ProtectedColumn::TransactionCalldata,
+            Arc::new(XorCodec(0xff)),
+        );
+        let execute = Execute {
+            contract_address: Default::default(),
+            calldata: vec![0, 1],
+            value: Default::default(),
+            factory_deps: None,
+        };
+        let value = codecs.encode_execute(&execute);
+        assert_eq!(value["calldata"], "0xfffe");
+
+        let mut decoded: Execute = serde_json::from_value(value).unwrap();
+        decoded.calldata = codecs.decode(ProtectedColumn::TransactionCalldata, decoded.calldata);
+        assert_eq!(decoded.calldata, execute.calldata);
+    }
+
+    #[test]
+    #[should_panic(expected = "irreversible")]
+    fn redacting_calldata_is_not_allowed() {
+        DataCodecs::default().with_codec(
+            ProtectedColumn::TransactionCalldata,
+            Arc::new(RedactingCodec),
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! Export of chain data for offline analytics.
+//!
+//! Rows are returned with all values converted to text (byte arrays are hex-encoded with the `0x` prefix),
+//! so that they can be written to a tabular format without knowing column types. Only partitions attached
+//! to `events` and `storage_logs` are visible to the export; archived partitions are already exported
+//! to the object store by the partition archiver.
+
+use sqlx::Row;
+
+use std::{fmt, ops::Range, str::FromStr, time::Instant};
+
+use zksync_types::MiniblockNumber;
+
+use crate::StorageProcessor;
+
+/// Table that can be exported using [`DataExportDal`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExportedTable {
+    Transactions,
+    Events,
+    /// Storage writes (i.e., storage diffs) of executed transactions.
+    StorageLogs,
+}
+
+impl ExportedTable {
+    pub const ALL: [Self; 3] = [Self::Transactions, Self::Events, Self::StorageLogs];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Transactions => "transactions",
+            Self::Events => "events",
+            Self::StorageLogs => "storage_logs",
+        }
+    }
+
+    /// Returns exported column names together with SQL expressions converting column values to text.
+    fn column_expressions(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Transactions => &[
+                ("hash", "'0x' || encode(hash, 'hex')"),
+                ("miniblock_number", "miniblock_number::text"),
+                ("index_in_block", "index_in_block::text"),
+                ("is_priority", "is_priority::text"),
+                (
+                    "initiator_address",
+                    "'0x' || encode(initiator_address, 'hex')",
+                ),
+                ("nonce", "nonce::text"),
+                (
+                    "contract_address",
+                    "'0x' || encode(contract_address, 'hex')",
+                ),
+                ("value", "value::text"),
+                ("gas_limit", "gas_limit::text"),
+                ("effective_gas_price", "effective_gas_price::text"),
+                ("refunded_gas", "refunded_gas::text"),
+                ("error", "error"),
+                ("received_at", "received_at::text"),
+            ],
+            Self::Events => &[
+                ("miniblock_number", "miniblock_number::text"),
+                ("tx_hash", "'0x' || encode(tx_hash, 'hex')"),
+                ("tx_index_in_block", "tx_index_in_block::text"),
+                ("event_index_in_block", "event_index_in_block::text"),
+                ("event_index_in_tx", "event_index_in_tx::text"),
+                ("address", "'0x' || encode(address, 'hex')"),
+                ("topic1", "'0x' || encode(topic1, 'hex')"),
+                ("topic2", "'0x' || encode(topic2, 'hex')"),
+                ("topic3", "'0x' || encode(topic3, 'hex')"),
+                ("topic4", "'0x' || encode(topic4, 'hex')"),
+                ("value", "'0x' || encode(value, 'hex')"),
+            ],
+            Self::StorageLogs => &[
+                ("miniblock_number", "miniblock_number::text"),
+                ("operation_number", "operation_number::text"),
+                ("tx_hash", "'0x' || encode(tx_hash, 'hex')"),
+                ("address", "'0x' || encode(address, 'hex')"),
+                ("key", "'0x' || encode(key, 'hex')"),
+                ("value", "'0x' || encode(value, 'hex')"),
+            ],
+        }
+    }
+
+    fn order_by(self) -> &'static str {
+        match self {
+            Self::Transactions => "miniblock_number, index_in_block",
+            Self::Events => "miniblock_number, event_index_in_block",
+            Self::StorageLogs => "miniblock_number, operation_number",
+        }
+    }
+
+    /// Returns names of the exported columns in the order of values in exported rows.
+    pub fn columns(self) -> Vec<&'static str> {
+        self.column_expressions()
+            .iter()
+            .map(|&(name, _)| name)
+            .collect()
+    }
+}
+
+impl fmt::Display for ExportedTable {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ExportedTable {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|table| table.as_str() == s)
+            .ok_or_else(|| format!("unknown exported table: `{s}`"))
+    }
+}
+
+#[derive(Debug)]
+pub struct DataExportDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl DataExportDal<'_, '_> {
+    /// Returns rows of the specified table for the miniblock range. Values in each row are ordered
+    /// according to [`ExportedTable::columns()`]; `None` corresponds to `NULL`.
+    pub async fn export_rows(
+        &mut self,
+        table: ExportedTable,
+        miniblocks: Range<MiniblockNumber>,
+    ) -> Vec<Vec<Option<String>>> {
+        let started_at = Instant::now();
+        let columns = table.column_expressions();
+        let select_list = columns
+            .iter()
+            .map(|&(name, expression)| format!("{expression} AS {name}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let query = format!(
+            "SELECT {select_list} FROM {table} \
+            WHERE miniblock_number >= $1 AND miniblock_number < $2 \
+            ORDER BY {}",
+            table.order_by()
+        );
+        let rows = sqlx::query(&query)
+            .bind(miniblocks.start.0 as i64)
+            .bind(miniblocks.end.0 as i64)
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap();
+
+        let rows = rows
+            .into_iter()
+            .map(|row| {
+                (0..columns.len())
+                    .map(|i| row.get::<Option<String>, _>(i))
+                    .collect()
+            })
+            .collect();
+        metrics::histogram!(
+            "dal.request",
+            started_at.elapsed(),
+            "method" => "export_rows",
+            "table" => table.as_str()
+        );
+        rows
+    }
+}
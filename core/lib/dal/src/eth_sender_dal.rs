@@ -10,7 +10,7 @@ use std::convert::TryFrom;
 use std::str::FromStr;
 use zksync_types::aggregated_operations::AggregatedActionType;
 use zksync_types::eth_sender::{EthTx, TxHistory, TxHistoryToSend};
-use zksync_types::{Address, L1BatchNumber, H256, U256};
+use zksync_types::{Address, L1BatchNumber, Nonce, H256, U256};
 
 #[derive(Debug)]
 pub struct EthSenderDal<'a, 'c> {
@@ -378,12 +378,15 @@ impl EthSenderDal<'_, '_> {
         }
     }
 
+    /// Returns the L1 block at which the first attempt to send the eth_tx with its current nonce was made.
     pub async fn get_block_number_on_first_sent_attempt(&mut self, eth_tx_id: u32) -> Option<u32> {
         {
             let sent_at_block = sqlx::query_scalar!(
-            "SELECT sent_at_block FROM eth_txs_history WHERE eth_tx_id = $1 AND sent_at_block IS NOT NULL ORDER BY created_at ASC LIMIT 1",
-            eth_tx_id as i32
-        )
+                "SELECT sent_at_block FROM eth_txs_history \
+                 WHERE eth_tx_id = $1 AND sent_at_block IS NOT NULL AND NOT nonce_consumed_externally \
+                 ORDER BY created_at ASC LIMIT 1",
+                eth_tx_id as i32
+            )
             .fetch_optional(self.storage.conn())
             .await
             .unwrap();
@@ -415,6 +418,48 @@ impl EthSenderDal<'_, '_> {
         }
     }
 
+    /// Marks sending attempts of the specified eth_txs as never to be mined, because their nonces were consumed
+    /// by transactions sent from the operator account bypassing eth_sender. Attempts that weren't sent are removed.
+    pub async fn mark_nonces_consumed_externally(&mut self, eth_tx_ids: &[u32]) {
+        {
+            let eth_tx_ids: Vec<_> = eth_tx_ids.iter().map(|&id| id as i32).collect();
+            sqlx::query!(
+                "DELETE FROM eth_txs_history WHERE eth_tx_id = ANY($1) AND sent_at_block IS NULL",
+                &eth_tx_ids
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+            sqlx::query!(
+                "UPDATE eth_txs_history SET nonce_consumed_externally = TRUE, updated_at = now() \
+                 WHERE eth_tx_id = ANY($1)",
+                &eth_tx_ids
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        }
+    }
+
+    /// Assigns sequential nonces starting from `first_nonce` to all eth_txs with IDs starting from `first_eth_tx_id`.
+    pub async fn reassign_nonces(&mut self, first_eth_tx_id: u32, first_nonce: Nonce) {
+        {
+            sqlx::query!(
+                "UPDATE eth_txs SET nonce = renumbered.nonce, updated_at = now() \
+                 FROM ( \
+                     SELECT id, $2 + ROW_NUMBER() OVER (ORDER BY id) - 1 AS nonce \
+                     FROM eth_txs WHERE id >= $1 \
+                 ) AS renumbered \
+                 WHERE eth_txs.id = renumbered.id",
+                first_eth_tx_id as i32,
+                first_nonce.0 as i64
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        }
+    }
+
     pub async fn mark_failed_transaction(&mut self, eth_tx_id: u32) {
         {
             sqlx::query!(
@@ -0,0 +1,137 @@
+use zksync_types::{api::RejectedPriorityOp, L1BlockNumber, PriorityOpId, H256};
+
+use crate::StorageProcessor;
+
+/// Checkpoints of L1 watchers, i.e., the last L1 block processed by each watcher.
+/// Checkpoints allow watchers to resume from the exact block after a restart.
+///
+/// Also stores the priority mode state. The priority mode is activated by the priority ops watcher
+/// once a priority operation is not processed before its deadline; while it's active,
+/// the state keeper only includes L1 transactions into new L1 batches.
+///
+/// Priority operations rejected by the watcher (e.g., because of an excessive gas limit) are stored
+/// together with rejection reasons instead of being added to the mempool.
+#[derive(Debug)]
+pub struct EthWatcherDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl EthWatcherDal<'_, '_> {
+    pub async fn get_last_processed_l1_block(
+        &mut self,
+        watcher_name: &str,
+    ) -> Option<L1BlockNumber> {
+        sqlx::query!(
+            "SELECT last_processed_l1_block FROM eth_watcher_checkpoints WHERE watcher_name = $1",
+            watcher_name
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()
+        .map(|row| L1BlockNumber(row.last_processed_l1_block as u32))
+    }
+
+    pub async fn save_last_processed_l1_block(
+        &mut self,
+        watcher_name: &str,
+        l1_block_number: L1BlockNumber,
+    ) {
+        sqlx::query!(
+            "INSERT INTO eth_watcher_checkpoints \
+                (watcher_name, last_processed_l1_block, created_at, updated_at) \
+            VALUES ($1, $2, now(), now()) \
+            ON CONFLICT (watcher_name) DO UPDATE \
+            SET last_processed_l1_block = $2, updated_at = now()",
+            watcher_name,
+            l1_block_number.0 as i64
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Returns the ID of the first expired priority operation if the priority mode is active.
+    pub async fn get_priority_mode(&mut self) -> Option<PriorityOpId> {
+        sqlx::query!("SELECT first_expired_priority_op_id FROM priority_mode")
+            .fetch_optional(self.storage.conn())
+            .await
+            .unwrap()
+            .map(|row| PriorityOpId(row.first_expired_priority_op_id as u64))
+    }
+
+    pub async fn activate_priority_mode(
+        &mut self,
+        first_expired_op_id: PriorityOpId,
+        l1_block_number: L1BlockNumber,
+    ) {
+        sqlx::query!(
+            "INSERT INTO priority_mode \
+                (id, first_expired_priority_op_id, activated_at_l1_block, created_at, updated_at) \
+            VALUES (TRUE, $1, $2, now(), now()) \
+            ON CONFLICT (id) DO NOTHING",
+            first_expired_op_id.0 as i64,
+            l1_block_number.0 as i64
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    pub async fn deactivate_priority_mode(&mut self) {
+        sqlx::query!("DELETE FROM priority_mode")
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+    }
+
+    pub async fn insert_rejected_priority_op(
+        &mut self,
+        op_id: PriorityOpId,
+        tx_hash: H256,
+        l1_block_number: L1BlockNumber,
+        reason: &str,
+    ) {
+        sqlx::query!(
+            "INSERT INTO rejected_priority_ops \
+                (priority_op_id, tx_hash, l1_block_number, reason, created_at, updated_at) \
+            VALUES ($1, $2, $3, $4, now(), now()) \
+            ON CONFLICT (priority_op_id) DO NOTHING",
+            op_id.0 as i64,
+            tx_hash.as_bytes(),
+            l1_block_number.0 as i64,
+            reason
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    pub async fn get_last_rejected_priority_id(&mut self) -> Option<PriorityOpId> {
+        sqlx::query!("SELECT MAX(priority_op_id) AS priority_op_id FROM rejected_priority_ops")
+            .fetch_one(self.storage.conn())
+            .await
+            .unwrap()
+            .priority_op_id
+            .map(|id| PriorityOpId(id as u64))
+    }
+
+    /// Returns up to `limit` most recently rejected priority operations, starting from the latest one.
+    pub async fn get_rejected_priority_ops(&mut self, limit: usize) -> Vec<RejectedPriorityOp> {
+        sqlx::query!(
+            "SELECT priority_op_id, tx_hash, l1_block_number, reason FROM rejected_priority_ops \
+            ORDER BY priority_op_id DESC LIMIT $1",
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| RejectedPriorityOp {
+            serial_id: (row.priority_op_id as u64).into(),
+            transaction_hash: H256::from_slice(&row.tx_hash),
+            l1_block_number: (row.l1_block_number as u64).into(),
+            reason: row.reason,
+        })
+        .collect()
+    }
+}
@@ -1,10 +1,15 @@
+use futures::{Stream, StreamExt};
 use sqlx::types::chrono::Utc;
 
 use std::fmt;
 
-use crate::{models::storage_event::StorageL2ToL1Log, SqlxError, StorageProcessor};
+use crate::data_codec::{DataCodecs, ProtectedColumn};
+use crate::StorageProcessor;
 use zksync_types::{
-    l2_to_l1_log::L2ToL1Log, tx::IncludedTxLocation, MiniblockNumber, VmEvent, H256,
+    event::{EventIndexingPolicy, L1MessengerMessage},
+    l2_to_l1_log::L2ToL1Log,
+    tx::IncludedTxLocation,
+    Address, L1BatchNumber, MiniblockNumber, VmEvent, H256,
 };
 
 /// Wrapper around an optional event topic allowing to hex-format it for `COPY` instructions.
@@ -27,11 +32,12 @@ pub struct EventsDal<'a, 'c> {
 }
 
 impl EventsDal<'_, '_> {
-    /// Saves events for the specified miniblock.
+    /// Saves events for the specified miniblock. Events not matching `indexing_policy` are stored raw-only.
     pub async fn save_events(
         &mut self,
         block_number: MiniblockNumber,
         all_block_events: &[(IncludedTxLocation, Vec<&VmEvent>)],
+        indexing_policy: &EventIndexingPolicy,
     ) {
         let mut copy = self
             .storage
@@ -41,7 +47,7 @@ impl EventsDal<'_, '_> {
                     miniblock_number, tx_hash, tx_index_in_block, address,
                     event_index_in_block, event_index_in_tx,
                     topic1, topic2, topic3, topic4, value,
-                    tx_initiator_address,
+                    tx_initiator_address, is_indexed,
                     created_at, updated_at
                 )
                 FROM STDIN WITH (DELIMITER '|')",
@@ -49,9 +55,11 @@ impl EventsDal<'_, '_> {
             .await
             .unwrap();
 
+        let codecs = DataCodecs::global();
         let mut buffer = String::new();
         let now = Utc::now().naive_utc().to_string();
         let mut event_index_in_block = 0_u32;
+        let mut raw_only_event_count = 0_u64;
         for (tx_location, events) in all_block_events {
             let IncludedTxLocation {
                 tx_hash,
@@ -74,10 +82,12 @@ impl EventsDal<'_, '_> {
                     topic2 = EventTopic(event.indexed_topics.get(2)),
                     topic3 = EventTopic(event.indexed_topics.get(3))
                 );
+                let is_indexed = indexing_policy.is_indexed(event);
+                raw_only_event_count += u64::from(!is_indexed);
                 writeln_str!(
                     &mut buffer,
-                    r"\\x{value}|\\x{tx_initiator_address:x}|{now}|{now}",
-                    value = hex::encode(&event.value)
+                    r"\\x{value}|\\x{tx_initiator_address:x}|{is_indexed}|{now}|{now}",
+                    value = hex::encode(codecs.encode(ProtectedColumn::EventData, &event.value))
                 );
 
                 event_index_in_block += 1;
@@ -86,6 +96,7 @@ impl EventsDal<'_, '_> {
         copy.send(buffer.as_bytes()).await.unwrap();
         // note: all the time spent in this function is spent in `copy.finish()`
         copy.finish().await.unwrap();
+        metrics::counter!("dal.events.raw_only", raw_only_event_count);
     }
 
     /// Removes events with a block number strictly greater than the specified `block_number`.
@@ -172,32 +183,114 @@ impl EventsDal<'_, '_> {
         .unwrap();
     }
 
-    pub(crate) async fn l2_to_l1_logs(
+    /// Saves messages sent via the L1 messenger in an L1 batch.
+    pub async fn insert_l1_messenger_messages(
         &mut self,
-        tx_hash: H256,
-    ) -> Result<Vec<StorageL2ToL1Log>, SqlxError> {
-        sqlx::query_as!(
-            StorageL2ToL1Log,
-            "SELECT \
-                miniblock_number, log_index_in_miniblock, log_index_in_tx, tx_hash, \
-                Null::bytea as \"block_hash\", Null::bigint as \"l1_batch_number?\", \
-                shard_id, is_service, tx_index_in_miniblock, tx_index_in_l1_batch, sender, key, value \
-            FROM l2_to_l1_logs \
-            WHERE tx_hash = $1 \
-            ORDER BY log_index_in_tx ASC",
-            tx_hash.as_bytes()
+        l1_batch_number: L1BatchNumber,
+        messages: &[L1MessengerMessage],
+    ) {
+        let mut indices = Vec::with_capacity(messages.len());
+        let mut tx_indices = Vec::with_capacity(messages.len());
+        let mut senders = Vec::with_capacity(messages.len());
+        let mut hashes = Vec::with_capacity(messages.len());
+        let mut contents = Vec::with_capacity(messages.len());
+        let mut log_indices = Vec::with_capacity(messages.len());
+        for message in messages {
+            indices.push(message.index_in_l1_batch as i32);
+            tx_indices.push(i32::from(message.tx_index_in_l1_batch));
+            senders.push(message.sender.as_bytes().to_vec());
+            hashes.push(message.hash.as_bytes().to_vec());
+            contents.push(message.message.clone());
+            log_indices.push(message.l2_to_l1_log_index.map(|index| index as i32));
+        }
+
+        sqlx::query!(
+            "INSERT INTO l1_messenger_messages \
+            (l1_batch_number, index_in_l1_batch, tx_index_in_l1_batch, sender, hash, message, l2_to_l1_log_index) \
+            SELECT $1, u.index_in_l1_batch, u.tx_index_in_l1_batch, u.sender, u.hash, u.message, u.l2_to_l1_log_index \
+            FROM UNNEST($2::INT[], $3::INT[], $4::BYTEA[], $5::BYTEA[], $6::BYTEA[], $7::INT[]) \
+            AS u(index_in_l1_batch, tx_index_in_l1_batch, sender, hash, message, l2_to_l1_log_index)",
+            l1_batch_number.0 as i64,
+            &indices,
+            &tx_indices,
+            &senders,
+            &hashes,
+            &contents,
+            &log_indices as &[Option<i32>]
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Returns the message with the specified index among messages sent via the L1 messenger in an L1 batch.
+    pub async fn get_l1_messenger_message(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        index_in_l1_batch: u32,
+    ) -> Option<L1MessengerMessage> {
+        let row = sqlx::query!(
+            "SELECT tx_index_in_l1_batch, sender, hash, message, l2_to_l1_log_index \
+            FROM l1_messenger_messages WHERE l1_batch_number = $1 AND index_in_l1_batch = $2",
+            l1_batch_number.0 as i64,
+            index_in_l1_batch as i32
         )
-        .fetch_all(self.storage.conn())
+        .fetch_optional(self.storage.conn())
         .await
+        .unwrap()?;
+
+        Some(L1MessengerMessage {
+            index_in_l1_batch,
+            tx_index_in_l1_batch: row.tx_index_in_l1_batch as u16,
+            sender: Address::from_slice(&row.sender),
+            hash: H256::from_slice(&row.hash),
+            message: row.message,
+            l2_to_l1_log_index: row.l2_to_l1_log_index.map(|index| index as u32),
+        })
+    }
+
+    /// Streams events in the specified miniblock range (inclusive) ordered by miniblock and index
+    /// in the miniblock. Unlike collecting all events, this doesn't materialize them in memory, and thus
+    /// is suitable for processing large ranges. The location of returned events is not set.
+    pub fn stream_events(
+        &mut self,
+        miniblock_range: (MiniblockNumber, MiniblockNumber),
+    ) -> impl Stream<Item = (MiniblockNumber, VmEvent)> + '_ {
+        let codecs = self.storage.protected_data_codecs();
+        sqlx::query!(
+            "SELECT miniblock_number, address, topic1, topic2, topic3, topic4, value FROM events \
+            WHERE miniblock_number BETWEEN $1 AND $2 \
+            ORDER BY miniblock_number, event_index_in_block",
+            miniblock_range.0 .0 as i64,
+            miniblock_range.1 .0 as i64
+        )
+        .fetch(self.storage.conn())
+        .map(|row| {
+            let row = row.unwrap();
+            let indexed_topics = [row.topic1, row.topic2, row.topic3, row.topic4]
+                .iter()
+                .filter(|topic| !topic.is_empty())
+                .map(|topic| H256::from_slice(topic))
+                .collect();
+            let event = VmEvent {
+                address: Address::from_slice(&row.address),
+                indexed_topics,
+                value: codecs.decode(ProtectedColumn::EventData, row.value),
+                ..VmEvent::default()
+            };
+            (MiniblockNumber(row.miniblock_number as u32), event)
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{tests::create_miniblock_header, ConnectionPool};
+    use crate::{
+        models::storage_event::StorageL2ToL1Log, tests::create_miniblock_header, ConnectionPool,
+    };
     use db_test_macro::db_test;
-    use zksync_types::{Address, L1BatchNumber};
+    use zksync_types::{api::GetLogsFilter, Address, L1BatchNumber};
 
     fn create_vm_event(index: u8, topic_count: u8) -> VmEvent {
         assert!(topic_count <= 4);
@@ -241,7 +334,11 @@ mod tests {
             (second_location, second_events.iter().collect()),
         ];
         conn.events_dal()
-            .save_events(MiniblockNumber(1), &all_events)
+            .save_events(
+                MiniblockNumber(1),
+                &all_events,
+                &EventIndexingPolicy::default(),
+            )
             .await;
 
         let logs = conn
@@ -266,6 +363,65 @@ mod tests {
             assert_eq!(log.data.0, [i]);
             assert_eq!(log.topics, *expected_topics);
         }
+
+        let streamed_events: Vec<_> = conn
+            .events_dal()
+            .stream_events((MiniblockNumber(1), MiniblockNumber(1)))
+            .collect()
+            .await;
+        assert_eq!(streamed_events.len(), 5);
+        let expected_events = first_events.iter().chain(&second_events);
+        for ((miniblock_number, event), expected_event) in
+            streamed_events.iter().zip(expected_events)
+        {
+            assert_eq!(*miniblock_number, MiniblockNumber(1));
+            assert_eq!(event.address, expected_event.address);
+            assert_eq!(event.indexed_topics, expected_event.indexed_topics);
+            assert_eq!(event.value, expected_event.value);
+        }
+    }
+
+    #[db_test(dal_crate)]
+    async fn raw_only_events_are_not_returned_by_filtered_queries(pool: ConnectionPool) {
+        let mut conn = pool.access_storage().await;
+        conn.events_dal().rollback_events(MiniblockNumber(0)).await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await;
+
+        let location = IncludedTxLocation {
+            tx_hash: H256([1; 32]),
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: Address::default(),
+        };
+        let events = vec![create_vm_event(0, 2), create_vm_event(1, 2)];
+        let all_events = vec![(location, events.iter().collect())];
+        let policy =
+            EventIndexingPolicy::parse(&[format!("{:?}", Address::repeat_byte(0))]).unwrap();
+        conn.events_dal()
+            .save_events(MiniblockNumber(1), &all_events, &policy)
+            .await;
+
+        // Raw-only events are still stored.
+        let logs = conn
+            .events_web3_dal()
+            .get_all_logs(MiniblockNumber(0))
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 2);
+
+        let filter = GetLogsFilter {
+            from_block: MiniblockNumber(1),
+            to_block: None,
+            addresses: vec![Address::repeat_byte(0), Address::repeat_byte(1)],
+            topics: vec![],
+        };
+        let logs = conn.events_web3_dal().get_logs(filter, 10).await.unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].address, Address::repeat_byte(0));
     }
 
     fn create_l2_to_l1_log(tx_number_in_block: u16, index: u8) -> L2ToL1Log {
@@ -279,6 +435,26 @@ mod tests {
         }
     }
 
+    async fn l2_to_l1_logs(
+        conn: &mut StorageProcessor<'_>,
+        tx_hash: H256,
+    ) -> Vec<StorageL2ToL1Log> {
+        sqlx::query_as!(
+            StorageL2ToL1Log,
+            "SELECT \
+                miniblock_number, log_index_in_miniblock, log_index_in_tx, tx_hash, \
+                Null::bytea as \"block_hash\", Null::bigint as \"l1_batch_number?\", \
+                shard_id, is_service, tx_index_in_miniblock, tx_index_in_l1_batch, sender, key, value \
+            FROM l2_to_l1_logs \
+            WHERE tx_hash = $1 \
+            ORDER BY log_index_in_tx ASC",
+            tx_hash.as_bytes()
+        )
+        .fetch_all(conn.conn())
+        .await
+        .unwrap()
+    }
+
     #[db_test(dal_crate)]
     async fn storing_l2_to_l1_logs(pool: ConnectionPool) {
         let mut conn = pool.access_storage().await;
@@ -316,11 +492,7 @@ mod tests {
             .save_l2_to_l1_logs(MiniblockNumber(1), &all_logs)
             .await;
 
-        let logs = conn
-            .events_dal()
-            .l2_to_l1_logs(H256([1; 32]))
-            .await
-            .unwrap();
+        let logs = l2_to_l1_logs(&mut conn, H256([1; 32])).await;
         assert_eq!(logs.len(), first_logs.len());
         for (i, log) in logs.iter().enumerate() {
             assert_eq!(log.log_index_in_miniblock as usize, i);
@@ -332,11 +504,7 @@ mod tests {
             assert_eq!(log.sender, expected_log.sender.as_bytes());
         }
 
-        let logs = conn
-            .events_dal()
-            .l2_to_l1_logs(H256([2; 32]))
-            .await
-            .unwrap();
+        let logs = l2_to_l1_logs(&mut conn, H256([2; 32])).await;
         assert_eq!(logs.len(), second_logs.len());
         for (i, log) in logs.iter().enumerate() {
             assert_eq!(log.log_index_in_miniblock as usize, i + first_logs.len());
@@ -4,14 +4,135 @@ use sqlx::Row;
 
 use crate::models::storage_block::web3_block_number_to_sql;
 use zksync_types::{
-    api::{GetLogsFilter, Log},
+    api::{BlockNumber, GetLogsFilter, Log},
+    event::bloom_bit_indices,
     MiniblockNumber,
 };
 
 use crate::models::storage_event::StorageWeb3Log;
+use crate::pruning_dal::HistoryQueryError;
 use crate::SqlxError;
 use crate::StorageProcessor;
 
+/// Max number of rows scanned when estimating the selectivity of a `get_logs` filter column.
+/// If all filter columns match at least this many rows in the requested range, logs are
+/// fetched by scanning the range in the miniblock order.
+const LOGS_PLAN_PROBE_LIMIT: i64 = 10_000;
+/// Max number of addresses and topics in a `get_logs` filter for which Bloom filters are checked.
+/// Predicates for larger filters are more expensive than the scan they are supposed to narrow.
+const MAX_BLOOM_FILTER_VALUES: usize = 16;
+
+/// Returns SQL checking that the Bloom filter stored in `column` may match `filter`, or `None`
+/// if the filter doesn't constrain addresses or topics.
+fn bloom_predicate_sql(filter: &GetLogsFilter, column: &str) -> Option<String> {
+    let mut groups: Vec<Vec<&[u8]>> = vec![];
+    if !filter.addresses.is_empty() {
+        groups.push(
+            filter
+                .addresses
+                .iter()
+                .map(|address| address.as_bytes())
+                .collect(),
+        );
+    }
+    for (_, topics) in &filter.topics {
+        if !topics.is_empty() {
+            groups.push(topics.iter().map(|topic| topic.as_bytes()).collect());
+        }
+    }
+    let value_count: usize = groups.iter().map(Vec::len).sum();
+    if groups.is_empty() || value_count > MAX_BLOOM_FILTER_VALUES {
+        return None;
+    }
+
+    let group_predicates = groups.into_iter().map(|values| {
+        let value_predicates = values.into_iter().map(|value| {
+            let bit_predicates = bloom_bit_indices(value).map(|bit_index| {
+                // `get_bit()` numbers bits from the least significant bit of the first byte,
+                // while the Bloom filter is stored in the big-endian order.
+                let position = (255 - bit_index / 8) * 8 + bit_index % 8;
+                format!("get_bit({column}, {position}) = 1")
+            });
+            format!("({})", bit_predicates.join(" AND "))
+        });
+        format!("({})", value_predicates.collect::<Vec<_>>().join(" OR "))
+    });
+    Some(group_predicates.collect::<Vec<_>>().join(" AND "))
+}
+
+/// Column of the `events` table constrained by a `get_logs` filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LogsFilterColumn {
+    Address,
+    Topic(u32),
+}
+
+/// Query plan used to fetch logs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LogsQueryPlan {
+    /// Scan events in the requested miniblock range in the order of miniblocks.
+    /// Efficient if the filter is matched by a large share of events in the range.
+    BlockRange,
+    /// Select matching events using the composite `(column, miniblock_number, event_index_in_block)`
+    /// index on the most selective filter column, and apply the remaining filters afterwards.
+    Indexed(LogsFilterColumn),
+}
+
+impl LogsQueryPlan {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::BlockRange => "block_range",
+            Self::Indexed(LogsFilterColumn::Address) => "address_index",
+            Self::Indexed(LogsFilterColumn::Topic(_)) => "topic_index",
+        }
+    }
+}
+
+/// SQL predicates for a `get_logs` filter.
+#[derive(Debug)]
+struct GetLogsPredicates {
+    range_sql: String,
+    /// Predicates for filter columns together with the index of the bound argument.
+    column_predicates: Vec<(LogsFilterColumn, String)>,
+    /// Index of the next argument.
+    arg_index: u8,
+}
+
+impl GetLogsPredicates {
+    fn where_sql(&self) -> String {
+        let mut where_sql = self.range_sql.clone();
+        for (_, predicate) in &self.column_predicates {
+            where_sql += " AND ";
+            where_sql += predicate;
+        }
+        where_sql
+    }
+
+    /// Returns SQL for the source of events in the `FROM` clause for the specified `plan`.
+    fn events_source_sql(&self, plan: LogsQueryPlan) -> String {
+        let column = match plan {
+            LogsQueryPlan::BlockRange => return "events".to_owned(),
+            LogsQueryPlan::Indexed(column) => column,
+        };
+        let (_, driving_predicate) = self
+            .column_predicates
+            .iter()
+            .find(|(filter_column, _)| *filter_column == column)
+            .expect("query plan uses a column not present in the filter");
+        // `MATERIALIZED` prevents Postgres from inlining the CTE, so that the driving index
+        // is used regardless of the planner statistics.
+        format!(
+            "(WITH driving_events AS MATERIALIZED ( \
+                SELECT miniblock_number, event_index_in_block FROM events \
+                WHERE {} AND {driving_predicate} \
+            ) \
+            SELECT events.* FROM events \
+            INNER JOIN driving_events USING (miniblock_number, event_index_in_block)) AS events",
+            self.range_sql
+        )
+    }
+}
+
 #[derive(Debug)]
 pub struct EventsWeb3Dal<'a, 'c> {
     pub(crate) storage: &'a mut StorageProcessor<'c>,
@@ -25,19 +146,26 @@ impl EventsWeb3Dal<'_, '_> {
         filter: GetLogsFilter,
         offset: usize,
     ) -> Result<Option<MiniblockNumber>, SqlxError> {
+        let mut filter = filter;
+        if !self.narrow_range_by_blooms(&mut filter).await? {
+            return Ok(None);
+        }
         {
             let started_at = Instant::now();
-            let (where_sql, arg_index) = self.build_get_logs_where_clause(&filter);
+            let predicates = Self::build_get_logs_predicates(&filter);
+            let plan = self.choose_logs_query_plan(&filter, &predicates).await?;
 
             let query = format!(
                 r#"
                     SELECT miniblock_number
-                    FROM events
+                    FROM {}
                     WHERE {}
                     ORDER BY miniblock_number ASC, event_index_in_block ASC
                     LIMIT 1 OFFSET ${}
                 "#,
-                where_sql, arg_index
+                predicates.events_source_sql(plan),
+                predicates.where_sql(),
+                predicates.arg_index
             );
 
             let mut query = sqlx::query(&query);
@@ -63,16 +191,26 @@ impl EventsWeb3Dal<'_, '_> {
         }
     }
 
-    /// Returns logs for given filter.
+    /// Returns logs for given filter. Returns [`HistoryQueryError::PrunedHistory`] if the filter
+    /// range starts at a miniblock which events are pruned.
     #[allow(clippy::type_complexity)]
     pub async fn get_logs(
         &mut self,
         filter: GetLogsFilter,
         limit: usize,
-    ) -> Result<Vec<Log>, SqlxError> {
+    ) -> Result<Vec<Log>, HistoryQueryError> {
+        self.storage
+            .pruning_dal()
+            .check_miniblock_history(filter.from_block)
+            .await?;
+        let mut filter = filter;
+        if !self.narrow_range_by_blooms(&mut filter).await? {
+            return Ok(vec![]);
+        }
         {
             let started_at = Instant::now();
-            let (where_sql, arg_index) = self.build_get_logs_where_clause(&filter);
+            let predicates = Self::build_get_logs_predicates(&filter);
+            let plan = self.choose_logs_query_plan(&filter, &predicates).await?;
 
             let query = format!(
                 r#"
@@ -81,7 +219,7 @@ impl EventsWeb3Dal<'_, '_> {
                         address, topic1, topic2, topic3, topic4, value,
                         miniblock_number, tx_hash, tx_index_in_block,
                         event_index_in_block, event_index_in_tx
-                    FROM events
+                    FROM {}
                     WHERE {}
                     ORDER BY miniblock_number ASC, event_index_in_block ASC
                     LIMIT ${}
@@ -91,7 +229,9 @@ impl EventsWeb3Dal<'_, '_> {
                 LEFT JOIN miniblocks ON events_select.miniblock_number = miniblocks.number
                 ORDER BY miniblock_number ASC, event_index_in_block ASC
                 "#,
-                where_sql, arg_index
+                predicates.events_source_sql(plan),
+                predicates.where_sql(),
+                predicates.arg_index
             );
 
             let mut query = sqlx::query_as(&query);
@@ -110,31 +250,155 @@ impl EventsWeb3Dal<'_, '_> {
             query = query.bind(limit as i32);
 
             let db_logs: Vec<StorageWeb3Log> = query.fetch_all(self.storage.conn()).await?;
-            let logs = db_logs.into_iter().map(Into::into).collect();
+            let mut logs: Vec<_> = db_logs.into_iter().map(Into::into).collect();
+            self.storage.protected_data_codecs().decode_logs(&mut logs);
             metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_logs");
             Ok(logs)
         }
     }
 
+    /// Narrows the miniblock range of `filter` to miniblocks which Bloom filters may match it.
+    /// Miniblocks are skipped if either their own filter or the filter of their L1 batch
+    /// doesn't match. Miniblocks and L1 batches without computed filters are never skipped.
+    ///
+    /// Returns `false` if no miniblock in the range may contain matching logs.
+    async fn narrow_range_by_blooms(
+        &mut self,
+        filter: &mut GetLogsFilter,
+    ) -> Result<bool, SqlxError> {
+        let (Some(batch_predicate), Some(miniblock_predicate)) = (
+            bloom_predicate_sql(filter, "bloom"),
+            bloom_predicate_sql(filter, "logs_bloom"),
+        ) else {
+            return Ok(true);
+        };
+
+        let started_at = Instant::now();
+        let mut range_sql = format!("number >= {}", filter.from_block.0);
+        if let Some(to_block) = filter.to_block {
+            range_sql += &format!(" AND number <= {}", web3_block_number_to_sql(to_block));
+        }
+        let query = format!(
+            "SELECT MIN(number) AS min_number, MAX(number) AS max_number FROM miniblocks \
+            WHERE {range_sql} \
+                AND (l1_batch_number IS NULL OR l1_batch_number IN ( \
+                    SELECT number FROM l1_batches WHERE NOT bloom_computed OR ({batch_predicate}) \
+                )) \
+                AND (logs_bloom IS NULL OR ({miniblock_predicate}))"
+        );
+        let row = sqlx::query(&query).fetch_one(self.storage.conn()).await?;
+        let min_number: Option<i64> = row.get("min_number");
+        let max_number: Option<i64> = row.get("max_number");
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "narrow_logs_range_by_blooms");
+
+        let (Some(min_number), Some(max_number)) = (min_number, max_number) else {
+            metrics::increment_counter!("dal.events_web3.get_logs_bloom_skipped");
+            return Ok(false);
+        };
+        filter.from_block = MiniblockNumber(min_number as u32);
+        filter.to_block = Some(BlockNumber::Number((max_number as u64).into()));
+        Ok(true)
+    }
+
+    #[cfg(test)]
     fn build_get_logs_where_clause(&self, filter: &GetLogsFilter) -> (String, u8) {
-        let mut arg_index = 1;
+        let predicates = Self::build_get_logs_predicates(filter);
+        (predicates.where_sql(), predicates.arg_index)
+    }
 
-        let mut where_sql = format!("(miniblock_number >= {})", filter.from_block.0 as i64);
+    fn build_get_logs_predicates(filter: &GetLogsFilter) -> GetLogsPredicates {
+        let mut arg_index = 1;
 
+        let mut range_sql = format!("(miniblock_number >= {})", filter.from_block.0 as i64);
         if let Some(to_block) = filter.to_block {
             let block_sql = web3_block_number_to_sql(to_block);
-            where_sql += &format!(" AND (miniblock_number <= {})", block_sql);
+            range_sql += &format!(" AND (miniblock_number <= {})", block_sql);
         }
+
+        let mut column_predicates = vec![];
         if !filter.addresses.is_empty() {
-            where_sql += &format!(" AND (address = ANY(${}))", arg_index);
+            let predicate = format!("(address = ANY(${}))", arg_index);
+            column_predicates.push((LogsFilterColumn::Address, predicate));
             arg_index += 1;
         }
         for (topic_index, _) in filter.topics.iter() {
-            where_sql += &format!(" AND (topic{} = ANY(${}))", topic_index, arg_index);
+            let predicate = format!("(topic{} = ANY(${}))", topic_index, arg_index);
+            column_predicates.push((LogsFilterColumn::Topic(*topic_index), predicate));
             arg_index += 1;
         }
+        // Raw-only events aren't covered by the address and topic indexes, so they are never returned
+        // for filtered queries, regardless of the query plan.
+        if !column_predicates.is_empty() {
+            range_sql += " AND is_indexed";
+        }
 
-        (where_sql, arg_index)
+        GetLogsPredicates {
+            range_sql,
+            column_predicates,
+            arg_index,
+        }
+    }
+
+    /// Chooses the query plan for the filter by probing the number of events matching each
+    /// filter column in the requested range. Probes are bounded by [`LOGS_PLAN_PROBE_LIMIT`]
+    /// and are served by the composite column indexes, so they are cheap even for popular
+    /// addresses or topics.
+    async fn choose_logs_query_plan(
+        &mut self,
+        filter: &GetLogsFilter,
+        predicates: &GetLogsPredicates,
+    ) -> Result<LogsQueryPlan, SqlxError> {
+        let mut best_column = None;
+        let mut best_count = LOGS_PLAN_PROBE_LIMIT;
+        for (column, _) in &predicates.column_predicates {
+            let values: Vec<Vec<u8>> = match column {
+                LogsFilterColumn::Address => filter
+                    .addresses
+                    .iter()
+                    .map(|address| address.0.to_vec())
+                    .collect(),
+                LogsFilterColumn::Topic(topic_index) => filter
+                    .topics
+                    .iter()
+                    .find(|(idx, _)| idx == topic_index)
+                    .map(|(_, topics)| topics.iter().map(|topic| topic.0.to_vec()).collect())
+                    .unwrap_or_default(),
+            };
+            let count = self
+                .probe_logs_filter_column(*column, &predicates.range_sql, values)
+                .await?;
+            if count < best_count {
+                best_count = count;
+                best_column = Some(*column);
+            }
+        }
+
+        let plan = best_column.map_or(LogsQueryPlan::BlockRange, LogsQueryPlan::Indexed);
+        metrics::increment_counter!("dal.events_web3.get_logs_plan", "plan" => plan.as_str());
+        Ok(plan)
+    }
+
+    async fn probe_logs_filter_column(
+        &mut self,
+        column: LogsFilterColumn,
+        range_sql: &str,
+        values: Vec<Vec<u8>>,
+    ) -> Result<i64, SqlxError> {
+        let column_sql = match column {
+            LogsFilterColumn::Address => "address".to_owned(),
+            LogsFilterColumn::Topic(topic_index) => format!("topic{}", topic_index),
+        };
+        let query = format!(
+            "SELECT COUNT(*) AS count FROM ( \
+                SELECT 1 FROM events WHERE {range_sql} AND ({column_sql} = ANY($1)) LIMIT $2 \
+            ) AS probe"
+        );
+        let row = sqlx::query(&query)
+            .bind(values)
+            .bind(LOGS_PLAN_PROBE_LIMIT)
+            .fetch_one(self.storage.conn())
+            .await?;
+        Ok(row.get::<i64, &str>("count"))
     }
 
     pub async fn get_all_logs(
@@ -166,7 +430,8 @@ impl EventsWeb3Dal<'_, '_> {
             )
                 .fetch_all(self.storage.conn())
                 .await?;
-            let logs = db_logs.into_iter().map(Into::into).collect();
+            let mut logs: Vec<_> = db_logs.into_iter().map(Into::into).collect();
+            self.storage.protected_data_codecs().decode_logs(&mut logs);
             Ok(logs)
         }
     }
@@ -192,7 +457,9 @@ mod tests {
             topics: vec![(0, vec![H256::from_low_u64_be(456)])],
         };
 
-        let expected_sql = "(miniblock_number >= 100) AND (miniblock_number <= 200) AND (address = ANY($1)) AND (topic0 = ANY($2))";
+        let expected_sql =
+            "(miniblock_number >= 100) AND (miniblock_number <= 200) AND is_indexed \
+            AND (address = ANY($1)) AND (topic0 = ANY($2))";
         let expected_arg_index = 3;
 
         let (actual_sql, actual_arg_index) = events_web3_dal.build_get_logs_where_clause(&filter);
@@ -200,4 +467,60 @@ mod tests {
         assert_eq!(actual_sql, expected_sql);
         assert_eq!(actual_arg_index, expected_arg_index);
     }
+
+    #[test]
+    fn indexed_logs_plan_uses_driving_predicate() {
+        let filter = GetLogsFilter {
+            from_block: MiniblockNumber(100),
+            to_block: None,
+            addresses: vec![Address::from_low_u64_be(123)],
+            topics: vec![(2, vec![H256::from_low_u64_be(456)])],
+        };
+        let predicates = EventsWeb3Dal::build_get_logs_predicates(&filter);
+        assert_eq!(
+            predicates.events_source_sql(LogsQueryPlan::BlockRange),
+            "events"
+        );
+
+        let source_sql =
+            predicates.events_source_sql(LogsQueryPlan::Indexed(LogsFilterColumn::Topic(2)));
+        assert!(
+            source_sql
+                .contains("WHERE (miniblock_number >= 100) AND is_indexed AND (topic2 = ANY($2))"),
+            "{source_sql}"
+        );
+        assert!(!source_sql.contains("address"), "{source_sql}");
+    }
+
+    #[test]
+    fn bloom_predicate_for_logs_filter() {
+        let mut filter = GetLogsFilter {
+            from_block: MiniblockNumber(100),
+            to_block: None,
+            addresses: vec![],
+            topics: vec![],
+        };
+        assert_eq!(bloom_predicate_sql(&filter, "logs_bloom"), None);
+
+        filter.addresses = vec![Address::from_low_u64_be(1), Address::from_low_u64_be(2)];
+        filter.topics = vec![(1, vec![H256::from_low_u64_be(3)])];
+        let predicate = bloom_predicate_sql(&filter, "logs_bloom").unwrap();
+        assert_eq!(predicate.matches(" OR ").count(), 1, "{predicate}");
+        assert_eq!(predicate.matches("get_bit(logs_bloom, ").count(), 9);
+
+        // Bit positions are consistent with the Bloom filter layout.
+        let bloom = zksync_types::event::events_bloom([&zksync_types::VmEvent {
+            address: filter.addresses[0],
+            ..zksync_types::VmEvent::default()
+        }]);
+        for bit_index in bloom_bit_indices(filter.addresses[0].as_bytes()) {
+            let position = (255 - bit_index / 8) * 8 + bit_index % 8;
+            assert_ne!(bloom.0[position / 8] & (1 << (position % 8)), 0);
+        }
+
+        filter.addresses = (0..=MAX_BLOOM_FILTER_VALUES as u64)
+            .map(Address::from_low_u64_be)
+            .collect();
+        assert_eq!(bloom_predicate_sql(&filter, "logs_bloom"), None);
+    }
 }
@@ -11,6 +11,7 @@ use zksync_types::{
 
 use sqlx::postgres::types::PgInterval;
 
+use crate::data_codec::ProtectedColumn;
 use crate::models::storage_verification_request::StorageVerificationRequest;
 use crate::SqlxError;
 use crate::StorageProcessor;
@@ -244,6 +245,7 @@ impl ContractVerificationDal<'_, '_> {
     ) -> Result<Option<(Vec<u8>, DeployContractCalldata)>, SqlxError> {
         {
             let hashed_key = get_code_key(&address).hashed_key();
+            let codecs = self.storage.protected_data_codecs();
             let result = sqlx::query!(
                 r#"
                     SELECT factory_deps.bytecode, transactions.data as "data?", transactions.contract_address as "contract_address?"
@@ -273,6 +275,8 @@ impl ContractVerificationDal<'_, '_> {
                         let calldata_str: String =
                             serde_json::from_value(data.get("calldata").unwrap().clone()).unwrap();
                         let calldata = hex::decode(&calldata_str[2..]).unwrap();
+                        let calldata =
+                            codecs.decode(ProtectedColumn::TransactionCalldata, calldata);
                         DeployContractCalldata::Deploy(calldata)
                     }
                     _ => DeployContractCalldata::Ignore,
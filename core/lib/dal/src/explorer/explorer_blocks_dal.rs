@@ -1,8 +1,8 @@
 use std::time::Instant;
 
 use zksync_types::explorer_api::{
-    BlockDetails, BlockPageItem, BlocksQuery, L1BatchDetails, L1BatchPageItem, L1BatchesQuery,
-    PaginationDirection,
+    BlockDetails, BlockPageItem, BlocksQuery, L1BatchDetails, L1BatchPageItem,
+    L1BatchPipelineStatus, L1BatchesQuery, PaginationDirection,
 };
 use zksync_types::{Address, L1BatchNumber, MiniblockNumber};
 
@@ -184,4 +184,65 @@ impl ExplorerBlocksDal<'_, '_> {
             Ok(l1_batch_details.map(L1BatchDetails::from))
         }
     }
+
+    /// Returns the status of the L1 batch in the tree / proving pipeline. Both the old and FRI prover jobs
+    /// are taken into account.
+    pub async fn get_l1_batch_pipeline_status(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<L1BatchPipelineStatus, SqlxError> {
+        {
+            let started_at = Instant::now();
+            let tree_root_available = sqlx::query!(
+                r#"SELECT hash IS NOT NULL AS "tree_root_available!" FROM l1_batches WHERE number = $1"#,
+                l1_batch_number.0 as i64
+            )
+            .fetch_optional(self.storage.conn())
+            .await?
+            .map_or(false, |row| row.tree_root_available);
+
+            let witness_generation_status = sqlx::query!(
+                r#"
+                    SELECT status AS "status!" FROM witness_inputs WHERE l1_batch_number = $1
+                    UNION ALL
+                    SELECT status FROM witness_inputs_fri WHERE l1_batch_number = $1
+                "#,
+                l1_batch_number.0 as i64
+            )
+            .fetch_optional(self.storage.conn())
+            .await?
+            .map(|row| row.status);
+
+            let job_counts = sqlx::query!(
+                r#"
+                    SELECT status AS "status!", COUNT(*) AS "count!" FROM (
+                        SELECT status FROM prover_jobs WHERE l1_batch_number = $1
+                        UNION ALL
+                        SELECT status FROM prover_jobs_fri WHERE l1_batch_number = $1
+                    ) AS jobs
+                    GROUP BY status
+                "#,
+                l1_batch_number.0 as i64
+            )
+            .fetch_all(self.storage.conn())
+            .await?;
+
+            let mut status = L1BatchPipelineStatus {
+                tree_root_available,
+                witness_generation_status,
+                ..L1BatchPipelineStatus::default()
+            };
+            for row in job_counts {
+                let count = row.count as usize;
+                status.prover_jobs += count;
+                match row.status.as_str() {
+                    "successful" => status.successful_prover_jobs += count,
+                    "failed" => status.failed_prover_jobs += count,
+                    _ => {}
+                }
+            }
+            metrics::histogram!("dal.request", started_at.elapsed(), "method" => "explorer_get_l1_batch_pipeline_status");
+            Ok(status)
+        }
+    }
 }
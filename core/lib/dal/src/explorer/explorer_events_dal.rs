@@ -33,7 +33,11 @@ impl ExplorerEventsDal<'_, '_> {
                 bind_index += 1;
             }
             if query.contract_address.is_some() {
-                filters.push(format!("(events.address = ${})", bind_index));
+                // Raw-only events aren't covered by the address index.
+                filters.push(format!(
+                    "(events.is_indexed AND events.address = ${})",
+                    bind_index
+                ));
                 bind_index += 1;
             }
             let filters: String = if !filters.is_empty() {
@@ -81,7 +85,8 @@ impl ExplorerEventsDal<'_, '_> {
 
             let storage_web3_logs: Vec<StorageWeb3Log> =
                 sql_query.fetch_all(self.storage.conn()).await?;
-            let logs = storage_web3_logs.into_iter().map(Log::from).collect();
+            let mut logs: Vec<_> = storage_web3_logs.into_iter().map(Log::from).collect();
+            self.storage.protected_data_codecs().decode_logs(&mut logs);
 
             let sql_count_query_str = format!(
                 r#"
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::slice;
 use std::time::Instant;
 
 use itertools::Itertools;
@@ -17,6 +18,7 @@ use zksync_types::{
     L2_ETH_TOKEN_ADDRESS, U256, U64,
 };
 
+use crate::data_codec::{DataCodecs, ProtectedColumn};
 use crate::models::storage_event::StorageWeb3Log;
 use crate::models::storage_transaction::{
     transaction_details_from_storage, StorageTransactionDetails,
@@ -80,6 +82,7 @@ impl ExplorerTransactionsDal<'_, '_> {
                     .storage_tx_list_to_tx_details_list(vec![tx_details], l2_erc20_bridge_addr)
                     .await?;
                 let tx = list[0].clone();
+                let codecs = self.storage.protected_data_codecs();
                 let logs: Vec<Log> = sqlx::query_as!(
                     StorageWeb3Log,
                     r#"
@@ -99,6 +102,7 @@ impl ExplorerTransactionsDal<'_, '_> {
                 .into_iter()
                 .map(|storage_log: StorageWeb3Log| {
                     let mut log = Log::from(storage_log);
+                    codecs.decode_logs(slice::from_mut(&mut log));
                     log.block_hash = tx.block_hash;
                     log.l1_batch_number = tx.l1_batch_number.map(|n| U64::from(n.0));
                     log
@@ -742,11 +746,13 @@ impl ExplorerTransactionsDal<'_, '_> {
             .collect();
 
             // 2) Calldata is a valid ERC20 `transfer` calldata
+            let codecs = self.storage.protected_data_codecs();
             let erc20_transfers_iter = txs.iter().filter_map(|tx| {
                 let hash = H256::from_slice(&tx.hash);
                 if let Some(token_info) = filtered_by_contract_address.get(&hash).cloned() {
                     let execute = serde_json::from_value::<Execute>(tx.data.clone()).unwrap();
-                    let calldata = execute.calldata();
+                    let calldata =
+                        codecs.decode(ProtectedColumn::TransactionCalldata, execute.calldata);
                     Self::parse_erc20_transfer_calldata(calldata).map(|(to, amount)| {
                         let from = Address::from_slice(&tx.initiator_address);
                         (
@@ -774,7 +780,9 @@ impl ExplorerTransactionsDal<'_, '_> {
                 .expect("Info about ETH should be present in DB");
             let eth_transfers_iter = txs.iter().filter_map(|tx| {
                 let hash = H256::from_slice(&tx.hash);
-                let execute = serde_json::from_value::<Execute>(tx.data.clone()).unwrap();
+                let mut execute = serde_json::from_value::<Execute>(tx.data.clone()).unwrap();
+                execute.calldata =
+                    codecs.decode(ProtectedColumn::TransactionCalldata, execute.calldata);
                 // All transactions with an empty calldata are considered to be called "transfers".
                 if execute.calldata().is_empty() {
                     let from = Address::from_slice(&tx.initiator_address);
@@ -812,6 +820,7 @@ impl ExplorerTransactionsDal<'_, '_> {
             .await?;
         let erc20_transfers_filtered = self.filter_erc20_transfers(&txs).await?;
         let deposits_map = self.get_deposits(hashes, l2_erc20_bridge_addr).await?;
+        let codecs = self.storage.protected_data_codecs();
         let txs = txs
             .into_iter()
             .map(|tx_details| {
@@ -821,6 +830,7 @@ impl ExplorerTransactionsDal<'_, '_> {
                     &erc20_transfers_filtered,
                     &deposits_map,
                     tx_details,
+                    codecs,
                 )
             })
             .collect();
@@ -833,6 +843,7 @@ impl ExplorerTransactionsDal<'_, '_> {
         filtered_transfers: &HashMap<H256, Erc20TransferInfo>,
         deposits_map: &HashMap<H256, Vec<BalanceChangeInfo>>,
         tx_details: StorageTransactionDetails,
+        codecs: &DataCodecs,
     ) -> TransactionDetails {
         let hash = H256::from_slice(&tx_details.hash);
         let erc20_transfers = erc20_transfers_map.get(&hash).cloned().unwrap_or_default();
@@ -845,6 +856,7 @@ impl ExplorerTransactionsDal<'_, '_> {
             withdrawals,
             transfer,
             deposits,
+            codecs,
         )
     }
 
@@ -10,11 +10,16 @@ use sqlx::pool::PoolConnection;
 pub use sqlx::types::BigDecimal;
 
 // Local imports
+use crate::api_read_models::ApiReadModelsIntermediary;
 use crate::blocks_dal::BlocksDal;
 use crate::blocks_web3_dal::BlocksWeb3Dal;
+use crate::change_stream_dal::ChangeStreamDal;
 pub use crate::connection::ConnectionPool;
 use crate::connection::{holder::ConnectionHolder, test_pool::TestPoolLock};
+use crate::data_codec::DataCodecs;
+use crate::data_export_dal::DataExportDal;
 use crate::eth_sender_dal::EthSenderDal;
+use crate::eth_watcher_dal::EthWatcherDal;
 use crate::events_dal::EventsDal;
 use crate::events_web3_dal::EventsWeb3Dal;
 use crate::explorer::ExplorerIntermediary;
@@ -22,24 +27,37 @@ use crate::fri_prover_dal::FriProverDal;
 use crate::fri_scheduler_dependency_tracker_dal::FriSchedulerDependencyTrackerDal;
 use crate::fri_witness_generator_dal::FriWitnessGeneratorDal;
 use crate::gpu_prover_queue_dal::GpuProverQueueDal;
+use crate::maintenance_dal::MaintenanceDal;
+use crate::partitions_dal::PartitionsDal;
+use crate::protocol_versions_dal::ProtocolVersionsDal;
+use crate::prover_artifacts_dal::ProverArtifactsDal;
 use crate::prover_dal::ProverDal;
+use crate::pruning_dal::PruningDal;
+use crate::state_snapshots_dal::StateSnapshotsDal;
 use crate::storage_dal::StorageDal;
 use crate::storage_logs_dal::StorageLogsDal;
 use crate::storage_logs_dedup_dal::StorageLogsDedupDal;
 use crate::storage_web3_dal::StorageWeb3Dal;
 use crate::sync_dal::SyncDal;
+use crate::tee_proof_generation_dal::TeeProofGenerationDal;
 use crate::tokens_dal::TokensDal;
 use crate::tokens_web3_dal::TokensWeb3Dal;
+use crate::transaction_history_dal::TransactionHistoryDal;
 use crate::transactions_dal::TransactionsDal;
 use crate::transactions_web3_dal::TransactionsWeb3Dal;
 use crate::witness_generator_dal::WitnessGeneratorDal;
 
 #[macro_use]
 mod macro_utils;
+pub mod api_read_models;
 pub mod blocks_dal;
 pub mod blocks_web3_dal;
+pub mod change_stream_dal;
 pub mod connection;
+pub mod data_codec;
+pub mod data_export_dal;
 pub mod eth_sender_dal;
+pub mod eth_watcher_dal;
 pub mod events_dal;
 pub mod events_web3_dal;
 pub mod explorer;
@@ -48,16 +66,24 @@ pub mod fri_scheduler_dependency_tracker_dal;
 pub mod fri_witness_generator_dal;
 pub mod gpu_prover_queue_dal;
 pub mod healthcheck;
+pub mod maintenance_dal;
 mod models;
+pub mod partitions_dal;
+pub mod protocol_versions_dal;
+pub mod prover_artifacts_dal;
 pub mod prover_dal;
+pub mod pruning_dal;
+pub mod state_snapshots_dal;
 pub mod storage_dal;
 pub mod storage_logs_dal;
 pub mod storage_logs_dedup_dal;
 pub mod storage_web3_dal;
 pub mod sync_dal;
+pub mod tee_proof_generation_dal;
 pub mod time_utils;
 pub mod tokens_dal;
 pub mod tokens_web3_dal;
+pub mod transaction_history_dal;
 pub mod transactions_dal;
 pub mod transactions_web3_dal;
 pub mod witness_generator_dal;
@@ -92,6 +118,8 @@ pub fn get_test_database_url() -> String {
 pub struct StorageProcessor<'a> {
     conn: ConnectionHolder<'a>,
     in_transaction: bool,
+    /// Whether values in protected columns are decoded when read. See [`data_codec`] for details.
+    protected_data_access: bool,
 }
 
 impl<'a> StorageProcessor<'a> {
@@ -105,6 +133,7 @@ impl<'a> StorageProcessor<'a> {
         StorageProcessor {
             conn: ConnectionHolder::Direct(connection),
             in_transaction: false,
+            protected_data_access: true,
         }
     }
 
@@ -113,6 +142,7 @@ impl<'a> StorageProcessor<'a> {
 
         let mut processor = StorageProcessor::from_transaction(transaction);
         processor.in_transaction = true;
+        processor.protected_data_access = self.protected_data_access;
 
         processor
     }
@@ -126,6 +156,7 @@ impl<'a> StorageProcessor<'a> {
         Self {
             conn: ConnectionHolder::Transaction(conn),
             in_transaction: true,
+            protected_data_access: true,
         }
     }
 
@@ -133,6 +164,7 @@ impl<'a> StorageProcessor<'a> {
         StorageProcessor {
             conn: ConnectionHolder::TestTransaction(conn),
             in_transaction: true,
+            protected_data_access: true,
         }
     }
 
@@ -151,6 +183,23 @@ impl<'a> StorageProcessor<'a> {
         Self {
             conn: ConnectionHolder::Pooled(conn),
             in_transaction: false,
+            protected_data_access: true,
+        }
+    }
+
+    /// Restricts access to protected data for this processor: values in protected columns are returned
+    /// as stored (e.g., encrypted) rather than decoded.
+    pub fn restrict_protected_data_access(&mut self) {
+        self.protected_data_access = false;
+    }
+
+    /// Returns codecs to decode protected data with. If access to protected data is restricted,
+    /// returned codecs leave values as stored.
+    pub(crate) fn protected_data_codecs(&self) -> &'static DataCodecs {
+        if self.protected_data_access {
+            DataCodecs::global()
+        } else {
+            DataCodecs::none()
         }
     }
 
@@ -167,6 +216,10 @@ impl<'a> StorageProcessor<'a> {
         TransactionsDal { storage: self }
     }
 
+    pub fn transaction_history_dal(&mut self) -> TransactionHistoryDal<'_, 'a> {
+        TransactionHistoryDal { storage: self }
+    }
+
     pub fn transactions_web3_dal(&mut self) -> TransactionsWeb3Dal<'_, 'a> {
         TransactionsWeb3Dal { storage: self }
     }
@@ -183,6 +236,10 @@ impl<'a> StorageProcessor<'a> {
         EthSenderDal { storage: self }
     }
 
+    pub fn eth_watcher_dal(&mut self) -> EthWatcherDal<'_, 'a> {
+        EthWatcherDal { storage: self }
+    }
+
     pub fn events_dal(&mut self) -> EventsDal<'_, 'a> {
         EventsDal { storage: self }
     }
@@ -227,6 +284,10 @@ impl<'a> StorageProcessor<'a> {
         ExplorerIntermediary { storage: self }
     }
 
+    pub fn api_read_models(&mut self) -> ApiReadModelsIntermediary<'_, 'a> {
+        ApiReadModelsIntermediary { storage: self }
+    }
+
     pub fn gpu_prover_queue_dal(&mut self) -> GpuProverQueueDal<'_, 'a> {
         GpuProverQueueDal { storage: self }
     }
@@ -243,6 +304,42 @@ impl<'a> StorageProcessor<'a> {
         SyncDal { storage: self }
     }
 
+    pub fn partitions_dal(&mut self) -> PartitionsDal<'_, 'a> {
+        PartitionsDal { storage: self }
+    }
+
+    pub fn maintenance_dal(&mut self) -> MaintenanceDal<'_, 'a> {
+        MaintenanceDal { storage: self }
+    }
+
+    pub fn pruning_dal(&mut self) -> PruningDal<'_, 'a> {
+        PruningDal { storage: self }
+    }
+
+    pub fn state_snapshots_dal(&mut self) -> StateSnapshotsDal<'_, 'a> {
+        StateSnapshotsDal { storage: self }
+    }
+
+    pub fn tee_proof_generation_dal(&mut self) -> TeeProofGenerationDal<'_, 'a> {
+        TeeProofGenerationDal { storage: self }
+    }
+
+    pub fn protocol_versions_dal(&mut self) -> ProtocolVersionsDal<'_, 'a> {
+        ProtocolVersionsDal { storage: self }
+    }
+
+    pub fn change_stream_dal(&mut self) -> ChangeStreamDal<'_, 'a> {
+        ChangeStreamDal { storage: self }
+    }
+
+    pub fn data_export_dal(&mut self) -> DataExportDal<'_, 'a> {
+        DataExportDal { storage: self }
+    }
+
+    pub fn prover_artifacts_dal(&mut self) -> ProverArtifactsDal<'_, 'a> {
+        ProverArtifactsDal { storage: self }
+    }
+
     pub fn fri_scheduler_dependency_tracker_dal(
         &mut self,
     ) -> FriSchedulerDependencyTrackerDal<'_, 'a> {
@@ -0,0 +1,172 @@
+//! Table statistics and maintenance operations (`VACUUM` / `ANALYZE`) for hot tables.
+
+use sqlx::migrate::{MigrationType, Migrator};
+
+use std::time::Instant;
+
+use crate::StorageProcessor;
+
+/// Statistics for a table. For partitioned tables, statistics are aggregated over all partitions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableStats {
+    pub table_name: String,
+    pub live_rows: i64,
+    pub dead_rows: i64,
+    /// Number of rows modified since the table was last analyzed.
+    pub modified_rows_since_analyze: i64,
+    pub table_size_bytes: i64,
+    pub indexes_size_bytes: i64,
+    pub last_vacuum: Option<chrono::NaiveDateTime>,
+    pub last_analyze: Option<chrono::NaiveDateTime>,
+}
+
+impl TableStats {
+    /// Share of dead rows in the table. Used as an estimate of the table bloat.
+    pub fn dead_rows_ratio(&self) -> f64 {
+        let total_rows = self.live_rows + self.dead_rows;
+        if total_rows == 0 {
+            0.0
+        } else {
+            self.dead_rows as f64 / total_rows as f64
+        }
+    }
+
+    /// Ratio of rows modified since the last `ANALYZE` to live rows.
+    pub fn modified_rows_ratio(&self) -> f64 {
+        if self.live_rows == 0 {
+            0.0
+        } else {
+            self.modified_rows_since_analyze as f64 / self.live_rows as f64
+        }
+    }
+}
+
+/// Migrations known to this build of the server.
+static MIGRATOR: Migrator = sqlx::migrate!();
+
+/// Difference between the migrations known to this build of the server and the ones applied to the database.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchemaMismatch {
+    /// Versions of migrations known to the server, but not applied to the database.
+    pub missing_migrations: Vec<i64>,
+    /// Versions of migrations applied to the database, but unknown to the server. Usually means
+    /// that the database was migrated by a newer version of the server.
+    pub unknown_migrations: Vec<i64>,
+}
+
+impl SchemaMismatch {
+    pub fn is_empty(&self) -> bool {
+        self.missing_migrations.is_empty() && self.unknown_migrations.is_empty()
+    }
+}
+
+#[derive(Debug)]
+pub struct MaintenanceDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl MaintenanceDal<'_, '_> {
+    pub async fn get_table_stats(&mut self, table_names: &[&str]) -> Vec<TableStats> {
+        let started_at = Instant::now();
+        let table_names: Vec<_> = table_names.iter().map(|name| name.to_string()).collect();
+        let rows = sqlx::query!(
+            r#"
+                SELECT COALESCE(parent.relname, stats.relname)::text AS "table_name!",
+                    SUM(stats.n_live_tup)::bigint AS "live_rows!",
+                    SUM(stats.n_dead_tup)::bigint AS "dead_rows!",
+                    SUM(stats.n_mod_since_analyze)::bigint AS "modified_rows!",
+                    SUM(pg_table_size(stats.relid))::bigint AS "table_size!",
+                    SUM(pg_indexes_size(stats.relid))::bigint AS "indexes_size!",
+                    MAX(GREATEST(stats.last_vacuum, stats.last_autovacuum))::timestamp AS last_vacuum,
+                    MAX(GREATEST(stats.last_analyze, stats.last_autoanalyze))::timestamp AS last_analyze
+                FROM pg_stat_user_tables AS stats
+                LEFT JOIN pg_inherits ON pg_inherits.inhrelid = stats.relid
+                LEFT JOIN pg_class AS parent ON parent.oid = pg_inherits.inhparent
+                WHERE COALESCE(parent.relname, stats.relname) = ANY($1::text[])
+                GROUP BY 1
+                ORDER BY 1
+            "#,
+            &table_names
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap();
+
+        let stats = rows
+            .into_iter()
+            .map(|row| TableStats {
+                table_name: row.table_name,
+                live_rows: row.live_rows,
+                dead_rows: row.dead_rows,
+                modified_rows_since_analyze: row.modified_rows,
+                table_size_bytes: row.table_size,
+                indexes_size_bytes: row.indexes_size,
+                last_vacuum: row.last_vacuum,
+                last_analyze: row.last_analyze,
+            })
+            .collect();
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_table_stats");
+        stats
+    }
+
+    /// Runs `ANALYZE` for the table. Must not be called within a transaction.
+    pub async fn analyze_table(&mut self, table_name: &str) {
+        assert!(
+            !self.storage.in_transaction(),
+            "ANALYZE must not run in a transaction"
+        );
+        let started_at = Instant::now();
+        sqlx::query(&format!("ANALYZE {table_name}"))
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "analyze_table");
+    }
+
+    /// Runs `VACUUM ANALYZE` for the table. Must not be called within a transaction.
+    pub async fn vacuum_table(&mut self, table_name: &str) {
+        assert!(
+            !self.storage.in_transaction(),
+            "VACUUM must not run in a transaction"
+        );
+        let started_at = Instant::now();
+        sqlx::query(&format!("VACUUM (ANALYZE) {table_name}"))
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "vacuum_table");
+    }
+
+    /// Compares migrations applied to the database with the ones embedded into the server.
+    pub async fn get_schema_mismatch(&mut self) -> SchemaMismatch {
+        let started_at = Instant::now();
+        let applied_versions: Vec<i64> =
+            sqlx::query!("SELECT version FROM _sqlx_migrations WHERE success ORDER BY version")
+                .fetch_all(self.storage.conn())
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|row| row.version)
+                .collect();
+        let known_versions: Vec<i64> = MIGRATOR
+            .iter()
+            .filter(|migration| !matches!(migration.migration_type, MigrationType::ReversibleDown))
+            .map(|migration| migration.version)
+            .collect();
+
+        let mismatch = SchemaMismatch {
+            missing_migrations: known_versions
+                .iter()
+                .filter(|version| !applied_versions.contains(version))
+                .copied()
+                .collect(),
+            unknown_migrations: applied_versions
+                .iter()
+                .filter(|version| !known_versions.contains(version))
+                .copied()
+                .collect(),
+        };
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_schema_mismatch");
+        mismatch
+    }
+}
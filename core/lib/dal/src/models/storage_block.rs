@@ -83,6 +83,15 @@ pub struct StorageBlock {
     pub gas_per_pubdata_limit: i64,
 
     pub skip_proof: bool,
+
+    pub pubdata_da: Option<String>,
+    pub calldata_pubdata_cost: Option<BigDecimal>,
+    pub blob_pubdata_cost: Option<BigDecimal>,
+    pub protocol_version: Option<i32>,
+    pub state_diffs: Option<Vec<u8>>,
+    pub compressed_state_diffs: Option<Vec<u8>>,
+    pub bloom_computed: bool,
+    pub seal_reason: Option<String>,
 }
 
 impl From<StorageBlock> for L1BatchHeader {
@@ -160,6 +169,8 @@ impl TryInto<BlockMetadata> for StorageBlock {
             repeated_writes_compressed: self
                 .compressed_repeated_writes
                 .ok_or(StorageBlockConvertError::IncompleteBlock)?,
+            // Not a part of `StorageBlock`; loaded separately by `BlocksDal`.
+            state_diffs_compressed: None,
             l2_l1_messages_compressed: self
                 .l2_l1_compressed_messages
                 .ok_or(StorageBlockConvertError::IncompleteBlock)?,
@@ -271,10 +282,10 @@ pub fn web3_block_number_to_sql(block_number: api::BlockNumber) -> String {
         api::BlockNumber::Number(number) => number.to_string(),
         api::BlockNumber::Earliest => 0.to_string(),
         api::BlockNumber::Pending => {
-            "(SELECT (MAX(number) + 1) as number FROM miniblocks)".to_string()
+            "(SELECT (MAX(number) + 1) as number FROM miniblocks WHERE is_sealed)".to_string()
         }
         api::BlockNumber::Latest | api::BlockNumber::Committed => {
-            "(SELECT MAX(number) as number FROM miniblocks)".to_string()
+            "(SELECT MAX(number) as number FROM miniblocks WHERE is_sealed)".to_string()
         }
         api::BlockNumber::Finalized => "
                 (SELECT COALESCE(
@@ -295,8 +306,10 @@ pub fn web3_block_number_to_sql(block_number: api::BlockNumber) -> String {
     }
 }
 
+/// Returns a predicate selecting the specified miniblock. Only sealed miniblocks are matched, so that
+/// miniblocks with partially persisted data are never returned.
 pub fn web3_block_where_sql(block_id: api::BlockId, arg_index: u8) -> String {
-    match block_id {
+    let block_sql = match block_id {
         api::BlockId::Hash(_) => format!("miniblocks.hash = ${arg_index}"),
         api::BlockId::Number(api::BlockNumber::Number(_)) => {
             format!("miniblocks.number = ${arg_index}")
@@ -305,7 +318,8 @@ pub fn web3_block_where_sql(block_id: api::BlockId, arg_index: u8) -> String {
             let block_sql = web3_block_number_to_sql(number);
             format!("miniblocks.number = {}", block_sql)
         }
-    }
+    };
+    format!("{block_sql} AND miniblocks.is_sealed")
 }
 
 pub fn bind_block_where_sql_params<'q>(
@@ -398,6 +412,8 @@ impl StorageBlockDetails {
                 .fee_account_address
                 .map(|fee_account_address| Address::from_slice(&fee_account_address))
                 .unwrap_or(current_operator_address),
+            protocol_version: None,
+            seal_reason: None,
         }
     }
 }
@@ -473,6 +489,10 @@ impl From<StorageL1BatchDetails> for L1BatchDetails {
                     .map(|default_aa_code_hash| H256::from_slice(&default_aa_code_hash))
                     .expect("should not be none"),
             },
+            pipeline_status: None,
+            protocol_version: None,
+            seal_reason: None,
+            scheduled_execution_at: None,
         }
     }
 }
@@ -532,7 +552,7 @@ mod tests {
         let sql = web3_block_number_to_sql(api::BlockNumber::Pending);
         assert_eq!(
             sql,
-            "(SELECT (MAX(number) + 1) as number FROM miniblocks)".to_string()
+            "(SELECT (MAX(number) + 1) as number FROM miniblocks WHERE is_sealed)".to_string()
         );
     }
 
@@ -541,7 +561,7 @@ mod tests {
         let sql = web3_block_number_to_sql(api::BlockNumber::Latest);
         assert_eq!(
             sql,
-            "(SELECT MAX(number) as number FROM miniblocks)".to_string()
+            "(SELECT MAX(number) as number FROM miniblocks WHERE is_sealed)".to_string()
         );
     }
 
@@ -550,7 +570,7 @@ mod tests {
         let sql = web3_block_number_to_sql(api::BlockNumber::Committed);
         assert_eq!(
             sql,
-            "(SELECT MAX(number) as number FROM miniblocks)".to_string()
+            "(SELECT MAX(number) as number FROM miniblocks WHERE is_sealed)".to_string()
         );
     }
 
@@ -50,6 +50,7 @@ pub struct StorageTxHistory {
     pub updated_at: NaiveDateTime,
     pub signed_raw_tx: Option<Vec<u8>>,
     pub sent_at_block: Option<i32>,
+    pub nonce_consumed_externally: bool,
 }
 
 impl From<StorageEthTx> for EthTx {
@@ -1,3 +1,4 @@
+use crate::data_codec::DataCodecs;
 use crate::BigDecimal;
 use bigdecimal::Zero;
 use itertools::Itertools;
@@ -8,6 +9,7 @@ use sqlx::Row;
 use std::str::FromStr;
 use zksync_types::l2::TransactionType;
 use zksync_types::transaction_request::PaymasterParams;
+use zksync_types::tx::ExecutionMetrics;
 use zksync_types::vm_trace::Call;
 use zksync_types::web3::types::U64;
 use zksync_types::{api, explorer_api, L2_ETH_TOKEN_ADDRESS};
@@ -20,7 +22,9 @@ use zksync_types::{
     EIP_712_TX_TYPE, H160, H256, U256,
 };
 use zksync_types::{ExecuteTransactionCommon, L1BatchNumber, MiniblockNumber};
-use zksync_utils::bigdecimal_to_u256;
+use zksync_utils::{bigdecimal_to_u256, h256_to_account_address};
+
+use super::storage_event::{StorageL2ToL1Log, StorageWeb3Log};
 
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct StorageTransaction {
@@ -63,6 +67,9 @@ pub struct StorageTransaction {
     pub l1_tx_mint: Option<BigDecimal>,
     pub l1_tx_refund_recipient: Option<Vec<u8>>,
 
+    pub valid_until: Option<i64>,
+    pub rejection: Option<serde_json::Value>,
+
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -104,6 +111,8 @@ pub struct StorageTransactionDetails {
 
     pub l1_tx_mint: Option<BigDecimal>,
     pub l1_tx_refund_recipient: Option<Vec<u8>>,
+    pub valid_until: Option<i64>,
+    pub rejection: Option<serde_json::Value>,
 
     pub refunded_gas: i64,
 
@@ -163,6 +172,20 @@ impl From<StorageTransactionDetails> for api::TransactionDetails {
             .eth_execute_tx_hash
             .map(|hash| H256::from_str(&hash).unwrap());
 
+        // Until the transaction is included in a miniblock, `execution_info` holds the metrics
+        // of the mempool-time validation, which have a different shape.
+        let execution_metrics = if tx_details.miniblock_number.is_some() {
+            serde_json::from_value::<ExecutionMetrics>(tx_details.execution_info)
+                .ok()
+                .map(api::TransactionExecutionMetrics::from)
+        } else {
+            None
+        };
+        // `rejection` is NULL for transactions rejected before structured reasons were persisted.
+        let rejection = tx_details.rejection.map(|rejection| {
+            serde_json::from_value(rejection).expect("invalid tx rejection in storage")
+        });
+
         api::TransactionDetails {
             is_l1_originated: tx_details.is_priority,
             status,
@@ -173,6 +196,8 @@ impl From<StorageTransactionDetails> for api::TransactionDetails {
             eth_commit_tx_hash,
             eth_prove_tx_hash,
             eth_execute_tx_hash,
+            execution_metrics,
+            rejection,
         }
     }
 }
@@ -419,6 +444,7 @@ impl From<StorageTransaction> for Transaction {
                 input,
                 data,
                 received_at,
+                valid_until,
                 ..
             } = tx;
 
@@ -427,7 +453,7 @@ impl From<StorageTransaction> for Transaction {
                 paymaster_input,
             };
 
-            let tx_common_data = L2TxCommonData::new(
+            let mut tx_common_data = L2TxCommonData::new(
                 nonce,
                 fee,
                 Address::from_slice(&initiator_address),
@@ -439,6 +465,7 @@ impl From<StorageTransaction> for Transaction {
                 H256::from_slice(&hash),
                 paymaster_params,
             );
+            tx_common_data.valid_until = valid_until.map(|timestamp| timestamp as u64);
 
             let inner = serde_json::from_value::<Execute>(data)
                 .unwrap_or_else(|_| panic!("invalid json in database for tx {:?}", hash));
@@ -457,6 +484,7 @@ pub fn transaction_details_from_storage(
     mut withdrawals: Vec<BalanceChangeInfo>,
     transfer: Option<Erc20TransferInfo>,
     mut deposits: Vec<BalanceChangeInfo>,
+    codecs: &DataCodecs,
 ) -> explorer_api::TransactionDetails {
     let status = tx_details.get_transaction_status();
 
@@ -526,6 +554,8 @@ pub fn transaction_details_from_storage(
         paymaster_input: tx_details.paymaster_input,
         l1_tx_mint: tx_details.l1_tx_mint,
         l1_tx_refund_recipient: tx_details.l1_tx_refund_recipient,
+        valid_until: tx_details.valid_until,
+        rejection: tx_details.rejection,
         refunded_gas: tx_details.refunded_gas,
         execution_info: tx_details.execution_info,
         created_at: tx_details.created_at,
@@ -533,7 +563,8 @@ pub fn transaction_details_from_storage(
     };
     let effective_gas_price =
         bigdecimal_to_u256(storage_tx.effective_gas_price.clone().unwrap_or_default());
-    let tx: Transaction = storage_tx.into();
+    let mut tx: Transaction = storage_tx.into();
+    codecs.decode_transaction(&mut tx);
     let fee = (tx.gas_limit() - tx_details.refunded_gas) * effective_gas_price;
 
     let tx_type = tx.tx_format();
@@ -648,10 +679,225 @@ pub fn transaction_details_from_storage(
     }
 }
 
+/// Receipt of a transaction together with its logs and L2-to-L1 logs aggregated into arrays.
+#[derive(Debug, Clone)]
+pub struct StorageTransactionReceipt {
+    pub tx_hash: Vec<u8>,
+    pub index_in_block: Option<i32>,
+    pub l1_batch_tx_index: Option<i32>,
+    pub block_number: Option<i64>,
+    pub error: Option<String>,
+    pub effective_gas_price: Option<BigDecimal>,
+    pub initiator_address: Vec<u8>,
+    pub transfer_to: Option<serde_json::Value>,
+    pub execute_contract_address: Option<serde_json::Value>,
+    pub tx_format: Option<i32>,
+    pub refunded_gas: i64,
+    pub gas_limit: Option<BigDecimal>,
+    pub block_hash: Option<Vec<u8>>,
+    pub l1_batch_number: Option<i64>,
+    pub contract_address: Option<Vec<u8>>,
+    pub event_addresses: Option<Vec<Vec<u8>>>,
+    pub event_topics1: Option<Vec<Vec<u8>>>,
+    pub event_topics2: Option<Vec<Vec<u8>>>,
+    pub event_topics3: Option<Vec<Vec<u8>>>,
+    pub event_topics4: Option<Vec<Vec<u8>>>,
+    pub event_values: Option<Vec<Vec<u8>>>,
+    pub event_indices_in_block: Option<Vec<i32>>,
+    pub event_indices_in_tx: Option<Vec<i32>>,
+    pub l2_to_l1_log_indices_in_miniblock: Option<Vec<i32>>,
+    pub l2_to_l1_log_indices_in_tx: Option<Vec<i32>>,
+    pub l2_to_l1_log_shard_ids: Option<Vec<i32>>,
+    pub l2_to_l1_log_is_service: Option<Vec<bool>>,
+    pub l2_to_l1_log_tx_indices_in_l1_batch: Option<Vec<i32>>,
+    pub l2_to_l1_log_senders: Option<Vec<Vec<u8>>>,
+    pub l2_to_l1_log_keys: Option<Vec<Vec<u8>>>,
+    pub l2_to_l1_log_values: Option<Vec<Vec<u8>>>,
+}
+
+impl From<StorageTransactionReceipt> for api::TransactionReceipt {
+    fn from(receipt: StorageTransactionReceipt) -> Self {
+        let block_number = receipt.block_number;
+        let status = match (block_number, &receipt.error) {
+            (_, Some(_)) => Some(U64::from(0)),
+            (Some(_), None) => Some(U64::from(1)),
+            // tx not executed yet
+            _ => None,
+        };
+        let tx_type = receipt.tx_format.map(U64::from).unwrap_or_default();
+        let index_in_block = receipt.index_in_block;
+        let transaction_index = index_in_block.map(U64::from).unwrap_or_default();
+        let block_hash = receipt.block_hash.map(|bytes| H256::from_slice(&bytes));
+        let l1_batch_number = receipt.l1_batch_number.map(U64::from);
+        let transaction_hash = H256::from_slice(&receipt.tx_hash);
+        let refunded_gas = U256::from(receipt.refunded_gas as u64);
+
+        let mut api_receipt = api::TransactionReceipt {
+            transaction_hash,
+            transaction_index,
+            block_hash,
+            block_number: block_number.map(U64::from),
+            l1_batch_tx_index: receipt.l1_batch_tx_index.map(U64::from),
+            l1_batch_number,
+            from: H160::from_slice(&receipt.initiator_address),
+            to: receipt
+                .transfer_to
+                .or(receipt.execute_contract_address)
+                .map(|addr| {
+                    serde_json::from_value::<Address>(addr)
+                        .expect("invalid address value in the database")
+                })
+                // For better compatibility with various clients, we never return null.
+                .or_else(|| Some(Address::default())),
+            cumulative_gas_used: Default::default(),
+            gas_used: receipt
+                .gas_limit
+                .map(|gas_limit| bigdecimal_to_u256(gas_limit) - refunded_gas),
+            effective_gas_price: Some(
+                receipt
+                    .effective_gas_price
+                    .map(bigdecimal_to_u256)
+                    .unwrap_or_default(),
+            ),
+            contract_address: receipt
+                .contract_address
+                .as_deref()
+                .map(|addr| h256_to_account_address(&H256::from_slice(addr))),
+            logs: vec![],
+            l2_to_l1_logs: vec![],
+            status,
+            root: block_hash,
+            logs_bloom: Default::default(),
+            // Even though the Rust SDK recommends us to supply "None" for legacy transactions
+            // we always supply some number anyway to have the same behaviour as most popular RPCs
+            transaction_type: Some(tx_type),
+            confirmation_status: None,
+        };
+
+        // Logs and L2-to-L1 logs can only be present for executed transactions.
+        let (Some(block_number), Some(index_in_block)) = (block_number, index_in_block) else {
+            return api_receipt;
+        };
+        let event_addresses = receipt.event_addresses.unwrap_or_default();
+        if !event_addresses.is_empty() {
+            let topics1 = receipt.event_topics1.unwrap_or_default();
+            let topics2 = receipt.event_topics2.unwrap_or_default();
+            let topics3 = receipt.event_topics3.unwrap_or_default();
+            let topics4 = receipt.event_topics4.unwrap_or_default();
+            let values = receipt.event_values.unwrap_or_default();
+            let indices_in_block = receipt.event_indices_in_block.unwrap_or_default();
+            let indices_in_tx = receipt.event_indices_in_tx.unwrap_or_default();
+            let storage_logs = itertools::izip!(
+                event_addresses,
+                topics1,
+                topics2,
+                topics3,
+                topics4,
+                values,
+                indices_in_block,
+                indices_in_tx
+            );
+            api_receipt.logs = storage_logs
+                .map(
+                    |(
+                        address,
+                        topic1,
+                        topic2,
+                        topic3,
+                        topic4,
+                        value,
+                        event_index_in_block,
+                        event_index_in_tx,
+                    )| {
+                        let mut log = api::Log::from(StorageWeb3Log {
+                            address,
+                            topic1,
+                            topic2,
+                            topic3,
+                            topic4,
+                            value,
+                            block_hash: None,
+                            miniblock_number: block_number,
+                            l1_batch_number: None,
+                            tx_hash: transaction_hash.as_bytes().to_vec(),
+                            tx_index_in_block: index_in_block,
+                            event_index_in_block,
+                            event_index_in_tx,
+                        });
+                        log.block_hash = block_hash;
+                        log.l1_batch_number = l1_batch_number;
+                        log
+                    },
+                )
+                .collect();
+        }
+
+        let l2_to_l1_log_senders = receipt.l2_to_l1_log_senders.unwrap_or_default();
+        if !l2_to_l1_log_senders.is_empty() {
+            let indices_in_miniblock = receipt
+                .l2_to_l1_log_indices_in_miniblock
+                .unwrap_or_default();
+            let indices_in_tx = receipt.l2_to_l1_log_indices_in_tx.unwrap_or_default();
+            let shard_ids = receipt.l2_to_l1_log_shard_ids.unwrap_or_default();
+            let is_service = receipt.l2_to_l1_log_is_service.unwrap_or_default();
+            let tx_indices_in_l1_batch = receipt
+                .l2_to_l1_log_tx_indices_in_l1_batch
+                .unwrap_or_default();
+            let keys = receipt.l2_to_l1_log_keys.unwrap_or_default();
+            let values = receipt.l2_to_l1_log_values.unwrap_or_default();
+            let storage_logs = itertools::izip!(
+                l2_to_l1_log_senders,
+                indices_in_miniblock,
+                indices_in_tx,
+                shard_ids,
+                is_service,
+                tx_indices_in_l1_batch,
+                keys,
+                values
+            );
+            api_receipt.l2_to_l1_logs = storage_logs
+                .map(
+                    |(
+                        sender,
+                        index_in_miniblock,
+                        index_in_tx,
+                        shard_id,
+                        is_service,
+                        tx_index_in_l1_batch,
+                        key,
+                        value,
+                    )| {
+                        let mut log = api::L2ToL1Log::from(StorageL2ToL1Log {
+                            block_hash: None,
+                            miniblock_number: block_number,
+                            l1_batch_number: None,
+                            log_index_in_miniblock: index_in_miniblock,
+                            log_index_in_tx: index_in_tx,
+                            tx_hash: transaction_hash.as_bytes().to_vec(),
+                            shard_id,
+                            is_service,
+                            tx_index_in_miniblock: index_in_block,
+                            tx_index_in_l1_batch,
+                            sender,
+                            key,
+                            value,
+                        });
+                        log.block_hash = block_hash;
+                        log.l1_batch_number = l1_batch_number;
+                        log
+                    },
+                )
+                .collect();
+        }
+        api_receipt
+    }
+}
+
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct CallTrace {
     pub tx_hash: Vec<u8>,
     pub call_trace: Vec<u8>,
+    pub truncated: bool,
 }
 
 impl From<CallTrace> for Call {
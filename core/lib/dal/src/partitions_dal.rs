@@ -0,0 +1,324 @@
+//! Management of tables partitioned by miniblock number ranges (`events` and `storage_logs`).
+
+use sqlx::Row;
+
+use std::{fmt, ops::Range, time::Instant};
+
+use zksync_types::MiniblockNumber;
+
+use crate::StorageProcessor;
+
+/// Table partitioned by miniblock number ranges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PartitionedTable {
+    Events,
+    StorageLogs,
+}
+
+impl PartitionedTable {
+    pub const ALL: [Self; 2] = [Self::Events, Self::StorageLogs];
+
+    /// Returns whether partitions of the table are detached and archived once they fall out of the retention window.
+    /// `storage_logs` partitions are always retained, since storage logs are required to serve historical state,
+    /// roll back miniblocks and create state snapshots.
+    pub fn is_archivable(self) -> bool {
+        matches!(self, Self::Events)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Events => "events",
+            Self::StorageLogs => "storage_logs",
+        }
+    }
+
+    fn default_partition(self) -> String {
+        format!("{}_default", self.as_str())
+    }
+
+    fn partition_name(self, from_miniblock: u32) -> String {
+        format!("{}_p{from_miniblock}", self.as_str())
+    }
+}
+
+impl fmt::Display for PartitionedTable {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+/// Status of a [`MiniblockRangePartition`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartitionStatus {
+    /// Partition is attached to its parent table.
+    Attached,
+    /// Partition is detached from its parent table, but is not archived yet.
+    Detached,
+    /// Partition data is exported to the object store, and the partition is dropped.
+    Archived,
+}
+
+impl PartitionStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Attached => "attached",
+            Self::Detached => "detached",
+            Self::Archived => "archived",
+        }
+    }
+
+    fn parse(s: &str) -> Self {
+        match s {
+            "attached" => Self::Attached,
+            "detached" => Self::Detached,
+            "archived" => Self::Archived,
+            _ => panic!("Unknown partition status: {s}"),
+        }
+    }
+}
+
+/// Partition of a [`PartitionedTable`] holding data for a range of miniblocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MiniblockRangePartition {
+    pub name: String,
+    pub table: PartitionedTable,
+    /// Range of miniblocks covered by the partition (the end is exclusive).
+    pub miniblocks: Range<u32>,
+    pub status: PartitionStatus,
+}
+
+#[derive(Debug)]
+pub struct PartitionsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl PartitionsDal<'_, '_> {
+    pub async fn get_partitions(
+        &mut self,
+        table: PartitionedTable,
+        status: PartitionStatus,
+    ) -> Vec<MiniblockRangePartition> {
+        let rows = sqlx::query!(
+            "SELECT partition_name, from_miniblock, to_miniblock, status FROM miniblock_range_partitions \
+            WHERE table_name = $1 AND status = $2 \
+            ORDER BY from_miniblock",
+            table.as_str(),
+            status.as_str()
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap();
+
+        rows.into_iter()
+            .map(|row| MiniblockRangePartition {
+                name: row.partition_name,
+                table,
+                miniblocks: row.from_miniblock as u32..row.to_miniblock as u32,
+                status: PartitionStatus::parse(&row.status),
+            })
+            .collect()
+    }
+
+    /// Returns the first miniblock that is not covered by any of the partitions of the `table`.
+    /// Takes into account rows in the default partition, since a partition cannot be created
+    /// for a range which has rows in the default partition.
+    pub async fn get_next_partition_start(&mut self, table: PartitionedTable) -> MiniblockNumber {
+        let max_covered = sqlx::query!(
+            "SELECT MAX(to_miniblock) AS max_to FROM miniblock_range_partitions \
+            WHERE table_name = $1",
+            table.as_str()
+        )
+        .fetch_one(self.storage.conn())
+        .await
+        .unwrap()
+        .max_to;
+
+        let query = format!(
+            "SELECT MAX(miniblock_number) AS max_number FROM {}",
+            table.default_partition()
+        );
+        let max_in_default: Option<i64> = sqlx::query(&query)
+            .fetch_one(self.storage.conn())
+            .await
+            .unwrap()
+            .get("max_number");
+
+        let next_start = max_covered
+            .unwrap_or(0)
+            .max(max_in_default.map_or(0, |number| number + 1));
+        MiniblockNumber(next_start as u32)
+    }
+
+    /// Returns the first miniblock for which rows of the `table` are retained in attached partitions,
+    /// or `None` if no partitions were ever detached.
+    pub async fn get_first_retained_miniblock(
+        &mut self,
+        table: PartitionedTable,
+    ) -> Option<MiniblockNumber> {
+        let max_detached = sqlx::query!(
+            "SELECT MAX(to_miniblock) AS max_to FROM miniblock_range_partitions \
+            WHERE table_name = $1 AND status <> $2",
+            table.as_str(),
+            PartitionStatus::Attached.as_str()
+        )
+        .fetch_one(self.storage.conn())
+        .await
+        .unwrap()
+        .max_to;
+        max_detached.map(|number| MiniblockNumber(number as u32))
+    }
+
+    /// Creates a new attached partition of the `table` covering the specified range of miniblocks.
+    pub async fn create_partition(&mut self, table: PartitionedTable, miniblocks: Range<u32>) {
+        let started_at = Instant::now();
+        let name = table.partition_name(miniblocks.start);
+        let mut transaction = self.storage.start_transaction().await;
+
+        let query = format!(
+            "CREATE TABLE {name} PARTITION OF {table} FOR VALUES FROM ({}) TO ({})",
+            miniblocks.start, miniblocks.end
+        );
+        sqlx::query(&query)
+            .execute(transaction.conn())
+            .await
+            .unwrap();
+        sqlx::query!(
+            "INSERT INTO miniblock_range_partitions \
+            (partition_name, table_name, from_miniblock, to_miniblock, status, created_at, updated_at) \
+            VALUES ($1, $2, $3, $4, $5, now(), now())",
+            name,
+            table.as_str(),
+            miniblocks.start as i64,
+            miniblocks.end as i64,
+            PartitionStatus::Attached.as_str()
+        )
+        .execute(transaction.conn())
+        .await
+        .unwrap();
+        transaction.commit().await;
+
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "create_partition");
+    }
+
+    /// Detaches the partition from its parent table. The partition data becomes inaccessible
+    /// via the parent table, but remains in the DB until the partition is archived.
+    pub async fn detach_partition(&mut self, partition: &MiniblockRangePartition) {
+        let started_at = Instant::now();
+        let mut transaction = self.storage.start_transaction().await;
+
+        let query = format!(
+            "ALTER TABLE {} DETACH PARTITION {}",
+            partition.table, partition.name
+        );
+        sqlx::query(&query)
+            .execute(transaction.conn())
+            .await
+            .unwrap();
+        Self::set_partition_status(
+            &mut transaction,
+            &partition.name,
+            PartitionStatus::Detached,
+            None,
+        )
+        .await;
+        transaction.commit().await;
+
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "detach_partition");
+    }
+
+    /// Exports rows of a detached partition for the specified range of miniblocks as JSON lines.
+    pub async fn export_partition_rows(
+        &mut self,
+        partition: &MiniblockRangePartition,
+        miniblocks: Range<u32>,
+    ) -> Vec<u8> {
+        let started_at = Instant::now();
+        let query = format!(
+            "SELECT row_to_json(p)::text AS row FROM {} AS p \
+            WHERE miniblock_number >= {} AND miniblock_number < {} \
+            ORDER BY miniblock_number",
+            partition.name, miniblocks.start, miniblocks.end
+        );
+        let rows = sqlx::query(&query)
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap();
+
+        let mut buffer = Vec::new();
+        for row in rows {
+            buffer.extend_from_slice(row.get::<String, _>("row").as_bytes());
+            buffer.push(b'\n');
+        }
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "export_partition_rows");
+        buffer
+    }
+
+    /// Drops a detached partition after its data was exported to the object store.
+    pub async fn drop_archived_partition(
+        &mut self,
+        partition: &MiniblockRangePartition,
+        archive_object_prefix: &str,
+    ) {
+        assert_eq!(
+            partition.status,
+            PartitionStatus::Detached,
+            "Only detached partitions can be archived"
+        );
+        let mut transaction = self.storage.start_transaction().await;
+
+        let query = format!("DROP TABLE {}", partition.name);
+        sqlx::query(&query)
+            .execute(transaction.conn())
+            .await
+            .unwrap();
+        Self::set_partition_status(
+            &mut transaction,
+            &partition.name,
+            PartitionStatus::Archived,
+            Some(archive_object_prefix),
+        )
+        .await;
+        transaction.commit().await;
+    }
+
+    async fn set_partition_status(
+        storage: &mut StorageProcessor<'_>,
+        partition_name: &str,
+        status: PartitionStatus,
+        archive_object_prefix: Option<&str>,
+    ) {
+        sqlx::query!(
+            "UPDATE miniblock_range_partitions \
+            SET status = $2, archive_object_prefix = COALESCE($3, archive_object_prefix), updated_at = now() \
+            WHERE partition_name = $1",
+            partition_name,
+            status.as_str(),
+            archive_object_prefix
+        )
+        .execute(storage.conn())
+        .await
+        .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partition_status_roundtrip() {
+        for status in [
+            PartitionStatus::Attached,
+            PartitionStatus::Detached,
+            PartitionStatus::Archived,
+        ] {
+            assert_eq!(PartitionStatus::parse(status.as_str()), status);
+        }
+    }
+
+    #[test]
+    fn storage_logs_are_not_archivable() {
+        assert!(PartitionedTable::Events.is_archivable());
+        assert!(!PartitionedTable::StorageLogs.is_archivable());
+    }
+}
@@ -0,0 +1,86 @@
+use zksync_contracts::BaseSystemContractsHashes;
+use zksync_types::{api::ProtocolVersion, L1BlockNumber, ProtocolVersionId, H256};
+
+use crate::StorageProcessor;
+
+#[derive(Debug)]
+pub struct ProtocolVersionsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ProtocolVersionsDal<'_, '_> {
+    /// Registers a protocol version observed on L1 together with the hashes of base system contracts
+    /// it uses, if they are known. Re-registering a known version only fills in the missing hashes.
+    pub async fn save_protocol_version(
+        &mut self,
+        version_id: ProtocolVersionId,
+        l1_block_number: L1BlockNumber,
+        base_system_contracts_hashes: Option<BaseSystemContractsHashes>,
+    ) {
+        sqlx::query!(
+            "INSERT INTO protocol_versions \
+            (id, l1_block_number, bootloader_code_hash, default_account_code_hash, created_at) \
+            VALUES ($1, $2, $3, $4, now()) \
+            ON CONFLICT (id) DO UPDATE SET \
+            bootloader_code_hash = \
+                COALESCE(protocol_versions.bootloader_code_hash, EXCLUDED.bootloader_code_hash), \
+            default_account_code_hash = \
+                COALESCE(protocol_versions.default_account_code_hash, EXCLUDED.default_account_code_hash)",
+            version_id.0 as i32,
+            l1_block_number.0 as i64,
+            base_system_contracts_hashes.map(|hashes| hashes.bootloader.as_bytes().to_vec()),
+            base_system_contracts_hashes.map(|hashes| hashes.default_aa.as_bytes().to_vec())
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Returns the latest protocol version registered on L1, or `None` if no versions are registered.
+    pub async fn latest_version_id(&mut self) -> Option<ProtocolVersionId> {
+        let id = sqlx::query!("SELECT MAX(id) AS id FROM protocol_versions")
+            .fetch_one(self.storage.conn())
+            .await
+            .unwrap()
+            .id;
+        id.map(|id| ProtocolVersionId(id as u16))
+    }
+
+    pub async fn get_protocol_version(
+        &mut self,
+        version_id: ProtocolVersionId,
+    ) -> Option<ProtocolVersion> {
+        sqlx::query!(
+            "SELECT id, l1_block_number FROM protocol_versions WHERE id = $1",
+            version_id.0 as i32
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()
+        .map(|row| ProtocolVersion {
+            version_id: ProtocolVersionId(row.id as u16),
+            l1_block_number: L1BlockNumber(row.l1_block_number as u32),
+        })
+    }
+
+    /// Returns hashes of base system contracts used by the specified protocol version, or `None`
+    /// if the version is unknown or its contracts weren't fetched from L1.
+    pub async fn get_base_system_contracts_hashes(
+        &mut self,
+        version_id: ProtocolVersionId,
+    ) -> Option<BaseSystemContractsHashes> {
+        let row = sqlx::query!(
+            "SELECT bootloader_code_hash, default_account_code_hash \
+            FROM protocol_versions WHERE id = $1",
+            version_id.0 as i32
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()?;
+
+        Some(BaseSystemContractsHashes {
+            bootloader: H256::from_slice(&row.bootloader_code_hash?),
+            default_aa: H256::from_slice(&row.default_account_code_hash?),
+        })
+    }
+}
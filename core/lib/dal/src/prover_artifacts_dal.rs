@@ -0,0 +1,129 @@
+//! Garbage collection of FRI prover artifacts.
+//!
+//! Artifacts of an L1 batch (witness inputs, circuits, aggregation inputs and intermediate proofs) are no longer
+//! needed once the batch proof is verified on L1. The last L1 batch which artifacts were removed from the object store
+//! is recorded in the single-row `prover_artifacts_gc` table, so that the cleanup resumes where it has stopped.
+
+use zksync_types::{proofs::AggregationRound, L1BatchNumber};
+
+use crate::{SqlxError, StorageProcessor};
+
+/// Object store keys of the prover artifacts of a single L1 batch.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct L1BatchProverArtifacts {
+    pub witness_inputs: Vec<String>,
+    pub circuits: Vec<String>,
+    /// Proofs of all aggregation rounds except for the final (scheduler) one.
+    pub intermediate_proofs: Vec<String>,
+    pub leaf_aggregation_inputs: Vec<String>,
+    pub node_aggregations: Vec<String>,
+    pub scheduler_inputs: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct ProverArtifactsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ProverArtifactsDal<'_, '_> {
+    /// Returns the last L1 batch which artifacts were removed, or `None` if no artifacts were removed yet.
+    pub async fn get_last_cleaned_l1_batch(&mut self) -> Result<Option<L1BatchNumber>, SqlxError> {
+        let row = sqlx::query!("SELECT last_cleaned_l1_batch FROM prover_artifacts_gc")
+            .fetch_optional(self.storage.conn())
+            .await?;
+        Ok(row.map(|row| L1BatchNumber(row.last_cleaned_l1_batch as u32)))
+    }
+
+    pub async fn set_last_cleaned_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<(), SqlxError> {
+        sqlx::query!(
+            "INSERT INTO prover_artifacts_gc (id, last_cleaned_l1_batch, created_at, updated_at) \
+            VALUES (TRUE, $1, now(), now()) \
+            ON CONFLICT (id) DO UPDATE SET last_cleaned_l1_batch = $1, updated_at = now()",
+            l1_batch_number.0 as i64
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Returns object store keys of all artifacts produced by FRI witness generators and provers
+    /// for the specified L1 batch.
+    pub async fn get_l1_batch_artifacts(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<L1BatchProverArtifacts, SqlxError> {
+        let l1_batch_number = l1_batch_number.0 as i64;
+        let witness_inputs = sqlx::query!(
+            "SELECT merkle_tree_paths_blob_url AS \"url?\" FROM witness_inputs_fri \
+            WHERE l1_batch_number = $1",
+            l1_batch_number
+        )
+        .fetch_all(self.storage.conn())
+        .await?
+        .into_iter()
+        .filter_map(|row| row.url)
+        .collect();
+        let circuits = sqlx::query!(
+            "SELECT circuit_blob_url AS \"url?\" FROM prover_jobs_fri WHERE l1_batch_number = $1",
+            l1_batch_number
+        )
+        .fetch_all(self.storage.conn())
+        .await?
+        .into_iter()
+        .filter_map(|row| row.url)
+        .collect();
+        let intermediate_proofs = sqlx::query!(
+            "SELECT proof_blob_url AS \"url?\" FROM prover_jobs_fri \
+            WHERE l1_batch_number = $1 AND aggregation_round <> $2",
+            l1_batch_number,
+            AggregationRound::Scheduler as i16
+        )
+        .fetch_all(self.storage.conn())
+        .await?
+        .into_iter()
+        .filter_map(|row| row.url)
+        .collect();
+        let leaf_aggregation_inputs = sqlx::query!(
+            "SELECT closed_form_inputs_blob_url AS \"url?\" FROM leaf_aggregation_witness_jobs_fri \
+            WHERE l1_batch_number = $1",
+            l1_batch_number
+        )
+        .fetch_all(self.storage.conn())
+        .await?
+        .into_iter()
+        .filter_map(|row| row.url)
+        .collect();
+        let node_aggregations = sqlx::query!(
+            "SELECT aggregations_url AS \"url?\" FROM node_aggregation_witness_jobs_fri \
+            WHERE l1_batch_number = $1",
+            l1_batch_number
+        )
+        .fetch_all(self.storage.conn())
+        .await?
+        .into_iter()
+        .filter_map(|row| row.url)
+        .collect();
+        let scheduler_inputs = sqlx::query!(
+            "SELECT scheduler_partial_input_blob_url AS \"url?\" FROM scheduler_witness_jobs_fri \
+            WHERE l1_batch_number = $1",
+            l1_batch_number
+        )
+        .fetch_all(self.storage.conn())
+        .await?
+        .into_iter()
+        .filter_map(|row| row.url)
+        .collect();
+
+        Ok(L1BatchProverArtifacts {
+            witness_inputs,
+            circuits,
+            intermediate_proofs,
+            leaf_aggregation_inputs,
+            node_aggregations,
+            scheduler_inputs,
+        })
+    }
+}
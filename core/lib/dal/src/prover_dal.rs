@@ -5,7 +5,8 @@ use std::time::{Duration, Instant};
 
 use zksync_types::aggregated_operations::BlockProofForL1;
 use zksync_types::proofs::{
-    AggregationRound, JobCountStatistics, JobExtendedStatistics, ProverJobInfo, ProverJobMetadata,
+    AggregationRound, JobCountStatistics, JobExtendedStatistics, JobQueueStatistics, ProverJobInfo,
+    ProverJobMetadata,
 };
 use zksync_types::zkevm_test_harness::abstract_zksync_circuit::concrete_circuits::ZkSyncProof;
 use zksync_types::zkevm_test_harness::bellman::bn256::Bn256;
@@ -13,6 +14,7 @@ use zksync_types::L1BatchNumber;
 
 use crate::models::storage_prover_job_info::StorageProverJobInfo;
 use crate::time_utils::{duration_to_naive_time, pg_interval_from_duration};
+use crate::witness_generator_dal::update_job_queue_stats;
 use crate::StorageProcessor;
 
 #[derive(Debug)]
@@ -310,6 +312,38 @@ impl ProverDal<'_, '_> {
         }
     }
 
+    /// Returns the depth of prover job queues per aggregation round together with the age of the oldest jobs.
+    pub async fn get_prover_jobs_queue_stats_per_round(
+        &mut self,
+    ) -> HashMap<AggregationRound, JobQueueStatistics> {
+        let rows = sqlx::query!(
+            r#"
+                SELECT aggregation_round, status, COUNT(*) AS "count!",
+                    EXTRACT(EPOCH FROM now() - MIN(
+                        CASE WHEN status = 'in_progress' THEN processing_started_at ELSE updated_at END
+                    ))::FLOAT8 AS oldest_age_sec
+                FROM prover_jobs
+                WHERE status IN ('queued', 'in_progress')
+                GROUP BY aggregation_round, status
+            "#
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap();
+
+        let mut stats = HashMap::<_, JobQueueStatistics>::new();
+        for row in rows {
+            let round = AggregationRound::from(row.aggregation_round as u8);
+            update_job_queue_stats(
+                stats.entry(round).or_default(),
+                &row.status,
+                row.count,
+                row.oldest_age_sec,
+            );
+        }
+        stats
+    }
+
     pub async fn get_prover_jobs_stats(&mut self) -> JobCountStatistics {
         {
             let mut results: HashMap<String, usize> = sqlx::query!(
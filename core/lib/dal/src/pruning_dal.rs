@@ -0,0 +1,230 @@
+//! Pruning of historical data for nodes running in the full (i.e., non-archive) mode.
+//!
+//! A full node keeps the state (storage logs, factory deps, block headers), but removes
+//! transactions, events and call traces for miniblocks beyond the retention horizon. The last
+//! pruned miniblock is recorded in the `pruning_info` table; DAL queries concerning pruned
+//! miniblocks return [`HistoryQueryError::PrunedHistory`] instead of incomplete data. On archive
+//! nodes, the table is empty and no data is ever considered pruned.
+
+use std::time::Instant;
+
+use zksync_types::{L1BatchNumber, MiniblockNumber};
+
+use crate::{SqlxError, StorageProcessor};
+
+/// Error returned by DAL queries that may concern pruned historical data.
+#[derive(Debug, thiserror::Error)]
+pub enum HistoryQueryError {
+    #[error("history up to miniblock #{last_pruned_miniblock} is pruned on this node")]
+    PrunedHistory {
+        last_pruned_miniblock: MiniblockNumber,
+    },
+    #[error(transparent)]
+    Sqlx(#[from] SqlxError),
+}
+
+/// Information about pruned historical data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PruningInfo {
+    pub last_pruned_l1_batch: Option<L1BatchNumber>,
+    pub last_pruned_miniblock: Option<MiniblockNumber>,
+}
+
+/// Numbers of rows removed by [`PruningDal::prune_history()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PruningStats {
+    pub deleted_transactions: u64,
+    pub deleted_events: u64,
+}
+
+#[derive(Debug)]
+pub struct PruningDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl PruningDal<'_, '_> {
+    pub async fn get_pruning_info(&mut self) -> Result<PruningInfo, SqlxError> {
+        let row =
+            sqlx::query!("SELECT last_pruned_l1_batch, last_pruned_miniblock FROM pruning_info")
+                .fetch_optional(self.storage.conn())
+                .await?;
+        Ok(row.map_or_else(PruningInfo::default, |row| PruningInfo {
+            last_pruned_l1_batch: Some(L1BatchNumber(row.last_pruned_l1_batch as u32)),
+            last_pruned_miniblock: Some(MiniblockNumber(row.last_pruned_miniblock as u32)),
+        }))
+    }
+
+    /// Checks that historical data for the specified miniblock is not pruned.
+    pub async fn check_miniblock_history(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> Result<(), HistoryQueryError> {
+        let info = self.get_pruning_info().await?;
+        match info.last_pruned_miniblock {
+            Some(last_pruned_miniblock) if miniblock_number <= last_pruned_miniblock => {
+                Err(HistoryQueryError::PrunedHistory {
+                    last_pruned_miniblock,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Removes transactions, their call traces and events for all miniblocks up to and including
+    /// the last miniblock of `last_l1_batch`. Miniblock headers and L2-to-L1 logs are retained.
+    pub async fn prune_history(
+        &mut self,
+        last_l1_batch: L1BatchNumber,
+    ) -> Result<PruningStats, SqlxError> {
+        let started_at = Instant::now();
+        let mut transaction = self.storage.start_transaction().await;
+
+        let last_miniblock = sqlx::query!(
+            "SELECT MAX(number) AS number FROM miniblocks WHERE l1_batch_number <= $1",
+            last_l1_batch.0 as i64
+        )
+        .fetch_one(transaction.conn())
+        .await?
+        .number;
+        let Some(last_miniblock) = last_miniblock else {
+            return Ok(PruningStats::default());
+        };
+
+        let deleted_events = sqlx::query!(
+            "DELETE FROM events WHERE miniblock_number <= $1",
+            last_miniblock
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+        // Call traces are removed via `ON DELETE CASCADE`.
+        let deleted_transactions = sqlx::query!(
+            "DELETE FROM transactions WHERE miniblock_number <= $1",
+            last_miniblock
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+        sqlx::query!(
+            "DELETE FROM api_transaction_details WHERE miniblock_number <= $1",
+            last_miniblock
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        sqlx::query!(
+            "INSERT INTO pruning_info \
+                (last_pruned_l1_batch, last_pruned_miniblock, created_at, updated_at) \
+            VALUES ($1, $2, now(), now()) \
+            ON CONFLICT (id) DO UPDATE SET \
+                last_pruned_l1_batch = GREATEST(pruning_info.last_pruned_l1_batch, $1), \
+                last_pruned_miniblock = GREATEST(pruning_info.last_pruned_miniblock, $2), \
+                updated_at = now()",
+            last_l1_batch.0 as i64,
+            last_miniblock
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await;
+
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "prune_history");
+        Ok(PruningStats {
+            deleted_transactions,
+            deleted_events,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use db_test_macro::db_test;
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{
+        block::{BlockGasCount, L1BatchHeader},
+        fee::TransactionExecutionMetrics,
+        Address,
+    };
+
+    use super::*;
+    use crate::{
+        tests::{create_miniblock_header, mock_execution_result, mock_l2_transaction},
+        ConnectionPool,
+    };
+
+    #[db_test(dal_crate)]
+    async fn pruning_history(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        let info = conn.pruning_dal().get_pruning_info().await.unwrap();
+        assert_eq!(info, PruningInfo::default());
+        conn.pruning_dal()
+            .check_miniblock_history(MiniblockNumber(0))
+            .await
+            .unwrap();
+
+        for number in 0..2 {
+            let header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                0,
+                Address::default(),
+                BaseSystemContractsHashes::default(),
+            );
+            conn.blocks_dal()
+                .insert_l1_batch(&header, BlockGasCount::default())
+                .await;
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await;
+
+            let tx = mock_l2_transaction();
+            conn.transactions_dal()
+                .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+                .await;
+            conn.transactions_dal()
+                .mark_txs_as_executed_in_miniblock(
+                    MiniblockNumber(number),
+                    &[mock_execution_result(tx)],
+                    1.into(),
+                )
+                .await;
+            conn.blocks_dal()
+                .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(number))
+                .await;
+        }
+
+        let stats = conn
+            .pruning_dal()
+            .prune_history(L1BatchNumber(0))
+            .await
+            .unwrap();
+        assert_eq!(stats.deleted_transactions, 1);
+
+        let info = conn.pruning_dal().get_pruning_info().await.unwrap();
+        assert_eq!(info.last_pruned_l1_batch, Some(L1BatchNumber(0)));
+        assert_eq!(info.last_pruned_miniblock, Some(MiniblockNumber(0)));
+        let err = conn
+            .pruning_dal()
+            .check_miniblock_history(MiniblockNumber(0))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            HistoryQueryError::PrunedHistory {
+                last_pruned_miniblock: MiniblockNumber(0)
+            }
+        ));
+        conn.pruning_dal()
+            .check_miniblock_history(MiniblockNumber(1))
+            .await
+            .unwrap();
+
+        let remaining_txs = conn
+            .transactions_web3_dal()
+            .get_raw_miniblock_transactions(MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(remaining_txs.len(), 1);
+    }
+}
@@ -0,0 +1,238 @@
+//! Materialized snapshots of the storage state at specific miniblocks.
+//!
+//! A snapshot holds values of all storage slots as of the end of its miniblock. Historical state
+//! at a miniblock is resolved from the latest snapshot at or before it plus storage logs written after
+//! the snapshot, which bounds the range of `storage_logs` scanned by a query and keeps state queries
+//! functional after old partitions of `storage_logs` are detached.
+
+use std::time::Instant;
+
+use zksync_types::MiniblockNumber;
+
+use crate::{SqlxError, StorageProcessor};
+
+#[derive(Debug)]
+pub struct StateSnapshotsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl StateSnapshotsDal<'_, '_> {
+    /// Returns miniblocks for which snapshots are created, in the ascending order.
+    pub async fn get_snapshot_miniblocks(&mut self) -> Result<Vec<MiniblockNumber>, SqlxError> {
+        let rows = sqlx::query!(
+            "SELECT miniblock_number FROM storage_snapshot_miniblocks ORDER BY miniblock_number"
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| MiniblockNumber(row.miniblock_number as u32))
+            .collect())
+    }
+
+    /// Returns the latest snapshot at or before the specified miniblock.
+    pub async fn get_latest_snapshot_at(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> Result<Option<MiniblockNumber>, SqlxError> {
+        let number = sqlx::query!(
+            "SELECT MAX(miniblock_number) AS number FROM storage_snapshot_miniblocks \
+            WHERE miniblock_number <= $1",
+            miniblock_number.0 as i64
+        )
+        .fetch_one(self.storage.conn())
+        .await?
+        .number;
+        Ok(number.map(|number| MiniblockNumber(number as u32)))
+    }
+
+    /// Creates a snapshot of the state at the specified miniblock from the previous snapshot
+    /// (if any) and the storage logs written after it. Returns the number of storage slots
+    /// in the snapshot, or `None` if the snapshot already exists.
+    pub async fn create_snapshot(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> Result<Option<u64>, SqlxError> {
+        let started_at = Instant::now();
+        let mut transaction = self.storage.start_transaction().await;
+        let inserted = sqlx::query!(
+            "INSERT INTO storage_snapshot_miniblocks (miniblock_number, created_at) \
+            VALUES ($1, now()) \
+            ON CONFLICT (miniblock_number) DO NOTHING",
+            miniblock_number.0 as i64
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+        if inserted == 0 {
+            return Ok(None);
+        }
+
+        let base_snapshot = sqlx::query!(
+            "SELECT MAX(miniblock_number) AS number FROM storage_snapshot_miniblocks \
+            WHERE miniblock_number < $1",
+            miniblock_number.0 as i64
+        )
+        .fetch_one(transaction.conn())
+        .await?
+        .number;
+
+        let slot_count = sqlx::query!(
+            "INSERT INTO storage_snapshots (miniblock_number, hashed_key, value) \
+            SELECT DISTINCT ON (hashed_key) $1, hashed_key, value FROM ( \
+                SELECT hashed_key, value, miniblock_number, operation_number FROM storage_logs \
+                WHERE miniblock_number > $2 AND miniblock_number <= $1 \
+                UNION ALL \
+                SELECT hashed_key, value, miniblock_number, -1 AS operation_number FROM storage_snapshots \
+                WHERE miniblock_number = $2 \
+            ) AS logs \
+            ORDER BY hashed_key, miniblock_number DESC, operation_number DESC",
+            miniblock_number.0 as i64,
+            base_snapshot.unwrap_or(-1)
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+        transaction.commit().await;
+
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "create_state_snapshot");
+        Ok(Some(slot_count))
+    }
+
+    pub async fn delete_snapshot(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> Result<(), SqlxError> {
+        sqlx::query!(
+            "DELETE FROM storage_snapshot_miniblocks WHERE miniblock_number = $1",
+            miniblock_number.0 as i64
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use db_test_macro::db_test;
+
+    use zksync_types::{AccountTreeId, Address, StorageKey, StorageLog, H256};
+
+    use super::*;
+    use crate::{tests::create_miniblock_header, ConnectionPool};
+
+    async fn insert_miniblock(conn: &mut StorageProcessor<'_>, number: u32, logs: Vec<StorageLog>) {
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(number))
+            .await;
+        conn.storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(number), &[(H256::zero(), logs)])
+            .await;
+    }
+
+    #[db_test(dal_crate)]
+    async fn creating_and_reading_snapshots(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+
+        let keys: Vec<_> = (0_u8..3)
+            .map(|i| StorageKey::new(AccountTreeId::new(Address::repeat_byte(i)), H256::zero()))
+            .collect();
+        let value = |i: u8| H256::repeat_byte(i);
+        insert_miniblock(
+            &mut conn,
+            1,
+            vec![
+                StorageLog::new_write_log(keys[0], value(1)),
+                StorageLog::new_write_log(keys[1], value(2)),
+            ],
+        )
+        .await;
+        insert_miniblock(
+            &mut conn,
+            2,
+            vec![
+                StorageLog::new_write_log(keys[0], value(3)),
+                StorageLog::new_write_log(keys[0], value(4)),
+            ],
+        )
+        .await;
+        insert_miniblock(
+            &mut conn,
+            3,
+            vec![StorageLog::new_write_log(keys[2], value(5))],
+        )
+        .await;
+
+        let slot_count = conn
+            .state_snapshots_dal()
+            .create_snapshot(MiniblockNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(slot_count, Some(2));
+        let slot_count = conn
+            .state_snapshots_dal()
+            .create_snapshot(MiniblockNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(slot_count, None);
+        // The second snapshot is built incrementally from the first one.
+        let slot_count = conn
+            .state_snapshots_dal()
+            .create_snapshot(MiniblockNumber(3))
+            .await
+            .unwrap();
+        assert_eq!(slot_count, Some(3));
+
+        let snapshots = conn
+            .state_snapshots_dal()
+            .get_snapshot_miniblocks()
+            .await
+            .unwrap();
+        assert_eq!(snapshots, [MiniblockNumber(2), MiniblockNumber(3)]);
+        for (number, expected_snapshot) in [(1, None), (2, Some(2)), (5, Some(3))] {
+            let snapshot = conn
+                .state_snapshots_dal()
+                .get_latest_snapshot_at(MiniblockNumber(number))
+                .await
+                .unwrap();
+            assert_eq!(snapshot, expected_snapshot.map(MiniblockNumber));
+        }
+
+        // Values read via snapshots must match ones read directly from storage logs.
+        for number in 1..=3 {
+            for snapshot in [None, Some(MiniblockNumber(2))] {
+                if snapshot.map_or(false, |snapshot| snapshot.0 > number) {
+                    continue;
+                }
+                for key in &keys {
+                    let expected = conn
+                        .storage_web3_dal()
+                        .get_historical_value_unchecked(key, MiniblockNumber(number))
+                        .await
+                        .unwrap();
+                    let actual = conn
+                        .storage_web3_dal()
+                        .get_historical_value_from_snapshot(key, MiniblockNumber(number), snapshot)
+                        .await
+                        .unwrap();
+                    assert_eq!(actual, expected, "key {key:?}, miniblock {number}");
+                }
+            }
+        }
+
+        conn.state_snapshots_dal()
+            .delete_snapshot(MiniblockNumber(2))
+            .await
+            .unwrap();
+        let snapshots = conn
+            .state_snapshots_dal()
+            .get_snapshot_miniblocks()
+            .await
+            .unwrap();
+        assert_eq!(snapshots, [MiniblockNumber(3)]);
+    }
+}
@@ -6,7 +6,7 @@ use std::{
 };
 
 use crate::StorageProcessor;
-use zksync_contracts::{BaseSystemContracts, SystemContractCode};
+use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes, SystemContractCode};
 use zksync_types::{MiniblockNumber, StorageKey, StorageLog, StorageValue, H256, U256};
 use zksync_utils::{bytes_to_be_words, bytes_to_chunks};
 
@@ -86,6 +86,27 @@ impl StorageDal<'_, '_> {
         }
     }
 
+    /// Same as [`Self::get_base_system_contracts()`], but returns `None` if any of the bytecodes
+    /// is not present in the database (e.g., if it's introduced by a protocol upgrade and wasn't
+    /// persisted yet).
+    pub async fn try_get_base_system_contracts(
+        &mut self,
+        hashes: BaseSystemContractsHashes,
+    ) -> Option<BaseSystemContracts> {
+        let bootloader_bytecode = self.get_factory_dep(hashes.bootloader).await?;
+        let default_aa_bytecode = self.get_factory_dep(hashes.default_aa).await?;
+        Some(BaseSystemContracts {
+            bootloader: SystemContractCode {
+                code: bytes_to_be_words(bootloader_bytecode),
+                hash: hashes.bootloader,
+            },
+            default_aa: SystemContractCode {
+                code: bytes_to_be_words(default_aa_bytecode),
+                hash: hashes.default_aa,
+            },
+        })
+    }
+
     /// Returns bytecodes for factory deps with the specified `hashes`.
     pub async fn get_factory_deps(
         &mut self,
@@ -1,11 +1,12 @@
+use futures::{Stream, StreamExt};
 use sqlx::types::chrono::Utc;
 
-use std::{collections::HashMap, time::Instant};
+use std::{collections::HashMap, ops, time::Instant};
 
 use crate::StorageProcessor;
 use zksync_types::{
-    get_code_key, AccountTreeId, Address, L1BatchNumber, MiniblockNumber, StorageKey, StorageLog,
-    FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256,
+    api::StorageSlotDiff, get_code_key, AccountTreeId, Address, L1BatchNumber, MiniblockNumber,
+    StorageKey, StorageLog, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256,
 };
 
 #[derive(Debug)]
@@ -236,6 +237,94 @@ impl StorageLogsDal<'_, '_> {
         touched_slots.collect()
     }
 
+    /// Streams all storage logs for the specified L1 batch in the order they were applied. Unlike
+    /// [`Self::get_touched_slots_for_l1_batch()`], logs are not deduplicated and are not materialized
+    /// in memory, so this method is suitable for processing large batches.
+    pub fn stream_storage_logs_for_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> impl Stream<Item = (StorageKey, H256)> + '_ {
+        sqlx::query!(
+            "SELECT address, key, value \
+            FROM storage_logs \
+            WHERE miniblock_number BETWEEN \
+                (SELECT MIN(number) FROM miniblocks WHERE l1_batch_number = $1) \
+                AND (SELECT MAX(number) FROM miniblocks WHERE l1_batch_number = $1) \
+            ORDER BY miniblock_number, operation_number",
+            l1_batch_number.0 as i64
+        )
+        .fetch(self.storage.conn())
+        .map(|row| {
+            let row = row.unwrap();
+            let key = StorageKey::new(
+                AccountTreeId::new(Address::from_slice(&row.address)),
+                H256::from_slice(&row.key),
+            );
+            (key, H256::from_slice(&row.value))
+        })
+    }
+
+    /// Returns net changes of storage slots written to in the specified miniblock range together with
+    /// their hashed keys, ordered by the hashed key. Slots which values at the end of the range
+    /// are equal to their values before the range are skipped.
+    ///
+    /// Results are paginated by the hashed key: only slots with hashed keys greater than `after_hashed_key`
+    /// are returned, up to `limit` slots.
+    pub async fn get_storage_diff(
+        &mut self,
+        miniblock_range: ops::RangeInclusive<MiniblockNumber>,
+        address: Option<Address>,
+        after_hashed_key: Option<H256>,
+        limit: usize,
+    ) -> Vec<(H256, StorageSlotDiff)> {
+        let started_at = Instant::now();
+        // The last write for each slot in the range is selected first, so that previous values
+        // are only looked up for distinct slots.
+        let rows = sqlx::query!(
+            "SELECT hashed_key, address, key, value, previous_value FROM ( \
+                SELECT last_writes.*, \
+                    (SELECT value FROM storage_logs \
+                    WHERE hashed_key = last_writes.hashed_key AND miniblock_number < $1 \
+                    ORDER BY miniblock_number DESC, operation_number DESC LIMIT 1) AS previous_value \
+                FROM ( \
+                    SELECT DISTINCT ON (hashed_key) hashed_key, address, key, value \
+                    FROM storage_logs \
+                    WHERE miniblock_number BETWEEN $1 AND $2 \
+                        AND ($3::bytea IS NULL OR address = $3) \
+                        AND ($4::bytea IS NULL OR hashed_key > $4) \
+                    ORDER BY hashed_key, miniblock_number DESC, operation_number DESC \
+                ) AS last_writes \
+            ) AS diffs \
+            WHERE COALESCE(previous_value, $5) <> value \
+            ORDER BY hashed_key \
+            LIMIT $6",
+            miniblock_range.start().0 as i64,
+            miniblock_range.end().0 as i64,
+            address.as_ref().map(Address::as_bytes),
+            after_hashed_key.as_ref().map(H256::as_bytes),
+            H256::zero().as_bytes(),
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap();
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_storage_diff");
+
+        rows.into_iter()
+            .map(|row| {
+                let diff = StorageSlotDiff {
+                    address: Address::from_slice(&row.address),
+                    key: H256::from_slice(&row.key),
+                    previous_value: row
+                        .previous_value
+                        .map_or_else(H256::zero, |value| H256::from_slice(&value)),
+                    value: H256::from_slice(&row.value),
+                };
+                (H256::from_slice(&row.hashed_key), diff)
+            })
+            .collect()
+    }
+
     /// Returns (hashed) storage keys and the corresponding values that need to be applied to a storage
     /// in order to revert it to the specified L1 batch. Deduplication is taken into account.
     pub async fn get_storage_logs_for_revert(
@@ -468,11 +557,108 @@ mod tests {
             .await;
         assert_eq!(touched_slots.len(), 2);
         assert_eq!(touched_slots[&first_key], H256::repeat_byte(3));
+
+        let streamed_logs: Vec<_> = conn
+            .storage_logs_dal()
+            .stream_storage_logs_for_l1_batch(L1BatchNumber(1))
+            .collect()
+            .await;
+        assert_eq!(
+            streamed_logs,
+            [
+                (first_key, H256::repeat_byte(1)),
+                (second_key, H256::repeat_byte(2)),
+                (first_key, H256::repeat_byte(3)),
+            ]
+        );
         assert_eq!(touched_slots[&second_key], H256::repeat_byte(2));
 
         test_rollback(&mut conn, first_key, second_key).await;
     }
 
+    #[db_test(dal_crate)]
+    async fn getting_storage_diff(pool: ConnectionPool) {
+        let mut conn = pool.access_storage().await;
+
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await;
+        conn.blocks_dal().delete_l1_batches(L1BatchNumber(0)).await;
+
+        let account = AccountTreeId::new(Address::repeat_byte(1));
+        let other_account = AccountTreeId::new(Address::repeat_byte(2));
+        let keys: Vec<_> = (0..3)
+            .map(|i| StorageKey::new(account, H256::from_low_u64_be(i)))
+            .collect();
+        let other_key = StorageKey::new(other_account, H256::zero());
+        let logs = vec![
+            StorageLog::new_write_log(keys[0], H256::repeat_byte(1)),
+            StorageLog::new_write_log(keys[1], H256::repeat_byte(2)),
+            StorageLog::new_write_log(other_key, H256::repeat_byte(3)),
+        ];
+        insert_miniblock(&mut conn, 1, logs).await;
+        let logs = vec![
+            StorageLog::new_write_log(keys[0], H256::repeat_byte(0xff)),
+            // The value is restored by the end of the miniblock, so the slot is not in the diff.
+            StorageLog::new_write_log(keys[1], H256::repeat_byte(0xaa)),
+            StorageLog::new_write_log(keys[1], H256::repeat_byte(2)),
+            StorageLog::new_write_log(keys[2], H256::repeat_byte(0xbb)),
+        ];
+        insert_miniblock(&mut conn, 2, logs).await;
+        let logs = vec![
+            StorageLog::new_write_log(keys[0], H256::repeat_byte(0xee)),
+            StorageLog::new_write_log(other_key, H256::repeat_byte(0xcc)),
+        ];
+        insert_miniblock(&mut conn, 3, logs).await;
+
+        let range = MiniblockNumber(2)..=MiniblockNumber(3);
+        let diff = conn
+            .storage_logs_dal()
+            .get_storage_diff(range.clone(), None, None, 10)
+            .await;
+        let mut expected_diff = vec![
+            (keys[0], H256::repeat_byte(1), H256::repeat_byte(0xee)),
+            (keys[2], H256::zero(), H256::repeat_byte(0xbb)),
+            (other_key, H256::repeat_byte(3), H256::repeat_byte(0xcc)),
+        ];
+        expected_diff.sort_unstable_by_key(|(key, ..)| key.hashed_key());
+        let expected_diff: Vec<_> = expected_diff
+            .into_iter()
+            .map(|(key, previous_value, value)| {
+                let diff = StorageSlotDiff {
+                    address: *key.address(),
+                    key: *key.key(),
+                    previous_value,
+                    value,
+                };
+                (key.hashed_key(), diff)
+            })
+            .collect();
+        assert_eq!(diff, expected_diff);
+
+        let account_diff = conn
+            .storage_logs_dal()
+            .get_storage_diff(range.clone(), Some(*other_account.address()), None, 10)
+            .await;
+        assert_eq!(account_diff.len(), 1);
+        assert_eq!(account_diff[0].1.key, H256::zero());
+
+        let mut paginated_diff = vec![];
+        let mut cursor = None;
+        loop {
+            let page = conn
+                .storage_logs_dal()
+                .get_storage_diff(range.clone(), None, cursor, 2)
+                .await;
+            let Some((last_key, _)) = page.last() else {
+                break;
+            };
+            cursor = Some(*last_key);
+            paginated_diff.extend(page);
+        }
+        assert_eq!(paginated_diff, expected_diff);
+    }
+
     async fn test_rollback(
         conn: &mut StorageProcessor<'_>,
         key: StorageKey,
@@ -8,7 +8,9 @@ use zksync_types::{
 };
 use zksync_utils::h256_to_u256;
 
-use crate::{SqlxError, StorageProcessor};
+use crate::{
+    partitions_dal::PartitionedTable, pruning_dal::HistoryQueryError, SqlxError, StorageProcessor,
+};
 
 #[derive(Debug)]
 pub struct StorageWeb3Dal<'a, 'c> {
@@ -78,6 +80,95 @@ impl StorageWeb3Dal<'_, '_> {
         }
     }
 
+    /// Returns the value of a storage slot at the specified miniblock, checking that the state
+    /// at this miniblock is not pruned.
+    pub async fn get_historical_value(
+        &mut self,
+        key: &StorageKey,
+        block_number: MiniblockNumber,
+    ) -> Result<H256, HistoryQueryError> {
+        self.check_state_history(block_number).await?;
+        let snapshot = self
+            .storage
+            .state_snapshots_dal()
+            .get_latest_snapshot_at(block_number)
+            .await?;
+        Ok(self
+            .get_historical_value_from_snapshot(key, block_number, snapshot)
+            .await?)
+    }
+
+    /// Same as [`Self::get_historical_value_unchecked()`], but only scans storage logs written after
+    /// the specified state snapshot, falling back to the snapshot value.
+    pub async fn get_historical_value_from_snapshot(
+        &mut self,
+        key: &StorageKey,
+        block_number: MiniblockNumber,
+        snapshot: Option<MiniblockNumber>,
+    ) -> Result<H256, SqlxError> {
+        let Some(snapshot) = snapshot else {
+            return self.get_historical_value_unchecked(key, block_number).await;
+        };
+        let started_at = Instant::now();
+        // Bounding `miniblock_number` from both sides allows Postgres to skip irrelevant partitions of `storage_logs`.
+        let row = sqlx::query!(
+            "SELECT value AS \"value!\" FROM ( \
+                (SELECT value, miniblock_number, operation_number FROM storage_logs \
+                WHERE hashed_key = $1 AND miniblock_number > $3 AND miniblock_number <= $2 \
+                ORDER BY miniblock_number DESC, operation_number DESC \
+                LIMIT 1) \
+                UNION ALL \
+                (SELECT value, miniblock_number, -1 AS operation_number FROM storage_snapshots \
+                WHERE miniblock_number = $3 AND hashed_key = $1) \
+            ) AS candidates \
+            ORDER BY miniblock_number DESC, operation_number DESC \
+            LIMIT 1",
+            key.hashed_key().as_bytes(),
+            block_number.0 as i64,
+            snapshot.0 as i64
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+
+        let value = row.map_or_else(H256::zero, |row| H256::from_slice(&row.value));
+        metrics::histogram!(
+            "dal.request",
+            started_at.elapsed(),
+            "method" => "get_historical_value_from_snapshot"
+        );
+        Ok(value)
+    }
+
+    /// Checks that the state at the specified miniblock can be restored. This is not the case if
+    /// the storage logs for the miniblock were detached from `storage_logs` and there is no
+    /// state snapshot covering the gap.
+    pub async fn check_state_history(
+        &mut self,
+        block_number: MiniblockNumber,
+    ) -> Result<(), HistoryQueryError> {
+        let first_retained = self
+            .storage
+            .partitions_dal()
+            .get_first_retained_miniblock(PartitionedTable::StorageLogs)
+            .await;
+        let Some(first_retained) = first_retained else {
+            return Ok(());
+        };
+        let snapshot = self
+            .storage
+            .state_snapshots_dal()
+            .get_latest_snapshot_at(block_number)
+            .await?;
+        match snapshot {
+            Some(snapshot) if snapshot == block_number || snapshot.0 + 1 >= first_retained.0 => {
+                Ok(())
+            }
+            _ => Err(HistoryQueryError::PrunedHistory {
+                last_pruned_miniblock: first_retained - 1,
+            }),
+        }
+    }
+
     /// Gets the L1 batch number that the miniblock has now or will have in the future (provided
     /// that the node will operate correctly). Assumes that the miniblock is present in the DB;
     /// this is not checked, and if this is false, the returned value will be meaningless.
@@ -2,7 +2,7 @@ use std::time::Instant;
 
 use crate::models::storage_sync::StorageSyncBlock;
 use crate::models::storage_transaction::StorageTransaction;
-use crate::SqlxError;
+use crate::pruning_dal::HistoryQueryError;
 use crate::StorageProcessor;
 use zksync_types::api::en::SyncBlock;
 use zksync_types::MiniblockNumber;
@@ -20,8 +20,16 @@ impl SyncDal<'_, '_> {
         block_number: MiniblockNumber,
         current_operator_address: Address,
         include_transactions: bool,
-    ) -> Result<Option<SyncBlock>, SqlxError> {
+    ) -> Result<Option<SyncBlock>, HistoryQueryError> {
         let started_at = Instant::now();
+        if include_transactions {
+            self.storage
+                .pruning_dal()
+                .check_miniblock_history(block_number)
+                .await?;
+        }
+        // Miniblocks of a pending L1 batch have no batch header yet, so their fee account is taken
+        // from the miniblock itself.
         let storage_block_details: Option<StorageSyncBlock> = sqlx::query_as!(
             StorageSyncBlock,
             r#"
@@ -40,7 +48,7 @@ impl SyncDal<'_, '_> {
                     miniblocks.l2_fair_gas_price,
                     miniblocks.bootloader_code_hash,
                     miniblocks.default_aa_code_hash,
-                    l1_batches.fee_account_address as "fee_account_address?"
+                    COALESCE(l1_batches.fee_account_address, miniblocks.fee_account_address) as "fee_account_address?"
                 FROM miniblocks
                 LEFT JOIN l1_batches ON miniblocks.l1_batch_number = l1_batches.number
                 LEFT JOIN eth_txs_history as commit_tx ON (l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id AND commit_tx.confirmed_at IS NOT NULL)
@@ -55,6 +63,7 @@ impl SyncDal<'_, '_> {
 
         let res = if let Some(storage_block_details) = storage_block_details {
             let transactions = if include_transactions {
+                let codecs = self.storage.protected_data_codecs();
                 let block_transactions = sqlx::query_as!(
                     StorageTransaction,
                     r#"SELECT * FROM transactions WHERE miniblock_number = $1 ORDER BY index_in_block"#,
@@ -63,7 +72,11 @@ impl SyncDal<'_, '_> {
                 .fetch_all(self.storage.conn())
                 .await?
                 .into_iter()
-                .map(Transaction::from)
+                .map(|tx| {
+                    let mut tx = Transaction::from(tx);
+                    codecs.decode_transaction(&mut tx);
+                    tx
+                })
                 .collect();
                 Some(block_transactions)
             } else {
@@ -0,0 +1,133 @@
+use std::time::{Duration, Instant};
+
+use zksync_types::{tee::TeeType, L1BatchNumber, H256};
+
+use crate::{time_utils::pg_interval_from_duration, StorageProcessor};
+
+#[derive(Debug)]
+pub struct TeeProofGenerationDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl TeeProofGenerationDal<'_, '_> {
+    /// Picks the next L1 batch to be proven by a TEE prover of the specified type. Batches
+    /// picked by a prover more than `processing_timeout` ago without a submitted proof are re-picked first;
+    /// otherwise, the oldest batch with saved witness inputs that has no TEE proof job yet is picked.
+    pub async fn get_next_batch_to_be_proven(
+        &mut self,
+        tee_type: TeeType,
+        processing_timeout: Duration,
+    ) -> Option<L1BatchNumber> {
+        let started_at = Instant::now();
+        let processing_timeout = pg_interval_from_duration(processing_timeout);
+        let mut transaction = self.storage.start_transaction().await;
+
+        let repicked = sqlx::query!(
+            "UPDATE tee_proof_generation_details \
+            SET prover_taken_at = now(), updated_at = now() \
+            WHERE tee_type = $1 AND l1_batch_number = ( \
+                SELECT l1_batch_number FROM tee_proof_generation_details \
+                WHERE tee_type = $1 AND status = 'picked_by_prover' \
+                    AND prover_taken_at < now() - $2::interval \
+                ORDER BY l1_batch_number \
+                LIMIT 1 \
+                FOR UPDATE SKIP LOCKED \
+            ) \
+            RETURNING l1_batch_number",
+            tee_type.as_str(),
+            &processing_timeout
+        )
+        .fetch_optional(transaction.conn())
+        .await
+        .unwrap()
+        .map(|row| row.l1_batch_number);
+
+        let l1_batch_number = if repicked.is_some() {
+            repicked
+        } else {
+            sqlx::query!(
+                "INSERT INTO tee_proof_generation_details \
+                (l1_batch_number, tee_type, status, prover_taken_at, created_at, updated_at) \
+                SELECT witness_inputs.l1_batch_number, $1, 'picked_by_prover', now(), now(), now() \
+                FROM witness_inputs \
+                WHERE witness_inputs.l1_batch_number > 0 \
+                    AND witness_inputs.merkel_tree_paths_blob_url IS NOT NULL \
+                    AND NOT EXISTS ( \
+                        SELECT 1 FROM tee_proof_generation_details \
+                        WHERE tee_proof_generation_details.l1_batch_number = witness_inputs.l1_batch_number \
+                            AND tee_proof_generation_details.tee_type = $1 \
+                    ) \
+                ORDER BY witness_inputs.l1_batch_number \
+                LIMIT 1 \
+                ON CONFLICT DO NOTHING \
+                RETURNING l1_batch_number",
+                tee_type.as_str()
+            )
+            .fetch_optional(transaction.conn())
+            .await
+            .unwrap()
+            .map(|row| row.l1_batch_number)
+        };
+        transaction.commit().await;
+
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_next_tee_batch_to_be_proven");
+        l1_batch_number.map(|number| L1BatchNumber(number as u32))
+    }
+
+    /// Saves a verified proof for a batch picked by a TEE prover. Returns `false` if the batch
+    /// is not picked by a prover of the specified type (e.g., the proof is already submitted).
+    pub async fn save_proof(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        tee_type: TeeType,
+        pubkey: &[u8],
+        signature: &[u8],
+        root_hash: H256,
+    ) -> bool {
+        let started_at = Instant::now();
+        let result = sqlx::query!(
+            "UPDATE tee_proof_generation_details \
+            SET status = 'generated', pubkey = $3, signature = $4, root_hash = $5, updated_at = now() \
+            WHERE l1_batch_number = $1 AND tee_type = $2 AND status = 'picked_by_prover'",
+            l1_batch_number.0 as i64,
+            tee_type.as_str(),
+            pubkey,
+            signature,
+            root_hash.as_bytes()
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "save_tee_proof");
+        result.rows_affected() == 1
+    }
+
+    /// Saves a verified attestation for the enclave public key.
+    pub async fn save_attestation(&mut self, pubkey: &[u8], attestation: &[u8], mrenclave: H256) {
+        sqlx::query!(
+            "INSERT INTO tee_attestations (pubkey, attestation, mrenclave, created_at, updated_at) \
+            VALUES ($1, $2, $3, now(), now()) \
+            ON CONFLICT (pubkey) DO UPDATE \
+            SET attestation = $2, mrenclave = $3, updated_at = now()",
+            pubkey,
+            attestation,
+            mrenclave.as_bytes()
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Returns the enclave measurement from the attestation registered for `pubkey`, if any.
+    pub async fn get_attested_mrenclave(&mut self, pubkey: &[u8]) -> Option<H256> {
+        sqlx::query!(
+            "SELECT mrenclave FROM tee_attestations WHERE pubkey = $1",
+            pubkey
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()
+        .map(|row| H256::from_slice(&row.mrenclave))
+    }
+}
@@ -4,6 +4,7 @@ use std::time::Duration;
 use db_test_macro::db_test;
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_types::{
+    api,
     block::{L1BatchHeader, MiniblockHeader},
     fee::{Fee, TransactionExecutionMetrics},
     helpers::unix_timestamp_ms,
@@ -11,8 +12,9 @@ use zksync_types::{
     l2::L2Tx,
     proofs::AggregationRound,
     tx::{tx_execution_info::TxExecutionStatus, ExecutionMetrics, TransactionExecutionResult},
-    Address, Execute, L1BatchNumber, L1BlockNumber, L1TxCommonData, L2ChainId, MiniblockNumber,
-    PriorityOpId, H160, H256, MAX_GAS_PER_PUBDATA_BYTE, U256,
+    Address, Execute, ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber, L1TxCommonData,
+    L2ChainId, MiniblockNumber, PriorityOpId, Transaction, H160, H256, MAX_GAS_PER_PUBDATA_BYTE,
+    U256,
 };
 use zksync_utils::miniblock_hash;
 
@@ -113,6 +115,7 @@ pub(crate) fn mock_execution_result(transaction: L2Tx) -> TransactionExecutionRe
         compressed_bytecodes: vec![],
         call_traces: vec![],
         revert_reason: None,
+        result_truncated: false,
     }
 }
 
@@ -144,6 +147,7 @@ async fn workflow_with_submit_tx_diff_hashes(connection_pool: ConnectionPool) {
 
     let nonce = tx.common_data.nonce;
     let initiator_address = tx.common_data.initiator_address;
+    let replaced_hash = tx.hash();
 
     let result = transactions_dal
         .insert_transaction_l2(tx, mock_tx_execution_metrics())
@@ -154,11 +158,150 @@ async fn workflow_with_submit_tx_diff_hashes(connection_pool: ConnectionPool) {
     let mut tx = mock_l2_transaction();
     tx.common_data.nonce = nonce;
     tx.common_data.initiator_address = initiator_address;
+    let replacement_hash = tx.hash();
     let result = transactions_dal
         .insert_transaction_l2(tx, mock_tx_execution_metrics())
         .await;
 
     assert_eq!(result, L2TxSubmissionResult::Replaced);
+
+    let history = transactions_dal
+        .storage
+        .transaction_history_dal()
+        .get_transaction_history(replaced_hash)
+        .await
+        .unwrap();
+    assert_eq!(history.initiator_address, initiator_address);
+    assert_eq!(history.nonce, U256::from(nonce.0));
+    assert_eq!(history.current_tx_hash, replacement_hash);
+    assert_eq!(history.replacements.len(), 1);
+    assert_eq!(history.replacements[0].replaced_tx_hash, replaced_hash);
+}
+
+#[db_test(dal_crate)]
+async fn bulk_inserting_l2_txs(connection_pool: ConnectionPool) {
+    let mut storage = connection_pool.access_test_storage().await;
+    let txs = vec![
+        (mock_l2_transaction(), mock_tx_execution_metrics()),
+        (mock_l2_transaction(), mock_tx_execution_metrics()),
+    ];
+    let nonces = storage
+        .transactions_dal()
+        .insert_transactions_l2_bulk(&txs)
+        .await
+        .unwrap();
+    // No nonces are stored for the mock accounts.
+    assert!(nonces.is_empty());
+
+    // Inserted transactions must not be loaded by the mempool fetcher.
+    let (synced_txs, _) = storage
+        .transactions_dal()
+        .sync_mempool(vec![], vec![], 0, 0, 1000)
+        .await;
+    assert!(synced_txs.is_empty());
+
+    let mut transaction = storage.start_transaction().await;
+    let err = transaction
+        .transactions_dal()
+        .insert_transactions_l2_bulk(&[
+            (mock_l2_transaction(), mock_tx_execution_metrics()),
+            txs[0].clone(),
+        ])
+        .await
+        .unwrap_err();
+    assert_eq!(err, (txs[0].0.hash(), L2TxSubmissionResult::Replaced));
+}
+
+#[db_test(dal_crate)]
+async fn expired_txs_are_not_loaded_into_mempool(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut miniblock_header = create_miniblock_header(1);
+    miniblock_header.timestamp = 1_000;
+    BlocksDal { storage }
+        .insert_miniblock(&miniblock_header)
+        .await;
+
+    let mut transactions_dal = TransactionsDal { storage };
+    // Deadlines are compared with the last miniblock timestamp rather than the wall-clock time.
+    let mut expired_tx = mock_l2_transaction();
+    expired_tx.common_data.valid_until = Some(1_000);
+    let mut valid_tx = mock_l2_transaction();
+    valid_tx.common_data.valid_until = Some(1_001);
+    for tx in [&expired_tx, &valid_tx] {
+        let result = transactions_dal
+            .insert_transaction_l2(tx.clone(), mock_tx_execution_metrics())
+            .await;
+        assert_eq!(result, L2TxSubmissionResult::Added);
+    }
+
+    let delta = transactions_dal.get_mempool_delta(None, 0, 0, 1_000).await;
+    assert_eq!(delta.transactions.len(), 1);
+    assert_eq!(delta.transactions[0].hash(), valid_tx.hash());
+    let ExecuteTransactionCommon::L2(data) = &delta.transactions[0].common_data else {
+        panic!("unexpected transaction: {:?}", delta.transactions[0]);
+    };
+    assert_eq!(data.valid_until, Some(1_001));
+
+    let expired_count = transactions_dal.mark_expired_txs(&[]).await;
+    assert_eq!(expired_count, 1);
+    // Transactions reported by the mempool are marked even if their deadline hasn't passed according to Postgres.
+    let expired_count = transactions_dal.mark_expired_txs(&[valid_tx.hash()]).await;
+    assert_eq!(expired_count, 1);
+    let delta = transactions_dal.get_mempool_delta(None, 0, 0, 1_000).await;
+    assert!(delta.transactions.is_empty());
+}
+
+#[db_test(dal_crate)]
+async fn stashing_and_purging_mempool_accounts(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let account = Address::repeat_byte(1);
+    let mut account_txs = vec![];
+    for nonce in 0..2 {
+        let mut tx = mock_l2_transaction();
+        tx.common_data.nonce = zksync_types::Nonce(nonce);
+        tx.common_data.initiator_address = account;
+        account_txs.push((tx, mock_tx_execution_metrics()));
+    }
+    // Bulk-ingested transactions are marked as loaded into the mempool.
+    storage
+        .transactions_dal()
+        .insert_transactions_l2_bulk(&account_txs)
+        .await
+        .unwrap();
+    let other_tx = mock_l2_transaction();
+    storage
+        .transactions_dal()
+        .insert_transaction_l2(other_tx.clone(), mock_tx_execution_metrics())
+        .await;
+
+    let delta = storage
+        .transactions_dal()
+        .get_mempool_delta(None, 0, 0, 1_000)
+        .await;
+    let hashes: Vec<_> = delta.transactions.iter().map(Transaction::hash).collect();
+    assert_eq!(hashes, [other_tx.hash()]);
+
+    storage
+        .transactions_dal()
+        .unstash_mempool_accounts(&[account], &[])
+        .await;
+    let delta = storage
+        .transactions_dal()
+        .get_mempool_delta(None, 0, 0, 1_000)
+        .await;
+    assert_eq!(delta.transactions.len(), 3);
+
+    // All pending transactions of a purged account are removed, regardless of whether they're loaded.
+    storage
+        .transactions_dal()
+        .remove_pending_account_txs(&[account])
+        .await;
+    let delta = storage
+        .transactions_dal()
+        .get_mempool_delta(None, 0, 0, 1_000)
+        .await;
+    let hashes: Vec<_> = delta.transactions.iter().map(Transaction::hash).collect();
+    assert_eq!(hashes, [other_tx.hash()]);
 }
 
 #[db_test(dal_crate)]
@@ -245,6 +388,38 @@ async fn remove_stuck_txs(connection_pool: ConnectionPool) {
         .unwrap();
 }
 
+#[db_test(dal_crate)]
+async fn unsealed_miniblocks_are_not_returned_by_web3_api(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    for number in 0..2 {
+        storage
+            .blocks_dal()
+            .insert_miniblock(&create_miniblock_header(number))
+            .await;
+    }
+    storage
+        .blocks_dal()
+        .set_miniblock_is_sealed(MiniblockNumber(1), false)
+        .await;
+
+    let mut blocks_web3_dal = storage.blocks_web3_dal();
+    let latest = api::BlockId::Number(api::BlockNumber::Latest);
+    let resolved = blocks_web3_dal.resolve_block_id(latest).await.unwrap();
+    assert_eq!(resolved, Some(MiniblockNumber(0)));
+    let unsealed_ids = [
+        api::BlockId::Number(api::BlockNumber::Number(1.into())),
+        api::BlockId::Hash(miniblock_hash(MiniblockNumber(1))),
+    ];
+    for block_id in unsealed_ids {
+        let resolved = blocks_web3_dal.resolve_block_id(block_id).await.unwrap();
+        assert_eq!(resolved, None);
+        let tx_count = blocks_web3_dal.get_block_tx_count(block_id).await.unwrap();
+        assert_eq!(tx_count, None);
+    }
+    let tx_count = blocks_web3_dal.get_block_tx_count(latest).await.unwrap();
+    assert_eq!(tx_count, Some(0.into()));
+}
+
 fn create_circuits() -> Vec<(&'static str, String)> {
     vec![
         ("Main VM", "1_0_Main VM_BasicCircuits.bin".to_owned()),
@@ -411,6 +586,53 @@ async fn test_move_leaf_aggregation_jobs_from_waiting_to_queued(connection_pool:
     assert_eq!(l1_batch_number, job.unwrap().block_number);
 }
 
+#[db_test(dal_crate)]
+async fn test_move_basic_circuit_shards_to_proven(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let l1_batch_number = L1BatchNumber(1);
+    let header = L1BatchHeader::new(l1_batch_number, 0, Default::default(), Default::default());
+    storage
+        .blocks_dal()
+        .insert_l1_batch(&header, Default::default())
+        .await;
+    let mut circuits = create_circuits();
+    circuits.push(("Main VM", "1_4_Main VM_BasicCircuits.bin".to_owned()));
+    let circuit_types: Vec<_> = circuits
+        .iter()
+        .map(|(circuit_type, _)| *circuit_type)
+        .collect();
+
+    let mut prover_dal = ProverDal { storage };
+    prover_dal
+        .insert_prover_jobs(l1_batch_number, circuits, AggregationRound::BasicCircuits)
+        .await;
+    let prover_jobs_params = get_default_prover_jobs_params(l1_batch_number);
+    let jobs = prover_dal.get_jobs(prover_jobs_params).await.unwrap();
+    let proof = get_sample_proof();
+    // Only prove the "Main VM" shard.
+    for job in jobs.iter().filter(|job| job.circuit_type == "Main VM") {
+        prover_dal
+            .save_proof(job.id, Duration::from_secs(0), proof.clone(), "unit-test")
+            .await;
+    }
+
+    let mut witness_generator_dal = WitnessGeneratorDal { storage };
+    witness_generator_dal
+        .create_basic_circuit_shards(l1_batch_number, &circuit_types)
+        .await;
+    let proven_shards = witness_generator_dal
+        .move_basic_circuit_shards_to_proven()
+        .await;
+    assert_eq!(proven_shards, [(1, "Main VM".to_owned())]);
+    let unproven_shards = witness_generator_dal
+        .get_unproven_basic_circuit_shards(l1_batch_number)
+        .await;
+    assert_eq!(
+        unproven_shards,
+        ["Code decommitter", "Log demuxer", "SHA256"]
+    );
+}
+
 #[db_test(dal_crate)]
 async fn test_move_node_aggregation_jobs_from_waiting_to_queued(connection_pool: ConnectionPool) {
     let storage = &mut connection_pool.access_test_storage().await;
@@ -4,10 +4,10 @@ use num::{rational::Ratio, BigUint};
 use sqlx::types::chrono::Utc;
 use zksync_types::{
     tokens::{TokenInfo, TokenMarketVolume, TokenMetadata, TokenPrice},
-    Address, MiniblockNumber, ACCOUNT_CODE_STORAGE_ADDRESS,
-    FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH,
+    Address, L1BlockNumber, MiniblockNumber, ACCOUNT_CODE_STORAGE_ADDRESS,
+    FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256, U256,
 };
-use zksync_utils::ratio_to_big_decimal;
+use zksync_utils::{ratio_to_big_decimal, u256_to_h256};
 
 // Precision of the USD price per token
 pub(crate) const STORED_USD_PRICE_PRECISION: usize = 6;
@@ -193,6 +193,42 @@ impl TokensDal<'_, '_> {
         }
     }
 
+    /// Registers a token bridged via the ERC-20 bridge. Only the first deposit of a token is recorded;
+    /// subsequent calls for the same L1 token are no-ops.
+    pub async fn add_bridged_token(
+        &mut self,
+        l1_address: Address,
+        metadata: TokenMetadata,
+        first_seen_l1_block: L1BlockNumber,
+        first_deposit_tx_hash: H256,
+    ) {
+        sqlx::query!(
+            "INSERT INTO bridged_tokens \
+            (l1_address, name, symbol, decimals, first_seen_l1_block, first_deposit_tx_hash, created_at, updated_at) \
+            VALUES ($1, $2, $3, $4, $5, $6, now(), now()) \
+            ON CONFLICT (l1_address) DO NOTHING",
+            l1_address.as_bytes(),
+            metadata.name,
+            metadata.symbol,
+            metadata.decimals as i32,
+            first_seen_l1_block.0 as i64,
+            first_deposit_tx_hash.as_bytes()
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    pub async fn get_bridged_token_l1_addresses(&mut self) -> Vec<Address> {
+        sqlx::query!("SELECT l1_address FROM bridged_tokens")
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| Address::from_slice(&row.l1_address))
+            .collect()
+    }
+
     pub async fn rollback_tokens(&mut self, block_number: MiniblockNumber) {
         {
             sqlx::query!(
@@ -219,4 +255,46 @@ impl TokensDal<'_, '_> {
             .unwrap();
         }
     }
+
+    /// Inserts token balances changed in the specified miniblock. Balances are specified as
+    /// `(account, token, balance)` tuples; ETH balances must use the zero token address.
+    pub async fn insert_token_balances(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+        balances: &[(Address, Address, U256)],
+    ) {
+        let mut accounts = Vec::with_capacity(balances.len());
+        let mut tokens = Vec::with_capacity(balances.len());
+        let mut values = Vec::with_capacity(balances.len());
+        for (account, token, balance) in balances {
+            accounts.push(account.as_bytes().to_vec());
+            tokens.push(token.as_bytes().to_vec());
+            values.push(u256_to_h256(*balance).as_bytes().to_vec());
+        }
+
+        sqlx::query!(
+            "INSERT INTO token_balances (account, token, miniblock_number, balance) \
+            SELECT u.account, u.token, $4, u.balance \
+            FROM UNNEST($1::BYTEA[], $2::BYTEA[], $3::BYTEA[]) AS u(account, token, balance) \
+            ON CONFLICT (account, token, miniblock_number) DO UPDATE SET balance = excluded.balance",
+            &accounts,
+            &tokens,
+            &values,
+            miniblock_number.0 as i64
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Removes token balances changed in miniblocks with numbers strictly greater than `block_number`.
+    pub async fn rollback_token_balances(&mut self, block_number: MiniblockNumber) {
+        sqlx::query!(
+            "DELETE FROM token_balances WHERE miniblock_number > $1",
+            block_number.0 as i64
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
 }
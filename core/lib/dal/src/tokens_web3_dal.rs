@@ -4,10 +4,11 @@ use crate::StorageProcessor;
 use num::{rational::Ratio, BigUint};
 use sqlx::postgres::types::PgInterval;
 use zksync_types::{
+    api::TokenFilter,
     tokens::{TokenInfo, TokenMetadata, TokenPrice},
-    Address,
+    Address, L1BatchNumber, H256, U256,
 };
-use zksync_utils::ratio_to_big_decimal;
+use zksync_utils::{h256_to_u256, ratio_to_big_decimal};
 
 // Precision of the USD price per token
 pub(crate) const STORED_USD_PRICE_PRECISION: usize = 6;
@@ -43,6 +44,108 @@ impl TokensWeb3Dal<'_, '_> {
         }
     }
 
+    /// Returns a page of confirmed tokens, i.e. well-known tokens and tokens bridged via the ERC-20 bridge
+    /// that are deployed on L2. For bridged tokens, the number of the L1 batch that included the first
+    /// deposit is returned as well (if the deposit is already included in a batch).
+    pub async fn get_confirmed_tokens(
+        &mut self,
+        offset: u32,
+        limit: u8,
+        filter: &TokenFilter,
+    ) -> Result<Vec<(TokenInfo, Option<L1BatchNumber>)>, SqlxError> {
+        let l1_addresses: Option<Vec<Vec<u8>>> = filter.l1_addresses.as_ref().map(|addresses| {
+            addresses
+                .iter()
+                .map(|addr| addr.as_bytes().to_vec())
+                .collect()
+        });
+        let rows = sqlx::query!(
+            "SELECT tokens.l1_address, tokens.l2_address, \
+                COALESCE(bridged_tokens.name, tokens.name) AS \"name!\", \
+                COALESCE(bridged_tokens.symbol, tokens.symbol) AS \"symbol!\", \
+                COALESCE(bridged_tokens.decimals, tokens.decimals) AS \"decimals!\", \
+                transactions.l1_batch_number AS first_seen_l1_batch \
+            FROM tokens \
+            LEFT JOIN bridged_tokens ON bridged_tokens.l1_address = tokens.l1_address \
+            LEFT JOIN transactions ON transactions.hash = bridged_tokens.first_deposit_tx_hash \
+            WHERE (tokens.well_known = true OR bridged_tokens.l1_address IS NOT NULL) \
+                AND ($3::VARCHAR IS NULL OR LOWER(COALESCE(bridged_tokens.symbol, tokens.symbol)) = LOWER($3)) \
+                AND ($4::BYTEA[] IS NULL OR tokens.l1_address = ANY($4)) \
+            ORDER BY \"symbol!\", tokens.l1_address \
+            OFFSET $1 LIMIT $2",
+            offset as i64,
+            limit as i64,
+            filter.symbol.as_deref(),
+            l1_addresses
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let token_info = TokenInfo {
+                    l1_address: Address::from_slice(&row.l1_address),
+                    l2_address: Address::from_slice(&row.l2_address),
+                    metadata: TokenMetadata {
+                        name: row.name,
+                        symbol: row.symbol,
+                        decimals: row.decimals as u8,
+                    },
+                };
+                let first_seen_l1_batch = row
+                    .first_seen_l1_batch
+                    .map(|number| L1BatchNumber(number as u32));
+                (token_info, first_seen_l1_batch)
+            })
+            .collect())
+    }
+
+    /// Returns a page of non-zero balances of well-known tokens held by `account`, ordered by the token address.
+    /// If `tokens` are specified, only balances of these tokens are returned. Balances are read from
+    /// the `token_balances` table maintained when sealing miniblocks; ETH is denoted by the zero address.
+    pub async fn get_indexed_token_balances(
+        &mut self,
+        account: Address,
+        tokens: Option<&[Address]>,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<(Address, U256)>, SqlxError> {
+        let tokens: Option<Vec<Vec<u8>>> = tokens.map(|tokens| {
+            tokens
+                .iter()
+                .map(|token| token.as_bytes().to_vec())
+                .collect()
+        });
+        let rows = sqlx::query!(
+            "SELECT latest.token, latest.balance FROM ( \
+                SELECT DISTINCT ON (token) token, balance FROM token_balances \
+                WHERE account = $1 AND ($2::BYTEA[] IS NULL OR token = ANY($2)) \
+                ORDER BY token, miniblock_number DESC \
+            ) AS latest \
+            INNER JOIN tokens ON tokens.l2_address = latest.token \
+            WHERE tokens.well_known = true AND latest.balance != $3 \
+            ORDER BY latest.token \
+            OFFSET $4 LIMIT $5",
+            account.as_bytes(),
+            tokens,
+            H256::zero().as_bytes(),
+            offset as i64,
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let token = Address::from_slice(&row.token);
+                let balance = H256::from_slice(&row.balance);
+                (token, h256_to_u256(balance))
+            })
+            .collect())
+    }
+
     pub async fn is_token_actively_trading(
         &mut self,
         l2_token: &Address,
@@ -122,3 +225,96 @@ impl TokensWeb3Dal<'_, '_> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use db_test_macro::db_test;
+    use zksync_types::{tokens::ETHEREUM_ADDRESS, MiniblockNumber};
+
+    use super::*;
+    use crate::ConnectionPool;
+
+    fn create_token(l1_address: Address, l2_address: Address) -> TokenInfo {
+        TokenInfo {
+            l1_address,
+            l2_address,
+            metadata: TokenMetadata {
+                name: "Test".to_owned(),
+                symbol: "TST".to_owned(),
+                decimals: 18,
+            },
+        }
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_indexed_token_balances(pool: ConnectionPool) {
+        let mut conn = pool.access_storage().await;
+        let token = Address::repeat_byte(0x11);
+        let unknown_token = Address::repeat_byte(0x22);
+        conn.tokens_dal()
+            .add_tokens(vec![
+                create_token(ETHEREUM_ADDRESS, ETHEREUM_ADDRESS),
+                create_token(Address::repeat_byte(1), token),
+                create_token(Address::repeat_byte(2), unknown_token),
+            ])
+            .await;
+        for l1_address in [ETHEREUM_ADDRESS, Address::repeat_byte(1)] {
+            let metadata = create_token(l1_address, l1_address).metadata;
+            conn.tokens_dal()
+                .update_well_known_l1_token(&l1_address, metadata)
+                .await;
+        }
+
+        let account = Address::repeat_byte(0xaa);
+        let balances = [
+            (account, ETHEREUM_ADDRESS, U256::from(100)),
+            (account, token, U256::from(5)),
+            (account, unknown_token, U256::from(7)),
+            (Address::repeat_byte(0xbb), token, U256::from(1)),
+        ];
+        conn.tokens_dal()
+            .insert_token_balances(MiniblockNumber(1), &balances)
+            .await;
+        conn.tokens_dal()
+            .insert_token_balances(
+                MiniblockNumber(2),
+                &[(account, ETHEREUM_ADDRESS, U256::from(50))],
+            )
+            .await;
+
+        let balances = conn
+            .tokens_web3_dal()
+            .get_indexed_token_balances(account, None, 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(
+            balances,
+            [(ETHEREUM_ADDRESS, U256::from(50)), (token, U256::from(5))]
+        );
+        let balances = conn
+            .tokens_web3_dal()
+            .get_indexed_token_balances(account, None, 1, 1)
+            .await
+            .unwrap();
+        assert_eq!(balances, [(token, U256::from(5))]);
+        let balances = conn
+            .tokens_web3_dal()
+            .get_indexed_token_balances(account, Some(&[token, unknown_token][..]), 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(balances, [(token, U256::from(5))]);
+
+        conn.tokens_dal()
+            .rollback_token_balances(MiniblockNumber(1))
+            .await;
+        conn.tokens_dal()
+            .insert_token_balances(MiniblockNumber(2), &[(account, token, U256::zero())])
+            .await;
+        let balances = conn
+            .tokens_web3_dal()
+            .get_indexed_token_balances(account, None, 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(balances, [(ETHEREUM_ADDRESS, U256::from(100))]);
+    }
+}
@@ -0,0 +1,82 @@
+//! Replacement history of L2 transactions.
+//!
+//! An L2 transaction can be replaced by another transaction with the same initiator and nonce while it's
+//! not included into a miniblock. The replaced transaction is overwritten in the `transactions` table,
+//! so its hash would disappear without a trace; this DAL records each replacement, allowing to explain
+//! to users what happened to a transaction they've submitted.
+
+use sqlx::types::chrono::{DateTime, Utc};
+
+use zksync_types::{
+    api::{TransactionHistory, TransactionReplacement},
+    Address, Nonce, H256, U256,
+};
+
+use crate::StorageProcessor;
+
+#[derive(Debug)]
+pub struct TransactionHistoryDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl TransactionHistoryDal<'_, '_> {
+    pub async fn record_replacement(
+        &mut self,
+        initiator_address: Address,
+        nonce: Nonce,
+        replaced_tx_hash: H256,
+        replacement_tx_hash: H256,
+    ) {
+        sqlx::query!(
+            "INSERT INTO transaction_replacements \
+            (initiator_address, nonce, replaced_tx_hash, replacement_tx_hash, replaced_at) \
+            VALUES ($1, $2, $3, $4, now())",
+            initiator_address.as_bytes(),
+            nonce.0 as i64,
+            replaced_tx_hash.as_bytes(),
+            replacement_tx_hash.as_bytes()
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Returns all replacements of transactions with the same initiator and nonce as the specified transaction,
+    /// in the chronological order. Returns `None` if the transaction never replaced another transaction
+    /// and was never replaced itself.
+    pub async fn get_transaction_history(&mut self, tx_hash: H256) -> Option<TransactionHistory> {
+        let rows = sqlx::query!(
+            "SELECT initiator_address, nonce, replaced_tx_hash, replacement_tx_hash, replaced_at \
+            FROM transaction_replacements \
+            WHERE (initiator_address, nonce) IN ( \
+                SELECT initiator_address, nonce FROM transaction_replacements \
+                WHERE replaced_tx_hash = $1 OR replacement_tx_hash = $1 \
+                LIMIT 1 \
+            ) \
+            ORDER BY id",
+            tx_hash.as_bytes()
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap();
+
+        let first_row = rows.first()?;
+        let initiator_address = Address::from_slice(&first_row.initiator_address);
+        let nonce = U256::from(first_row.nonce as u64);
+        let replacements: Vec<_> = rows
+            .iter()
+            .map(|row| TransactionReplacement {
+                replaced_tx_hash: H256::from_slice(&row.replaced_tx_hash),
+                replacement_tx_hash: H256::from_slice(&row.replacement_tx_hash),
+                replaced_at: DateTime::<Utc>::from_utc(row.replaced_at, Utc),
+            })
+            .collect();
+        let current_tx_hash = replacements.last()?.replacement_tx_hash;
+        Some(TransactionHistory {
+            initiator_address,
+            nonce,
+            current_tx_hash,
+            replacements,
+        })
+    }
+}
@@ -9,20 +9,34 @@ use itertools::Itertools;
 use sqlx::error;
 use sqlx::types::chrono::NaiveDateTime;
 
-use zksync_types::tx::tx_execution_info::TxExecutionStatus;
+use zksync_types::tx::tx_execution_info::{PersistedTxExecution, TxExecutionStatus};
 use zksync_types::vm_trace::Call;
 use zksync_types::{get_nonce_key, U256};
 use zksync_types::{
-    l1::L1Tx, l2::L2Tx, tx::TransactionExecutionResult, vm_trace::VmExecutionTrace, Address,
-    ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber, MiniblockNumber, Nonce, PriorityOpId,
-    Transaction, H256,
+    l1::L1Tx,
+    l2::L2Tx,
+    tx::{TransactionExecutionResult, TxRejection},
+    vm_trace::VmExecutionTrace,
+    Address, ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber, MiniblockNumber, Nonce,
+    PriorityOpId, Transaction, H256,
 };
 use zksync_utils::{h256_to_u32, u256_to_big_decimal};
 
+use crate::data_codec::{DataCodecs, ProtectedColumn};
 use crate::models::storage_transaction::{CallTrace, StorageTransaction};
 use crate::time_utils::pg_interval_from_duration;
 use crate::StorageProcessor;
 
+/// Result of [`TransactionsDal::get_mempool_delta()`].
+#[derive(Debug)]
+pub struct MempoolDelta {
+    pub transactions: Vec<Transaction>,
+    /// Committed nonces of the transaction initiators.
+    pub nonces: HashMap<Address, Nonce>,
+    /// Position of the last returned transaction.
+    pub last_position: Option<(NaiveDateTime, H256)>,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum L2TxSubmissionResult {
     Added,
@@ -50,8 +64,7 @@ impl TransactionsDal<'_, '_> {
         {
             let contract_address = tx.execute.contract_address.as_bytes().to_vec();
             let tx_hash = tx.hash().0.to_vec();
-            let json_data = serde_json::to_value(&tx.execute)
-                .unwrap_or_else(|_| panic!("cannot serialize tx {:?} to json", tx.hash()));
+            let json_data = DataCodecs::global().encode_execute(&tx.execute);
             let gas_limit = u256_to_big_decimal(tx.common_data.gas_limit);
             let max_fee_per_gas = u256_to_big_decimal(tx.common_data.max_fee_per_gas);
             let full_fee = u256_to_big_decimal(tx.common_data.full_fee);
@@ -132,6 +145,7 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    #[tracing::instrument(name = "insert_transaction_l2", skip_all, fields(tx_hash = ?tx.hash()))]
     pub async fn insert_transaction_l2(
         &mut self,
         tx: L2Tx,
@@ -140,8 +154,8 @@ impl TransactionsDal<'_, '_> {
         {
             let contract_address = tx.execute.contract_address.as_bytes().to_vec();
             let tx_hash = tx.hash().0.to_vec();
-            let json_data = serde_json::to_value(&tx.execute)
-                .unwrap_or_else(|_| panic!("cannot serialize tx {:?} to json", tx.hash()));
+            let codecs = DataCodecs::global();
+            let json_data = codecs.encode_execute(&tx.execute);
             let gas_limit = u256_to_big_decimal(tx.common_data.fee.gas_limit);
             let max_fee_per_gas = u256_to_big_decimal(tx.common_data.fee.max_fee_per_gas);
             let max_priority_fee_per_gas =
@@ -152,27 +166,26 @@ impl TransactionsDal<'_, '_> {
             let initiator = tx.initiator_account().0.to_vec();
             let signature = tx.common_data.signature.clone();
             let nonce = tx.common_data.nonce.0 as i64;
-            let input_data = tx
-                .common_data
-                .input
-                .clone()
-                .expect("Data is mandatory")
-                .data;
+            let input_data = tx.common_data.input_data().expect("Data is mandatory");
+            let input_data = codecs
+                .encode(ProtectedColumn::TransactionCalldata, input_data)
+                .into_owned();
             let value = u256_to_big_decimal(tx.execute.value);
             let paymaster = tx.common_data.paymaster_params.paymaster.0.to_vec();
             let paymaster_input = tx.common_data.paymaster_params.paymaster_input.clone();
             let secs = (tx.received_timestamp_ms / 1000) as i64;
             let nanosecs = ((tx.received_timestamp_ms % 1000) * 1_000_000) as u32;
             let received_at = NaiveDateTime::from_timestamp_opt(secs, nanosecs).unwrap();
+            let valid_until = tx.common_data.valid_until.map(|timestamp| timestamp as i64);
             // Besides just adding or updating(on conflict) the record, we want to extract some info
             // from the query below, to indicate what actually happened:
             // 1) transaction is added
             // 2) transaction is replaced
             // 3) WHERE clause conditions for DO UPDATE block were not met, so the transaction can't be replaced
             // the subquery in RETURNING clause looks into pre-UPDATE state of the table. So if the subquery will return NULL
-            // transaction is fresh and was added to db(the second condition of RETURNING clause checks it).
+            // transaction is fresh and was added to db.
             // Otherwise, if the subquery won't return NULL it means that there is already tx with such nonce and initiator_address in DB
-            // and we can replace it WHERE clause conditions are met.
+            // and we can replace it WHERE clause conditions are met; the subquery returns the hash of the replaced tx.
             // It is worth mentioning that if WHERE clause conditions are not met, None will be returned.
             let query_result = sqlx::query!(
                 r#"
@@ -196,6 +209,7 @@ impl TransactionsDal<'_, '_> {
                     paymaster_input,
                     execution_info,
                     received_at,
+                    valid_until,
                     created_at,
                     updated_at
                 )
@@ -203,7 +217,7 @@ impl TransactionsDal<'_, '_> {
                     (
                         $1, FALSE, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15,
                         jsonb_build_object('gas_used', $16::bigint, 'storage_writes', $17::int, 'contracts_used', $18::int),
-                        $19, now(), now()
+                        $19, $20, now(), now()
                     )
                 ON CONFLICT
                     (initiator_address, nonce)
@@ -224,11 +238,13 @@ impl TransactionsDal<'_, '_> {
                         execution_info=jsonb_build_object('gas_used', $16::bigint, 'storage_writes', $17::int, 'contracts_used', $18::int),
                         in_mempool=FALSE,
                         received_at=$19,
+                        valid_until=$20,
                         created_at=now(),
                         updated_at=now(),
-                        error = NULL
+                        error = NULL,
+                        rejection = NULL
                     WHERE transactions.is_priority = FALSE AND transactions.miniblock_number IS NULL
-                    RETURNING (SELECT hash FROM transactions WHERE transactions.initiator_address = $2 AND transactions.nonce = $3) IS NOT NULL as "is_replaced!"
+                    RETURNING (SELECT hash FROM transactions WHERE transactions.initiator_address = $2 AND transactions.nonce = $3) as "replaced_hash?"
                 "#,
                 &tx_hash,
                 &initiator,
@@ -248,16 +264,32 @@ impl TransactionsDal<'_, '_> {
                 exec_info.gas_used as i64,
                 (exec_info.initial_storage_writes + exec_info.repeated_storage_writes) as i32,
                 exec_info.contracts_used as i32,
-                received_at
+                received_at,
+                valid_until
             )
                 .fetch_optional(self.storage.conn())
                 .await
-                .map(|option_record| option_record.map(|record| record.is_replaced));
+                .map(|option_record| option_record.map(|record| record.replaced_hash));
 
             let l2_tx_insertion_result = match query_result {
                 Ok(option_query_result) => match option_query_result {
-                    Some(true) => L2TxSubmissionResult::Replaced,
-                    Some(false) => L2TxSubmissionResult::Added,
+                    Some(Some(replaced_hash)) => {
+                        let replaced_hash = H256::from_slice(&replaced_hash);
+                        // Resubmissions of the same transaction are not recorded in the history.
+                        if replaced_hash != tx.hash() {
+                            self.storage
+                                .transaction_history_dal()
+                                .record_replacement(
+                                    tx.initiator_account(),
+                                    tx.common_data.nonce,
+                                    replaced_hash,
+                                    tx.hash(),
+                                )
+                                .await;
+                        }
+                        L2TxSubmissionResult::Replaced
+                    }
+                    Some(None) => L2TxSubmissionResult::Added,
                     None => L2TxSubmissionResult::AlreadyExecuted,
                 },
                 Err(err) => {
@@ -291,6 +323,55 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Inserts a batch of validated L2 transactions together with their execution metrics and marks them
+    /// as loaded into the mempool, so that they are not picked up by the mempool fetcher. Returns committed nonces
+    /// of the transaction initiators, which should be supplied to the mempool together with the transactions.
+    ///
+    /// If a transaction cannot be added (e.g., it's a duplicate or replaces another transaction),
+    /// returns its hash and the submission result. The caller is expected to run this method
+    /// in a DB transaction and roll it back in this case, so that the batch is inserted atomically.
+    pub async fn insert_transactions_l2_bulk(
+        &mut self,
+        txs: &[(L2Tx, TransactionExecutionMetrics)],
+    ) -> Result<HashMap<Address, Nonce>, (H256, L2TxSubmissionResult)> {
+        let started_at = Instant::now();
+        for (tx, exec_info) in txs {
+            let result = self.insert_transaction_l2(tx.clone(), *exec_info).await;
+            if result != L2TxSubmissionResult::Added {
+                return Err((tx.hash(), result));
+            }
+        }
+
+        let hashes: Vec<_> = txs.iter().map(|(tx, _)| tx.hash().0.to_vec()).collect();
+        sqlx::query!(
+            "UPDATE transactions SET in_mempool = TRUE WHERE hash = ANY($1)",
+            &hashes
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+        let nonces = self
+            .get_account_nonces(txs.iter().map(|(tx, _)| tx.initiator_account()))
+            .await;
+
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "insert_transactions_l2_bulk");
+        Ok(nonces)
+    }
+
+    /// Resets the mempool flag for the specified pending transactions, so that they are loaded
+    /// by the mempool fetcher.
+    pub async fn unmark_txs_as_in_mempool(&mut self, tx_hashes: &[H256]) {
+        let hashes: Vec<_> = tx_hashes.iter().map(|hash| hash.0.to_vec()).collect();
+        sqlx::query!(
+            "UPDATE transactions SET in_mempool = FALSE, updated_at = now() \
+            WHERE hash = ANY($1) AND miniblock_number IS NULL",
+            &hashes
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
     pub async fn mark_txs_as_executed_in_l1_batch(
         &mut self,
         block_number: L1BatchNumber,
@@ -326,6 +407,11 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    #[tracing::instrument(
+        name = "mark_txs_as_executed_in_miniblock",
+        skip_all,
+        fields(miniblock_number = miniblock_number.0, tx_count = transactions.len())
+    )]
     pub async fn mark_txs_as_executed_in_miniblock(
         &mut self,
         miniblock_number: MiniblockNumber,
@@ -333,6 +419,7 @@ impl TransactionsDal<'_, '_> {
         block_base_fee_per_gas: U256,
     ) {
         {
+            let codecs = DataCodecs::global();
             let mut transaction = self.storage.start_transaction().await;
             let mut l1_hashes = Vec::with_capacity(transactions.len());
             let mut l1_indices_in_block = Vec::with_capacity(transactions.len());
@@ -364,6 +451,7 @@ impl TransactionsDal<'_, '_> {
 
             let mut call_traces_tx_hashes = Vec::with_capacity(transactions.len());
             let mut bytea_call_traces = Vec::with_capacity(transactions.len());
+            let mut call_traces_truncated = Vec::with_capacity(transactions.len());
             transactions
                 .iter()
                 .enumerate()
@@ -390,6 +478,7 @@ impl TransactionsDal<'_, '_> {
                         let started_at = Instant::now();
                         bytea_call_traces.push(bincode::serialize(&call_trace).unwrap());
                         call_traces_tx_hashes.push(hash.0.to_vec());
+                        call_traces_truncated.push(tx_res.result_truncated);
                         metrics::histogram!(
                             "dal.transactions.serialize_tracer",
                             started_at.elapsed()
@@ -407,7 +496,7 @@ impl TransactionsDal<'_, '_> {
                                 .push(u256_to_big_decimal(common_data.max_fee_per_gas));
                         }
                         ExecuteTransactionCommon::L2(common_data) => {
-                            let data = serde_json::to_value(&transaction.execute).unwrap();
+                            let data = codecs.encode_execute(&transaction.execute);
                             l2_values.push(u256_to_big_decimal(transaction.execute.value));
                             l2_contract_addresses
                                 .push(transaction.execute.contract_address.as_bytes().to_vec());
@@ -429,7 +518,12 @@ impl TransactionsDal<'_, '_> {
                                 .push(u256_to_big_decimal(l2_effective_gas_price));
                             l2_execution_infos.push(serde_json::to_value(execution_info).unwrap());
                             // Normally input data is mandatory
-                            l2_inputs.push(common_data.input_data().unwrap_or_default());
+                            let input = common_data.input_data().unwrap_or_default();
+                            l2_inputs.push(
+                                codecs
+                                    .encode(ProtectedColumn::TransactionCalldata, input)
+                                    .into_owned(),
+                            );
                             l2_datas.push(data);
                             l2_gas_limits.push(u256_to_big_decimal(common_data.fee.gas_limit));
                             l2_max_fees_per_gas
@@ -515,7 +609,7 @@ impl TransactionsDal<'_, '_> {
                     &l2_errors,
                     &l2_effective_gas_prices,
                     &l2_execution_infos,
-                    &l2_inputs as &[&[u8]],
+                    &l2_inputs,
                     &l2_datas,
                     &l2_refunded_gas,
                     &l2_values,
@@ -572,13 +666,14 @@ impl TransactionsDal<'_, '_> {
                 let started_at = Instant::now();
                 sqlx::query!(
                     r#"
-                        INSERT INTO call_traces (tx_hash, call_trace)
-                        SELECT u.tx_hash, u.call_trace
-                        FROM UNNEST($1::bytea[], $2::bytea[])
-                        AS u(tx_hash, call_trace)
+                        INSERT INTO call_traces (tx_hash, call_trace, truncated)
+                        SELECT u.tx_hash, u.call_trace, u.truncated
+                        FROM UNNEST($1::bytea[], $2::bytea[], $3::bool[])
+                        AS u(tx_hash, call_trace, truncated)
                         "#,
                     &call_traces_tx_hashes,
-                    &bytea_call_traces
+                    &bytea_call_traces,
+                    &call_traces_truncated
                 )
                 .execute(transaction.conn())
                 .await
@@ -589,16 +684,20 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
-    pub async fn mark_tx_as_rejected(&mut self, transaction_hash: H256, error: &str) {
+    /// Marks the transaction as rejected, persisting both a human-readable error and a structured rejection reason.
+    pub async fn mark_tx_as_rejected(&mut self, transaction_hash: H256, rejection: &TxRejection) {
         {
             // If the rejected tx has been replaced, it means that this tx hash does not exist in the database
             // and we will update nothing.
             // These txs don't affect the state, so we can just easily skip this update.
+            let error = format!("rejected: {}", rejection);
+            let rejection = serde_json::to_value(rejection).expect("cannot serialize tx rejection");
             sqlx::query!(
                 "UPDATE transactions
-                    SET error = $1, updated_at = now()
-                    WHERE hash = $2",
+                    SET error = $1, rejection = $2, updated_at = now()
+                    WHERE hash = $3",
                 error,
+                rejection,
                 transaction_hash.0.to_vec()
             )
             .execute(self.storage.conn())
@@ -654,6 +753,7 @@ impl TransactionsDal<'_, '_> {
     /// Fetches new updates for mempool
     /// Returns new transactions and current nonces for related accounts
     /// Latter is only used to bootstrap mempool for given account
+    #[tracing::instrument(name = "sync_mempool", skip_all, fields(limit = limit))]
     pub async fn sync_mempool(
         &mut self,
         stashed_accounts: Vec<Address>,
@@ -709,39 +809,200 @@ impl TransactionsDal<'_, '_> {
             .await
             .unwrap();
 
-            let nonce_keys: HashMap<_, _> = transactions
+            let initiators = transactions
                 .iter()
+                .map(|tx| Address::from_slice(&tx.initiator_address));
+            let nonces = self.get_account_nonces(initiators).await;
+
+            let codecs = self.storage.protected_data_codecs();
+            let transactions = transactions
+                .into_iter()
                 .map(|tx| {
-                    let address = Address::from_slice(&tx.initiator_address);
-                    let nonce_key = get_nonce_key(&address).hashed_key();
-                    (nonce_key, address)
+                    let mut tx = tx.into();
+                    codecs.decode_transaction(&mut tx);
+                    tx
                 })
                 .collect();
+            (transactions, nonces)
+        }
+    }
 
-            let storage_keys: Vec<_> = nonce_keys.keys().map(|key| key.0.to_vec()).collect();
-            let nonces: HashMap<_, _> = sqlx::query!(
-                r#"SELECT hashed_key, value as "value!" FROM storage WHERE hashed_key = ANY($1)"#,
-                &storage_keys,
-            )
-            .fetch_all(self.storage.conn())
+    /// Returns the current time according to Postgres. Used as a watermark for [`Self::get_mempool_delta()`],
+    /// so that it doesn't depend on the clock skew between the server and Postgres.
+    pub async fn current_db_time(&mut self) -> NaiveDateTime {
+        sqlx::query!("SELECT now()::timestamp AS \"now!\"")
+            .fetch_one(self.storage.conn())
             .await
             .unwrap()
-            .into_iter()
-            .map(|row| {
-                let nonce_key = H256::from_slice(&row.hashed_key);
-                let nonce = Nonce(h256_to_u32(H256::from_slice(&row.value)));
+            .now
+    }
 
-                (*nonce_keys.get(&nonce_key).unwrap(), nonce)
+    /// Loads pending transactions eligible for the mempool that were added or updated after
+    /// the specified `(updated_at, hash)` position, or all eligible transactions if the position is `None`.
+    /// Transactions are ordered by the position, so that the position of the last returned transaction
+    /// can be used to load the next page.
+    ///
+    /// Unlike [`Self::sync_mempool()`], this method doesn't mark returned transactions as loaded into the mempool;
+    /// the caller is responsible for tracking loaded transactions.
+    #[tracing::instrument(name = "get_mempool_delta", skip_all, fields(limit = limit))]
+    pub async fn get_mempool_delta(
+        &mut self,
+        after: Option<(NaiveDateTime, H256)>,
+        gas_per_pubdata: u32,
+        fee_per_gas: u64,
+        limit: usize,
+    ) -> MempoolDelta {
+        let started_at = Instant::now();
+        let (after_timestamp, after_hash) = match after {
+            Some((timestamp, hash)) => (Some(timestamp), hash.as_bytes().to_vec()),
+            None => (None, vec![]),
+        };
+        // Transactions expired according to the last miniblock timestamp cannot be included into the next miniblocks.
+        let last_miniblock_timestamp = self.last_miniblock_timestamp().await;
+        let storage_txs = sqlx::query_as!(
+            StorageTransaction,
+            "SELECT * FROM transactions \
+            WHERE miniblock_number IS NULL AND in_mempool = FALSE AND error IS NULL \
+                AND (is_priority = TRUE OR (max_fee_per_gas >= $2 AND gas_per_pubdata_limit >= $3)) \
+                AND (valid_until IS NULL OR valid_until > $6) \
+                AND ($4::timestamp IS NULL OR (updated_at, hash) > ($4, $5)) \
+            ORDER BY updated_at, hash \
+            LIMIT $1",
+            limit as i64,
+            BigDecimal::from(fee_per_gas),
+            BigDecimal::from(gas_per_pubdata),
+            after_timestamp,
+            &after_hash,
+            last_miniblock_timestamp
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap();
+
+        let last_position = storage_txs
+            .last()
+            .map(|tx| (tx.updated_at, H256::from_slice(&tx.hash)));
+        let codecs = self.storage.protected_data_codecs();
+        let transactions: Vec<Transaction> = storage_txs
+            .into_iter()
+            .map(|storage_tx| {
+                let mut tx = Transaction::from(storage_tx);
+                codecs.decode_transaction(&mut tx);
+                tx
             })
             .collect();
+        let nonces = self
+            .get_account_nonces(transactions.iter().map(Transaction::initiator_account))
+            .await;
 
-            (
-                transactions.into_iter().map(|tx| tx.into()).collect(),
-                nonces,
-            )
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_mempool_delta");
+        MempoolDelta {
+            transactions,
+            nonces,
+            last_position,
         }
     }
 
+    /// Makes pending transactions of the specified accounts that were in the mempool eligible
+    /// for [`Self::get_mempool_delta()`] again. A transaction is considered to be in the mempool if it's
+    /// marked as such (e.g., after bulk ingestion) or is in `loaded_tx_hashes`. Other transactions
+    /// of the accounts are left intact, so that their `updated_at` isn't bumped needlessly.
+    pub async fn unstash_mempool_accounts(
+        &mut self,
+        accounts: &[Address],
+        loaded_tx_hashes: &[H256],
+    ) {
+        let addresses: Vec<_> = accounts.iter().map(|address| address.0.to_vec()).collect();
+        let hashes: Vec<_> = loaded_tx_hashes
+            .iter()
+            .map(|hash| hash.0.to_vec())
+            .collect();
+        sqlx::query!(
+            "UPDATE transactions SET in_mempool = FALSE, updated_at = now() \
+            WHERE miniblock_number IS NULL AND error IS NULL AND initiator_address = ANY($1) \
+                AND (in_mempool = TRUE OR hash = ANY($2))",
+            &addresses,
+            &hashes
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Marks pending transactions with the passed `valid_until` deadline as expired, together with
+    /// the specified transactions dropped from the mempool as expired. Returns the number of marked transactions.
+    ///
+    /// Deadlines are compared with the timestamp of the last miniblock in the storage, which is a lower bound
+    /// for the timestamp of any miniblock a pending transaction can be included into.
+    pub async fn mark_expired_txs(&mut self, tx_hashes: &[H256]) -> usize {
+        let hashes: Vec<_> = tx_hashes.iter().map(|hash| hash.0.to_vec()).collect();
+        let last_miniblock_timestamp = self.last_miniblock_timestamp().await;
+        let result = sqlx::query!(
+            "UPDATE transactions SET error = 'expired', in_mempool = FALSE, updated_at = now() \
+            WHERE miniblock_number IS NULL AND error IS NULL AND valid_until IS NOT NULL \
+                AND (hash = ANY($1) OR valid_until <= $2)",
+            &hashes,
+            last_miniblock_timestamp
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+        result.rows_affected() as usize
+    }
+
+    /// Returns the timestamp of the last miniblock in the storage, or 0 if there are no miniblocks.
+    async fn last_miniblock_timestamp(&mut self) -> i64 {
+        sqlx::query!("SELECT timestamp FROM miniblocks ORDER BY number DESC LIMIT 1")
+            .fetch_optional(self.storage.conn())
+            .await
+            .unwrap()
+            .map_or(0, |row| row.timestamp)
+    }
+
+    /// Removes all pending transactions of the specified accounts, including the ones not loaded into the mempool.
+    pub async fn remove_pending_account_txs(&mut self, accounts: &[Address]) {
+        let addresses: Vec<_> = accounts.iter().map(|address| address.0.to_vec()).collect();
+        sqlx::query!(
+            "DELETE FROM transactions \
+            WHERE miniblock_number IS NULL AND initiator_address = ANY($1)",
+            &addresses
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+    }
+
+    /// Returns the committed nonces of the specified accounts. Accounts with the zero nonce
+    /// are not included into the returned map.
+    async fn get_account_nonces(
+        &mut self,
+        accounts: impl Iterator<Item = Address>,
+    ) -> HashMap<Address, Nonce> {
+        let nonce_keys: HashMap<_, _> = accounts
+            .map(|address| {
+                let nonce_key = get_nonce_key(&address).hashed_key();
+                (nonce_key, address)
+            })
+            .collect();
+
+        let storage_keys: Vec<_> = nonce_keys.keys().map(|key| key.0.to_vec()).collect();
+        sqlx::query!(
+            r#"SELECT hashed_key, value as "value!" FROM storage WHERE hashed_key = ANY($1)"#,
+            &storage_keys,
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| {
+            let nonce_key = H256::from_slice(&row.hashed_key);
+            let nonce = Nonce(h256_to_u32(H256::from_slice(&row.value)));
+
+            (*nonce_keys.get(&nonce_key).unwrap(), nonce)
+        })
+        .collect()
+    }
+
     pub async fn reset_mempool(&mut self) {
         {
             sqlx::query!("UPDATE transactions SET in_mempool = FALSE WHERE in_mempool = TRUE")
@@ -766,6 +1027,26 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Returns the ID and the L1 block of the oldest priority operation not included into a miniblock yet.
+    pub async fn get_oldest_pending_priority_op(
+        &mut self,
+    ) -> Option<(PriorityOpId, L1BlockNumber)> {
+        let row = sqlx::query!(
+            "SELECT priority_op_id AS \"priority_op_id!\", l1_block_number FROM transactions \
+            WHERE is_priority = TRUE AND miniblock_number IS NULL \
+            ORDER BY priority_op_id \
+            LIMIT 1"
+        )
+        .fetch_optional(self.storage.conn())
+        .await
+        .unwrap()?;
+        let l1_block_number = row.l1_block_number?;
+        Some((
+            PriorityOpId(row.priority_op_id as u64),
+            L1BlockNumber(l1_block_number as u32),
+        ))
+    }
+
     pub async fn last_priority_id(&mut self) -> Option<PriorityOpId> {
         {
             let op_id = sqlx::query!(
@@ -831,6 +1112,7 @@ impl TransactionsDal<'_, '_> {
         &mut self,
     ) -> Vec<(MiniblockNumber, Vec<Transaction>)> {
         {
+            let codecs = self.storage.protected_data_codecs();
             sqlx::query_as!(
                 StorageTransaction,
                 "
@@ -848,14 +1130,39 @@ impl TransactionsDal<'_, '_> {
             .map(|(miniblock_number, txs)| {
                 (
                     MiniblockNumber(miniblock_number.unwrap() as u32),
-                    txs.map(Into::<Transaction>::into)
-                        .collect::<Vec<Transaction>>(),
+                    txs.map(|tx| {
+                        let mut tx = Transaction::from(tx);
+                        codecs.decode_transaction(&mut tx);
+                        tx
+                    })
+                    .collect::<Vec<Transaction>>(),
                 )
             })
             .collect()
         }
     }
 
+    /// Returns execution outcomes persisted for the transactions returned by
+    /// [`Self::get_transactions_to_reexecute()`], in the same order.
+    pub async fn get_persisted_executions_to_reexecute(&mut self) -> Vec<PersistedTxExecution> {
+        sqlx::query!(
+            "SELECT hash, error, refunded_gas, execution_info FROM transactions \
+            WHERE miniblock_number IS NOT NULL AND l1_batch_number IS NULL \
+            ORDER BY miniblock_number, index_in_block"
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| PersistedTxExecution {
+            hash: H256::from_slice(&row.hash),
+            status: TxExecutionStatus::from_has_failed(row.error.is_some()),
+            refunded_gas: row.refunded_gas as u32,
+            metrics: serde_json::from_value(row.execution_info).ok(),
+        })
+        .collect()
+    }
+
     pub async fn get_tx_locations(&mut self, l1_batch_number: L1BatchNumber) -> TxLocations {
         {
             sqlx::query!(
@@ -885,7 +1192,9 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
-    pub async fn get_call_trace(&mut self, tx_hash: H256) -> Option<Call> {
+    /// Returns the call trace of the specified transaction together with the flag whether the trace
+    /// was truncated to fit into the size limits when persisted.
+    pub async fn get_call_trace(&mut self, tx_hash: H256) -> Option<(Call, bool)> {
         {
             sqlx::query_as!(
                 CallTrace,
@@ -898,7 +1207,10 @@ impl TransactionsDal<'_, '_> {
             .fetch_optional(self.storage.conn())
             .await
             .unwrap()
-            .map(|trace| trace.into())
+            .map(|trace| {
+                let truncated = trace.truncated;
+                (trace.into(), truncated)
+            })
         }
     }
 
@@ -1,20 +1,21 @@
 use sqlx::types::chrono::NaiveDateTime;
 
+use std::str::FromStr;
+use std::time::Instant;
+
 use zksync_types::{
-    api, Address, L2ChainId, MiniblockNumber, Transaction, ACCOUNT_CODE_STORAGE_ADDRESS,
-    FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H160, H256, U256, U64,
+    api, Address, L1BatchNumber, L2ChainId, MiniblockNumber, Transaction,
+    ACCOUNT_CODE_STORAGE_ADDRESS, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256, U256,
 };
-use zksync_utils::{bigdecimal_to_u256, h256_to_account_address};
 
 use crate::models::{
     storage_block::{bind_block_where_sql_params, web3_block_where_sql},
-    storage_event::StorageWeb3Log,
     storage_transaction::{
         extract_web3_transaction, web3_transaction_select_sql, StorageTransaction,
-        StorageTransactionDetails,
+        StorageTransactionDetails, StorageTransactionReceipt,
     },
 };
-use crate::{SqlxError, StorageProcessor};
+use crate::{pruning_dal::HistoryQueryError, SqlxError, StorageProcessor};
 
 #[derive(Debug)]
 pub struct TransactionsWeb3Dal<'a, 'c> {
@@ -26,147 +27,127 @@ impl TransactionsWeb3Dal<'_, '_> {
         &mut self,
         hash: H256,
     ) -> Result<Option<api::TransactionReceipt>, SqlxError> {
-        {
-            let receipt = sqlx::query!(
-                r#"
-                WITH sl AS (
-                    SELECT * FROM storage_logs
-                    WHERE storage_logs.address = $1 AND storage_logs.tx_hash = $2
-                    ORDER BY storage_logs.miniblock_number DESC, storage_logs.operation_number DESC
-                    LIMIT 1
-                )
+        let mut receipts = self.get_transaction_receipts(&[hash]).await?;
+        Ok(receipts.pop())
+    }
+
+    /// Returns receipts for the specified transactions together with their logs and L2-to-L1 logs. Receipts
+    /// are assembled with a single query; transactions unknown to the database are skipped.
+    pub async fn get_transaction_receipts(
+        &mut self,
+        hashes: &[H256],
+    ) -> Result<Vec<api::TransactionReceipt>, SqlxError> {
+        let started_at = Instant::now();
+        let hashes: Vec<_> = hashes.iter().map(|hash| hash.as_bytes().to_vec()).collect();
+        // Logs and L2-to-L1 logs are aggregated into arrays, so that a receipt is assembled from a single row.
+        let receipts = sqlx::query_as!(
+            StorageTransactionReceipt,
+            r#"
                 SELECT
-                     transactions.hash as tx_hash,
-                     transactions.index_in_block as index_in_block,
-                     transactions.l1_batch_tx_index as l1_batch_tx_index,
-                     transactions.miniblock_number as block_number,
-                     transactions.error as error,
-                     transactions.effective_gas_price as effective_gas_price,
-                     transactions.initiator_address as initiator_address,
-                     transactions.data->'to' as "transfer_to?",
-                     transactions.data->'contractAddress' as "execute_contract_address?",
-                     transactions.tx_format as "tx_format?",
-                     transactions.refunded_gas as refunded_gas,
-                     transactions.gas_limit as gas_limit,
-                     miniblocks.hash as "block_hash?",
-                     miniblocks.l1_batch_number as "l1_batch_number?",
-                     sl.key as "contract_address?"
+                    transactions.hash AS tx_hash,
+                    transactions.index_in_block,
+                    transactions.l1_batch_tx_index,
+                    transactions.miniblock_number AS block_number,
+                    transactions.error,
+                    transactions.effective_gas_price,
+                    transactions.initiator_address,
+                    transactions.data->'to' AS "transfer_to?",
+                    transactions.data->'contractAddress' AS "execute_contract_address?",
+                    transactions.tx_format,
+                    transactions.refunded_gas,
+                    transactions.gas_limit,
+                    miniblocks.hash AS "block_hash?",
+                    miniblocks.l1_batch_number AS "l1_batch_number?",
+                    sl.key AS "contract_address?",
+                    tx_events.addresses AS "event_addresses?",
+                    tx_events.topics1 AS "event_topics1?",
+                    tx_events.topics2 AS "event_topics2?",
+                    tx_events.topics3 AS "event_topics3?",
+                    tx_events.topics4 AS "event_topics4?",
+                    tx_events.log_values AS "event_values?",
+                    tx_events.indices_in_block AS "event_indices_in_block?",
+                    tx_events.indices_in_tx AS "event_indices_in_tx?",
+                    tx_l2_to_l1_logs.indices_in_miniblock AS "l2_to_l1_log_indices_in_miniblock?",
+                    tx_l2_to_l1_logs.indices_in_tx AS "l2_to_l1_log_indices_in_tx?",
+                    tx_l2_to_l1_logs.shard_ids AS "l2_to_l1_log_shard_ids?",
+                    tx_l2_to_l1_logs.is_service AS "l2_to_l1_log_is_service?",
+                    tx_l2_to_l1_logs.tx_indices_in_l1_batch AS "l2_to_l1_log_tx_indices_in_l1_batch?",
+                    tx_l2_to_l1_logs.senders AS "l2_to_l1_log_senders?",
+                    tx_l2_to_l1_logs.keys AS "l2_to_l1_log_keys?",
+                    tx_l2_to_l1_logs.log_values AS "l2_to_l1_log_values?"
                 FROM transactions
-                LEFT JOIN miniblocks
-                    ON miniblocks.number = transactions.miniblock_number
-                LEFT JOIN sl
-                    ON sl.value != $3
-                WHERE transactions.hash = $2
-                "#,
-                ACCOUNT_CODE_STORAGE_ADDRESS.as_bytes(),
-                hash.0.to_vec(),
-                FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH.as_bytes()
-            )
-            .fetch_optional(self.storage.conn())
-            .await?
-            .map(|db_row| {
-                let status = match (db_row.block_number, db_row.error) {
-                    (_, Some(_)) => Some(U64::from(0)),
-                    (Some(_), None) => Some(U64::from(1)),
-                    // tx not executed yet
-                    _ => None,
-                };
-                let tx_type = db_row.tx_format.map(U64::from).unwrap_or_default();
-                let transaction_index = db_row.index_in_block.map(U64::from).unwrap_or_default();
-
-                api::TransactionReceipt {
-                    transaction_hash: H256::from_slice(&db_row.tx_hash),
-                    transaction_index,
-                    block_hash: db_row
-                        .block_hash
-                        .clone()
-                        .map(|bytes| H256::from_slice(&bytes)),
-                    block_number: db_row.block_number.map(U64::from),
-                    l1_batch_tx_index: db_row.l1_batch_tx_index.map(U64::from),
-                    l1_batch_number: db_row.l1_batch_number.map(U64::from),
-                    from: H160::from_slice(&db_row.initiator_address),
-                    to: db_row
-                        .transfer_to
-                        .or(db_row.execute_contract_address)
-                        .map(|addr| {
-                            serde_json::from_value::<Address>(addr)
-                                .expect("invalid address value in the database")
-                        })
-                        // For better compatibility with various clients, we never return null.
-                        .or_else(|| Some(Address::default())),
-                    cumulative_gas_used: Default::default(),
-                    gas_used: {
-                        let refunded_gas: U256 = db_row.refunded_gas.into();
-                        db_row.gas_limit.map(|val| {
-                            let gas_limit = bigdecimal_to_u256(val);
-                            gas_limit - refunded_gas
-                        })
-                    },
-                    effective_gas_price: Some(
-                        db_row
-                            .effective_gas_price
-                            .map(bigdecimal_to_u256)
-                            .unwrap_or_default(),
-                    ),
-                    contract_address: db_row
-                        .contract_address
-                        .map(|addr| h256_to_account_address(&H256::from_slice(&addr))),
-                    logs: vec![],
-                    l2_to_l1_logs: vec![],
-                    status,
-                    root: db_row.block_hash.map(|bytes| H256::from_slice(&bytes)),
-                    logs_bloom: Default::default(),
-                    // Even though the Rust SDK recommends us to supply "None" for legacy transactions
-                    // we always supply some number anyway to have the same behaviour as most popular RPCs
-                    transaction_type: Some(tx_type),
-                }
-            });
-            match receipt {
-                Some(mut receipt) => {
-                    let logs: Vec<_> = sqlx::query_as!(
-                        StorageWeb3Log,
-                        r#"
-                        SELECT
-                            address, topic1, topic2, topic3, topic4, value,
-                            Null::bytea as "block_hash", Null::bigint as "l1_batch_number?",
-                            miniblock_number, tx_hash, tx_index_in_block,
-                            event_index_in_block, event_index_in_tx
-                        FROM events
-                        WHERE tx_hash = $1
-                        ORDER BY miniblock_number ASC, event_index_in_block ASC
-                        "#,
-                        hash.as_bytes()
-                    )
-                    .fetch_all(self.storage.conn())
-                    .await?
-                    .into_iter()
-                    .map(|storage_log| {
-                        let mut log = api::Log::from(storage_log);
-                        log.block_hash = receipt.block_hash;
-                        log.l1_batch_number = receipt.l1_batch_number;
-                        log
-                    })
-                    .collect();
-
-                    receipt.logs = logs;
-
-                    let l2_to_l1_logs = self.storage.events_dal().l2_to_l1_logs(hash).await?;
-                    let l2_to_l1_logs: Vec<_> = l2_to_l1_logs
-                        .into_iter()
-                        .map(|storage_l2_to_l1_log| {
-                            let mut l2_to_l1_log = api::L2ToL1Log::from(storage_l2_to_l1_log);
-                            l2_to_l1_log.block_hash = receipt.block_hash;
-                            l2_to_l1_log.l1_batch_number = receipt.l1_batch_number;
-                            l2_to_l1_log
-                        })
-                        .collect();
-                    receipt.l2_to_l1_logs = l2_to_l1_logs;
-
-                    Ok(Some(receipt))
-                }
-                None => Ok(None),
-            }
-        }
+                LEFT JOIN miniblocks ON miniblocks.number = transactions.miniblock_number
+                LEFT JOIN LATERAL (
+                    SELECT key, value FROM storage_logs
+                    WHERE storage_logs.address = $1 AND storage_logs.tx_hash = transactions.hash
+                    ORDER BY storage_logs.miniblock_number DESC, storage_logs.operation_number DESC
+                    LIMIT 1
+                ) sl ON sl.value != $2
+                LEFT JOIN LATERAL (
+                    SELECT
+                        ARRAY_AGG(address ORDER BY event_index_in_block) AS addresses,
+                        ARRAY_AGG(topic1 ORDER BY event_index_in_block) AS topics1,
+                        ARRAY_AGG(topic2 ORDER BY event_index_in_block) AS topics2,
+                        ARRAY_AGG(topic3 ORDER BY event_index_in_block) AS topics3,
+                        ARRAY_AGG(topic4 ORDER BY event_index_in_block) AS topics4,
+                        ARRAY_AGG(value ORDER BY event_index_in_block) AS log_values,
+                        ARRAY_AGG(event_index_in_block ORDER BY event_index_in_block) AS indices_in_block,
+                        ARRAY_AGG(event_index_in_tx ORDER BY event_index_in_block) AS indices_in_tx
+                    FROM events WHERE events.tx_hash = transactions.hash
+                ) tx_events ON TRUE
+                LEFT JOIN LATERAL (
+                    SELECT
+                        ARRAY_AGG(log_index_in_miniblock ORDER BY log_index_in_tx) AS indices_in_miniblock,
+                        ARRAY_AGG(log_index_in_tx ORDER BY log_index_in_tx) AS indices_in_tx,
+                        ARRAY_AGG(shard_id ORDER BY log_index_in_tx) AS shard_ids,
+                        ARRAY_AGG(is_service ORDER BY log_index_in_tx) AS is_service,
+                        ARRAY_AGG(tx_index_in_l1_batch ORDER BY log_index_in_tx) AS tx_indices_in_l1_batch,
+                        ARRAY_AGG(sender ORDER BY log_index_in_tx) AS senders,
+                        ARRAY_AGG(key ORDER BY log_index_in_tx) AS keys,
+                        ARRAY_AGG(value ORDER BY log_index_in_tx) AS log_values
+                    FROM l2_to_l1_logs WHERE l2_to_l1_logs.tx_hash = transactions.hash
+                ) tx_l2_to_l1_logs ON TRUE
+                WHERE transactions.hash = ANY($3)
+                ORDER BY transactions.miniblock_number, transactions.index_in_block
+            "#,
+            ACCOUNT_CODE_STORAGE_ADDRESS.as_bytes(),
+            FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH.as_bytes(),
+            &hashes
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let codecs = self.storage.protected_data_codecs();
+        let receipts = receipts
+            .into_iter()
+            .map(|receipt| {
+                let mut receipt = api::TransactionReceipt::from(receipt);
+                codecs.decode_logs(&mut receipt.logs);
+                receipt
+            })
+            .collect();
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_transaction_receipts");
+        Ok(receipts)
+    }
+
+    /// Returns receipts for all transactions in the specified miniblock, ordered by the transaction index.
+    pub async fn get_miniblock_receipts(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> Result<Vec<api::TransactionReceipt>, SqlxError> {
+        let started_at = Instant::now();
+        let hashes: Vec<_> = sqlx::query!(
+            "SELECT hash FROM transactions WHERE miniblock_number = $1 ORDER BY index_in_block",
+            miniblock_number.0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?
+        .into_iter()
+        .map(|row| H256::from_slice(&row.hash))
+        .collect();
+        let receipts = self.get_transaction_receipts(&hashes).await?;
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_miniblock_receipts");
+        Ok(receipts)
     }
 
     pub async fn get_transaction(
@@ -204,10 +185,12 @@ impl TransactionsWeb3Dal<'_, '_> {
             }
         };
 
-        let tx = query
-            .fetch_optional(self.storage.conn())
-            .await?
-            .map(|row| extract_web3_transaction(row, chain_id));
+        let codecs = self.storage.protected_data_codecs();
+        let tx = query.fetch_optional(self.storage.conn()).await?.map(|row| {
+            let mut tx = extract_web3_transaction(row, chain_id);
+            codecs.decode_api_transaction(&mut tx);
+            tx
+        });
         Ok(tx)
     }
 
@@ -244,6 +227,71 @@ impl TransactionsWeb3Dal<'_, '_> {
         }
     }
 
+    /// Returns the current lifecycle status of the specified transactions. Unknown transactions are skipped.
+    pub async fn get_tx_lifecycle_statuses(
+        &mut self,
+        hashes: &[H256],
+    ) -> Result<Vec<api::TxLifecycleStatus>, SqlxError> {
+        let hashes: Vec<_> = hashes.iter().map(H256::as_bytes).collect();
+        let rows = sqlx::query!(
+            "SELECT transactions.hash, transactions.miniblock_number, transactions.l1_batch_number, \
+                transactions.error, \
+                commit_tx.tx_hash AS eth_commit_tx_hash, \
+                prove_tx.tx_hash AS eth_prove_tx_hash, \
+                execute_tx.tx_hash AS eth_execute_tx_hash \
+            FROM transactions \
+            LEFT JOIN l1_batches ON l1_batches.number = transactions.l1_batch_number \
+            LEFT JOIN eth_txs_history AS commit_tx \
+                ON (l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id AND commit_tx.confirmed_at IS NOT NULL) \
+            LEFT JOIN eth_txs_history AS prove_tx \
+                ON (l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id AND prove_tx.confirmed_at IS NOT NULL) \
+            LEFT JOIN eth_txs_history AS execute_tx \
+                ON (l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id AND execute_tx.confirmed_at IS NOT NULL) \
+            WHERE transactions.hash = ANY($1)",
+            &hashes as &[&[u8]]
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let statuses = rows.into_iter().map(|row| {
+            let tx_hash = H256::from_slice(&row.hash);
+            let miniblock_number = row
+                .miniblock_number
+                .map(|number| MiniblockNumber(number as u32));
+            let l1_batch_number = row
+                .l1_batch_number
+                .map(|number| L1BatchNumber(number as u32));
+            let eth_tx_hash = |hash: Option<String>| {
+                hash.map(|hash| H256::from_str(&hash).expect("invalid L1 tx hash"))
+            };
+
+            let (stage, eth_tx_hash) = if let Some(hash) = eth_tx_hash(row.eth_execute_tx_hash) {
+                (api::TxLifecycleStage::ExecutedOnL1, Some(hash))
+            } else if let Some(hash) = eth_tx_hash(row.eth_prove_tx_hash) {
+                (api::TxLifecycleStage::ProvenOnL1, Some(hash))
+            } else if let Some(hash) = eth_tx_hash(row.eth_commit_tx_hash) {
+                (api::TxLifecycleStage::CommittedOnL1, Some(hash))
+            } else if l1_batch_number.is_some() {
+                (api::TxLifecycleStage::SealedInBatch, None)
+            } else if miniblock_number.is_some() {
+                (api::TxLifecycleStage::IncludedInMiniblock, None)
+            } else if row.error.is_some() {
+                (api::TxLifecycleStage::Rejected, None)
+            } else {
+                (api::TxLifecycleStage::AcceptedToMempool, None)
+            };
+
+            api::TxLifecycleStatus {
+                tx_hash,
+                stage,
+                miniblock_number,
+                l1_batch_number,
+                eth_tx_hash,
+            }
+        });
+        Ok(statuses.collect())
+    }
+
     /// Returns hashes of txs which were received after `from_timestamp` and the time of receiving the last tx.
     pub async fn get_pending_txs_hashes_after(
         &mut self,
@@ -322,11 +370,16 @@ impl TransactionsWeb3Dal<'_, '_> {
     }
 
     /// Returns the server transactions (not API ones) from a certain miniblock.
-    /// Returns an empty list if the miniblock doesn't exist.
+    /// Returns an empty list if the miniblock doesn't exist, and [`HistoryQueryError::PrunedHistory`]
+    /// if the miniblock transactions are pruned.
     pub async fn get_raw_miniblock_transactions(
         &mut self,
         miniblock: MiniblockNumber,
-    ) -> Result<Vec<Transaction>, SqlxError> {
+    ) -> Result<Vec<Transaction>, HistoryQueryError> {
+        self.storage
+            .pruning_dal()
+            .check_miniblock_history(miniblock)
+            .await?;
         let rows = sqlx::query_as!(
             StorageTransaction,
             "SELECT * FROM transactions \
@@ -337,14 +390,58 @@ impl TransactionsWeb3Dal<'_, '_> {
         .fetch_all(self.storage.conn())
         .await?;
 
-        Ok(rows.into_iter().map(Into::into).collect())
+        let codecs = self.storage.protected_data_codecs();
+        let transactions = rows
+            .into_iter()
+            .map(|row| {
+                let mut tx = Transaction::from(row);
+                codecs.decode_transaction(&mut tx);
+                tx
+            })
+            .collect();
+        Ok(transactions)
+    }
+
+    /// Returns the server transaction (not the API one) with the specified hash together with the number
+    /// of the miniblock it was included in. Returns `None` if the transaction is unknown or not included
+    /// in a miniblock yet.
+    pub async fn get_raw_included_transaction(
+        &mut self,
+        hash: H256,
+    ) -> Result<Option<(Transaction, MiniblockNumber)>, HistoryQueryError> {
+        let row = sqlx::query_as!(
+            StorageTransaction,
+            "SELECT * FROM transactions \
+            WHERE hash = $1 AND miniblock_number IS NOT NULL",
+            hash.as_bytes()
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let miniblock = MiniblockNumber(row.miniblock_number.unwrap() as u32);
+        self.storage
+            .pruning_dal()
+            .check_miniblock_history(miniblock)
+            .await?;
+        let mut tx = Transaction::from(row);
+        self.storage
+            .protected_data_codecs()
+            .decode_transaction(&mut tx);
+        Ok(Some((tx, miniblock)))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use db_test_macro::db_test;
-    use zksync_types::{fee::TransactionExecutionMetrics, l2::L2Tx};
+    use zksync_types::{
+        event::EventIndexingPolicy, explorer_api::TransactionStatus,
+        fee::TransactionExecutionMetrics, l2::L2Tx, l2_to_l1_log::L2ToL1Log, tx::ExecutionMetrics,
+        tx::IncludedTxLocation, tx::TxRejectedResource, tx::TxRejection, VmEvent,
+    };
     use zksync_utils::miniblock_hash;
 
     use super::*;
@@ -430,6 +527,86 @@ mod tests {
         }
     }
 
+    #[db_test(dal_crate)]
+    async fn getting_receipts(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        prepare_transaction(&mut conn, tx).await;
+
+        let tx_location = IncludedTxLocation {
+            tx_hash,
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: Address::default(),
+        };
+        let events: Vec<_> = (0_u8..2)
+            .map(|index| VmEvent {
+                location: (L1BatchNumber(1), 0),
+                address: Address::repeat_byte(index),
+                indexed_topics: vec![H256::repeat_byte(index)],
+                value: vec![index],
+            })
+            .collect();
+        conn.events_dal()
+            .save_events(
+                MiniblockNumber(1),
+                &[(tx_location.clone(), events.iter().collect())],
+                &EventIndexingPolicy::default(),
+            )
+            .await;
+        let l2_to_l1_log = L2ToL1Log {
+            shard_id: 0,
+            is_service: false,
+            tx_number_in_block: 0,
+            sender: Address::repeat_byte(1),
+            key: H256::repeat_byte(2),
+            value: H256::repeat_byte(3),
+        };
+        conn.events_dal()
+            .save_l2_to_l1_logs(MiniblockNumber(1), &[(tx_location, vec![&l2_to_l1_log])])
+            .await;
+
+        let receipts = conn
+            .transactions_web3_dal()
+            .get_transaction_receipts(&[tx_hash, H256::zero()])
+            .await
+            .unwrap();
+        assert_eq!(receipts.len(), 1);
+        let receipt = &receipts[0];
+        assert_eq!(receipt.transaction_hash, tx_hash);
+        assert_eq!(receipt.block_number, Some(1.into()));
+        assert_eq!(receipt.status, Some(1.into()));
+
+        assert_eq!(receipt.logs.len(), 2);
+        for (i, (log, event)) in receipt.logs.iter().zip(&events).enumerate() {
+            assert_eq!(log.address, event.address);
+            assert_eq!(log.topics, event.indexed_topics);
+            assert_eq!(log.data.0, event.value);
+            assert_eq!(log.log_index, Some(i.into()));
+            assert_eq!(log.block_hash, receipt.block_hash);
+            assert_eq!(log.transaction_hash, Some(tx_hash));
+        }
+        assert_eq!(receipt.l2_to_l1_logs.len(), 1);
+        let log = &receipt.l2_to_l1_logs[0];
+        assert_eq!(log.sender, l2_to_l1_log.sender);
+        assert_eq!(log.key, l2_to_l1_log.key);
+        assert_eq!(log.value, l2_to_l1_log.value);
+        assert_eq!(log.block_hash, receipt.block_hash);
+
+        let miniblock_receipts = conn
+            .transactions_web3_dal()
+            .get_miniblock_receipts(MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(miniblock_receipts, receipts);
+        let miniblock_receipts = conn
+            .transactions_web3_dal()
+            .get_miniblock_receipts(MiniblockNumber(0))
+            .await
+            .unwrap();
+        assert!(miniblock_receipts.is_empty());
+    }
+
     #[db_test(dal_crate)]
     async fn getting_miniblock_transactions(connection_pool: ConnectionPool) {
         let mut conn = connection_pool.access_test_storage().await;
@@ -452,4 +629,89 @@ mod tests {
         assert_eq!(raw_txs.len(), 1);
         assert_eq!(raw_txs[0].hash(), tx_hash);
     }
+
+    #[db_test(dal_crate)]
+    async fn getting_transaction_details_with_execution_metrics(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await;
+
+        let details = conn
+            .transactions_web3_dal()
+            .get_transaction_details(tx_hash)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(details.execution_metrics.is_none());
+
+        prepare_transaction(&mut conn, tx).await;
+        let details = conn
+            .transactions_web3_dal()
+            .get_transaction_details(tx_hash)
+            .await
+            .unwrap()
+            .unwrap();
+        let metrics = details.execution_metrics.unwrap();
+        assert_eq!(metrics, ExecutionMetrics::default().into());
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_transaction_details_for_rejected_tx(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+            .await;
+        let rejection =
+            TxRejection::resource_limit_exceeded(TxRejectedResource::Pubdata, "too much pubdata");
+        conn.transactions_dal()
+            .mark_tx_as_rejected(tx_hash, &rejection)
+            .await;
+
+        let details = conn
+            .transactions_web3_dal()
+            .get_transaction_details(tx_hash)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(details.status, TransactionStatus::Failed));
+        assert_eq!(details.rejection, Some(rejection));
+    }
+
+    #[db_test(dal_crate)]
+    async fn getting_tx_lifecycle_statuses(connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_test_storage().await;
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await;
+
+        let statuses = conn
+            .transactions_web3_dal()
+            .get_tx_lifecycle_statuses(&[tx_hash, H256::zero()])
+            .await
+            .unwrap();
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].tx_hash, tx_hash);
+        assert_eq!(statuses[0].stage, api::TxLifecycleStage::AcceptedToMempool);
+
+        prepare_transaction(&mut conn, tx).await;
+        let statuses = conn
+            .transactions_web3_dal()
+            .get_tx_lifecycle_statuses(&[tx_hash])
+            .await
+            .unwrap();
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(
+            statuses[0].stage,
+            api::TxLifecycleStage::IncludedInMiniblock
+        );
+        assert_eq!(statuses[0].miniblock_number, Some(MiniblockNumber(1)));
+        assert_eq!(statuses[0].l1_batch_number, None);
+    }
 }
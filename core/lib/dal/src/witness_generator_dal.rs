@@ -6,7 +6,8 @@ use itertools::Itertools;
 use sqlx::Row;
 
 use zksync_types::proofs::{
-    AggregationRound, JobCountStatistics, WitnessGeneratorJobMetadata, WitnessJobInfo,
+    AggregationRound, JobCountStatistics, JobQueueStatistics, StuckJobs,
+    WitnessGeneratorJobMetadata, WitnessJobInfo,
 };
 use zksync_types::zkevm_test_harness::abstract_zksync_circuit::concrete_circuits::ZkSyncCircuit;
 use zksync_types::zkevm_test_harness::abstract_zksync_circuit::concrete_circuits::ZkSyncProof;
@@ -503,6 +504,76 @@ impl WitnessGeneratorDal<'_, '_> {
         }
     }
 
+    /// Creates per-circuit-type shards of basic circuits in `waiting_for_proofs` status. `circuit_types` lists types
+    /// of all basic circuits of the batch in the order of their sequence numbers.
+    pub async fn create_basic_circuit_shards(
+        &mut self,
+        block_number: L1BatchNumber,
+        circuit_types: &[&str],
+    ) {
+        let started_at = Instant::now();
+        let shards = circuit_types.iter().counts();
+        let (types, counts): (Vec<_>, Vec<_>) = shards
+            .into_iter()
+            .map(|(circuit_type, count)| (circuit_type.to_string(), count as i32))
+            .unzip();
+        sqlx::query!(
+            "INSERT INTO basic_circuit_shards \
+            (l1_batch_number, circuit_type, circuit_count, status, created_at, updated_at) \
+            SELECT $1, circuit_type, circuit_count, 'waiting_for_proofs', now(), now() \
+            FROM UNNEST($2::TEXT[], $3::INT[]) AS shards(circuit_type, circuit_count) \
+            ON CONFLICT (l1_batch_number, circuit_type) DO NOTHING",
+            block_number.0 as i64,
+            &types,
+            &counts
+        )
+        .execute(self.storage.conn())
+        .await
+        .unwrap();
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "create_basic_circuit_shards");
+    }
+
+    /// Marks shards of basic circuits as successful once all their circuits are proven.
+    /// Returns L1 batch numbers and circuit types of the updated shards.
+    pub async fn move_basic_circuit_shards_to_proven(&mut self) -> Vec<(i64, String)> {
+        sqlx::query!(
+            "UPDATE basic_circuit_shards SET status = 'successful', updated_at = now() \
+            WHERE status = 'waiting_for_proofs' AND circuit_count = ( \
+                SELECT COUNT(*) FROM prover_jobs \
+                WHERE prover_jobs.l1_batch_number = basic_circuit_shards.l1_batch_number \
+                    AND prover_jobs.circuit_type = basic_circuit_shards.circuit_type \
+                    AND prover_jobs.aggregation_round = 0 \
+                    AND prover_jobs.status = 'successful' \
+            ) \
+            RETURNING l1_batch_number, circuit_type"
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| (row.l1_batch_number, row.circuit_type))
+        .collect()
+    }
+
+    /// Returns circuit types of shards of basic circuits for the specified L1 batch that are not proven yet.
+    pub async fn get_unproven_basic_circuit_shards(
+        &mut self,
+        block_number: L1BatchNumber,
+    ) -> Vec<String> {
+        sqlx::query!(
+            "SELECT circuit_type FROM basic_circuit_shards \
+            WHERE l1_batch_number = $1 AND status = 'waiting_for_proofs' \
+            ORDER BY circuit_type",
+            block_number.0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| row.circuit_type)
+        .collect()
+    }
+
     /// Saves artifacts in node_aggregation_job
     /// and advances it to `waiting_for_proofs` status
     /// it will be advanced to `queued` by the prover when all the dependency proofs are computed.
@@ -634,6 +705,70 @@ impl WitnessGeneratorDal<'_, '_> {
         }
     }
 
+    /// Returns the depth of the job queue for the specified round together with the age of the oldest jobs.
+    pub async fn get_witness_jobs_queue_stats(
+        &mut self,
+        aggregation_round: AggregationRound,
+    ) -> JobQueueStatistics {
+        let table_name = Self::input_table_name_for(aggregation_round);
+        let sql = format!(
+            "SELECT status, COUNT(*) AS count, \
+                EXTRACT(EPOCH FROM now() - MIN( \
+                    CASE WHEN status = 'in_progress' THEN processing_started_at ELSE updated_at END \
+                ))::FLOAT8 AS oldest_age_sec \
+            FROM {table_name} \
+            WHERE status IN ('queued', 'in_progress') \
+            GROUP BY status"
+        );
+        let rows = sqlx::query(&sql)
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap();
+
+        let mut stats = JobQueueStatistics::default();
+        for row in rows {
+            update_job_queue_stats(
+                &mut stats,
+                row.get("status"),
+                row.get("count"),
+                row.get("oldest_age_sec"),
+            );
+        }
+        stats
+    }
+
+    /// Requeues jobs of the specified round that have been `in_progress` for longer than `processing_timeout`
+    /// (e.g., because the witness generator processing the job has crashed). Jobs that have exhausted
+    /// `max_attempts` are marked as failed instead.
+    pub async fn requeue_stuck_jobs(
+        &mut self,
+        aggregation_round: AggregationRound,
+        processing_timeout: Duration,
+        max_attempts: u32,
+    ) -> Vec<StuckJobs> {
+        let table_name = Self::input_table_name_for(aggregation_round);
+        let processing_timeout = pg_interval_from_duration(processing_timeout);
+        let sql = format!(
+            "UPDATE {table_name} \
+            SET status = CASE WHEN attempts < $2 THEN 'queued' ELSE 'failed' END, updated_at = now() \
+            WHERE status = 'in_progress' AND processing_started_at < now() - $1::interval \
+            RETURNING l1_batch_number, status, attempts"
+        );
+        sqlx::query(&sql)
+            .bind(&processing_timeout)
+            .bind(max_attempts as i32)
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| StuckJobs {
+                id: row.get::<i64, _>("l1_batch_number") as u64,
+                status: row.get("status"),
+                attempts: row.get::<i32, _>("attempts") as u64,
+            })
+            .collect()
+    }
+
     fn input_table_name_for(aggregation_round: AggregationRound) -> &'static str {
         match aggregation_round {
             AggregationRound::BasicCircuits => "witness_inputs",
@@ -963,3 +1098,23 @@ impl WitnessGeneratorDal<'_, '_> {
 pub struct GetWitnessJobsParams {
     pub blocks: Option<Range<L1BatchNumber>>,
 }
+
+pub(crate) fn update_job_queue_stats(
+    stats: &mut JobQueueStatistics,
+    status: &str,
+    count: i64,
+    oldest_age_sec: Option<f64>,
+) {
+    let oldest_age = oldest_age_sec.map(|age| Duration::from_secs_f64(age.max(0.0)));
+    match status {
+        "queued" => {
+            stats.queued = count as usize;
+            stats.oldest_queued_age = oldest_age;
+        }
+        "in_progress" => {
+            stats.in_progress = count as usize;
+            stats.oldest_in_progress_age = oldest_age;
+        }
+        _ => {}
+    }
+}
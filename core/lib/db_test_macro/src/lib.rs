@@ -102,6 +102,17 @@ fn parse_knobs(mut input: syn::ItemFn, inside_dal_crate: bool) -> Result<TokenSt
     Ok(result.into())
 }
 
+/// Marks an async function as a DB test. The function must take one or two `ConnectionPool` arguments
+/// (the main pool and, optionally, the prover pool).
+///
+/// Each pool is backed by [`TestPool`](../zksync_dal/connection/struct.TestPool.html): the test runs
+/// over a single connection inside a transaction that is rolled back once the test finishes, so no database
+/// is created or dropped per test, and changes made by one test are never visible to other tests.
+/// The flip side is that a pool can hand out only one `StorageProcessor` at a time; tests that need
+/// several simultaneous connections should use a real `ConnectionPool` instead.
+///
+/// When used inside the `zksync_dal` crate, the macro must be supplied with the `dal_crate` argument:
+/// `#[db_test(dal_crate)]`.
 #[proc_macro_attribute]
 pub fn db_test(raw_args: TokenStream, item: TokenStream) -> TokenStream {
     let input = syn::parse_macro_input!(item as syn::ItemFn);
@@ -1,5 +1,7 @@
+mod failover;
 mod query;
 mod signing;
 
+pub use failover::FailoverTransport;
 pub use query::QueryClient;
 pub use signing::{PKSigningClient, SigningClient};
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use std::time::Instant;
 
 use async_trait::async_trait;
+use zksync_config::ETHClientConfig;
 use zksync_types::web3::{
     self,
     contract::{
@@ -13,12 +14,14 @@ use zksync_types::web3::{
     helpers::CallFuture,
     transports::Http,
     types::{
-        Address, Block, BlockId, BlockNumber, Bytes, Filter, Log, Transaction, TransactionId,
-        TransactionReceipt, H256, U256, U64,
+        Address, Block, BlockId, BlockNumber, Bytes, CallRequest, Filter, Log, Transaction,
+        TransactionId, TransactionReceipt, H256, U256, U64,
     },
     Transport, Web3,
 };
+use zksync_types::L1ChainId;
 
+use super::failover::FailoverTransport;
 use crate::{
     types::{Error, ExecutedTxStatus, FailureInfo},
     EthInterface,
@@ -28,11 +31,17 @@ use crate::{
 /// tied to a particular account.
 #[derive(Debug, Clone)]
 pub struct QueryClient {
-    web3: Arc<Web3<Http>>,
+    web3: Arc<Web3<FailoverTransport>>,
 }
 
 impl From<Http> for QueryClient {
     fn from(transport: Http) -> Self {
+        FailoverTransport::from(transport).into()
+    }
+}
+
+impl From<FailoverTransport> for QueryClient {
+    fn from(transport: FailoverTransport) -> Self {
         Self {
             web3: Arc::new(Web3::new(transport)),
         }
@@ -45,6 +54,12 @@ impl QueryClient {
         let transport = web3::transports::Http::new(node_url)?;
         Ok(transport.into())
     }
+
+    /// Creates a new HTTP client using the primary L1 provider and fallback providers from the config.
+    pub fn from_config(config: &ETHClientConfig) -> Result<Self, Error> {
+        let transport = FailoverTransport::http(config.web3_urls())?;
+        Ok(transport.into())
+    }
 }
 
 #[async_trait]
@@ -74,6 +89,14 @@ impl EthInterface for QueryClient {
         Ok(block_number)
     }
 
+    async fn fetch_chain_id(&self, component: &'static str) -> Result<L1ChainId, Error> {
+        metrics::counter!("server.ethereum_gateway.call", 1, "component" => component, "method" => "fetch_chain_id");
+        let start = Instant::now();
+        let chain_id = self.web3.eth().chain_id().await?;
+        metrics::histogram!("eth_client.direct.fetch_chain_id", start.elapsed());
+        Ok(L1ChainId(chain_id.as_u64()))
+    }
+
     async fn get_gas_price(&self, component: &'static str) -> Result<U256, Error> {
         metrics::counter!("server.ethereum_gateway.call", 1, "component" => component, "method" => "get_gas_price");
         let start = Instant::now();
@@ -82,6 +105,20 @@ impl EthInterface for QueryClient {
         Ok(network_gas_price)
     }
 
+    async fn blob_base_fee(&self, component: &'static str) -> Result<Option<U256>, Error> {
+        metrics::counter!("server.ethereum_gateway.call", 1, "component" => component, "method" => "blob_base_fee");
+        let start = Instant::now();
+        let blob_base_fee: Result<U256, web3::Error> =
+            CallFuture::new(self.web3.transport().execute("eth_blobBaseFee", vec![])).await;
+        metrics::histogram!("eth_client.direct.blob_base_fee", start.elapsed());
+        match blob_base_fee {
+            Ok(fee) => Ok(Some(fee)),
+            // Nodes not supporting EIP-4844 reject the method.
+            Err(web3::Error::Rpc(_)) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
     async fn send_raw_tx(&self, tx: Vec<u8>) -> Result<H256, Error> {
         let start = Instant::now();
         let tx = self.web3.eth().send_raw_transaction(Bytes(tx)).await?;
@@ -281,6 +318,31 @@ impl EthInterface for QueryClient {
         Ok(balance)
     }
 
+    async fn call(
+        &self,
+        request: CallRequest,
+        block: Option<BlockId>,
+        component: &'static str,
+    ) -> Result<Bytes, Error> {
+        metrics::counter!("server.ethereum_gateway.call", 1, "component" => component, "method" => "call");
+        let start = Instant::now();
+        let output = self.web3.eth().call(request, block).await?;
+        metrics::histogram!("eth_client.direct.call", start.elapsed());
+        Ok(output)
+    }
+
+    async fn estimate_gas(
+        &self,
+        request: CallRequest,
+        component: &'static str,
+    ) -> Result<U256, Error> {
+        metrics::counter!("server.ethereum_gateway.call", 1, "component" => component, "method" => "estimate_gas");
+        let start = Instant::now();
+        let gas = self.web3.eth().estimate_gas(request, None).await?;
+        metrics::histogram!("eth_client.direct.estimate_gas", start.elapsed());
+        Ok(gas)
+    }
+
     async fn logs(&self, filter: Filter, component: &'static str) -> Result<Vec<Log>, Error> {
         metrics::counter!("server.ethereum_gateway.call", 1, "component" => component, "method" => "logs");
         let start = Instant::now();
@@ -9,16 +9,14 @@ use zksync_eth_signer::PrivateKeySigner;
 use zksync_eth_signer::{raw_ethereum_tx::TransactionParameters, EthereumSigner};
 use zksync_types::web3::types::Block;
 use zksync_types::web3::{
-    self,
     contract::{
         tokens::{Detokenize, Tokenize},
         Options,
     },
     ethabi,
-    transports::Http,
     types::{
-        Address, BlockId, BlockNumber, Filter, Log, Transaction, TransactionReceipt, H160, H256,
-        U256, U64,
+        Address, BlockId, BlockNumber, Bytes, CallRequest, Filter, Log, Transaction,
+        TransactionReceipt, H160, H256, U256, U64,
     },
 };
 use zksync_types::{L1ChainId, PackedEthSignature, EIP_1559_TX_TYPE};
@@ -41,7 +39,6 @@ impl PKSigningClient {
     ) -> Self {
         // Gather required data from the config.
         // It's done explicitly to simplify getting rid of this function later.
-        let main_node_url = &eth_client.web3_url;
         let operator_private_key = eth_sender
             .sender
             .private_key()
@@ -50,15 +47,15 @@ impl PKSigningClient {
         let default_priority_fee_per_gas = eth_sender.gas_adjuster.default_priority_fee_per_gas;
         let l1_chain_id = eth_client.chain_id;
 
-        let transport =
-            web3::transports::Http::new(main_node_url).expect("Failed to create transport");
+        let query_client =
+            QueryClient::from_config(eth_client).expect("Failed to create transport");
         let operator_address = PackedEthSignature::address_from_private_key(&operator_private_key)
             .expect("Failed to get address from private key");
 
         vlog::info!("Operator address: {:?}", operator_address);
 
         SigningClient::new(
-            transport,
+            query_client,
             zksync_contract(),
             operator_address,
             PrivateKeySigner::new(operator_private_key),
@@ -121,10 +118,18 @@ impl<S: EthereumSigner> EthInterface for SigningClient<S> {
         self.query_client.block_number(component).await
     }
 
+    async fn fetch_chain_id(&self, component: &'static str) -> Result<L1ChainId, Error> {
+        self.query_client.fetch_chain_id(component).await
+    }
+
     async fn get_gas_price(&self, component: &'static str) -> Result<U256, Error> {
         self.query_client.get_gas_price(component).await
     }
 
+    async fn blob_base_fee(&self, component: &'static str) -> Result<Option<U256>, Error> {
+        self.query_client.blob_base_fee(component).await
+    }
+
     async fn send_raw_tx(&self, tx: Vec<u8>) -> Result<H256, Error> {
         self.query_client.send_raw_tx(tx).await
     }
@@ -211,6 +216,23 @@ impl<S: EthereumSigner> EthInterface for SigningClient<S> {
         self.query_client.eth_balance(address, component).await
     }
 
+    async fn call(
+        &self,
+        request: CallRequest,
+        block: Option<BlockId>,
+        component: &'static str,
+    ) -> Result<Bytes, Error> {
+        self.query_client.call(request, block, component).await
+    }
+
+    async fn estimate_gas(
+        &self,
+        request: CallRequest,
+        component: &'static str,
+    ) -> Result<U256, Error> {
+        self.query_client.estimate_gas(request, component).await
+    }
+
     async fn logs(&self, filter: Filter, component: &'static str) -> Result<Vec<Log>, Error> {
         self.query_client.logs(filter, component).await
     }
@@ -344,7 +366,7 @@ impl<S: EthereumSigner> BoundEthInterface for SigningClient<S> {
 
 impl<S: EthereumSigner> SigningClient<S> {
     pub fn new(
-        transport: Http,
+        transport: impl Into<QueryClient>,
         contract: ethabi::Contract,
         operator_eth_addr: H160,
         eth_signer: S,
@@ -5,7 +5,7 @@ use jsonrpc_core::types::error::Error as RpcError;
 use std::collections::{BTreeMap, HashMap};
 use std::sync::RwLock;
 use zksync_types::web3::contract::tokens::Detokenize;
-use zksync_types::web3::types::{Block, BlockId, Filter, Log, Transaction};
+use zksync_types::web3::types::{Block, BlockId, Bytes, CallRequest, Filter, Log, Transaction};
 use zksync_types::web3::{
     contract::tokens::Tokenize,
     contract::Options,
@@ -57,6 +57,7 @@ impl From<Vec<u8>> for MockTx {
 pub struct MockEthereum {
     pub block_number: AtomicU64,
     pub max_fee_per_gas: U256,
+    pub blob_base_fee: Option<U256>,
     pub base_fee_history: RwLock<Vec<u64>>,
     pub max_priority_fee_per_gas: U256,
     pub tx_statuses: RwLock<HashMap<H256, ExecutedTxStatus>>,
@@ -64,6 +65,10 @@ pub struct MockEthereum {
     pub current_nonce: AtomicU64,
     pub pending_nonce: AtomicU64,
     pub nonces: RwLock<BTreeMap<u64, u64>>,
+    /// Requests made via `eth_call`.
+    pub calls: RwLock<Vec<CallRequest>>,
+    /// ETH balance returned for any account.
+    pub balance: RwLock<U256>,
     /// If true, the mock will not check the ordering nonces of the transactions.
     /// This is useful for testing the cases when the transactions are executed out of order.
     pub non_ordering_confirmations: bool,
@@ -73,6 +78,7 @@ impl Default for MockEthereum {
     fn default() -> Self {
         Self {
             max_fee_per_gas: 100.into(),
+            blob_base_fee: None,
             max_priority_fee_per_gas: 10.into(),
             block_number: Default::default(),
             base_fee_history: Default::default(),
@@ -81,6 +87,8 @@ impl Default for MockEthereum {
             current_nonce: Default::default(),
             pending_nonce: Default::default(),
             nonces: RwLock::new([(0, 0)].into()),
+            calls: Default::default(),
+            balance: RwLock::new(U256::exp10(20)),
             non_ordering_confirmations: false,
         }
     }
@@ -139,6 +147,15 @@ impl MockEthereum {
         Ok(())
     }
 
+    /// Increments the blocks by a provided `confirmations` and consumes the next sender nonce,
+    /// emulating a transaction sent from the same account bypassing the client.
+    pub fn execute_external_tx(&self, confirmations: u64) {
+        let block_number = self.block_number.fetch_add(confirmations, Ordering::SeqCst);
+        let nonce = self.current_nonce.fetch_add(1, Ordering::SeqCst);
+        self.pending_nonce.fetch_max(nonce + 1, Ordering::SeqCst);
+        self.nonces.write().unwrap().insert(block_number, nonce + 1);
+    }
+
     pub fn sign_prepared_tx(
         &self,
         mut raw_tx: Vec<u8>,
@@ -180,6 +197,20 @@ impl MockEthereum {
         }
     }
 
+    pub fn with_blob_base_fee(self, blob_base_fee: U256) -> Self {
+        Self {
+            blob_base_fee: Some(blob_base_fee),
+            ..self
+        }
+    }
+
+    pub fn with_balance(self, balance: U256) -> Self {
+        Self {
+            balance: RwLock::new(balance),
+            ..self
+        }
+    }
+
     pub fn with_non_ordering_confirmation(self, non_ordering_confirmations: bool) -> Self {
         Self {
             non_ordering_confirmations,
@@ -202,6 +233,10 @@ impl EthInterface for MockEthereum {
         Ok(self.block_number.load(Ordering::SeqCst).into())
     }
 
+    async fn fetch_chain_id(&self, _: &'static str) -> Result<L1ChainId, Error> {
+        unimplemented!("Not needed right now")
+    }
+
     async fn send_raw_tx(&self, tx: Vec<u8>) -> Result<H256, Error> {
         let mock_tx = MockTx::from(tx);
 
@@ -235,6 +270,10 @@ impl EthInterface for MockEthereum {
         Ok(self.max_fee_per_gas)
     }
 
+    async fn blob_base_fee(&self, _: &'static str) -> Result<Option<U256>, Error> {
+        Ok(self.blob_base_fee)
+    }
+
     async fn base_fee_history(
         &self,
         from_block: usize,
@@ -307,7 +346,26 @@ impl EthInterface for MockEthereum {
         _address: Address,
         _component: &'static str,
     ) -> Result<U256, Error> {
-        unimplemented!("Not needed right now")
+        Ok(*self.balance.read().unwrap())
+    }
+
+    async fn call(
+        &self,
+        request: CallRequest,
+        _block: Option<BlockId>,
+        _component: &'static str,
+    ) -> Result<Bytes, Error> {
+        self.calls.write().unwrap().push(request);
+        Ok(Bytes::default())
+    }
+
+    async fn estimate_gas(
+        &self,
+        request: CallRequest,
+        _component: &'static str,
+    ) -> Result<U256, Error> {
+        let data_len = request.data.map_or(0, |data| data.0.len());
+        Ok(U256::from(21_000 + 16 * data_len))
     }
 
     async fn logs(&self, _filter: Filter, _component: &'static str) -> Result<Vec<Log>, Error> {
@@ -422,10 +480,18 @@ impl<T: AsRef<MockEthereum> + Send + Sync> EthInterface for T {
         self.as_ref().get_gas_price(component).await
     }
 
+    async fn blob_base_fee(&self, component: &'static str) -> Result<Option<U256>, Error> {
+        self.as_ref().blob_base_fee(component).await
+    }
+
     async fn block_number(&self, component: &'static str) -> Result<U64, Error> {
         self.as_ref().block_number(component).await
     }
 
+    async fn fetch_chain_id(&self, component: &'static str) -> Result<L1ChainId, Error> {
+        self.as_ref().fetch_chain_id(component).await
+    }
+
     async fn send_raw_tx(&self, tx: Vec<u8>) -> Result<H256, Error> {
         self.as_ref().send_raw_tx(tx).await
     }
@@ -492,6 +558,23 @@ impl<T: AsRef<MockEthereum> + Send + Sync> EthInterface for T {
         self.as_ref().eth_balance(address, component).await
     }
 
+    async fn call(
+        &self,
+        request: CallRequest,
+        block: Option<BlockId>,
+        component: &'static str,
+    ) -> Result<Bytes, Error> {
+        self.as_ref().call(request, block, component).await
+    }
+
+    async fn estimate_gas(
+        &self,
+        request: CallRequest,
+        component: &'static str,
+    ) -> Result<U256, Error> {
+        self.as_ref().estimate_gas(request, component).await
+    }
+
     async fn logs(&self, filter: Filter, component: &'static str) -> Result<Vec<Log>, Error> {
         self.as_ref().logs(filter, component).await
     }
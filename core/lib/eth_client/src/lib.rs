@@ -13,8 +13,8 @@ use zksync_types::{
         },
         ethabi,
         types::{
-            Address, Block, BlockId, BlockNumber, Filter, Log, Transaction, TransactionReceipt,
-            H160, H256, U256, U64,
+            Address, Block, BlockId, BlockNumber, Bytes, CallRequest, Filter, Log, Transaction,
+            TransactionReceipt, H160, H256, U256, U64,
         },
     },
     L1ChainId,
@@ -66,9 +66,17 @@ pub trait EthInterface: Sync + Send {
     /// Returns the current gas price.
     async fn get_gas_price(&self, component: &'static str) -> Result<U256, Error>;
 
+    /// Returns the current blob base fee (EIP-4844), or `None` if the network doesn't support blobs.
+    async fn blob_base_fee(&self, _component: &'static str) -> Result<Option<U256>, Error> {
+        Ok(None)
+    }
+
     /// Returns the current block number.
     async fn block_number(&self, component: &'static str) -> Result<U64, Error>;
 
+    /// Fetches the chain ID of the L1 network the client is connected to.
+    async fn fetch_chain_id(&self, component: &'static str) -> Result<L1ChainId, Error>;
+
     /// Sends a transaction to the Ethereum network.
     async fn send_raw_tx(&self, tx: Vec<u8>) -> Result<H256, Error>;
 
@@ -107,6 +115,21 @@ pub trait EthInterface: Sync + Send {
     /// Returns the ETH balance of the specified token for the specified address.
     async fn eth_balance(&self, address: Address, component: &'static str) -> Result<U256, Error>;
 
+    /// Executes a call without creating a transaction (`eth_call`) and returns its output.
+    async fn call(
+        &self,
+        request: CallRequest,
+        block: Option<BlockId>,
+        component: &'static str,
+    ) -> Result<Bytes, Error>;
+
+    /// Estimates the amount of gas necessary to execute the call as a transaction (`eth_estimateGas`).
+    async fn estimate_gas(
+        &self,
+        request: CallRequest,
+        component: &'static str,
+    ) -> Result<U256, Error>;
+
     /// Invokes a function on a contract specified by `contract_address` / `contract_abi` using `eth_call`.
     #[allow(clippy::too_many_arguments)]
     async fn call_contract_function<R, A, B, P>(
@@ -182,6 +205,11 @@ pub trait BoundEthInterface: EthInterface {
         component: &'static str,
     ) -> Result<SignedCallResult, Error>;
 
+    /// Returns `true` if the implementation can sign transactions carrying EIP-4844 blobs.
+    fn supports_blob_transactions(&self) -> bool {
+        false
+    }
+
     /// Returns the nonce of the `Self::sender_account()` at the specified block.
     async fn nonce_at(&self, block: BlockNumber, component: &'static str) -> Result<U256, Error> {
         self.nonce_at_for_account(self.sender_account(), block, component)
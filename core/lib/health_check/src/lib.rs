@@ -1,11 +1,29 @@
 /// Public re-export for other crates to be able to implement the interface.
 pub use async_trait::async_trait;
 
+use std::sync::Arc;
+
 /// Interface to be used for healthchecks
 /// There's a list of health checks that are looped in the /healthcheck endpoint to verify status
 #[async_trait]
 pub trait CheckHealth: Send + Sync + 'static {
     async fn check_health(&self) -> CheckHealthStatus;
+
+    /// Human-readable name of the component being checked.
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+}
+
+#[async_trait]
+impl<T: CheckHealth + ?Sized> CheckHealth for Arc<T> {
+    async fn check_health(&self) -> CheckHealthStatus {
+        (**self).check_health().await
+    }
+
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
 }
 
 /// Used to return health status when checked.
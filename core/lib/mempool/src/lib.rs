@@ -2,5 +2,5 @@ mod mempool_store;
 #[cfg(test)]
 mod tests;
 mod types;
-pub use mempool_store::{MempoolInfo, MempoolStore};
-pub use types::L2TxFilter;
+pub use mempool_store::{MempoolInfo, MempoolSnapshot, MempoolStats, MempoolStore};
+pub use types::{FilterMismatch, FilteredTxStats, L2TxFilter};
@@ -1,9 +1,9 @@
-use crate::types::{AccountTransactions, L2TxFilter, MempoolScore};
+use crate::types::{AccountTransactions, FilteredTxStats, L2TxFilter, MempoolScore};
 use std::collections::hash_map::Entry;
 use std::collections::{BTreeSet, HashMap, HashSet};
 
 use zksync_types::{
-    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction,
+    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction, H256,
 };
 
 #[derive(Debug, Default)]
@@ -17,15 +17,55 @@ pub struct MempoolStore {
     /// Next priority operation
     next_priority_id: PriorityOpId,
     stashed_accounts: Vec<Address>,
+    /// Hashes of L2 transactions dropped from the mempool because their `valid_until` deadline has passed
+    expired_transactions: Vec<H256>,
     /// number of l2 transactions in the mempool
     size: u64,
+    /// number of l2 transactions in the mempool that are ready for execution;
+    /// the remaining `size - pending_size` transactions are queued behind nonce gaps
+    pending_size: u64,
     capacity: u64,
+    /// max difference between the transaction nonce and the account nonce in the mempool.
+    /// Transactions further ahead are deferred until the gap is closed
+    max_nonce_lookahead: Option<u32>,
+    /// L2 transactions not inserted because their nonce exceeds `max_nonce_lookahead`, grouped by initiator address.
+    /// Other transactions of these accounts are unaffected
+    deferred_transactions: HashMap<Address, Vec<(Nonce, H256)>>,
+    /// Numbers of transactions skipped because they didn't match the filter, grouped by the reason.
+    filtered_tx_stats: FilteredTxStats,
+    /// Filter used during the latest attempt to fetch a transaction.
+    last_filter: Option<L2TxFilter>,
 }
 
 #[derive(Debug)]
 pub struct MempoolInfo {
     pub stashed_accounts: Vec<Address>,
     pub purged_accounts: Vec<Address>,
+    pub expired_transactions: Vec<H256>,
+    /// Hashes of deferred L2 transactions that are now within the nonce lookahead and should be reloaded.
+    pub unblocked_transactions: Vec<H256>,
+}
+
+/// Point-in-time copy of the mempool contents used to warm up a standby sequencer. L1 transactions
+/// are not included since they are loaded from L1 independently by each sequencer.
+#[derive(Debug, Clone)]
+pub struct MempoolSnapshot {
+    pub next_priority_id: PriorityOpId,
+    /// L2 transactions ordered by the initiator account and nonce.
+    pub l2_transactions: Vec<L2Tx>,
+    /// Filter used during the latest attempt to fetch a transaction from the mempool.
+    pub last_filter: Option<L2TxFilter>,
+}
+
+/// Current sizes of the mempool sub-pools.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MempoolStats {
+    /// Number of pending L1 transactions.
+    pub l1_transactions: u64,
+    /// Number of L2 transactions that can be executed right away.
+    pub pending_l2_transactions: u64,
+    /// Number of L2 transactions waiting for a nonce gap to be closed.
+    pub queued_l2_transactions: u64,
 }
 
 impl MempoolStore {
@@ -36,11 +76,23 @@ impl MempoolStore {
             l2_priority_queue: BTreeSet::new(),
             next_priority_id,
             stashed_accounts: vec![],
+            expired_transactions: vec![],
             size: 0,
+            pending_size: 0,
             capacity,
+            max_nonce_lookahead: None,
+            deferred_transactions: HashMap::new(),
+            filtered_tx_stats: FilteredTxStats::default(),
+            last_filter: None,
         }
     }
 
+    /// Limits how far ahead of the account nonce L2 transactions are accepted.
+    pub fn with_max_nonce_lookahead(mut self, max_nonce_lookahead: u32) -> Self {
+        self.max_nonce_lookahead = Some(max_nonce_lookahead);
+        self
+    }
+
     /// Inserts batch of new transactions to mempool
     /// `initial_nonces` provides current committed nonce information to mempool
     /// variable is used only if account is not present in mempool yet and we have to bootstrap it
@@ -90,16 +142,40 @@ impl MempoolStore {
         initial_nonces: &HashMap<Address, Nonce>,
     ) {
         let account = transaction.initiator_account();
+        if self.stashed_accounts.contains(&account) {
+            // All transactions of the account will be reloaded from the database.
+            return;
+        }
 
-        let metadata = match self.l2_transactions_per_account.entry(account) {
-            Entry::Occupied(mut txs) => txs.get_mut().insert(transaction),
+        let account_txs = match self.l2_transactions_per_account.entry(account) {
+            Entry::Occupied(txs) => txs.into_mut(),
             Entry::Vacant(entry) => {
                 let account_nonce = initial_nonces.get(&account).cloned().unwrap_or(Nonce(0));
-                entry
-                    .insert(AccountTransactions::new(account_nonce))
-                    .insert(transaction)
+                entry.insert(AccountTransactions::new(account_nonce))
             }
         };
+        let nonce = transaction.common_data.nonce;
+        if let Some(lookahead) = self.max_nonce_lookahead {
+            if nonce.0 > account_txs.nonce().0.saturating_add(lookahead) {
+                vlog::debug!(
+                    "deferring transaction {:?} of account {:?}: nonce {} is too far ahead of {}",
+                    transaction.hash(),
+                    account,
+                    nonce,
+                    account_txs.nonce()
+                );
+                metrics::increment_counter!("server.state_keeper.mempool_nonce_lookahead_exceeded");
+                self.deferred_transactions
+                    .entry(account)
+                    .or_default()
+                    .push((nonce, transaction.hash()));
+                return;
+            }
+        }
+
+        let pending_len = account_txs.pending_len();
+        let metadata = account_txs.insert(transaction);
+        self.pending_size += (account_txs.pending_len() - pending_len) as u64;
         if let Some(score) = metadata.previous_score {
             self.l2_priority_queue.remove(&score);
         }
@@ -121,14 +197,66 @@ impl MempoolStore {
                 .is_some()
     }
 
-    /// Returns next transaction for execution from mempool
+    /// Returns `true` if the next priority operation is in the mempool.
+    pub fn has_next_l1_transaction(&self) -> bool {
+        self.l1_transactions.contains_key(&self.next_priority_id)
+    }
+
+    /// Returns the next priority operation for execution, ignoring L2 transactions.
+    /// Used in the priority mode, in which L1 batches consist only of L1 transactions.
+    pub fn next_l1_transaction(&mut self) -> Option<Transaction> {
+        let transaction = self.l1_transactions.remove(&self.next_priority_id)?;
+        self.next_priority_id += 1;
+        Some(transaction.into())
+    }
+
+    /// Returns next transaction for execution from mempool. Deadlines of L2 transactions are not checked;
+    /// use [`Self::next_transaction_at()`] to skip expired transactions.
     pub fn next_transaction(&mut self, filter: &L2TxFilter) -> Option<Transaction> {
+        self.last_filter = Some(filter.clone());
         if let Some(transaction) = self.l1_transactions.remove(&self.next_priority_id) {
             self.next_priority_id += 1;
             return Some(transaction.into());
         }
+        self.next_l2_transaction(filter).map(Into::into)
+    }
 
+    /// Returns next transaction for execution from mempool, skipping L2 transactions that are expired
+    /// at the specified UNIX timestamp (in seconds), which should be the timestamp of the miniblock
+    /// the transaction will be included into. Expired transactions are dropped in the same way as transactions
+    /// rejected by the state keeper: subsequent transactions of the account remain in the mempool,
+    /// waiting for the nonce gap to be closed.
+    pub fn next_transaction_at(
+        &mut self,
+        filter: &L2TxFilter,
+        timestamp: u64,
+    ) -> Option<Transaction> {
+        self.last_filter = Some(filter.clone());
+        if let Some(transaction) = self.l1_transactions.remove(&self.next_priority_id) {
+            self.next_priority_id += 1;
+            return Some(transaction.into());
+        }
+
+        loop {
+            let transaction = self.next_l2_transaction(filter)?;
+            if !transaction.common_data.is_expired_at(timestamp) {
+                return Some(transaction.into());
+            }
+            vlog::debug!(
+                "dropping expired transaction {:?} (valid until {:?}, miniblock timestamp {timestamp})",
+                transaction.hash(),
+                transaction.common_data.valid_until
+            );
+            metrics::increment_counter!("server.state_keeper.mempool_expired_txs");
+            self.expired_transactions.push(transaction.hash());
+            self.rollback(&transaction.into());
+            self.collect_stats();
+        }
+    }
+
+    fn next_l2_transaction(&mut self, filter: &L2TxFilter) -> Option<L2Tx> {
         let mut removed = 0;
+        let mut removed_pending = 0;
         // We want to fetch the next transaction that would match the fee requirements.
         let tx_pointer = self
             .l2_priority_queue
@@ -143,11 +271,15 @@ impl MempoolStore {
             .into_iter()
             .skip(1)
         {
-            removed += self
+            if let Some(mismatch) = stashed_pointer.filter_mismatch(filter) {
+                self.filtered_tx_stats.record(mismatch);
+            }
+            let account_txs = self
                 .l2_transactions_per_account
                 .remove(&stashed_pointer.account)
-                .expect("mempool: dangling pointer in priority queue")
-                .len();
+                .expect("mempool: dangling pointer in priority queue");
+            removed += account_txs.len();
+            removed_pending += account_txs.pending_len();
 
             self.stashed_accounts.push(stashed_pointer.account);
         }
@@ -165,8 +297,12 @@ impl MempoolStore {
             .size
             .checked_sub((removed + 1) as u64)
             .expect("mempool size can't be negative");
+        self.pending_size = self
+            .pending_size
+            .checked_sub((removed_pending + 1) as u64)
+            .expect("mempool pending size can't be negative");
         self.collect_stats();
-        Some(transaction.into())
+        Some(transaction)
     }
 
     /// When a state_keeper starts the block over after a rejected transaction,
@@ -180,12 +316,15 @@ impl MempoolStore {
                 self.next_priority_id = self.next_priority_id.min(data.serial_id);
             }
             ExecuteTransactionCommon::L2(_) => {
-                if let Some(score) = self
+                let account_txs = self
                     .l2_transactions_per_account
                     .get_mut(&tx.initiator_account())
-                    .expect("account is not available in mempool")
-                    .reset(tx)
-                {
+                    .expect("account is not available in mempool");
+                let pending_len = account_txs.pending_len();
+                let successor_score = account_txs.reset(tx);
+                self.pending_size =
+                    self.pending_size + account_txs.pending_len() as u64 - pending_len as u64;
+                if let Some(score) = successor_score {
                     self.l2_priority_queue.remove(&score);
                 }
             }
@@ -193,18 +332,107 @@ impl MempoolStore {
     }
 
     pub fn get_mempool_info(&mut self) -> MempoolInfo {
+        let stashed_accounts = std::mem::take(&mut self.stashed_accounts);
+        let purged_accounts = self.gc();
+        // Deferred transactions of stashed and purged accounts are handled together with other account transactions.
+        for account in stashed_accounts.iter().chain(&purged_accounts) {
+            self.deferred_transactions.remove(account);
+        }
         MempoolInfo {
-            stashed_accounts: std::mem::take(&mut self.stashed_accounts),
-            purged_accounts: self.gc(),
+            stashed_accounts,
+            purged_accounts,
+            expired_transactions: std::mem::take(&mut self.expired_transactions),
+            unblocked_transactions: self.take_unblocked_transactions(),
+        }
+    }
+
+    /// Returns hashes of deferred transactions that are within the nonce lookahead of their account
+    /// (or whose account is no longer tracked by the mempool) and forgets about them.
+    fn take_unblocked_transactions(&mut self) -> Vec<H256> {
+        let lookahead = match self.max_nonce_lookahead {
+            Some(lookahead) => lookahead,
+            None => return vec![],
+        };
+        let l2_transactions_per_account = &self.l2_transactions_per_account;
+        let mut unblocked = vec![];
+        self.deferred_transactions.retain(|account, deferred_txs| {
+            let max_nonce = l2_transactions_per_account
+                .get(account)
+                .map(|account_txs| account_txs.nonce().0.saturating_add(lookahead));
+            deferred_txs.retain(|&(nonce, tx_hash)| {
+                let is_unblocked = max_nonce.map_or(true, |max_nonce| nonce.0 <= max_nonce);
+                if is_unblocked {
+                    unblocked.push(tx_hash);
+                }
+                !is_unblocked
+            });
+            !deferred_txs.is_empty()
+        });
+        unblocked
+    }
+
+    /// Returns the number of L2 transactions that can be inserted before the mempool reaches its capacity.
+    pub fn remaining_capacity(&self) -> u64 {
+        self.capacity.saturating_sub(self.size)
+    }
+
+    /// Removes all transactions from the mempool. The next priority operation ID, capacity and other
+    /// configuration are retained.
+    pub fn clear(&mut self) {
+        self.l1_transactions.clear();
+        self.l2_transactions_per_account.clear();
+        self.l2_priority_queue.clear();
+        self.stashed_accounts.clear();
+        self.expired_transactions.clear();
+        self.deferred_transactions.clear();
+        self.size = 0;
+        self.pending_size = 0;
+        self.collect_stats();
+    }
+
+    pub fn stats(&self) -> MempoolStats {
+        MempoolStats {
+            l1_transactions: self.l1_transactions.len() as u64,
+            pending_l2_transactions: self.pending_size,
+            queued_l2_transactions: self.size - self.pending_size,
         }
     }
 
+    /// Copies L2 transactions currently in the mempool together with the filter state.
+    pub fn snapshot(&self) -> MempoolSnapshot {
+        let mut l2_transactions: Vec<_> = self
+            .l2_transactions_per_account
+            .values()
+            .flat_map(AccountTransactions::transactions)
+            .cloned()
+            .collect();
+        l2_transactions.sort_unstable_by_key(|tx| (tx.initiator_account(), tx.common_data.nonce));
+        MempoolSnapshot {
+            next_priority_id: self.next_priority_id,
+            l2_transactions,
+            last_filter: self.last_filter.clone(),
+        }
+    }
+
+    /// Returns cumulative numbers of transactions skipped because they didn't match the filter.
+    pub fn filtered_tx_stats(&self) -> FilteredTxStats {
+        self.filtered_tx_stats
+    }
+
     fn collect_stats(&self) {
         metrics::gauge!(
             "server.state_keeper.mempool_l1_size",
             self.l1_transactions.len() as f64
         );
         metrics::gauge!("server.state_keeper.mempool_l2_size", self.size as f64);
+        metrics::gauge!(
+            "server.state_keeper.mempool_l2_pending_size",
+            self.pending_size as f64
+        );
+        metrics::gauge!(
+            "server.state_keeper.mempool_l2_queued_size",
+            (self.size - self.pending_size) as f64
+        );
         metrics::gauge!(
             "server.state_keeper.mempool_l2_priority_queue_size",
             self.l2_priority_queue.len() as f64
@@ -232,6 +460,10 @@ impl MempoolStore {
                 .l2_transactions_per_account
                 .iter()
                 .fold(0, |agg, (_, tnxs)| agg + tnxs.len() as u64);
+            self.pending_size = self
+                .l2_transactions_per_account
+                .iter()
+                .fold(0, |agg, (_, tnxs)| agg + tnxs.pending_len() as u64);
             return drained.into_keys().collect();
         }
         vec![]
@@ -1,4 +1,7 @@
-use crate::{mempool_store::MempoolStore, types::L2TxFilter};
+use crate::{
+    mempool_store::{MempoolStats, MempoolStore},
+    types::{FilteredTxStats, L2TxFilter},
+};
 use std::collections::{HashMap, HashSet};
 use std::iter::FromIterator;
 use zksync_types::fee::Fee;
@@ -111,6 +114,36 @@ fn prioritize_l1_txns() {
         .is_l1())
 }
 
+#[test]
+fn only_l1_txns_in_priority_mode() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let account = Address::random();
+    let transactions = vec![
+        gen_l2_tx(account, Nonce(0)),
+        gen_l1_tx(PriorityOpId(0)),
+        gen_l1_tx(PriorityOpId(1)),
+    ];
+    mempool.insert(transactions, HashMap::new());
+
+    for idx in 0..2 {
+        assert!(mempool.has_next_l1_transaction());
+        let tx = mempool.next_l1_transaction().unwrap();
+        match tx.common_data {
+            ExecuteTransactionCommon::L1(data) => {
+                assert_eq!(data.serial_id, PriorityOpId(idx));
+            }
+            _ => unreachable!("expected L1 transaction"),
+        }
+    }
+    assert!(!mempool.has_next_l1_transaction());
+    assert!(mempool.next_l1_transaction().is_none());
+    // L2 transactions are still available outside the priority mode.
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account, 0)
+    );
+}
+
 #[test]
 fn l1_txns_priority_id() {
     let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
@@ -279,6 +312,116 @@ fn filtering() {
     assert_eq!(mempool.next_transaction(&filter_zero), None);
 }
 
+#[test]
+fn filtered_txs_are_counted() {
+    let filter = L2TxFilter {
+        l1_gas_price: 0u64,
+        fee_per_gas: 0u64,
+        gas_per_pubdata: 1u32,
+    };
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    // The older transaction doesn't match the filter, so it's skipped when picking the newer one.
+    mempool.insert(
+        gen_transactions_for_filtering(vec![
+            (account0, Nonce(0), unix_timestamp_ms() - 10, 0),
+            (account1, Nonce(0), unix_timestamp_ms(), 1),
+        ]),
+        HashMap::new(),
+    );
+
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 0));
+    assert_eq!(
+        mempool.filtered_tx_stats(),
+        FilteredTxStats {
+            fee_too_low: 0,
+            gas_per_pubdata_too_low: 1,
+        }
+    );
+}
+
+#[test]
+fn queued_txs_are_promoted() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let account = Address::random();
+    let transactions = vec![
+        gen_l2_tx(account, Nonce(0)),
+        gen_l2_tx(account, Nonce(2)),
+        gen_l2_tx(account, Nonce(3)),
+    ];
+    mempool.insert(transactions, HashMap::new());
+    assert_eq!(
+        mempool.stats(),
+        MempoolStats {
+            l1_transactions: 0,
+            pending_l2_transactions: 1,
+            queued_l2_transactions: 2,
+        }
+    );
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account, 0)
+    );
+    assert!(mempool.next_transaction(&L2TxFilter::default()).is_none());
+    assert_eq!(mempool.stats().pending_l2_transactions, 0);
+    assert_eq!(mempool.stats().queued_l2_transactions, 2);
+
+    // close the gap
+    mempool.insert(vec![gen_l2_tx(account, Nonce(1))], HashMap::new());
+    assert_eq!(mempool.stats().pending_l2_transactions, 3);
+    assert_eq!(mempool.stats().queued_l2_transactions, 0);
+    for nonce in 1..=3 {
+        assert_eq!(
+            view(mempool.next_transaction(&L2TxFilter::default())),
+            (account, nonce)
+        );
+    }
+    assert_eq!(mempool.stats(), MempoolStats::default());
+}
+
+#[test]
+fn nonce_lookahead() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100).with_max_nonce_lookahead(2);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let mut far_ahead_tx = gen_l2_tx(account1, Nonce(3));
+    let far_ahead_tx_hash = H256::repeat_byte(1);
+    match &mut far_ahead_tx.common_data {
+        ExecuteTransactionCommon::L2(data) => {
+            data.set_input(vec![], far_ahead_tx_hash);
+        }
+        _ => unreachable!(),
+    }
+    let transactions = vec![
+        gen_l2_tx(account0, Nonce(0)),
+        gen_l2_tx(account0, Nonce(2)),
+        gen_l2_tx(account1, Nonce(0)),
+        far_ahead_tx,
+        gen_l2_tx(account1, Nonce(1)),
+    ];
+    mempool.insert(transactions, HashMap::new());
+    // Only the transaction too far ahead of the account nonce is deferred
+    assert_eq!(mempool.size(), 4);
+    assert_eq!(mempool.stats().pending_l2_transactions, 3);
+    assert_eq!(mempool.stats().queued_l2_transactions, 1);
+    let info = mempool.get_mempool_info();
+    assert!(info.stashed_accounts.is_empty());
+    assert!(info.unblocked_transactions.is_empty());
+
+    let mut executed: Vec<_> = (0..3)
+        .map(|_| view(mempool.next_transaction(&L2TxFilter::default())))
+        .collect();
+    executed.sort_unstable_by_key(|&(account, nonce)| (account == account1, nonce));
+    assert_eq!(executed, [(account0, 0), (account1, 0), (account1, 1)]);
+    assert!(mempool.next_transaction(&L2TxFilter::default()).is_none());
+
+    // The account nonce has advanced, so the deferred transaction should be reloaded
+    let info = mempool.get_mempool_info();
+    assert_eq!(info.unblocked_transactions, [far_ahead_tx_hash]);
+    assert!(mempool.get_mempool_info().unblocked_transactions.is_empty());
+}
+
 #[test]
 fn stashed_accounts() {
     let filter_non_zero = L2TxFilter {
@@ -359,6 +502,141 @@ fn mempool_capacity() {
     );
 }
 
+#[test]
+fn remaining_capacity() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 5);
+    assert_eq!(mempool.remaining_capacity(), 5);
+    let account = Address::random();
+    let transactions = vec![gen_l2_tx(account, Nonce(0)), gen_l2_tx(account, Nonce(1))];
+    mempool.insert(transactions, HashMap::new());
+    assert_eq!(mempool.remaining_capacity(), 3);
+
+    // L1 transactions do not count towards the capacity.
+    mempool.insert(vec![gen_l1_tx(PriorityOpId(0))], HashMap::new());
+    assert_eq!(mempool.remaining_capacity(), 3);
+}
+
+#[test]
+fn clear_mempool() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 5);
+    let account = Address::random();
+    let transactions = vec![
+        gen_l1_tx(PriorityOpId(0)),
+        gen_l1_tx(PriorityOpId(1)),
+        gen_l2_tx(account, Nonce(0)),
+        gen_l2_tx(account, Nonce(1)),
+    ];
+    mempool.insert(transactions, HashMap::new());
+    // Consume the first priority operation.
+    assert!(mempool.next_transaction(&L2TxFilter::default()).is_some());
+
+    mempool.clear();
+    assert_eq!(mempool.remaining_capacity(), 5);
+    assert_eq!(mempool.next_transaction(&L2TxFilter::default()), None);
+
+    // The next expected priority operation ID is retained.
+    mempool.insert(vec![gen_l1_tx(PriorityOpId(1))], HashMap::new());
+    assert!(mempool.next_transaction(&L2TxFilter::default()).is_some());
+}
+
+#[test]
+fn expired_txs_are_skipped() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let mut expiring_tx = gen_l2_tx_with_timestamp(account0, Nonce(0), unix_timestamp_ms() + 10);
+    let expiring_tx_hash = H256::repeat_byte(1);
+    match &mut expiring_tx.common_data {
+        ExecuteTransactionCommon::L2(data) => {
+            data.set_input(vec![], expiring_tx_hash);
+            data.valid_until = Some(100);
+        }
+        _ => unreachable!(),
+    }
+    let transactions = vec![
+        expiring_tx,
+        gen_l2_tx_with_timestamp(account0, Nonce(1), unix_timestamp_ms() + 10),
+        gen_l2_tx(account1, Nonce(0)),
+    ];
+    mempool.insert(transactions.clone(), HashMap::new());
+    assert_eq!(
+        view(mempool.next_transaction_at(&L2TxFilter::default(), 99)),
+        (account1, 0)
+    );
+    assert_eq!(
+        view(mempool.next_transaction_at(&L2TxFilter::default(), 99)),
+        (account0, 0)
+    );
+
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    mempool.insert(transactions, HashMap::new());
+    assert_eq!(
+        view(mempool.next_transaction_at(&L2TxFilter::default(), 100)),
+        (account1, 0)
+    );
+    // The successor of the expired transaction is kept, but it waits for the nonce gap to be closed.
+    assert_eq!(
+        mempool.next_transaction_at(&L2TxFilter::default(), 100),
+        None
+    );
+    assert_eq!(mempool.size(), 1);
+    assert_eq!(mempool.stats().queued_l2_transactions, 1);
+    let info = mempool.get_mempool_info();
+    assert_eq!(info.expired_transactions, [expiring_tx_hash]);
+    assert!(info.stashed_accounts.is_empty());
+
+    // A replacement for the expired transaction unblocks the successor.
+    mempool.insert(vec![gen_l2_tx(account0, Nonce(0))], HashMap::new());
+    assert_eq!(
+        view(mempool.next_transaction_at(&L2TxFilter::default(), 100)),
+        (account0, 0)
+    );
+    assert_eq!(
+        view(mempool.next_transaction_at(&L2TxFilter::default(), 100)),
+        (account0, 1)
+    );
+}
+
+#[test]
+fn mempool_snapshot() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let snapshot = mempool.snapshot();
+    assert_eq!(snapshot.next_priority_id, PriorityOpId(0));
+    assert!(snapshot.l2_transactions.is_empty());
+    assert_eq!(snapshot.last_filter, None);
+
+    let account0 = Address::repeat_byte(1);
+    let account1 = Address::repeat_byte(2);
+    let transactions = vec![
+        gen_l1_tx(PriorityOpId(0)),
+        gen_l2_tx(account1, Nonce(0)),
+        gen_l2_tx(account0, Nonce(2)),
+        gen_l2_tx(account0, Nonce(0)),
+        gen_l2_tx(account0, Nonce(1)),
+    ];
+    mempool.insert(transactions, HashMap::new());
+    let filter = L2TxFilter {
+        l1_gas_price: 1_000,
+        fee_per_gas: 0,
+        gas_per_pubdata: 0,
+    };
+    // Consume the priority operation.
+    mempool.next_transaction(&filter).unwrap();
+
+    let snapshot = mempool.snapshot();
+    assert_eq!(snapshot.next_priority_id, PriorityOpId(1));
+    assert_eq!(snapshot.last_filter, Some(filter));
+    let l2_transactions: Vec<_> = snapshot
+        .l2_transactions
+        .into_iter()
+        .map(|tx| view(Some(tx.into())))
+        .collect();
+    assert_eq!(
+        l2_transactions,
+        [(account0, 0), (account0, 1), (account0, 2), (account1, 0)]
+    );
+}
+
 fn gen_l2_tx(address: Address, nonce: Nonce) -> Transaction {
     gen_l2_tx_with_timestamp(address, nonce, unix_timestamp_ms())
 }
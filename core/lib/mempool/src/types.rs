@@ -12,6 +12,9 @@ pub(crate) struct AccountTransactions {
     /// account nonce in mempool
     /// equals to committed nonce in db + number of transactions sent to state keeper
     nonce: Nonce,
+    /// number of transactions with consecutive nonces starting from `nonce`.
+    /// These transactions are ready for execution; the rest are queued until the nonce gap is closed
+    pending_len: usize,
 }
 
 impl AccountTransactions {
@@ -19,9 +22,19 @@ impl AccountTransactions {
         Self {
             transactions: HashMap::new(),
             nonce,
+            pending_len: 0,
         }
     }
 
+    pub fn nonce(&self) -> Nonce {
+        self.nonce
+    }
+
+    /// Iterates over transactions of the account in no particular order.
+    pub fn transactions(&self) -> impl Iterator<Item = &L2Tx> + '_ {
+        self.transactions.values()
+    }
+
     /// Inserts new transaction for given account. Returns insertion metadata
     pub fn insert(&mut self, transaction: L2Tx) -> InsertionMetadata {
         let mut metadata = InsertionMetadata::default();
@@ -40,6 +53,8 @@ impl AccountTransactions {
             metadata.new_score = Some(new_score);
             metadata.previous_score = previous_score;
         }
+        // Transaction may close the nonce gap, promoting queued transactions to pending ones
+        self.extend_pending();
         metadata
     }
 
@@ -51,6 +66,7 @@ impl AccountTransactions {
             .remove(&self.nonce)
             .expect("missing transaction in mempool");
         self.nonce += 1;
+        self.pending_len -= 1;
         let score = self
             .transactions
             .get(&self.nonce)
@@ -65,6 +81,8 @@ impl AccountTransactions {
             .nonce()
             .expect("nonce is not set for L2 transaction");
         self.nonce = self.nonce.min(tx_nonce);
+        self.pending_len = 0;
+        self.extend_pending();
         self.transactions
             .get(&(tx_nonce + 1))
             .map(Self::score_for_transaction)
@@ -74,6 +92,27 @@ impl AccountTransactions {
         self.transactions.len()
     }
 
+    /// Number of transactions that can be executed right away.
+    pub fn pending_len(&self) -> usize {
+        self.pending_len
+    }
+
+    /// Score of the transaction that would be executed next, if any.
+    pub fn current_score(&self) -> Option<MempoolScore> {
+        self.transactions
+            .get(&self.nonce)
+            .map(Self::score_for_transaction)
+    }
+
+    fn extend_pending(&mut self) {
+        while self
+            .transactions
+            .contains_key(&(self.nonce + self.pending_len as u32))
+        {
+            self.pending_len += 1;
+        }
+    }
+
     fn score_for_transaction(transaction: &L2Tx) -> MempoolScore {
         MempoolScore {
             account: transaction.initiator_account(),
@@ -98,8 +137,56 @@ pub struct MempoolScore {
 impl MempoolScore {
     /// Checks whether transaction matches requirements provided by state keeper.
     pub fn matches_filter(&self, filter: &L2TxFilter) -> bool {
-        self.fee_data.max_fee_per_gas >= U256::from(filter.fee_per_gas)
-            && self.fee_data.gas_per_pubdata_limit >= U256::from(filter.gas_per_pubdata)
+        self.filter_mismatch(filter).is_none()
+    }
+
+    /// Returns the reason why the transaction doesn't match the filter, or `None` if it matches.
+    pub fn filter_mismatch(&self, filter: &L2TxFilter) -> Option<FilterMismatch> {
+        if self.fee_data.max_fee_per_gas < U256::from(filter.fee_per_gas) {
+            Some(FilterMismatch::FeeTooLow)
+        } else if self.fee_data.gas_per_pubdata_limit < U256::from(filter.gas_per_pubdata) {
+            Some(FilterMismatch::GasPerPubdataTooLow)
+        } else {
+            None
+        }
+    }
+}
+
+/// Reason why an L2 transaction doesn't match the [`L2TxFilter`] provided by the state keeper.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FilterMismatch {
+    /// Max fee per gas is lower than the current base fee.
+    FeeTooLow,
+    /// Gas per pubdata limit is lower than the currently required value.
+    GasPerPubdataTooLow,
+}
+
+impl FilterMismatch {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::FeeTooLow => "fee_too_low",
+            Self::GasPerPubdataTooLow => "gas_per_pubdata_too_low",
+        }
+    }
+}
+
+/// Cumulative numbers of L2 transactions skipped by the state keeper because they didn't match the filter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FilteredTxStats {
+    pub fee_too_low: u64,
+    pub gas_per_pubdata_too_low: u64,
+}
+
+impl FilteredTxStats {
+    pub(crate) fn record(&mut self, mismatch: FilterMismatch) {
+        match mismatch {
+            FilterMismatch::FeeTooLow => self.fee_too_low += 1,
+            FilterMismatch::GasPerPubdataTooLow => self.gas_per_pubdata_too_low += 1,
+        }
+        metrics::increment_counter!(
+            "server.state_keeper.mempool_filtered_txs",
+            "reason" => mismatch.as_str()
+        );
     }
 }
 
@@ -128,7 +215,7 @@ pub(crate) struct InsertionMetadata {
 
 /// Structure that can be used by state keeper to describe
 /// criteria for transaction it wants to fetch.
-#[derive(Debug, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct L2TxFilter {
     /// L1 gas price.
     pub l1_gas_price: u64,
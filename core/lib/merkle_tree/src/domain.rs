@@ -2,7 +2,7 @@
 
 use rayon::{ThreadPool, ThreadPoolBuilder};
 
-use std::{borrow::Borrow, num::NonZeroU32};
+use std::{borrow::Borrow, collections::HashMap, num::NonZeroU32};
 
 use crate::{
     storage::{MerkleTreeColumnFamily, PatchSet, Patched, RocksDBWrapper},
@@ -28,6 +28,8 @@ pub struct TreeMetadata {
     pub initial_writes: Vec<InitialStorageWrite>,
     /// Repeated writes performed in the processed block.
     pub repeated_writes: Vec<RepeatedStorageWrite>,
+    /// Enumeration indices of the keys updated by repeated writes, keyed by the tree key.
+    pub repeated_write_indices: HashMap<Key, u64>,
     /// Witness information.
     pub witness: Option<PrepareBasicCircuitsJob>,
 }
@@ -221,7 +223,8 @@ impl ZkSyncTree {
             };
             Some((key, value))
         });
-        let (initial_writes, repeated_writes) = Self::extract_writes(logs, kvs);
+        let (initial_writes, repeated_writes, repeated_write_indices) =
+            Self::extract_writes(logs, kvs);
 
         vlog::info!(
             "Processed batch #{block_number}; root hash is {root_hash}, \
@@ -237,6 +240,7 @@ impl ZkSyncTree {
             rollup_last_leaf_index: output.leaf_count + 1,
             initial_writes,
             repeated_writes,
+            repeated_write_indices,
             witness: Some(witness),
         }
     }
@@ -257,15 +261,21 @@ impl ZkSyncTree {
     fn extract_writes(
         logs: impl Iterator<Item = TreeLogEntry>,
         kvs: impl Iterator<Item = (Key, ValueHash)>,
-    ) -> (Vec<InitialStorageWrite>, Vec<RepeatedStorageWrite>) {
+    ) -> (
+        Vec<InitialStorageWrite>,
+        Vec<RepeatedStorageWrite>,
+        HashMap<Key, u64>,
+    ) {
         let mut initial_writes = vec![];
         let mut repeated_writes = vec![];
+        let mut repeated_write_indices = HashMap::new();
         for (log_entry, (key, value)) in logs.zip(kvs) {
             match log_entry {
                 TreeLogEntry::Inserted { .. } => {
                     initial_writes.push(InitialStorageWrite { key, value });
                 }
                 TreeLogEntry::Updated { leaf_index, .. } => {
+                    repeated_write_indices.insert(key, leaf_index);
                     repeated_writes.push(RepeatedStorageWrite {
                         index: leaf_index,
                         value,
@@ -274,7 +284,7 @@ impl ZkSyncTree {
                 TreeLogEntry::Read { .. } | TreeLogEntry::ReadMissingKey => {}
             }
         }
-        (initial_writes, repeated_writes)
+        (initial_writes, repeated_writes, repeated_write_indices)
     }
 
     fn process_block_lightweight(&mut self, storage_logs: &[WitnessStorageLog]) -> TreeMetadata {
@@ -291,7 +301,7 @@ impl ZkSyncTree {
         } else {
             self.tree.extend(kvs.clone())
         };
-        let (initial_writes, repeated_writes) =
+        let (initial_writes, repeated_writes, repeated_write_indices) =
             Self::extract_writes(output.logs.into_iter(), kvs.into_iter());
 
         vlog::info!(
@@ -309,6 +319,7 @@ impl ZkSyncTree {
             rollup_last_leaf_index: output.leaf_count + 1,
             initial_writes,
             repeated_writes,
+            repeated_write_indices,
             witness: None,
         }
     }
@@ -90,23 +90,43 @@ impl<'a, const LEAF_SIZE: usize> MiniMerkleTree<'a, LEAF_SIZE> {
         if self.hashes.is_empty() {
             H256::zero()
         } else {
-            self.compute_merkle_root_and_path(0, None)
+            self.compute_merkle_root_and_paths(&mut [], &mut [])
         }
     }
 
     /// Returns the root hash and the Merkle proof for a leaf with the specified 0-based `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
     pub fn merkle_root_and_path(self, index: usize) -> (H256, Vec<H256>) {
-        let mut merkle_path = Vec::with_capacity(MAX_TREE_DEPTH);
-        let root_hash = self.compute_merkle_root_and_path(index, Some(&mut merkle_path));
-        (root_hash, merkle_path)
+        let (root_hash, mut merkle_paths) = self.merkle_root_and_paths(&[index]);
+        (root_hash, merkle_paths.pop().unwrap())
     }
 
-    fn compute_merkle_root_and_path(
+    /// Returns the root hash and the Merkle proofs for leaves with the specified 0-based `indices`.
+    /// The tree is traversed only once, so this is more efficient than calling
+    /// [`Self::merkle_root_and_path()`] for each index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `indices` are empty or any of them is out of bounds.
+    pub fn merkle_root_and_paths(self, indices: &[usize]) -> (H256, Vec<Vec<H256>>) {
+        assert!(!indices.is_empty(), "no tree leaf indices supplied");
+        let mut indices = indices.to_vec();
+        let mut merkle_paths = vec![Vec::with_capacity(MAX_TREE_DEPTH); indices.len()];
+        let root_hash = self.compute_merkle_root_and_paths(&mut indices, &mut merkle_paths);
+        (root_hash, merkle_paths)
+    }
+
+    fn compute_merkle_root_and_paths(
         self,
-        mut index: usize,
-        mut merkle_path: Option<&mut Vec<H256>>,
+        indices: &mut [usize],
+        merkle_paths: &mut [Vec<H256>],
     ) -> H256 {
-        assert!(index < self.hashes.len(), "invalid tree leaf index");
+        for &index in &*indices {
+            assert!(index < self.hashes.len(), "invalid tree leaf index");
+        }
 
         let depth = tree_depth_by_size(self.tree_size);
 
@@ -115,14 +135,15 @@ impl<'a, const LEAF_SIZE: usize> MiniMerkleTree<'a, LEAF_SIZE> {
         for level in 0..depth {
             let empty_hash_at_level = self.hasher.empty_subtree_hash(level);
 
-            if let Some(merkle_path) = merkle_path.as_deref_mut() {
-                let adjacent_idx = index ^ 1;
+            for (index, merkle_path) in indices.iter_mut().zip(&mut *merkle_paths) {
+                let adjacent_idx = *index ^ 1;
                 let adjacent_hash = if adjacent_idx < level_len {
                     hashes[adjacent_idx]
                 } else {
                     empty_hash_at_level
                 };
                 merkle_path.push(adjacent_hash);
+                *index /= 2;
             }
 
             for i in 0..(level_len / 2) {
@@ -134,7 +155,6 @@ impl<'a, const LEAF_SIZE: usize> MiniMerkleTree<'a, LEAF_SIZE> {
                     .compress(&hashes[level_len - 1], &empty_hash_at_level);
             }
 
-            index /= 2;
             level_len = level_len / 2 + level_len % 2;
         }
         hashes[0]
@@ -175,3 +175,18 @@ fn merkle_proofs_are_valid_in_larger_tree() {
         verify_merkle_proof(&item, i, 512, &path, merkle_root);
     }
 }
+
+#[test]
+fn merkle_proofs_for_multiple_indices_match_single_proofs() {
+    let leaves = (1_u8..=100).map(|byte| [byte; 88]);
+    let tree = MiniMerkleTree::new(leaves.clone(), 128);
+
+    let indices = [0, 1, 50, 99, 1];
+    let (merkle_root, paths) = tree.clone().merkle_root_and_paths(&indices);
+    assert_eq!(paths.len(), indices.len());
+    for (&index, path) in indices.iter().zip(&paths) {
+        let (expected_root, expected_path) = tree.clone().merkle_root_and_path(index);
+        assert_eq!(merkle_root, expected_root);
+        assert_eq!(*path, expected_path);
+    }
+}
@@ -1,7 +1,7 @@
 //! Stored objects.
 
 use zksync_types::{
-    proofs::{AggregationRound, PrepareBasicCircuitsJob},
+    proofs::{AggregationRound, L1BatchVmArtifacts, PrepareBasicCircuitsJob},
     zkevm_test_harness::{
         abstract_zksync_circuit::concrete_circuits::ZkSyncCircuit,
         bellman::bn256::Bn256,
@@ -72,6 +72,17 @@ impl StoredObject for PrepareBasicCircuitsJob {
     serialize_using_bincode!();
 }
 
+impl StoredObject for L1BatchVmArtifacts {
+    const BUCKET: Bucket = Bucket::WitnessInput;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("vm_artifacts_{key}.bin")
+    }
+
+    serialize_using_bincode!();
+}
+
 impl StoredObject for BlockBasicCircuits<Bn256> {
     const BUCKET: Bucket = Bucket::LeafAggregationWitnessJobs;
     type Key<'a> = L1BatchNumber;
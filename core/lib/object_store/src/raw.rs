@@ -20,6 +20,8 @@ pub enum Bucket {
     NodeAggregationWitnessJobsFri,
     SchedulerWitnessJobsFri,
     ProofsFri,
+    ArchivedPartitions,
+    DataExports,
 }
 
 impl Bucket {
@@ -35,6 +37,8 @@ impl Bucket {
             Self::NodeAggregationWitnessJobsFri => "node_aggregation_witness_jobs_fri",
             Self::SchedulerWitnessJobsFri => "scheduler_witness_jobs_fri",
             Self::ProofsFri => "proofs_fri",
+            Self::ArchivedPartitions => "archived_partitions",
+            Self::DataExports => "data_exports",
         }
     }
 }
@@ -1,26 +1,53 @@
 // necessary for usize to f64 convertions for metrics
 #![allow(clippy::cast_precision_loss)]
 
-use std::{hash::Hash, time::Instant};
+use std::{hash::Hash, mem, time::Instant};
 
-type MokaBase<K> = mini_moka::sync::Cache<K, Vec<u8>>;
+use zksync_types::{H256, U256};
+
+type MokaBase<K, V> = mini_moka::sync::Cache<K, V>;
+
+/// Value that can be stored in a [`Cache`].
+pub trait CacheValue: Clone + Send + Sync + 'static {
+    /// Approximate size of the value in bytes used for cache capacity accounting.
+    fn cache_weight(&self) -> u32;
+}
+
+impl CacheValue for Vec<u8> {
+    fn cache_weight(&self) -> u32 {
+        self.len().try_into().unwrap_or(u32::MAX)
+    }
+}
+
+impl CacheValue for Vec<U256> {
+    fn cache_weight(&self) -> u32 {
+        (self.len() * mem::size_of::<U256>())
+            .try_into()
+            .unwrap_or(u32::MAX)
+    }
+}
+
+/// Cache of contract bytecodes decoded into 32-byte words (i.e., in the form consumed by the VM decommitter),
+/// keyed by the bytecode hash. Can be shared among VM instances to avoid repeatedly loading and decoding
+/// popular contracts.
+pub type BytecodeCache = Cache<H256, Vec<U256>>;
 
 /// [`Cache`] implementation that uses LRU eviction policy.
 #[derive(Debug, Clone)]
-pub struct Cache<K: Hash + Eq + Send + Sync> {
+pub struct Cache<K: Hash + Eq + Send + Sync, V = Vec<u8>> {
     name: &'static str,
-    cache: MokaBase<K>,
+    cache: MokaBase<K, V>,
 }
 
-impl<K: Hash + Eq + Send + Sync + 'static> Cache<K> {
+impl<K: Hash + Eq + Send + Sync + 'static, V: CacheValue> Cache<K, V> {
     /// Creates a new cache.
     ///
     /// # Panics
     ///
     /// Panics if an invalid cache capacity (usize) is provided.
     pub fn new(name: &'static str, capacity_mb: usize) -> Self {
-        let cache = MokaBase::<K>::builder()
-            .weigher(|_, value| -> u32 { value.len().try_into().unwrap_or(u32::MAX) })
+        let cache = MokaBase::<K, V>::builder()
+            .weigher(|_, value: &V| -> u32 { value.cache_weight() })
             .max_capacity((capacity_mb * 1_000_000) as u64)
             .build();
 
@@ -28,7 +55,7 @@ impl<K: Hash + Eq + Send + Sync + 'static> Cache<K> {
     }
 
     /// Gets an entry and pulls it to the front if it exists.
-    pub fn get(&self, key: &K) -> Option<Vec<u8>> {
+    pub fn get(&self, key: &K) -> Option<V> {
         let start_instant = Instant::now();
         let entry = self.cache.get(key);
         metrics::histogram!(
@@ -48,7 +75,7 @@ impl<K: Hash + Eq + Send + Sync + 'static> Cache<K> {
     }
 
     /// Pushes an entry and performs LRU cache operations.
-    pub fn insert(&self, key: K, value: Vec<u8>) {
+    pub fn insert(&self, key: K, value: V) {
         let start_instant = Instant::now();
         self.cache.insert(key, value);
         metrics::histogram!(
@@ -66,3 +93,19 @@ impl<K: Hash + Eq + Send + Sync + 'static> Cache<K> {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytecode_cache_basics() {
+        let cache = BytecodeCache::new("test", 1);
+        let bytecode = vec![U256::from(1_u32), U256::from(2_u32)];
+        assert_eq!(bytecode.cache_weight(), 64);
+
+        cache.insert(H256::repeat_byte(1), bytecode.clone());
+        assert_eq!(cache.get(&H256::repeat_byte(1)), Some(bytecode));
+        assert_eq!(cache.get(&H256::repeat_byte(2)), None);
+    }
+}
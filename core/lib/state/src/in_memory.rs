@@ -10,7 +10,7 @@ use zksync_types::{
 pub const IN_MEMORY_STORAGE_DEFAULT_NETWORK_ID: u16 = 270;
 
 /// In-memory storage.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct InMemoryStorage {
     pub(crate) state: HashMap<StorageKey, StorageValue>,
     pub(crate) factory_deps: HashMap<H256, Vec<u8>>,
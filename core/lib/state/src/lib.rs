@@ -26,6 +26,7 @@ mod storage_view;
 mod test_utils;
 
 pub use self::{
+    cache::{BytecodeCache, CacheValue},
     in_memory::InMemoryStorage,
     postgres::FactoryDepsCache,
     postgres::PostgresStorage,
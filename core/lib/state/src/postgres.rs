@@ -15,6 +15,8 @@ pub struct PostgresStorage<'a> {
     connection: StorageProcessor<'a>,
     block_number: MiniblockNumber,
     l1_batch_number: L1BatchNumber,
+    /// Latest state snapshot at or before `block_number`; values are read from it and the storage logs written after it.
+    state_snapshot: Option<MiniblockNumber>,
     consider_new_l1_batch: bool,
     factory_deps_cache: Option<FactoryDepsCache>,
 }
@@ -31,12 +33,20 @@ impl<'a> PostgresStorage<'a> {
         let l1_batch_number = rt_handle
             .block_on(dal.get_provisional_l1_batch_number_of_miniblock_unchecked(block_number))
             .expect("cannot fetch L1 batch number for miniblock");
+        let state_snapshot = rt_handle
+            .block_on(
+                connection
+                    .state_snapshots_dal()
+                    .get_latest_snapshot_at(block_number),
+            )
+            .expect("cannot fetch state snapshot for miniblock");
 
         Self {
             rt_handle,
             connection,
             block_number,
             l1_batch_number,
+            state_snapshot,
             consider_new_l1_batch,
             factory_deps_cache: None,
         }
@@ -64,7 +74,7 @@ impl ReadStorage for PostgresStorage<'_> {
                     started_at.elapsed(),
                     "method" => "read_value"
                 );
-                dal.get_historical_value_unchecked(key, self.block_number)
+                dal.get_historical_value_from_snapshot(key, self.block_number, self.state_snapshot)
                     .await
             })
             .unwrap();
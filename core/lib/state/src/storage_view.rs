@@ -74,6 +74,14 @@ impl<S: ReadStorage + fmt::Debug> StorageView<S> {
         }
     }
 
+    /// Pre-fills the cache of [`ReadStorage::is_write_initial()`] checks. The cache must be obtained
+    /// for the same L1 batch.
+    #[must_use]
+    pub fn with_initial_writes(mut self, initial_writes: HashMap<StorageKey, bool>) -> Self {
+        self.initial_writes_cache = initial_writes;
+        self
+    }
+
     /// Returns a cloneable reference to this storage view.
     pub fn as_ptr(&mut self) -> StoragePtr<'_> {
         Rc::new(RefCell::new(self))
@@ -109,6 +117,16 @@ impl<S: ReadStorage + fmt::Debug> StorageView<S> {
         }
     }
 
+    /// Returns values read from the underlying storage.
+    pub fn read_storage_keys(&self) -> &HashMap<StorageKey, StorageValue> {
+        &self.read_storage_keys
+    }
+
+    /// Returns results of [`ReadStorage::is_write_initial()`] checks performed on the underlying storage.
+    pub fn initial_writes(&self) -> &HashMap<StorageKey, bool> {
+        &self.initial_writes_cache
+    }
+
     /// Unwraps this view, retrieving the read cache. This should be used in tandem with
     /// [`Self::new_with_read_keys()`] to share the read cache across multiple views.
     pub fn into_read_storage_keys(self) -> HashMap<StorageKey, StorageValue> {
@@ -26,6 +26,14 @@ impl BlocksCommitOperation {
         vec![stored_block_info, Token::Array(blocks_to_commit)]
     }
 
+    /// Returns the size of the data published on L1 for each committed block.
+    pub fn pubdata_sizes(&self) -> Vec<usize> {
+        self.blocks
+            .iter()
+            .map(BlockWithMetadata::l1_commit_data_size)
+            .collect()
+    }
+
     pub fn block_range(&self) -> (L1BatchNumber, L1BatchNumber) {
         let BlocksCommitOperation { blocks, .. } = self;
         (
@@ -35,6 +43,36 @@ impl BlocksCommitOperation {
     }
 }
 
+/// Data availability mode used to publish pubdata of committed L1 batches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PubdataDA {
+    /// Pubdata is published as a part of the commit transaction calldata.
+    Calldata,
+    /// Pubdata is published in EIP-4844 blobs attached to the commit transaction.
+    Blobs,
+}
+
+impl PubdataDA {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Calldata => "Calldata",
+            Self::Blobs => "Blobs",
+        }
+    }
+}
+
+impl std::str::FromStr for PubdataDA {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Calldata" => Ok(Self::Calldata),
+            "Blobs" => Ok(Self::Blobs),
+            _ => Err(format!("Incorrect pubdata DA mode: {s}")),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct BlocksCreateProofOperation {
     pub blocks: Vec<BlockWithMetadata>,
@@ -1,13 +1,16 @@
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
+use crate::block::{L1BatchOperationCost, L1BatchResourceUsage};
 use crate::explorer_api::TransactionStatus;
 pub use crate::transaction_request::{
     Eip712Meta, SerializationTransactionError, TransactionRequest,
 };
-use crate::vm_trace::{Call, CallType};
+use crate::tx::{ExecutionMetrics, TxRejection};
+use crate::vm_trace::{Call, CallPubdata, CallType, StorageWrite};
 use crate::web3::types::{AccessList, Index, H2048};
-use crate::{Address, MiniblockNumber};
+use crate::{Address, L1BatchNumber, L1BlockNumber, MiniblockNumber, ProtocolVersionId};
 use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
 pub use zksync_basic_types::web3::{
     self, ethabi,
     types::{Bytes, Work, H160, H256, H64, U256, U64},
@@ -175,7 +178,7 @@ pub struct L2ToL1LogProof {
     pub root: H256,
 }
 
-/// A struct with the two default bridge contracts.
+/// A struct with the two default bridge contracts and other chain-specific L2 contracts.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BridgeAddresses {
@@ -183,6 +186,8 @@ pub struct BridgeAddresses {
     pub l2_erc20_default_bridge: Address,
     pub l1_weth_bridge: Option<Address>,
     pub l2_weth_bridge: Option<Address>,
+    pub l2_weth_token: Option<Address>,
+    pub l2_timestamp_asserter: Option<Address>,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
@@ -244,6 +249,24 @@ pub struct TransactionReceipt {
     /// Effective gas price
     #[serde(rename = "effectiveGasPrice")]
     pub effective_gas_price: Option<U256>,
+    /// Set to `pending` for provisional receipts of transactions executed in a miniblock that is not persisted yet.
+    /// Absent for receipts of persisted transactions.
+    #[serde(
+        rename = "confirmationStatus",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub confirmation_status: Option<ReceiptConfirmationStatus>,
+}
+
+/// Confirmation status of a [`TransactionReceipt`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReceiptConfirmationStatus {
+    /// The transaction is executed by the state keeper, but the miniblock containing it is not persisted yet.
+    /// The receipt may lack some data (e.g., the L1 batch number), and in rare cases (e.g., a server restart
+    /// before the miniblock is persisted) the transaction may be re-executed with a different result.
+    Pending,
 }
 
 /// The block type returned from RPC calls.
@@ -521,6 +544,406 @@ pub struct TransactionDetails {
     pub eth_commit_tx_hash: Option<H256>,
     pub eth_prove_tx_hash: Option<H256>,
     pub eth_execute_tx_hash: Option<H256>,
+    /// Metrics collected during the transaction execution. Only present for transactions
+    /// included in a miniblock.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_metrics: Option<TransactionExecutionMetrics>,
+    /// Reason the transaction was rejected by the state keeper. Only present for rejected transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rejection: Option<TxRejection>,
+}
+
+/// Replacement of an L2 transaction by a transaction with the same initiator and nonce.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionReplacement {
+    pub replaced_tx_hash: H256,
+    pub replacement_tx_hash: H256,
+    pub replaced_at: DateTime<Utc>,
+}
+
+/// Replacement history of transactions with the same initiator and nonce, as returned by `zks_getTransactionHistory`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionHistory {
+    pub initiator_address: Address,
+    pub nonce: U256,
+    /// Hash of the transaction that replaced all the other transactions.
+    pub current_tx_hash: H256,
+    /// Replacements in the chronological order.
+    pub replacements: Vec<TransactionReplacement>,
+}
+
+/// Summary of transactions sent by an account, as returned by `zks_getAccountSummary`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSummary {
+    pub address: Address,
+    /// Number of transactions initiated by the account and included into miniblocks.
+    pub sent_transaction_count: U64,
+    /// Number of the first miniblock including a transaction initiated by the account.
+    pub first_active_block: U64,
+    /// Number of the last miniblock including a transaction initiated by the account.
+    pub last_active_block: U64,
+}
+
+/// Lifecycle stage of a transaction, as reported to `zks_subscribe("txStatus")` subscribers.
+/// Stages are ordered; a transaction only moves to later stages (unless the chain is reverted).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TxLifecycleStage {
+    AcceptedToMempool,
+    IncludedInMiniblock,
+    SealedInBatch,
+    CommittedOnL1,
+    ProvenOnL1,
+    ExecutedOnL1,
+    /// Transaction was rejected by the state keeper and will not be included in a block.
+    Rejected,
+}
+
+impl TxLifecycleStage {
+    /// Returns `true` if the transaction won't move to any other stage.
+    pub fn is_final(self) -> bool {
+        matches!(self, Self::ExecutedOnL1 | Self::Rejected)
+    }
+}
+
+/// Current lifecycle status of a transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxLifecycleStatus {
+    pub tx_hash: H256,
+    pub stage: TxLifecycleStage,
+    pub miniblock_number: Option<MiniblockNumber>,
+    pub l1_batch_number: Option<L1BatchNumber>,
+    /// Hash of the L1 transaction that moved the transaction to the current stage
+    /// (only for the stages happening on L1).
+    pub eth_tx_hash: Option<H256>,
+}
+
+/// Resources consumed by a transaction, as recorded by the state keeper when the transaction was sealed.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionExecutionMetrics {
+    pub gas_used: u64,
+    pub computational_gas_used: u32,
+    /// Total number of bytes published to L1: L2->L1 logs, long L2->L1 messages and bytecodes.
+    pub pubdata_published: u64,
+    pub published_bytecode_bytes: u64,
+    pub l2_l1_logs: u64,
+    pub l2_l1_long_messages: u64,
+    /// Number of storage log queries (both reads and writes).
+    pub storage_logs: u64,
+    pub contracts_deployed: u16,
+    pub contracts_used: u64,
+    pub vm_events: u64,
+    pub cycles_used: u32,
+    /// Number of storage slots restored to their values at the start of the batch, which were refunded.
+    pub refunded_storage_writes: u64,
+}
+
+impl From<ExecutionMetrics> for TransactionExecutionMetrics {
+    fn from(metrics: ExecutionMetrics) -> Self {
+        Self {
+            gas_used: metrics.gas_used as u64,
+            computational_gas_used: metrics.computational_gas_used,
+            pubdata_published: metrics.size() as u64,
+            published_bytecode_bytes: metrics.published_bytecode_bytes as u64,
+            l2_l1_logs: metrics.l2_l1_logs as u64,
+            l2_l1_long_messages: metrics.l2_l1_long_messages as u64,
+            storage_logs: metrics.storage_logs as u64,
+            contracts_deployed: metrics.contracts_deployed,
+            contracts_used: metrics.contracts_used as u64,
+            vm_events: metrics.vm_events as u64,
+            cycles_used: metrics.cycles_used,
+            refunded_storage_writes: metrics.refunded_storage_writes as u64,
+        }
+    }
+}
+
+/// Message sent via the L1 messenger together with the proof of its inclusion into the L1 batch,
+/// as returned by `zks_getL2ToL1MessageProof`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct L2ToL1MessageProof {
+    pub l1_batch_number: L1BatchNumber,
+    /// Index of the message among messages sent in the L1 batch.
+    pub message_index: u32,
+    /// Index of the transaction that sent the message in the L1 batch.
+    pub tx_index_in_l1_batch: u16,
+    /// Address of the contract that sent the message.
+    pub sender: Address,
+    /// Message sent to L1.
+    pub message: Bytes,
+    /// Merkle proof of the accompanying L2->L1 log.
+    pub proof: Vec<H256>,
+    /// Index of the accompanying L2->L1 log in the L1 batch, i.e., the leaf id in the tree.
+    pub id: u32,
+    /// Root of the tree.
+    pub root: H256,
+}
+
+/// Data required to finalize a withdrawal on L1, as returned by `zks_getWithdrawalFinalizationParams`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalFinalizationParams {
+    /// Number of the L1 batch the withdrawal transaction was included in.
+    pub l1_batch_number: L1BatchNumber,
+    /// Index of the L2->L1 message in the L1 batch.
+    pub l2_message_index: u32,
+    /// Index of the withdrawal transaction in the L1 batch.
+    pub l2_tx_number_in_block: u16,
+    /// Message sent to L1.
+    pub message: Bytes,
+    /// Address of the contract that sent the message.
+    pub sender: Address,
+    /// Merkle proof of the message inclusion into the L1 batch.
+    pub proof: Vec<H256>,
+}
+
+/// Filter for `zks_getConfirmedTokens`. All specified conditions must hold for a token to be returned.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenFilter {
+    /// Case-insensitive token symbol.
+    #[serde(default)]
+    pub symbol: Option<String>,
+    /// L1 addresses of the tokens.
+    #[serde(default)]
+    pub l1_addresses: Option<Vec<Address>>,
+}
+
+/// Filter and pagination options for `zks_getAllAccountBalances`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BalancesFilter {
+    /// L2 addresses of the tokens to return balances for. ETH is denoted by the zero address.
+    #[serde(default)]
+    pub tokens: Option<Vec<Address>>,
+    /// Number of balances to skip. Balances are ordered by the token address.
+    #[serde(default)]
+    pub offset: Option<u32>,
+    /// Max number of returned balances. If not specified, the entities limit of the API server is used.
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+/// Protocol version registered on L1, as returned by `zks_getProtocolVersion`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolVersion {
+    pub version_id: ProtocolVersionId,
+    /// L1 block at which the version was first observed by the node.
+    pub l1_block_number: L1BlockNumber,
+}
+
+/// Response of `txpool_status`, compatible with the Geth format.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct TxpoolStatus {
+    /// Number of transactions ready for execution.
+    pub pending: U64,
+    /// Number of transactions waiting for a nonce gap to be closed.
+    pub queued: U64,
+}
+
+/// Response of `zks_getRejectionStats`: cumulative numbers of rejected transactions grouped by the rejection reason.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectionStats {
+    /// Transactions rejected by the API server on submission.
+    pub tx_sender: BTreeMap<String, u64>,
+    /// Transactions temporarily skipped by the state keeper because they don't match the current fee filter;
+    /// `None` if the API server doesn't run together with the state keeper.
+    pub mempool: Option<BTreeMap<String, u64>>,
+}
+
+/// Parameters of the built-in transaction admission policies, accepted by `admin_setAdmissionPolicies`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AdmissionPoliciesConfig {
+    /// If set, only the listed accounts are allowed to deploy contracts.
+    #[serde(default)]
+    pub deployer_allowlist: Option<Vec<Address>>,
+    /// Contracts that transactions are not allowed to call.
+    #[serde(default)]
+    pub callee_denylist: Vec<Address>,
+    /// Max size of the transaction calldata (in bytes).
+    #[serde(default)]
+    pub max_calldata_size: Option<usize>,
+}
+
+/// Health of a single node component, returned by `admin_health`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentHealth {
+    pub name: String,
+    pub ready: bool,
+    /// Reason why the component is not ready.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Aggregated status of the node for operator dashboards, as returned by `zks_getNodeStatus`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeStatus {
+    pub last_sealed_miniblock: MiniblockNumber,
+    pub last_sealed_l1_batch: L1BatchNumber,
+    pub last_committed_l1_batch: Option<L1BatchNumber>,
+    pub last_proven_l1_batch: Option<L1BatchNumber>,
+    pub last_executed_l1_batch: Option<L1BatchNumber>,
+    /// Number of sealed L1 batches not yet processed by the Merkle tree.
+    pub tree_lag: u32,
+    /// Nonce of the next L1 transaction sent by `eth_sender`; `None` if no transactions were sent yet.
+    pub eth_sender_next_nonce: Option<u64>,
+    /// Mempool status; `None` if the API server doesn't run together with the state keeper.
+    pub mempool: Option<TxpoolStatus>,
+    /// Health of the components running in the same process as the API server.
+    pub components: Vec<ComponentHealth>,
+    /// Operator balance on L1; `None` if `eth_tx_aggregator` doesn't run together with the API server.
+    pub operator_balance: Option<OperatorBalance>,
+}
+
+/// ETH balance of the operator account on L1, as tracked by `eth_tx_aggregator`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorBalance {
+    /// Balance in wei.
+    pub balance: U256,
+    /// Projected time (in seconds) until the balance is depleted at the recent spending rate; `None` if
+    /// the balance wasn't spent recently.
+    pub runway_secs: Option<u64>,
+    /// Whether aggregation of L1 operations is paused because the balance is below the configured floor.
+    pub aggregation_paused: bool,
+}
+
+/// Resources consumed by an L1 batch, as returned by `zks_getBatchResourceUsage`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchResourceUsage {
+    pub l1_batch_number: L1BatchNumber,
+    pub gas_used: u64,
+    /// Total number of bytes published to L1, including the state diffs.
+    pub pubdata_bytes: u64,
+    pub initial_storage_writes: u64,
+    pub repeated_storage_writes: u64,
+    /// Estimated number of basic circuits of each type required to prove the batch.
+    pub estimated_circuits: BTreeMap<String, u64>,
+    /// Cost of the L1 transactions processing the batch; `None` if the corresponding operation
+    /// hasn't been confirmed on L1 yet.
+    pub commit: Option<L1OperationCost>,
+    pub prove: Option<L1OperationCost>,
+    pub execute: Option<L1OperationCost>,
+}
+
+impl BatchResourceUsage {
+    pub fn new(l1_batch_number: L1BatchNumber, usage: L1BatchResourceUsage) -> Self {
+        let estimated_circuits = usage
+            .circuit_statistic
+            .circuits_by_type()
+            .into_iter()
+            .map(|(name, count)| (name.to_owned(), count as u64))
+            .collect();
+        Self {
+            l1_batch_number,
+            gas_used: usage.gas_used,
+            pubdata_bytes: usage.pubdata_bytes,
+            initial_storage_writes: usage.initial_storage_writes,
+            repeated_storage_writes: usage.repeated_storage_writes,
+            estimated_circuits,
+            commit: None,
+            prove: None,
+            execute: None,
+        }
+    }
+}
+
+/// Cost of an L1 transaction processing one or more L1 batches.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct L1OperationCost {
+    pub tx_hash: H256,
+    /// Gas used by the transaction; `None` if it wasn't recorded when the transaction was confirmed.
+    pub gas_used: Option<u64>,
+    pub base_fee_per_gas: u64,
+    pub priority_fee_per_gas: u64,
+    /// Number of L1 batches processed by the transaction; the cost is shared among them.
+    pub l1_batch_count: u32,
+}
+
+impl From<L1BatchOperationCost> for L1OperationCost {
+    fn from(cost: L1BatchOperationCost) -> Self {
+        Self {
+            tx_hash: cost.tx_hash,
+            gas_used: cost.gas_used,
+            base_fee_per_gas: cost.base_fee_per_gas,
+            priority_fee_per_gas: cost.priority_fee_per_gas,
+            l1_batch_count: cost.l1_batch_count,
+        }
+    }
+}
+
+/// Result of simulating a transaction bundle with `zks_simulateBundle`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleSimulation {
+    /// Number of the miniblock the bundle was simulated against.
+    pub block_number: U64,
+    /// Results of the bundle transactions in the execution order.
+    pub transactions: Vec<BundleTransactionResult>,
+    pub total_gas_used: U256,
+}
+
+/// Result of a single transaction in a simulated bundle.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleTransactionResult {
+    pub transaction_hash: H256,
+    /// Either 1 (success) or 0 (failure), same as in transaction receipts.
+    pub status: U64,
+    /// Gas used by the transaction after the refund.
+    pub gas_used: U256,
+    pub gas_refunded: U256,
+    /// Revert reason if the transaction has failed.
+    pub revert_reason: Option<String>,
+    /// Events emitted by the transaction. Block-related fields are not set.
+    pub logs: Vec<Log>,
+    /// Storage slots changed by the transaction.
+    pub state_diffs: Vec<StorageSlotDiff>,
+}
+
+/// Change of a storage slot value.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageSlotDiff {
+    pub address: Address,
+    pub key: H256,
+    pub previous_value: H256,
+    pub value: H256,
+}
+
+/// Pagination of [`StorageDiff`]s.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageDiffPagination {
+    /// Cursor returned with the previous page. If not specified, the first page is returned.
+    pub cursor: Option<H256>,
+    /// Max number of slot diffs in the page. If not specified, the server-side limit is used.
+    pub limit: Option<usize>,
+}
+
+/// Net storage changes between two L1 batches, as returned by `zks_getStorageDiff`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageDiff {
+    pub l1_batch_from: L1BatchNumber,
+    pub l1_batch_to: L1BatchNumber,
+    /// Slots changed by L1 batches in `(l1_batch_from, l1_batch_to]`, ordered by the hashed storage key.
+    /// Slots which values were restored by the end of the range are not included.
+    pub diffs: Vec<StorageSlotDiff>,
+    /// Cursor to request the next page with, or `None` if this is the last page.
+    pub next_cursor: Option<H256>,
 }
 
 #[derive(Debug, Clone)]
@@ -539,6 +962,31 @@ pub struct ResultDebugCall {
     pub result: DebugCall,
 }
 
+/// Storage access set of a transaction in an L1 batch, returned by `debug_getL1BatchAccessSets`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionAccessSet {
+    pub transaction_hash: H256,
+    pub index_in_l1_batch: U64,
+    /// Hashed storage keys read by the transaction.
+    pub reads: Vec<H256>,
+    /// Hashed storage keys written by the transaction.
+    pub writes: Vec<H256>,
+    /// Indices of earlier transactions in the L1 batch that the transaction directly conflicts with.
+    pub depends_on: Vec<U64>,
+}
+
+/// L1 priority operation rejected by the server, returned by `debug_getRejectedPriorityOps`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedPriorityOp {
+    pub serial_id: U64,
+    pub transaction_hash: H256,
+    pub l1_block_number: U64,
+    /// Human-readable reason of the rejection.
+    pub reason: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum DebugCallType {
     Call,
@@ -559,6 +1007,54 @@ pub struct DebugCall {
     pub error: Option<String>,
     pub revert_reason: Option<String>,
     pub calls: Vec<DebugCall>,
+    /// Set for persisted traces if outputs or revert reasons in the trace were truncated to fit into
+    /// the size limits configured for the node. Truncated revert reasons end with a truncation marker.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub results_truncated: bool,
+    /// Pubdata attributed to the call. Only returned for the `zkTracer`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pubdata: Option<DebugCallPubdata>,
+}
+
+/// Pubdata produced by a call frame, returned by the `zkTracer`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugCallPubdata {
+    /// Number of pubdata bytes the call and its subcalls were charged for.
+    pub pubdata_bytes: U64,
+    /// Storage slots written directly by the call, in execution order.
+    pub storage_writes: Vec<DebugStorageWrite>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugStorageWrite {
+    pub address: Address,
+    pub key: H256,
+    pub value: H256,
+}
+
+impl From<CallPubdata> for DebugCallPubdata {
+    fn from(value: CallPubdata) -> Self {
+        Self {
+            pubdata_bytes: value.pubdata_bytes.into(),
+            storage_writes: value
+                .storage_writes
+                .into_iter()
+                .map(DebugStorageWrite::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<StorageWrite> for DebugStorageWrite {
+    fn from(value: StorageWrite) -> Self {
+        Self {
+            address: value.address,
+            key: value.key,
+            value: value.value,
+        }
+    }
 }
 
 impl From<Call> for DebugCall {
@@ -581,6 +1077,8 @@ impl From<Call> for DebugCall {
             error: value.error.clone(),
             revert_reason: value.revert_reason,
             calls,
+            results_truncated: false,
+            pubdata: value.pubdata.map(DebugCallPubdata::from),
         }
     }
 }
@@ -589,6 +1087,8 @@ impl From<Call> for DebugCall {
 #[serde(rename_all = "camelCase")]
 pub enum SupportedTracers {
     CallTracer,
+    /// Call tracer annotating each call with the pubdata it has produced and the storage slots it has written.
+    ZkTracer,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -5,9 +5,10 @@ use zksync_basic_types::{H2048, H256, U256};
 use zksync_contracts::BaseSystemContractsHashes;
 
 use crate::{
-    l2_to_l1_log::L2ToL1Log, priority_op_onchain_data::PriorityOpOnchainData,
-    pubdata_packing::pack_storage_log, web3::signing::keccak256, AccountTreeId, Address,
-    L1BatchNumber, MiniblockNumber, StorageKey, StorageLogKind, WitnessStorageLog,
+    circuit::CircuitStatistic, l2_to_l1_log::L2ToL1Log,
+    priority_op_onchain_data::PriorityOpOnchainData, pubdata_packing::pack_storage_log,
+    web3::signing::keccak256, AccountTreeId, Address, L1BatchNumber, MiniblockNumber, StorageKey,
+    StorageLogKind, WitnessStorageLog,
 };
 
 /// Represents a successfully deployed smart contract.
@@ -77,6 +78,47 @@ pub struct MiniblockHeader {
     pub base_system_contracts_hashes: BaseSystemContractsHashes,
 }
 
+/// Hasher of miniblock contents.
+///
+/// A miniblock hash commits to the miniblock number and timestamp, the hash of the previous miniblock
+/// and hashes of all transactions in the miniblock (in the execution order). Hence, miniblock hashes
+/// form a chain and are unique for each miniblock.
+#[derive(Debug, Clone)]
+pub struct MiniblockHasher {
+    number: MiniblockNumber,
+    timestamp: u64,
+    prev_miniblock_hash: H256,
+    txs_rolling_hash: H256,
+}
+
+impl MiniblockHasher {
+    pub fn new(number: MiniblockNumber, timestamp: u64, prev_miniblock_hash: H256) -> Self {
+        Self {
+            number,
+            timestamp,
+            prev_miniblock_hash,
+            txs_rolling_hash: H256::zero(),
+        }
+    }
+
+    /// Adds the hash of the next transaction in the miniblock.
+    pub fn push_tx_hash(&mut self, tx_hash: H256) {
+        let mut digest = [0_u8; 64];
+        digest[..32].copy_from_slice(self.txs_rolling_hash.as_bytes());
+        digest[32..].copy_from_slice(tx_hash.as_bytes());
+        self.txs_rolling_hash = H256(keccak256(&digest));
+    }
+
+    pub fn finalize(self) -> H256 {
+        let mut digest = [0_u8; 128];
+        U256::from(self.number.0).to_big_endian(&mut digest[..32]);
+        U256::from(self.timestamp).to_big_endian(&mut digest[32..64]);
+        digest[64..96].copy_from_slice(self.prev_miniblock_hash.as_bytes());
+        digest[96..].copy_from_slice(self.txs_rolling_hash.as_bytes());
+        H256(keccak256(&digest))
+    }
+}
+
 impl L1BatchHeader {
     pub fn new(
         number: L1BatchNumber,
@@ -124,6 +166,30 @@ impl L1BatchHeader {
 }
 
 /// Utility structure that holds the block header together with its logs required to generate the witness
+/// Resources consumed by an L1 batch, persisted when the batch is sealed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct L1BatchResourceUsage {
+    /// L2 gas used by the batch transactions.
+    pub gas_used: u64,
+    /// Size of the pubdata published for the batch, in bytes.
+    pub pubdata_bytes: u64,
+    pub initial_storage_writes: u64,
+    pub repeated_storage_writes: u64,
+    /// Estimated number of basic circuits of each type required to prove the batch.
+    pub circuit_statistic: CircuitStatistic,
+}
+
+/// Cost of a confirmed L1 transaction (commit, prove or execute) covering an L1 batch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct L1BatchOperationCost {
+    pub tx_hash: H256,
+    pub gas_used: Option<u64>,
+    pub base_fee_per_gas: u64,
+    pub priority_fee_per_gas: u64,
+    /// Number of L1 batches covered by the transaction; the cost is shared among them.
+    pub l1_batch_count: u32,
+}
+
 #[derive(Debug)]
 pub struct WitnessBlockWithLogs {
     pub header: L1BatchHeader,
@@ -185,3 +251,35 @@ impl Add for BlockGasCount {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use zksync_utils::miniblock_hash;
+
+    use super::*;
+
+    #[test]
+    fn miniblock_hash_commits_to_contents() {
+        let prev_hash = miniblock_hash(MiniblockNumber(0));
+        let hash = |timestamp, tx_hashes: &[H256]| {
+            let mut hasher = MiniblockHasher::new(MiniblockNumber(1), timestamp, prev_hash);
+            for &tx_hash in tx_hashes {
+                hasher.push_tx_hash(tx_hash);
+            }
+            hasher.finalize()
+        };
+
+        let tx_hashes = [H256::repeat_byte(1), H256::repeat_byte(2)];
+        let reference_hash = hash(1, &tx_hashes);
+        assert_eq!(hash(1, &tx_hashes), reference_hash);
+        assert_ne!(hash(2, &tx_hashes), reference_hash);
+        assert_ne!(hash(1, &tx_hashes[..1]), reference_hash);
+        assert_ne!(hash(1, &[tx_hashes[1], tx_hashes[0]]), reference_hash);
+        assert_ne!(hash(1, &[]), miniblock_hash(MiniblockNumber(1)));
+
+        let mut hasher = MiniblockHasher::new(MiniblockNumber(1), 1, H256::zero());
+        hasher.push_tx_hash(tx_hashes[0]);
+        hasher.push_tx_hash(tx_hashes[1]);
+        assert_ne!(hasher.finalize(), reference_hash);
+    }
+}
@@ -1,3 +1,6 @@
+use serde::{Deserialize, Serialize};
+use std::ops::Add;
+
 use zkevm_test_harness::geometry_config::get_geometry_config;
 use zkevm_test_harness::toolset::GeometryConfig;
 
@@ -11,3 +14,72 @@ pub const LEAF_CIRCUIT_INDEX: u8 = 2;
 pub const NODE_CIRCUIT_INDEX: u8 = 1;
 
 pub const GEOMETRY_CONFIG: GeometryConfig = get_geometry_config();
+
+/// Estimated usage of the basic circuits of each type, measured in (fractional) number of circuits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CircuitStatistic {
+    pub main_vm: f32,
+    pub ram_permutation: f32,
+    pub storage_application: f32,
+    pub storage_sorter: f32,
+    pub code_decommitter: f32,
+    pub code_decommitter_sorter: f32,
+    pub log_demuxer: f32,
+    pub events_sorter: f32,
+    pub keccak256: f32,
+    pub ecrecover: f32,
+    pub sha256: f32,
+}
+
+impl CircuitStatistic {
+    /// Converts the number of cycles spent in a certain circuit type into the number of circuits.
+    pub fn circuits_for_cycles(cycles: usize, cycles_per_circuit: u32) -> f32 {
+        cycles as f32 / cycles_per_circuit as f32
+    }
+
+    /// Returns the estimated number of circuits of each type, with each type's count rounded up.
+    pub fn circuits_by_type(&self) -> [(&'static str, usize); 11] {
+        [
+            ("main_vm", self.main_vm),
+            ("ram_permutation", self.ram_permutation),
+            ("storage_application", self.storage_application),
+            ("storage_sorter", self.storage_sorter),
+            ("code_decommitter", self.code_decommitter),
+            ("code_decommitter_sorter", self.code_decommitter_sorter),
+            ("log_demuxer", self.log_demuxer),
+            ("events_sorter", self.events_sorter),
+            ("keccak256", self.keccak256),
+            ("ecrecover", self.ecrecover),
+            ("sha256", self.sha256),
+        ]
+        .map(|(name, circuits)| (name, circuits.ceil() as usize))
+    }
+
+    /// Returns the estimated total number of basic circuits.
+    pub fn total(&self) -> usize {
+        self.circuits_by_type()
+            .iter()
+            .map(|(_, circuits)| circuits)
+            .sum()
+    }
+}
+
+impl Add for CircuitStatistic {
+    type Output = CircuitStatistic;
+
+    fn add(self, other: CircuitStatistic) -> CircuitStatistic {
+        CircuitStatistic {
+            main_vm: self.main_vm + other.main_vm,
+            ram_permutation: self.ram_permutation + other.ram_permutation,
+            storage_application: self.storage_application + other.storage_application,
+            storage_sorter: self.storage_sorter + other.storage_sorter,
+            code_decommitter: self.code_decommitter + other.code_decommitter,
+            code_decommitter_sorter: self.code_decommitter_sorter + other.code_decommitter_sorter,
+            log_demuxer: self.log_demuxer + other.log_demuxer,
+            events_sorter: self.events_sorter + other.events_sorter,
+            keccak256: self.keccak256 + other.keccak256,
+            ecrecover: self.ecrecover + other.ecrecover,
+            sha256: self.sha256 + other.sha256,
+        }
+    }
+}
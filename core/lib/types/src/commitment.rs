@@ -18,6 +18,7 @@ use zksync_mini_merkle_tree::MiniMerkleTree;
 use crate::circuit::GEOMETRY_CONFIG;
 use crate::ethabi::Token;
 use crate::l2_to_l1_log::L2ToL1Log;
+use crate::state_diffs::{compress_state_diffs, StateDiffRecord};
 use crate::web3::signing::keccak256;
 use crate::writes::{InitialStorageWrite, RepeatedStorageWrite};
 use crate::{block::L1BatchHeader, H256, KNOWN_CODES_STORAGE_ADDRESS, U256};
@@ -56,6 +57,10 @@ pub struct BlockMetadata {
     pub merkle_root_hash: H256,
     pub initial_writes_compressed: Vec<u8>,
     pub repeated_writes_compressed: Vec<u8>,
+    /// Compressed state diffs published instead of initial and repeated writes.
+    /// `None` for batches sealed before state diffs were persisted.
+    #[serde(default)]
+    pub state_diffs_compressed: Option<Vec<u8>>,
     pub commitment: H256,
     pub l2_l1_messages_compressed: Vec<u8>,
     pub l2_l1_merkle_root: H256,
@@ -125,7 +130,7 @@ impl BlockWithMetadata {
     }
 
     pub fn l1_commit_data(&self) -> Token {
-        Token::Tuple(vec![
+        let mut tokens = vec![
             Token::Uint(U256::from(self.header.number.0)),
             Token::Uint(U256::from(self.header.timestamp)),
             Token::Uint(U256::from(self.metadata.rollup_last_leaf_index)),
@@ -138,8 +143,18 @@ impl BlockWithMetadata {
                     .as_bytes()
                     .to_vec(),
             ),
-            Token::Bytes(self.metadata.initial_writes_compressed.clone()),
-            Token::Bytes(self.metadata.repeated_writes_compressed.clone()),
+        ];
+        if let Some(state_diffs) = &self.metadata.state_diffs_compressed {
+            tokens.push(Token::Bytes(state_diffs.clone()));
+        } else {
+            tokens.push(Token::Bytes(
+                self.metadata.initial_writes_compressed.clone(),
+            ));
+            tokens.push(Token::Bytes(
+                self.metadata.repeated_writes_compressed.clone(),
+            ));
+        }
+        tokens.extend([
             Token::Bytes(self.metadata.l2_l1_messages_compressed.clone()),
             Token::Array(
                 self.header
@@ -154,7 +169,8 @@ impl BlockWithMetadata {
                     .map(|bytecode| Token::Bytes(bytecode.to_vec()))
                     .collect(),
             ),
-        ])
+        ]);
+        Token::Tuple(tokens)
     }
 
     pub fn l1_commit_data_size(&self) -> usize {
@@ -214,6 +230,8 @@ struct BlockAuxiliaryOutput {
     initial_writes_hash: H256,
     repeated_writes_compressed: Vec<u8>,
     repeated_writes_hash: H256,
+    state_diffs_compressed: Option<Vec<u8>>,
+    state_diffs_hash: Option<H256>,
 }
 
 impl BlockAuxiliaryOutput {
@@ -221,6 +239,7 @@ impl BlockAuxiliaryOutput {
         l2_l1_logs: Vec<L2ToL1Log>,
         initial_writes: Vec<InitialStorageWrite>,
         repeated_writes: Vec<RepeatedStorageWrite>,
+        state_diffs: Option<&[StateDiffRecord]>,
     ) -> Self {
         let l2_l1_logs_compressed = serialize_commitments(&l2_l1_logs);
         let initial_writes_compressed = serialize_commitments(&initial_writes);
@@ -229,6 +248,10 @@ impl BlockAuxiliaryOutput {
         let l2_l1_logs_linear_hash = H256::from(keccak256(&l2_l1_logs_compressed));
         let initial_writes_hash = H256::from(keccak256(&initial_writes_compressed));
         let repeated_writes_hash = H256::from(keccak256(&repeated_writes_compressed));
+        let state_diffs_compressed = state_diffs.map(compress_state_diffs);
+        let state_diffs_hash = state_diffs_compressed
+            .as_ref()
+            .map(|compressed| H256::from(keccak256(compressed)));
 
         let merkle_tree_leaves = l2_l1_logs_compressed[4..]
             .chunks(L2ToL1Log::SERIALIZED_SIZE)
@@ -248,17 +271,23 @@ impl BlockAuxiliaryOutput {
             l2_l1_logs_merkle_root,
             initial_writes_hash,
             repeated_writes_hash,
+            state_diffs_compressed,
+            state_diffs_hash,
         }
     }
 
     pub fn to_bytes(&self) -> Vec<u8> {
-        // 4 H256 values
+        // 4 H256 values; 3 if the state diffs hash replaces the initial and repeated writes hashes
         const SERIALIZED_SIZE: usize = 128;
         let mut result = Vec::with_capacity(SERIALIZED_SIZE);
         result.extend(self.l2_l1_logs_merkle_root.as_bytes());
         result.extend(self.l2_l1_logs_linear_hash.as_bytes());
-        result.extend(self.initial_writes_hash.as_bytes());
-        result.extend(self.repeated_writes_hash.as_bytes());
+        if let Some(state_diffs_hash) = self.state_diffs_hash {
+            result.extend(state_diffs_hash.as_bytes());
+        } else {
+            result.extend(self.initial_writes_hash.as_bytes());
+            result.extend(self.repeated_writes_hash.as_bytes());
+        }
         result
     }
 
@@ -346,6 +375,7 @@ impl BlockCommitment {
         rollup_root_hash: H256,
         initial_writes: Vec<InitialStorageWrite>,
         repeated_writes: Vec<RepeatedStorageWrite>,
+        state_diffs: Option<Vec<StateDiffRecord>>,
         bootloader_code_hash: H256,
         default_aa_code_hash: H256,
     ) -> Self {
@@ -373,6 +403,7 @@ impl BlockCommitment {
                 l2_to_l1_logs,
                 initial_writes,
                 repeated_writes,
+                state_diffs.as_deref(),
             ),
             meta_parameters,
         }
@@ -402,6 +433,10 @@ impl BlockCommitment {
         &self.auxiliary_output.repeated_writes_compressed
     }
 
+    pub fn state_diffs_compressed(&self) -> Option<&[u8]> {
+        self.auxiliary_output.state_diffs_compressed.as_deref()
+    }
+
     pub fn initial_writes_pubdata_hash(&self) -> H256 {
         self.auxiliary_output.initial_writes_hash
     }
@@ -438,8 +473,10 @@ mod tests {
         BlockAuxiliaryOutput, BlockCommitment, BlockMetaParameters, BlockPassThroughData,
     };
     use crate::l2_to_l1_log::L2ToL1Log;
+    use crate::state_diffs::{compress_state_diffs, StateDiffRecord};
+    use crate::web3::signing::keccak256;
     use crate::writes::{InitialStorageWrite, RepeatedStorageWrite};
-    use crate::{H256, U256};
+    use crate::{Address, H256, U256};
 
     #[serde_as]
     #[derive(Debug, Serialize, Deserialize)]
@@ -509,6 +546,7 @@ mod tests {
             commitment_test.auxiliary_input.l2_l1_logs.clone(),
             initial_writes,
             commitment_test.auxiliary_input.repeated_writes.clone(),
+            None,
         );
 
         let commitment = BlockCommitment {
@@ -589,4 +627,24 @@ mod tests {
             commitment_test.expected_outputs.commitment_hash
         );
     }
+
+    #[test]
+    fn state_diffs_replace_storage_writes_in_aux_output() {
+        let state_diffs = [StateDiffRecord {
+            address: Address::repeat_byte(0x01),
+            key: U256::from(1),
+            derived_key: [0x02; 32],
+            enumeration_index: 0,
+            initial_value: U256::zero(),
+            final_value: U256::from(3),
+        }];
+        let auxiliary_output =
+            BlockAuxiliaryOutput::new(vec![], vec![], vec![], Some(&state_diffs));
+
+        let state_diffs_compressed = auxiliary_output.state_diffs_compressed.clone().unwrap();
+        assert_eq!(state_diffs_compressed, compress_state_diffs(&state_diffs));
+        let aux_bytes = auxiliary_output.to_bytes();
+        assert_eq!(aux_bytes.len(), 96);
+        assert_eq!(&aux_bytes[64..], keccak256(&state_diffs_compressed));
+    }
 }
@@ -1,13 +1,15 @@
 use crate::{
     ethabi,
+    l2_to_l1_log::L2ToL1Log,
     tokens::{TokenInfo, TokenMetadata},
-    Address, L1BatchNumber, CONTRACT_DEPLOYER_ADDRESS, H256, KNOWN_CODES_STORAGE_ADDRESS,
+    web3::signing::keccak256,
+    Address, L1BatchNumber, CONTRACT_DEPLOYER_ADDRESS, H2048, H256, KNOWN_CODES_STORAGE_ADDRESS,
     L1_MESSENGER_ADDRESS,
 };
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::fmt::Debug;
-use zksync_utils::h256_to_account_address;
+use std::{fmt::Debug, str::FromStr};
+use zksync_utils::{address_to_h256, h256_to_account_address};
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct VmEvent {
@@ -29,6 +31,132 @@ impl VmEvent {
     }
 }
 
+/// Pattern matching events by the emitting contract and / or the event signature (i.e., the first topic).
+///
+/// Parsed from `<address>`, `<address>:<topic>` or `*:<topic>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventIndexingPattern {
+    pub address: Option<Address>,
+    pub topic: Option<H256>,
+}
+
+impl EventIndexingPattern {
+    pub fn matches(&self, event: &VmEvent) -> bool {
+        let address_matches = self
+            .address
+            .map_or(true, |address| address == event.address);
+        let topic_matches = self
+            .topic
+            .map_or(true, |topic| event.indexed_topics.first() == Some(&topic));
+        address_matches && topic_matches
+    }
+}
+
+impl FromStr for EventIndexingPattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        fn parse_hex<T: FromStr>(s: &str) -> Result<T, String>
+        where
+            T::Err: Debug,
+        {
+            let s = s.strip_prefix("0x").unwrap_or(s);
+            T::from_str(s).map_err(|err| format!("cannot parse `{s}`: {err:?}"))
+        }
+
+        let (address, topic) = match s.trim().split_once(':') {
+            Some((address, topic)) => (address, Some(topic)),
+            None => (s.trim(), None),
+        };
+        let address = if address == "*" {
+            None
+        } else {
+            Some(parse_hex(address)?)
+        };
+        let topic = topic.map(parse_hex).transpose()?;
+        if address.is_none() && topic.is_none() {
+            return Err("pattern must specify an address or a topic".to_owned());
+        }
+        Ok(Self { address, topic })
+    }
+}
+
+/// Policy determining which events are fully indexed in Postgres. Events not matching the policy
+/// are stored raw-only: they are returned in transaction receipts and unfiltered log queries,
+/// but not in log queries filtering by address or topics.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventIndexingPolicy {
+    /// If empty, all events are indexed.
+    patterns: Vec<EventIndexingPattern>,
+}
+
+impl EventIndexingPolicy {
+    pub fn new(patterns: Vec<EventIndexingPattern>) -> Self {
+        Self { patterns }
+    }
+
+    /// Parses the policy from string patterns (see [`EventIndexingPattern`] for the format).
+    pub fn parse(patterns: &[String]) -> Result<Self, String> {
+        let patterns = patterns
+            .iter()
+            .map(|pattern| pattern.parse())
+            .collect::<Result<_, _>>()?;
+        Ok(Self { patterns })
+    }
+
+    pub fn is_indexed(&self, event: &VmEvent) -> bool {
+        self.patterns.is_empty() || self.patterns.iter().any(|pattern| pattern.matches(event))
+    }
+}
+
+/// Returns indices of the 3 bits set in a Bloom filter for `input`, as defined in the Ethereum yellow paper.
+/// Bits are numbered starting from the least significant bit of the filter.
+pub fn bloom_bit_indices(input: &[u8]) -> [usize; 3] {
+    let hash = keccak256(input);
+    let mut indices = [0; 3];
+    for (i, index) in indices.iter_mut().enumerate() {
+        *index = ((usize::from(hash[2 * i]) << 8) | usize::from(hash[2 * i + 1])) & 2047;
+    }
+    indices
+}
+
+/// Returns the byte offset and the bit mask of the bit with the specified index in a Bloom filter.
+fn bloom_bit_position(bit_index: usize) -> (usize, u8) {
+    (255 - bit_index / 8, 1 << (bit_index % 8))
+}
+
+pub fn accrue_bloom(bloom: &mut H2048, input: &[u8]) {
+    for bit_index in bloom_bit_indices(input) {
+        let (offset, mask) = bloom_bit_position(bit_index);
+        bloom.0[offset] |= mask;
+    }
+}
+
+/// Checks whether `bloom` may contain `input`. False positives are possible, false negatives are not.
+pub fn bloom_may_contain(bloom: &H2048, input: &[u8]) -> bool {
+    bloom_bit_indices(input).iter().all(|&bit_index| {
+        let (offset, mask) = bloom_bit_position(bit_index);
+        bloom.0[offset] & mask != 0
+    })
+}
+
+/// Computes a Bloom filter over addresses and topics of the provided events.
+pub fn events_bloom<'a>(events: impl IntoIterator<Item = &'a VmEvent>) -> H2048 {
+    let mut bloom = H2048::zero();
+    for event in events {
+        accrue_event_bloom(&mut bloom, event);
+    }
+    bloom
+}
+
+/// Adds the address and topics of the `event` to the Bloom filter.
+pub fn accrue_event_bloom(bloom: &mut H2048, event: &VmEvent) {
+    accrue_bloom(bloom, event.address.as_bytes());
+    for topic in &event.indexed_topics {
+        accrue_bloom(bloom, topic.as_bytes());
+    }
+}
+
 pub static DEPLOY_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
     ethabi::long_signature(
         "ContractDeployed",
@@ -40,7 +168,7 @@ pub static DEPLOY_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
     )
 });
 
-static L1_MESSAGE_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
+pub static L1_MESSAGE_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
     ethabi::long_signature(
         "L1MessageSent",
         &[
@@ -163,6 +291,73 @@ pub fn extract_long_l2_to_l1_messages(all_generated_events: &[VmEvent]) -> Vec<V
         .collect()
 }
 
+/// Message sent to L1 via the L1 messenger system contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct L1MessengerMessage {
+    /// Index of the message among all messages sent in the L1 batch.
+    pub index_in_l1_batch: u32,
+    pub tx_index_in_l1_batch: u16,
+    /// Address of the contract that sent the message.
+    pub sender: Address,
+    /// Hash of the message, which is the value of the accompanying L2->L1 log.
+    pub hash: H256,
+    pub message: Vec<u8>,
+    /// Index of the accompanying L2->L1 log in the L1 batch; `None` if the log is not found.
+    pub l2_to_l1_log_index: Option<u32>,
+}
+
+/// Decodes messages sent via the L1 messenger from `L1MessageSent` events of an L1 batch and matches
+/// them with the accompanying L2->L1 logs, which are emitted by the messenger in the same order.
+pub fn extract_l1_messenger_messages(
+    all_generated_events: &[VmEvent],
+    l2_to_l1_logs: &[L2ToL1Log],
+) -> Vec<L1MessengerMessage> {
+    let mut messenger_logs = l2_to_l1_logs
+        .iter()
+        .enumerate()
+        .filter(|(_, log)| log.sender == L1_MESSENGER_ADDRESS);
+
+    all_generated_events
+        .iter()
+        .filter(|event| {
+            event.address == L1_MESSENGER_ADDRESS
+                && event.indexed_topics.len() == 3
+                && event.indexed_topics[0] == *L1_MESSAGE_EVENT_SIGNATURE
+        })
+        .enumerate()
+        .map(|(index, event)| {
+            let tx_index_in_l1_batch = event.location.1 as u16;
+            let sender = h256_to_account_address(&event.indexed_topics[1]);
+            let hash = event.indexed_topics[2];
+            let decoded_tokens = ethabi::decode(&[ethabi::ParamType::Bytes], &event.value)
+                .expect("Failed to decode L1MessageSent message");
+            let bytes_token = decoded_tokens.into_iter().next().unwrap();
+            let message = bytes_token.into_bytes().unwrap();
+
+            // Logs are only consumed if a matching log is found, so that a missing log doesn't affect
+            // matching of the following messages.
+            let mut remaining_logs = messenger_logs.clone();
+            let l2_to_l1_log_index = remaining_logs.find(|(_, log)| {
+                log.tx_number_in_block == tx_index_in_l1_batch
+                    && log.key == address_to_h256(&sender)
+                    && log.value == hash
+            });
+            if l2_to_l1_log_index.is_some() {
+                messenger_logs = remaining_logs;
+            }
+            let l2_to_l1_log_index = l2_to_l1_log_index.map(|(log_index, _)| log_index as u32);
+            L1MessengerMessage {
+                index_in_l1_batch: index as u32,
+                tx_index_in_l1_batch,
+                sender,
+                hash,
+                message,
+                l2_to_l1_log_index,
+            }
+        })
+        .collect()
+}
+
 // Extract all bytecodes marked as known on the system contracts
 pub fn extract_bytecodes_marked_as_known(all_generated_events: &[VmEvent]) -> Vec<H256> {
     all_generated_events
@@ -197,3 +392,109 @@ pub struct VmEventGroupKey {
     pub address: Address,
     pub topic: (u32, H256),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_bloom_matches_events() {
+        let event = VmEvent {
+            address: Address::repeat_byte(0x01),
+            indexed_topics: vec![H256::repeat_byte(0x02), H256::repeat_byte(0x03)],
+            ..VmEvent::default()
+        };
+        let bloom = events_bloom([&event]);
+
+        assert!(bloom_may_contain(&bloom, event.address.as_bytes()));
+        for topic in &event.indexed_topics {
+            assert!(bloom_may_contain(&bloom, topic.as_bytes()));
+        }
+        assert!(!bloom_may_contain(
+            &bloom,
+            Address::repeat_byte(0x04).as_bytes()
+        ));
+        assert!(!bloom_may_contain(&H2048::zero(), event.address.as_bytes()));
+        assert!(bloom.0.iter().map(|byte| byte.count_ones()).sum::<u32>() <= 9);
+    }
+
+    #[test]
+    fn extracting_l1_messenger_messages() {
+        let sender = Address::repeat_byte(0x01);
+        let message_event = |tx_index: u32, message: &[u8]| VmEvent {
+            location: (L1BatchNumber(1), tx_index),
+            address: L1_MESSENGER_ADDRESS,
+            indexed_topics: vec![
+                *L1_MESSAGE_EVENT_SIGNATURE,
+                address_to_h256(&sender),
+                H256(keccak256(message)),
+            ],
+            value: ethabi::encode(&[ethabi::Token::Bytes(message.to_vec())]),
+        };
+        let messenger_log = |tx_index: u16, message: &[u8]| L2ToL1Log {
+            tx_number_in_block: tx_index,
+            sender: L1_MESSENGER_ADDRESS,
+            key: address_to_h256(&sender),
+            value: H256(keccak256(message)),
+            ..L2ToL1Log::default()
+        };
+        let events = [
+            message_event(0, b"first"),
+            VmEvent::default(),
+            message_event(2, b"second"),
+        ];
+        let logs = [
+            messenger_log(0, b"first"),
+            L2ToL1Log::default(),
+            messenger_log(2, b"second"),
+        ];
+
+        let messages = extract_l1_messenger_messages(&events, &logs);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].index_in_l1_batch, 0);
+        assert_eq!(messages[0].message, b"first");
+        assert_eq!(messages[0].l2_to_l1_log_index, Some(0));
+        assert_eq!(messages[1].index_in_l1_batch, 1);
+        assert_eq!(messages[1].tx_index_in_l1_batch, 2);
+        assert_eq!(messages[1].sender, sender);
+        assert_eq!(messages[1].message, b"second");
+        assert_eq!(messages[1].l2_to_l1_log_index, Some(2));
+
+        let messages = extract_l1_messenger_messages(&events, &logs[1..]);
+        assert_eq!(messages[0].l2_to_l1_log_index, None);
+        assert_eq!(messages[1].l2_to_l1_log_index, Some(1));
+    }
+
+    #[test]
+    fn event_indexing_policy() {
+        let address = Address::repeat_byte(0x01);
+        let topic = H256::repeat_byte(0x02);
+        let event = VmEvent {
+            address,
+            indexed_topics: vec![topic, H256::repeat_byte(0x03)],
+            ..VmEvent::default()
+        };
+        assert!(EventIndexingPolicy::default().is_indexed(&event));
+
+        let matching_patterns = [
+            format!("{address:?}"),
+            format!("{address:?}:{topic:?}"),
+            format!("*:{topic:?}"),
+        ];
+        for pattern in matching_patterns {
+            let policy = EventIndexingPolicy::parse(&[pattern.clone()]).unwrap();
+            assert!(policy.is_indexed(&event), "{pattern}");
+        }
+
+        let other_address = Address::repeat_byte(0x04);
+        let policy = EventIndexingPolicy::parse(&[
+            format!("{other_address:?}"),
+            format!("{address:?}:{:?}", H256::repeat_byte(0x03)),
+        ])
+        .unwrap();
+        assert!(!policy.is_indexed(&event));
+
+        assert!(EventIndexingPolicy::parse(&["*".to_owned()]).is_err());
+        assert!(EventIndexingPolicy::parse(&["0x123:".to_owned()]).is_err());
+    }
+}
@@ -6,7 +6,10 @@ use bigdecimal::BigDecimal;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::{api::Log, Address, Bytes, Execute, L1BatchNumber, MiniblockNumber, Nonce, H256, U256};
+use crate::{
+    api::Log, Address, Bytes, Execute, L1BatchNumber, MiniblockNumber, Nonce, ProtocolVersionId,
+    H256, U256,
+};
 
 use serde_with::rust::display_fromstr::deserialize as deserialize_fromstr;
 
@@ -287,6 +290,13 @@ pub struct BlockDetails {
     pub l2_fair_gas_price: u64,
     pub base_system_contracts_hashes: BaseSystemContractsHashes,
     pub operator_address: Address,
+    /// Protocol version the block was produced with. Only provided by the Web3 API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<ProtocolVersionId>,
+    /// Name of the rule or criterion that caused the block to be sealed (e.g., `miniblock_timeout`).
+    /// Only provided by the Web3 API, and only for blocks sealed by nodes persisting this information.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seal_reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -307,6 +317,38 @@ pub struct L1BatchDetails {
     pub l1_gas_price: u64,
     pub l2_fair_gas_price: u64,
     pub base_system_contracts_hashes: BaseSystemContractsHashes,
+    /// Status of the batch in the post-sealing pipeline. Only provided by the Web3 API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pipeline_status: Option<L1BatchPipelineStatus>,
+    /// Protocol version the batch was produced with. Only provided by the Web3 API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<ProtocolVersionId>,
+    /// Name of the rule or criterion that caused the batch to be sealed (e.g., `gas` or `no_txs_timeout`).
+    /// Only provided by the Web3 API, and only for batches sealed by nodes persisting this information.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seal_reason: Option<String>,
+    /// Time at which the batch is scheduled to be executed on L1, i.e., the time its proof was confirmed on L1
+    /// plus the execution delay. Only provided by the Web3 API for proven but not executed batches, and only
+    /// if the execution delay is configured for the node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheduled_execution_at: Option<DateTime<Utc>>,
+}
+
+/// Progress of an L1 batch through the tree / proving pipeline.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchPipelineStatus {
+    /// Whether the Merkle tree root hash has been computed for the batch.
+    pub tree_root_available: bool,
+    /// Status of the witness generation job for the batch (e.g., `queued`, `in_progress` or `successful`).
+    /// `None` if there's no such job (e.g., if the batch wasn't queued for proving yet).
+    pub witness_generation_status: Option<String>,
+    /// Total number of prover jobs created for the batch.
+    pub prover_jobs: usize,
+    /// Number of prover jobs that have completed successfully.
+    pub successful_prover_jobs: usize,
+    /// Number of prover jobs that have failed.
+    pub failed_prover_jobs: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +22,9 @@ pub struct TransactionExecutionMetrics {
     pub total_log_queries: usize,
     pub cycles_used: u32,
     pub computational_gas_used: u32,
+    /// Number of storage slots restored to their initial values; see `ExecutionMetrics`.
+    #[serde(default)]
+    pub refunded_storage_writes: usize,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -50,6 +53,30 @@ impl Fee {
     }
 }
 
+/// Breakdown of the estimated transaction fee by the kind of consumed resources.
+/// All values are expressed in L2 gas, except for `pubdata_bytes`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeBreakdown {
+    /// Gas spent on the computation during transaction execution.
+    pub compute_gas: U256,
+    /// Gas spent on publishing the transaction pubdata (storage writes, L2->L1 messages and bytecodes) on L1.
+    pub pubdata_gas: U256,
+    /// Number of pubdata bytes published by the transaction.
+    pub pubdata_bytes: U256,
+    /// Fixed overhead charged to the transaction for taking up resources of the L1 batch.
+    pub overhead_gas: U256,
+    /// Price of a single pubdata byte used for the estimation.
+    pub gas_per_pubdata: U256,
+}
+
+/// Result of the transaction fee estimation.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeEstimate {
+    #[serde(flatten)]
+    pub fee: Fee,
+    pub breakdown: FeeBreakdown,
+}
+
 /// Returns how many slots would ABI-encoding of the transaction with such parameters take
 pub fn encoding_len(
     data_len: u64,
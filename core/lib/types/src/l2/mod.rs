@@ -47,6 +47,13 @@ pub struct L2TxCommonData {
     pub input: Option<InputData>,
 
     pub paymaster_params: PaymasterParams,
+    /// UNIX timestamp (in seconds) starting from which the transaction must not be included into a miniblock;
+    /// compared with the miniblock timestamp.
+    ///
+    /// Not a part of the signed transaction data; it's specified when submitting the transaction. Thus, the deadline
+    /// is best-effort only: anyone who knows the signed transaction can resubmit it with another deadline or without one.
+    #[serde(default)]
+    pub valid_until: Option<u64>,
 }
 
 impl L2TxCommonData {
@@ -70,6 +77,7 @@ impl L2TxCommonData {
             transaction_type,
             input,
             paymaster_params,
+            valid_until: None,
         }
     }
 
@@ -88,6 +96,12 @@ impl L2TxCommonData {
     pub fn set_input(&mut self, input: Vec<u8>, hash: H256) {
         self.input = Some(InputData { hash, data: input })
     }
+
+    /// Checks whether the transaction has expired at the specified UNIX timestamp (in seconds).
+    pub fn is_expired_at(&self, timestamp: u64) -> bool {
+        self.valid_until
+            .map_or(false, |valid_until| valid_until <= timestamp)
+    }
 }
 
 impl Default for L2TxCommonData {
@@ -100,6 +114,7 @@ impl Default for L2TxCommonData {
             transaction_type: TransactionType::EIP712Transaction,
             input: Default::default(),
             paymaster_params: Default::default(),
+            valid_until: None,
         }
     }
 }
@@ -138,6 +153,7 @@ impl L2Tx {
                 transaction_type: TransactionType::EIP712Transaction,
                 input: None,
                 paymaster_params,
+                valid_until: None,
             },
             received_timestamp_ms: unix_timestamp_ms(),
         }
@@ -36,9 +36,11 @@ pub mod l2;
 pub mod l2_to_l1_log;
 pub mod priority_op_onchain_data;
 pub mod pubdata_packing;
+pub mod state_diffs;
 pub mod storage;
 pub mod storage_writes_deduplicator;
 pub mod system_contracts;
+pub mod tee;
 pub mod tokens;
 pub mod tx;
 pub mod vm_trace;
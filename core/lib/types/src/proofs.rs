@@ -2,6 +2,7 @@ use std::convert::{TryFrom, TryInto};
 use std::fmt::Debug;
 use std::ops::Add;
 use std::str::FromStr;
+use std::time::Duration;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -21,6 +22,8 @@ use zkevm_test_harness::{
 
 use zksync_basic_types::{L1BatchNumber, H256, U256};
 
+use crate::{StorageKey, StorageValue};
+
 const HASH_LEN: usize = H256::len_bytes();
 
 /// Metadata emitted by a Merkle tree after processing single storage log.
@@ -223,6 +226,21 @@ pub struct BasicCircuitWitnessGeneratorInput {
     pub merkle_paths_input: PrepareBasicCircuitsJob,
 }
 
+/// Artifacts of the VM run for an L1 batch recorded by the state keeper when the batch is sealed.
+///
+/// The witness generator uses the artifacts instead of loading the corresponding data from Postgres;
+/// this makes the witness generator run on exactly the same inputs as the state keeper.
+/// This type is serialized using `bincode`; changes in its `serde` serialization must be backwards-compatible.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct L1BatchVmArtifacts {
+    /// Values of storage slots at the start of the batch for all slots accessed by the VM.
+    pub storage_reads: Vec<(StorageKey, StorageValue)>,
+    /// Results of `is_write_initial()` checks performed by the VM.
+    pub initial_writes: Vec<(StorageKey, bool)>,
+    /// Bytecodes decommitted by the VM, split into 32-byte words and keyed by the bytecode hash.
+    pub used_bytecodes: Vec<(U256, Vec<U256>)>,
+}
+
 #[derive(Clone)]
 pub struct PrepareLeafAggregationCircuitsJob {
     pub basic_circuits: BlockBasicCircuits<Bn256>,
@@ -421,6 +439,17 @@ impl Add for JobCountStatistics {
     }
 }
 
+/// Depth of a job queue together with the age of the oldest job in it.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct JobQueueStatistics {
+    pub queued: usize,
+    /// Time since the oldest queued job was (re-)queued.
+    pub oldest_queued_age: Option<Duration>,
+    pub in_progress: usize,
+    /// Time since the oldest in-progress job was picked by a worker.
+    pub oldest_in_progress_age: Option<Duration>,
+}
+
 #[derive(Debug)]
 pub struct StuckJobs {
     pub id: u64,
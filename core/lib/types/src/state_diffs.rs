@@ -0,0 +1,280 @@
+//! Compressed state diffs published as the storage part of the L1 batch pubdata.
+//!
+//! Instead of publishing every deduplicated storage write with its full value, we publish
+//! one record per touched slot:
+//!
+//! - Initial writes (slots that are written for the first time) are identified by their derived key,
+//!   i.e. the hashed key used by the Merkle tree.
+//! - Repeated writes are identified by the enumeration index assigned to the slot by the Merkle tree,
+//!   which takes [`BYTES_PER_ENUMERATION_INDEX`] bytes instead of 32.
+//!
+//! Values are compressed relative to the value of the slot at the start of the batch: we choose
+//! the shortest of adding a delta, subtracting a delta or replacing the value with a new one.
+//! Each compressed value is prefixed with a metadata byte `(length << 3) | operation`.
+
+use serde::{Deserialize, Serialize};
+use std::convert::{TryFrom, TryInto};
+use zk_evm::aux_structures::LogQuery;
+use zksync_utils::u256_to_h256;
+
+use crate::{Address, StorageKey, U256};
+
+/// Number of bytes used to encode the enumeration index of a repeated write.
+pub const BYTES_PER_ENUMERATION_INDEX: usize = 4;
+/// Number of bytes used to encode the derived key of an initial write.
+pub const BYTES_PER_DERIVED_KEY: usize = 32;
+
+/// Operation used to compress a storage value. Stored in the 3 lower bits of the metadata byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ValueCompression {
+    /// Full 32-byte final value.
+    NoCompression = 0,
+    /// `final_value = initial_value + delta`.
+    Add = 1,
+    /// `final_value = initial_value - delta`.
+    Sub = 2,
+    /// `final_value = new_value`, with leading zero bytes stripped.
+    Transform = 3,
+}
+
+/// Difference in the value of a single storage slot between the start and the end of an L1 batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateDiffRecord {
+    pub address: Address,
+    pub key: U256,
+    /// Hashed key of the slot, as used by the Merkle tree.
+    pub derived_key: [u8; 32],
+    /// Enumeration index of the slot in the Merkle tree, or 0 for initial writes.
+    pub enumeration_index: u64,
+    pub initial_value: U256,
+    pub final_value: U256,
+}
+
+impl StateDiffRecord {
+    /// Size of the record as persisted in Postgres.
+    pub const PADDED_SIZE: usize = 20 + 32 + 32 + 8 + 32 + 32;
+
+    /// Creates a record from a deduplicated write log query. The enumeration index is not known
+    /// at this point; it is assigned once the batch is processed by the Merkle tree.
+    pub fn from_write_query(query: &LogQuery) -> Self {
+        Self {
+            address: query.address,
+            key: query.key,
+            derived_key: StorageKey::raw_hashed_key(&query.address, &u256_to_h256(query.key)),
+            enumeration_index: 0,
+            initial_value: query.read_value,
+            final_value: query.written_value,
+        }
+    }
+
+    /// Key used by the Merkle tree for this slot.
+    pub fn tree_key(&self) -> U256 {
+        U256::from_little_endian(&self.derived_key)
+    }
+
+    pub fn is_initial_write(&self) -> bool {
+        self.enumeration_index == 0
+    }
+
+    pub fn encode_padded(&self) -> [u8; Self::PADDED_SIZE] {
+        let mut buffer = [0_u8; Self::PADDED_SIZE];
+        buffer[0..20].copy_from_slice(self.address.as_bytes());
+        self.key.to_big_endian(&mut buffer[20..52]);
+        buffer[52..84].copy_from_slice(&self.derived_key);
+        buffer[84..92].copy_from_slice(&self.enumeration_index.to_be_bytes());
+        self.initial_value.to_big_endian(&mut buffer[92..124]);
+        self.final_value.to_big_endian(&mut buffer[124..156]);
+        buffer
+    }
+
+    /// Decodes a record produced by [`Self::encode_padded()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` has unexpected length.
+    pub fn decode_padded(bytes: &[u8]) -> Self {
+        assert_eq!(bytes.len(), Self::PADDED_SIZE, "Invalid state diff length");
+        Self {
+            address: Address::from_slice(&bytes[0..20]),
+            key: U256::from_big_endian(&bytes[20..52]),
+            derived_key: bytes[52..84].try_into().unwrap(),
+            enumeration_index: u64::from_be_bytes(bytes[84..92].try_into().unwrap()),
+            initial_value: U256::from_big_endian(&bytes[92..124]),
+            final_value: U256::from_big_endian(&bytes[124..156]),
+        }
+    }
+
+    /// Compresses the record: identifier of the slot followed by the compressed value.
+    pub fn compress(&self) -> Vec<u8> {
+        let mut compressed = if self.is_initial_write() {
+            self.derived_key.to_vec()
+        } else {
+            let index = u32::try_from(self.enumeration_index)
+                .expect("enumeration index does not fit into pubdata encoding");
+            index.to_be_bytes().to_vec()
+        };
+        compressed.extend(compress_value(self.initial_value, self.final_value));
+        compressed
+    }
+}
+
+/// Encodes multiple records padded to [`StateDiffRecord::PADDED_SIZE`] for persistence.
+pub fn encode_state_diffs(diffs: &[StateDiffRecord]) -> Vec<u8> {
+    diffs
+        .iter()
+        .flat_map(StateDiffRecord::encode_padded)
+        .collect()
+}
+
+/// Decodes records encoded with [`encode_state_diffs()`].
+pub fn decode_state_diffs(bytes: &[u8]) -> Vec<StateDiffRecord> {
+    bytes
+        .chunks(StateDiffRecord::PADDED_SIZE)
+        .map(StateDiffRecord::decode_padded)
+        .collect()
+}
+
+fn byte_len(value: U256) -> usize {
+    (value.bits() + 7) / 8
+}
+
+/// Compresses the transition of a slot value from `initial_value` to `final_value`,
+/// choosing the shortest available representation.
+pub fn compress_value(initial_value: U256, final_value: U256) -> Vec<u8> {
+    let mut candidates = vec![(ValueCompression::Transform, final_value)];
+    if final_value >= initial_value {
+        candidates.push((ValueCompression::Add, final_value - initial_value));
+    } else {
+        candidates.push((ValueCompression::Sub, initial_value - final_value));
+    }
+    let (operation, operand) = candidates
+        .into_iter()
+        .min_by_key(|(_, operand)| byte_len(*operand))
+        .unwrap();
+
+    let len = byte_len(operand);
+    let mut bytes = [0_u8; 32];
+    if len == 32 {
+        final_value.to_big_endian(&mut bytes);
+        let mut compressed = vec![ValueCompression::NoCompression as u8];
+        compressed.extend_from_slice(&bytes);
+        return compressed;
+    }
+
+    operand.to_big_endian(&mut bytes);
+    let mut compressed = vec![((len as u8) << 3) | operation as u8];
+    compressed.extend_from_slice(&bytes[32 - len..]);
+    compressed
+}
+
+/// Decompresses a value compressed with [`compress_value()`]. Returns the final value
+/// and the number of bytes consumed from `compressed`.
+pub fn decompress_value(initial_value: U256, compressed: &[u8]) -> (U256, usize) {
+    let metadata = compressed[0];
+    let len = (metadata >> 3) as usize;
+    let operation = metadata & 0b111;
+    if operation == ValueCompression::NoCompression as u8 {
+        return (U256::from_big_endian(&compressed[1..33]), 33);
+    }
+
+    let operand = U256::from_big_endian(&compressed[1..=len]);
+    let final_value = match operation {
+        op if op == ValueCompression::Add as u8 => initial_value + operand,
+        op if op == ValueCompression::Sub as u8 => initial_value - operand,
+        op if op == ValueCompression::Transform as u8 => operand,
+        _ => panic!("Unknown value compression operation: {operation}"),
+    };
+    (final_value, len + 1)
+}
+
+/// Compresses state diffs of an L1 batch. The result consists of:
+///
+/// 1. Number of initial writes (4 bytes)
+/// 2. Initial writes sorted by derived key
+/// 3. Number of repeated writes (4 bytes)
+/// 4. Repeated writes sorted by enumeration index
+pub fn compress_state_diffs(diffs: &[StateDiffRecord]) -> Vec<u8> {
+    let (mut initial_writes, mut repeated_writes): (Vec<_>, Vec<_>) =
+        diffs.iter().partition(|diff| diff.is_initial_write());
+    initial_writes.sort_unstable_by_key(|diff| diff.derived_key);
+    repeated_writes.sort_unstable_by_key(|diff| diff.enumeration_index);
+
+    let mut compressed = vec![];
+    for writes in [initial_writes, repeated_writes] {
+        compressed.extend_from_slice(&(writes.len() as u32).to_be_bytes());
+        for diff in writes {
+            compressed.extend(diff.compress());
+        }
+    }
+    compressed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(enumeration_index: u64, initial_value: u64, final_value: u64) -> StateDiffRecord {
+        StateDiffRecord {
+            address: Address::repeat_byte(0x01),
+            key: U256::from(enumeration_index),
+            derived_key: [enumeration_index as u8; 32],
+            enumeration_index,
+            initial_value: initial_value.into(),
+            final_value: final_value.into(),
+        }
+    }
+
+    #[test]
+    fn value_compression_picks_shortest_encoding() {
+        let test_vector = [
+            (0_u64, 0_u64, "03"),
+            (1_000_000, 1_000_001, "0901"),
+            (1_000_001, 1_000_000, "0a01"),
+            (1_000_000, 5, "0b05"),
+            (0, 0x1234, "131234"),
+        ];
+        for (initial_value, final_value, expected) in test_vector {
+            let compressed = compress_value(initial_value.into(), final_value.into());
+            assert_eq!(hex::encode(&compressed), expected);
+            let (decompressed, len) = decompress_value(initial_value.into(), &compressed);
+            assert_eq!(decompressed, final_value.into());
+            assert_eq!(len, compressed.len());
+        }
+    }
+
+    #[test]
+    fn value_compression_falls_back_to_full_value() {
+        let initial_value = U256::max_value();
+        let final_value = U256::one() << 248;
+        let compressed = compress_value(initial_value, final_value);
+        assert_eq!(compressed.len(), 33);
+        assert_eq!(compressed[0], ValueCompression::NoCompression as u8);
+        assert_eq!(
+            decompress_value(initial_value, &compressed),
+            (final_value, 33)
+        );
+    }
+
+    #[test]
+    fn padded_encoding_roundtrip() {
+        let diffs = vec![record(0, 0, 1), record(5, u64::MAX, 0)];
+        let encoded = encode_state_diffs(&diffs);
+        assert_eq!(encoded.len(), 2 * StateDiffRecord::PADDED_SIZE);
+        assert_eq!(decode_state_diffs(&encoded), diffs);
+    }
+
+    #[test]
+    fn state_diffs_compression() {
+        let diffs = [record(7, 1000, 1001), record(0, 0, 2), record(3, 1000, 999)];
+        let compressed = compress_state_diffs(&diffs);
+
+        let mut expected = vec![0, 0, 0, 1];
+        expected.extend_from_slice(&[0; 32]);
+        expected.extend_from_slice(&[0x0b, 0x02]);
+        expected.extend_from_slice(&[0, 0, 0, 2]);
+        expected.extend_from_slice(&[0, 0, 0, 3, 0x0a, 0x01]);
+        expected.extend_from_slice(&[0, 0, 0, 7, 0x09, 0x01]);
+        assert_eq!(compressed, expected);
+    }
+}
@@ -6,10 +6,10 @@ use zksync_contracts::{read_sys_contract_bytecode, ContractLanguage};
 
 use crate::{
     block::DeployedContract, ACCOUNT_CODE_STORAGE_ADDRESS, BOOTLOADER_ADDRESS,
-    CONTRACT_DEPLOYER_ADDRESS, ECRECOVER_PRECOMPILE_ADDRESS, IMMUTABLE_SIMULATOR_STORAGE_ADDRESS,
-    KECCAK256_PRECOMPILE_ADDRESS, KNOWN_CODES_STORAGE_ADDRESS, L1_MESSENGER_ADDRESS,
-    L2_ETH_TOKEN_ADDRESS, MSG_VALUE_SIMULATOR_ADDRESS, NONCE_HOLDER_ADDRESS,
-    SHA256_PRECOMPILE_ADDRESS, SYSTEM_CONTEXT_ADDRESS,
+    CONTRACT_DEPLOYER_ADDRESS, ECRECOVER_PRECOMPILE_ADDRESS, EVM_INTERPRETER_ADDRESS,
+    IMMUTABLE_SIMULATOR_STORAGE_ADDRESS, KECCAK256_PRECOMPILE_ADDRESS, KNOWN_CODES_STORAGE_ADDRESS,
+    L1_MESSENGER_ADDRESS, L2_ETH_TOKEN_ADDRESS, MSG_VALUE_SIMULATOR_ADDRESS, NONCE_HOLDER_ADDRESS,
+    P256VERIFY_PRECOMPILE_ADDRESS, SHA256_PRECOMPILE_ADDRESS, SYSTEM_CONTEXT_ADDRESS,
 };
 use once_cell::sync::Lazy;
 
@@ -89,6 +89,12 @@ static SYSTEM_CONTRACTS: Lazy<Vec<DeployedContract>> = Lazy::new(|| {
             ECRECOVER_PRECOMPILE_ADDRESS,
             ContractLanguage::Yul,
         ),
+        (
+            "precompiles/",
+            "P256Verify",
+            P256VERIFY_PRECOMPILE_ADDRESS,
+            ContractLanguage::Yul,
+        ),
         (
             "",
             "SystemContext",
@@ -113,6 +119,12 @@ static SYSTEM_CONTRACTS: Lazy<Vec<DeployedContract>> = Lazy::new(|| {
             BYTECODE_COMPRESSOR_ADDRESS,
             ContractLanguage::Sol,
         ),
+        (
+            "",
+            "EvmInterpreter",
+            EVM_INTERPRETER_ADDRESS,
+            ContractLanguage::Yul,
+        ),
     ]
     .map(|(path, name, address, contract_lang)| DeployedContract {
         account_id: AccountTreeId::new(address),
@@ -0,0 +1,827 @@
+//! Types used by TEE-based provers (e.g., Intel SGX) which re-execute L1 batches inside an enclave
+//! and submit attested execution results alongside ZK proofs.
+
+use p256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use x509_parser::{
+    oid_registry::OID_SIG_ECDSA_WITH_SHA256, parse_x509_certificate, pem::Pem, time::ASN1Time,
+};
+
+use std::{
+    convert::{TryFrom, TryInto},
+    fmt,
+    str::FromStr,
+};
+
+use crate::{
+    proofs::PrepareBasicCircuitsJob, tx::primitives::PackedEthSignature, web3::signing::keccak256,
+    Address, L1BatchNumber, H256,
+};
+
+/// Type of trusted execution environment a prover runs in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TeeType {
+    Sgx,
+}
+
+impl TeeType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Sgx => "sgx",
+        }
+    }
+}
+
+impl fmt::Display for TeeType {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TeeType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sgx" => Ok(Self::Sgx),
+            _ => Err(format!("Unknown TEE type: {s}")),
+        }
+    }
+}
+
+/// Inputs required for a TEE prover to verify execution of an L1 batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeeVerifierInput {
+    pub l1_batch_number: L1BatchNumber,
+    /// State tree root hash before the batch is applied.
+    pub previous_root_hash: H256,
+    /// Merkle paths for all storage accesses in the batch.
+    pub prepare_basic_circuits_job: PrepareBasicCircuitsJob,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeeProofGenerationDataRequest {
+    pub tee_type: TeeType,
+}
+
+/// Response to [`TeeProofGenerationDataRequest`]; `None` if there are no batches to prove.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeeProofGenerationDataResponse(pub Option<Box<TeeVerifierInput>>);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisterTeeAttestationRequest {
+    /// Attestation quote produced by the enclave.
+    #[serde(with = "hex_bytes")]
+    pub attestation: Vec<u8>,
+    /// Uncompressed secp256k1 public key (64 bytes, without the `0x04` prefix) generated
+    /// inside the enclave. Proofs signed by this key are accepted once the attestation is registered.
+    #[serde(with = "hex_bytes")]
+    pub pubkey: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmitTeeProofRequest {
+    pub tee_type: TeeType,
+    /// Public key of a registered attestation.
+    #[serde(with = "hex_bytes")]
+    pub pubkey: Vec<u8>,
+    /// Packed signature over [`TeeProof::signed_message()`].
+    #[serde(with = "hex_bytes")]
+    pub signature: Vec<u8>,
+    /// State tree root hash after the batch computed by the enclave.
+    pub root_hash: H256,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SubmitTeeProofResponse {
+    Success,
+}
+
+/// Errors that can occur when verifying TEE attestations and proofs.
+#[derive(Debug, Error)]
+pub enum TeeVerificationError {
+    #[error("attestation quote is too short: {0} bytes")]
+    QuoteTooShort(usize),
+    #[error("malformed attestation quote: {0}")]
+    MalformedQuote(&'static str),
+    #[error("unsupported attestation quote version {0}")]
+    UnsupportedQuoteVersion(u16),
+    #[error("attestation quote is not produced by an SGX enclave (TEE type {0:#x})")]
+    NotSgxQuote(u32),
+    #[error("unsupported quote certification data type {0}")]
+    UnsupportedCertificationData(u16),
+    #[error("no enclave measurements are allowed")]
+    NoAllowedEnclaves,
+    #[error("attestation quote is produced by an enclave in debug mode")]
+    DebugEnclave,
+    #[error("enclave measurement {0:?} is not allowed")]
+    EnclaveNotAllowed(H256),
+    #[error("attestation report data doesn't commit to the provided public key")]
+    PubkeyMismatch,
+    #[error("invalid attestation quote signature: {0}")]
+    InvalidQuoteSignature(String),
+    #[error("quoting enclave report doesn't commit to the attestation key")]
+    AttestationKeyMismatch,
+    #[error("invalid PCK certificate chain: {0}")]
+    InvalidCertificateChain(String),
+    #[error("PCK certificate chain doesn't end with the trusted root certificate")]
+    UntrustedRootCertificate,
+    #[error("public key must be 64 bytes, got {0}")]
+    InvalidPubkey(usize),
+    #[error("invalid proof signature: {0}")]
+    InvalidSignature(String),
+    #[error("proof is signed by {actual:?}, expected {expected:?}")]
+    SignerMismatch { expected: Address, actual: Address },
+}
+
+/// Trusted root certificate (e.g., Intel SGX Root CA) which PCK certificate chains embedded
+/// into attestation quotes must end with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SgxRootCertificate {
+    der: Vec<u8>,
+}
+
+impl SgxRootCertificate {
+    /// Parses a DER or PEM-encoded certificate.
+    pub fn new(bytes: &[u8]) -> Result<Self, TeeVerificationError> {
+        let der = if bytes.starts_with(b"-----BEGIN") {
+            Pem::iter_from_buffer(bytes)
+                .next()
+                .ok_or_else(|| chain_error("no PEM blocks"))
+                .and_then(|pem| pem.map_err(chain_error))?
+                .contents
+        } else {
+            bytes.to_vec()
+        };
+        parse_x509_certificate(&der).map_err(chain_error)?;
+        Ok(Self { der })
+    }
+}
+
+/// ECDSA signature data of a DCAP quote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct QuoteSignatureData {
+    /// Signature of the quote header and report body by the attestation key (raw `r || s`).
+    quote_signature: [u8; 64],
+    /// Attestation key of the quoting enclave (raw `x || y` coordinates of a P-256 point).
+    attestation_key: [u8; 64],
+    /// Report body of the quoting enclave.
+    qe_report: Vec<u8>,
+    /// Signature of the quoting enclave report by the PCK certificate key (raw `r || s`).
+    qe_report_signature: [u8; 64],
+    qe_auth_data: Vec<u8>,
+    /// PEM-encoded PCK certificate chain, starting from the PCK certificate and ending with the root certificate.
+    pck_cert_chain: Vec<u8>,
+}
+
+/// Cursor over the quote bytes.
+struct QuoteReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> QuoteReader<'a> {
+    fn read(&mut self, len: usize, field: &'static str) -> Result<&'a [u8], TeeVerificationError> {
+        if self.bytes.len() < len {
+            return Err(TeeVerificationError::MalformedQuote(field));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn read_array(&mut self, field: &'static str) -> Result<[u8; 64], TeeVerificationError> {
+        Ok(self.read(64, field)?.try_into().unwrap())
+    }
+
+    fn read_u16(&mut self, field: &'static str) -> Result<u16, TeeVerificationError> {
+        Ok(u16::from_le_bytes(self.read(2, field)?.try_into().unwrap()))
+    }
+
+    fn read_u32(&mut self, field: &'static str) -> Result<u32, TeeVerificationError> {
+        Ok(u32::from_le_bytes(self.read(4, field)?.try_into().unwrap()))
+    }
+
+    /// Reads certification data with the expected type, returning its contents.
+    fn read_certification_data(
+        &mut self,
+        expected_type: u16,
+    ) -> Result<&'a [u8], TeeVerificationError> {
+        let data_type = self.read_u16("certification data type")?;
+        if data_type != expected_type {
+            return Err(TeeVerificationError::UnsupportedCertificationData(
+                data_type,
+            ));
+        }
+        let len = self.read_u32("certification data size")?;
+        self.read(len as usize, "certification data")
+    }
+}
+
+/// View of an Intel SGX DCAP quote (versions 3 and 4) with an ECDSA P-256 signature.
+///
+/// [`Self::verify()`] checks the quote signature chain up to the trusted root certificate: the quote
+/// is signed by the attestation key, which is bound to the quoting enclave report, which is in turn
+/// signed by the PCK certificate issued by the root certificate. Quotes of debug enclaves are rejected
+/// since the enclave memory can be inspected and modified by the host. TCB status and certificate revocation
+/// are not checked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SgxQuote {
+    pub version: u16,
+    /// Flags of the enclave attributes (`ATTRIBUTES.FLAGS`).
+    pub attributes_flags: u64,
+    /// Measurement of the enclave code (`MRENCLAVE`).
+    pub mrenclave: H256,
+    /// Measurement of the enclave signer (`MRSIGNER`).
+    pub mrsigner: H256,
+    /// User data embedded by the enclave into the report.
+    pub report_data: [u8; 64],
+    /// Quote header and report body covered by the quote signature.
+    signed_data: Vec<u8>,
+    signature_data: QuoteSignatureData,
+}
+
+impl SgxQuote {
+    const HEADER_LEN: usize = 48;
+    const REPORT_BODY_LEN: usize = 384;
+    const ATTRIBUTES_FLAGS_OFFSET: usize = Self::HEADER_LEN + 48;
+    const MRENCLAVE_OFFSET: usize = Self::HEADER_LEN + 64;
+    const MRSIGNER_OFFSET: usize = Self::HEADER_LEN + 128;
+    const REPORT_DATA_OFFSET: usize = Self::HEADER_LEN + 320;
+    /// Offset of the report data within a report body.
+    const REPORT_BODY_DATA_OFFSET: usize = 320;
+    /// Enclave attribute flag set for debug enclaves (`SGX_FLAGS_DEBUG`).
+    const DEBUG_FLAG: u64 = 1 << 1;
+    /// TEE type in the v4 quote header corresponding to SGX.
+    const SGX_TEE_TYPE: u32 = 0;
+    /// Certification data type for a PEM-encoded PCK certificate chain.
+    const PCK_CERT_CHAIN_TYPE: u16 = 5;
+    /// Certification data type for the quoting enclave report certification data (used in v4 quotes).
+    const QE_REPORT_CERTIFICATION_DATA_TYPE: u16 = 6;
+
+    pub fn parse(quote: &[u8]) -> Result<Self, TeeVerificationError> {
+        let signed_len = Self::HEADER_LEN + Self::REPORT_BODY_LEN;
+        if quote.len() < signed_len {
+            return Err(TeeVerificationError::QuoteTooShort(quote.len()));
+        }
+        let version = u16::from_le_bytes([quote[0], quote[1]]);
+        if !matches!(version, 3 | 4) {
+            return Err(TeeVerificationError::UnsupportedQuoteVersion(version));
+        }
+        let tee_type = u32::from_le_bytes(quote[4..8].try_into().unwrap());
+        if tee_type != Self::SGX_TEE_TYPE {
+            return Err(TeeVerificationError::NotSgxQuote(tee_type));
+        }
+
+        let mut report_data = [0_u8; 64];
+        report_data
+            .copy_from_slice(&quote[Self::REPORT_DATA_OFFSET..Self::REPORT_DATA_OFFSET + 64]);
+        let attributes_flags = u64::from_le_bytes(
+            quote[Self::ATTRIBUTES_FLAGS_OFFSET..Self::ATTRIBUTES_FLAGS_OFFSET + 8]
+                .try_into()
+                .unwrap(),
+        );
+        Ok(Self {
+            version,
+            attributes_flags,
+            mrenclave: H256::from_slice(
+                &quote[Self::MRENCLAVE_OFFSET..Self::MRENCLAVE_OFFSET + 32],
+            ),
+            mrsigner: H256::from_slice(&quote[Self::MRSIGNER_OFFSET..Self::MRSIGNER_OFFSET + 32]),
+            report_data,
+            signed_data: quote[..signed_len].to_vec(),
+            signature_data: Self::parse_signature_data(version, &quote[signed_len..])?,
+        })
+    }
+
+    fn parse_signature_data(
+        version: u16,
+        bytes: &[u8],
+    ) -> Result<QuoteSignatureData, TeeVerificationError> {
+        let mut reader = QuoteReader { bytes };
+        let len = reader.read_u32("signature data length")?;
+        let mut reader = QuoteReader {
+            bytes: reader.read(len as usize, "signature data")?,
+        };
+        let quote_signature = reader.read_array("quote signature")?;
+        let attestation_key = reader.read_array("attestation key")?;
+        if version == 4 {
+            // In v4 quotes, the quoting enclave report is wrapped into certification data.
+            reader = QuoteReader {
+                bytes: reader.read_certification_data(Self::QE_REPORT_CERTIFICATION_DATA_TYPE)?,
+            };
+        }
+        let qe_report = reader.read(Self::REPORT_BODY_LEN, "QE report")?.to_vec();
+        let qe_report_signature = reader.read_array("QE report signature")?;
+        let qe_auth_data_len = reader.read_u16("QE authentication data length")?;
+        let qe_auth_data = reader
+            .read(qe_auth_data_len as usize, "QE authentication data")?
+            .to_vec();
+        let pck_cert_chain = reader
+            .read_certification_data(Self::PCK_CERT_CHAIN_TYPE)?
+            .to_vec();
+
+        Ok(QuoteSignatureData {
+            quote_signature,
+            attestation_key,
+            qe_report,
+            qe_report_signature,
+            qe_auth_data,
+            pck_cert_chain,
+        })
+    }
+
+    /// Checks whether the quote is produced by an enclave in debug mode.
+    pub fn is_debug_enclave(&self) -> bool {
+        self.attributes_flags & Self::DEBUG_FLAG != 0
+    }
+
+    /// Verifies that the quote is produced by an allowed production enclave, commits to `pubkey` and is signed
+    /// by a PCK certificate issued by `root_certificate`. Certificates are checked to be valid at
+    /// the specified UNIX `timestamp` (in seconds).
+    ///
+    /// The enclave is expected to put `keccak256(pubkey)` into the first 32 bytes of the report data.
+    /// If `allowed_mrenclaves` is empty, all quotes are rejected.
+    pub fn verify(
+        &self,
+        pubkey: &[u8],
+        allowed_mrenclaves: &[H256],
+        root_certificate: &SgxRootCertificate,
+        timestamp: u64,
+    ) -> Result<(), TeeVerificationError> {
+        if allowed_mrenclaves.is_empty() {
+            return Err(TeeVerificationError::NoAllowedEnclaves);
+        }
+        if !allowed_mrenclaves.contains(&self.mrenclave) {
+            return Err(TeeVerificationError::EnclaveNotAllowed(self.mrenclave));
+        }
+        if self.is_debug_enclave() {
+            return Err(TeeVerificationError::DebugEnclave);
+        }
+        if self.report_data[..32] != keccak256(pubkey) {
+            return Err(TeeVerificationError::PubkeyMismatch);
+        }
+        self.verify_signature(root_certificate, timestamp)
+    }
+
+    fn verify_signature(
+        &self,
+        root_certificate: &SgxRootCertificate,
+        timestamp: u64,
+    ) -> Result<(), TeeVerificationError> {
+        let signature_data = &self.signature_data;
+        let mut attestation_key = [4_u8; 65];
+        attestation_key[1..].copy_from_slice(&signature_data.attestation_key);
+        verify_p256_signature(
+            &attestation_key,
+            &self.signed_data,
+            &signature_data.quote_signature,
+        )
+        .map_err(TeeVerificationError::InvalidQuoteSignature)?;
+
+        // The quoting enclave binds the attestation key by putting `sha256(attestation_key || qe_auth_data)`
+        // into the report data, padded with zeros.
+        let mut hasher = Sha256::new();
+        hasher.update(signature_data.attestation_key);
+        hasher.update(&signature_data.qe_auth_data);
+        let expected_report_data = hasher.finalize();
+        let qe_report_data = &signature_data.qe_report[Self::REPORT_BODY_DATA_OFFSET..];
+        if qe_report_data[..32] != expected_report_data[..]
+            || qe_report_data[32..].iter().any(|&byte| byte != 0)
+        {
+            return Err(TeeVerificationError::AttestationKeyMismatch);
+        }
+
+        let pck_key =
+            verify_certificate_chain(&signature_data.pck_cert_chain, root_certificate, timestamp)?;
+        verify_p256_signature(
+            &pck_key,
+            &signature_data.qe_report,
+            &signature_data.qe_report_signature,
+        )
+        .map_err(TeeVerificationError::InvalidQuoteSignature)
+    }
+}
+
+/// Verifies a raw `r || s` ECDSA P-256 signature over SHA-256 of `message`.
+fn verify_p256_signature(
+    sec1_key: &[u8],
+    message: &[u8],
+    signature: &[u8; 64],
+) -> Result<(), String> {
+    let key = VerifyingKey::from_sec1_bytes(sec1_key).map_err(|err| err.to_string())?;
+    let signature = Signature::try_from(&signature[..]).map_err(|err| err.to_string())?;
+    key.verify(message, &signature)
+        .map_err(|err| err.to_string())
+}
+
+fn chain_error(err: impl fmt::Display) -> TeeVerificationError {
+    TeeVerificationError::InvalidCertificateChain(err.to_string())
+}
+
+/// Verifies the PEM-encoded certificate chain (ordered from the leaf to the root) and returns
+/// the SEC1-encoded public key of the leaf certificate.
+fn verify_certificate_chain(
+    pem_chain: &[u8],
+    root_certificate: &SgxRootCertificate,
+    timestamp: u64,
+) -> Result<Vec<u8>, TeeVerificationError> {
+    let ders = Pem::iter_from_buffer(pem_chain)
+        .map(|pem| pem.map(|pem| pem.contents))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(chain_error)?;
+    let certificates = ders
+        .iter()
+        .map(|der| parse_x509_certificate(der).map(|(_, certificate)| certificate))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(chain_error)?;
+    let (_, trusted_root) = parse_x509_certificate(&root_certificate.der)
+        .expect("root certificate is checked to be valid on creation");
+    // The root certificate is trusted based on its subject and public key.
+    let is_trusted = certificates.last().map_or(false, |root| {
+        root.subject() == trusted_root.subject()
+            && root.public_key().raw == trusted_root.public_key().raw
+    });
+    if !is_trusted {
+        return Err(TeeVerificationError::UntrustedRootCertificate);
+    }
+    let time = ASN1Time::from_timestamp(timestamp as i64).map_err(chain_error)?;
+
+    for (i, certificate) in certificates.iter().enumerate() {
+        // The root certificate is self-signed.
+        let issuer = certificates.get(i + 1).unwrap_or(certificate);
+        let subject = certificate.subject();
+        if !certificate.validity().is_valid_at(time) {
+            return Err(chain_error(format_args!(
+                "certificate `{subject}` is not valid at {timestamp}"
+            )));
+        }
+        if certificate.issuer() != issuer.subject() || !issuer.is_ca() {
+            return Err(chain_error(format_args!(
+                "certificate `{subject}` is not issued by `{}`",
+                issuer.subject()
+            )));
+        }
+        if certificate.signature_algorithm.algorithm != OID_SIG_ECDSA_WITH_SHA256 {
+            return Err(chain_error(format_args!(
+                "certificate `{subject}` is not signed using ECDSA with SHA-256"
+            )));
+        }
+        let key = VerifyingKey::from_sec1_bytes(&issuer.public_key().subject_public_key.data)
+            .map_err(chain_error)?;
+        let signature =
+            Signature::from_der(&certificate.signature_value.data).map_err(chain_error)?;
+        key.verify(certificate.tbs_certificate.as_ref(), &signature)
+            .map_err(|err| chain_error(format_args!("certificate `{subject}`: {err}")))?;
+    }
+    Ok(certificates[0]
+        .public_key()
+        .subject_public_key
+        .data
+        .to_vec())
+}
+
+/// Execution result for an L1 batch submitted by a TEE prover.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TeeProof {
+    pub l1_batch_number: L1BatchNumber,
+    pub root_hash: H256,
+}
+
+impl TeeProof {
+    /// Returns the message signed by the enclave key: `keccak256(l1_batch_number || root_hash)`,
+    /// with the batch number encoded as a big-endian `u32`.
+    pub fn signed_message(&self) -> H256 {
+        let mut bytes = Vec::with_capacity(36);
+        bytes.extend_from_slice(&self.l1_batch_number.0.to_be_bytes());
+        bytes.extend_from_slice(self.root_hash.as_bytes());
+        H256(keccak256(&bytes))
+    }
+
+    /// Checks that `signature` over this proof was produced by the key `pubkey`.
+    pub fn verify_signature(
+        &self,
+        pubkey: &[u8],
+        signature: &[u8],
+    ) -> Result<(), TeeVerificationError> {
+        let expected = pubkey_to_address(pubkey)?;
+        let signature = PackedEthSignature::deserialize_packed(signature)
+            .map_err(|err| TeeVerificationError::InvalidSignature(err.to_string()))?;
+        let actual = signature
+            .signature_recover_signer(&self.signed_message())
+            .map_err(|err| TeeVerificationError::InvalidSignature(err.to_string()))?;
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(TeeVerificationError::SignerMismatch { expected, actual })
+        }
+    }
+}
+
+fn pubkey_to_address(pubkey: &[u8]) -> Result<Address, TeeVerificationError> {
+    if pubkey.len() != 64 {
+        return Err(TeeVerificationError::InvalidPubkey(pubkey.len()));
+    }
+    Ok(Address::from_slice(&keccak256(pubkey)[12..]))
+}
+
+mod hex_bytes {
+    use serde::{de::Error as _, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("0x{}", hex::encode(bytes)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        hex::decode(s.strip_prefix("0x").unwrap_or(&s)).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::{
+        ecdsa::{signature::Signer, SigningKey},
+        pkcs8::DecodePrivateKey,
+    };
+    use parity_crypto::publickey::{Generator, Random};
+    use rcgen::{BasicConstraints, Certificate, CertificateParams, DnType, IsCa};
+
+    fn create_certificate(name: &str, is_ca: bool) -> Certificate {
+        let mut params = CertificateParams::default();
+        params.distinguished_name.push(DnType::CommonName, name);
+        if is_ca {
+            params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        }
+        Certificate::from_params(params).unwrap()
+    }
+
+    /// Mock PCK certificate chain: root CA -> intermediate CA -> PCK certificate.
+    struct MockPki {
+        root: Certificate,
+        pck_key: SigningKey,
+        pck_cert_chain: Vec<u8>,
+    }
+
+    impl MockPki {
+        fn new() -> Self {
+            let root = create_certificate("Mock SGX Root CA", true);
+            let intermediate = create_certificate("Mock SGX Platform CA", true);
+            let pck = create_certificate("Mock SGX PCK Certificate", false);
+            let pck_cert_chain = [
+                pck.serialize_pem_with_signer(&intermediate).unwrap(),
+                intermediate.serialize_pem_with_signer(&root).unwrap(),
+                root.serialize_pem().unwrap(),
+            ]
+            .concat();
+            let pck_key = SigningKey::from_pkcs8_der(&pck.serialize_private_key_der()).unwrap();
+            Self {
+                root,
+                pck_key,
+                pck_cert_chain: pck_cert_chain.into_bytes(),
+            }
+        }
+
+        fn root_certificate(&self) -> SgxRootCertificate {
+            SgxRootCertificate::new(self.root.serialize_pem().unwrap().as_bytes()).unwrap()
+        }
+    }
+
+    fn certification_data(data_type: u16, data: &[u8]) -> Vec<u8> {
+        let mut bytes = data_type.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    fn mock_quote(version: u16, mrenclave: H256, pubkey: &[u8], pki: &MockPki) -> Vec<u8> {
+        mock_quote_with_flags(version, 0, mrenclave, pubkey, pki)
+    }
+
+    fn mock_quote_with_flags(
+        version: u16,
+        attributes_flags: u64,
+        mrenclave: H256,
+        pubkey: &[u8],
+        pki: &MockPki,
+    ) -> Vec<u8> {
+        let mut quote = vec![0_u8; SgxQuote::HEADER_LEN + SgxQuote::REPORT_BODY_LEN];
+        quote[..2].copy_from_slice(&version.to_le_bytes());
+        quote[SgxQuote::ATTRIBUTES_FLAGS_OFFSET..SgxQuote::ATTRIBUTES_FLAGS_OFFSET + 8]
+            .copy_from_slice(&attributes_flags.to_le_bytes());
+        quote[SgxQuote::MRENCLAVE_OFFSET..SgxQuote::MRENCLAVE_OFFSET + 32]
+            .copy_from_slice(mrenclave.as_bytes());
+        quote[SgxQuote::REPORT_DATA_OFFSET..SgxQuote::REPORT_DATA_OFFSET + 32]
+            .copy_from_slice(&keccak256(pubkey));
+
+        let attestation_key = SigningKey::from_bytes(&[7_u8; 32]).unwrap();
+        let attestation_pubkey = attestation_key.verifying_key().to_encoded_point(false);
+        let attestation_pubkey = &attestation_pubkey.as_bytes()[1..];
+        let qe_auth_data = [1_u8; 32];
+        let mut qe_report = vec![0_u8; SgxQuote::REPORT_BODY_LEN];
+        let qe_report_data: [u8; 32] = Sha256::new()
+            .chain_update(attestation_pubkey)
+            .chain_update(qe_auth_data)
+            .finalize()
+            .into();
+        qe_report[SgxQuote::REPORT_BODY_DATA_OFFSET..SgxQuote::REPORT_BODY_DATA_OFFSET + 32]
+            .copy_from_slice(&qe_report_data);
+
+        let mut qe_data = qe_report.clone();
+        let qe_report_signature: Signature = pki.pck_key.sign(&qe_report);
+        qe_data.extend_from_slice(qe_report_signature.as_ref());
+        qe_data.extend_from_slice(&(qe_auth_data.len() as u16).to_le_bytes());
+        qe_data.extend_from_slice(&qe_auth_data);
+        qe_data.extend_from_slice(&certification_data(
+            SgxQuote::PCK_CERT_CHAIN_TYPE,
+            &pki.pck_cert_chain,
+        ));
+
+        let quote_signature: Signature = attestation_key.sign(&quote);
+        let mut signature_data = quote_signature.as_ref().to_vec();
+        signature_data.extend_from_slice(attestation_pubkey);
+        if version == 4 {
+            signature_data.extend_from_slice(&certification_data(
+                SgxQuote::QE_REPORT_CERTIFICATION_DATA_TYPE,
+                &qe_data,
+            ));
+        } else {
+            signature_data.extend_from_slice(&qe_data);
+        }
+        quote.extend_from_slice(&(signature_data.len() as u32).to_le_bytes());
+        quote.extend_from_slice(&signature_data);
+        quote
+    }
+
+    #[test]
+    fn verifying_sgx_quote() {
+        let pki = MockPki::new();
+        let root_certificate = pki.root_certificate();
+        let timestamp = 1_700_000_000;
+        let pubkey = [1_u8; 64];
+        let mrenclave = H256::repeat_byte(0x42);
+
+        for version in [3, 4] {
+            let quote = SgxQuote::parse(&mock_quote(version, mrenclave, &pubkey, &pki)).unwrap();
+            assert_eq!(quote.version, version);
+            assert_eq!(quote.mrenclave, mrenclave);
+            quote
+                .verify(&pubkey, &[mrenclave], &root_certificate, timestamp)
+                .unwrap();
+        }
+
+        let quote = SgxQuote::parse(&mock_quote(3, mrenclave, &pubkey, &pki)).unwrap();
+        let err = quote
+            .verify(&pubkey, &[], &root_certificate, timestamp)
+            .unwrap_err();
+        assert!(matches!(err, TeeVerificationError::NoAllowedEnclaves));
+        let err = quote
+            .verify(&pubkey, &[H256::zero()], &root_certificate, timestamp)
+            .unwrap_err();
+        assert!(matches!(err, TeeVerificationError::EnclaveNotAllowed(_)));
+        let err = quote
+            .verify(&[2_u8; 64], &[mrenclave], &root_certificate, timestamp)
+            .unwrap_err();
+        assert!(matches!(err, TeeVerificationError::PubkeyMismatch));
+
+        let err = SgxQuote::parse(&[0_u8; 100]).unwrap_err();
+        assert!(matches!(err, TeeVerificationError::QuoteTooShort(100)));
+        let mut tdx_quote = mock_quote(4, mrenclave, &pubkey, &pki);
+        tdx_quote[4] = 0x81;
+        let err = SgxQuote::parse(&tdx_quote).unwrap_err();
+        assert!(matches!(err, TeeVerificationError::NotSgxQuote(0x81)));
+        let quote_bytes = mock_quote(3, mrenclave, &pubkey, &pki);
+        let err = SgxQuote::parse(&quote_bytes[..quote_bytes.len() - 1]).unwrap_err();
+        assert!(matches!(err, TeeVerificationError::MalformedQuote(_)));
+    }
+
+    #[test]
+    fn debug_enclave_quotes_are_rejected() {
+        const INIT_FLAG: u64 = 1;
+
+        let pki = MockPki::new();
+        let root_certificate = pki.root_certificate();
+        let timestamp = 1_700_000_000;
+        let pubkey = [1_u8; 64];
+        let mrenclave = H256::repeat_byte(0x42);
+
+        let quote_bytes = mock_quote_with_flags(3, INIT_FLAG, mrenclave, &pubkey, &pki);
+        let quote = SgxQuote::parse(&quote_bytes).unwrap();
+        assert!(!quote.is_debug_enclave());
+        quote
+            .verify(&pubkey, &[mrenclave], &root_certificate, timestamp)
+            .unwrap();
+
+        for version in [3, 4] {
+            let flags = INIT_FLAG | SgxQuote::DEBUG_FLAG;
+            let quote_bytes = mock_quote_with_flags(version, flags, mrenclave, &pubkey, &pki);
+            let quote = SgxQuote::parse(&quote_bytes).unwrap();
+            assert_eq!(quote.attributes_flags, flags);
+            assert!(quote.is_debug_enclave());
+            let err = quote
+                .verify(&pubkey, &[mrenclave], &root_certificate, timestamp)
+                .unwrap_err();
+            assert!(matches!(err, TeeVerificationError::DebugEnclave));
+        }
+    }
+
+    #[test]
+    fn sgx_quote_signature_chain_is_checked() {
+        let pki = MockPki::new();
+        let root_certificate = pki.root_certificate();
+        let timestamp = 1_700_000_000;
+        let pubkey = [1_u8; 64];
+        let mrenclave = H256::repeat_byte(0x42);
+        let quote_bytes = mock_quote(3, mrenclave, &pubkey, &pki);
+
+        // Tampering with the report body invalidates the quote signature.
+        let mut tampered_quote = quote_bytes.clone();
+        tampered_quote[SgxQuote::MRSIGNER_OFFSET] ^= 1;
+        let err = SgxQuote::parse(&tampered_quote)
+            .unwrap()
+            .verify(&pubkey, &[mrenclave], &root_certificate, timestamp)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            TeeVerificationError::InvalidQuoteSignature(_)
+        ));
+
+        // Tampering with the QE authentication data breaks the attestation key binding.
+        let mut quote = SgxQuote::parse(&quote_bytes).unwrap();
+        quote.signature_data.qe_auth_data[0] ^= 1;
+        let err = quote
+            .verify(&pubkey, &[mrenclave], &root_certificate, timestamp)
+            .unwrap_err();
+        assert!(matches!(err, TeeVerificationError::AttestationKeyMismatch));
+
+        // A chain issued by another root is rejected.
+        let quote = SgxQuote::parse(&quote_bytes).unwrap();
+        let other_root_certificate = MockPki::new().root_certificate();
+        let err = quote
+            .verify(&pubkey, &[mrenclave], &other_root_certificate, timestamp)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            TeeVerificationError::UntrustedRootCertificate
+        ));
+
+        // A QE report signed by a key other than the PCK certificate key is rejected.
+        let other_pki = MockPki {
+            pck_key: SigningKey::from_bytes(&[9_u8; 32]).unwrap(),
+            ..MockPki::new()
+        };
+        let quote = SgxQuote::parse(&mock_quote(3, mrenclave, &pubkey, &other_pki)).unwrap();
+        let err = quote
+            .verify(
+                &pubkey,
+                &[mrenclave],
+                &other_pki.root_certificate(),
+                timestamp,
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            TeeVerificationError::InvalidQuoteSignature(_)
+        ));
+
+        // Certificates must be valid at the verification time.
+        let quote = SgxQuote::parse(&quote_bytes).unwrap();
+        let err = quote
+            .verify(&pubkey, &[mrenclave], &root_certificate, 0)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            TeeVerificationError::InvalidCertificateChain(_)
+        ));
+    }
+
+    #[test]
+    fn verifying_proof_signature() {
+        let key_pair = Random.generate();
+        let pubkey = key_pair.public().as_bytes().to_vec();
+        let proof = TeeProof {
+            l1_batch_number: L1BatchNumber(5),
+            root_hash: H256::repeat_byte(1),
+        };
+        let private_key = H256::from_slice(key_pair.secret().as_bytes());
+        let signature = PackedEthSignature::sign_raw(&private_key, &proof.signed_message())
+            .unwrap()
+            .serialize_packed();
+        proof.verify_signature(&pubkey, &signature).unwrap();
+
+        let other_proof = TeeProof {
+            l1_batch_number: L1BatchNumber(6),
+            ..proof
+        };
+        let err = other_proof
+            .verify_signature(&pubkey, &signature)
+            .unwrap_err();
+        assert!(matches!(err, TeeVerificationError::SignerMismatch { .. }));
+
+        let other_pubkey = Random.generate().public().as_bytes().to_vec();
+        assert!(proof.verify_signature(&other_pubkey, &signature).is_err());
+    }
+}
@@ -596,8 +596,9 @@ impl TransactionRequest {
                         DecoderError::RlpIncorrectListLen,
                     ));
                 }
+                // The chain ID is a part of the EIP-712 domain, so it must match the chain ID of the node.
                 let tx_chain_id = rlp.val_at(10).ok();
-                if tx_chain_id.is_some() && tx_chain_id != Some(chain_id) {
+                if tx_chain_id != Some(chain_id) {
                     return Err(SerializationTransactionError::WrongChainId(tx_chain_id));
                 }
 
@@ -622,7 +623,31 @@ impl TransactionRequest {
                 }
             }
             Some(&EIP_2930_TX_TYPE) => {
-                return Err(SerializationTransactionError::AccessListsNotSupported)
+                rlp = Rlp::new(&bytes[1..]);
+                if rlp.item_count()? != 11 {
+                    return Err(SerializationTransactionError::DecodeRlpError(
+                        DecoderError::RlpIncorrectListLen,
+                    ));
+                }
+                if let Ok(access_list_rlp) = rlp.at(7) {
+                    if access_list_rlp.item_count()? > 0 {
+                        return Err(SerializationTransactionError::AccessListsNotSupported);
+                    }
+                }
+
+                let tx_chain_id = rlp.val_at(0).ok();
+                if tx_chain_id != Some(chain_id) {
+                    return Err(SerializationTransactionError::WrongChainId(tx_chain_id));
+                }
+                Self {
+                    chain_id: tx_chain_id,
+                    v: Some(rlp.val_at(8)?),
+                    r: Some(rlp.val_at(9)?),
+                    s: Some(rlp.val_at(10)?),
+                    raw: Some(Bytes(rlp.as_raw().to_vec())),
+                    transaction_type: Some(EIP_2930_TX_TYPE.into()),
+                    ..Self::decode_standard_fields(&rlp, 1)?
+                }
             }
             _ => return Err(SerializationTransactionError::UnknownTransactionFormat),
         };
@@ -1262,7 +1287,46 @@ mod tests {
     }
 
     #[test]
-    fn check_failed_to_decode_eip2930() {
+    fn check_recovered_public_key_eip2930() {
+        let random_tx_max_size = 1_000_000; // bytes
+        let private_key = H256::random();
+        let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+
+        let transaction_request = TransactionRequest {
+            transaction_type: Some(EIP_2930_TX_TYPE.into()),
+            nonce: U256::from(1u32),
+            to: Some(Address::random()),
+            value: U256::from(10u32),
+            gas_price: U256::from(11u32),
+            gas: U256::from(12u32),
+            input: Bytes::from(vec![1, 2, 3]),
+            chain_id: Some(270),
+            ..Default::default()
+        };
+        let mut rlp_stream = RlpStream::new();
+        transaction_request.rlp(&mut rlp_stream, 270, None);
+        let mut data = rlp_stream.out().to_vec();
+        data.insert(0, EIP_2930_TX_TYPE);
+        let msg = PackedEthSignature::message_to_signed_bytes(&data);
+        let signature = PackedEthSignature::sign_raw(&private_key, &msg).unwrap();
+
+        let encoded_tx = transaction_request.get_signed_bytes(&signature, L2ChainId(270));
+        let (decoded_tx, _) =
+            TransactionRequest::from_bytes(encoded_tx.as_slice(), 270, random_tx_max_size).unwrap();
+        assert_eq!(decoded_tx.from, Some(address));
+        assert_eq!(decoded_tx.transaction_type, Some(EIP_2930_TX_TYPE.into()));
+        assert_eq!(decoded_tx.gas_price, U256::from(11u32));
+
+        let decoded_tx =
+            TransactionRequest::from_bytes(encoded_tx.as_slice(), 272, random_tx_max_size);
+        assert_eq!(
+            decoded_tx,
+            Err(SerializationTransactionError::WrongChainId(Some(270)))
+        );
+    }
+
+    #[test]
+    fn check_decode_eip2930_with_access_list() {
         let random_tx_max_size = 1_000_000; // bytes
         let private_key = H256::random();
         let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
@@ -1277,6 +1341,7 @@ mod tests {
             gas: U256::from(12u32),
             input: Bytes::from(vec![1, 2, 3]),
             chain_id: Some(270),
+            access_list: Some(vec![Default::default()]),
             ..Default::default()
         };
         let mut rlp_stream = RlpStream::new();
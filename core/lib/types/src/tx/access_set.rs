@@ -0,0 +1,274 @@
+//! Storage access sets of transactions in an L1 batch.
+//!
+//! Access sets are recorded when an L1 batch is sealed and serve as dependency hints for research
+//! on parallel execution and conflict-aware transaction ordering.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    convert::TryInto,
+};
+
+use zksync_basic_types::{AccountTreeId, H256};
+use zksync_utils::u256_to_h256;
+
+use crate::{StorageKey, StorageLogQuery};
+
+/// Hashed storage keys read and written by a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxAccessSet {
+    pub tx_hash: H256,
+    pub index_in_l1_batch: u32,
+    /// Sorted hashed keys of the slots read by the transaction.
+    pub read_keys: Vec<H256>,
+    /// Sorted hashed keys of the slots written by the transaction. Rolled back writes are not included.
+    pub written_keys: Vec<H256>,
+}
+
+impl TxAccessSet {
+    /// Groups storage logs of an L1 batch by transaction. `tx_hashes` must list the batch transactions
+    /// in the execution order; logs produced by the bootloader outside of transactions are ignored.
+    pub fn from_storage_logs(tx_hashes: &[H256], logs: &[StorageLogQuery]) -> Vec<Self> {
+        let mut read_keys = vec![BTreeSet::new(); tx_hashes.len()];
+        // Number of writes to each key not rolled back, for each transaction.
+        let mut write_counts = vec![HashMap::<H256, i64>::new(); tx_hashes.len()];
+        for log in logs {
+            let query = &log.log_query;
+            let tx_index = usize::from(query.tx_number_in_block);
+            if tx_index >= tx_hashes.len() {
+                continue;
+            }
+            let key = StorageKey::new(AccountTreeId::new(query.address), u256_to_h256(query.key))
+                .hashed_key();
+            if !query.rw_flag {
+                read_keys[tx_index].insert(key);
+            } else {
+                let count = write_counts[tx_index].entry(key).or_default();
+                *count += if query.rollback { -1 } else { 1 };
+            }
+        }
+
+        tx_hashes
+            .iter()
+            .zip(read_keys.into_iter().zip(write_counts))
+            .enumerate()
+            .map(|(index, (&tx_hash, (read_keys, write_counts)))| {
+                let mut written_keys: Vec<_> = write_counts
+                    .into_iter()
+                    .filter_map(|(key, count)| (count > 0).then_some(key))
+                    .collect();
+                written_keys.sort_unstable();
+                Self {
+                    tx_hash,
+                    index_in_l1_batch: index as u32,
+                    read_keys: read_keys.into_iter().collect(),
+                    written_keys,
+                }
+            })
+            .collect()
+    }
+
+    /// For each transaction, returns indices of earlier transactions it directly depends on: the last preceding
+    /// writer of each slot the transaction accesses, and (for written slots) preceding readers of the slot since
+    /// its last write. Other conflicts are implied transitively, so executing transactions in an order consistent
+    /// with these dependencies yields the same result as the sequential execution. `access_sets` must be ordered
+    /// by the index in the batch.
+    pub fn dependencies(access_sets: &[Self]) -> Vec<Vec<u32>> {
+        #[derive(Default)]
+        struct SlotAccess {
+            last_writer: Option<u32>,
+            readers_since_write: Vec<u32>,
+        }
+
+        let mut slots = HashMap::<H256, SlotAccess>::new();
+        access_sets
+            .iter()
+            .map(|access_set| {
+                let index = access_set.index_in_l1_batch;
+                let mut dependencies = BTreeSet::new();
+                for key in &access_set.read_keys {
+                    let slot = slots.entry(*key).or_default();
+                    dependencies.extend(slot.last_writer);
+                }
+                for key in &access_set.written_keys {
+                    let slot = slots.entry(*key).or_default();
+                    dependencies.extend(slot.last_writer);
+                    dependencies.extend(slot.readers_since_write.iter().copied());
+                }
+
+                for key in &access_set.read_keys {
+                    slots.get_mut(key).unwrap().readers_since_write.push(index);
+                }
+                for key in &access_set.written_keys {
+                    let slot = slots.get_mut(key).unwrap();
+                    slot.last_writer = Some(index);
+                    slot.readers_since_write.clear();
+                }
+                dependencies.remove(&index);
+                dependencies.into_iter().collect()
+            })
+            .collect()
+    }
+}
+
+/// Encodes access sets of an L1 batch in a compact binary format: hashed keys accessed in the batch are stored once
+/// in a sorted dictionary, and transactions reference them by 4-byte indices. All integers are big-endian.
+///
+/// ```text
+/// key_count: u32, keys: [H256; key_count],
+/// tx_count: u32, [tx_hash: H256, read_count: u32, written_count: u32, key_indices: [u32; read_count + written_count]; tx_count]
+/// ```
+///
+/// Transactions are stored in the order of `access_sets`, which must be ordered by the index in the batch.
+pub fn encode_access_sets(access_sets: &[TxAccessSet]) -> Vec<u8> {
+    let keys: BTreeSet<_> = access_sets
+        .iter()
+        .flat_map(|set| set.read_keys.iter().chain(&set.written_keys))
+        .copied()
+        .collect();
+    let key_indices: HashMap<_, _> = keys
+        .iter()
+        .enumerate()
+        .map(|(index, key)| (*key, index as u32))
+        .collect();
+
+    let mut bytes = Vec::with_capacity(8 + keys.len() * 32);
+    bytes.extend_from_slice(&(keys.len() as u32).to_be_bytes());
+    for key in &keys {
+        bytes.extend_from_slice(key.as_bytes());
+    }
+    bytes.extend_from_slice(&(access_sets.len() as u32).to_be_bytes());
+    for set in access_sets {
+        bytes.extend_from_slice(set.tx_hash.as_bytes());
+        bytes.extend_from_slice(&(set.read_keys.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(&(set.written_keys.len() as u32).to_be_bytes());
+        for key in set.read_keys.iter().chain(&set.written_keys) {
+            bytes.extend_from_slice(&key_indices[key].to_be_bytes());
+        }
+    }
+    bytes
+}
+
+/// Decodes access sets encoded with [`encode_access_sets()`].
+///
+/// # Panics
+///
+/// Panics if `bytes` are malformed.
+pub fn decode_access_sets(bytes: &[u8]) -> Vec<TxAccessSet> {
+    fn take<'a>(bytes: &mut &'a [u8], len: usize) -> &'a [u8] {
+        assert!(bytes.len() >= len, "access sets are truncated");
+        let (head, tail) = bytes.split_at(len);
+        *bytes = tail;
+        head
+    }
+
+    fn take_u32(bytes: &mut &[u8]) -> u32 {
+        u32::from_be_bytes(take(bytes, 4).try_into().unwrap())
+    }
+
+    let mut bytes = bytes;
+    let key_count = take_u32(&mut bytes) as usize;
+    let keys: Vec<_> = (0..key_count)
+        .map(|_| H256::from_slice(take(&mut bytes, 32)))
+        .collect();
+    let tx_count = take_u32(&mut bytes);
+    let access_sets = (0..tx_count)
+        .map(|index_in_l1_batch| {
+            let tx_hash = H256::from_slice(take(&mut bytes, 32));
+            let read_count = take_u32(&mut bytes);
+            let written_count = take_u32(&mut bytes);
+            let mut take_keys = |count| -> Vec<_> {
+                (0..count)
+                    .map(|_| keys[take_u32(&mut bytes) as usize])
+                    .collect()
+            };
+            let read_keys = take_keys(read_count);
+            let written_keys = take_keys(written_count);
+            TxAccessSet {
+                tx_hash,
+                index_in_l1_batch,
+                read_keys,
+                written_keys,
+            }
+        })
+        .collect();
+    assert!(bytes.is_empty(), "access sets have trailing bytes");
+    access_sets
+}
+
+#[cfg(test)]
+mod tests {
+    use zk_evm::aux_structures::{LogQuery, Timestamp};
+    use zksync_basic_types::{Address, U256};
+
+    use super::*;
+    use crate::StorageLogQueryType;
+
+    fn log(tx_number_in_block: u16, key: u64, rw_flag: bool, rollback: bool) -> StorageLogQuery {
+        StorageLogQuery {
+            log_query: LogQuery {
+                timestamp: Timestamp(0),
+                tx_number_in_block,
+                aux_byte: 0,
+                shard_id: 0,
+                address: Address::repeat_byte(1),
+                key: U256::from(key),
+                read_value: U256::zero(),
+                written_value: U256::one(),
+                rw_flag,
+                rollback,
+                is_service: false,
+            },
+            log_type: if rw_flag {
+                StorageLogQueryType::RepeatedWrite
+            } else {
+                StorageLogQueryType::Read
+            },
+        }
+    }
+
+    fn hashed_key(key: u64) -> H256 {
+        StorageKey::new(
+            AccountTreeId::new(Address::repeat_byte(1)),
+            u256_to_h256(key.into()),
+        )
+        .hashed_key()
+    }
+
+    #[test]
+    fn access_sets_and_dependencies() {
+        let tx_hashes = [
+            H256::repeat_byte(1),
+            H256::repeat_byte(2),
+            H256::repeat_byte(3),
+        ];
+        let logs = [
+            // Tx #0 reads slot 1 and writes slot 2.
+            log(0, 1, false, false),
+            log(0, 2, true, false),
+            // Tx #1 reads slot 2; its write to slot 3 is rolled back.
+            log(1, 2, false, false),
+            log(1, 3, true, false),
+            log(1, 3, true, true),
+            // Tx #2 writes slot 1.
+            log(2, 1, true, false),
+            // Bootloader logs after the last transaction are ignored.
+            log(3, 4, true, false),
+        ];
+
+        let access_sets = TxAccessSet::from_storage_logs(&tx_hashes, &logs);
+        assert_eq!(access_sets.len(), 3);
+        assert_eq!(access_sets[0].read_keys, [hashed_key(1)]);
+        assert_eq!(access_sets[0].written_keys, [hashed_key(2)]);
+        assert_eq!(access_sets[1].read_keys, [hashed_key(2)]);
+        assert!(access_sets[1].written_keys.is_empty());
+        assert_eq!(access_sets[2].tx_hash, tx_hashes[2]);
+        assert_eq!(access_sets[2].written_keys, [hashed_key(1)]);
+
+        let dependencies = TxAccessSet::dependencies(&access_sets);
+        assert_eq!(dependencies, [vec![], vec![0], vec![0]]);
+
+        let encoded = encode_access_sets(&access_sets);
+        assert_eq!(encoded.len(), 4 + 2 * 32 + 4 + 3 * (32 + 8) + 4 * 4);
+        assert_eq!(decode_access_sets(&encoded), access_sets);
+    }
+}
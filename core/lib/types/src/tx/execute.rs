@@ -1,8 +1,14 @@
-use crate::{web3::ethabi, Address, EIP712TypedStructure, StructBuilder, H256, U256};
+use crate::{
+    web3::ethabi, Address, EIP712TypedStructure, StructBuilder, CONTRACT_DEPLOYER_ADDRESS, H256,
+    U256,
+};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use zksync_utils::ZeroPrefixHexSerde;
 
+static CREATE_EVM_FUNCTION_SIGNATURE: Lazy<[u8; 4]> =
+    Lazy::new(|| ethabi::short_signature("createEVM", &[ethabi::ParamType::Bytes]));
+
 /// `Execute` transaction executes a previously deployed smart contract in the L2 rollup.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -66,6 +72,30 @@ impl Execute {
         FUNCTION_SIGNATURE.iter().copied().chain(params).collect()
     }
 
+    /// Prepares calldata to invoke deployer contract.
+    /// This method encodes parameters for the `createEVM` method, which deploys a contract with EVM bytecode.
+    pub fn encode_deploy_params_create_evm(init_code: Vec<u8>) -> Vec<u8> {
+        let params = ethabi::encode(&[ethabi::Token::Bytes(init_code)]);
+        CREATE_EVM_FUNCTION_SIGNATURE
+            .iter()
+            .copied()
+            .chain(params)
+            .collect()
+    }
+
+    /// Returns the init code if this transaction deploys a contract with EVM bytecode,
+    /// i.e., calls the `createEVM` method of the deployer contract.
+    pub fn evm_init_code(&self) -> Option<Vec<u8>> {
+        if self.contract_address != CONTRACT_DEPLOYER_ADDRESS {
+            return None;
+        }
+        let params = self
+            .calldata
+            .strip_prefix(CREATE_EVM_FUNCTION_SIGNATURE.as_slice())?;
+        let mut tokens = ethabi::decode(&[ethabi::ParamType::Bytes], params).ok()?;
+        tokens.pop()?.into_bytes()
+    }
+
     /// Number of new factory dependencies in this transaction
     pub fn factory_deps_length(&self) -> usize {
         self.factory_deps
@@ -73,4 +103,32 @@ impl Execute {
             .map(|deps| deps.len())
             .unwrap_or_default()
     }
+
+    /// Total size of new factory dependencies in this transaction in bytes
+    pub fn factory_deps_size(&self) -> usize {
+        self.factory_deps
+            .as_ref()
+            .map(|deps| deps.iter().map(Vec::len).sum())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evm_init_code_roundtrip() {
+        let init_code = vec![0x60, 0x80, 0x60, 0x40, 0x52];
+        let mut execute = Execute {
+            contract_address: CONTRACT_DEPLOYER_ADDRESS,
+            calldata: Execute::encode_deploy_params_create_evm(init_code.clone()),
+            value: U256::zero(),
+            factory_deps: None,
+        };
+        assert_eq!(execute.evm_init_code(), Some(init_code));
+
+        execute.calldata = Execute::encode_deploy_params_create(H256::zero(), H256::zero(), vec![]);
+        assert_eq!(execute.evm_init_code(), None);
+    }
 }
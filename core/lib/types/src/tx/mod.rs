@@ -8,12 +8,18 @@ use std::fmt::Debug;
 use zksync_basic_types::{Address, H256};
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
+pub mod access_set;
 pub mod execute;
 pub mod primitives;
+pub mod rejection;
 pub mod tx_execution_info;
 
-pub use self::execute::Execute;
-use crate::vm_trace::Call;
+pub use self::{
+    access_set::{decode_access_sets, encode_access_sets, TxAccessSet},
+    execute::Execute,
+    rejection::{TxRejectedResource, TxRejection, TxRejectionKind},
+};
+use crate::vm_trace::{truncate_message, Call};
 use crate::Transaction;
 pub use tx_execution_info::ExecutionMetrics;
 use tx_execution_info::TxExecutionStatus;
@@ -29,9 +35,24 @@ pub struct TransactionExecutionResult {
     pub compressed_bytecodes: Vec<CompressedBytecodeInfo>,
     pub call_traces: Vec<Call>,
     pub revert_reason: Option<String>,
+    /// Whether the call traces or the revert reason were truncated to fit into the size limits.
+    pub result_truncated: bool,
 }
 
 impl TransactionExecutionResult {
+    /// Truncates call traces and the revert reason to the specified sizes in bytes, and sets
+    /// the [`Self::result_truncated`] flag if anything was truncated.
+    pub fn truncate_results(&mut self, max_output_size: usize, max_message_size: usize) {
+        let mut truncated = false;
+        if let Some(revert_reason) = &mut self.revert_reason {
+            truncated |= truncate_message(revert_reason, max_message_size);
+        }
+        for call in &mut self.call_traces {
+            truncated |= call.truncate_results(max_output_size, max_message_size);
+        }
+        self.result_truncated |= truncated;
+    }
+
     pub fn call_trace(&self) -> Option<Call> {
         if self.call_traces.is_empty() {
             None
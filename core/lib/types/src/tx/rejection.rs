@@ -0,0 +1,111 @@
+//! Structured reasons for rejecting transactions during execution in the state keeper.
+
+use serde::{Deserialize, Serialize};
+
+use std::fmt;
+
+/// Kind of the reason a transaction was rejected by the state keeper.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TxRejectionKind {
+    /// The transaction exceeds a limit that cannot be satisfied even by an L1 batch consisting
+    /// of this transaction only.
+    ResourceLimitExceeded,
+    /// The transaction was rejected by the VM, e.g., because of failed account validation.
+    RejectedByVm,
+    /// The batch executor has crashed while executing the transaction.
+    ExecutorCrashed,
+}
+
+/// Resource whose limit was exceeded by a rejected transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TxRejectedResource {
+    Gas,
+    /// Data published to L1.
+    Pubdata,
+    /// Circuits required to prove the transaction.
+    Circuits,
+    /// Size of the transaction encoding in the bootloader memory.
+    EncodingSize,
+    /// Number of factory dependencies (i.e., bytecodes published by the transaction).
+    FactoryDeps,
+    /// Wall-clock time of the transaction execution.
+    ExecutionTime,
+}
+
+/// Machine-readable reason for a transaction rejection, persisted together with the transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxRejection {
+    pub kind: TxRejectionKind,
+    /// Resource whose limit was exceeded. Only set for [`TxRejectionKind::ResourceLimitExceeded`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource: Option<TxRejectedResource>,
+    /// Human-readable description of the rejection.
+    pub message: String,
+}
+
+impl TxRejection {
+    pub fn resource_limit_exceeded(
+        resource: TxRejectedResource,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            kind: TxRejectionKind::ResourceLimitExceeded,
+            resource: Some(resource),
+            message: message.into(),
+        }
+    }
+
+    pub fn rejected_by_vm(message: impl Into<String>) -> Self {
+        Self {
+            kind: TxRejectionKind::RejectedByVm,
+            resource: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn executor_crashed(message: impl Into<String>) -> Self {
+        Self {
+            kind: TxRejectionKind::ExecutorCrashed,
+            resource: None,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for TxRejection {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejection_serialization() {
+        let rejection =
+            TxRejection::resource_limit_exceeded(TxRejectedResource::Pubdata, "too much pubdata");
+        let json = serde_json::to_value(&rejection).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "kind": "resource_limit_exceeded",
+                "resource": "pubdata",
+                "message": "too much pubdata",
+            })
+        );
+        let restored: TxRejection = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, rejection);
+
+        let rejection = TxRejection::rejected_by_vm("validation failed");
+        let json = serde_json::to_value(&rejection).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "kind": "rejected_by_vm", "message": "validation failed" })
+        );
+    }
+}
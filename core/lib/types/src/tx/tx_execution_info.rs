@@ -1,9 +1,10 @@
+use crate::circuit::CircuitStatistic;
 use crate::commitment::SerializeCommitment;
 use crate::event::{extract_long_l2_to_l1_messages, extract_published_bytecodes};
 use crate::fee::TransactionExecutionMetrics;
 use crate::l2_to_l1_log::L2ToL1Log;
 use crate::writes::{InitialStorageWrite, RepeatedStorageWrite};
-use crate::{StorageLogQuery, VmEvent, PUBLISH_BYTECODE_OVERHEAD};
+use crate::{StorageLogQuery, VmEvent, H256, PUBLISH_BYTECODE_OVERHEAD};
 use std::ops::{Add, AddAssign};
 use zksync_utils::bytecode::bytecode_len_in_bytes;
 
@@ -32,6 +33,19 @@ impl TxExecutionStatus {
     }
 }
 
+/// Execution outcome of a transaction as persisted when its miniblock was sealed.
+///
+/// Used to check that re-executing a transaction from a pending L1 batch yields the same result
+/// as the original execution, which may have already been exposed via the API.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PersistedTxExecution {
+    pub hash: H256,
+    pub status: TxExecutionStatus,
+    pub refunded_gas: u32,
+    /// Execution metrics; `None` if they could not be parsed from the persisted data.
+    pub metrics: Option<ExecutionMetrics>,
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct DeduplicatedWritesMetrics {
     pub initial_storage_writes: usize,
@@ -52,7 +66,7 @@ impl DeduplicatedWritesMetrics {
     }
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct ExecutionMetrics {
     pub gas_used: usize,
     pub published_bytecode_bytes: usize,
@@ -65,6 +79,13 @@ pub struct ExecutionMetrics {
     pub total_log_queries: usize,
     pub cycles_used: u32,
     pub computational_gas_used: u32,
+    /// Number of storage slots written and then restored to their values at the start of the batch.
+    /// Such slots are not published, so the pubdata paid for them is refunded.
+    #[serde(default)]
+    pub refunded_storage_writes: usize,
+    /// Estimated usage of the basic circuits of each type.
+    #[serde(default)]
+    pub circuit_statistic: CircuitStatistic,
 }
 
 impl ExecutionMetrics {
@@ -81,6 +102,8 @@ impl ExecutionMetrics {
             total_log_queries: tx_metrics.total_log_queries,
             cycles_used: tx_metrics.cycles_used,
             computational_gas_used: tx_metrics.computational_gas_used,
+            refunded_storage_writes: tx_metrics.refunded_storage_writes,
+            circuit_statistic: CircuitStatistic::default(),
         }
     }
 
@@ -126,6 +149,8 @@ impl ExecutionMetrics {
             total_log_queries: logs.total_log_queries_count,
             cycles_used,
             computational_gas_used,
+            refunded_storage_writes: 0,
+            circuit_statistic: CircuitStatistic::default(),
         }
     }
 }
@@ -147,6 +172,8 @@ impl Add for ExecutionMetrics {
             total_log_queries: self.total_log_queries + other.total_log_queries,
             cycles_used: self.cycles_used + other.cycles_used,
             computational_gas_used: self.computational_gas_used + other.computational_gas_used,
+            refunded_storage_writes: self.refunded_storage_writes + other.refunded_storage_writes,
+            circuit_statistic: self.circuit_statistic + other.circuit_statistic,
         }
     }
 }
@@ -1,4 +1,4 @@
-use crate::{Address, U256};
+use crate::{Address, H256, U256};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::{HashMap, HashSet};
 use std::fmt;
@@ -94,6 +94,27 @@ pub struct Call {
     pub revert_reason: Option<String>,
     /// Subcalls.
     pub calls: Vec<Call>,
+    /// Pubdata attributed to the call. Only collected if the call tracer is run with pubdata attribution,
+    /// and never persisted, so that the serialization format of stored traces stays the same.
+    #[serde(skip)]
+    pub pubdata: Option<CallPubdata>,
+}
+
+/// Pubdata produced by a call frame, including its subcalls.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CallPubdata {
+    /// Number of pubdata bytes the call and its subcalls were charged for.
+    pub pubdata_bytes: u32,
+    /// Storage slots written directly by the call (i.e., not by its subcalls), in execution order.
+    pub storage_writes: Vec<StorageWrite>,
+}
+
+/// Storage write performed by a call frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StorageWrite {
+    pub address: Address,
+    pub key: H256,
+    pub value: H256,
 }
 
 impl Call {
@@ -119,7 +140,45 @@ impl Call {
             error: None,
             revert_reason,
             calls,
+            pubdata: None,
+        }
+    }
+}
+
+/// Marker appended to revert reasons and error messages truncated to fit into the configured size limits.
+pub const TRUNCATION_MARKER: &str = "...[truncated]";
+
+/// Truncates the string to at most `max_size` bytes (including [`TRUNCATION_MARKER`]), respecting char boundaries.
+/// Returns `true` if the string was truncated.
+pub fn truncate_message(message: &mut String, max_size: usize) -> bool {
+    if message.len() <= max_size {
+        return false;
+    }
+    let mut end = max_size.saturating_sub(TRUNCATION_MARKER.len());
+    while !message.is_char_boundary(end) {
+        end -= 1;
+    }
+    message.truncate(end);
+    message.push_str(TRUNCATION_MARKER);
+    true
+}
+
+impl Call {
+    /// Truncates outputs, errors and revert reasons of this call and all its subcalls to the specified sizes
+    /// in bytes. Returns `true` if anything was truncated.
+    pub fn truncate_results(&mut self, max_output_size: usize, max_message_size: usize) -> bool {
+        let mut truncated = false;
+        if self.output.len() > max_output_size {
+            self.output.truncate(max_output_size);
+            truncated = true;
+        }
+        for message in self.error.iter_mut().chain(&mut self.revert_reason) {
+            truncated |= truncate_message(message, max_message_size);
         }
+        for call in &mut self.calls {
+            truncated |= call.truncate_results(max_output_size, max_message_size);
+        }
+        truncated
     }
 }
 
@@ -172,6 +231,7 @@ impl Default for Call {
             error: None,
             revert_reason: None,
             calls: vec![],
+            pubdata: None,
         }
     }
 }
@@ -191,6 +251,47 @@ impl fmt::Debug for Call {
             .field("error", &self.error)
             .field("revert_reason", &format_args!("{:?}", self.revert_reason))
             .field("call_traces", &self.calls)
+            .field("pubdata", &self.pubdata)
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncating_messages() {
+        let mut message = "short".to_owned();
+        assert!(!truncate_message(&mut message, 32));
+        assert_eq!(message, "short");
+
+        let mut message = "тест".repeat(10);
+        assert!(truncate_message(&mut message, 21));
+        assert_eq!(message, format!("тес{TRUNCATION_MARKER}"));
+    }
+
+    #[test]
+    fn truncating_call_results() {
+        let subcall = Call {
+            output: vec![1; 100],
+            revert_reason: Some("x".repeat(100)),
+            ..Call::default()
+        };
+        let mut call = Call {
+            output: vec![2; 10],
+            error: Some("error".to_owned()),
+            calls: vec![subcall],
+            ..Call::default()
+        };
+        assert!(!call.clone().truncate_results(100, 100));
+
+        assert!(call.truncate_results(50, 50));
+        assert_eq!(call.output, [2; 10]);
+        assert_eq!(call.error.as_deref(), Some("error"));
+        assert_eq!(call.calls[0].output, [1; 50]);
+        let revert_reason = call.calls[0].revert_reason.as_ref().unwrap();
+        assert_eq!(revert_reason.len(), 50);
+        assert!(revert_reason.ends_with(TRUNCATION_MARKER));
+    }
+}
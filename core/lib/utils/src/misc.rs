@@ -1,6 +1,8 @@
 use zksync_basic_types::web3::signing::keccak256;
 use zksync_basic_types::{MiniblockNumber, H256, U256};
 
+/// Legacy placeholder miniblock hash depending only on the miniblock number. Miniblocks are now hashed
+/// with `MiniblockHasher` from `zksync_types`; the legacy hash is retained for the genesis miniblock.
 pub fn miniblock_hash(miniblock_number: MiniblockNumber) -> H256 {
     H256(keccak256(&miniblock_number.0.to_be_bytes()))
 }
@@ -17,6 +19,16 @@ pub fn ceil_div_u256(a: U256, b: U256) -> U256 {
     (a + b - U256::from(1)) / b
 }
 
+/// Compares two byte strings (e.g., authentication tokens) in time independent of their contents,
+/// so that the expected value cannot be guessed byte by byte. Only the length may leak.
+pub fn constant_time_eq(expected: &[u8], provided: &[u8]) -> bool {
+    let diff = expected
+        .iter()
+        .zip(provided)
+        .fold(0_u8, |acc, (x, y)| acc | (x ^ y));
+    diff == 0 && expected.len() == provided.len()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -34,6 +46,16 @@ mod tests {
         assert_eq!(3, ceil_div(15, 7));
     }
 
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"token", b"token"));
+        assert!(constant_time_eq(b"", b""));
+        assert!(!constant_time_eq(b"token", b"tokem"));
+        assert!(!constant_time_eq(b"token", b"tok"));
+        assert!(!constant_time_eq(b"tok", b"token"));
+        assert!(!constant_time_eq(b"token", b""));
+    }
+
     #[test]
     fn test_ceil_div_no_roundup_required() {
         assert_eq!(2, ceil_div(4, 2));
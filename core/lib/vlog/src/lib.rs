@@ -12,9 +12,10 @@
 //! https://docs.sentry.io/platforms/rust/
 //!
 
-use std::{borrow::Cow, str::FromStr};
+use std::{borrow::Cow, collections::HashMap, str::FromStr, sync::Mutex};
 
-use opentelemetry::sdk::{resource::Resource, trace::Sampler};
+use opentelemetry::propagation::TextMapPropagator;
+use opentelemetry::sdk::{propagation::TraceContextPropagator, resource::Resource, trace::Sampler};
 use opentelemetry::trace::{TraceContextExt, TraceId};
 use opentelemetry::KeyValue;
 use opentelemetry_otlp::WithExportConfig;
@@ -22,7 +23,9 @@ use sentry::protocol::Event;
 use sentry::{types::Dsn, ClientInitGuard, ClientOptions};
 use std::backtrace::Backtrace;
 use tracing_opentelemetry::OpenTelemetrySpanExt;
-use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{
+    fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Registry,
+};
 
 pub use chrono as __chrono;
 pub use sentry as __sentry;
@@ -167,6 +170,28 @@ fn get_sampling_ratio() -> f64 {
         .unwrap_or(DEFAULT_SAMPLING_RATIO)
 }
 
+/// Handle allowing to change the log filter after logging is initialized.
+static LOG_FILTER_HANDLE: Mutex<Option<reload::Handle<EnvFilter, Registry>>> = Mutex::new(None);
+
+/// Replaces log filtering directives at runtime. `directives` have the same format as
+/// the `RUST_LOG` env variable (e.g., `zksync_core=debug,info`).
+///
+/// Returns an error if the directives cannot be parsed, or if logging wasn't initialized via [`init()`].
+pub fn set_log_directives(directives: &str) -> Result<(), String> {
+    let filter = EnvFilter::try_new(directives).map_err(|err| err.to_string())?;
+    let handle = LOG_FILTER_HANDLE.lock().unwrap();
+    let handle = handle
+        .as_ref()
+        .ok_or_else(|| "logging is not initialized".to_owned())?;
+    handle.reload(filter).map_err(|err| err.to_string())
+}
+
+/// Returns the currently used log filtering directives, or `None` if logging wasn't initialized via [`init()`].
+pub fn log_directives() -> Option<String> {
+    let handle = LOG_FILTER_HANDLE.lock().unwrap();
+    handle.as_ref()?.with_current(ToString::to_string).ok()
+}
+
 /// Initialize logging with tracing and set up log format
 pub fn init() {
     let log_format = std::env::var("MISC_LOG_FORMAT").unwrap_or_else(|_| "plain".to_string());
@@ -202,20 +227,26 @@ pub fn init() {
             )
             .install_simple()
             .unwrap();
+        // Allows to continue traces started by the clients (e.g. API requests with the `traceparent` header).
+        opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
         tracing_opentelemetry::layer().with_tracer(tracer)
     });
+    // The filter is reloadable so that log verbosity can be adjusted at runtime.
+    let (log_filter, log_filter_handle) = reload::Layer::new(EnvFilter::from_default_env());
+    *LOG_FILTER_HANDLE.lock().unwrap() = Some(log_filter_handle);
+
     match log_format.as_str() {
         "plain" => {
             if let Some(opentelemetry) = opentelemetry {
                 tracing_subscriber::registry()
+                    .with(log_filter)
                     .with(opentelemetry)
                     .with(fmt::Layer::default())
-                    .with(tracing_subscriber::EnvFilter::from_default_env())
                     .init();
             } else {
                 tracing_subscriber::registry()
+                    .with(log_filter)
                     .with(fmt::Layer::default())
-                    .with(tracing_subscriber::EnvFilter::from_default_env())
                     .init();
             }
         }
@@ -225,14 +256,14 @@ pub fn init() {
             install_pretty_panic_hook();
             if let Some(opentelemetry) = opentelemetry {
                 tracing_subscriber::registry()
+                    .with(log_filter)
                     .with(opentelemetry)
                     .with(fmt::Layer::default().with_timer(timer).json())
-                    .with(tracing_subscriber::EnvFilter::from_default_env())
                     .init();
             } else {
                 tracing_subscriber::registry()
+                    .with(log_filter)
                     .with(fmt::Layer::default().with_timer(timer).json())
-                    .with(tracing_subscriber::EnvFilter::from_default_env())
                     .init();
             }
         }
@@ -240,6 +271,24 @@ pub fn init() {
     };
 }
 
+/// Sets the remote trace context extracted from the W3C Trace Context headers (`traceparent`, `tracestate`)
+/// as the parent of the provided span. Does nothing if the headers don't contain a valid context,
+/// or if OpenTelemetry export is not configured.
+pub fn set_remote_parent<'a>(
+    span: &tracing::Span,
+    headers: impl IntoIterator<Item = (&'a str, &'a str)>,
+) {
+    let carrier: HashMap<String, String> = headers
+        .into_iter()
+        .map(|(name, value)| (name.to_lowercase(), value.to_owned()))
+        .collect();
+    let parent_context =
+        opentelemetry::global::get_text_map_propagator(|propagator| propagator.extract(&carrier));
+    if parent_context.span().span_context().is_valid() {
+        span.set_parent(parent_context);
+    }
+}
+
 /// If the sentry URL is provided via an environment variable, this function will initialize sentry.
 /// Returns a sentry client guard. The full description can be found in the official documentation:
 /// https://docs.sentry.io/platforms/rust/#configure
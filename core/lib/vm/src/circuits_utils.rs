@@ -0,0 +1,96 @@
+use crate::history_recorder::HistoryMode;
+use crate::VmInstance;
+use zk_evm::aux_structures::Timestamp;
+use zksync_config::constants::{
+    ECRECOVER_PRECOMPILE_ADDRESS, KECCAK256_PRECOMPILE_ADDRESS, SHA256_PRECOMPILE_ADDRESS,
+};
+use zksync_types::circuit::{CircuitStatistic, GEOMETRY_CONFIG};
+
+/// Upper estimate of the number of RAM queries made by the VM per cycle: an opcode may read code,
+/// read an operand from memory and write the result into memory.
+const RAM_QUERIES_PER_CYCLE: usize = 3;
+
+/// Number of bytecode words processed by the code decommitter per cycle.
+const DECOMMITTED_WORDS_PER_CYCLE: usize = 2;
+
+impl<H: HistoryMode> VmInstance<'_, H> {
+    /// Estimates the usage of the basic circuits of each type by the execution that started at `from_timestamp`
+    /// and took `cycles_used` VM cycles.
+    pub(crate) fn circuit_statistic_after_timestamp(
+        &self,
+        from_timestamp: Timestamp,
+        cycles_used: u32,
+    ) -> CircuitStatistic {
+        let storage_log_queries = self
+            .state
+            .storage
+            .storage_log_queries_after_timestamp(from_timestamp)
+            .len();
+        let event_log_queries = self
+            .state
+            .event_sink
+            .log_queries_after_timestamp(from_timestamp)
+            .len();
+        let precompiles = &self.state.precompiles_processor;
+        let precompile_calls = precompiles
+            .get_timestamp_history()
+            .iter()
+            .rev()
+            .take_while(|t| **t >= from_timestamp)
+            .count();
+        let decommitter = &self.state.decommittment_processor;
+        let decommitted_bytecodes =
+            decommitter.get_decommitted_bytecodes_after_timestamp(from_timestamp);
+        let decommitted_words =
+            decommitter.get_decommitted_bytecode_words_after_timestamp(from_timestamp);
+
+        let circuits = CircuitStatistic::circuits_for_cycles;
+        let cycles_used = cycles_used as usize;
+        CircuitStatistic {
+            main_vm: circuits(cycles_used, GEOMETRY_CONFIG.cycles_per_vm_snapshot),
+            ram_permutation: circuits(
+                cycles_used * RAM_QUERIES_PER_CYCLE,
+                GEOMETRY_CONFIG.cycles_per_ram_permutation,
+            ),
+            storage_application: circuits(
+                storage_log_queries,
+                GEOMETRY_CONFIG.cycles_per_storage_application,
+            ),
+            storage_sorter: circuits(
+                storage_log_queries,
+                GEOMETRY_CONFIG.cycles_per_storage_sorter,
+            ),
+            code_decommitter: circuits(
+                (decommitted_words + DECOMMITTED_WORDS_PER_CYCLE - 1) / DECOMMITTED_WORDS_PER_CYCLE,
+                GEOMETRY_CONFIG.cycles_per_code_decommitter,
+            ),
+            code_decommitter_sorter: circuits(
+                decommitted_bytecodes,
+                GEOMETRY_CONFIG.cycles_code_decommitter_sorter,
+            ),
+            log_demuxer: circuits(
+                storage_log_queries + event_log_queries + precompile_calls,
+                GEOMETRY_CONFIG.cycles_per_log_demuxer,
+            ),
+            events_sorter: circuits(
+                event_log_queries,
+                GEOMETRY_CONFIG.cycles_per_events_or_l1_messages_sorter,
+            ),
+            keccak256: circuits(
+                precompiles.rounds_after_timestamp(KECCAK256_PRECOMPILE_ADDRESS, from_timestamp)
+                    as usize,
+                GEOMETRY_CONFIG.cycles_per_keccak256_circuit,
+            ),
+            ecrecover: circuits(
+                precompiles.rounds_after_timestamp(ECRECOVER_PRECOMPILE_ADDRESS, from_timestamp)
+                    as usize,
+                GEOMETRY_CONFIG.cycles_per_ecrecover_circuit,
+            ),
+            sha256: circuits(
+                precompiles.rounds_after_timestamp(SHA256_PRECOMPILE_ADDRESS, from_timestamp)
+                    as usize,
+                GEOMETRY_CONFIG.cycles_per_sha256_circuit,
+            ),
+        }
+    }
+}
@@ -37,6 +37,11 @@ pub enum TxRevertReason {
     NotEnoughGasProvided,
     // The tx consumes too much missing invocations to memory
     MissingInvocationLimitReached,
+    // The tx wasn't processed within the wall-clock time budget and its execution was aborted.
+    ExecutionTimeout,
+    // The tx produced too much output (returndata, events or storage accesses) and its execution was aborted.
+    // Can only be returned if output limits are set for the VM.
+    OutputLimitExceeded(String),
 }
 
 impl TxRevertReason {
@@ -210,6 +215,12 @@ impl Display for TxRevertReason {
             TxRevertReason::MissingInvocationLimitReached => {
                 write!(f, "Tx produced too much cold storage accesses")
             }
+            TxRevertReason::ExecutionTimeout => {
+                write!(f, "Tx execution took too much time")
+            }
+            TxRevertReason::OutputLimitExceeded(reason) => {
+                write!(f, "Tx execution output limit exceeded: {reason}")
+            }
         }
     }
 }
@@ -1,6 +1,7 @@
 #![allow(clippy::derive_partial_eq_without_eq)]
 
 mod bootloader_state;
+mod circuits_utils;
 mod errors;
 pub mod event_sink;
 mod events;
@@ -10,7 +10,7 @@ use crate::oracles::{
     storage::StorageOracle,
 };
 use zk_evm::witness_trace::DummyTracer;
-use zksync_state::WriteStorage;
+use zksync_state::{BytecodeCache, WriteStorage};
 
 /// zkEVM requires a bunch of objects implementing given traits to work.
 /// For example: Storage, Memory, PrecompilerProcessor etc
@@ -42,3 +42,12 @@ impl<'a, H: HistoryMode> OracleTools<'a, false, H> {
         }
     }
 }
+
+impl<'a, const B: bool, H: HistoryMode> OracleTools<'a, B, H> {
+    /// Makes the decommitter use the provided cache of decoded bytecodes shared with other VM instances.
+    #[must_use]
+    pub fn with_bytecode_cache(mut self, cache: BytecodeCache) -> Self {
+        self.decommittment_processor.set_bytecode_cache(cache);
+        self
+    }
+}
@@ -8,7 +8,7 @@ use zk_evm::{
     abstractions::{DecommittmentProcessor, Memory},
     aux_structures::{DecommittmentQuery, MemoryIndex, MemoryLocation, MemoryPage, MemoryQuery},
 };
-use zksync_state::StoragePtr;
+use zksync_state::{BytecodeCache, StoragePtr};
 use zksync_types::U256;
 use zksync_utils::bytecode::bytecode_len_in_words;
 use zksync_utils::{bytes_to_be_words, u256_to_h256};
@@ -30,6 +30,9 @@ pub struct DecommitterOracle<'a, const B: bool, H: HistoryMode> {
     pub decommitted_code_hashes: HistoryRecorder<HashMap<U256, u32>, HistoryEnabled>,
     /// Stores history of decommitment requests.
     decommitment_requests: HistoryRecorder<Vec<()>, H>,
+    /// Cache of decoded bytecodes shared with other VM instances. Consulted before loading
+    /// a bytecode from the storage.
+    bytecode_cache: Option<BytecodeCache>,
 }
 
 impl<'a, const B: bool, H: HistoryMode> DecommitterOracle<'a, B, H> {
@@ -39,9 +42,15 @@ impl<'a, const B: bool, H: HistoryMode> DecommitterOracle<'a, B, H> {
             known_bytecodes: HistoryRecorder::default(),
             decommitted_code_hashes: HistoryRecorder::default(),
             decommitment_requests: HistoryRecorder::default(),
+            bytecode_cache: None,
         }
     }
 
+    /// Sets the cache of decoded bytecodes shared with other VM instances.
+    pub fn set_bytecode_cache(&mut self, cache: BytecodeCache) {
+        self.bytecode_cache = Some(cache);
+    }
+
     /// Gets the bytecode for a given hash (either from storage, or from 'known_bytecodes' that were populated by `populate` method).
     /// Panics if bytecode doesn't exist.
     pub fn get_bytecode(&mut self, hash: U256, timestamp: Timestamp) -> Vec<U256> {
@@ -50,16 +59,27 @@ impl<'a, const B: bool, H: HistoryMode> DecommitterOracle<'a, B, H> {
         match entry {
             Some(x) => x.clone(),
             None => {
-                // It is ok to panic here, since the decommitter is never called directly by
-                // the users and always called by the VM. VM will never let decommit the
-                // code hash which we didn't previously claim to know the preimage of.
-                let value = self
-                    .storage
-                    .borrow_mut()
-                    .load_factory_dep(u256_to_h256(hash))
-                    .expect("Trying to decode unexisting hash");
-
-                let value = bytes_to_be_words(value);
+                let hash_h256 = u256_to_h256(hash);
+                let cached_value = self
+                    .bytecode_cache
+                    .as_ref()
+                    .and_then(|cache| cache.get(&hash_h256));
+                let value = cached_value.unwrap_or_else(|| {
+                    // It is ok to panic here, since the decommitter is never called directly by
+                    // the users and always called by the VM. VM will never let decommit the
+                    // code hash which we didn't previously claim to know the preimage of.
+                    let value = self
+                        .storage
+                        .borrow_mut()
+                        .load_factory_dep(hash_h256)
+                        .expect("Trying to decode unexisting hash");
+                    let value = bytes_to_be_words(value);
+                    if let Some(cache) = &self.bytecode_cache {
+                        cache.insert(hash_h256, value.clone());
+                    }
+                    value
+                });
+
                 self.known_bytecodes.insert(hash, value.clone(), timestamp);
                 value
             }
@@ -92,6 +112,17 @@ impl<'a, const B: bool, H: HistoryMode> DecommitterOracle<'a, B, H> {
             .count()
     }
 
+    /// Returns the total length (in 32-byte words) of bytecodes decommitted at or after `timestamp`.
+    pub fn get_decommitted_bytecode_words_after_timestamp(&self, timestamp: Timestamp) -> usize {
+        self.decommitted_code_hashes
+            .history()
+            .iter()
+            .rev()
+            .take_while(|(t, _)| *t >= timestamp)
+            .map(|(_, event)| bytecode_len_in_words(&u256_to_h256(event.key)) as usize)
+            .sum()
+    }
+
     pub fn get_decommitted_code_hashes_with_history(
         &self,
     ) -> &HistoryRecorder<HashMap<U256, u32>, HistoryEnabled> {
@@ -1,10 +1,17 @@
+use p256::{
+    ecdsa::{signature::hazmat::PrehashVerifier, Signature, VerifyingKey},
+    EncodedPoint,
+};
 use zk_evm::{
     abstractions::Memory,
+    abstractions::MemoryType,
     abstractions::PrecompileCyclesWitness,
     abstractions::PrecompilesProcessor,
-    aux_structures::{LogQuery, MemoryQuery, Timestamp},
-    precompiles::DefaultPrecompilesProcessor,
+    aux_structures::{LogQuery, MemoryIndex, MemoryLocation, MemoryPage, MemoryQuery, Timestamp},
+    precompiles::{precompile_abi_in_log, DefaultPrecompilesProcessor},
 };
+use zksync_config::constants::{ECRECOVER_PRECOMPILE_ADDRESS, P256VERIFY_PRECOMPILE_ADDRESS};
+use zksync_types::{Address, U256};
 
 use crate::history_recorder::{HistoryEnabled, HistoryMode, HistoryRecorder};
 
@@ -18,6 +25,8 @@ use super::OracleWithHistory;
 #[derive(Debug, Clone)]
 pub struct PrecompilesProcessorWithHistory<const B: bool, H: HistoryMode> {
     pub timestamp_history: HistoryRecorder<Vec<Timestamp>, H>,
+    /// Address of the called precompile and the number of its rounds, for each entry in `timestamp_history`.
+    pub rounds_history: HistoryRecorder<Vec<(Address, u64)>, H>,
     pub default_precompiles_processor: DefaultPrecompilesProcessor<B>,
 }
 
@@ -25,6 +34,7 @@ impl<const B: bool, H: HistoryMode> Default for PrecompilesProcessorWithHistory<
     fn default() -> Self {
         Self {
             timestamp_history: Default::default(),
+            rounds_history: Default::default(),
             default_precompiles_processor: DefaultPrecompilesProcessor,
         }
     }
@@ -33,6 +43,7 @@ impl<const B: bool, H: HistoryMode> Default for PrecompilesProcessorWithHistory<
 impl<const B: bool> OracleWithHistory for PrecompilesProcessorWithHistory<B, HistoryEnabled> {
     fn rollback_to_timestamp(&mut self, timestamp: Timestamp) {
         self.timestamp_history.rollback_to_timestamp(timestamp);
+        self.rounds_history.rollback_to_timestamp(timestamp);
     }
 }
 
@@ -41,8 +52,23 @@ impl<const B: bool, H: HistoryMode> PrecompilesProcessorWithHistory<B, H> {
         self.timestamp_history.inner()
     }
 
+    /// Returns the total number of rounds performed by the specified precompile
+    /// in calls made at or after `from_timestamp`.
+    pub fn rounds_after_timestamp(&self, address: Address, from_timestamp: Timestamp) -> u64 {
+        let start = self
+            .timestamp_history
+            .inner()
+            .partition_point(|t| *t < from_timestamp);
+        self.rounds_history.inner()[start..]
+            .iter()
+            .filter(|(precompile_address, _)| *precompile_address == address)
+            .map(|(_, rounds)| rounds)
+            .sum()
+    }
+
     pub fn delete_history(&mut self) {
         self.timestamp_history.delete_history();
+        self.rounds_history.delete_history();
     }
 }
 
@@ -63,6 +89,18 @@ impl<const B: bool, H: HistoryMode> PrecompilesProcessor for PrecompilesProcesso
         // where operations and timestamp have different types.
         self.timestamp_history
             .push(query.timestamp, query.timestamp);
+        // For the round function precompiles, the number of rounds is passed by the caller
+        // in the extra data of the precompile ABI; `ecrecover` always performs a single round.
+        let rounds = if query.address == ECRECOVER_PRECOMPILE_ADDRESS {
+            1
+        } else {
+            precompile_abi_in_log(query).precompile_interpreted_data
+        };
+        self.rounds_history
+            .push((query.address, rounds), query.timestamp);
+        if query.address == P256VERIFY_PRECOMPILE_ADDRESS {
+            return execute_p256_verify(monotonic_cycle_counter, query, memory);
+        }
         self.default_precompiles_processor.execute_precompile(
             monotonic_cycle_counter,
             query,
@@ -73,3 +111,118 @@ impl<const B: bool, H: HistoryMode> PrecompilesProcessor for PrecompilesProcesso
         self.default_precompiles_processor.finish_frame(_panicked);
     }
 }
+
+/// Number of words the `p256Verify` precompile reads: message hash, `r`, `s` and the `x`, `y` coordinates of the public key.
+const P256_VERIFY_INPUT_WORDS: usize = 5;
+
+/// Executes the `p256Verify` (secp256r1) precompile.
+/// The layout of the input follows RIP-7212. Two words are written to the output:
+/// the success flag (always set, since malformed inputs are not an error) and `1` if the signature is valid or `0` otherwise.
+///
+/// The circuits have no support for this precompile yet, so no witness is produced for it.
+fn execute_p256_verify<M: Memory>(
+    monotonic_cycle_counter: u32,
+    query: LogQuery,
+    memory: &mut M,
+) -> Option<(Vec<MemoryQuery>, Vec<MemoryQuery>, PrecompileCyclesWitness)> {
+    let params = precompile_abi_in_log(query);
+    let timestamp_to_read = query.timestamp;
+    let timestamp_to_write = Timestamp(timestamp_to_read.0 + 1);
+
+    let mut read_location = MemoryLocation {
+        memory_type: MemoryType::Heap,
+        page: MemoryPage(params.memory_page_to_read),
+        index: MemoryIndex(params.input_memory_offset),
+    };
+    let mut input = [U256::zero(); P256_VERIFY_INPUT_WORDS];
+    for value in input.iter_mut() {
+        let read_query = MemoryQuery {
+            timestamp: timestamp_to_read,
+            location: read_location,
+            value: U256::zero(),
+            value_is_pointer: false,
+            rw_flag: false,
+        };
+        *value = memory
+            .execute_partial_query(monotonic_cycle_counter, read_query)
+            .value;
+        read_location.index.0 += 1;
+    }
+
+    let [digest, r, s, x, y] = input;
+    let is_valid = verify_p256_signature(digest, r, s, x, y);
+    vlog::trace!(
+        "p256Verify precompile call at {:?}: signature is valid = {}",
+        query.timestamp,
+        is_valid
+    );
+
+    let mut write_location = MemoryLocation {
+        memory_type: MemoryType::Heap,
+        page: MemoryPage(params.memory_page_to_write),
+        index: MemoryIndex(params.output_memory_offset),
+    };
+    for value in [U256::one(), U256::from(is_valid as u8)] {
+        let write_query = MemoryQuery {
+            timestamp: timestamp_to_write,
+            location: write_location,
+            value,
+            value_is_pointer: false,
+            rw_flag: true,
+        };
+        memory.execute_partial_query(monotonic_cycle_counter, write_query);
+        write_location.index.0 += 1;
+    }
+
+    None
+}
+
+fn verify_p256_signature(digest: U256, r: U256, s: U256, x: U256, y: U256) -> bool {
+    let to_bytes = |value: U256| {
+        let mut bytes = [0_u8; 32];
+        value.to_big_endian(&mut bytes);
+        bytes
+    };
+
+    let signature = match Signature::from_scalars(to_bytes(r), to_bytes(s)) {
+        Ok(signature) => signature,
+        Err(_) => return false,
+    };
+    let public_key =
+        EncodedPoint::from_affine_coordinates(&to_bytes(x).into(), &to_bytes(y).into(), false);
+    let verifying_key = match VerifyingKey::from_encoded_point(&public_key) {
+        Ok(key) => key,
+        Err(_) => return false,
+    };
+    verifying_key
+        .verify_prehash(&to_bytes(digest), &signature)
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use p256::ecdsa::{signature::hazmat::PrehashSigner, SigningKey};
+
+    #[test]
+    fn p256_signature_verification() {
+        let signing_key = SigningKey::from_bytes(&[1_u8; 32]).unwrap();
+        let digest = [7_u8; 32];
+        let signature: Signature = signing_key.sign_prehash(&digest).unwrap();
+        let public_key = signing_key.verifying_key().to_encoded_point(false);
+
+        let (r, s) = (
+            U256::from_big_endian(signature.r().to_bytes().as_slice()),
+            U256::from_big_endian(signature.s().to_bytes().as_slice()),
+        );
+        let x = U256::from_big_endian(public_key.x().unwrap());
+        let y = U256::from_big_endian(public_key.y().unwrap());
+        let digest = U256::from_big_endian(&digest);
+
+        assert!(verify_p256_signature(digest, r, s, x, y));
+        assert!(!verify_p256_signature(digest + 1, r, s, x, y));
+        assert!(!verify_p256_signature(digest, r, s, y, x));
+        assert!(!verify_p256_signature(digest, U256::zero(), s, x, y));
+    }
+}
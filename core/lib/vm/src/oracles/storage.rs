@@ -17,7 +17,7 @@ use zksync_types::{
     AccountTreeId, Address, StorageKey, StorageLogQuery, StorageLogQueryType, BOOTLOADER_ADDRESS,
     U256,
 };
-use zksync_utils::u256_to_h256;
+use zksync_utils::{h256_to_u256, u256_to_h256};
 
 use super::OracleWithHistory;
 
@@ -45,6 +45,10 @@ pub struct StorageOracle<'a, H: HistoryMode> {
     // to cover this slot.
     // `paid_changes` history is necessary
     pub paid_changes: HistoryRecorder<HashMap<StorageKey, u32>, HistoryEnabled>,
+
+    // Values of the storage slots at the start of the batch, recorded on the first write to each slot.
+    // It doesn't need history, since values are only inserted there and they stay valid after rollbacks.
+    initial_values: HashMap<StorageKey, U256>,
 }
 
 impl OracleWithHistory for StorageOracle<'_, HistoryEnabled> {
@@ -61,6 +65,7 @@ impl<'a, H: HistoryMode> StorageOracle<'a, H> {
             storage: HistoryRecorder::from_inner(StorageWrapper::new(storage)),
             frames_stack: Default::default(),
             paid_changes: Default::default(),
+            initial_values: HashMap::new(),
         }
     }
 
@@ -130,6 +135,24 @@ impl<'a, H: HistoryMode> StorageOracle<'a, H> {
             .unwrap_or_default()
     }
 
+    // Returns the value of the storage slot at the start of the batch.
+    fn initial_value(&self, storage_key: &StorageKey) -> U256 {
+        self.initial_values
+            .get(storage_key)
+            .copied()
+            .unwrap_or_else(|| {
+                // The slot wasn't written to during the batch, so its current value is the initial one.
+                let value = self.storage.get_ptr().borrow_mut().read_value(storage_key);
+                h256_to_u256(value)
+            })
+    }
+
+    /// Returns `true` if the write restores the storage slot to its value at the start of the batch.
+    /// Such slots are not published, so the user doesn't pay for them.
+    pub(crate) fn is_write_restoring_initial_value(&self, query: &LogQuery) -> bool {
+        query.written_value == self.initial_value(&storage_key_of_log(query))
+    }
+
     pub(crate) fn base_price_for_write(&self, query: &LogQuery) -> u32 {
         let storage_key = storage_key_of_log(query);
 
@@ -150,6 +173,10 @@ impl<'a, H: HistoryMode> StorageOracle<'a, H> {
     fn value_update_price(&self, query: &LogQuery) -> u32 {
         let storage_key = storage_key_of_log(query);
 
+        if self.is_write_restoring_initial_value(query) {
+            return 0;
+        }
+
         let base_cost = self.base_price_for_write(query);
 
         let already_paid = self.prepaid_for_write(&storage_key);
@@ -195,8 +222,10 @@ impl<'a, H: HistoryMode> StorageOracle<'a, H> {
         let frames_stack_size = self.frames_stack.get_size();
         let paid_changes_size =
             self.paid_changes.inner().len() * std::mem::size_of::<(StorageKey, u32)>();
+        let initial_values_size =
+            self.initial_values.len() * std::mem::size_of::<(StorageKey, U256)>();
 
-        frames_stack_size + paid_changes_size
+        frames_stack_size + paid_changes_size + initial_values_size
     }
 
     pub fn get_history_size(&self) -> usize {
@@ -235,7 +264,8 @@ impl<H: HistoryMode> VmStorageOracle for StorageOracle<'_, H> {
             let to_pay_by_user = self.base_price_for_write(&query);
             let prepaid = self.prepaid_for_write(&storage_key);
 
-            if to_pay_by_user > prepaid {
+            // Writes restoring the initial value are free, so they don't pay for the slot.
+            if to_pay_by_user > prepaid && !self.is_write_restoring_initial_value(&query) {
                 self.paid_changes.apply_historic_record(
                     HashMapHistoryEvent {
                         key: storage_key,
@@ -244,7 +274,11 @@ impl<H: HistoryMode> VmStorageOracle for StorageOracle<'_, H> {
                     query.timestamp,
                 );
             }
-            self.write_value(query)
+            let query = self.write_value(query);
+            self.initial_values
+                .entry(storage_key)
+                .or_insert(query.read_value);
+            query
         } else {
             self.read_value(query)
         }
@@ -1,6 +1,7 @@
 use crate::errors::VmRevertReason;
 use crate::history_recorder::HistoryMode;
 use crate::memory::SimpleMemory;
+use crate::oracles::tracer::utils::gas_spent_on_bytecodes_and_long_messages_this_opcode;
 use std::convert::TryFrom;
 use std::marker::PhantomData;
 use std::mem;
@@ -8,13 +9,14 @@ use zk_evm::tracing::{
     AfterDecodingData, AfterExecutionData, BeforeExecutionData, Tracer, VmLocalStateData,
 };
 use zk_evm::zkevm_opcode_defs::{
-    FarCallABI, FarCallOpcode, Opcode, RetOpcode, CALL_IMPLICIT_CALLDATA_FAT_PTR_REGISTER,
-    RET_IMPLICIT_RETURNDATA_PARAMS_REGISTER,
+    FarCallABI, FarCallOpcode, LogOpcode, Opcode, RetOpcode,
+    CALL_IMPLICIT_CALLDATA_FAT_PTR_REGISTER, RET_IMPLICIT_RETURNDATA_PARAMS_REGISTER,
 };
 use zkevm_assembly::zkevm_opcode_defs::FatPointer;
 use zksync_config::constants::CONTRACT_DEPLOYER_ADDRESS;
-use zksync_types::vm_trace::{Call, CallType};
+use zksync_types::vm_trace::{Call, CallPubdata, CallType, StorageWrite};
 use zksync_types::U256;
+use zksync_utils::u256_to_h256;
 
 /// NOTE Auto implementing clone for this tracer can cause stack overflow.
 /// This is because of the stack field which is a Vec with nested vecs inside.
@@ -23,19 +25,54 @@ use zksync_types::U256;
 #[derive(Debug, Default)]
 pub struct CallTracer<H: HistoryMode> {
     stack: Vec<Call>,
+    /// Set if pubdata and storage writes should be attributed to call frames.
+    pubdata_attribution: Option<PubdataAttribution>,
     _phantom: PhantomData<H>,
 }
 
+/// State needed to attribute pubdata to call frames.
+#[derive(Debug, Default)]
+struct PubdataAttribution {
+    /// Gas spent on publishing bytecodes and long L2->L1 messages so far.
+    gas_spent_on_bytecodes_and_long_messages: u32,
+    /// Frames corresponding to the calls in the call tracer stack.
+    frames: Vec<PubdataFrame>,
+}
+
+#[derive(Debug, Default)]
+struct PubdataFrame {
+    /// Total gas spent on pubdata at the start of the call.
+    pubdata_gas_at_start: u32,
+    storage_writes: Vec<StorageWrite>,
+}
+
+impl PubdataAttribution {
+    fn pubdata_gas(&self, state: &VmLocalStateData<'_>) -> u32 {
+        self.gas_spent_on_bytecodes_and_long_messages + state.vm_local_state.spent_pubdata_counter
+    }
+}
+
 impl<H: HistoryMode> CallTracer<H> {
     pub fn new() -> Self {
         Self {
             stack: vec![],
+            pubdata_attribution: None,
             _phantom: PhantomData,
         }
     }
+
+    /// Creates a tracer that additionally annotates each call with the pubdata it has produced
+    /// and the storage slots it has written.
+    pub fn with_pubdata_attribution() -> Self {
+        Self {
+            pubdata_attribution: Some(PubdataAttribution::default()),
+            ..Self::new()
+        }
+    }
 }
 
 impl<H: HistoryMode> Tracer for CallTracer<H> {
+    const CALL_BEFORE_EXECUTION: bool = true;
     const CALL_AFTER_EXECUTION: bool = true;
 
     type SupportedMemory = SimpleMemory<H>;
@@ -52,10 +89,31 @@ impl<H: HistoryMode> Tracer for CallTracer<H> {
 
     fn before_execution(
         &mut self,
-        _state: VmLocalStateData<'_>,
-        _data: BeforeExecutionData,
+        state: VmLocalStateData<'_>,
+        data: BeforeExecutionData,
         _memory: &Self::SupportedMemory,
     ) {
+        let Some(attribution) = self.pubdata_attribution.as_mut() else {
+            return;
+        };
+        attribution.gas_spent_on_bytecodes_and_long_messages +=
+            gas_spent_on_bytecodes_and_long_messages_this_opcode(&state, &data);
+
+        if data.opcode.variant.opcode == Opcode::Log(LogOpcode::StorageWrite) {
+            // Near calls are filtered out of the resulting trace, so writes are attributed
+            // to the innermost far call.
+            let frame_index = self
+                .stack
+                .iter()
+                .rposition(|call| call.r#type != CallType::NearCall);
+            if let Some(frame) = frame_index.and_then(|idx| attribution.frames.get_mut(idx)) {
+                frame.storage_writes.push(StorageWrite {
+                    address: state.vm_local_state.callstack.current.this_address,
+                    key: u256_to_h256(data.src0_value.value),
+                    value: u256_to_h256(data.src1_value.value),
+                });
+            }
+        }
     }
 
     fn after_execution(
@@ -89,6 +147,12 @@ impl<H: HistoryMode> Tracer for CallTracer<H> {
                 self.handle_near_call_op_code(state, data, memory, &mut current_call);
             }
         }
+        if let Some(attribution) = self.pubdata_attribution.as_mut() {
+            attribution.frames.push(PubdataFrame {
+                pubdata_gas_at_start: attribution.pubdata_gas(&state),
+                storage_writes: vec![],
+            });
+        }
         self.stack.push(current_call);
     }
 }
@@ -240,6 +304,7 @@ impl<H: HistoryMode> CallTracer<H> {
         if current_call.r#type != CallType::NearCall {
             self.save_output(state, memory, ret_opcode, &mut current_call);
         }
+        self.attribute_pubdata(&state, &mut current_call);
 
         // If there is a parent call, push the current call to it
         // Otherwise, push the current call to the stack, because it's the top level call
@@ -247,9 +312,36 @@ impl<H: HistoryMode> CallTracer<H> {
             parent_call.calls.push(current_call);
         } else {
             self.stack.push(current_call);
+            if let Some(attribution) = self.pubdata_attribution.as_mut() {
+                // Keep frames aligned with the stack.
+                attribution.frames.push(PubdataFrame::default());
+            }
         }
     }
 
+    fn attribute_pubdata(&mut self, state: &VmLocalStateData<'_>, current_call: &mut Call) {
+        let Some(attribution) = self.pubdata_attribution.as_mut() else {
+            return;
+        };
+        let Some(frame) = attribution.frames.pop() else {
+            return;
+        };
+        if current_call.r#type == CallType::NearCall {
+            return;
+        }
+
+        let pubdata_gas = attribution
+            .pubdata_gas(state)
+            .saturating_sub(frame.pubdata_gas_at_start);
+        let pubdata_bytes = pubdata_gas
+            .checked_div(state.vm_local_state.current_ergs_per_pubdata_byte)
+            .unwrap_or(0);
+        current_call.pubdata = Some(CallPubdata {
+            pubdata_bytes,
+            storage_writes: frame.storage_writes,
+        });
+    }
+
     // Filter all near calls from the call stack
     // Important that the very first call is near call
     // And this NearCall includes several Normal or Mimic calls
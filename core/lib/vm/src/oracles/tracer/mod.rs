@@ -11,7 +11,9 @@ mod validation;
 pub use bootloader::BootloaderTracer;
 pub use call::CallTracer;
 pub use one_tx::OneTxTracer;
-pub use validation::{ValidationError, ValidationTracer, ValidationTracerParams};
+pub use validation::{
+    ValidationError, ValidationTracer, ValidationTracerParams, ViolatedValidationRule,
+};
 
 pub(crate) use transaction_result::TransactionResultTracer;
 
@@ -10,6 +10,7 @@ use crate::{
 };
 
 use crate::oracles::tracer::{CallTracer, StorageInvocationTracer};
+use std::time::Instant;
 use zk_evm::{
     tracing::{
         AfterDecodingData, AfterExecutionData, BeforeExecutionData, Tracer, VmLocalStateData,
@@ -18,6 +19,10 @@ use zk_evm::{
 };
 use zksync_types::vm_trace::Call;
 
+/// Number of opcodes executed between consecutive checks of the execution deadline.
+/// Querying the clock on each opcode would noticeably slow down the execution.
+const DEADLINE_CHECK_INTERVAL: u32 = 1 << 12;
+
 /// Allows any opcodes, but tells the VM to end the execution once the tx is over.
 // Internally depeds on Bootloader's VMHooks to get the notification once the transaction is finished.
 #[derive(Debug)]
@@ -38,6 +43,11 @@ pub struct OneTxTracer<H: HistoryMode> {
     computational_gas_limit: u32,
     in_account_validation: bool,
 
+    // Wall-clock time after which the execution of the transaction is aborted.
+    deadline: Option<Instant>,
+    executed_opcodes: u32,
+    deadline_exceeded: bool,
+
     bootloader_tracer: BootloaderTracer<H>,
     call_tracer: Option<CallTracer<H>>,
 }
@@ -85,6 +95,17 @@ impl<H: HistoryMode> Tracer for OneTxTracer<H> {
 
         self.gas_spent_on_bytecodes_and_long_messages +=
             gas_spent_on_bytecodes_and_long_messages_this_opcode(&state, &data);
+
+        if let Some(deadline) = self.deadline {
+            self.executed_opcodes = self.executed_opcodes.wrapping_add(1);
+            if self.executed_opcodes % DEADLINE_CHECK_INTERVAL == 0 {
+                self.deadline_exceeded = Instant::now() >= deadline;
+            }
+        }
+
+        if let Some(call_tracer) = self.call_tracer.as_mut() {
+            call_tracer.before_execution(state, data, memory);
+        }
     }
 
     fn after_execution(
@@ -105,6 +126,7 @@ impl<H: HistoryMode> ExecutionEndTracer<H> for OneTxTracer<H> {
         self.tx_has_been_processed
             || self.bootloader_tracer.should_stop_execution()
             || self.validation_run_out_of_gas()
+            || self.deadline_exceeded
     }
 }
 
@@ -141,11 +163,20 @@ impl<H: HistoryMode> OneTxTracer<H> {
             computational_gas_used: 0,
             computational_gas_limit,
             in_account_validation: false,
+            deadline: None,
+            executed_opcodes: 0,
+            deadline_exceeded: false,
             bootloader_tracer: BootloaderTracer::default(),
             call_tracer,
         }
     }
 
+    /// Sets the wall-clock deadline for the transaction execution. Once it's exceeded, the tracer stops the VM.
+    pub fn with_deadline(mut self, deadline: Option<Instant>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
     pub fn is_bootloader_out_of_gas(&self) -> bool {
         self.bootloader_tracer.is_bootloader_out_of_gas()
     }
@@ -158,6 +189,10 @@ impl<H: HistoryMode> OneTxTracer<H> {
         self.computational_gas_used > self.computational_gas_limit
     }
 
+    pub fn deadline_exceeded(&self) -> bool {
+        self.deadline_exceeded
+    }
+
     pub fn call_traces(&mut self) -> Vec<Call> {
         self.call_tracer
             .as_mut()
@@ -3,7 +3,7 @@ use zk_evm::{
         AfterDecodingData, AfterExecutionData, BeforeExecutionData, Tracer, VmLocalStateData,
     },
     vm_state::VmLocalState,
-    zkevm_opcode_defs::FatPointer,
+    zkevm_opcode_defs::{FatPointer, LogOpcode, Opcode},
 };
 use zksync_types::{vm_trace, U256};
 
@@ -13,6 +13,7 @@ use crate::oracles::tracer::{
     StorageInvocationTracer,
 };
 use crate::vm::get_vm_hook_params;
+use crate::vm_with_bootloader::ExecutionOutputLimits;
 use crate::{
     history_recorder::HistoryMode,
     oracles::tracer::utils::{
@@ -28,10 +29,19 @@ pub(crate) struct TransactionResultTracer<H: HistoryMode> {
     pub(crate) call_tracer: Option<CallTracer<H>>,
     missed_storage_invocation_limit: usize,
     missed_storage_invocation: usize,
+    output_limits: ExecutionOutputLimits,
+    events_count: usize,
+    storage_accesses_count: usize,
+    /// Description of the exceeded output limit, if any.
+    pub(crate) output_limit_exceeded: Option<String>,
 }
 
 impl<H: HistoryMode> TransactionResultTracer<H> {
-    pub(crate) fn new(missed_storage_invocation_limit: usize, with_call_tracer: bool) -> Self {
+    pub(crate) fn new(
+        missed_storage_invocation_limit: usize,
+        output_limits: ExecutionOutputLimits,
+        with_call_tracer: bool,
+    ) -> Self {
         let call_tracer = if with_call_tracer {
             Some(CallTracer::new())
         } else {
@@ -42,14 +52,50 @@ impl<H: HistoryMode> TransactionResultTracer<H> {
             revert_reason: None,
             gas_spent_on_bytecodes_and_long_messages: 0,
             missed_storage_invocation: 0,
+            output_limits,
+            events_count: 0,
+            storage_accesses_count: 0,
+            output_limit_exceeded: None,
             call_tracer,
         }
     }
+
+    /// Makes the call tracer attribute pubdata and storage writes to call frames.
+    pub(crate) fn with_pubdata_attribution(mut self) -> Self {
+        self.call_tracer = Some(CallTracer::with_pubdata_attribution());
+        self
+    }
+
     pub fn call_trace(&mut self) -> Option<Vec<vm_trace::Call>> {
         self.call_tracer
             .as_mut()
             .map(|call_tracer| call_tracer.extract_calls())
     }
+
+    fn check_output_limits(&mut self, data: &BeforeExecutionData) {
+        let limits = &self.output_limits;
+        match data.opcode.variant.opcode {
+            Opcode::Log(LogOpcode::Event) => {
+                self.events_count += 1;
+                if self.events_count > limits.max_events {
+                    self.output_limit_exceeded = Some(format!(
+                        "transaction emitted more than {} events",
+                        limits.max_events
+                    ));
+                }
+            }
+            Opcode::Log(LogOpcode::StorageRead | LogOpcode::StorageWrite) => {
+                self.storage_accesses_count += 1;
+                if self.storage_accesses_count > limits.max_storage_accesses {
+                    self.output_limit_exceeded = Some(format!(
+                        "transaction accessed storage more than {} times",
+                        limits.max_storage_accesses
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
 }
 
 impl<H: HistoryMode> Tracer for TransactionResultTracer<H> {
@@ -79,6 +125,15 @@ impl<H: HistoryMode> Tracer for TransactionResultTracer<H> {
 
             let success = vm_hook_params[0];
             let returndata_ptr = FatPointer::from_u256(vm_hook_params[1]);
+            let max_returndata_size = self.output_limits.max_returndata_size;
+            if returndata_ptr.length as usize > max_returndata_size {
+                // Do not copy the returndata; the execution will be stopped anyway.
+                self.output_limit_exceeded = Some(format!(
+                    "transaction returned {} bytes of data, while at most {max_returndata_size} bytes are allowed",
+                    returndata_ptr.length
+                ));
+                return;
+            }
             let returndata = read_pointer(memory, returndata_ptr);
 
             if success == U256::zero() {
@@ -88,8 +143,12 @@ impl<H: HistoryMode> Tracer for TransactionResultTracer<H> {
             }
         }
 
+        self.check_output_limits(&data);
         self.gas_spent_on_bytecodes_and_long_messages +=
             gas_spent_on_bytecodes_and_long_messages_this_opcode(&state, &data);
+        if let Some(call_tracer) = self.call_tracer.as_mut() {
+            call_tracer.before_execution(state, data, memory);
+        }
     }
 
     fn after_execution(
@@ -105,9 +164,10 @@ impl<H: HistoryMode> Tracer for TransactionResultTracer<H> {
 }
 
 impl<H: HistoryMode> ExecutionEndTracer<H> for TransactionResultTracer<H> {
-    // If we reach the limit of memory invocations, we stop the execution and return the error to user
+    // If we reach the limit of memory invocations or one of output limits, we stop the execution
+    // and return the error to user
     fn should_stop_execution(&self) -> bool {
-        self.is_limit_reached()
+        self.is_limit_reached() || self.output_limit_exceeded.is_some()
     }
 }
 
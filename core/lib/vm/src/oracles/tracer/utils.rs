@@ -12,7 +12,7 @@ use zk_evm::{
 };
 use zksync_config::constants::{
     ECRECOVER_PRECOMPILE_ADDRESS, KECCAK256_PRECOMPILE_ADDRESS, KNOWN_CODES_STORAGE_ADDRESS,
-    L1_MESSENGER_ADDRESS, SHA256_PRECOMPILE_ADDRESS,
+    L1_MESSENGER_ADDRESS, P256VERIFY_PRECOMPILE_ADDRESS, SHA256_PRECOMPILE_ADDRESS,
 };
 use zksync_types::U256;
 use zksync_utils::u256_to_h256;
@@ -163,6 +163,7 @@ pub(crate) fn computational_gas_price(
             if address == KECCAK256_PRECOMPILE_ADDRESS
                 || address == SHA256_PRECOMPILE_ADDRESS
                 || address == ECRECOVER_PRECOMPILE_ADDRESS
+                || address == P256VERIFY_PRECOMPILE_ADDRESS
             {
                 data.src1_value.value.low_u32()
             } else {
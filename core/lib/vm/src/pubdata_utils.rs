@@ -39,6 +39,22 @@ impl<H: HistoryMode> VmInstance<'_, H> {
             + published_bytecode_bytes
     }
 
+    /// Returns the number of storage slots written after `from_timestamp` that were restored to their values
+    /// at the start of the batch. Such slots are not published, and the pubdata paid for them is refunded.
+    pub(crate) fn refunded_storage_writes(&self, from_timestamp: Timestamp) -> usize {
+        let storage_logs = self
+            .state
+            .storage
+            .storage_log_queries_after_timestamp(from_timestamp);
+        let (_, deduplicated_logs) =
+            sort_storage_access_queries(storage_logs.iter().map(|log| &log.log_query));
+
+        deduplicated_logs
+            .iter()
+            .filter(|log| log.rw_flag && self.state.storage.is_write_restoring_initial_value(log))
+            .count()
+    }
+
     fn pubdata_published_for_writes(&self, from_timestamp: Timestamp) -> u32 {
         // This `HashMap` contains how much was already paid for every slot that was paid during the last tx execution.
         // For the slots that weren't paid during the last tx execution we can just use
@@ -77,7 +93,7 @@ impl<H: HistoryMode> VmInstance<'_, H> {
         deduplicated_logs
             .into_iter()
             .filter_map(|log| {
-                if log.rw_flag {
+                if log.rw_flag && !self.state.storage.is_write_restoring_initial_value(&log) {
                     let key = storage_key_of_log(&log);
                     let pre_paid = pre_paid_before_tx(&key);
                     let to_pay_by_user = self.state.storage.base_price_for_write(&log);
@@ -26,7 +26,7 @@ use crate::{
     vm_with_bootloader::{
         bytecode_to_factory_dep, get_bootloader_memory, get_bootloader_memory_for_encoded_tx,
         push_raw_transaction_to_bootloader_memory, BlockContext, BlockContextMode,
-        BootloaderJobType, TxExecutionMode,
+        BootloaderJobType, ExecutionOutputLimits, TxExecutionMode,
     },
     vm_with_bootloader::{
         init_vm_inner, push_transaction_to_bootloader_memory, DerivedBlockContext,
@@ -1526,7 +1526,8 @@ fn test_tracing_of_execution_errors() {
         None,
     );
 
-    let mut tracer = TransactionResultTracer::new(usize::MAX, false);
+    let mut tracer =
+        TransactionResultTracer::new(usize::MAX, ExecutionOutputLimits::default(), false);
     assert_eq!(
         vm.execute_with_custom_tracer(&mut tracer),
         VmExecutionStopReason::VmFinished,
@@ -1576,7 +1577,7 @@ fn test_tracing_of_execution_errors() {
         None,
     );
 
-    let mut tracer = TransactionResultTracer::new(10, false);
+    let mut tracer = TransactionResultTracer::new(10, ExecutionOutputLimits::default(), false);
     assert_eq!(
         vm.execute_with_custom_tracer(&mut tracer),
         VmExecutionStopReason::TracerRequestedStop,
@@ -1584,6 +1585,57 @@ fn test_tracing_of_execution_errors() {
     assert!(tracer.is_limit_reached());
 }
 
+#[test]
+fn test_output_limits() {
+    let mut vm_test_env = VmTestEnv::default();
+    let contract_code = read_test_contract();
+    let tx: Transaction = get_deploy_tx(
+        H256::random(),
+        Nonce(0),
+        &contract_code,
+        vec![],
+        &[],
+        Fee {
+            gas_limit: U256::from(20000000u32),
+            max_fee_per_gas: U256::from(250_000_000),
+            max_priority_fee_per_gas: U256::zero(),
+            gas_per_pubdata_limit: U256::from(MAX_GAS_PER_PUBDATA_BYTE),
+        },
+    )
+    .into();
+    vm_test_env.set_rich_account(&tx.initiator_account());
+
+    let limits = [
+        ExecutionOutputLimits {
+            max_events: 0,
+            ..ExecutionOutputLimits::default()
+        },
+        ExecutionOutputLimits {
+            max_storage_accesses: 1,
+            ..ExecutionOutputLimits::default()
+        },
+    ];
+    for limits in limits {
+        let mut vm_helper = VmTestHelper::new(&mut vm_test_env);
+        let mut vm = vm_helper.vm();
+        vm.set_output_limits(limits);
+        push_transaction_to_bootloader_memory(&mut vm, &tx, TxExecutionMode::VerifyExecute, None);
+
+        let result = vm.execute_till_block_end(BootloaderJobType::TransactionExecution);
+        let revert_reason = result.block_tip_result.revert_reason;
+        assert!(
+            matches!(revert_reason, Some(TxRevertReason::OutputLimitExceeded(_))),
+            "{revert_reason:?}"
+        );
+    }
+
+    let mut vm_helper = VmTestHelper::new(&mut vm_test_env);
+    let mut vm = vm_helper.vm();
+    push_transaction_to_bootloader_memory(&mut vm, &tx, TxExecutionMode::VerifyExecute, None);
+    let result = vm.execute_till_block_end(BootloaderJobType::TransactionExecution);
+    assert_eq!(result.block_tip_result.revert_reason, None);
+}
+
 /// Checks that `TX_GAS_LIMIT_OFFSET` constant is correct.
 #[test]
 fn test_tx_gas_limit_offset() {
@@ -1892,6 +1944,7 @@ fn test_call_tracer() {
         error: None,
         revert_reason: None,
         calls: vec![],
+        pubdata: None,
     };
     assert_eq!(create_call.unwrap(), expected);
 
@@ -1935,6 +1988,7 @@ fn test_call_tracer() {
         error: None,
         revert_reason: None,
         calls: vec![],
+        pubdata: None,
     };
 
     // First loop filter out the bootloaders calls and
@@ -1976,6 +2030,7 @@ fn test_call_tracer() {
         error: None,
         revert_reason: Some("This method always reverts".to_string()),
         calls: vec![],
+        pubdata: None,
     };
 
     for call in calls {
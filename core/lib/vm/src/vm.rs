@@ -1,5 +1,6 @@
 use std::convert::TryFrom;
 use std::fmt::Debug;
+use std::time::Instant;
 
 use zk_evm::aux_structures::Timestamp;
 use zk_evm::vm_state::{PrimitiveValue, VmLocalState, VmState};
@@ -7,6 +8,7 @@ use zk_evm::witness_trace::DummyTracer;
 use zk_evm::zkevm_opcode_defs::decoding::{AllowedPcOrImm, EncodingModeProduction, VmEncodingMode};
 use zk_evm::zkevm_opcode_defs::definitions::RET_IMPLICIT_RETURNDATA_PARAMS_REGISTER;
 use zksync_config::constants::MAX_TXS_IN_BLOCK;
+use zksync_types::circuit::CircuitStatistic;
 use zksync_types::l2_to_l1_log::L2ToL1Log;
 use zksync_types::tx::tx_execution_info::{TxExecutionStatus, VmExecutionLogs};
 use zksync_types::vm_trace::{Call, VmExecutionTrace, VmTrace};
@@ -33,8 +35,8 @@ use crate::utils::{
     precompile_calls_count_after_timestamp,
 };
 use crate::vm_with_bootloader::{
-    BootloaderJobType, DerivedBlockContext, TxExecutionMode, BOOTLOADER_HEAP_PAGE,
-    OPERATOR_REFUNDS_OFFSET,
+    BootloaderJobType, DerivedBlockContext, ExecutionOutputLimits, TxExecutionMode,
+    BOOTLOADER_HEAP_PAGE, OPERATOR_REFUNDS_OFFSET,
 };
 use crate::Word;
 
@@ -72,6 +74,7 @@ pub struct VmInstance<'a, H: HistoryMode> {
     pub execution_mode: TxExecutionMode,
     pub block_context: DerivedBlockContext,
     pub(crate) bootloader_state: BootloaderState,
+    pub(crate) output_limits: ExecutionOutputLimits,
 
     pub snapshots: Vec<VmSnapshot>,
 }
@@ -133,6 +136,11 @@ pub struct VmPartialExecutionResult {
     pub contracts_used: usize,
     pub cycles_used: u32,
     pub computational_gas_used: u32,
+    /// Number of storage slots written by the transaction that were restored to their values at the start
+    /// of the batch. These slots are not published, and the pubdata paid for them is refunded.
+    pub refunded_storage_writes: usize,
+    /// Estimated usage of the basic circuits of each type.
+    pub circuit_statistic: CircuitStatistic,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -313,6 +321,12 @@ pub struct VmSnapshot {
 }
 
 impl<H: HistoryMode> VmInstance<'_, H> {
+    /// Sets limits on the output of transactions executed with [`Self::execute_till_block_end()`]
+    /// and its variations.
+    pub fn set_output_limits(&mut self, limits: ExecutionOutputLimits) {
+        self.output_limits = limits;
+    }
+
     fn has_ended(&self) -> bool {
         match vm_may_have_ended_inner(&self.state) {
             None | Some(NewVmExecutionResult::MostLikelyDidNotFinish(_, _)) => false,
@@ -585,11 +599,28 @@ impl<H: HistoryMode> VmInstance<'_, H> {
         &mut self,
         validation_computational_gas_limit: u32,
         with_call_tracer: bool,
+    ) -> Result<VmTxExecutionResult, TxRevertReason> {
+        self.execute_next_tx_with_deadline(
+            validation_computational_gas_limit,
+            with_call_tracer,
+            None,
+        )
+    }
+
+    /// Same as [`Self::execute_next_tx()`], but aborts the execution with [`TxRevertReason::ExecutionTimeout`]
+    /// if the transaction isn't processed until the provided wall-clock `deadline`.
+    /// The VM state is not rolled back in this case; the caller is responsible for this.
+    pub fn execute_next_tx_with_deadline(
+        &mut self,
+        validation_computational_gas_limit: u32,
+        with_call_tracer: bool,
+        deadline: Option<Instant>,
     ) -> Result<VmTxExecutionResult, TxRevertReason> {
         let tx_index = self.bootloader_state.next_unexecuted_tx() as u32;
 
         let mut tx_tracer: OneTxTracer<H> =
-            OneTxTracer::new(validation_computational_gas_limit, with_call_tracer);
+            OneTxTracer::new(validation_computational_gas_limit, with_call_tracer)
+                .with_deadline(deadline);
 
         let timestamp_initial = Timestamp(self.state.local_state.timestamp);
         let cycles_initial = self.state.local_state.monotonic_cycle_counter;
@@ -622,6 +653,8 @@ impl<H: HistoryMode> VmInstance<'_, H> {
                     let vm_execution_logs =
                         self.collect_execution_logs_after_timestamp(timestamp_initial);
 
+                    let cycles_used =
+                        self.state.local_state.monotonic_cycle_counter - cycles_initial;
                     let computational_gas_used = calculate_computational_gas_used(
                         self,
                         &tx_tracer,
@@ -645,9 +678,12 @@ impl<H: HistoryMode> VmInstance<'_, H> {
                                 .state
                                 .decommittment_processor
                                 .get_decommitted_bytecodes_after_timestamp(timestamp_initial),
-                            cycles_used: self.state.local_state.monotonic_cycle_counter
-                                - cycles_initial,
+                            cycles_used,
                             computational_gas_used,
+                            refunded_storage_writes: self
+                                .refunded_storage_writes(timestamp_initial),
+                            circuit_statistic: self
+                                .circuit_statistic_after_timestamp(timestamp_initial, cycles_used),
                         },
                         call_traces: tx_tracer.call_traces(),
                     })
@@ -659,6 +695,8 @@ impl<H: HistoryMode> VmInstance<'_, H> {
                         ),
                         data: vec![],
                     }))
+                } else if tx_tracer.deadline_exceeded() {
+                    Err(TxRevertReason::ExecutionTimeout)
                 } else {
                     // VM ended up in state
                     // `stop_reason == VmExecutionStopReason::TracerRequestedStop && !tx_tracer.tx_has_been_processed() && !tx_tracer.validation_run_out_of_gas()`.
@@ -672,22 +710,42 @@ impl<H: HistoryMode> VmInstance<'_, H> {
 
     /// Returns full VM result and partial result produced within the current execution.
     pub fn execute_till_block_end(&mut self, job_type: BootloaderJobType) -> VmBlockResult {
-        self.execute_till_block_end_with_tracer(
-            job_type,
-            &mut TransactionResultTracer::new(self.execution_mode.invocation_limit(), false),
-        )
+        let mut tracer = self.transaction_result_tracer(false);
+        self.execute_till_block_end_with_tracer(job_type, &mut tracer)
     }
 
     pub fn execute_till_block_end_with_call_tracer(
         &mut self,
         job_type: BootloaderJobType,
     ) -> VmBlockResult {
-        let mut tracer = TransactionResultTracer::new(self.execution_mode.invocation_limit(), true);
+        let mut tracer = self.transaction_result_tracer(true);
         let mut block_result = self.execute_till_block_end_with_tracer(job_type, &mut tracer);
         block_result.full_result.trace = VmTrace::CallTrace(tracer.call_trace().unwrap());
         block_result
     }
 
+    /// Same as [`Self::execute_till_block_end_with_call_tracer()`], but calls in the trace are additionally
+    /// annotated with the pubdata they have produced and the storage slots they have written.
+    pub fn execute_till_block_end_with_zk_tracer(
+        &mut self,
+        job_type: BootloaderJobType,
+    ) -> VmBlockResult {
+        let mut tracer = self
+            .transaction_result_tracer(true)
+            .with_pubdata_attribution();
+        let mut block_result = self.execute_till_block_end_with_tracer(job_type, &mut tracer);
+        block_result.full_result.trace = VmTrace::CallTrace(tracer.call_trace().unwrap());
+        block_result
+    }
+
+    fn transaction_result_tracer(&self, with_call_tracer: bool) -> TransactionResultTracer<H> {
+        TransactionResultTracer::new(
+            self.execution_mode.invocation_limit(),
+            self.output_limits,
+            with_call_tracer,
+        )
+    }
+
     fn execute_till_block_end_with_tracer(
         &mut self,
         job_type: BootloaderJobType,
@@ -741,6 +799,7 @@ impl<H: HistoryMode> VmInstance<'_, H> {
                     full_result.revert_reason = Some(revert_reason);
                 }
 
+                let cycles_used = self.state.local_state.monotonic_cycle_counter - cycles_initial;
                 let block_tip_result = VmPartialExecutionResult {
                     logs: self.collect_execution_logs_after_timestamp(timestamp_initial),
                     revert_reason: full_result.revert_reason.clone().map(|r| r.revert_reason),
@@ -748,8 +807,11 @@ impl<H: HistoryMode> VmInstance<'_, H> {
                         .state
                         .decommittment_processor
                         .get_decommitted_bytecodes_after_timestamp(timestamp_initial),
-                    cycles_used: self.state.local_state.monotonic_cycle_counter - cycles_initial,
+                    cycles_used,
                     computational_gas_used,
+                    refunded_storage_writes: self.refunded_storage_writes(timestamp_initial),
+                    circuit_statistic: self
+                        .circuit_statistic_after_timestamp(timestamp_initial, cycles_used),
                 };
 
                 // Collecting `block_tip_result` needs logs with timestamp, so we drain events for the `full_result`
@@ -771,39 +833,44 @@ impl<H: HistoryMode> VmInstance<'_, H> {
             VmExecutionStopReason::TracerRequestedStop => {
                 metrics::increment_counter!("runtime_context.execution.dropped");
 
-                if tx_result_tracer.is_limit_reached() {
-                    VmBlockResult {
-                        // Normally tracer should never stop, but if it's transaction call and it consumes
-                        // too much requests to memory, we stop execution and return error.
-                        full_result: VmExecutionResult {
-                            events: vec![],
-                            storage_log_queries: vec![],
-                            used_contract_hashes: vec![],
-                            l2_to_l1_logs: vec![],
-                            return_data: vec![],
-                            gas_used: 0,
-                            computational_gas_used: 0,
-                            contracts_used: 0,
-                            revert_reason: Some(VmRevertReasonParsingResult {
-                                revert_reason: TxRevertReason::MissingInvocationLimitReached,
-                                original_data: vec![],
-                            }),
-                            trace: VmTrace::ExecutionTrace(VmExecutionTrace::default()),
-                            total_log_queries: 0,
-                            cycles_used: 0,
-                        },
-                        block_tip_result: VmPartialExecutionResult {
-                            logs: Default::default(),
-                            revert_reason: Some(TxRevertReason::MissingInvocationLimitReached),
-                            contracts_used: 0,
-                            cycles_used: 0,
-                            computational_gas_used: 0,
-                        },
-                    }
+                let revert_reason = if tx_result_tracer.is_limit_reached() {
+                    TxRevertReason::MissingInvocationLimitReached
+                } else if let Some(message) = tx_result_tracer.output_limit_exceeded.take() {
+                    TxRevertReason::OutputLimitExceeded(message)
                 } else {
                     unreachable!(
-                        "Tracer should never stop execution, except MissingInvocationLimitReached"
+                        "Tracer should never stop execution, except when one of execution limits is reached"
                     );
+                };
+                // Normally tracer should never stop, but if it's transaction call and it exceeds
+                // one of execution limits, we stop execution and return error.
+                VmBlockResult {
+                    full_result: VmExecutionResult {
+                        events: vec![],
+                        storage_log_queries: vec![],
+                        used_contract_hashes: vec![],
+                        l2_to_l1_logs: vec![],
+                        return_data: vec![],
+                        gas_used: 0,
+                        computational_gas_used: 0,
+                        contracts_used: 0,
+                        revert_reason: Some(VmRevertReasonParsingResult {
+                            revert_reason: revert_reason.clone(),
+                            original_data: vec![],
+                        }),
+                        trace: VmTrace::ExecutionTrace(VmExecutionTrace::default()),
+                        total_log_queries: 0,
+                        cycles_used: 0,
+                    },
+                    block_tip_result: VmPartialExecutionResult {
+                        logs: Default::default(),
+                        revert_reason: Some(revert_reason),
+                        contracts_used: 0,
+                        cycles_used: 0,
+                        computational_gas_used: 0,
+                        refunded_storage_writes: 0,
+                        circuit_statistic: CircuitStatistic::default(),
+                    },
                 }
             }
         }
@@ -842,6 +909,7 @@ impl<H: HistoryMode> VmInstance<'_, H> {
             gas_remaining_before,
             spent_pubdata_counter_before,
         );
+        let cycles_used = self.state.local_state.monotonic_cycle_counter - cycles_initial;
         VmPartialExecutionResult {
             logs: self.collect_execution_logs_after_timestamp(timestamp_initial),
             revert_reason,
@@ -849,8 +917,11 @@ impl<H: HistoryMode> VmInstance<'_, H> {
                 .state
                 .decommittment_processor
                 .get_decommitted_bytecodes_after_timestamp(timestamp_initial),
-            cycles_used: self.state.local_state.monotonic_cycle_counter - cycles_initial,
+            cycles_used,
             computational_gas_used,
+            refunded_storage_writes: self.refunded_storage_writes(timestamp_initial),
+            circuit_statistic: self
+                .circuit_statistic_after_timestamp(timestamp_initial, cycles_used),
         }
     }
 
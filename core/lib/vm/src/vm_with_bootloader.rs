@@ -200,6 +200,32 @@ impl TxExecutionMode {
     }
 }
 
+/// Limits on the output of a transaction executed with [`VmInstance::execute_till_block_end()`]
+/// and its variations. If any of the limits is exceeded, the execution is aborted with
+/// [`TxRevertReason::OutputLimitExceeded`](crate::TxRevertReason::OutputLimitExceeded).
+///
+/// The limits are not applied by default; they are meant to protect the API server from calls
+/// producing excessive amounts of data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutionOutputLimits {
+    /// Max size of the data returned by the transaction, in bytes.
+    pub max_returndata_size: usize,
+    /// Max number of events emitted during the execution.
+    pub max_events: usize,
+    /// Max number of storage reads and writes performed during the execution.
+    pub max_storage_accesses: usize,
+}
+
+impl Default for ExecutionOutputLimits {
+    fn default() -> Self {
+        Self {
+            max_returndata_size: usize::MAX,
+            max_events: usize::MAX,
+            max_storage_accesses: usize::MAX,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BootloaderJobType {
     TransactionExecution,
@@ -364,6 +390,7 @@ pub fn init_vm_inner<'a, H: HistoryMode>(
         execution_mode,
         block_context: block_context.inner_block_context(),
         bootloader_state: BootloaderState::new(),
+        output_limits: ExecutionOutputLimits::default(),
         snapshots: Vec::new(),
     });
 
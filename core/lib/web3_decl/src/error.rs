@@ -35,4 +35,16 @@ pub enum Web3Error {
     LogsLimitExceeded(usize, u32, u32),
     #[error("invalid filter: if blockHash is supplied fromBlock and toBlock must not be")]
     InvalidFilterBlockHash,
+    #[error("Too many items requested, the limit is {0}")]
+    TooManyItemsRequested(usize),
+    #[error("Historical data up to block #{0} is pruned on this node")]
+    PrunedHistory(u32),
+    #[error("Unauthorized")]
+    Unauthorized,
+    #[error("Invalid log directives: {0}")]
+    InvalidLogDirectives(String),
+    #[error("Invalid transaction bundle: {0}")]
+    InvalidBundle(String),
+    #[error("Invalid L1 batch range: {0}")]
+    InvalidL1BatchRange(String),
 }
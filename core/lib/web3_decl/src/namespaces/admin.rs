@@ -0,0 +1,54 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use zksync_types::{
+    api::{AdmissionPoliciesConfig, ComponentHealth},
+    L1BatchNumber,
+};
+
+/// Namespace for node operators. Each method requires the admin token configured for the node
+/// as the first param.
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "admin")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "admin")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "admin")
+)]
+pub trait AdminNamespace {
+    #[method(name = "pauseTxAdmission")]
+    fn pause_tx_admission(&self, token: String) -> RpcResult<()>;
+
+    #[method(name = "resumeTxAdmission")]
+    fn resume_tx_admission(&self, token: String) -> RpcResult<()>;
+
+    #[method(name = "forceSealBatch")]
+    fn force_seal_batch(&self, token: String) -> RpcResult<()>;
+
+    #[method(name = "resyncMempool")]
+    fn resync_mempool(&self, token: String) -> RpcResult<()>;
+
+    /// Allows executing L1 batches up to and including the specified one without waiting for the execution delay.
+    #[method(name = "skipExecutionDelay")]
+    fn skip_execution_delay(&self, token: String, l1_batch_number: L1BatchNumber) -> RpcResult<()>;
+
+    #[method(name = "setLogDirectives")]
+    fn set_log_directives(&self, token: String, directives: String) -> RpcResult<()>;
+
+    /// Returns the currently used log filtering directives, or `null` if they cannot be changed at runtime.
+    #[method(name = "getLogDirectives")]
+    fn get_log_directives(&self, token: String) -> RpcResult<Option<String>>;
+
+    #[method(name = "setAdmissionPolicies")]
+    fn set_admission_policies(
+        &self,
+        token: String,
+        config: AdmissionPoliciesConfig,
+    ) -> RpcResult<()>;
+
+    #[method(name = "health")]
+    async fn health(&self, token: String) -> RpcResult<Vec<ComponentHealth>>;
+}
@@ -1,8 +1,12 @@
 use crate::types::H256;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 
-use zksync_types::api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig};
+use zksync_types::api::{
+    BlockId, BlockNumber, DebugCall, RejectedPriorityOp, ResultDebugCall, TracerConfig,
+    TransactionAccessSet,
+};
 use zksync_types::transaction_request::CallRequest;
+use zksync_types::L1BatchNumber;
 
 #[cfg_attr(
     all(feature = "client", feature = "server"),
@@ -42,4 +46,11 @@ pub trait DebugNamespace {
         tx_hash: H256,
         options: Option<TracerConfig>,
     ) -> RpcResult<Option<DebugCall>>;
+    #[method(name = "getL1BatchAccessSets")]
+    async fn get_l1_batch_access_sets(
+        &self,
+        batch: L1BatchNumber,
+    ) -> RpcResult<Option<Vec<TransactionAccessSet>>>;
+    #[method(name = "getRejectedPriorityOps")]
+    async fn get_rejected_priority_ops(&self) -> RpcResult<Vec<RejectedPriorityOp>>;
 }
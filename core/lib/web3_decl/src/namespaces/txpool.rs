@@ -0,0 +1,19 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use zksync_types::api::TxpoolStatus;
+
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "txpool")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "txpool")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "txpool")
+)]
+pub trait TxpoolNamespace {
+    #[method(name = "status")]
+    fn status(&self) -> RpcResult<TxpoolStatus>;
+}
@@ -2,13 +2,18 @@ use crate::types::Token;
 use bigdecimal::BigDecimal;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use std::collections::HashMap;
-use zksync_types::api::{BridgeAddresses, L2ToL1LogProof, TransactionDetails};
+use zksync_types::api::{
+    AccountSummary, BalancesFilter, BatchResourceUsage, BlockIdVariant, BridgeAddresses,
+    BundleSimulation, L2ToL1LogProof, L2ToL1MessageProof, NodeStatus, ProtocolVersion,
+    RejectionStats, StorageDiff, StorageDiffPagination, TokenFilter, TransactionDetails,
+    TransactionHistory, WithdrawalFinalizationParams,
+};
 use zksync_types::transaction_request::CallRequest;
 use zksync_types::{
     api::U64,
     explorer_api::{BlockDetails, L1BatchDetails},
-    fee::Fee,
-    Address, H256, U256,
+    fee::FeeEstimate,
+    Address, Bytes, H256, U256,
 };
 use zksync_types::{L1BatchNumber, MiniblockNumber};
 
@@ -26,7 +31,7 @@ use zksync_types::{L1BatchNumber, MiniblockNumber};
 )]
 pub trait ZksNamespace {
     #[method(name = "estimateFee")]
-    async fn estimate_fee(&self, req: CallRequest) -> RpcResult<Fee>;
+    async fn estimate_fee(&self, req: CallRequest) -> RpcResult<FeeEstimate>;
 
     #[method(name = "estimateGasL1ToL2")]
     async fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> RpcResult<U256>;
@@ -44,13 +49,21 @@ pub trait ZksNamespace {
     async fn l1_chain_id(&self) -> RpcResult<U64>;
 
     #[method(name = "getConfirmedTokens")]
-    async fn get_confirmed_tokens(&self, from: u32, limit: u8) -> RpcResult<Vec<Token>>;
+    async fn get_confirmed_tokens(
+        &self,
+        from: u32,
+        limit: u8,
+        filter: Option<TokenFilter>,
+    ) -> RpcResult<Vec<Token>>;
     #[method(name = "getTokenPrice")]
     async fn get_token_price(&self, token_address: Address) -> RpcResult<BigDecimal>;
 
     #[method(name = "getAllAccountBalances")]
-    async fn get_all_account_balances(&self, address: Address)
-        -> RpcResult<HashMap<Address, U256>>;
+    async fn get_all_account_balances(
+        &self,
+        address: Address,
+        filter: Option<BalancesFilter>,
+    ) -> RpcResult<HashMap<Address, U256>>;
 
     #[method(name = "getL2ToL1MsgProof")]
     async fn get_l2_to_l1_msg_proof(
@@ -68,6 +81,27 @@ pub trait ZksNamespace {
         index: Option<usize>,
     ) -> RpcResult<Option<L2ToL1LogProof>>;
 
+    #[method(name = "getL2ToL1LogProofs")]
+    async fn get_l2_to_l1_log_proofs(
+        &self,
+        tx_hash: H256,
+        indices: Vec<usize>,
+    ) -> RpcResult<Vec<Option<L2ToL1LogProof>>>;
+
+    #[method(name = "getWithdrawalFinalizationParams")]
+    async fn get_withdrawal_finalization_params(
+        &self,
+        tx_hash: H256,
+        index: Option<usize>,
+    ) -> RpcResult<Option<WithdrawalFinalizationParams>>;
+
+    #[method(name = "getL2ToL1MessageProof")]
+    async fn get_l2_to_l1_message_proof(
+        &self,
+        batch: L1BatchNumber,
+        index: u32,
+    ) -> RpcResult<Option<L2ToL1MessageProof>>;
+
     #[method(name = "L1BatchNumber")]
     async fn get_l1_batch_number(&self) -> RpcResult<U64>;
 
@@ -83,6 +117,26 @@ pub trait ZksNamespace {
     #[method(name = "getTransactionDetails")]
     async fn get_transaction_details(&self, hash: H256) -> RpcResult<Option<TransactionDetails>>;
 
+    /// Returns the history of replacements of transactions with the same initiator and nonce
+    /// as the specified transaction.
+    #[method(name = "getTransactionHistory")]
+    async fn get_transaction_history(&self, hash: H256) -> RpcResult<Option<TransactionHistory>>;
+
+    /// Returns the aggregated node status: progress of miniblocks and L1 batches, the mempool size
+    /// and health of the node components.
+    #[method(name = "getNodeStatus")]
+    async fn get_node_status(&self) -> RpcResult<NodeStatus>;
+
+    /// Simulates an ordered bundle of signed raw transactions on top of the state at the specified block
+    /// (the pending block by default) without submitting them. Transactions are executed one after another,
+    /// so each transaction observes state changes made by the previous ones.
+    #[method(name = "simulateBundle")]
+    async fn simulate_bundle(
+        &self,
+        txs: Vec<Bytes>,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<BundleSimulation>;
+
     #[method(name = "getRawBlockTransactions")]
     async fn get_raw_block_transactions(
         &self,
@@ -93,9 +147,58 @@ pub trait ZksNamespace {
     async fn get_l1_batch_details(&self, batch: L1BatchNumber)
         -> RpcResult<Option<L1BatchDetails>>;
 
+    /// Returns resources consumed by the L1 batch and the cost of the L1 transactions processing it.
+    #[method(name = "getBatchResourceUsage")]
+    async fn get_batch_resource_usage(
+        &self,
+        batch: L1BatchNumber,
+    ) -> RpcResult<Option<BatchResourceUsage>>;
+
     #[method(name = "getBytecodeByHash")]
     async fn get_bytecode_by_hash(&self, hash: H256) -> RpcResult<Option<Vec<u8>>>;
 
     #[method(name = "getL1GasPrice")]
     async fn get_l1_gas_price(&self) -> RpcResult<U64>;
+
+    #[method(name = "getGasPerPubdata")]
+    async fn get_gas_per_pubdata(&self) -> RpcResult<U64>;
+
+    #[method(name = "getProtocolVersion")]
+    async fn get_protocol_version(
+        &self,
+        version_id: Option<u16>,
+    ) -> RpcResult<Option<ProtocolVersion>>;
+
+    #[method(name = "getOperatorAddress")]
+    async fn get_operator_address(&self) -> RpcResult<Address>;
+
+    /// Returns numbers of transactions rejected since the server start, grouped by the rejection reason.
+    #[method(name = "getRejectionStats")]
+    async fn get_rejection_stats(&self) -> RpcResult<RejectionStats>;
+
+    /// Returns net storage changes made by L1 batches in `(l1_batch_from, l1_batch_to]`. Results are paginated;
+    /// use `next_cursor` from the response to request the next page.
+    #[method(name = "getStorageDiff")]
+    async fn get_storage_diff(
+        &self,
+        l1_batch_from: L1BatchNumber,
+        l1_batch_to: L1BatchNumber,
+        address: Option<Address>,
+        pagination: Option<StorageDiffPagination>,
+    ) -> RpcResult<StorageDiff>;
+
+    /// Returns a summary of transactions sent by the specified account, or `None` if the account
+    /// hasn't sent any transactions.
+    #[method(name = "getAccountSummary")]
+    async fn get_account_summary(&self, address: Address) -> RpcResult<Option<AccountSummary>>;
+
+    /// Submits a raw transaction that must not be included into a miniblock with the timestamp greater than
+    /// or equal to `valid_until` (a UNIX timestamp in seconds). The deadline is not signed, so it's best-effort only:
+    /// the same signed transaction can be resubmitted with another deadline (e.g., via `eth_sendRawTransaction`).
+    #[method(name = "sendRawTransactionWithDeadline")]
+    async fn send_raw_transaction_with_deadline(
+        &self,
+        tx_bytes: Bytes,
+        valid_until: U64,
+    ) -> RpcResult<H256>;
 }
@@ -14,6 +14,7 @@ use itertools::unfold;
 use rlp::Rlp;
 use serde::{de, Deserialize, Serialize, Serializer};
 
+use zksync_types::{api::TxLifecycleStatus, L1BatchNumber};
 pub use zksync_types::{
     api::{Block, BlockNumber, Log, TransactionReceipt, TransactionRequest},
     vm_trace::{ContractSourceDebugInfo, VmDebugTrace, VmExecutionStep},
@@ -35,6 +36,9 @@ pub struct Token {
     pub name: String,
     pub symbol: String,
     pub decimals: u8,
+    /// For tokens bridged via the ERC-20 bridge, number of the L1 batch that included the first deposit of the token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_seen_l1_batch: Option<L1BatchNumber>,
 }
 
 /// Helper structure used to parse deserialized `Ethereum` transaction.
@@ -345,6 +349,7 @@ fn topic_to_option<T>(topic: ethabi::Topic<T>) -> Option<Vec<T>> {
 pub enum PubSubResult {
     Header(BlockHeader),
     Log(Log),
+    TxStatus(TxLifecycleStatus),
     TxHash(H256),
     Syncing(bool),
 }
@@ -0,0 +1,105 @@
+use serde::Deserialize;
+
+use std::time::Duration;
+
+/// Storage backend the state keeper is run against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IoMode {
+    /// State keeper IO and the batch executor operate over in-memory storage. Doesn't require
+    /// Postgres; suitable for regression detection on CI.
+    InMemory,
+    /// Production IO (`MempoolIO`) persisting miniblocks and L1 batches to Postgres. Requires
+    /// `DATABASE_URL` pointing to a dedicated database with genesis already performed.
+    Postgres,
+}
+
+/// Relative weights of transaction kinds in the generated stream.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct TransactionWeights {
+    #[serde(default = "default_transfer_weight")]
+    pub transfer_weight: u32,
+    #[serde(default = "default_erc20_weight")]
+    pub erc20_weight: u32,
+    #[serde(default = "default_heavy_compute_weight")]
+    pub heavy_compute_weight: u32,
+    #[serde(default = "default_heavy_pubdata_weight")]
+    pub heavy_pubdata_weight: u32,
+}
+
+/// Configuration of the state keeper benchmark. Loaded from env variables with
+/// the `SK_BENCHMARK_` prefix; all parameters have defaults.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BenchmarkConfig {
+    #[serde(default = "default_io_mode")]
+    pub io_mode: IoMode,
+    /// Number of accounts sending transactions.
+    #[serde(default = "default_accounts")]
+    pub accounts: usize,
+    /// Total number of transactions in the stream (not counting the contract deployment).
+    #[serde(default = "default_transactions")]
+    pub transactions: usize,
+    /// Amount of gas burned by each heavy compute transaction.
+    #[serde(default = "default_heavy_compute_gas")]
+    pub heavy_compute_gas: u32,
+    /// Number of new storage slots written by each heavy pubdata transaction.
+    #[serde(default = "default_heavy_pubdata_writes")]
+    pub heavy_pubdata_writes: u32,
+    /// Max duration of the benchmark; the benchmark fails if the stream isn't processed in time.
+    #[serde(default = "default_timeout_sec")]
+    pub timeout_sec: u64,
+    /// If set, the benchmark fails if the measured throughput is lower than this value.
+    pub min_tps: Option<f64>,
+}
+
+impl BenchmarkConfig {
+    pub fn from_env() -> anyhow::Result<(Self, TransactionWeights)> {
+        let config = envy::prefixed("SK_BENCHMARK_").from_env()?;
+        let weights = envy::prefixed("SK_BENCHMARK_").from_env()?;
+        Ok((config, weights))
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_sec)
+    }
+}
+
+fn default_io_mode() -> IoMode {
+    IoMode::InMemory
+}
+
+fn default_accounts() -> usize {
+    100
+}
+
+fn default_transactions() -> usize {
+    5_000
+}
+
+fn default_heavy_compute_gas() -> u32 {
+    10_000_000
+}
+
+fn default_heavy_pubdata_writes() -> u32 {
+    100
+}
+
+fn default_timeout_sec() -> u64 {
+    600
+}
+
+fn default_transfer_weight() -> u32 {
+    40
+}
+
+fn default_erc20_weight() -> u32 {
+    40
+}
+
+fn default_heavy_compute_weight() -> u32 {
+    10
+}
+
+fn default_heavy_pubdata_weight() -> u32 {
+    10
+}
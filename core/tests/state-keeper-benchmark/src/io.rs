@@ -0,0 +1,273 @@
+//! `StateKeeperIO` implementations used by the benchmark.
+
+use async_trait::async_trait;
+use tokio::sync::watch;
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use vm::{
+    vm_with_bootloader::{BlockContext, BlockContextMode, DerivedBlockContext},
+    zk_evm::block_properties::BlockProperties,
+    VmBlockResult,
+};
+use zksync_contracts::BaseSystemContracts;
+use zksync_core::state_keeper::{L1BatchParams, PendingBatchData, StateKeeperIO, UpdatesManager};
+use zksync_state::InMemoryStorage;
+use zksync_types::{
+    tx::TxRejection, zkevm_test_harness::witness::sort_storage_access::sort_storage_access_queries,
+    AccountTreeId, Address, L1BatchNumber, MiniblockNumber, StorageKey, Transaction, H256,
+    ZKPORTER_IS_AVAILABLE,
+};
+use zksync_utils::{h256_to_u256, time::seconds_since_epoch, u256_to_h256};
+
+use crate::report::BenchmarkStats;
+
+/// IO operating over in-memory storage shared with `InMemoryBatchExecutorBuilder`. Transactions
+/// are taken from a pre-generated queue; sealed L1 batches are applied to the shared storage.
+#[derive(Debug)]
+pub struct InMemoryIO {
+    storage: Arc<Mutex<InMemoryStorage>>,
+    transactions: VecDeque<Transaction>,
+    base_system_contracts: BaseSystemContracts,
+    fee_account: Address,
+    l1_gas_price: u64,
+    fair_l2_gas_price: u64,
+    current_l1_batch_number: L1BatchNumber,
+    current_miniblock_number: MiniblockNumber,
+    prev_timestamp: u64,
+    pending_factory_deps: HashMap<H256, Vec<u8>>,
+}
+
+impl InMemoryIO {
+    pub fn new(
+        storage: Arc<Mutex<InMemoryStorage>>,
+        transactions: Vec<Transaction>,
+        fee_account: Address,
+        l1_gas_price: u64,
+        fair_l2_gas_price: u64,
+    ) -> Self {
+        Self {
+            storage,
+            transactions: transactions.into(),
+            base_system_contracts: BaseSystemContracts::load_from_disk(),
+            fee_account,
+            l1_gas_price,
+            fair_l2_gas_price,
+            // Batch #0 and miniblock #0 are reserved for genesis.
+            current_l1_batch_number: L1BatchNumber(1),
+            current_miniblock_number: MiniblockNumber(1),
+            prev_timestamp: 0,
+            pending_factory_deps: HashMap::new(),
+        }
+    }
+
+    /// Returns the next timestamp. Timestamps of miniblocks must strictly increase,
+    /// so we don't wait for the wall clock to tick like the production IO does.
+    fn next_timestamp(&mut self) -> u64 {
+        self.prev_timestamp = seconds_since_epoch().max(self.prev_timestamp + 1);
+        self.prev_timestamp
+    }
+}
+
+#[async_trait]
+impl StateKeeperIO for InMemoryIO {
+    fn current_l1_batch_number(&self) -> L1BatchNumber {
+        self.current_l1_batch_number
+    }
+
+    fn current_miniblock_number(&self) -> MiniblockNumber {
+        self.current_miniblock_number
+    }
+
+    async fn load_pending_batch(&mut self) -> Option<PendingBatchData> {
+        None
+    }
+
+    async fn wait_for_new_batch_params(&mut self, _max_wait: Duration) -> Option<L1BatchParams> {
+        let context = BlockContext {
+            block_number: self.current_l1_batch_number.0,
+            block_timestamp: self.next_timestamp(),
+            l1_gas_price: self.l1_gas_price,
+            fair_l2_gas_price: self.fair_l2_gas_price,
+            operator_address: self.fee_account,
+        };
+        let properties = BlockProperties {
+            default_aa_code_hash: h256_to_u256(self.base_system_contracts.default_aa.hash),
+            zkporter_is_available: ZKPORTER_IS_AVAILABLE,
+        };
+        Some(L1BatchParams {
+            // The previous batch hash isn't checked by the VM.
+            context_mode: BlockContextMode::NewBlock(context.into(), 0.into()),
+            properties,
+            base_system_contracts: self.base_system_contracts.clone(),
+            protocol_version: None,
+        })
+    }
+
+    async fn wait_for_new_miniblock_params(&mut self, _max_wait: Duration) -> Option<u64> {
+        Some(self.next_timestamp())
+    }
+
+    async fn wait_for_next_tx(&mut self, max_wait: Duration) -> Option<Transaction> {
+        let tx = self.transactions.pop_front();
+        if tx.is_none() {
+            tokio::time::sleep(max_wait).await;
+        }
+        tx
+    }
+
+    async fn rollback(&mut self, tx: Transaction) {
+        self.transactions.push_front(tx);
+    }
+
+    async fn reject(&mut self, _tx: &Transaction, _rejection: &TxRejection) {
+        // Rejections are accounted for in `MeteredIO`.
+    }
+
+    async fn seal_miniblock(&mut self, updates_manager: &UpdatesManager) {
+        let new_factory_deps = &updates_manager.miniblock.new_factory_deps;
+        self.pending_factory_deps.extend(
+            new_factory_deps
+                .iter()
+                .map(|(hash, bytecode)| (*hash, bytecode.clone())),
+        );
+        self.current_miniblock_number += 1;
+    }
+
+    async fn seal_l1_batch(
+        &mut self,
+        block_result: VmBlockResult,
+        _updates_manager: UpdatesManager,
+        _block_context: DerivedBlockContext,
+    ) {
+        let (_, deduped_log_queries) = sort_storage_access_queries(
+            block_result
+                .full_result
+                .storage_log_queries
+                .iter()
+                .map(|log| &log.log_query),
+        );
+
+        let mut storage = self.storage.lock().expect("in-memory storage is poisoned");
+        for log_query in deduped_log_queries.iter().filter(|log| log.rw_flag) {
+            let key = StorageKey::new(
+                AccountTreeId::new(log_query.address),
+                u256_to_h256(log_query.key),
+            );
+            storage.set_value(key, u256_to_h256(log_query.written_value));
+        }
+        for (hash, bytecode) in self.pending_factory_deps.drain() {
+            storage.store_factory_dep(hash, bytecode);
+        }
+        drop(storage);
+
+        self.current_miniblock_number += 1; // Due to fictive miniblock being sealed.
+        self.current_l1_batch_number += 1;
+    }
+}
+
+/// Wrapper around a `StateKeeperIO` measuring seal latencies and tracking progress of the benchmark.
+/// Once all transactions from the stream are either included into sealed miniblocks or rejected,
+/// the wrapper sends a stop signal to the state keeper.
+#[derive(Debug)]
+pub struct MeteredIO<I> {
+    inner: I,
+    stats: Arc<Mutex<BenchmarkStats>>,
+    stop_sender: watch::Sender<bool>,
+}
+
+impl<I: StateKeeperIO> MeteredIO<I> {
+    pub fn new(
+        inner: I,
+        stats: Arc<Mutex<BenchmarkStats>>,
+        stop_sender: watch::Sender<bool>,
+    ) -> Self {
+        Self {
+            inner,
+            stats,
+            stop_sender,
+        }
+    }
+
+    fn stats(&self) -> std::sync::MutexGuard<'_, BenchmarkStats> {
+        self.stats.lock().expect("benchmark stats are poisoned")
+    }
+
+    fn stop_if_finished(&self) {
+        if self.stats().is_finished() {
+            self.stop_sender.send_replace(true);
+        }
+    }
+}
+
+#[async_trait]
+impl<I: StateKeeperIO> StateKeeperIO for MeteredIO<I> {
+    fn current_l1_batch_number(&self) -> L1BatchNumber {
+        self.inner.current_l1_batch_number()
+    }
+
+    fn current_miniblock_number(&self) -> MiniblockNumber {
+        self.inner.current_miniblock_number()
+    }
+
+    async fn load_pending_batch(&mut self) -> Option<PendingBatchData> {
+        self.inner.load_pending_batch().await
+    }
+
+    async fn wait_for_new_batch_params(&mut self, max_wait: Duration) -> Option<L1BatchParams> {
+        self.inner.wait_for_new_batch_params(max_wait).await
+    }
+
+    async fn wait_for_new_miniblock_params(&mut self, max_wait: Duration) -> Option<u64> {
+        self.inner.wait_for_new_miniblock_params(max_wait).await
+    }
+
+    async fn wait_for_next_tx(&mut self, max_wait: Duration) -> Option<Transaction> {
+        self.inner.wait_for_next_tx(max_wait).await
+    }
+
+    async fn rollback(&mut self, tx: Transaction) {
+        self.inner.rollback(tx).await;
+    }
+
+    async fn reject(&mut self, tx: &Transaction, rejection: &TxRejection) {
+        vlog::warn!("Transaction {:?} was rejected: {rejection}", tx.hash());
+        self.inner.reject(tx, rejection).await;
+        self.stats().rejected_txs += 1;
+        self.stop_if_finished();
+    }
+
+    async fn seal_miniblock(&mut self, updates_manager: &UpdatesManager) {
+        let started_at = Instant::now();
+        self.inner.seal_miniblock(updates_manager).await;
+        let latency = started_at.elapsed();
+
+        let miniblock = &updates_manager.miniblock;
+        let tx_count = miniblock.executed_transactions.len();
+        let write_count = miniblock
+            .storage_logs
+            .iter()
+            .filter(|log| log.log_query.rw_flag)
+            .count();
+        self.stats()
+            .record_miniblock(latency, tx_count, miniblock.events.len(), write_count);
+        self.stop_if_finished();
+    }
+
+    async fn seal_l1_batch(
+        &mut self,
+        block_result: VmBlockResult,
+        updates_manager: UpdatesManager,
+        block_context: DerivedBlockContext,
+    ) {
+        let started_at = Instant::now();
+        self.inner
+            .seal_l1_batch(block_result, updates_manager, block_context)
+            .await;
+        self.stats().record_l1_batch(started_at.elapsed());
+    }
+}
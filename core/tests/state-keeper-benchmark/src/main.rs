@@ -0,0 +1,248 @@
+//! State keeper benchmark: drives the state keeper with a synthetic stream of transactions
+//! and measures its throughput, seal latencies and DB write throughput.
+//!
+//! The benchmark is configured via env variables, see `README.md` for details.
+
+use anyhow::Context as _;
+use tempfile::TempDir;
+use tokio::sync::watch;
+
+use std::sync::{Arc, Mutex};
+
+use vm::vm_with_bootloader::derive_base_fee_and_gas_per_pubdata;
+use zksync_config::{
+    configs::chain::{MempoolConfig, StateKeeperConfig},
+    ContractsConfig,
+};
+use zksync_core::{
+    chain_contracts::ChainContracts,
+    l1_gas_price::L1GasPriceProvider,
+    state_keeper::{
+        InMemoryBatchExecutorBuilder, MainBatchExecutorBuilder, MempoolGuard, MempoolIO,
+        MiniblockSealer, SealManager, ZkSyncStateKeeper,
+    },
+};
+use zksync_dal::{connection::DbVariant, ConnectionPool, StorageProcessor};
+use zksync_state::InMemoryStorage;
+use zksync_types::{
+    fee::TransactionExecutionMetrics, utils::storage_key_for_standard_token_balance, AccountTreeId,
+    Address, MiniblockNumber, Nonce, StorageLog, Transaction, H256, L2_ETH_TOKEN_ADDRESS, U256,
+};
+use zksync_utils::{bytecode::hash_bytecode, u256_to_h256};
+
+use crate::{
+    config::{BenchmarkConfig, IoMode},
+    io::{InMemoryIO, MeteredIO},
+    report::BenchmarkStats,
+    workload::Workload,
+};
+
+mod config;
+mod io;
+mod report;
+mod workload;
+
+/// L1 gas price used by the benchmark. A constant value makes the results reproducible.
+const L1_GAS_PRICE: u64 = 10_000_000_000; // 10 gwei
+
+#[derive(Debug)]
+struct ConstantL1GasPrice;
+
+impl L1GasPriceProvider for ConstantL1GasPrice {
+    fn estimate_effective_gas_price(&self) -> u64 {
+        L1_GAS_PRICE
+    }
+}
+
+fn account_balance_log(address: &Address) -> StorageLog {
+    let key =
+        storage_key_for_standard_token_balance(AccountTreeId::new(L2_ETH_TOKEN_ADDRESS), address);
+    let balance = U256::from(10_u32).pow(32.into()); // 10^32 wei
+    StorageLog::new_write_log(key, u256_to_h256(balance))
+}
+
+fn in_memory_state_keeper(
+    state_keeper_config: StateKeeperConfig,
+    workload: Workload,
+    stats: Arc<Mutex<BenchmarkStats>>,
+    stop_sender: watch::Sender<bool>,
+    stop_receiver: watch::Receiver<bool>,
+) -> ZkSyncStateKeeper {
+    let mut storage = InMemoryStorage::with_system_contracts(hash_bytecode);
+    for address in &workload.accounts {
+        let log = account_balance_log(address);
+        storage.set_value(log.key, log.value);
+    }
+    let storage = Arc::new(Mutex::new(storage));
+
+    let batch_executor_base = InMemoryBatchExecutorBuilder::new(
+        storage.clone(),
+        state_keeper_config.max_allowed_l2_tx_gas_limit.into(),
+        state_keeper_config.validation_computational_gas_limit,
+    );
+    let transactions = workload
+        .transactions
+        .into_iter()
+        .map(Transaction::from)
+        .collect();
+    let io = InMemoryIO::new(
+        storage,
+        transactions,
+        state_keeper_config.fee_account_addr,
+        L1_GAS_PRICE,
+        state_keeper_config.fair_l2_gas_price,
+    );
+    let io = MeteredIO::new(io, stats, stop_sender);
+    let sealer = SealManager::new(state_keeper_config, Arc::new(ConstantL1GasPrice));
+    ZkSyncStateKeeper::new(
+        stop_receiver,
+        Box::new(io),
+        Box::new(batch_executor_base),
+        sealer,
+    )
+}
+
+/// Funds benchmark accounts by writing their balances directly to the genesis state.
+async fn fund_accounts(storage: &mut StorageProcessor<'_>, accounts: &[Address]) {
+    let storage_logs: Vec<_> = accounts.iter().map(account_balance_log).collect();
+    let storage_logs = [(H256::zero(), storage_logs)];
+    storage
+        .storage_logs_dal()
+        .append_storage_logs(MiniblockNumber(0), &storage_logs)
+        .await;
+    storage
+        .storage_dal()
+        .apply_storage_logs(&storage_logs)
+        .await;
+}
+
+async fn postgres_state_keeper(
+    state_keeper_config: StateKeeperConfig,
+    workload: Workload,
+    state_keeper_db_dir: &TempDir,
+    stats: Arc<Mutex<BenchmarkStats>>,
+    stop_sender: watch::Sender<bool>,
+    stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<ZkSyncStateKeeper> {
+    let pool = ConnectionPool::new(None, DbVariant::Master).await;
+    let mut storage = pool.access_storage_tagged("state_keeper_benchmark").await;
+    anyhow::ensure!(
+        !storage.blocks_dal().is_genesis_needed().await,
+        "Postgres mode requires a database with genesis performed"
+    );
+    fund_accounts(&mut storage, &workload.accounts).await;
+
+    let next_priority_id = storage.transactions_dal().next_priority_id().await;
+    let mut mempool = MempoolGuard::new(next_priority_id, workload.transactions.len() as u64, None);
+    let mut transactions = Vec::with_capacity(workload.transactions.len());
+    for tx in workload.transactions {
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await;
+        transactions.push(tx.into());
+    }
+    let nonces = workload
+        .accounts
+        .iter()
+        .map(|&address| (address, Nonce(0)))
+        .collect();
+    mempool.insert(transactions, nonces);
+    drop(storage);
+
+    let (miniblock_sealer, miniblock_sealer_handle) = MiniblockSealer::new(
+        pool.clone(),
+        state_keeper_config.miniblock_seal_queue_capacity,
+    );
+    tokio::spawn(miniblock_sealer.run());
+
+    let batch_executor_base = MainBatchExecutorBuilder::new(
+        state_keeper_db_dir.path().to_str().unwrap().to_owned(),
+        pool.clone(),
+        state_keeper_config.max_allowed_l2_tx_gas_limit.into(),
+        state_keeper_config.save_call_traces,
+        state_keeper_config.validation_computational_gas_limit,
+        state_keeper_config.tx_execution_timeout(),
+    );
+    let gas_price_provider = Arc::new(ConstantL1GasPrice);
+    let io = MempoolIO::new(
+        mempool,
+        miniblock_sealer_handle,
+        gas_price_provider.clone(),
+        pool,
+        &state_keeper_config,
+        MempoolConfig::from_env().delay_interval(),
+        // The benchmark doesn't have access to L1, so addresses are taken from the config as-is.
+        ChainContracts::from_config(&ContractsConfig::from_env()),
+    )
+    .await;
+    let io = MeteredIO::new(io, stats, stop_sender);
+    let sealer = SealManager::new(state_keeper_config, gas_price_provider);
+    Ok(ZkSyncStateKeeper::new(
+        stop_receiver,
+        Box::new(io),
+        Box::new(batch_executor_base),
+        sealer,
+    ))
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    vlog::init();
+
+    let (config, weights) =
+        BenchmarkConfig::from_env().context("failed loading benchmark config")?;
+    let state_keeper_config = StateKeeperConfig::from_env();
+    vlog::info!("Running state keeper benchmark with config {config:?}, tx weights {weights:?}");
+
+    let (max_fee_per_gas, _) =
+        derive_base_fee_and_gas_per_pubdata(L1_GAS_PRICE, state_keeper_config.fair_l2_gas_price);
+    let workload = Workload::generate(&config, weights, max_fee_per_gas);
+    for (kind, count) in &workload.kind_counts {
+        vlog::info!("Generated {count} transactions of kind `{}`", kind.as_str());
+    }
+
+    let stats = BenchmarkStats::new(workload.transactions.len());
+    let stats = Arc::new(Mutex::new(stats));
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let state_keeper_db_dir = TempDir::new()?;
+    let state_keeper = match config.io_mode {
+        IoMode::InMemory => in_memory_state_keeper(
+            state_keeper_config,
+            workload,
+            stats.clone(),
+            stop_sender,
+            stop_receiver,
+        ),
+        IoMode::Postgres => {
+            postgres_state_keeper(
+                state_keeper_config,
+                workload,
+                &state_keeper_db_dir,
+                stats.clone(),
+                stop_sender,
+                stop_receiver,
+            )
+            .await?
+        }
+    };
+
+    stats.lock().unwrap().start();
+    let run_result = tokio::time::timeout(config.timeout(), state_keeper.run()).await;
+    let report = stats.lock().unwrap().report();
+    vlog::info!("{report}");
+
+    anyhow::ensure!(
+        run_result.is_ok(),
+        "benchmark didn't finish in {:?}",
+        config.timeout()
+    );
+    if let Some(min_tps) = config.min_tps {
+        anyhow::ensure!(
+            report.tps >= min_tps,
+            "throughput regression: {:.1} TPS is lower than the expected minimum {min_tps:.1} TPS",
+            report.tps
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,194 @@
+//! Benchmark statistics and the final report.
+
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// Statistics collected by `MeteredIO` while the state keeper is running.
+#[derive(Debug)]
+pub struct BenchmarkStats {
+    expected_txs: usize,
+    started_at: Instant,
+    finished_at: Option<Instant>,
+    pub sealed_txs: usize,
+    pub rejected_txs: usize,
+    miniblock_seal_latencies: Vec<Duration>,
+    l1_batch_seal_latencies: Vec<Duration>,
+    /// Number of persisted entities (transactions, events and storage writes).
+    persisted_entities: usize,
+}
+
+impl BenchmarkStats {
+    pub fn new(expected_txs: usize) -> Self {
+        Self {
+            expected_txs,
+            started_at: Instant::now(),
+            finished_at: None,
+            sealed_txs: 0,
+            rejected_txs: 0,
+            miniblock_seal_latencies: vec![],
+            l1_batch_seal_latencies: vec![],
+            persisted_entities: 0,
+        }
+    }
+
+    /// Resets the start of the measurement; should be called right before the state keeper is started.
+    pub fn start(&mut self) {
+        self.started_at = Instant::now();
+    }
+
+    pub fn is_finished(&mut self) -> bool {
+        let is_finished = self.sealed_txs + self.rejected_txs >= self.expected_txs;
+        if is_finished && self.finished_at.is_none() {
+            self.finished_at = Some(Instant::now());
+        }
+        is_finished
+    }
+
+    pub fn record_miniblock(
+        &mut self,
+        latency: Duration,
+        tx_count: usize,
+        event_count: usize,
+        write_count: usize,
+    ) {
+        self.sealed_txs += tx_count;
+        self.persisted_entities += tx_count + event_count + write_count;
+        self.miniblock_seal_latencies.push(latency);
+    }
+
+    pub fn record_l1_batch(&mut self, latency: Duration) {
+        self.l1_batch_seal_latencies.push(latency);
+    }
+
+    pub fn report(&self) -> BenchmarkReport {
+        let elapsed = self
+            .finished_at
+            .unwrap_or_else(Instant::now)
+            .duration_since(self.started_at);
+        let total_seal_time: Duration = self
+            .miniblock_seal_latencies
+            .iter()
+            .chain(&self.l1_batch_seal_latencies)
+            .sum();
+
+        BenchmarkReport {
+            elapsed,
+            sealed_txs: self.sealed_txs,
+            rejected_txs: self.rejected_txs,
+            tps: self.sealed_txs as f64 / elapsed.as_secs_f64(),
+            miniblock_seal_latency: LatencyStats::new(&self.miniblock_seal_latencies),
+            l1_batch_seal_latency: LatencyStats::new(&self.l1_batch_seal_latencies),
+            db_writes_per_sec: (total_seal_time > Duration::ZERO)
+                .then(|| self.persisted_entities as f64 / total_seal_time.as_secs_f64()),
+        }
+    }
+}
+
+/// Latency percentiles.
+#[derive(Debug, Clone, Copy)]
+pub struct LatencyStats {
+    pub count: usize,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub max: Duration,
+}
+
+impl LatencyStats {
+    fn new(latencies: &[Duration]) -> Option<Self> {
+        if latencies.is_empty() {
+            return None;
+        }
+        let mut latencies = latencies.to_vec();
+        latencies.sort_unstable();
+        let percentile = |p: usize| latencies[(latencies.len() - 1) * p / 100];
+        Some(Self {
+            count: latencies.len(),
+            p50: percentile(50),
+            p95: percentile(95),
+            max: *latencies.last().unwrap(),
+        })
+    }
+}
+
+impl fmt::Display for LatencyStats {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "{} sealed, p50 {:?}, p95 {:?}, max {:?}",
+            self.count, self.p50, self.p95, self.max
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct BenchmarkReport {
+    pub elapsed: Duration,
+    pub sealed_txs: usize,
+    pub rejected_txs: usize,
+    pub tps: f64,
+    pub miniblock_seal_latency: Option<LatencyStats>,
+    pub l1_batch_seal_latency: Option<LatencyStats>,
+    /// Transactions, events and storage writes persisted per second of sealing.
+    pub db_writes_per_sec: Option<f64>,
+}
+
+impl fmt::Display for BenchmarkReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            formatter,
+            "Processed {} transactions ({} rejected) in {:?}: {:.1} TPS",
+            self.sealed_txs, self.rejected_txs, self.elapsed, self.tps
+        )?;
+        if let Some(latency) = &self.miniblock_seal_latency {
+            writeln!(formatter, "Miniblocks: {latency}")?;
+        }
+        if let Some(latency) = &self.l1_batch_seal_latency {
+            writeln!(formatter, "L1 batches: {latency}")?;
+        }
+        if let Some(db_writes_per_sec) = self.db_writes_per_sec {
+            write!(
+                formatter,
+                "Seal throughput: {db_writes_per_sec:.1} entities/s"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computing_latency_stats() {
+        let latencies: Vec<_> = (1..=100).rev().map(Duration::from_millis).collect();
+        let stats = LatencyStats::new(&latencies).unwrap();
+        assert_eq!(stats.count, 100);
+        assert_eq!(stats.p50, Duration::from_millis(50));
+        assert_eq!(stats.p95, Duration::from_millis(95));
+        assert_eq!(stats.max, Duration::from_millis(100));
+
+        assert!(LatencyStats::new(&[]).is_none());
+    }
+
+    #[test]
+    fn finishing_benchmark() {
+        let mut stats = BenchmarkStats::new(10);
+        stats.record_miniblock(Duration::from_millis(5), 7, 3, 20);
+        assert!(!stats.is_finished());
+        stats.rejected_txs += 1;
+        stats.record_miniblock(Duration::from_millis(5), 2, 0, 4);
+        assert!(stats.is_finished());
+
+        let report = stats.report();
+        assert_eq!(report.sealed_txs, 9);
+        assert_eq!(report.rejected_txs, 1);
+        let db_writes_per_sec = report.db_writes_per_sec.unwrap();
+        assert!(
+            (db_writes_per_sec - 3_600.0).abs() < 1e-6,
+            "{db_writes_per_sec}"
+        );
+    }
+}
@@ -0,0 +1,233 @@
+//! Generation of synthetic transaction streams.
+
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng};
+
+use std::collections::HashMap;
+
+use vm::{
+    test_utils::{
+        get_create_zksync_address, get_deploy_tx, mock_loadnext_gas_burn_call,
+        mock_loadnext_test_call,
+    },
+    zk_evm::zkevm_opcode_defs::system_params::INITIAL_STORAGE_WRITE_PUBDATA_BYTES,
+};
+use zksync_contracts::{get_loadnext_contract, TestContract};
+use zksync_types::{
+    ethabi::{encode, Token},
+    fee::Fee,
+    l2::L2Tx,
+    Address, L2ChainId, Nonce, PackedEthSignature, H256, U256,
+};
+use zksync_utils::test_utils::LoadnextContractExecutionParams;
+
+use crate::config::{BenchmarkConfig, TransactionWeights};
+
+/// Chain ID used for signing transactions. Test utils in the `vm` crate are hard-wired to this value.
+pub const CHAIN_ID: L2ChainId = L2ChainId(270);
+const GAS_PER_PUBDATA: u32 = 800;
+const BASE_GAS_LIMIT: u32 = 10_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TxKind {
+    /// Deployment of the loadnext contract used by other transaction kinds.
+    Deploy,
+    /// Base token transfer to a new address.
+    Transfer,
+    /// Call approximating an ERC-20 transfer: 2 storage reads, 2 writes and an event. The test
+    /// contracts don't include a mintable token, so the call is made to the loadnext contract.
+    Erc20,
+    /// Call burning a configurable amount of computational gas.
+    HeavyCompute,
+    /// Call writing to a configurable number of new storage slots.
+    HeavyPubdata,
+}
+
+impl TxKind {
+    const WEIGHTED: [Self; 4] = [
+        Self::Transfer,
+        Self::Erc20,
+        Self::HeavyCompute,
+        Self::HeavyPubdata,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Deploy => "deploy",
+            Self::Transfer => "transfer",
+            Self::Erc20 => "erc20",
+            Self::HeavyCompute => "heavy_compute",
+            Self::HeavyPubdata => "heavy_pubdata",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Account {
+    pk: H256,
+    nonce: Nonce,
+}
+
+impl Account {
+    fn random() -> Self {
+        Self {
+            pk: H256::random(),
+            nonce: Nonce(0),
+        }
+    }
+
+    fn address(&self) -> Address {
+        PackedEthSignature::address_from_private_key(&self.pk).unwrap()
+    }
+
+    fn next_nonce(&mut self) -> Nonce {
+        let nonce = self.nonce;
+        self.nonce += 1;
+        nonce
+    }
+}
+
+/// Synthetic stream of L2 transactions together with the accounts that need to be funded
+/// before the stream is executed.
+#[derive(Debug)]
+pub struct Workload {
+    pub accounts: Vec<Address>,
+    pub transactions: Vec<L2Tx>,
+    pub kind_counts: HashMap<TxKind, usize>,
+}
+
+impl Workload {
+    /// Generates a stream. The first transaction deploys the loadnext contract; other transactions
+    /// are distributed among accounts in a round-robin fashion, so that nonces of each account
+    /// are consecutive.
+    pub fn generate(
+        config: &BenchmarkConfig,
+        weights: TransactionWeights,
+        max_fee_per_gas: u64,
+    ) -> Self {
+        assert!(config.accounts > 0, "benchmark requires at least 1 account");
+        let weights = [
+            weights.transfer_weight,
+            weights.erc20_weight,
+            weights.heavy_compute_weight,
+            weights.heavy_pubdata_weight,
+        ];
+        let distribution = WeightedIndex::new(weights).expect("invalid transaction weights");
+        let mut rng = rand::thread_rng();
+
+        let mut accounts: Vec<_> = (0..config.accounts).map(|_| Account::random()).collect();
+        let mut kind_counts = HashMap::new();
+        let (deploy_tx, contract_address) = deploy_loadnext_tx(&mut accounts[0], max_fee_per_gas);
+        kind_counts.insert(TxKind::Deploy, 1);
+
+        let mut transactions = Vec::with_capacity(config.transactions + 1);
+        transactions.push(deploy_tx);
+        for i in 0..config.transactions {
+            let kind = TxKind::WEIGHTED[distribution.sample(&mut rng)];
+            let account = &mut accounts[i % config.accounts];
+            let tx = match kind {
+                TxKind::Transfer => {
+                    let value = U256::from(rng.gen_range(1_u64..1_000_000));
+                    transfer_tx(account, value, max_fee_per_gas)
+                }
+                TxKind::Erc20 => {
+                    let params = LoadnextContractExecutionParams {
+                        reads: 2,
+                        writes: 2,
+                        events: 1,
+                        hashes: 0,
+                        recursive_calls: 0,
+                        deploys: 0,
+                    };
+                    let fee = fee(BASE_GAS_LIMIT, max_fee_per_gas);
+                    let nonce = account.next_nonce();
+                    mock_loadnext_test_call(account.pk, nonce, contract_address, fee, params)
+                }
+                TxKind::HeavyCompute => {
+                    let gas_limit = BASE_GAS_LIMIT + config.heavy_compute_gas;
+                    let fee = fee(gas_limit, max_fee_per_gas);
+                    let nonce = account.next_nonce();
+                    mock_loadnext_gas_burn_call(
+                        account.pk,
+                        nonce,
+                        contract_address,
+                        fee,
+                        config.heavy_compute_gas,
+                    )
+                }
+                TxKind::HeavyPubdata => {
+                    let writes = config.heavy_pubdata_writes;
+                    // Each write to the loadnext contract updates 2 slots: the length of the vector
+                    // and the new vector element.
+                    let pubdata_gas =
+                        2 * GAS_PER_PUBDATA * writes * INITIAL_STORAGE_WRITE_PUBDATA_BYTES as u32;
+                    let params = LoadnextContractExecutionParams {
+                        reads: 0,
+                        writes: writes as usize,
+                        events: 10,
+                        hashes: 0,
+                        recursive_calls: 0,
+                        deploys: 0,
+                    };
+                    let fee = fee(BASE_GAS_LIMIT + pubdata_gas, max_fee_per_gas);
+                    let nonce = account.next_nonce();
+                    mock_loadnext_test_call(account.pk, nonce, contract_address, fee, params)
+                }
+                TxKind::Deploy => unreachable!(),
+            };
+            transactions.push(tx);
+            *kind_counts.entry(kind).or_default() += 1;
+        }
+
+        Self {
+            accounts: accounts.iter().map(Account::address).collect(),
+            transactions,
+            kind_counts,
+        }
+    }
+}
+
+fn fee(gas_limit: u32, max_fee_per_gas: u64) -> Fee {
+    Fee {
+        gas_limit: U256::from(gas_limit),
+        max_fee_per_gas: U256::from(max_fee_per_gas),
+        max_priority_fee_per_gas: U256::zero(),
+        gas_per_pubdata_limit: U256::from(GAS_PER_PUBDATA),
+    }
+}
+
+fn deploy_loadnext_tx(account: &mut Account, max_fee_per_gas: u64) -> (L2Tx, Address) {
+    let TestContract {
+        bytecode,
+        factory_deps,
+        ..
+    } = get_loadnext_contract();
+    let nonce = account.next_nonce();
+    let deploy_tx = get_deploy_tx(
+        account.pk,
+        nonce,
+        &bytecode,
+        factory_deps,
+        &encode(&[Token::Uint(U256::from(1000))]),
+        fee(500_000_000, max_fee_per_gas),
+    );
+    let contract_address = get_create_zksync_address(deploy_tx.initiator_account(), nonce);
+    (deploy_tx, contract_address)
+}
+
+fn transfer_tx(account: &mut Account, value: U256, max_fee_per_gas: u64) -> L2Tx {
+    let mut tx = L2Tx::new_signed(
+        Address::random(),
+        vec![],
+        account.next_nonce(),
+        fee(BASE_GAS_LIMIT, max_fee_per_gas),
+        value,
+        CHAIN_ID,
+        &account.pk,
+        None,
+        Default::default(),
+    )
+    .unwrap();
+    // Randomize the input so that tx hashes are unique.
+    tx.set_input(H256::random().0.to_vec(), H256::random());
+    tx
+}
@@ -26,7 +26,10 @@ const CONFIG_FILES = [
     'house_keeper.toml',
     'fri_prover.toml',
     'fri_witness_generator.toml',
-    'fri_prover_group.toml'
+    'fri_prover_group.toml',
+    'proof_data_handler.toml',
+    'change_stream.toml',
+    'chain_registry.toml'
 ];
 
 function loadConfigFile(path: string) {